@@ -93,10 +93,44 @@ impl BuildConstants {
     }
 }
 
+/// Compiles the eBPF programs of the `ebpf` source into an object embedded in Vector, which is
+/// relocated against the BTF of the running kernel when loaded.
+///
+/// This needs `clang`, or the compiler set in `CLANG`, and the `libbpf` headers.
+#[cfg(feature = "sources-ebpf")]
+fn compile_ebpf_programs() {
+    let source = "src/sources/ebpf/bpf/telemetry.bpf.c";
+    println!("cargo:rerun-if-changed={}", source);
+    println!("cargo:rerun-if-env-changed=CLANG");
+
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("linux") {
+        return;
+    }
+    let arch = match env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+        Ok("x86_64") => "x86",
+        Ok("aarch64") => "arm64",
+        Ok(arch) => panic!("eBPF programs can't be built for {}", arch),
+        Err(_) => panic!("Cargo-provided environment variables should always exist!"),
+    };
+    let output = Path::new(&env::var("OUT_DIR").unwrap()).join("ebpf_telemetry.bpf.o");
+
+    let status = std::process::Command::new(env::var("CLANG").unwrap_or_else(|_| "clang".into()))
+        .args(["-O2", "-g", "-target", "bpf"])
+        .arg(format!("-D__TARGET_ARCH_{}", arch))
+        .args(["-c", source, "-o"])
+        .arg(&output)
+        .status()
+        .expect("failed to run clang to build the eBPF programs");
+    assert!(status.success(), "failed to build the eBPF programs");
+}
+
 fn main() {
     // Always rerun if the build script itself changes.
     println!("cargo:rerun-if-changed=build.rs");
 
+    #[cfg(feature = "sources-ebpf")]
+    compile_ebpf_programs();
+
     #[cfg(feature = "protobuf-build")]
     {
         println!("cargo:rerun-if-changed=proto/dd_trace.proto");
@@ -105,6 +139,7 @@ fn main() {
         println!("cargo:rerun-if-changed=proto/dd_metric.proto");
         println!("cargo:rerun-if-changed=proto/google/pubsub/v1/pubsub.proto");
         println!("cargo:rerun-if-changed=proto/vector.proto");
+        println!("cargo:rerun-if-changed=proto/opentelemetry");
 
         let mut prost_build = prost_build::Config::new();
         prost_build.btree_map(&["."]);
@@ -120,6 +155,9 @@ fn main() {
                     "proto/dd_trace.proto",
                     "proto/google/pubsub/v1/pubsub.proto",
                     "proto/vector.proto",
+                    "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                    "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                    "proto/opentelemetry/proto/collector/trace/v1/trace_service.proto",
                 ],
                 &["proto/", "lib/vector-core/proto/"],
             )
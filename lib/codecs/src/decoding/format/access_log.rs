@@ -0,0 +1,396 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+use crate::decoding::BuildError;
+
+/// The Common Log Format, as written by Apache's `%h %l %u %t "%r" %>s %b`.
+const COMMON_LOG_FORMAT: &str = r#"$remote_addr $remote_logname $remote_user [$time_local] "$request" $status $body_bytes_sent"#;
+
+/// The Combined Log Format, which adds the referer and user agent to the Common Log Format.
+const COMBINED_LOG_FORMAT: &str = r#"$remote_addr $remote_logname $remote_user [$time_local] "$request" $status $body_bytes_sent "$http_referer" "$http_user_agent""#;
+
+/// The variables whose values are decoded as integers.
+const INTEGER_VARIABLES: [&str; 6] = [
+    "body_bytes_sent",
+    "bytes_sent",
+    "connection",
+    "connection_requests",
+    "request_length",
+    "status",
+];
+
+/// The variables whose values are decoded as floats.
+const FLOAT_VARIABLES: [&str; 5] = [
+    "msec",
+    "request_time",
+    "upstream_connect_time",
+    "upstream_header_time",
+    "upstream_response_time",
+];
+
+/// Config used to build an `AccessLogDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AccessLogDeserializerConfig {
+    /// Options for the access log deserializer.
+    #[serde(default)]
+    pub access_log: AccessLogDeserializerOptions,
+}
+
+impl AccessLogDeserializerConfig {
+    /// Creates a new `AccessLogDeserializerConfig`.
+    pub const fn new(access_log: AccessLogDeserializerOptions) -> Self {
+        Self { access_log }
+    }
+
+    /// Build the `AccessLogDeserializer` from this configuration.
+    pub fn build(&self) -> Result<AccessLogDeserializer, BuildError> {
+        let format = match (self.access_log.format, &self.access_log.log_format) {
+            (AccessLogFormat::Common, None) => COMMON_LOG_FORMAT,
+            (AccessLogFormat::Combined, None) => COMBINED_LOG_FORMAT,
+            (AccessLogFormat::Custom, Some(log_format)) => log_format.as_str(),
+            (AccessLogFormat::Custom, None) => {
+                return Err("`log_format` must be set when `format` is `custom`".into())
+            }
+            (_, Some(_)) => {
+                return Err("`log_format` can only be set when `format` is `custom`".into())
+            }
+        };
+        let (regex, variables) = compile_log_format(format)?;
+        Ok(AccessLogDeserializer { regex, variables })
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .with_field(
+                log_schema().timestamp_key(),
+                // The access log decoder inserts a timestamp, unless a variable of the same name
+                // exists.
+                Kind::bytes().or_timestamp(),
+                Some("timestamp"),
+            )
+            .unknown_fields(Kind::bytes().or_integer().or_float().or_timestamp())
+    }
+}
+
+/// Options for building an `AccessLogDeserializer`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct AccessLogDeserializerOptions {
+    /// The format of the access log.
+    #[serde(default)]
+    pub format: AccessLogFormat,
+
+    /// The format of the access log when `format` is `custom`, in the syntax of the nginx
+    /// `log_format` directive.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub log_format: Option<String>,
+}
+
+impl Default for AccessLogDeserializerOptions {
+    fn default() -> Self {
+        Self {
+            format: AccessLogFormat::default(),
+            log_format: None,
+        }
+    }
+}
+
+/// The format of an access log.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogFormat {
+    /// The Common Log Format, as written by Apache's `common` and nginx's `common` formats.
+    Common,
+
+    /// The Combined Log Format, as written by Apache's `combined` and nginx's default `combined`
+    /// formats.
+    Combined,
+
+    /// The format set by `log_format`.
+    Custom,
+}
+
+impl Default for AccessLogFormat {
+    fn default() -> Self {
+        Self::Combined
+    }
+}
+
+/// Compiles a format in the syntax of the nginx `log_format` directive into a regex capturing the
+/// value of each variable, and the names of the variables.
+fn compile_log_format(format: &str) -> Result<(Regex, Vec<String>), BuildError> {
+    let mut pattern = String::from("^");
+    let mut variables = Vec::<String>::new();
+    let mut rest = format;
+    while let Some(index) = rest.find('$') {
+        let literal = &rest[..index];
+        pattern.push_str(&regex::escape(literal));
+
+        let after = &rest[index + 1..];
+        let (name, remaining) = match after.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced
+                    .find('}')
+                    .ok_or("Unterminated `${` in the log format")?;
+                (&braced[..end], &braced[end + 1..])
+            }
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                after.split_at(end)
+            }
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid variable name {:?} in the log format", name).into());
+        }
+
+        // The values of the variables extend up to the character closing the quotes or brackets
+        // they are enclosed in, or up to the next whitespace otherwise.
+        let value = match literal.chars().last() {
+            Some('"') => r#"(?:[^"\\]|\\.)*"#,
+            Some('[') => r"[^\]]*",
+            _ => r"\S*",
+        };
+        if variables.iter().any(|variable| variable == name) {
+            write!(pattern, "(?:{})", value).expect("Writing to a String can't fail");
+        } else {
+            write!(pattern, "(?P<{}>{})", name, value).expect("Writing to a String can't fail");
+            variables.push(name.to_owned());
+        }
+
+        rest = remaining;
+    }
+    pattern.push_str(&regex::escape(rest));
+    pattern.push('$');
+
+    Ok((Regex::new(&pattern)?, variables))
+}
+
+fn to_value(variable: &str, value: &str) -> Value {
+    let typed = if INTEGER_VARIABLES.contains(&variable) {
+        value.parse::<i64>().ok().map(Value::from)
+    } else if FLOAT_VARIABLES.contains(&variable) {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .map(Value::from_f64_or_zero)
+    } else if variable == "time_local" {
+        DateTime::parse_from_str(value, "%d/%b/%Y:%H:%M:%S %z")
+            .ok()
+            .map(|timestamp| Value::from(timestamp.with_timezone(&Utc)))
+    } else if variable == "time_iso8601" {
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|timestamp| Value::from(timestamp.with_timezone(&Utc)))
+    } else {
+        None
+    };
+    // Values that don't have the expected type, such as the comma separated times of the
+    // upstreams of a request, are kept as strings.
+    typed.unwrap_or_else(|| Value::from(value))
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing an Apache or nginx access log
+/// line.
+///
+/// The value of each variable of the format is decoded into the field of the event named after
+/// it, unless it is `-`. Status, byte count and timing variables are decoded into integers and
+/// floats, and `$time_local` and `$time_iso8601` into timestamps, which are also used as the
+/// timestamp of the event. When the format has a `$request` variable, the request is also split
+/// into `request_method`, `request_uri` and `server_protocol` fields.
+#[derive(Debug, Clone)]
+pub struct AccessLogDeserializer {
+    regex: Regex,
+    variables: Vec<String>,
+}
+
+impl Deserializer for AccessLogDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let line = String::from_utf8_lossy(&bytes);
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        if line.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let captures = self
+            .regex
+            .captures(line)
+            .ok_or("Line doesn't match the access log format")?;
+
+        let mut fields = BTreeMap::new();
+        for variable in &self.variables {
+            match captures.name(variable).map(|value| value.as_str()) {
+                Some("-") | None => {}
+                Some(value) => {
+                    fields.insert(variable.clone(), to_value(variable, value));
+                }
+            }
+        }
+
+        if let Some(request) = captures.name("request").map(|request| request.as_str()) {
+            let parts = ["request_method", "request_uri", "server_protocol"];
+            if request != "-" && !parts.iter().any(|part| fields.contains_key(*part)) {
+                for (part, value) in parts.iter().zip(request.splitn(3, ' ')) {
+                    fields.insert((*part).to_owned(), Value::from(value));
+                }
+            }
+        }
+
+        let timestamp = ["time_local", "time_iso8601"].iter().find_map(|variable| {
+            match fields.get(*variable) {
+                Some(Value::Timestamp(timestamp)) => Some(*timestamp),
+                _ => None,
+            }
+        });
+
+        let mut log = LogEvent::from(fields);
+        let timestamp_key = log_schema().timestamp_key();
+        if !log.contains(timestamp_key) {
+            log.insert(timestamp_key, timestamp.unwrap_or_else(Utc::now));
+        }
+
+        Ok(smallvec![log.into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn deserializer(format: AccessLogFormat, log_format: Option<&str>) -> AccessLogDeserializer {
+        AccessLogDeserializerConfig::new(AccessLogDeserializerOptions {
+            format,
+            log_format: log_format.map(Into::into),
+        })
+        .build()
+        .unwrap()
+    }
+
+    fn parse(deserializer: &AccessLogDeserializer, input: &str) -> BTreeMap<String, Value> {
+        let mut events = deserializer.parse(Bytes::from(input.to_owned())).unwrap();
+        assert_eq!(events.len(), 1);
+        events
+            .remove(0)
+            .into_log()
+            .into_parts()
+            .0
+            .into_object()
+            .unwrap()
+    }
+
+    #[test]
+    fn deserialize_common_log_format() {
+        let deserializer = deserializer(AccessLogFormat::Common, None);
+        let timestamp = Value::from(Utc.ymd(2000, 10, 10).and_hms(20, 55, 36));
+
+        assert_eq!(
+            parse(
+                &deserializer,
+                r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#
+            ),
+            btreemap! {
+                "remote_addr" => Value::from("127.0.0.1"),
+                "remote_user" => Value::from("frank"),
+                "time_local" => timestamp.clone(),
+                "request" => Value::from("GET /apache_pb.gif HTTP/1.0"),
+                "request_method" => Value::from("GET"),
+                "request_uri" => Value::from("/apache_pb.gif"),
+                "server_protocol" => Value::from("HTTP/1.0"),
+                "status" => Value::from(200),
+                "body_bytes_sent" => Value::from(2326),
+                log_schema().timestamp_key() => timestamp,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_combined_log_format() {
+        let deserializer = deserializer(AccessLogFormat::Combined, None);
+        let fields = parse(
+            &deserializer,
+            r#"10.1.2.3 - - [13/Jul/2022:08:01:02 +0000] "POST /api?q=\"x\" HTTP/1.1" 304 - "-" "curl/7.79.1""#,
+        );
+
+        assert_eq!(fields["request_uri"], Value::from(r#"/api?q=\"x\""#));
+        assert_eq!(fields["status"], Value::from(304));
+        assert_eq!(fields["http_user_agent"], Value::from("curl/7.79.1"));
+        assert!(!fields.contains_key("remote_user"));
+        assert!(!fields.contains_key("body_bytes_sent"));
+        assert!(!fields.contains_key("http_referer"));
+    }
+
+    #[test]
+    fn deserialize_custom_log_format() {
+        let deserializer = deserializer(
+            AccessLogFormat::Custom,
+            Some(
+                r#"$remote_addr [$time_iso8601] "$request" $status ${request_time}s $upstream_response_time"#,
+            ),
+        );
+        let fields = parse(
+            &deserializer,
+            r#"::1 [2022-07-13T08:01:02+02:00] "GET / HTTP/2.0" 502 0.250s 0.100,0.150"#,
+        );
+
+        assert_eq!(fields["status"], Value::from(502));
+        assert_eq!(fields["request_time"], Value::from_f64_or_zero(0.25));
+        assert_eq!(fields["upstream_response_time"], Value::from("0.100,0.150"));
+        assert_eq!(
+            fields[log_schema().timestamp_key()],
+            Value::from(Utc.ymd(2022, 7, 13).and_hms(6, 1, 2))
+        );
+    }
+
+    #[test]
+    fn rejects_lines_not_matching_the_format() {
+        let deserializer = deserializer(AccessLogFormat::Common, None);
+
+        assert!(deserializer
+            .parse(Bytes::from_static(b"not an access log"))
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_formats() {
+        for (format, log_format) in [
+            (AccessLogFormat::Custom, None),
+            (AccessLogFormat::Common, Some("$status")),
+            (AccessLogFormat::Custom, Some("${status")),
+            (AccessLogFormat::Custom, Some("$ $status")),
+        ] {
+            let config = AccessLogDeserializerConfig::new(AccessLogDeserializerOptions {
+                format,
+                log_format: log_format.map(Into::into),
+            });
+            assert!(config.build().is_err());
+        }
+    }
+}
@@ -0,0 +1,532 @@
+use std::{convert::TryFrom, fmt::Debug, path::PathBuf, sync::Arc};
+
+use avro_rs::{types::Value as AvroValue, Schema};
+use bytes::Bytes;
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+use crate::decoding::BuildError;
+
+/// The magic byte starting the messages framed with the Confluent Schema Registry wire format.
+pub const SCHEMA_REGISTRY_MAGIC_BYTE: u8 = 0;
+
+/// Config used to build an `AvroDeserializer`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AvroDeserializerConfig {
+    /// Options for the Avro deserializer.
+    pub avro: AvroDeserializerOptions,
+}
+
+impl AvroDeserializerConfig {
+    /// Build the `AvroDeserializer` from this configuration.
+    ///
+    /// The schemas referenced by the messages framed with the schema registry wire format are
+    /// fetched by the caller, see `AvroDeserializerConfig::build_with_resolver`.
+    pub fn build(&self) -> Result<AvroDeserializer, BuildError> {
+        if self.avro.schema_registry.is_some() {
+            return Err("Decoding Avro with a schema registry requires a schema resolver".into());
+        }
+        let schema = self.avro.read_schema()?;
+        Ok(AvroDeserializer::new(Arc::new(schema)))
+    }
+
+    /// Build the `AvroDeserializer` from this configuration, resolving the schemas of the
+    /// messages framed with the schema registry wire format with `resolver`.
+    pub fn build_with_resolver(
+        &self,
+        resolver: Arc<dyn AvroSchemaResolver>,
+    ) -> Result<AvroDeserializer, BuildError> {
+        if self.avro.schema.is_some() || self.avro.schema_file.is_some() {
+            return Err(
+                "Only one of `schema`, `schema_file` and `schema_registry` can be set".into(),
+            );
+        }
+        Ok(AvroDeserializer {
+            schema: AvroSchema::Registry(resolver),
+        })
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        // TODO: Convert the Avro schema to a vector schema definition.
+        schema::Definition::empty().unknown_fields(Kind::any())
+    }
+}
+
+/// Options for building an `AvroDeserializer`.
+///
+/// Exactly one of `schema`, `schema_file` and `schema_registry` must be set.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct AvroDeserializerOptions {
+    /// The Avro schema of the messages, as JSON.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub schema: Option<String>,
+
+    /// The path of a file containing the Avro schema of the messages, as JSON.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub schema_file: Option<PathBuf>,
+
+    /// The schema registry to fetch the Avro schemas of the messages from, by the ID each message
+    /// is framed with.
+    #[configurable(derived)]
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub schema_registry: Option<AvroSchemaRegistryOptions>,
+}
+
+impl AvroDeserializerOptions {
+    fn read_schema(&self) -> Result<Schema, BuildError> {
+        read_schema(self.schema.as_deref(), self.schema_file.as_ref())
+    }
+}
+
+/// Options for fetching the Avro schemas from a [Confluent Schema Registry][schema_registry].
+///
+/// The messages must be framed with the schema registry wire format: a zero magic byte and the ID
+/// of their schema, as a big-endian 32-bit integer, followed by the Avro datum.
+///
+/// [schema_registry]: https://docs.confluent.io/platform/current/schema-registry/index.html
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AvroSchemaRegistryOptions {
+    /// The URL of the schema registry.
+    pub url: String,
+
+    /// The username used to authenticate with the schema registry.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub username: Option<String>,
+
+    /// The password used to authenticate with the schema registry.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub password: Option<String>,
+}
+
+/// Reads the JSON definition of an Avro schema, either inline or from a file.
+pub(crate) fn read_schema_text(
+    schema: Option<&str>,
+    schema_file: Option<&PathBuf>,
+) -> Result<String, BuildError> {
+    match (schema, schema_file) {
+        (Some(schema), None) => Ok(schema.to_owned()),
+        (None, Some(path)) => std::fs::read_to_string(path).map_err(|error| {
+            format!("Failed reading Avro schema from {:?}: {}", path, error).into()
+        }),
+        (None, None) => Err("One of `schema` and `schema_file` must be set".into()),
+        (Some(_), Some(_)) => Err("Only one of `schema` and `schema_file` can be set".into()),
+    }
+}
+
+/// Reads an Avro schema, either inline or from a file.
+pub(crate) fn read_schema(
+    schema: Option<&str>,
+    schema_file: Option<&PathBuf>,
+) -> Result<Schema, BuildError> {
+    let schema = read_schema_text(schema, schema_file)?;
+    Schema::parse_str(&schema).map_err(|error| format!("Invalid Avro schema: {}", error).into())
+}
+
+/// Resolves the Avro schemas referenced by their ID in the messages framed with the schema
+/// registry wire format.
+pub trait AvroSchemaResolver: Debug + Send + Sync {
+    /// Returns the schema with the given ID.
+    fn resolve(&self, id: u32) -> vector_core::Result<Arc<Schema>>;
+}
+
+#[derive(Debug, Clone)]
+enum AvroSchema {
+    Fixed(Arc<Schema>),
+    Registry(Arc<dyn AvroSchemaResolver>),
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing an Avro datum.
+#[derive(Debug, Clone)]
+pub struct AvroDeserializer {
+    schema: AvroSchema,
+}
+
+impl AvroDeserializer {
+    /// Creates a new `AvroDeserializer` decoding all messages with the given schema.
+    pub const fn new(schema: Arc<Schema>) -> Self {
+        Self {
+            schema: AvroSchema::Fixed(schema),
+        }
+    }
+}
+
+impl Deserializer for AvroDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        if bytes.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let (schema, mut datum) = match &self.schema {
+            AvroSchema::Fixed(schema) => (Arc::clone(schema), &bytes[..]),
+            AvroSchema::Registry(resolver) => {
+                if bytes.len() < 5 || bytes[0] != SCHEMA_REGISTRY_MAGIC_BYTE {
+                    return Err("Message is not framed with the schema registry wire format".into());
+                }
+                let id = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+                (resolver.resolve(id)?, &bytes[5..])
+            }
+        };
+
+        let value = avro_rs::from_avro_datum(&schema, &mut datum, None)
+            .map_err(|error| format!("Error parsing Avro: {}", error))?;
+        let mut log = match avro_to_value(value, &schema) {
+            Value::Object(fields) => LogEvent::from(fields),
+            value => {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key(), value);
+                log
+            }
+        };
+
+        let timestamp_key = log_schema().timestamp_key();
+        if !log.contains(timestamp_key) {
+            log.insert(timestamp_key, Utc::now());
+        }
+
+        Ok(smallvec![log.into()])
+    }
+}
+
+/// Converts an Avro value into the equivalent value, following its schema to convert the logical
+/// types.
+pub fn avro_to_value(value: AvroValue, schema: &Schema) -> Value {
+    match value {
+        AvroValue::Null => Value::Null,
+        AvroValue::Boolean(value) => Value::Boolean(value),
+        AvroValue::Int(value) => Value::Integer(value.into()),
+        AvroValue::Long(value) => Value::Integer(value),
+        AvroValue::Float(value) => Value::from_f64_or_zero(f64::from(value)),
+        AvroValue::Double(value) => Value::from_f64_or_zero(value),
+        AvroValue::Bytes(value) | AvroValue::Fixed(_, value) => Value::Bytes(value.into()),
+        AvroValue::String(value) | AvroValue::Enum(_, value) => Value::from(value),
+        AvroValue::Union(value) => {
+            let schema = match schema {
+                Schema::Union(union) => union.find_schema(&value).map(|(_, schema)| schema),
+                _ => None,
+            };
+            avro_to_value(*value, schema.unwrap_or(&Schema::Null))
+        }
+        AvroValue::Array(values) => {
+            let items = match schema {
+                Schema::Array(items) => items.as_ref(),
+                _ => &Schema::Null,
+            };
+            Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| avro_to_value(value, items))
+                    .collect(),
+            )
+        }
+        AvroValue::Map(values) => {
+            let items = match schema {
+                Schema::Map(items) => items.as_ref(),
+                _ => &Schema::Null,
+            };
+            Value::Object(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, avro_to_value(value, items)))
+                    .collect(),
+            )
+        }
+        AvroValue::Record(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let field_schema = match schema {
+                        Schema::Record { fields, lookup, .. } => lookup
+                            .get(&name)
+                            .and_then(|index| fields.get(*index))
+                            .map(|field| &field.schema),
+                        _ => None,
+                    };
+                    let value = avro_to_value(value, field_schema.unwrap_or(&Schema::Null));
+                    (name, value)
+                })
+                .collect(),
+        ),
+        AvroValue::Date(days) => {
+            let date = NaiveDate::from_ymd(1970, 1, 1) + chrono::Duration::days(days.into());
+            Value::Timestamp(Utc.from_utc_datetime(&date.and_hms(0, 0, 0)))
+        }
+        AvroValue::TimeMillis(millis) => time_to_value(i64::from(millis) * 1_000_000),
+        AvroValue::TimeMicros(micros) => time_to_value(micros * 1_000),
+        AvroValue::TimestampMillis(millis) => Value::Timestamp(Utc.timestamp_millis(millis)),
+        AvroValue::TimestampMicros(micros) => Value::Timestamp(Utc.timestamp_nanos(micros * 1_000)),
+        AvroValue::Decimal(decimal) => {
+            let scale = match schema {
+                Schema::Decimal { scale, .. } => *scale,
+                _ => 0,
+            };
+            match Vec::<u8>::try_from(&decimal) {
+                Ok(bytes) => decimal_to_value(&bytes, scale),
+                Err(_) => Value::Null,
+            }
+        }
+        AvroValue::Duration(duration) => Value::Object(
+            [
+                ("months", u32::from(duration.months())),
+                ("days", u32::from(duration.days())),
+                ("milliseconds", u32::from(duration.millis())),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), Value::Integer(value.into())))
+            .collect(),
+        ),
+        AvroValue::Uuid(uuid) => Value::from(uuid.to_string()),
+    }
+}
+
+/// Converts a time of day, in nanoseconds since midnight, into its `HH:MM:SS.fff` form.
+fn time_to_value(nanos: i64) -> Value {
+    let seconds = nanos.div_euclid(1_000_000_000) as u32;
+    let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+    match NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos) {
+        Some(time) => Value::from(time.to_string()),
+        None => Value::Null,
+    }
+}
+
+/// Converts a decimal, as the big-endian two's complement of its unscaled value, into its exact
+/// string representation, as there is no decimal value.
+fn decimal_to_value(bytes: &[u8], scale: usize) -> Value {
+    if bytes.is_empty() || bytes.len() > 16 {
+        return Value::Bytes(bytes.to_vec().into());
+    }
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0 };
+    let mut buffer = [fill; 16];
+    buffer[16 - bytes.len()..].copy_from_slice(bytes);
+    let unscaled = i128::from_be_bytes(buffer);
+
+    let digits = unscaled.unsigned_abs().to_string();
+    let digits = if digits.len() <= scale {
+        format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits)
+    } else {
+        digits
+    };
+    let sign = if unscaled < 0 { "-" } else { "" };
+    let value = if scale == 0 {
+        format!("{}{}", sign, digits)
+    } else {
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        format!("{}{}.{}", sign, integer, fraction)
+    };
+    Value::from(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use indoc::indoc;
+    use vector_common::btreemap;
+
+    use super::*;
+
+    const SCHEMA: &str = indoc! {r#"
+        {
+            "type": "record",
+            "name": "Log",
+            "fields": [
+                {"name": "message", "type": "string"},
+                {"name": "status", "type": "int"}
+            ]
+        }
+    "#};
+
+    fn datum(schema: &Schema) -> Vec<u8> {
+        let value = AvroValue::Record(vec![
+            ("message".into(), AvroValue::String("hello".into())),
+            ("status".into(), AvroValue::Int(200)),
+        ]);
+        avro_rs::to_avro_datum(schema, value).unwrap()
+    }
+
+    #[derive(Debug)]
+    struct StaticResolver(HashMap<u32, Arc<Schema>>);
+
+    impl AvroSchemaResolver for StaticResolver {
+        fn resolve(&self, id: u32) -> vector_core::Result<Arc<Schema>> {
+            self.0
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| format!("Unknown schema {}", id).into())
+        }
+    }
+
+    #[test]
+    fn deserialize_avro() {
+        let config = AvroDeserializerConfig {
+            avro: AvroDeserializerOptions {
+                schema: Some(SCHEMA.to_owned()),
+                ..Default::default()
+            },
+        };
+        let deserializer = config.build().unwrap();
+        let bytes = datum(&Schema::parse_str(SCHEMA).unwrap());
+
+        let events = deserializer.parse(bytes.into()).unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["status"], 200.into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_avro_with_schema_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.avsc");
+        std::fs::write(&path, SCHEMA).unwrap();
+        let config = AvroDeserializerConfig {
+            avro: AvroDeserializerOptions {
+                schema_file: Some(path),
+                ..Default::default()
+            },
+        };
+        let deserializer = config.build().unwrap();
+        let bytes = datum(&Schema::parse_str(SCHEMA).unwrap());
+
+        let events = deserializer.parse(bytes.into()).unwrap();
+        assert_eq!(events[0].as_log()["message"], "hello".into());
+    }
+
+    #[test]
+    fn deserialize_avro_with_schema_registry() {
+        let schema = Arc::new(Schema::parse_str(SCHEMA).unwrap());
+        let resolver = StaticResolver(HashMap::from([(7, Arc::clone(&schema))]));
+        let config = AvroDeserializerConfig {
+            avro: AvroDeserializerOptions {
+                schema_registry: Some(AvroSchemaRegistryOptions {
+                    url: "http://localhost:8081".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+        assert!(config.build().is_err());
+        let deserializer = config.build_with_resolver(Arc::new(resolver)).unwrap();
+
+        let mut bytes = vec![SCHEMA_REGISTRY_MAGIC_BYTE, 0, 0, 0, 7];
+        bytes.extend(datum(&schema));
+        let events = deserializer.parse(bytes.into()).unwrap();
+        assert_eq!(events[0].as_log()["status"], 200.into());
+
+        let mut unknown = vec![SCHEMA_REGISTRY_MAGIC_BYTE, 0, 0, 0, 8];
+        unknown.extend(datum(&schema));
+        assert!(deserializer.parse(unknown.into()).is_err());
+        assert!(deserializer.parse(datum(&schema).into()).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        let config = AvroDeserializerConfig {
+            avro: AvroDeserializerOptions {
+                schema: Some(r#"{"type": "record"}"#.to_owned()),
+                ..Default::default()
+            },
+        };
+        assert!(config.build().is_err());
+        assert!(AvroDeserializerConfig {
+            avro: AvroDeserializerOptions::default()
+        }
+        .build()
+        .is_err());
+    }
+
+    #[test]
+    fn converts_logical_types() {
+        let schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "order",
+                "fields": [
+                    {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+                    {"name": "placed_on", "type": {"type": "int", "logicalType": "date"}},
+                    {"name": "placed_at", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+                    {"name": "cutoff", "type": {"type": "int", "logicalType": "time-millis"}},
+                    {"name": "total", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}]},
+                    {"name": "tags", "type": {"type": "array", "items": "string"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let value = AvroValue::Record(vec![
+            (
+                "id".into(),
+                AvroValue::Uuid("5e0e5c4a-9c0a-4b7e-8f3a-6c1d2b3a4f5e".parse().unwrap()),
+            ),
+            ("placed_on".into(), AvroValue::Date(19_213)),
+            (
+                "placed_at".into(),
+                AvroValue::TimestampMillis(1_660_000_000_123),
+            ),
+            ("cutoff".into(), AvroValue::TimeMillis(61_200_500)),
+            (
+                "total".into(),
+                AvroValue::Union(Box::new(AvroValue::Decimal(
+                    (-12345_i32).to_be_bytes().into(),
+                ))),
+            ),
+            (
+                "tags".into(),
+                AvroValue::Array(vec![AvroValue::String("express".into())]),
+            ),
+        ]);
+
+        let value = avro_to_value(value, &schema);
+        assert_eq!(
+            value,
+            Value::Object(btreemap! {
+                "id" => "5e0e5c4a-9c0a-4b7e-8f3a-6c1d2b3a4f5e",
+                "placed_on" => Utc.ymd(2022, 8, 9).and_hms(0, 0, 0),
+                "placed_at" => Utc.timestamp_millis(1_660_000_000_123),
+                "cutoff" => "17:00:00.500",
+                "total" => "-123.45",
+                "tags" => Value::Array(vec!["express".into()]),
+            })
+        );
+    }
+
+    #[test]
+    fn converts_decimals() {
+        assert_eq!(decimal_to_value(&[0x30, 0x39], 0), Value::from("12345"));
+        assert_eq!(decimal_to_value(&[0x30, 0x39], 3), Value::from("12.345"));
+        assert_eq!(decimal_to_value(&[0x07], 3), Value::from("0.007"));
+        assert_eq!(decimal_to_value(&[0xff], 1), Value::from("-0.1"));
+    }
+}
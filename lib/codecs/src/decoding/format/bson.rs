@@ -0,0 +1,447 @@
+use std::collections::BTreeMap;
+
+use bson::{spec::BinarySubtype, Binary, Bson, Decimal128, Document};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// The bias of the exponent of Decimal128s.
+const DECIMAL128_EXPONENT_BIAS: i32 = 6176;
+
+/// The largest exponent of Decimal128s.
+const DECIMAL128_MAX_EXPONENT: i32 = 6111;
+
+/// The largest number of significant digits of Decimal128s.
+const DECIMAL128_MAX_DIGITS: usize = 34;
+
+/// Config used to build a `BsonDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BsonDeserializerConfig {
+    /// Options for the BSON deserializer.
+    #[serde(default)]
+    pub bson: BsonDeserializerOptions,
+}
+
+impl BsonDeserializerConfig {
+    /// Creates a new `BsonDeserializerConfig`.
+    pub const fn new(bson: BsonDeserializerOptions) -> Self {
+        Self { bson }
+    }
+
+    /// Build the `BsonDeserializer` from this configuration.
+    pub const fn build(&self) -> BsonDeserializer {
+        BsonDeserializer {
+            extended_json: self.bson.extended_json,
+        }
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        let kind = Kind::json().or_bytes().or_timestamp();
+        schema::Definition::empty()
+            .with_field(
+                log_schema().timestamp_key(),
+                // The BSON decoder inserts a timestamp, unless the document has a field of the
+                // same name.
+                kind.clone(),
+                Some("timestamp"),
+            )
+            .unknown_fields(kind)
+    }
+}
+
+/// Options for building a `BsonDeserializer`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct BsonDeserializerOptions {
+    /// Whether ObjectIds and Decimal128s are decoded into extended JSON objects, such as
+    /// `{"$oid": "..."}`, rather than strings.
+    ///
+    /// The BSON encoder encodes these objects back into their BSON type.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub extended_json: bool,
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing a sequence of [BSON][bson]
+/// documents, such as a `mongodump` file.
+///
+/// Each document of the sequence is decoded into an event. ObjectIds are decoded into their
+/// hexadecimal string, Decimal128s into their exact decimal string, date/times into timestamps
+/// and generic binary data into bytes. The types without an equivalent, such as the timestamps
+/// of the oplog, are decoded into their relaxed extended JSON object.
+///
+/// [bson]: https://bsonspec.org/spec.html
+#[derive(Debug, Clone, Default)]
+pub struct BsonDeserializer {
+    extended_json: bool,
+}
+
+impl Deserializer for BsonDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let mut events: SmallVec<[Event; 1]> = smallvec![];
+        let mut reader = &bytes[..];
+        while !reader.is_empty() {
+            let document = Document::from_reader(&mut reader)
+                .map_err(|error| format!("Error parsing BSON: {}", error))?;
+            events.push(LogEvent::from(self.to_fields(document)).into());
+        }
+
+        let timestamp = Utc::now();
+        for event in &mut events {
+            let log = event.as_mut_log();
+            let timestamp_key = log_schema().timestamp_key();
+
+            if !log.contains(timestamp_key) {
+                log.insert(timestamp_key, timestamp);
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+impl BsonDeserializer {
+    fn to_fields(&self, document: Document) -> BTreeMap<String, Value> {
+        document
+            .into_iter()
+            .map(|(key, value)| (key, self.to_value(value)))
+            .collect()
+    }
+
+    fn to_value(&self, bson: Bson) -> Value {
+        match bson {
+            Bson::Null | Bson::Undefined => Value::Null,
+            Bson::Boolean(boolean) => Value::Boolean(boolean),
+            Bson::Int32(integer) => Value::Integer(integer.into()),
+            Bson::Int64(integer) => Value::Integer(integer),
+            Bson::Double(float) => Value::from_f64_or_zero(float),
+            Bson::String(string) => Value::from(string),
+            Bson::ObjectId(id) if !self.extended_json => Value::from(id.to_hex()),
+            Bson::Decimal128(decimal) if !self.extended_json => {
+                Value::from(decimal128_to_string(&decimal))
+            }
+            Bson::Decimal128(decimal) => Value::from(BTreeMap::from([(
+                "$numberDecimal".to_owned(),
+                Value::from(decimal128_to_string(&decimal)),
+            )])),
+            Bson::DateTime(date_time) => Value::Timestamp(date_time.to_chrono()),
+            Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            }) => Value::Bytes(bytes.into()),
+            Bson::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.to_value(value))
+                    .collect(),
+            ),
+            Bson::Document(document) => Value::Object(self.to_fields(document)),
+            bson => Value::from(bson.into_relaxed_extjson()),
+        }
+    }
+}
+
+/// Formats a Decimal128 into its exact decimal string, as specified by the BSON Decimal128
+/// specification.
+pub(crate) fn decimal128_to_string(decimal: &Decimal128) -> String {
+    let bits = u128::from_le_bytes(decimal.bytes());
+    let sign = if bits >> 127 == 1 { "-" } else { "" };
+    match (bits >> 122) & 0x1f {
+        0x1f => return "NaN".to_owned(),
+        0x1e => return format!("{}Infinity", sign),
+        _ => {}
+    }
+
+    let (exponent, coefficient) = if (bits >> 125) & 0b11 == 0b11 {
+        // The coefficients of this form exceed the largest coefficient, so the value is zero.
+        ((bits >> 111) & 0x3fff, 0)
+    } else {
+        ((bits >> 113) & 0x3fff, bits & ((1 << 113) - 1))
+    };
+    let exponent = exponent as i32 - DECIMAL128_EXPONENT_BIAS;
+    let coefficient = if coefficient < 10u128.pow(DECIMAL128_MAX_DIGITS as u32) {
+        coefficient
+    } else {
+        0
+    };
+
+    let digits = coefficient.to_string();
+    let adjusted_exponent = exponent + digits.len() as i32 - 1;
+    if exponent <= 0 && adjusted_exponent >= -6 {
+        let point = digits.len() as i32 + exponent;
+        if exponent == 0 {
+            format!("{}{}", sign, digits)
+        } else if point > 0 {
+            let (integer, fraction) = digits.split_at(point as usize);
+            format!("{}{}.{}", sign, integer, fraction)
+        } else {
+            format!("{}0.{}{}", sign, "0".repeat(-point as usize), digits)
+        }
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let point = if rest.is_empty() { "" } else { "." };
+        format!("{}{}{}{}E{:+}", sign, first, point, rest, adjusted_exponent)
+    }
+}
+
+/// Parses a decimal string into a Decimal128, unless it can't be represented exactly.
+pub(crate) fn parse_decimal128(text: &str) -> Option<Decimal128> {
+    let (negative, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let sign = u128::from(negative) << 127;
+    if unsigned.eq_ignore_ascii_case("nan") {
+        return Some(Decimal128::from_bytes((0x7cu128 << 120).to_le_bytes()));
+    }
+    if unsigned.eq_ignore_ascii_case("inf") || unsigned.eq_ignore_ascii_case("infinity") {
+        return Some(Decimal128::from_bytes((sign | 0x78 << 120).to_le_bytes()));
+    }
+
+    let (mantissa, exponent) = match unsigned.find(|c| c == 'e' || c == 'E') {
+        Some(index) => (
+            &unsigned[..index],
+            unsigned[index + 1..].parse::<i32>().ok()?,
+        ),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mut digits = format!("{}{}", integer, fraction)
+        .trim_start_matches('0')
+        .to_owned();
+    let mut exponent = exponent.checked_sub(fraction.len() as i32)?;
+    // Trailing zeros are traded for exponents to fit the precision and the range of exponents.
+    loop {
+        if digits.len() > DECIMAL128_MAX_DIGITS || exponent < -DECIMAL128_EXPONENT_BIAS {
+            if digits.is_empty() {
+                exponent = -DECIMAL128_EXPONENT_BIAS;
+            } else if digits.ends_with('0') {
+                digits.pop();
+                exponent += 1;
+            } else {
+                return None;
+            }
+        } else if exponent > DECIMAL128_MAX_EXPONENT {
+            if digits.is_empty() {
+                exponent = DECIMAL128_MAX_EXPONENT;
+            } else if digits.len() < DECIMAL128_MAX_DIGITS {
+                digits.push('0');
+                exponent -= 1;
+            } else {
+                return None;
+            }
+        } else {
+            break;
+        }
+    }
+
+    let coefficient = if digits.is_empty() {
+        0
+    } else {
+        digits.parse::<u128>().ok()?
+    };
+    let biased_exponent = (exponent + DECIMAL128_EXPONENT_BIAS) as u128;
+    Some(Decimal128::from_bytes(
+        (sign | biased_exponent << 113 | coefficient).to_le_bytes(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::{doc, oid::ObjectId, DateTime, Timestamp};
+    use chrono::TimeZone;
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn parse(deserializer: &BsonDeserializer, documents: &[Document]) -> Vec<LogEvent> {
+        let mut bytes = Vec::new();
+        for document in documents {
+            document.to_writer(&mut bytes).unwrap();
+        }
+        deserializer
+            .parse(Bytes::from(bytes))
+            .unwrap()
+            .into_iter()
+            .map(Event::into_log)
+            .collect()
+    }
+
+    #[test]
+    fn deserialize_bson() {
+        let id = ObjectId::parse_str("62a0c4f4b5a3d2c1e0f9a8b7").unwrap();
+        let price = Decimal128::from_bytes((0x303cu128 << 112 | 1999).to_le_bytes());
+        let raw = Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![0, 159],
+        };
+        let ts = Timestamp {
+            time: 1_654_084_800,
+            increment: 1,
+        };
+        let events = parse(
+            &BsonDeserializer::default(),
+            &[doc! {
+                "_id": id,
+                "message": "hi",
+                "count": 3_i32,
+                "total": 4_i64,
+                "ratio": 0.5,
+                "price": price,
+                "created": DateTime::from_millis(1_654_084_800_250),
+                "raw": raw,
+                "ts": ts,
+                "tags": ["a", Bson::Null],
+            }],
+        );
+
+        assert_eq!(events.len(), 1);
+        let log = &events[0];
+        let fields = log.as_map().unwrap();
+        assert_eq!(log["_id"], Value::from("62a0c4f4b5a3d2c1e0f9a8b7"));
+        assert_eq!(log["message"], Value::from("hi"));
+        assert_eq!(log["count"], Value::from(3));
+        assert_eq!(log["total"], Value::from(4));
+        assert_eq!(log["ratio"], Value::from_f64_or_zero(0.5));
+        assert_eq!(log["price"], Value::from("19.99"));
+        assert_eq!(
+            log["created"],
+            Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250))
+        );
+        assert_eq!(log["raw"], Value::Bytes(Bytes::from_static(&[0, 159])));
+        assert_eq!(
+            fields["ts"],
+            Value::from(btreemap! {
+                "$timestamp" => Value::from(btreemap! {
+                    "t" => Value::from(1_654_084_800),
+                    "i" => Value::from(1),
+                }),
+            })
+        );
+        assert_eq!(
+            log["tags"],
+            Value::Array(vec![Value::from("a"), Value::Null])
+        );
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_extended_json() {
+        let deserializer = BsonDeserializerConfig::new(BsonDeserializerOptions {
+            extended_json: true,
+        })
+        .build();
+        let events = parse(
+            &deserializer,
+            &[doc! {
+                "_id": ObjectId::parse_str("62a0c4f4b5a3d2c1e0f9a8b7").unwrap(),
+                "price": parse_decimal128("19.99").unwrap(),
+            }],
+        );
+
+        let fields = events[0].as_map().unwrap();
+        assert_eq!(
+            fields["_id"],
+            Value::from(btreemap! { "$oid" => Value::from("62a0c4f4b5a3d2c1e0f9a8b7") })
+        );
+        assert_eq!(
+            fields["price"],
+            Value::from(btreemap! { "$numberDecimal" => Value::from("19.99") })
+        );
+    }
+
+    #[test]
+    fn deserialize_document_sequences() {
+        let events = parse(
+            &BsonDeserializer::default(),
+            &[doc! { "id": 1_i32 }, doc! { "id": 2_i32 }],
+        );
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|log| log["id"].clone())
+                .collect::<Vec<_>>(),
+            vec![Value::from(1), Value::from(2)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert!(BsonDeserializer::default()
+            .parse(Bytes::from_static(&[0x10, 0, 0, 0, 0x02]))
+            .is_err());
+        assert!(BsonDeserializer::default()
+            .parse(Bytes::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn formats_decimal128s() {
+        for (coefficient, exponent, text) in [
+            (0, 0, "0"),
+            (1, 0, "1"),
+            (100, -2, "1.00"),
+            (1, -3, "0.001"),
+            (1, -7, "1E-7"),
+            (1, 3, "1E+3"),
+            (12345, -10, "0.0000012345"),
+            (12345, 2, "1.2345E+6"),
+            (0, -6176, "0E-6176"),
+        ] {
+            let bits = ((exponent + DECIMAL128_EXPONENT_BIAS) as u128) << 113 | coefficient;
+            let decimal = Decimal128::from_bytes(bits.to_le_bytes());
+            assert_eq!(decimal128_to_string(&decimal), text);
+            assert_eq!(parse_decimal128(text).unwrap().bytes(), decimal.bytes());
+        }
+
+        for text in ["-1.5", "-Infinity", "NaN"] {
+            assert_eq!(decimal128_to_string(&parse_decimal128(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn parses_decimal128s_exactly() {
+        assert_eq!(
+            parse_decimal128("1").unwrap().bytes(),
+            (0x3040u128 << 112 | 1).to_le_bytes()
+        );
+        assert!(parse_decimal128("1.5e-6200").is_none());
+        assert!(parse_decimal128("12345678901234567890123456789012345").is_none());
+        assert_eq!(
+            decimal128_to_string(&parse_decimal128("12345678901234567890123456789012340").unwrap()),
+            "1.234567890123456789012345678901234E+34"
+        );
+        assert!(parse_decimal128("1.2.3").is_none());
+        assert!(parse_decimal128("").is_none());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value as CborValue;
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// The CBOR tag of date/time strings.
+const DATE_TIME_STRING_TAG: u64 = 0;
+
+/// The CBOR tag of epoch-based date/times.
+const EPOCH_DATE_TIME_TAG: u64 = 1;
+
+/// Config used to build a `CborDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CborDeserializerConfig;
+
+impl CborDeserializerConfig {
+    /// Build the `CborDeserializer` from this configuration.
+    pub const fn build(&self) -> CborDeserializer {
+        CborDeserializer
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        let kind = Kind::json().or_bytes().or_timestamp();
+        schema::Definition::empty()
+            .with_field(
+                log_schema().timestamp_key(),
+                // The CBOR decoder inserts a timestamp, unless the map has a field of the same name.
+                kind.clone(),
+                Some("timestamp"),
+            )
+            .unknown_fields(kind)
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing a sequence of [CBOR][cbor] data
+/// items.
+///
+/// Each map of the sequence is decoded into an event, as are the maps of the arrays of the
+/// sequence. Byte strings are decoded into bytes values, date/times tagged as such into timestamps,
+/// and the values of the other tags into the value they tag.
+///
+/// [cbor]: https://www.rfc-editor.org/rfc/rfc8949.html
+#[derive(Debug, Clone, Default)]
+pub struct CborDeserializer;
+
+impl Deserializer for CborDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let mut events: SmallVec<[Event; 1]> = smallvec![];
+        for item in serde_cbor::Deserializer::from_slice(&bytes).into_iter::<CborValue>() {
+            let item = item.map_err(|error| format!("Error parsing CBOR: {}", error))?;
+            match item {
+                CborValue::Array(items) => {
+                    for item in items {
+                        events.push(to_event(item)?);
+                    }
+                }
+                item => events.push(to_event(item)?),
+            }
+        }
+
+        let timestamp = Utc::now();
+        for event in &mut events {
+            let log = event.as_mut_log();
+            let timestamp_key = log_schema().timestamp_key();
+
+            if !log.contains(timestamp_key) {
+                log.insert(timestamp_key, timestamp);
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+fn to_event(item: CborValue) -> vector_core::Result<Event> {
+    match to_value(item) {
+        Value::Object(fields) => Ok(LogEvent::from(fields).into()),
+        _ => Err("Attempted to convert a CBOR data item which isn't a map into an event".into()),
+    }
+}
+
+fn to_value(item: CborValue) -> Value {
+    match item {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(boolean) => Value::Boolean(boolean),
+        CborValue::Integer(integer) => i64::try_from(integer)
+            .map_or_else(|_| Value::from_f64_or_zero(integer as f64), Value::Integer),
+        CborValue::Float(float) => Value::from_f64_or_zero(float),
+        CborValue::Bytes(bytes) => Value::Bytes(bytes.into()),
+        CborValue::Text(text) => Value::from(text),
+        CborValue::Array(items) => Value::Array(items.into_iter().map(to_value).collect()),
+        CborValue::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (to_key(key), to_value(value)))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+        CborValue::Tag(tag, item) => to_tagged_value(tag, *item),
+        // `serde_cbor::Value` is non exhaustive.
+        _ => Value::Null,
+    }
+}
+
+/// Decodes the date/times tagged as such into timestamps, and the other tagged items into the
+/// values they hold.
+fn to_tagged_value(tag: u64, item: CborValue) -> Value {
+    let timestamp = match (tag, &item) {
+        (DATE_TIME_STRING_TAG, CborValue::Text(text)) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        (EPOCH_DATE_TIME_TAG, CborValue::Integer(seconds)) => i64::try_from(*seconds)
+            .ok()
+            .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single()),
+        (EPOCH_DATE_TIME_TAG, CborValue::Float(seconds)) if seconds.is_finite() => Utc
+            .timestamp_opt(
+                seconds.floor() as i64,
+                ((seconds - seconds.floor()) * 1e9) as u32,
+            )
+            .single(),
+        _ => None,
+    };
+    timestamp.map_or_else(|| to_value(item), Value::Timestamp)
+}
+
+/// Map keys which aren't text strings are decoded into their textual representation.
+fn to_key(key: CborValue) -> String {
+    match key {
+        CborValue::Text(text) => text,
+        CborValue::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        CborValue::Integer(integer) => integer.to_string(),
+        key => match to_value(key) {
+            Value::Object(_) | Value::Array(_) => String::new(),
+            value => value.to_string_lossy(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(items: &[CborValue]) -> vector_core::Result<Vec<LogEvent>> {
+        let mut bytes = Vec::new();
+        for item in items {
+            serde_cbor::to_writer(&mut bytes, item).unwrap();
+        }
+        Ok(CborDeserializer
+            .parse(Bytes::from(bytes))?
+            .into_iter()
+            .map(Event::into_log)
+            .collect())
+    }
+
+    fn map(entries: Vec<(CborValue, CborValue)>) -> CborValue {
+        CborValue::Map(entries.into_iter().collect())
+    }
+
+    #[test]
+    fn deserialize_cbor() {
+        let events = parse(&[map(vec![
+            (
+                CborValue::Text("message".into()),
+                CborValue::Text("hi".into()),
+            ),
+            (CborValue::Text("count".into()), CborValue::Integer(3)),
+            (CborValue::Text("ratio".into()), CborValue::Float(0.5)),
+            (
+                CborValue::Text("raw".into()),
+                CborValue::Bytes(vec![0, 159]),
+            ),
+            (CborValue::Integer(1), CborValue::Bool(true)),
+            (
+                CborValue::Text("tags".into()),
+                CborValue::Array(vec![CborValue::Null, CborValue::Text("a".into())]),
+            ),
+        ])])
+        .unwrap();
+
+        assert_eq!(events.len(), 1);
+        let log = &events[0];
+        assert_eq!(log["message"], Value::from("hi"));
+        assert_eq!(log["count"], Value::from(3));
+        assert_eq!(log["ratio"], Value::from_f64_or_zero(0.5));
+        assert_eq!(log["raw"], Value::Bytes(Bytes::from_static(&[0, 159])));
+        assert_eq!(log.as_map().unwrap()["1"], Value::from(true));
+        assert_eq!(
+            log["tags"],
+            Value::Array(vec![Value::Null, Value::from("a")])
+        );
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_cbor_tags() {
+        let events = parse(&[map(vec![
+            (
+                CborValue::Text("timestamp".into()),
+                CborValue::Tag(0, Box::new(CborValue::Text("2022-06-01T12:00:00Z".into()))),
+            ),
+            (
+                CborValue::Text("epoch".into()),
+                CborValue::Tag(1, Box::new(CborValue::Integer(1_654_084_800))),
+            ),
+            (
+                CborValue::Text("uri".into()),
+                CborValue::Tag(32, Box::new(CborValue::Text("https://vector.dev".into()))),
+            ),
+        ])])
+        .unwrap();
+
+        let timestamp = Utc.ymd(2022, 6, 1).and_hms(12, 0, 0);
+        assert_eq!(events[0]["timestamp"], Value::Timestamp(timestamp));
+        assert_eq!(events[0]["epoch"], Value::Timestamp(timestamp));
+        assert_eq!(events[0]["uri"], Value::from("https://vector.dev"));
+    }
+
+    #[test]
+    fn deserialize_cbor_sequences_and_arrays() {
+        let item = |id| map(vec![(CborValue::Text("id".into()), CborValue::Integer(id))]);
+        let events = parse(&[item(1), CborValue::Array(vec![item(2), item(3)])]).unwrap();
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|log| log["id"].clone())
+                .collect::<Vec<_>>(),
+            vec![Value::from(1), Value::from(2), Value::from(3)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_items() {
+        assert!(parse(&[CborValue::Text("message".into())]).is_err());
+        assert!(CborDeserializer
+            .parse(Bytes::from_static(&[0xa1, 0x61]))
+            .is_err());
+        assert!(parse(&[]).unwrap().is_empty());
+    }
+}
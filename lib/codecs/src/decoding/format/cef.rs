@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::{kind::Collection, Kind};
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// The names of the fields the CEF header fields are decoded into, following the version.
+pub const CEF_HEADER_FIELDS: [&str; 6] = [
+    "device_vendor",
+    "device_product",
+    "device_version",
+    "device_event_class_id",
+    "name",
+    "severity",
+];
+
+/// Config used to build a `CefDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CefDeserializerConfig;
+
+impl CefDeserializerConfig {
+    /// Build the `CefDeserializer` from this configuration.
+    pub const fn build(&self) -> CefDeserializer {
+        CefDeserializer
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        CEF_HEADER_FIELDS.iter().fold(
+            schema::Definition::empty()
+                .with_field("version", Kind::bytes(), None)
+                .with_field(
+                    "extensions",
+                    Kind::object(Collection::from_unknown(Kind::bytes())),
+                    None,
+                )
+                .with_field(
+                    log_schema().timestamp_key(),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                ),
+            |definition, field| definition.with_field(*field, Kind::bytes(), None),
+        )
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing an [ArcSight Common Event
+/// Format][cef] message.
+///
+/// The header fields are decoded into the fields of the event named after them, and the
+/// extension into the `extensions` field, by key.
+///
+/// [cef]: https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf
+#[derive(Debug, Clone, Default)]
+pub struct CefDeserializer;
+
+impl Deserializer for CefDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let message = String::from_utf8_lossy(&bytes);
+        let message = message.trim();
+        if message.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let message = message
+            .strip_prefix("CEF:")
+            .ok_or("Error parsing CEF: missing \"CEF:\" prefix")?;
+        let (header, extension) = split_header(message)?;
+
+        let mut log = LogEvent::default();
+        let mut header = header.into_iter();
+        log.insert(
+            "version",
+            header.next().expect("header has a version").trim(),
+        );
+        for (field, value) in CEF_HEADER_FIELDS.iter().zip(header) {
+            log.insert(*field, value);
+        }
+        log.insert("extensions", parse_extension(extension));
+        log.insert(log_schema().timestamp_key(), Utc::now());
+
+        Ok(smallvec![log.into()])
+    }
+}
+
+/// Splits the version and header fields, unescaping them, from the extension.
+fn split_header(message: &str) -> vector_core::Result<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(CEF_HEADER_FIELDS.len() + 1);
+    let mut field = String::new();
+    let mut chars = message.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, c @ ('\\' | '|'))) => field.push(c),
+                Some((_, c)) => {
+                    field.push('\\');
+                    field.push(c);
+                }
+                None => field.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() == CEF_HEADER_FIELDS.len() + 1 {
+                    return Ok((fields, &message[index + 1..]));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    Err(format!(
+        "Error parsing CEF: expected {} header fields, found {}",
+        CEF_HEADER_FIELDS.len() + 1,
+        fields.len()
+    )
+    .into())
+}
+
+/// Parses the space separated `key=value` pairs of the extension.
+///
+/// Values can contain spaces, so a value ends where the key of the next pair starts: at the last
+/// space before the next unescaped equal sign.
+fn parse_extension(extension: &str) -> BTreeMap<String, Value> {
+    // The spans of the keys, as the index they start at and the index of their equal sign.
+    let mut keys = Vec::new();
+    let bytes = extension.as_bytes();
+    let mut value_start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'=' => {
+                let key_start = match extension[value_start..index].rfind(' ') {
+                    Some(space) => value_start + space + 1,
+                    None if keys.is_empty() => value_start,
+                    // An equal sign within a value.
+                    None => {
+                        index += 1;
+                        continue;
+                    }
+                };
+                if key_start < index {
+                    keys.push((key_start, index));
+                    value_start = index + 1;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    keys.iter()
+        .enumerate()
+        .map(|(position, (key_start, equal))| {
+            let value_end = keys
+                .get(position + 1)
+                .map_or(extension.len(), |(next_key_start, _)| *next_key_start);
+            let key = extension[*key_start..*equal].trim().to_owned();
+            let value = unescape_extension_value(extension[equal + 1..value_end].trim_end());
+            (key, Value::from(value))
+        })
+        .collect()
+}
+
+fn unescape_extension_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c @ ('\\' | '=')) => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn parse(input: &str) -> LogEvent {
+        let mut events = CefDeserializer
+            .parse(Bytes::from(input.to_owned()))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap().into_log()
+    }
+
+    #[test]
+    fn deserialize_cef() {
+        let log = parse(
+            r#"CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232"#,
+        );
+
+        assert_eq!(log["version"], Value::from("0"));
+        assert_eq!(log["device_vendor"], Value::from("Security"));
+        assert_eq!(log["device_product"], Value::from("threatmanager"));
+        assert_eq!(log["device_version"], Value::from("1.0"));
+        assert_eq!(log["device_event_class_id"], Value::from("100"));
+        assert_eq!(log["name"], Value::from("worm successfully stopped"));
+        assert_eq!(log["severity"], Value::from("10"));
+        assert_eq!(
+            log["extensions"],
+            Value::from(btreemap! {
+                "src" => "10.0.0.1",
+                "dst" => "2.1.2.2",
+                "spt" => "1232",
+            })
+        );
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_cef_with_escapes() {
+        let log = parse(
+            r#"CEF:1|a\|b|c\\d|1|id|name|Low|msg=detected a = sign \= and \\ here\nnext act=blocked  cs1Label=x"#,
+        );
+
+        assert_eq!(log["device_vendor"], Value::from("a|b"));
+        assert_eq!(log["device_product"], Value::from("c\\d"));
+        assert_eq!(log["severity"], Value::from("Low"));
+        assert_eq!(
+            log["extensions"],
+            Value::from(btreemap! {
+                "msg" => "detected a = sign = and \\ here\nnext",
+                "act" => "blocked",
+                "cs1Label" => "x",
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_cef_without_extension() {
+        let log = parse("CEF:0|v|p|1|id|name|3|");
+
+        assert_eq!(log["severity"], Value::from("3"));
+        assert_eq!(
+            log["extensions"],
+            Value::from(BTreeMap::<String, Value>::new())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        assert!(CefDeserializer
+            .parse(Bytes::from_static(b"LEEF:1.0|v|p|1|id|"))
+            .is_err());
+        assert!(CefDeserializer
+            .parse(Bytes::from_static(b"CEF:0|v|p|1"))
+            .is_err());
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_common::{
+    conversion::{parse_check_conversion_map, parse_conversion_map, Conversion},
+    TimeZone,
+};
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+use crate::decoding::BuildError;
+
+/// Config used to build a `CsvDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CsvDeserializerConfig {
+    /// Options for the CSV deserializer.
+    #[serde(default)]
+    pub csv: CsvDeserializerOptions,
+}
+
+impl CsvDeserializerConfig {
+    /// Creates a new `CsvDeserializerConfig`.
+    pub const fn new(csv: CsvDeserializerOptions) -> Self {
+        Self { csv }
+    }
+
+    /// Build the `CsvDeserializer` from this configuration.
+    pub fn build(&self) -> Result<CsvDeserializer, BuildError> {
+        let header = self
+            .csv
+            .header
+            .unwrap_or_else(|| self.csv.columns.is_empty());
+        if !header && self.csv.columns.is_empty() {
+            return Err("Either `columns` must be set or `header` enabled".into());
+        }
+        let timezone = self.csv.timezone.unwrap_or_default();
+        let conversions = if self.csv.columns.is_empty() {
+            parse_conversion_map(&self.csv.types, timezone)?
+        } else {
+            parse_check_conversion_map(&self.csv.types, &self.csv.columns, timezone)?
+        };
+        Ok(CsvDeserializer {
+            delimiter: self.csv.delimiter,
+            quote: self.csv.quote,
+            header,
+            columns: (!self.csv.columns.is_empty()).then(|| Arc::new(self.csv.columns.clone())),
+            conversions: Arc::new(conversions),
+            header_columns: Mutex::new(None),
+        })
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        let kind = Kind::bytes()
+            .or_integer()
+            .or_float()
+            .or_boolean()
+            .or_timestamp();
+        schema::Definition::empty()
+            .with_field(
+                log_schema().timestamp_key(),
+                // The CSV decoder inserts a timestamp, unless a column of the same name exists.
+                kind.clone(),
+                Some("timestamp"),
+            )
+            .unknown_fields(kind)
+    }
+}
+
+/// Options for building a `CsvDeserializer`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct CsvDeserializerOptions {
+    /// The character separating the fields of a record.
+    #[serde(default = "default_delimiter", with = "vector_core::serde::ascii_char")]
+    pub delimiter: u8,
+
+    /// The character quoting the fields containing delimiters, quotes or line breaks.
+    ///
+    /// Quote characters are escaped within quoted fields by doubling them.
+    #[serde(default = "default_quote", with = "vector_core::serde::ascii_char")]
+    pub quote: u8,
+
+    /// Whether the first record is a header naming the columns.
+    ///
+    /// When `columns` is set, the header is skipped. Defaults to `true` unless `columns` is set.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub header: Option<bool>,
+
+    /// The names of the columns, used as the names of the fields of the events.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub columns: Vec<String>,
+
+    /// The types the values of the columns are coerced to, by column name.
+    ///
+    /// The supported types are `bytes`, `integer`, `float`, `boolean`, `timestamp` and
+    /// `timestamp|<format>`. The values of the other columns are kept as strings.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub types: HashMap<String, String>,
+
+    /// The time zone of the timestamps without one. Defaults to the local time zone.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub timezone: Option<TimeZone>,
+}
+
+const fn default_delimiter() -> u8 {
+    b','
+}
+
+const fn default_quote() -> u8 {
+    b'"'
+}
+
+impl Default for CsvDeserializerOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            quote: default_quote(),
+            header: None,
+            columns: Vec::new(),
+            types: HashMap::new(),
+            timezone: None,
+        }
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing CSV records.
+///
+/// Each record of the frame is parsed into an event. When the records start with a header, the
+/// first record parsed by the deserializer is read as the header, so a new deserializer must be
+/// used for each CSV document.
+#[derive(Debug)]
+pub struct CsvDeserializer {
+    delimiter: u8,
+    quote: u8,
+    header: bool,
+    columns: Option<Arc<Vec<String>>>,
+    conversions: Arc<HashMap<String, Conversion>>,
+    header_columns: Mutex<Option<Arc<Vec<String>>>>,
+}
+
+impl Clone for CsvDeserializer {
+    /// Clones the deserializer, which reads the header of its own document.
+    fn clone(&self) -> Self {
+        Self {
+            delimiter: self.delimiter,
+            quote: self.quote,
+            header: self.header,
+            columns: self.columns.clone(),
+            conversions: Arc::clone(&self.conversions),
+            header_columns: Mutex::new(None),
+        }
+    }
+}
+
+impl CsvDeserializer {
+    fn to_event(
+        &self,
+        columns: &[String],
+        record: &::csv::ByteRecord,
+    ) -> vector_core::Result<Event> {
+        if record.len() > columns.len() {
+            return Err(format!(
+                "Record has {} fields, but only {} columns are defined",
+                record.len(),
+                columns.len()
+            )
+            .into());
+        }
+
+        let mut fields = BTreeMap::new();
+        for (column, field) in columns.iter().zip(record.iter()) {
+            let field = Bytes::copy_from_slice(field);
+            let value = match self.conversions.get(column) {
+                Some(conversion) => conversion
+                    .convert::<Value>(field)
+                    .map_err(|error| format!("Invalid value for column {:?}: {}", column, error))?,
+                None => Value::Bytes(field),
+            };
+            fields.insert(column.clone(), value);
+        }
+        Ok(LogEvent::from(fields).into())
+    }
+}
+
+impl Deserializer for CsvDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(bytes.as_ref());
+        let mut header_columns = self
+            .header_columns
+            .lock()
+            .expect("CSV header mutex poisoned");
+
+        let mut events: SmallVec<[Event; 1]> = smallvec![];
+        for record in reader.byte_records() {
+            let record = record.map_err(|error| format!("Error parsing CSV: {}", error))?;
+            if self.header && header_columns.is_none() {
+                let columns = record
+                    .iter()
+                    .map(|field| String::from_utf8_lossy(field).trim().to_owned())
+                    .collect();
+                *header_columns = Some(Arc::new(columns));
+                continue;
+            }
+
+            let columns = self
+                .columns
+                .as_ref()
+                .or_else(|| header_columns.as_ref())
+                .expect("CSV columns are either configured or read from the header");
+            events.push(self.to_event(columns, &record)?);
+        }
+
+        let timestamp = Utc::now();
+        for event in &mut events {
+            let log = event.as_mut_log();
+            let timestamp_key = log_schema().timestamp_key();
+
+            if !log.contains(timestamp_key) {
+                log.insert(timestamp_key, timestamp);
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn deserializer(options: CsvDeserializerOptions) -> CsvDeserializer {
+        CsvDeserializerConfig::new(options).build().unwrap()
+    }
+
+    fn parse(deserializer: &CsvDeserializer, input: &str) -> Vec<BTreeMap<String, Value>> {
+        deserializer
+            .parse(Bytes::from(input.to_owned()))
+            .unwrap()
+            .into_iter()
+            .map(|event| {
+                let mut log = event.into_log();
+                log.remove(log_schema().timestamp_key()).unwrap();
+                log.into_parts().0.into_object().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deserialize_csv_with_header() {
+        let deserializer = deserializer(CsvDeserializerOptions::default());
+
+        assert!(parse(&deserializer, "name, status").is_empty());
+        assert_eq!(
+            parse(&deserializer, "\"Doe, John\",\"said \"\"hi\"\"\"\nJane,200"),
+            vec![
+                btreemap! {
+                    "name" => Value::from("Doe, John"),
+                    "status" => Value::from("said \"hi\""),
+                },
+                btreemap! {
+                    "name" => Value::from("Jane"),
+                    "status" => Value::from("200"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_csv_with_columns() {
+        let deserializer = deserializer(CsvDeserializerOptions {
+            delimiter: b';',
+            quote: b'\'',
+            columns: vec!["host".to_owned(), "message".to_owned()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            parse(&deserializer, "a;'x;y'\nb"),
+            vec![
+                btreemap! {
+                    "host" => Value::from("a"),
+                    "message" => Value::from("x;y"),
+                },
+                btreemap! { "host" => Value::from("b") },
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_header_with_columns() {
+        let deserializer = deserializer(CsvDeserializerOptions {
+            header: Some(true),
+            columns: vec!["a".to_owned()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            parse(&deserializer, "ignored\nvalue"),
+            vec![btreemap! { "a" => Value::from("value") }]
+        );
+    }
+
+    #[test]
+    fn coerces_column_types() {
+        let deserializer = deserializer(CsvDeserializerOptions {
+            columns: vec!["count".to_owned(), "ratio".to_owned(), "ok".to_owned()],
+            types: HashMap::from([
+                ("count".to_owned(), "integer".to_owned()),
+                ("ratio".to_owned(), "float".to_owned()),
+                ("ok".to_owned(), "boolean".to_owned()),
+            ]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            parse(&deserializer, "3,0.5,true"),
+            vec![btreemap! {
+                "count" => Value::from(3),
+                "ratio" => Value::from_f64_or_zero(0.5),
+                "ok" => Value::from(true),
+            }]
+        );
+        assert!(deserializer
+            .parse(Bytes::from_static(b"three,0.5,true"))
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_records_with_extra_fields() {
+        let deserializer = deserializer(CsvDeserializerOptions {
+            columns: vec!["a".to_owned()],
+            ..Default::default()
+        });
+
+        assert!(deserializer.parse(Bytes::from_static(b"1,2")).is_err());
+    }
+
+    #[test]
+    fn rejects_missing_columns() {
+        let config = CsvDeserializerConfig::new(CsvDeserializerOptions {
+            header: Some(false),
+            ..Default::default()
+        });
+
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    fn clones_read_their_own_header() {
+        let deserializer = deserializer(CsvDeserializerOptions::default());
+        parse(&deserializer, "a");
+        let clone = deserializer.clone();
+
+        assert!(parse(&clone, "b").is_empty());
+        assert_eq!(
+            parse(&clone, "1"),
+            vec![btreemap! { "b" => Value::from("1") }]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::{kind::Collection, Kind};
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// The names of the fields the LEEF header fields are decoded into, following the version.
+pub const LEEF_HEADER_FIELDS: [&str; 4] = ["vendor", "product", "product_version", "event_id"];
+
+/// Config used to build a `LeefDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LeefDeserializerConfig {
+    /// Options for the LEEF deserializer.
+    #[serde(default)]
+    pub leef: LeefDeserializerOptions,
+}
+
+impl LeefDeserializerConfig {
+    /// Creates a new `LeefDeserializerConfig`.
+    pub const fn new(leef: LeefDeserializerOptions) -> Self {
+        Self { leef }
+    }
+
+    /// Build the `LeefDeserializer` from this configuration.
+    pub const fn build(&self) -> LeefDeserializer {
+        LeefDeserializer {
+            delimiter: self.leef.delimiter as char,
+        }
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        LEEF_HEADER_FIELDS.iter().fold(
+            schema::Definition::empty()
+                .with_field("version", Kind::bytes(), None)
+                .with_field(
+                    "attributes",
+                    Kind::object(Collection::from_unknown(Kind::bytes())),
+                    None,
+                )
+                .with_field(
+                    log_schema().timestamp_key(),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                ),
+            |definition, field| definition.with_field(*field, Kind::bytes(), None),
+        )
+    }
+}
+
+/// Options for building a `LeefDeserializer`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct LeefDeserializerOptions {
+    /// The character separating the attributes of the messages that don't declare one.
+    ///
+    /// LEEF 1.0 messages can't declare one, and use tabs by default.
+    #[serde(default = "default_delimiter", with = "vector_core::serde::ascii_char")]
+    pub delimiter: u8,
+}
+
+const fn default_delimiter() -> u8 {
+    b'\t'
+}
+
+impl Default for LeefDeserializerOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+        }
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing an IBM QRadar [Log Event
+/// Extended Format][leef] 1.0 or 2.0 message.
+///
+/// The header fields are decoded into the fields of the event named after them, and the
+/// attributes into the `attributes` field, by key.
+///
+/// [leef]: https://www.ibm.com/docs/en/dsm?topic=leef-overview
+#[derive(Debug, Clone)]
+pub struct LeefDeserializer {
+    delimiter: char,
+}
+
+impl Default for LeefDeserializer {
+    fn default() -> Self {
+        LeefDeserializerConfig::default().build()
+    }
+}
+
+impl Deserializer for LeefDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let message = String::from_utf8_lossy(&bytes);
+        let message = message.trim_end_matches(|c| c == '\n' || c == '\r');
+        if message.trim().is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let message = message
+            .trim_start()
+            .strip_prefix("LEEF:")
+            .ok_or("Error parsing LEEF: missing \"LEEF:\" prefix")?;
+        let (header, mut attributes) = split_header(message)?;
+        let version = header[0].trim().to_owned();
+
+        // LEEF 2.0 messages can declare the delimiter in an additional header field.
+        let mut delimiter = self.delimiter;
+        if version.starts_with('2') {
+            if let Some((field, rest)) = attributes.split_once('|') {
+                if field.is_empty() {
+                    attributes = rest;
+                } else if let Some(declared) = parse_delimiter(field) {
+                    delimiter = declared;
+                    attributes = rest;
+                }
+            }
+        }
+
+        let mut log = LogEvent::default();
+        log.insert("version", version);
+        for (field, value) in LEEF_HEADER_FIELDS.iter().zip(header.into_iter().skip(1)) {
+            log.insert(*field, value);
+        }
+        log.insert("attributes", parse_attributes(attributes, delimiter));
+        log.insert(log_schema().timestamp_key(), Utc::now());
+
+        Ok(smallvec![log.into()])
+    }
+}
+
+/// Splits the version and header fields, unescaping their pipes, from the attributes.
+fn split_header(message: &str) -> vector_core::Result<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(LEEF_HEADER_FIELDS.len() + 1);
+    let mut field = String::new();
+    let mut chars = message.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, '|')) => field.push('|'),
+                Some((_, c)) => {
+                    field.push('\\');
+                    field.push(c);
+                }
+                None => field.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() == LEEF_HEADER_FIELDS.len() + 1 {
+                    return Ok((fields, &message[index + 1..]));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    Err(format!(
+        "Error parsing LEEF: expected {} header fields, found {}",
+        LEEF_HEADER_FIELDS.len() + 1,
+        fields.len()
+    )
+    .into())
+}
+
+/// Parses a delimiter declared by a LEEF 2.0 header: either a single character, or the hexadecimal
+/// code point of one, prefixed with `x` or `0x`.
+fn parse_delimiter(field: &str) -> Option<char> {
+    let mut chars = field.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => field
+            .strip_prefix("0x")
+            .or_else(|| field.strip_prefix('x'))
+            .or_else(|| field.strip_prefix("0X"))
+            .or_else(|| field.strip_prefix('X'))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32),
+    }
+}
+
+fn parse_attributes(attributes: &str, delimiter: char) -> BTreeMap<String, Value> {
+    attributes
+        .split(delimiter)
+        .filter_map(|attribute| attribute.split_once('='))
+        .filter(|(key, _)| !key.trim().is_empty())
+        .map(|(key, value)| (key.trim().to_owned(), Value::from(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn parse(deserializer: &LeefDeserializer, input: &str) -> LogEvent {
+        let mut events = deserializer.parse(Bytes::from(input.to_owned())).unwrap();
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap().into_log()
+    }
+
+    #[test]
+    fn deserialize_leef_1() {
+        let log = parse(
+            &LeefDeserializer::default(),
+            "LEEF:1.0|Microsoft|MSExchange|2016|15345|src=10.50.1.1\tdst=2.10.20.20\tmsg=a b=c",
+        );
+
+        assert_eq!(log["version"], Value::from("1.0"));
+        assert_eq!(log["vendor"], Value::from("Microsoft"));
+        assert_eq!(log["product"], Value::from("MSExchange"));
+        assert_eq!(log["product_version"], Value::from("2016"));
+        assert_eq!(log["event_id"], Value::from("15345"));
+        assert_eq!(
+            log["attributes"],
+            Value::from(btreemap! {
+                "src" => "10.50.1.1",
+                "dst" => "2.10.20.20",
+                "msg" => "a b=c",
+            })
+        );
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_leef_2_with_declared_delimiters() {
+        let deserializer = LeefDeserializer::default();
+
+        for (input, attributes) in [
+            ("LEEF:2.0|V|P|1|id|^|a=1^b=2", "^"),
+            ("LEEF:2.0|V|P|1|id|x5E|a=1^b=2", "x5E"),
+            ("LEEF:2.0|V|P|1|id|0x09|a=1\tb=2", "0x09"),
+            ("LEEF:2.0|V|P|1|id||a=1\tb=2", "empty"),
+        ] {
+            let log = parse(&deserializer, input);
+            assert_eq!(
+                log["attributes"],
+                Value::from(btreemap! { "a" => "1", "b" => "2" }),
+                "{}",
+                attributes
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_leef_with_configured_delimiter() {
+        let deserializer =
+            LeefDeserializerConfig::new(LeefDeserializerOptions { delimiter: b';' }).build();
+        let log = parse(&deserializer, "LEEF:1.0|V|P\\|X|1|id|a=1;b=2");
+
+        assert_eq!(log["product"], Value::from("P|X"));
+        assert_eq!(
+            log["attributes"],
+            Value::from(btreemap! { "a" => "1", "b" => "2" })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let deserializer = LeefDeserializer::default();
+
+        assert!(deserializer
+            .parse(Bytes::from_static(b"CEF:0|v|p|1|id|name|1|"))
+            .is_err());
+        assert!(deserializer
+            .parse(Bytes::from_static(b"LEEF:1.0|v|p"))
+            .is_err());
+    }
+}
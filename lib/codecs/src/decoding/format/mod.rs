@@ -3,24 +3,50 @@
 
 #![deny(missing_docs)]
 
+mod access_log;
+mod avro;
+mod bson;
 mod bytes;
+mod cbor;
+mod cef;
+mod csv;
 mod json;
+mod leef;
 mod native;
 mod native_json;
 #[cfg(feature = "syslog")]
 mod syslog;
+mod w3c;
 
 use std::fmt::Debug;
 
 use ::bytes::Bytes;
+pub use access_log::{
+    AccessLogDeserializer, AccessLogDeserializerConfig, AccessLogDeserializerOptions,
+    AccessLogFormat,
+};
+pub use avro::{
+    avro_to_value, AvroDeserializer, AvroDeserializerConfig, AvroDeserializerOptions,
+    AvroSchemaRegistryOptions, AvroSchemaResolver, SCHEMA_REGISTRY_MAGIC_BYTE,
+};
+pub(crate) use avro::{read_schema, read_schema_text};
+pub use cbor::{CborDeserializer, CborDeserializerConfig};
+pub use cef::{CefDeserializer, CefDeserializerConfig, CEF_HEADER_FIELDS};
 use dyn_clone::DynClone;
 pub use json::{JsonDeserializer, JsonDeserializerConfig};
+pub use leef::{
+    LeefDeserializer, LeefDeserializerConfig, LeefDeserializerOptions, LEEF_HEADER_FIELDS,
+};
 pub use native::{NativeDeserializer, NativeDeserializerConfig};
 pub use native_json::{NativeJsonDeserializer, NativeJsonDeserializerConfig};
 use smallvec::SmallVec;
 use vector_core::event::Event;
+pub use w3c::{W3cDeserializer, W3cDeserializerConfig, W3cDeserializerOptions};
 
+pub(crate) use self::bson::parse_decimal128;
+pub use self::bson::{BsonDeserializer, BsonDeserializerConfig, BsonDeserializerOptions};
 pub use self::bytes::{BytesDeserializer, BytesDeserializerConfig};
+pub use self::csv::{CsvDeserializer, CsvDeserializerConfig, CsvDeserializerOptions};
 #[cfg(feature = "syslog")]
 pub use self::syslog::{SyslogDeserializer, SyslogDeserializerConfig};
 
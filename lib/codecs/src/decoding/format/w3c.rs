@@ -0,0 +1,325 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_config::configurable_component;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// Config used to build a `W3cDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct W3cDeserializerConfig {
+    /// Options for the W3C deserializer.
+    #[serde(default)]
+    pub w3c: W3cDeserializerOptions,
+}
+
+impl W3cDeserializerConfig {
+    /// Creates a new `W3cDeserializerConfig`.
+    pub const fn new(w3c: W3cDeserializerOptions) -> Self {
+        Self { w3c }
+    }
+
+    /// Build the `W3cDeserializer` from this configuration.
+    pub fn build(&self) -> W3cDeserializer {
+        W3cDeserializer {
+            default_fields: (!self.w3c.fields.is_empty())
+                .then(|| Arc::new(self.w3c.fields.clone())),
+            fields: Mutex::new(None),
+        }
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .with_field(
+                log_schema().timestamp_key(),
+                // The W3C decoder inserts a timestamp, unless a field of the same name exists.
+                Kind::bytes().or_timestamp(),
+                Some("timestamp"),
+            )
+            .unknown_fields(Kind::bytes())
+    }
+}
+
+/// Options for building a `W3cDeserializer`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct W3cDeserializerOptions {
+    /// The names of the fields, used until a `#Fields` directive is read.
+    ///
+    /// This is needed for logs written without directives, such as AWS ALB access logs.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub fields: Vec<String>,
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing W3C extended log format
+/// entries.
+///
+/// Each entry of the frame is parsed into an event whose fields are named after the last
+/// `#Fields` directive read by the deserializer. The other directives are skipped, and fields
+/// with the value `-` are left out of the event. When the entry has both a `date` and a `time`
+/// field, they are used as the timestamp of the event.
+#[derive(Debug)]
+pub struct W3cDeserializer {
+    default_fields: Option<Arc<Vec<String>>>,
+    fields: Mutex<Option<Arc<Vec<String>>>>,
+}
+
+impl Clone for W3cDeserializer {
+    /// Clones the deserializer, which reads the directives of its own log.
+    fn clone(&self) -> Self {
+        Self {
+            default_fields: self.default_fields.clone(),
+            fields: Mutex::new(None),
+        }
+    }
+}
+
+/// Splits an entry into its whitespace separated values, unquoting the quoted ones.
+fn split_entry(line: &str) -> vector_core::Result<Vec<String>> {
+    let mut values = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+        match chars.peek() {
+            None => return Ok(values),
+            Some('"') => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // Quotes are escaped within quoted values by doubling them.
+                        Some('"') if chars.next_if_eq(&'"').is_some() => value.push('"'),
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("Unterminated quoted value".into()),
+                    }
+                }
+                values.push(value);
+            }
+            Some(_) => {
+                let mut value = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_ascii_whitespace()) {
+                    value.push(c);
+                }
+                values.push(value);
+            }
+        }
+    }
+}
+
+fn parse_timestamp(date: &Value, time: &Value) -> Option<DateTime<Utc>> {
+    let date_time = format!(
+        "{} {}",
+        String::from_utf8_lossy(date.as_bytes()?),
+        String::from_utf8_lossy(time.as_bytes()?)
+    );
+    NaiveDateTime::parse_from_str(&date_time, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|date_time| DateTime::from_utc(date_time, Utc))
+}
+
+impl Deserializer for W3cDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let mut fields = self.fields.lock().expect("W3C fields mutex poisoned");
+
+        let mut events = smallvec![];
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(directive) = line.strip_prefix('#') {
+                if let Some(names) = directive.strip_prefix("Fields:") {
+                    *fields = Some(Arc::new(
+                        names.split_ascii_whitespace().map(Into::into).collect(),
+                    ));
+                }
+                continue;
+            }
+
+            let names = fields
+                .as_ref()
+                .or_else(|| self.default_fields.as_ref())
+                .ok_or("Entry precedes the `#Fields` directive and no `fields` are configured")?;
+            let values = split_entry(line)?;
+            if values.len() > names.len() {
+                return Err(format!(
+                    "Entry has {} values, but only {} fields are defined",
+                    values.len(),
+                    names.len()
+                )
+                .into());
+            }
+
+            let entry = names
+                .iter()
+                .zip(values)
+                .filter(|(_, value)| value != "-")
+                .map(|(name, value)| (name.clone(), Value::from(value)))
+                .collect::<BTreeMap<_, _>>();
+            let timestamp = match (entry.get("date"), entry.get("time")) {
+                (Some(date), Some(time)) => parse_timestamp(date, time),
+                _ => None,
+            };
+
+            let mut log = LogEvent::from(entry);
+            let timestamp_key = log_schema().timestamp_key();
+            if !log.contains(timestamp_key) {
+                log.insert(timestamp_key, timestamp.unwrap_or_else(Utc::now));
+            }
+            events.push(log.into());
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn parse(deserializer: &W3cDeserializer, input: &str) -> Vec<BTreeMap<String, Value>> {
+        deserializer
+            .parse(Bytes::from(input.to_owned()))
+            .unwrap()
+            .into_iter()
+            .map(|event| {
+                let mut log = event.into_log();
+                log.remove(log_schema().timestamp_key()).unwrap();
+                log.into_parts().0.into_object().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deserialize_iis_log() {
+        let deserializer = W3cDeserializerConfig::default().build();
+        let input = "#Software: Microsoft Internet Information Services 10.0\n\
+            #Version: 1.0\n\
+            #Date: 2022-07-01 10:00:00\n\
+            #Fields: date time s-ip cs-method cs-uri-stem cs-uri-query sc-status cs(User-Agent)\n\
+            2022-07-01 10:00:01 10.0.0.1 GET /index.html - 200 Mozilla/5.0+(X11)\n";
+
+        let events = deserializer.parse(Bytes::from(input)).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log.as_map().unwrap()["s-ip"], Value::from("10.0.0.1"));
+        assert_eq!(log.as_map().unwrap()["sc-status"], Value::from("200"));
+        assert_eq!(
+            log.as_map().unwrap()["cs(User-Agent)"],
+            Value::from("Mozilla/5.0+(X11)")
+        );
+        assert!(!log.as_map().unwrap().contains_key("cs-uri-query"));
+        assert_eq!(
+            log.get(log_schema().timestamp_key()),
+            Some(&Value::from(Utc.ymd(2022, 7, 1).and_hms(10, 0, 1)))
+        );
+    }
+
+    #[test]
+    fn deserialize_tab_separated_log() {
+        let deserializer = W3cDeserializerConfig::default().build();
+
+        assert!(parse(
+            &deserializer,
+            "#Version: 1.0\n#Fields: x-edge-location sc-bytes"
+        )
+        .is_empty());
+        assert_eq!(
+            parse(&deserializer, "SEA19-C1\t2390"),
+            vec![btreemap! {
+                "x-edge-location" => Value::from("SEA19-C1"),
+                "sc-bytes" => Value::from("2390"),
+            }]
+        );
+    }
+
+    #[test]
+    fn deserialize_quoted_values() {
+        let deserializer = W3cDeserializerConfig::new(W3cDeserializerOptions {
+            fields: vec!["type".to_owned(), "request".to_owned(), "agent".to_owned()],
+        })
+        .build();
+
+        assert_eq!(
+            parse(
+                &deserializer,
+                r#"https "GET https://example.com:443/ HTTP/1.1" "say ""hi""""#
+            ),
+            vec![btreemap! {
+                "type" => Value::from("https"),
+                "request" => Value::from("GET https://example.com:443/ HTTP/1.1"),
+                "agent" => Value::from("say \"hi\""),
+            }]
+        );
+    }
+
+    #[test]
+    fn fields_directive_replaces_fields() {
+        let deserializer = W3cDeserializerConfig::new(W3cDeserializerOptions {
+            fields: vec!["a".to_owned()],
+        })
+        .build();
+
+        assert_eq!(
+            parse(&deserializer, "1\n#Fields: b c\n2 3"),
+            vec![
+                btreemap! { "a" => Value::from("1") },
+                btreemap! {
+                    "b" => Value::from("2"),
+                    "c" => Value::from("3"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_entries_without_fields() {
+        let deserializer = W3cDeserializerConfig::default().build();
+
+        assert!(deserializer.parse(Bytes::from_static(b"1 2")).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_with_extra_values() {
+        let deserializer = W3cDeserializerConfig::default().build();
+
+        assert!(deserializer
+            .parse(Bytes::from_static(b"#Fields: a\n1 2"))
+            .is_err());
+    }
+
+    #[test]
+    fn clones_read_their_own_directives() {
+        let deserializer = W3cDeserializerConfig::default().build();
+        parse(&deserializer, "#Fields: a");
+        let clone = deserializer.clone();
+
+        assert!(clone.parse(Bytes::from_static(b"1")).is_err());
+    }
+}
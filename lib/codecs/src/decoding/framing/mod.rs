@@ -6,8 +6,10 @@
 mod bytes;
 mod character_delimited;
 mod length_delimited;
+mod multiline;
 mod newline_delimited;
 mod octet_counting;
+mod varint_length_delimited;
 
 use std::fmt::Debug;
 
@@ -17,6 +19,9 @@ pub use character_delimited::{
 };
 use dyn_clone::DynClone;
 pub use length_delimited::{LengthDelimitedDecoder, LengthDelimitedDecoderConfig};
+pub use multiline::{
+    MultilineDecoder, MultilineDecoderConfig, MultilineDecoderOptions, MultilineMode,
+};
 pub use newline_delimited::{
     NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig, NewlineDelimitedDecoderOptions,
 };
@@ -24,6 +29,10 @@ pub use octet_counting::{
     OctetCountingDecoder, OctetCountingDecoderConfig, OctetCountingDecoderOptions,
 };
 use tokio_util::codec::LinesCodecError;
+pub use varint_length_delimited::{
+    VarintLengthDelimitedDecoder, VarintLengthDelimitedDecoderConfig,
+    VarintLengthDelimitedDecoderOptions,
+};
 
 pub use self::bytes::{BytesDecoder, BytesDecoderConfig};
 use super::StreamDecodingError;
@@ -0,0 +1,469 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use bytes::{Bytes, BytesMut};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Decoder;
+use vector_config::configurable_component;
+
+use super::{BoxedFramingError, NewlineDelimitedDecoder};
+use crate::decoding::BuildError;
+
+/// Mode of operation of the line aggregator.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilineMode {
+    /// All consecutive lines matching this pattern are included in the group.
+    ///
+    /// The first line (the line that matched the start pattern) does not need to match the `ContinueThrough` pattern.
+    ///
+    /// This is useful in cases such as a Java stack trace, where some indicator in the line (such as leading
+    /// whitespace) indicates that it is an extension of the proceeding line.
+    ContinueThrough,
+
+    /// All consecutive lines matching this pattern, plus one additional line, are included in the group.
+    ///
+    /// This is useful in cases where a log message ends with a continuation marker, such as a backslash, indicating
+    /// that the following line is part of the same message.
+    ContinuePast,
+
+    /// All consecutive lines not matching this pattern are included in the group.
+    ///
+    /// This is useful where a log line contains a marker indicating that it begins a new message.
+    HaltBefore,
+
+    /// All consecutive lines, up to and including the first line matching this pattern, are included in the group.
+    ///
+    /// This is useful where a log line ends with a termination marker, such as a semicolon.
+    HaltWith,
+}
+
+/// Config used to build a `MultilineDecoder`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MultilineDecoderConfig {
+    /// Options for the multiline decoder.
+    pub multiline: MultilineDecoderOptions,
+}
+
+impl MultilineDecoderConfig {
+    /// Build the `MultilineDecoder` from this configuration.
+    pub fn build(&self) -> Result<MultilineDecoder, BuildError> {
+        let options = &self.multiline;
+        let start_pattern = Regex::new(&options.start_pattern).map_err(|error| {
+            format!(
+                "Unable to parse multiline start pattern from {:?}: {}",
+                options.start_pattern, error
+            )
+        })?;
+        let condition_pattern = Regex::new(&options.condition_pattern).map_err(|error| {
+            format!(
+                "Unable to parse multiline condition pattern from {:?}: {}",
+                options.condition_pattern, error
+            )
+        })?;
+
+        Ok(MultilineDecoder {
+            lines: NewlineDelimitedDecoder::new(),
+            start_pattern,
+            condition_pattern,
+            mode: options.mode,
+            timeout: Duration::from_millis(options.timeout_ms),
+            max_lines: options.max_lines.unwrap_or(usize::MAX),
+            max_bytes: options.max_bytes.unwrap_or(usize::MAX),
+            aggregate: None,
+            frames: VecDeque::new(),
+        })
+    }
+}
+
+/// Options for building a `MultilineDecoder`.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MultilineDecoderOptions {
+    /// Regular expression pattern that is used to match the start of a new message.
+    pub start_pattern: String,
+
+    /// Regular expression pattern that is used to determine whether or not more lines should be read.
+    ///
+    /// This setting must be configured in conjunction with `mode`.
+    pub condition_pattern: String,
+
+    /// Aggregation mode.
+    ///
+    /// This setting must be configured in conjunction with `condition_pattern`.
+    pub mode: MultilineMode,
+
+    /// The maximum amount of time to wait for the next additional line, in milliseconds.
+    ///
+    /// Once this timeout is reached, the buffered message is flushed, even if incomplete, whether or not more data is
+    /// received.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// The maximum number of lines of a message.
+    ///
+    /// Once a message has this many lines, it is flushed, even if incomplete.
+    #[serde(skip_serializing_if = "vector_core::serde::skip_serializing_if_default")]
+    pub max_lines: Option<usize>,
+
+    /// The maximum length of a message, in bytes.
+    ///
+    /// Once a message is this long, it is flushed, even if incomplete.
+    #[serde(skip_serializing_if = "vector_core::serde::skip_serializing_if_default")]
+    pub max_bytes: Option<usize>,
+}
+
+const fn default_timeout_ms() -> u64 {
+    1000
+}
+
+/// What to do with the message being aggregated when a line is received.
+enum Decision {
+    Continue,
+    EndInclude,
+    EndExclude,
+}
+
+/// The lines of the message being aggregated.
+#[derive(Debug, Clone)]
+struct Aggregate {
+    lines: Vec<Bytes>,
+    bytes: usize,
+    updated_at: Instant,
+}
+
+impl Aggregate {
+    fn new(first_line: Bytes) -> Self {
+        Self {
+            bytes: first_line.len(),
+            lines: vec![first_line],
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn add_next_line(&mut self, line: Bytes) {
+        self.bytes += line.len() + 1;
+        self.lines.push(line);
+        self.updated_at = Instant::now();
+    }
+
+    fn merge(self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(self.bytes);
+        for (index, line) in self.lines.into_iter().enumerate() {
+            if index > 0 {
+                bytes.extend_from_slice(b"\n");
+            }
+            bytes.extend_from_slice(&line);
+        }
+        bytes.freeze()
+    }
+}
+
+/// A decoder that splits a byte stream into lines, and aggregates the lines of multiline messages, such as stack
+/// traces, into single frames.
+///
+/// Since decoders only run when data is received, a message that timed out is flushed before handling the next
+/// received lines, or when the stream ends. Readers of streams which may not receive more data for a while poll the
+/// decoder again once its [`deadline`](MultilineDecoder::deadline) is reached, to flush the message in time.
+#[derive(Debug, Clone)]
+pub struct MultilineDecoder {
+    lines: NewlineDelimitedDecoder,
+    start_pattern: Regex,
+    condition_pattern: Regex,
+    mode: MultilineMode,
+    timeout: Duration,
+    max_lines: usize,
+    max_bytes: usize,
+    aggregate: Option<Aggregate>,
+    frames: VecDeque<Bytes>,
+}
+
+impl MultilineDecoder {
+    /// The instant at which the message being aggregated times out, if any, after which the decoder flushes it as
+    /// soon as it's polled, even without more data.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.aggregate
+            .as_ref()
+            .map(|aggregate| aggregate.updated_at + self.timeout)
+    }
+
+    fn handle_line(&mut self, line: Bytes) {
+        match self.aggregate.as_mut() {
+            Some(aggregate) => {
+                let condition_matched = self.condition_pattern.is_match(&line);
+                let decision = match (self.mode, condition_matched) {
+                    (MultilineMode::ContinueThrough, true) => Decision::Continue,
+                    (MultilineMode::ContinueThrough, false) => Decision::EndExclude,
+                    (MultilineMode::ContinuePast, true) => Decision::Continue,
+                    (MultilineMode::ContinuePast, false) => Decision::EndInclude,
+                    (MultilineMode::HaltBefore, true) => Decision::EndExclude,
+                    (MultilineMode::HaltBefore, false) => Decision::Continue,
+                    (MultilineMode::HaltWith, true) => Decision::EndInclude,
+                    (MultilineMode::HaltWith, false) => Decision::Continue,
+                };
+
+                match decision {
+                    Decision::Continue => {
+                        aggregate.add_next_line(line);
+                        self.flush_if_full();
+                    }
+                    Decision::EndInclude => {
+                        aggregate.add_next_line(line);
+                        self.flush();
+                    }
+                    Decision::EndExclude => {
+                        self.flush();
+                        // The line that ended the message may start the next one.
+                        self.handle_line(line);
+                    }
+                }
+            }
+            None if self.start_pattern.is_match(&line) => {
+                self.aggregate = Some(Aggregate::new(line));
+                self.flush_if_full();
+            }
+            None => self.frames.push_back(line),
+        }
+    }
+
+    fn flush_if_full(&mut self) {
+        if let Some(aggregate) = &self.aggregate {
+            if aggregate.lines.len() >= self.max_lines || aggregate.bytes >= self.max_bytes {
+                self.flush();
+            }
+        }
+    }
+
+    fn flush_if_timed_out(&mut self) {
+        if matches!(self.deadline(), Some(deadline) if deadline <= Instant::now()) {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(aggregate) = self.aggregate.take() {
+            self.frames.push_back(aggregate.merge());
+        }
+    }
+}
+
+impl Decoder for MultilineDecoder {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        self.flush_if_timed_out();
+        loop {
+            if let Some(frame) = self.frames.pop_front() {
+                return Ok(Some(frame));
+            }
+            match self.lines.decode(buf)? {
+                Some(line) => self.handle_line(line),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        // The message that timed out is flushed before the remaining lines are handled, rather than along with them.
+        self.flush_if_timed_out();
+        loop {
+            if let Some(frame) = self.decode(buf)? {
+                return Ok(Some(frame));
+            }
+            match self.lines.decode_eof(buf)? {
+                Some(line) => self.handle_line(line),
+                None => {
+                    self.flush();
+                    return Ok(self.frames.pop_front());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoder(
+        start_pattern: &str,
+        condition_pattern: &str,
+        mode: MultilineMode,
+    ) -> MultilineDecoder {
+        MultilineDecoderConfig {
+            multiline: MultilineDecoderOptions {
+                start_pattern: start_pattern.to_owned(),
+                condition_pattern: condition_pattern.to_owned(),
+                mode,
+                timeout_ms: 60_000,
+                max_lines: None,
+                max_bytes: None,
+            },
+        }
+        .build()
+        .unwrap()
+    }
+
+    fn decode_all(decoder: &mut MultilineDecoder, input: &str) -> Vec<Bytes> {
+        let mut input = BytesMut::from(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.decode_eof(&mut input).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn decode_continue_through() {
+        let mut decoder = decoder("^[^\\s]", "^[\\s]+", MultilineMode::ContinueThrough);
+
+        assert_eq!(
+            decode_all(
+                &mut decoder,
+                "usual line\nException\n  at a\n  at b\nanother line\nlast\n  part\n"
+            ),
+            vec![
+                "usual line",
+                "Exception\n  at a\n  at b",
+                "another line",
+                "last\n  part"
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_continue_past() {
+        let mut decoder = decoder("\\\\$", "\\\\$", MultilineMode::ContinuePast);
+
+        assert_eq!(
+            decode_all(&mut decoder, "a\nb \\\nc \\\nd\ne\n"),
+            vec!["a", "b \\\nc \\\nd", "e"]
+        );
+    }
+
+    #[test]
+    fn decode_halt_before() {
+        let mut decoder = decoder("", "^START", MultilineMode::HaltBefore);
+
+        assert_eq!(
+            decode_all(&mut decoder, "START 1\nx\ny\nSTART 2\nz"),
+            vec!["START 1\nx\ny", "START 2\nz"]
+        );
+    }
+
+    #[test]
+    fn decode_halt_with() {
+        let mut decoder = decoder("[^;]$", ";$", MultilineMode::HaltWith);
+
+        assert_eq!(
+            decode_all(&mut decoder, "single;\nSELECT *\nFROM t\nWHERE x;\n"),
+            vec!["single;", "SELECT *\nFROM t\nWHERE x;"]
+        );
+    }
+
+    #[test]
+    fn decode_partial_input() {
+        let mut decoder = decoder("^[^\\s]", "^[\\s]+", MultilineMode::ContinueThrough);
+        let mut input = BytesMut::from("Exception\n  at a\n");
+
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        input.extend_from_slice(b"  at b\nnext\n");
+        assert_eq!(
+            decoder.decode(&mut input).unwrap(),
+            Some(Bytes::from("Exception\n  at a\n  at b"))
+        );
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        assert_eq!(
+            decoder.decode_eof(&mut input).unwrap(),
+            Some(Bytes::from("next"))
+        );
+    }
+
+    #[test]
+    fn flushes_full_messages() {
+        let mut decoder = decoder("^[^\\s]", "^[\\s]+", MultilineMode::ContinueThrough);
+        decoder.max_lines = 2;
+
+        assert_eq!(
+            decode_all(&mut decoder, "Exception\n  at a\n  at b\n"),
+            vec!["Exception\n  at a", "  at b"]
+        );
+
+        decoder.max_lines = usize::MAX;
+        decoder.max_bytes = 12;
+        assert_eq!(
+            decode_all(&mut decoder, "Exception\n  at a\n  at b\n"),
+            vec!["Exception\n  at a", "  at b"]
+        );
+    }
+
+    #[test]
+    fn flushes_timed_out_messages() {
+        let mut decoder = decoder("^[^\\s]", "^[\\s]+", MultilineMode::ContinueThrough);
+        decoder.timeout = Duration::from_millis(10);
+        let mut input = BytesMut::from("Exception\n  at a\n");
+
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        std::thread::sleep(Duration::from_millis(20));
+        input.extend_from_slice(b"  at b\n");
+        assert_eq!(
+            decoder.decode(&mut input).unwrap(),
+            Some(Bytes::from("Exception\n  at a"))
+        );
+    }
+
+    #[test]
+    fn flushes_timed_out_messages_without_more_data() {
+        let mut decoder = decoder("^[^\\s]", "^[\\s]+", MultilineMode::ContinueThrough);
+        decoder.timeout = Duration::from_millis(10);
+        let mut input = BytesMut::from("Exception\n  at a\n");
+
+        assert_eq!(decoder.deadline(), None);
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        let deadline = decoder.deadline().expect("message should be aggregated");
+
+        // Polling the decoder once the deadline is reached flushes the message, without any more data.
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        assert_eq!(
+            decoder.decode(&mut input).unwrap(),
+            Some(Bytes::from("Exception\n  at a"))
+        );
+        assert_eq!(decoder.deadline(), None);
+
+        // The timed out message is flushed from `decode_eof` as well, before the remaining input.
+        input.extend_from_slice(b"Exception\n");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        std::thread::sleep(Duration::from_millis(20));
+        input.extend_from_slice(b"  at b");
+        assert_eq!(
+            decoder.decode_eof(&mut input).unwrap(),
+            Some(Bytes::from("Exception"))
+        );
+        assert_eq!(
+            decoder.decode_eof(&mut input).unwrap(),
+            Some(Bytes::from("  at b"))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let config = MultilineDecoderConfig {
+            multiline: MultilineDecoderOptions {
+                start_pattern: "(".to_owned(),
+                condition_pattern: ".".to_owned(),
+                mode: MultilineMode::HaltWith,
+                timeout_ms: 1000,
+                max_lines: None,
+                max_bytes: None,
+            },
+        };
+
+        assert!(config.build().is_err());
+    }
+}
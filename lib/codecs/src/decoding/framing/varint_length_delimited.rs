@@ -0,0 +1,196 @@
+use bytes::{Buf, Bytes, BytesMut};
+use derivative::Derivative;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Decoder;
+use vector_config::configurable_component;
+
+use super::BoxedFramingError;
+
+/// The maximum length of a 64-bit varint, in bytes.
+const MAX_VARINT_LENGTH: usize = 10;
+
+/// Config used to build a `VarintLengthDelimitedDecoder`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct VarintLengthDelimitedDecoderConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    /// Options for the varint length delimited decoder.
+    pub varint_length_delimited: VarintLengthDelimitedDecoderOptions,
+}
+
+impl VarintLengthDelimitedDecoderConfig {
+    /// Build the `VarintLengthDelimitedDecoder` from this configuration.
+    pub const fn build(&self) -> VarintLengthDelimitedDecoder {
+        VarintLengthDelimitedDecoder::new_with_max_frame_length(
+            self.varint_length_delimited.max_frame_length,
+        )
+    }
+}
+
+/// Options for building a `VarintLengthDelimitedDecoder`.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+pub struct VarintLengthDelimitedDecoderOptions {
+    /// The maximum length of a frame.
+    ///
+    /// This length does *not* include the length header. Frames longer than this are rejected.
+    #[serde(default = "default_max_frame_length")]
+    #[derivative(Default(value = "default_max_frame_length()"))]
+    pub max_frame_length: usize,
+}
+
+const fn default_max_frame_length() -> usize {
+    8 * 1_024 * 1_024
+}
+
+/// A codec for handling byte sequences prefixed by their length, encoded as a protobuf varint.
+///
+/// This is the framing used by the `writeDelimitedTo`/`parseDelimitedFrom` methods of the protobuf
+/// libraries.
+#[derive(Debug, Clone)]
+pub struct VarintLengthDelimitedDecoder {
+    max_frame_length: usize,
+}
+
+impl VarintLengthDelimitedDecoder {
+    /// Creates a new `VarintLengthDelimitedDecoder`.
+    pub const fn new() -> Self {
+        Self::new_with_max_frame_length(default_max_frame_length())
+    }
+
+    /// Creates a `VarintLengthDelimitedDecoder` with a maximum frame length limit.
+    pub const fn new_with_max_frame_length(max_frame_length: usize) -> Self {
+        Self { max_frame_length }
+    }
+}
+
+impl Default for VarintLengthDelimitedDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the varint at the start of the buffer, returning its value and length, or `None` if the
+/// buffer doesn't contain the whole varint yet.
+fn read_varint(src: &[u8]) -> Result<Option<(u64, usize)>, std::io::Error> {
+    let mut value = 0_u64;
+    for (index, byte) in src.iter().take(MAX_VARINT_LENGTH).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok(Some((value, index + 1)));
+        }
+    }
+    if src.len() < MAX_VARINT_LENGTH {
+        Ok(None)
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "invalid varint length header",
+        ))
+    }
+}
+
+impl Decoder for VarintLengthDelimitedDecoder {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let (length, header_length) = match read_varint(src)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        if length > self.max_frame_length as u64 {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes exceeds the maximum frame length of {} bytes",
+                    length, self.max_frame_length
+                ),
+            )));
+        }
+
+        let length = length as usize;
+        if src.len() < header_length + length {
+            src.reserve(header_length + length - src.len());
+            return Ok(None);
+        }
+        src.advance(header_length);
+        Ok(Some(src.split_to(length).freeze()))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "bytes remaining on stream",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_frames() {
+        let mut input = BytesMut::from(&b"\x03foo\x00\x03bar"[..]);
+        let mut decoder = VarintLengthDelimitedDecoder::new();
+
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "");
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "bar");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_multi_byte_header() {
+        let frame = "x".repeat(300);
+        let mut input = BytesMut::from(&b"\xac\x02"[..]);
+        input.extend_from_slice(frame.as_bytes());
+        let mut decoder = VarintLengthDelimitedDecoder::new();
+
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), frame);
+    }
+
+    #[test]
+    fn decode_partial_input() {
+        let mut input = BytesMut::from(&b"\xac"[..]);
+        let mut decoder = VarintLengthDelimitedDecoder::new();
+
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        input.extend_from_slice(b"\x02");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        input.extend_from_slice("x".repeat(300).as_bytes());
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap().len(), 300);
+    }
+
+    #[test]
+    fn decode_eof_frame_unexpected_eof() {
+        let mut input = BytesMut::from(&b"\x03fo"[..]);
+        let mut decoder = VarintLengthDelimitedDecoder::new();
+
+        assert!(decoder.decode_eof(&mut input).is_err());
+    }
+
+    #[test]
+    fn rejects_frames_exceeding_max_frame_length() {
+        let mut input = BytesMut::from(&b"\x04fooo"[..]);
+        let mut decoder = VarintLengthDelimitedDecoder::new_with_max_frame_length(3);
+
+        assert!(decoder.decode(&mut input).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let mut input = BytesMut::from(&[0xff; 11][..]);
+        let mut decoder = VarintLengthDelimitedDecoder::new();
+
+        assert!(decoder.decode(&mut input).is_err());
+    }
+}
@@ -5,28 +5,39 @@ mod error;
 pub mod format;
 pub mod framing;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Instant};
 
 use bytes::{Bytes, BytesMut};
 pub use error::StreamDecodingError;
 pub use format::{
-    BoxedDeserializer, BytesDeserializer, BytesDeserializerConfig, JsonDeserializer,
-    JsonDeserializerConfig, NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
-    NativeJsonDeserializerConfig,
+    AccessLogDeserializer, AccessLogDeserializerConfig, AccessLogDeserializerOptions,
+    AccessLogFormat, AvroDeserializer, AvroDeserializerConfig, AvroDeserializerOptions,
+    AvroSchemaRegistryOptions, AvroSchemaResolver, BoxedDeserializer, BsonDeserializer,
+    BsonDeserializerConfig, BsonDeserializerOptions, BytesDeserializer, BytesDeserializerConfig,
+    CborDeserializer, CborDeserializerConfig, CefDeserializer, CefDeserializerConfig,
+    CsvDeserializer, CsvDeserializerConfig, CsvDeserializerOptions, JsonDeserializer,
+    JsonDeserializerConfig, LeefDeserializer, LeefDeserializerConfig, LeefDeserializerOptions,
+    NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
+    NativeJsonDeserializerConfig, W3cDeserializer, W3cDeserializerConfig, W3cDeserializerOptions,
 };
 #[cfg(feature = "syslog")]
 pub use format::{SyslogDeserializer, SyslogDeserializerConfig};
 pub use framing::{
     BoxedFramer, BoxedFramingError, BytesDecoder, BytesDecoderConfig, CharacterDelimitedDecoder,
     CharacterDelimitedDecoderConfig, CharacterDelimitedDecoderOptions, FramingError,
-    LengthDelimitedDecoder, LengthDelimitedDecoderConfig, NewlineDelimitedDecoder,
-    NewlineDelimitedDecoderConfig, NewlineDelimitedDecoderOptions, OctetCountingDecoder,
-    OctetCountingDecoderConfig, OctetCountingDecoderOptions,
+    LengthDelimitedDecoder, LengthDelimitedDecoderConfig, MultilineDecoder, MultilineDecoderConfig,
+    MultilineDecoderOptions, MultilineMode, NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig,
+    NewlineDelimitedDecoderOptions, OctetCountingDecoder, OctetCountingDecoderConfig,
+    OctetCountingDecoderOptions, VarintLengthDelimitedDecoder, VarintLengthDelimitedDecoderConfig,
+    VarintLengthDelimitedDecoderOptions,
 };
 use smallvec::SmallVec;
 use vector_config::configurable_component;
 use vector_core::{config::DataType, event::Event, schema};
 
+/// An error that occurred while building a decoder.
+pub type BuildError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 /// An error that occurred while decoding structured events from a byte stream /
 /// byte messages.
 #[derive(Debug)]
@@ -81,6 +92,11 @@ pub enum FramingConfig {
     },
     /// Configures the `LengthDelimitedDecoder`.
     LengthDelimited,
+    /// Configures the `MultilineDecoder`.
+    Multiline {
+        /// Options for the multiline decoder.
+        multiline: MultilineDecoderOptions,
+    },
     /// Configures the `NewlineDelimitedDecoder`.
     NewlineDelimited {
         #[serde(
@@ -99,6 +115,15 @@ pub enum FramingConfig {
         /// Options for the octet counting decoder.
         octet_counting: OctetCountingDecoderOptions,
     },
+    /// Configures the `VarintLengthDelimitedDecoder`.
+    VarintLengthDelimited {
+        #[serde(
+            default,
+            skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+        )]
+        /// Options for the varint length delimited decoder.
+        varint_length_delimited: VarintLengthDelimitedDecoderOptions,
+    },
 }
 
 impl From<BytesDecoderConfig> for FramingConfig {
@@ -121,6 +146,14 @@ impl From<LengthDelimitedDecoderConfig> for FramingConfig {
     }
 }
 
+impl From<MultilineDecoderConfig> for FramingConfig {
+    fn from(config: MultilineDecoderConfig) -> Self {
+        Self::Multiline {
+            multiline: config.multiline,
+        }
+    }
+}
+
 impl From<NewlineDelimitedDecoderConfig> for FramingConfig {
     fn from(config: NewlineDelimitedDecoderConfig) -> Self {
         Self::NewlineDelimited {
@@ -137,10 +170,18 @@ impl From<OctetCountingDecoderConfig> for FramingConfig {
     }
 }
 
+impl From<VarintLengthDelimitedDecoderConfig> for FramingConfig {
+    fn from(config: VarintLengthDelimitedDecoderConfig) -> Self {
+        Self::VarintLengthDelimited {
+            varint_length_delimited: config.varint_length_delimited,
+        }
+    }
+}
+
 impl FramingConfig {
     /// Build the `Framer` from this configuration.
-    pub fn build(&self) -> Framer {
-        match self {
+    pub fn build(&self) -> Result<Framer, BuildError> {
+        Ok(match self {
             FramingConfig::Bytes => Framer::Bytes(BytesDecoderConfig.build()),
             FramingConfig::CharacterDelimited {
                 character_delimited,
@@ -153,6 +194,12 @@ impl FramingConfig {
             FramingConfig::LengthDelimited => {
                 Framer::LengthDelimited(LengthDelimitedDecoderConfig.build())
             }
+            FramingConfig::Multiline { multiline } => Framer::Multiline(
+                MultilineDecoderConfig {
+                    multiline: multiline.clone(),
+                }
+                .build()?,
+            ),
             FramingConfig::NewlineDelimited { newline_delimited } => Framer::NewlineDelimited(
                 NewlineDelimitedDecoderConfig {
                     newline_delimited: newline_delimited.clone(),
@@ -165,7 +212,15 @@ impl FramingConfig {
                 }
                 .build(),
             ),
-        }
+            FramingConfig::VarintLengthDelimited {
+                varint_length_delimited,
+            } => Framer::VarintLengthDelimited(
+                VarintLengthDelimitedDecoderConfig {
+                    varint_length_delimited: varint_length_delimited.clone(),
+                }
+                .build(),
+            ),
+        })
     }
 }
 
@@ -178,14 +233,29 @@ pub enum Framer {
     CharacterDelimited(CharacterDelimitedDecoder),
     /// Uses a `LengthDelimitedDecoder` for framing.
     LengthDelimited(LengthDelimitedDecoder),
+    /// Uses a `MultilineDecoder` for framing.
+    Multiline(MultilineDecoder),
     /// Uses a `NewlineDelimitedDecoder` for framing.
     NewlineDelimited(NewlineDelimitedDecoder),
     /// Uses a `OctetCountingDecoder` for framing.
     OctetCounting(OctetCountingDecoder),
+    /// Uses a `VarintLengthDelimitedDecoder` for framing.
+    VarintLengthDelimited(VarintLengthDelimitedDecoder),
     /// Uses an opaque `Framer` implementation for framing.
     Boxed(BoxedFramer),
 }
 
+impl Framer {
+    /// The instant at which the framer has to be polled again, even if no more data is received, to flush the frame it
+    /// holds back, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self {
+            Framer::Multiline(framer) => framer.deadline(),
+            _ => None,
+        }
+    }
+}
+
 impl tokio_util::codec::Decoder for Framer {
     type Item = Bytes;
     type Error = BoxedFramingError;
@@ -195,8 +265,10 @@ impl tokio_util::codec::Decoder for Framer {
             Framer::Bytes(framer) => framer.decode(src),
             Framer::CharacterDelimited(framer) => framer.decode(src),
             Framer::LengthDelimited(framer) => framer.decode(src),
+            Framer::Multiline(framer) => framer.decode(src),
             Framer::NewlineDelimited(framer) => framer.decode(src),
             Framer::OctetCounting(framer) => framer.decode(src),
+            Framer::VarintLengthDelimited(framer) => framer.decode(src),
             Framer::Boxed(framer) => framer.decode(src),
         }
     }
@@ -206,8 +278,10 @@ impl tokio_util::codec::Decoder for Framer {
             Framer::Bytes(framer) => framer.decode_eof(src),
             Framer::CharacterDelimited(framer) => framer.decode_eof(src),
             Framer::LengthDelimited(framer) => framer.decode_eof(src),
+            Framer::Multiline(framer) => framer.decode_eof(src),
             Framer::NewlineDelimited(framer) => framer.decode_eof(src),
             Framer::OctetCounting(framer) => framer.decode_eof(src),
+            Framer::VarintLengthDelimited(framer) => framer.decode_eof(src),
             Framer::Boxed(framer) => framer.decode_eof(src),
         }
     }
@@ -221,10 +295,43 @@ impl tokio_util::codec::Decoder for Framer {
 #[derive(Clone, Debug)]
 #[serde(tag = "codec", rename_all = "snake_case")]
 pub enum DeserializerConfig {
+    /// Configures the `AccessLogDeserializer`.
+    AccessLog {
+        /// Options for the access log deserializer.
+        #[serde(default)]
+        access_log: AccessLogDeserializerOptions,
+    },
+    /// Configures the `AvroDeserializer`.
+    Avro {
+        /// Options for the Avro deserializer.
+        avro: AvroDeserializerOptions,
+    },
+    /// Configures the `BsonDeserializer`.
+    Bson {
+        /// Options for the BSON deserializer.
+        #[serde(default)]
+        bson: BsonDeserializerOptions,
+    },
     /// Configures the `BytesDeserializer`.
     Bytes,
+    /// Configures the `CborDeserializer`.
+    Cbor,
+    /// Configures the `CefDeserializer`.
+    Cef,
+    /// Configures the `CsvDeserializer`.
+    Csv {
+        /// Options for the CSV deserializer.
+        #[serde(default)]
+        csv: CsvDeserializerOptions,
+    },
     /// Configures the `JsonDeserializer`.
     Json,
+    /// Configures the `LeefDeserializer`.
+    Leef {
+        /// Options for the LEEF deserializer.
+        #[serde(default)]
+        leef: LeefDeserializerOptions,
+    },
     #[cfg(feature = "syslog")]
     /// Configures the `SyslogDeserializer`.
     Syslog,
@@ -232,6 +339,32 @@ pub enum DeserializerConfig {
     Native,
     /// Configures the `NativeJsonDeserializer`.
     NativeJson,
+    /// Configures the `W3cDeserializer`.
+    W3c {
+        /// Options for the W3C deserializer.
+        #[serde(default)]
+        w3c: W3cDeserializerOptions,
+    },
+}
+
+impl From<AccessLogDeserializerConfig> for DeserializerConfig {
+    fn from(config: AccessLogDeserializerConfig) -> Self {
+        Self::AccessLog {
+            access_log: config.access_log,
+        }
+    }
+}
+
+impl From<AvroDeserializerConfig> for DeserializerConfig {
+    fn from(config: AvroDeserializerConfig) -> Self {
+        Self::Avro { avro: config.avro }
+    }
+}
+
+impl From<BsonDeserializerConfig> for DeserializerConfig {
+    fn from(config: BsonDeserializerConfig) -> Self {
+        Self::Bson { bson: config.bson }
+    }
 }
 
 impl From<BytesDeserializerConfig> for DeserializerConfig {
@@ -240,6 +373,24 @@ impl From<BytesDeserializerConfig> for DeserializerConfig {
     }
 }
 
+impl From<CborDeserializerConfig> for DeserializerConfig {
+    fn from(_: CborDeserializerConfig) -> Self {
+        Self::Cbor
+    }
+}
+
+impl From<CefDeserializerConfig> for DeserializerConfig {
+    fn from(_: CefDeserializerConfig) -> Self {
+        Self::Cef
+    }
+}
+
+impl From<CsvDeserializerConfig> for DeserializerConfig {
+    fn from(config: CsvDeserializerConfig) -> Self {
+        Self::Csv { csv: config.csv }
+    }
+}
+
 impl From<JsonDeserializerConfig> for DeserializerConfig {
     fn from(_: JsonDeserializerConfig) -> Self {
         Self::Json
@@ -253,28 +404,72 @@ impl From<SyslogDeserializerConfig> for DeserializerConfig {
     }
 }
 
+impl From<LeefDeserializerConfig> for DeserializerConfig {
+    fn from(config: LeefDeserializerConfig) -> Self {
+        Self::Leef { leef: config.leef }
+    }
+}
+
+impl From<W3cDeserializerConfig> for DeserializerConfig {
+    fn from(config: W3cDeserializerConfig) -> Self {
+        Self::W3c { w3c: config.w3c }
+    }
+}
+
 impl DeserializerConfig {
     /// Build the `Deserializer` from this configuration.
-    pub fn build(&self) -> Deserializer {
+    pub fn build(&self) -> Result<Deserializer, BuildError> {
         match self {
-            DeserializerConfig::Bytes => Deserializer::Bytes(BytesDeserializerConfig.build()),
-            DeserializerConfig::Json => Deserializer::Json(JsonDeserializerConfig.build()),
+            DeserializerConfig::AccessLog { access_log } => Ok(Deserializer::AccessLog(
+                AccessLogDeserializerConfig::new(access_log.clone()).build()?,
+            )),
+            DeserializerConfig::Avro { avro } => Ok(Deserializer::Avro(
+                AvroDeserializerConfig { avro: avro.clone() }.build()?,
+            )),
+            DeserializerConfig::Bson { bson } => Ok(Deserializer::Bson(
+                BsonDeserializerConfig::new(bson.clone()).build(),
+            )),
+            DeserializerConfig::Bytes => Ok(Deserializer::Bytes(BytesDeserializerConfig.build())),
+            DeserializerConfig::Cbor => Ok(Deserializer::Cbor(CborDeserializerConfig.build())),
+            DeserializerConfig::Cef => Ok(Deserializer::Cef(CefDeserializerConfig.build())),
+            DeserializerConfig::Csv { csv } => Ok(Deserializer::Csv(
+                CsvDeserializerConfig::new(csv.clone()).build()?,
+            )),
+            DeserializerConfig::Json => Ok(Deserializer::Json(JsonDeserializerConfig.build())),
+            DeserializerConfig::Leef { leef } => Ok(Deserializer::Leef(
+                LeefDeserializerConfig::new(leef.clone()).build(),
+            )),
             #[cfg(feature = "syslog")]
-            DeserializerConfig::Syslog => Deserializer::Syslog(SyslogDeserializerConfig.build()),
-            DeserializerConfig::Native => Deserializer::Native(NativeDeserializerConfig.build()),
-            DeserializerConfig::NativeJson => {
-                Deserializer::NativeJson(NativeJsonDeserializerConfig.build())
+            DeserializerConfig::Syslog => {
+                Ok(Deserializer::Syslog(SyslogDeserializerConfig.build()))
+            }
+            DeserializerConfig::Native => {
+                Ok(Deserializer::Native(NativeDeserializerConfig.build()))
             }
+            DeserializerConfig::NativeJson => Ok(Deserializer::NativeJson(
+                NativeJsonDeserializerConfig.build(),
+            )),
+            DeserializerConfig::W3c { w3c } => Ok(Deserializer::W3c(
+                W3cDeserializerConfig::new(w3c.clone()).build(),
+            )),
         }
     }
 
     /// Return an appropriate default framer for the given deserializer
     pub fn default_stream_framing(&self) -> FramingConfig {
         match self {
-            DeserializerConfig::Native => FramingConfig::LengthDelimited,
-            DeserializerConfig::Bytes
+            DeserializerConfig::Avro { .. }
+            | DeserializerConfig::Bson { .. }
+            | DeserializerConfig::Cbor
+            | DeserializerConfig::Native => FramingConfig::LengthDelimited,
+            DeserializerConfig::AccessLog { .. }
+            | DeserializerConfig::Bytes
+            | DeserializerConfig::Cef
+            | DeserializerConfig::Csv { .. }
             | DeserializerConfig::Json
-            | DeserializerConfig::NativeJson => FramingConfig::NewlineDelimited {
+            | DeserializerConfig::Leef { .. }
+            | DeserializerConfig::NativeJson
+            | DeserializerConfig::W3c { .. } => FramingConfig::NewlineDelimited {
                 newline_delimited: Default::default(),
             },
             #[cfg(feature = "syslog")]
@@ -287,24 +482,64 @@ impl DeserializerConfig {
     /// Return the type of event build by this deserializer.
     pub fn output_type(&self) -> DataType {
         match self {
+            DeserializerConfig::AccessLog { access_log } => {
+                AccessLogDeserializerConfig::new(access_log.clone()).output_type()
+            }
+            DeserializerConfig::Avro { avro } => {
+                AvroDeserializerConfig { avro: avro.clone() }.output_type()
+            }
+            DeserializerConfig::Bson { bson } => {
+                BsonDeserializerConfig::new(bson.clone()).output_type()
+            }
             DeserializerConfig::Bytes => BytesDeserializerConfig.output_type(),
+            DeserializerConfig::Cbor => CborDeserializerConfig.output_type(),
+            DeserializerConfig::Cef => CefDeserializerConfig.output_type(),
+            DeserializerConfig::Csv { csv } => {
+                CsvDeserializerConfig::new(csv.clone()).output_type()
+            }
             DeserializerConfig::Json => JsonDeserializerConfig.output_type(),
+            DeserializerConfig::Leef { leef } => {
+                LeefDeserializerConfig::new(leef.clone()).output_type()
+            }
             #[cfg(feature = "syslog")]
             DeserializerConfig::Syslog => SyslogDeserializerConfig.output_type(),
             DeserializerConfig::Native => NativeDeserializerConfig.output_type(),
             DeserializerConfig::NativeJson => NativeJsonDeserializerConfig.output_type(),
+            DeserializerConfig::W3c { w3c } => {
+                W3cDeserializerConfig::new(w3c.clone()).output_type()
+            }
         }
     }
 
     /// The schema produced by the deserializer.
     pub fn schema_definition(&self) -> schema::Definition {
         match self {
+            DeserializerConfig::AccessLog { access_log } => {
+                AccessLogDeserializerConfig::new(access_log.clone()).schema_definition()
+            }
+            DeserializerConfig::Avro { avro } => {
+                AvroDeserializerConfig { avro: avro.clone() }.schema_definition()
+            }
+            DeserializerConfig::Bson { bson } => {
+                BsonDeserializerConfig::new(bson.clone()).schema_definition()
+            }
             DeserializerConfig::Bytes => BytesDeserializerConfig.schema_definition(),
+            DeserializerConfig::Cbor => CborDeserializerConfig.schema_definition(),
+            DeserializerConfig::Cef => CefDeserializerConfig.schema_definition(),
+            DeserializerConfig::Csv { csv } => {
+                CsvDeserializerConfig::new(csv.clone()).schema_definition()
+            }
             DeserializerConfig::Json => JsonDeserializerConfig.schema_definition(),
+            DeserializerConfig::Leef { leef } => {
+                LeefDeserializerConfig::new(leef.clone()).schema_definition()
+            }
             #[cfg(feature = "syslog")]
             DeserializerConfig::Syslog => SyslogDeserializerConfig.schema_definition(),
             DeserializerConfig::Native => NativeDeserializerConfig.schema_definition(),
             DeserializerConfig::NativeJson => NativeJsonDeserializerConfig.schema_definition(),
+            DeserializerConfig::W3c { w3c } => {
+                W3cDeserializerConfig::new(w3c.clone()).schema_definition()
+            }
         }
     }
 }
@@ -312,10 +547,24 @@ impl DeserializerConfig {
 /// Parse structured events from bytes.
 #[derive(Debug, Clone)]
 pub enum Deserializer {
+    /// Uses an `AccessLogDeserializer` for deserialization.
+    AccessLog(AccessLogDeserializer),
+    /// Uses an `AvroDeserializer` for deserialization.
+    Avro(AvroDeserializer),
+    /// Uses a `BsonDeserializer` for deserialization.
+    Bson(BsonDeserializer),
     /// Uses a `BytesDeserializer` for deserialization.
     Bytes(BytesDeserializer),
+    /// Uses a `CborDeserializer` for deserialization.
+    Cbor(CborDeserializer),
+    /// Uses a `CefDeserializer` for deserialization.
+    Cef(CefDeserializer),
+    /// Uses a `CsvDeserializer` for deserialization.
+    Csv(CsvDeserializer),
     /// Uses a `JsonDeserializer` for deserialization.
     Json(JsonDeserializer),
+    /// Uses a `LeefDeserializer` for deserialization.
+    Leef(LeefDeserializer),
     #[cfg(feature = "syslog")]
     /// Uses a `SyslogDeserializer` for deserialization.
     Syslog(SyslogDeserializer),
@@ -323,6 +572,8 @@ pub enum Deserializer {
     Native(NativeDeserializer),
     /// Uses a `NativeDeserializer` for deserialization.
     NativeJson(NativeJsonDeserializer),
+    /// Uses a `W3cDeserializer` for deserialization.
+    W3c(W3cDeserializer),
     /// Uses an opaque `Deserializer` implementation for deserialization.
     Boxed(BoxedDeserializer),
 }
@@ -330,12 +581,20 @@ pub enum Deserializer {
 impl format::Deserializer for Deserializer {
     fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
         match self {
+            Deserializer::AccessLog(deserializer) => deserializer.parse(bytes),
+            Deserializer::Avro(deserializer) => deserializer.parse(bytes),
+            Deserializer::Bson(deserializer) => deserializer.parse(bytes),
             Deserializer::Bytes(deserializer) => deserializer.parse(bytes),
+            Deserializer::Cbor(deserializer) => deserializer.parse(bytes),
+            Deserializer::Cef(deserializer) => deserializer.parse(bytes),
+            Deserializer::Csv(deserializer) => deserializer.parse(bytes),
             Deserializer::Json(deserializer) => deserializer.parse(bytes),
+            Deserializer::Leef(deserializer) => deserializer.parse(bytes),
             #[cfg(feature = "syslog")]
             Deserializer::Syslog(deserializer) => deserializer.parse(bytes),
             Deserializer::Native(deserializer) => deserializer.parse(bytes),
             Deserializer::NativeJson(deserializer) => deserializer.parse(bytes),
+            Deserializer::W3c(deserializer) => deserializer.parse(bytes),
             Deserializer::Boxed(deserializer) => deserializer.parse(bytes),
         }
     }
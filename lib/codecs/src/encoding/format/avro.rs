@@ -1,4 +1,9 @@
-use crate::encoding::BuildError;
+use std::path::PathBuf;
+
+use crate::{
+    decoding::format::{read_schema, read_schema_text, SCHEMA_REGISTRY_MAGIC_BYTE},
+    encoding::BuildError,
+};
 use bytes::{BufMut, BytesMut};
 use serde::{Deserialize, Serialize};
 use tokio_util::codec::Encoder;
@@ -15,15 +20,24 @@ impl AvroSerializerConfig {
     /// Creates a new `AvroSerializerConfig`.
     pub const fn new(schema: String) -> Self {
         Self {
-            avro: AvroSerializerOptions { schema },
+            avro: AvroSerializerOptions {
+                schema: Some(schema),
+                schema_file: None,
+                schema_id: None,
+            },
         }
     }
 
     /// Build the `AvroSerializer` from this configuration.
     pub fn build(&self) -> Result<AvroSerializer, BuildError> {
-        let schema = avro_rs::Schema::parse_str(&self.avro.schema)
+        let schema = self
+            .avro
+            .read_schema()
             .map_err(|error| format!("Failed building Avro serializer: {}", error))?;
-        Ok(AvroSerializer { schema })
+        Ok(AvroSerializer {
+            schema,
+            schema_id: self.avro.schema_id,
+        })
     }
 
     /// The data type of events that are accepted by `AvroSerializer`.
@@ -39,22 +53,52 @@ impl AvroSerializerConfig {
 }
 
 /// Options for building an `AvroSerializer`.
+///
+/// Exactly one of `schema` and `schema_file` must be set.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AvroSerializerOptions {
     /// The Avro schema.
-    pub schema: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// The path of a file containing the Avro schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_file: Option<PathBuf>,
+
+    /// The ID of the schema in a Confluent Schema Registry.
+    ///
+    /// When set, the events are framed with the schema registry wire format: a zero magic byte
+    /// and the ID of their schema, as a big-endian 32-bit integer, followed by the Avro datum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<u32>,
+}
+
+impl AvroSerializerOptions {
+    /// Reads the JSON definition of the Avro schema.
+    pub fn read_schema_text(&self) -> Result<String, BuildError> {
+        read_schema_text(self.schema.as_deref(), self.schema_file.as_ref())
+    }
+
+    /// Reads and parses the Avro schema.
+    pub fn read_schema(&self) -> Result<avro_rs::Schema, BuildError> {
+        read_schema(self.schema.as_deref(), self.schema_file.as_ref())
+    }
 }
 
 /// Serializer that converts an `Event` to bytes using the Apache Avro format.
 #[derive(Debug, Clone)]
 pub struct AvroSerializer {
     schema: avro_rs::Schema,
+    schema_id: Option<u32>,
 }
 
 impl AvroSerializer {
     /// Creates a new `AvroSerializer`.
     pub const fn new(schema: avro_rs::Schema) -> Self {
-        Self { schema }
+        Self {
+            schema,
+            schema_id: None,
+        }
     }
 }
 
@@ -66,6 +110,10 @@ impl Encoder<Event> for AvroSerializer {
         let value = avro_rs::to_value(log)?;
         let value = value.resolve(&self.schema)?;
         let bytes = avro_rs::to_avro_datum(&self.schema, value)?;
+        if let Some(schema_id) = self.schema_id {
+            buffer.put_u8(SCHEMA_REGISTRY_MAGIC_BYTE);
+            buffer.put_u32(schema_id);
+        }
         buffer.put_slice(&bytes);
         Ok(())
     }
@@ -106,4 +154,31 @@ mod tests {
 
         assert_eq!(bytes.freeze(), b"\0\x06bar".as_slice());
     }
+
+    #[test]
+    fn serialize_avro_with_schema_id() {
+        let event = Event::Log(LogEvent::from(btreemap! {
+            "foo" => Value::from("bar")
+        }));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.avsc");
+        std::fs::write(
+            &path,
+            r#"{"type": "record", "name": "Log", "fields": [{"name": "foo", "type": "string"}]}"#,
+        )
+        .unwrap();
+        let config = AvroSerializerConfig {
+            avro: AvroSerializerOptions {
+                schema: None,
+                schema_file: Some(path),
+                schema_id: Some(42),
+            },
+        };
+        let mut serializer = config.build().unwrap();
+        let mut bytes = BytesMut::new();
+
+        serializer.encode(event, &mut bytes).unwrap();
+
+        assert_eq!(bytes.freeze(), b"\0\0\0\0\x2a\x06bar".as_slice());
+    }
 }
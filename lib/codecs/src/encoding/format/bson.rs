@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use bson::{spec::BinarySubtype, Binary, Bson, DateTime, Document};
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, Value},
+    schema,
+};
+
+use crate::decoding::format::parse_decimal128;
+
+/// Config used to build a `BsonSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BsonSerializerConfig;
+
+impl BsonSerializerConfig {
+    /// Creates a new `BsonSerializerConfig`.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Build the `BsonSerializer` from this configuration.
+    pub const fn build(&self) -> BsonSerializer {
+        BsonSerializer
+    }
+
+    /// The data type of events that are accepted by `BsonSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::all()
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the BSON format.
+///
+/// Log and trace events are encoded into documents. Bytes values are encoded into strings, unless
+/// they aren't valid UTF-8, and timestamps into date/times. Extended JSON objects holding a single
+/// field, such as `{"$oid": "..."}` or `{"$numberDecimal": "..."}`, are encoded into the BSON type
+/// they represent.
+#[derive(Debug, Clone)]
+pub struct BsonSerializer;
+
+impl BsonSerializer {
+    /// Creates a new `BsonSerializer`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Encoder<Event> for BsonSerializer {
+    type Error = vector_core::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let document = match event {
+            Event::Log(log) => to_document(log.value()),
+            Event::Metric(metric) => bson::to_document(&metric)?,
+            Event::Trace(trace) => to_document(trace.value()),
+        };
+        document.to_writer(buffer.writer()).map_err(Into::into)
+    }
+}
+
+fn to_document(value: &Value) -> Document {
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| (key.clone(), to_bson(value)))
+            .collect(),
+        value => [(log_schema().message_key().to_owned(), to_bson(value))]
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn to_bson(value: &Value) -> Bson {
+    match value {
+        Value::Null => Bson::Null,
+        Value::Boolean(boolean) => Bson::Boolean(*boolean),
+        Value::Integer(integer) => Bson::Int64(*integer),
+        Value::Float(float) => Bson::Double(float.into_inner()),
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(string) => Bson::String(string.to_owned()),
+            Err(_) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: bytes.to_vec(),
+            }),
+        },
+        Value::Regex(_) => Bson::String(value.to_string_lossy()),
+        Value::Timestamp(timestamp) => Bson::DateTime(DateTime::from_chrono(*timestamp)),
+        Value::Array(values) => Bson::Array(values.iter().map(to_bson).collect()),
+        Value::Object(fields) => {
+            from_extended_json(fields).unwrap_or_else(|| Bson::Document(to_document(value)))
+        }
+    }
+}
+
+/// Encodes the extended JSON objects holding a single field into the BSON type they represent.
+fn from_extended_json(fields: &BTreeMap<String, Value>) -> Option<Bson> {
+    let (key, value) = fields.iter().next()?;
+    if fields.len() != 1 || !key.starts_with('$') {
+        return None;
+    }
+
+    match (key.as_str(), value) {
+        // The extended JSON parser of the `bson` crate doesn't support decimal strings.
+        ("$numberDecimal", Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(parse_decimal128)
+            .map(Bson::Decimal128),
+        _ => serde_json::to_value(fields)
+            .ok()
+            .and_then(|json| Bson::try_from(json).ok())
+            .filter(|bson| !matches!(bson, Bson::Document(_))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::{doc, oid::ObjectId};
+    use bytes::Bytes;
+    use chrono::{TimeZone, Utc};
+    use vector_common::btreemap;
+    use vector_core::event::LogEvent;
+
+    use super::*;
+    use crate::decoding::format::{BsonDeserializerConfig, BsonDeserializerOptions, Deserializer};
+
+    fn encode(log: LogEvent) -> Document {
+        let mut bytes = BytesMut::new();
+        BsonSerializer::new()
+            .encode(log.into(), &mut bytes)
+            .unwrap();
+        Document::from_reader(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn serialize_bson() {
+        let timestamp = Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250);
+        let raw = Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![0, 159],
+        };
+        let document = encode(LogEvent::from(btreemap! {
+            "message" => Value::from("hi"),
+            "count" => Value::from(3),
+            "raw" => Value::Bytes(Bytes::from_static(&[0, 159])),
+            "timestamp" => Value::Timestamp(timestamp),
+            "tags" => Value::Array(vec![Value::Null, Value::from(true)]),
+        }));
+
+        assert_eq!(
+            document,
+            doc! {
+                "count": 3_i64,
+                "message": "hi",
+                "raw": raw,
+                "tags": [Bson::Null, true],
+                "timestamp": DateTime::from_chrono(timestamp),
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_extended_json() {
+        let document = encode(LogEvent::from(btreemap! {
+            "_id" => Value::from(btreemap! { "$oid" => Value::from("62a0c4f4b5a3d2c1e0f9a8b7") }),
+            "price" => Value::from(btreemap! { "$numberDecimal" => Value::from("19.99") }),
+            "$inc" => Value::from(btreemap! { "count" => Value::from(1) }),
+            "filter" => Value::from(btreemap! { "$gt" => Value::from(1) }),
+        }));
+
+        assert_eq!(
+            document.get_object_id("_id").unwrap(),
+            ObjectId::parse_str("62a0c4f4b5a3d2c1e0f9a8b7").unwrap()
+        );
+        assert_eq!(
+            document.get("price"),
+            Some(&Bson::Decimal128(parse_decimal128("19.99").unwrap()))
+        );
+        assert_eq!(
+            document.get_document("$inc").unwrap(),
+            &doc! { "count": 1_i64 }
+        );
+        assert_eq!(
+            document.get_document("filter").unwrap(),
+            &doc! { "$gt": 1_i64 }
+        );
+    }
+
+    #[test]
+    fn roundtrips_through_the_deserializer() {
+        let log = LogEvent::from(btreemap! {
+            "_id" => Value::from(btreemap! { "$oid" => Value::from("62a0c4f4b5a3d2c1e0f9a8b7") }),
+            "price" => Value::from(btreemap! { "$numberDecimal" => Value::from("1.2345E+6") }),
+            "ratio" => Value::from_f64_or_zero(0.5),
+            "nested" => Value::from(btreemap! {
+                "list" => Value::Array(vec![Value::Null, Value::from(-3)]),
+            }),
+            "timestamp" => Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250)),
+        });
+        let mut bytes = BytesMut::new();
+        BsonSerializer::new()
+            .encode(log.clone().into(), &mut bytes)
+            .unwrap();
+
+        let deserializer = BsonDeserializerConfig::new(BsonDeserializerOptions {
+            extended_json: true,
+        })
+        .build();
+        let decoded = deserializer.parse(bytes.freeze()).unwrap().pop().unwrap();
+        assert_eq!(decoded.into_log(), log);
+    }
+}
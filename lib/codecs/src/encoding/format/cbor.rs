@@ -0,0 +1,164 @@
+use bytes::{BufMut, BytesMut};
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value as CborValue;
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::DataType,
+    event::{Event, Value},
+    schema,
+};
+
+/// The CBOR tag of date/time strings.
+const DATE_TIME_STRING_TAG: u64 = 0;
+
+/// Config used to build a `CborSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CborSerializerConfig;
+
+impl CborSerializerConfig {
+    /// Creates a new `CborSerializerConfig`.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Build the `CborSerializer` from this configuration.
+    pub const fn build(&self) -> CborSerializer {
+        CborSerializer
+    }
+
+    /// The data type of events that are accepted by `CborSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::all()
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the CBOR format.
+///
+/// Log and trace events are encoded into maps. Bytes values are encoded into text strings, unless
+/// they aren't valid UTF-8, and timestamps into date/time strings tagged as such.
+#[derive(Debug, Clone)]
+pub struct CborSerializer;
+
+impl CborSerializer {
+    /// Creates a new `CborSerializer`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Encoder<Event> for CborSerializer {
+    type Error = vector_core::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let writer = buffer.writer();
+        match event {
+            Event::Log(log) => serde_cbor::to_writer(writer, &to_cbor_value(log.value())),
+            Event::Metric(metric) => serde_cbor::to_writer(writer, &metric),
+            Event::Trace(trace) => serde_cbor::to_writer(writer, &to_cbor_value(trace.value())),
+        }
+        .map_err(Into::into)
+    }
+}
+
+fn to_cbor_value(value: &Value) -> CborValue {
+    match value {
+        Value::Null => CborValue::Null,
+        Value::Boolean(boolean) => CborValue::Bool(*boolean),
+        Value::Integer(integer) => CborValue::Integer((*integer).into()),
+        Value::Float(float) => CborValue::Float(float.into_inner()),
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => CborValue::Text(text.to_owned()),
+            Err(_) => CborValue::Bytes(bytes.to_vec()),
+        },
+        Value::Regex(_) => CborValue::Text(value.to_string_lossy()),
+        Value::Timestamp(timestamp) => CborValue::Tag(
+            DATE_TIME_STRING_TAG,
+            Box::new(CborValue::Text(
+                timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            )),
+        ),
+        Value::Array(values) => CborValue::Array(values.iter().map(to_cbor_value).collect()),
+        Value::Object(fields) => CborValue::Map(
+            fields
+                .iter()
+                .map(|(key, value)| (CborValue::Text(key.clone()), to_cbor_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use chrono::{TimeZone, Utc};
+    use vector_common::btreemap;
+    use vector_core::event::LogEvent;
+
+    use super::*;
+    use crate::decoding::format::{CborDeserializer, Deserializer};
+
+    #[test]
+    fn serialize_cbor() {
+        let timestamp = Utc.ymd(2022, 6, 1).and_hms(12, 0, 0);
+        let event = Event::Log(LogEvent::from(btreemap! {
+            "message" => Value::from("hi"),
+            "raw" => Value::Bytes(Bytes::from_static(&[0, 159])),
+            "timestamp" => Value::Timestamp(timestamp),
+        }));
+        let mut bytes = BytesMut::new();
+        CborSerializer::new().encode(event, &mut bytes).unwrap();
+
+        let item: CborValue = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(
+            item,
+            CborValue::Map(
+                vec![
+                    (
+                        CborValue::Text("message".into()),
+                        CborValue::Text("hi".into())
+                    ),
+                    (
+                        CborValue::Text("raw".into()),
+                        CborValue::Bytes(vec![0, 159])
+                    ),
+                    (
+                        CborValue::Text("timestamp".into()),
+                        CborValue::Tag(0, Box::new(CborValue::Text("2022-06-01T12:00:00Z".into())))
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn roundtrips_through_the_deserializer() {
+        let log = LogEvent::from(btreemap! {
+            "message" => Value::from("hi"),
+            "count" => Value::from(-3),
+            "ratio" => Value::from_f64_or_zero(0.5),
+            "nested" => Value::from(btreemap! {
+                "list" => Value::Array(vec![Value::Null, Value::from(true)]),
+            }),
+            "timestamp" => Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250)),
+        });
+        let mut bytes = BytesMut::new();
+        CborSerializer::new()
+            .encode(log.clone().into(), &mut bytes)
+            .unwrap();
+
+        let decoded = CborDeserializer
+            .parse(bytes.freeze())
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(decoded.into_log(), log);
+    }
+}
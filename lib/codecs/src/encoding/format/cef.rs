@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::DataType,
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+/// Config used to build a `CefSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CefSerializerConfig {
+    /// Options for the CEF serializer.
+    #[serde(default)]
+    pub cef: CefSerializerOptions,
+}
+
+impl CefSerializerConfig {
+    /// Creates a new `CefSerializerConfig`.
+    pub const fn new(cef: CefSerializerOptions) -> Self {
+        Self { cef }
+    }
+
+    /// Build the `CefSerializer` from this configuration.
+    pub fn build(&self) -> CefSerializer {
+        CefSerializer::new(self.cef.clone())
+    }
+
+    /// The data type of events that are accepted by `CefSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Options for building a `CefSerializer`.
+///
+/// The header and extension fields are read from the fields of the events they are mapped to. The
+/// defaults map them to the fields the `cef` decoder decodes them into.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CefSerializerOptions {
+    /// The CEF version.
+    #[serde(default = "default_version")]
+    pub version: String,
+
+    /// The field the device vendor is read from.
+    #[serde(default = "default_device_vendor_field")]
+    pub device_vendor_field: String,
+
+    /// The field the device product is read from.
+    #[serde(default = "default_device_product_field")]
+    pub device_product_field: String,
+
+    /// The field the device version is read from.
+    #[serde(default = "default_device_version_field")]
+    pub device_version_field: String,
+
+    /// The field the device event class ID is read from.
+    #[serde(default = "default_device_event_class_id_field")]
+    pub device_event_class_id_field: String,
+
+    /// The field the name is read from.
+    #[serde(default = "default_name_field")]
+    pub name_field: String,
+
+    /// The field the severity is read from.
+    #[serde(default = "default_severity_field")]
+    pub severity_field: String,
+
+    /// Maps the keys of the extension to the fields of the events their values are read from.
+    ///
+    /// When empty, the extension is read from the `extensions` object of the events.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, String>,
+}
+
+fn default_version() -> String {
+    "0".to_owned()
+}
+
+fn default_device_vendor_field() -> String {
+    "device_vendor".to_owned()
+}
+
+fn default_device_product_field() -> String {
+    "device_product".to_owned()
+}
+
+fn default_device_version_field() -> String {
+    "device_version".to_owned()
+}
+
+fn default_device_event_class_id_field() -> String {
+    "device_event_class_id".to_owned()
+}
+
+fn default_name_field() -> String {
+    "name".to_owned()
+}
+
+fn default_severity_field() -> String {
+    "severity".to_owned()
+}
+
+impl Default for CefSerializerOptions {
+    fn default() -> Self {
+        Self {
+            version: default_version(),
+            device_vendor_field: default_device_vendor_field(),
+            device_product_field: default_device_product_field(),
+            device_version_field: default_device_version_field(),
+            device_event_class_id_field: default_device_event_class_id_field(),
+            name_field: default_name_field(),
+            severity_field: default_severity_field(),
+            extensions: BTreeMap::new(),
+        }
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the ArcSight Common Event Format.
+#[derive(Debug, Clone)]
+pub struct CefSerializer {
+    options: CefSerializerOptions,
+}
+
+impl CefSerializer {
+    /// Creates a new `CefSerializer`.
+    pub const fn new(options: CefSerializerOptions) -> Self {
+        Self { options }
+    }
+
+    fn header_fields(&self) -> [&str; 6] {
+        [
+            self.options.device_vendor_field.as_str(),
+            self.options.device_product_field.as_str(),
+            self.options.device_version_field.as_str(),
+            self.options.device_event_class_id_field.as_str(),
+            self.options.name_field.as_str(),
+            self.options.severity_field.as_str(),
+        ]
+    }
+
+    fn extension(&self, log: &LogEvent) -> Vec<(String, String)> {
+        if self.options.extensions.is_empty() {
+            return match log.get("extensions") {
+                Some(Value::Object(extensions)) => extensions
+                    .iter()
+                    .filter(|(_, value)| !matches!(value, Value::Null))
+                    .map(|(key, value)| (key.clone(), to_string(value)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+        }
+        self.options
+            .extensions
+            .iter()
+            .filter_map(|(key, field)| match log.get(field.as_str()) {
+                None | Some(Value::Null) => None,
+                Some(value) => Some((key.clone(), to_string(value))),
+            })
+            .collect()
+    }
+}
+
+impl Encoder<Event> for CefSerializer {
+    type Error = vector_core::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let log = event.into_log();
+
+        buffer.put_slice(b"CEF:");
+        put_header_field(buffer, &self.options.version);
+        for field in self.header_fields() {
+            buffer.put_u8(b'|');
+            if let Some(value) = log.get(field) {
+                put_header_field(buffer, &to_string(value));
+            }
+        }
+        buffer.put_u8(b'|');
+
+        for (index, (key, value)) in self.extension(&log).into_iter().enumerate() {
+            if index > 0 {
+                buffer.put_u8(b' ');
+            }
+            buffer.put_slice(key.as_bytes());
+            buffer.put_u8(b'=');
+            put_extension_value(buffer, &value);
+        }
+
+        Ok(())
+    }
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Object(_) | Value::Array(_) => {
+            serde_json::to_string(value).unwrap_or_else(|_| value.to_string_lossy())
+        }
+        value => value.to_string_lossy(),
+    }
+}
+
+/// Writes a header field, escaping pipes and backslashes, and replacing line breaks, which
+/// header fields can't contain, with spaces.
+fn put_header_field(buffer: &mut BytesMut, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => buffer.put_slice(b"\\\\"),
+            '|' => buffer.put_slice(b"\\|"),
+            '\n' | '\r' => buffer.put_u8(b' '),
+            c => buffer.put_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+/// Writes an extension value, escaping equal signs, backslashes and line breaks.
+fn put_extension_value(buffer: &mut BytesMut, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => buffer.put_slice(b"\\\\"),
+            '=' => buffer.put_slice(b"\\="),
+            '\n' => buffer.put_slice(b"\\n"),
+            '\r' => buffer.put_slice(b"\\r"),
+            c => buffer.put_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use vector_common::btreemap;
+
+    use super::*;
+    use crate::decoding::format::{CefDeserializer, Deserializer};
+
+    fn encode(options: CefSerializerOptions, log: LogEvent) -> Bytes {
+        let mut serializer = CefSerializerConfig::new(options).build();
+        let mut bytes = BytesMut::new();
+        serializer.encode(log.into(), &mut bytes).unwrap();
+        bytes.freeze()
+    }
+
+    #[test]
+    fn serialize_cef() {
+        let log = LogEvent::from(btreemap! {
+            "device_vendor" => Value::from("Sec|urity"),
+            "device_product" => Value::from("threatmanager"),
+            "device_version" => Value::from("1.0"),
+            "device_event_class_id" => Value::from(100),
+            "name" => Value::from("worm\nstopped"),
+            "severity" => Value::from(10),
+            "extensions" => Value::from(btreemap! {
+                "dst" => Value::from("2.1.2.2"),
+                "msg" => Value::from("a=b \\ c\nd"),
+            }),
+        });
+
+        assert_eq!(
+            encode(CefSerializerOptions::default(), log),
+            r#"CEF:0|Sec\|urity|threatmanager|1.0|100|worm stopped|10|dst=2.1.2.2 msg=a\=b \\ c\nd"#
+        );
+    }
+
+    #[test]
+    fn serialize_cef_with_field_mappings() {
+        let options = CefSerializerOptions {
+            device_vendor_field: "vendor".to_owned(),
+            extensions: btreemap! {
+                "src" => "client.ip",
+                "suser" => "user",
+                "missing" => "nothing",
+            },
+            ..Default::default()
+        };
+        let log = LogEvent::from(btreemap! {
+            "vendor" => Value::from("Vector"),
+            "client" => Value::from(btreemap! { "ip" => Value::from("10.0.0.1") }),
+            "user" => Value::from("jane"),
+        });
+
+        assert_eq!(
+            encode(options, log),
+            "CEF:0|Vector||||||src=10.0.0.1 suser=jane"
+        );
+    }
+
+    #[test]
+    fn roundtrips_through_the_deserializer() {
+        let input = r#"CEF:0|a\|b|p|1|id|name \\ here|5|msg=x\=y z\nw act=allowed"#;
+        let event = CefDeserializer
+            .parse(Bytes::from_static(input.as_bytes()))
+            .unwrap()
+            .pop()
+            .unwrap();
+        let mut serializer = CefSerializerConfig::default().build();
+        let mut bytes = BytesMut::new();
+        serializer.encode(event, &mut bytes).unwrap();
+
+        assert_eq!(
+            bytes.freeze(),
+            r#"CEF:0|a\|b|p|1|id|name \\ here|5|act=allowed msg=x\=y z\nw"#
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::DataType,
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use crate::encoding::BuildError;
+
+/// Config used to build a `LeefSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LeefSerializerConfig {
+    /// Options for the LEEF serializer.
+    #[serde(default)]
+    pub leef: LeefSerializerOptions,
+}
+
+impl LeefSerializerConfig {
+    /// Creates a new `LeefSerializerConfig`.
+    pub const fn new(leef: LeefSerializerOptions) -> Self {
+        Self { leef }
+    }
+
+    /// Build the `LeefSerializer` from this configuration.
+    pub fn build(&self) -> Result<LeefSerializer, BuildError> {
+        match self.leef.delimiter {
+            b'=' | b'|' | b'\n' | b'\r' => Err(format!(
+                "The LEEF delimiter can't be {:?}",
+                self.leef.delimiter as char
+            )
+            .into()),
+            _ => Ok(LeefSerializer::new(self.leef.clone())),
+        }
+    }
+
+    /// The data type of events that are accepted by `LeefSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// The LEEF version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LeefVersion {
+    /// LEEF 1.0, whose attributes are separated by tabs.
+    #[serde(rename = "1.0")]
+    V1,
+
+    /// LEEF 2.0, which declares the delimiter of the attributes in the header.
+    #[serde(rename = "2.0")]
+    V2,
+}
+
+impl Default for LeefVersion {
+    fn default() -> Self {
+        Self::V2
+    }
+}
+
+/// Options for building a `LeefSerializer`.
+///
+/// The header fields and attributes are read from the fields of the events they are mapped to. The
+/// defaults map them to the fields the `leef` decoder decodes them into.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LeefSerializerOptions {
+    /// The LEEF version.
+    #[serde(default)]
+    pub version: LeefVersion,
+
+    /// The character separating the attributes.
+    ///
+    /// Only LEEF 2.0 messages can use another delimiter than a tab.
+    #[serde(default = "default_delimiter", with = "vector_core::serde::ascii_char")]
+    pub delimiter: u8,
+
+    /// The field the vendor is read from.
+    #[serde(default = "default_vendor_field")]
+    pub vendor_field: String,
+
+    /// The field the product is read from.
+    #[serde(default = "default_product_field")]
+    pub product_field: String,
+
+    /// The field the product version is read from.
+    #[serde(default = "default_product_version_field")]
+    pub product_version_field: String,
+
+    /// The field the event ID is read from.
+    #[serde(default = "default_event_id_field")]
+    pub event_id_field: String,
+
+    /// Maps the keys of the attributes to the fields of the events their values are read from.
+    ///
+    /// When empty, the attributes are read from the `attributes` object of the events.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+const fn default_delimiter() -> u8 {
+    b'\t'
+}
+
+fn default_vendor_field() -> String {
+    "vendor".to_owned()
+}
+
+fn default_product_field() -> String {
+    "product".to_owned()
+}
+
+fn default_product_version_field() -> String {
+    "product_version".to_owned()
+}
+
+fn default_event_id_field() -> String {
+    "event_id".to_owned()
+}
+
+impl Default for LeefSerializerOptions {
+    fn default() -> Self {
+        Self {
+            version: LeefVersion::default(),
+            delimiter: default_delimiter(),
+            vendor_field: default_vendor_field(),
+            product_field: default_product_field(),
+            product_version_field: default_product_version_field(),
+            event_id_field: default_event_id_field(),
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the IBM QRadar Log Event Extended Format.
+#[derive(Debug, Clone)]
+pub struct LeefSerializer {
+    options: LeefSerializerOptions,
+}
+
+impl LeefSerializer {
+    /// Creates a new `LeefSerializer`.
+    pub const fn new(options: LeefSerializerOptions) -> Self {
+        Self { options }
+    }
+
+    /// The delimiter of the attributes, which LEEF 1.0 doesn't allow to change.
+    const fn delimiter(&self) -> u8 {
+        match self.options.version {
+            LeefVersion::V1 => default_delimiter(),
+            LeefVersion::V2 => self.options.delimiter,
+        }
+    }
+
+    fn header_fields(&self) -> [&str; 4] {
+        [
+            self.options.vendor_field.as_str(),
+            self.options.product_field.as_str(),
+            self.options.product_version_field.as_str(),
+            self.options.event_id_field.as_str(),
+        ]
+    }
+
+    fn attributes(&self, log: &LogEvent) -> Vec<(String, String)> {
+        if self.options.attributes.is_empty() {
+            return match log.get("attributes") {
+                Some(Value::Object(attributes)) => attributes
+                    .iter()
+                    .filter(|(_, value)| !matches!(value, Value::Null))
+                    .map(|(key, value)| (key.clone(), to_string(value)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+        }
+        self.options
+            .attributes
+            .iter()
+            .filter_map(|(key, field)| match log.get(field.as_str()) {
+                None | Some(Value::Null) => None,
+                Some(value) => Some((key.clone(), to_string(value))),
+            })
+            .collect()
+    }
+}
+
+impl Encoder<Event> for LeefSerializer {
+    type Error = vector_core::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let log = event.into_log();
+        let delimiter = self.delimiter();
+
+        buffer.put_slice(match self.options.version {
+            LeefVersion::V1 => b"LEEF:1.0",
+            LeefVersion::V2 => b"LEEF:2.0",
+        });
+        for field in self.header_fields() {
+            buffer.put_u8(b'|');
+            if let Some(value) = log.get(field) {
+                put_header_field(buffer, &to_string(value));
+            }
+        }
+        buffer.put_u8(b'|');
+        if self.options.version == LeefVersion::V2 {
+            if delimiter.is_ascii_graphic() {
+                buffer.put_u8(delimiter);
+            } else {
+                buffer.put_slice(format!("x{:02X}", delimiter).as_bytes());
+            }
+            buffer.put_u8(b'|');
+        }
+
+        for (index, (key, value)) in self.attributes(&log).into_iter().enumerate() {
+            if index > 0 {
+                buffer.put_u8(delimiter);
+            }
+            buffer.put_slice(key.as_bytes());
+            buffer.put_u8(b'=');
+            put_attribute_value(buffer, &value, delimiter as char);
+        }
+
+        Ok(())
+    }
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Object(_) | Value::Array(_) => {
+            serde_json::to_string(value).unwrap_or_else(|_| value.to_string_lossy())
+        }
+        value => value.to_string_lossy(),
+    }
+}
+
+/// Writes a header field, escaping pipes, and replacing line breaks, which header fields can't
+/// contain, with spaces.
+fn put_header_field(buffer: &mut BytesMut, value: &str) {
+    for c in value.chars() {
+        match c {
+            '|' => buffer.put_slice(b"\\|"),
+            '\n' | '\r' => buffer.put_u8(b' '),
+            c => buffer.put_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+/// Writes an attribute value, replacing the delimiter and line breaks, which LEEF has no escape
+/// sequences for, with spaces.
+fn put_attribute_value(buffer: &mut BytesMut, value: &str, delimiter: char) {
+    for c in value.chars() {
+        match c {
+            '\n' | '\r' => buffer.put_u8(b' '),
+            c if c == delimiter => buffer.put_u8(b' '),
+            c => buffer.put_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use vector_common::btreemap;
+
+    use super::*;
+    use crate::decoding::format::{Deserializer, LeefDeserializer};
+
+    fn encode(options: LeefSerializerOptions, log: LogEvent) -> Bytes {
+        let mut serializer = LeefSerializerConfig::new(options).build().unwrap();
+        let mut bytes = BytesMut::new();
+        serializer.encode(log.into(), &mut bytes).unwrap();
+        bytes.freeze()
+    }
+
+    #[test]
+    fn serialize_leef() {
+        let log = LogEvent::from(btreemap! {
+            "vendor" => Value::from("Micro|soft"),
+            "product" => Value::from("MSExchange"),
+            "product_version" => Value::from("2016"),
+            "event_id" => Value::from(15345),
+            "attributes" => Value::from(btreemap! {
+                "dst" => Value::from("2.10.20.20"),
+                "msg" => Value::from("a\tb\nc"),
+            }),
+        });
+
+        assert_eq!(
+            encode(LeefSerializerOptions::default(), log.clone()),
+            "LEEF:2.0|Micro\\|soft|MSExchange|2016|15345|x09|dst=2.10.20.20\tmsg=a b c"
+        );
+        assert_eq!(
+            encode(
+                LeefSerializerOptions {
+                    version: LeefVersion::V1,
+                    delimiter: b'^',
+                    ..Default::default()
+                },
+                log
+            ),
+            "LEEF:1.0|Micro\\|soft|MSExchange|2016|15345|dst=2.10.20.20\tmsg=a b c"
+        );
+    }
+
+    #[test]
+    fn serialize_leef_with_field_mappings() {
+        let options = LeefSerializerOptions {
+            delimiter: b'^',
+            vendor_field: "source".to_owned(),
+            attributes: btreemap! {
+                "src" => "client.ip",
+                "usrName" => "user",
+                "missing" => "nothing",
+            },
+            ..Default::default()
+        };
+        let log = LogEvent::from(btreemap! {
+            "source" => Value::from("Vector"),
+            "client" => Value::from(btreemap! { "ip" => Value::from("10.0.0.1") }),
+            "user" => Value::from("ja^ne"),
+        });
+
+        assert_eq!(
+            encode(options, log),
+            "LEEF:2.0|Vector||||^|src=10.0.0.1^usrName=ja ne"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_delimiters() {
+        for delimiter in [b'=', b'|', b'\n'] {
+            let config = LeefSerializerConfig::new(LeefSerializerOptions {
+                delimiter,
+                ..Default::default()
+            });
+            assert!(config.build().is_err());
+        }
+    }
+
+    #[test]
+    fn roundtrips_through_the_deserializer() {
+        let input = "LEEF:2.0|a\\|b|p|1|id|^|usrName=jane^src=10.0.0.1";
+        let event = LeefDeserializer::default()
+            .parse(Bytes::from_static(input.as_bytes()))
+            .unwrap()
+            .pop()
+            .unwrap();
+        let mut serializer = LeefSerializerConfig::new(LeefSerializerOptions {
+            delimiter: b'^',
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+        let mut bytes = BytesMut::new();
+        serializer.encode(event, &mut bytes).unwrap();
+
+        assert_eq!(
+            bytes.freeze(),
+            "LEEF:2.0|a\\|b|p|1|id|^|src=10.0.0.1^usrName=jane"
+        );
+    }
+}
@@ -4,25 +4,36 @@
 #![deny(missing_docs)]
 
 mod avro;
+mod bson;
+mod cbor;
+mod cef;
 mod json;
+mod leef;
 mod logfmt;
 mod native;
 mod native_json;
+mod protobuf;
 mod raw_message;
 mod text;
 
 use std::fmt::Debug;
 
 pub use avro::{AvroSerializer, AvroSerializerConfig, AvroSerializerOptions};
+pub use cbor::{CborSerializer, CborSerializerConfig};
+pub use cef::{CefSerializer, CefSerializerConfig, CefSerializerOptions};
 use dyn_clone::DynClone;
 pub use json::{JsonSerializer, JsonSerializerConfig};
+pub use leef::{LeefSerializer, LeefSerializerConfig, LeefSerializerOptions, LeefVersion};
 pub use logfmt::{LogfmtSerializer, LogfmtSerializerConfig};
 pub use native::{NativeSerializer, NativeSerializerConfig};
 pub use native_json::{NativeJsonSerializer, NativeJsonSerializerConfig};
+pub use protobuf::{ProtobufSerializer, ProtobufSerializerConfig, ProtobufSerializerOptions};
 pub use raw_message::{RawMessageSerializer, RawMessageSerializerConfig};
 pub use text::{TextSerializer, TextSerializerConfig};
 use vector_core::event::Event;
 
+pub use self::bson::{BsonSerializer, BsonSerializerConfig};
+
 /// Serialize a structured event into a byte frame.
 pub trait Serializer:
     tokio_util::codec::Encoder<Event, Error = vector_core::Error> + DynClone + Debug + Send + Sync
@@ -0,0 +1,504 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::encoding::BuildError;
+use bytes::BytesMut;
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, Kind, MapKey, MessageDescriptor,
+    Value as ProtobufValue,
+};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::DataType,
+    event::{Event, Value},
+    schema,
+};
+
+/// The full name of the well-known timestamp message, which timestamps are converted to.
+const TIMESTAMP_MESSAGE_TYPE: &str = "google.protobuf.Timestamp";
+
+/// Config used to build a `ProtobufSerializer`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProtobufSerializerConfig {
+    /// Options for the Protobuf serializer.
+    pub protobuf: ProtobufSerializerOptions,
+}
+
+impl ProtobufSerializerConfig {
+    /// Build the `ProtobufSerializer` from this configuration.
+    pub fn build(&self) -> Result<ProtobufSerializer, BuildError> {
+        let descriptor = self
+            .protobuf
+            .read_message_descriptor()
+            .map_err(|error| format!("Failed building Protobuf serializer: {}", error))?;
+        Ok(ProtobufSerializer {
+            descriptor,
+            field_mappings: self.protobuf.field_mappings.clone(),
+        })
+    }
+
+    /// The data type of events that are accepted by `ProtobufSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        // TODO: Convert the message descriptor to a vector schema requirement.
+        schema::Requirement::empty()
+    }
+}
+
+/// Options for building a `ProtobufSerializer`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProtobufSerializerOptions {
+    /// The path of the compiled descriptor set containing the message type, as produced by
+    /// `protoc --include_imports --descriptor_set_out`.
+    pub desc_file: PathBuf,
+
+    /// The fully qualified name of the message type the events are serialized into, e.g.
+    /// `package.Message`.
+    pub message_type: String,
+
+    /// Maps the fields of the message, by path, to the fields of the events they are read from.
+    ///
+    /// The message fields that aren't mapped are read from the event fields of the same name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_mappings: BTreeMap<String, String>,
+}
+
+impl ProtobufSerializerOptions {
+    /// Reads the descriptor set and looks up the descriptor of the message type.
+    pub fn read_message_descriptor(&self) -> Result<MessageDescriptor, BuildError> {
+        let bytes = std::fs::read(&self.desc_file).map_err(|error| {
+            format!(
+                "Failed reading descriptor set file {:?}: {}",
+                self.desc_file, error
+            )
+        })?;
+        let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|error| {
+            format!(
+                "Invalid descriptor set in file {:?}: {}",
+                self.desc_file, error
+            )
+        })?;
+        pool.get_message_by_name(&self.message_type).ok_or_else(|| {
+            format!(
+                "Message type {:?} not found in descriptor set file {:?}",
+                self.message_type, self.desc_file
+            )
+            .into()
+        })
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the Protocol Buffers format.
+#[derive(Debug, Clone)]
+pub struct ProtobufSerializer {
+    descriptor: MessageDescriptor,
+    field_mappings: BTreeMap<String, String>,
+}
+
+impl ProtobufSerializer {
+    /// Creates a new `ProtobufSerializer`.
+    pub fn new(descriptor: MessageDescriptor) -> Self {
+        Self {
+            descriptor,
+            field_mappings: BTreeMap::new(),
+        }
+    }
+
+    /// Converts a log event to a message, reading the mapped fields from their event fields.
+    fn event_to_message(&self, event: Event) -> vector_core::Result<DynamicMessage> {
+        let log = event.into_log();
+        let mut fields = log.value().clone();
+        for (message_path, event_path) in &self.field_mappings {
+            match log.get(event_path.as_str()) {
+                Some(value) => fields.insert(message_path.as_str(), value.clone()),
+                None => fields.remove(message_path.as_str(), false),
+            };
+        }
+        match fields {
+            Value::Object(fields) => object_to_message(&self.descriptor, fields),
+            _ => Err("Log event root must be an object".into()),
+        }
+    }
+}
+
+impl Encoder<Event> for ProtobufSerializer {
+    type Error = vector_core::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let message = self.event_to_message(event)?;
+        message.encode(buffer)?;
+        Ok(())
+    }
+}
+
+/// Converts an object to a message, ignoring the fields the message type doesn't have.
+fn object_to_message(
+    descriptor: &MessageDescriptor,
+    fields: BTreeMap<String, Value>,
+) -> vector_core::Result<DynamicMessage> {
+    let mut message = DynamicMessage::new(descriptor.clone());
+    for (name, value) in fields {
+        if matches!(value, Value::Null) {
+            continue;
+        }
+        if let Some(field) = descriptor.get_field_by_name(&name) {
+            let value = to_field_value(&field, value)
+                .map_err(|error| format!("Invalid value for field {:?}: {}", name, error))?;
+            message.set_field(&field, value);
+        }
+    }
+    Ok(message)
+}
+
+fn to_field_value(field: &FieldDescriptor, value: Value) -> vector_core::Result<ProtobufValue> {
+    let kind = field.kind();
+    if field.is_map() {
+        let entry = match &kind {
+            Kind::Message(entry) => entry,
+            _ => unreachable!("map fields are messages"),
+        };
+        let key_kind = entry.map_entry_key_field().kind();
+        let value_kind = entry.map_entry_value_field().kind();
+        match value {
+            Value::Object(object) => object
+                .into_iter()
+                .filter(|(_, value)| !matches!(value, Value::Null))
+                .map(|(key, value)| {
+                    Ok((to_map_key(&key_kind, key)?, to_value(&value_kind, value)?))
+                })
+                .collect::<vector_core::Result<_>>()
+                .map(ProtobufValue::Map),
+            value => Err(format!("expected an object, found {}", value.kind_str()).into()),
+        }
+    } else if field.is_list() {
+        match value {
+            Value::Array(values) => values
+                .into_iter()
+                .map(|value| to_value(&kind, value))
+                .collect::<vector_core::Result<_>>()
+                .map(ProtobufValue::List),
+            value => Err(format!("expected an array, found {}", value.kind_str()).into()),
+        }
+    } else {
+        to_value(&kind, value)
+    }
+}
+
+fn to_value(kind: &Kind, value: Value) -> vector_core::Result<ProtobufValue> {
+    let mismatch = |value: &Value| -> vector_core::Error {
+        format!("cannot convert {} to {:?}", value.kind_str(), kind).into()
+    };
+    Ok(match (kind, value) {
+        (Kind::Bool, Value::Boolean(value)) => ProtobufValue::Bool(value),
+        (Kind::Int32 | Kind::Sint32 | Kind::Sfixed32, Value::Integer(value)) => {
+            ProtobufValue::I32(i32::try_from(value)?)
+        }
+        (Kind::Int64 | Kind::Sint64 | Kind::Sfixed64, Value::Integer(value)) => {
+            ProtobufValue::I64(value)
+        }
+        (Kind::Uint32 | Kind::Fixed32, Value::Integer(value)) => {
+            ProtobufValue::U32(u32::try_from(value)?)
+        }
+        (Kind::Uint64 | Kind::Fixed64, Value::Integer(value)) => {
+            ProtobufValue::U64(u64::try_from(value)?)
+        }
+        (Kind::Float, Value::Integer(value)) => ProtobufValue::F32(value as f32),
+        (Kind::Float, Value::Float(value)) => ProtobufValue::F32(value.into_inner() as f32),
+        (Kind::Double, Value::Integer(value)) => ProtobufValue::F64(value as f64),
+        (Kind::Double, Value::Float(value)) => ProtobufValue::F64(value.into_inner()),
+        (Kind::String, value @ (Value::Object(_) | Value::Array(_))) => {
+            ProtobufValue::String(serde_json::to_string(&value)?)
+        }
+        (Kind::String, value) => ProtobufValue::String(value.to_string_lossy()),
+        (Kind::Bytes, Value::Bytes(value)) => ProtobufValue::Bytes(value),
+        (Kind::Enum(_), Value::Integer(number)) => {
+            ProtobufValue::EnumNumber(i32::try_from(number)?)
+        }
+        (Kind::Enum(descriptor), Value::Bytes(name)) => {
+            let name = String::from_utf8_lossy(&name);
+            let value = descriptor.get_value_by_name(&name).ok_or_else(|| {
+                format!(
+                    "unknown value {:?} of enum {}",
+                    name,
+                    descriptor.full_name()
+                )
+            })?;
+            ProtobufValue::EnumNumber(value.number())
+        }
+        (Kind::Message(descriptor), Value::Timestamp(timestamp))
+            if descriptor.full_name() == TIMESTAMP_MESSAGE_TYPE =>
+        {
+            let mut message = DynamicMessage::new(descriptor.clone());
+            message.set_field_by_name("seconds", ProtobufValue::I64(timestamp.timestamp()));
+            message.set_field_by_name(
+                "nanos",
+                ProtobufValue::I32(timestamp.timestamp_subsec_nanos() as i32),
+            );
+            ProtobufValue::Message(message)
+        }
+        (Kind::Message(descriptor), Value::Object(fields)) => {
+            ProtobufValue::Message(object_to_message(descriptor, fields)?)
+        }
+        (_, value) => return Err(mismatch(&value)),
+    })
+}
+
+fn to_map_key(kind: &Kind, key: String) -> vector_core::Result<MapKey> {
+    Ok(match kind {
+        Kind::Bool => MapKey::Bool(key.parse()?),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => MapKey::I32(key.parse()?),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => MapKey::I64(key.parse()?),
+        Kind::Uint32 | Kind::Fixed32 => MapKey::U32(key.parse()?),
+        Kind::Uint64 | Kind::Fixed64 => MapKey::U64(key.parse()?),
+        Kind::String => MapKey::String(key),
+        _ => return Err(format!("invalid map key type {:?}", kind).into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use prost_reflect::prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, FileDescriptorSet, MessageOptions,
+    };
+    use vector_common::btreemap;
+    use vector_core::event::LogEvent;
+
+    use super::*;
+
+    fn field(name: &str, number: i32, ty: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            ..Default::default()
+        }
+    }
+
+    fn typed_field(name: &str, number: i32, ty: Type, type_name: &str) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            type_name: Some(type_name.to_owned()),
+            ..field(name, number, ty, Label::Optional)
+        }
+    }
+
+    /// Writes a descriptor set for:
+    ///
+    /// ```protobuf
+    /// package test;
+    /// enum Level { INFO = 0; ERROR = 1; }
+    /// message Resource { string service = 1; }
+    /// message Log {
+    ///   string message = 1;
+    ///   int32 status = 2;
+    ///   Level level = 3;
+    ///   repeated string tags = 4;
+    ///   map<string, string> attributes = 5;
+    ///   Resource resource = 6;
+    ///   double duration = 7;
+    /// }
+    /// ```
+    fn write_descriptor_set(path: &std::path::Path) {
+        let attributes_entry = DescriptorProto {
+            name: Some("AttributesEntry".to_owned()),
+            field: vec![
+                field("key", 1, Type::String, Label::Optional),
+                field("value", 2, Type::String, Label::Optional),
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("test.proto".to_owned()),
+            package: Some("test".to_owned()),
+            syntax: Some("proto3".to_owned()),
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Level".to_owned()),
+                value: vec![
+                    EnumValueDescriptorProto {
+                        name: Some("INFO".to_owned()),
+                        number: Some(0),
+                        ..Default::default()
+                    },
+                    EnumValueDescriptorProto {
+                        name: Some("ERROR".to_owned()),
+                        number: Some(1),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Resource".to_owned()),
+                    field: vec![field("service", 1, Type::String, Label::Optional)],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("Log".to_owned()),
+                    field: vec![
+                        field("message", 1, Type::String, Label::Optional),
+                        field("status", 2, Type::Int32, Label::Optional),
+                        typed_field("level", 3, Type::Enum, ".test.Level"),
+                        field("tags", 4, Type::String, Label::Repeated),
+                        FieldDescriptorProto {
+                            label: Some(Label::Repeated as i32),
+                            ..typed_field(
+                                "attributes",
+                                5,
+                                Type::Message,
+                                ".test.Log.AttributesEntry",
+                            )
+                        },
+                        typed_field("resource", 6, Type::Message, ".test.Resource"),
+                        field("duration", 7, Type::Double, Label::Optional),
+                    ],
+                    nested_type: vec![attributes_entry],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let set = FileDescriptorSet { file: vec![file] };
+        std::fs::write(path, set.encode_to_vec()).unwrap();
+    }
+
+    fn build(
+        field_mappings: BTreeMap<String, String>,
+    ) -> (ProtobufSerializer, MessageDescriptor, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = dir.path().join("test.desc");
+        write_descriptor_set(&desc_file);
+        let options = ProtobufSerializerOptions {
+            desc_file,
+            message_type: "test.Log".to_owned(),
+            field_mappings,
+        };
+        let descriptor = options.read_message_descriptor().unwrap();
+        let serializer = ProtobufSerializerConfig { protobuf: options }
+            .build()
+            .unwrap();
+        (serializer, descriptor, dir)
+    }
+
+    fn encode(serializer: &mut ProtobufSerializer, event: LogEvent) -> BytesMut {
+        let mut bytes = BytesMut::new();
+        serializer.encode(event.into(), &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn serialize_protobuf() {
+        let (mut serializer, descriptor, _dir) = build(BTreeMap::new());
+        let event = LogEvent::from(btreemap! {
+            "message" => Value::from("GET /"),
+            "status" => Value::from(200),
+            "level" => Value::from("ERROR"),
+            "tags" => Value::from(vec![Value::from("a"), Value::from("b")]),
+            "attributes" => Value::from(btreemap! { "host" => Value::from("h1") }),
+            "resource" => Value::from(btreemap! { "service" => Value::from("api") }),
+            "duration" => Value::from(1),
+            "unknown" => Value::from(true),
+        });
+
+        let bytes = encode(&mut serializer, event);
+
+        let message = DynamicMessage::decode(descriptor, bytes.freeze()).unwrap();
+        let get = |name: &str| message.get_field_by_name(name).unwrap().into_owned();
+        assert_eq!(get("message"), ProtobufValue::String("GET /".to_owned()));
+        assert_eq!(get("status"), ProtobufValue::I32(200));
+        assert_eq!(get("level"), ProtobufValue::EnumNumber(1));
+        assert_eq!(
+            get("tags"),
+            ProtobufValue::List(vec![
+                ProtobufValue::String("a".to_owned()),
+                ProtobufValue::String("b".to_owned()),
+            ])
+        );
+        assert_eq!(
+            get("attributes").as_map().unwrap()[&MapKey::String("host".to_owned())],
+            ProtobufValue::String("h1".to_owned())
+        );
+        assert_eq!(
+            get("resource")
+                .as_message()
+                .unwrap()
+                .get_field_by_name("service")
+                .unwrap()
+                .as_str(),
+            Some("api")
+        );
+        assert_eq!(get("duration"), ProtobufValue::F64(1.0));
+    }
+
+    #[test]
+    fn serialize_protobuf_with_field_mappings() {
+        let (mut serializer, descriptor, _dir) = build(btreemap! {
+            "message" => "msg",
+            "resource.service" => "labels.app",
+            "status" => "missing",
+        });
+        let event = LogEvent::from(btreemap! {
+            "msg" => Value::from("mapped"),
+            "message" => Value::from("ignored"),
+            "status" => Value::from(500),
+            "labels" => Value::from(btreemap! { "app" => Value::from("web") }),
+        });
+
+        let bytes = encode(&mut serializer, event);
+
+        let message = DynamicMessage::decode(descriptor, bytes.freeze()).unwrap();
+        assert_eq!(
+            message.get_field_by_name("message").unwrap().as_str(),
+            Some("mapped")
+        );
+        assert_eq!(
+            message
+                .get_field_by_name("resource")
+                .unwrap()
+                .as_message()
+                .unwrap()
+                .get_field_by_name("service")
+                .unwrap()
+                .as_str(),
+            Some("web")
+        );
+        assert!(!message.has_field_by_name("status"));
+    }
+
+    #[test]
+    fn rejects_mismatched_values() {
+        let (mut serializer, _, _dir) = build(BTreeMap::new());
+        let event = LogEvent::from(btreemap! { "status" => Value::from("ok") });
+        let mut bytes = BytesMut::new();
+
+        assert!(serializer.encode(event.into(), &mut bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_message_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = dir.path().join("test.desc");
+        write_descriptor_set(&desc_file);
+        let config = ProtobufSerializerConfig {
+            protobuf: ProtobufSerializerOptions {
+                desc_file,
+                message_type: "test.Missing".to_owned(),
+                field_mappings: BTreeMap::new(),
+            },
+        };
+
+        assert!(config.build().is_err());
+    }
+}
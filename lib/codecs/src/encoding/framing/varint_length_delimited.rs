@@ -0,0 +1,77 @@
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+
+use super::BoxedFramingError;
+
+/// Config used to build a `VarintLengthDelimitedEncoder`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct VarintLengthDelimitedEncoderConfig;
+
+impl VarintLengthDelimitedEncoderConfig {
+    /// Creates a `VarintLengthDelimitedEncoderConfig`.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Build the `VarintLengthDelimitedEncoder` from this configuration.
+    pub const fn build(&self) -> VarintLengthDelimitedEncoder {
+        VarintLengthDelimitedEncoder
+    }
+}
+
+/// An encoder for handling bytes that are prefixed by their length, encoded as a protobuf varint.
+///
+/// This is the framing used by the `writeDelimitedTo`/`parseDelimitedFrom` methods of the protobuf
+/// libraries.
+#[derive(Debug, Clone, Default)]
+pub struct VarintLengthDelimitedEncoder;
+
+impl VarintLengthDelimitedEncoder {
+    /// Creates a `VarintLengthDelimitedEncoder`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Encoder<()> for VarintLengthDelimitedEncoder {
+    type Error = BoxedFramingError;
+
+    fn encode(&mut self, _: (), buffer: &mut BytesMut) -> Result<(), BoxedFramingError> {
+        let frame = buffer.split();
+        let mut length = frame.len() as u64;
+        while length >= 0x80 {
+            buffer.put_u8((length as u8) | 0x80);
+            length >>= 7;
+        }
+        buffer.put_u8(length as u8);
+        buffer.unsplit(frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let mut codec = VarintLengthDelimitedEncoder::new();
+
+        let mut buffer = BytesMut::from("abc");
+        codec.encode((), &mut buffer).unwrap();
+
+        assert_eq!(&buffer[..], b"\x03abc");
+    }
+
+    #[test]
+    fn encode_multi_byte_header() {
+        let mut codec = VarintLengthDelimitedEncoder::new();
+
+        let mut buffer = BytesMut::from("x".repeat(300).as_str());
+        codec.encode((), &mut buffer).unwrap();
+
+        assert_eq!(&buffer[..2], b"\xac\x02");
+        assert_eq!(buffer.len(), 302);
+    }
+}
@@ -8,15 +8,20 @@ use std::fmt::Debug;
 
 use bytes::BytesMut;
 pub use format::{
-    AvroSerializer, AvroSerializerConfig, AvroSerializerOptions, JsonSerializer,
-    JsonSerializerConfig, LogfmtSerializer, LogfmtSerializerConfig, NativeJsonSerializer,
-    NativeJsonSerializerConfig, NativeSerializer, NativeSerializerConfig, RawMessageSerializer,
-    RawMessageSerializerConfig, TextSerializer, TextSerializerConfig,
+    AvroSerializer, AvroSerializerConfig, AvroSerializerOptions, BsonSerializer,
+    BsonSerializerConfig, CborSerializer, CborSerializerConfig, CefSerializer, CefSerializerConfig,
+    CefSerializerOptions, JsonSerializer, JsonSerializerConfig, LeefSerializer,
+    LeefSerializerConfig, LeefSerializerOptions, LeefVersion, LogfmtSerializer,
+    LogfmtSerializerConfig, NativeJsonSerializer, NativeJsonSerializerConfig, NativeSerializer,
+    NativeSerializerConfig, ProtobufSerializer, ProtobufSerializerConfig,
+    ProtobufSerializerOptions, RawMessageSerializer, RawMessageSerializerConfig, TextSerializer,
+    TextSerializerConfig,
 };
 pub use framing::{
     BoxedFramer, BoxedFramingError, BytesEncoder, BytesEncoderConfig, CharacterDelimitedEncoder,
     CharacterDelimitedEncoderConfig, CharacterDelimitedEncoderOptions, LengthDelimitedEncoder,
     LengthDelimitedEncoderConfig, NewlineDelimitedEncoder, NewlineDelimitedEncoderConfig,
+    VarintLengthDelimitedEncoder, VarintLengthDelimitedEncoderConfig,
 };
 use serde::{Deserialize, Serialize};
 use vector_core::{config::DataType, event::Event, schema};
@@ -68,6 +73,8 @@ pub enum FramingConfig {
     LengthDelimited,
     /// Configures the `NewlineDelimitedEncoder`.
     NewlineDelimited,
+    /// Configures the `VarintLengthDelimitedEncoder`.
+    VarintLengthDelimited,
 }
 
 impl From<BytesEncoderConfig> for FramingConfig {
@@ -96,6 +103,12 @@ impl From<NewlineDelimitedEncoderConfig> for FramingConfig {
     }
 }
 
+impl From<VarintLengthDelimitedEncoderConfig> for FramingConfig {
+    fn from(_: VarintLengthDelimitedEncoderConfig) -> Self {
+        Self::VarintLengthDelimited
+    }
+}
+
 impl FramingConfig {
     /// Build the `Framer` from this configuration.
     pub fn build(&self) -> Framer {
@@ -115,6 +128,9 @@ impl FramingConfig {
             FramingConfig::NewlineDelimited => {
                 Framer::NewlineDelimited(NewlineDelimitedEncoderConfig.build())
             }
+            FramingConfig::VarintLengthDelimited => {
+                Framer::VarintLengthDelimited(VarintLengthDelimitedEncoderConfig.build())
+            }
         }
     }
 }
@@ -130,6 +146,8 @@ pub enum Framer {
     LengthDelimited(LengthDelimitedEncoder),
     /// Uses a `NewlineDelimitedEncoder` for framing.
     NewlineDelimited(NewlineDelimitedEncoder),
+    /// Uses a `VarintLengthDelimitedEncoder` for framing.
+    VarintLengthDelimited(VarintLengthDelimitedEncoder),
     /// Uses an opaque `Encoder` implementation for framing.
     Boxed(BoxedFramer),
 }
@@ -158,6 +176,12 @@ impl From<NewlineDelimitedEncoder> for Framer {
     }
 }
 
+impl From<VarintLengthDelimitedEncoder> for Framer {
+    fn from(encoder: VarintLengthDelimitedEncoder) -> Self {
+        Self::VarintLengthDelimited(encoder)
+    }
+}
+
 impl From<BoxedFramer> for Framer {
     fn from(encoder: BoxedFramer) -> Self {
         Self::Boxed(encoder)
@@ -173,6 +197,7 @@ impl tokio_util::codec::Encoder<()> for Framer {
             Framer::CharacterDelimited(framer) => framer.encode((), buffer),
             Framer::LengthDelimited(framer) => framer.encode((), buffer),
             Framer::NewlineDelimited(framer) => framer.encode((), buffer),
+            Framer::VarintLengthDelimited(framer) => framer.encode((), buffer),
             Framer::Boxed(framer) => framer.encode((), buffer),
         }
     }
@@ -190,14 +215,35 @@ pub enum SerializerConfig {
         /// Options for the avro serializer.
         avro: AvroSerializerOptions,
     },
+    /// Configures the `BsonSerializer`.
+    Bson,
+    /// Configures the `CborSerializer`.
+    Cbor,
+    /// Configures the `CefSerializer`.
+    Cef {
+        /// Options for the CEF serializer.
+        #[serde(default)]
+        cef: CefSerializerOptions,
+    },
     /// Configures the `JsonSerializer`.
     Json,
+    /// Configures the `LeefSerializer`.
+    Leef {
+        /// Options for the LEEF serializer.
+        #[serde(default)]
+        leef: LeefSerializerOptions,
+    },
     /// Configures the `LogfmtSerializer`.
     Logfmt,
     /// Configures the `NativeSerializer`.
     Native,
     /// Configures the `NativeJsonSerializer`.
     NativeJson,
+    /// Configures the `ProtobufSerializer`.
+    Protobuf {
+        /// Options for the protobuf serializer.
+        protobuf: ProtobufSerializerOptions,
+    },
     /// Configures the `RawMessageSerializer`.
     RawMessage,
     /// Configures the `TextSerializer`.
@@ -210,12 +256,36 @@ impl From<AvroSerializerConfig> for SerializerConfig {
     }
 }
 
+impl From<BsonSerializerConfig> for SerializerConfig {
+    fn from(_: BsonSerializerConfig) -> Self {
+        Self::Bson
+    }
+}
+
+impl From<CborSerializerConfig> for SerializerConfig {
+    fn from(_: CborSerializerConfig) -> Self {
+        Self::Cbor
+    }
+}
+
+impl From<CefSerializerConfig> for SerializerConfig {
+    fn from(config: CefSerializerConfig) -> Self {
+        Self::Cef { cef: config.cef }
+    }
+}
+
 impl From<JsonSerializerConfig> for SerializerConfig {
     fn from(_: JsonSerializerConfig) -> Self {
         Self::Json
     }
 }
 
+impl From<LeefSerializerConfig> for SerializerConfig {
+    fn from(config: LeefSerializerConfig) -> Self {
+        Self::Leef { leef: config.leef }
+    }
+}
+
 impl From<LogfmtSerializerConfig> for SerializerConfig {
     fn from(_: LogfmtSerializerConfig) -> Self {
         Self::Logfmt
@@ -234,6 +304,14 @@ impl From<NativeJsonSerializerConfig> for SerializerConfig {
     }
 }
 
+impl From<ProtobufSerializerConfig> for SerializerConfig {
+    fn from(config: ProtobufSerializerConfig) -> Self {
+        Self::Protobuf {
+            protobuf: config.protobuf,
+        }
+    }
+}
+
 impl From<RawMessageSerializerConfig> for SerializerConfig {
     fn from(_: RawMessageSerializerConfig) -> Self {
         Self::RawMessage
@@ -251,14 +329,28 @@ impl SerializerConfig {
     pub fn build(&self) -> Result<Serializer, Box<dyn std::error::Error + Send + Sync + 'static>> {
         match self {
             SerializerConfig::Avro { avro } => Ok(Serializer::Avro(
-                AvroSerializerConfig::new(avro.schema.clone()).build()?,
+                AvroSerializerConfig { avro: avro.clone() }.build()?,
+            )),
+            SerializerConfig::Bson => Ok(Serializer::Bson(BsonSerializerConfig.build())),
+            SerializerConfig::Cbor => Ok(Serializer::Cbor(CborSerializerConfig.build())),
+            SerializerConfig::Cef { cef } => Ok(Serializer::Cef(
+                CefSerializerConfig::new(cef.clone()).build(),
             )),
             SerializerConfig::Json => Ok(Serializer::Json(JsonSerializerConfig.build())),
+            SerializerConfig::Leef { leef } => Ok(Serializer::Leef(
+                LeefSerializerConfig::new(leef.clone()).build()?,
+            )),
             SerializerConfig::Logfmt => Ok(Serializer::Logfmt(LogfmtSerializerConfig.build())),
             SerializerConfig::Native => Ok(Serializer::Native(NativeSerializerConfig.build())),
             SerializerConfig::NativeJson => {
                 Ok(Serializer::NativeJson(NativeJsonSerializerConfig.build()))
             }
+            SerializerConfig::Protobuf { protobuf } => Ok(Serializer::Protobuf(
+                ProtobufSerializerConfig {
+                    protobuf: protobuf.clone(),
+                }
+                .build()?,
+            )),
             SerializerConfig::RawMessage => {
                 Ok(Serializer::RawMessage(RawMessageSerializerConfig.build()))
             }
@@ -270,12 +362,20 @@ impl SerializerConfig {
     pub fn input_type(&self) -> DataType {
         match self {
             SerializerConfig::Avro { avro } => {
-                AvroSerializerConfig::new(avro.schema.clone()).input_type()
+                AvroSerializerConfig { avro: avro.clone() }.input_type()
             }
+            SerializerConfig::Bson => BsonSerializerConfig.input_type(),
+            SerializerConfig::Cbor => CborSerializerConfig.input_type(),
+            SerializerConfig::Cef { cef } => CefSerializerConfig::new(cef.clone()).input_type(),
             SerializerConfig::Json => JsonSerializerConfig.input_type(),
+            SerializerConfig::Leef { leef } => LeefSerializerConfig::new(leef.clone()).input_type(),
             SerializerConfig::Logfmt => LogfmtSerializerConfig.input_type(),
             SerializerConfig::Native => NativeSerializerConfig.input_type(),
             SerializerConfig::NativeJson => NativeJsonSerializerConfig.input_type(),
+            SerializerConfig::Protobuf { protobuf } => ProtobufSerializerConfig {
+                protobuf: protobuf.clone(),
+            }
+            .input_type(),
             SerializerConfig::RawMessage => RawMessageSerializerConfig.input_type(),
             SerializerConfig::Text => TextSerializerConfig.input_type(),
         }
@@ -285,12 +385,24 @@ impl SerializerConfig {
     pub fn schema_requirement(&self) -> schema::Requirement {
         match self {
             SerializerConfig::Avro { avro } => {
-                AvroSerializerConfig::new(avro.schema.clone()).schema_requirement()
+                AvroSerializerConfig { avro: avro.clone() }.schema_requirement()
+            }
+            SerializerConfig::Bson => BsonSerializerConfig.schema_requirement(),
+            SerializerConfig::Cbor => CborSerializerConfig.schema_requirement(),
+            SerializerConfig::Cef { cef } => {
+                CefSerializerConfig::new(cef.clone()).schema_requirement()
             }
             SerializerConfig::Json => JsonSerializerConfig.schema_requirement(),
+            SerializerConfig::Leef { leef } => {
+                LeefSerializerConfig::new(leef.clone()).schema_requirement()
+            }
             SerializerConfig::Logfmt => LogfmtSerializerConfig.schema_requirement(),
             SerializerConfig::Native => NativeSerializerConfig.schema_requirement(),
             SerializerConfig::NativeJson => NativeJsonSerializerConfig.schema_requirement(),
+            SerializerConfig::Protobuf { protobuf } => ProtobufSerializerConfig {
+                protobuf: protobuf.clone(),
+            }
+            .schema_requirement(),
             SerializerConfig::RawMessage => RawMessageSerializerConfig.schema_requirement(),
             SerializerConfig::Text => TextSerializerConfig.schema_requirement(),
         }
@@ -302,14 +414,24 @@ impl SerializerConfig {
 pub enum Serializer {
     /// Uses an `AvroSerializer` for serialization.
     Avro(AvroSerializer),
+    /// Uses a `BsonSerializer` for serialization.
+    Bson(BsonSerializer),
+    /// Uses a `CborSerializer` for serialization.
+    Cbor(CborSerializer),
+    /// Uses a `CefSerializer` for serialization.
+    Cef(CefSerializer),
     /// Uses a `JsonSerializer` for serialization.
     Json(JsonSerializer),
+    /// Uses a `LeefSerializer` for serialization.
+    Leef(LeefSerializer),
     /// Uses a `LogfmtSerializer` for serialization.
     Logfmt(LogfmtSerializer),
     /// Uses a `NativeSerializer` for serialization.
     Native(NativeSerializer),
     /// Uses a `NativeJsonSerializer` for serialization.
     NativeJson(NativeJsonSerializer),
+    /// Uses a `ProtobufSerializer` for serialization.
+    Protobuf(ProtobufSerializer),
     /// Uses a `RawMessageSerializer` for serialization.
     RawMessage(RawMessageSerializer),
     /// Uses a `TextSerializer` for serialization.
@@ -322,9 +444,14 @@ impl Serializer {
         match self {
             Serializer::Json(_) | Serializer::NativeJson(_) => true,
             Serializer::Avro(_)
+            | Serializer::Bson(_)
+            | Serializer::Cbor(_)
+            | Serializer::Cef(_)
+            | Serializer::Leef(_)
             | Serializer::Logfmt(_)
             | Serializer::Text(_)
             | Serializer::Native(_)
+            | Serializer::Protobuf(_)
             | Serializer::RawMessage(_) => false,
         }
     }
@@ -340,9 +467,14 @@ impl Serializer {
             Serializer::Json(serializer) => serializer.to_json_value(event),
             Serializer::NativeJson(serializer) => serializer.to_json_value(event),
             Serializer::Avro(_)
+            | Serializer::Bson(_)
+            | Serializer::Cbor(_)
+            | Serializer::Cef(_)
+            | Serializer::Leef(_)
             | Serializer::Logfmt(_)
             | Serializer::Text(_)
             | Serializer::Native(_)
+            | Serializer::Protobuf(_)
             | Serializer::RawMessage(_) => {
                 panic!("Serializer does not support JSON")
             }
@@ -356,12 +488,36 @@ impl From<AvroSerializer> for Serializer {
     }
 }
 
+impl From<BsonSerializer> for Serializer {
+    fn from(serializer: BsonSerializer) -> Self {
+        Self::Bson(serializer)
+    }
+}
+
+impl From<CborSerializer> for Serializer {
+    fn from(serializer: CborSerializer) -> Self {
+        Self::Cbor(serializer)
+    }
+}
+
+impl From<CefSerializer> for Serializer {
+    fn from(serializer: CefSerializer) -> Self {
+        Self::Cef(serializer)
+    }
+}
+
 impl From<JsonSerializer> for Serializer {
     fn from(serializer: JsonSerializer) -> Self {
         Self::Json(serializer)
     }
 }
 
+impl From<LeefSerializer> for Serializer {
+    fn from(serializer: LeefSerializer) -> Self {
+        Self::Leef(serializer)
+    }
+}
+
 impl From<LogfmtSerializer> for Serializer {
     fn from(serializer: LogfmtSerializer) -> Self {
         Self::Logfmt(serializer)
@@ -380,6 +536,12 @@ impl From<NativeJsonSerializer> for Serializer {
     }
 }
 
+impl From<ProtobufSerializer> for Serializer {
+    fn from(serializer: ProtobufSerializer) -> Self {
+        Self::Protobuf(serializer)
+    }
+}
+
 impl From<RawMessageSerializer> for Serializer {
     fn from(serializer: RawMessageSerializer) -> Self {
         Self::RawMessage(serializer)
@@ -398,10 +560,15 @@ impl tokio_util::codec::Encoder<Event> for Serializer {
     fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
         match self {
             Serializer::Avro(serializer) => serializer.encode(event, buffer),
+            Serializer::Bson(serializer) => serializer.encode(event, buffer),
+            Serializer::Cbor(serializer) => serializer.encode(event, buffer),
+            Serializer::Cef(serializer) => serializer.encode(event, buffer),
             Serializer::Json(serializer) => serializer.encode(event, buffer),
+            Serializer::Leef(serializer) => serializer.encode(event, buffer),
             Serializer::Logfmt(serializer) => serializer.encode(event, buffer),
             Serializer::Native(serializer) => serializer.encode(event, buffer),
             Serializer::NativeJson(serializer) => serializer.encode(event, buffer),
+            Serializer::Protobuf(serializer) => serializer.encode(event, buffer),
             Serializer::RawMessage(serializer) => serializer.encode(event, buffer),
             Serializer::Text(serializer) => serializer.encode(event, buffer),
         }
@@ -8,20 +8,28 @@ pub mod decoding;
 pub mod encoding;
 
 pub use decoding::{
-    BytesDecoder, BytesDecoderConfig, BytesDeserializer, BytesDeserializerConfig,
-    CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig, JsonDeserializer,
-    JsonDeserializerConfig, LengthDelimitedDecoder, LengthDelimitedDecoderConfig,
-    NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
-    NativeJsonDeserializerConfig, NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig,
-    OctetCountingDecoder, OctetCountingDecoderConfig, StreamDecodingError,
+    AccessLogDeserializer, AccessLogDeserializerConfig, AvroDeserializer, AvroDeserializerConfig,
+    BsonDeserializer, BsonDeserializerConfig, BytesDecoder, BytesDecoderConfig, BytesDeserializer,
+    BytesDeserializerConfig, CborDeserializer, CborDeserializerConfig, CefDeserializer,
+    CefDeserializerConfig, CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig,
+    CsvDeserializer, CsvDeserializerConfig, JsonDeserializer, JsonDeserializerConfig,
+    LeefDeserializer, LeefDeserializerConfig, LengthDelimitedDecoder, LengthDelimitedDecoderConfig,
+    MultilineDecoder, MultilineDecoderConfig, NativeDeserializer, NativeDeserializerConfig,
+    NativeJsonDeserializer, NativeJsonDeserializerConfig, NewlineDelimitedDecoder,
+    NewlineDelimitedDecoderConfig, OctetCountingDecoder, OctetCountingDecoderConfig,
+    StreamDecodingError, VarintLengthDelimitedDecoder, VarintLengthDelimitedDecoderConfig,
+    W3cDeserializer, W3cDeserializerConfig,
 };
 #[cfg(feature = "syslog")]
 pub use decoding::{SyslogDeserializer, SyslogDeserializerConfig};
 pub use encoding::{
-    BytesEncoder, BytesEncoderConfig, CharacterDelimitedEncoder, CharacterDelimitedEncoderConfig,
-    JsonSerializer, JsonSerializerConfig, LengthDelimitedEncoder, LengthDelimitedEncoderConfig,
-    LogfmtSerializer, LogfmtSerializerConfig, NativeJsonSerializer, NativeJsonSerializerConfig,
-    NativeSerializer, NativeSerializerConfig, NewlineDelimitedEncoder,
-    NewlineDelimitedEncoderConfig, RawMessageSerializer, RawMessageSerializerConfig,
-    TextSerializer, TextSerializerConfig,
+    BsonSerializer, BsonSerializerConfig, BytesEncoder, BytesEncoderConfig, CborSerializer,
+    CborSerializerConfig, CefSerializer, CefSerializerConfig, CharacterDelimitedEncoder,
+    CharacterDelimitedEncoderConfig, JsonSerializer, JsonSerializerConfig, LeefSerializer,
+    LeefSerializerConfig, LengthDelimitedEncoder, LengthDelimitedEncoderConfig, LogfmtSerializer,
+    LogfmtSerializerConfig, NativeJsonSerializer, NativeJsonSerializerConfig, NativeSerializer,
+    NativeSerializerConfig, NewlineDelimitedEncoder, NewlineDelimitedEncoderConfig,
+    ProtobufSerializer, ProtobufSerializerConfig, RawMessageSerializer, RawMessageSerializerConfig,
+    TextSerializer, TextSerializerConfig, VarintLengthDelimitedEncoder,
+    VarintLengthDelimitedEncoderConfig,
 };
@@ -1,6 +1,7 @@
 use std::{
     collections::BTreeSet,
-    fs, io,
+    fs,
+    io::{self, Write},
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
@@ -40,6 +41,15 @@ struct Checkpoint {
     modified: DateTime<Utc>,
 }
 
+/// A store the checkpoints are written through to, besides the checkpoints file of the data
+/// directory, such as one outliving the host.
+pub trait CheckpointsStore: Send + Sync {
+    /// Replaces the checkpoints in the store with `data`, in the format of the checkpoints file.
+    ///
+    /// This is called from blocking threads.
+    fn save(&self, data: Vec<u8>) -> io::Result<()>;
+}
+
 pub struct Checkpointer {
     directory: PathBuf,
     tmp_file_path: PathBuf,
@@ -47,6 +57,7 @@ pub struct Checkpointer {
     glob_string: String,
     checkpoints: Arc<CheckpointsView>,
     last: Mutex<Option<State>>,
+    store: Option<Box<dyn CheckpointsStore>>,
 }
 
 /// A thread-safe handle for reading and writing checkpoints in-memory across
@@ -205,9 +216,16 @@ impl Checkpointer {
             stable_file_path,
             checkpoints: Arc::new(CheckpointsView::default()),
             last: Mutex::new(None),
+            store: None,
         }
     }
 
+    /// Writes the checkpoints through to `store` as well, whenever they are persisted.
+    pub fn with_store(mut self, store: Box<dyn CheckpointsStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     pub fn view(&self) -> Arc<CheckpointsView> {
         Arc::clone(&self.checkpoints)
     }
@@ -305,9 +323,10 @@ impl Checkpointer {
             // disk. If vector dies anywhere during this section, the existing
             // stable file will still be in its current valid state and we'll be
             // able to recover.
-            let mut f = io::BufWriter::new(fs::File::create(&self.tmp_file_path)?);
-            serde_json::to_writer(&mut f, &current)?;
-            f.into_inner()?.sync_all()?;
+            let data = serde_json::to_vec(&current)?;
+            let mut f = fs::File::create(&self.tmp_file_path)?;
+            f.write_all(&data)?;
+            f.sync_all()?;
 
             // Once the temp file is fully flushed, rename the tmp file to replace
             // the previous stable file. This is an atomic operation on POSIX
@@ -316,6 +335,11 @@ impl Checkpointer {
             // one full valid file to recover from.
             fs::rename(&self.tmp_file_path, &self.stable_file_path)?;
 
+            // Until the store has them, the checkpoints are written again on the next attempt.
+            if let Some(store) = &self.store {
+                store.save(data)?;
+            }
+
             *last = Some(current);
         }
 
@@ -419,16 +443,37 @@ impl Checkpointer {
 
 #[cfg(test)]
 mod test {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
     use chrono::{Duration, Utc};
     use pretty_assertions::assert_eq;
     use tempfile::tempdir;
 
     use super::{
         super::{FingerprintStrategy, Fingerprinter},
-        Checkpoint, Checkpointer, FileFingerprint, FilePosition, CHECKPOINT_FILE_NAME,
-        TMP_FILE_NAME,
+        Checkpoint, Checkpointer, CheckpointsStore, FileFingerprint, FilePosition,
+        CHECKPOINT_FILE_NAME, TMP_FILE_NAME,
     };
 
+    #[derive(Clone, Default)]
+    struct MemoryStore {
+        saved: Arc<Mutex<Vec<Vec<u8>>>>,
+        failing: Arc<Mutex<bool>>,
+    }
+
+    impl CheckpointsStore for MemoryStore {
+        fn save(&self, data: Vec<u8>) -> io::Result<()> {
+            if *self.failing.lock().unwrap() {
+                return Err(io::Error::new(io::ErrorKind::Other, "unavailable"));
+            }
+            self.saved.lock().unwrap().push(data);
+            Ok(())
+        }
+    }
+
     #[test]
     fn test_checkpointer_basics() {
         let fingerprints = vec![
@@ -526,6 +571,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpointer_writes_through_store() {
+        let fingerprint = FileFingerprint::DevInode(1, 2);
+        let data_dir = tempdir().unwrap();
+        let store = MemoryStore::default();
+        let mut chkptr = Checkpointer::new(data_dir.path()).with_store(Box::new(store.clone()));
+
+        chkptr.update_checkpoint(fingerprint, 1234);
+        chkptr.write_checkpoints().unwrap();
+        let saved = store.saved.lock().unwrap().clone();
+        assert_eq!(
+            saved,
+            vec![std::fs::read(data_dir.path().join(CHECKPOINT_FILE_NAME)).unwrap()]
+        );
+
+        // Unchanged checkpoints are not written again.
+        chkptr.write_checkpoints().unwrap();
+        assert_eq!(store.saved.lock().unwrap().len(), 1);
+
+        // Checkpoints the store failed to save are written again on the next attempt.
+        chkptr.update_checkpoint(fingerprint, 5678);
+        *store.failing.lock().unwrap() = true;
+        assert!(chkptr.write_checkpoints().is_err());
+        *store.failing.lock().unwrap() = false;
+        chkptr.write_checkpoints().unwrap();
+        assert_eq!(store.saved.lock().unwrap().len(), 2);
+    }
+
     #[test]
     fn test_checkpointer_fingerprint_upgrades_unknown() {
         let log_dir = tempdir().unwrap();
@@ -15,21 +15,38 @@ use futures::{
 };
 use indexmap::IndexMap;
 use tokio::time::sleep;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
+#[cfg(target_os = "linux")]
+use crate::uring::Ring;
 use crate::{
     checkpointer::{Checkpointer, CheckpointsView},
     file_watcher::FileWatcher,
     fingerprinter::{FileFingerprint, Fingerprinter},
+    notifier::Notifier,
     paths_provider::PathsProvider,
     FileSourceInternalEvents, ReadFrom,
 };
 
+/// Files are only read through an io_uring on Linux.
+#[cfg(not(target_os = "linux"))]
+enum Ring {}
+
+/// The number of reads submitted at once to the io_uring.
+#[cfg(target_os = "linux")]
+const IO_URING_ENTRIES: u32 = 32;
+/// The least size of the buffers registered with the io_uring.
+#[cfg(target_os = "linux")]
+const IO_URING_MIN_BUFFER_BYTES: usize = 64 * 1024;
+
 /// `FileServer` is a Source which cooperatively schedules reads over files,
 /// converting the lines of said files into `LogLine` structures. As
 /// `FileServer` is intended to be useful across multiple operating systems with
-/// POSIX filesystem semantics `FileServer` must poll for changes. That is, no
-/// event notification is used by `FileServer`.
+/// POSIX filesystem semantics `FileServer` polls for changes by default. With
+/// `use_notifications`, files are instead read when the platform notifies
+/// their changes, and only polled along with the search for new files. With
+/// `use_io_uring`, on Linux, the files to read and to fingerprint are read in
+/// batches submitted to an io_uring, into buffers registered with it.
 ///
 /// `FileServer` is configured on a path to watch. The files do _not_ need to
 /// exist at startup. `FileServer` will discover new files which match
@@ -50,6 +67,8 @@ where
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
+    pub use_notifications: bool,
+    pub use_io_uring: bool,
     pub emitter: E,
     pub handle: tokio::runtime::Handle,
 }
@@ -101,17 +120,32 @@ where
 
         let mut known_small_files = HashSet::new();
 
-        let mut existing_files = Vec::new();
-        for path in self.paths_provider.paths().into_iter() {
-            if let Some(file_id) = self.fingerprinter.get_fingerprint_or_log_error(
-                &path,
-                &mut fingerprint_buffer,
-                &mut known_small_files,
-                &self.emitter,
-            ) {
-                existing_files.push((path, file_id));
+        let mut notifier = if self.use_notifications {
+            match Notifier::new() {
+                Ok(notifier) => Some(notifier),
+                Err(error) => {
+                    warn!(
+                        message = "Failed to set up file notifications, polling files instead.",
+                        %error,
+                    );
+                    None
+                }
             }
-        }
+        } else {
+            None
+        };
+        // Files which changed according to notifications, and haven't been
+        // entirely read yet.
+        let mut changed_files = HashSet::new();
+
+        let mut ring = self.setup_io_uring();
+
+        let mut existing_files = self.get_fingerprints(
+            self.paths_provider.paths(),
+            &mut ring,
+            &mut fingerprint_buffer,
+            &mut known_small_files,
+        );
 
         existing_files.sort_by_key(|(path, _file_id)| {
             fs::metadata(&path)
@@ -130,7 +164,7 @@ where
                 &mut fingerprint_buffer,
             );
 
-            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, true);
+            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, &ring, true);
         }
         self.emitter.emit_files_open(fp_map.len());
 
@@ -157,7 +191,8 @@ where
         loop {
             // Glob find files to follow, but not too often.
             let now_time = time::Instant::now();
-            if next_glob_time <= now_time {
+            let discovering = next_glob_time <= now_time;
+            if discovering {
                 // Schedule the next glob time.
                 next_glob_time = now_time.checked_add(self.glob_minimum_cooldown).unwrap();
 
@@ -174,56 +209,57 @@ where
                 for (_file_id, watcher) in &mut fp_map {
                     watcher.set_file_findable(false); // assume not findable until found
                 }
-                for path in self.paths_provider.paths().into_iter() {
-                    if let Some(file_id) = self.fingerprinter.get_fingerprint_or_log_error(
-                        &path,
-                        &mut fingerprint_buffer,
-                        &mut known_small_files,
-                        &self.emitter,
-                    ) {
-                        if let Some(watcher) = fp_map.get_mut(&file_id) {
-                            // file fingerprint matches a watched file
-                            let was_found_this_cycle = watcher.file_findable();
-                            watcher.set_file_findable(true);
-                            if watcher.path == path {
-                                trace!(
-                                    message = "Continue watching file.",
-                                    path = ?path,
-                                );
-                            } else if !was_found_this_cycle {
-                                // matches a file with a different path
-                                info!(
-                                    message = "Watched file has been renamed.",
-                                    path = ?path,
-                                    old_path = ?watcher.path
-                                );
-                                watcher.update_path(path).ok(); // ok if this fails: might fix next cycle
-                            } else {
-                                info!(
-                                    message = "More than one file has the same fingerprint.",
-                                    path = ?path,
-                                    old_path = ?watcher.path
-                                );
-                                let (old_path, new_path) = (&watcher.path, &path);
-                                if let (Ok(old_modified_time), Ok(new_modified_time)) = (
-                                    fs::metadata(&old_path).and_then(|m| m.modified()),
-                                    fs::metadata(&new_path).and_then(|m| m.modified()),
-                                ) {
-                                    if old_modified_time < new_modified_time {
-                                        info!(
-                                            message = "Switching to watch most recently modified file.",
-                                            new_modified_time = ?new_modified_time,
-                                            old_modified_time = ?old_modified_time,
-                                        );
-                                        watcher.update_path(path).ok(); // ok if this fails: might fix next cycle
-                                    }
+                let paths = self.get_fingerprints(
+                    self.paths_provider.paths(),
+                    &mut ring,
+                    &mut fingerprint_buffer,
+                    &mut known_small_files,
+                );
+                for (path, file_id) in paths {
+                    if let Some(watcher) = fp_map.get_mut(&file_id) {
+                        // file fingerprint matches a watched file
+                        let was_found_this_cycle = watcher.file_findable();
+                        watcher.set_file_findable(true);
+                        if watcher.path == path {
+                            trace!(
+                                message = "Continue watching file.",
+                                path = ?path,
+                            );
+                        } else if !was_found_this_cycle {
+                            // matches a file with a different path
+                            info!(
+                                message = "Watched file has been renamed.",
+                                path = ?path,
+                                old_path = ?watcher.path
+                            );
+                            watcher.update_path(path).ok(); // ok if this fails: might fix next cycle
+                            read_positioned(watcher, &ring);
+                        } else {
+                            info!(
+                                message = "More than one file has the same fingerprint.",
+                                path = ?path,
+                                old_path = ?watcher.path
+                            );
+                            let (old_path, new_path) = (&watcher.path, &path);
+                            if let (Ok(old_modified_time), Ok(new_modified_time)) = (
+                                fs::metadata(&old_path).and_then(|m| m.modified()),
+                                fs::metadata(&new_path).and_then(|m| m.modified()),
+                            ) {
+                                if old_modified_time < new_modified_time {
+                                    info!(
+                                        message = "Switching to watch most recently modified file.",
+                                        new_modified_time = ?new_modified_time,
+                                        old_modified_time = ?old_modified_time,
+                                    );
+                                    watcher.update_path(path).ok(); // ok if this fails: might fix next cycle
+                                    read_positioned(watcher, &ring);
                                 }
                             }
-                        } else {
-                            // untracked file fingerprint
-                            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, false);
-                            self.emitter.emit_files_open(fp_map.len());
                         }
+                    } else {
+                        // untracked file fingerprint
+                        self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, &ring, false);
+                        self.emitter.emit_files_open(fp_map.len());
                     }
                 }
                 stats.record("discovery", start.elapsed());
@@ -232,8 +268,42 @@ where
             // Collect lines by polling files.
             let mut global_bytes_read: usize = 0;
             let mut maxed_out_reading_single_file = false;
+            let mut unfinished_files = HashSet::new();
+            if let Some(notifier) = &mut notifier {
+                changed_files.extend(notifier.take_changed());
+            }
+            // The buffers of the files to read are filled at once, with reads
+            // submitted together to the io_uring.
+            #[cfg(target_os = "linux")]
+            if let Some(ring) = &mut ring {
+                let start = time::Instant::now();
+                let (reads, mut watchers): (Vec<_>, Vec<_>) = fp_map
+                    .values_mut()
+                    .filter(|watcher| {
+                        let changed = changed_files.contains(&watcher.path);
+                        let notified = notifier
+                            .as_mut()
+                            .map_or(false, |notifier| notifier.watch(&watcher.path));
+                        !(notified && !changed && !discovering)
+                            && (changed || watcher.should_read())
+                    })
+                    .filter_map(|watcher| watcher.pending_read().map(|read| (read, watcher)))
+                    .unzip();
+                ring.read_all(&reads, |index, result| watchers[index].fill(result));
+                stats.record("reading", start.elapsed());
+            }
             for (&file_id, watcher) in &mut fp_map {
-                if !watcher.should_read() {
+                // Files whose changes are notified are only read when they
+                // changed, or along with the search for new files, in case
+                // some changes weren't notified.
+                let changed = changed_files.remove(&watcher.path);
+                let notified = notifier
+                    .as_mut()
+                    .map_or(false, |notifier| notifier.watch(&watcher.path));
+                if notified && !changed && !discovering {
+                    continue;
+                }
+                if !changed && !watcher.should_read() {
                     continue;
                 }
 
@@ -259,11 +329,16 @@ where
 
                     if bytes_read > self.max_read_bytes {
                         maxed_out_reading_single_file = true;
+                        unfinished_files.insert(watcher.path.clone());
                         break;
                     }
                 }
                 stats.record("reading", start.elapsed());
 
+                if watcher.dead() && !watcher.rotated() {
+                    self.resume_from_rotated_copy(file_id, watcher, &mut fingerprint_buffer);
+                }
+
                 if bytes_read > 0 {
                     global_bytes_read = global_bytes_read.saturating_add(bytes_read);
                 } else {
@@ -291,6 +366,20 @@ where
                 }
             }
 
+            // Changes to the files which weren't read, because older files were
+            // read first, are kept, while those to files which aren't watched
+            // are dropped.
+            if self.oldest_first && maxed_out_reading_single_file {
+                let watched_paths = fp_map
+                    .values()
+                    .map(|watcher| &watcher.path)
+                    .collect::<HashSet<_>>();
+                changed_files.retain(|path| watched_paths.contains(path));
+            } else {
+                changed_files.clear();
+            }
+            changed_files.extend(unfinished_files);
+
             // A FileWatcher is dead when the underlying file has disappeared.
             // If the FileWatcher is dead we don't retain it; it will be deallocated.
             fp_map.retain(|file_id, watcher| {
@@ -333,9 +422,17 @@ where
             // call. Also since we are using block_on here and in the above code,
             // this should be run in its own thread. `spawn_blocking` fulfills
             // all of these requirements.
+            let notified = notifier.as_mut();
             let sleep = async move {
                 if backoff > 0 {
-                    sleep(Duration::from_millis(backoff as u64)).await;
+                    let delay = sleep(Duration::from_millis(backoff as u64));
+                    match notified {
+                        Some(notifier) => tokio::select! {
+                            _ = delay => {},
+                            _ = notifier.wait() => {},
+                        },
+                        None => delay.await,
+                    }
                 }
             };
             futures::pin_mut!(sleep);
@@ -356,12 +453,131 @@ where
         }
     }
 
+    /// Looks for a copy of a file which disappeared, or was truncated, before it was entirely
+    /// read, as left by a rotation which may also have compressed it, in order to read the rest
+    /// of the file from it. Such copies are in the same directory, and their name starts with the
+    /// name of the file, like `app.log.1` or `app.log.1.gz` for `app.log`.
+    fn resume_from_rotated_copy(
+        &self,
+        file_id: FileFingerprint,
+        watcher: &mut FileWatcher,
+        fingerprint_buffer: &mut Vec<u8>,
+    ) {
+        let (directory, name) = match (watcher.path.parent(), watcher.path.file_name()) {
+            (Some(directory), Some(name)) => (directory, name.to_string_lossy().into_owned()),
+            _ => return,
+        };
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let rotated = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                *path != watcher.path
+                    && path.file_name().map_or(false, |file_name| {
+                        file_name.to_string_lossy().starts_with(&name)
+                    })
+            })
+            .find(|path| {
+                self.fingerprinter
+                    .get_fingerprint_of_decompressed_file(path, fingerprint_buffer)
+                    .ok()
+                    == Some(file_id)
+            });
+
+        if let Some(path) = rotated {
+            let old_path = watcher.path.clone();
+            match watcher.resume_from_rotated(path.clone()) {
+                Ok(()) => info!(
+                    message = "Reading the rest of watched file from its rotated copy.",
+                    path = ?path,
+                    old_path = ?old_path,
+                ),
+                Err(error) => debug!(
+                    message = "Failed to read rotated copy of watched file.",
+                    path = ?path,
+                    old_path = ?old_path,
+                    %error,
+                ),
+            }
+        }
+    }
+
+    /// Sets up the io_uring through which files are read, if it's enabled and
+    /// supported.
+    fn setup_io_uring(&self) -> Option<Ring> {
+        if !self.use_io_uring {
+            return None;
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let buffer_size = cmp::max(self.max_line_bytes, IO_URING_MIN_BUFFER_BYTES);
+            match Ring::new(IO_URING_ENTRIES, buffer_size) {
+                Ok(ring) => Some(ring),
+                Err(error) => {
+                    warn!(
+                        message = "Failed to set up io_uring, reading files with system calls instead.",
+                        %error,
+                    );
+                    None
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            warn!(
+                message =
+                    "io_uring is only supported on Linux, reading files with system calls instead."
+            );
+            None
+        }
+    }
+
+    /// Fingerprints the files at `paths`, in batches submitted to the io_uring
+    /// if there's one, skipping those which can't be fingerprinted.
+    fn get_fingerprints(
+        &self,
+        paths: Vec<PathBuf>,
+        ring: &mut Option<Ring>,
+        fingerprint_buffer: &mut Vec<u8>,
+        known_small_files: &mut HashSet<PathBuf>,
+    ) -> Vec<(PathBuf, FileFingerprint)> {
+        #[cfg(target_os = "linux")]
+        if let Some(ring) = ring {
+            return self.fingerprinter.get_fingerprints_or_log_errors(
+                paths,
+                ring,
+                fingerprint_buffer,
+                known_small_files,
+                &self.emitter,
+            );
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = ring;
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                self.fingerprinter
+                    .get_fingerprint_or_log_error(
+                        &path,
+                        fingerprint_buffer,
+                        known_small_files,
+                        &self.emitter,
+                    )
+                    .map(|file_id| (path, file_id))
+            })
+            .collect()
+    }
+
     fn watch_new_file(
         &self,
         path: PathBuf,
         file_id: FileFingerprint,
         fp_map: &mut IndexMap<FileFingerprint, FileWatcher>,
         checkpoints: &CheckpointsView,
+        ring: &Option<Ring>,
         startup: bool,
     ) {
         // Determine the initial _requested_ starting point in the file. This can be overridden
@@ -404,6 +620,7 @@ where
                     self.emitter.emit_file_added(&path);
                 }
                 watcher.set_file_findable(true);
+                read_positioned(&mut watcher, ring);
                 fp_map.insert(file_id, watcher);
             }
             Err(error) => self.emitter.emit_file_watch_error(&path, error),
@@ -411,6 +628,23 @@ where
     }
 }
 
+/// Reads the file of the `watcher` at its position if files are read through
+/// the io_uring, so that its reads can be batched.
+fn read_positioned(watcher: &mut FileWatcher, ring: &Option<Ring>) {
+    #[cfg(target_os = "linux")]
+    if let Some(ring) = ring {
+        if let Err(error) = watcher.read_positioned(ring.buffer_size()) {
+            debug!(
+                message = "Failed to reopen file for batched reads.",
+                path = ?watcher.path,
+                %error,
+            );
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (watcher, ring);
+}
+
 async fn checkpoint_writer(
     checkpointer: Checkpointer,
     sleep_duration: Duration,
@@ -1,6 +1,6 @@
 use std::{
     fs::{self, File},
-    io::{self, BufRead, Seek},
+    io::{self, BufRead, Read, Seek},
     path::PathBuf,
     time::{Duration, Instant},
 };
@@ -10,9 +10,14 @@ use chrono::{DateTime, Utc};
 use flate2::bufread::MultiGzDecoder;
 use tracing::debug;
 
+#[cfg(target_os = "linux")]
+use crate::uring::ReadAt;
 use crate::{
     buffer::read_until_with_max_size, metadata_ext::PortableFileExt, FilePosition, ReadFrom,
 };
+
+#[cfg(target_os = "linux")]
+mod positioned;
 #[cfg(test)]
 mod tests;
 
@@ -26,7 +31,10 @@ mod tests;
 pub struct FileWatcher {
     pub path: PathBuf,
     findable: bool,
-    reader: Box<dyn BufRead>,
+    reader: Reader,
+    /// Whether the file is read as it is, rather than decompressed or not at all.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    plain: bool,
     file_position: FilePosition,
     devno: u64,
     inode: u64,
@@ -36,6 +44,7 @@ pub struct FileWatcher {
     max_line_bytes: usize,
     line_delimiter: Bytes,
     buf: BytesMut,
+    rotated: bool,
 }
 
 impl FileWatcher {
@@ -127,7 +136,8 @@ impl FileWatcher {
         Ok(FileWatcher {
             path,
             findable: true,
-            reader,
+            reader: Reader::Buffered(reader),
+            plain: !gzipped,
             file_position,
             devno,
             inode: ino,
@@ -137,6 +147,7 @@ impl FileWatcher {
             max_line_bytes,
             line_delimiter,
             buf: BytesMut::new(),
+            rotated: false,
         })
     }
 
@@ -155,7 +166,8 @@ impl FileWatcher {
                 reader.seek(io::SeekFrom::Start(self.file_position))?;
                 Box::new(reader)
             };
-            self.reader = new_reader;
+            self.reader = Reader::Buffered(new_reader);
+            self.plain = !gzipped;
             self.devno = file_handle.portable_dev()?;
             self.inode = file_handle.portable_ino()?;
         }
@@ -163,6 +175,79 @@ impl FileWatcher {
         Ok(())
     }
 
+    /// Continues reading from a copy of the file left by a rotation, which may have been
+    /// compressed, skipping its content which was already read.
+    ///
+    /// This recovers the end of a file which was truncated or removed before we could read all
+    /// of it, as happens when rotating with `copytruncate` and compressing races the reads.
+    pub fn resume_from_rotated(&mut self, path: PathBuf) -> io::Result<()> {
+        let mut file = io::BufReader::new(fs::File::open(&path)?);
+        let reader = if is_gzipped(&mut file)? || is_zstd(&mut file)? {
+            let mut reader = decompressed_reader(file)?;
+            let skipped = io::copy(&mut (&mut reader).take(self.file_position), &mut io::sink())?;
+            if skipped < self.file_position {
+                return Err(rotated_file_too_short());
+            }
+            reader
+        } else {
+            if file.seek(io::SeekFrom::End(0))? < self.file_position {
+                return Err(rotated_file_too_short());
+            }
+            file.seek(io::SeekFrom::Start(self.file_position))?;
+            Box::new(file)
+        };
+
+        self.reader = Reader::Buffered(reader);
+        self.plain = false;
+        self.path = path;
+        self.is_dead = false;
+        self.rotated = true;
+        Ok(())
+    }
+
+    /// Reads the file at its position, so that its buffer can be filled along with those of other
+    /// files by [`Self::pending_read`] and [`Self::fill`], unless it's compressed.
+    ///
+    /// This must be called before the file is read, or right after its path was updated.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn read_positioned(&mut self, buffer_size: usize) -> io::Result<()> {
+        if self.plain && matches!(self.reader, Reader::Buffered(_)) {
+            let file = File::open(&self.path)?;
+            if (file.portable_dev()?, file.portable_ino()?) == (self.devno, self.inode) {
+                self.reader = Reader::Positioned(positioned::PositionedReader::new(
+                    file,
+                    self.file_position,
+                    buffer_size,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the read filling the buffer of the file, when it's read at its position and its
+    /// buffer is empty.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn pending_read(&self) -> Option<ReadAt> {
+        match &self.reader {
+            Reader::Positioned(reader) => reader.pending_read(),
+            Reader::Buffered(_) => None,
+        }
+    }
+
+    /// Fills the buffer of the file with the result of its pending read. If it failed, the read is
+    /// made again when reading the file.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn fill(&mut self, result: io::Result<&[u8]>) {
+        if let (Reader::Positioned(reader), Ok(data)) = (&mut self.reader, result) {
+            reader.fill(data);
+        }
+    }
+
+    /// Whether the file is read from a copy left by a rotation.
+    pub fn rotated(&self) -> bool {
+        self.rotated
+    }
+
     pub fn set_file_findable(&mut self, f: bool) {
         self.findable = f;
     }
@@ -252,6 +337,41 @@ impl FileWatcher {
     }
 }
 
+/// The reader of a watched file.
+enum Reader {
+    Buffered(Box<dyn BufRead>),
+    #[cfg(target_os = "linux")]
+    Positioned(positioned::PositionedReader),
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Reader::Buffered(reader) => reader.read(buf),
+            #[cfg(target_os = "linux")]
+            Reader::Positioned(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for Reader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            Reader::Buffered(reader) => reader.fill_buf(),
+            #[cfg(target_os = "linux")]
+            Reader::Positioned(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match self {
+            Reader::Buffered(reader) => reader.consume(amount),
+            #[cfg(target_os = "linux")]
+            Reader::Positioned(reader) => reader.consume(amount),
+        }
+    }
+}
+
 fn is_gzipped(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
     let header_bytes = r.fill_buf()?;
     // WARN: The paired `BufReader::consume` is not called intentionally. If we
@@ -259,6 +379,34 @@ fn is_gzipped(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
     Ok(header_bytes.starts_with(&[0x1f, 0x8b]))
 }
 
+fn is_zstd(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
+    let header_bytes = r.fill_buf()?;
+    Ok(header_bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]))
+}
+
+/// Wraps the reader of a gzip or zstd compressed file with a decoder, so that it reads the
+/// content of the file as it was before being compressed.
+pub(crate) fn decompressed_reader(
+    mut reader: io::BufReader<fs::File>,
+) -> io::Result<Box<dyn BufRead>> {
+    Ok(if is_gzipped(&mut reader)? {
+        Box::new(io::BufReader::new(MultiGzDecoder::new(reader)))
+    } else if is_zstd(&mut reader)? {
+        Box::new(io::BufReader::new(
+            zstd::stream::read::Decoder::with_buffer(reader)?,
+        ))
+    } else {
+        Box::new(reader)
+    })
+}
+
+fn rotated_file_too_short() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "rotated file is shorter than the read position",
+    )
+}
+
 fn null_reader() -> impl BufRead {
     io::Cursor::new(Vec::new())
 }
@@ -0,0 +1,85 @@
+use std::{
+    cmp,
+    fs::File,
+    io::{self, BufRead, Read},
+    os::unix::{fs::FileExt, io::AsRawFd},
+};
+
+use crate::uring::ReadAt;
+
+/// Reads a plain file at its position, rather than at the offset of its descriptor, so that its
+/// buffer can also be filled by a read made along with those of other files, in a batch submitted
+/// to an io_uring.
+pub(super) struct PositionedReader {
+    file: File,
+    /// The position in the file of the end of the buffer.
+    position: u64,
+    buf: Box<[u8]>,
+    start: usize,
+    end: usize,
+    /// Whether the end of the file was reached by a batched read, which is returned once rather
+    /// than read again.
+    at_end: bool,
+}
+
+impl PositionedReader {
+    pub(super) fn new(file: File, position: u64, buffer_size: usize) -> Self {
+        Self {
+            file,
+            position,
+            buf: vec![0; buffer_size].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            at_end: false,
+        }
+    }
+
+    /// Returns the read filling the buffer, unless it still holds some of the file.
+    pub(super) fn pending_read(&self) -> Option<ReadAt> {
+        (self.start == self.end && !self.at_end).then(|| ReadAt {
+            fd: self.file.as_raw_fd(),
+            offset: self.position,
+            len: self.buf.len(),
+        })
+    }
+
+    /// Fills the buffer with the bytes read by the pending read.
+    pub(super) fn fill(&mut self, data: &[u8]) {
+        let len = cmp::min(data.len(), self.buf.len());
+        self.buf[..len].copy_from_slice(&data[..len]);
+        self.position += len as u64;
+        self.start = 0;
+        self.end = len;
+        self.at_end = len == 0;
+    }
+}
+
+impl Read for PositionedReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = cmp::min(available.len(), out.len());
+        out[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for PositionedReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.start == self.end {
+            if self.at_end {
+                self.at_end = false;
+                return Ok(&[]);
+            }
+            let len = self.file.read_at(&mut self.buf, self.position)?;
+            self.position += len as u64;
+            self.start = 0;
+            self.end = len;
+        }
+        Ok(&self.buf[self.start..self.end])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.start = cmp::min(self.start + amount, self.end);
+    }
+}
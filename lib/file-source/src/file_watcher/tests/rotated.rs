@@ -0,0 +1,72 @@
+use std::{fs, io::Write};
+
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+
+use crate::{file_watcher::FileWatcher, ReadFrom};
+
+fn read_rest(watcher: &mut FileWatcher, rotated: &[u8]) -> Vec<Bytes> {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let path = dir.path().join("app.log.1");
+    fs::write(&path, rotated).expect("could not write rotated file");
+    watcher
+        .resume_from_rotated(path)
+        .expect("must be able to resume");
+    assert!(watcher.rotated());
+
+    let mut lines = Vec::new();
+    while let Some(line) = watcher.read_line().expect("must be able to read") {
+        lines.push(line);
+    }
+    lines
+}
+
+fn watcher_after_first_line(dir: &tempfile::TempDir) -> FileWatcher {
+    let path = dir.path().join("app.log");
+    fs::write(&path, b"first\nsecond\n").expect("could not write file");
+    let mut watcher = FileWatcher::new(path, ReadFrom::Beginning, None, 100_000, Bytes::from("\n"))
+        .expect("must be able to create");
+    assert_eq!(watcher.read_line().unwrap(), Some(Bytes::from("first")));
+    watcher
+}
+
+#[test]
+fn resumes_from_gzipped_rotated_file() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let mut watcher = watcher_after_first_line(&dir);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"first\nsecond\nthird\n").unwrap();
+    let lines = read_rest(&mut watcher, &encoder.finish().unwrap());
+    assert_eq!(lines, vec![Bytes::from("second"), Bytes::from("third")]);
+}
+
+#[test]
+fn resumes_from_zstd_rotated_file() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let mut watcher = watcher_after_first_line(&dir);
+
+    let compressed = zstd::encode_all(&b"first\nsecond\nthird\n"[..], 0).unwrap();
+    let lines = read_rest(&mut watcher, &compressed);
+    assert_eq!(lines, vec![Bytes::from("second"), Bytes::from("third")]);
+}
+
+#[test]
+fn resumes_from_plain_rotated_file() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let mut watcher = watcher_after_first_line(&dir);
+
+    let lines = read_rest(&mut watcher, b"first\nsecond\nthird\n");
+    assert_eq!(lines, vec![Bytes::from("second"), Bytes::from("third")]);
+}
+
+#[test]
+fn rejects_shorter_rotated_file() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let mut watcher = watcher_after_first_line(&dir);
+
+    let path = dir.path().join("app.log.1");
+    fs::write(&path, b"fir").unwrap();
+    assert!(watcher.resume_from_rotated(path).is_err());
+    assert!(!watcher.rotated());
+}
@@ -5,10 +5,17 @@ use std::{
     path::{Path, PathBuf},
 };
 
+#[cfg(target_os = "linux")]
+use std::os::unix::io::AsRawFd;
+
 use crc::Crc;
 use serde::{Deserialize, Serialize};
 
-use crate::{metadata_ext::PortableFileExt, FileSourceInternalEvents};
+#[cfg(target_os = "linux")]
+use crate::uring::{ReadAt, Ring};
+use crate::{
+    file_watcher::decompressed_reader, metadata_ext::PortableFileExt, FileSourceInternalEvents,
+};
 
 const FINGERPRINT_CRC: Crc<u64> = Crc::<u64>::new(&crc::CRC_64_ECMA_182);
 const LEGACY_FINGERPRINT_CRC: Crc<u64> = Crc::<u64>::new(&crc::CRC_64_XZ);
@@ -103,6 +110,40 @@ impl Fingerprinter {
         }
     }
 
+    /// Computes the fingerprint a gzip or zstd compressed file had before being compressed, which
+    /// is the fingerprint of the file itself if it isn't compressed.
+    ///
+    /// The content of files fingerprinted by their device and inode doesn't matter, so compressed
+    /// copies of such files never have the fingerprint of the original.
+    pub fn get_fingerprint_of_decompressed_file(
+        &self,
+        path: &Path,
+        buffer: &mut Vec<u8>,
+    ) -> Result<FileFingerprint, io::Error> {
+        match self.strategy {
+            FingerprintStrategy::DevInode => self.get_fingerprint_of_file(path, buffer),
+            FingerprintStrategy::Checksum {
+                ignored_header_bytes,
+                bytes: _,
+                lines,
+            }
+            | FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes,
+                lines,
+            } => {
+                buffer.resize(self.max_line_length, 0u8);
+                let mut reader = decompressed_reader(io::BufReader::new(File::open(path)?))?;
+                io::copy(
+                    &mut (&mut reader).take(ignored_header_bytes as u64),
+                    &mut io::sink(),
+                )?;
+                fingerprinter_read_until(reader, b'\n', lines, buffer)?;
+                let fingerprint = FINGERPRINT_CRC.checksum(&buffer[..]);
+                Ok(FileFingerprint::FirstLinesChecksum(fingerprint))
+            }
+        }
+    }
+
     pub fn get_fingerprint_or_log_error(
         &self,
         path: &Path,
@@ -118,24 +159,142 @@ impl Fingerprinter {
                     self.get_fingerprint_of_file(path, buffer).map(Some)
                 }
             })
-            .map_err(|error| match error.kind() {
-                io::ErrorKind::UnexpectedEof => {
-                    if !known_small_files.contains(path) {
-                        emitter.emit_file_checksum_failed(path);
-                        known_small_files.insert(path.to_path_buf());
-                    }
+            .map_err(|error| self.log_error(path, error, known_small_files, emitter))
+            .ok()
+            .flatten()
+    }
+
+    /// Computes the fingerprints of many files like [`Self::get_fingerprint_or_log_error`], except
+    /// that the beginning of the files is read with `ring`, in batches, rather than one file after
+    /// the other. Files which can't be fingerprinted are left out.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn get_fingerprints_or_log_errors(
+        &self,
+        paths: Vec<PathBuf>,
+        ring: &mut Ring,
+        buffer: &mut Vec<u8>,
+        known_small_files: &mut HashSet<PathBuf>,
+        emitter: &impl FileSourceInternalEvents,
+    ) -> Vec<(PathBuf, FileFingerprint)> {
+        let (ignored_header_bytes, lines) = match self.strategy {
+            FingerprintStrategy::Checksum {
+                ignored_header_bytes,
+                bytes: _,
+                lines,
+            }
+            | FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes,
+                lines,
+            } if ring.buffer_size() >= self.max_line_length => (ignored_header_bytes, lines),
+            // Files aren't read, or their first lines don't fit in the buffers of the ring.
+            _ => {
+                return paths
+                    .into_iter()
+                    .filter_map(|path| {
+                        self.get_fingerprint_or_log_error(&path, buffer, known_small_files, emitter)
+                            .map(|file_id| (path, file_id))
+                    })
+                    .collect()
+            }
+        };
+
+        let mut files = Vec::new();
+        for path in paths {
+            let file = metadata(&path).and_then(|metadata| {
+                if metadata.is_dir() {
+                    Ok(None)
+                } else {
+                    File::open(&path).map(Some)
                 }
-                io::ErrorKind::NotFound => {
-                    if !self.ignore_not_found {
-                        emitter.emit_file_fingerprint_read_error(path, error);
+            });
+            match file {
+                Ok(Some(file)) => files.push((path, file)),
+                Ok(None) => {}
+                Err(error) => self.log_error(&path, error, known_small_files, emitter),
+            }
+        }
+
+        let reads = files
+            .iter()
+            .map(|(_, file)| ReadAt {
+                fd: file.as_raw_fd(),
+                offset: ignored_header_bytes as u64,
+                len: self.max_line_length,
+            })
+            .collect::<Vec<_>>();
+        let mut fingerprints = (0..files.len()).map(|_| None).collect::<Vec<_>>();
+        ring.read_all(&reads, |index, result| {
+            fingerprints[index] =
+                Some(result.and_then(|data| self.first_lines_checksum(data, lines, buffer)));
+        });
+
+        files
+            .into_iter()
+            .zip(fingerprints)
+            .filter_map(|((path, _file), fingerprint)| {
+                match fingerprint.expect("all files were read") {
+                    Ok(file_id) => Some((path, file_id)),
+                    Err(error) => {
+                        self.log_error(&path, error, known_small_files, emitter);
+                        None
                     }
                 }
-                _ => {
+            })
+            .collect()
+    }
+
+    /// Computes the checksum of the first `lines` lines of a file from `data`, read at once from
+    /// its beginning, like [`Self::get_fingerprint_of_file`] does from the file itself.
+    #[cfg(target_os = "linux")]
+    fn first_lines_checksum(
+        &self,
+        data: &[u8],
+        lines: usize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<FileFingerprint, io::Error> {
+        buffer.resize(self.max_line_length, 0u8);
+        let len = data.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&data[..len]);
+        let end_of_lines = data[..len]
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == b'\n')
+            .nth(lines.max(1) - 1);
+        match end_of_lines {
+            Some((pos, _)) => buffer[(pos + 1)..].fill(0),
+            // The file ended before the lines did.
+            None if len < buffer.len() => {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "EOF reached"))
+            }
+            None => {}
+        }
+        let fingerprint = FINGERPRINT_CRC.checksum(&buffer[..]);
+        Ok(FileFingerprint::FirstLinesChecksum(fingerprint))
+    }
+
+    fn log_error(
+        &self,
+        path: &Path,
+        error: io::Error,
+        known_small_files: &mut HashSet<PathBuf>,
+        emitter: &impl FileSourceInternalEvents,
+    ) {
+        match error.kind() {
+            io::ErrorKind::UnexpectedEof => {
+                if !known_small_files.contains(path) {
+                    emitter.emit_file_checksum_failed(path);
+                    known_small_files.insert(path.to_path_buf());
+                }
+            }
+            io::ErrorKind::NotFound => {
+                if !self.ignore_not_found {
                     emitter.emit_file_fingerprint_read_error(path, error);
                 }
-            })
-            .ok()
-            .flatten()
+            }
+            _ => {
+                emitter.emit_file_fingerprint_read_error(path, error);
+            }
+        }
     }
 
     pub fn get_bytes_checksum(
@@ -223,8 +382,15 @@ fn fingerprinter_read_until(
 
 #[cfg(test)]
 mod test {
-    use std::{collections::HashSet, fs, io::Error, path::Path, time::Duration};
-
+    use std::{
+        collections::HashSet,
+        fs,
+        io::{Error, Write},
+        path::Path,
+        time::Duration,
+    };
+
+    use flate2::{write::GzEncoder, Compression};
     use tempfile::tempdir;
 
     use super::{FileSourceInternalEvents, FingerprintStrategy, Fingerprinter};
@@ -402,6 +568,52 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_decompressed_file_fingerprint() {
+        let fingerprinter = Fingerprinter {
+            strategy: FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 0,
+                lines: 1,
+            },
+            max_line_length: 64,
+            ignore_not_found: false,
+        };
+
+        let target_dir = tempdir().unwrap();
+        let data = b"hello world\nthe next line\n";
+        let plain_path = target_dir.path().join("app.log");
+        let gzip_path = target_dir.path().join("app.log.1.gz");
+        let zstd_path = target_dir.path().join("app.log.1.zst");
+        fs::write(&plain_path, data).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        fs::write(&gzip_path, encoder.finish().unwrap()).unwrap();
+        fs::write(&zstd_path, zstd::encode_all(&data[..], 0).unwrap()).unwrap();
+
+        let mut buf = Vec::new();
+        let plain = fingerprinter
+            .get_fingerprint_of_file(&plain_path, &mut buf)
+            .unwrap();
+        assert_eq!(
+            fingerprinter
+                .get_fingerprint_of_decompressed_file(&plain_path, &mut buf)
+                .unwrap(),
+            plain
+        );
+        assert_eq!(
+            fingerprinter
+                .get_fingerprint_of_decompressed_file(&gzip_path, &mut buf)
+                .unwrap(),
+            plain
+        );
+        assert_eq!(
+            fingerprinter
+                .get_fingerprint_of_decompressed_file(&zstd_path, &mut buf)
+                .unwrap(),
+            plain
+        );
+    }
+
     #[test]
     fn test_inode_fingerprint() {
         let fingerprinter = Fingerprinter {
@@ -459,6 +671,63 @@ mod test {
             .is_none());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn batched_fingerprints_match() {
+        let fingerprinter = Fingerprinter {
+            strategy: FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 6,
+                lines: 2,
+            },
+            max_line_length: 64,
+            ignore_not_found: false,
+        };
+        let mut ring = match crate::uring::Ring::new(2, 64) {
+            Ok(ring) => ring,
+            // The kernel running the tests may not support io_uring.
+            Err(_) => return,
+        };
+
+        let target_dir = tempdir().unwrap();
+        let mut paths = vec![target_dir.path().to_path_buf()];
+        for (i, contents) in [
+            &b"hello world\nfrom vector\n"[..],
+            b"hello world\nfrom vector\nthe next line\n",
+            b"header line one\nline two\nline three\n",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let path = target_dir.path().join(format!("{}.log", i));
+            fs::write(&path, contents).unwrap();
+            paths.push(path);
+        }
+
+        let mut buf = Vec::new();
+        let mut small_files = HashSet::new();
+        let fingerprints = fingerprinter.get_fingerprints_or_log_errors(
+            paths.clone(),
+            &mut ring,
+            &mut buf,
+            &mut small_files,
+            &NoErrors,
+        );
+        let expected = paths[1..]
+            .iter()
+            .map(|path| {
+                (
+                    path.clone(),
+                    fingerprinter
+                        .get_fingerprint_of_file(path, &mut buf)
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fingerprints, expected);
+        assert_eq!(fingerprints[0].1, fingerprints[1].1);
+        assert_ne!(fingerprints[0].1, fingerprints[2].1);
+    }
+
     #[derive(Clone)]
     struct NoErrors;
 
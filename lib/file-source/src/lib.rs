@@ -11,10 +11,13 @@ mod file_watcher;
 mod fingerprinter;
 mod internal_events;
 mod metadata_ext;
+mod notifier;
 pub mod paths_provider;
+#[cfg(target_os = "linux")]
+mod uring;
 
 pub use self::{
-    checkpointer::{Checkpointer, CheckpointsView, CHECKPOINT_FILE_NAME},
+    checkpointer::{Checkpointer, CheckpointsStore, CheckpointsView, CHECKPOINT_FILE_NAME},
     file_server::{FileServer, Line, Shutdown as FileServerShutdown},
     fingerprinter::{FileFingerprint, FingerprintStrategy, Fingerprinter},
     internal_events::FileSourceInternalEvents,
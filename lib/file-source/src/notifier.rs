@@ -0,0 +1,123 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+};
+
+use notify::{raw_watcher, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tracing::warn;
+
+/// `Notifier` collects the paths of the files changed in the directories it
+/// watches, using the file system notifications of the platform, such as
+/// inotify or FSEvents.
+///
+/// Directories are watched rather than files, so that many files in a few
+/// directories only need a few watches.
+pub struct Notifier {
+    watcher: RecommendedWatcher,
+    events: UnboundedReceiver<PathBuf>,
+    directories: HashMap<PathBuf, bool>,
+    changed: HashSet<PathBuf>,
+}
+
+impl Notifier {
+    pub fn new() -> Result<Notifier, notify::Error> {
+        let (sender, receiver) = mpsc::channel();
+        let watcher = raw_watcher(sender)?;
+
+        // Notifications are sent to a blocking channel, so they are forwarded
+        // to one which can be awaited along with the other futures of the file
+        // server. This stops once the watcher is dropped.
+        let (events_sender, events) = unbounded_channel();
+        thread::spawn(move || {
+            for RawEvent { path, .. } in receiver {
+                if let Some(path) = path {
+                    if events_sender.send(path).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Notifier {
+            watcher,
+            events,
+            directories: HashMap::new(),
+            changed: HashSet::new(),
+        })
+    }
+
+    /// Watches the directory holding a file, unless it's already watched.
+    /// Returns whether the changes to the file are notified, which isn't the
+    /// case when its directory can't be watched.
+    pub fn watch(&mut self, path: &Path) -> bool {
+        let directory = match path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => return false,
+        };
+        if let Some(watched) = self.directories.get(directory) {
+            return *watched;
+        }
+
+        let watched = match self.watcher.watch(directory, RecursiveMode::NonRecursive) {
+            Ok(()) => true,
+            Err(error) => {
+                warn!(
+                    message = "Failed to watch directory, polling its files instead.",
+                    ?directory,
+                    %error,
+                );
+                false
+            }
+        };
+        self.directories.insert(directory.to_path_buf(), watched);
+        watched
+    }
+
+    /// Waits for a change to be notified.
+    pub async fn wait(&mut self) {
+        match self.events.recv().await {
+            Some(path) => {
+                self.changed.insert(path);
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Returns the paths of the files changed since the last call.
+    pub fn take_changed(&mut self) -> HashSet<PathBuf> {
+        while let Ok(path) = self.events.try_recv() {
+            self.changed.insert(path);
+        }
+        std::mem::take(&mut self.changed)
+    }
+}
+
+// FSEvents notifies canonical paths, which aren't those of temporary directories.
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use std::{fs, time::Duration};
+
+    use tempfile::tempdir;
+
+    use super::Notifier;
+
+    #[tokio::test]
+    async fn notifies_changed_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, b"first\n").unwrap();
+
+        let mut notifier = Notifier::new().unwrap();
+        assert!(notifier.watch(&path));
+        assert!(!notifier.watch(std::path::Path::new("relative.log")));
+
+        fs::write(&path, b"first\nsecond\n").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), notifier.wait())
+            .await
+            .expect("change must be notified");
+        assert!(notifier.take_changed().contains(&path));
+    }
+}
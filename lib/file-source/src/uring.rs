@@ -0,0 +1,470 @@
+//! A minimal io_uring, used to read many files at once with a single system call.
+//!
+//! Reads are made into buffers registered with the kernel once, so they aren't mapped again on
+//! every read, and are submitted in batches of as many reads as there are buffers.
+
+use std::{
+    io,
+    mem::size_of,
+    os::unix::io::RawFd,
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_CQ_RING: libc::off_t = 0x800_0000;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+const IORING_FEAT_SINGLE_MMAP: u32 = 1;
+const IORING_ENTER_GETEVENTS: u32 = 1;
+const IORING_REGISTER_BUFFERS: u32 = 0;
+const IORING_OP_READ_FIXED: u8 = 4;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+#[allow(dead_code)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    resv2: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+#[allow(dead_code)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    resv2: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    rw_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    pad: [u64; 2],
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+/// A memory mapping shared with the kernel, unmapped when dropped.
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: RawFd, len: usize, offset: libc::off_t) -> io::Result<Self> {
+        // SAFETY: a new mapping is created, which doesn't alias any memory of the process.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                offset,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self { ptr, len })
+        }
+    }
+
+    /// Returns a pointer to the value at `offset` in the mapping.
+    fn at<T>(&self, offset: u32) -> *mut T {
+        debug_assert!(offset as usize + size_of::<T>() <= self.len);
+        // SAFETY: the offsets are given by the kernel, and are within the mapping.
+        unsafe { self.ptr.cast::<u8>().add(offset as usize).cast() }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        // SAFETY: the mapping is no longer used once it's dropped.
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// A read of `len` bytes at `offset` in the file `fd`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ReadAt {
+    pub fd: RawFd,
+    pub offset: u64,
+    pub len: usize,
+}
+
+/// An io_uring with registered buffers, reading files in batches.
+pub(crate) struct Ring {
+    fd: RawFd,
+    // The io_uring is closed when the ring is dropped, before its mappings and buffers are.
+    sq_ring: Mmap,
+    cq_ring: Option<Mmap>,
+    sqes: Mmap,
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+    buffers: Vec<Box<[u8]>>,
+    /// Whether reads may still be in flight after failing to wait for them, in which case the
+    /// ring can't be used anymore, and its buffers are leaked rather than freed.
+    broken: bool,
+}
+
+// SAFETY: the mappings and buffers are owned by the ring, and only used through `&mut self`.
+unsafe impl Send for Ring {}
+
+impl Ring {
+    /// Sets up an io_uring with `entries` registered buffers of `buffer_size` bytes each, which
+    /// may fail on kernels without io_uring or when the limit of locked memory is too low.
+    pub(crate) fn new(entries: u32, buffer_size: usize) -> io::Result<Self> {
+        let mut params = Params::default();
+        // SAFETY: `params` is a valid `io_uring_params` structure.
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_setup,
+                entries,
+                &mut params as *mut Params,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = fd as RawFd;
+
+        let mut ring = match Self::map(fd, &params) {
+            Ok(ring) => ring,
+            Err(error) => {
+                // SAFETY: the io_uring isn't used anymore.
+                unsafe { libc::close(fd) };
+                return Err(error);
+            }
+        };
+        // The io_uring is closed along with the ring if this fails.
+        ring.register_buffers(params.sq_entries as usize, buffer_size)?;
+        Ok(ring)
+    }
+
+    fn map(fd: RawFd, params: &Params) -> io::Result<Self> {
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * size_of::<u32>();
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * size_of::<Cqe>();
+        // Recent kernels map both rings at once.
+        let single_mmap = params.features & IORING_FEAT_SINGLE_MMAP != 0;
+        let sq_ring = Mmap::new(
+            fd,
+            if single_mmap {
+                sq_len.max(cq_len)
+            } else {
+                sq_len
+            },
+            IORING_OFF_SQ_RING,
+        )?;
+        let cq_ring = if single_mmap {
+            None
+        } else {
+            Some(Mmap::new(fd, cq_len, IORING_OFF_CQ_RING)?)
+        };
+        let sqes = Mmap::new(
+            fd,
+            params.sq_entries as usize * size_of::<Sqe>(),
+            IORING_OFF_SQES,
+        )?;
+
+        Ok(Self {
+            fd,
+            sq_ring,
+            cq_ring,
+            sqes,
+            sq_off: params.sq_off,
+            cq_off: params.cq_off,
+            buffers: Vec::new(),
+            broken: false,
+        })
+    }
+
+    fn register_buffers(&mut self, count: usize, size: usize) -> io::Result<()> {
+        let buffers: Vec<Box<[u8]>> = (0..count)
+            .map(|_| vec![0; size].into_boxed_slice())
+            .collect();
+        let iovecs: Vec<libc::iovec> = buffers
+            .iter()
+            .map(|buffer| libc::iovec {
+                iov_base: buffer.as_ptr() as *mut libc::c_void,
+                iov_len: buffer.len(),
+            })
+            .collect();
+        // SAFETY: the buffers outlive the io_uring, as they're dropped after it's closed.
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_register,
+                self.fd,
+                IORING_REGISTER_BUFFERS,
+                iovecs.as_ptr(),
+                iovecs.len() as u32,
+            )
+        };
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.buffers = buffers;
+        Ok(())
+    }
+
+    /// The size of the registered buffers, which is the most that a read can return.
+    pub(crate) fn buffer_size(&self) -> usize {
+        self.buffers.first().map_or(0, |buffer| buffer.len())
+    }
+
+    /// Makes the `reads`, in batches of as many reads as there are buffers, calling `done` with
+    /// the index of each read and the bytes it read once its batch completed.
+    ///
+    /// Reads longer than the buffers are shortened to their size.
+    pub(crate) fn read_all(
+        &mut self,
+        reads: &[ReadAt],
+        mut done: impl FnMut(usize, io::Result<&[u8]>),
+    ) {
+        let batch_size = self.buffers.len();
+        for (batch, reads) in reads.chunks(batch_size.max(1)).enumerate() {
+            let first = batch * batch_size;
+            let mut fail = |error: &io::Error| {
+                for index in 0..reads.len() {
+                    done(
+                        first + index,
+                        Err(io::Error::new(error.kind(), error.to_string())),
+                    );
+                }
+            };
+            if self.broken {
+                fail(&io::Error::new(io::ErrorKind::Other, "io_uring is broken"));
+                continue;
+            }
+            if let Err(error) = self.submit(reads) {
+                // Some of the reads may have been submitted.
+                self.broken = true;
+                fail(&error);
+                continue;
+            }
+
+            let mut results: Vec<Option<i32>> = vec![None; reads.len()];
+            let mut remaining = reads.len();
+            while remaining > 0 {
+                if let Err(error) = self.enter(0, remaining as u32) {
+                    self.broken = true;
+                    fail(&error);
+                    return;
+                }
+                remaining -= self.reap(|buffer, res| results[buffer] = Some(res));
+            }
+            for (buffer, res) in results.into_iter().enumerate() {
+                let res = res.expect("all reads completed");
+                let result = if res < 0 {
+                    Err(io::Error::from_raw_os_error(-res))
+                } else {
+                    Ok(&self.buffers[buffer][..res as usize])
+                };
+                done(first + buffer, result);
+            }
+        }
+    }
+
+    /// Queues the `reads` into the buffers of the same index, and submits them.
+    fn submit(&self, reads: &[ReadAt]) -> io::Result<()> {
+        let ring = &self.sq_ring;
+        let mask = unsafe { *ring.at::<u32>(self.sq_off.ring_mask) };
+        let tail = unsafe { &*ring.at::<AtomicU32>(self.sq_off.tail) };
+        let array = ring.at::<u32>(self.sq_off.array);
+        let sqes = self.sqes.at::<Sqe>(0);
+
+        // The ring is only used by this thread, and is empty between batches.
+        let mut next = tail.load(Ordering::Relaxed);
+        for (index, read) in reads.iter().enumerate() {
+            let buffer = &self.buffers[index];
+            let slot = next & mask;
+            // SAFETY: `slot` is within the submission queue, which has an entry per buffer.
+            unsafe {
+                ptr::write(
+                    sqes.add(slot as usize),
+                    Sqe {
+                        opcode: IORING_OP_READ_FIXED,
+                        flags: 0,
+                        ioprio: 0,
+                        fd: read.fd,
+                        off: read.offset,
+                        addr: buffer.as_ptr() as u64,
+                        len: read.len.min(buffer.len()) as u32,
+                        rw_flags: 0,
+                        user_data: index as u64,
+                        buf_index: index as u16,
+                        personality: 0,
+                        splice_fd_in: 0,
+                        pad: [0; 2],
+                    },
+                );
+                *array.add(slot as usize) = slot;
+            }
+            next = next.wrapping_add(1);
+        }
+        tail.store(next, Ordering::Release);
+
+        let mut submitted = 0;
+        while submitted < reads.len() {
+            submitted += self.enter((reads.len() - submitted) as u32, 0)?;
+        }
+        Ok(())
+    }
+
+    fn enter(&self, to_submit: u32, min_complete: u32) -> io::Result<usize> {
+        loop {
+            // SAFETY: no signal mask is passed.
+            let res = unsafe {
+                libc::syscall(
+                    libc::SYS_io_uring_enter,
+                    self.fd,
+                    to_submit,
+                    min_complete,
+                    if min_complete > 0 {
+                        IORING_ENTER_GETEVENTS
+                    } else {
+                        0
+                    },
+                    ptr::null::<libc::sigset_t>(),
+                    0,
+                )
+            };
+            if res >= 0 {
+                return Ok(res as usize);
+            }
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                return Err(error);
+            }
+        }
+    }
+
+    /// Calls `done` with the buffer and result of each completed read, returning their number.
+    fn reap(&self, mut done: impl FnMut(usize, i32)) -> usize {
+        let ring = self.cq_ring.as_ref().unwrap_or(&self.sq_ring);
+        let mask = unsafe { *ring.at::<u32>(self.cq_off.ring_mask) };
+        let head = unsafe { &*ring.at::<AtomicU32>(self.cq_off.head) };
+        let tail = unsafe { &*ring.at::<AtomicU32>(self.cq_off.tail) };
+        let cqes = ring.at::<Cqe>(self.cq_off.cqes);
+
+        let mut next = head.load(Ordering::Relaxed);
+        let end = tail.load(Ordering::Acquire);
+        let mut count = 0;
+        while next != end {
+            // SAFETY: entries between the head and the tail were written by the kernel.
+            let cqe = unsafe { ptr::read(cqes.add((next & mask) as usize)) };
+            done(cqe.user_data as usize, cqe.res);
+            next = next.wrapping_add(1);
+            count += 1;
+        }
+        head.store(next, Ordering::Release);
+        count
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        if self.broken {
+            for buffer in self.buffers.drain(..) {
+                std::mem::forget(buffer);
+            }
+        }
+        // SAFETY: the io_uring isn't used anymore. Its mappings are unmapped once the fields are
+        // dropped, which the kernel allows after it's closed.
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::Write, os::unix::io::AsRawFd};
+
+    use super::*;
+
+    #[test]
+    fn reads_files_in_batches() {
+        let mut ring = match Ring::new(2, 16) {
+            Ok(ring) => ring,
+            // The kernel running the tests may not support io_uring.
+            Err(_) => return,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<File> = (0..5)
+            .map(|i| {
+                let path = dir.path().join(i.to_string());
+                File::create(&path)
+                    .unwrap()
+                    .write_all(format!("file {}\n", i).as_bytes())
+                    .unwrap();
+                File::open(path).unwrap()
+            })
+            .collect();
+        let reads: Vec<ReadAt> = files
+            .iter()
+            .map(|file| ReadAt {
+                fd: file.as_raw_fd(),
+                offset: 5,
+                len: 100,
+            })
+            .collect();
+
+        let mut results = vec![None; reads.len()];
+        ring.read_all(&reads, |index, result| {
+            results[index] = Some(result.unwrap().to_vec());
+        });
+        for (i, result) in results.into_iter().enumerate() {
+            assert_eq!(result.unwrap(), format!("{}\n", i).into_bytes());
+        }
+    }
+}
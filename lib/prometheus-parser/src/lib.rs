@@ -3,6 +3,7 @@
 use std::{collections::BTreeMap, convert::TryFrom};
 
 use indexmap::IndexMap;
+use prost::Message;
 use snafu::ResultExt;
 
 mod line;
@@ -17,6 +18,16 @@ pub mod proto {
 
     pub use metric_metadata::MetricType;
 
+    /// The protobuf exposition format of Prometheus clients.
+    pub mod client {
+        include!(concat!(env!("OUT_DIR"), "/io.prometheus.client.rs"));
+    }
+
+    /// The Remote Write 2.0 protocol, whose strings are interned in a table of symbols.
+    pub mod v2 {
+        include!(concat!(env!("OUT_DIR"), "/io.prometheus.write.v2.rs"));
+    }
+
     impl MetricType {
         pub fn as_str(&self) -> &'static str {
             match self {
@@ -59,6 +70,13 @@ pub enum ParserError {
     MultipleMetricKinds { name: String },
     #[snafu(display("request is missing metric name label"))]
     RequestNoNameLabel,
+    #[snafu(display("request references unknown symbol {}", index))]
+    RequestInvalidSymbol { index: u32 },
+    #[snafu(display("request has an odd number of label references"))]
+    RequestOddLabelReferences,
+
+    #[snafu(display("error decoding protobuf: {}", source))]
+    DecodeProtobuf { source: prost::DecodeError },
 }
 
 vector_common::impl_event_data_eq!(ParserError);
@@ -275,6 +293,207 @@ impl MetricGroup {
         }
         self.metrics.try_push(self.name.len(), metric)
     }
+
+    fn from_family(family: proto::client::MetricFamily) -> Result<Self, ParserError> {
+        use proto::client::MetricType;
+
+        let kind = match family.r#type() {
+            MetricType::Counter => MetricKind::Counter,
+            MetricType::Gauge => MetricKind::Gauge,
+            MetricType::Summary => MetricKind::Summary,
+            MetricType::Untyped => MetricKind::Untyped,
+            MetricType::Histogram | MetricType::GaugeHistogram => MetricKind::Histogram,
+        };
+        let mut metrics = GroupKind::new(kind);
+
+        for metric in family.metric {
+            let key = GroupKey {
+                timestamp: metric.timestamp_ms,
+                labels: metric
+                    .label
+                    .into_iter()
+                    .map(|label| {
+                        (
+                            label.name.unwrap_or_default(),
+                            label.value.unwrap_or_default(),
+                        )
+                    })
+                    .collect(),
+            };
+            match &mut metrics {
+                GroupKind::Counter(metrics) => {
+                    let value = metric.counter.unwrap_or_default().value();
+                    metrics.insert(key, SimpleMetric { value });
+                }
+                GroupKind::Gauge(metrics) => {
+                    let value = metric.gauge.unwrap_or_default().value();
+                    metrics.insert(key, SimpleMetric { value });
+                }
+                GroupKind::Untyped(metrics) => {
+                    let value = metric.untyped.unwrap_or_default().value();
+                    metrics.insert(key, SimpleMetric { value });
+                }
+                GroupKind::Summary(metrics) => {
+                    let summary = metric.summary.unwrap_or_default();
+                    metrics.insert(
+                        key,
+                        SummaryMetric {
+                            quantiles: summary
+                                .quantile
+                                .iter()
+                                .map(|quantile| SummaryQuantile {
+                                    quantile: quantile.quantile(),
+                                    value: quantile.value(),
+                                })
+                                .collect(),
+                            sum: summary.sample_sum(),
+                            count: try_f64_to_u32(summary.sample_count() as f64)?,
+                        },
+                    );
+                }
+                GroupKind::Histogram(metrics) => {
+                    let histogram = metric.histogram.unwrap_or_default();
+                    metrics.insert(key, HistogramMetric::from_proto(&histogram)?);
+                }
+            }
+        }
+
+        Ok(MetricGroup {
+            name: family.name.unwrap_or_default(),
+            metrics,
+        })
+    }
+}
+
+impl HistogramMetric {
+    fn from_proto(histogram: &proto::client::Histogram) -> Result<Self, ParserError> {
+        let buckets = if is_native(histogram) {
+            native_buckets(histogram)?
+        } else {
+            histogram
+                .bucket
+                .iter()
+                .map(|bucket| {
+                    let count =
+                        float_or_int(bucket.cumulative_count_float(), bucket.cumulative_count());
+                    Ok(HistogramBucket {
+                        bucket: bucket.upper_bound(),
+                        count: try_f64_to_u32(count)?,
+                    })
+                })
+                .collect::<Result<_, ParserError>>()?
+        };
+        let count = float_or_int(histogram.sample_count_float(), histogram.sample_count());
+
+        Ok(HistogramMetric {
+            buckets,
+            sum: histogram.sample_sum(),
+            count: try_f64_to_u32(count)?,
+        })
+    }
+}
+
+/// Float counts override integer counts when they are positive.
+fn float_or_int(float: f64, int: u64) -> f64 {
+    if float > 0.0 {
+        float
+    } else {
+        int as f64
+    }
+}
+
+/// Whether a histogram has native buckets, in which case its conventional buckets, if any, are
+/// ignored.
+fn is_native(histogram: &proto::client::Histogram) -> bool {
+    histogram.zero_threshold() > 0.0
+        || histogram.zero_count() > 0
+        || histogram.zero_count_float() > 0.0
+        || !histogram.negative_span.is_empty()
+        || !histogram.positive_span.is_empty()
+}
+
+/// Converts the buckets of a native histogram into cumulative buckets, bounded by the exponential
+/// boundaries of its schema, so that they can be handled like conventional buckets.
+fn native_buckets(
+    histogram: &proto::client::Histogram,
+) -> Result<Vec<HistogramBucket>, ParserError> {
+    // Each boundary is the previous one multiplied by 2^(2^-schema), 1 being a boundary.
+    let factor = 2f64.powi(-histogram.schema());
+    let boundary = |index: i32| 2f64.powf(f64::from(index) * factor);
+
+    let mut buckets = Vec::new();
+    let mut cumulative = 0.0;
+    // Negative bucket `i` holds the values in [-boundary(i), -boundary(i - 1)), so the buckets are
+    // ordered by decreasing index.
+    let negative = expand_spans(
+        &histogram.negative_span,
+        &histogram.negative_delta,
+        &histogram.negative_count,
+    );
+    for (index, count) in negative.into_iter().rev() {
+        cumulative += count;
+        buckets.push(HistogramBucket {
+            bucket: -boundary(index - 1),
+            count: try_f64_to_u32(cumulative)?,
+        });
+    }
+
+    // The zero bucket holds the values in [-zero_threshold, zero_threshold].
+    let zero_count = float_or_int(histogram.zero_count_float(), histogram.zero_count());
+    if histogram.zero_threshold() > 0.0 || zero_count > 0.0 {
+        cumulative += zero_count;
+        buckets.push(HistogramBucket {
+            bucket: histogram.zero_threshold(),
+            count: try_f64_to_u32(cumulative)?,
+        });
+    }
+
+    // Positive bucket `i` holds the values in (boundary(i - 1), boundary(i)].
+    let positive = expand_spans(
+        &histogram.positive_span,
+        &histogram.positive_delta,
+        &histogram.positive_count,
+    );
+    for (index, count) in positive {
+        cumulative += count;
+        buckets.push(HistogramBucket {
+            bucket: boundary(index),
+            count: try_f64_to_u32(cumulative)?,
+        });
+    }
+
+    Ok(buckets)
+}
+
+/// Returns the index and count of each bucket of the spans. Counts are either deltas to the
+/// previous bucket, for integer histograms, or absolute, for float histograms.
+fn expand_spans(
+    spans: &[proto::client::BucketSpan],
+    deltas: &[i64],
+    counts: &[f64],
+) -> Vec<(i32, f64)> {
+    let mut buckets = Vec::new();
+    let mut index = 0;
+    let mut position = 0;
+    let mut count = 0;
+    for span in spans {
+        // The offset of the first span is its starting index, and the offsets of the others are
+        // gaps from the end of the previous span.
+        index += span.offset();
+        for _ in 0..span.length() {
+            let value = match counts.get(position) {
+                Some(value) => *value,
+                None => {
+                    count += deltas.get(position).copied().unwrap_or_default();
+                    count as f64
+                }
+            };
+            buckets.push((index, value));
+            index += 1;
+            position += 1;
+        }
+    }
+    buckets
 }
 
 fn matching_group<T: Default>(values: &mut MetricMap<T>, group: GroupKey) -> &mut T {
@@ -380,6 +599,21 @@ impl MetricGroupSet {
     }
 }
 
+/// Parse the given input in the delimited protobuf exposition format, grouping the metrics by
+/// family.
+///
+/// Native histograms, which are only exposed in this format, are converted into cumulative
+/// buckets bounded by the exponential boundaries of their schema.
+pub fn parse_protobuf(mut input: &[u8]) -> Result<Vec<MetricGroup>, ParserError> {
+    let mut groups = Vec::new();
+    while !input.is_empty() {
+        let family = proto::client::MetricFamily::decode_length_delimited(&mut input)
+            .context(DecodeProtobufSnafu)?;
+        groups.push(MetricGroup::from_family(family)?);
+    }
+    Ok(groups)
+}
+
 /// Parse the given remote_write request, grouping the metrics into
 /// higher-level metric types based on the metadata.
 pub fn parse_request(request: proto::WriteRequest) -> Result<Vec<MetricGroup>, ParserError> {
@@ -412,6 +646,111 @@ pub fn parse_request(request: proto::WriteRequest) -> Result<Vec<MetricGroup>, P
     Ok(groups.finish())
 }
 
+impl TryFrom<proto::v2::Request> for proto::WriteRequest {
+    type Error = ParserError;
+
+    /// Resolves the symbols of a Remote Write 2.0 request, converting it into a 1.0 request.
+    ///
+    /// The metadata of each series is collected into the metadata of its metric family.
+    fn try_from(request: proto::v2::Request) -> Result<Self, Self::Error> {
+        let symbols = request.symbols;
+        let symbol = |index: u32| {
+            symbols
+                .get(index as usize)
+                .cloned()
+                .ok_or(ParserError::RequestInvalidSymbol { index })
+        };
+        let labels = |refs: &[u32]| {
+            if refs.len() % 2 != 0 {
+                return Err(ParserError::RequestOddLabelReferences);
+            }
+            refs.chunks(2)
+                .map(|pair| {
+                    Ok(proto::Label {
+                        name: symbol(pair[0])?,
+                        value: symbol(pair[1])?,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut metadata = IndexMap::new();
+        let mut timeseries = Vec::with_capacity(request.timeseries.len());
+        for series in request.timeseries {
+            let series_labels = labels(&series.labels_refs)?;
+
+            if let Some(series_metadata) = series.metadata.filter(|metadata| {
+                metadata.r#type != 0 || metadata.help_ref != 0 || metadata.unit_ref != 0
+            }) {
+                let name = series_labels
+                    .iter()
+                    .find(|label| label.name == METRIC_NAME_LABEL)
+                    .ok_or(ParserError::RequestNoNameLabel)?;
+                // The metric types of both versions share the same values.
+                let r#type = proto::MetricType::try_from(series_metadata.r#type)
+                    .unwrap_or(proto::MetricType::Unknown);
+                let family = family_name(&name.value, r#type);
+                if !metadata.contains_key(family) {
+                    metadata.insert(
+                        family.to_owned(),
+                        proto::MetricMetadata {
+                            r#type: r#type as i32,
+                            metric_family_name: family.to_owned(),
+                            help: symbol(series_metadata.help_ref)?,
+                            unit: symbol(series_metadata.unit_ref)?,
+                        },
+                    );
+                }
+            }
+
+            let exemplars = series
+                .exemplars
+                .into_iter()
+                .map(|exemplar| {
+                    Ok(proto::Exemplar {
+                        labels: labels(&exemplar.labels_refs)?,
+                        value: exemplar.value,
+                        timestamp: exemplar.timestamp,
+                    })
+                })
+                .collect::<Result<_, ParserError>>()?;
+            timeseries.push(proto::TimeSeries {
+                labels: series_labels,
+                samples: series
+                    .samples
+                    .into_iter()
+                    .map(|sample| proto::Sample {
+                        value: sample.value,
+                        timestamp: sample.timestamp,
+                    })
+                    .collect(),
+                exemplars,
+            });
+        }
+
+        Ok(Self {
+            timeseries,
+            metadata: metadata.into_iter().map(|(_, metadata)| metadata).collect(),
+        })
+    }
+}
+
+/// Gets the name of the metric family of a series, whose samples of histograms and summaries
+/// are split into several suffixed series.
+fn family_name(name: &str, r#type: proto::MetricType) -> &str {
+    let suffixes: &[&str] = match r#type {
+        proto::MetricType::Histogram | proto::MetricType::Gaugehistogram => {
+            &["_bucket", "_sum", "_count"]
+        }
+        proto::MetricType::Summary => &["_sum", "_count"],
+        _ => &[],
+    };
+    suffixes
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name)
+}
+
 impl From<proto::MetricType> for MetricKind {
     fn from(kind: proto::MetricType) -> Self {
         use proto::MetricType::*;
@@ -690,6 +1029,7 @@ mod test {
                     samples: vec![
                         $( proto::Sample { value: $sample as f64, timestamp: $timestamp as i64 }, )*
                     ],
+                    exemplars: vec![],
                 }, )* ],
             }
         };
@@ -840,4 +1180,235 @@ mod test {
             assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(Some(1395066367700), labels!(), 24.0));
         });
     }
+
+    #[test]
+    fn parse_protobuf_families() {
+        use proto::client;
+
+        let families = [
+            client::MetricFamily {
+                name: Some("requests_total".into()),
+                help: None,
+                r#type: Some(client::MetricType::Counter as i32),
+                metric: vec![client::Metric {
+                    label: vec![client::LabelPair {
+                        name: Some("code".into()),
+                        value: Some("200".into()),
+                    }],
+                    counter: Some(client::Counter {
+                        value: Some(1027.0),
+                    }),
+                    timestamp_ms: Some(1395066363000),
+                    ..Default::default()
+                }],
+            },
+            client::MetricFamily {
+                name: Some("latency_seconds".into()),
+                help: None,
+                r#type: Some(client::MetricType::Histogram as i32),
+                metric: vec![client::Metric {
+                    histogram: Some(client::Histogram {
+                        sample_count: Some(3),
+                        sample_sum: Some(1.5),
+                        bucket: vec![
+                            client::Bucket {
+                                cumulative_count: Some(2),
+                                upper_bound: Some(0.5),
+                                ..Default::default()
+                            },
+                            client::Bucket {
+                                cumulative_count: Some(3),
+                                upper_bound: Some(1.0),
+                                ..Default::default()
+                            },
+                        ],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+            },
+        ];
+        let mut input = Vec::new();
+        for family in &families {
+            family.encode_length_delimited(&mut input).unwrap();
+        }
+
+        let parsed = parse_protobuf(&input).unwrap();
+        assert_eq!(parsed.len(), 2);
+        match_group!(parsed[0], "requests_total", Counter => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(Some(1395066363000), labels!(code => "200"), 1027.0)
+            );
+        });
+        match_group!(parsed[1], "latency_seconds", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap().1,
+                &HistogramMetric {
+                    buckets: vec![
+                        HistogramBucket { bucket: 0.5, count: 2 },
+                        HistogramBucket { bucket: 1.0, count: 3 },
+                    ],
+                    count: 3,
+                    sum: 1.5,
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn parse_protobuf_native_histogram() {
+        use proto::client;
+
+        let family = client::MetricFamily {
+            name: Some("latency_seconds".into()),
+            help: None,
+            r#type: Some(client::MetricType::Histogram as i32),
+            metric: vec![client::Metric {
+                histogram: Some(client::Histogram {
+                    sample_count: Some(9),
+                    sample_sum: Some(12.5),
+                    schema: Some(0),
+                    zero_threshold: Some(0.001),
+                    zero_count: Some(1),
+                    negative_span: vec![client::BucketSpan {
+                        offset: Some(1),
+                        length: Some(1),
+                    }],
+                    negative_delta: vec![1],
+                    positive_span: vec![
+                        client::BucketSpan {
+                            offset: Some(0),
+                            length: Some(2),
+                        },
+                        client::BucketSpan {
+                            offset: Some(1),
+                            length: Some(1),
+                        },
+                    ],
+                    positive_delta: vec![2, -1, 3],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        };
+        let mut input = Vec::new();
+        family.encode_length_delimited(&mut input).unwrap();
+
+        let parsed = parse_protobuf(&input).unwrap();
+        assert_eq!(parsed.len(), 1);
+        match_group!(parsed[0], "latency_seconds", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            assert_eq!(
+                metrics.get_index(0).unwrap().1,
+                &HistogramMetric {
+                    buckets: vec![
+                        HistogramBucket { bucket: -1.0, count: 1 },
+                        HistogramBucket { bucket: 0.001, count: 2 },
+                        HistogramBucket { bucket: 1.0, count: 4 },
+                        HistogramBucket { bucket: 2.0, count: 5 },
+                        HistogramBucket { bucket: 8.0, count: 9 },
+                    ],
+                    count: 9,
+                    sum: 12.5,
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn converts_request_v2() {
+        let symbols = [
+            "",
+            "__name__",
+            "latency_seconds_bucket",
+            "le",
+            "0.5",
+            "Latency.",
+            "seconds",
+            "trace_id",
+            "abc",
+        ];
+        let request = proto::v2::Request {
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            timeseries: vec![proto::v2::TimeSeries {
+                labels_refs: vec![1, 2, 3, 4],
+                samples: vec![proto::v2::Sample {
+                    value: 3.0,
+                    timestamp: 1395066367500,
+                }],
+                exemplars: vec![proto::v2::Exemplar {
+                    labels_refs: vec![7, 8],
+                    value: 0.25,
+                    timestamp: 1395066367000,
+                }],
+                metadata: Some(proto::v2::Metadata {
+                    r#type: proto::v2::metadata::MetricType::Histogram as i32,
+                    help_ref: 5,
+                    unit_ref: 6,
+                }),
+                created_timestamp: 0,
+            }],
+        };
+
+        let label = |name: &str, value: &str| proto::Label {
+            name: name.into(),
+            value: value.into(),
+        };
+        assert_eq!(
+            proto::WriteRequest::try_from(request).unwrap(),
+            proto::WriteRequest {
+                timeseries: vec![proto::TimeSeries {
+                    labels: vec![
+                        label("__name__", "latency_seconds_bucket"),
+                        label("le", "0.5")
+                    ],
+                    samples: vec![proto::Sample {
+                        value: 3.0,
+                        timestamp: 1395066367500,
+                    }],
+                    exemplars: vec![proto::Exemplar {
+                        labels: vec![label("trace_id", "abc")],
+                        value: 0.25,
+                        timestamp: 1395066367000,
+                    }],
+                }],
+                metadata: vec![proto::MetricMetadata {
+                    r#type: proto::MetricType::Histogram as i32,
+                    metric_family_name: "latency_seconds".into(),
+                    help: "Latency.".into(),
+                    unit: "seconds".into(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn converts_request_v2_invalid_symbols() {
+        let request = |labels_refs| proto::v2::Request {
+            symbols: vec!["".into(), "__name__".into()],
+            timeseries: vec![proto::v2::TimeSeries {
+                labels_refs,
+                ..Default::default()
+            }],
+        };
+
+        assert_eq!(
+            proto::WriteRequest::try_from(request(vec![1, 2])),
+            Err(ParserError::RequestInvalidSymbol { index: 2 })
+        );
+        assert_eq!(
+            proto::WriteRequest::try_from(request(vec![1])),
+            Err(ParserError::RequestOddLabelReferences)
+        );
+    }
+
+    #[test]
+    fn parse_protobuf_invalid() {
+        assert!(matches!(
+            parse_protobuf(&[0x05, 0x0a]),
+            Err(ParserError::DecodeProtobuf { .. })
+        ));
+    }
 }
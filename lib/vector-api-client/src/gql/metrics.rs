@@ -167,6 +167,36 @@ impl component_sent_events_throughputs_subscription::ComponentSentEventsThroughp
 )]
 pub struct ComponentErrorsTotalsSubscription;
 
+/// ComponentErrorTypesSubscription contains metrics on the number of errors, by error type,
+/// against specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_error_types.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorTypesSubscription;
+
+/// ComponentBufferUsagesSubscription contains metrics on the number and size of the events
+/// in the buffers of specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_buffer_usages.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentBufferUsagesSubscription;
+
+/// ComponentRetriesSubscription contains metrics on the requests retried by specific
+/// components, and their current backoff.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_retries.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentRetriesSubscription;
+
 /// Extension methods for metrics subscriptions
 pub trait MetricsSubscriptionExt {
     /// Executes an uptime metrics subscription.
@@ -242,6 +272,24 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
+    /// Executes a component error types subscription.
+    fn component_error_types_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorTypesSubscription>;
+
+    /// Executes a component buffer usages subscription.
+    fn component_buffer_usages_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentBufferUsagesSubscription>;
+
+    /// Executes a component retries subscription.
+    fn component_retries_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentRetriesSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -394,4 +442,41 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentErrorsTotalsSubscription>(&request_body)
     }
+
+    /// Executes a component error types subscription.
+    fn component_error_types_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentErrorTypesSubscription> {
+        let request_body = ComponentErrorTypesSubscription::build_query(
+            component_error_types_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorTypesSubscription>(&request_body)
+    }
+
+    /// Executes a component buffer usages subscription.
+    fn component_buffer_usages_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentBufferUsagesSubscription> {
+        let request_body = ComponentBufferUsagesSubscription::build_query(
+            component_buffer_usages_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentBufferUsagesSubscription>(&request_body)
+    }
+
+    /// Executes a component retries subscription.
+    fn component_retries_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentRetriesSubscription> {
+        let request_body =
+            ComponentRetriesSubscription::build_query(component_retries_subscription::Variables {
+                interval,
+            });
+
+        self.start::<ComponentRetriesSubscription>(&request_body)
+    }
 }
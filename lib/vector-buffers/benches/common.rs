@@ -11,7 +11,7 @@ use vector_buffers::{
         builder::TopologyBuilder,
         channel::{BufferReceiver, BufferSender},
     },
-    BufferType, EventCount,
+    BufferType, EventCount, EventPriority, Priority,
 };
 use vector_common::byte_size_of::ByteSizeOf;
 
@@ -42,6 +42,12 @@ impl<const N: usize> EventCount for Message<N> {
     }
 }
 
+impl<const N: usize> EventPriority for Message<N> {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 #[derive(Debug)]
 pub struct EncodeError;
 
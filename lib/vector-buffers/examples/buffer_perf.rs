@@ -16,12 +16,13 @@ use tokio::{select, sync::oneshot, task, time};
 use tracing::{debug, info, Span};
 use tracing_subscriber::EnvFilter;
 use vector_buffers::{
+    config::DiskBufferCompression,
     encoding::FixedEncodable,
     topology::{
         builder::TopologyBuilder,
         channel::{BufferReceiver, BufferSender},
     },
-    Acker, BufferType, Bufferable, EventCount, WhenFull,
+    Acker, BufferType, Bufferable, EventCount, EventPriority, Priority, WhenFull,
 };
 use vector_common::byte_size_of::ByteSizeOf;
 
@@ -53,6 +54,12 @@ impl EventCount for VariableMessage {
     }
 }
 
+impl EventPriority for VariableMessage {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl FixedEncodable for VariableMessage {
     type EncodeError = EncodeError;
     type DecodeError = DecodeError;
@@ -263,6 +270,10 @@ where
             BufferType::DiskV2 {
                 max_size: max_size_bytes,
                 when_full,
+                compression: DiskBufferCompression::None,
+                encryption: None,
+                partition_by: None,
+                use_io_uring: false,
             }
         }
         s => panic!(
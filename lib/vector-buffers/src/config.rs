@@ -13,10 +13,15 @@ use crate::{
         builder::{TopologyBuilder, TopologyError},
         channel::{BufferReceiver, BufferSender},
     },
-    variants::{DiskV1Buffer, DiskV2Buffer, MemoryBuffer},
+    variants::{
+        disk_v2::{EncryptionKeyError, EncryptionKeys},
+        DiskV1Buffer, DiskV2Buffer, MemoryBuffer,
+    },
     Acker, Bufferable, WhenFull,
 };
 
+pub use crate::variants::disk_v2::DiskBufferCompression;
+
 #[derive(Debug, Snafu)]
 pub enum BufferBuildError {
     #[snafu(display("the configured buffer type requires `data_dir` be specified"))]
@@ -25,6 +30,8 @@ pub enum BufferBuildError {
     FailedToBuildTopology { source: TopologyError },
     #[snafu(display("`max_events` must be greater than zero"))]
     InvalidMaxEvents,
+    #[snafu(display("invalid disk buffer encryption keys: {}", source))]
+    InvalidEncryptionKeys { source: EncryptionKeyError },
 }
 
 #[derive(Deserialize, Serialize)]
@@ -37,7 +44,30 @@ enum BufferTypeKind {
     DiskV2,
 }
 
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 8] = [
+    "type",
+    "max_events",
+    "max_size",
+    "when_full",
+    "compression",
+    "encryption",
+    "partition_by",
+    "use_io_uring",
+];
+
+const MEMORY_FIELDS: [&str; 3] = ["type", "max_events", "when_full"];
+
+const DISK_V1_FIELDS: [&str; 3] = ["type", "max_size", "when_full"];
+
+const DISK_V2_FIELDS: [&str; 7] = [
+    "type",
+    "max_size",
+    "when_full",
+    "compression",
+    "encryption",
+    "partition_by",
+    "use_io_uring",
+];
 
 struct BufferTypeVisitor;
 
@@ -50,32 +80,20 @@ impl BufferTypeVisitor {
         let mut max_events: Option<NonZeroUsize> = None;
         let mut max_size: Option<NonZeroU64> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut compression: Option<DiskBufferCompression> = None;
+        let mut encryption: Option<DiskBufferEncryption> = None;
+        let mut partition_by: Option<String> = None;
+        let mut use_io_uring: Option<bool> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
-                "type" => {
-                    if kind.is_some() {
-                        return Err(de::Error::duplicate_field("type"));
-                    }
-                    kind = Some(map.next_value()?);
-                }
-                "max_events" => {
-                    if max_events.is_some() {
-                        return Err(de::Error::duplicate_field("max_events"));
-                    }
-                    max_events = Some(map.next_value()?);
-                }
-                "max_size" => {
-                    if max_size.is_some() {
-                        return Err(de::Error::duplicate_field("max_size"));
-                    }
-                    max_size = Some(map.next_value()?);
-                }
-                "when_full" => {
-                    if when_full.is_some() {
-                        return Err(de::Error::duplicate_field("when_full"));
-                    }
-                    when_full = Some(map.next_value()?);
-                }
+                "type" => Self::set_field(&mut map, "type", &mut kind)?,
+                "max_events" => Self::set_field(&mut map, "max_events", &mut max_events)?,
+                "max_size" => Self::set_field(&mut map, "max_size", &mut max_size)?,
+                "when_full" => Self::set_field(&mut map, "when_full", &mut when_full)?,
+                "compression" => Self::set_field(&mut map, "compression", &mut compression)?,
+                "encryption" => Self::set_field(&mut map, "encryption", &mut encryption)?,
+                "partition_by" => Self::set_field(&mut map, "partition_by", &mut partition_by)?,
+                "use_io_uring" => Self::set_field(&mut map, "use_io_uring", &mut use_io_uring)?,
                 other => {
                     return Err(de::Error::unknown_field(other, &ALL_FIELDS));
                 }
@@ -83,45 +101,78 @@ impl BufferTypeVisitor {
         }
         let kind = kind.unwrap_or(BufferTypeKind::Memory);
         let when_full = when_full.unwrap_or_default();
+        // The fields only disk v2 buffers accept, and whether they're set.
+        let disk_v2_only_fields = [
+            ("compression", compression.is_some()),
+            ("encryption", encryption.is_some()),
+            ("partition_by", partition_by.is_some()),
+            ("use_io_uring", use_io_uring.is_some()),
+        ];
         match kind {
             BufferTypeKind::Memory => {
-                if max_size.is_some() {
-                    return Err(de::Error::unknown_field(
-                        "max_size",
-                        &["type", "max_events", "when_full"],
-                    ));
-                }
+                Self::reject_fields(
+                    std::iter::once(("max_size", max_size.is_some())).chain(disk_v2_only_fields),
+                    &MEMORY_FIELDS,
+                )?;
                 Ok(BufferType::Memory {
                     max_events: max_events.unwrap_or_else(memory_buffer_default_max_events),
                     when_full,
                 })
             }
             BufferTypeKind::DiskV1 => {
-                if max_events.is_some() {
-                    return Err(de::Error::unknown_field(
-                        "max_events",
-                        &["type", "max_size", "when_full"],
-                    ));
-                }
+                Self::reject_fields(
+                    std::iter::once(("max_events", max_events.is_some()))
+                        .chain(disk_v2_only_fields),
+                    &DISK_V1_FIELDS,
+                )?;
                 Ok(BufferType::DiskV1 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
                 })
             }
             BufferTypeKind::DiskV2 => {
-                if max_events.is_some() {
-                    return Err(de::Error::unknown_field(
-                        "max_events",
-                        &["type", "max_size", "when_full"],
-                    ));
-                }
+                Self::reject_fields([("max_events", max_events.is_some())], &DISK_V2_FIELDS)?;
                 Ok(BufferType::DiskV2 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
+                    compression: compression.unwrap_or_default(),
+                    encryption,
+                    partition_by,
+                    use_io_uring: use_io_uring.unwrap_or_default(),
                 })
             }
         }
     }
+
+    /// Deserializes the value of the given field into `value`, unless it was already set.
+    fn set_field<'de, A, T>(
+        map: &mut A,
+        field: &'static str,
+        value: &mut Option<T>,
+    ) -> Result<(), A::Error>
+    where
+        A: de::MapAccess<'de>,
+        T: Deserialize<'de>,
+    {
+        if value.is_some() {
+            return Err(de::Error::duplicate_field(field));
+        }
+        *value = Some(map.next_value()?);
+        Ok(())
+    }
+
+    /// Rejects the first of the given fields that is set, as the buffer type being deserialized
+    /// only accepts the `expected` fields.
+    fn reject_fields<E, I>(fields: I, expected: &'static [&'static str]) -> Result<(), E>
+    where
+        E: de::Error,
+        I: IntoIterator<Item = (&'static str, bool)>,
+    {
+        match fields.into_iter().find(|(_, set)| *set) {
+            Some((field, _)) => Err(E::unknown_field(field, expected)),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'de> de::Visitor<'de> for BufferTypeVisitor {
@@ -207,8 +258,28 @@ pub const fn memory_buffer_default_max_events() -> NonZeroUsize {
     unsafe { NonZeroUsize::new_unchecked(500) }
 }
 
+/// Encryption at rest for a disk buffer stage.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiskBufferEncryption {
+    /// Hex-encoded 256-bit keys used to encrypt buffered records.
+    ///
+    /// The first key is used to encrypt records written to new data files, while all of the keys
+    /// can be used to decrypt records, which allows rotating keys while older records are still
+    /// buffered.
+    pub keys: Vec<String>,
+}
+
+impl fmt::Debug for DiskBufferEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskBufferEncryption")
+            .field("keys", &format_args!("<{} redacted>", self.keys.len()))
+            .finish()
+    }
+}
+
 /// A specific type of buffer stage.
-#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum BufferType {
@@ -233,6 +304,20 @@ pub enum BufferType {
         max_size: NonZeroU64,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default)]
+        compression: DiskBufferCompression,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<DiskBufferEncryption>,
+        /// A template of the key events are partitioned by, such as a tenant or a topic, each
+        /// partition being a disk buffer of its own with this stage's `max_size`.
+        ///
+        /// Events are partitioned by Vector, which builds a stage per partition, so this is ignored
+        /// when building the stage itself.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        partition_by: Option<String>,
+        /// Whether data files are written through an `io_uring`, on Linux.
+        #[serde(default)]
+        use_io_uring: bool,
     },
 }
 
@@ -241,8 +326,9 @@ impl BufferType {
     ///
     /// # Errors
     ///
-    /// If a required parameter is missing, or if there is an error building the topology itself, an
-    /// error variant will be returned desribing the error
+    /// If a required parameter is missing, if the disk buffer encryption keys are invalid, or if
+    /// there is an error building the topology itself, an error variant will be returned desribing
+    /// the error
     pub fn add_to_builder<T>(
         &self,
         builder: &mut TopologyBuilder<T>,
@@ -252,26 +338,45 @@ impl BufferType {
     where
         T: Bufferable + Clone,
     {
-        match *self {
+        match self {
             BufferType::Memory {
                 when_full,
                 max_events,
             } => {
-                builder.stage(MemoryBuffer::new(max_events), when_full);
+                builder.stage(MemoryBuffer::new(*max_events), *when_full);
             }
             BufferType::DiskV1 {
                 when_full,
                 max_size,
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV1Buffer::new(id, data_dir, max_size), when_full);
+                builder.stage(DiskV1Buffer::new(id, data_dir, *max_size), *when_full);
             }
             BufferType::DiskV2 {
                 when_full,
                 max_size,
+                compression,
+                encryption,
+                use_io_uring,
+                ..
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
+                let encryption_keys = encryption
+                    .as_ref()
+                    .map(|encryption| EncryptionKeys::from_hex(&encryption.keys))
+                    .transpose()
+                    .context(InvalidEncryptionKeysSnafu)?;
+                builder.stage(
+                    DiskV2Buffer::new(
+                        id,
+                        data_dir,
+                        *max_size,
+                        encryption_keys,
+                        *compression,
+                        *use_io_uring,
+                    ),
+                    *when_full,
+                );
             }
         };
 
@@ -360,13 +465,16 @@ impl BufferConfig {
 mod test {
     use std::num::{NonZeroU64, NonZeroUsize};
 
-    use crate::{BufferConfig, BufferType, WhenFull};
+    use crate::{
+        config::DiskBufferEncryption, variants::disk_v2::DiskBufferCompression, BufferConfig,
+        BufferType, WhenFull,
+    };
 
-    fn check_single_stage(source: &str, expected: BufferType) {
+    fn check_single_stage(source: &str, expected: &BufferType) {
         let config: BufferConfig = serde_yaml::from_str(source).unwrap();
         assert_eq!(config.stages.len(), 1);
         let actual = config.stages.first().unwrap();
-        assert_eq!(actual, &expected);
+        assert_eq!(actual, expected);
     }
 
     fn check_multiple_stages(source: &str, expected_stages: &[BufferType]) {
@@ -390,7 +498,7 @@ mod test {
         let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
         assert_eq!(
             error.to_string(),
-            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full` at line 1 column 4"
+            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full`, `compression`, `encryption`, `partition_by`, `use_io_uring` at line 1 column 4"
         );
     }
 
@@ -412,7 +520,7 @@ max_events: 42
             r#"
           max_events: 100
           "#,
-            BufferType::Memory {
+            &BufferType::Memory {
                 max_events: NonZeroUsize::new(100).unwrap(),
                 when_full: WhenFull::Block,
             },
@@ -447,7 +555,7 @@ max_events: 42
           type: disk_v1
           max_size: 1024
           "#,
-            BufferType::DiskV1 {
+            &BufferType::DiskV1 {
                 max_size: NonZeroU64::new(1024).unwrap(),
                 when_full: WhenFull::Block,
             },
@@ -457,7 +565,7 @@ max_events: 42
             r#"
           type: memory
           "#,
-            BufferType::Memory {
+            &BufferType::Memory {
                 max_events: NonZeroUsize::new(500).unwrap(),
                 when_full: WhenFull::Block,
             },
@@ -468,7 +576,7 @@ max_events: 42
           type: memory
           max_events: 100
           "#,
-            BufferType::Memory {
+            &BufferType::Memory {
                 max_events: NonZeroUsize::new(100).unwrap(),
                 when_full: WhenFull::Block,
             },
@@ -479,7 +587,7 @@ max_events: 42
           type: memory
           when_full: drop_newest
           "#,
-            BufferType::Memory {
+            &BufferType::Memory {
                 max_events: NonZeroUsize::new(500).unwrap(),
                 when_full: WhenFull::DropNewest,
             },
@@ -490,7 +598,7 @@ max_events: 42
           type: memory
           when_full: overflow
           "#,
-            BufferType::Memory {
+            &BufferType::Memory {
                 max_events: NonZeroUsize::new(500).unwrap(),
                 when_full: WhenFull::Overflow,
             },
@@ -501,10 +609,144 @@ max_events: 42
           type: disk
           max_size: 1024
           "#,
-            BufferType::DiskV2 {
+            &BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                compression: DiskBufferCompression::None,
+                encryption: None,
+                partition_by: None,
+                use_io_uring: false,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_disk_compression() {
+        check_single_stage(
+            r#"
+          type: disk
+          max_size: 1024
+          compression: zstd
+          "#,
+            &BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                compression: DiskBufferCompression::Zstd,
+                encryption: None,
+                partition_by: None,
+                use_io_uring: false,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_disk_encryption() {
+        check_single_stage(
+            r#"
+          type: disk
+          max_size: 1024
+          encryption:
+            keys:
+              - 000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f
+              - 1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100
+          "#,
+            &BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                compression: DiskBufferCompression::None,
+                encryption: Some(DiskBufferEncryption {
+                    keys: vec![
+                        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+                            .to_string(),
+                        "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100"
+                            .to_string(),
+                    ],
+                }),
+                partition_by: None,
+                use_io_uring: false,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_disk_partition_by() {
+        check_single_stage(
+            r#"
+          type: disk
+          max_size: 1024
+          partition_by: "{{ tenant }}"
+          "#,
+            &BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                compression: DiskBufferCompression::None,
+                encryption: None,
+                partition_by: Some("{{ tenant }}".to_string()),
+                use_io_uring: false,
+            },
+        );
+
+        let source = r#"type: memory
+partition_by: "{{ tenant }}"
+"#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown field `partition_by`, expected one of `type`, `max_events`, `when_full` at line 1 column 5"
+        );
+    }
+
+    #[test]
+    fn parse_disk_io_uring() {
+        check_single_stage(
+            r#"
+          type: disk
+          max_size: 1024
+          use_io_uring: true
+          "#,
+            &BufferType::DiskV2 {
                 max_size: NonZeroU64::new(1024).unwrap(),
                 when_full: WhenFull::Block,
+                compression: DiskBufferCompression::None,
+                encryption: None,
+                partition_by: None,
+                use_io_uring: true,
             },
         );
+
+        let source = r#"type: memory
+use_io_uring: true
+"#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown field `use_io_uring`, expected one of `type`, `max_events`, `when_full` at line 1 column 5"
+        );
+    }
+
+    #[test]
+    fn parse_encryption_only_for_disk() {
+        let source = r#"type: memory
+encryption:
+  keys: []
+"#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown field `encryption`, expected one of `type`, `max_events`, `when_full` at line 1 column 5"
+        );
+    }
+
+    #[test]
+    fn parse_compression_only_for_disk() {
+        let source = r#"type: disk_v1
+max_size: 1024
+compression: lz4
+"#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown field `compression`, expected one of `type`, `max_size`, `when_full` at line 1 column 5"
+        );
     }
 }
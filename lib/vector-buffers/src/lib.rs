@@ -67,17 +67,49 @@ impl Arbitrary for WhenFull {
     }
 }
 
+/// Priority of an event when a buffer is full.
+///
+/// When a buffer is configured to drop or overflow events once it's full, lower priority events are
+/// shed first, so that high priority events -- audit logs, errors, and so on -- make it through a
+/// backlog while low priority events such as debug logs are dropped:
+/// - low priority events are shed as soon as the buffer is half full
+/// - normal priority events are shed once the buffer is full
+/// - high priority events are never dropped, and wait for the buffer to have room instead, once the
+///   overflow buffer is full when overflowing
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
 /// An item that can be buffered.
 ///
 /// This supertrait serves as the base trait for any item that can be pushed into a buffer.
 pub trait Bufferable:
-    ByteSizeOf + Encodable + EventCount + Debug + Send + Sync + Unpin + Sized + 'static
+    ByteSizeOf + Encodable + EventCount + EventPriority + Debug + Send + Sync + Unpin + Sized + 'static
 {
 }
 
 // Blanket implementation for anything that is already bufferable.
 impl<T> Bufferable for T where
-    T: ByteSizeOf + Encodable + EventCount + Debug + Send + Sync + Unpin + Sized + 'static
+    T: ByteSizeOf
+        + Encodable
+        + EventCount
+        + EventPriority
+        + Debug
+        + Send
+        + Sync
+        + Unpin
+        + Sized
+        + 'static
 {
 }
 
@@ -103,6 +135,34 @@ where
     }
 }
 
+pub trait EventPriority {
+    /// Gets the priority of this item.
+    ///
+    /// For items made up of multiple events, this is the highest priority of any of the events.
+    fn event_priority(&self) -> Priority;
+}
+
+impl<T> EventPriority for Vec<T>
+where
+    T: EventPriority,
+{
+    fn event_priority(&self) -> Priority {
+        self.iter()
+            .map(EventPriority::event_priority)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl<'a, T> EventPriority for &'a T
+where
+    T: EventPriority,
+{
+    fn event_priority(&self) -> Priority {
+        (*self).event_priority()
+    }
+}
+
 #[track_caller]
 pub(crate) fn spawn_named<T>(
     task: impl std::future::Future<Output = T> + Send + 'static,
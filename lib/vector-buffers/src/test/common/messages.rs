@@ -4,7 +4,7 @@ use bytes::{Buf, BufMut};
 use quickcheck::{Arbitrary, Gen};
 use vector_common::byte_size_of::ByteSizeOf;
 
-use crate::{encoding::FixedEncodable, EventCount};
+use crate::{encoding::FixedEncodable, EventCount, EventPriority, Priority};
 
 #[derive(Debug)]
 pub struct EncodeError;
@@ -51,6 +51,12 @@ impl EventCount for Message {
     }
 }
 
+impl EventPriority for Message {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl Arbitrary for Message {
     fn arbitrary(g: &mut Gen) -> Self {
         Message {
@@ -105,6 +111,12 @@ impl EventCount for SizedRecord {
     }
 }
 
+impl EventPriority for SizedRecord {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl FixedEncodable for SizedRecord {
     type EncodeError = io::Error;
     type DecodeError = io::Error;
@@ -150,6 +162,12 @@ impl EventCount for UndecodableRecord {
     }
 }
 
+impl EventPriority for UndecodableRecord {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl FixedEncodable for UndecodableRecord {
     type EncodeError = io::Error;
     type DecodeError = io::Error;
@@ -198,6 +216,12 @@ impl EventCount for MultiEventRecord {
     }
 }
 
+impl EventPriority for MultiEventRecord {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl FixedEncodable for MultiEventRecord {
     type EncodeError = io::Error;
     type DecodeError = io::Error;
@@ -253,6 +277,12 @@ impl EventCount for PoisonPillMultiEventRecord {
     }
 }
 
+impl EventPriority for PoisonPillMultiEventRecord {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl FixedEncodable for PoisonPillMultiEventRecord {
     type EncodeError = io::Error;
     type DecodeError = io::Error;
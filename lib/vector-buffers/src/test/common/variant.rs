@@ -12,7 +12,7 @@ use crate::{
         builder::TopologyBuilder,
         channel::{BufferReceiver, BufferSender},
     },
-    variants::{DiskV1Buffer, DiskV2Buffer, MemoryBuffer},
+    variants::{disk_v2::DiskBufferCompression, DiskV1Buffer, DiskV2Buffer, MemoryBuffer},
     Bufferable, WhenFull,
 };
 
@@ -79,7 +79,14 @@ impl Variant {
                 id,
             } => {
                 builder.stage(
-                    DiskV2Buffer::new(id.clone(), data_dir.clone(), *max_size),
+                    DiskV2Buffer::new(
+                        id.clone(),
+                        data_dir.clone(),
+                        *max_size,
+                        None,
+                        DiskBufferCompression::None,
+                        false,
+                    ),
                     *when_full,
                 );
             }
@@ -11,6 +11,7 @@ use async_stream::stream;
 use crossbeam_queue::ArrayQueue;
 use futures::Stream;
 use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use vector_common::memory_budget::{MemoryUse, Reservation};
 
 use crate::Bufferable;
 
@@ -56,7 +57,8 @@ impl<T: fmt::Debug> std::error::Error for TrySendError<T> {}
 
 #[derive(Debug)]
 struct Inner<T> {
-    data: Arc<ArrayQueue<(OwnedSemaphorePermit, T)>>,
+    /// The items of the channel, along with their permits and the memory they hold.
+    data: Arc<ArrayQueue<(OwnedSemaphorePermit, Reservation, T)>>,
     limit: usize,
     limiter: Arc<Semaphore>,
     read_waker: Arc<Notify>,
@@ -93,6 +95,11 @@ impl<T: Bufferable> LimitedSender<T> {
         self.inner.limiter.available_permits()
     }
 
+    /// Gets whether or not at least half of this channel's capacity is in use.
+    pub fn is_half_full(&self) -> bool {
+        self.available_capacity() * 2 <= self.inner.limit
+    }
+
     /// Sends an item into the channel.
     ///
     /// # Errors
@@ -113,9 +120,10 @@ impl<T: Bufferable> LimitedSender<T> {
             Err(_) => return Err(SendError(item)),
         };
 
+        let reservation = Reservation::new(MemoryUse::Buffers, item.size_of());
         self.inner
             .data
-            .push((permits, item))
+            .push((permits, reservation, item))
             .expect("acquired permits but channel reported being full");
         self.inner.read_waker.notify_one();
 
@@ -150,9 +158,10 @@ impl<T: Bufferable> LimitedSender<T> {
             }
         };
 
+        let reservation = Reservation::new(MemoryUse::Buffers, item.size_of());
         self.inner
             .data
-            .push((permits, item))
+            .push((permits, reservation, item))
             .expect("acquired permits but channel reported being full");
         self.inner.read_waker.notify_one();
 
@@ -196,7 +205,7 @@ impl<T: Send + 'static> LimitedReceiver<T> {
 
     pub async fn next(&mut self) -> Option<T> {
         loop {
-            if let Some((_permit, item)) = self.inner.data.pop() {
+            if let Some((_permit, _reservation, item)) = self.inner.data.pop() {
                 return Some(item);
             }
 
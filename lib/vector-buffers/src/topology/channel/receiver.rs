@@ -30,7 +30,9 @@ pub enum ReceiverAdapter<T: Bufferable> {
     DiskV1(disk_v1::Reader<T>),
 
     /// The disk v2 buffer.
-    DiskV2(disk_v2::Reader<T, ProductionFilesystem>),
+    ///
+    /// Boxed, as the reader is much larger than the other receivers.
+    DiskV2(Box<disk_v2::Reader<T, ProductionFilesystem>>),
 }
 
 impl<T: Bufferable> From<LimitedReceiver<T>> for ReceiverAdapter<T> {
@@ -47,7 +49,7 @@ impl<T: Bufferable> From<disk_v1::Reader<T>> for ReceiverAdapter<T> {
 
 impl<T: Bufferable> From<disk_v2::Reader<T, ProductionFilesystem>> for ReceiverAdapter<T> {
     fn from(v: disk_v2::Reader<T, ProductionFilesystem>) -> Self {
-        Self::DiskV2(v)
+        Self::DiskV2(Box::new(v))
     }
 }
 
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use async_recursion::async_recursion;
 use tokio::sync::Mutex;
+use vector_common::memory_budget;
 
 use super::limited_queue::LimitedSender;
 use crate::{
@@ -10,7 +11,7 @@ use crate::{
         disk_v1,
         disk_v2::{self, ProductionFilesystem},
     },
-    Bufferable, WhenFull,
+    Bufferable, Priority, WhenFull,
 };
 
 /// Adapter for papering over various sender backends.
@@ -118,6 +119,32 @@ where
         }
     }
 
+    /// Gets whether or not at least half of the underlying buffer is in use.
+    pub(crate) async fn is_half_full(&mut self) -> bool {
+        match self {
+            Self::InMemory(tx) => tx.is_half_full(),
+            Self::DiskV1(writer) => writer.is_half_full(),
+            Self::DiskV2(writer) => writer.lock().await.is_buffer_half_full(),
+        }
+    }
+
+    /// Attempts to send an item, shedding it early if its priority is too low for how full the
+    /// underlying buffer is.
+    ///
+    /// Low priority items are shed as soon as the buffer is half full, which leaves the remaining
+    /// space for higher priority items.
+    async fn try_send_with_priority(
+        &mut self,
+        item: T,
+        priority: Priority,
+    ) -> Result<Option<T>, ()> {
+        if priority == Priority::Low && self.is_half_full().await {
+            return Ok(Some(item));
+        }
+
+        self.try_send(item).await
+    }
+
     pub fn capacity(&self) -> Option<usize> {
         match self {
             Self::InMemory(tx) => Some(tx.available_capacity()),
@@ -143,6 +170,17 @@ where
 /// dropped and proceed no further. In "overflow" mode, events will be sent to another buffer
 /// sender.  Callers can specify the overflow sender to use when constructing their buffers initially.
 ///
+/// In "drop newest" and "overflow" mode, the priority of events is also taken into account, so that
+/// lower priority events are shed first: low priority events are dropped, or sent to the overflow
+/// buffer, as soon as the channel is half full, while high priority events are never dropped, and
+/// instead wait for the channel to have enough capacity, as in "block" mode.  In "overflow" mode,
+/// high priority events are sent to the overflow buffer once the channel is full, like normal
+/// priority events, and wait for the overflow buffer to have enough capacity once it's full too,
+/// whatever its own "when full" mode is.
+///
+/// In "overflow" mode, events are also sent to the overflow buffer, rather than held in memory,
+/// once the memory budget of the process says to spill them to disk.
+///
 /// TODO: We should eventually rework `BufferSender`/`BufferReceiver` so that they contain a vector
 /// of the fields we already have here, but instead of cascading via calling into `overflow`, we'd
 /// linearize the nesting instead, so that `BufferSender` would only ever be calling the underlying
@@ -210,18 +248,34 @@ impl<T: Bufferable> BufferSender<T> {
             .instrumentation
             .as_ref()
             .map(|_| (item.event_count(), item.size_of()));
+        let priority = item.event_priority();
 
         let mut sent_to_base = true;
         let mut was_dropped = false;
         match self.when_full {
             WhenFull::Block => self.base.send(item).await?,
+            WhenFull::DropNewest if priority == Priority::High => self.base.send(item).await?,
             WhenFull::DropNewest => {
-                if self.base.try_send(item).await?.is_some() {
+                if self
+                    .base
+                    .try_send_with_priority(item, priority)
+                    .await?
+                    .is_some()
+                {
                     was_dropped = true;
                 }
             }
             WhenFull::Overflow => {
-                if let Some(item) = self.base.try_send(item).await? {
+                // Items held in memory are spilled to the overflow buffer as soon as the memory
+                // budget of the process runs low, rather than once the buffer is full.
+                let item = if matches!(self.base, SenderAdapter::InMemory(_))
+                    && memory_budget::should_spill_to_disk()
+                {
+                    Some(item)
+                } else {
+                    self.base.try_send_with_priority(item, priority).await?
+                };
+                if let Some(item) = item {
                     sent_to_base = false;
                     self.overflow
                         .as_mut()
@@ -8,7 +8,7 @@ use tokio::{pin, sync::Barrier, time::sleep};
 use crate::{
     topology::{
         channel::{BufferReceiver, BufferSender},
-        test_util::{assert_current_send_capacity, build_buffer},
+        test_util::{assert_current_send_capacity, build_buffer, HIGH_PRIORITY, LOW_PRIORITY},
     },
     Bufferable, WhenFull,
 };
@@ -185,6 +185,96 @@ async fn test_sender_overflow_drop_newest() {
     assert_eq!(results, vec![1, 2, 7, 8]);
 }
 
+#[tokio::test]
+async fn test_sender_drop_newest_sheds_low_priority_first() {
+    // Get a non-overflow buffer in "drop newest" mode with a capacity of 4.
+    let (mut tx, rx, handle) = build_buffer(4, WhenFull::DropNewest, None).await;
+
+    // Low priority items are accepted until the buffer is half full...
+    assert_current_send_capacity(&mut tx, Some(4), None);
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 1, Some(3), None).await;
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 2, Some(2), None).await;
+
+    // ...after which they're dropped, leaving the remaining capacity for normal priority items.
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 3, Some(2), None).await;
+    assert_send_ok_with_capacities(&mut tx, 1, Some(1), None).await;
+    assert_send_ok_with_capacities(&mut tx, 2, Some(0), None).await;
+
+    let snapshot = handle.snapshot();
+    assert_eq!(1, snapshot.dropped_event_count_intentional);
+
+    let mut results = drain_receiver(tx, rx).await;
+    results.sort_unstable();
+    assert_eq!(results, vec![1, 2, LOW_PRIORITY + 1, LOW_PRIORITY + 2]);
+}
+
+#[tokio::test]
+async fn test_sender_drop_newest_blocks_high_priority() {
+    // Get a non-overflow buffer in "drop newest" mode with a capacity of 2.
+    let (mut tx, rx, _) = build_buffer(2, WhenFull::DropNewest, None).await;
+
+    assert_current_send_capacity(&mut tx, Some(2), None);
+    assert_send_ok_with_capacities(&mut tx, 1, Some(1), None).await;
+    assert_send_ok_with_capacities(&mut tx, 2, Some(0), None).await;
+
+    // High priority items are never dropped, so our next send _should_ block until the receiver
+    // makes room for it, just like in blocking mode.
+    let mut results = blocking_send_and_drain_receiver(tx, rx, HIGH_PRIORITY + 1).await;
+    results.sort_unstable();
+    assert_eq!(results, vec![1, 2, HIGH_PRIORITY + 1]);
+}
+
+#[tokio::test]
+async fn test_sender_overflow_sheds_low_priority_first() {
+    // Get an overflow buffer, where the overflow buffer is in "drop newest" mode, and both the
+    // base and overflow buffers have a capacity of 2.
+    let (mut tx, rx, _) = build_buffer(2, WhenFull::Overflow, Some(WhenFull::DropNewest)).await;
+
+    // Once the base buffer is half full, low priority items go to the overflow buffer, and once
+    // that one is half full as well, they're dropped.
+    assert_current_send_capacity(&mut tx, Some(2), Some(2));
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 1, Some(1), Some(2)).await;
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 2, Some(1), Some(1)).await;
+    assert_send_ok_with_capacities(&mut tx, LOW_PRIORITY + 3, Some(1), Some(1)).await;
+
+    // Normal priority items can still use the remaining capacity of both buffers.
+    assert_send_ok_with_capacities(&mut tx, 1, Some(0), Some(1)).await;
+    assert_send_ok_with_capacities(&mut tx, 2, Some(0), Some(0)).await;
+
+    let mut results = drain_receiver(tx, rx).await;
+    results.sort_unstable();
+    assert_eq!(results, vec![1, 2, LOW_PRIORITY + 1, LOW_PRIORITY + 2]);
+}
+
+#[tokio::test]
+async fn test_sender_overflow_blocks_high_priority() {
+    // Get an overflow buffer, where the overflow buffer is in "drop newest" mode, and both the
+    // base and overflow buffers have a capacity of 2.
+    let (mut tx, rx, _) = build_buffer(2, WhenFull::Overflow, Some(WhenFull::DropNewest)).await;
+
+    // High priority items go to the overflow buffer once the base buffer is full...
+    assert_current_send_capacity(&mut tx, Some(2), Some(2));
+    assert_send_ok_with_capacities(&mut tx, 1, Some(1), Some(2)).await;
+    assert_send_ok_with_capacities(&mut tx, 2, Some(0), Some(2)).await;
+    assert_send_ok_with_capacities(&mut tx, HIGH_PRIORITY + 1, Some(0), Some(1)).await;
+    assert_send_ok_with_capacities(&mut tx, HIGH_PRIORITY + 2, Some(0), Some(0)).await;
+
+    // ...and aren't dropped once the overflow buffer is full as well, so our next send _should_
+    // block until the receiver makes room for it.
+    let mut results = blocking_send_and_drain_receiver(tx, rx, HIGH_PRIORITY + 3).await;
+    results.sort_unstable();
+    assert_eq!(
+        results,
+        vec![
+            1,
+            2,
+            HIGH_PRIORITY + 1,
+            HIGH_PRIORITY + 2,
+            HIGH_PRIORITY + 3
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_buffer_metrics_normal() {
     // Get a regular blocking buffer.
@@ -7,7 +7,7 @@ use crate::{
     buffer_usage_data::BufferUsageHandle,
     encoding::FixedEncodable,
     topology::channel::{BufferReceiver, BufferSender},
-    Bufferable, EventCount, WhenFull,
+    Bufferable, EventCount, EventPriority, Priority, WhenFull,
 };
 
 // Silly implementation of `Encodable` to fulfill `Bufferable` for our test buffer code.
@@ -42,6 +42,26 @@ impl EventCount for u64 {
     }
 }
 
+/// Test items of at least this value are treated as low priority.
+pub const LOW_PRIORITY: u64 = 1_000;
+
+/// Test items of at least this value are treated as high priority.
+pub const HIGH_PRIORITY: u64 = 2_000;
+
+// Silly implementation of `EventPriority` so that our test buffer code can exercise priority-aware
+// shedding with plain integers.
+impl EventPriority for u64 {
+    fn event_priority(&self) -> Priority {
+        if *self >= HIGH_PRIORITY {
+            Priority::High
+        } else if *self >= LOW_PRIORITY {
+            Priority::Low
+        } else {
+            Priority::Normal
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BasicError(pub(crate) String);
 
@@ -91,6 +91,11 @@ where
         }
     }
 
+    /// Gets whether or not at least half of the buffer's maximum size is in use.
+    pub fn is_half_full(&self) -> bool {
+        self.current_size.load(Ordering::Relaxed) * 2 >= self.max_size
+    }
+
     #[cfg_attr(test, instrument(skip(self), level = "debug"))]
     pub fn try_send(&mut self, item: T) -> Option<T> {
         let event_len = item.event_count();
@@ -7,7 +7,11 @@ use std::{
 use crc32fast::Hasher;
 use snafu::Snafu;
 
-use super::io::{Filesystem, ProductionFilesystem};
+use super::{
+    compression::DiskBufferCompression,
+    encryption::EncryptionKeys,
+    io::{Filesystem, ProductionFilesystem},
+};
 
 // We don't want data files to be bigger than 128MB, but we might end up overshooting slightly.
 pub const DEFAULT_MAX_DATA_FILE_SIZE: usize = 128 * 1024 * 1024;
@@ -101,6 +105,21 @@ pub struct DiskBufferConfig<FS> {
     /// amount of data written since the last flush would be lost.
     pub(crate) flush_interval: Duration,
 
+    /// Keys used to encrypt and decrypt record payloads.
+    ///
+    /// When set, the payload of every record written is encrypted with a key derived from the
+    /// active key when the data file it's written to is opened.  Records that were encrypted with
+    /// any of the keys can be decrypted, which allows rotating keys while records written with the
+    /// previous key are still in the buffer.
+    pub(crate) encryption_keys: Option<EncryptionKeys>,
+
+    /// Compression applied to record payloads.
+    ///
+    /// Compressing records trades CPU time for being able to store more records in the same amount
+    /// of disk space.  Records are always decompressed when read, whether or not compression is
+    /// currently enabled.
+    pub(crate) compression: DiskBufferCompression,
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -121,6 +140,8 @@ where
     pub(crate) max_record_size: Option<usize>,
     pub(crate) write_buffer_size: Option<usize>,
     pub(crate) flush_interval: Option<Duration>,
+    pub(crate) encryption_keys: Option<EncryptionKeys>,
+    pub(crate) compression: Option<DiskBufferCompression>,
     pub(crate) filesystem: FS,
 }
 
@@ -136,7 +157,9 @@ impl DiskBufferConfigBuilder {
             max_record_size: None,
             write_buffer_size: None,
             flush_interval: None,
-            filesystem: ProductionFilesystem,
+            encryption_keys: None,
+            compression: None,
+            filesystem: ProductionFilesystem::default(),
         }
     }
 }
@@ -216,6 +239,33 @@ where
         self
     }
 
+    /// Sets the keys used to encrypt and decrypt record payloads.
+    ///
+    /// When set, the payload of every record written is encrypted with a key derived from the
+    /// active key when the data file it's written to is opened.  Records that were encrypted with
+    /// any of the keys can be decrypted, which allows rotating keys while records written with the
+    /// previous key are still in the buffer.
+    ///
+    /// Defaults to no encryption.
+    #[allow(dead_code)]
+    pub fn encryption_keys(mut self, keys: EncryptionKeys) -> Self {
+        self.encryption_keys = Some(keys);
+        self
+    }
+
+    /// Sets the compression applied to record payloads.
+    ///
+    /// Compressing records trades CPU time for being able to store more records in the same amount
+    /// of disk space.  Records are always decompressed when read, whether or not compression is
+    /// currently enabled.
+    ///
+    /// Defaults to no compression.
+    #[allow(dead_code)]
+    pub fn compression(mut self, compression: DiskBufferCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -235,6 +285,8 @@ where
             max_record_size: self.max_record_size,
             write_buffer_size: self.write_buffer_size,
             flush_interval: self.flush_interval,
+            encryption_keys: self.encryption_keys,
+            compression: self.compression,
             filesystem,
         }
     }
@@ -249,6 +301,8 @@ where
         let max_record_size = self.max_record_size.unwrap_or(DEFAULT_MAX_RECORD_SIZE);
         let write_buffer_size = self.write_buffer_size.unwrap_or(DEFAULT_WRITE_BUFFER_SIZE);
         let flush_interval = self.flush_interval.unwrap_or(DEFAULT_FLUSH_INTERVAL);
+        let encryption_keys = self.encryption_keys;
+        let compression = self.compression.unwrap_or_default();
         let filesystem = self.filesystem;
 
         // Validate the input parameters.
@@ -308,6 +362,8 @@ where
             max_record_size,
             write_buffer_size,
             flush_interval,
+            encryption_keys,
+            compression,
             filesystem,
         })
     }
@@ -0,0 +1,95 @@
+//! Compression of record payloads.
+//!
+//! When compression is configured, the writer compresses the payload of every record before it's
+//! encrypted and archived, and marks the record as compressed by setting [`COMPRESSED_RECORD_FLAG`]
+//! in the record metadata.  Since records are written in batches of events, this works out to
+//! compressing the buffer block by block.  The first byte of a compressed payload identifies the
+//! algorithm used, so records can always be decompressed, regardless of how compression is
+//! currently configured:
+//!
+//!   compressed payload:
+//!     `algorithm`: uint8 (1 = LZ4, 2 = Zstandard)
+//!     `data`:      uint8[]
+//!
+//! Payloads that don't get any smaller when compressed are written uncompressed instead.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// Flag set in the record metadata when the record payload is compressed.
+pub(super) const COMPRESSED_RECORD_FLAG: u32 = 1 << 30;
+
+const LZ4_ALGORITHM: u8 = 1;
+const ZSTD_ALGORITHM: u8 = 2;
+
+/// Compression applied to the records stored in a disk buffer.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskBufferCompression {
+    /// Records are stored uncompressed.
+    None,
+
+    /// Records are compressed with LZ4, which is very fast, but compresses less.
+    Lz4,
+
+    /// Records are compressed with Zstandard, which compresses better, but uses more CPU.
+    Zstd,
+}
+
+impl Default for DiskBufferCompression {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl DiskBufferCompression {
+    /// Compresses the given payload into `buf`, replacing its previous contents.
+    ///
+    /// If the payload was compressed, `true` is returned.  Otherwise, if compression is disabled or
+    /// the payload didn't get any smaller, `false` is returned and the payload should be written
+    /// as-is.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurred during compression, an error variant will be returned describing the
+    /// error.
+    pub(super) fn compress(self, payload: &[u8], buf: &mut Vec<u8>) -> io::Result<bool> {
+        buf.clear();
+        match self {
+            Self::None => return Ok(false),
+            Self::Lz4 => {
+                buf.push(LZ4_ALGORITHM);
+                buf.extend_from_slice(&lz4_flex::compress_prepend_size(payload));
+            }
+            Self::Zstd => {
+                buf.push(ZSTD_ALGORITHM);
+                buf.extend_from_slice(&zstd::bulk::compress(payload, 0)?);
+            }
+        }
+
+        Ok(buf.len() < payload.len())
+    }
+}
+
+/// Decompresses the given compressed payload.
+///
+/// # Errors
+///
+/// If the payload is malformed, or was compressed with an unknown algorithm, an error variant will
+/// be returned describing the error.
+pub(super) fn decompress(payload: &[u8]) -> io::Result<Vec<u8>> {
+    match payload.split_first() {
+        Some((&LZ4_ALGORITHM, data)) => lz4_flex::decompress_size_prepended(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+        Some((&ZSTD_ALGORITHM, data)) => zstd::stream::decode_all(data),
+        Some((algorithm, _)) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown compression algorithm {}", algorithm),
+        )),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "compressed payload is empty",
+        )),
+    }
+}
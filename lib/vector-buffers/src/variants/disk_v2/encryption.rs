@@ -0,0 +1,334 @@
+//! Encryption at rest for record payloads.
+//!
+//! When encryption keys are configured, the writer encrypts the payload of every record with
+//! AES-256-GCM before archiving it, and marks the record as encrypted by setting
+//! [`ENCRYPTED_RECORD_FLAG`] in the record metadata.  The record structure itself is unchanged, so
+//! checksumming, framing, and so on, all work exactly the same as for plaintext records.
+//!
+//! Keys are rotated on data file rollover: every time the writer opens a data file, it derives a
+//! fresh data file key from the active master key and a random salt, using HKDF-SHA256.  The ID of
+//! the master key, and the salt, are stored alongside each encrypted payload, which lets the reader
+//! derive the same data file key as long as the master key is still configured:
+//!
+//!   encrypted payload:
+//!     `version`:    uint8
+//!     `key_id`:     uint8[4] (first four bytes of SHA-256(master key))
+//!     `salt`:       uint8[16]
+//!     `nonce`:      uint8[12]
+//!     `ciphertext`: uint8[] (including the 16 byte authentication tag)
+//!
+//! The record ID and metadata are used as the additional authenticated data, which ensures that an
+//! encrypted payload can't be moved to another record without being detected.
+
+use std::{fmt, sync::Arc};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    digest::{digest, SHA256},
+    error::Unspecified,
+    hkdf::{Salt, HKDF_SHA256},
+    rand::{SecureRandom, SystemRandom},
+};
+use snafu::Snafu;
+
+/// Flag set in the record metadata when the record payload is encrypted.
+///
+/// The buffer reserves the highest bit of the record metadata for itself, which is how records
+/// written before encryption was enabled can still be read after it has been enabled.
+pub(super) const ENCRYPTED_RECORD_FLAG: u32 = 1 << 31;
+
+/// Length, in bytes, of the master keys used to encrypt records.
+pub const ENCRYPTION_KEY_LEN: usize = 32;
+
+const ENVELOPE_VERSION: u8 = 1;
+const KEY_ID_LEN: usize = 4;
+const SALT_LEN: usize = 16;
+const ENVELOPE_HEADER_LEN: usize = 1 + KEY_ID_LEN + SALT_LEN + NONCE_LEN;
+const DATA_FILE_KEY_INFO: &[u8] = b"vector disk buffer v2 data file key";
+
+/// Error that occurred when loading encryption keys.
+#[derive(Debug, PartialEq, Snafu)]
+pub enum EncryptionKeyError {
+    /// No encryption keys were given.
+    #[snafu(display("at least one encryption key must be configured"))]
+    NoKeys,
+
+    /// An encryption key was not a valid, hex-encoded, 256-bit key.
+    #[snafu(display(
+        "encryption key at index {} must be {} hex-encoded bytes",
+        index,
+        ENCRYPTION_KEY_LEN
+    ))]
+    InvalidKey { index: usize },
+}
+
+/// Error that occurred when decrypting a record payload.
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum DecryptionError {
+    #[snafu(display("encrypted payload too short ({} bytes)", len))]
+    Truncated { len: usize },
+
+    #[snafu(display("unsupported encrypted payload version {}", version))]
+    UnsupportedVersion { version: u8 },
+
+    #[snafu(display("no configured encryption key matches key ID {}", key_id))]
+    UnknownKey { key_id: String },
+
+    #[snafu(display("payload failed authentication"))]
+    Unauthenticated,
+}
+
+#[derive(Clone)]
+struct MasterKey {
+    id: [u8; KEY_ID_LEN],
+    material: [u8; ENCRYPTION_KEY_LEN],
+}
+
+impl MasterKey {
+    fn new(material: [u8; ENCRYPTION_KEY_LEN]) -> Self {
+        let mut id = [0; KEY_ID_LEN];
+        id.copy_from_slice(&digest(&SHA256, &material).as_ref()[..KEY_ID_LEN]);
+        Self { id, material }
+    }
+
+    /// Derives the key for a data file from this master key and the given salt.
+    fn derive(&self, salt: &[u8]) -> LessSafeKey {
+        let prk = Salt::new(HKDF_SHA256, salt).extract(&self.material);
+        let okm = prk
+            .expand(&[DATA_FILE_KEY_INFO], &AES_256_GCM)
+            .expect("AES-256-GCM key length is always a valid HKDF output length");
+        LessSafeKey::new(UnboundKey::from(okm))
+    }
+}
+
+/// Master keys used to encrypt and decrypt buffered records.
+///
+/// The first key is the active key, and is used to derive the key of every new data file.  The
+/// remaining keys are only used for decrypting records that were written with them, which allows
+/// rotating the master key without losing records that are still in the buffer.
+#[derive(Clone)]
+pub struct EncryptionKeys {
+    keys: Arc<[MasterKey]>,
+    rng: SystemRandom,
+}
+
+impl EncryptionKeys {
+    /// Creates a new `EncryptionKeys` from hex-encoded 256-bit keys, with the first key being the
+    /// active key.
+    ///
+    /// # Errors
+    ///
+    /// If no keys are given, or if any of the keys is not a valid hex-encoded 256-bit key, an error
+    /// variant will be returned describing the error.
+    pub fn from_hex<S>(keys: &[S]) -> Result<Self, EncryptionKeyError>
+    where
+        S: AsRef<str>,
+    {
+        if keys.is_empty() {
+            return Err(EncryptionKeyError::NoKeys);
+        }
+
+        let keys = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                decode_hex_key(key.as_ref().trim())
+                    .map(MasterKey::new)
+                    .ok_or(EncryptionKeyError::InvalidKey { index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            keys: keys.into(),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Creates the cipher used to encrypt the records of a newly opened data file.
+    ///
+    /// # Errors
+    ///
+    /// If the random salt for the data file key could not be generated, an error will be returned.
+    pub(super) fn data_file_cipher(&self) -> Result<RecordCipher, Unspecified> {
+        let active = &self.keys[0];
+        let mut salt = [0; SALT_LEN];
+        self.rng.fill(&mut salt)?;
+
+        Ok(RecordCipher {
+            key_id: active.id,
+            salt,
+            key: active.derive(&salt),
+            rng: self.rng.clone(),
+        })
+    }
+
+    /// Creates a decryptor for reading encrypted records.
+    pub(super) fn decryptor(&self) -> RecordDecryptor {
+        RecordDecryptor {
+            keys: self.clone(),
+            data_file_key: None,
+            buf: Vec::new(),
+        }
+    }
+
+    fn find(&self, key_id: [u8; KEY_ID_LEN]) -> Option<&MasterKey> {
+        self.keys.iter().find(|key| key.id == key_id)
+    }
+}
+
+impl fmt::Debug for EncryptionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key_ids = self
+            .keys
+            .iter()
+            .map(|key| encode_hex(&key.id))
+            .collect::<Vec<_>>();
+        f.debug_struct("EncryptionKeys")
+            .field("key_ids", &key_ids)
+            .finish()
+    }
+}
+
+/// Encrypts the records written to a single data file.
+pub(super) struct RecordCipher {
+    key_id: [u8; KEY_ID_LEN],
+    salt: [u8; SALT_LEN],
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl RecordCipher {
+    /// Encrypts the given payload into `buf`, replacing its previous contents.
+    ///
+    /// # Errors
+    ///
+    /// If the random nonce could not be generated, or the payload is too large to be encrypted, an
+    /// error will be returned.
+    pub fn seal(
+        &self,
+        id: u64,
+        metadata: u32,
+        payload: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Unspecified> {
+        let mut nonce = [0; NONCE_LEN];
+        self.rng.fill(&mut nonce)?;
+
+        buf.clear();
+        buf.push(ENVELOPE_VERSION);
+        buf.extend_from_slice(&self.key_id);
+        buf.extend_from_slice(&self.salt);
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(payload);
+
+        let tag = self.key.seal_in_place_separate_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(additional_data(id, metadata)),
+            &mut buf[ENVELOPE_HEADER_LEN..],
+        )?;
+        buf.extend_from_slice(tag.as_ref());
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RecordCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordCipher")
+            .field("key_id", &encode_hex(&self.key_id))
+            .finish()
+    }
+}
+
+/// Decrypts encrypted record payloads.
+///
+/// The key of the data file being read is cached, as records are generally read sequentially, and
+/// so consecutive records will usually have been encrypted with the same data file key.
+pub(super) struct RecordDecryptor {
+    keys: EncryptionKeys,
+    data_file_key: Option<([u8; KEY_ID_LEN], [u8; SALT_LEN], LessSafeKey)>,
+    buf: Vec<u8>,
+}
+
+impl RecordDecryptor {
+    /// Decrypts the given encrypted payload.
+    ///
+    /// # Errors
+    ///
+    /// If the payload is malformed, was encrypted with a key that is not configured, or fails
+    /// authentication, an error variant will be returned describing the error.
+    pub fn open(
+        &mut self,
+        id: u64,
+        metadata: u32,
+        payload: &[u8],
+    ) -> Result<&[u8], DecryptionError> {
+        if payload.len() < ENVELOPE_HEADER_LEN + AES_256_GCM.tag_len() {
+            return Err(DecryptionError::Truncated { len: payload.len() });
+        }
+
+        let (header, ciphertext) = payload.split_at(ENVELOPE_HEADER_LEN);
+        if header[0] != ENVELOPE_VERSION {
+            return Err(DecryptionError::UnsupportedVersion { version: header[0] });
+        }
+
+        let (key_id, rest) = header[1..].split_at(KEY_ID_LEN);
+        let (salt, nonce) = rest.split_at(SALT_LEN);
+        let key_id: [u8; KEY_ID_LEN] = key_id.try_into().expect("key ID length is fixed");
+        let salt: [u8; SALT_LEN] = salt.try_into().expect("salt length is fixed");
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("nonce length is fixed");
+
+        let is_cached = matches!(
+            &self.data_file_key,
+            Some((cached_key_id, cached_salt, _)) if *cached_key_id == key_id && *cached_salt == salt
+        );
+        if !is_cached {
+            let master = self
+                .keys
+                .find(key_id)
+                .ok_or_else(|| DecryptionError::UnknownKey {
+                    key_id: encode_hex(&key_id),
+                })?;
+            self.data_file_key = Some((key_id, salt, master.derive(&salt)));
+        }
+        let (_, _, key) = self
+            .data_file_key
+            .as_ref()
+            .expect("data file key must be present");
+
+        self.buf.clear();
+        self.buf.extend_from_slice(ciphertext);
+        key.open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(additional_data(id, metadata)),
+            &mut self.buf,
+        )
+        .map(|plaintext| &*plaintext)
+        .map_err(|_| DecryptionError::Unauthenticated)
+    }
+}
+
+fn additional_data(id: u64, metadata: u32) -> [u8; 12] {
+    let mut aad = [0; 12];
+    aad[..8].copy_from_slice(&id.to_be_bytes());
+    aad[8..].copy_from_slice(&metadata.to_be_bytes());
+    aad
+}
+
+fn decode_hex_key(key: &str) -> Option<[u8; ENCRYPTION_KEY_LEN]> {
+    if key.len() != ENCRYPTION_KEY_LEN * 2 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut material = [0; ENCRYPTION_KEY_LEN];
+    for (byte, digits) in material.iter_mut().zip(key.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).ok()?;
+        *byte = u8::from_str_radix(digits, 16).ok()?;
+    }
+
+    Some(material)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -1,7 +1,16 @@
-use std::{io, path::Path};
+use std::{
+    io,
+    path::Path,
+    pin::Pin,
+    sync::{atomic::AtomicBool, Arc},
+    task::{Context, Poll},
+};
 
 use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+#[cfg(target_os = "linux")]
+use super::uring::{Ring, RingFile, RingWriter, RING_BUFFER_SIZE, RING_ENTRIES};
 
 /// File metadata.
 pub struct Metadata {
@@ -83,6 +92,9 @@ pub trait Filesystem: Send + Sync {
     async fn delete_file(&self, path: &Path) -> io::Result<()>;
 }
 
+/// A file of a [`Filesystem`].
+///
+/// Writes may be buffered until the file is flushed, so it must be flushed before being synchronized.
 #[async_trait]
 pub trait AsyncFile: AsyncRead + AsyncWrite + Send + Sync {
     /// Queries metadata about the underlying file.
@@ -119,35 +131,100 @@ pub trait WritableMemoryMap: ReadableMemoryMap {
 /// A normal filesystem used for production operations.
 ///
 /// Uses Tokio's `File` for asynchronous file reading/writing, and `memmap2` for memory-mapped files.
-#[derive(Clone, Debug)]
-pub struct ProductionFilesystem;
+/// On Linux, files opened for writing can be written through an `io_uring` instead.
+#[derive(Clone, Debug, Default)]
+pub struct ProductionFilesystem {
+    /// Whether files opened for writing are written through an `io_uring`, which is disabled once it
+    /// fails to be set up.
+    io_uring: Arc<AtomicBool>,
+}
+
+impl ProductionFilesystem {
+    /// Creates a filesystem writing files through an `io_uring`, on Linux.
+    ///
+    /// If `io_uring` can't be set up, such as on older kernels or when the limit of locked memory is
+    /// too low, files are written with system calls as usual.
+    pub fn with_io_uring() -> Self {
+        #[cfg(not(target_os = "linux"))]
+        warn!(
+            message =
+                "io_uring is only supported on Linux, writing disk buffers with system calls instead."
+        );
+        Self {
+            io_uring: Arc::new(AtomicBool::new(cfg!(target_os = "linux"))),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn ring(&self) -> Option<Ring> {
+        use std::sync::atomic::Ordering;
+
+        if !self.io_uring.load(Ordering::Relaxed) {
+            return None;
+        }
+        match Ring::new(RING_ENTRIES, RING_BUFFER_SIZE) {
+            Ok(ring) => Some(ring),
+            Err(error) => {
+                if self.io_uring.swap(false, Ordering::Relaxed) {
+                    warn!(
+                        message =
+                            "Failed to set up io_uring, writing disk buffers with system calls instead.",
+                        %error,
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    async fn open_writable(
+        &self,
+        options: &mut tokio::fs::OpenOptions,
+        path: &Path,
+    ) -> io::Result<ProductionFile> {
+        #[cfg(target_os = "linux")]
+        if let Some(ring) = self.ring() {
+            // Buffers are written at their offset, possibly several at once, so the file isn't
+            // opened in append mode.
+            let file = options.write(true).open(path).await?;
+            let len = file.metadata().await?.len();
+            let writer = RingWriter::new(ring, file.try_clone().await?.into_std().await, len);
+            return Ok(ProductionFile {
+                file,
+                ring: Some(RingFile::new(writer)),
+            });
+        }
+
+        let file = options.append(true).open(path).await?;
+        Ok(ProductionFile::from(file))
+    }
+}
 
 #[async_trait]
 impl Filesystem for ProductionFilesystem {
-    type File = tokio::fs::File;
+    type File = ProductionFile;
     type MemoryMap = memmap2::Mmap;
     type MutableMemoryMap = memmap2::MmapMut;
 
     async fn open_file_writable(&self, path: &Path) -> io::Result<Self::File> {
-        tokio::fs::OpenOptions::new()
-            .append(true)
-            .read(true)
-            .create(true)
-            .open(path)
+        self.open_writable(tokio::fs::OpenOptions::new().read(true).create(true), path)
             .await
     }
 
     async fn open_file_writable_atomic(&self, path: &Path) -> io::Result<Self::File> {
+        self.open_writable(
+            tokio::fs::OpenOptions::new().read(true).create_new(true),
+            path,
+        )
+        .await
+    }
+
+    async fn open_file_readable(&self, path: &Path) -> io::Result<Self::File> {
         tokio::fs::OpenOptions::new()
-            .append(true)
             .read(true)
-            .create_new(true)
             .open(path)
             .await
-    }
-
-    async fn open_file_readable(&self, path: &Path) -> io::Result<Self::File> {
-        tokio::fs::OpenOptions::new().read(true).open(path).await
+            .map(ProductionFile::from)
     }
 
     async fn open_mmap_readable(&self, path: &Path) -> io::Result<Self::MemoryMap> {
@@ -171,6 +248,80 @@ impl Filesystem for ProductionFilesystem {
     }
 }
 
+/// A file of the [`ProductionFilesystem`].
+///
+/// Reads go through Tokio's `File`, as do writes unless the file is written through an `io_uring`.
+#[derive(Debug)]
+pub struct ProductionFile {
+    file: tokio::fs::File,
+    #[cfg(target_os = "linux")]
+    ring: Option<RingFile>,
+}
+
+impl From<tokio::fs::File> for ProductionFile {
+    fn from(file: tokio::fs::File) -> Self {
+        Self {
+            file,
+            #[cfg(target_os = "linux")]
+            ring: None,
+        }
+    }
+}
+
+impl AsyncRead for ProductionFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().file).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ProductionFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        #[cfg(target_os = "linux")]
+        if let Some(ring) = this.ring.as_mut() {
+            return ring.poll_write(cx, buf);
+        }
+        Pin::new(&mut this.file).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        #[cfg(target_os = "linux")]
+        if let Some(ring) = this.ring.as_mut() {
+            return ring.poll_flush(cx);
+        }
+        Pin::new(&mut this.file).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        #[cfg(target_os = "linux")]
+        if let Some(ring) = this.ring.as_mut() {
+            return ring.poll_flush(cx);
+        }
+        Pin::new(&mut this.file).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl AsyncFile for ProductionFile {
+    async fn metadata(&self) -> io::Result<Metadata> {
+        AsyncFile::metadata(&self.file).await
+    }
+
+    async fn sync_all(&self) -> io::Result<()> {
+        AsyncFile::sync_all(&self.file).await
+    }
+}
+
 #[async_trait]
 impl AsyncFile for tokio::fs::File {
     async fn metadata(&self) -> io::Result<Metadata> {
@@ -277,17 +277,20 @@ where
         let last_total_buffer_size = self.total_buffer_size.fetch_add(amount, Ordering::AcqRel);
         trace!(
             previous_buffer_size = last_total_buffer_size,
-            new_buffer_size = last_total_buffer_size + amount,
+            new_buffer_size = last_total_buffer_size.wrapping_add(amount),
             "Updated buffer size.",
         );
     }
 
     /// Decrements the total number of bytes for all unread records in the buffer.
+    ///
+    /// Records can be read as soon as they're written to the data file, which may be before the
+    /// writer accounted for them, so the total wraps around until it does.
     pub(super) fn decrement_total_buffer_size(&self, amount: u64) {
         let last_total_buffer_size = self.total_buffer_size.fetch_sub(amount, Ordering::AcqRel);
         trace!(
             previous_buffer_size = last_total_buffer_size,
-            new_buffer_size = last_total_buffer_size - amount,
+            new_buffer_size = last_total_buffer_size.wrapping_sub(amount),
             "Updated buffer size.",
         );
     }
@@ -608,6 +611,7 @@ where
             }
 
             // Now sync the file to ensure everything is on disk before proceeding.
+            ledger_handle.flush().await.context(IoSnafu)?;
             ledger_handle.sync_all().await.context(IoSnafu)?;
         }
 
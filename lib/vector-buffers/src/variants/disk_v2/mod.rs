@@ -45,6 +45,20 @@
 //! This represents a small amount of extra space overhead per record, but is beneficial to us as we
 //! avoid a more formal deserialization step, with scratch buffers and memory copies.
 //!
+//! ### Compression
+//!
+//! Record payloads can optionally be compressed, with either LZ4 or Zstandard.  When compression is
+//! configured, the payload of each record is compressed before it's encrypted, if encryption is
+//! also configured, and the second highest bit of the record metadata is set to flag the record as
+//! compressed.  See the `compression` module for the layout of compressed payloads.
+//!
+//! ### Encryption
+//!
+//! Record payloads can optionally be encrypted at rest.  When encryption keys are configured, the
+//! payload of each record is encrypted with AES-256-GCM, using a key that is derived from the active
+//! key whenever the writer opens a data file, and the highest bit of the record metadata is set to
+//! flag the record as encrypted.  See the `encryption` module for the layout of encrypted payloads.
+//!
 //! ## Writing records
 //!
 //! Records are added to a data file sequentially, and contiguously, with no gaps or data alignment
@@ -149,11 +163,15 @@ use snafu::{ResultExt, Snafu};
 mod acknowledgements;
 mod backed_archive;
 mod common;
+mod compression;
+mod encryption;
 mod io;
 mod ledger;
 mod reader;
 mod record;
 mod ser;
+#[cfg(target_os = "linux")]
+mod uring;
 mod v1_migration;
 mod writer;
 
@@ -165,6 +183,8 @@ use self::{
 };
 pub use self::{
     common::{DiskBufferConfig, DiskBufferConfigBuilder},
+    compression::DiskBufferCompression,
+    encryption::{EncryptionKeyError, EncryptionKeys},
     io::{Filesystem, ProductionFilesystem},
     ledger::LedgerLoadCreateError,
     reader::{Reader, ReaderError},
@@ -270,14 +290,27 @@ pub struct DiskV2Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: NonZeroU64,
+    encryption_keys: Option<EncryptionKeys>,
+    compression: DiskBufferCompression,
+    use_io_uring: bool,
 }
 
 impl DiskV2Buffer {
-    pub fn new(id: String, data_dir: PathBuf, max_size: NonZeroU64) -> Self {
+    pub fn new(
+        id: String,
+        data_dir: PathBuf,
+        max_size: NonZeroU64,
+        encryption_keys: Option<EncryptionKeys>,
+        compression: DiskBufferCompression,
+        use_io_uring: bool,
+    ) -> Self {
         Self {
             id,
             data_dir,
             max_size,
+            encryption_keys,
+            compression,
+            use_io_uring,
         }
     }
 }
@@ -298,7 +331,13 @@ where
     {
         // Attempt to migrate a disk v1 buffer based on the same data directory and buffer ID if one
         // exists. If one doesn't exist, then this method does nothing.
-        try_disk_v1_migration::<T>(self.data_dir.as_path(), self.id.as_str()).await?;
+        try_disk_v1_migration::<T>(
+            self.data_dir.as_path(),
+            self.id.as_str(),
+            self.encryption_keys.clone(),
+            self.compression,
+        )
+        .await?;
 
         // Now that we've handled any necessary migrations, go ahead and build the buffer.
         let (writer, reader, acker) = build_disk_v2_buffer(
@@ -306,6 +345,9 @@ where
             &self.data_dir,
             self.id.as_str(),
             self.max_size,
+            self.encryption_keys,
+            self.compression,
+            self.use_io_uring,
         )
         .await?;
 
@@ -318,6 +360,9 @@ async fn build_disk_v2_buffer<T>(
     data_dir: &Path,
     id: &str,
     max_size: NonZeroU64,
+    encryption_keys: Option<EncryptionKeys>,
+    compression: DiskBufferCompression,
+    use_io_uring: bool,
 ) -> Result<
     (
         Writer<T, ProductionFilesystem>,
@@ -332,9 +377,16 @@ where
     usage_handle.set_buffer_limits(Some(max_size.get()), None);
 
     let buffer_path = get_disk_v2_data_dir_path(data_dir, id);
-    let config = DiskBufferConfigBuilder::from_path(buffer_path)
+    let mut builder = DiskBufferConfigBuilder::from_path(buffer_path)
         .max_buffer_size(max_size.get())
-        .build()?;
+        .compression(compression);
+    if let Some(encryption_keys) = encryption_keys {
+        builder = builder.encryption_keys(encryption_keys);
+    }
+    if use_io_uring {
+        builder = builder.filesystem(ProductionFilesystem::with_io_uring());
+    }
+    let config = builder.build()?;
     Buffer::from_config(config, usage_handle)
         .await
         .map_err(Into::into)
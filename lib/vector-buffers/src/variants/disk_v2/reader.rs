@@ -14,6 +14,8 @@ use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 
 use super::{
     common::create_crc32c_hasher,
+    compression::{decompress, COMPRESSED_RECORD_FLAG},
+    encryption::{EncryptionKeys, RecordDecryptor, ENCRYPTED_RECORD_FLAG},
     ledger::Ledger,
     record::{validate_record_archive, ArchivedRecord, Record, RecordStatus},
     Filesystem,
@@ -107,6 +109,21 @@ where
     #[snafu(display("record version not compatible: {}", reason))]
     Incompatible { reason: String },
 
+    /// The record could not be decrypted.
+    ///
+    /// At this stage, the record can be assumed to have been written correctly, and read correctly
+    /// from disk, as the checksum was also validated.  This most likely indicates that the key the
+    /// record was encrypted with is no longer configured.
+    #[snafu(display("failed to decrypt record: {}", reason))]
+    Decryption { reason: String },
+
+    /// The record could not be decompressed.
+    ///
+    /// At this stage, the record can be assumed to have been written correctly, and read correctly
+    /// from disk, as the checksum was also validated.
+    #[snafu(display("failed to decompress record: {}", source))]
+    Decompression { source: io::Error },
+
     /// The reader detected that a data file contains a partially-written record.
     ///
     /// Records should never be partially written to a data file (we don't split records across data
@@ -144,6 +161,8 @@ where
             ReaderError::Checksum { .. } => "checksum_mismatch",
             ReaderError::Decode { .. } => "decode_failed",
             ReaderError::Incompatible { .. } => "incompatible_record_version",
+            ReaderError::Decryption { .. } => "decryption_failed",
+            ReaderError::Decompression { .. } => "decompression_failed",
             ReaderError::PartialWrite => "partial_write",
             ReaderError::EmptyRecord => "empty_record",
         }
@@ -159,6 +178,8 @@ where
             | ReaderError::Checksum { .. }
             | ReaderError::Decode { .. }
             | ReaderError::Incompatible { .. }
+            | ReaderError::Decryption { .. }
+            | ReaderError::Decompression { .. }
             | ReaderError::PartialWrite => Some(BufferReadError { error_code, error }),
         }
     }
@@ -185,7 +206,12 @@ impl<T: Bufferable> PartialEq for ReaderError<T> {
                 },
             ) => l_calculated == r_calculated && l_actual == r_actual,
             (Self::Decode { .. }, Self::Decode { .. }) => true,
-            (Self::Incompatible { reason: l_reason }, Self::Incompatible { reason: r_reason }) => {
+            (
+                Self::Decompression { source: l_source },
+                Self::Decompression { source: r_source },
+            ) => l_source.kind() == r_source.kind(),
+            (Self::Incompatible { reason: l_reason }, Self::Incompatible { reason: r_reason })
+            | (Self::Decryption { reason: l_reason }, Self::Decryption { reason: r_reason }) => {
                 l_reason == r_reason
             }
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
@@ -198,6 +224,7 @@ pub(super) struct RecordReader<R, T> {
     reader: BufReader<R>,
     aligned_buf: AlignedVec,
     checksummer: Hasher,
+    decryptor: Option<RecordDecryptor>,
     current_record_id: u64,
     _t: PhantomData<T>,
 }
@@ -216,11 +243,18 @@ where
             reader: BufReader::with_capacity(256 * 1024, reader),
             aligned_buf: AlignedVec::new(),
             checksummer: create_crc32c_hasher(),
+            decryptor: None,
             current_record_id: 0,
             _t: PhantomData,
         }
     }
 
+    /// Sets the decryptor used to decrypt encrypted record payloads.
+    pub fn with_decryptor(mut self, decryptor: Option<RecordDecryptor>) -> Self {
+        self.decryptor = decryptor;
+        self
+    }
+
     #[cfg_attr(test, instrument(skip(self), level = "trace"))]
     async fn read_length_delimiter(
         &mut self,
@@ -375,7 +409,7 @@ where
         // - `try_next_record` does all the archive checks, checksum validation, etc
         let record = unsafe { archived_root::<Record<'_>>(&self.aligned_buf) };
 
-        decode_record_payload(record)
+        decode_record_payload(record, self.decryptor.as_mut())
     }
 }
 
@@ -784,7 +818,13 @@ where
                 "Opened data file for reading."
             );
 
-            self.reader = Some(RecordReader::new(data_file));
+            let decryptor = self
+                .ledger
+                .config()
+                .encryption_keys
+                .as_ref()
+                .map(EncryptionKeys::decryptor);
+            self.reader = Some(RecordReader::new(data_file).with_decryptor(decryptor));
             return Ok(());
         }
     }
@@ -849,7 +889,13 @@ where
                 } => {
                     let record = try_as_record_archive(data_file_mmap.as_ref())
                         .expect("record was already validated");
-                    let item = match decode_record_payload::<T>(record) {
+                    let mut decryptor = self
+                        .ledger
+                        .config()
+                        .encryption_keys
+                        .as_ref()
+                        .map(EncryptionKeys::decryptor);
+                    let item = match decode_record_payload::<T>(record, decryptor.as_mut()) {
                         Ok(item) => item,
                         // If there's an error decoding the item, just fall back to the slow path,
                         // because this file might actually be where we left off, so we don't want
@@ -1097,10 +1143,17 @@ where
 
 pub(crate) fn decode_record_payload<T: Bufferable>(
     record: &ArchivedRecord<'_>,
+    decryptor: Option<&mut RecordDecryptor>,
 ) -> Result<T, ReaderError<T>> {
+    // The two highest bits of the record metadata are reserved for flagging compressed and
+    // encrypted records, so strip them before handing the metadata to `T`.
+    let is_compressed = record.metadata() & COMPRESSED_RECORD_FLAG != 0;
+    let is_encrypted = record.metadata() & ENCRYPTED_RECORD_FLAG != 0;
+    let raw_metadata = record.metadata() & !(COMPRESSED_RECORD_FLAG | ENCRYPTED_RECORD_FLAG);
+
     // Try and convert the raw record metadata into the true metadata type used by `T`, and then
     // also verify that `T` is able to decode records with the metadata used for this record in particular.
-    let metadata = T::Metadata::from_u32(record.metadata()).ok_or(ReaderError::Incompatible {
+    let metadata = T::Metadata::from_u32(raw_metadata).ok_or(ReaderError::Incompatible {
         reason: format!("invalid metadata for {}", std::any::type_name::<T>()),
     })?;
 
@@ -1108,11 +1161,31 @@ pub(crate) fn decode_record_payload<T: Bufferable>(
         return Err(ReaderError::Incompatible {
             reason: format!(
                 "record metadata not supported (metadata: {:#036b})",
-                record.metadata()
+                raw_metadata
             ),
         });
     }
 
+    // If the record is encrypted, we have to decrypt it first, which can only be done if the key it
+    // was encrypted with is still configured.
+    let mut payload = record.payload();
+    if is_encrypted {
+        let decryptor = decryptor.ok_or_else(|| ReaderError::Decryption {
+            reason: "record is encrypted but no encryption keys are configured".to_string(),
+        })?;
+        payload = decryptor
+            .open(record.id(), record.metadata(), payload)
+            .map_err(|e| ReaderError::Decryption {
+                reason: e.to_string(),
+            })?;
+    }
+
+    // Compressed records can always be decompressed, whether or not compression is enabled.
+    if is_compressed {
+        let decompressed = decompress(payload).context(DecompressionSnafu)?;
+        return T::decode(metadata, &decompressed[..]).context(DecodeSnafu);
+    }
+
     // Now we can finally try decoding.
-    T::decode(metadata, record.payload()).context(DecodeSnafu)
+    T::decode(metadata, payload).context(DecodeSnafu)
 }
@@ -123,6 +123,11 @@ impl<'a> Record<'a> {
 }
 
 impl<'a> ArchivedRecord<'a> {
+    /// Gets the ID of this record.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     /// Gets the metadata of this record.
     pub fn metadata(&self) -> u32 {
         self.metadata
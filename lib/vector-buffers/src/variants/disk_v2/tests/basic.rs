@@ -7,8 +7,12 @@ use tracing::Instrument;
 use super::create_default_buffer_v2;
 use crate::{
     assert_buffer_is_empty, assert_buffer_records,
+    buffer_usage_data::BufferUsageHandle,
     test::common::{install_tracing_helpers, with_temp_dir, MultiEventRecord, SizedRecord},
-    variants::disk_v2::{tests::create_default_buffer_v2_with_usage, writer::RecordWriter},
+    variants::disk_v2::{
+        tests::create_default_buffer_v2_with_usage, writer::RecordWriter, Buffer,
+        DiskBufferConfigBuilder, ProductionFilesystem,
+    },
     EventCount,
 };
 
@@ -66,6 +70,64 @@ async fn basic_read_write_loop() {
     .await;
 }
 
+#[tokio::test]
+async fn basic_read_write_loop_with_io_uring() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write the data files through an io_uring, which falls back to regular writes if the
+            // kernel running the tests doesn't support it.
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .filesystem(ProductionFilesystem::with_io_uring())
+                .build()
+                .expect("creating buffer should not fail");
+            let (mut writer, mut reader, acker, ledger) =
+                Buffer::from_config_inner(config, BufferUsageHandle::noop())
+                    .await
+                    .expect("should not fail to create buffer");
+            assert_buffer_is_empty!(ledger);
+
+            // Enough records to fill the writer's internal buffer, and the io_uring's buffers, a
+            // few times over.
+            let expected_items = (512..768)
+                .into_iter()
+                .cycle()
+                .take(1000)
+                .map(SizedRecord)
+                .collect::<Vec<_>>();
+            let input_items = expected_items.clone();
+
+            let write_task = tokio::spawn(async move {
+                for item in input_items {
+                    writer
+                        .write_record(item)
+                        .await
+                        .expect("write should not fail");
+                }
+                writer.flush().await.expect("writer flush should not fail");
+                writer.close();
+            });
+
+            let read_task = tokio::spawn(async move {
+                let mut items = Vec::new();
+                while let Some(record) = reader.next().await.expect("reader should not fail") {
+                    items.push(record);
+                    acker.ack(1);
+                }
+                items
+            });
+
+            write_task.await.expect("write task should not panic");
+            let actual_items = read_task.await.expect("read task should not panic");
+
+            assert_buffer_is_empty!(ledger);
+            assert_eq!(actual_items, expected_items);
+        }
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn reader_exits_cleanly_when_writer_done_and_in_flight_acks() {
     let assertion_registry = install_tracing_helpers();
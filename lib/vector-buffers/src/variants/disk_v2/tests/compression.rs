@@ -0,0 +1,146 @@
+use super::{create_buffer_v2_with_compression, create_default_buffer_v2};
+use crate::{
+    buffer_usage_data::BufferUsageHandle,
+    test::common::{with_temp_dir, SizedRecord},
+    variants::disk_v2::{Buffer, DiskBufferCompression, DiskBufferConfigBuilder, EncryptionKeys},
+};
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+async fn compressed_records_roundtrip(compression: DiskBufferCompression) {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, acker, ledger) =
+                create_buffer_v2_with_compression(data_dir, compression).await;
+
+            for _ in 0..4 {
+                writer
+                    .write_record(SizedRecord(8192))
+                    .await
+                    .expect("write should not fail");
+            }
+            writer.flush().await.expect("flush should not fail");
+
+            // `SizedRecord` encodes itself as a long run of `0x42` bytes, which compresses very
+            // well, so all four records should take up less space than a single uncompressed one.
+            let data_file = tokio::fs::metadata(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("metadata should not fail");
+            assert!(data_file.len() < 8192);
+
+            for _ in 0..4 {
+                let record = reader.next().await.expect("read should not fail");
+                assert_eq!(record, Some(SizedRecord(8192)));
+            }
+            acker.ack(4);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn lz4_compressed_records_roundtrip() {
+    compressed_records_roundtrip(DiskBufferCompression::Lz4).await;
+}
+
+#[tokio::test]
+async fn zstd_compressed_records_roundtrip() {
+    compressed_records_roundtrip(DiskBufferCompression::Zstd).await;
+}
+
+#[tokio::test]
+async fn incompressible_records_are_written_uncompressed() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // A zero-length `SizedRecord` is only four bytes when encoded, which can't be made any
+            // smaller, so it should be written as-is and still be readable.
+            let (mut writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_compression(data_dir, DiskBufferCompression::Zstd).await;
+            writer
+                .write_record(SizedRecord(0))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(0)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn compressed_records_are_readable_after_disabling_compression() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_compression(data_dir.clone(), DiskBufferCompression::Lz4)
+                    .await;
+            writer
+                .write_record(SizedRecord(1024))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            let (mut writer, mut reader, acker, _ledger) = create_default_buffer_v2(data_dir).await;
+            writer
+                .write_record(SizedRecord(2048))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            let first = reader.next().await.expect("read should not fail");
+            assert_eq!(first, Some(SizedRecord(1024)));
+            let second = reader.next().await.expect("read should not fail");
+            assert_eq!(second, Some(SizedRecord(2048)));
+            acker.ack(2);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn compressed_records_can_be_encrypted() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .compression(DiskBufferCompression::Zstd)
+                .encryption_keys(EncryptionKeys::from_hex(&[KEY]).expect("key should be valid"))
+                .build()
+                .expect("creating buffer should not fail");
+            let (mut writer, mut reader, acker, ledger) =
+                Buffer::<SizedRecord>::from_config_inner(config, BufferUsageHandle::noop())
+                    .await
+                    .expect("should not fail to create buffer");
+
+            writer
+                .write_record(SizedRecord(8192))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            // The record is compressed before it's encrypted, so it should still take up far less
+            // space than the uncompressed record would.
+            let data_file = tokio::fs::metadata(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("metadata should not fail");
+            assert!(data_file.len() < 8192);
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(8192)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
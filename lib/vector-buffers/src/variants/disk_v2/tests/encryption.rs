@@ -0,0 +1,189 @@
+use super::{create_buffer_v2_with_encryption_keys, create_default_buffer_v2};
+use crate::{
+    buffer_usage_data::BufferUsageHandle,
+    test::common::{with_temp_dir, SizedRecord},
+    variants::disk_v2::{
+        reader::RecordReader, writer::RecordWriter, Buffer, BufferError, DiskBufferConfigBuilder,
+        EncryptionKeys, ReaderError,
+    },
+};
+
+const KEY_A: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const KEY_B: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+fn keys(keys: &[&str]) -> EncryptionKeys {
+    EncryptionKeys::from_hex(keys).expect("keys should be valid")
+}
+
+#[test]
+fn invalid_encryption_keys_are_rejected() {
+    let no_keys: &[&str] = &[];
+    assert!(EncryptionKeys::from_hex(no_keys).is_err());
+    assert!(EncryptionKeys::from_hex(&[&KEY_A[2..]]).is_err());
+    assert!(EncryptionKeys::from_hex(&[KEY_A.replace('0', "g")]).is_err());
+    assert!(EncryptionKeys::from_hex(&[KEY_A, "+f"]).is_err());
+}
+
+#[tokio::test]
+async fn encrypted_records_are_not_written_in_plaintext() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, acker, ledger) =
+                create_buffer_v2_with_encryption_keys(data_dir, keys(&[KEY_A])).await;
+
+            writer
+                .write_record(SizedRecord(512))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            // `SizedRecord` encodes itself as a long run of `0x42` bytes, which should not be
+            // anywhere in the data file once encrypted.
+            let data_file = tokio::fs::read(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("read should not fail");
+            assert!(!data_file
+                .windows(64)
+                .any(|window| window.iter().all(|b| *b == 0x42)));
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(512)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn records_written_with_previous_key_are_readable_after_rotation() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a record with the original key, and then close the buffer.
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption_keys(data_dir.clone(), keys(&[KEY_A])).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // Now reopen it with a new active key, keeping the original key for decryption, and
+            // make sure records written with either key can be read.
+            let (mut writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_encryption_keys(data_dir, keys(&[KEY_B, KEY_A])).await;
+            writer
+                .write_record(SizedRecord(128))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            let first = reader.next().await.expect("read should not fail");
+            assert_eq!(first, Some(SizedRecord(64)));
+            let second = reader.next().await.expect("read should not fail");
+            assert_eq!(second, Some(SizedRecord(128)));
+            acker.ack(2);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn plaintext_records_are_readable_after_enabling_encryption() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _, _, ledger) = create_default_buffer_v2(data_dir.clone()).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            let (_writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_encryption_keys(data_dir, keys(&[KEY_A])).await;
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(64)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn buffer_fails_to_open_when_encryption_key_is_missing() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption_keys(data_dir.clone(), keys(&[KEY_A])).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // The writer can't validate its last write without the original key, so the buffer
+            // should refuse to open rather than silently dropping the records.
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .encryption_keys(keys(&[KEY_B]))
+                .build()
+                .expect("creating buffer should not fail");
+            let result =
+                Buffer::<SizedRecord>::from_config_inner(config, BufferUsageHandle::noop()).await;
+            assert!(matches!(result, Err(BufferError::WriterSeekFailed { .. })));
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn record_reader_throws_error_when_record_cannot_be_decrypted() {
+    let (writer_io, reader_io) = tokio::io::duplex(4096);
+
+    let cipher = keys(&[KEY_A])
+        .data_file_cipher()
+        .expect("cipher should not fail");
+    let mut record_writer =
+        RecordWriter::new(writer_io, 0, 16_384, u64::MAX, 2048).with_cipher(Some(cipher));
+    let mut record_reader: RecordReader<_, SizedRecord> =
+        RecordReader::new(reader_io).with_decryptor(Some(keys(&[KEY_B]).decryptor()));
+
+    for _ in 0..2 {
+        record_writer
+            .write_record(314, SizedRecord(73))
+            .await
+            .expect("write should not fail");
+    }
+    record_writer.flush().await.expect("flush should not fail");
+
+    // Without the key the record was encrypted with, the record can't be decrypted...
+    let read_token = record_reader
+        .try_next_record(false)
+        .await
+        .expect("read should not fail")
+        .expect("record should be present");
+    let read_result = record_reader.read_record(read_token);
+    assert!(matches!(read_result, Err(ReaderError::Decryption { .. })));
+
+    // ...and likewise without any keys at all.
+    let mut record_reader = record_reader.with_decryptor(None);
+    let read_token = record_reader
+        .try_next_record(false)
+        .await
+        .expect("read should not fail")
+        .expect("record should be present");
+    let read_result = record_reader.read_record(read_token);
+    assert!(matches!(read_result, Err(ReaderError::Decryption { .. })));
+}
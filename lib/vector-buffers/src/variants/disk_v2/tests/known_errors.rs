@@ -19,7 +19,7 @@ use crate::{
     encoding::{AsMetadata, Encodable},
     test::common::{install_tracing_helpers, with_temp_dir, SizedRecord, UndecodableRecord},
     variants::disk_v2::{backed_archive::BackedArchive, record::Record, ReaderError},
-    EventCount,
+    EventCount, EventPriority, Priority,
 };
 
 #[tokio::test]
@@ -739,6 +739,12 @@ async fn reader_throws_error_when_record_is_undecodable_via_metadata() {
         }
     }
 
+    impl EventPriority for ControllableRecord {
+        fn event_priority(&self) -> Priority {
+            Priority::Normal
+        }
+    }
+
     with_temp_dir(|dir| {
         let data_dir = dir.to_path_buf();
 
@@ -9,7 +9,7 @@ use tokio::io::DuplexStream;
 
 use super::{
     io::{AsyncFile, Metadata, ProductionFilesystem, ReadableMemoryMap, WritableMemoryMap},
-    Buffer, DiskBufferConfigBuilder, Ledger, Reader, Writer,
+    Buffer, DiskBufferCompression, DiskBufferConfigBuilder, EncryptionKeys, Ledger, Reader, Writer,
 };
 use crate::{buffer_usage_data::BufferUsageHandle, Acker, Bufferable};
 
@@ -17,6 +17,8 @@ type FilesystemUnderTest = ProductionFilesystem;
 
 mod acknowledgements;
 mod basic;
+mod compression;
+mod encryption;
 mod invariants;
 mod known_errors;
 mod model;
@@ -307,3 +309,51 @@ where
         .await
         .expect("should not fail to create buffer")
 }
+
+pub(crate) async fn create_buffer_v2_with_encryption_keys<P, R>(
+    data_dir: P,
+    encryption_keys: EncryptionKeys,
+) -> (
+    Writer<R, FilesystemUnderTest>,
+    Reader<R, FilesystemUnderTest>,
+    Acker,
+    Arc<Ledger<FilesystemUnderTest>>,
+)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let config = DiskBufferConfigBuilder::from_path(data_dir)
+        .encryption_keys(encryption_keys)
+        .build()
+        .expect("creating buffer should not fail");
+    let usage_handle = BufferUsageHandle::noop();
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
+
+pub(crate) async fn create_buffer_v2_with_compression<P, R>(
+    data_dir: P,
+    compression: DiskBufferCompression,
+) -> (
+    Writer<R, FilesystemUnderTest>,
+    Reader<R, FilesystemUnderTest>,
+    Acker,
+    Arc<Ledger<FilesystemUnderTest>>,
+)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let config = DiskBufferConfigBuilder::from_path(data_dir)
+        .compression(compression)
+        .build()
+        .expect("creating buffer should not fail");
+    let usage_handle = BufferUsageHandle::noop();
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
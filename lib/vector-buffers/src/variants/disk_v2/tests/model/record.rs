@@ -3,7 +3,7 @@ use std::{error, fmt, mem};
 use bytes::{Buf, BufMut};
 use vector_common::byte_size_of::ByteSizeOf;
 
-use crate::{encoding::FixedEncodable, EventCount};
+use crate::{encoding::FixedEncodable, EventCount, EventPriority, Priority};
 
 #[derive(Debug)]
 pub struct EncodeError;
@@ -58,6 +58,12 @@ impl EventCount for Record {
     }
 }
 
+impl EventPriority for Record {
+    fn event_priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
 impl ByteSizeOf for Record {
     fn allocated_bytes(&self) -> usize {
         0
@@ -0,0 +1,687 @@
+//! A minimal `io_uring`, used to write data files with fewer system calls.
+//!
+//! Writes are copied into buffers registered with the kernel once, so they aren't mapped again on
+//! every write.  A buffer is written at its offset in the file once it's full, or flushed, while the
+//! next buffer is filled, so that several writes can be in flight at once.
+
+use std::{
+    fmt,
+    fs::File,
+    future::Future,
+    io,
+    mem::size_of,
+    os::unix::{
+        fs::FileExt,
+        io::{AsRawFd, RawFd},
+    },
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+    task::{Context, Poll},
+};
+
+use futures::ready;
+use tokio::task::{spawn_blocking, JoinHandle};
+
+/// The number of buffers registered with the kernel, which is the most writes in flight at once.
+pub(super) const RING_ENTRIES: u32 = 4;
+
+/// The size of each registered buffer.
+pub(super) const RING_BUFFER_SIZE: usize = 64 * 1024;
+
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_CQ_RING: libc::off_t = 0x800_0000;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+const IORING_FEAT_SINGLE_MMAP: u32 = 1;
+const IORING_ENTER_GETEVENTS: u32 = 1;
+const IORING_REGISTER_BUFFERS: u32 = 0;
+const IORING_OP_WRITE_FIXED: u8 = 5;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+#[allow(dead_code)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    resv2: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+#[allow(dead_code)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    resv2: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    rw_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    pad: [u64; 2],
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+/// A memory mapping shared with the kernel, unmapped when dropped.
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: RawFd, len: usize, offset: libc::off_t) -> io::Result<Self> {
+        // SAFETY: a new mapping is created, which doesn't alias any memory of the process.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                offset,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self { ptr, len })
+        }
+    }
+
+    /// Returns a pointer to the value at `offset` in the mapping.
+    fn at<T>(&self, offset: u32) -> *mut T {
+        debug_assert!(offset as usize + size_of::<T>() <= self.len);
+        // SAFETY: the offsets are given by the kernel, and are within the mapping.
+        unsafe { self.ptr.cast::<u8>().add(offset as usize).cast() }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        // SAFETY: the mapping is no longer used once it's dropped.
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// An `io_uring` with registered buffers.
+pub(super) struct Ring {
+    fd: RawFd,
+    // The io_uring is closed when the ring is dropped, before its mappings and buffers are.
+    sq_ring: Mmap,
+    cq_ring: Option<Mmap>,
+    sqes: Mmap,
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+    buffers: Vec<Box<[u8]>>,
+    /// Whether writes may still be in flight after failing to submit or wait for them, in which
+    /// case the ring can't be used anymore, and its buffers are leaked rather than freed.
+    broken: bool,
+}
+
+// SAFETY: the mappings and buffers are owned by the ring, and only used through `&mut self`.
+unsafe impl Send for Ring {}
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    /// Sets up an `io_uring` with `entries` registered buffers of `buffer_size` bytes each, which
+    /// may fail on kernels without `io_uring` or when the limit of locked memory is too low.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(super) fn new(entries: u32, buffer_size: usize) -> io::Result<Self> {
+        let mut params = Params::default();
+        // SAFETY: `params` is a valid `io_uring_params` structure.
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_setup,
+                entries,
+                std::ptr::addr_of_mut!(params),
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = fd as RawFd;
+
+        let mut ring = match Self::map(fd, &params) {
+            Ok(ring) => ring,
+            Err(error) => {
+                // SAFETY: the io_uring isn't used anymore.
+                unsafe { libc::close(fd) };
+                return Err(error);
+            }
+        };
+        // The io_uring is closed along with the ring if this fails.
+        ring.register_buffers(entries as usize, buffer_size)?;
+        Ok(ring)
+    }
+
+    fn map(fd: RawFd, params: &Params) -> io::Result<Self> {
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * size_of::<u32>();
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * size_of::<Cqe>();
+        // Recent kernels map both rings at once.
+        let single_mmap = params.features & IORING_FEAT_SINGLE_MMAP != 0;
+        let sq_ring = Mmap::new(
+            fd,
+            if single_mmap {
+                sq_len.max(cq_len)
+            } else {
+                sq_len
+            },
+            IORING_OFF_SQ_RING,
+        )?;
+        let cq_ring = if single_mmap {
+            None
+        } else {
+            Some(Mmap::new(fd, cq_len, IORING_OFF_CQ_RING)?)
+        };
+        let sqes = Mmap::new(
+            fd,
+            params.sq_entries as usize * size_of::<Sqe>(),
+            IORING_OFF_SQES,
+        )?;
+
+        Ok(Self {
+            fd,
+            sq_ring,
+            cq_ring,
+            sqes,
+            sq_off: params.sq_off,
+            cq_off: params.cq_off,
+            buffers: Vec::new(),
+            broken: false,
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn register_buffers(&mut self, count: usize, size: usize) -> io::Result<()> {
+        let buffers: Vec<Box<[u8]>> = (0..count)
+            .map(|_| vec![0; size].into_boxed_slice())
+            .collect();
+        let iovecs: Vec<libc::iovec> = buffers
+            .iter()
+            .map(|buffer| libc::iovec {
+                iov_base: buffer.as_ptr() as *mut libc::c_void,
+                iov_len: buffer.len(),
+            })
+            .collect();
+        // SAFETY: the buffers outlive the io_uring, as they're dropped after it's closed.
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_register,
+                self.fd,
+                IORING_REGISTER_BUFFERS,
+                iovecs.as_ptr(),
+                iovecs.len() as u32,
+            )
+        };
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.buffers = buffers;
+        Ok(())
+    }
+
+    /// Queues a write of the first `len` bytes of the buffer `index` at `offset` in the file `fd`,
+    /// and submits it.
+    ///
+    /// The submission queue has an entry per buffer, so it can't overflow as long as a buffer
+    /// isn't written again before its previous write completed.
+    #[allow(clippy::cast_possible_truncation)]
+    fn submit_write(&mut self, fd: RawFd, index: usize, offset: u64, len: usize) -> io::Result<()> {
+        let ring = &self.sq_ring;
+        let mask = unsafe { *ring.at::<u32>(self.sq_off.ring_mask) };
+        let tail = unsafe { &*ring.at::<AtomicU32>(self.sq_off.tail) };
+        let array = ring.at::<u32>(self.sq_off.array);
+        let sqes = self.sqes.at::<Sqe>(0);
+        let buffer = &self.buffers[index];
+
+        let next = tail.load(Ordering::Relaxed);
+        let slot = next & mask;
+        // SAFETY: `slot` is within the submission queue, and the kernel consumed the entry it
+        // held before, since every write is submitted before the next one is queued.
+        unsafe {
+            ptr::write(
+                sqes.add(slot as usize),
+                Sqe {
+                    opcode: IORING_OP_WRITE_FIXED,
+                    flags: 0,
+                    ioprio: 0,
+                    fd,
+                    off: offset,
+                    addr: buffer.as_ptr() as u64,
+                    len: len.min(buffer.len()) as u32,
+                    rw_flags: 0,
+                    user_data: index as u64,
+                    buf_index: index as u16,
+                    personality: 0,
+                    splice_fd_in: 0,
+                    pad: [0; 2],
+                },
+            );
+            *array.add(slot as usize) = slot;
+        }
+        tail.store(next.wrapping_add(1), Ordering::Release);
+
+        while self.enter(1, 0)? == 0 {}
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn enter(&mut self, to_submit: u32, min_complete: u32) -> io::Result<usize> {
+        loop {
+            // SAFETY: no signal mask is passed.
+            let res = unsafe {
+                libc::syscall(
+                    libc::SYS_io_uring_enter,
+                    self.fd,
+                    to_submit,
+                    min_complete,
+                    if min_complete > 0 {
+                        IORING_ENTER_GETEVENTS
+                    } else {
+                        0
+                    },
+                    ptr::null::<libc::sigset_t>(),
+                    0,
+                )
+            };
+            if res >= 0 {
+                return Ok(res as usize);
+            }
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                // Whether the write was submitted or not is unknown.
+                self.broken = true;
+                return Err(error);
+            }
+        }
+    }
+
+    /// Waits for at least one write to complete, calling `done` with the buffer and result of
+    /// each completed write.
+    #[allow(clippy::cast_possible_truncation)]
+    fn wait(&mut self, mut done: impl FnMut(usize, i32)) -> io::Result<()> {
+        self.enter(0, 1)?;
+
+        let ring = self.cq_ring.as_ref().unwrap_or(&self.sq_ring);
+        let mask = unsafe { *ring.at::<u32>(self.cq_off.ring_mask) };
+        let head = unsafe { &*ring.at::<AtomicU32>(self.cq_off.head) };
+        let tail = unsafe { &*ring.at::<AtomicU32>(self.cq_off.tail) };
+        let cqes = ring.at::<Cqe>(self.cq_off.cqes);
+
+        let mut next = head.load(Ordering::Relaxed);
+        let end = tail.load(Ordering::Acquire);
+        while next != end {
+            // SAFETY: entries between the head and the tail were written by the kernel.
+            let cqe = unsafe { ptr::read(cqes.add((next & mask) as usize)) };
+            done(cqe.user_data as usize, cqe.res);
+            next = next.wrapping_add(1);
+        }
+        head.store(next, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        if self.broken {
+            for buffer in self.buffers.drain(..) {
+                std::mem::forget(buffer);
+            }
+        }
+        // SAFETY: the io_uring isn't used anymore. Its mappings are unmapped once the fields are
+        // dropped, which the kernel allows after it's closed.
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+fn broken_ring() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "io_uring is broken")
+}
+
+/// Writes a file through a [`Ring`], appending to it.
+///
+/// This blocks while waiting for writes to complete, so it's used from blocking threads.
+pub(super) struct RingWriter {
+    ring: Ring,
+    file: File,
+    /// The offset in the file the next buffer is written at.
+    offset: u64,
+    /// The buffer being filled.
+    current: usize,
+    /// How many bytes of the current buffer are filled.
+    filled: usize,
+    /// The offset and length of the write of each buffer that's in flight.
+    in_flight: Vec<Option<(u64, usize)>>,
+}
+
+impl RingWriter {
+    /// Creates a writer appending to `file`, which is `len` bytes long.
+    pub(super) fn new(ring: Ring, file: File, len: u64) -> Self {
+        let in_flight = vec![None; ring.buffers.len()];
+        Self {
+            ring,
+            file,
+            offset: len,
+            current: 0,
+            filled: 0,
+            in_flight,
+        }
+    }
+
+    fn buffer_size(&self) -> usize {
+        self.ring.buffers[self.current].len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.filled == self.buffer_size()
+    }
+
+    /// Whether everything that was written made it to the file.
+    fn is_flushed(&self) -> bool {
+        self.filled == 0 && self.in_flight.iter().all(Option::is_none)
+    }
+
+    /// Copies as much of `buf` as fits into the current buffer, returning how many bytes were.
+    fn fill(&mut self, buf: &[u8]) -> usize {
+        let len = buf.len().min(self.buffer_size() - self.filled);
+        self.ring.buffers[self.current][self.filled..self.filled + len]
+            .copy_from_slice(&buf[..len]);
+        self.filled += len;
+        len
+    }
+
+    /// Submits the write of the current buffer, and waits for the next buffer to be written, if
+    /// it's still in flight, so that it can be filled.
+    fn write_buffer(&mut self) -> io::Result<()> {
+        if self.ring.broken {
+            return Err(broken_ring());
+        }
+        if self.filled > 0 {
+            self.ring.submit_write(
+                self.file.as_raw_fd(),
+                self.current,
+                self.offset,
+                self.filled,
+            )?;
+            self.in_flight[self.current] = Some((self.offset, self.filled));
+            self.offset += self.filled as u64;
+            self.filled = 0;
+            self.current = (self.current + 1) % self.in_flight.len();
+        }
+        self.wait_until(|writer| writer.in_flight[writer.current].is_none())
+    }
+
+    /// Writes the current buffer, and waits for all the writes in flight to complete.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.wait_until(|writer| writer.in_flight.iter().all(Option::is_none))
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn wait_until(&mut self, done: impl Fn(&Self) -> bool) -> io::Result<()> {
+        while !done(self) {
+            let mut completed = Vec::new();
+            self.ring.wait(|index, res| completed.push((index, res)))?;
+            for (index, res) in completed {
+                let (offset, len) = self.in_flight[index]
+                    .take()
+                    .expect("completed write is in flight");
+                if res < 0 {
+                    return Err(io::Error::from_raw_os_error(-res));
+                }
+                let written = res as usize;
+                if written < len {
+                    // Short writes are rare for regular files, the rest is written directly.
+                    self.file.write_all_at(
+                        &self.ring.buffers[index][written..len],
+                        offset + written as u64,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RingWriter {
+    fn drop(&mut self) {
+        // The buffers can't be freed while they're written, so whatever is left is written, and
+        // the writes in flight are waited for.
+        if !self.ring.broken && self.flush().is_err() && !self.is_flushed() {
+            self.ring.broken = true;
+        }
+    }
+}
+
+enum State {
+    /// The writer isn't used by a blocking thread, if it wasn't lost to a panic.
+    Idle(Option<Box<RingWriter>>),
+    /// The writer is used by a blocking thread to write or flush its buffers.
+    Busy(JoinHandle<(Box<RingWriter>, io::Result<()>)>),
+}
+
+/// Writes a file through a [`RingWriter`] from asynchronous code.
+///
+/// Like Tokio's `File`, a write returns once its data is copied into the current buffer, and an
+/// error writing the buffer is returned by the next write or flush.
+pub(super) struct RingFile {
+    state: State,
+}
+
+impl RingFile {
+    pub(super) fn new(writer: RingWriter) -> Self {
+        Self {
+            state: State::Idle(Some(Box::new(writer))),
+        }
+    }
+
+    /// Runs `op` with the writer in a blocking thread.
+    fn start(&mut self, op: fn(&mut RingWriter) -> io::Result<()>) {
+        if let State::Idle(writer) = &mut self.state {
+            if let Some(mut writer) = writer.take() {
+                self.state = State::Busy(spawn_blocking(move || {
+                    let result = op(&mut writer);
+                    (writer, result)
+                }));
+            }
+        }
+    }
+
+    /// Waits for the blocking thread to be done with the writer.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&mut RingWriter>> {
+        if let State::Busy(handle) = &mut self.state {
+            let result = ready!(Pin::new(handle).poll(cx));
+            let (writer, result) = match result {
+                Ok((writer, result)) => (Some(writer), result),
+                Err(error) => (None, Err(io::Error::new(io::ErrorKind::Other, error))),
+            };
+            self.state = State::Idle(writer);
+            result?;
+        }
+        match &mut self.state {
+            State::Idle(Some(writer)) => Poll::Ready(Ok(writer)),
+            _ => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Other,
+                "io_uring writer was lost",
+            ))),
+        }
+    }
+
+    pub(super) fn poll_write(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let writer = ready!(self.poll_idle(cx))?;
+            if writer.is_full() {
+                // The last attempt to write the buffer failed.
+                self.start(RingWriter::write_buffer);
+                continue;
+            }
+
+            let written = writer.fill(buf);
+            if writer.is_full() {
+                self.start(RingWriter::write_buffer);
+            }
+            return Poll::Ready(Ok(written));
+        }
+    }
+
+    pub(super) fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            if ready!(self.poll_idle(cx))?.is_flushed() {
+                return Poll::Ready(Ok(()));
+            }
+            self.start(RingWriter::flush);
+        }
+    }
+}
+
+impl Drop for RingFile {
+    fn drop(&mut self) {
+        // Dropping the writer waits for its writes, which is left to a blocking thread if possible.
+        if let State::Idle(Some(writer)) = &self.state {
+            if !writer.is_flushed() {
+                if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                    let state = std::mem::replace(&mut self.state, State::Idle(None));
+                    handle.spawn_blocking(move || drop(state));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RingFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingFile")
+            .field("busy", &matches!(self.state, State::Busy(_)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use temp_dir::TempDir;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    struct TestFile(RingFile);
+
+    impl tokio::io::AsyncWrite for TestFile {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.get_mut().0.poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().0.poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().0.poll_flush(cx)
+        }
+    }
+
+    fn open_writer(dir: &TempDir, entries: u32, buffer_size: usize) -> Option<RingWriter> {
+        // The kernel running the tests may not support io_uring.
+        let ring = Ring::new(entries, buffer_size).ok()?;
+        let path = dir.child("data");
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&path)
+            .unwrap();
+        let len = file.metadata().unwrap().len();
+        Some(RingWriter::new(ring, file, len))
+    }
+
+    #[tokio::test]
+    async fn appends_in_batches_of_buffers() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.child("data"), b"existing\n").unwrap();
+        let mut file = match open_writer(&dir, 2, 16) {
+            Some(writer) => TestFile(RingFile::new(writer)),
+            None => return,
+        };
+
+        let mut expected = b"existing\n".to_vec();
+        for i in 0..20 {
+            let line = format!("line {}\n", i);
+            file.write_all(line.as_bytes()).await.unwrap();
+            expected.extend_from_slice(line.as_bytes());
+        }
+        file.flush().await.unwrap();
+        assert_eq!(fs::read(dir.child("data")).unwrap(), expected);
+    }
+
+    #[test]
+    fn writes_the_rest_when_dropped() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = match open_writer(&dir, 2, 16) {
+            Some(writer) => writer,
+            None => return,
+        };
+
+        assert_eq!(writer.fill(b"rest\n"), 5);
+        drop(writer);
+        assert_eq!(fs::read(dir.child("data")).unwrap(), b"rest\n");
+    }
+}
@@ -25,6 +25,8 @@ use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use super::{
     common::{create_crc32c_hasher, DiskBufferConfig},
+    compression::{DiskBufferCompression, COMPRESSED_RECORD_FLAG},
+    encryption::{EncryptionKeys, RecordCipher, ENCRYPTED_RECORD_FLAG},
     io::Filesystem,
     ledger::Ledger,
     record::{validate_record_archive, Record, RecordStatus},
@@ -105,6 +107,17 @@ where
     #[snafu(display("failed to serialize encoded record to buffer: {}", reason))]
     FailedToSerialize { reason: String },
 
+    /// The writer failed to compress the record.
+    #[snafu(display("failed to compress encoded record: {}", source))]
+    FailedToCompress { source: io::Error },
+
+    /// The writer failed to encrypt the record.
+    ///
+    /// Encrypting a record can only fail if the system is unable to provide random bytes for the
+    /// nonce, or if the record is too large for AES-GCM, which is far above any record size limit.
+    #[snafu(display("failed to encrypt encoded record"))]
+    FailedToEncrypt,
+
     /// The writer failed to validate the last written record.
     ///
     /// Specifically, for `Writer`, this can only ever be returned when creating the buffer, during
@@ -279,7 +292,7 @@ impl<W: AsyncWrite + Unpin> TrackingBufWriter<W> {
         Ok(flush_result)
     }
 
-    /// Flushes the internal buffer to the underlying writer.
+    /// Flushes the internal buffer to the underlying writer, and then flushes the underlying writer.
     ///
     /// Internally, a counter is kept of how many buffered events are waiting to be flushed. This
     /// count is incremented every time `write` can fully buffer the record without having to flush
@@ -300,7 +313,10 @@ impl<W: AsyncWrite + Unpin> TrackingBufWriter<W> {
         let events_flushed = self.unflushed_events as u64;
         let bytes_flushed = self.buf.len() as u64;
 
-        let result = self.inner.write_all(&self.buf[..]).await;
+        let result = match self.inner.write_all(&self.buf[..]).await {
+            Ok(()) => self.inner.flush().await,
+            Err(error) => Err(error),
+        };
         self.unflushed_events = 0;
         self.buf.clear();
 
@@ -342,6 +358,10 @@ impl<W: fmt::Debug> fmt::Debug for TrackingBufWriter<W> {
 pub(super) struct RecordWriter<W, T> {
     writer: TrackingBufWriter<W>,
     encode_buf: Vec<u8>,
+    compress_buf: Vec<u8>,
+    compression: DiskBufferCompression,
+    encrypt_buf: Vec<u8>,
+    cipher: Option<RecordCipher>,
     ser_buf: AlignedVec,
     ser_scratch: AlignedVec,
     checksummer: Hasher,
@@ -370,6 +390,10 @@ where
         Self {
             writer: TrackingBufWriter::with_capacity(write_buffer_size, writer),
             encode_buf: Vec::with_capacity(16_384),
+            compress_buf: Vec::new(),
+            compression: DiskBufferCompression::None,
+            encrypt_buf: Vec::new(),
+            cipher: None,
             ser_buf: AlignedVec::with_capacity(16_384),
             ser_scratch: AlignedVec::with_capacity(16_384),
             checksummer: create_crc32c_hasher(),
@@ -380,6 +404,18 @@ where
         }
     }
 
+    /// Sets the compression applied to record payloads before they're archived.
+    pub fn with_compression(mut self, compression: DiskBufferCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the cipher used to encrypt record payloads before they're archived.
+    pub fn with_cipher(mut self, cipher: Option<RecordCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Gets a reference to the underlying writer.
     #[cfg(test)]
     pub fn get_ref(&self) -> &W {
@@ -439,9 +475,37 @@ where
             });
         }
 
-        let metadata = T::get_metadata().into_u32();
-        let wrapped_record =
-            Record::with_checksum(id, metadata, &self.encode_buf, &self.checksummer);
+        let mut metadata = T::get_metadata().into_u32();
+        debug_assert_eq!(
+            metadata & (COMPRESSED_RECORD_FLAG | ENCRYPTED_RECORD_FLAG),
+            0,
+            "two highest bits of record metadata are reserved for the buffer"
+        );
+
+        // If compression is enabled, and it actually made the record smaller, the compressed form
+        // of the record is what gets archived, and we flag the record as compressed so that the
+        // reader knows to decompress it.
+        let mut payload = &self.encode_buf[..];
+        if self
+            .compression
+            .compress(payload, &mut self.compress_buf)
+            .context(FailedToCompressSnafu)?
+        {
+            metadata |= COMPRESSED_RECORD_FLAG;
+            payload = &self.compress_buf[..];
+        }
+
+        // Likewise, if encryption is enabled, the encrypted form of the record is what gets
+        // archived, and we flag the record as encrypted so that the reader knows to decrypt it.
+        if let Some(cipher) = self.cipher.as_ref() {
+            metadata |= ENCRYPTED_RECORD_FLAG;
+            cipher
+                .seal(id, metadata, payload, &mut self.encrypt_buf)
+                .map_err(|_| WriterError::FailedToEncrypt)?;
+            payload = &self.encrypt_buf[..];
+        }
+
+        let wrapped_record = Record::with_checksum(id, metadata, payload, &self.checksummer);
 
         // Push 8 dummy bytes where our length delimiter will sit.  We'll fix this up after
         // serialization.  Notably, `AlignedSerializer` will report the serializer position as
@@ -752,7 +816,12 @@ where
                 // next writer record ID should be.
                 let record = try_as_record_archive(data_file_mmap.as_ref())
                     .expect("record was already validated");
-                let item = decode_record_payload::<T>(record).map_err(|e| {
+                let mut decryptor = self
+                    .config
+                    .encryption_keys
+                    .as_ref()
+                    .map(EncryptionKeys::decryptor);
+                let item = decode_record_payload::<T>(record, decryptor.as_mut()).map_err(|e| {
                     WriterError::FailedToValidate {
                         reason: e.to_string(),
                     }
@@ -990,13 +1059,32 @@ where
                 // Make sure the file is flushed to disk, especially if we just created it.
                 data_file.sync_all().await?;
 
-                self.writer = Some(RecordWriter::new(
-                    data_file,
-                    data_file_size,
-                    self.config.write_buffer_size,
-                    self.config.max_data_file_size,
-                    self.config.max_record_size,
-                ));
+                // Every data file we open gets its own encryption key, which is derived from the
+                // currently active key, so that keys are rotated whenever we roll over.
+                let cipher = self
+                    .config
+                    .encryption_keys
+                    .as_ref()
+                    .map(EncryptionKeys::data_file_cipher)
+                    .transpose()
+                    .map_err(|_| {
+                        io::Error::new(
+                            ErrorKind::Other,
+                            "failed to generate data file encryption key",
+                        )
+                    })?;
+
+                self.writer = Some(
+                    RecordWriter::new(
+                        data_file,
+                        data_file_size,
+                        self.config.write_buffer_size,
+                        self.config.max_data_file_size,
+                        self.config.max_record_size,
+                    )
+                    .with_compression(self.config.compression)
+                    .with_cipher(cipher),
+                );
                 self.data_file_size = data_file_size;
 
                 // If we opened the "next" data file, we need to increment the current writer
@@ -1021,6 +1109,12 @@ where
         }
     }
 
+    /// Gets whether or not at least half of the maximum buffer size is in use.
+    pub fn is_buffer_half_full(&self) -> bool {
+        let total_buffer_size = self.ledger.get_total_buffer_size() + self.unflushed_bytes;
+        total_buffer_size * 2 >= self.config.max_buffer_size
+    }
+
     /// Attempts to write a record.
     ///
     /// If the buffer is currently full, the original record will be immediately returned.
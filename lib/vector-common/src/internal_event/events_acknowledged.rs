@@ -0,0 +1,36 @@
+use metrics::counter;
+use tracing::trace;
+
+use crate::{finalization::EventStatus, internal_event::InternalEvent};
+
+/// The outcome of a sink's attempt to deliver events, once their status is known.
+#[derive(Debug)]
+pub struct EventsAcknowledged {
+    pub count: usize,
+    pub status: EventStatus,
+    pub error_class: Option<&'static str>,
+}
+
+impl InternalEvent for EventsAcknowledged {
+    fn emit(self) {
+        let outcome = match self.status {
+            EventStatus::Delivered => "delivered",
+            EventStatus::Errored => "errored",
+            EventStatus::Rejected => "rejected",
+            EventStatus::Dropped | EventStatus::Recorded => return,
+        };
+        trace!(message = "Events acknowledged.", count = %self.count, outcome, error_class = ?self.error_class);
+
+        if let Some(error_class) = self.error_class {
+            counter!("component_acknowledged_events_total", self.count as u64,
+                     "outcome" => outcome, "error_class" => error_class);
+        } else {
+            counter!("component_acknowledged_events_total", self.count as u64,
+                     "outcome" => outcome);
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("EventsAcknowledged")
+    }
+}
@@ -1,8 +1,10 @@
 mod bytes_sent;
+mod events_acknowledged;
 mod events_received;
 mod events_sent;
 
 pub use bytes_sent::BytesSent;
+pub use events_acknowledged::EventsAcknowledged;
 pub use events_received::{EventsReceived, OldEventsReceived};
 pub use events_sent::{EventsSent, DEFAULT_OUTPUT};
 
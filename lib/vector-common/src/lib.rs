@@ -47,6 +47,8 @@ pub use finalizer::EmptyStream;
 
 pub mod internal_event;
 
+pub mod memory_budget;
+
 pub mod shutdown;
 
 #[cfg(feature = "tokenize")]
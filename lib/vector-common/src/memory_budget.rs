@@ -0,0 +1,220 @@
+//! A memory budget shared by the whole process.
+//!
+//! Components reserve the memory they hold on to against the budget -- events held by in-memory
+//! buffers, the state of transforms such as `reduce` and `aggregate`, the batches being built by
+//! sinks -- and load is shed as the budget is approached, rather than the process being killed
+//! once it runs out of memory:
+//! - buffers overflowing to disk spill events to disk rather than holding them in memory
+//! - sources drop their low priority events
+//! - sources are paused until memory is released
+//!
+//! Without a budget, reservations are still counted, but nothing is shed.
+
+#![allow(clippy::module_name_repetitions)]
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// How often paused sources check whether they can resume.
+const RESUME_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+static USED_BYTES: [AtomicUsize; 3] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+static SPILL_TO_DISK_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+static DROP_LOW_PRIORITY_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+static PAUSE_SOURCES_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// What reserved memory is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryUse {
+    /// Events held by in-memory buffers.
+    Buffers,
+    /// The state held by transforms, such as the events being reduced or the metrics being aggregated.
+    TransformState,
+    /// The batches being built by sinks.
+    Batches,
+}
+
+impl MemoryUse {
+    pub const ALL: [MemoryUse; 3] = [
+        MemoryUse::Buffers,
+        MemoryUse::TransformState,
+        MemoryUse::Batches,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            MemoryUse::Buffers => "buffers",
+            MemoryUse::TransformState => "transform_state",
+            MemoryUse::Batches => "batches",
+        }
+    }
+
+    fn used_bytes(self) -> &'static AtomicUsize {
+        &USED_BYTES[self as usize]
+    }
+}
+
+/// The amounts of reserved memory, in bytes, past which load is shed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Past this amount, buffers overflowing to disk spill events to disk.
+    pub spill_to_disk_bytes: usize,
+    /// Past this amount, sources drop their low priority events.
+    pub drop_low_priority_bytes: usize,
+    /// Past this amount, sources are paused.
+    pub pause_sources_bytes: usize,
+}
+
+/// Sets the budget of the process, or removes it so that no load is shed.
+pub fn configure(budget: Option<MemoryBudget>) {
+    let budget = budget.unwrap_or(MemoryBudget {
+        spill_to_disk_bytes: usize::MAX,
+        drop_low_priority_bytes: usize::MAX,
+        pause_sources_bytes: usize::MAX,
+    });
+    SPILL_TO_DISK_BYTES.store(budget.spill_to_disk_bytes, Ordering::Relaxed);
+    DROP_LOW_PRIORITY_BYTES.store(budget.drop_low_priority_bytes, Ordering::Relaxed);
+    PAUSE_SOURCES_BYTES.store(budget.pause_sources_bytes, Ordering::Relaxed);
+}
+
+/// Gets the amount of memory reserved, in bytes, for all uses.
+#[must_use]
+pub fn used_bytes() -> usize {
+    MemoryUse::ALL
+        .iter()
+        .map(|usage| usage.used_bytes().load(Ordering::Relaxed))
+        .sum()
+}
+
+/// Gets the amount of memory reserved, in bytes, for the given use.
+#[must_use]
+pub fn used_bytes_for(usage: MemoryUse) -> usize {
+    usage.used_bytes().load(Ordering::Relaxed)
+}
+
+/// Whether buffers overflowing to disk should spill events to disk.
+#[must_use]
+pub fn should_spill_to_disk() -> bool {
+    used_bytes() >= SPILL_TO_DISK_BYTES.load(Ordering::Relaxed)
+}
+
+/// Whether sources should drop their low priority events.
+#[must_use]
+pub fn should_drop_low_priority() -> bool {
+    used_bytes() >= DROP_LOW_PRIORITY_BYTES.load(Ordering::Relaxed)
+}
+
+/// Whether sources should be paused.
+#[must_use]
+pub fn should_pause_sources() -> bool {
+    used_bytes() >= PAUSE_SOURCES_BYTES.load(Ordering::Relaxed)
+}
+
+/// Waits until sources don't need to be paused anymore.
+pub async fn wait_for_sources_to_resume() {
+    while should_pause_sources() {
+        tokio::time::sleep(RESUME_CHECK_INTERVAL).await;
+    }
+}
+
+/// Memory reserved against the budget for a given use, which is released when dropped.
+#[derive(Debug)]
+pub struct Reservation {
+    usage: MemoryUse,
+    bytes: usize,
+}
+
+impl Reservation {
+    /// Reserves `bytes` of memory for the given use.
+    #[must_use]
+    pub fn new(usage: MemoryUse, bytes: usize) -> Self {
+        usage.used_bytes().fetch_add(bytes, Ordering::Relaxed);
+        Self { usage, bytes }
+    }
+
+    /// Gets the amount of memory reserved, in bytes.
+    #[must_use]
+    pub const fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Reserves `bytes` of memory more.
+    pub fn grow(&mut self, bytes: usize) {
+        self.usage.used_bytes().fetch_add(bytes, Ordering::Relaxed);
+        self.bytes += bytes;
+    }
+
+    /// Releases `bytes` of the reserved memory, or all of it if less is reserved.
+    pub fn shrink(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.bytes);
+        self.usage.used_bytes().fetch_sub(bytes, Ordering::Relaxed);
+        self.bytes -= bytes;
+    }
+
+    /// Changes the amount of reserved memory to `bytes`.
+    pub fn resize(&mut self, bytes: usize) {
+        if bytes > self.bytes {
+            self.grow(bytes - self.bytes);
+        } else {
+            self.shrink(self.bytes - bytes);
+        }
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.usage
+            .used_bytes()
+            .fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The budget is global, so it's only configured by this test.
+    #[test]
+    fn sheds_load_as_budget_is_approached() {
+        let before = used_bytes();
+        configure(Some(MemoryBudget {
+            spill_to_disk_bytes: before + 100,
+            drop_low_priority_bytes: before + 200,
+            pause_sources_bytes: before + 300,
+        }));
+        assert!(!should_spill_to_disk());
+
+        let mut buffered = Reservation::new(MemoryUse::Buffers, 150);
+        assert!(should_spill_to_disk());
+        assert!(!should_drop_low_priority());
+
+        let state = Reservation::new(MemoryUse::TransformState, 100);
+        assert!(should_drop_low_priority());
+        assert!(!should_pause_sources());
+
+        buffered.resize(250);
+        assert!(should_pause_sources());
+        assert_eq!(used_bytes(), before + 350);
+
+        buffered.shrink(1000);
+        assert_eq!(buffered.bytes(), 0);
+        assert!(!should_spill_to_disk());
+
+        buffered.grow(100);
+        drop(state);
+        assert!(!should_drop_low_priority());
+        assert!(should_spill_to_disk());
+
+        drop(buffered);
+        assert_eq!(used_bytes(), before);
+
+        configure(None);
+    }
+}
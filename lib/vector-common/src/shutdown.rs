@@ -205,6 +205,20 @@ impl SourceShutdownCoordinator {
     /// Panics if this coordinator has had its triggers removed (ie
     /// has been taken over with `Self::takeover_source`).
     pub fn shutdown_all(self, deadline: Instant) -> impl Future<Output = ()> {
+        self.shutdown_all_with_deadlines(move |_| deadline)
+    }
+
+    /// Sends a signal to begin shutting down to all sources, like `Self::shutdown_all`, except that
+    /// the deadline of each source is given by `deadline`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this coordinator has had its triggers removed (ie
+    /// has been taken over with `Self::takeover_source`).
+    pub fn shutdown_all_with_deadlines(
+        self,
+        deadline: impl Fn(&ComponentKey) -> Instant,
+    ) -> impl Future<Output = ()> {
         let mut complete_futures = Vec::new();
 
         let shutdown_begun_triggers = self.shutdown_begun_triggers;
@@ -232,7 +246,7 @@ impl SourceShutdownCoordinator {
                 shutdown_complete_tripwire,
                 shutdown_force_trigger,
                 id.clone(),
-                deadline,
+                deadline(&id),
             );
 
             complete_futures.push(source_complete);
@@ -7,7 +7,7 @@ use std::{iter, slice, vec};
 use futures::{stream, Stream};
 #[cfg(test)]
 use quickcheck::{Arbitrary, Gen};
-use vector_buffers::EventCount;
+use vector_buffers::{EventCount, EventPriority, Priority};
 
 use super::{Event, EventDataEq, EventMutRef, EventRef, LogEvent, Metric, TraceEvent};
 use crate::ByteSizeOf;
@@ -234,6 +234,15 @@ impl EventCount for EventArray {
     }
 }
 
+impl EventPriority for EventArray {
+    fn event_priority(&self) -> Priority {
+        self.iter_events()
+            .map(|event| event.metadata().priority())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
 impl EventContainer for EventArray {
     type IntoIter = EventArrayIntoIter;
 
@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use value::{Secrets, Value};
+use vector_buffers::Priority;
 use vector_common::EventDataEq;
 
 use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
@@ -34,6 +35,14 @@ pub struct EventMetadata {
     /// TODO(Jean): must not skip serialization to track schemas across restarts.
     #[serde(default = "default_schema_definition", skip)]
     schema_definition: Arc<schema::Definition>,
+
+    /// The priority of the event, used to decide which events to shed first when a buffer is full.
+    #[serde(default, skip)]
+    priority: Priority,
+
+    /// The identifier of the delivery audit record of the event, if it was sampled to be audited.
+    #[serde(default, skip)]
+    audit_id: Option<u64>,
 }
 
 fn default_metadata_value() -> Value {
@@ -90,6 +99,8 @@ impl Default for EventMetadata {
             secrets: Secrets::new(),
             finalizers: Default::default(),
             schema_definition: default_schema_definition(),
+            priority: Priority::default(),
+            audit_id: None,
         }
     }
 }
@@ -144,12 +155,23 @@ impl EventMetadata {
         self
     }
 
+    /// Replace the priority with the given one.
+    #[must_use]
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
     /// If a Splunk HEC token is not set in `self`, the one from `other` will be used.
+    /// The merged priority is the higher of the two priorities.
+    /// If no delivery audit record is set in `self`, the one from `other` will be used.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         self.secrets.merge(other.secrets);
+        self.priority = self.priority.max(other.priority);
+        self.audit_id = self.audit_id.or(other.audit_id);
     }
 
     /// Update the finalizer(s) status.
@@ -191,6 +213,26 @@ impl EventMetadata {
     pub fn set_schema_definition(&mut self, definition: &Arc<schema::Definition>) {
         self.schema_definition = Arc::clone(definition);
     }
+
+    /// Get the priority.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Set the priority.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+
+    /// Get the identifier of the delivery audit record, if the event is audited.
+    pub fn audit_id(&self) -> Option<u64> {
+        self.audit_id
+    }
+
+    /// Set the identifier of the delivery audit record.
+    pub fn set_audit_id(&mut self, audit_id: u64) {
+        self.audit_id = Some(audit_id);
+    }
 }
 
 impl EventDataEq for EventMetadata {
@@ -238,4 +280,28 @@ mod test {
         assert_eq!(metadata.datadog_api_key().unwrap().as_ref(), SECRET);
         assert_eq!(metadata.splunk_hec_token().unwrap().as_ref(), SECRET2);
     }
+
+    #[test]
+    fn merge_keeps_highest_priority() {
+        let mut metadata = EventMetadata::default().with_priority(Priority::Low);
+        metadata.merge(EventMetadata::default().with_priority(Priority::High));
+        assert_eq!(metadata.priority(), Priority::High);
+
+        metadata.merge(EventMetadata::default());
+        assert_eq!(metadata.priority(), Priority::High);
+    }
+
+    #[test]
+    fn merge_keeps_audit_id() {
+        let mut metadata = EventMetadata::default();
+        let mut other = EventMetadata::default();
+        other.set_audit_id(1);
+        metadata.merge(other);
+        assert_eq!(metadata.audit_id(), Some(1));
+
+        let mut other = EventMetadata::default();
+        other.set_audit_id(2);
+        metadata.merge(other);
+        assert_eq!(metadata.audit_id(), Some(1));
+    }
 }
@@ -121,6 +121,7 @@ impl From<Metric> for event::Metric {
         };
 
         let name = metric.name;
+        let metric_metadata = metric.metadata;
 
         let namespace = if metric.namespace.is_empty() {
             None
@@ -181,10 +182,14 @@ impl From<Metric> for event::Metric {
             },
         };
 
-        Self::new(name, kind, value)
+        let mut metric = Self::new(name, kind, value)
             .with_namespace(namespace)
             .with_tags(tags)
-            .with_timestamp(timestamp)
+            .with_timestamp(timestamp);
+        if let Some(value) = metric_metadata.and_then(decode_value) {
+            *metric.metadata_mut().value_mut() = value;
+        }
+        metric
     }
 }
 
@@ -342,6 +347,15 @@ impl From<event::Metric> for WithMetadata<Metric> {
             },
         };
 
+        // Only set when there is something to keep, so that metrics without metadata are encoded
+        // as before.
+        let metric_metadata = match metadata.value() {
+            ::value::Value::Object(fields) if !fields.is_empty() => {
+                Some(encode_value(metadata.value().clone()))
+            }
+            _ => None,
+        };
+
         let data = Metric {
             name,
             namespace,
@@ -349,6 +363,7 @@ impl From<event::Metric> for WithMetadata<Metric> {
             tags,
             kind,
             value: Some(metric),
+            metadata: metric_metadata,
         };
         Self { data, metadata }
     }
@@ -146,7 +146,7 @@ impl VrlTarget {
         }
     }
 
-    fn metadata(&self) -> &EventMetadata {
+    pub fn metadata(&self) -> &EventMetadata {
         match self {
             VrlTarget::LogEvent(_, metadata) | VrlTarget::Trace(_, metadata) => metadata,
             VrlTarget::Metric { metric, .. } => metric.metadata(),
@@ -12,5 +12,6 @@ impl LabelFilter for VectorLabelFilter {
             || key == "component_kind"
             || key == "component_name"
             || key == "buffer_type"
+            || key == "tenant"
     }
 }
@@ -1,6 +1,7 @@
 use std::{
+    any::Any,
     collections::{BinaryHeap, VecDeque},
-    fmt,
+    error, fmt, io,
     num::NonZeroUsize,
     task::Poll,
 };
@@ -8,10 +9,10 @@ use std::{
 use futures::{poll, FutureExt, Stream, StreamExt, TryFutureExt};
 use futures_util::future::poll_fn;
 use tokio::{pin, select};
-use tower::Service;
+use tower::{timeout::error::Elapsed, Service};
 use tracing::Instrument;
 use vector_buffers::{Ackable, Acker};
-use vector_common::internal_event::BytesSent;
+use vector_common::internal_event::{BytesSent, EventsAcknowledged};
 
 use super::FuturesUnorderedChunked;
 use crate::{
@@ -144,6 +145,41 @@ pub trait DriverResponse {
     fn bytes_sent(&self) -> Option<BytesSent> {
         None
     }
+
+    /// Classifies why the events of the request weren't delivered, when the status isn't
+    /// `Delivered`, for the `component_acknowledged_events_total` metric.
+    fn error_class(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Classifies an error returned by a service: `timeout` when the request timed out, `io` when it
+/// was caused by an I/O error, and `request_failed` otherwise.
+pub fn error_class(error: &(dyn error::Error + 'static)) -> &'static str {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if error.is::<Elapsed>() {
+            return "timeout";
+        }
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            return if error.kind() == io::ErrorKind::TimedOut {
+                "timeout"
+            } else {
+                "io"
+            };
+        }
+        source = error.source();
+    }
+    "request_failed"
+}
+
+/// Classifies the error of a service whose error type may be the boxed `crate::Error`.
+fn service_error_class<E: 'static>(error: &E) -> &'static str {
+    let error: &dyn Any = error;
+    match error.downcast_ref::<crate::Error>() {
+        Some(error) => error_class(error.as_ref()),
+        None => "request_failed",
+    }
 }
 
 /// Drives the interaction between a stream of items and a service which processes them
@@ -290,10 +326,20 @@ where
                                     Err(error) => {
                                         error!(message = "Service call failed.", ?error, request_id);
                                         finalizers.update_status(EventStatus::Rejected);
+                                        emit(EventsAcknowledged {
+                                            count: ack_size,
+                                            status: EventStatus::Rejected,
+                                            error_class: Some(service_error_class(&error)),
+                                        });
                                     },
                                     Ok(response) => {
                                         trace!(message = "Service call succeeded.", request_id);
                                         finalizers.update_status(response.event_status());
+                                        emit(EventsAcknowledged {
+                                            count: ack_size,
+                                            status: response.event_status(),
+                                            error_class: response.error_class(),
+                                        });
                                         if response.event_status() == EventStatus::Delivered {
                                             if let Some(bytes_sent) = response.bytes_sent() {
                                                 emit(bytes_sent);
@@ -329,6 +375,7 @@ mod tests {
     use std::{
         collections::VecDeque,
         future::Future,
+        io,
         iter::repeat_with,
         num::NonZeroUsize,
         pin::Pin,
@@ -346,11 +393,11 @@ mod tests {
         time::sleep,
     };
     use tokio_util::sync::PollSemaphore;
-    use tower::Service;
+    use tower::{timeout::error::Elapsed, Service};
     use vector_buffers::{Ackable, Acker};
     use vector_common::internal_event::EventsSent;
 
-    use super::{Driver, DriverResponse};
+    use super::{service_error_class, Driver, DriverResponse};
     use crate::{
         event::{EventFinalizers, EventStatus, Finalizable},
         stream::driver::AcknowledgementTracker,
@@ -594,6 +641,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn classifies_service_errors() {
+        let error: crate::Error = Box::new(Elapsed::new());
+        assert_eq!(service_error_class(&error), "timeout");
+
+        let error: crate::Error = Box::new(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        assert_eq!(service_error_class(&error), "timeout");
+
+        let error: crate::Error = Box::new(io::Error::new(io::ErrorKind::BrokenPipe, "broken"));
+        assert_eq!(service_error_class(&error), "io");
+
+        let error: crate::Error = "invalid request".into();
+        assert_eq!(service_error_class(&error), "request_failed");
+        assert_eq!(service_error_class(&"invalid request"), "request_failed");
+    }
+
     #[tokio::test]
     async fn driver_simple() {
         // This test uses a service which creates response futures that sleep for a variable, but
@@ -16,6 +16,7 @@ use futures::{
 use pin_project::pin_project;
 use tokio_util::time::{delay_queue::Key, DelayQueue};
 use twox_hash::XxHash64;
+use vector_common::memory_budget::{MemoryUse, Reservation};
 
 use crate::{
     partition::Partitioner,
@@ -120,6 +121,9 @@ struct Batch<I> {
     allocation_limit: usize,
     /// The store of `I` elements.
     elements: Vec<I>,
+    /// The memory held by the elements, reserved against the memory budget of the process until
+    /// the batch is decomposed.
+    reservation: Reservation,
 }
 
 impl<I> ByteSizeOf for Batch<I> {
@@ -161,6 +165,7 @@ where
             element_limit,
             allocation_limit,
             elements: Vec::with_capacity(128),
+            reservation: Reservation::new(MemoryUse::Batches, 0),
         }
     }
 
@@ -170,7 +175,9 @@ where
     /// to call `has_space` prior to calling this and it will never
     /// panic. Intended to be used only when insertion must not fail.
     fn with(mut self, value: I) -> Self {
-        self.allocated_bytes += value.size_of();
+        let size = value.size_of();
+        self.allocated_bytes += size;
+        self.reservation.grow(size);
         self.elements.push(value);
         self
     }
@@ -206,7 +213,9 @@ where
     /// for a new element to be inserted.
     fn push(&mut self, value: I) {
         assert!(self.has_space(&value));
-        self.allocated_bytes += value.size_of();
+        let size = value.size_of();
+        self.allocated_bytes += size;
+        self.reservation.grow(size);
         self.elements.push(value);
     }
 }
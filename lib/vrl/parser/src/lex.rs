@@ -984,6 +984,11 @@ impl<'input> Lexer<'input> {
 
                 ch if is_ident_continue(ch) => {}
 
+                // namespaced function calls
+                ':' if last_char == Some(':') => {}
+                ':' if last_char.map(is_ident_continue) == Some(true)
+                    && namespaced_function_len(&self.input[pos..]).is_some() => {}
+
                 // Any other character breaks the query chain.
                 _ => break,
             }
@@ -1078,7 +1083,16 @@ impl<'input> Lexer<'input> {
     }
 
     fn identifier_or_function_call(&mut self, start: usize) -> Spanned<'input, usize> {
-        let (end, ident) = self.take_while(start, is_ident_continue);
+        let (mut end, mut ident) = self.take_while(start, is_ident_continue);
+
+        // Functions of imported libraries are namespaced, as in `parsers::parse_user()`.
+        if let Some(len) = namespaced_function_len(&self.input[end..]) {
+            while self.next_index() < end + len {
+                self.bump();
+            }
+            end += len;
+            ident = self.slice(start, end);
+        }
 
         let token = if self.test_peek(|ch| ch == '(' || ch == '!') {
             Token::FunctionCall(ident)
@@ -1222,6 +1236,20 @@ fn is_ident_continue(ch: char) -> bool {
     }
 }
 
+/// Returns the length of the namespaced part of a function call, such as `::parse_user` in `::parse_user()`, if the
+/// input starts with one.
+fn namespaced_function_len(input: &str) -> Option<usize> {
+    let mut len = 0;
+    while let Some(rest) = input[len..].strip_prefix("::") {
+        if !rest.starts_with(is_ident_start) {
+            return None;
+        }
+        len += 2 + rest.find(|ch| !is_ident_continue(ch)).unwrap_or(rest.len());
+    }
+
+    (len > 0 && input[len..].starts_with(|ch| ch == '(' || ch == '!')).then(|| len)
+}
+
 fn is_query_start(ch: char) -> bool {
     match ch {
         '.' | '{' | '[' => true,
@@ -1515,6 +1543,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn namespaced_function_calls() {
+        test(
+            data(r#"foo::bar() foo::bar::baz!() foo::bar"#),
+            vec![
+                (
+                    r#"~~~~~~~~                             "#,
+                    FunctionCall("foo::bar"),
+                ),
+                (r#"        ~                            "#, LParen),
+                (r#"         ~                           "#, RParen),
+                (
+                    r#"           ~~~~~~~~~~~~~             "#,
+                    FunctionCall("foo::bar::baz"),
+                ),
+                (r#"                        ~            "#, Bang),
+                (r#"                         ~           "#, LParen),
+                (r#"                          ~          "#, RParen),
+                (
+                    r#"                            ~~~      "#,
+                    Identifier("foo"),
+                ),
+                (r#"                               ~     "#, Colon),
+                (r#"                                ~    "#, Colon),
+                (
+                    r#"                                 ~~~ "#,
+                    Identifier("bar"),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn namespaced_function_call_queries() {
+        use StringLiteral as L;
+        use StringLiteralToken as S;
+
+        test(
+            data(r#"foo::bar("a").b"#),
+            vec![
+                (r#"~              "#, LQuery),
+                (r#"~~~~~~~~       "#, FunctionCall("foo::bar")),
+                (r#"        ~      "#, LParen),
+                (r#"         ~~~   "#, L(S("a"))),
+                (r#"            ~  "#, RParen),
+                (r#"             ~ "#, Dot),
+                (r#"              ~"#, Identifier("b")),
+                (r#"              ~"#, RQuery),
+            ],
+        );
+    }
+
     #[test]
     fn single_query() {
         test(
@@ -1,10 +1,21 @@
-use std::sync::{
-    atomic::{self, AtomicBool},
-    Arc,
+use std::{
+    str::FromStr,
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, Mutex,
+    },
 };
 
+use bytes::Bytes;
+use openssl::memcmp;
+use serde::Deserialize;
 use serde_json::json;
-use warp::{reply::json, Rejection, Reply};
+use warp::{http::StatusCode, reply::json, Rejection, Reply};
+
+use crate::{
+    config::{self, ComponentKey, ConfigBuilder, Format, SinkOuter, SourceOuter, TransformOuter},
+    signal::{SignalTo, SignalTx},
+};
 
 // Health handler, responds with '{ ok: true }' when running and '{ ok: false}'
 // when shutting down
@@ -21,3 +32,490 @@ pub(super) async fn health(running: Arc<AtomicBool>) -> Result<impl Reply, Rejec
         ))
     }
 }
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(super) struct ReloadQuery {
+    /// Layer the pushed configuration on top of the configuration files Vector was started with,
+    /// rather than replacing them.
+    partial: bool,
+}
+
+// Reload handler, validates the pushed configuration and hands it over to be applied the same way
+// as a configuration reloaded from disk. Responds with '{ ok: true }' once the configuration has
+// been accepted, or with '{ ok: false, errors: [...] }' if it was rejected.
+pub(super) async fn reload(
+    authorization: Option<String>,
+    content_type: Option<String>,
+    query: ReloadQuery,
+    body: Bytes,
+    reload_token: Option<Arc<str>>,
+    signal_tx: SignalTx,
+) -> Result<impl Reply, Rejection> {
+    // The endpoint doesn't exist unless a token has been configured.
+    let reload_token = reload_token.ok_or_else(warp::reject::not_found)?;
+
+    if !is_authorized(authorization.as_deref(), &reload_token) {
+        return Ok(reload_reply(
+            StatusCode::UNAUTHORIZED,
+            vec!["Missing or invalid reload token.".to_owned()],
+        ));
+    }
+
+    let format = format_from_content_type(content_type.as_deref());
+    let builder = match reload_builder(&body, format, query.partial) {
+        Ok(builder) => builder,
+        Err(errors) => return Ok(reload_reply(StatusCode::BAD_REQUEST, errors)),
+    };
+
+    if signal_tx
+        .send(SignalTo::ReloadFromConfigBuilder(builder))
+        .is_err()
+    {
+        return Ok(reload_reply(
+            StatusCode::SERVICE_UNAVAILABLE,
+            vec!["Vector is shutting down.".to_owned()],
+        ));
+    }
+
+    Ok(reload_reply(StatusCode::ACCEPTED, Vec::new()))
+}
+
+/// The kind of component edited through the `/components` endpoints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ComponentKind {
+    Source,
+    Transform,
+    Sink,
+}
+
+impl FromStr for ComponentKind {
+    type Err = ();
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "sources" => Ok(Self::Source),
+            "transforms" => Ok(Self::Transform),
+            "sinks" => Ok(Self::Sink),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An edit of a single component of the running configuration.
+#[derive(Debug)]
+pub(super) enum ComponentEdit {
+    /// Adds the component, or replaces it, with the configuration in the payload.
+    Put { payload: Bytes, format: Format },
+    /// Removes the component.
+    Remove,
+    /// Replaces the inputs of the component.
+    Rewire { inputs: Vec<String> },
+}
+
+// Component edit handler, applies the edit to the running configuration, validates it, and hands it
+// over to be applied the same way as a configuration pushed to the reload handler, so only the
+// edited component, and the components it's wired to, are rebuilt. Edits are applied on top of each
+// other, even before the topology has been reloaded with the previous ones, and the running
+// configuration is reset every time the topology is reloaded.
+pub(super) async fn edit_component(
+    authorization: Option<String>,
+    kind: ComponentKind,
+    key: ComponentKey,
+    edit: ComponentEdit,
+    reload_token: Option<Arc<str>>,
+    running_config: Arc<Mutex<ConfigBuilder>>,
+    signal_tx: SignalTx,
+) -> Result<impl Reply, Rejection> {
+    // Like the reload endpoint, these don't exist unless a token has been configured.
+    let reload_token = reload_token.ok_or_else(warp::reject::not_found)?;
+
+    if !is_authorized(authorization.as_deref(), &reload_token) {
+        return Ok(reload_reply(
+            StatusCode::UNAUTHORIZED,
+            vec!["Missing or invalid reload token.".to_owned()],
+        ));
+    }
+
+    let mut running_config = running_config
+        .lock()
+        .expect("running configuration lock poisoned");
+
+    let mut builder = running_config.clone();
+    if let Err((status, errors)) = apply_edit(&mut builder, kind, key, edit) {
+        return Ok(reload_reply(status, errors));
+    }
+    if let Err(errors) = builder.clone().build() {
+        return Ok(reload_reply(StatusCode::BAD_REQUEST, errors));
+    }
+
+    if signal_tx
+        .send(SignalTo::ReloadFromConfigBuilder(builder.clone()))
+        .is_err()
+    {
+        return Ok(reload_reply(
+            StatusCode::SERVICE_UNAVAILABLE,
+            vec!["Vector is shutting down.".to_owned()],
+        ));
+    }
+    *running_config = builder;
+
+    Ok(reload_reply(StatusCode::ACCEPTED, Vec::new()))
+}
+
+/// Applies an edit of a single component to the configuration.
+fn apply_edit(
+    builder: &mut ConfigBuilder,
+    kind: ComponentKind,
+    key: ComponentKey,
+    edit: ComponentEdit,
+) -> Result<(), (StatusCode, Vec<String>)> {
+    let bad_request = |errors| (StatusCode::BAD_REQUEST, errors);
+    let not_found = |key: &ComponentKey| {
+        (
+            StatusCode::NOT_FOUND,
+            vec![format!("Component \"{}\" doesn't exist.", key)],
+        )
+    };
+
+    match (edit, kind) {
+        (ComponentEdit::Put { payload, format }, ComponentKind::Source) => {
+            let (source, _) =
+                config::load::<_, SourceOuter>(payload.as_ref(), format).map_err(bad_request)?;
+            builder.sources.insert(key, source);
+        }
+        (ComponentEdit::Put { payload, format }, ComponentKind::Transform) => {
+            let (transform, _) =
+                config::load::<_, TransformOuter<String>>(payload.as_ref(), format)
+                    .map_err(bad_request)?;
+            builder.transforms.insert(key, transform);
+        }
+        (ComponentEdit::Put { payload, format }, ComponentKind::Sink) => {
+            let (sink, _) = config::load::<_, SinkOuter<String>>(payload.as_ref(), format)
+                .map_err(bad_request)?;
+            builder.sinks.insert(key, sink);
+        }
+        (ComponentEdit::Remove, kind) => {
+            let removed = match kind {
+                ComponentKind::Source => builder.sources.shift_remove(&key).is_some(),
+                ComponentKind::Transform => builder.transforms.shift_remove(&key).is_some(),
+                ComponentKind::Sink => builder.sinks.shift_remove(&key).is_some(),
+            };
+            if !removed {
+                return Err(not_found(&key));
+            }
+        }
+        (ComponentEdit::Rewire { .. }, ComponentKind::Source) => {
+            return Err(bad_request(vec!["Sources don't have inputs.".to_owned()]));
+        }
+        (ComponentEdit::Rewire { inputs }, ComponentKind::Transform) => {
+            builder
+                .transforms
+                .get_mut(&key)
+                .ok_or_else(|| not_found(&key))?
+                .inputs = inputs;
+        }
+        (ComponentEdit::Rewire { inputs }, ComponentKind::Sink) => {
+            builder
+                .sinks
+                .get_mut(&key)
+                .ok_or_else(|| not_found(&key))?
+                .inputs = inputs;
+        }
+    }
+
+    Ok(())
+}
+
+fn reload_reply(
+    status: StatusCode,
+    errors: Vec<String>,
+) -> warp::reply::WithStatus<warp::reply::Json> {
+    let body = if errors.is_empty() {
+        json!({"ok": true})
+    } else {
+        json!({"ok": false, "errors": errors})
+    };
+
+    warp::reply::with_status(json(&body), status)
+}
+
+pub(super) fn is_authorized(authorization: Option<&str>, reload_token: &str) -> bool {
+    match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+        Some(token) => {
+            token.len() == reload_token.len()
+                && memcmp::eq(token.as_bytes(), reload_token.as_bytes())
+        }
+        None => false,
+    }
+}
+
+pub(super) fn format_from_content_type(content_type: Option<&str>) -> Format {
+    let mime = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+
+    match mime.as_deref() {
+        Some("application/json") => Format::Json,
+        Some("application/yaml" | "application/x-yaml" | "text/yaml") => Format::Yaml,
+        _ => Format::Toml,
+    }
+}
+
+/// Builds the configuration to reload from the pushed payload, and validates it.
+fn reload_builder(
+    payload: &[u8],
+    format: Format,
+    partial: bool,
+) -> Result<ConfigBuilder, Vec<String>> {
+    let (payload, _) = config::load::<_, ConfigBuilder>(payload, format)?;
+
+    // Secrets and providers are resolved while loading the configuration from disk, which doesn't
+    // happen for pushed configuration.
+    if payload.provider.is_some() || !payload.secret.is_empty() {
+        return Err(vec![
+            "Pushed configuration must not use a provider or secrets.".to_owned(),
+        ]);
+    }
+
+    let builder = if partial {
+        let config_paths = config::CONFIG_PATHS
+            .lock()
+            .map(|config_paths| config_paths.clone())
+            .unwrap_or_default();
+        let (mut builder, _) = config::load_builder_from_paths(&config_paths)?;
+        if builder.provider.is_some() || !builder.secret.is_empty() {
+            return Err(vec![
+                "Partial configuration can't be pushed when the configuration files use a provider or secrets."
+                    .to_owned(),
+            ]);
+        }
+
+        builder.append(payload)?;
+        builder
+    } else {
+        payload
+    };
+
+    builder.clone().build()?;
+
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use tokio::sync::broadcast;
+
+    use super::*;
+
+    const TOKEN: &str = "secret";
+
+    const CONFIG: &str = indoc! {r#"
+        [sources.in]
+          type = "basic_source"
+
+        [sinks.out]
+          type = "basic_sink"
+          inputs = ["in"]
+    "#};
+
+    async fn reload_status(
+        authorization: Option<&str>,
+        body: &'static str,
+        signal_tx: SignalTx,
+    ) -> StatusCode {
+        reload(
+            authorization.map(Into::into),
+            None,
+            ReloadQuery::default(),
+            Bytes::from(body),
+            Some(TOKEN.into()),
+            signal_tx,
+        )
+        .await
+        .expect("reload should not be rejected")
+        .into_response()
+        .status()
+    }
+
+    async fn edit_component_status(
+        kind: ComponentKind,
+        id: &str,
+        edit: ComponentEdit,
+        running_config: Arc<Mutex<ConfigBuilder>>,
+        signal_tx: SignalTx,
+    ) -> StatusCode {
+        edit_component(
+            Some(format!("Bearer {}", TOKEN)),
+            kind,
+            ComponentKey::from(id),
+            edit,
+            Some(TOKEN.into()),
+            running_config,
+            signal_tx,
+        )
+        .await
+        .expect("edit should not be rejected")
+        .into_response()
+        .status()
+    }
+
+    fn running_config() -> Arc<Mutex<ConfigBuilder>> {
+        let (builder, _): (ConfigBuilder, _) =
+            config::load(CONFIG.as_bytes(), Format::Toml).unwrap();
+        Arc::new(Mutex::new(builder))
+    }
+
+    #[test]
+    fn detects_format_from_content_type() {
+        assert_eq!(format_from_content_type(None), Format::Toml);
+        assert_eq!(
+            format_from_content_type(Some("application/json; charset=utf-8")),
+            Format::Json
+        );
+        assert_eq!(
+            format_from_content_type(Some("application/x-yaml")),
+            Format::Yaml
+        );
+        assert_eq!(format_from_content_type(Some("text/plain")), Format::Toml);
+    }
+
+    #[tokio::test]
+    async fn reload_is_disabled_without_token() {
+        let (signal_tx, _signal_rx) = broadcast::channel(1);
+        let result = reload(
+            Some(format!("Bearer {}", TOKEN)),
+            None,
+            ReloadQuery::default(),
+            Bytes::from(CONFIG),
+            None,
+            signal_tx,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn reload_requires_token() {
+        let (signal_tx, mut signal_rx) = broadcast::channel(1);
+
+        for authorization in [None, Some("Bearer other"), Some("secret")] {
+            let status = reload_status(authorization, CONFIG, signal_tx.clone()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        assert!(signal_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn reload_rejects_invalid_config() {
+        let (signal_tx, mut signal_rx) = broadcast::channel(1);
+        let config = indoc! {r#"
+            [sinks.out]
+              type = "basic_sink"
+              inputs = ["missing"]
+        "#};
+
+        let status = reload_status(Some("Bearer secret"), config, signal_tx).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(signal_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn reload_sends_valid_config() {
+        let (signal_tx, mut signal_rx) = broadcast::channel(1);
+
+        let status = reload_status(Some("Bearer secret"), CONFIG, signal_tx).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(matches!(
+            signal_rx.try_recv(),
+            Ok(SignalTo::ReloadFromConfigBuilder(builder)) if builder.sources.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn edits_compose_on_running_config() {
+        let (signal_tx, mut signal_rx) = broadcast::channel(2);
+        let running_config = running_config();
+
+        let sink = indoc! {r#"
+            type = "basic_sink"
+            inputs = ["in"]
+        "#};
+        let status = edit_component_status(
+            ComponentKind::Sink,
+            "other",
+            ComponentEdit::Put {
+                payload: Bytes::from(sink),
+                format: Format::Toml,
+            },
+            Arc::clone(&running_config),
+            signal_tx.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let status = edit_component_status(
+            ComponentKind::Sink,
+            "out",
+            ComponentEdit::Remove,
+            Arc::clone(&running_config),
+            signal_tx,
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        assert!(matches!(
+            signal_rx.try_recv(),
+            Ok(SignalTo::ReloadFromConfigBuilder(builder)) if builder.sinks.len() == 2
+        ));
+        assert!(matches!(
+            signal_rx.try_recv(),
+            Ok(SignalTo::ReloadFromConfigBuilder(builder))
+                if builder.sinks.keys().eq([&ComponentKey::from("other")])
+        ));
+    }
+
+    #[tokio::test]
+    async fn edits_are_validated() {
+        let (signal_tx, mut signal_rx) = broadcast::channel(1);
+        let running_config = running_config();
+
+        // The sink would be left without inputs.
+        let status = edit_component_status(
+            ComponentKind::Source,
+            "in",
+            ComponentEdit::Remove,
+            Arc::clone(&running_config),
+            signal_tx.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = edit_component_status(
+            ComponentKind::Sink,
+            "out",
+            ComponentEdit::Rewire {
+                inputs: vec!["missing".to_owned()],
+            },
+            Arc::clone(&running_config),
+            signal_tx.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = edit_component_status(
+            ComponentKind::Transform,
+            "missing",
+            ComponentEdit::Remove,
+            Arc::clone(&running_config),
+            signal_tx,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        assert!(signal_rx.try_recv().is_err());
+        assert_eq!(running_config.lock().unwrap().sources.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
+};
+
+use serde::Serialize;
+use serde_json::json;
+use warp::{http::StatusCode, reply::json, Rejection, Reply};
+
+use super::schema::ComponentBufferUsage;
+use crate::{
+    config::{api::ReadinessOptions, ComponentKey},
+    event::Metric,
+    metrics::Controller,
+    topology::health::{self, ComponentHealth, HealthcheckStatus},
+};
+
+/// The readiness of a component, and the reasons it isn't ready.
+#[derive(Debug, Serialize)]
+struct ComponentReadiness {
+    kind: &'static str,
+    ready: bool,
+    running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    healthcheck: Option<HealthcheckStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buffer_utilization: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idle_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reasons: Vec<String>,
+}
+
+// Liveness handler, responds with '{ ok: true }' while Vector is running and the tasks of all of
+// its components are too, and with '{ ok: false, stopped: [...] }' otherwise, so Vector is
+// restarted when a component has died.
+pub(super) async fn live(running: Arc<AtomicBool>) -> Result<impl Reply, Rejection> {
+    let mut stopped = health::components()
+        .into_iter()
+        .filter(|(_, health)| !health.running)
+        .map(|(key, _)| key.to_string())
+        .collect::<Vec<_>>();
+    stopped.sort();
+
+    let ok = running.load(atomic::Ordering::Relaxed) && stopped.is_empty();
+    Ok(warp::reply::with_status(
+        json(&json!({"ok": ok, "stopped": stopped})),
+        status(ok),
+    ))
+}
+
+// Readiness handler, responds with '{ ok: true, components: {...} }' while Vector is running and
+// all of its components meet the readiness criteria, and with '{ ok: false, components: {...} }'
+// otherwise, so traffic is held until the pipeline is healthy.
+pub(super) async fn ready(
+    running: Arc<AtomicBool>,
+    criteria: ReadinessOptions,
+) -> Result<impl Reply, Rejection> {
+    let buffer_utilizations = buffer_utilizations();
+    let components = health::components()
+        .into_iter()
+        .map(|(key, health)| {
+            let buffer_utilization = buffer_utilizations.get(key.id()).copied();
+            (
+                key.to_string(),
+                evaluate(&health, buffer_utilization, &criteria),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let ok = running.load(atomic::Ordering::Relaxed)
+        && components.values().all(|component| component.ready);
+    Ok(warp::reply::with_status(
+        json(&json!({"ok": ok, "components": components})),
+        status(ok),
+    ))
+}
+
+const fn status(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// Evaluates the readiness of a component against the readiness criteria.
+fn evaluate(
+    health: &ComponentHealth,
+    buffer_utilization: Option<f64>,
+    criteria: &ReadinessOptions,
+) -> ComponentReadiness {
+    let mut reasons = Vec::new();
+
+    if !health.running {
+        reasons.push("The component isn't running.".to_owned());
+    }
+
+    if criteria.require_healthchecks {
+        match health.healthcheck {
+            Some(HealthcheckStatus::Pending) => {
+                reasons.push("The healthcheck hasn't completed yet.".to_owned())
+            }
+            Some(HealthcheckStatus::Failed) => reasons.push("The healthcheck failed.".to_owned()),
+            Some(HealthcheckStatus::TimedOut) => {
+                reasons.push("The healthcheck timed out.".to_owned())
+            }
+            Some(HealthcheckStatus::Passed | HealthcheckStatus::Disabled) | None => {}
+        }
+    }
+
+    if let (Some(utilization), Some(max_utilization)) =
+        (buffer_utilization, criteria.max_buffer_utilization)
+    {
+        if utilization > max_utilization {
+            reasons.push(format!(
+                "The buffer is {:.0}% full, over the maximum of {:.0}%.",
+                utilization * 100.0,
+                max_utilization * 100.0
+            ));
+        }
+    }
+
+    let idle_secs = health.activity.idle().map(|idle| idle.as_secs());
+    if let (Some(idle_secs), Some(max_idle_secs)) = (idle_secs, criteria.max_source_idle_secs) {
+        if idle_secs > max_idle_secs {
+            reasons.push(format!(
+                "No events have been received for {} seconds, over the maximum of {} seconds.",
+                idle_secs, max_idle_secs
+            ));
+        }
+    }
+
+    ComponentReadiness {
+        kind: health.kind,
+        ready: reasons.is_empty(),
+        running: health.running,
+        healthcheck: health.healthcheck,
+        buffer_utilization,
+        idle_secs,
+        reasons,
+    }
+}
+
+/// Returns the ratio of the capacity of their buffer in use of the components with a bounded
+/// buffer, keyed by their ID.
+fn buffer_utilizations() -> HashMap<String, f64> {
+    let metrics = match Controller::get() {
+        Ok(controller) => controller.capture_metrics(),
+        Err(_) => return HashMap::new(),
+    };
+
+    let mut buffers = HashMap::<String, Vec<Metric>>::new();
+    for metric in metrics
+        .into_iter()
+        .filter(|metric| metric.name().starts_with("buffer_"))
+    {
+        if let Some(id) = metric.tag_value("component_id") {
+            buffers.entry(id).or_default().push(metric);
+        }
+    }
+
+    buffers
+        .into_iter()
+        .filter_map(|(id, metrics)| {
+            let utilization = ComponentBufferUsage::new(ComponentKey::from(id.as_str()), &metrics)
+                .utilization()?;
+            Some((id, utilization))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(healthcheck: HealthcheckStatus) -> ComponentHealth {
+        ComponentHealth {
+            kind: "sink",
+            running: true,
+            healthcheck: Some(healthcheck),
+            activity: Default::default(),
+        }
+    }
+
+    #[test]
+    fn sinks_are_ready_once_healthy() {
+        let criteria = ReadinessOptions::default();
+
+        let readiness = evaluate(&sink(HealthcheckStatus::Passed), None, &criteria);
+        assert!(readiness.ready);
+
+        let readiness = evaluate(&sink(HealthcheckStatus::Pending), None, &criteria);
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons, ["The healthcheck hasn't completed yet."]);
+
+        let criteria = ReadinessOptions {
+            require_healthchecks: false,
+            ..criteria
+        };
+        let readiness = evaluate(&sink(HealthcheckStatus::Failed), None, &criteria);
+        assert!(readiness.ready);
+    }
+
+    #[test]
+    fn sinks_are_not_ready_with_saturated_buffer() {
+        let criteria = ReadinessOptions {
+            max_buffer_utilization: Some(0.9),
+            ..ReadinessOptions::default()
+        };
+        let health = sink(HealthcheckStatus::Disabled);
+
+        assert!(evaluate(&health, Some(0.5), &criteria).ready);
+        assert!(evaluate(&health, None, &criteria).ready);
+
+        let readiness = evaluate(&health, Some(0.95), &criteria);
+        assert!(!readiness.ready);
+        assert_eq!(
+            readiness.reasons,
+            ["The buffer is 95% full, over the maximum of 90%."]
+        );
+    }
+
+    #[test]
+    fn stopped_components_are_not_ready() {
+        let mut health = sink(HealthcheckStatus::Passed);
+        health.running = false;
+
+        let readiness = evaluate(&health, None, &ReadinessOptions::default());
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons, ["The component isn't running."]);
+    }
+}
@@ -1,4 +1,7 @@
 mod handler;
+mod health;
+#[cfg(feature = "api-profiling")]
+mod profiling;
 mod schema;
 mod server;
 pub mod tap;
@@ -0,0 +1,499 @@
+//! CPU and heap profiles of Vector in the pprof format, so that its performance can be investigated
+//! in production with `go tool pprof`, or any other tool reading that format, without rebuilding
+//! it or attaching an external profiler.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_void, CString},
+    io::Write,
+    time::Duration,
+};
+
+use flate2::{write::GzEncoder, Compression};
+use pprof::protos;
+use prost::Message;
+use serde::Deserialize;
+use warp::{
+    filters::BoxedFilter,
+    http::{Response, StatusCode},
+    reply::Reply,
+    Filter, Rejection,
+};
+
+/// The longest CPU profile that can be taken.
+const MAX_CPU_PROFILE_SECS: u64 = 300;
+
+/// Libraries whose frames are left out of CPU profiles, as the profiler can't unwind through them
+/// reliably.
+const CPU_PROFILE_BLOCKLIST: [&str; 4] = ["libc", "libgcc", "pthread", "vdso"];
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct CpuProfileQuery {
+    /// How long the profile is taken for.
+    seconds: u64,
+    /// How many times per second stacks are sampled.
+    frequency: i32,
+}
+
+impl Default for CpuProfileQuery {
+    fn default() -> Self {
+        Self {
+            seconds: 30,
+            frequency: 99,
+        }
+    }
+}
+
+/// Starts sampling allocations, which the heap profile is made of. Allocations made before then
+/// aren't part of it.
+pub(super) fn activate_heap_profiling() -> Result<(), tikv_jemalloc_ctl::Error> {
+    // Safety: `prof.active` is a boolean.
+    unsafe { tikv_jemalloc_ctl::raw::write(b"prof.active\0", true) }
+}
+
+/// The `/debug/pprof/profile` and `/debug/pprof/heap` endpoints, named after those of Go programs
+/// so that tools find them where they expect them.
+pub(super) fn routes() -> BoxedFilter<(warp::reply::Response,)> {
+    let cpu = warp::path!("debug" / "pprof" / "profile")
+        .and(warp::get())
+        .and(warp::query::<CpuProfileQuery>())
+        .and_then(cpu_profile);
+    let heap = warp::path!("debug" / "pprof" / "heap")
+        .and(warp::get())
+        .and_then(heap_profile);
+
+    cpu.or(heap).unify().boxed()
+}
+
+// CPU profile handler, samples the stacks of all threads for the requested number of seconds, and
+// responds with the gzipped profile.
+async fn cpu_profile(query: CpuProfileQuery) -> Result<warp::reply::Response, Rejection> {
+    if query.seconds == 0 || query.seconds > MAX_CPU_PROFILE_SECS {
+        return Ok(error_reply(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "`seconds` must be between 1 and {}, got {}.",
+                MAX_CPU_PROFILE_SECS, query.seconds
+            ),
+        ));
+    }
+    if query.frequency <= 0 {
+        return Ok(error_reply(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "`frequency` must be greater than zero, got {}.",
+                query.frequency
+            ),
+        ));
+    }
+
+    // The profiler isn't meant to be held across await points, so it's run on a thread of its own.
+    let profile = tokio::task::spawn_blocking(move || {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(query.frequency)
+            .blocklist(&CPU_PROFILE_BLOCKLIST)
+            .build()?;
+        std::thread::sleep(Duration::from_secs(query.seconds));
+        guard.report().build()?.pprof()
+    })
+    .await
+    .expect("CPU profiler panicked");
+
+    Ok(match profile {
+        Ok(profile) => profile_reply(&profile, "profile.pb.gz"),
+        Err(pprof::Error::Running) => error_reply(
+            StatusCode::CONFLICT,
+            "A CPU profile is already being taken.".to_owned(),
+        ),
+        Err(error) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+    })
+}
+
+// Heap profile handler, responds with the gzipped profile of the memory currently allocated, as
+// sampled by jemalloc.
+async fn heap_profile() -> Result<warp::reply::Response, Rejection> {
+    let profile = tokio::task::spawn_blocking(heap::profile)
+        .await
+        .expect("heap profiler panicked");
+
+    Ok(match profile {
+        Ok(profile) => profile_reply(&profile, "heap.pb.gz"),
+        Err(error) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+    })
+}
+
+fn profile_reply(profile: &protos::Profile, filename: &str) -> warp::reply::Response {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let body = encoder
+        .write_all(&profile.encode_to_vec())
+        .and_then(|_| encoder.finish());
+
+    match body {
+        Ok(body) => Response::builder()
+            .header("content-type", "application/octet-stream")
+            .header(
+                "content-disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            )
+            .body(body)
+            .expect("valid response")
+            .into_response(),
+        Err(error) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+    }
+}
+
+fn error_reply(status: StatusCode, error: String) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({"ok": false, "errors": [error]})),
+        status,
+    )
+    .into_response()
+}
+
+mod heap {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// A frame of a stack, as the function it's in along with its file and line, if known.
+    pub(super) type Frame = (String, Option<String>, Option<u32>);
+
+    /// A heap profile, as dumped by jemalloc.
+    #[derive(Debug, Default, PartialEq)]
+    pub(super) struct Dump {
+        /// The average number of bytes allocated between samples.
+        pub(super) sample_period: u64,
+        /// The stacks allocations were sampled from, innermost frame first, along with the number
+        /// of objects and bytes they hold.
+        pub(super) stacks: Vec<(Vec<u64>, u64, u64)>,
+        pub(super) mappings: Vec<Mapping>,
+    }
+
+    /// An executable memory mapping of the process.
+    #[derive(Debug, PartialEq)]
+    pub(super) struct Mapping {
+        pub(super) start: u64,
+        pub(super) end: u64,
+        pub(super) offset: u64,
+        pub(super) path: String,
+    }
+
+    /// Dumps a heap profile of the process, and converts it to the pprof format.
+    pub(super) fn profile() -> crate::Result<protos::Profile> {
+        static DUMPS: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "vector-heap-{}-{}.prof",
+            std::process::id(),
+            DUMPS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let c_path = CString::new(path.to_string_lossy().into_owned())?;
+        // Safety: `prof.dump` is the path of the file to dump the profile to, which outlives the
+        // call.
+        unsafe { tikv_jemalloc_ctl::raw::write(b"prof.dump\0", c_path.as_ptr()) }?;
+
+        let dump = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        Ok(to_profile(&parse(&dump?)?, symbolize))
+    }
+
+    fn symbolize(address: u64) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        // Return addresses point to the instruction after the call.
+        backtrace::resolve(address.saturating_sub(1) as *mut c_void, |symbol| {
+            let name = symbol
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("{:#x}", address));
+            let filename = symbol
+                .filename()
+                .map(|filename| filename.display().to_string());
+            frames.push((name, filename, symbol.lineno()));
+        });
+        frames
+    }
+
+    /// Parses a heap profile in the `heap_v2` format of jemalloc.
+    pub(super) fn parse(dump: &str) -> Result<Dump, String> {
+        let mut lines = dump.lines();
+        let sample_period = lines
+            .next()
+            .and_then(|header| header.strip_prefix("heap_v2/"))
+            .and_then(|period| period.trim().parse::<u64>().ok())
+            .filter(|period| *period > 0)
+            .ok_or_else(|| "Heap profile doesn't start with a `heap_v2` header.".to_owned())?;
+
+        let mut profile = Dump {
+            sample_period,
+            ..Default::default()
+        };
+        let mut stack = None;
+        let mut in_mappings = false;
+        for line in lines {
+            if in_mappings {
+                profile.mappings.extend(parse_mapping(line));
+            } else if line == "MAPPED_LIBRARIES:" {
+                in_mappings = true;
+            } else if let Some(addresses) = line.strip_prefix("@ ") {
+                stack = Some(
+                    addresses
+                        .split_whitespace()
+                        .map(|address| {
+                            u64::from_str_radix(address.trim_start_matches("0x"), 16)
+                                .map_err(|_| format!("Invalid address \"{}\".", address))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+            } else if let Some(counts) = line.trim_start().strip_prefix("t*: ") {
+                // Only the first line following the stack holds the counts of all threads.
+                if let Some(stack) = stack.take() {
+                    let (objects, bytes) = parse_counts(counts)
+                        .ok_or_else(|| format!("Invalid counts \"{}\".", counts))?;
+                    if objects > 0 {
+                        profile.stacks.push((stack, objects, bytes));
+                    }
+                }
+            }
+        }
+
+        Ok(profile)
+    }
+
+    /// Parses counts such as `2: 1024 [0: 0]`, as objects and bytes.
+    fn parse_counts(counts: &str) -> Option<(u64, u64)> {
+        let (objects, rest) = counts.split_once(':')?;
+        let bytes = rest.split_whitespace().next()?;
+        Some((objects.trim().parse().ok()?, bytes.parse().ok()?))
+    }
+
+    /// Parses an executable mapping, in the format of `/proc/self/maps`.
+    fn parse_mapping(line: &str) -> Option<Mapping> {
+        let mut fields = line.split_whitespace();
+        let (start, end) = fields.next()?.split_once('-')?;
+        let permissions = fields.next()?;
+        let offset = fields.next()?;
+        let path = fields.nth(2)?;
+        if !permissions.contains('x') {
+            return None;
+        }
+
+        Some(Mapping {
+            start: u64::from_str_radix(start, 16).ok()?,
+            end: u64::from_str_radix(end, 16).ok()?,
+            offset: u64::from_str_radix(offset, 16).ok()?,
+            path: path.to_owned(),
+        })
+    }
+
+    /// Converts a heap profile to the pprof format, with the counts scaled up from the samples to
+    /// estimates of all the memory allocated, the same way `jeprof` does.
+    pub(super) fn to_profile(
+        dump: &Dump,
+        symbolize: impl Fn(u64) -> Vec<Frame>,
+    ) -> protos::Profile {
+        let mut strings = StringTable::default();
+        let mut profile = protos::Profile {
+            sample_type: vec![
+                protos::ValueType {
+                    ty: strings.get("inuse_objects"),
+                    unit: strings.get("count"),
+                    ..Default::default()
+                },
+                protos::ValueType {
+                    ty: strings.get("inuse_space"),
+                    unit: strings.get("bytes"),
+                    ..Default::default()
+                },
+            ],
+            period_type: Some(protos::ValueType {
+                ty: strings.get("space"),
+                unit: strings.get("bytes"),
+                ..Default::default()
+            }),
+            period: dump.sample_period as i64,
+            ..Default::default()
+        };
+
+        for (id, mapping) in dump.mappings.iter().enumerate() {
+            profile.mapping.push(protos::Mapping {
+                id: id as u64 + 1,
+                memory_start: mapping.start,
+                memory_limit: mapping.end,
+                file_offset: mapping.offset,
+                filename: strings.get(&mapping.path),
+                has_functions: true,
+                ..Default::default()
+            });
+        }
+
+        let mut locations = HashMap::new();
+        let mut functions = HashMap::new();
+        for (stack, objects, bytes) in &dump.stacks {
+            let location_ids = stack
+                .iter()
+                .map(|address| {
+                    let next_id = locations.len() as u64 + 1;
+                    *locations.entry(*address).or_insert_with(|| {
+                        let lines = symbolize(*address)
+                            .into_iter()
+                            .map(|(name, filename, line)| {
+                                let next_id = functions.len() as u64 + 1;
+                                let function_id = *functions
+                                    .entry((name.clone(), filename.clone()))
+                                    .or_insert_with(|| {
+                                        let name = strings.get(&name);
+                                        profile.function.push(protos::Function {
+                                            id: next_id,
+                                            name,
+                                            system_name: name,
+                                            filename: strings
+                                                .get(filename.as_deref().unwrap_or("")),
+                                            ..Default::default()
+                                        });
+                                        next_id
+                                    });
+                                protos::Line {
+                                    function_id,
+                                    line: line.map_or(0, i64::from),
+                                    ..Default::default()
+                                }
+                            })
+                            .collect();
+                        let mapping_id = dump
+                            .mappings
+                            .iter()
+                            .position(|mapping| (mapping.start..mapping.end).contains(address))
+                            .map_or(0, |index| index as u64 + 1);
+                        profile.location.push(protos::Location {
+                            id: next_id,
+                            mapping_id,
+                            address: *address,
+                            line: lines,
+                            ..Default::default()
+                        });
+                        next_id
+                    })
+                })
+                .collect();
+
+            let average = *bytes as f64 / *objects as f64;
+            let scale = 1.0 / (1.0 - (-average / dump.sample_period as f64).exp());
+            profile.sample.push(protos::Sample {
+                location_id: location_ids,
+                value: vec![
+                    (*objects as f64 * scale) as i64,
+                    (*bytes as f64 * scale) as i64,
+                ],
+                ..Default::default()
+            });
+        }
+
+        profile.string_table = strings.into_strings();
+        profile
+    }
+
+    /// The strings of a profile, which are referred to by their index.
+    struct StringTable {
+        indices: HashMap<String, i64>,
+        strings: Vec<String>,
+    }
+
+    impl Default for StringTable {
+        fn default() -> Self {
+            // The first string is always the empty string.
+            Self {
+                indices: [(String::new(), 0)].into_iter().collect(),
+                strings: vec![String::new()],
+            }
+        }
+    }
+
+    impl StringTable {
+        fn get(&mut self, string: &str) -> i64 {
+            if let Some(index) = self.indices.get(string) {
+                return *index;
+            }
+            let index = self.strings.len() as i64;
+            self.indices.insert(string.to_owned(), index);
+            self.strings.push(string.to_owned());
+            index
+        }
+
+        fn into_strings(self) -> Vec<String> {
+            self.strings
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::heap::{parse, to_profile, Dump, Mapping};
+
+    const DUMP: &str = indoc! {"
+        heap_v2/524288
+          t*: 3: 1572864 [0: 0]
+          t0: 3: 1572864 [0: 0]
+        @ 0x1010 0x1020
+          t*: 2: 1048576 [0: 0]
+          t0: 2: 1048576 [0: 0]
+        @ 0x1010 0x2010
+          t*: 1: 524288 [0: 0]
+          t1: 1: 524288 [0: 0]
+        @ 0x3010
+          t*: 0: 0 [0: 0]
+
+        MAPPED_LIBRARIES:
+        00001000-00003000 r-xp 00000000 08:01 1234 /usr/bin/vector
+        00003000-00004000 rw-p 00002000 08:01 1234 /usr/bin/vector
+    "};
+
+    #[test]
+    fn parses_heap_dump() {
+        assert_eq!(
+            parse(DUMP),
+            Ok(Dump {
+                sample_period: 524288,
+                stacks: vec![
+                    (vec![0x1010, 0x1020], 2, 1048576),
+                    (vec![0x1010, 0x2010], 1, 524288),
+                ],
+                mappings: vec![Mapping {
+                    start: 0x1000,
+                    end: 0x3000,
+                    offset: 0,
+                    path: "/usr/bin/vector".to_owned(),
+                }],
+            })
+        );
+        assert!(parse("heap_v1/524288").is_err());
+    }
+
+    #[test]
+    fn converts_heap_dump() {
+        let dump = parse(DUMP).unwrap();
+        let profile = to_profile(&dump, |address| {
+            vec![(format!("function_{:x}", address), None, Some(1))]
+        });
+
+        assert_eq!(profile.string_table[0], "");
+        assert_eq!(profile.sample.len(), 2);
+        // Both stacks share their innermost frame.
+        assert_eq!(profile.location.len(), 3);
+        assert_eq!(profile.function.len(), 3);
+        assert_eq!(profile.sample[0].location_id, vec![1, 2]);
+        assert_eq!(profile.sample[1].location_id, vec![1, 3]);
+        assert!(profile
+            .location
+            .iter()
+            .all(|location| location.mapping_id == 1));
+
+        // A single sampled allocation as large as the sample period stands for more than one.
+        let value = &profile.sample[1].value;
+        assert_eq!(value[0], 1);
+        assert!(value[1] > 524288 && value[1] < 2 * 524288, "{:?}", value);
+    }
+}
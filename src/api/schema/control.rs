@@ -0,0 +1,139 @@
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+
+use async_graphql::{Context, Error, Object, Result};
+
+use super::components::{state, Component};
+use crate::{api::handler, config::ComponentKey, topology::control::ComponentControl};
+
+/// The token authorizing mutations, which are disabled unless one has been configured.
+pub struct MutationToken(pub Option<Arc<str>>);
+
+/// The `Authorization` header of the request.
+pub struct Authorization(pub Option<String>);
+
+#[derive(Default)]
+pub struct ControlMutation;
+
+#[Object]
+impl ControlMutation {
+    /// Pauses a source or sink, stopping events from going through it until it's resumed
+    async fn pause_component(&self, ctx: &Context<'_>, component_id: String) -> Result<bool> {
+        source_or_sink_control(ctx, component_id)?.pause();
+        Ok(true)
+    }
+
+    /// Resumes a paused source or sink
+    async fn resume_component(&self, ctx: &Context<'_>, component_id: String) -> Result<bool> {
+        source_or_sink_control(ctx, component_id)?.resume();
+        Ok(true)
+    }
+
+    /// Limits the number of events per second going through a component, for the given number of
+    /// seconds
+    async fn throttle_component(
+        &self,
+        ctx: &Context<'_>,
+        component_id: String,
+        #[graphql(validator(minimum = 1))] max_events_per_sec: i32,
+        #[graphql(validator(minimum = 1))] duration_secs: i32,
+    ) -> Result<bool> {
+        let max_events_per_sec =
+            NonZeroU32::new(max_events_per_sec as u32).expect("validated to be positive");
+        component_control(ctx, component_id)?.throttle(
+            max_events_per_sec,
+            Duration::from_secs(duration_secs as u64),
+        );
+        Ok(true)
+    }
+
+    /// Removes the limit on the number of events per second going through a component
+    async fn unthrottle_component(&self, ctx: &Context<'_>, component_id: String) -> Result<bool> {
+        component_control(ctx, component_id)?.unthrottle();
+        Ok(true)
+    }
+}
+
+/// Returns the controls of a configured component, if the request is authorized.
+fn component_control(ctx: &Context<'_>, component_id: String) -> Result<Arc<ComponentControl>> {
+    authorize(ctx)?;
+
+    let key = ComponentKey::from(component_id);
+    match state::component_by_component_key(&key) {
+        Some(_) => Ok(ComponentControl::get(&key)),
+        None => Err(Error::new(format!("Component \"{}\" doesn't exist.", key))),
+    }
+}
+
+/// Returns the controls of a configured source or sink, if the request is authorized.
+fn source_or_sink_control(
+    ctx: &Context<'_>,
+    component_id: String,
+) -> Result<Arc<ComponentControl>> {
+    authorize(ctx)?;
+
+    let key = ComponentKey::from(component_id);
+    match state::component_by_component_key(&key) {
+        Some(Component::Source(_) | Component::Sink(_)) => Ok(ComponentControl::get(&key)),
+        Some(Component::Transform(_)) => Err(Error::new(format!(
+            "Component \"{}\" is a transform; only sources and sinks can be paused.",
+            key
+        ))),
+        None => Err(Error::new(format!("Component \"{}\" doesn't exist.", key))),
+    }
+}
+
+/// Checks that the request presents the reload token, like the `/reload` endpoint.
+fn authorize(ctx: &Context<'_>) -> Result<()> {
+    let token = ctx
+        .data_opt::<MutationToken>()
+        .and_then(|token| token.0.as_deref())
+        .ok_or_else(|| Error::new("Mutations are disabled unless a reload token is configured."))?;
+    let authorization = ctx
+        .data_opt::<Authorization>()
+        .and_then(|authorization| authorization.0.as_deref());
+
+    if handler::is_authorized(authorization, token) {
+        Ok(())
+    } else {
+        Err(Error::new("Missing or invalid reload token."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Request;
+
+    use super::*;
+    use crate::api::build_schema;
+
+    #[tokio::test]
+    async fn mutations_require_token() {
+        let query = r#"mutation { unthrottleComponent(componentId: "missing") }"#;
+
+        let schema = build_schema().finish();
+        let response = schema
+            .execute(Request::new(query).data(Authorization(Some("Bearer secret".into()))))
+            .await;
+        assert_eq!(
+            response.errors[0].message,
+            "Mutations are disabled unless a reload token is configured."
+        );
+
+        let schema = build_schema()
+            .data(MutationToken(Some("secret".into())))
+            .finish();
+        let response = schema.execute(Request::new(query)).await;
+        assert_eq!(
+            response.errors[0].message,
+            "Missing or invalid reload token."
+        );
+
+        let response = schema
+            .execute(Request::new(query).data(Authorization(Some("Bearer secret".into()))))
+            .await;
+        assert_eq!(
+            response.errors[0].message,
+            "Component \"missing\" doesn't exist."
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use async_graphql::{Enum, Object, ID};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    event::BatchStatus,
+    topology::delivery_audit::{self, DeliveryRecord, Hop},
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum DeliveryStatus {
+    /// All the sinks the event went to delivered it
+    Delivered,
+    /// A sink failed to deliver the event, though it may succeed if retried
+    Errored,
+    /// A sink was refused delivery of the event by its destination
+    Rejected,
+}
+
+impl From<BatchStatus> for DeliveryStatus {
+    fn from(status: BatchStatus) -> Self {
+        match status {
+            BatchStatus::Delivered => Self::Delivered,
+            BatchStatus::Errored => Self::Errored,
+            BatchStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+pub struct DeliveryHop(Hop);
+
+#[Object]
+impl DeliveryHop {
+    /// Component ID
+    async fn component_id(&self) -> &str {
+        self.0.component_key.id()
+    }
+
+    /// Component kind: source, transform or sink
+    async fn component_kind(&self) -> &str {
+        self.0.component_kind
+    }
+
+    /// When the event went into the component
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.0.timestamp
+    }
+}
+
+pub struct DeliveryAudit(DeliveryRecord);
+
+#[Object]
+impl DeliveryAudit {
+    /// Delivery audit ID
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.to_string())
+    }
+
+    /// Components the event went through, starting with its source
+    async fn hops(&self) -> Vec<DeliveryHop> {
+        self.0.hops.iter().cloned().map(DeliveryHop).collect()
+    }
+
+    /// Final status of the event, unless it's still in flight
+    async fn status(&self) -> Option<DeliveryStatus> {
+        self.0.status.map(Into::into)
+    }
+
+    /// When the event was finalized by all the sinks it went to
+    async fn finalized_at(&self) -> Option<DateTime<Utc>> {
+        self.0.finalized_at
+    }
+
+    /// Milliseconds between the event coming out of its source and it being finalized
+    async fn latency_ms(&self) -> Option<i64> {
+        let started_at = self.0.hops.first()?.timestamp;
+        self.0
+            .finalized_at
+            .map(|finalized_at| (finalized_at - started_at).num_milliseconds())
+    }
+}
+
+#[derive(Default)]
+pub struct DeliveryAuditQuery;
+
+#[Object]
+impl DeliveryAuditQuery {
+    /// Most recent delivery audits of the events sampled by the `delivery_audit` option, most
+    /// recent first
+    async fn delivery_audits(
+        &self,
+        #[graphql(validator(minimum = 1))] first: Option<i32>,
+    ) -> Vec<DeliveryAudit> {
+        let records = delivery_audit::records().into_iter().map(DeliveryAudit);
+        match first {
+            Some(first) => records.take(first as usize).collect(),
+            None => records.collect(),
+        }
+    }
+
+    /// Delivery audit of an event, while it's still kept
+    async fn delivery_audit(&self, id: ID) -> Option<DeliveryAudit> {
+        let id = id.parse::<u64>().ok()?;
+        delivery_audit::record(id).map(DeliveryAudit)
+    }
+}
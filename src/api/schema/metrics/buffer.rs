@@ -0,0 +1,66 @@
+use async_graphql::Object;
+
+use super::filter::sum_gauges;
+use crate::{config::ComponentKey, event::Metric};
+
+pub struct ComponentBufferUsage {
+    component_key: ComponentKey,
+    events: f64,
+    byte_size: f64,
+    max_events: Option<f64>,
+    max_byte_size: Option<f64>,
+}
+
+impl ComponentBufferUsage {
+    /// Returns a new `ComponentBufferUsage`, summing the `buffer_*` gauges of the stages of the
+    /// component's buffer
+    pub fn new(component_key: ComponentKey, metrics: &[Metric]) -> Self {
+        Self {
+            component_key,
+            events: sum_gauges(metrics, "buffer_events").unwrap_or(0.0),
+            byte_size: sum_gauges(metrics, "buffer_byte_size").unwrap_or(0.0),
+            max_events: sum_gauges(metrics, "buffer_max_event_size"),
+            max_byte_size: sum_gauges(metrics, "buffer_max_byte_size"),
+        }
+    }
+
+    /// Returns the ratio of the capacity of the buffer in use, by events or by size, whichever is
+    /// highest, unless the buffer is unbounded
+    pub fn utilization(&self) -> Option<f64> {
+        [
+            (self.events, self.max_events),
+            (self.byte_size, self.max_byte_size),
+        ]
+        .into_iter()
+        .filter_map(|(used, max)| max.filter(|max| *max > 0.0).map(|max| used / max))
+        .reduce(f64::max)
+    }
+}
+
+#[Object]
+impl ComponentBufferUsage {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Number of events in the buffer
+    async fn events(&self) -> f64 {
+        self.events
+    }
+
+    /// Size of the events in the buffer, in bytes
+    async fn byte_size(&self) -> f64 {
+        self.byte_size
+    }
+
+    /// Maximum number of events the buffer can hold, if it's bounded by events
+    async fn max_events(&self) -> Option<f64> {
+        self.max_events
+    }
+
+    /// Maximum size of the buffer in bytes, if it's bounded by size
+    async fn max_byte_size(&self) -> Option<f64> {
+        self.max_byte_size
+    }
+}
@@ -69,3 +69,71 @@ impl ComponentErrorsTotal {
         ErrorsTotal::new(self.metric.clone())
     }
 }
+
+pub struct ErrorTypeTotal {
+    error_type: String,
+    stage: Option<String>,
+    total: f64,
+    recent: f64,
+}
+
+impl ErrorTypeTotal {
+    pub const fn new(error_type: String, stage: Option<String>, total: f64, recent: f64) -> Self {
+        Self {
+            error_type,
+            stage,
+            total,
+            recent,
+        }
+    }
+}
+
+#[Object]
+impl ErrorTypeTotal {
+    /// Type of the errors, such as `request_failed` or `parser_failed`
+    async fn error_type(&self) -> &str {
+        &self.error_type
+    }
+
+    /// Stage of the component the errors happened in
+    async fn stage(&self) -> Option<&str> {
+        self.stage.as_deref()
+    }
+
+    /// Total error count
+    async fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// Errors since the previous sample
+    async fn recent(&self) -> f64 {
+        self.recent
+    }
+}
+
+pub struct ComponentErrorTypes {
+    component_key: ComponentKey,
+    error_types: Vec<ErrorTypeTotal>,
+}
+
+impl ComponentErrorTypes {
+    pub const fn new(component_key: ComponentKey, error_types: Vec<ErrorTypeTotal>) -> Self {
+        Self {
+            component_key,
+            error_types,
+        }
+    }
+}
+
+#[Object]
+impl ComponentErrorTypes {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Errors of the component, by type
+    async fn error_types(&self) -> &[ErrorTypeTotal] {
+        &self.error_types
+    }
+}
@@ -5,8 +5,8 @@ use tokio::time::Duration;
 use tokio_stream::{Stream, StreamExt};
 
 use super::{
-    filter_output_metric, EventsInTotal, EventsOutTotal, OutputThroughput, ProcessedBytesTotal,
-    ProcessedEventsTotal, ReceivedEventsTotal, SentEventsTotal,
+    filter_output_metric, ErrorTypeTotal, EventsInTotal, EventsOutTotal, OutputThroughput,
+    ProcessedBytesTotal, ProcessedEventsTotal, ReceivedEventsTotal, SentEventsTotal,
 };
 use crate::{
     config::ComponentKey,
@@ -45,6 +45,30 @@ fn sum_metrics_owned<I: IntoIterator<Item = Metric>>(metrics: I) -> Option<Metri
     Some(iter.fold(m, |mut m1, m2| if m1.update(&m2) { m1 } else { m2 }))
 }
 
+/// Sums the values of the gauges named `name`, such as those of the stages of a buffer.
+pub fn sum_gauges(metrics: &[Metric], name: &str) -> Option<f64> {
+    metrics
+        .iter()
+        .filter(|m| m.name() == name)
+        .filter_map(|m| match m.value() {
+            MetricValue::Gauge { value } => Some(*value),
+            _ => None,
+        })
+        .reduce(|sum, value| sum + value)
+}
+
+/// Sums the values of the counters named `name`.
+pub fn sum_counters(metrics: &[Metric], name: &str) -> Option<f64> {
+    metrics
+        .iter()
+        .filter(|m| m.name() == name)
+        .filter_map(|m| match m.value() {
+            MetricValue::Counter { value } => Some(*value),
+            _ => None,
+        })
+        .reduce(|sum, value| sum + value)
+}
+
 pub trait MetricsFilter<'a> {
     fn processed_events_total(&self) -> Option<ProcessedEventsTotal>;
     fn processed_bytes_total(&self) -> Option<ProcessedBytesTotal>;
@@ -366,6 +390,72 @@ pub fn component_sent_events_total_throughputs_with_outputs(
         .skip(1)
 }
 
+/// Returns a stream of the metrics of each component filtered by `filter_fn`, such as gauges
+/// that are summed by the caller.
+pub fn component_metrics(
+    interval: i32,
+    filter_fn: &'static MetricFilterFn,
+) -> impl Stream<Item = Vec<(ComponentKey, Vec<Metric>)>> {
+    component_to_filtered_metrics(interval, filter_fn).map(|map| {
+        map.into_iter()
+            .map(|(id, metrics)| (ComponentKey::from(id), metrics))
+            .collect()
+    })
+}
+
+/// Returns a stream of the `component_errors_total` metric of each component with errors, split
+/// by `error_type` and `stage`, along with the number of errors since the previous sample.
+pub fn component_error_types(
+    interval: i32,
+) -> impl Stream<Item = Vec<(ComponentKey, Vec<ErrorTypeTotal>)>> {
+    let mut cache = BTreeMap::new();
+    let mut sampled = false;
+
+    component_to_filtered_metrics(interval, &|m| m.name() == "component_errors_total").map(
+        move |map| {
+            let component_error_types = map
+                .into_iter()
+                .filter_map(|(id, metrics)| {
+                    let mut totals = BTreeMap::new();
+                    for m in metrics.iter() {
+                        if let MetricValue::Counter { value } = m.value() {
+                            let error_type = m
+                                .tag_value("error_type")
+                                .unwrap_or_else(|| "unknown".to_owned());
+                            *totals
+                                .entry((error_type, m.tag_value("stage")))
+                                .or_insert(0.00) += *value;
+                        }
+                    }
+
+                    let error_types = totals
+                        .into_iter()
+                        .map(|((error_type, stage), total)| {
+                            // Errors that appear after the first sample are all recent.
+                            let key = format!(
+                                "{}.{}.{}",
+                                id,
+                                error_type,
+                                stage.as_deref().unwrap_or_default()
+                            );
+                            let last = cache.insert(key, total).unwrap_or(if sampled {
+                                0.00
+                            } else {
+                                total
+                            });
+                            ErrorTypeTotal::new(error_type, stage, total, total - last)
+                        })
+                        .collect::<Vec<_>>();
+
+                    (!error_types.is_empty()).then(|| (ComponentKey::from(id), error_types))
+                })
+                .collect();
+            sampled = true;
+            component_error_types
+        },
+    )
+}
+
 /// Returns a map of Component ID to list of metrics where metrics have been
 /// filtered by `filter_fn`
 fn component_to_filtered_metrics(
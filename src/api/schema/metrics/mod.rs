@@ -1,3 +1,4 @@
+mod buffer;
 mod errors;
 mod events_in;
 mod events_out;
@@ -6,6 +7,7 @@ mod output;
 mod processed_bytes;
 mod processed_events;
 mod received_events;
+mod retries;
 mod sent_events;
 mod sink;
 pub mod source;
@@ -16,8 +18,9 @@ mod uptime;
 mod host;
 
 use async_graphql::{Interface, Object, Subscription};
+pub use buffer::ComponentBufferUsage;
 use chrono::{DateTime, Utc};
-pub use errors::{ComponentErrorsTotal, ErrorsTotal};
+pub use errors::{ComponentErrorTypes, ComponentErrorsTotal, ErrorTypeTotal, ErrorsTotal};
 pub use events_in::EventsInTotal;
 pub use events_out::EventsOutTotal;
 pub use filter::*;
@@ -31,6 +34,7 @@ pub use processed_events::{
 pub use received_events::{
     ComponentReceivedEventsThroughput, ComponentReceivedEventsTotal, ReceivedEventsTotal,
 };
+pub use retries::ComponentRetries;
 pub use sent_events::{ComponentSentEventsThroughput, ComponentSentEventsTotal, SentEventsTotal};
 pub use sink::{IntoSinkMetrics, SinkMetrics};
 pub use source::{IntoSourceMetrics, SourceMetrics};
@@ -297,6 +301,48 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component errors over `interval`, by error type.
+    async fn component_error_types(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentErrorTypes>> {
+        component_error_types(interval).map(|m| {
+            m.into_iter()
+                .map(|(key, error_types)| ComponentErrorTypes::new(key, error_types))
+                .collect()
+        })
+    }
+
+    /// Component buffer usage over `interval`, for the components with a buffer.
+    async fn component_buffer_usages(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentBufferUsage>> {
+        component_metrics(interval, &|m| m.name().starts_with("buffer_")).map(|m| {
+            m.into_iter()
+                .map(|(key, metrics)| ComponentBufferUsage::new(key, &metrics))
+                .collect()
+        })
+    }
+
+    /// Component request retries over `interval`, for the components that retried requests.
+    async fn component_retries(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentRetries>> {
+        component_metrics(interval, &|m| {
+            matches!(
+                m.name(),
+                "component_retries_total" | "component_retry_backoff_seconds"
+            )
+        })
+        .map(|m| {
+            m.into_iter()
+                .map(|(key, metrics)| ComponentRetries::new(key, &metrics))
+                .collect()
+        })
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
@@ -0,0 +1,41 @@
+use async_graphql::Object;
+
+use super::filter::{sum_counters, sum_gauges};
+use crate::{config::ComponentKey, event::Metric};
+
+pub struct ComponentRetries {
+    component_key: ComponentKey,
+    retries_total: f64,
+    backoff_seconds: f64,
+}
+
+impl ComponentRetries {
+    /// Returns a new `ComponentRetries` from the `component_retries_total` and
+    /// `component_retry_backoff_seconds` metrics of a component
+    pub fn new(component_key: ComponentKey, metrics: &[Metric]) -> Self {
+        Self {
+            component_key,
+            retries_total: sum_counters(metrics, "component_retries_total").unwrap_or(0.0),
+            backoff_seconds: sum_gauges(metrics, "component_retry_backoff_seconds").unwrap_or(0.0),
+        }
+    }
+}
+
+#[Object]
+impl ComponentRetries {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Total number of requests retried
+    async fn retries_total(&self) -> f64 {
+        self.retries_total
+    }
+
+    /// Delay before the latest retried request is sent again, in seconds, or zero if the latest
+    /// request didn't need to be retried
+    async fn backoff_seconds(&self) -> f64 {
+        self.backoff_seconds
+    }
+}
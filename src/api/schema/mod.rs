@@ -1,4 +1,6 @@
 pub mod components;
+mod control;
+mod delivery_audit;
 pub mod events;
 pub mod filter;
 mod health;
@@ -7,7 +9,9 @@ mod metrics;
 mod relay;
 pub mod sort;
 
-use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
+pub use control::{Authorization, MutationToken};
+pub use metrics::ComponentBufferUsage;
 
 #[derive(MergedObject, Default)]
 pub struct Query(
@@ -15,8 +19,12 @@ pub struct Query(
     components::ComponentsQuery,
     metrics::MetricsQuery,
     meta::MetaQuery,
+    delivery_audit::DeliveryAuditQuery,
 );
 
+#[derive(MergedObject, Default)]
+pub struct Mutation(control::ControlMutation);
+
 #[derive(MergedSubscription, Default)]
 pub struct Subscription(
     health::HealthSubscription,
@@ -26,6 +34,10 @@ pub struct Subscription(
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(
+        Query::default(),
+        Mutation::default(),
+        Subscription::default(),
+    )
 }
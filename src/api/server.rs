@@ -1,7 +1,7 @@
 use std::{
     convert::Infallible,
     net::SocketAddr,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{atomic::AtomicBool, Arc, Mutex},
 };
 
 use async_graphql::{
@@ -12,23 +12,54 @@ use async_graphql_warp::{graphql_protocol, GraphQLResponse, GraphQLWebSocket};
 use tokio::sync::oneshot;
 use warp::{filters::BoxedFilter, http::Response, ws::Ws, Filter, Reply};
 
-use super::{handler, schema, ShutdownTx};
-use crate::{config, topology};
+use super::{handler, health, schema, ShutdownTx};
+use crate::{
+    config::{self, api::ReadinessOptions, ConfigBuilder},
+    signal::SignalTx,
+    topology,
+};
+
+/// Maximum size, in bytes, of a configuration pushed to the `/reload` endpoint.
+const MAX_RELOAD_PAYLOAD_SIZE: u64 = 10 * 1024 * 1024;
 
 pub struct Server {
     _shutdown: ShutdownTx,
     addr: SocketAddr,
+    running_config: Arc<Mutex<ConfigBuilder>>,
 }
 
 impl Server {
     /// Start the API server. This creates the routes and spawns a Warp server. The server is
     /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing.
+    ///
+    /// Configuration pushed to the server is sent as a reload signal via `signal_tx`. The reload
+    /// token is fixed for the lifetime of the server, so that a pushed configuration can't lock
+    /// out further pushes. Edits of individual components are applied to a copy of the running
+    /// configuration, and sent the same way.
     pub fn start(
         config: &config::Config,
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
+        signal_tx: SignalTx,
     ) -> Self {
-        let routes = make_routes(config.api.playground, watch_rx, running);
+        let running_config = Arc::new(Mutex::new(ConfigBuilder::from(config)));
+        #[cfg(feature = "api-profiling")]
+        if config.api.profiling {
+            if let Err(error) = super::profiling::activate_heap_profiling() {
+                warn!(message = "Heap profiling couldn't be activated.", %error);
+            }
+        }
+
+        let routes = make_routes(
+            config.api.playground,
+            config.api.profiling,
+            config.api.readiness,
+            watch_rx,
+            running,
+            config.api.reload_token.as_deref().map(Into::into),
+            Arc::clone(&running_config),
+            signal_tx,
+        );
 
         let (_shutdown, rx) = oneshot::channel();
         let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
@@ -44,7 +75,11 @@ impl Server {
         // Spawn the server in the background.
         tokio::spawn(server);
 
-        Self { _shutdown, addr }
+        Self {
+            _shutdown,
+            addr,
+            running_config,
+        }
     }
 
     /// Returns a copy of the SocketAddr that the server was started on.
@@ -52,26 +87,122 @@ impl Server {
         self.addr
     }
 
-    /// Update the configuration of a running server. While this instance method doesn't
-    /// directly involve `self`, it provides a neater API to expose an internal implementation
-    /// detail than exposing the function of the sub-mod directly.
+    /// Update the configuration of a running server, which component edits are then applied to.
     pub fn update_config(&self, config: &config::Config) {
+        *self
+            .running_config
+            .lock()
+            .expect("running configuration lock poisoned") = ConfigBuilder::from(config);
         schema::components::update_config(config)
     }
 }
 
 fn make_routes(
     playground: bool,
+    profiling: bool,
+    readiness: ReadinessOptions,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
+    reload_token: Option<Arc<str>>,
+    running_config: Arc<Mutex<ConfigBuilder>>,
+    signal_tx: SignalTx,
 ) -> BoxedFilter<(impl Reply,)> {
     // Routes...
 
     // Health.
     let health = warp::path("health")
-        .and(with_shared(running))
+        .and(warp::path::end())
+        .and(with_shared(Arc::clone(&running)))
         .and_then(handler::health);
 
+    // Liveness and readiness, based on the health of the components rather than only on Vector
+    // running.
+    let live = warp::path("health")
+        .and(warp::path("live"))
+        .and(warp::path::end())
+        .and(with_shared(Arc::clone(&running)))
+        .and_then(health::live);
+    let ready = warp::path("health")
+        .and(warp::path("ready"))
+        .and(warp::path::end())
+        .and(with_shared(running))
+        .and_then(move |running| health::ready(running, readiness));
+
+    // Config reload. Accepts a full or partial configuration, authenticated with the configured
+    // reload token.
+    let reload = {
+        let reload_token = reload_token.clone();
+        let signal_tx = signal_tx.clone();
+        warp::path("reload")
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::header::optional::<String>("content-type"))
+            .and(warp::query::<handler::ReloadQuery>())
+            .and(warp::body::content_length_limit(MAX_RELOAD_PAYLOAD_SIZE))
+            .and(warp::body::bytes())
+            .and_then(move |authorization, content_type, query, body| {
+                handler::reload(
+                    authorization,
+                    content_type,
+                    query,
+                    body,
+                    reload_token.clone(),
+                    signal_tx.clone(),
+                )
+            })
+    };
+
+    // GraphQL mutations are authenticated with the reload token as well.
+    let mutation_token = reload_token.clone();
+
+    // Component edits. Adds, replaces, removes or rewires a single component of the running
+    // configuration, authenticated with the reload token.
+    let component = warp::path("components")
+        .and(warp::path::param::<handler::ComponentKind>())
+        .and(warp::path::param::<String>())
+        .map(|kind, id: String| (kind, config::ComponentKey::from(id)));
+    let put_component = component
+        .clone()
+        .and(warp::path::end())
+        .and(warp::put())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::body::content_length_limit(MAX_RELOAD_PAYLOAD_SIZE))
+        .and(warp::body::bytes())
+        .map(|target, content_type: Option<String>, payload| {
+            let format = handler::format_from_content_type(content_type.as_deref());
+            (target, handler::ComponentEdit::Put { payload, format })
+        });
+    let remove_component = component
+        .clone()
+        .and(warp::path::end())
+        .and(warp::delete())
+        .map(|target| (target, handler::ComponentEdit::Remove));
+    let rewire_component = component
+        .and(warp::path("inputs"))
+        .and(warp::path::end())
+        .and(warp::put())
+        .and(warp::body::content_length_limit(MAX_RELOAD_PAYLOAD_SIZE))
+        .and(warp::body::json())
+        .map(|target, inputs| (target, handler::ComponentEdit::Rewire { inputs }));
+    let components = put_component
+        .or(remove_component)
+        .unify()
+        .or(rewire_component)
+        .unify()
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |((kind, key), edit), authorization| {
+            handler::edit_component(
+                authorization,
+                kind,
+                key,
+                edit,
+                reload_token.clone(),
+                Arc::clone(&running_config),
+                signal_tx.clone(),
+            )
+        });
+
     // 404.
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
@@ -104,14 +235,22 @@ fn make_routes(
 
     // Handle GraphQL queries. Headers will first be parsed to determine whether the query is
     // a subscription and if so, an attempt will be made to upgrade the connection to WebSockets.
-    // All other queries will fall back to the default HTTP handler.
-    let graphql_handler = warp::path("graphql").and(graphql_subscription_handler.or(
-        async_graphql_warp::graphql(schema::build_schema().finish()).and_then(
-            |(schema, request): (Schema<_, _, _>, Request)| async move {
+    // All other queries will fall back to the default HTTP handler, which passes the
+    // `Authorization` header on to the mutations.
+    let graphql_handler = warp::path("graphql").and(
+        graphql_subscription_handler.or(async_graphql_warp::graphql(
+            schema::build_schema()
+                .data(schema::MutationToken(mutation_token))
+                .finish(),
+        )
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            |(schema, request): (Schema<_, _, _>, Request), authorization| async move {
+                let request = request.data(schema::Authorization(authorization));
                 Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
             },
-        ),
-    ));
+        )),
+    );
 
     // Provide a playground for executing GraphQL queries/mutations/subscriptions.
     let graphql_playground = if playground {
@@ -128,11 +267,19 @@ fn make_routes(
         not_found.boxed()
     };
 
+    // CPU and heap profiles, which are only served when enabled.
+    let profiles = profiling_routes(profiling);
+
     // Wire up the health + GraphQL endpoints. Provides a permissive CORS policy to allow for
     // cross-origin interaction with the Vector API.
     health
+        .or(live)
+        .or(ready)
+        .or(reload)
+        .or(components)
         .or(graphql_handler)
         .or(graphql_playground)
+        .or(profiles)
         .or(not_found)
         .with(
             warp::cors()
@@ -146,17 +293,38 @@ fn make_routes(
                     "Access-Control-Allow-Origin",
                     "Access-Control-Request-Headers",
                     "Content-Type",
+                    "Authorization",
                     "X-Apollo-Tracing", // for Apollo GraphQL clients
                     "Pragma",
                     "Host",
                     "Connection",
                     "Cache-Control",
                 ])
-                .allow_methods(vec!["POST", "GET"]),
+                .allow_methods(vec!["POST", "GET", "PUT", "DELETE"]),
         )
         .boxed()
 }
 
+#[cfg(feature = "api-profiling")]
+fn profiling_routes(enabled: bool) -> BoxedFilter<(warp::reply::Response,)> {
+    if enabled {
+        super::profiling::routes()
+    } else {
+        profiling_disabled()
+    }
+}
+
+#[cfg(not(feature = "api-profiling"))]
+fn profiling_routes(_enabled: bool) -> BoxedFilter<(warp::reply::Response,)> {
+    profiling_disabled()
+}
+
+fn profiling_disabled() -> BoxedFilter<(warp::reply::Response,)> {
+    warp::any()
+        .and_then(|| async { Err(warp::reject::not_found()) })
+        .boxed()
+}
+
 fn with_shared(
     shared: Arc<AtomicBool>,
 ) -> impl Filter<Extract = (Arc<AtomicBool>,), Error = Infallible> + Clone {
@@ -20,8 +20,9 @@ use super::{
     ShutdownRx, ShutdownTx,
 };
 use crate::{
+    conditions::{Condition, ConditionConfig, VrlConfig},
     config::ComponentKey,
-    event::{EventArray, LogArray, MetricArray, TraceArray},
+    event::{Event, EventArray, EventContainer, LogArray, MetricArray, TraceArray},
     topology::{fanout, fanout::ControlChannel, TapOutput, TapResource, WatchRx},
 };
 
@@ -125,19 +126,74 @@ impl TapPayload {
     }
 }
 
+/// Narrows down the events surfaced by a tap to those matching a VRL condition, and then to one
+/// out of every `sample_rate` of them, so that busy components can be tapped without flooding the
+/// client.
+#[derive(Clone, Debug)]
+pub struct TapFilter {
+    condition: Option<Condition>,
+    sample_rate: u64,
+}
+
+impl TapFilter {
+    /// Compiles the VRL condition, if any, events must match.
+    pub fn new(vrl: Option<String>, sample_rate: u64) -> crate::Result<Self> {
+        let condition = vrl
+            .map(|source| {
+                ConditionConfig::Vrl(VrlConfig {
+                    source,
+                    runtime: Default::default(),
+                })
+                .build(&Default::default())
+            })
+            .transpose()?;
+
+        Ok(Self {
+            condition,
+            sample_rate: sample_rate.max(1),
+        })
+    }
+
+    const fn is_pass_through(&self) -> bool {
+        self.condition.is_none() && self.sample_rate == 1
+    }
+}
+
+impl Default for TapFilter {
+    fn default() -> Self {
+        Self {
+            condition: None,
+            sample_rate: 1,
+        }
+    }
+}
+
 /// A `TapTransformer` transforms raw events and ships them to the global tap receiver.
 #[derive(Clone)]
 pub struct TapTransformer {
     tap_tx: TapSender,
     output: TapOutput,
+    filter: TapFilter,
+    /// Number of events that matched the filter's condition, to sample them.
+    matched: u64,
 }
 
 impl TapTransformer {
-    pub const fn new(tap_tx: TapSender, output: TapOutput) -> Self {
-        Self { tap_tx, output }
+    pub const fn new(tap_tx: TapSender, output: TapOutput, filter: TapFilter) -> Self {
+        Self {
+            tap_tx,
+            output,
+            filter,
+            matched: 0,
+        }
     }
 
     pub fn try_send(&mut self, events: EventArray) {
+        let events = self.filtered(events);
+        if events.is_empty() {
+            return;
+        }
+
         let payload = match events {
             EventArray::Logs(logs) => TapPayload::Log(self.output.clone(), logs),
             EventArray::Metrics(metrics) => TapPayload::Metric(self.output.clone(), metrics),
@@ -152,6 +208,42 @@ impl TapTransformer {
             );
         }
     }
+
+    /// Keeps the events matching the filter's condition, and then one out of every `sample_rate`.
+    fn filtered(&mut self, events: EventArray) -> EventArray {
+        if self.filter.is_pass_through() {
+            return events;
+        }
+
+        match events {
+            EventArray::Logs(logs) => EventArray::Logs(self.retain(logs, Event::into_log)),
+            EventArray::Metrics(metrics) => {
+                EventArray::Metrics(self.retain(metrics, Event::into_metric))
+            }
+            EventArray::Traces(traces) => {
+                EventArray::Traces(self.retain(traces, Event::into_trace))
+            }
+        }
+    }
+
+    fn retain<T: Into<Event>>(&mut self, events: Vec<T>, from_event: fn(Event) -> T) -> Vec<T> {
+        events
+            .into_iter()
+            .filter_map(|event| {
+                let event = match &self.filter.condition {
+                    Some(condition) => match condition.check(event.into()) {
+                        (true, event) => event,
+                        (false, _) => return None,
+                    },
+                    None => event.into(),
+                };
+
+                let matched = self.matched;
+                self.matched += 1;
+                (matched % self.filter.sample_rate == 0).then(|| from_event(event))
+            })
+            .collect()
+    }
 }
 
 /// A tap sink spawns a process for listening for topology changes. If topology changes,
@@ -165,10 +257,15 @@ impl TapController {
     /// Creates a new tap sink, and spawns a handler for watching for topology changes
     /// and a separate inner handler for events. Uses a oneshot channel to trigger shutdown
     /// of handlers when the `TapSink` drops out of scope.
-    pub fn new(watch_rx: WatchRx, tap_tx: TapSender, patterns: TapPatterns) -> Self {
+    pub fn new(
+        watch_rx: WatchRx,
+        tap_tx: TapSender,
+        patterns: TapPatterns,
+        filter: TapFilter,
+    ) -> Self {
         let (_shutdown, shutdown_rx) = oneshot::channel();
 
-        tokio::spawn(tap_handler(patterns, tap_tx, watch_rx, shutdown_rx));
+        tokio::spawn(tap_handler(patterns, filter, tap_tx, watch_rx, shutdown_rx));
 
         Self { _shutdown }
     }
@@ -237,6 +334,7 @@ async fn send_invalid_output_pattern_match(
 /// `LogEvent`s` when a component matches one or more of the provided patterns.
 async fn tap_handler(
     patterns: TapPatterns,
+    filter: TapFilter,
     tx: TapSender,
     mut watch_rx: WatchRx,
     mut shutdown_rx: ShutdownRx,
@@ -316,7 +414,7 @@ async fn tap_handler(
                             // wrap each event payload with the necessary metadata before forwarding
                             // it to our global tap receiver.
                             let (tap_buffer_tx, mut tap_buffer_rx) = TopologyBuilder::standalone_memory(TAP_BUFFER_SIZE, WhenFull::DropNewest).await;
-                            let mut tap_transformer = TapTransformer::new(tx.clone(), output.clone());
+                            let mut tap_transformer = TapTransformer::new(tx.clone(), output.clone(), filter.clone());
 
                             tokio::spawn(async move {
                                 while let Some(events) = tap_buffer_rx.next().await {
@@ -485,6 +583,7 @@ mod tests {
                 HashSet::from([pattern_matched.to_string(), pattern_not_matched.to_string()]),
                 HashSet::new(),
             ),
+            TapFilter::default(),
         );
 
         // Add the outputs to trigger a change event.
@@ -536,6 +635,53 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    /// A tap transformer should only send the events matching its filter's condition, sampled
+    /// at its rate.
+    async fn transformer_filters_and_samples() {
+        let (tap_tx, mut tap_rx) = tokio_mpsc::channel(10);
+        let filter = TapFilter::new(Some(r#".tenant == "acme""#.to_string()), 2).unwrap();
+        let mut transformer = TapTransformer::new(
+            tap_tx,
+            TapOutput {
+                output_id: OutputId::from(&ComponentKey::from("test")),
+                component_kind: "source",
+                component_type: "demo".to_string(),
+            },
+            filter,
+        );
+
+        let logs = (0..6)
+            .map(|index| {
+                let mut log = LogEvent::from(index.to_string());
+                log.insert("tenant", if index % 3 == 0 { "other" } else { "acme" });
+                log
+            })
+            .collect::<Vec<_>>();
+        transformer.try_send(logs.into());
+
+        match tap_rx.recv().await {
+            Some(TapPayload::Log(_, logs)) => {
+                let messages = logs
+                    .iter()
+                    .map(|log| log["message"].to_string_lossy())
+                    .collect::<Vec<_>>();
+                assert_eq!(messages, ["1", "4"]);
+            }
+            _ => panic!("unexpected payload"),
+        }
+
+        // Nothing is sent when no event is kept.
+        transformer.try_send(LogEvent::from("0").into());
+        assert!(tap_rx.try_recv().is_err());
+    }
+
+    #[test]
+    /// An invalid VRL condition should be rejected.
+    fn invalid_filter() {
+        assert!(TapFilter::new(Some(".tenant ==".to_string()), 1).is_err());
+    }
+
     fn assert_notification(payload: OutputEventsPayload) -> Notification {
         if let OutputEventsPayload::Notification(event_notification) = payload {
             event_notification.notification
@@ -587,6 +733,7 @@ mod tests {
         let source_tap_stream = create_events_stream(
             topology.watch(),
             TapPatterns::new(HashSet::from(["in".to_string()]), HashSet::new()),
+            TapFilter::default(),
             500,
             100,
         );
@@ -642,6 +789,7 @@ mod tests {
         let source_tap_stream = create_events_stream(
             topology.watch(),
             TapPatterns::new(HashSet::from(["to_metric".to_string()]), HashSet::new()),
+            TapFilter::default(),
             500,
             100,
         );
@@ -690,6 +838,7 @@ mod tests {
         let transform_tap_stream = create_events_stream(
             topology.watch(),
             TapPatterns::new(HashSet::from(["transform".to_string()]), HashSet::new()),
+            TapFilter::default(),
             500,
             100,
         );
@@ -744,6 +893,7 @@ mod tests {
                 HashSet::new(),
                 HashSet::from(["transform".to_string(), "in".to_string()]),
             ),
+            TapFilter::default(),
             500,
             100,
         );
@@ -813,6 +963,7 @@ mod tests {
         let tap_stream = create_events_stream(
             topology.watch(),
             TapPatterns::new(HashSet::new(), HashSet::from(["out".to_string()])),
+            TapFilter::default(),
             500,
             100,
         );
@@ -874,6 +1025,7 @@ mod tests {
                 HashSet::from(["transform.dropped".to_string()]),
                 HashSet::new(),
             ),
+            TapFilter::default(),
             500,
             100,
         );
@@ -945,6 +1097,7 @@ mod tests {
         let mut transform_tap_all_outputs_stream = create_events_stream(
             topology.watch(),
             TapPatterns::new(HashSet::from(["transform*".to_string()]), HashSet::new()),
+            TapFilter::default(),
             500,
             100,
         );
@@ -24,13 +24,13 @@ use crate::{api, internal_events::ApiStarted};
 use crate::{
     cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand},
     config::{self},
-    generate, graph, heartbeat, list,
+    convert, generate, graph, heartbeat, list,
     signal::{self, SignalTo},
     topology::{self, RunningTopology},
     trace, unit_test, validate,
 };
 #[cfg(feature = "api-client")]
-use crate::{tap, top};
+use crate::{reload, tap, top};
 
 pub static WORKER_THREADS: OnceNonZeroUsize = OnceNonZeroUsize::new();
 
@@ -143,6 +143,7 @@ impl Application {
                 if let Some(s) = sub_command {
                     let code = match s {
                         SubCommand::Generate(g) => generate::cmd(&g),
+                        SubCommand::Convert(c) => convert::cmd(&c),
                         SubCommand::Graph(g) => graph::cmd(&g),
                         SubCommand::Config(c) => config::cmd(&c),
                         SubCommand::List(l) => list::cmd(&l),
@@ -153,6 +154,8 @@ impl Application {
                         SubCommand::Top(t) => top::cmd(&t).await,
                         #[cfg(feature = "api-client")]
                         SubCommand::Tap(t) => tap::cmd(&t, signal_rx).await,
+                        #[cfg(feature = "api-client")]
+                        SubCommand::Reload(r) => reload::cmd(&r).await,
 
                         SubCommand::Validate(v) => validate::validate(&v, color).await,
                         #[cfg(feature = "vrl-cli")]
@@ -219,7 +222,7 @@ impl Application {
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
 
                 let result = topology::start_validated(config, diff, pieces).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
@@ -282,12 +285,16 @@ impl Application {
                     playground: api_config.playground
                 });
 
-                Some(api::Server::start(topology.config(), topology.watch(), Arc::<AtomicBool>::clone(&topology.running)))
+                Some(api::Server::start(topology.config(), topology.watch(), Arc::<AtomicBool>::clone(&topology.running), signal_handler.clone_tx()))
             } else {
                 info!(message="API is disabled, enable by setting `api.enabled` to `true` and use commands like `vector top`.");
                 None
             };
 
+            // Export internal telemetry, if applicable.
+            #[cfg(feature = "sources-opentelemetry")]
+            crate::telemetry_export::configure(topology.config().telemetry_export.as_ref());
+
             let mut sources_finished = topology.sources_finished();
 
             let signal = loop {
@@ -320,6 +327,9 @@ impl Application {
                                             .await
                                         {
                                             Ok(true) => {
+                                                #[cfg(feature = "sources-opentelemetry")]
+                                                crate::telemetry_export::configure(topology.config().telemetry_export.as_ref());
+
                                                 #[cfg(feature = "api")]
                                                 // Pass the new config to the API server.
                                                 if let Some(ref api_server) = api_server {
@@ -328,7 +338,15 @@ impl Application {
 
                                                 emit!(VectorReloaded { config_paths: &config_paths })
                                             },
-                                            Ok(false) => emit!(VectorReloadError),
+                                            Ok(false) => {
+                                                #[cfg(feature = "api")]
+                                                // Discard the component edits the API server applied to its copy of the config.
+                                                if let Some(ref api_server) = api_server {
+                                                    api_server.update_config(topology.config());
+                                                }
+
+                                                emit!(VectorReloadError)
+                                            },
                                             // Trigger graceful shutdown for what remains of the topology
                                             Err(()) => {
                                                 emit!(VectorReloadError);
@@ -375,6 +393,9 @@ impl Application {
                                         .await
                                     {
                                         Ok(true) => {
+                                            #[cfg(feature = "sources-opentelemetry")]
+                                            crate::telemetry_export::configure(topology.config().telemetry_export.as_ref());
+
                                             #[cfg(feature = "api")]
                                             // Pass the new config to the API server.
                                             if let Some(ref api_server) = api_server {
@@ -12,7 +12,7 @@ const DEFAULT_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Configuration of the authentication strategy for interacting with AWS services.
 #[configurable_component]
-#[derive(Clone, Debug, Derivative)]
+#[derive(Clone, Debug, Derivative, PartialEq)]
 #[derivative(Default)]
 #[serde(deny_unknown_fields, untagged)]
 pub enum AwsAuthentication {
@@ -27,7 +27,7 @@ pub use region::RegionOrEndpoint;
 use tower::{Layer, Service, ServiceBuilder};
 
 use crate::config::ProxyConfig;
-use crate::http::{build_proxy_connector, build_tls_connector};
+use crate::http::{build_http2_tls_connector, build_proxy_connector, build_tls_connector};
 use crate::internal_events::AwsBytesSent;
 use crate::tls::{MaybeTlsSettings, TlsConfig};
 
@@ -77,6 +77,10 @@ pub trait ClientBuilder {
     type Client;
     type DefaultMiddleware: SmithyMiddleware<DynConnector> + Clone + Send + Sync + 'static;
 
+    /// Whether the client negotiates HTTP/2, which the event stream operations of some services
+    /// require. It isn't negotiated through proxies.
+    const HTTP2: bool = false;
+
     fn default_middleware() -> Self::DefaultMiddleware;
 
     fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client;
@@ -96,7 +100,11 @@ pub async fn create_smithy_client<T: ClientBuilder>(
         let hyper_client = aws_smithy_client::hyper_ext::Adapter::builder().build(proxy);
         aws_smithy_client::erase::DynConnector::new(hyper_client)
     } else {
-        let tls_connector = build_tls_connector(tls_settings)?;
+        let tls_connector = if T::HTTP2 {
+            build_http2_tls_connector(tls_settings)?
+        } else {
+            build_tls_connector(tls_settings)?
+        };
         let hyper_client = aws_smithy_client::hyper_ext::Adapter::builder().build(tls_connector);
         aws_smithy_client::erase::DynConnector::new(hyper_client)
     };
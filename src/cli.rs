@@ -2,13 +2,15 @@ use std::path::PathBuf;
 
 use clap::{AppSettings, FromArgMatches, IntoApp, Parser};
 
+#[cfg(feature = "api-client")]
+use crate::reload;
 #[cfg(windows)]
 use crate::service;
 #[cfg(feature = "api-client")]
 use crate::tap;
 #[cfg(feature = "api-client")]
 use crate::top;
-use crate::{config, generate, get_version, graph, list, unit_test, validate};
+use crate::{config, convert, generate, get_version, graph, list, unit_test, validate};
 
 #[derive(Parser, Debug)]
 #[clap(rename_all = "kebab-case")]
@@ -34,6 +36,7 @@ impl Opts {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Graph(_))
             | Some(SubCommand::Generate(_))
+            | Some(SubCommand::Convert(_))
             | Some(SubCommand::List(_))
             | Some(SubCommand::Test(_)) => {
                 if self.root.verbose == 0 {
@@ -179,6 +182,9 @@ pub enum SubCommand {
     /// Generate a Vector configuration containing a list of components.
     Generate(generate::Opts),
 
+    /// Convert a fluentd, Logstash or Filebeat configuration into a Vector configuration.
+    Convert(convert::Opts),
+
     /// Output a provided Vector configuration file/dir as a single JSON object, useful for checking in to version control.
     #[clap(hide = true)]
     Config(config::Opts),
@@ -201,6 +207,10 @@ pub enum SubCommand {
     #[cfg(feature = "api-client")]
     Tap(tap::Opts),
 
+    /// Push a configuration to a local or remote Vector instance, which validates and reloads it
+    #[cfg(feature = "api-client")]
+    Reload(reload::Opts),
+
     /// Manage the vector service.
     #[cfg(windows)]
     Service(service::Opts),
@@ -1,7 +1,15 @@
-use codecs::decoding::{DeserializerConfig, FramingConfig};
+use std::sync::Arc;
+
+use codecs::decoding::{AvroDeserializerConfig, Deserializer, DeserializerConfig, FramingConfig};
 use serde::{Deserialize, Serialize};
 
-use crate::codecs::Decoder;
+use crate::{
+    codecs::{
+        schema_registry::{SchemaRegistryConfig, SchemaRegistryResolver},
+        Decoder,
+    },
+    config::ProxyConfig,
+};
 
 /// Config used to build a `Decoder`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,13 +28,26 @@ impl DecodingConfig {
     }
 
     /// Builds a `Decoder` from the provided configuration.
-    pub fn build(&self) -> Decoder {
+    pub fn build(&self) -> crate::Result<Decoder> {
         // Build the framer.
-        let framer = self.framing.build();
+        let framer = self.framing.build()?;
 
-        // Build the deserializer.
-        let deserializer = self.decoding.build();
+        // Build the deserializer, resolving the Avro schemas from the registry if configured.
+        let deserializer = match &self.decoding {
+            DeserializerConfig::Avro { avro } => match &avro.schema_registry {
+                Some(registry) => {
+                    let registry =
+                        SchemaRegistryConfig::from(registry).build(&ProxyConfig::from_env())?;
+                    Deserializer::Avro(
+                        AvroDeserializerConfig { avro: avro.clone() }
+                            .build_with_resolver(Arc::new(SchemaRegistryResolver::new(registry)))?,
+                    )
+                }
+                None => self.decoding.build()?,
+            },
+            _ => self.decoding.build()?,
+        };
 
-        Decoder::new(framer, deserializer)
+        Ok(Decoder::new(framer, deserializer))
     }
 }
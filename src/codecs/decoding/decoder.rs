@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use bytes::{Bytes, BytesMut};
 use codecs::decoding::{
     format::Deserializer as _, BoxedFramingError, BytesDeserializer, Deserializer, Error, Framer,
@@ -5,6 +7,7 @@ use codecs::decoding::{
 };
 use smallvec::SmallVec;
 
+use super::DecoderDeadline;
 use crate::{
     event::Event,
     internal_events::{DecoderDeserializeFailed, DecoderFramingFailed},
@@ -69,6 +72,12 @@ impl Decoder {
     }
 }
 
+impl DecoderDeadline for Decoder {
+    fn deadline(&self) -> Option<Instant> {
+        self.framer.deadline()
+    }
+}
+
 impl tokio_util::codec::Decoder for Decoder {
     type Item = (SmallVec<[Event; 1]>, usize);
     type Error = Error;
@@ -0,0 +1,157 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use futures::{Future, Stream, StreamExt};
+use tokio::{
+    io::AsyncRead,
+    time::{sleep_until, Sleep},
+};
+use tokio_util::codec::{Decoder, FramedRead};
+
+/// A decoder that may hold back a frame until a deadline, such as a multiline message waiting for its
+/// next line.
+pub trait DecoderDeadline {
+    /// The instant at which the decoder has to be polled again, even if no more data is received,
+    /// to decode the frame it holds back, if any.
+    fn deadline(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// A `FramedRead` that polls its decoder again once the decoder's deadline is reached, so frames
+/// held back by the decoder are decoded in time even if no more data is received.
+pub struct DeadlineFramedRead<T, D> {
+    inner: FramedRead<T, D>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<T, D> DeadlineFramedRead<T, D>
+where
+    T: AsyncRead,
+    D: Decoder,
+{
+    /// Creates a new `DeadlineFramedRead` reading from the given reader with the given decoder.
+    pub fn new(inner: T, decoder: D) -> Self {
+        Self {
+            inner: FramedRead::new(inner, decoder),
+            sleep: None,
+        }
+    }
+}
+
+impl<T, D> DeadlineFramedRead<T, D> {
+    /// Returns a reference to the underlying `FramedRead`.
+    pub const fn get_ref(&self) -> &FramedRead<T, D> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying `FramedRead`.
+    pub fn get_mut(&mut self) -> &mut FramedRead<T, D> {
+        &mut self.inner
+    }
+}
+
+impl<T, D> Stream for DeadlineFramedRead<T, D>
+where
+    T: AsyncRead + Unpin,
+    D: Decoder + DecoderDeadline,
+{
+    type Item = Result<D::Item, D::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Poll::Ready(item) = self.inner.poll_next_unpin(cx) {
+                return Poll::Ready(item);
+            }
+
+            let deadline = match self.inner.decoder().deadline() {
+                Some(deadline) => tokio::time::Instant::from_std(deadline),
+                None => return Poll::Pending,
+            };
+            let sleep = self
+                .sleep
+                .get_or_insert_with(|| Box::pin(sleep_until(deadline)));
+            if sleep.deadline() != deadline {
+                sleep.as_mut().reset(deadline);
+            }
+            if sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            // The decoder is polled with the data read so far, which it couldn't decode a frame
+            // from yet, as no more data was received.
+            let mut buffer = std::mem::take(self.inner.read_buffer_mut());
+            let result = self.inner.decoder_mut().decode(&mut buffer);
+            *self.inner.read_buffer_mut() = buffer;
+            match result {
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                // The next deadline is waited for, unless the decoder didn't move it, in which case
+                // it's polled again once more data is received.
+                Ok(None)
+                    if self
+                        .inner
+                        .decoder()
+                        .deadline()
+                        .map(tokio::time::Instant::from_std)
+                        == Some(deadline) =>
+                {
+                    return Poll::Pending
+                }
+                Ok(None) => {}
+                Err(error) => return Poll::Ready(Some(Err(error))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use codecs::decoding::{
+        BytesDeserializer, Deserializer, Framer, MultilineDecoderConfig, MultilineDecoderOptions,
+        MultilineMode,
+    };
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::codecs::Decoder;
+
+    #[tokio::test]
+    async fn decodes_held_back_frames_without_more_data() {
+        let framer = MultilineDecoderConfig {
+            multiline: MultilineDecoderOptions {
+                start_pattern: "^[^\\s]".to_owned(),
+                condition_pattern: "^[\\s]+".to_owned(),
+                mode: MultilineMode::ContinueThrough,
+                timeout_ms: 50,
+                max_lines: None,
+                max_bytes: None,
+            },
+        }
+        .build()
+        .unwrap();
+        let decoder = Decoder::new(
+            Framer::Multiline(framer),
+            Deserializer::Bytes(BytesDeserializer::new()),
+        );
+
+        // The writer is kept open, so no more data is received and the stream doesn't end.
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(b"Exception\n  at a\n").await.unwrap();
+        let mut stream = DeadlineFramedRead::new(reader, decoder);
+
+        let (events, byte_size) = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("message should be flushed once it timed out")
+            .unwrap()
+            .unwrap();
+        assert_eq!(byte_size, "Exception\n  at a".len());
+        assert_eq!(events[0].as_log()["message"], "Exception\n  at a".into());
+        assert!(stream.get_ref().decoder().deadline().is_none());
+        drop(writer);
+    }
+}
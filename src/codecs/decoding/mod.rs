@@ -1,5 +1,7 @@
 mod config;
 mod decoder;
+mod framed_read;
 
 pub use config::DecodingConfig;
 pub use decoder::Decoder;
+pub use framed_read::{DeadlineFramedRead, DecoderDeadline};
@@ -113,9 +113,13 @@ impl Encoder<Framer> {
                 Serializer::Json(_) | Serializer::NativeJson(_),
                 Framer::CharacterDelimited(CharacterDelimitedEncoder { delimiter: b',' }),
             ) => "application/json",
-            (Serializer::Native(_), _) => "application/octet-stream",
+            (Serializer::Bson(_) | Serializer::Native(_), _) => "application/octet-stream",
+            (Serializer::Cbor(_), _) => "application/cbor",
+            (Serializer::Protobuf(_), _) => "application/x-protobuf",
             (
                 Serializer::Avro(_)
+                | Serializer::Cef(_)
+                | Serializer::Leef(_)
                 | Serializer::Json(_)
                 | Serializer::Logfmt(_)
                 | Serializer::NativeJson(_)
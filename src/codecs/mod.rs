@@ -6,7 +6,8 @@
 mod decoding;
 mod encoding;
 mod ready_frames;
+pub(crate) mod schema_registry;
 
-pub use decoding::{Decoder, DecodingConfig};
+pub use decoding::{DeadlineFramedRead, Decoder, DecoderDeadline, DecodingConfig};
 pub use encoding::{Encoder, EncodingConfig, EncodingConfigWithFraming};
 pub use ready_frames::ReadyFrames;
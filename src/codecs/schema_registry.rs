@@ -0,0 +1,231 @@
+//! Decoding of Avro messages framed with the Confluent Schema Registry wire format.
+//!
+//! Each message starts with a zero magic byte and the ID of its schema, as a big-endian 32-bit
+//! integer, followed by the Avro datum. Schemas are fetched from the registry by ID, and kept
+//! for the lifetime of the component, as a schema ID always refers to the same schema.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use avro_rs::Schema;
+use codecs::decoding::{
+    format::{avro_to_value, SCHEMA_REGISTRY_MAGIC_BYTE},
+    AvroSchemaRegistryOptions, AvroSchemaResolver,
+};
+use http::{header, Request, StatusCode, Uri};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+
+use crate::{
+    config::ProxyConfig,
+    event::Value,
+    http::{Auth, HttpClient, HttpError},
+    internal_events::SchemaRegistryRequestError,
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// The number of attempts at fetching a schema before giving up on the message.
+const FETCH_ATTEMPTS: usize = 3;
+
+/// How long to wait before fetching a schema again after an error.
+const FETCH_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Configuration for decoding Avro messages with a Confluent Schema Registry.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaRegistryConfig {
+    /// The URL of the schema registry.
+    pub url: String,
+
+    #[configurable(derived)]
+    pub auth: Option<Auth>,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
+impl From<&AvroSchemaRegistryOptions> for SchemaRegistryConfig {
+    fn from(options: &AvroSchemaRegistryOptions) -> Self {
+        let auth = options.username.as_ref().map(|user| Auth::Basic {
+            user: user.clone(),
+            password: options.password.clone().unwrap_or_default(),
+        });
+        Self {
+            url: options.url.clone(),
+            auth,
+            tls: None,
+        }
+    }
+}
+
+impl SchemaRegistryConfig {
+    pub(crate) fn build(&self, proxy: &ProxyConfig) -> crate::Result<SchemaRegistry> {
+        let url = self.url.trim_end_matches('/').to_string();
+        url.parse::<Uri>()?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, proxy)?;
+
+        Ok(SchemaRegistry {
+            client,
+            url,
+            auth: self.auth.clone(),
+            schemas: HashMap::new(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum SchemaRegistryError {
+    #[snafu(display("Message is not framed with the schema registry wire format"))]
+    InvalidFraming,
+    #[snafu(display("Failed to fetch schema {}: {}", id, source))]
+    Request { id: u32, source: HttpError },
+    #[snafu(display("Failed to read schema {}: {}", id, source))]
+    ReadBody { id: u32, source: hyper::Error },
+    #[snafu(display("Failed to fetch schema {}: unexpected status {}", id, status))]
+    UnexpectedStatus { id: u32, status: StatusCode },
+    #[snafu(display("Invalid response for schema {}: {}", id, source))]
+    InvalidResponse { id: u32, source: serde_json::Error },
+    #[snafu(display("Invalid schema {}: {}", id, source))]
+    InvalidSchema { id: u32, source: avro_rs::Error },
+    #[snafu(display("Failed to decode message with schema {}: {}", id, source))]
+    Decode { id: u32, source: avro_rs::Error },
+}
+
+impl SchemaRegistryError {
+    fn is_retriable(&self) -> bool {
+        match self {
+            Self::Request { source, .. } => source.is_retriable(),
+            Self::ReadBody { .. } => true,
+            Self::UnexpectedStatus { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+/// The response of the registry for `GET /schemas/ids/{id}`.
+#[derive(Deserialize)]
+struct SchemaResponse {
+    schema: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct SchemaRegistry {
+    client: HttpClient,
+    url: String,
+    auth: Option<Auth>,
+    schemas: HashMap<u32, Arc<Schema>>,
+}
+
+impl SchemaRegistry {
+    /// Decodes a message into a value, fetching its schema if it wasn't yet.
+    pub(crate) async fn decode(&mut self, payload: &[u8]) -> Result<Value, SchemaRegistryError> {
+        if payload.len() < 5 || payload[0] != SCHEMA_REGISTRY_MAGIC_BYTE {
+            return Err(SchemaRegistryError::InvalidFraming);
+        }
+        let id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+        let schema = self.schema(id).await?;
+
+        let value = avro_rs::from_avro_datum(&schema, &mut &payload[5..], None)
+            .context(DecodeSnafu { id })?;
+        Ok(avro_to_value(value, &schema))
+    }
+
+    pub(crate) async fn schema(&mut self, id: u32) -> Result<Arc<Schema>, SchemaRegistryError> {
+        if let Some(schema) = self.schemas.get(&id) {
+            return Ok(Arc::clone(schema));
+        }
+
+        let mut attempt = 1;
+        let schema = loop {
+            match self.fetch(id).await {
+                Ok(schema) => break Arc::new(schema),
+                Err(error) if error.is_retriable() && attempt < FETCH_ATTEMPTS => {
+                    emit!(SchemaRegistryRequestError { error: &error });
+                    attempt += 1;
+                    tokio::time::sleep(FETCH_RETRY_DELAY).await;
+                }
+                Err(error) => return Err(error),
+            }
+        };
+        self.schemas.insert(id, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    async fn fetch(&self, id: u32) -> Result<Schema, SchemaRegistryError> {
+        let mut request = Request::get(format!("{}/schemas/ids/{}", self.url, id))
+            .header(header::ACCEPT, "application/vnd.schemaregistry.v1+json")
+            .body(Body::empty())
+            .expect("error creating request");
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self
+            .client
+            .send(request)
+            .await
+            .context(RequestSnafu { id })?;
+        let (parts, body) = response.into_parts();
+        if parts.status != StatusCode::OK {
+            return Err(SchemaRegistryError::UnexpectedStatus {
+                id,
+                status: parts.status,
+            });
+        }
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadBodySnafu { id })?;
+        let response: SchemaResponse =
+            serde_json::from_slice(&body).context(InvalidResponseSnafu { id })?;
+        Schema::parse_str(&response.schema).context(InvalidSchemaSnafu { id })
+    }
+}
+
+/// Resolves the schemas of the Avro decoder from the registry.
+///
+/// Decoders are synchronous, so the current thread blocks while a schema is fetched. This only
+/// happens once per schema.
+#[derive(Debug)]
+pub(crate) struct SchemaRegistryResolver {
+    registry: tokio::sync::Mutex<SchemaRegistry>,
+}
+
+impl SchemaRegistryResolver {
+    pub(crate) fn new(registry: SchemaRegistry) -> Self {
+        Self {
+            registry: tokio::sync::Mutex::new(registry),
+        }
+    }
+}
+
+impl AvroSchemaResolver for SchemaRegistryResolver {
+    fn resolve(&self, id: u32) -> vector_core::Result<Arc<Schema>> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(async { self.registry.lock().await.schema(id).await })
+        })
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_unframed_messages() {
+        let config = SchemaRegistryConfig {
+            url: "http://localhost:8081".into(),
+            auth: None,
+            tls: None,
+        };
+        let mut registry = config.build(&ProxyConfig::default()).unwrap();
+        assert!(matches!(
+            registry.decode(b"{\"message\": \"hello\"}").await,
+            Err(SchemaRegistryError::InvalidFraming)
+        ));
+    }
+}
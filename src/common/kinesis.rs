@@ -0,0 +1,39 @@
+use crate::aws::ClientBuilder;
+
+pub(crate) struct KinesisClientBuilder;
+
+impl ClientBuilder for KinesisClientBuilder {
+    type Config = aws_sdk_kinesis::config::Config;
+    type Client = aws_sdk_kinesis::client::Client;
+    type DefaultMiddleware = aws_sdk_kinesis::middleware::DefaultMiddleware;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_kinesis::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        aws_sdk_kinesis::client::Client::with_config(client, config.into())
+    }
+}
+
+/// Builds Kinesis clients negotiating HTTP/2, which the subscriptions to shards of enhanced fan-out
+/// consumers require.
+#[cfg(feature = "sources-aws_kinesis_streams")]
+pub(crate) struct KinesisHttp2ClientBuilder;
+
+#[cfg(feature = "sources-aws_kinesis_streams")]
+impl ClientBuilder for KinesisHttp2ClientBuilder {
+    type Config = aws_sdk_kinesis::config::Config;
+    type Client = aws_sdk_kinesis::client::Client;
+    type DefaultMiddleware = aws_sdk_kinesis::middleware::DefaultMiddleware;
+
+    const HTTP2: bool = true;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_kinesis::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        aws_sdk_kinesis::client::Client::with_config(client, config.into())
+    }
+}
@@ -9,6 +9,22 @@
 ))]
 pub(crate) mod datadog;
 
+#[cfg(any(
+    feature = "sources-aws_cloudwatch_logs",
+    feature = "sinks-aws_cloudwatch_logs"
+))]
+pub(crate) mod cloudwatch_logs;
+
+#[cfg(feature = "sources-aws_kinesis_streams")]
+pub(crate) mod dynamodb;
+
+#[cfg(any(
+    feature = "sources-aws_cloudwatch_logs",
+    feature = "sources-aws_kinesis_streams",
+    feature = "sinks-aws_kinesis_streams"
+))]
+pub(crate) mod kinesis;
+
 #[cfg(any(
     feature = "sources-aws_sqs",
     feature = "sinks-aws_sqs",
@@ -16,5 +32,10 @@ pub(crate) mod datadog;
 ))]
 pub(crate) mod sqs;
 
-#[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
+#[cfg(any(
+    feature = "sources-aws_s3",
+    feature = "sinks-aws_s3",
+    feature = "providers-aws_s3",
+    feature = "sources-utils-checkpoint"
+))]
 pub(crate) mod s3;
@@ -2,7 +2,7 @@ use std::net::{Ipv4Addr, SocketAddr};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -13,6 +13,49 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// Token that must be presented as a bearer token to push configuration to the `/reload`
+    /// endpoint. The endpoint is disabled unless a token is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reload_token: Option<String>,
+
+    /// Whether the `/debug/pprof/profile` and `/debug/pprof/heap` endpoints are served, which
+    /// respond with CPU and heap profiles in the pprof format.
+    #[serde(default)]
+    pub profiling: bool,
+
+    /// Criteria under which the `/health/ready` endpoint reports Vector as ready.
+    #[serde(default)]
+    pub readiness: ReadinessOptions,
+}
+
+/// Criteria under which the components are ready, on top of their tasks running.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadinessOptions {
+    /// Whether the sinks must have passed their healthcheck, rather than having one pending or
+    /// failed. Sinks with healthchecks disabled are ready regardless.
+    pub require_healthchecks: bool,
+
+    /// The ratio of the capacity of the buffer of a sink, between 0 and 1, past which the sink
+    /// isn't ready.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_buffer_utilization: Option<f64>,
+
+    /// The number of seconds without events past which a source isn't ready. Sources that haven't
+    /// sent any events yet are ready regardless.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_source_idle_secs: Option<u64>,
+}
+
+impl Default for ReadinessOptions {
+    fn default() -> Self {
+        Self {
+            require_healthchecks: true,
+            max_buffer_utilization: None,
+            max_source_idle_secs: None,
+        }
+    }
 }
 
 impl Default for Options {
@@ -21,6 +64,9 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            reload_token: None,
+            profiling: false,
+            readiness: ReadinessOptions::default(),
         }
     }
 }
@@ -61,15 +107,58 @@ impl Options {
             }
         };
 
+        // Try to merge reload token
+        let reload_token = match (self.reload_token.take(), other.reload_token) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(_), Some(_)) => return Err("Conflicting `api` reload token.".to_owned()),
+        };
+
+        // Try to merge readiness criteria, preferring non default ones
+        let readiness = match (self.readiness, other.readiness) {
+            (a, b) if a == b => a,
+            (a, b) if b == ReadinessOptions::default() => a,
+            (a, b) if a == ReadinessOptions::default() => b,
+            (_, _) => return Err("Conflicting `api` readiness criteria.".to_owned()),
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            reload_token,
+            profiling: self.profiling | other.profiling,
+            readiness,
         };
 
         *self = options;
         Ok(())
     }
+
+    pub(super) fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.profiling && !cfg!(feature = "api-profiling") {
+            errors.push(
+                "`api.profiling` requires Vector to be built with the `api-profiling` feature."
+                    .to_owned(),
+            );
+        }
+        if let Some(max_buffer_utilization) = self.readiness.max_buffer_utilization {
+            if !(0.0..=1.0).contains(&max_buffer_utilization) {
+                errors.push(
+                    "`api.readiness.max_buffer_utilization` must be between 0 and 1.".to_owned(),
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
 #[test]
@@ -78,6 +167,9 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        reload_token: None,
+        profiling: false,
+        readiness: ReadinessOptions::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -88,6 +180,9 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            reload_token: None,
+            profiling: false,
+            readiness: ReadinessOptions::default(),
         }
     );
 }
@@ -99,6 +194,9 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        reload_token: None,
+        profiling: false,
+        readiness: ReadinessOptions::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -109,6 +207,9 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            reload_token: None,
+            profiling: false,
+            readiness: ReadinessOptions::default(),
         }
     );
 }
@@ -127,3 +228,75 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn reload_token_merge() {
+    let mut a = Options {
+        reload_token: Some("secret".to_owned()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.reload_token.as_deref(), Some("secret"));
+
+    let mut b = Options::default();
+    b.merge(a).unwrap();
+    assert_eq!(b.reload_token.as_deref(), Some("secret"));
+}
+
+#[test]
+fn reload_token_conflict() {
+    let mut a = Options {
+        reload_token: Some("secret".to_owned()),
+        ..Options::default()
+    };
+
+    let b = Options {
+        reload_token: Some("other".to_owned()),
+        ..Options::default()
+    };
+
+    assert!(a.merge(b).is_err());
+}
+
+#[test]
+fn readiness_merge() {
+    let readiness = ReadinessOptions {
+        max_buffer_utilization: Some(0.9),
+        ..ReadinessOptions::default()
+    };
+    let mut a = Options {
+        readiness,
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.readiness, readiness);
+
+    let b = Options {
+        readiness: ReadinessOptions {
+            require_healthchecks: false,
+            ..ReadinessOptions::default()
+        },
+        ..Options::default()
+    };
+    assert!(a.merge(b).is_err());
+}
+
+#[test]
+fn readiness_validation() {
+    let options = Options {
+        readiness: ReadinessOptions {
+            max_buffer_utilization: Some(1.5),
+            ..ReadinessOptions::default()
+        },
+        ..Options::default()
+    };
+
+    assert_eq!(
+        options.validate(),
+        Err(vec![
+            "`api.readiness.max_buffer_utilization` must be between 0 and 1.".to_owned()
+        ])
+    );
+}
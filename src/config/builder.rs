@@ -13,9 +13,10 @@ use super::api;
 #[cfg(feature = "enterprise")]
 use super::enterprise;
 use super::{
-    compiler, provider, schema, ComponentKey, Config, EnrichmentTableConfig, EnrichmentTableOuter,
-    HealthcheckOptions, SecretBackend, SinkConfig, SinkOuter, SourceConfig, SourceOuter,
-    TestDefinition, TransformOuter,
+    compiler, provider, schema, ComponentKey, Config, DeliveryAuditConfig, EnrichmentTableConfig,
+    EnrichmentTableOuter, HealthcheckOptions, MemoryBudgetConfig, RateLimitGroupConfig,
+    SecretBackend, ShutdownOptions, SinkConfig, SinkOuter, SourceConfig, SourceOuter,
+    TelemetryExportConfig, TenantConfig, TestDefinition, TransformOuter,
 };
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -34,6 +35,8 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub healthchecks: HealthcheckOptions,
     #[serde(default)]
+    pub shutdown: ShutdownOptions,
+    #[serde(default)]
     pub enrichment_tables: IndexMap<ComponentKey, EnrichmentTableOuter>,
     #[serde(default)]
     pub sources: IndexMap<ComponentKey, SourceOuter>,
@@ -46,6 +49,16 @@ pub struct ConfigBuilder {
     pub provider: Option<Box<dyn provider::ProviderConfig>>,
     #[serde(default)]
     pub secret: IndexMap<ComponentKey, Box<dyn SecretBackend>>,
+    #[serde(default)]
+    pub tenants: IndexMap<String, TenantConfig>,
+    #[serde(default)]
+    pub rate_limit_groups: IndexMap<String, RateLimitGroupConfig>,
+    #[serde(default)]
+    pub memory_budget: Option<MemoryBudgetConfig>,
+    #[serde(default)]
+    pub telemetry_export: Option<TelemetryExportConfig>,
+    #[serde(default)]
+    pub delivery_audit: Option<DeliveryAuditConfig>,
 }
 
 #[cfg(feature = "enterprise")]
@@ -56,6 +69,8 @@ struct ConfigBuilderHash<'a> {
     schema: &'a schema::Options,
     global: &'a GlobalOptions,
     healthchecks: &'a HealthcheckOptions,
+    #[serde(skip_serializing_if = "ShutdownOptions::is_default")]
+    shutdown: &'a ShutdownOptions,
     enrichment_tables: BTreeMap<&'a ComponentKey, &'a EnrichmentTableOuter>,
     sources: BTreeMap<&'a ComponentKey, &'a SourceOuter>,
     sinks: BTreeMap<&'a ComponentKey, &'a SinkOuter<String>>,
@@ -63,6 +78,16 @@ struct ConfigBuilderHash<'a> {
     tests: &'a Vec<TestDefinition<String>>,
     provider: &'a Option<Box<dyn provider::ProviderConfig>>,
     secret: BTreeMap<&'a ComponentKey, &'a dyn SecretBackend>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tenants: BTreeMap<&'a String, &'a TenantConfig>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    rate_limit_groups: BTreeMap<&'a String, &'a RateLimitGroupConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_budget: &'a Option<MemoryBudgetConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    telemetry_export: &'a Option<TelemetryExportConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivery_audit: &'a Option<DeliveryAuditConfig>,
 }
 
 #[cfg(feature = "enterprise")]
@@ -134,6 +159,7 @@ impl<'a> From<&'a ConfigBuilder> for ConfigBuilderHash<'a> {
             schema: &value.schema,
             global: &value.global,
             healthchecks: &value.healthchecks,
+            shutdown: &value.shutdown,
             enrichment_tables: value.enrichment_tables.iter().collect(),
             sources: value.sources.iter().collect(),
             sinks: value.sinks.iter().collect(),
@@ -141,6 +167,11 @@ impl<'a> From<&'a ConfigBuilder> for ConfigBuilderHash<'a> {
             tests: &value.tests,
             provider: &value.provider,
             secret: value.secret.iter().map(|(k, v)| (k, v.as_ref())).collect(),
+            tenants: value.tenants.iter().collect(),
+            rate_limit_groups: value.rate_limit_groups.iter().collect(),
+            memory_budget: &value.memory_budget,
+            telemetry_export: &value.telemetry_export,
+            delivery_audit: &value.delivery_audit,
         }
     }
 }
@@ -167,12 +198,18 @@ impl From<Config> for ConfigBuilder {
             #[cfg(feature = "enterprise")]
             enterprise,
             healthchecks,
+            shutdown,
             enrichment_tables,
             sources,
             sinks,
             transforms,
             tests,
             secret,
+            tenants,
+            rate_limit_groups,
+            memory_budget,
+            telemetry_export,
+            delivery_audit,
             ..
         } = config;
 
@@ -196,6 +233,7 @@ impl From<Config> for ConfigBuilder {
             #[cfg(feature = "enterprise")]
             enterprise,
             healthchecks,
+            shutdown,
             enrichment_tables,
             sources,
             sinks,
@@ -203,10 +241,78 @@ impl From<Config> for ConfigBuilder {
             provider: None,
             tests,
             secret,
+            tenants,
+            rate_limit_groups,
+            memory_budget,
+            telemetry_export,
+            delivery_audit,
         }
     }
 }
 
+impl From<&Config> for ConfigBuilder {
+    /// Copies the configuration of a running topology, so that it can be edited and built again.
+    /// Unit tests and secrets aren't copied, as secrets have already been resolved.
+    fn from(config: &Config) -> Self {
+        // Like cloning, this goes through JSON as trait objects can't be cloned. Inputs are
+        // turned back into the strings they were parsed from, except for the dead letter outputs
+        // of sinks, which are added back from their `dead_letter` option.
+        fn with_string_inputs<T: Serialize>(
+            component: &T,
+            inputs: &[super::OutputId],
+            config: &Config,
+        ) -> serde_json::Value {
+            let mut json = serde_json::to_value(component).unwrap();
+            json["inputs"] = inputs
+                .iter()
+                .filter(|input| config.sink(&input.component).is_none())
+                .map(ToString::to_string)
+                .collect();
+            json
+        }
+
+        let transforms = config
+            .transforms
+            .iter()
+            .map(|(key, transform)| {
+                (
+                    key,
+                    with_string_inputs(transform, &transform.inputs, config),
+                )
+            })
+            .collect::<IndexMap<_, _>>();
+        let sinks = config
+            .sinks
+            .iter()
+            .map(|(key, sink)| (key, with_string_inputs(sink, &sink.inputs, config)))
+            .collect::<IndexMap<_, _>>();
+
+        // Global options are flattened into the top level of the configuration.
+        let mut json = serde_json::to_value(&config.global).unwrap();
+        json["schema"] = serde_json::to_value(&config.schema).unwrap();
+        json["healthchecks"] = serde_json::to_value(&config.healthchecks).unwrap();
+        json["shutdown"] = serde_json::to_value(&config.shutdown).unwrap();
+        json["enrichment_tables"] = serde_json::to_value(&config.enrichment_tables).unwrap();
+        json["sources"] = serde_json::to_value(&config.sources).unwrap();
+        json["transforms"] = serde_json::to_value(transforms).unwrap();
+        json["sinks"] = serde_json::to_value(sinks).unwrap();
+        json["tenants"] = serde_json::to_value(&config.tenants).unwrap();
+        json["rate_limit_groups"] = serde_json::to_value(&config.rate_limit_groups).unwrap();
+        json["memory_budget"] = serde_json::to_value(&config.memory_budget).unwrap();
+        json["telemetry_export"] = serde_json::to_value(&config.telemetry_export).unwrap();
+        json["delivery_audit"] = serde_json::to_value(&config.delivery_audit).unwrap();
+        #[cfg(feature = "api")]
+        {
+            json["api"] = serde_json::to_value(&config.api).unwrap();
+        }
+        #[cfg(feature = "enterprise")]
+        {
+            json["enterprise"] = serde_json::to_value(&config.enterprise).unwrap();
+        }
+        serde_json::from_value(json).unwrap()
+    }
+}
+
 impl ConfigBuilder {
     pub fn build(self) -> Result<Config, Vec<String>> {
         let (config, warnings) = self.build_with_warnings()?;
@@ -269,6 +375,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            shutdown_timeout_secs: None,
         };
 
         self.transforms
@@ -328,6 +435,10 @@ impl ConfigBuilder {
 
         self.healthchecks.merge(with.healthchecks);
 
+        if let Err(merge_errors) = self.shutdown.merge(with.shutdown) {
+            errors.extend(merge_errors);
+        }
+
         with.enrichment_tables.keys().for_each(|k| {
             if self.enrichment_tables.contains_key(k) {
                 errors.push(format!("duplicate enrichment_table name found: {}", k));
@@ -358,6 +469,35 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate secret id found: {}", k));
             }
         });
+        with.tenants.keys().for_each(|k| {
+            if self.tenants.contains_key(k) {
+                errors.push(format!("duplicate tenant name found: {}", k));
+            }
+        });
+        with.rate_limit_groups.keys().for_each(|k| {
+            if self.rate_limit_groups.contains_key(k) {
+                errors.push(format!("duplicate rate limit group name found: {}", k));
+            }
+        });
+
+        if self.memory_budget.is_some()
+            && with.memory_budget.is_some()
+            && self.memory_budget != with.memory_budget
+        {
+            errors.push("conflicting values for 'memory_budget' found".to_owned());
+        }
+        if self.telemetry_export.is_some()
+            && with.telemetry_export.is_some()
+            && self.telemetry_export != with.telemetry_export
+        {
+            errors.push("conflicting values for 'telemetry_export' found".to_owned());
+        }
+        if self.delivery_audit.is_some()
+            && with.delivery_audit.is_some()
+            && self.delivery_audit != with.delivery_audit
+        {
+            errors.push("conflicting values for 'delivery_audit' found".to_owned());
+        }
         if !errors.is_empty() {
             return Err(errors);
         }
@@ -368,6 +508,17 @@ impl ConfigBuilder {
         self.transforms.extend(with.transforms);
         self.tests.extend(with.tests);
         self.secret.extend(with.secret);
+        self.tenants.extend(with.tenants);
+        self.rate_limit_groups.extend(with.rate_limit_groups);
+        if with.memory_budget.is_some() {
+            self.memory_budget = with.memory_budget;
+        }
+        if with.telemetry_export.is_some() {
+            self.telemetry_export = with.telemetry_export;
+        }
+        if with.delivery_audit.is_some() {
+            self.delivery_audit = with.delivery_audit;
+        }
 
         Ok(())
     }
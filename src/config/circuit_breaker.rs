@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use super::ComponentKey;
+
+/// Stops handing events to a sink failing to deliver them, falling back to pausing it or to
+/// routing the events to its `dead_letter` component, until a probe is delivered again.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive batches of events the sink failed to deliver, whether errored
+    /// or rejected, after which the circuit breaker opens.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// How long the circuit breaker stays open before handing the sink a batch of events to probe
+    /// whether it recovered.
+    #[serde(default = "default_probe_interval_secs")]
+    pub probe_interval_secs: u64,
+
+    /// What happens to the events while the circuit breaker is open.
+    #[serde(default)]
+    pub fallback: CircuitBreakerFallback,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerFallback {
+    /// The events are held back, applying backpressure to the components sending them.
+    Pause,
+    /// The events are routed to the `dead_letter` component of the sink, with the `circuit_open`
+    /// reason.
+    DeadLetter,
+}
+
+impl Default for CircuitBreakerFallback {
+    fn default() -> Self {
+        Self::Pause
+    }
+}
+
+const fn default_failure_threshold() -> u32 {
+    5
+}
+
+const fn default_probe_interval_secs() -> u64 {
+    30
+}
+
+impl CircuitBreakerConfig {
+    pub(super) fn validate(
+        &self,
+        key: &ComponentKey,
+        dead_letter: bool,
+    ) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.failure_threshold == 0 {
+            errors.push(format!(
+                "Sink \"{}\": `circuit_breaker.failure_threshold` must be greater than zero.",
+                key
+            ));
+        }
+        if self.probe_interval_secs == 0 {
+            errors.push(format!(
+                "Sink \"{}\": `circuit_breaker.probe_interval_secs` must be greater than zero.",
+                key
+            ));
+        }
+        if self.fallback == CircuitBreakerFallback::DeadLetter && !dead_letter {
+            errors.push(format!(
+                "Sink \"{}\": the `dead_letter` fallback of the circuit breaker requires the `dead_letter` option.",
+                key
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::{CircuitBreakerConfig, CircuitBreakerFallback};
+    use crate::config::{ComponentKey, ConfigBuilder};
+
+    #[test]
+    fn parses_defaults() {
+        let config: CircuitBreakerConfig = toml::from_str("").unwrap();
+        assert_eq!(
+            config,
+            CircuitBreakerConfig {
+                failure_threshold: 5,
+                probe_interval_secs: 30,
+                fallback: CircuitBreakerFallback::Pause,
+            }
+        );
+    }
+
+    #[test]
+    fn validates() {
+        let key = ComponentKey::from("out");
+        let config: CircuitBreakerConfig = toml::from_str(indoc! {r#"
+            failure_threshold = 0
+            fallback = "dead_letter"
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            config.validate(&key, false).unwrap_err(),
+            vec![
+                "Sink \"out\": `circuit_breaker.failure_threshold` must be greater than zero."
+                    .to_owned(),
+                "Sink \"out\": the `dead_letter` fallback of the circuit breaker requires the `dead_letter` option."
+                    .to_owned(),
+            ]
+        );
+
+        let config = CircuitBreakerConfig {
+            failure_threshold: 3,
+            ..config
+        };
+        assert!(config.validate(&key, true).is_ok());
+    }
+
+    #[test]
+    fn parses_sink_option() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [sources.in]
+            type = "basic_source"
+
+            [sinks.out]
+            type = "basic_sink"
+            inputs = ["in"]
+            dead_letter = "failed"
+            circuit_breaker.fallback = "dead_letter"
+
+            [sinks.failed]
+            type = "basic_sink"
+            inputs = []
+        "#})
+        .unwrap();
+
+        let sink = &config.sinks[&ComponentKey::from("out")];
+        assert_eq!(
+            sink.circuit_breaker.map(|config| config.fallback),
+            Some(CircuitBreakerFallback::DeadLetter)
+        );
+        assert!(config.build().is_ok());
+    }
+}
@@ -10,6 +10,9 @@ use crate::config;
 #[derive(Parser, Debug, Clone)]
 #[clap(rename_all = "kebab-case")]
 pub struct Opts {
+    #[clap(subcommand)]
+    sub_command: Option<SubCommand>,
+
     /// Pretty print JSON
     #[clap(short, long)]
     pretty: bool,
@@ -57,6 +60,78 @@ pub struct Opts {
     pub config_dirs: Vec<PathBuf>,
 }
 
+#[derive(Parser, Debug, Clone)]
+#[clap(rename_all = "kebab-case")]
+enum SubCommand {
+    /// Output the configuration after resolving includes and overlays, without interpolating
+    /// environment variables or secrets.
+    Render(RenderOpts),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(rename_all = "kebab-case")]
+struct RenderOpts {
+    /// Format to render the configuration in
+    #[clap(default_value = "toml", possible_values = &["toml", "json", "yaml"], short, long)]
+    format: String,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[clap(
+        name = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        use_value_delimiter(true)
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[clap(name = "config-toml", long, use_value_delimiter(true))]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[clap(name = "config-json", long, use_value_delimiter(true))]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[clap(name = "config-yaml", long, use_value_delimiter(true))]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[clap(
+        name = "config-dir",
+        short = 'C',
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        use_value_delimiter(true)
+    )]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl RenderOpts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::Toml)),
+            (&self.paths_json, Some(config::Format::Json)),
+            (&self.paths_yaml, Some(config::Format::Yaml)),
+        ])
+        .map(|(path, hint)| config::ConfigPath::File(path, hint))
+        .chain(
+            self.config_dirs
+                .iter()
+                .map(|dir| config::ConfigPath::Dir(dir.to_path_buf())),
+        )
+        .collect()
+    }
+}
+
 impl Opts {
     fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
         config::merge_path_lists(vec![
@@ -163,6 +238,10 @@ fn serialize_to_json(
 /// Pipelines expansions, etc. The JSON result of this serialization can itself be used as a config,
 /// which also makes it useful for version control or treating as a singular unit of configuration.
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    if let Some(SubCommand::Render(render_opts)) = &opts.sub_command {
+        return render(render_opts);
+    }
+
     let paths = opts.paths_with_formats();
     // Start by serializing to a `ConfigBuilder`. This will leverage validation in config
     // builder fields which we'll use to error out if required.
@@ -190,6 +269,45 @@ pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
     exitcode::OK
 }
 
+/// Serializes a raw user config to the given format.
+fn render_source(source: &toml::value::Table, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(source).map_err(|e| e.to_string()),
+        "yaml" => serde_yaml::to_string(source).map_err(|e| e.to_string()),
+        _ => toml::to_string_pretty(source).map_err(|e| e.to_string()),
+    }
+}
+
+/// Function used by the `vector config render` subcommand for outputting the configuration after
+/// resolving includes and overlays, so the result of deep-merging a base config with the overlays
+/// extending it can be inspected. Like the raw config output by `vector config`, environment
+/// variables and secrets are left as-is.
+fn render(opts: &RenderOpts) -> exitcode::ExitCode {
+    let paths = match process_paths(&opts.paths_with_formats()) {
+        Some(paths) => paths,
+        None => return exitcode::CONFIG,
+    };
+
+    let source = match load_source_from_paths(&paths) {
+        Ok((source, _)) => source,
+        Err(errs) => return handle_config_errors(errs),
+    };
+
+    match render_source(&source, &opts.format) {
+        Ok(rendered) => {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{}", rendered);
+            }
+            exitcode::OK
+        }
+        Err(error) => {
+            error!(message = "Failed to render configuration.", %error);
+            exitcode::SOFTWARE
+        }
+    }
+}
+
 #[cfg(all(test, feature = "sources", feature = "transforms", feature = "sinks"))]
 mod tests {
     use std::collections::HashMap;
@@ -3,7 +3,8 @@ use std::collections::HashSet;
 use indexmap::{IndexMap, IndexSet};
 
 use super::{
-    builder::ConfigBuilder, graph::Graph, schema, validation, ComponentKey, Config, OutputId,
+    builder::ConfigBuilder, graph::Graph, rate_limit_group, schema, tenant, validation,
+    ComponentKey, Config, OutputId,
 };
 
 /// to handle the expansions when building the graph we need to be able to get the list of inputs
@@ -53,6 +54,45 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         errors.extend(output_errors);
     }
 
+    let (tenant_components, tenant_warnings) = match tenant::assign(&builder) {
+        Ok(assigned) => assigned,
+        Err(tenant_errors) => {
+            errors.extend(tenant_errors);
+            Default::default()
+        }
+    };
+
+    let sink_rate_limit_groups = match rate_limit_group::assign(&builder) {
+        Ok(assigned) => assigned,
+        Err(rate_limit_group_errors) => {
+            errors.extend(rate_limit_group_errors);
+            Default::default()
+        }
+    };
+
+    if let Some(memory_budget) = &builder.memory_budget {
+        if let Err(memory_budget_errors) = memory_budget.validate() {
+            errors.extend(memory_budget_errors);
+        }
+    }
+
+    if let Some(telemetry_export) = &builder.telemetry_export {
+        if let Err(telemetry_export_errors) = telemetry_export.validate() {
+            errors.extend(telemetry_export_errors);
+        }
+    }
+
+    #[cfg(feature = "api")]
+    if let Err(api_errors) = builder.api.validate() {
+        errors.extend(api_errors);
+    }
+
+    if let Some(delivery_audit) = &builder.delivery_audit {
+        if let Err(delivery_audit_errors) = delivery_audit.validate() {
+            errors.extend(delivery_audit_errors);
+        }
+    }
+
     #[cfg(feature = "enterprise")]
     let version = Some(builder.sha256_hash());
 
@@ -67,6 +107,7 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         #[cfg(feature = "enterprise")]
         enterprise,
         healthchecks,
+        shutdown,
         enrichment_tables,
         sources,
         sinks,
@@ -74,6 +115,11 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         tests,
         provider: _,
         secret,
+        tenants,
+        rate_limit_groups,
+        memory_budget,
+        telemetry_export,
+        delivery_audit,
     } = builder;
 
     let str_expansions = to_string_expansions(&expansions);
@@ -124,6 +170,7 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
             enterprise,
             version,
             healthchecks,
+            shutdown,
             enrichment_tables,
             sources,
             sinks,
@@ -131,11 +178,19 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
             tests,
             expansions,
             secret,
+            tenants,
+            tenant_components,
+            rate_limit_groups,
+            sink_rate_limit_groups,
+            memory_budget,
+            telemetry_export,
+            delivery_audit,
         };
 
         config.propagate_acknowledgements()?;
 
-        let warnings = validation::warnings(&config);
+        let mut warnings = validation::warnings(&config);
+        warnings.extend(tenant_warnings);
 
         Ok((config, warnings))
     } else {
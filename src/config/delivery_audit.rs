@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// Records the path through the topology of a sample of the events, along with when they went
+/// through each component and whether they were delivered in the end, to be queried through the
+/// API.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DeliveryAuditConfig {
+    /// The rate at which events are sampled, one out of every `sample_rate` events coming out of
+    /// the sources.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u64,
+
+    /// The maximum number of audit records kept, past which the oldest are discarded.
+    #[serde(default = "default_max_records")]
+    pub max_records: usize,
+}
+
+const fn default_sample_rate() -> u64 {
+    1000
+}
+
+const fn default_max_records() -> usize {
+    1000
+}
+
+impl DeliveryAuditConfig {
+    pub(super) fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.sample_rate == 0 {
+            errors.push("`delivery_audit.sample_rate` must be greater than zero.".to_owned());
+        }
+        if self.max_records == 0 {
+            errors.push("`delivery_audit.max_records` must be greater than zero.".to_owned());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::DeliveryAuditConfig;
+    use crate::config::ConfigBuilder;
+
+    #[test]
+    fn parses_defaults() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [delivery_audit]
+            sample_rate = 10
+        "#})
+        .unwrap();
+        let delivery_audit = config.delivery_audit.unwrap();
+
+        assert_eq!(delivery_audit.validate(), Ok(()));
+        assert_eq!(delivery_audit.sample_rate, 10);
+        assert_eq!(delivery_audit.max_records, 1000);
+    }
+
+    #[test]
+    fn rejects_zero_values() {
+        let config = DeliveryAuditConfig {
+            sample_rate: 0,
+            max_records: 0,
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "`delivery_audit.sample_rate` must be greater than zero.".to_owned(),
+                "`delivery_audit.max_records` must be greater than zero.".to_owned(),
+            ])
+        );
+    }
+}
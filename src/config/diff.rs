@@ -18,12 +18,31 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
-        ConfigDiff {
+        let mut diff = ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
             sinks: Difference::new(&old.sinks, &new.sinks),
             enrichment_tables: Difference::new(&old.enrichment_tables, &new.enrichment_tables),
-        }
+        };
+
+        // Components are also rebuilt when their tenant changes, as they are limited and
+        // isolated according to it.
+        let tenant_changed = |key: &ComponentKey| old.tenant(key) != new.tenant(key);
+        diff.sources
+            .change_where(&old.sources, &new.sources, tenant_changed);
+        diff.transforms
+            .change_where(&old.transforms, &new.transforms, tenant_changed);
+        diff.sinks
+            .change_where(&old.sinks, &new.sinks, tenant_changed);
+
+        // Sinks are also rebuilt when the budget of their rate limit group changes, so all of
+        // the sinks of the group share the new one.
+        let rate_limit_group_changed =
+            |key: &ComponentKey| old.rate_limit_group(key) != new.rate_limit_group(key);
+        diff.sinks
+            .change_where(&old.sinks, &new.sinks, rate_limit_group_changed);
+
+        diff
     }
 
     /// Swaps removed with added in Differences.
@@ -92,6 +111,21 @@ impl Difference {
         }
     }
 
+    /// Marks components present in both configs as changed if they match `predicate`.
+    fn change_where<C>(
+        &mut self,
+        old: &IndexMap<ComponentKey, C>,
+        new: &IndexMap<ComponentKey, C>,
+        predicate: impl Fn(&ComponentKey) -> bool,
+    ) {
+        let changed = old
+            .keys()
+            .filter(|key| new.contains_key(*key) && predicate(key))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.to_change.extend(changed);
+    }
+
     /// Checks whether or not any components are being changed or added.
     pub fn any_changed_or_added(&self) -> bool {
         !(self.to_change.is_empty() && self.to_add.is_empty())
@@ -4,6 +4,7 @@ use indexmap::{set::IndexSet, IndexMap};
 
 use super::{
     schema, ComponentKey, DataType, Output, OutputId, SinkOuter, SourceOuter, TransformOuter,
+    DEAD_LETTER_OUTPUT,
 };
 
 #[derive(Debug, Clone)]
@@ -110,6 +111,16 @@ impl Graph {
             }
         }
 
+        // Sinks with a dead letter component feed the events they failed to deliver into it, as
+        // if it had their dead letter output as an input.
+        for (id, config) in sinks.iter() {
+            if let Some(dead_letter) = &config.dead_letter {
+                if let Err(e) = graph.add_dead_letter(id, dead_letter) {
+                    errors.push(e);
+                }
+            }
+        }
+
         if ignore_errors || errors.is_empty() {
             Ok(graph)
         } else {
@@ -148,6 +159,30 @@ impl Graph {
         }
     }
 
+    fn add_dead_letter(&mut self, from: &ComponentKey, to: &ComponentKey) -> Result<(), String> {
+        match self.nodes.get(to) {
+            _ if from == to => Err(format!(
+                "Dead letter component for sink \"{}\" can't be the sink itself.",
+                from
+            )),
+            Some(Node::Transform { .. } | Node::Sink { .. }) => {
+                self.edges.push(Edge {
+                    from: OutputId::from((from, DEAD_LETTER_OUTPUT.to_owned())),
+                    to: to.clone(),
+                });
+                Ok(())
+            }
+            Some(Node::Source { .. }) => Err(format!(
+                "Dead letter component \"{}\" for sink \"{}\" is a source, which can't receive events.",
+                to, from
+            )),
+            None => Err(format!(
+                "Dead letter component \"{}\" for sink \"{}\" doesn't match any transforms or sinks.",
+                to, from
+            )),
+        }
+    }
+
     /// Return the input type of a given component.
     ///
     /// # Panics
@@ -166,8 +201,7 @@ impl Graph {
     ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
             Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
@@ -175,7 +209,9 @@ impl Graph {
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
-            Node::Sink { .. } => panic!("no outputs on sinks"),
+            // The only output of sinks is their dead letter output, which passes on the events
+            // they accept.
+            Node::Sink { ty } => *ty,
         }
     }
 
@@ -495,6 +531,56 @@ mod test {
         );
     }
 
+    #[test]
+    fn dead_letter_edges() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_transform("parse", DataType::Log, DataType::Log, vec!["in"]);
+        graph.add_sink("out", DataType::Log, vec!["parse"]);
+        graph.add_sink("dlq", DataType::Log, vec![]);
+        graph.add_sink("metrics", DataType::Metric, vec![]);
+
+        graph.add_dead_letter(&"out".into(), &"dlq".into()).unwrap();
+        assert_eq!(
+            graph.inputs_for(&"dlq".into()),
+            vec![OutputId::from((
+                &"out".into(),
+                DEAD_LETTER_OUTPUT.to_owned()
+            ))]
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        graph.check_for_cycles().unwrap();
+
+        assert_eq!(
+            graph.add_dead_letter(&"out".into(), &"out".into()),
+            Err("Dead letter component for sink \"out\" can't be the sink itself.".into())
+        );
+        assert_eq!(
+            graph.add_dead_letter(&"out".into(), &"in".into()),
+            Err("Dead letter component \"in\" for sink \"out\" is a source, which can't receive events.".into())
+        );
+        assert_eq!(
+            graph.add_dead_letter(&"out".into(), &"missing".into()),
+            Err("Dead letter component \"missing\" for sink \"out\" doesn't match any transforms or sinks.".into())
+        );
+
+        graph
+            .add_dead_letter(&"out".into(), &"metrics".into())
+            .unwrap();
+        assert_eq!(
+            Err(vec![
+                "Data type mismatch between out.dead_letter (Log) and metrics (Metric)".into()
+            ]),
+            graph.typecheck()
+        );
+
+        // Feeding the failed events of a sink back into its own inputs is a cycle.
+        graph
+            .add_dead_letter(&"out".into(), &"parse".into())
+            .unwrap();
+        assert!(graph.check_for_cycles().is_err());
+    }
+
     #[test]
     fn allows_log_or_metric_into_any() {
         let mut graph = Graph::default();
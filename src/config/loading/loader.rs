@@ -166,20 +166,89 @@ pub(super) mod process {
             }
         }
 
-        /// Loads and deserializes a file into a TOML `Table`.
+        /// Loads and deserializes a file into a TOML `Table`, resolving its includes.
         fn load_file(
             &mut self,
             path: &Path,
             format: Format,
         ) -> Result<Option<(String, Table, Vec<String>)>, Vec<String>> {
             if let (Ok(name), Some(file)) = (component_name(path), open_file(path)) {
-                self.load(file, format)
-                    .map(|(value, warnings)| Some((name, value, warnings)))
+                let (table, warnings) = self.load(file, format)?;
+                self.resolve_includes(path, table, &mut Vec::new()).map(
+                    |(table, includes_warnings)| {
+                        Some((
+                            name,
+                            table,
+                            warnings.into_iter().chain(includes_warnings).collect(),
+                        ))
+                    },
+                )
             } else {
                 Ok(None)
             }
         }
 
+        /// Resolves the `includes` of a file loaded into a TOML `Table`. The included files are
+        /// deep-merged in order, and the file is then overlaid on top of them. `parents` holds the
+        /// files currently being included, to detect cycles.
+        fn resolve_includes(
+            &mut self,
+            path: &Path,
+            mut table: Table,
+            parents: &mut Vec<PathBuf>,
+        ) -> Result<(Table, Vec<String>), Vec<String>> {
+            let includes: Vec<PathBuf> = match table.remove(INCLUDES_KEY) {
+                Some(includes) => includes.try_into().map_err(|_| {
+                    vec![format!(
+                        "The `{}` of config file {:?} must be a list of paths.",
+                        INCLUDES_KEY, path
+                    )]
+                })?,
+                None => Vec::new(),
+            };
+
+            let mut warnings = Vec::new();
+            let mut merged = Table::new();
+            if !includes.is_empty() {
+                let canonical = path.canonicalize().map_err(|error| {
+                    vec![format!(
+                        "Could not resolve config file {:?}: {}.",
+                        path, error
+                    )]
+                })?;
+                if parents.contains(&canonical) {
+                    return Err(vec![format!(
+                        "Config file {:?} is included recursively.",
+                        path
+                    )]);
+                }
+                parents.push(canonical);
+
+                // Included paths are relative to the file including them.
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                for include in includes {
+                    let include = dir.join(include);
+                    let format = Format::from_path(&include).unwrap_or_default();
+                    let file = open_file(&include).ok_or_else(|| {
+                        vec![format!(
+                            "Could not open config file {:?} included by {:?}.",
+                            include, path
+                        )]
+                    })?;
+                    let (included, warns) = self.load(file, format)?;
+                    let (included, includes_warns) =
+                        self.resolve_includes(&include, included, parents)?;
+                    overlay(&mut merged, included);
+                    warnings.extend(warns.into_iter().chain(includes_warns));
+                }
+
+                parents.pop();
+            }
+
+            overlay(&mut merged, table);
+            Ok((merged, warnings))
+        }
+
         /// Loads a file, and if the path provided contains a sub-folder by the same name as the
         /// component, descend into it recursively, returning a TOML `Table`.
         fn load_file_recursive(
@@ -290,6 +359,36 @@ where
     }
 }
 
+/// Key of the list of files a config file includes, relative to it.
+pub(super) const INCLUDES_KEY: &str = "includes";
+
+/// Key marking a table to remove from the config it's overlaid on, when set to `true`.
+pub(super) const DELETE_KEY: &str = "$delete";
+
+/// Deep-merges `other` into `table`. Tables are merged recursively, and any other value, including
+/// arrays, replaces the existing value. A table with `DELETE_KEY` set to `true` removes the existing
+/// value instead.
+pub(super) fn overlay(table: &mut Table, other: Table) {
+    for (key, value) in other {
+        match value {
+            Value::Table(other) if other.get(DELETE_KEY) == Some(&Value::Boolean(true)) => {
+                table.remove(&key);
+            }
+            Value::Table(other) => match table.get_mut(&key) {
+                Some(Value::Table(existing)) => overlay(existing, other),
+                _ => {
+                    let mut inserted = Table::new();
+                    overlay(&mut inserted, other);
+                    table.insert(key, Value::Table(inserted));
+                }
+            },
+            value => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Merge two TOML `Value`s, returning a new `Value`.
 fn merge_values(value: toml::Value, other: toml::Value) -> Result<toml::Value, Vec<String>> {
     serde_toml_merge::merge(value, other).map_err(|e| vec![e.to_string()])
@@ -313,3 +412,110 @@ pub(super) fn deserialize_table<T: serde::de::DeserializeOwned>(
         .try_into()
         .map_err(|e| vec![e.to_string()])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::config::{load_source_from_paths, ConfigPath};
+
+    fn table(input: &str) -> Table {
+        toml::from_str(input).unwrap()
+    }
+
+    fn load_overlay(name: &str) -> Result<Table, Vec<String>> {
+        let path = PathBuf::from(".").join("tests").join("overlays").join(name);
+        load_source_from_paths(&[ConfigPath::File(path, None)]).map(|(table, _)| table)
+    }
+
+    #[test]
+    fn overlay_deep_merges_tables() {
+        let mut base = table(
+            r#"
+            [sinks.out]
+            type = "console"
+            inputs = ["a", "b"]
+            encoding.codec = "json"
+            "#,
+        );
+        overlay(
+            &mut base,
+            table(
+                r#"
+                [sinks.out]
+                inputs = ["c"]
+                encoding.timestamp_format = "unix"
+                "#,
+            ),
+        );
+
+        assert_eq!(
+            base,
+            table(
+                r#"
+                [sinks.out]
+                type = "console"
+                inputs = ["c"]
+                encoding.codec = "json"
+                encoding.timestamp_format = "unix"
+                "#,
+            )
+        );
+    }
+
+    #[test]
+    fn overlay_deletes_marked_tables() {
+        let mut base = table(
+            r#"
+            [sinks.out]
+            type = "console"
+            encoding.codec = "json"
+            "#,
+        );
+        overlay(
+            &mut base,
+            table(
+                r#"
+                sinks.out.encoding."$delete" = true
+                sinks.missing."$delete" = true
+                "#,
+            ),
+        );
+
+        assert_eq!(base, table(r#"sinks.out.type = "console""#));
+    }
+
+    #[test]
+    fn resolves_includes() {
+        let source = load_overlay("prod.toml").unwrap();
+
+        assert_eq!(
+            source,
+            table(
+                r#"
+                data_dir = "/var/lib/vector"
+
+                [sources.in]
+                type = "demo_logs"
+                format = "json"
+                interval = 0.1
+
+                [sinks.archive]
+                type = "file"
+                inputs = ["in"]
+                path = "/var/log/vector-%Y-%m-%d.log"
+                encoding.codec = "text"
+                buffer.type = "disk"
+                buffer.max_size = 268435488
+                "#,
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_include_cycles() {
+        let errors = load_overlay("cycle-a.toml").unwrap_err();
+        assert!(errors[0].contains("included recursively"), "{:?}", errors);
+    }
+}
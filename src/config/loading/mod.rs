@@ -140,20 +140,31 @@ pub async fn load_from_paths_with_provider_and_secrets(
     let (mut secrets_backends_loader, secrets_warning) =
         load_secret_backends_from_paths(config_paths)?;
     // And then, if needed, retrieve secrets from configured backends
-    let (mut builder, load_warnings) = if secrets_backends_loader.has_secrets_to_retrieve() {
+    let resolved_secrets = if secrets_backends_loader.has_secrets_to_retrieve() {
         debug!(message = "Secret placeholders found, retrieving secrets from configured backends.");
         let resolved_secrets = secrets_backends_loader
             .retrieve(&mut signal_handler.subscribe())
             .map_err(|e| vec![e])?;
-        load_builder_from_paths_with_secrets(config_paths, resolved_secrets)?
+        Some(resolved_secrets)
     } else {
         debug!(message = "No secret placeholder found, skipping secret resolution.");
-        load_builder_from_paths(config_paths)?
+        None
+    };
+    let (mut builder, load_warnings) = match &resolved_secrets {
+        Some(resolved_secrets) => {
+            load_builder_from_paths_with_secrets(config_paths, resolved_secrets.clone())?
+        }
+        None => load_builder_from_paths(config_paths)?,
     };
 
     validation::check_provider(&builder)?;
     signal_handler.clear();
 
+    // Secrets are refreshed for as long as this configuration is in use.
+    if let Some(resolved_secrets) = resolved_secrets {
+        secrets_backends_loader.refresh(&resolved_secrets, signal_handler);
+    }
+
     // If there's a provider, overwrite the existing config builder with the remote variant.
     if let Some(mut provider) = builder.provider {
         builder = provider.build(signal_handler).await?;
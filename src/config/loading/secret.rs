@@ -1,7 +1,13 @@
-use std::{collections::HashMap, io::Read};
+use std::{
+    collections::HashMap,
+    io::Read,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
+use async_stream::stream;
 use bytes::BytesMut;
-use futures::{executor, StreamExt};
+use futures::{executor, Stream, StreamExt};
 use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
@@ -25,10 +31,16 @@ use crate::{
 // - "SECRET[backend.secret_name]" will match and capture "backend" and "secret_name"
 // - "SECRET[backend.secret.name]" will match and catpure "backend" and "secret.name"
 // - "SECRET[backend..secret.name]" will match and catpure "backend" and ".secret.name"
+// - "SECRET[backend.kv/team-a/db.password]" will match and capture "backend" and "kv/team-a/db.password"
 // - "SECRET[secret_name]" will not match
 // - "SECRET[.secret.name]" wil not match
 static COLLECTOR: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:].]+)\]").unwrap());
+    Lazy::new(|| Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:]./-]+)\]").unwrap());
+
+/// Secrets retrieved from backends with caching enabled, keyed by backend name and configuration,
+/// along with when they were retrieved.
+static CACHE: Lazy<Mutex<HashMap<String, HashMap<String, (String, Instant)>>>> =
+    Lazy::new(Mutex::default);
 
 #[typetag::serde(tag = "type")]
 pub trait SecretBackend: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
@@ -37,6 +49,19 @@ pub trait SecretBackend: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
         secret_keys: Vec<String>,
         signal_rx: &mut signal::SignalRx,
     ) -> crate::Result<HashMap<String, String>>;
+
+    /// How long retrieved secrets are reused when the configuration is reloaded, instead of being
+    /// retrieved again. Secrets aren't cached if this is `None`.
+    fn cache_ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// How often secrets are retrieved again in the background, reloading the configuration when
+    /// any of them has changed, so rotated secrets are picked up. Secrets aren't refreshed if this
+    /// is `None`.
+    fn refresh_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -57,30 +82,71 @@ impl SecretBackendLoader {
         &mut self,
         signal_rx: &mut signal::SignalRx,
     ) -> Result<HashMap<String, String>, String> {
-        let secrets = self.secret_keys.iter().flat_map(|(backend_name, keys)| {
-            match self.backends.get_mut(&ComponentKey::from(backend_name.clone())) {
-                None => {
-                    vec![Err(format!("Backend \"{}\" is required for secret retrieval but was not found in config.", backend_name))]
-                },
-                Some(backend) => {
-                    debug!(message = "Retrieving secret from a backend.", backend = ?backend_name);
-                    match backend.retrieve(keys.to_vec(), signal_rx) {
-                        Err(e) => {
-                            vec![Err(format!("Error while retrieving secret from backend \"{}\": {}.", backend_name, e))]
-                        },
-                        Ok(s) => {
-                            s.into_iter().map(|(k, v)| {
-                                trace!(message = "Successfully retrieved a secret.", backend = ?backend_name, secret_key = ?k);
-                                Ok((format!("{}.{}", backend_name, k), v))
-                            }).collect::<Vec<Result<(String, String), String>>>()
-                        }
-                    }
-                },
+        let mut secrets = HashMap::new();
+        for (backend_name, keys) in &self.secret_keys {
+            let backend = self
+                .backends
+                .get_mut(&ComponentKey::from(backend_name.clone()))
+                .ok_or_else(|| {
+                    format!(
+                        "Backend \"{}\" is required for secret retrieval but was not found in config.",
+                        backend_name
+                    )
+                })?;
+            debug!(message = "Retrieving secret from a backend.", backend = ?backend_name);
+            let retrieved = retrieve_cached(backend_name, backend.as_mut(), keys, signal_rx, false)
+                .map_err(|e| {
+                    format!(
+                        "Error while retrieving secret from backend \"{}\": {}.",
+                        backend_name, e
+                    )
+                })?;
+            for (k, v) in retrieved {
+                trace!(message = "Successfully retrieved a secret.", backend = ?backend_name, secret_key = ?k);
+                secrets.insert(format!("{}.{}", backend_name, k), v);
             }
-        }).collect::<Result<HashMap<String,String>,String>>()?;
+        }
         Ok(secrets)
     }
 
+    /// Refreshes the secrets of backends with a refresh interval in the background, signalling a
+    /// reload of the configuration once any of them differs from `secrets`.
+    pub(crate) fn refresh(
+        self,
+        secrets: &HashMap<String, String>,
+        signal_handler: &mut signal::SignalHandler,
+    ) {
+        let Self {
+            backends,
+            mut secret_keys,
+        } = self;
+        for (backend_name, backend) in backends {
+            let backend_name = backend_name.to_string();
+            let (interval, keys) = match (
+                backend.refresh_interval(),
+                secret_keys.remove(&backend_name),
+            ) {
+                (Some(interval), Some(keys)) => (interval, keys),
+                _ => continue,
+            };
+            let current = keys
+                .iter()
+                .filter_map(|k| {
+                    secrets
+                        .get(&format!("{}.{}", backend_name, k))
+                        .map(|v| (k.clone(), v.clone()))
+                })
+                .collect();
+            let refresh = Refresh {
+                backend_name,
+                backend,
+                keys,
+                signal_rx: signal_handler.subscribe(),
+            };
+            signal_handler.add(refresh.poll(interval, current));
+        }
+    }
+
     pub(crate) fn has_secrets_to_retrieve(&self) -> bool {
         !self.secret_keys.is_empty()
     }
@@ -140,6 +206,113 @@ pub fn interpolate(input: &str, secrets: &HashMap<String, String>) -> Result<Str
     }
 }
 
+/// Retrieves secrets from `backend`, reusing any cached secrets that haven't expired unless
+/// `force` is set, and caching retrieved secrets if the backend has caching enabled.
+fn retrieve_cached(
+    backend_name: &str,
+    backend: &mut dyn SecretBackend,
+    keys: &[String],
+    signal_rx: &mut signal::SignalRx,
+    force: bool,
+) -> crate::Result<HashMap<String, String>> {
+    let ttl = match backend.cache_ttl() {
+        Some(ttl) => ttl,
+        None => return backend.retrieve(keys.to_vec(), signal_rx),
+    };
+    // The configuration is part of the key, so secrets aren't reused once a backend is changed.
+    let cache_key = format!("{}:{}", backend_name, serde_json::to_string(&*backend)?);
+
+    let mut secrets = HashMap::new();
+    let mut missing = Vec::new();
+    {
+        let cache = CACHE.lock().expect("cache lock poisoned");
+        let cached = cache.get(&cache_key);
+        for key in keys {
+            match cached.and_then(|cached| cached.get(key)) {
+                Some((value, retrieved_at)) if !force && retrieved_at.elapsed() < ttl => {
+                    trace!(message = "Using cached secret.", backend = ?backend_name, secret_key = ?key);
+                    secrets.insert(key.clone(), value.clone());
+                }
+                _ => missing.push(key.clone()),
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        let retrieved = backend.retrieve(missing, signal_rx)?;
+        let now = Instant::now();
+        let mut cache = CACHE.lock().expect("cache lock poisoned");
+        let cached = cache.entry(cache_key).or_default();
+        for (k, v) in retrieved {
+            cached.insert(k.clone(), (v.clone(), now));
+            secrets.insert(k, v);
+        }
+    }
+    Ok(secrets)
+}
+
+/// A backend whose secrets are refreshed in the background.
+struct Refresh {
+    backend_name: String,
+    backend: Box<dyn SecretBackend>,
+    keys: Vec<String>,
+    signal_rx: signal::SignalRx,
+}
+
+impl Refresh {
+    fn retrieve(&mut self) -> crate::Result<HashMap<String, String>> {
+        retrieve_cached(
+            &self.backend_name,
+            self.backend.as_mut(),
+            &self.keys,
+            &mut self.signal_rx,
+            true,
+        )
+    }
+
+    /// Retrieves the secrets after/every `interval`, returning a stream of config reload signals
+    /// for every change to them.
+    fn poll(
+        self,
+        interval: Duration,
+        mut current: HashMap<String, String>,
+    ) -> impl Stream<Item = signal::SignalTo> {
+        let mut interval = time::interval_at(time::Instant::now() + interval, interval);
+        let mut refresh = Some(self);
+
+        stream! {
+            while let Some(mut this) = refresh.take() {
+                interval.tick().await;
+
+                // Backends block while retrieving secrets, so they're kept off the runtime threads.
+                let (this, result) = match tokio::task::spawn_blocking(move || {
+                    let result = this.retrieve();
+                    (this, result)
+                })
+                .await
+                {
+                    Ok(refreshed) => refreshed,
+                    Err(error) => {
+                        error!(message = "Secret refresh failed.", %error);
+                        break;
+                    }
+                };
+
+                match result {
+                    Ok(secrets) if secrets != current => {
+                        info!(message = "Secrets have changed, reloading configuration.", backend = ?this.backend_name);
+                        current = secrets;
+                        yield signal::SignalTo::ReloadFromDisk;
+                    }
+                    Ok(_) => debug!(message = "Secrets are unchanged.", backend = ?this.backend_name),
+                    Err(error) => error!(message = "Failed to refresh secrets.", %error, backend = ?this.backend_name),
+                }
+                refresh = Some(this);
+            }
+        }
+    }
+}
+
 fn collect_secret_keys(input: &str, keys: &mut HashMap<String, Vec<String>>) {
     COLLECTOR.captures_iter(input).for_each(|cap| {
         if let (Some(backend), Some(key)) = (cap.get(1), cap.get(2)) {
@@ -309,11 +482,72 @@ impl SecretBackend for TestBackend {
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
 
     use indoc::indoc;
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::broadcast;
+
+    use super::{collect_secret_keys, interpolate, retrieve_cached, SecretBackend};
+    use crate::signal;
+
+    /// A backend returning how many times it retrieved secrets as their value.
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    struct CountingBackend {
+        name: String,
+        #[serde(skip)]
+        retrievals: Arc<AtomicUsize>,
+    }
+
+    #[typetag::serde(name = "test_counting")]
+    impl SecretBackend for CountingBackend {
+        fn retrieve(
+            &mut self,
+            secret_keys: Vec<String>,
+            _: &mut signal::SignalRx,
+        ) -> crate::Result<HashMap<String, String>> {
+            let retrievals = self.retrievals.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(secret_keys
+                .into_iter()
+                .map(|k| (k, retrievals.to_string()))
+                .collect())
+        }
 
-    use super::{collect_secret_keys, interpolate};
+        fn cache_ttl(&self) -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+    }
+
+    #[test]
+    fn caching() {
+        let (_, mut signal_rx) = broadcast::channel(1);
+        let mut backend = CountingBackend {
+            name: "caching".into(),
+            retrievals: Default::default(),
+        };
+        let mut retrieve = |keys: &[&str], force| {
+            let keys = keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+            retrieve_cached("backend", &mut backend, &keys, &mut signal_rx, force).unwrap()
+        };
+
+        assert_eq!(retrieve(&["a"], false)["a"], "1");
+        // Cached secrets are reused, and only missing ones are retrieved...
+        let secrets = retrieve(&["a", "b"], false);
+        assert_eq!(secrets["a"], "1");
+        assert_eq!(secrets["b"], "2");
+        // ...unless secrets are refreshed.
+        let secrets = retrieve(&["a", "b"], true);
+        assert_eq!(secrets["a"], "3");
+        assert_eq!(secrets["b"], "3");
+        assert_eq!(retrieve(&["a"], false)["a"], "3");
+    }
 
     #[test]
     fn replacement() {
@@ -362,6 +596,7 @@ mod test {
             SECRET[first_backend.another_secret_key]
             SECRET[second_backend.secret_key]
             SECRET[second_backend.secret.key]
+            SECRET[second_backend.kv/team-a/db.password]
             SECRET[first_backend.a_third.secret_key]
             SECRET[first_backend...an_extra_secret_key]
             SECRET[non_matching_syntax]
@@ -381,8 +616,9 @@ mod test {
         assert!(first_backend_keys.contains(&"..an_extra_secret_key".into()));
 
         let second_backend_keys = keys.get("second_backend").unwrap();
-        assert_eq!(second_backend_keys.len(), 2);
+        assert_eq!(second_backend_keys.len(), 3);
         assert!(second_backend_keys.contains(&"secret_key".into()));
         assert!(second_backend_keys.contains(&"secret.key".into()));
+        assert!(second_backend_keys.contains(&"kv/team-a/db.password".into()));
     }
 }
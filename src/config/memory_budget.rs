@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use vector_common::memory_budget::MemoryBudget;
+
+/// A memory budget for the whole process, against which the events held by in-memory buffers, the
+/// state of transforms such as `reduce` and `aggregate`, and the batches being built by sinks are
+/// counted. Load is shed as the budget is approached, rather than the process being killed once it
+/// runs out of memory.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryBudgetConfig {
+    /// The maximum number of bytes held by buffers, transforms and batches, all together.
+    pub max_bytes: usize,
+
+    /// The fraction of the budget past which buffers overflowing to disk spill events to disk,
+    /// rather than holding them in memory.
+    #[serde(default = "default_spill_to_disk_ratio")]
+    pub spill_to_disk_ratio: f64,
+
+    /// The fraction of the budget past which sources drop their low priority events.
+    #[serde(default = "default_drop_low_priority_ratio")]
+    pub drop_low_priority_ratio: f64,
+
+    /// The fraction of the budget past which sources are paused, until memory is released.
+    #[serde(default = "default_pause_sources_ratio")]
+    pub pause_sources_ratio: f64,
+}
+
+const fn default_spill_to_disk_ratio() -> f64 {
+    0.7
+}
+
+const fn default_drop_low_priority_ratio() -> f64 {
+    0.8
+}
+
+const fn default_pause_sources_ratio() -> f64 {
+    0.9
+}
+
+impl MemoryBudgetConfig {
+    pub(super) fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.max_bytes == 0 {
+            errors.push("`memory_budget.max_bytes` must be greater than zero.".to_owned());
+        }
+        for (name, ratio) in [
+            ("spill_to_disk_ratio", self.spill_to_disk_ratio),
+            ("drop_low_priority_ratio", self.drop_low_priority_ratio),
+            ("pause_sources_ratio", self.pause_sources_ratio),
+        ] {
+            if !(ratio > 0.0 && ratio <= 1.0) {
+                errors.push(format!(
+                    "`memory_budget.{}` must be greater than 0 and at most 1, got {}.",
+                    name, ratio
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the amounts of memory past which load is shed.
+    pub fn budget(&self) -> MemoryBudget {
+        let bytes = |ratio: f64| (self.max_bytes as f64 * ratio) as usize;
+        MemoryBudget {
+            spill_to_disk_bytes: bytes(self.spill_to_disk_ratio),
+            drop_low_priority_bytes: bytes(self.drop_low_priority_ratio),
+            pause_sources_bytes: bytes(self.pause_sources_ratio),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::MemoryBudgetConfig;
+    use crate::config::ConfigBuilder;
+
+    #[test]
+    fn computes_budget() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [memory_budget]
+            max_bytes = 1000
+            pause_sources_ratio = 0.95
+        "#})
+        .unwrap();
+        let budget = config.memory_budget.unwrap();
+        assert_eq!(budget.validate(), Ok(()));
+
+        let budget = budget.budget();
+        assert_eq!(budget.spill_to_disk_bytes, 700);
+        assert_eq!(budget.drop_low_priority_bytes, 800);
+        assert_eq!(budget.pause_sources_bytes, 950);
+    }
+
+    #[test]
+    fn rejects_invalid_ratios() {
+        let config = MemoryBudgetConfig {
+            max_bytes: 0,
+            spill_to_disk_ratio: 0.0,
+            drop_low_priority_ratio: 0.8,
+            pause_sources_ratio: 1.5,
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "`memory_budget.max_bytes` must be greater than zero.".to_owned(),
+                "`memory_budget.spill_to_disk_ratio` must be greater than 0 and at most 1, got 0."
+                    .to_owned(),
+                "`memory_budget.pause_sources_ratio` must be greater than 0 and at most 1, got 1.5."
+                    .to_owned(),
+            ])
+        );
+    }
+}
@@ -17,9 +17,11 @@ use crate::{conditions, event::Metric, serde::OneOrMany};
 
 pub mod api;
 mod builder;
+mod circuit_breaker;
 mod cmd;
 mod compiler;
 pub mod component;
+mod delivery_audit;
 mod diff;
 #[cfg(feature = "enterprise")]
 pub mod enterprise;
@@ -27,10 +29,14 @@ pub mod format;
 mod graph;
 mod id;
 mod loading;
+mod memory_budget;
 pub mod provider;
+mod rate_limit_group;
 mod schema;
 mod sink;
 mod source;
+mod telemetry_export;
+mod tenant;
 mod transform;
 mod unit_test;
 mod validation;
@@ -38,7 +44,9 @@ mod vars;
 pub mod watcher;
 
 pub use builder::ConfigBuilder;
+pub use circuit_breaker::{CircuitBreakerConfig, CircuitBreakerFallback};
 pub use cmd::{cmd, Opts};
+pub use delivery_audit::DeliveryAuditConfig;
 pub use diff::ConfigDiff;
 pub use format::{Format, FormatHint};
 pub use id::{ComponentKey, OutputId};
@@ -47,8 +55,14 @@ pub use loading::{
     load_from_str, load_source_from_paths, merge_path_lists, process_paths, SecretBackend,
     CONFIG_PATHS,
 };
-pub use sink::{SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter};
+pub use memory_budget::MemoryBudgetConfig;
+pub use rate_limit_group::RateLimitGroupConfig;
+pub use sink::{
+    SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter, DEAD_LETTER_OUTPUT,
+};
 pub use source::{SourceConfig, SourceContext, SourceDescription, SourceOuter};
+pub use telemetry_export::{OtlpProtocol, TelemetryExportConfig};
+pub use tenant::TenantConfig;
 pub use transform::{TransformDescription, TransformOuter};
 pub use unit_test::{build_unit_tests, build_unit_tests_main, UnitTestResult};
 pub use validation::warnings;
@@ -102,6 +116,7 @@ pub struct Config {
     pub enterprise: Option<enterprise::Options>,
     pub global: GlobalOptions,
     pub healthchecks: HealthcheckOptions,
+    pub shutdown: ShutdownOptions,
     sources: IndexMap<ComponentKey, SourceOuter>,
     sinks: IndexMap<ComponentKey, SinkOuter<OutputId>>,
     transforms: IndexMap<ComponentKey, TransformOuter<OutputId>>,
@@ -109,6 +124,13 @@ pub struct Config {
     tests: Vec<TestDefinition>,
     expansions: IndexMap<ComponentKey, Vec<ComponentKey>>,
     secret: IndexMap<ComponentKey, Box<dyn SecretBackend>>,
+    pub tenants: IndexMap<String, TenantConfig>,
+    tenant_components: IndexMap<ComponentKey, String>,
+    pub rate_limit_groups: IndexMap<String, RateLimitGroupConfig>,
+    sink_rate_limit_groups: IndexMap<ComponentKey, String>,
+    pub memory_budget: Option<MemoryBudgetConfig>,
+    pub telemetry_export: Option<TelemetryExportConfig>,
+    pub delivery_audit: Option<DeliveryAuditConfig>,
 }
 
 impl Config {
@@ -157,6 +179,20 @@ impl Config {
             .unwrap_or_else(|| vec![identifier.clone()])
     }
 
+    /// Returns the name and configuration of the tenant of a component, if it has one.
+    pub fn tenant(&self, key: &ComponentKey) -> Option<(&str, &TenantConfig)> {
+        let name = self.tenant_components.get(key)?;
+        self.tenants.get(name).map(|tenant| (name.as_str(), tenant))
+    }
+
+    /// Returns the name and configuration of the rate limit group of a sink, if it has one.
+    pub fn rate_limit_group(&self, key: &ComponentKey) -> Option<(&str, &RateLimitGroupConfig)> {
+        let name = self.sink_rate_limit_groups.get(key)?;
+        self.rate_limit_groups
+            .get(name)
+            .map(|group| (name.as_str(), group))
+    }
+
     pub fn propagate_acknowledgements(&mut self) -> Result<(), Vec<String>> {
         if self.global.acknowledgements.enabled() {
             for (name, sink) in &self.sinks {
@@ -243,6 +279,70 @@ impl Default for HealthcheckOptions {
     }
 }
 
+/// How long components are given to finish on shutdown. Sources are stopped first, then
+/// transforms flush their state, then sinks flush their buffers, each of these in turn being
+/// given their timeout before they're killed.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownOptions {
+    pub sources_timeout_secs: u64,
+    pub transforms_timeout_secs: u64,
+    pub sinks_timeout_secs: u64,
+}
+
+impl ShutdownOptions {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Merges the options of another config file, whose non-default timeouts take precedence.
+    fn merge(&mut self, other: Self) -> Result<(), Vec<String>> {
+        let default = Self::default();
+        let mut errors = Vec::new();
+        let mut merge_timeout = |name: &str, this: &mut u64, other: u64, default: u64| {
+            if other != default {
+                if *this != default && *this != other {
+                    errors.push(format!("conflicting values for 'shutdown.{}' found", name));
+                }
+                *this = other;
+            }
+        };
+        merge_timeout(
+            "sources_timeout_secs",
+            &mut self.sources_timeout_secs,
+            other.sources_timeout_secs,
+            default.sources_timeout_secs,
+        );
+        merge_timeout(
+            "transforms_timeout_secs",
+            &mut self.transforms_timeout_secs,
+            other.transforms_timeout_secs,
+            default.transforms_timeout_secs,
+        );
+        merge_timeout(
+            "sinks_timeout_secs",
+            &mut self.sinks_timeout_secs,
+            other.sinks_timeout_secs,
+            default.sinks_timeout_secs,
+        );
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Default for ShutdownOptions {
+    fn default() -> Self {
+        Self {
+            sources_timeout_secs: 30,
+            transforms_timeout_secs: 60,
+            sinks_timeout_secs: 60,
+        }
+    }
+}
+
 pub trait GenerateConfig {
     fn generate_config() -> toml::Value;
 }
@@ -548,7 +648,10 @@ mod tests {
     use crate::{config, topology};
     use indoc::indoc;
 
-    use super::{builder::ConfigBuilder, format, load_from_str, ComponentKey, ConfigDiff, Format};
+    use super::{
+        builder::ConfigBuilder, format, load_from_str, ComponentKey, ConfigDiff, Format, OutputId,
+        ShutdownOptions,
+    };
 
     async fn load(config: &str, format: config::Format) -> Result<Vec<String>, Vec<String>> {
         match config::load_from_str(config, format) {
@@ -795,6 +898,70 @@ mod tests {
         assert_eq!("then", config.global.log_schema.timestamp_key().to_string());
     }
 
+    #[test]
+    fn builder_from_running_config() {
+        let config = load_from_str(
+            indoc! {r#"
+                [log_schema]
+                  host_key = "this"
+
+                [sources.in]
+                  type = "basic_source"
+
+                [transforms.sample]
+                  type = "basic_transform"
+                  inputs = ["in"]
+
+                [sinks.out]
+                  type = "basic_sink"
+                  inputs = ["sample"]
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        let rebuilt = ConfigBuilder::from(&config).build().unwrap();
+        assert_eq!("this", rebuilt.global.log_schema.host_key().to_string());
+
+        let diff = ConfigDiff::new(&config, &rebuilt);
+        assert!(!diff.sources.any_changed_or_added());
+        assert!(!diff.transforms.any_changed_or_added());
+        assert!(!diff.sinks.any_changed_or_added());
+    }
+
+    #[test]
+    fn dead_letter() {
+        let config = load_from_str(
+            indoc! {r#"
+                [sources.in]
+                  type = "basic_source"
+
+                [sinks.out]
+                  type = "basic_sink"
+                  inputs = ["in"]
+                  dead_letter = "failed"
+
+                [sinks.failed]
+                  type = "basic_sink"
+                  inputs = []
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.sink(&ComponentKey::from("failed")).unwrap().inputs,
+            vec![OutputId::from((
+                &ComponentKey::from("out"),
+                "dead_letter".to_owned()
+            ))]
+        );
+
+        let rebuilt = ConfigBuilder::from(&config).build().unwrap();
+        let diff = ConfigDiff::new(&config, &rebuilt);
+        assert!(!diff.sinks.any_changed_or_added());
+    }
+
     #[test]
     fn config_append() {
         let mut config: ConfigBuilder = format::deserialize(
@@ -896,6 +1063,160 @@ mod tests {
         );
     }
 
+    #[test]
+    fn config_append_shutdown() {
+        let mut config: ConfigBuilder = format::deserialize(
+            indoc! {r#"
+                [shutdown]
+                  transforms_timeout_secs = 300
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.append(
+                format::deserialize(
+                    indoc! {r#"
+                        [shutdown]
+                          sinks_timeout_secs = 120
+                    "#},
+                    Format::Toml,
+                )
+                .unwrap()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            config.shutdown,
+            ShutdownOptions {
+                sources_timeout_secs: 30,
+                transforms_timeout_secs: 300,
+                sinks_timeout_secs: 120,
+            }
+        );
+
+        assert_eq!(
+            config.append(
+                format::deserialize(
+                    indoc! {r#"
+                        [shutdown]
+                          transforms_timeout_secs = 10
+                    "#},
+                    Format::Toml,
+                )
+                .unwrap()
+            ),
+            Err(vec![
+                "conflicting values for 'shutdown.transforms_timeout_secs' found".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn config_append_memory_budget() {
+        let mut config: ConfigBuilder = format::deserialize(
+            indoc! {r#"
+                [memory_budget]
+                  max_bytes = 1000
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(config.append(ConfigBuilder::default()), Ok(()));
+        assert_eq!(
+            config.memory_budget.map(|budget| budget.max_bytes),
+            Some(1000)
+        );
+
+        assert_eq!(
+            config.append(
+                format::deserialize(
+                    indoc! {r#"
+                        [memory_budget]
+                          max_bytes = 2000
+                    "#},
+                    Format::Toml,
+                )
+                .unwrap()
+            ),
+            Err(vec!["conflicting values for 'memory_budget' found".into()])
+        );
+    }
+
+    #[test]
+    fn config_append_telemetry_export() {
+        let mut config: ConfigBuilder = format::deserialize(
+            indoc! {r#"
+                [telemetry_export]
+                  endpoint = "http://localhost:4317"
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(config.append(ConfigBuilder::default()), Ok(()));
+        assert_eq!(
+            config
+                .telemetry_export
+                .as_ref()
+                .map(|telemetry_export| telemetry_export.endpoint.as_str()),
+            Some("http://localhost:4317")
+        );
+
+        assert_eq!(
+            config.append(
+                format::deserialize(
+                    indoc! {r#"
+                        [telemetry_export]
+                          endpoint = "http://localhost:4318"
+                          protocol = "http"
+                    "#},
+                    Format::Toml,
+                )
+                .unwrap()
+            ),
+            Err(vec![
+                "conflicting values for 'telemetry_export' found".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn config_append_delivery_audit() {
+        let mut config: ConfigBuilder = format::deserialize(
+            indoc! {r#"
+                [delivery_audit]
+                  sample_rate = 100
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert_eq!(config.append(ConfigBuilder::default()), Ok(()));
+        assert_eq!(
+            config
+                .delivery_audit
+                .map(|delivery_audit| delivery_audit.sample_rate),
+            Some(100)
+        );
+
+        assert_eq!(
+            config.append(
+                format::deserialize(
+                    indoc! {r#"
+                        [delivery_audit]
+                          sample_rate = 10
+                    "#},
+                    Format::Toml,
+                )
+                .unwrap()
+            ),
+            Err(vec!["conflicting values for 'delivery_audit' found".into()])
+        );
+    }
+
     #[test]
     fn with_proxy() {
         let config: ConfigBuilder = format::deserialize(
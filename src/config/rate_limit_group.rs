@@ -0,0 +1,113 @@
+use std::num::NonZeroU32;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::{builder::ConfigBuilder, ComponentKey};
+
+/// A budget shared by all of the sinks referencing it with their `request.rate_limit_group`
+/// option, such as the sinks sending to the same cluster.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitGroupConfig {
+    /// The maximum number of requests per second sent by the sinks of the group, all together.
+    #[serde(default)]
+    pub max_requests_per_sec: Option<NonZeroU32>,
+
+    /// The maximum number of bytes of events per second processed by the sinks of the group, all
+    /// together.
+    #[serde(default)]
+    pub max_bytes_per_sec: Option<NonZeroU32>,
+}
+
+/// Returns the rate limit group of each sink referencing one, checking that the groups exist.
+pub(super) fn assign(
+    builder: &ConfigBuilder,
+) -> Result<IndexMap<ComponentKey, String>, Vec<String>> {
+    let mut assigned = IndexMap::new();
+    let mut errors = Vec::new();
+
+    for (key, sink) in builder.sinks.iter() {
+        if let Some(group) = sink.rate_limit_group() {
+            if builder.rate_limit_groups.contains_key(&group) {
+                assigned.insert(key.clone(), group);
+            } else {
+                errors.push(format!(
+                    "Sink \"{}\" references unknown rate limit group \"{}\".",
+                    key, group
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(assigned)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(all(test, feature = "sources-demo_logs", feature = "sinks-http"))]
+mod tests {
+    use indoc::indoc;
+
+    use crate::config::ConfigBuilder;
+
+    fn build(config: &str) -> Result<crate::config::Config, Vec<String>> {
+        let builder: ConfigBuilder = toml::from_str(config).unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn assigns_sinks() {
+        let config = build(indoc! {r#"
+            [rate_limit_groups.cluster]
+            max_requests_per_sec = 100
+
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "http"
+            inputs = ["in"]
+            uri = "http://localhost:8080"
+            encoding.codec = "json"
+            request.rate_limit_group = "cluster"
+
+            [sinks.other]
+            type = "http"
+            inputs = ["in"]
+            uri = "http://localhost:8080"
+            encoding.codec = "json"
+        "#})
+        .unwrap();
+
+        let (name, group) = config.rate_limit_group(&"out".into()).unwrap();
+        assert_eq!(name, "cluster");
+        assert_eq!(group.max_requests_per_sec.unwrap().get(), 100);
+        assert!(config.rate_limit_group(&"other".into()).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_groups() {
+        let errors = build(indoc! {r#"
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "http"
+            inputs = ["in"]
+            uri = "http://localhost:8080"
+            encoding.codec = "json"
+            request.rate_limit_group = "cluster"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![r#"Sink "out" references unknown rate limit group "cluster"."#]
+        );
+    }
+}
@@ -4,9 +4,13 @@ use serde::{Deserialize, Serialize};
 use vector_buffers::{Acker, BufferConfig, BufferType};
 use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Input};
 
-use super::{component, schema, ComponentKey, ProxyConfig, Resource};
+use super::{component, schema, CircuitBreakerConfig, ComponentKey, ProxyConfig, Resource};
 use crate::sinks::{self, util::UriSerde};
 
+/// The name of the output through which a sink routes the events it failed to deliver to its
+/// `dead_letter` component.
+pub const DEAD_LETTER_OUTPUT: &str = "dead_letter";
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SinkOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
@@ -28,6 +32,21 @@ pub struct SinkOuter<T> {
     )]
     proxy: ProxyConfig,
 
+    /// How long the sink is given to flush its buffers on shutdown, overriding the
+    /// `shutdown.sinks_timeout_secs` option.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_timeout_secs: Option<u64>,
+
+    /// The transform or sink that events the sink failed to deliver, whether rejected by the
+    /// destination or errored past its retries, are routed to, with the reason attached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<ComponentKey>,
+
+    /// Stops handing events to the sink after sustained failure to deliver them, until it
+    /// recovers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -41,6 +60,9 @@ impl<T> SinkOuter<T> {
             healthcheck_uri: None,
             inner,
             proxy: Default::default(),
+            shutdown_timeout_secs: None,
+            dead_letter: None,
+            circuit_breaker: None,
         }
     }
 
@@ -79,6 +101,17 @@ impl<T> SinkOuter<T> {
         &self.proxy
     }
 
+    /// Returns the name of the rate limit group referenced by the `request.rate_limit_group`
+    /// option of the sink, if any.
+    pub fn rate_limit_group(&self) -> Option<String> {
+        // The request options are part of the configuration of each sink, which is opaque here.
+        serde_json::to_value(&self.inner)
+            .ok()?
+            .pointer("/request/rate_limit_group")?
+            .as_str()
+            .map(ToOwned::to_owned)
+    }
+
     pub(super) fn map_inputs<U>(self, f: impl Fn(&T) -> U) -> SinkOuter<U> {
         let inputs = self.inputs.iter().map(f).collect();
         self.with_inputs(inputs)
@@ -92,6 +125,9 @@ impl<T> SinkOuter<T> {
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
+            shutdown_timeout_secs: self.shutdown_timeout_secs,
+            dead_letter: self.dead_letter,
+            circuit_breaker: self.circuit_breaker,
         }
     }
 }
@@ -3,9 +3,14 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 use component::ComponentDescription;
 use serde::{Deserialize, Serialize};
-use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Output};
+use vector_core::{
+    buffers::Priority,
+    config::{AcknowledgementsConfig, GlobalOptions, Output},
+};
 
 use super::{component, schema, ComponentKey, ProxyConfig, Resource};
+#[cfg(feature = "leader-election")]
+use crate::leader_election::LeaderElectionConfig;
 use crate::{shutdown::ShutdownSignal, sources, SourceSender};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -15,6 +20,18 @@ pub struct SourceOuter {
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
     )]
     pub proxy: ProxyConfig,
+    /// The priority given to the events of this source, used to decide which events to shed first
+    /// when a buffer is full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// How the replicas of Vector running this source elect the only one of them running it.
+    #[cfg(feature = "leader-election")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader_election: Option<LeaderElectionConfig>,
+    /// How long the source is given to stop on shutdown before it's forced to, overriding the
+    /// `shutdown.sources_timeout_secs` option.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_timeout_secs: Option<u64>,
     #[serde(flatten)]
     pub(crate) inner: Box<dyn SourceConfig>,
     #[serde(default, skip)]
@@ -26,6 +43,10 @@ impl SourceOuter {
         Self {
             inner: Box::new(source),
             proxy: Default::default(),
+            priority: None,
+            #[cfg(feature = "leader-election")]
+            leader_election: None,
+            shutdown_timeout_secs: None,
             sink_acknowledgements: false,
         }
     }
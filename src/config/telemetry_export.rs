@@ -0,0 +1,132 @@
+use http::Uri;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The protocol used to export telemetry to an OTLP collector.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OtlpProtocol {
+    /// OTLP over gRPC.
+    Grpc,
+    /// OTLP over HTTP, with protobuf payloads.
+    Http,
+}
+
+impl Default for OtlpProtocol {
+    fn default() -> Self {
+        Self::Grpc
+    }
+}
+
+/// Exports the internal metrics and spans of Vector itself via OTLP to an external collector,
+/// rather than them only being available to the `internal_metrics` source of the topology.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryExportConfig {
+    /// The address of the collector, such as `http://localhost:4317` for gRPC or
+    /// `http://localhost:4318` for HTTP.
+    pub endpoint: String,
+
+    /// The protocol used to export telemetry.
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+
+    /// How often, in seconds, metrics are captured and spans are exported.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Whether internal metrics are exported.
+    #[serde(default = "crate::serde::default_true")]
+    pub metrics: bool,
+
+    /// Whether internal spans, such as those of the components, are exported once closed.
+    #[serde(default = "crate::serde::default_true")]
+    pub spans: bool,
+
+    /// Headers sent along with each export, such as the credentials of the collector.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+}
+
+const fn default_interval_secs() -> u64 {
+    10
+}
+
+impl TelemetryExportConfig {
+    pub(super) fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if !cfg!(feature = "sources-opentelemetry") {
+            errors.push(
+                "`telemetry_export` requires Vector to be built with the `sources-opentelemetry` feature."
+                    .to_owned(),
+            );
+        }
+        match self.endpoint.parse::<Uri>() {
+            Ok(uri) if uri.scheme().is_some() && uri.host().is_some() => {}
+            _ => errors.push(format!(
+                "`telemetry_export.endpoint` must be an absolute URI, got \"{}\".",
+                self.endpoint
+            )),
+        }
+        if self.interval_secs == 0 {
+            errors.push("`telemetry_export.interval_secs` must be greater than zero.".to_owned());
+        }
+        if !self.metrics && !self.spans {
+            errors.push(
+                "`telemetry_export` must export at least one of `metrics` or `spans`.".to_owned(),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::ConfigBuilder;
+
+    #[test]
+    fn parses_defaults() {
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [telemetry_export]
+            endpoint = "http://localhost:4318"
+            protocol = "http"
+        "#})
+        .unwrap();
+        let telemetry_export = config.telemetry_export.unwrap();
+
+        assert_eq!(telemetry_export.protocol, OtlpProtocol::Http);
+        assert_eq!(telemetry_export.interval_secs, 10);
+        assert!(telemetry_export.metrics && telemetry_export.spans);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let config = TelemetryExportConfig {
+            endpoint: "localhost".to_owned(),
+            protocol: OtlpProtocol::Grpc,
+            interval_secs: 0,
+            metrics: false,
+            spans: false,
+            headers: IndexMap::new(),
+        };
+
+        let errors = config.validate().unwrap_err();
+        assert!(errors.contains(
+            &"`telemetry_export.endpoint` must be an absolute URI, got \"localhost\".".to_owned()
+        ));
+        assert!(errors
+            .contains(&"`telemetry_export.interval_secs` must be greater than zero.".to_owned()));
+        assert!(errors.contains(
+            &"`telemetry_export` must export at least one of `metrics` or `spans`.".to_owned()
+        ));
+    }
+}
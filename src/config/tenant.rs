@@ -0,0 +1,315 @@
+use std::{num::NonZeroU32, path::PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use vector_buffers::BufferType;
+
+use super::{builder::ConfigBuilder, ComponentKey, GlobalOptions};
+
+/// A tenant of a shared pipeline, grouping components whose throughput and buffers are limited
+/// independently of the other tenants.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TenantConfig {
+    /// The IDs of the components of the tenant. Glob patterns are supported.
+    pub components: Vec<String>,
+
+    /// The maximum number of events per second sent by the sources of the tenant, all together.
+    /// Sources exceeding it are slowed down rather than dropping events.
+    #[serde(default)]
+    pub max_events_per_sec: Option<NonZeroU32>,
+
+    /// The maximum total size, in bytes, of the disk buffers of the sinks of the tenant.
+    #[serde(default)]
+    pub max_buffer_bytes: Option<u64>,
+
+    /// The maximum total number of events held by the memory buffers of the sinks of the tenant.
+    #[serde(default)]
+    pub max_buffer_events: Option<usize>,
+
+    /// The directory the disk buffers of the sinks of the tenant are stored in. Defaults to the
+    /// `tenants/<tenant>` subdirectory of the global data directory.
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+}
+
+impl TenantConfig {
+    /// Returns the directory disk buffers of the tenant are stored in.
+    pub fn data_dir(&self, name: &str, globals: &GlobalOptions) -> Option<PathBuf> {
+        self.data_dir.clone().or_else(|| {
+            globals
+                .data_dir
+                .as_deref()
+                .map(|data_dir| data_dir.join("tenants").join(name))
+        })
+    }
+
+    fn matches(&self, key: &ComponentKey) -> bool {
+        // Components expanded from a transform belong to the tenant of the transform.
+        let id = key.id();
+        let parent = id.split_once('.').map(|(parent, _)| parent);
+        self.components.iter().any(|pattern| {
+            let matches = |candidate: &str| match glob::Pattern::new(pattern) {
+                Ok(pattern) => pattern.matches(candidate),
+                Err(_) => pattern == candidate,
+            };
+            matches(id) || parent.map_or(false, matches)
+        })
+    }
+}
+
+/// Assigns the components of a config to their tenant, once transforms are expanded, checking
+/// that no component belongs to several tenants and that the buffers of each tenant fit its
+/// quotas. Returns the tenant of each component, and warnings about tenants with no components.
+pub(super) fn assign(
+    builder: &ConfigBuilder,
+) -> Result<(IndexMap<ComponentKey, String>, Vec<String>), Vec<String>> {
+    let mut assigned = IndexMap::<ComponentKey, String>::new();
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+
+    check_data_dirs(&builder.tenants, &builder.global, &mut errors);
+
+    let keys = builder
+        .sources
+        .keys()
+        .chain(builder.transforms.keys())
+        .chain(builder.sinks.keys());
+    for key in keys {
+        for (name, tenant) in builder.tenants.iter() {
+            if !tenant.matches(key) {
+                continue;
+            }
+            match assigned.get(key) {
+                Some(other) => errors.push(format!(
+                    "Component \"{}\" is assigned to both tenants \"{}\" and \"{}\".",
+                    key, other, name
+                )),
+                None => {
+                    assigned.insert(key.clone(), name.clone());
+                }
+            }
+        }
+    }
+
+    for (name, tenant) in builder.tenants.iter() {
+        if !assigned.values().any(|assigned| assigned == name) {
+            warnings.push(format!("Tenant \"{}\" has no components.", name));
+        }
+
+        let (mut buffer_bytes, mut buffer_events) = (0u64, 0usize);
+        let sinks = builder
+            .sinks
+            .iter()
+            .filter(|(key, _)| assigned.get(*key) == Some(name));
+        for (_, sink) in sinks {
+            for stage in sink.buffer.stages() {
+                match stage {
+                    BufferType::Memory { max_events, .. } => {
+                        buffer_events = buffer_events.saturating_add(max_events.get())
+                    }
+                    BufferType::DiskV1 { max_size, .. } | BufferType::DiskV2 { max_size, .. } => {
+                        buffer_bytes = buffer_bytes.saturating_add(max_size.get())
+                    }
+                }
+            }
+        }
+        if let Some(max_buffer_bytes) = tenant.max_buffer_bytes {
+            if buffer_bytes > max_buffer_bytes {
+                errors.push(format!(
+                    "Tenant \"{}\": the disk buffers of its sinks total {} bytes, exceeding `max_buffer_bytes` ({}).",
+                    name, buffer_bytes, max_buffer_bytes
+                ));
+            }
+        }
+        if let Some(max_buffer_events) = tenant.max_buffer_events {
+            if buffer_events > max_buffer_events {
+                errors.push(format!(
+                    "Tenant \"{}\": the memory buffers of its sinks total {} events, exceeding `max_buffer_events` ({}).",
+                    name, buffer_events, max_buffer_events
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok((assigned, warnings))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that tenants don't share a data directory, or use the global one, so the disk buffers
+/// of their sinks are isolated.
+fn check_data_dirs(
+    tenants: &IndexMap<String, TenantConfig>,
+    globals: &GlobalOptions,
+    errors: &mut Vec<String>,
+) {
+    let mut data_dirs = IndexMap::<PathBuf, &str>::new();
+    for (name, tenant) in tenants {
+        let tenant_data_dir = match tenant.data_dir(name, globals) {
+            Some(tenant_data_dir) => tenant_data_dir,
+            None => continue,
+        };
+        if globals.data_dir.as_deref() == Some(tenant_data_dir.as_path()) {
+            errors.push(format!(
+                "Tenant \"{}\" uses the global data directory, which isn't isolated.",
+                name
+            ));
+        } else if let Some(other) = data_dirs.insert(tenant_data_dir, name) {
+            errors.push(format!(
+                "Tenants \"{}\" and \"{}\" use the same data directory.",
+                other, name
+            ));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sources-demo_logs", feature = "sinks-console"))]
+mod tests {
+    use indoc::indoc;
+
+    use crate::config::ConfigBuilder;
+
+    fn build(config: &str) -> Result<crate::config::Config, Vec<String>> {
+        let builder: ConfigBuilder = toml::from_str(config).unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn assigns_components() {
+        let config = build(indoc! {r#"
+            data_dir = "/var/lib/vector"
+
+            [tenants.team_a]
+            components = ["team_a_*"]
+            max_events_per_sec = 1000
+
+            [tenants.team_b]
+            components = ["in_b", "out_b"]
+            data_dir = "/mnt/team_b"
+
+            [sources.team_a_in]
+            type = "demo_logs"
+            format = "json"
+
+            [sources.in_b]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.team_a_out]
+            type = "console"
+            inputs = ["team_a_in"]
+            encoding.codec = "json"
+
+            [sinks.out_b]
+            type = "console"
+            inputs = ["in_b"]
+            encoding.codec = "json"
+        "#})
+        .unwrap();
+
+        let (name, tenant) = config.tenant(&"team_a_out".into()).unwrap();
+        assert_eq!(name, "team_a");
+        assert_eq!(
+            tenant.data_dir(name, &config.global),
+            Some("/var/lib/vector/tenants/team_a".into())
+        );
+        let (name, tenant) = config.tenant(&"in_b".into()).unwrap();
+        assert_eq!(name, "team_b");
+        assert_eq!(
+            tenant.data_dir(name, &config.global),
+            Some("/mnt/team_b".into())
+        );
+    }
+
+    #[test]
+    fn rejects_components_of_several_tenants() {
+        let errors = build(indoc! {r#"
+            [tenants.team_a]
+            components = ["*"]
+
+            [tenants.team_b]
+            components = ["out"]
+
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding.codec = "json"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![r#"Component "out" is assigned to both tenants "team_a" and "team_b"."#]
+        );
+    }
+
+    #[test]
+    fn enforces_buffer_quotas() {
+        let errors = build(indoc! {r#"
+            [tenants.team_a]
+            components = ["*"]
+            max_buffer_events = 1000
+
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding.codec = "json"
+            buffer.max_events = 600
+
+            [sinks.out2]
+            type = "console"
+            inputs = ["in"]
+            encoding.codec = "json"
+            buffer.max_events = 600
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                r#"Tenant "team_a": the memory buffers of its sinks total 1200 events, exceeding `max_buffer_events` (1000)."#
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_shared_data_dirs() {
+        let errors = build(indoc! {r#"
+            data_dir = "/var/lib/vector"
+
+            [tenants.team_a]
+            components = ["in"]
+            data_dir = "/mnt/shared"
+
+            [tenants.team_b]
+            components = ["out"]
+            data_dir = "/mnt/shared"
+
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding.codec = "json"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![r#"Tenants "team_a" and "team_b" use the same data directory."#]
+        );
+    }
+}
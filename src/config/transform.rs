@@ -11,6 +11,10 @@ use super::{component, ComponentKey};
 pub struct TransformOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
     pub inputs: Vec<T>,
+    /// How long the transform is given to flush its state on shutdown, overriding the
+    /// `shutdown.transforms_timeout_secs` option.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_timeout_secs: Option<u64>,
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
@@ -20,6 +24,7 @@ impl<T> TransformOuter<T> {
     pub(super) fn new(inputs: Vec<T>, transform: impl TransformConfig + 'static) -> Self {
         TransformOuter {
             inputs,
+            shutdown_timeout_secs: None,
             inner: Box::new(transform),
         }
     }
@@ -32,6 +37,7 @@ impl<T> TransformOuter<T> {
     pub(crate) fn with_inputs<U>(self, inputs: Vec<U>) -> TransformOuter<U> {
         TransformOuter {
             inputs,
+            shutdown_timeout_secs: self.shutdown_timeout_secs,
             inner: self.inner,
         }
     }
@@ -76,6 +82,7 @@ impl TransformOuter<String> {
             for (inner_name, inner_transform) in inner_topology.inner {
                 let child = TransformOuter {
                     inputs: inner_transform.inputs,
+                    shutdown_timeout_secs: self.shutdown_timeout_secs,
                     inner: inner_transform.inner,
                 };
                 children.push(inner_name.clone());
@@ -4,9 +4,13 @@ mod unit_test_components;
 
 use std::{
     collections::{HashMap, HashSet},
+    hash::Hash,
     sync::Arc,
 };
 
+use bytes::BytesMut;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig};
 use futures_util::{stream::FuturesUnordered, StreamExt};
 use indexmap::IndexMap;
 use ordered_float::NotNan;
@@ -14,17 +18,21 @@ use tokio::sync::{
     oneshot::{self, Receiver},
     Mutex,
 };
+use tokio_util::codec::Decoder as _;
 use uuid::Uuid;
 
 use self::unit_test_components::{
-    UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult, UnitTestSourceConfig,
+    UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkEncoder, UnitTestSinkResult,
+    UnitTestSourceConfig,
 };
 use super::{compiler::expand_globs, graph::Graph, OutputId};
 use crate::{
+    codecs::DecodingConfig,
     conditions::Condition,
     config::{
-        self, compiler::expand_macros, loading, ComponentKey, Config, ConfigBuilder, ConfigPath,
-        SinkOuter, SourceOuter, TestDefinition, TestInput, TestInputValue, TestOutput,
+        self, compiler::expand_macros, loading, log_schema, ComponentKey, Config, ConfigBuilder,
+        ConfigPath, SinkOuter, SourceConfig, SourceOuter, TestDefinition, TestInput,
+        TestInputValue, TestOutput,
     },
     event::{Event, LogEvent, Value},
     schema,
@@ -94,14 +102,16 @@ pub async fn build_unit_tests_main(
 pub async fn build_unit_tests(
     mut config_builder: ConfigBuilder,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
-    // Sanitize config by removing existing sources and sinks
-    config_builder.sources = Default::default();
-    config_builder.sinks = Default::default();
+    // Sanitize config by removing existing sources and sinks, which are kept
+    // aside to be mocked by tests inserting inputs at them or extracting
+    // outputs from them
+    let sources = std::mem::take(&mut config_builder.sources);
+    let sinks = std::mem::take(&mut config_builder.sinks);
 
     let test_definitions = std::mem::take(&mut config_builder.tests);
     let mut tests = Vec::new();
     let mut build_errors = Vec::new();
-    let metadata = UnitTestBuildMetadata::initialize(&mut config_builder)?;
+    let metadata = UnitTestBuildMetadata::initialize(&mut config_builder, sources, sinks)?;
 
     for mut test_definition in test_definitions {
         let test_name = test_definition.name.clone();
@@ -139,10 +149,20 @@ pub struct UnitTestBuildMetadata {
     template_sources: IndexMap<ComponentKey, UnitTestSourceConfig>,
     // A mapping from transform name to unit test sink name.
     sink_ids: HashMap<OutputId, String>,
+    // The sources of the config, which inputs can be inserted at to be
+    // decoded as the source would decode them.
+    sources: IndexMap<ComponentKey, SourceOuter>,
+    // The sinks of the config, which outputs can be extracted from to check
+    // the payloads the sink would encode.
+    sinks: IndexMap<ComponentKey, SinkOuter<String>>,
 }
 
 impl UnitTestBuildMetadata {
-    pub fn initialize(config_builder: &mut ConfigBuilder) -> Result<Self, Vec<String>> {
+    pub fn initialize(
+        config_builder: &mut ConfigBuilder,
+        sources: IndexMap<ComponentKey, SourceOuter>,
+        sinks: IndexMap<ComponentKey, SinkOuter<String>>,
+    ) -> Result<Self, Vec<String>> {
         // A unique id used to name test sources and sinks to avoid name clashes
         let random_id = Uuid::new_v4().to_string();
 
@@ -208,6 +228,8 @@ impl UnitTestBuildMetadata {
             source_ids,
             template_sources,
             sink_ids,
+            sources,
+            sinks,
         })
     }
 
@@ -216,11 +238,18 @@ impl UnitTestBuildMetadata {
         &self,
         inputs: &[TestInput],
     ) -> Result<IndexMap<ComponentKey, SourceOuter>, Vec<String>> {
-        let inputs = build_and_validate_inputs(inputs, &self.available_insert_targets)?;
+        let inputs =
+            build_and_validate_inputs(inputs, &self.available_insert_targets, &self.sources)?;
         let mut template_sources = self.template_sources.clone();
         Ok(inputs
             .into_iter()
             .map(|(insert_at, events)| {
+                // Inputs inserted at a source are sent by a mock source
+                // replacing it, so they flow into the source's consumers
+                if self.sources.contains_key(&insert_at) {
+                    let source_config = UnitTestSourceConfig { events };
+                    return (insert_at, SourceOuter::new(source_config));
+                }
                 let mut source_config = template_sources.remove(&insert_at).unwrap_or_else(|| {
                     // At this point, all inputs should have been validated to
                     // correspond with valid transforms, and all valid transforms
@@ -241,12 +270,21 @@ impl UnitTestBuildMetadata {
             .collect::<IndexMap<_, _>>())
     }
 
+    /// Returns whether a test output is extracted from a sink of the config
+    fn is_sink_output(&self, output: &TestOutput<String>) -> bool {
+        match &output.extract_from {
+            OneOrMany::One(id) => self.sinks.contains_key(&ComponentKey::from(id.as_str())),
+            OneOrMany::Many(_) => false,
+        }
+    }
+
     /// Convert test outputs into sinks for use in a unit testing topology
     pub fn hydrate_into_sinks(
         &self,
         test_name: &str,
         outputs: &[TestOutput],
         no_outputs_from: &[OutputId],
+        sink_outputs: &[TestOutput<String>],
     ) -> Result<
         (
             Vec<Receiver<UnitTestSinkResult>>,
@@ -254,13 +292,14 @@ impl UnitTestBuildMetadata {
         ),
         Vec<String>,
     > {
-        if outputs.is_empty() && no_outputs_from.is_empty() {
+        if outputs.is_empty() && no_outputs_from.is_empty() && sink_outputs.is_empty() {
             return Err(vec![
                 "unit test must contain at least one of `outputs` or `no_outputs_from`."
                     .to_string(),
             ]);
         }
         let outputs = build_outputs(outputs)?;
+        let sink_outputs = build_outputs(sink_outputs)?;
 
         let mut template_sinks = IndexMap::new();
         let mut test_result_rxs = Vec::new();
@@ -273,6 +312,7 @@ impl UnitTestBuildMetadata {
                 transform_ids: ids.stringify().into_vec(),
                 result_tx: Arc::new(Mutex::new(Some(tx))),
                 check: UnitTestSinkCheck::Checks(checks),
+                encoder: None,
             };
 
             test_result_rxs.push(rx);
@@ -287,13 +327,14 @@ impl UnitTestBuildMetadata {
                 transform_ids: vec![id.to_string()],
                 result_tx: Arc::new(Mutex::new(Some(tx))),
                 check: UnitTestSinkCheck::NoOutputs,
+                encoder: None,
             };
 
             test_result_rxs.push(rx);
             template_sinks.insert(id.clone().into(), sink_config);
         }
 
-        let sinks = template_sinks
+        let mut sinks = template_sinks
             .into_iter()
             .map(|(transform_ids, sink_config)| {
                 let transform_ids_str = transform_ids.stringify().into_vec();
@@ -315,7 +356,40 @@ impl UnitTestBuildMetadata {
             })
             .collect::<IndexMap<_, _>>();
 
-        Ok((test_result_rxs, sinks))
+        // Replace sinks that outputs are extracted from with mock sinks
+        // checking the payloads they encode
+        let mut errors = Vec::new();
+        for (ids, checks) in sink_outputs {
+            let id = ComponentKey::from(ids.into_vec().remove(0));
+            let sink = &self.sinks[&id];
+            let encoder = match UnitTestSinkEncoder::new(sink.inner.as_ref()) {
+                Ok(encoder) => encoder,
+                Err(error) => {
+                    errors.push(format!("unable to mock sink '{}': {}", id, error));
+                    continue;
+                }
+            };
+            let (tx, rx) = oneshot::channel();
+            let sink_config = UnitTestSinkConfig {
+                test_name: test_name.to_string(),
+                transform_ids: vec![id.to_string()],
+                result_tx: Arc::new(Mutex::new(Some(tx))),
+                check: UnitTestSinkCheck::Checks(checks),
+                encoder: Some(encoder),
+            };
+
+            test_result_rxs.push(rx);
+            sinks.insert(
+                id,
+                SinkOuter::new(sink.inputs.clone(), Box::new(sink_config)),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok((test_result_rxs, sinks))
+        } else {
+            Err(errors)
+        }
     }
 }
 
@@ -330,13 +404,12 @@ fn get_relevant_test_components(
     for source in sources {
         let paths = graph.paths_to_sink_from(source);
         if paths.is_empty() {
+            let source = source.to_string();
             errors.push(format!(
                 "Unable to complete topology between input target '{}' and output target(s)",
                 source
-                    .to_string()
                     .rsplit_once("-source-")
-                    .unwrap_or(("", ""))
-                    .0
+                    .map_or(source.as_str(), |(target, _)| target)
             ));
         } else {
             for path in paths {
@@ -354,9 +427,15 @@ fn get_relevant_test_components(
 
 async fn build_unit_test(
     metadata: &UnitTestBuildMetadata,
-    test: TestDefinition<String>,
+    mut test: TestDefinition<String>,
     mut config_builder: ConfigBuilder,
 ) -> Result<UnitTest, Vec<String>> {
+    // Outputs extracted from sinks aren't part of the transform graph
+    let (sink_outputs, outputs) = std::mem::take(&mut test.outputs)
+        .into_iter()
+        .partition::<Vec<_>, _>(|output| metadata.is_sink_output(output));
+    test.outputs = outputs;
+
     let mut transform_only_config = config_builder.clone();
     let expansions = expand_macros(&mut transform_only_config)?;
     let expansions = crate::config::compiler::to_string_expansions(&expansions);
@@ -369,8 +448,12 @@ async fn build_unit_test(
     let test = test.resolve_outputs(&transform_only_graph, &expansions)?;
 
     let sources = metadata.hydrate_into_sources(&test.inputs)?;
-    let (test_result_rxs, sinks) =
-        metadata.hydrate_into_sinks(&test.name, &test.outputs, &test.no_outputs_from)?;
+    let (test_result_rxs, sinks) = metadata.hydrate_into_sinks(
+        &test.name,
+        &test.outputs,
+        &test.no_outputs_from,
+        &sink_outputs,
+    )?;
 
     config_builder.sources = sources;
     config_builder.sinks = sinks;
@@ -427,6 +510,11 @@ async fn build_unit_test(
             .filter(|input| valid_inputs.contains_key(input))
             .collect::<Vec<_>>();
     }
+    // Mock sinks keep the inputs of the sinks they replace, some of which may
+    // not be part of the test
+    for (_, sink) in config_builder.sinks.iter_mut() {
+        sink.inputs.retain(|input| valid_inputs.contains_key(input));
+    }
 
     if let Some(sink) = get_loose_end_outputs_sink(&config_builder) {
         config_builder
@@ -492,6 +580,7 @@ fn get_loose_end_outputs_sink(config: &ConfigBuilder) -> Option<SinkOuter<String
             transform_ids: vec![],
             result_tx: Arc::new(Mutex::new(None)),
             check: UnitTestSinkCheck::NoOp,
+            encoder: None,
         };
         Some(SinkOuter::new(loose_end_outputs, Box::new(noop_sink)))
     }
@@ -500,6 +589,7 @@ fn get_loose_end_outputs_sink(config: &ConfigBuilder) -> Option<SinkOuter<String
 fn build_and_validate_inputs(
     test_inputs: &[TestInput],
     available_insert_targets: &HashSet<ComponentKey>,
+    sources: &IndexMap<ComponentKey, SourceOuter>,
 ) -> Result<HashMap<ComponentKey, Vec<Event>>, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut errors = Vec::new();
@@ -509,7 +599,18 @@ fn build_and_validate_inputs(
     }
 
     for (index, input) in test_inputs.iter().enumerate() {
-        if available_insert_targets.contains(&input.insert_at) {
+        if let Some(source) = sources.get(&input.insert_at) {
+            match build_source_input_events(input, source.inner.as_ref()) {
+                Ok(input_events) => inputs
+                    .entry(input.insert_at.clone())
+                    .or_insert_with(Vec::new)
+                    .extend(input_events),
+                Err(error) => errors.push(format!(
+                    "inputs[{}]: unable to decode input for source '{}': {}",
+                    index, input.insert_at, error
+                )),
+            }
+        } else if available_insert_targets.contains(&input.insert_at) {
             match build_input_event(input) {
                 Ok(input_event) => {
                     inputs
@@ -536,10 +637,10 @@ fn build_and_validate_inputs(
     }
 }
 
-fn build_outputs(
-    test_outputs: &[TestOutput],
-) -> Result<IndexMap<OneOrMany<OutputId>, Vec<Vec<Condition>>>, Vec<String>> {
-    let mut outputs: IndexMap<OneOrMany<OutputId>, Vec<Vec<Condition>>> = IndexMap::new();
+fn build_outputs<T: Clone + Eq + Hash>(
+    test_outputs: &[TestOutput<T>],
+) -> Result<IndexMap<OneOrMany<T>, Vec<Vec<Condition>>>, Vec<String>> {
+    let mut outputs: IndexMap<OneOrMany<T>, Vec<Vec<Condition>>> = IndexMap::new();
     let mut errors = Vec::new();
 
     for output in test_outputs {
@@ -611,3 +712,56 @@ fn build_input_event(input: &TestInput) -> Result<Event, String> {
         )),
     }
 }
+
+/// Decodes a raw input inserted at a source with the source's `framing` and
+/// `decoding` options, as if the source had received it. Log and metric inputs
+/// are sent as they are.
+fn build_source_input_events(
+    input: &TestInput,
+    source: &dyn SourceConfig,
+) -> Result<Vec<Event>, String> {
+    if input.type_str != "raw" {
+        return build_input_event(input).map(|event| vec![event]);
+    }
+    let value = input
+        .value
+        .as_ref()
+        .ok_or_else(|| "input type 'raw' requires the field 'value'".to_string())?;
+
+    let config = serde_json::to_value(source).map_err(|error| error.to_string())?;
+    let decoding = match config.get("decoding") {
+        Some(decoding) if !decoding.is_null() => {
+            serde_json::from_value::<DeserializerConfig>(decoding.clone())
+                .map_err(|error| format!("invalid `decoding` option: {}", error))?
+        }
+        _ => return Err("source does not decode raw payloads".to_string()),
+    };
+    let framing = match config.get("framing") {
+        Some(framing) if !framing.is_null() => {
+            serde_json::from_value::<FramingConfig>(framing.clone())
+                .map_err(|error| format!("invalid `framing` option: {}", error))?
+        }
+        _ => decoding.default_stream_framing(),
+    };
+    let mut decoder = DecodingConfig::new(framing, decoding)
+        .build()
+        .map_err(|error| error.to_string())?;
+
+    let mut buffer = BytesMut::from(value.as_str());
+    let mut events = Vec::new();
+    while let Some((decoded, _)) = decoder
+        .decode_eof(&mut buffer)
+        .map_err(|error| error.to_string())?
+    {
+        events.extend(decoded);
+    }
+
+    let now = Utc::now();
+    for event in events.iter_mut() {
+        if let Event::Log(log) = event {
+            log.try_insert(log_schema().source_type_key(), source.source_type());
+            log.try_insert(log_schema().timestamp_key(), now);
+        }
+    }
+    Ok(events)
+}
@@ -1235,3 +1235,125 @@ async fn test_glob_input() {
     let mut tests = build_unit_tests(config).await.unwrap();
     assert!(tests.remove(0).run().await.errors.is_empty());
 }
+
+#[tokio::test]
+async fn test_source_input() {
+    let config: ConfigBuilder = toml::from_str(indoc! {r#"
+        [sources.in]
+          type = "demo_logs"
+          format = "shuffle"
+          lines = ["ignored"]
+          framing.method = "newline_delimited"
+          decoding.codec = "json"
+
+        [transforms.foo]
+          inputs = ["in"]
+          type = "remap"
+          source = '''
+          .new_field = "string value"
+          '''
+
+        [[tests]]
+          name = "source input"
+
+          [[tests.inputs]]
+            insert_at = "in"
+            value = "{\"id\": 1}\n{\"id\": 2}"
+
+          [[tests.outputs]]
+            extract_from = "foo"
+            [[tests.outputs.conditions]]
+              type = "vrl"
+              source = """
+                assert_eq!(.id, 1)
+                assert_eq!(.new_field, "string value")
+                assert_eq!(.source_type, "demo_logs")
+                assert!(is_timestamp(.timestamp))
+              """
+            [[tests.outputs.conditions]]
+              type = "vrl"
+              source = """
+                assert_eq!(.id, 2)
+              """
+    "#})
+    .unwrap();
+
+    let mut tests = build_unit_tests(config).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_source_input_bad_payload() {
+    let config: ConfigBuilder = toml::from_str(indoc! {r#"
+        [sources.in]
+          type = "demo_logs"
+          format = "shuffle"
+          lines = ["ignored"]
+          decoding.codec = "json"
+
+        [transforms.foo]
+          inputs = ["in"]
+          type = "remap"
+          source = ""
+
+        [[tests]]
+          name = "bad payload"
+
+          [[tests.inputs]]
+            insert_at = "in"
+            value = "not json"
+
+          [[tests.outputs]]
+            extract_from = "foo"
+    "#})
+    .unwrap();
+
+    let errs = build_unit_tests(config).await.err().unwrap();
+    assert_eq!(errs.len(), 1);
+    assert!(errs[0].contains("inputs[0]: unable to decode input for source 'in'"));
+}
+
+#[tokio::test]
+async fn test_sink_output() {
+    let config: ConfigBuilder = toml::from_str(indoc! {r#"
+        [sources.in]
+          type = "demo_logs"
+          format = "shuffle"
+          lines = ["ignored"]
+          framing.method = "newline_delimited"
+          decoding.codec = "json"
+
+        [transforms.foo]
+          inputs = ["in"]
+          type = "remap"
+          source = '''
+          .new_field = "string value"
+          '''
+
+        [sinks.out]
+          inputs = ["foo"]
+          type = "console"
+          encoding.codec = "json"
+          encoding.only_fields = ["id", "new_field"]
+
+        [[tests]]
+          name = "sink output"
+
+          [[tests.inputs]]
+            insert_at = "in"
+            value = "{\"id\": 1}\n{\"id\": 2}"
+
+          [[tests.outputs]]
+            extract_from = "out"
+            [[tests.outputs.conditions]]
+              type = "vrl"
+              source = '''
+                assert_eq!(.events, 2)
+                assert_eq!(.body, s'{"id":1,"new_field":"string value"}' + "\n" + s'{"id":2,"new_field":"string value"}')
+              '''
+    "#})
+    .unwrap();
+
+    let mut tests = build_unit_tests(config).await.unwrap();
+    assert_eq!(tests.remove(0).run().await.errors, Vec::<String>::new());
+}
@@ -1,18 +1,26 @@
 use std::sync::Arc;
 
+use codecs::{
+    encoding::{Framer, FramingConfig},
+    NewlineDelimitedEncoder,
+};
 use futures_util::{future, stream::BoxStream, FutureExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{oneshot, Mutex};
 use vector_core::{
     config::{DataType, Input, Output},
-    event::Event,
+    event::{Event, LogEvent},
     sink::{StreamSink, VectorSink},
 };
 
 use crate::{
+    codecs::{Encoder, EncodingConfig},
     conditions::Condition,
     config::{AcknowledgementsConfig, SinkConfig, SinkContext, SourceConfig, SourceContext},
-    sinks::Healthcheck,
+    sinks::{
+        util::encoding::{Encoder as _, Transformer},
+        Healthcheck,
+    },
     sources,
 };
 
@@ -67,6 +75,73 @@ impl Default for UnitTestSinkCheck {
     }
 }
 
+/// Encodes the events received by a mock sink into the payloads of the
+/// requests the mocked sink would send, according to its `encoding`, `framing`
+/// and `batch.max_events` options.
+#[derive(Clone)]
+pub struct UnitTestSinkEncoder {
+    transformer: Transformer,
+    encoder: Encoder<Framer>,
+    max_events: Option<usize>,
+}
+
+impl UnitTestSinkEncoder {
+    pub fn new(sink: &dyn SinkConfig) -> Result<Self, String> {
+        let config = serde_json::to_value(sink).map_err(|error| error.to_string())?;
+        let encoding = match config.get("encoding") {
+            Some(encoding) if !encoding.is_null() => {
+                serde_json::from_value::<EncodingConfig>(encoding.clone())
+                    .map_err(|error| format!("unsupported `encoding` option: {}", error))?
+            }
+            _ => return Err("sink does not encode events".to_string()),
+        };
+        let framer = match config.get("framing") {
+            Some(framing) if !framing.is_null() => {
+                serde_json::from_value::<FramingConfig>(framing.clone())
+                    .map_err(|error| format!("invalid `framing` option: {}", error))?
+                    .build()
+            }
+            _ => NewlineDelimitedEncoder::new().into(),
+        };
+        let serializer = encoding
+            .config()
+            .build()
+            .map_err(|error| error.to_string())?;
+        let max_events = config
+            .pointer("/batch/max_events")
+            .and_then(serde_json::Value::as_u64)
+            .map(|max_events| max_events as usize);
+
+        Ok(Self {
+            transformer: encoding.transformer(),
+            encoder: Encoder::<Framer>::new(framer, serializer),
+            max_events,
+        })
+    }
+
+    /// Returns a log event for each request, with the encoded `body` of the
+    /// request and the number of `events` it contains.
+    fn encode(&self, events: Vec<Event>) -> Result<Vec<Event>, String> {
+        let max_events = self.max_events.unwrap_or(usize::MAX).max(1);
+        let mut requests = Vec::new();
+        let mut events = events.into_iter().peekable();
+        while events.peek().is_some() {
+            let batch = events.by_ref().take(max_events).collect::<Vec<_>>();
+            let count = batch.len();
+            let mut body = Vec::new();
+            (self.transformer.clone(), self.encoder.clone())
+                .encode_input(batch, &mut body)
+                .map_err(|error| format!("failed to encode events: {}", error))?;
+
+            let mut request = LogEvent::default();
+            request.insert("body", String::from_utf8_lossy(&body).into_owned());
+            request.insert("events", count as i64);
+            requests.push(request.into());
+        }
+        Ok(requests)
+    }
+}
+
 #[derive(Debug)]
 pub struct UnitTestSinkResult {
     pub test_name: String,
@@ -87,6 +162,10 @@ pub struct UnitTestSinkConfig {
     #[derivative(Debug = "ignore")]
     // Check applied to incoming events
     pub check: UnitTestSinkCheck,
+    #[serde(skip)]
+    #[derivative(Debug = "ignore")]
+    // Encoder of the mocked sink, applied to incoming events before checking them
+    pub encoder: Option<UnitTestSinkEncoder>,
 }
 
 #[async_trait::async_trait]
@@ -99,6 +178,7 @@ impl SinkConfig for UnitTestSinkConfig {
             transform_ids: self.transform_ids.clone(),
             result_tx: tx,
             check: self.check.clone(),
+            encoder: self.encoder.clone(),
         };
         let healthcheck = future::ok(()).boxed();
 
@@ -124,6 +204,8 @@ pub struct UnitTestSink {
     // None for NoOp test sinks
     pub result_tx: Option<oneshot::Sender<UnitTestSinkResult>>,
     pub check: UnitTestSinkCheck,
+    // Some for mock sinks checking encoded payloads
+    pub encoder: Option<UnitTestSinkEncoder>,
 }
 
 #[async_trait::async_trait]
@@ -139,7 +221,22 @@ impl StreamSink<Event> for UnitTestSink {
             output_events.push(event);
         }
 
-        match self.check {
+        let mut check = self.check;
+        if let Some(encoder) = &self.encoder {
+            match encoder.encode(output_events) {
+                Ok(requests) => output_events = requests,
+                Err(error) => {
+                    output_events = Vec::new();
+                    result.test_errors.push(format!(
+                        "checks for sink {:?} failed: {}",
+                        self.transform_ids, error
+                    ));
+                    check = UnitTestSinkCheck::NoOp;
+                }
+            }
+        }
+
+        match check {
             UnitTestSinkCheck::Checks(checks) => {
                 if output_events.is_empty() {
                     result
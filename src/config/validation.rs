@@ -82,7 +82,12 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         .iter()
         .map(|(key, transform)| ("transform", key.clone(), transform.inputs.clone()));
     for (output_type, key, inputs) in sink_inputs.chain(transform_inputs) {
-        if inputs.is_empty() {
+        // The dead letter component of a sink may have no other input.
+        let is_dead_letter = config
+            .sinks
+            .values()
+            .any(|sink| sink.dead_letter.as_ref() == Some(&key));
+        if inputs.is_empty() && !is_dead_letter {
             errors.push(format!(
                 "{} \"{}\" has no inputs",
                 capitalize(output_type),
@@ -107,6 +112,16 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         }
     }
 
+    for (key, sink) in config.sinks.iter() {
+        if let Some(circuit_breaker) = &sink.circuit_breaker {
+            if let Err(circuit_breaker_errors) =
+                circuit_breaker.validate(key, sink.dead_letter.is_some())
+            {
+                errors.extend(circuit_breaker_errors);
+            }
+        }
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
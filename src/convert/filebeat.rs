@@ -0,0 +1,857 @@
+//! Conversion of Filebeat configuration.
+//!
+//! The events of each input go through the processors of the input, then through the global
+//! processors, then to the output. Processors are converted to `remap` transforms, and to
+//! `filter` transforms for those dropping events.
+
+use serde_yaml::{Mapping, Value as Yaml};
+
+use super::{
+    template, vrl_dotted_path, vrl_if, vrl_merge_parsed, vrl_path, vrl_regex, vrl_string,
+    with_scheme, Component, Conversion,
+};
+
+pub(super) fn convert(input: &str) -> Result<Conversion, Vec<String>> {
+    let config: Yaml = serde_yaml::from_str(input).map_err(|error| vec![error.to_string()])?;
+    // Filebeat reads dotted keys, such as `output.elasticsearch.hosts`, as nested settings.
+    let config = expand(config);
+    let mut conversion = Conversion::default();
+
+    let mut inputs = Vec::new();
+    for input in sequence(&config["filebeat"]["inputs"]) {
+        if input["enabled"].as_bool() != Some(false) {
+            inputs.extend(convert_input(input, &mut conversion));
+        }
+    }
+    if !config["filebeat"]["modules"].is_null()
+        || !config["filebeat"]["config"]["modules"].is_null()
+    {
+        conversion.note("Filebeat modules weren't converted, convert the inputs and ingest pipelines they set up by hand.");
+    }
+
+    let mut chain = Chain::new("processors", inputs);
+    chain.fields(&config);
+    chain.processors(&config["processors"], &mut conversion);
+    let inputs = chain.finish(&mut conversion);
+
+    if let Some(outputs) = config["output"].as_mapping() {
+        for (name, output) in outputs.iter() {
+            let name = name.as_str().unwrap_or_default();
+            if output["enabled"].as_bool() == Some(false) {
+                continue;
+            }
+            if let Some(sink) = output_component(name, output, &inputs, &mut conversion) {
+                conversion.add(name, sink);
+            }
+        }
+    }
+
+    Ok(conversion)
+}
+
+/// Expands the dotted keys of mappings into nested mappings.
+fn expand(value: Yaml) -> Yaml {
+    match value {
+        Yaml::Mapping(mapping) => {
+            let mut expanded = Mapping::new();
+            for (key, value) in mapping {
+                let value = expand(value);
+                match key.as_str().and_then(|key| key.split_once('.')) {
+                    Some((first, rest)) => {
+                        let value = rest.rsplit('.').fold(value, |value, segment| {
+                            let mut nested = Mapping::new();
+                            nested.insert(segment.into(), value);
+                            Yaml::Mapping(nested)
+                        });
+                        insert(&mut expanded, first.into(), value);
+                    }
+                    None => insert(&mut expanded, key, value),
+                }
+            }
+            Yaml::Mapping(expanded)
+        }
+        Yaml::Sequence(values) => Yaml::Sequence(values.into_iter().map(expand).collect()),
+        value => value,
+    }
+}
+
+/// Inserts a value into a mapping, merging it into the mapping already set for the key if any.
+fn insert(mapping: &mut Mapping, key: Yaml, value: Yaml) {
+    match (mapping.get_mut(&key), value) {
+        (Some(Yaml::Mapping(existing)), Yaml::Mapping(value)) => {
+            for (key, value) in value {
+                insert(existing, key, value);
+            }
+        }
+        (_, value) => {
+            mapping.insert(key, value);
+        }
+    }
+}
+
+fn sequence(value: &Yaml) -> &[Yaml] {
+    value.as_sequence().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// The strings of a value, which may be a single string or a sequence of them.
+fn strings(value: &Yaml) -> Vec<&str> {
+    match value {
+        Yaml::Sequence(values) => values.iter().filter_map(Yaml::as_str).collect(),
+        value => value.as_str().into_iter().collect(),
+    }
+}
+
+/// Returns the string of a scalar value.
+fn scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        Yaml::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the fields set by a mapping, along with the segments of their path, the fields of
+/// nested mappings being the fields of objects.
+fn fields(value: &Yaml) -> Vec<(Vec<&str>, &Yaml)> {
+    let mut fields = Vec::new();
+    if let Some(mapping) = value.as_mapping() {
+        for (key, value) in mapping.iter() {
+            let key = match key.as_str() {
+                Some(key) => key,
+                None => continue,
+            };
+            match value {
+                Yaml::Mapping(_) => {
+                    for (mut path, value) in self::fields(value) {
+                        path.insert(0, key);
+                        fields.push((path, value));
+                    }
+                }
+                value => fields.push((vec![key], value)),
+            }
+        }
+    }
+    fields
+}
+
+/// Returns the VRL literal of a value.
+fn vrl_literal(value: &Yaml) -> String {
+    match value {
+        Yaml::Null => "null".to_owned(),
+        Yaml::Bool(b) => b.to_string(),
+        Yaml::Number(n) => n.to_string(),
+        Yaml::String(s) => vrl_string(s),
+        Yaml::Sequence(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(vrl_literal)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Yaml::Mapping(mapping) => format!(
+            "{{{}}}",
+            mapping
+                .iter()
+                .filter_map(|(key, value)| {
+                    Some(format!(
+                        "{}: {}",
+                        vrl_string(key.as_str()?),
+                        vrl_literal(value)
+                    ))
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Parses a duration such as `5s` or `500ms`, in milliseconds. Numbers are seconds.
+fn duration_ms(value: &Yaml) -> Option<u64> {
+    if let Some(seconds) = value.as_f64() {
+        return Some((seconds * 1000.0) as u64);
+    }
+    let duration = value.as_str()?.trim();
+    let (number, multiplier) = if let Some(number) = duration.strip_suffix("ms") {
+        (number, 1.0)
+    } else if let Some(number) = duration.strip_suffix('s') {
+        (number, 1000.0)
+    } else if let Some(number) = duration.strip_suffix('m') {
+        (number, 60_000.0)
+    } else if let Some(number) = duration.strip_suffix('h') {
+        (number, 3_600_000.0)
+    } else {
+        return None;
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|number| (number * multiplier) as u64)
+}
+
+/// Converts a processor condition into a VRL condition, or explains why it can't be.
+fn condition(when: &Yaml) -> Result<String, String> {
+    let mapping = when
+        .as_mapping()
+        .ok_or_else(|| "its condition isn't a mapping".to_owned())?;
+    let mut conditions = Vec::new();
+    for (kind, operand) in mapping.iter() {
+        let kind = kind.as_str().unwrap_or_default();
+        let fields = fields(operand);
+        let condition = match kind {
+            "equals" => fields
+                .iter()
+                .map(|(path, value)| {
+                    format!("{} == {}", vrl_path(path.clone()), vrl_literal(value))
+                })
+                .collect::<Vec<_>>()
+                .join(" && "),
+            "contains" => fields
+                .iter()
+                .map(|(path, value)| {
+                    format!(
+                        "contains(to_string({}) ?? \"\", {})",
+                        vrl_path(path.clone()),
+                        vrl_string(&scalar(value).unwrap_or_default())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" && "),
+            "regexp" => fields
+                .iter()
+                .map(|(path, value)| {
+                    let regex = scalar(value)
+                        .and_then(|regex| vrl_regex(&regex))
+                        .ok_or_else(|| "its regular expression isn't supported".to_owned())?;
+                    Ok(format!(
+                        "match(to_string({}) ?? \"\", {})",
+                        vrl_path(path.clone()),
+                        regex
+                    ))
+                })
+                .collect::<Result<Vec<_>, String>>()?
+                .join(" && "),
+            "has_fields" => strings(operand)
+                .iter()
+                .map(|field| format!("exists({})", vrl_dotted_path(field)))
+                .collect::<Vec<_>>()
+                .join(" && "),
+            "and" | "or" => sequence(operand)
+                .iter()
+                .map(|operand| Ok(format!("({})", condition(operand)?)))
+                .collect::<Result<Vec<_>, String>>()?
+                .join(if kind == "and" { " && " } else { " || " }),
+            "not" => format!("!({})", condition(operand)?),
+            kind => return Err(format!("the `{}` condition isn't supported", kind)),
+        };
+        if condition.is_empty() {
+            return Err(format!("its `{}` condition is empty", kind));
+        }
+        conditions.push(condition);
+    }
+    match conditions.as_slice() {
+        [] => Err("its condition is empty".to_owned()),
+        [condition] => Ok(condition.clone()),
+        conditions => Ok(conditions
+            .iter()
+            .map(|condition| format!("({})", condition))
+            .collect::<Vec<_>>()
+            .join(" && ")),
+    }
+}
+
+/// The transforms converted from the processors of an input, or the global ones, chained after
+/// the given inputs.
+struct Chain {
+    name: String,
+    inputs: Vec<String>,
+    /// The program of the `remap` transform being converted.
+    program: Vec<String>,
+    notes: Vec<String>,
+}
+
+impl Chain {
+    fn new(name: &str, inputs: Vec<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            inputs,
+            program: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Adds the program being converted as a `remap` transform.
+    fn flush(&mut self, conversion: &mut Conversion) {
+        if self.program.is_empty() {
+            return;
+        }
+        let mut remap = Component::remap(&self.inputs, &self.program.join("\n"));
+        self.notes.drain(..).for_each(|note| remap.note(note));
+        self.program.clear();
+        self.inputs = vec![conversion.add(&self.name, remap)];
+    }
+
+    /// Adds a `filter` transform, letting through the events for which the condition holds.
+    fn filter(&mut self, condition: &str, conversion: &mut Conversion) {
+        self.flush(conversion);
+        let mut filter = Component::filter(&self.inputs, condition);
+        self.notes.drain(..).for_each(|note| filter.note(note));
+        self.inputs = vec![conversion.add(&format!("{}_filter", self.name), filter)];
+    }
+
+    /// Returns the ID of the last transform of the chain, or its inputs if there are none.
+    fn finish(mut self, conversion: &mut Conversion) -> Vec<String> {
+        self.flush(conversion);
+        self.notes
+            .into_iter()
+            .for_each(|note| conversion.note(note));
+        self.inputs
+    }
+
+    /// Converts the `fields`, `fields_under_root` and `tags` settings, of an input or global.
+    fn fields(&mut self, config: &Yaml) {
+        let target = if config["fields_under_root"].as_bool() == Some(true) {
+            ""
+        } else {
+            "fields"
+        };
+        for (path, value) in fields(&config["fields"]) {
+            self.program.push(format!(
+                "{} = {}",
+                vrl_path(target.split('.').chain(path)),
+                vrl_literal(value)
+            ));
+        }
+        for tag in strings(&config["tags"]) {
+            self.program.push(format!(
+                ".tags = push(array(.tags) ?? [], {})",
+                vrl_string(tag)
+            ));
+        }
+    }
+
+    fn processors(&mut self, processors: &Yaml, conversion: &mut Conversion) {
+        for processor in sequence(processors) {
+            let (name, settings) = match processor.as_mapping().and_then(|p| p.iter().next()) {
+                Some((name, settings)) => (name.as_str().unwrap_or_default(), settings),
+                None => continue,
+            };
+            let condition = match &settings["when"] {
+                Yaml::Null => None,
+                when => match condition(when) {
+                    Ok(condition) => Some(condition),
+                    Err(reason) => {
+                        self.notes.push(format!(
+                            "The `{}` processor wasn't converted, as {}.",
+                            name, reason
+                        ));
+                        continue;
+                    }
+                },
+            };
+
+            let mut program = Vec::new();
+            match name {
+                "drop_event" => {
+                    match condition {
+                        Some(condition) => {
+                            self.filter(&format!("!({})", condition), conversion);
+                        }
+                        None => self.notes.push("An unconditional `drop_event` processor wasn't converted, as it would drop all of the events.".to_owned()),
+                    }
+                    continue;
+                }
+                "add_fields" => {
+                    let target = settings["target"].as_str().unwrap_or("fields");
+                    for (path, value) in fields(&settings["fields"]) {
+                        program.push(format!(
+                            "{} = {}",
+                            vrl_path(target.split('.').chain(path)),
+                            vrl_literal(value)
+                        ));
+                    }
+                }
+                "add_tags" => {
+                    let target = vrl_dotted_path(settings["target"].as_str().unwrap_or("tags"));
+                    for tag in strings(&settings["tags"]) {
+                        program.push(format!(
+                            "{} = push(array({}) ?? [], {})",
+                            target,
+                            target,
+                            vrl_string(tag)
+                        ));
+                    }
+                }
+                "drop_fields" => {
+                    for field in strings(&settings["fields"]) {
+                        program.push(format!("del({})", vrl_dotted_path(field)));
+                    }
+                }
+                "rename" | "copy_fields" => {
+                    for field in sequence(&settings["fields"]) {
+                        if let (Some(from), Some(to)) =
+                            (field["from"].as_str(), field["to"].as_str())
+                        {
+                            let from = vrl_dotted_path(from);
+                            program.push(if name == "rename" {
+                                format!("{} = del({})", vrl_dotted_path(to), from)
+                            } else {
+                                format!("{} = {}", vrl_dotted_path(to), from)
+                            });
+                        }
+                    }
+                }
+                "decode_json_fields" => {
+                    for field in strings(&settings["fields"]) {
+                        let field = vrl_dotted_path(field);
+                        // The decoded object replaces the field, unless a target is set, the
+                        // empty target being the root of the event.
+                        let target = match settings["target"].as_str() {
+                            None => Some(field.clone()),
+                            Some("") => None,
+                            Some(target) => Some(vrl_dotted_path(target)),
+                        };
+                        program.push(vrl_merge_parsed(
+                            &format!("parse_json(to_string({}) ?? \"\")", field),
+                            target.as_deref(),
+                            "_jsonparsefailure",
+                        ));
+                    }
+                }
+                name => {
+                    self.notes.push(format!(
+                        "The `{}` processor has no equivalent, and wasn't converted.",
+                        name
+                    ));
+                    continue;
+                }
+            }
+
+            match condition {
+                Some(condition) => self.program.push(vrl_if(&condition, &program.join("\n"))),
+                None => self.program.extend(program),
+            }
+        }
+    }
+}
+
+/// Converts an input, along with its processors, and returns the ID of the last component
+/// converted from it.
+fn convert_input(input: &Yaml, conversion: &mut Conversion) -> Option<String> {
+    let input_type = input["type"].as_str().unwrap_or("log");
+    let mut program = Vec::new();
+    let mut filters = Vec::new();
+
+    let source = match input_type {
+        "log" | "filestream" => {
+            let mut source = Component::source("file");
+            source.set("include", strings(&input["paths"]));
+            source.set("read_from", "beginning");
+            if !input["exclude_files"].is_null()
+                || !input["prospector"]["scanner"]["exclude_files"].is_null()
+            {
+                source.note("`exclude_files` wasn't converted, as it's made of regular expressions, set `exclude` to the equivalent glob patterns.");
+            }
+
+            let parser = |name: &str| {
+                sequence(&input["parsers"])
+                    .iter()
+                    .map(|parser| &parser[name])
+                    .find(|parser| !parser.is_null())
+            };
+            let (multiline, json) = if input_type == "log" {
+                (Some(&input["multiline"]), Some(&input["json"]))
+            } else {
+                (parser("multiline"), parser("ndjson"))
+            };
+
+            if let Some(multiline) = multiline.filter(|multiline| !multiline.is_null()) {
+                match multiline_options(multiline) {
+                    Ok(options) => {
+                        for (key, value) in options {
+                            source.set(&format!("multiline.{}", key), value);
+                        }
+                    }
+                    Err(note) => source.note(note),
+                }
+            }
+
+            if let Some(json) = json.filter(|json| !json.is_null()) {
+                let target = if input_type == "log" {
+                    (json["keys_under_root"].as_bool() != Some(true)).then(|| ".json".to_owned())
+                } else {
+                    json["target"]
+                        .as_str()
+                        .filter(|target| !target.is_empty())
+                        .map(vrl_dotted_path)
+                };
+                program.push(vrl_merge_parsed(
+                    "parse_json(.message)",
+                    target.as_deref(),
+                    "_jsonparsefailure",
+                ));
+            }
+
+            for (setting, negate) in [("include_lines", false), ("exclude_lines", true)] {
+                let patterns = strings(&input[setting])
+                    .into_iter()
+                    .map(vrl_regex)
+                    .collect::<Option<Vec<_>>>();
+                match patterns {
+                    Some(patterns) if !patterns.is_empty() => filters.push(format!(
+                        "{}match_any(to_string(.message) ?? \"\", [{}])",
+                        if negate { "!" } else { "" },
+                        patterns.join(", ")
+                    )),
+                    Some(_) => {}
+                    None => source.note(format!(
+                        "`{}` wasn't converted, as one of its regular expressions isn't supported.",
+                        setting
+                    )),
+                }
+            }
+            source
+        }
+        "syslog" => {
+            let mut source = Component::source("syslog");
+            let (mode, protocol) = if input["protocol"]["tcp"].is_null() {
+                ("udp", &input["protocol"]["udp"])
+            } else {
+                ("tcp", &input["protocol"]["tcp"])
+            };
+            source.set("mode", mode);
+            source.set(
+                "address",
+                protocol["host"].as_str().unwrap_or("localhost:9000"),
+            );
+            source
+        }
+        "tcp" | "udp" => {
+            let mut source = Component::source("socket");
+            source.set("mode", input_type);
+            source.set(
+                "address",
+                input["host"].as_str().unwrap_or("localhost:9000"),
+            );
+            source
+        }
+        "stdin" => Component::source("stdin"),
+        "kafka" => {
+            let mut source = Component::source("kafka");
+            source.set("bootstrap_servers", strings(&input["hosts"]).join(","));
+            source.set("topics", strings(&input["topics"]));
+            source.set("group_id", input["group_id"].as_str().unwrap_or("filebeat"));
+            source
+        }
+        "http_endpoint" => {
+            let mut source = Component::source("http");
+            source.set(
+                "address",
+                format!(
+                    "{}:{}",
+                    input["listen_address"].as_str().unwrap_or("localhost"),
+                    scalar(&input["listen_port"]).unwrap_or_else(|| "8000".to_owned())
+                ),
+            );
+            source.set("decoding.codec", "json");
+            source
+        }
+        "container" | "docker" => {
+            conversion.note(format!(
+                "The `{}` input wasn't converted, use the `kubernetes_logs` or `docker_logs` source instead.",
+                input_type
+            ));
+            return None;
+        }
+        input_type => {
+            conversion.note(format!(
+                "The `{}` input has no equivalent, and wasn't converted.",
+                input_type
+            ));
+            return None;
+        }
+    };
+
+    let id = input["id"].as_str().unwrap_or(input_type);
+    let id = conversion.add(id, source);
+    let mut chain = Chain::new(&format!("{}_processors", id), vec![id]);
+    chain.program = program;
+    for filter in filters {
+        chain.filter(&filter, conversion);
+    }
+    chain.fields(input);
+    chain.processors(&input["processors"], conversion);
+    chain.finish(conversion).pop()
+}
+
+/// Converts the multiline settings of an input into the `multiline` options of the `file` source.
+fn multiline_options(multiline: &Yaml) -> Result<Vec<(&'static str, toml::Value)>, String> {
+    let multiline_type = multiline["type"].as_str().unwrap_or("pattern");
+    let pattern = match (multiline_type, multiline["pattern"].as_str()) {
+        ("pattern", Some(pattern)) => pattern,
+        _ => {
+            return Err(format!(
+                "The `{}` multiline settings weren't converted, only patterns are supported.",
+                multiline_type
+            ))
+        }
+    };
+    let negate = multiline["negate"].as_bool().unwrap_or(false);
+    let after = multiline["match"].as_str().unwrap_or("after") == "after";
+
+    // The lines matching the pattern, or not if negated, are appended to the previous line when
+    // matching after, and prepended to the next line when matching before.
+    let (start_pattern, mode) = match (negate, after) {
+        (true, true) => (pattern, "halt_before"),
+        (false, true) => ("", "continue_through"),
+        (false, false) => (pattern, "continue_past"),
+        (true, false) => ("", "halt_with"),
+    };
+    let timeout_ms = match &multiline["timeout"] {
+        Yaml::Null => 5000,
+        timeout => duration_ms(timeout).ok_or_else(|| {
+            "The multiline settings weren't converted, as their `timeout` is invalid.".to_owned()
+        })?,
+    };
+    Ok(vec![
+        ("start_pattern", start_pattern.into()),
+        ("condition_pattern", pattern.into()),
+        ("mode", mode.into()),
+        ("timeout_ms", (timeout_ms as i64).into()),
+    ])
+}
+
+fn output_component(
+    name: &str,
+    output: &Yaml,
+    inputs: &[String],
+    conversion: &mut Conversion,
+) -> Option<Component> {
+    let field_path = |field: &str| vrl_dotted_path(field.trim_matches(|c| c == '[' || c == ']'));
+    let hosts = strings(&output["hosts"]);
+
+    let sink = match name {
+        "elasticsearch" => {
+            let mut sink = Component::sink("elasticsearch", inputs);
+            let scheme = output["protocol"].as_str().unwrap_or("http");
+            sink.set(
+                "endpoint",
+                with_scheme(hosts.first().copied().unwrap_or("localhost:9200"), scheme),
+            );
+            if hosts.len() > 1 {
+                sink.note("Only the first of the `hosts` was converted.");
+            }
+            sink.set(
+                "bulk.index",
+                template(
+                    output["index"]
+                        .as_str()
+                        .unwrap_or("filebeat-%{+yyyy.MM.dd}"),
+                    field_path,
+                ),
+            );
+            if let (Some(user), Some(password)) =
+                (output["username"].as_str(), output["password"].as_str())
+            {
+                sink.set("auth.strategy", "basic");
+                sink.set("auth.user", user);
+                sink.set("auth.password", password);
+            }
+            if !output["pipeline"].is_null() {
+                sink.note("The ingest `pipeline` wasn't converted, set `pipeline` to its name.");
+            }
+            sink
+        }
+        "kafka" => {
+            let mut sink = Component::sink("kafka", inputs);
+            sink.set("bootstrap_servers", hosts.join(","));
+            sink.set(
+                "topic",
+                template(output["topic"].as_str().unwrap_or_default(), field_path),
+            );
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "file" => {
+            let mut sink = Component::sink("file", inputs);
+            sink.set(
+                "path",
+                format!(
+                    "{}/{}",
+                    output["path"].as_str().unwrap_or("/tmp/filebeat"),
+                    output["filename"].as_str().unwrap_or("filebeat")
+                ),
+            );
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "console" => {
+            let mut sink = Component::sink("console", inputs);
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "redis" => {
+            let mut sink = Component::sink("redis", inputs);
+            sink.set(
+                "url",
+                with_scheme(hosts.first().copied().unwrap_or("localhost:6379"), "redis"),
+            );
+            sink.set(
+                "key",
+                template(output["key"].as_str().unwrap_or("filebeat"), field_path),
+            );
+            sink.set("data_type", output["datatype"].as_str().unwrap_or("list"));
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "logstash" => {
+            conversion.note("The `logstash` output wasn't converted, as there is no sink for the Beats protocol. Send the events to a Vector aggregator with the `vector` sink instead.");
+            return None;
+        }
+        name => {
+            conversion.note(format!(
+                "The `{}` output has no equivalent, and wasn't converted.",
+                name
+            ));
+            return None;
+        }
+    };
+    Some(sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn component<'a>(conversion: &'a Conversion, id: &str) -> &'a Component {
+        &conversion
+            .components
+            .iter()
+            .find(|(component, _)| component == id)
+            .unwrap_or_else(|| panic!("{} wasn't converted", id))
+            .1
+    }
+
+    #[test]
+    fn expands_dotted_keys() {
+        let config: Yaml = serde_yaml::from_str(indoc! {r#"
+            output.elasticsearch.hosts: ["es:9200"]
+            output:
+              elasticsearch:
+                index: logs
+        "#})
+        .unwrap();
+        let config = expand(config);
+        assert_eq!(
+            strings(&config["output"]["elasticsearch"]["hosts"]),
+            ["es:9200"]
+        );
+        assert_eq!(
+            config["output"]["elasticsearch"]["index"].as_str(),
+            Some("logs")
+        );
+    }
+
+    #[test]
+    fn converts_conditions() {
+        let when: Yaml = serde_yaml::from_str(indoc! {r#"
+            or:
+              - equals.log.level: debug
+              - and:
+                  - regexp.message: "^GET /health"
+                  - not.has_fields: ["error"]
+        "#})
+        .unwrap();
+        assert_eq!(
+            condition(&expand(when)).unwrap(),
+            r#"(.log.level == "debug") || ((match(to_string(.message) ?? "", r'^GET /health')) && (!(exists(.error))))"#
+        );
+
+        let when: Yaml = serde_yaml::from_str("range.status: {gte: 400}").unwrap();
+        assert_eq!(
+            condition(&expand(when)).unwrap_err(),
+            "the `range` condition isn't supported"
+        );
+    }
+
+    #[test]
+    fn converts_inputs_and_processors() {
+        let conversion = convert(indoc! {r#"
+            filebeat.inputs:
+              - type: log
+                id: app
+                paths: ["/var/log/app/*.log"]
+                json.keys_under_root: true
+                multiline:
+                  pattern: '^\['
+                  negate: true
+                  match: after
+                fields:
+                  service: app
+                processors:
+                  - drop_fields:
+                      fields: ["agent"]
+              - type: container
+                paths: ["/var/log/containers/*.log"]
+            processors:
+              - drop_event.when.equals.level: debug
+              - rename:
+                  fields:
+                    - {from: "msg", to: "message"}
+            output.kafka:
+              hosts: ["kafka-1:9092", "kafka-2:9092"]
+              topic: "logs-%{[fields.service]}"
+        "#})
+        .unwrap();
+
+        let source = component(&conversion, "app");
+        assert_eq!(
+            source.options["multiline"]["mode"].as_str(),
+            Some("halt_before")
+        );
+        assert_eq!(
+            source.options["multiline"]["timeout_ms"].as_integer(),
+            Some(5000)
+        );
+
+        let processors = component(&conversion, "app_processors");
+        assert_eq!(
+            processors.options["source"].as_str(),
+            Some(indoc! {r#"
+                parsed, err = parse_json(.message)
+                if err == null {
+                  . = merge(., object(parsed) ?? {})
+                } else {
+                  .tags = push(array(.tags) ?? [], "_jsonparsefailure")
+                }
+                .fields.service = "app"
+                del(.agent)"#})
+        );
+
+        let filter = component(&conversion, "processors_filter");
+        assert_eq!(filter.options["inputs"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            filter.options["condition"].as_str(),
+            Some(r#"!(.level == "debug")"#)
+        );
+        assert_eq!(
+            component(&conversion, "processors").options["inputs"],
+            toml::Value::from(vec!["processors_filter"])
+        );
+
+        let kafka = component(&conversion, "kafka");
+        assert_eq!(
+            kafka.options["bootstrap_servers"].as_str(),
+            Some("kafka-1:9092,kafka-2:9092")
+        );
+        assert_eq!(
+            kafka.options["topic"].as_str(),
+            Some("logs-{{ fields.service }}")
+        );
+        assert_eq!(conversion.notes.len(), 1);
+    }
+}
@@ -0,0 +1,915 @@
+//! Conversion of fluentd configuration.
+//!
+//! Events are routed by fluentd according to their tag: they go through the `<filter>` directives
+//! matching their tag in order, then to the first `<match>` directive matching it. The routes
+//! are resolved at conversion time from the tag of each source, and turned into the inputs of
+//! the converted components.
+
+use regex::Regex;
+use toml::Value;
+
+use super::{
+    parse_size, vrl_dotted_path, vrl_merge_parsed, vrl_regex, vrl_string, with_scheme, Component,
+    Conversion,
+};
+
+/// A directive of a fluentd configuration, such as `<source>` or `<match app.**>`, or the root
+/// of the configuration.
+#[derive(Debug, Default)]
+struct Directive {
+    name: String,
+    arg: String,
+    params: Vec<(String, String)>,
+    children: Vec<Directive>,
+}
+
+impl Directive {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn flag(&self, key: &str) -> bool {
+        self.param(key) == Some("true")
+    }
+
+    fn plugin(&self) -> &str {
+        self.param("@type")
+            .or_else(|| self.param("type"))
+            .unwrap_or("")
+    }
+
+    fn child(&self, name: &str) -> Option<&Directive> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Directive> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The address a source listens on, from its `bind` and `port` parameters.
+    fn address(&self, default_port: u16) -> String {
+        format!(
+            "{}:{}",
+            self.param("bind").unwrap_or("0.0.0.0"),
+            self.param("port")
+                .map(str::to_owned)
+                .unwrap_or_else(|| default_port.to_string())
+        )
+    }
+}
+
+fn parse(input: &str) -> Result<Directive, Vec<String>> {
+    let mut stack = vec![Directive::default()];
+
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix("</").and_then(|l| l.strip_suffix('>')) {
+            let directive = stack
+                .pop()
+                .filter(|_| !stack.is_empty())
+                .ok_or_else(|| vec![format!("line {}: unexpected `{}`", number + 1, line)])?;
+            if directive.name != name.trim() {
+                return Err(vec![format!(
+                    "line {}: unexpected `{}`, expected `</{}>`",
+                    number + 1,
+                    line,
+                    directive.name
+                )]);
+            }
+            stack
+                .last_mut()
+                .expect("the root is never popped")
+                .children
+                .push(directive);
+        } else if let Some(inner) = line.strip_prefix('<').and_then(|l| l.strip_suffix('>')) {
+            let (name, arg) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+            stack.push(Directive {
+                name: name.to_owned(),
+                arg: arg.trim().to_owned(),
+                ..Default::default()
+            });
+        } else {
+            let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            stack
+                .last_mut()
+                .expect("the root is never popped")
+                .params
+                .push((key.to_owned(), unquote(value.trim()).to_owned()));
+        }
+    }
+
+    let root = stack.remove(0);
+    match stack.last() {
+        Some(directive) => Err(vec![format!("`<{}>` isn't closed", directive.name)]),
+        None => Ok(root),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|value| value.strip_suffix(*quote))
+        })
+        .unwrap_or(value)
+}
+
+/// Strips the slashes around a regular expression, and converts its named groups to the syntax
+/// of Rust regular expressions.
+fn ruby_regex(expression: &str) -> String {
+    let expression = expression
+        .strip_prefix('/')
+        .and_then(|expression| expression.rsplit_once('/'))
+        .map(|(expression, _flags)| expression)
+        .unwrap_or(expression);
+    expression.replace("(?<", "(?P<").replace("\\/", "/")
+}
+
+/// Turns the patterns of a `<filter>` or `<match>` directive into a regular expression matching
+/// the tags they match: `*` matches a part of a tag, `**` matches zero or more parts, and
+/// `{a,b}` matches either `a` or `b`.
+fn tag_regex(patterns: &str) -> Regex {
+    let patterns = if patterns.is_empty() { "**" } else { patterns };
+    let alternatives = patterns
+        .split_whitespace()
+        .map(|pattern| {
+            let mut regex = String::new();
+            let mut chars = pattern.chars().peekable();
+            let mut depth = 0;
+            while let Some(c) = chars.next() {
+                match c {
+                    '*' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        if chars.peek() == Some(&'.') {
+                            // `**.b` also matches `b`.
+                            chars.next();
+                            regex.push_str("(?:.*\\.)?");
+                        } else if let Some(prefix) = regex.strip_suffix("\\.") {
+                            // `a.**` also matches `a`.
+                            regex = format!("{}(?:\\..*)?", prefix);
+                        } else {
+                            regex.push_str(".*");
+                        }
+                    }
+                    '*' => regex.push_str("[^.]*"),
+                    '{' => {
+                        depth += 1;
+                        regex.push_str("(?:");
+                    }
+                    '}' if depth > 0 => {
+                        depth -= 1;
+                        regex.push(')');
+                    }
+                    ',' if depth > 0 => regex.push('|'),
+                    c => regex.push_str(&regex::escape(&c.to_string())),
+                }
+            }
+            regex
+        })
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!("^(?:{})$", alternatives)).expect("tag patterns are escaped")
+}
+
+/// The events of a source, routed through the configuration.
+struct Stream {
+    source: String,
+    /// The tag of the events, unless they are tagged by the clients sending them or by the path
+    /// of the files they're read from.
+    tag: Option<String>,
+    label: Option<String>,
+    /// The component the events come out of at this point of their route.
+    head: String,
+    /// Whether the events have been routed to a `<match>` directive.
+    matched: bool,
+}
+
+#[derive(Default)]
+struct Converter {
+    conversion: Conversion,
+    streams: Vec<Stream>,
+}
+
+pub(super) fn convert(input: &str) -> Result<Conversion, Vec<String>> {
+    let root = parse(input)?;
+    let mut converter = Converter::default();
+
+    for (key, value) in &root.params {
+        converter.conversion.note(format!(
+            "`{} {}` wasn't converted, the files it includes must be converted separately.",
+            key, value
+        ));
+    }
+    for directive in &root.children {
+        match directive.name.as_str() {
+            "source" => converter.source(directive),
+            "filter" | "match" | "label" => {}
+            "system" => converter
+                .conversion
+                .note("The `<system>` directive has no equivalent, and wasn't converted."),
+            name => converter
+                .conversion
+                .note(format!("The `<{}>` directive wasn't converted.", name)),
+        }
+    }
+
+    converter.route(&root.children, None);
+    for label in root.children("label") {
+        if label.arg == "@ERROR" {
+            converter.conversion.note(
+                "The `<label @ERROR>` directive wasn't converted, as events that fail to be processed are reported by the components themselves.",
+            );
+        } else {
+            converter.route(&label.children, Some(&label.arg));
+        }
+    }
+
+    for stream in converter.streams.iter().filter(|stream| !stream.matched) {
+        if stream.tag.is_some() {
+            converter.conversion.note(format!(
+                "The events of `{}` don't match any `<match>` directive, they are discarded.",
+                stream.source
+            ));
+        }
+    }
+
+    Ok(converter.conversion)
+}
+
+impl Converter {
+    fn source(&mut self, directive: &Directive) {
+        let plugin = directive.plugin();
+        let mut source;
+        let mut parsed_field = None;
+        match plugin {
+            "tail" => {
+                source = Component::source("file");
+                let paths = directive
+                    .param("path")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|path| path.trim().to_owned())
+                    .collect::<Vec<_>>();
+                source.set("include", paths);
+                if let Some(exclude) = directive.param("exclude_path") {
+                    match serde_json::from_str::<Vec<String>>(exclude) {
+                        Ok(exclude) => source.set("exclude", exclude),
+                        Err(_) => source
+                            .note(format!("`exclude_path {}` couldn't be converted.", exclude)),
+                    }
+                }
+                let read_from = if directive.flag("read_from_head") {
+                    "beginning"
+                } else {
+                    "end"
+                };
+                source.set("read_from", read_from);
+                if directive.param("pos_file").is_some() {
+                    source.note(
+                        "Vector keeps track of the position in the files in its data directory, rather than in `pos_file`.",
+                    );
+                }
+                parsed_field = Some("message");
+            }
+            "forward" => {
+                source = Component::source("fluent");
+                source.set("address", directive.address(24224));
+            }
+            "http" => {
+                source = Component::source("http");
+                source.set("address", directive.address(9880));
+                source.note("fluentd tags the events with the path of the request, Vector sets it in the `path` field instead.");
+            }
+            "syslog" => {
+                source = Component::source("syslog");
+                let mode = directive
+                    .child("transport")
+                    .map(Directive::plugin)
+                    .or_else(|| directive.param("protocol_type"))
+                    .unwrap_or("udp");
+                source.set("mode", mode);
+                source.set("address", directive.address(5140));
+            }
+            "tcp" | "udp" => {
+                source = Component::source("socket");
+                source.set("mode", plugin);
+                source.set(
+                    "address",
+                    directive.address(if plugin == "tcp" { 5170 } else { 5160 }),
+                );
+                parsed_field = Some("message");
+            }
+            "exec" => {
+                source = Component::source("exec");
+                let command = directive
+                    .param("command")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>();
+                source.set("command", command);
+                source.set("mode", "scheduled");
+                source.note(
+                    "Check the interval the command is run at, `run_interval` wasn't converted.",
+                );
+                parsed_field = Some("message");
+            }
+            plugin => {
+                self.conversion.note(format!(
+                    "The `{}` input plugin has no equivalent, and wasn't converted.",
+                    plugin
+                ));
+                return;
+            }
+        }
+
+        let tag = directive.param("tag").map(str::to_owned);
+        match &tag {
+            Some(tag) if tag.contains('*') => source.note(format!(
+                "fluentd tags the events with the path of the files they're read from, with `tag {}`; Vector sets the path in the `file` field instead, and the events are routed to all of the directives they could match.",
+                tag
+            )),
+            Some(_) => {}
+            None => source.note(
+                "The events are tagged by the clients sending them, so they are routed to all of the directives they could match. Use a `route` transform on their tag to route them further.",
+            ),
+        }
+
+        let name = directive.param("@id").unwrap_or(plugin).to_owned();
+        let id = self.conversion.add(&name, source);
+        let mut head = id.clone();
+
+        if let (Some(field), Some(parse)) = (parsed_field, directive.child("parse")) {
+            match parse_call(parse, &vrl_dotted_path(field)) {
+                Ok(Some(call)) => {
+                    let program = format!(
+                        "{}\ndel({})",
+                        vrl_merge_parsed(&call, None, "_parsefailure"),
+                        vrl_dotted_path(field)
+                    );
+                    head = self.conversion.add(
+                        &format!("{}_parse", name),
+                        Component::remap(&[head], &program),
+                    );
+                }
+                Ok(None) => {}
+                Err(note) => self.conversion.note(note),
+            }
+        }
+
+        self.streams.push(Stream {
+            source: id,
+            tag: tag.filter(|tag| !tag.contains('*')),
+            label: directive.param("@label").map(str::to_owned),
+            head,
+            matched: false,
+        });
+    }
+
+    /// Routes the events of the streams with the given label through the `<filter>` and
+    /// `<match>` directives.
+    fn route(&mut self, directives: &[Directive], label: Option<&str>) {
+        for directive in directives {
+            if directive.name != "filter" && directive.name != "match" {
+                continue;
+            }
+
+            let regex = tag_regex(&directive.arg);
+            let selected = self
+                .streams
+                .iter()
+                .enumerate()
+                .filter(|(_, stream)| {
+                    stream.label.as_deref() == label
+                        && !stream.matched
+                        && stream.tag.as_ref().map_or(true, |tag| regex.is_match(tag))
+                })
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            if selected.is_empty() {
+                self.conversion.note(format!(
+                    "`<{} {}>` doesn't match the events of any source, and wasn't converted.",
+                    directive.name, directive.arg
+                ));
+                continue;
+            }
+
+            let mut inputs = Vec::new();
+            for index in &selected {
+                let head = &self.streams[*index].head;
+                if !inputs.contains(head) {
+                    inputs.push(head.clone());
+                }
+            }
+
+            if directive.name == "filter" {
+                if let Some(id) = self.filter(directive, &inputs) {
+                    for index in &selected {
+                        self.streams[*index].head = id.clone();
+                    }
+                }
+            } else if directive.plugin() == "relabel" {
+                for index in &selected {
+                    self.streams[*index].label = directive.param("@label").map(str::to_owned);
+                }
+            } else {
+                self.output(directive, &inputs);
+                for index in selected {
+                    let stream = &mut self.streams[index];
+                    stream.matched = stream.tag.is_some();
+                }
+            }
+        }
+    }
+
+    /// Converts a `<filter>` directive, and returns the ID of the converted transform.
+    fn filter(&mut self, directive: &Directive, inputs: &[String]) -> Option<String> {
+        let plugin = directive.plugin();
+        let mut notes = Vec::new();
+        let mut transform = match plugin {
+            "grep" => {
+                let mut conditions = Vec::new();
+                for (section, negate) in [("regexp", false), ("exclude", true)] {
+                    for pattern in directive.children(section) {
+                        let (key, expression) =
+                            match (pattern.param("key"), pattern.param("pattern")) {
+                                (Some(key), Some(pattern)) => (key, ruby_regex(pattern)),
+                                _ => continue,
+                            };
+                        match vrl_regex(&expression) {
+                            Some(regex) => conditions.push(format!(
+                                "{}match(to_string({}) ?? \"\", {})",
+                                if negate { "!" } else { "" },
+                                vrl_dotted_path(key),
+                                regex
+                            )),
+                            None => notes
+                                .push(format!("The pattern of `{}` couldn't be converted.", key)),
+                        }
+                    }
+                }
+                if conditions.is_empty() {
+                    conditions.push("true".to_owned());
+                }
+                Component::filter(inputs, &conditions.join(" && "))
+            }
+            "record_transformer" => {
+                let mut program = Vec::new();
+                if directive.flag("enable_ruby") {
+                    notes.push(
+                        "Ruby expressions can't be converted, rewrite them in VRL.".to_owned(),
+                    );
+                }
+                for record in directive.children("record") {
+                    for (key, value) in &record.params {
+                        match template(value) {
+                            Some(value) => {
+                                program.push(format!("{} = {}", vrl_dotted_path(key), value))
+                            }
+                            None => notes.push(format!(
+                                "The value of `{}` couldn't be converted: `{}`.",
+                                key, value
+                            )),
+                        }
+                    }
+                }
+                for key in directive
+                    .param("remove_keys")
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|key| !key.trim().is_empty())
+                {
+                    program.push(format!("del({})", vrl_dotted_path(key.trim())));
+                }
+                Component::remap(inputs, &program.join("\n"))
+            }
+            "parser" => {
+                let field = vrl_dotted_path(directive.param("key_name").unwrap_or("message"));
+                let call = match directive.child("parse") {
+                    Some(parse) => parse_call(parse, &field),
+                    None => Err("The `parser` filter has no `<parse>` section.".to_owned()),
+                };
+                match call {
+                    Ok(Some(call)) => {
+                        let program = if directive.flag("reserve_data") {
+                            vrl_merge_parsed(&call, None, "_parsefailure")
+                        } else {
+                            vrl_merge_parsed(&call, Some("."), "_parsefailure")
+                        };
+                        Component::remap(inputs, &program)
+                    }
+                    Ok(None) => return None,
+                    Err(note) => {
+                        self.conversion.note(note);
+                        return None;
+                    }
+                }
+            }
+            "stdout" => {
+                self.conversion.note(
+                    "The `stdout` filter wasn't converted, use `vector tap` to observe the events instead.",
+                );
+                return None;
+            }
+            plugin => {
+                self.conversion.note(format!(
+                    "The `{}` filter plugin has no equivalent, and wasn't converted.",
+                    plugin
+                ));
+                return None;
+            }
+        };
+
+        notes.into_iter().for_each(|note| transform.note(note));
+        let name = directive.param("@id").unwrap_or(plugin);
+        Some(self.conversion.add(name, transform))
+    }
+
+    /// Converts a `<match>` directive, or each of the `<store>` sections of a `copy` output.
+    fn output(&mut self, directive: &Directive, inputs: &[String]) {
+        if directive.plugin() == "copy" {
+            for store in directive.children("store") {
+                self.output(store, inputs);
+            }
+            return;
+        }
+
+        let plugin = directive.plugin();
+        let mut sink = match plugin {
+            "elasticsearch" => {
+                let mut sink = Component::sink("elasticsearch", inputs);
+                let scheme = directive.param("scheme").unwrap_or("http");
+                let hosts = directive
+                    .param("hosts")
+                    .map(|hosts| hosts.split(',').map(str::trim).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let endpoint = match hosts.first() {
+                    Some(host) => with_scheme(host, scheme),
+                    None => format!(
+                        "{}://{}:{}",
+                        scheme,
+                        directive.param("host").unwrap_or("localhost"),
+                        directive.param("port").unwrap_or("9200")
+                    ),
+                };
+                if hosts.len() > 1 {
+                    sink.note("Only the first of the `hosts` was converted.");
+                }
+                sink.set("endpoint", endpoint);
+                let index = if directive.flag("logstash_format") {
+                    format!(
+                        "{}-%Y.%m.%d",
+                        directive.param("logstash_prefix").unwrap_or("logstash")
+                    )
+                } else {
+                    directive
+                        .param("index_name")
+                        .unwrap_or("fluentd")
+                        .to_owned()
+                };
+                sink.set("bulk.index", index);
+                if let (Some(user), Some(password)) =
+                    (directive.param("user"), directive.param("password"))
+                {
+                    sink.set("auth.strategy", "basic");
+                    sink.set("auth.user", user);
+                    sink.set("auth.password", password);
+                }
+                sink
+            }
+            "stdout" => {
+                let mut sink = Component::sink("console", inputs);
+                sink.set("encoding.codec", "json");
+                sink
+            }
+            "file" => {
+                let mut sink = Component::sink("file", inputs);
+                sink.set(
+                    "path",
+                    format!(
+                        "{}.%Y%m%d.log",
+                        directive.param("path").unwrap_or("/tmp/vector")
+                    ),
+                );
+                sink.set("encoding.codec", "json");
+                sink.note("The events are written as JSON, with the timestamp and tag as fields.");
+                sink
+            }
+            "s3" => {
+                let mut sink = Component::sink("aws_s3", inputs);
+                sink.set("bucket", directive.param("s3_bucket").unwrap_or_default());
+                if let Some(region) = directive.param("s3_region") {
+                    sink.set("region", region);
+                }
+                if let Some(path) = directive.param("path") {
+                    sink.set("key_prefix", path);
+                }
+                if let (Some(key_id), Some(secret)) = (
+                    directive.param("aws_key_id"),
+                    directive.param("aws_sec_key"),
+                ) {
+                    sink.set("auth.access_key_id", key_id);
+                    sink.set("auth.secret_access_key", secret);
+                }
+                let compression = match directive.param("store_as").unwrap_or("gzip") {
+                    "gzip" => "gzip",
+                    _ => "none",
+                };
+                sink.set("compression", compression);
+                sink.set("encoding.codec", "json");
+                sink
+            }
+            "kafka" | "kafka2" | "kafka_buffered" | "rdkafka2" => {
+                let mut sink = Component::sink("kafka", inputs);
+                sink.set(
+                    "bootstrap_servers",
+                    directive.param("brokers").unwrap_or("localhost:9092"),
+                );
+                sink.set(
+                    "topic",
+                    directive
+                        .param("default_topic")
+                        .or_else(|| directive.param("topic"))
+                        .unwrap_or_default(),
+                );
+                sink.set("encoding.codec", "json");
+                sink
+            }
+            "http" => {
+                let mut sink = Component::sink("http", inputs);
+                sink.set("uri", directive.param("endpoint").unwrap_or_default());
+                if let Some(method) = directive.param("http_method") {
+                    sink.set("method", method.to_ascii_lowercase());
+                }
+                sink.set("encoding.codec", "json");
+                sink
+            }
+            "forward" => {
+                let mut sink = Component::sink("vector", inputs);
+                let servers = directive.children("server").collect::<Vec<_>>();
+                if let Some(server) = servers.first() {
+                    sink.set(
+                        "address",
+                        format!(
+                            "{}:{}",
+                            server.param("host").unwrap_or("localhost"),
+                            server.param("port").unwrap_or("24224")
+                        ),
+                    );
+                }
+                sink.note("Vector has no sink for the forward protocol, this sink only forwards the events to another Vector instance.");
+                if servers.len() > 1 {
+                    sink.note("Only the first `<server>` was converted.");
+                }
+                sink
+            }
+            "null" => Component::sink("blackhole", inputs),
+            plugin => {
+                self.conversion.note(format!(
+                    "The `{}` output plugin has no equivalent, and `<match {}>` wasn't converted.",
+                    plugin, directive.arg
+                ));
+                return;
+            }
+        };
+
+        if let Some(buffer) = directive.child("buffer") {
+            buffer_options(buffer, &mut sink);
+        }
+        let name = directive.param("@id").unwrap_or(plugin);
+        self.conversion.add(name, sink);
+    }
+}
+
+/// Converts the `<buffer>` section of an output, file buffers becoming disk buffers.
+fn buffer_options(buffer: &Directive, sink: &mut Component) {
+    if buffer.plugin() != "file" {
+        return;
+    }
+
+    // Disk buffers can't be smaller than this.
+    const MIN_MAX_SIZE: u64 = 268435488;
+    let max_size = buffer
+        .param("total_limit_size")
+        .and_then(parse_size)
+        .unwrap_or(MIN_MAX_SIZE)
+        .max(MIN_MAX_SIZE);
+    sink.set("buffer.type", "disk");
+    sink.set("buffer.max_size", Value::Integer(max_size as i64));
+    sink.note(
+        "The file buffer was converted to a disk buffer, kept in the data directory of Vector.",
+    );
+}
+
+/// Returns the VRL call parsing a field according to a `<parse>` section, `None` if the field
+/// isn't parsed, or a note on why it can't be converted.
+fn parse_call(parse: &Directive, field: &str) -> Result<Option<String>, String> {
+    let call = match parse.plugin() {
+        "none" => return Ok(None),
+        "json" => format!("parse_json({})", field),
+        "regexp" => {
+            let expression = ruby_regex(parse.param("expression").unwrap_or_default());
+            let expression = vrl_regex(&expression)
+                .ok_or_else(|| "A `regexp` parser couldn't be converted.".to_owned())?;
+            format!("parse_regex({}, {})", field, expression)
+        }
+        "apache2" => format!("parse_apache_log({}, format: \"combined\")", field),
+        "apache_error" => format!("parse_apache_log({}, format: \"error\")", field),
+        "nginx" => format!("parse_nginx_log({}, format: \"combined\")", field),
+        "syslog" => format!("parse_syslog({})", field),
+        "logfmt" => format!("parse_logfmt({})", field),
+        "ltsv" => format!(
+            "parse_key_value({}, key_value_delimiter: \":\", field_delimiter: \"\\t\")",
+            field
+        ),
+        plugin => {
+            return Err(format!(
+                "The `{}` parser plugin has no equivalent, and wasn't converted.",
+                plugin
+            ))
+        }
+    };
+    Ok(Some(call))
+}
+
+/// Converts the value of a field set by `record_transformer`, with its `${...}` placeholders,
+/// into a VRL expression, unless it holds Ruby expressions.
+fn template(value: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        if start > 0 {
+            parts.push(vrl_string(&rest[..start]));
+        }
+        let end = rest[start..].find('}')? + start;
+        let placeholder = &rest[start + 2..end];
+        let expression = match placeholder {
+            "tag" => "to_string(.tag) ?? \"\"".to_owned(),
+            "hostname" => "get_hostname!()".to_owned(),
+            "time" => "format_timestamp!(now(), \"%+\")".to_owned(),
+            placeholder => {
+                let key = placeholder
+                    .strip_prefix("record[")?
+                    .strip_suffix(']')
+                    .map(unquote)?;
+                format!("to_string({}) ?? \"\"", vrl_dotted_path(key))
+            }
+        };
+        parts.push(if parts.is_empty() && end + 1 == rest.len() {
+            expression
+        } else {
+            format!("({})", expression)
+        });
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(vrl_string(rest));
+    }
+    Some(parts.join(" + "))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn component<'a>(conversion: &'a Conversion, id: &str) -> &'a Component {
+        &conversion
+            .components
+            .iter()
+            .find(|(component, _)| component == id)
+            .unwrap_or_else(|| panic!("{} wasn't converted", id))
+            .1
+    }
+
+    fn inputs(component: &Component) -> Vec<&str> {
+        component.options["inputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| input.as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn matches_tags() {
+        let regex = tag_regex("app.** {web,api}.*");
+        assert!(regex.is_match("app"));
+        assert!(regex.is_match("app.nginx.access"));
+        assert!(regex.is_match("web.access"));
+        assert!(!regex.is_match("web.access.error"));
+        assert!(!regex.is_match("application"));
+        assert!(tag_regex("").is_match("anything.at.all"));
+        assert!(tag_regex("**.error").is_match("error"));
+    }
+
+    #[test]
+    fn rejects_unclosed_directives() {
+        assert!(parse("<source>\n  @type tail\n").is_err());
+        assert!(parse("<source>\n</match>\n").is_err());
+    }
+
+    #[test]
+    fn converts_routes() {
+        let conversion = convert(indoc! {r#"
+            <source>
+              @type tail
+              path /var/log/nginx/access.log,/var/log/nginx/other.log
+              pos_file /var/log/td-agent/nginx.pos
+              tag nginx.access
+              <parse>
+                @type nginx
+              </parse>
+            </source>
+
+            <source>
+              @type forward
+              port 24224
+            </source>
+
+            <filter nginx.**>
+              @type record_transformer
+              <record>
+                host "${hostname}"
+                service nginx-${tag}
+              </record>
+            </filter>
+
+            <filter nginx.access>
+              @type grep
+              <exclude>
+                key path
+                pattern /^\/health/
+              </exclude>
+            </filter>
+
+            <match nginx.**>
+              @type elasticsearch
+              host es.internal
+              logstash_format true
+              <buffer>
+                @type file
+                total_limit_size 1GB
+              </buffer>
+            </match>
+
+            <match **>
+              @type null
+            </match>
+        "#})
+        .unwrap();
+
+        let source = component(&conversion, "tail");
+        assert_eq!(source.options["type"].as_str(), Some("file"));
+        assert_eq!(source.options["read_from"].as_str(), Some("end"));
+        assert_eq!(inputs(component(&conversion, "tail_parse")), ["tail"]);
+
+        let record = component(&conversion, "record_transformer");
+        assert_eq!(inputs(record), ["tail_parse", "forward"]);
+        assert_eq!(
+            record.options["source"].as_str(),
+            Some(".host = get_hostname!()\n.service = \"nginx-\" + (to_string(.tag) ?? \"\")")
+        );
+
+        let grep = component(&conversion, "grep");
+        assert_eq!(inputs(grep), ["record_transformer"]);
+        assert_eq!(
+            grep.options["condition"].as_str(),
+            Some(r#"!match(to_string(.path) ?? "", r'^/health')"#)
+        );
+
+        let elasticsearch = component(&conversion, "elasticsearch");
+        assert_eq!(inputs(elasticsearch), ["grep"]);
+        assert_eq!(
+            elasticsearch.options["endpoint"].as_str(),
+            Some("http://es.internal:9200")
+        );
+        assert_eq!(
+            elasticsearch.options["buffer"]["max_size"].as_integer(),
+            Some(1 << 30)
+        );
+
+        // The events of the forward source are tagged by the clients, so they go to both.
+        assert_eq!(inputs(component(&conversion, "null")), ["grep"]);
+    }
+
+    #[test]
+    fn converts_templates() {
+        assert_eq!(template("static").as_deref(), Some("\"static\""));
+        assert_eq!(
+            template("${record[\"user\"]}").as_deref(),
+            Some("to_string(.user) ?? \"\"")
+        );
+        assert_eq!(template("${ENV['HOME']}"), None);
+    }
+}
@@ -0,0 +1,1163 @@
+//! Conversion of Logstash pipeline configuration.
+//!
+//! All of the events of the inputs go through the filters in order, then to all of the outputs.
+//! Conditionals are converted to VRL conditions, guarding the programs of the converted filters
+//! and filtering the events going to the converted outputs.
+
+use super::{
+    joda_format, template, vrl_if, vrl_merge_parsed, vrl_path, vrl_regex, vrl_string, with_scheme,
+    Component, Conversion,
+};
+
+/// A value of a setting of a plugin.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    String(String),
+    Number(String),
+    Bareword(String),
+    Array(Vec<Value>),
+    Hash(Vec<(String, Value)>),
+    /// A plugin set as the value of a setting, such as a codec with settings of its own.
+    Plugin(Plugin),
+}
+
+impl Value {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::Number(s) | Value::Bareword(s) => Some(s),
+            Value::Plugin(plugin) => Some(&plugin.name),
+            _ => None,
+        }
+    }
+
+    /// The strings of the value, which may be a single string or an array of them.
+    fn strings(&self) -> Vec<&str> {
+        match self {
+            Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+            value => value.as_str().into_iter().collect(),
+        }
+    }
+
+    /// The pairs of the value, which may be a hash or an array of keys and values in turn.
+    fn pairs(&self) -> Vec<(&str, &Value)> {
+        match self {
+            Value::Hash(pairs) => pairs.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+            Value::Array(values) => values
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [key, value] => Some((key.as_str()?, value)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Plugin {
+    name: String,
+    settings: Vec<(String, Value)>,
+    /// The VRL condition under which the plugin applies, from the conditionals it's nested in.
+    condition: Option<String>,
+}
+
+impl Plugin {
+    fn setting(&self, key: &str) -> Option<&Value> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    fn string(&self, key: &str) -> Option<&str> {
+        self.setting(key).and_then(Value::as_str)
+    }
+
+    fn strings(&self, key: &str) -> Vec<&str> {
+        self.setting(key).map(Value::strings).unwrap_or_default()
+    }
+
+    fn pairs(&self, key: &str) -> Vec<(&str, &Value)> {
+        self.setting(key).map(Value::pairs).unwrap_or_default()
+    }
+}
+
+/// The plugins of the sections of a pipeline.
+#[derive(Debug, Default)]
+struct Pipeline {
+    inputs: Vec<Plugin>,
+    filters: Vec<Plugin>,
+    outputs: Vec<Plugin>,
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+type ParseResult<T> = Result<T, String>;
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input: input.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.input[..self.position]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count()
+            + 1;
+        format!("line {}: {}", line, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    /// Skips whitespace and comments.
+    fn skip(&mut self) {
+        while let Some(b) = self.peek() {
+            if b == b'#' {
+                while !matches!(self.peek(), None | Some(b'\n')) {
+                    self.position += 1;
+                }
+            } else if b.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip();
+        if self.input[self.position..].starts_with(token.as_bytes()) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> ParseResult<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", token)))
+        }
+    }
+
+    fn word(&mut self) -> Option<String> {
+        self.skip();
+        let start = self.position;
+        while matches!(self.peek(), Some(b) if b.is_ascii_alphanumeric() || b"_-@.".contains(&b)) {
+            self.position += 1;
+        }
+        (self.position > start)
+            .then(|| String::from_utf8_lossy(&self.input[start..self.position]).into_owned())
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        self.skip();
+        let quote = self.peek().ok_or_else(|| self.error("expected a string"))?;
+        self.position += 1;
+        let mut string = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'\\') if self.input.get(self.position + 1) == Some(&quote) => {
+                    string.push(quote);
+                    self.position += 2;
+                }
+                Some(b) if b == quote => {
+                    self.position += 1;
+                    return Ok(String::from_utf8_lossy(&string).into_owned());
+                }
+                Some(b) => {
+                    string.push(b);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    fn pipeline(&mut self) -> ParseResult<Pipeline> {
+        let mut pipeline = Pipeline::default();
+        loop {
+            self.skip();
+            if self.peek().is_none() {
+                return Ok(pipeline);
+            }
+            let section = self
+                .word()
+                .ok_or_else(|| self.error("expected `input`, `filter` or `output`"))?;
+            let plugins = match section.as_str() {
+                "input" => &mut pipeline.inputs,
+                "filter" => &mut pipeline.filters,
+                "output" => &mut pipeline.outputs,
+                section => return Err(self.error(&format!("unexpected section `{}`", section))),
+            };
+            self.expect("{")?;
+            self.plugins(plugins, None)?;
+        }
+    }
+
+    /// Parses plugins and conditionals until the end of the current block.
+    fn plugins(&mut self, plugins: &mut Vec<Plugin>, condition: Option<&str>) -> ParseResult<()> {
+        loop {
+            if self.eat("}") {
+                return Ok(());
+            }
+            let name = self
+                .word()
+                .ok_or_else(|| self.error("expected a plugin or a conditional"))?;
+            if name == "if" {
+                self.conditional(plugins, condition)?;
+            } else {
+                self.expect("{")?;
+                plugins.push(Plugin {
+                    name,
+                    settings: self.settings()?,
+                    condition: condition.map(str::to_owned),
+                });
+            }
+        }
+    }
+
+    /// Parses a conditional, once `if` has been consumed, along with its `else if` and `else`
+    /// branches, each branch applying when the previous conditions don't hold.
+    fn conditional(
+        &mut self,
+        plugins: &mut Vec<Plugin>,
+        condition: Option<&str>,
+    ) -> ParseResult<()> {
+        let mut previous = Vec::new();
+        let mut branch = Some(self.condition()?);
+        loop {
+            let mut conditions = condition.map(str::to_owned).into_iter().collect::<Vec<_>>();
+            conditions.extend(previous.iter().map(|c| format!("!({})", c)));
+            if let Some(branch) = &branch {
+                conditions.push(format!("({})", branch));
+            }
+            self.plugins(plugins, Some(&conditions.join(" && ")))?;
+
+            if !self.eat("else") {
+                return Ok(());
+            }
+            previous.extend(branch);
+            branch = if self.eat("if") {
+                Some(self.condition()?)
+            } else {
+                self.expect("{")?;
+                None
+            };
+            if branch.is_none() {
+                let mut conditions = condition.map(str::to_owned).into_iter().collect::<Vec<_>>();
+                conditions.extend(previous.iter().map(|c| format!("!({})", c)));
+                return self.plugins(plugins, Some(&conditions.join(" && ")));
+            }
+        }
+    }
+
+    /// Parses the condition of a conditional, up to its opening brace, into a VRL condition.
+    fn condition(&mut self) -> ParseResult<String> {
+        let mut condition = Vec::new();
+        loop {
+            self.skip();
+            let token = match self.peek() {
+                None => return Err(self.error("unterminated condition")),
+                Some(b'{') => {
+                    self.position += 1;
+                    return Ok(condition.join(" "));
+                }
+                Some(b'[') => {
+                    let mut segments = Vec::new();
+                    while self.peek() == Some(b'[') {
+                        self.position += 1;
+                        let start = self.position;
+                        while !matches!(self.peek(), None | Some(b']')) {
+                            self.position += 1;
+                        }
+                        segments.push(
+                            String::from_utf8_lossy(&self.input[start..self.position]).into_owned(),
+                        );
+                        self.position += 1;
+                    }
+                    Token::Field(segments)
+                }
+                Some(b'"' | b'\'') => Token::Literal(vrl_string(&self.string()?)),
+                Some(b'/') => {
+                    self.position += 1;
+                    let start = self.position;
+                    while !matches!(self.peek(), None | Some(b'/')) {
+                        if self.peek() == Some(b'\\') {
+                            self.position += 1;
+                        }
+                        self.position += 1;
+                    }
+                    let regex = String::from_utf8_lossy(&self.input[start..self.position])
+                        .replace("\\/", "/");
+                    self.position += 1;
+                    Token::Literal(
+                        vrl_regex(&regex)
+                            .ok_or_else(|| self.error("unsupported regular expression"))?,
+                    )
+                }
+                Some(b'(' | b')') => {
+                    self.position += 1;
+                    Token::Operator(char::from(self.input[self.position - 1]).to_string())
+                }
+                Some(b) if b.is_ascii_alphanumeric() || b == b'-' => {
+                    let word = self.word().expect("the word isn't empty");
+                    match word.as_str() {
+                        "and" => Token::Operator("&&".to_owned()),
+                        "or" => Token::Operator("||".to_owned()),
+                        "not" => Token::Operator("!".to_owned()),
+                        "in" => Token::Operator("in".to_owned()),
+                        "xor" | "nand" => return Err(self.error("unsupported operator")),
+                        _ => Token::Literal(word),
+                    }
+                }
+                Some(_) => {
+                    let start = self.position;
+                    while matches!(self.peek(), Some(b'=' | b'!' | b'<' | b'>' | b'~')) {
+                        self.position += 1;
+                    }
+                    if self.position == start {
+                        return Err(self.error("unexpected character in condition"));
+                    }
+                    Token::Operator(
+                        String::from_utf8_lossy(&self.input[start..self.position]).into_owned(),
+                    )
+                }
+            };
+            push_token(&mut condition, token);
+        }
+    }
+
+    /// Parses the settings of a plugin, once its opening brace has been consumed.
+    fn settings(&mut self) -> ParseResult<Vec<(String, Value)>> {
+        let mut settings = Vec::new();
+        loop {
+            if self.eat("}") {
+                return Ok(settings);
+            }
+            let key = match self.peek() {
+                Some(b'"' | b'\'') => self.string()?,
+                _ => self
+                    .word()
+                    .ok_or_else(|| self.error("expected a setting"))?,
+            };
+            self.expect("=>")?;
+            settings.push((key, self.value()?));
+            self.eat(",");
+        }
+    }
+
+    fn value(&mut self) -> ParseResult<Value> {
+        self.skip();
+        match self.peek() {
+            Some(b'"' | b'\'') => Ok(Value::String(self.string()?)),
+            Some(b'[') => {
+                self.position += 1;
+                let mut values = Vec::new();
+                loop {
+                    if self.eat("]") {
+                        return Ok(Value::Array(values));
+                    }
+                    values.push(self.value()?);
+                    self.eat(",");
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                Ok(Value::Hash(self.settings()?))
+            }
+            _ => {
+                let word = self.word().ok_or_else(|| self.error("expected a value"))?;
+                if word.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+                    Ok(Value::Number(word))
+                } else if self.eat("{") {
+                    Ok(Value::Plugin(Plugin {
+                        name: word,
+                        settings: self.settings()?,
+                        condition: None,
+                    }))
+                } else {
+                    Ok(Value::Bareword(word))
+                }
+            }
+        }
+    }
+}
+
+/// A token of a condition, being converted to VRL.
+enum Token {
+    Field(Vec<String>),
+    Literal(String),
+    Operator(String),
+}
+
+/// Pushes a token of a condition, rewriting the operators that have no VRL equivalent once their
+/// right operand is pushed.
+fn push_token(condition: &mut Vec<String>, token: Token) {
+    let operand = match token {
+        Token::Operator(operator) => {
+            condition.push(operator);
+            return;
+        }
+        Token::Field(segments) => field_path(&segments),
+        Token::Literal(literal) => literal,
+    };
+
+    let len = condition.len();
+    let rewritten = match condition.get(len.wrapping_sub(1)).map(String::as_str) {
+        Some("=~" | "!~") if len >= 2 => {
+            let negate = if condition[len - 1] == "!~" { "!" } else { "" };
+            Some(format!(
+                "{}match(to_string({}) ?? \"\", {})",
+                negate,
+                condition[len - 2],
+                operand
+            ))
+        }
+        Some("in") if len >= 2 => {
+            // `not in` is pushed as the `!` and `in` operators.
+            let negated = condition[len - 2] == "!" && len >= 3;
+            let value = if negated { len - 3 } else { len - 2 };
+            let expression = format!(
+                "{}includes(array({}) ?? [], {})",
+                if negated { "!" } else { "" },
+                operand,
+                condition[value]
+            );
+            condition.truncate(value);
+            condition.push(expression);
+            return;
+        }
+        _ => None,
+    };
+    match rewritten {
+        Some(expression) => {
+            condition.truncate(len - 2);
+            condition.push(expression);
+        }
+        None => condition.push(operand),
+    }
+}
+
+/// Returns the VRL path of a field reference, such as `[log][level]`.
+fn field_path(segments: &[String]) -> String {
+    vrl_path(segments.iter().map(String::as_str))
+}
+
+/// Returns the VRL path of a field referenced in a setting, such as `message` or `[log][level]`.
+fn setting_path(field: &str) -> String {
+    if field.starts_with('[') {
+        let segments = field
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split("][")
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        field_path(&segments)
+    } else {
+        vrl_path([field])
+    }
+}
+
+/// Converts a string with `%{field}` references into a VRL expression.
+fn sprintf(value: &str) -> String {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("%{") {
+        let end = match rest[start..].find('}') {
+            Some(end) => end + start,
+            None => break,
+        };
+        if start > 0 {
+            parts.push(vrl_string(&rest[..start]));
+        }
+        let field = &rest[start + 2..end];
+        parts.push(if let Some(format) = field.strip_prefix('+') {
+            format!(
+                "format_timestamp!(now(), {})",
+                vrl_string(&joda_format(format))
+            )
+        } else {
+            format!("(to_string({}) ?? \"\")", setting_path(field))
+        });
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(vrl_string(rest));
+    }
+    parts.join(" + ")
+}
+
+pub(super) fn convert(input: &str) -> Result<Conversion, Vec<String>> {
+    let pipeline = Parser::new(input).pipeline().map_err(|error| vec![error])?;
+    let mut conversion = Conversion::default();
+
+    let mut inputs = Vec::new();
+    for plugin in &pipeline.inputs {
+        if let Some(source) = input_component(plugin, &mut conversion) {
+            inputs.push(conversion.add(&plugin.name, source));
+        }
+    }
+
+    for plugin in &pipeline.filters {
+        if let Some(transform) = filter_component(plugin, &inputs, &mut conversion) {
+            inputs = vec![conversion.add(&plugin.name, transform)];
+        }
+    }
+
+    for plugin in &pipeline.outputs {
+        let sink_inputs = match &plugin.condition {
+            Some(condition) => {
+                let mut filter = Component::filter(&inputs, condition);
+                filter.note(format!(
+                    "Converted from the conditional around the `{}` output.",
+                    plugin.name
+                ));
+                vec![conversion.add(&format!("{}_condition", plugin.name), filter)]
+            }
+            None => inputs.clone(),
+        };
+        if let Some(sink) = output_component(plugin, &sink_inputs, &mut conversion) {
+            conversion.add(&plugin.name, sink);
+        }
+    }
+
+    Ok(conversion)
+}
+
+fn input_component(plugin: &Plugin, conversion: &mut Conversion) -> Option<Component> {
+    let address = |default_port: &str| {
+        format!(
+            "{}:{}",
+            plugin.string("host").unwrap_or("0.0.0.0"),
+            plugin.string("port").unwrap_or(default_port)
+        )
+    };
+
+    let mut source = match plugin.name.as_str() {
+        "beats" => {
+            let mut source = Component::source("logstash");
+            source.set("address", address("5044"));
+            source
+        }
+        "file" => {
+            let mut source = Component::source("file");
+            source.set("include", plugin.strings("path"));
+            let exclude = plugin.strings("exclude");
+            if !exclude.is_empty() {
+                source.set("exclude", exclude);
+            }
+            let read_from = match plugin.string("start_position") {
+                Some("beginning") => "beginning",
+                _ => "end",
+            };
+            source.set("read_from", read_from);
+            source
+        }
+        "tcp" | "udp" => {
+            let mut source = Component::source("socket");
+            source.set("mode", plugin.name.as_str());
+            source.set("address", address("5000"));
+            source
+        }
+        "syslog" => {
+            let mut source = Component::source("syslog");
+            source.set("mode", "tcp");
+            source.set("address", address("514"));
+            source.note("Logstash listens for syslog messages over both TCP and UDP, add another `syslog` source for UDP.");
+            source
+        }
+        "http" => {
+            let mut source = Component::source("http");
+            source.set("address", address("8080"));
+            source
+        }
+        "kafka" => {
+            let mut source = Component::source("kafka");
+            source.set(
+                "bootstrap_servers",
+                plugin
+                    .string("bootstrap_servers")
+                    .unwrap_or("localhost:9092"),
+            );
+            source.set("topics", plugin.strings("topics"));
+            source.set("group_id", plugin.string("group_id").unwrap_or("logstash"));
+            source
+        }
+        "stdin" => Component::source("stdin"),
+        name => {
+            conversion.note(format!(
+                "The `{}` input plugin has no equivalent, and wasn't converted.",
+                name
+            ));
+            return None;
+        }
+    };
+
+    match plugin.string("codec") {
+        Some("json" | "json_lines") if plugin.name != "beats" && plugin.name != "file" => {
+            source.set("decoding.codec", "json")
+        }
+        Some("plain" | "line") | None => {}
+        Some(codec) => source.note(format!("The `{}` codec wasn't converted.", codec)),
+    }
+    Some(source)
+}
+
+fn filter_component(
+    plugin: &Plugin,
+    inputs: &[String],
+    conversion: &mut Conversion,
+) -> Option<Component> {
+    let mut notes = Vec::new();
+    let mut program = Vec::new();
+
+    match plugin.name.as_str() {
+        "drop" => {
+            return match &plugin.condition {
+                Some(condition) => Some(Component::filter(inputs, &format!("!({})", condition))),
+                None => {
+                    conversion.note("An unconditional `drop` filter wasn't converted, as it would drop all of the events.");
+                    None
+                }
+            };
+        }
+        "grok" => {
+            for (field, patterns) in plugin.pairs("match") {
+                let patterns = patterns.strings();
+                let call = match patterns.as_slice() {
+                    [pattern] => format!(
+                        "parse_grok({}, {})",
+                        setting_path(field),
+                        vrl_string(pattern)
+                    ),
+                    patterns => format!(
+                        "parse_groks({}, patterns: [{}])",
+                        setting_path(field),
+                        patterns
+                            .iter()
+                            .map(|pattern| vrl_string(pattern))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+                program.push(vrl_merge_parsed(&call, None, "_grokparsefailure"));
+            }
+            if plugin.setting("patterns_dir").is_some()
+                || plugin.setting("pattern_definitions").is_some()
+            {
+                notes.push("Custom grok patterns weren't converted, add them with the `aliases` argument of `parse_groks`.".to_owned());
+            }
+        }
+        "json" => {
+            let call = format!(
+                "parse_json({})",
+                setting_path(plugin.string("source").unwrap_or("message"))
+            );
+            let target = plugin.string("target").map(setting_path);
+            program.push(vrl_merge_parsed(
+                &call,
+                target.as_deref(),
+                "_jsonparsefailure",
+            ));
+        }
+        "kv" => {
+            let call = format!(
+                "parse_key_value({}, key_value_delimiter: {}, field_delimiter: {})",
+                setting_path(plugin.string("source").unwrap_or("message")),
+                vrl_string(plugin.string("value_split").unwrap_or("=")),
+                vrl_string(plugin.string("field_split").unwrap_or(" "))
+            );
+            let target = plugin.string("target").map(setting_path);
+            program.push(vrl_merge_parsed(
+                &call,
+                target.as_deref(),
+                "_kv_filter_error",
+            ));
+        }
+        "date" => {
+            let matched = plugin.strings("match");
+            let target = setting_path(plugin.string("target").unwrap_or("@timestamp"));
+            if let Some((field, formats)) = matched.split_first() {
+                let field = setting_path(field);
+                let calls = formats
+                    .iter()
+                    .map(|format| match *format {
+                        "ISO8601" => {
+                            format!("parse_timestamp(to_string({}) ?? \"\", \"%+\")", field)
+                        }
+                        "UNIX" => format!("to_timestamp(to_int({}) ?? 0)", field),
+                        "UNIX_MS" => format!(
+                            "to_timestamp(to_int({}) ?? 0, unit: \"milliseconds\")",
+                            field
+                        ),
+                        format => format!(
+                            "parse_timestamp(to_string({}) ?? \"\", {})",
+                            field,
+                            vrl_string(&joda_format(format))
+                        ),
+                    })
+                    .collect::<Vec<_>>();
+                program.push(format!("{} = {} ?? {}", target, calls.join(" ?? "), target));
+                notes.push("Check the converted timestamp formats.".to_owned());
+            }
+        }
+        "mutate" => mutate(plugin, &mut program, &mut notes),
+        _ if !COMMON_SETTINGS
+            .iter()
+            .any(|setting| plugin.setting(setting).is_some()) =>
+        {
+            conversion.note(format!(
+                "The `{}` filter plugin has no equivalent, and wasn't converted.",
+                plugin.name
+            ));
+            return None;
+        }
+        name => notes.push(format!(
+            "The `{}` filter plugin has no equivalent, only its common settings were converted.",
+            name
+        )),
+    }
+
+    common_settings(plugin, &mut program, &mut notes);
+    if program.is_empty() {
+        return None;
+    }
+
+    let mut program = program.join("\n");
+    if let Some(condition) = &plugin.condition {
+        program = vrl_if(condition, &program);
+    }
+    let mut transform = Component::remap(inputs, &program);
+    notes.into_iter().for_each(|note| transform.note(note));
+    Some(transform)
+}
+
+/// The settings shared by all filter plugins, applied once they succeed.
+const COMMON_SETTINGS: [&str; 3] = ["add_field", "remove_field", "add_tag"];
+
+fn common_settings(plugin: &Plugin, program: &mut Vec<String>, notes: &mut Vec<String>) {
+    for (field, value) in plugin.pairs("add_field") {
+        if let Some(value) = value.as_str() {
+            program.push(format!("{} = {}", setting_path(field), sprintf(value)));
+        }
+    }
+    if plugin.setting("remove_tag").is_some() {
+        notes.push("`remove_tag` wasn't converted.".to_owned());
+    }
+    for field in plugin.strings("remove_field") {
+        program.push(format!("del({})", setting_path(field)));
+    }
+    for tag in plugin.strings("add_tag") {
+        program.push(format!(
+            ".tags = push(array(.tags) ?? [], {})",
+            sprintf(tag)
+        ));
+    }
+}
+
+/// The operations of `mutate`, in the order Logstash applies them whatever the order of the
+/// settings.
+const MUTATIONS: [&str; 10] = [
+    "rename",
+    "update",
+    "replace",
+    "convert",
+    "gsub",
+    "uppercase",
+    "lowercase",
+    "strip",
+    "split",
+    "copy",
+];
+
+fn mutate(plugin: &Plugin, program: &mut Vec<String>, notes: &mut Vec<String>) {
+    for (setting, _) in &plugin.settings {
+        if !MUTATIONS.contains(&setting.as_str()) && !COMMON_SETTINGS.contains(&setting.as_str()) {
+            notes.push(format!("The `{}` mutation wasn't converted.", setting));
+        }
+    }
+
+    for operation in MUTATIONS
+        .into_iter()
+        .filter(|operation| plugin.setting(operation).is_some())
+    {
+        match operation {
+            "rename" => {
+                for (from, to) in plugin.pairs("rename") {
+                    if let Some(to) = to.as_str() {
+                        program.push(format!(
+                            "{} = del({})",
+                            setting_path(to),
+                            setting_path(from)
+                        ));
+                    }
+                }
+            }
+            "replace" | "update" => {
+                for (field, value) in plugin.pairs(operation) {
+                    if let Some(value) = value.as_str() {
+                        program.push(format!("{} = {}", setting_path(field), sprintf(value)));
+                    }
+                }
+            }
+            "copy" => {
+                for (from, to) in plugin.pairs("copy") {
+                    if let Some(to) = to.as_str() {
+                        program.push(format!("{} = {}", setting_path(to), setting_path(from)));
+                    }
+                }
+            }
+            "convert" => {
+                for (field, kind) in plugin.pairs("convert") {
+                    let function = match kind.as_str() {
+                        Some("integer") => "to_int",
+                        Some("float") => "to_float",
+                        Some("string") => "to_string",
+                        Some("boolean") => "to_bool",
+                        _ => {
+                            notes.push(format!("The conversion of `{}` wasn't converted.", field));
+                            continue;
+                        }
+                    };
+                    let field = setting_path(field);
+                    program.push(format!("{} = {}({}) ?? {}", field, function, field, field));
+                }
+            }
+            "lowercase" | "uppercase" | "strip" => {
+                let function = match operation {
+                    "lowercase" => "downcase",
+                    "uppercase" => "upcase",
+                    _ => "strip_whitespace",
+                };
+                for field in plugin.strings(operation) {
+                    let field = setting_path(field);
+                    program.push(format!(
+                        "if is_string({}) {{ {} = {}(string!({})) }}",
+                        field, field, function, field
+                    ));
+                }
+            }
+            "gsub" => {
+                for substitution in plugin.strings("gsub").chunks(3) {
+                    if let [field, pattern, replacement] = substitution {
+                        match vrl_regex(pattern) {
+                            Some(pattern) => {
+                                let field = setting_path(field);
+                                program.push(format!(
+                                    "if is_string({}) {{ {} = replace(string!({}), {}, {}) }}",
+                                    field,
+                                    field,
+                                    field,
+                                    pattern,
+                                    vrl_string(replacement)
+                                ));
+                            }
+                            None => notes
+                                .push(format!("The substitution of `{}` wasn't converted.", field)),
+                        }
+                    }
+                }
+            }
+            "split" => {
+                for (field, separator) in plugin.pairs("split") {
+                    if let Some(separator) = separator.as_str() {
+                        let field = setting_path(field);
+                        program.push(format!(
+                            "if is_string({}) {{ {} = split(string!({}), {}) }}",
+                            field,
+                            field,
+                            field,
+                            vrl_string(separator)
+                        ));
+                    }
+                }
+            }
+            _ => unreachable!("all of the mutations are handled"),
+        }
+    }
+}
+
+fn output_component(
+    plugin: &Plugin,
+    inputs: &[String],
+    conversion: &mut Conversion,
+) -> Option<Component> {
+    let mut sink = match plugin.name.as_str() {
+        "elasticsearch" => {
+            let mut sink = Component::sink("elasticsearch", inputs);
+            let hosts = plugin.strings("hosts");
+            sink.set(
+                "endpoint",
+                with_scheme(hosts.first().copied().unwrap_or("localhost:9200"), "http"),
+            );
+            if hosts.len() > 1 {
+                sink.note("Only the first of the `hosts` was converted.");
+            }
+            sink.set(
+                "bulk.index",
+                template(
+                    plugin.string("index").unwrap_or("logstash-%{+yyyy.MM.dd}"),
+                    setting_path,
+                ),
+            );
+            if let (Some(user), Some(password)) = (plugin.string("user"), plugin.string("password"))
+            {
+                sink.set("auth.strategy", "basic");
+                sink.set("auth.user", user);
+                sink.set("auth.password", password);
+            }
+            if plugin.setting("document_id").is_some() {
+                sink.note("`document_id` wasn't converted, set `id_key` to the field holding the ID of the documents.");
+            }
+            sink
+        }
+        "stdout" => {
+            let mut sink = Component::sink("console", inputs);
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "file" => {
+            let mut sink = Component::sink("file", inputs);
+            sink.set(
+                "path",
+                template(plugin.string("path").unwrap_or_default(), setting_path),
+            );
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "kafka" => {
+            let mut sink = Component::sink("kafka", inputs);
+            sink.set(
+                "bootstrap_servers",
+                plugin
+                    .string("bootstrap_servers")
+                    .unwrap_or("localhost:9092"),
+            );
+            sink.set(
+                "topic",
+                template(plugin.string("topic_id").unwrap_or_default(), setting_path),
+            );
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "s3" => {
+            let mut sink = Component::sink("aws_s3", inputs);
+            sink.set("bucket", plugin.string("bucket").unwrap_or_default());
+            if let Some(region) = plugin.string("region") {
+                sink.set("region", region);
+            }
+            if let Some(prefix) = plugin.string("prefix") {
+                sink.set("key_prefix", template(prefix, setting_path));
+            }
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "http" => {
+            let mut sink = Component::sink("http", inputs);
+            sink.set("uri", plugin.string("url").unwrap_or_default());
+            if let Some(method) = plugin.string("http_method") {
+                sink.set("method", method.to_ascii_lowercase());
+            }
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "tcp" => {
+            let mut sink = Component::sink("socket", inputs);
+            sink.set("mode", "tcp");
+            sink.set(
+                "address",
+                format!(
+                    "{}:{}",
+                    plugin.string("host").unwrap_or("localhost"),
+                    plugin.string("port").unwrap_or_default()
+                ),
+            );
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        "redis" => {
+            let mut sink = Component::sink("redis", inputs);
+            let host = plugin
+                .strings("host")
+                .first()
+                .copied()
+                .unwrap_or("127.0.0.1")
+                .to_owned();
+            sink.set(
+                "url",
+                format!(
+                    "redis://{}:{}",
+                    host,
+                    plugin.string("port").unwrap_or("6379")
+                ),
+            );
+            sink.set(
+                "key",
+                template(plugin.string("key").unwrap_or_default(), setting_path),
+            );
+            sink.set("data_type", plugin.string("data_type").unwrap_or("list"));
+            sink.set("encoding.codec", "json");
+            sink
+        }
+        name => {
+            conversion.note(format!(
+                "The `{}` output plugin has no equivalent, and wasn't converted.",
+                name
+            ));
+            return None;
+        }
+    };
+
+    match plugin.string("codec") {
+        Some("json" | "json_lines" | "rubydebug") | None => {}
+        Some("plain" | "line") => sink.set("encoding.codec", "text"),
+        Some(codec) => sink.note(format!("The `{}` codec wasn't converted.", codec)),
+    }
+    Some(sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn component<'a>(conversion: &'a Conversion, id: &str) -> &'a Component {
+        &conversion
+            .components
+            .iter()
+            .find(|(component, _)| component == id)
+            .unwrap_or_else(|| panic!("{} wasn't converted", id))
+            .1
+    }
+
+    fn option<'a>(component: &'a Component, key: &str) -> &'a str {
+        component.options[key].as_str().unwrap()
+    }
+
+    #[test]
+    fn parses_pipeline() {
+        let pipeline = Parser::new(indoc! {r#"
+            # Events from Filebeat.
+            input {
+              beats { port => 5044 }
+            }
+            filter {
+              if [type] == "nginx" and "debug" not in [tags] {
+                grok { match => { "message" => "%{COMBINEDAPACHELOG}" } }
+              } else if [log][level] =~ /^warn/ {
+                mutate { add_tag => ["warning"] }
+              } else {
+                drop {}
+              }
+            }
+            output {
+              elasticsearch { hosts => ["es:9200"] codec => json { charset => "UTF-8" } }
+            }
+        "#})
+        .pipeline()
+        .unwrap();
+
+        assert_eq!(pipeline.inputs[0].string("port"), Some("5044"));
+        let conditions = pipeline
+            .filters
+            .iter()
+            .map(|plugin| plugin.condition.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        let nginx = r#".type == "nginx" && !includes(array(.tags) ?? [], "debug")"#;
+        let warning = r#"match(to_string(.log.level) ?? "", r'^warn')"#;
+        assert_eq!(
+            conditions,
+            [
+                format!("({})", nginx),
+                format!("!({}) && ({})", nginx, warning),
+                format!("!({}) && !({})", nginx, warning),
+            ]
+        );
+        assert_eq!(pipeline.outputs[0].string("codec"), Some("json"));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let error = Parser::new("input {\n  stdin {\n}\n")
+            .pipeline()
+            .unwrap_err();
+        assert_eq!(error, "line 4: expected a plugin or a conditional");
+    }
+
+    #[test]
+    fn converts_pipeline() {
+        let conversion = convert(indoc! {r#"
+            input {
+              file { path => "/var/log/app/*.log" start_position => "beginning" }
+              tcp { port => 5000 codec => json }
+            }
+            filter {
+              json { source => "message" remove_field => ["message"] }
+              mutate {
+                rename => { "[app][lvl]" => "level" }
+                lowercase => ["level"]
+              }
+              if [level] == "debug" { drop {} }
+            }
+            output {
+              if [level] == "error" {
+                kafka { topic_id => "errors-%{[app][name]}" }
+              }
+              elasticsearch { index => "app-%{+YYYY.MM.dd}" }
+            }
+        "#})
+        .unwrap();
+
+        let file = component(&conversion, "file");
+        assert_eq!(option(file, "read_from"), "beginning");
+        assert_eq!(
+            component(&conversion, "tcp").options["decoding"]["codec"].as_str(),
+            Some("json")
+        );
+
+        assert_eq!(
+            option(component(&conversion, "json"), "source"),
+            indoc! {r#"
+                parsed, err = parse_json(.message)
+                if err == null {
+                  . = merge(., object(parsed) ?? {})
+                } else {
+                  .tags = push(array(.tags) ?? [], "_jsonparsefailure")
+                }
+                del(.message)"#}
+        );
+        assert_eq!(
+            option(component(&conversion, "mutate"), "source"),
+            ".level = del(.app.lvl)\nif is_string(.level) { .level = downcase(string!(.level)) }"
+        );
+        assert_eq!(
+            option(component(&conversion, "drop"), "condition"),
+            r#"!((.level == "debug"))"#
+        );
+
+        let condition = component(&conversion, "kafka_condition");
+        assert_eq!(option(condition, "condition"), r#"(.level == "error")"#);
+        assert_eq!(
+            option(component(&conversion, "kafka"), "topic"),
+            "errors-{{ app.name }}"
+        );
+        assert_eq!(
+            component(&conversion, "elasticsearch").options["bulk"]["index"].as_str(),
+            Some("app-%Y.%m.%d")
+        );
+    }
+}
@@ -0,0 +1,608 @@
+//! Conversion of the configuration of other log shippers into Vector configuration.
+//!
+//! Each converter maps the inputs, filters and outputs of the configuration it reads onto Vector
+//! sources, transforms and sinks. Whatever can't be mapped exactly is converted on a best-effort
+//! basis, and annotated with notes rendered as comments in the converted configuration.
+
+mod filebeat;
+mod fluentd;
+mod logstash;
+
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use colored::*;
+use toml::{map::Map, Value};
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The log shipper the configuration is written for. Detected from the configuration when
+    /// omitted.
+    #[clap(long, possible_values = &["fluentd", "logstash", "filebeat"])]
+    from: Option<Tool>,
+
+    /// Write the converted configuration to a file, rather than to the standard output.
+    #[clap(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// The configuration file to convert.
+    #[clap(parse(from_os_str))]
+    input: PathBuf,
+}
+
+/// A log shipper whose configuration can be converted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    Fluentd,
+    Logstash,
+    Filebeat,
+}
+
+impl std::str::FromStr for Tool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fluentd" => Ok(Tool::Fluentd),
+            "logstash" => Ok(Tool::Logstash),
+            "filebeat" => Ok(Tool::Filebeat),
+            s => Err(format!(
+                "{} is not a valid option, expected `fluentd`, `logstash` or `filebeat`",
+                s
+            )),
+        }
+    }
+}
+
+impl Tool {
+    /// Detects the log shipper a configuration is written for: Filebeat configuration is YAML,
+    /// and fluentd configuration is made of `<source>` and `<match>` directives.
+    fn detect(path: &Path, contents: &str) -> Self {
+        let yaml = matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("yml" | "yaml")
+        );
+        if yaml {
+            Tool::Filebeat
+        } else if contents.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("<source") || line.starts_with("<match")
+        }) {
+            Tool::Fluentd
+        } else {
+            Tool::Logstash
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Tool::Fluentd => "fluentd",
+            Tool::Logstash => "Logstash",
+            Tool::Filebeat => "Filebeat",
+        }
+    }
+
+    fn convert(self, contents: &str) -> Result<Conversion, Vec<String>> {
+        match self {
+            Tool::Fluentd => fluentd::convert(contents),
+            Tool::Logstash => logstash::convert(contents),
+            Tool::Filebeat => filebeat::convert(contents),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Source,
+    Transform,
+    Sink,
+}
+
+impl Kind {
+    const fn table(self) -> &'static str {
+        match self {
+            Kind::Source => "sources",
+            Kind::Transform => "transforms",
+            Kind::Sink => "sinks",
+        }
+    }
+}
+
+/// A converted component, along with notes on what couldn't be converted exactly.
+#[derive(Debug)]
+struct Component {
+    kind: Kind,
+    options: Map<String, Value>,
+    notes: Vec<String>,
+}
+
+impl Component {
+    fn new(kind: Kind, component_type: &str) -> Self {
+        let mut options = Map::new();
+        options.insert("type".to_owned(), component_type.into());
+        Self {
+            kind,
+            options,
+            notes: Vec::new(),
+        }
+    }
+
+    fn source(component_type: &str) -> Self {
+        Self::new(Kind::Source, component_type)
+    }
+
+    fn transform(component_type: &str, inputs: &[String]) -> Self {
+        let mut transform = Self::new(Kind::Transform, component_type);
+        transform.set("inputs", inputs.to_vec());
+        transform
+    }
+
+    fn sink(component_type: &str, inputs: &[String]) -> Self {
+        let mut sink = Self::new(Kind::Sink, component_type);
+        sink.set("inputs", inputs.to_vec());
+        sink
+    }
+
+    /// A `remap` transform running the given VRL program.
+    fn remap(inputs: &[String], program: &str) -> Self {
+        let mut remap = Self::transform("remap", inputs);
+        remap.set("source", program);
+        remap
+    }
+
+    /// A `filter` transform letting through the events for which the given VRL condition holds.
+    fn filter(inputs: &[String], condition: &str) -> Self {
+        let mut filter = Self::transform("filter", inputs);
+        filter.set("condition", condition);
+        filter
+    }
+
+    /// Sets an option, whose path is made of dot-separated keys.
+    fn set(&mut self, path: &str, value: impl Into<Value>) {
+        let mut keys = path.split('.').peekable();
+        let mut table = &mut self.options;
+        while let Some(key) = keys.next() {
+            if keys.peek().is_none() {
+                table.insert(key.to_owned(), value.into());
+                return;
+            }
+            let entry = table
+                .entry(key.to_owned())
+                .or_insert_with(|| Value::Table(Map::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Map::new());
+            }
+            table = entry.as_table_mut().expect("entry is a table");
+        }
+    }
+
+    fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+}
+
+/// A converted configuration.
+#[derive(Debug, Default)]
+struct Conversion {
+    components: Vec<(String, Component)>,
+    ids: HashSet<String>,
+    /// Notes on what couldn't be converted at all.
+    notes: Vec<String>,
+}
+
+impl Conversion {
+    /// Adds a component, and returns its ID, based on `name` and unique in the configuration.
+    fn add(&mut self, name: &str, component: Component) -> String {
+        let name = sanitize_id(name);
+        let mut id = name.clone();
+        let mut suffix = 1;
+        while self.ids.contains(&id) {
+            suffix += 1;
+            id = format!("{}_{}", name, suffix);
+        }
+        self.ids.insert(id.clone());
+        self.components.push((id.clone(), component));
+        id
+    }
+
+    fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Renders the converted configuration as TOML, with the notes as comments.
+    fn render(&self, tool: Tool, input: &Path) -> Result<String, Vec<String>> {
+        let mut rendered = format!(
+            "# Converted from the {} configuration in {:?} by `vector convert`.\n\
+             # What couldn't be converted exactly is explained in notes, review them before use.\n",
+            tool.name(),
+            input
+        );
+        for note in &self.notes {
+            rendered.push_str(&format!("# NOTE: {}\n", note));
+        }
+
+        let mut errors = Vec::new();
+        for kind in [Kind::Source, Kind::Transform, Kind::Sink] {
+            for (id, component) in self.components.iter().filter(|(_, c)| c.kind == kind) {
+                let mut components = Map::new();
+                components.insert(id.clone(), Value::Table(component.options.clone()));
+                let mut table = Map::new();
+                table.insert(kind.table().to_owned(), Value::Table(components));
+
+                rendered.push('\n');
+                for note in &component.notes {
+                    rendered.push_str(&format!("# NOTE: {}\n", note));
+                }
+                match toml::to_string(&Value::Table(table)) {
+                    Ok(toml) => rendered.push_str(&toml),
+                    Err(error) => errors.push(format!("failed to marshal {}: {}", id, error)),
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(rendered)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Restricts a component ID to lowercase alphanumeric characters and underscores.
+fn sanitize_id(name: &str) -> String {
+    let id = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let id = id.trim_matches('_');
+    if id.is_empty() {
+        "component".to_owned()
+    } else {
+        id.to_owned()
+    }
+}
+
+/// Quotes a string as a VRL string literal.
+fn vrl_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes a regular expression as a VRL regex literal, unless it can't be quoted.
+fn vrl_regex(regex: &str) -> Option<String> {
+    (!regex.contains('\'')).then(|| format!("r'{}'", regex))
+}
+
+/// Returns the VRL path of a field, given the segments of its path.
+fn vrl_path<'a>(segments: impl IntoIterator<Item = &'a str>) -> String {
+    let path = segments
+        .into_iter()
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            // The timestamp of events is kept in the `timestamp` field by Vector.
+            let segment = if segment == "@timestamp" {
+                "timestamp"
+            } else {
+                segment
+            };
+            if segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                format!(".{}", segment)
+            } else {
+                format!(".{}", vrl_string(segment))
+            }
+        })
+        .collect::<String>();
+    if path.is_empty() {
+        ".".to_owned()
+    } else {
+        path
+    }
+}
+
+/// Returns the VRL path of a field whose path is made of dot-separated segments.
+fn vrl_dotted_path(field: &str) -> String {
+    vrl_path(field.split('.'))
+}
+
+/// Returns a VRL program running the given program if a condition holds.
+fn vrl_if(condition: &str, program: &str) -> String {
+    let program = program
+        .lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("if {} {{\n{}\n}}", condition, program)
+}
+
+/// Returns the VRL program merging into the event the object parsed from a field by a fallible
+/// function call, such as `parse_json(.message)`. Events that fail to be parsed are tagged,
+/// rather than left unchanged and reported by the `remap` transform.
+fn vrl_merge_parsed(call: &str, target: Option<&str>, failure_tag: &str) -> String {
+    let assignment = match target {
+        Some(target) => format!("{} = parsed", target),
+        None => ". = merge(., object(parsed) ?? {})".to_owned(),
+    };
+    format!(
+        "parsed, err = {}\nif err == null {{\n  {}\n}} else {{\n  .tags = push(array(.tags) ?? [], {})\n}}",
+        call,
+        assignment,
+        vrl_string(failure_tag)
+    )
+}
+
+/// Converts a string with `%{field}` and `%{+date}` references, as used by Logstash and Filebeat,
+/// into a Vector template, given the function returning the VRL path of the referenced fields.
+fn template(value: &str, path: impl Fn(&str) -> String) -> String {
+    let mut template = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("%{") {
+        let end = match rest[start..].find('}') {
+            Some(end) => end + start,
+            None => break,
+        };
+        template.push_str(&rest[..start]);
+        let field = &rest[start + 2..end];
+        if let Some(format) = field.strip_prefix('+') {
+            template.push_str(&joda_format(format));
+        } else {
+            template.push_str(&format!(
+                "{{{{ {} }}}}",
+                path(field).trim_start_matches('.')
+            ));
+        }
+        rest = &rest[end + 1..];
+    }
+    template.push_str(rest);
+    template
+}
+
+/// Converts a Joda-Time format, used by Logstash and Filebeat for dates, into a strftime format.
+fn joda_format(format: &str) -> String {
+    let mut converted = String::new();
+    let chars = format.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|c| *c == '\'')
+                .map_or(chars.len(), |end| end + i + 1);
+            converted.extend(&chars[i + 1..end]);
+            i = end + 1;
+            continue;
+        }
+        let count = chars[i..].iter().take_while(|other| **other == c).count();
+        let specifier = match (c, count) {
+            ('y' | 'Y', 2) => "%y",
+            ('y' | 'Y', _) => "%Y",
+            ('M', 1 | 2) => "%m",
+            ('M', 3) => "%b",
+            ('M', _) => "%B",
+            ('d', _) => "%d",
+            ('H', _) => "%H",
+            ('h', _) => "%I",
+            ('m', _) => "%M",
+            ('s', _) => "%S",
+            ('S', _) => "%3f",
+            ('a', _) => "%p",
+            ('E', 1..=3) => "%a",
+            ('E', _) => "%A",
+            ('Z', 1) => "%z",
+            ('Z', 2) => "%:z",
+            ('Z', _) => "%Z",
+            _ => {
+                converted.extend(&chars[i..i + count]);
+                i += count;
+                continue;
+            }
+        };
+        converted.push_str(specifier);
+        i += count;
+    }
+    converted
+}
+
+/// Parses a size such as `8g`, `512MB` or `1024`, in bytes.
+fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim().to_ascii_lowercase();
+    let size = size.strip_suffix('b').unwrap_or(&size);
+    let (number, multiplier) = match size.chars().last()? {
+        'k' => (&size[..size.len() - 1], 1_u64 << 10),
+        'm' => (&size[..size.len() - 1], 1 << 20),
+        'g' => (&size[..size.len() - 1], 1 << 30),
+        't' => (&size[..size.len() - 1], 1 << 40),
+        _ => (size, 1),
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|number| (number * multiplier as f64) as u64)
+}
+
+/// Prefixes an address with a scheme, unless it has one.
+fn with_scheme(address: &str, scheme: &str) -> String {
+    if address.contains("://") {
+        address.to_owned()
+    } else {
+        format!("{}://{}", scheme, address)
+    }
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let contents = match fs::read_to_string(&opts.input) {
+        Ok(contents) => contents,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!(
+                    "{}",
+                    format!("failed to read {:?}: {}", opts.input, error).red()
+                );
+            }
+            return exitcode::NOINPUT;
+        }
+    };
+
+    let tool = opts
+        .from
+        .unwrap_or_else(|| Tool::detect(&opts.input, &contents));
+    let converted = tool
+        .convert(&contents)
+        .and_then(|conversion| conversion.render(tool, &opts.input));
+    let converted = match converted {
+        Ok(converted) => converted,
+        Err(errors) => {
+            #[allow(clippy::print_stderr)]
+            {
+                errors.iter().for_each(|e| eprintln!("{}", e.red()));
+            }
+            return exitcode::CONFIG;
+        }
+    };
+
+    match &opts.output {
+        Some(output) => match write_config(output, &converted) {
+            Ok(()) => {
+                #[allow(clippy::print_stdout)]
+                {
+                    println!("Config file written to {:?}", output);
+                }
+                exitcode::OK
+            }
+            Err(error) => {
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!("{}", format!("failed to write to file: {}", error).red());
+                }
+                exitcode::CANTCREAT
+            }
+        },
+        None => {
+            #[allow(clippy::print_stdout)]
+            {
+                print!("{}", converted);
+            }
+            exitcode::OK
+        }
+    }
+}
+
+/// Writes the converted configuration, unless the file already exists.
+fn write_config(path: &Path, converted: &str) -> std::io::Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(converted.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_tool() {
+        assert_eq!(
+            Tool::detect(Path::new("filebeat.yml"), "filebeat.inputs: []"),
+            Tool::Filebeat
+        );
+        assert_eq!(
+            Tool::detect(
+                Path::new("td-agent.conf"),
+                "<source>\n  @type tail\n</source>"
+            ),
+            Tool::Fluentd
+        );
+        assert_eq!(
+            Tool::detect(Path::new("pipeline.conf"), "input { stdin {} }"),
+            Tool::Logstash
+        );
+    }
+
+    #[test]
+    fn renders_unique_ids_and_notes() {
+        let mut conversion = Conversion::default();
+        conversion.note("Unsupported plugin.");
+        let mut source = Component::source("stdin");
+        source.note("Converted from a stdin input.");
+        let first = conversion.add("in put", source);
+        let second = conversion.add("in put", Component::source("stdin"));
+        let mut sink = Component::sink("console", &[first.clone(), second.clone()]);
+        sink.set("encoding.codec", "json");
+        conversion.add("out", sink);
+
+        assert_eq!((first.as_str(), second.as_str()), ("in_put", "in_put_2"));
+
+        let rendered = conversion
+            .render(Tool::Logstash, Path::new("pipeline.conf"))
+            .unwrap();
+        assert!(rendered.contains("# NOTE: Unsupported plugin.\n"));
+        assert!(rendered.contains("# NOTE: Converted from a stdin input.\n[sources.in_put]\n"));
+
+        let config: crate::config::ConfigBuilder = toml::from_str(&rendered).unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(config.sinks.len(), 1);
+    }
+
+    #[test]
+    fn quotes_vrl() {
+        assert_eq!(vrl_string("a \"b\"\n"), r#""a \"b\"\n""#);
+        assert_eq!(vrl_path(["@timestamp"]), ".timestamp");
+        assert_eq!(vrl_dotted_path("log.file-path"), r#".log."file-path""#);
+        assert_eq!(vrl_regex("it's"), None);
+    }
+
+    #[test]
+    fn converts_templates() {
+        assert_eq!(joda_format("yyyy.MM.dd"), "%Y.%m.%d");
+        assert_eq!(
+            joda_format("dd/MMM/yyyy:HH:mm:ss Z"),
+            "%d/%b/%Y:%H:%M:%S %z"
+        );
+        assert_eq!(
+            joda_format("yyyy-MM-dd'T'HH:mm:ss.SSS"),
+            "%Y-%m-%dT%H:%M:%S.%3f"
+        );
+        assert_eq!(
+            template("logs-%{[app]}-%{+YYYY.MM}", |field| format!(".{}", field)),
+            "logs-{{ [app] }}-%Y.%m"
+        );
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("8GB"), Some(8 << 30));
+        assert_eq!(parse_size("1.5m"), Some(3 << 19));
+        assert_eq!(parse_size("lots"), None);
+    }
+}
@@ -15,6 +15,9 @@ use vector_config::configurable_component;
 // From bollard source.
 const DEFAULT_TIMEOUT: u64 = 120;
 
+#[cfg(unix)]
+const DEFAULT_DOCKER_SOCKET: &str = "/var/run/docker.sock";
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("URL has no host."))]
@@ -41,7 +44,7 @@ pub struct DockerTlsConfig {
 }
 
 pub fn docker(host: Option<String>, tls: Option<DockerTlsConfig>) -> crate::Result<Docker> {
-    let host = host.or_else(|| env::var("DOCKER_HOST").ok());
+    let host = resolve_host(host);
 
     match host {
         None => Docker::connect_with_local_defaults().map_err(Into::into),
@@ -91,6 +94,53 @@ pub fn docker(host: Option<String>, tls: Option<DockerTlsConfig>) -> crate::Resu
     }
 }
 
+/// Resolves the host to connect to, which is the given host, then `DOCKER_HOST`, and then on Unix
+/// platforms, the socket of Podman if the one of Docker doesn't exist.
+fn resolve_host(host: Option<String>) -> Option<String> {
+    host.or_else(|| env::var("DOCKER_HOST").ok())
+        .or_else(podman_socket)
+}
+
+/// Returns the path of the Unix socket the given host resolves to, if it's one.
+#[cfg(unix)]
+pub fn unix_socket_path(host: Option<String>) -> Option<PathBuf> {
+    match resolve_host(host) {
+        None => Some(PathBuf::from(DEFAULT_DOCKER_SOCKET)),
+        Some(host) => match host.split_once("://") {
+            Some(("unix", path)) => Some(PathBuf::from(path)),
+            Some(_) => None,
+            None => Some(PathBuf::from(host)),
+        },
+    }
+}
+
+/// Finds the socket of the Podman service, preferring the one of the current user as Podman is
+/// commonly run rootless.
+#[cfg(unix)]
+fn podman_socket() -> Option<String> {
+    if std::path::Path::new(DEFAULT_DOCKER_SOCKET).exists() {
+        return None;
+    }
+
+    let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| format!("/run/user/{}", nix::unistd::getuid()).into());
+    let socket = [
+        runtime_dir.join("podman/podman.sock"),
+        PathBuf::from("/run/podman/podman.sock"),
+    ]
+    .into_iter()
+    .find(|socket| socket.exists())?;
+
+    debug!(message = "Using the Podman socket.", socket = ?socket);
+    Some(format!("unix://{}", socket.display()))
+}
+
+#[cfg(not(unix))]
+const fn podman_socket() -> Option<String> {
+    None
+}
+
 // From bollard source, unfortunately they don't export this function.
 fn default_certs() -> Option<DockerTlsConfig> {
     let from_env = env::var("DOCKER_CERT_PATH").or_else(|_| env::var("DOCKER_CONFIG"));
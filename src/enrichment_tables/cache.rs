@@ -0,0 +1,42 @@
+use std::{
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+
+/// Caches the results of lookups in external stores for a fixed time, evicting the least recently
+/// used entries once full.
+pub(crate) struct Cache<K: Hash + Eq, V> {
+    entries: Mutex<LruCache<K, (Instant, V)>>,
+    ttl: Duration,
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    pub(crate) fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(max_entries)),
+            ttl,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &K, now: Instant) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let expired = match entries.get(key) {
+            Some((inserted, value)) if now.duration_since(*inserted) < self.ttl => {
+                return Some(value.clone())
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            entries.pop(key);
+        }
+        None
+    }
+
+    pub(crate) fn put(&self, key: K, value: V, now: Instant) {
+        self.entries.lock().unwrap().put(key, (now, value));
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     fs,
     hash::Hasher,
+    io,
     path::PathBuf,
     time::SystemTime,
 };
@@ -53,63 +54,6 @@ const fn default_delimiter() -> char {
 }
 
 impl FileConfig {
-    fn parse_column(
-        &self,
-        timezone: TimeZone,
-        column: &str,
-        row: usize,
-        value: &str,
-    ) -> Result<Value, String> {
-        use chrono::TimeZone;
-
-        Ok(match self.schema.get(column) {
-            Some(format) => {
-                let mut split = format.splitn(2, '|').map(|segment| segment.trim());
-
-                match (split.next(), split.next()) {
-                    (Some("date"), None) => Value::Timestamp(
-                        chrono::FixedOffset::east(0)
-                            .from_utc_datetime(
-                                &chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
-                                    .map_err(|_| {
-                                        format!(
-                                            "unable to parse date {} found in row {}",
-                                            value, row
-                                        )
-                                    })?
-                                    .and_hms(0, 0, 0),
-                            )
-                            .into(),
-                    ),
-                    (Some("date"), Some(format)) => Value::Timestamp(
-                        chrono::FixedOffset::east(0)
-                            .from_utc_datetime(
-                                &chrono::NaiveDate::parse_from_str(value, format)
-                                    .map_err(|_| {
-                                        format!(
-                                            "unable to parse date {} found in row {}",
-                                            value, row
-                                        )
-                                    })?
-                                    .and_hms(0, 0, 0),
-                            )
-                            .into(),
-                    ),
-                    _ => {
-                        let conversion =
-                            Conversion::parse(format, timezone).map_err(|err| err.to_string())?;
-                        conversion
-                            .convert(Bytes::copy_from_slice(value.as_bytes()))
-                            .map_err(|_| {
-                                format!("unable to parse {} found in row {}", value, row)
-                            })?
-                    }
-                }
-            }
-            None => value.into(),
-        })
-    }
-
     fn load_file(
         &self,
         timezone: TimeZone,
@@ -119,36 +63,13 @@ impl FileConfig {
             delimiter,
         } = self.file.encoding;
 
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(include_headers)
-            .delimiter(delimiter as u8)
-            .from_path(&self.file.path)?;
-
-        let headers = if include_headers {
-            reader
-                .headers()?
-                .iter()
-                .map(|col| col.to_string())
-                .collect::<Vec<_>>()
-        } else {
-            // If there are no headers in the datafile we make headers as the numerical index of
-            // the column.
-            match reader.records().next() {
-                Some(Ok(row)) => (0..row.len()).map(|idx| idx.to_string()).collect(),
-                _ => Vec::new(),
-            }
-        };
-
-        let data = reader
-            .records()
-            .map(|row| {
-                Ok(row?
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, col)| self.parse_column(timezone, &headers[idx], idx, col))
-                    .collect::<Result<Vec<_>, String>>()?)
-            })
-            .collect::<crate::Result<Vec<_>>>()?;
+        let (headers, data) = read_csv(
+            fs::File::open(&self.file.path)?,
+            include_headers,
+            delimiter,
+            &self.schema,
+            timezone,
+        )?;
 
         trace!(
             "Loaded enrichment file {} with headers {:?}.",
@@ -162,6 +83,98 @@ impl FileConfig {
     }
 }
 
+/// Converts a column according to its type in the schema.
+pub(crate) fn parse_column(
+    schema: &HashMap<String, String>,
+    timezone: TimeZone,
+    column: &str,
+    row: usize,
+    value: &str,
+) -> Result<Value, String> {
+    use chrono::TimeZone;
+
+    Ok(match schema.get(column) {
+        Some(format) => {
+            let mut split = format.splitn(2, '|').map(|segment| segment.trim());
+
+            match (split.next(), split.next()) {
+                (Some("date"), None) => Value::Timestamp(
+                    chrono::FixedOffset::east(0)
+                        .from_utc_datetime(
+                            &chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                                .map_err(|_| {
+                                    format!("unable to parse date {} found in row {}", value, row)
+                                })?
+                                .and_hms(0, 0, 0),
+                        )
+                        .into(),
+                ),
+                (Some("date"), Some(format)) => Value::Timestamp(
+                    chrono::FixedOffset::east(0)
+                        .from_utc_datetime(
+                            &chrono::NaiveDate::parse_from_str(value, format)
+                                .map_err(|_| {
+                                    format!("unable to parse date {} found in row {}", value, row)
+                                })?
+                                .and_hms(0, 0, 0),
+                        )
+                        .into(),
+                ),
+                _ => {
+                    let conversion =
+                        Conversion::parse(format, timezone).map_err(|err| err.to_string())?;
+                    conversion
+                        .convert(Bytes::copy_from_slice(value.as_bytes()))
+                        .map_err(|_| format!("unable to parse {} found in row {}", value, row))?
+                }
+            }
+        }
+        None => value.into(),
+    })
+}
+
+/// Reads the headers and the rows of CSV data.
+pub(crate) fn read_csv<R: io::Read>(
+    reader: R,
+    include_headers: bool,
+    delimiter: char,
+    schema: &HashMap<String, String>,
+    timezone: TimeZone,
+) -> crate::Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(include_headers)
+        .delimiter(delimiter as u8)
+        .from_reader(reader);
+
+    let headers = if include_headers {
+        reader
+            .headers()?
+            .iter()
+            .map(|col| col.to_string())
+            .collect::<Vec<_>>()
+    } else {
+        // If there are no headers in the datafile we make headers as the numerical index of
+        // the column.
+        match reader.records().next() {
+            Some(Ok(row)) => (0..row.len()).map(|idx| idx.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    let data = reader
+        .records()
+        .map(|row| {
+            Ok(row?
+                .iter()
+                .enumerate()
+                .map(|(idx, col)| parse_column(schema, timezone, &headers[idx], idx, col))
+                .collect::<Result<Vec<_>, String>>()?)
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    Ok((headers, data))
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "file")]
 impl EnrichmentTableConfig for FileConfig {
@@ -210,6 +223,11 @@ impl File {
         }
     }
 
+    /// Creates a table from data that isn't read from a file, and so never needs reloading.
+    pub(crate) fn from_data(data: Vec<Vec<Value>>, headers: Vec<String>) -> Self {
+        Self::new(FileConfig::default(), SystemTime::UNIX_EPOCH, data, headers)
+    }
+
     fn column_index(&self, col: &str) -> Option<usize> {
         self.headers.iter().position(|header| header == col)
     }
@@ -541,29 +559,36 @@ mod tests {
 
         assert_eq!(
             Ok(Value::from("zork")),
-            config.parse_column(Default::default(), "col1", 1, "zork")
+            super::parse_column(&config.schema, Default::default(), "col1", 1, "zork")
         );
 
         assert_eq!(
             Ok(Value::from(chrono::Utc.ymd(2020, 3, 5).and_hms(0, 0, 0))),
-            config.parse_column(Default::default(), "col2", 1, "2020-03-05")
+            super::parse_column(&config.schema, Default::default(), "col2", 1, "2020-03-05")
         );
 
         assert_eq!(
             Ok(Value::from(chrono::Utc.ymd(2020, 3, 5).and_hms(0, 0, 0))),
-            config.parse_column(Default::default(), "col3", 1, "03/05/2020")
+            super::parse_column(&config.schema, Default::default(), "col3", 1, "03/05/2020")
         );
 
         assert_eq!(
             Ok(Value::from(chrono::Utc.ymd(2020, 3, 5).and_hms(0, 0, 0))),
-            config.parse_column(Default::default(), "col3-spaces", 1, "03 05 2020")
+            super::parse_column(
+                &config.schema,
+                Default::default(),
+                "col3-spaces",
+                1,
+                "03 05 2020"
+            )
         );
 
         assert_eq!(
             Ok(Value::from(
                 chrono::Utc.ymd(2001, 7, 7).and_hms_micro(15, 4, 0, 26490)
             )),
-            config.parse_column(
+            super::parse_column(
+                &config.schema,
                 Default::default(),
                 "col4",
                 1,
@@ -575,7 +600,8 @@ mod tests {
             Ok(Value::from(
                 chrono::Utc.ymd(2001, 7, 7).and_hms_micro(15, 4, 0, 26490)
             )),
-            config.parse_column(
+            super::parse_column(
+                &config.schema,
                 Default::default(),
                 "col4-spaces",
                 1,
@@ -585,7 +611,7 @@ mod tests {
 
         assert_eq!(
             Ok(Value::from(42)),
-            config.parse_column(Default::default(), "col5", 1, "42")
+            super::parse_column(&config.schema, Default::default(), "col5", 1, "42")
         );
     }
 
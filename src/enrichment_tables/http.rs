@@ -0,0 +1,516 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use arc_swap::ArcSwap;
+use enrichment::{Case, Condition, IndexHandle, Table};
+use http::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderValue, Request, StatusCode, Uri,
+};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use value::Value;
+use vector_common::datetime::TimeZone;
+
+use super::file::{parse_column, read_csv, File};
+use crate::{
+    config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig},
+    http::{Auth, HttpClient},
+    tls::{TlsConfig, TlsSettings},
+};
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Encoding {
+    /// The dataset is a JSON array of objects, each object being a row.
+    Json,
+    Csv {
+        #[serde(default = "crate::serde::default_true")]
+        include_headers: bool,
+        #[serde(default = "default_delimiter")]
+        delimiter: char,
+    },
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+const fn default_delimiter() -> char {
+    ','
+}
+
+const fn default_refresh_interval_secs() -> u64 {
+    300
+}
+
+const fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    url: String,
+    #[serde(default)]
+    encoding: Encoding,
+    #[serde(default)]
+    schema: HashMap<String, String>,
+    #[serde(default = "default_refresh_interval_secs")]
+    refresh_interval_secs: u64,
+    #[serde(default = "default_request_timeout_secs")]
+    request_timeout_secs: u64,
+    auth: Option<Auth>,
+    tls: Option<TlsConfig>,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "http")]
+impl EnrichmentTableConfig for HttpConfig {
+    async fn build(
+        &self,
+        globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        if self.refresh_interval_secs == 0 {
+            return Err("`refresh_interval_secs` must be greater than zero".into());
+        }
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let mut fetcher = Fetcher {
+            config: self.clone(),
+            uri: self.url.parse::<Uri>()?,
+            client: HttpClient::new(tls, &globals.proxy)?,
+            timezone: globals.timezone,
+            etag: None,
+            last_modified: None,
+        };
+
+        // The table is only built once the dataset has been fetched, as the file table is only
+        // built once its file has been read.
+        let (headers, data) = fetcher
+            .fetch()
+            .await?
+            .ok_or("dataset was reported as not modified before being fetched")?;
+        let table = Http {
+            current: Arc::new(ArcSwap::from_pointee(File::from_data(data, headers))),
+            indexes: Default::default(),
+        };
+
+        tokio::spawn(refresh(
+            fetcher,
+            Arc::downgrade(&table.current),
+            Arc::downgrade(&table.indexes),
+            Duration::from_secs(self.refresh_interval_secs),
+        ));
+
+        Ok(Box::new(table))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<HttpConfig>("http")
+}
+
+impl GenerateConfig for HttpConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc::indoc! {r#"
+            url = "https://cmdb.example.com/api/hosts"
+            refresh_interval_secs = 300
+        "#})
+        .unwrap()
+    }
+}
+
+struct Fetcher {
+    config: HttpConfig,
+    uri: Uri,
+    client: HttpClient,
+    timezone: TimeZone,
+
+    /// The validators of the last fetched dataset, sent back so the server can answer with
+    /// `304 Not Modified` when the dataset hasn't changed.
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+type Dataset = (Vec<String>, Vec<Vec<Value>>);
+
+impl Fetcher {
+    /// Fetches the dataset, returning `None` if it hasn't changed since it was last fetched.
+    async fn fetch(&mut self) -> crate::Result<Option<Dataset>> {
+        let timeout = Duration::from_secs(self.config.request_timeout_secs);
+        tokio::time::timeout(timeout, self.request())
+            .await
+            .map_err(|_| format!("request timed out after {} seconds", timeout.as_secs()))?
+    }
+
+    async fn request(&mut self) -> crate::Result<Option<Dataset>> {
+        let mut request = Request::get(&self.uri).body(Body::empty())?;
+        let headers = request.headers_mut();
+        if let Some(etag) = &self.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+        if let Some(auth) = &self.config.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(format!("unexpected status {}", status).into());
+        }
+
+        let etag = response.headers().get(ETAG).cloned();
+        let last_modified = response.headers().get(LAST_MODIFIED).cloned();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let dataset = self.parse(&body)?;
+
+        // The validators are only kept once the dataset has been parsed, so that a dataset that
+        // failed to parse is fetched again in full.
+        self.etag = etag;
+        self.last_modified = last_modified;
+        Ok(Some(dataset))
+    }
+
+    fn parse(&self, body: &[u8]) -> crate::Result<Dataset> {
+        match self.config.encoding {
+            Encoding::Json => parse_json(body, &self.config.schema, self.timezone),
+            Encoding::Csv {
+                include_headers,
+                delimiter,
+            } => read_csv(
+                body,
+                include_headers,
+                delimiter,
+                &self.config.schema,
+                self.timezone,
+            ),
+        }
+    }
+}
+
+/// Reads a JSON array of objects. The headers are the fields of all objects, in the order they're
+/// first found, and the fields missing from an object are null.
+fn parse_json(
+    body: &[u8],
+    schema: &HashMap<String, String>,
+    timezone: TimeZone,
+) -> crate::Result<Dataset> {
+    let objects = serde_json::from_slice::<Vec<serde_json::Map<String, serde_json::Value>>>(body)?;
+
+    let mut headers = Vec::new();
+    let mut positions = HashMap::new();
+    for field in objects.iter().flat_map(|object| object.keys()) {
+        if !positions.contains_key(field) {
+            positions.insert(field.clone(), headers.len());
+            headers.push(field.clone());
+        }
+    }
+
+    let data = objects
+        .into_iter()
+        .enumerate()
+        .map(|(idx, object)| {
+            let mut row = vec![Value::Null; headers.len()];
+            for (field, value) in object {
+                row[positions[&field]] = match value {
+                    // Strings are converted according to the schema, as CSV columns are.
+                    serde_json::Value::String(value) => {
+                        parse_column(schema, timezone, &field, idx, &value)?
+                    }
+                    value => value.into(),
+                };
+            }
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok((headers, data))
+}
+
+/// Periodically fetches the dataset until the table is dropped, swapping in the new data when it
+/// has changed.
+async fn refresh(
+    mut fetcher: Fetcher,
+    current: Weak<ArcSwap<File>>,
+    indexes: Weak<Mutex<Vec<(Case, Vec<String>)>>>,
+    period: Duration,
+) {
+    let mut interval = tokio::time::interval(period);
+    // The first tick completes immediately, and the dataset has just been fetched.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let (current, indexes) = match (current.upgrade(), indexes.upgrade()) {
+            (Some(current), Some(indexes)) => (current, indexes),
+            _ => break,
+        };
+
+        match fetcher.fetch().await {
+            Ok(Some((headers, data))) => {
+                if let Err(error) = swap(&current, &indexes, headers, data) {
+                    error!(
+                        message = "Unable to index refreshed enrichment table; keeping previous data.",
+                        url = %fetcher.uri,
+                        %error,
+                        internal_log_rate_secs = 30,
+                    );
+                }
+            }
+            Ok(None) => debug!(message = "Enrichment table not modified.", url = %fetcher.uri),
+            Err(error) => error!(
+                message = "Unable to refresh enrichment table; keeping previous data.",
+                url = %fetcher.uri,
+                %error,
+                internal_log_rate_secs = 30,
+            ),
+        }
+    }
+}
+
+/// Indexes the new data as the previous data was indexed, and swaps it in.
+fn swap(
+    current: &ArcSwap<File>,
+    indexes: &Mutex<Vec<(Case, Vec<String>)>>,
+    headers: Vec<String>,
+    data: Vec<Vec<Value>>,
+) -> Result<(), String> {
+    // The lock is held until the data is swapped, so that no index is added to the previous data
+    // in the meantime.
+    let indexes = indexes.lock().unwrap();
+    let mut file = File::from_data(data, headers);
+    for (case, fields) in indexes.iter() {
+        file.add_index(
+            *case,
+            &fields.iter().map(String::as_str).collect::<Vec<_>>(),
+        )?;
+    }
+    current.store(Arc::new(file));
+    Ok(())
+}
+
+/// A table whose data is fetched over HTTP, and swapped atomically when refreshed.
+#[derive(Clone)]
+pub struct Http {
+    current: Arc<ArcSwap<File>>,
+    indexes: Arc<Mutex<Vec<(Case, Vec<String>)>>>,
+}
+
+impl Table for Http {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        self.current
+            .load()
+            .find_table_row(case, condition, select, index)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.current
+            .load()
+            .find_table_rows(case, condition, select, index)
+    }
+
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        let mut indexes = self.indexes.lock().unwrap();
+        let mut file = File::clone(&self.current.load());
+        let handle = file.add_index(case, fields)?;
+
+        // Refreshed data is indexed in the same order, so that the handles stay valid.
+        if handle.0 == indexes.len() {
+            indexes.push((case, fields.iter().map(|field| field.to_string()).collect()));
+            self.current.store(Arc::new(file));
+        }
+        Ok(handle)
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.current.load().index_fields()
+    }
+
+    /// The data is refreshed in place, so the table never needs reloading.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Http {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Http {:?}", self.current.load())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HttpConfig>();
+    }
+
+    #[test]
+    fn parses_json_datasets() {
+        let schema = HashMap::from([("since".to_string(), "date".to_string())]);
+        let (headers, data) = parse_json(
+            br#"[
+                {"host": "web-1", "team": "web", "since": "2022-03-05"},
+                {"host": "db-1", "replicas": 2}
+            ]"#,
+            &schema,
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(headers, vec!["host", "team", "since", "replicas"]);
+        assert_eq!(data[0][0], Value::from("web-1"));
+        assert!(matches!(data[0][2], Value::Timestamp(_)));
+        assert_eq!(data[0][3], Value::Null);
+        assert_eq!(data[1][1], Value::Null);
+        assert_eq!(data[1][3], Value::from(2));
+
+        assert!(parse_json(br#"{"host": "web-1"}"#, &schema, Default::default()).is_err());
+    }
+
+    fn condition(host: &str) -> Vec<Condition<'static>> {
+        vec![Condition::Equals {
+            field: "host",
+            value: host.into(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn fetches_and_revalidates_dataset() {
+        let addr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_service = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let response = if request.headers().get(IF_NONE_MATCH)
+                        == Some(&HeaderValue::from_static("\"v1\""))
+                    {
+                        Response::builder()
+                            .status(StatusCode::NOT_MODIFIED)
+                            .body(Body::empty())
+                    } else {
+                        Response::builder()
+                            .header(ETAG, "\"v1\"")
+                            .body(Body::from("host,team\nweb-1,web\ndb-1,storage\n"))
+                    };
+                    futures::future::ok::<_, hyper::Error>(response.unwrap())
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_service));
+
+        let config = toml::from_str::<HttpConfig>(&format!(
+            r#"
+            url = "http://{}/hosts.csv"
+            encoding.type = "csv"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let mut fetcher = Fetcher {
+            config: config.clone(),
+            uri: config.url.parse().unwrap(),
+            client: HttpClient::new(None, &Default::default()).unwrap(),
+            timezone: Default::default(),
+            etag: None,
+            last_modified: None,
+        };
+
+        let (headers, data) = fetcher.fetch().await.unwrap().unwrap();
+        assert_eq!(headers, vec!["host", "team"]);
+        assert_eq!(data.len(), 2);
+        assert!(fetcher.fetch().await.unwrap().is_none());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let mut table = config.build(&Default::default()).await.unwrap();
+        let handle = table.add_index(Case::Sensitive, &["host"]).unwrap();
+        assert_eq!(
+            table.find_table_row(Case::Sensitive, &condition("db-1"), None, Some(handle)),
+            Ok(BTreeMap::from([
+                ("host".to_string(), Value::from("db-1")),
+                ("team".to_string(), Value::from("storage")),
+            ]))
+        );
+    }
+
+    #[test]
+    fn swaps_indexed_data() {
+        let mut table = Http {
+            current: Arc::new(ArcSwap::from_pointee(File::from_data(
+                vec![vec!["web-1".into(), "web".into()]],
+                vec!["host".into(), "team".into()],
+            ))),
+            indexes: Default::default(),
+        };
+        let handle = table.add_index(Case::Sensitive, &["host"]).unwrap();
+        assert_eq!(table.add_index(Case::Sensitive, &["host"]), Ok(handle));
+
+        swap(
+            &table.current,
+            &table.indexes,
+            vec!["host".into(), "team".into()],
+            vec![vec!["db-1".into(), "storage".into()]],
+        )
+        .unwrap();
+        assert!(table
+            .find_table_row(Case::Sensitive, &condition("web-1"), None, Some(handle))
+            .is_err());
+        assert!(table
+            .find_table_row(Case::Sensitive, &condition("db-1"), None, Some(handle))
+            .is_ok());
+        assert_eq!(
+            table.index_fields(),
+            vec![(Case::Sensitive, vec!["host".to_string()])]
+        );
+
+        // Data that can't be indexed as the previous data was isn't swapped in.
+        assert!(swap(
+            &table.current,
+            &table.indexes,
+            vec!["name".into()],
+            vec![vec!["web-2".into()]],
+        )
+        .is_err());
+        assert!(table
+            .find_table_row(Case::Sensitive, &condition("db-1"), None, Some(handle))
+            .is_ok());
+    }
+}
@@ -1,4 +1,21 @@
 pub use enrichment::{Condition, IndexHandle, Table};
 
+#[cfg(any(
+    feature = "enrichment-tables-redis",
+    feature = "enrichment-tables-sql",
+    feature = "transforms-remap-kms",
+    feature = "transforms-remap-lookups"
+))]
+pub(crate) mod cache;
+
 #[cfg(feature = "enrichment-tables-file")]
 pub mod file;
+
+#[cfg(feature = "enrichment-tables-http")]
+pub mod http;
+
+#[cfg(feature = "enrichment-tables-redis")]
+pub mod redis;
+
+#[cfg(feature = "enrichment-tables-sql")]
+pub mod sql;
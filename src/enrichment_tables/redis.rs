@@ -0,0 +1,555 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use derivative::Derivative;
+use enrichment::{Case, Condition, IndexHandle, Table};
+use redis::{Commands, RedisError};
+use serde::{Deserialize, Serialize};
+use value::Value;
+
+use super::cache::Cache;
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+
+/// How the rows are stored in Redis.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DataTypeConfig {
+    /// Each row is a hash, whose fields are the columns of the row, read with `HGETALL`.
+    #[derivative(Default)]
+    Hash,
+    /// Each row is a string holding a JSON object, read with `GET`.
+    String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedisConfig {
+    url: String,
+    key_field: String,
+    #[serde(default)]
+    key_prefix: String,
+    #[serde(default)]
+    data_type: DataTypeConfig,
+    #[serde(default = "default_cache_ttl_secs")]
+    cache_ttl_secs: u64,
+    #[serde(default = "default_cache_max_entries")]
+    cache_max_entries: usize,
+    #[serde(default = "default_pool_size")]
+    pool_size: usize,
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_cache_max_entries() -> usize {
+    10_000
+}
+
+const fn default_pool_size() -> usize {
+    4
+}
+
+const fn default_timeout_secs() -> u64 {
+    1
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl EnrichmentTableConfig for RedisConfig {
+    async fn build(
+        &self,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        if self.pool_size == 0 {
+            return Err("`pool_size` must be greater than zero".into());
+        }
+        if self.cache_ttl_secs > 0 && self.cache_max_entries == 0 {
+            return Err("`cache_max_entries` must be greater than zero".into());
+        }
+
+        let client = redis::Client::open(self.url.as_str())?;
+
+        // Fail early if the server can't be reached, as the file table does for missing files.
+        let mut connection = client.get_async_connection().await?;
+        redis::cmd("PING")
+            .query_async::<_, ()>(&mut connection)
+            .await?;
+
+        Ok(Box::new(Redis::new(self.clone(), client)))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<RedisConfig>("redis")
+}
+
+impl GenerateConfig for RedisConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc::indoc! {r#"
+            url = "redis://127.0.0.1:6379/0"
+            key_field = "id"
+            key_prefix = "user:"
+        "#})
+        .unwrap()
+    }
+}
+
+/// A pool of blocking connections, as lookups are made synchronously from VRL.
+struct Pool {
+    client: redis::Client,
+    idle: Mutex<Vec<redis::Connection>>,
+    size: usize,
+    timeout: Duration,
+}
+
+impl Pool {
+    fn get(&self) -> Result<redis::Connection, RedisError> {
+        if let Some(connection) = self.idle.lock().unwrap().pop() {
+            return Ok(connection);
+        }
+
+        let connection = self.client.get_connection_with_timeout(self.timeout)?;
+        connection.set_read_timeout(Some(self.timeout))?;
+        connection.set_write_timeout(Some(self.timeout))?;
+        Ok(connection)
+    }
+
+    /// Returns a connection to the pool, unless the pool is already full.
+    fn put(&self, connection: redis::Connection) {
+        let mut idle = self.idle.lock().unwrap();
+        if connection.is_open() && idle.len() < self.size {
+            idle.push(connection);
+        }
+    }
+}
+
+type Row = BTreeMap<String, Value>;
+
+#[derive(Clone)]
+pub struct Redis {
+    config: RedisConfig,
+    pool: Arc<Pool>,
+    /// Caches the rows, and the keys without rows, for `cache_ttl_secs`.
+    cache: Option<Arc<Cache<String, Option<Row>>>>,
+    indexes: Vec<(Case, Vec<String>)>,
+}
+
+impl Redis {
+    fn new(config: RedisConfig, client: redis::Client) -> Self {
+        let pool = Arc::new(Pool {
+            client,
+            idle: Mutex::new(Vec::with_capacity(config.pool_size)),
+            size: config.pool_size,
+            timeout: Duration::from_secs(config.timeout_secs),
+        });
+        let cache = (config.cache_ttl_secs > 0).then(|| {
+            Arc::new(Cache::new(
+                config.cache_max_entries,
+                Duration::from_secs(config.cache_ttl_secs),
+            ))
+        });
+        Self {
+            config,
+            pool,
+            cache,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Returns the value the key field must be equal to, which is the key of the row.
+    fn key<'a>(&self, case: Case, condition: &'a [Condition<'a>]) -> Result<String, String> {
+        if case == Case::Insensitive {
+            return Err("redis enrichment tables don't support case insensitive searches".into());
+        }
+
+        condition
+            .iter()
+            .find_map(|condition| match condition {
+                Condition::Equals { field, value } if *field == self.config.key_field => Some(
+                    format!("{}{}", self.config.key_prefix, value.to_string_lossy()),
+                ),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                format!(
+                    "redis enrichment tables must be searched by field '{}'",
+                    self.config.key_field
+                )
+            })
+    }
+
+    fn lookup(&self, key: String) -> Result<Option<Row>, String> {
+        let now = Instant::now();
+        if let Some(row) = self.cache.as_ref().and_then(|cache| cache.get(&key, now)) {
+            return Ok(row);
+        }
+
+        let row = self.fetch(&key)?;
+        if let Some(cache) = &self.cache {
+            cache.put(key, row.clone(), now);
+        }
+        Ok(row)
+    }
+
+    fn fetch(&self, key: &str) -> Result<Option<Row>, String> {
+        let mut connection = self
+            .pool
+            .get()
+            .map_err(|error| format!("unable to connect to redis: {}", error))?;
+
+        let row = match self.config.data_type {
+            DataTypeConfig::Hash => connection
+                .hgetall::<_, HashMap<String, Vec<u8>>>(key)
+                .map(parse_hash),
+            DataTypeConfig::String => connection
+                .get::<_, Option<Vec<u8>>>(key)
+                .map(|value| value.map(|value| parse_json(&value)).transpose()),
+        }
+        .map_err(|error| format!("unable to read key '{}' from redis: {}", key, error))?;
+
+        // Connections are only reused after successful commands, as failed ones may have been
+        // left in an unknown state.
+        self.pool.put(connection);
+        row
+    }
+
+    fn add_key_field(&self, condition: &[Condition], mut row: Row) -> Row {
+        for condition in condition {
+            if let Condition::Equals { field, value } = condition {
+                if *field == self.config.key_field {
+                    row.entry(field.to_string())
+                        .or_insert_with(|| value.clone());
+                }
+            }
+        }
+        row
+    }
+}
+
+fn parse_hash(fields: HashMap<String, Vec<u8>>) -> Result<Option<Row>, String> {
+    // Redis doesn't keep empty hashes, so an empty reply means the key doesn't exist.
+    Ok((!fields.is_empty()).then(|| {
+        fields
+            .into_iter()
+            .map(|(field, value)| (field, Value::from(Bytes::from(value))))
+            .collect()
+    }))
+}
+
+fn parse_json(value: &[u8]) -> Result<Row, String> {
+    match serde_json::from_slice::<serde_json::Value>(value)
+        .map_err(|error| format!("unable to parse row as JSON: {}", error))?
+    {
+        serde_json::Value::Object(object) => Ok(object
+            .into_iter()
+            .map(|(field, value)| (field, value.into()))
+            .collect()),
+        _ => Err("row is not a JSON object".into()),
+    }
+}
+
+/// Does the row match all the conditions specified?
+fn row_matches(condition: &[Condition], row: &Row) -> bool {
+    condition.iter().all(|condition| match condition {
+        Condition::Equals { field, value } => row.get(*field) == Some(value),
+        Condition::BetweenDates { field, from, to } => match row.get(*field) {
+            Some(Value::Timestamp(date)) => from <= date && date <= to,
+            _ => false,
+        },
+    })
+}
+
+fn select_columns(select: Option<&[String]>, row: Row) -> Row {
+    match select {
+        Some(select) => row
+            .into_iter()
+            .filter(|(field, _)| select.contains(field))
+            .collect(),
+        None => row,
+    }
+}
+
+impl Table for Redis {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        // Keys are unique, so there is never more than one row.
+        self.find_table_rows(case, condition, select, index)?
+            .pop()
+            .ok_or_else(|| "no rows found".to_string())
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let key = self.key(case, condition)?;
+        Ok(self
+            .lookup(key)?
+            .map(|row| self.add_key_field(condition, row))
+            .filter(|row| row_matches(condition, row))
+            .map(|row| select_columns(select, row))
+            .into_iter()
+            .collect())
+    }
+
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        if case == Case::Insensitive {
+            return Err("redis enrichment tables don't support case insensitive searches".into());
+        }
+        if !fields.contains(&self.config.key_field.as_str()) {
+            return Err(format!(
+                "redis enrichment tables must be searched by field '{}'",
+                self.config.key_field
+            ));
+        }
+
+        // Rows are always looked up by their key, so indexes are only recorded, to be reapplied
+        // when the table is reloaded.
+        let fields = fields.iter().map(|field| field.to_string()).collect();
+        let index = (case, fields);
+        match self.indexes.iter().position(|existing| *existing == index) {
+            Some(pos) => Ok(IndexHandle(pos)),
+            None => {
+                self.indexes.push(index);
+                Ok(IndexHandle(self.indexes.len() - 1))
+            }
+        }
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes.clone()
+    }
+
+    /// The data is read from Redis on each lookup, so it never needs reloading.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Redis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Redis {} {} index(es)",
+            self.config.key_field,
+            self.indexes.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisConfig>();
+    }
+
+    fn table() -> Redis {
+        let config = toml::from_str::<RedisConfig>(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key_field = "id"
+            key_prefix = "user:"
+            "#,
+        )
+        .unwrap();
+        let client = redis::Client::open(config.url.as_str()).unwrap();
+        Redis::new(config, client)
+    }
+
+    fn condition(value: &str) -> Vec<Condition<'static>> {
+        vec![Condition::Equals {
+            field: "id",
+            value: value.into(),
+        }]
+    }
+
+    #[test]
+    fn builds_key_from_key_field() {
+        let table = table();
+        assert_eq!(
+            table.key(Case::Sensitive, &condition("42")),
+            Ok("user:42".to_string())
+        );
+        assert!(table
+            .key(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "name",
+                    value: "bob".into(),
+                }]
+            )
+            .is_err());
+        assert!(table.key(Case::Insensitive, &condition("42")).is_err());
+    }
+
+    #[test]
+    fn serves_rows_from_cache() {
+        let table = table();
+        let row = BTreeMap::from([("name".to_string(), Value::from("bob"))]);
+        table
+            .cache
+            .as_ref()
+            .unwrap()
+            .put("user:42".into(), Some(row), Instant::now());
+
+        // No server is needed, as the row is cached.
+        assert_eq!(
+            table.find_table_row(Case::Sensitive, &condition("42"), None, None),
+            Ok(BTreeMap::from([
+                ("id".to_string(), Value::from("42")),
+                ("name".to_string(), Value::from("bob")),
+            ]))
+        );
+        assert_eq!(
+            table.find_table_row(
+                Case::Sensitive,
+                &condition("42"),
+                Some(&["name".to_string()]),
+                None
+            ),
+            Ok(BTreeMap::from([("name".to_string(), Value::from("bob"))]))
+        );
+    }
+
+    #[test]
+    fn caches_missing_rows() {
+        let table = table();
+        table
+            .cache
+            .as_ref()
+            .unwrap()
+            .put("user:43".into(), None, Instant::now());
+
+        assert_eq!(
+            table.find_table_rows(Case::Sensitive, &condition("43"), None, None),
+            Ok(vec![])
+        );
+        assert!(table
+            .find_table_row(Case::Sensitive, &condition("43"), None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn expires_cached_rows() {
+        let table = table();
+        let cache = table.cache.as_ref().unwrap();
+        let inserted = Instant::now();
+        cache.put("user:42".into(), None, inserted);
+
+        assert_eq!(cache.get(&"user:42".into(), inserted), Some(None));
+        assert_eq!(
+            cache.get(&"user:42".into(), inserted + Duration::from_secs(60)),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_json_rows() {
+        assert_eq!(
+            parse_json(br#"{"name": "bob", "age": 42}"#),
+            Ok(BTreeMap::from([
+                ("age".to_string(), Value::from(42)),
+                ("name".to_string(), Value::from("bob")),
+            ]))
+        );
+        assert!(parse_json(b"[1, 2]").is_err());
+    }
+
+    #[test]
+    fn only_indexes_key_field() {
+        let mut table = table();
+        assert_eq!(
+            table.add_index(Case::Sensitive, &["id"]),
+            Ok(IndexHandle(0))
+        );
+        assert_eq!(
+            table.add_index(Case::Sensitive, &["id"]),
+            Ok(IndexHandle(0))
+        );
+        assert!(table.add_index(Case::Sensitive, &["name"]).is_err());
+        assert!(table.add_index(Case::Insensitive, &["id"]).is_err());
+        assert_eq!(
+            table.index_fields(),
+            vec![(Case::Sensitive, vec!["id".to_string()])]
+        );
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+
+    fn redis_server() -> String {
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379/0".to_owned())
+    }
+
+    #[tokio::test]
+    async fn looks_up_hashes_and_strings() {
+        let client = redis::Client::open(redis_server()).unwrap();
+        let mut connection = client.get_async_connection().await.unwrap();
+        redis::cmd("HSET")
+            .arg("enrichment:hash:1")
+            .arg("name")
+            .arg("bob")
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .unwrap();
+        redis::cmd("SET")
+            .arg("enrichment:string:1")
+            .arg(r#"{"name": "alice"}"#)
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .unwrap();
+
+        for (data_type, name) in [("hash", "bob"), ("string", "alice")] {
+            let config = toml::from_str::<RedisConfig>(&format!(
+                r#"
+                url = "{}"
+                key_field = "id"
+                key_prefix = "enrichment:{}:"
+                data_type = "{}"
+                "#,
+                redis_server(),
+                data_type,
+                data_type
+            ))
+            .unwrap();
+            let table = config.build(&Default::default()).await.unwrap();
+
+            let row = table
+                .find_table_row(Case::Sensitive, &condition("1"), None, None)
+                .unwrap();
+            assert_eq!(row.get("name"), Some(&Value::from(name)));
+            assert!(table
+                .find_table_row(Case::Sensitive, &condition("2"), None, None)
+                .is_err());
+        }
+    }
+
+    fn condition(value: &str) -> Vec<Condition<'static>> {
+        vec![Condition::Equals {
+            field: "id",
+            value: value.into(),
+        }]
+    }
+}
@@ -7,7 +7,7 @@ use goauth::{
     credentials::Credentials,
     GoErr,
 };
-use http::{uri::PathAndQuery, Uri};
+use http::Uri;
 use hyper::header::AUTHORIZATION;
 use once_cell::sync::Lazy;
 use smpl_jwt::Jwt;
@@ -157,11 +157,13 @@ impl GcpAuthenticator {
             Self::Credentials(_) | Self::None => (),
             Self::ApiKey(api_key) => {
                 let mut parts = uri.clone().into_parts();
-                let path = parts
-                    .path_and_query
-                    .as_ref()
-                    .map_or("/", PathAndQuery::path);
-                let paq = format!("{path}?key={api_key}");
+                let paq = match parts.path_and_query.as_ref() {
+                    Some(paq) => match paq.query() {
+                        Some(query) => format!("{}?{query}&key={api_key}", paq.path()),
+                        None => format!("{}?key={api_key}", paq.path()),
+                    },
+                    None => format!("/?key={api_key}"),
+                };
                 // The API key is verified above to only contain
                 // URL-safe characters. That key is added to a path
                 // that came from a successfully parsed URI. As such,
@@ -321,6 +323,10 @@ mod tests {
             apply_uri(&auth, "http://example.com/path1/"),
             format!("http://example.com/path1/?key={key}")
         );
+        assert_eq!(
+            apply_uri(&auth, "http://example.com/path?filter=a"),
+            format!("http://example.com/path?filter=a&key={key}")
+        );
     }
 
     #[tokio::test]
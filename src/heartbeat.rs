@@ -2,14 +2,15 @@ use std::time::{Duration, Instant};
 
 use tokio::time::interval;
 
-use crate::internal_events::Heartbeat;
+use crate::internal_events::{Heartbeat, MemoryBudgetUsage};
 
-/// Emits Heartbeat event every second.
+/// Emits Heartbeat event every second, along with the memory reserved against the memory budget.
 pub async fn heartbeat() {
     let since = Instant::now();
     let mut interval = interval(Duration::from_secs(1));
     loop {
         interval.tick().await;
         emit!(Heartbeat { since });
+        emit!(MemoryBudgetUsage);
     }
 }
@@ -149,11 +149,30 @@ pub fn build_proxy_connector(
 
 pub fn build_tls_connector(
     tls_settings: MaybeTlsSettings,
+) -> Result<HttpsConnector<HttpConnector>, HttpError> {
+    build_tls_connector_with_alpn(tls_settings, None)
+}
+
+/// Builds a TLS connector negotiating HTTP/2 through ALPN, falling back to HTTP/1.1 when the
+/// server doesn't support it.
+pub fn build_http2_tls_connector(
+    tls_settings: MaybeTlsSettings,
+) -> Result<HttpsConnector<HttpConnector>, HttpError> {
+    build_tls_connector_with_alpn(tls_settings, Some(b"\x02h2\x08http/1.1"))
+}
+
+fn build_tls_connector_with_alpn(
+    tls_settings: MaybeTlsSettings,
+    alpn_protocols: Option<&[u8]>,
 ) -> Result<HttpsConnector<HttpConnector>, HttpError> {
     let mut http = HttpConnector::new();
     http.enforce_http(false);
 
-    let tls = tls_connector_builder(&tls_settings).context(BuildTlsConnectorSnafu)?;
+    let mut tls = tls_connector_builder(&tls_settings).context(BuildTlsConnectorSnafu)?;
+    if let Some(alpn_protocols) = alpn_protocols {
+        tls.set_alpn_protos(alpn_protocols)
+            .context(MakeHttpsConnectorSnafu)?;
+    }
     let mut https = HttpsConnector::with_connector(http, tls).context(MakeHttpsConnectorSnafu)?;
 
     let settings = tls_settings.tls().cloned();
@@ -0,0 +1,12 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AggregateLogsGroupEvicted;
+
+impl InternalEvent for AggregateLogsGroupEvicted {
+    fn emit(self) {
+        debug!(message = "Maximum number of groups reached; flushing oldest group early.");
+        counter!("groups_evicted_total", 1);
+    }
+}
@@ -0,0 +1,20 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AnomalyDetected<'a> {
+    pub name: &'a str,
+    pub score: f64,
+}
+
+impl<'a> InternalEvent for AnomalyDetected<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Detected anomalous metric value.",
+            name = %self.name,
+            score = %self.score,
+            internal_log_rate_secs = 10,
+        );
+        counter!("anomalies_detected_total", 1);
+    }
+}
@@ -64,3 +64,115 @@ impl InternalEvent for AwsCloudwatchLogsEncoderError {
         );
     }
 }
+
+#[cfg(feature = "sources-aws_cloudwatch_logs")]
+mod source {
+    use aws_sdk_cloudwatchlogs::{error::FilterLogEventsError, types::SdkError};
+    use metrics::counter;
+    use vector_core::internal_event::InternalEvent;
+
+    use super::{error_stage, error_type};
+
+    #[derive(Debug)]
+    pub struct AwsCloudwatchLogsFilterError<'a> {
+        pub log_group: &'a str,
+        pub error: &'a SdkError<FilterLogEventsError>,
+    }
+
+    impl<'a> InternalEvent for AwsCloudwatchLogsFilterError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to filter log events.",
+                log_group = %self.log_group,
+                error = %self.error,
+                error_code = "failed_filtering_log_events",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_filtering_log_events",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AwsCloudwatchLogsKinesisError<'a> {
+        pub stream_name: &'a str,
+        pub error: &'a crate::Error,
+    }
+
+    impl<'a> InternalEvent for AwsCloudwatchLogsKinesisError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to read Kinesis stream.",
+                stream_name = %self.stream_name,
+                error = %self.error,
+                error_code = "failed_reading_stream",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_reading_stream",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AwsCloudwatchLogsSubscriptionDecodeError {
+        pub error: std::io::Error,
+    }
+
+    impl InternalEvent for AwsCloudwatchLogsSubscriptionDecodeError {
+        fn emit(self) {
+            error!(
+                message = "Failed to decode subscription filter record.",
+                error = %self.error,
+                error_code = "failed_decoding_record",
+                error_type = error_type::PARSER_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_decoding_record",
+                "error_type" => error_type::PARSER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AwsCloudwatchLogsCheckpointError {
+        pub error: crate::Error,
+    }
+
+    impl InternalEvent for AwsCloudwatchLogsCheckpointError {
+        fn emit(self) {
+            error!(
+                message = "Failed to write checkpoint.",
+                error = %self.error,
+                error_code = "writing_checkpoint",
+                error_type = error_type::WRITER_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "writing_checkpoint",
+                "error_type" => error_type::WRITER_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
+}
+
+#[cfg(feature = "sources-aws_cloudwatch_logs")]
+pub use self::source::*;
@@ -0,0 +1,82 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsListShardsError<'a> {
+    pub stream_name: &'a str,
+    pub error: &'a crate::Error,
+}
+
+impl<'a> InternalEvent for AwsKinesisStreamsListShardsError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to list shards.",
+            stream_name = %self.stream_name,
+            error = %self.error,
+            error_code = "failed_listing_shards",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_listing_shards",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsShardReadError<'a> {
+    pub stream_name: &'a str,
+    pub shard_id: &'a str,
+    pub error: &'a crate::Error,
+}
+
+impl<'a> InternalEvent for AwsKinesisStreamsShardReadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read shard.",
+            stream_name = %self.stream_name,
+            shard_id = %self.shard_id,
+            error = %self.error,
+            error_code = "failed_reading_shard",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_reading_shard",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsCheckpointError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AwsKinesisStreamsCheckpointError {
+    fn emit(self) {
+        error!(
+            message = "Failed to save checkpoint.",
+            error = %self.error,
+            error_code = "failed_saving_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_saving_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -143,6 +143,80 @@ mod s3 {
             counter!("sqs_message_delete_batch_failed_total", 1);
         }
     }
+
+    #[derive(Debug)]
+    pub struct S3ListObjectsError<'a, E> {
+        pub bucket: &'a str,
+        pub error: &'a E,
+    }
+
+    impl<'a, E: std::fmt::Display> InternalEvent for S3ListObjectsError<'a, E> {
+        fn emit(self) {
+            error!(
+                message = "Failed to list S3 objects.",
+                bucket = %self.bucket,
+                error = %self.error,
+                error_code = "failed_listing_s3_objects",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_listing_s3_objects",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3ObjectProcessingError<'a> {
+        pub error: &'a ProcessingError,
+    }
+
+    impl<'a> InternalEvent for S3ObjectProcessingError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to process S3 object.",
+                error = %self.error,
+                error_code = "failed_processing_s3_object",
+                error_type = error_type::READER_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_processing_s3_object",
+                "error_type" => error_type::READER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3CheckpointError<'a> {
+        pub error: &'a crate::Error,
+    }
+
+    impl<'a> InternalEvent for S3CheckpointError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to save checkpoint of processed S3 objects.",
+                error = %self.error,
+                error_code = "failed_saving_checkpoint",
+                error_type = error_type::WRITER_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_saving_checkpoint",
+                "error_type" => error_type::WRITER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,43 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreakerOpened {
+    pub failures: u32,
+    pub fallback: &'static str,
+}
+
+impl InternalEvent for CircuitBreakerOpened {
+    fn emit(self) {
+        warn!(
+            message = "Sink failed to deliver events; circuit breaker opened.",
+            failures = %self.failures,
+            fallback = %self.fallback,
+        );
+        counter!(
+            "component_circuit_breaker_trips_total", 1,
+            "fallback" => self.fallback,
+        );
+        gauge!("component_circuit_breaker_open", 1.0);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreakerProbing;
+
+impl InternalEvent for CircuitBreakerProbing {
+    fn emit(self) {
+        debug!(message = "Probing whether the sink recovered.");
+        counter!("component_circuit_breaker_probes_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreakerClosed;
+
+impl InternalEvent for CircuitBreakerClosed {
+    fn emit(self) {
+        info!(message = "Sink recovered; circuit breaker closed.");
+        gauge!("component_circuit_breaker_open", 0.0);
+    }
+}
@@ -0,0 +1,30 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct ComponentPaused;
+
+impl InternalEvent for ComponentPaused {
+    fn emit(self) {
+        info!(
+            message = "Component paused through the API; waiting until it's resumed.",
+            internal_log_rate_secs = 10,
+        );
+        counter!("component_pauses_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ComponentEventsThrottled {
+    pub count: usize,
+}
+
+impl InternalEvent for ComponentEventsThrottled {
+    fn emit(self) {
+        trace!(
+            message = "Component rate limit set through the API exceeded; delaying events.",
+            count = %self.count,
+        );
+        counter!("component_throttled_events_total", self.count as u64);
+    }
+}
@@ -0,0 +1,23 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct SinkEventsDeadLettered {
+    pub count: usize,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for SinkEventsDeadLettered {
+    fn emit(self) {
+        debug!(
+            message = "Events failed to be delivered; routing them to the dead letter component.",
+            count = %self.count,
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_dead_lettered_events_total", self.count as u64,
+            "reason" => self.reason,
+        );
+    }
+}
@@ -4,6 +4,9 @@ use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
 use super::prelude::{error_stage, error_type};
+use crate::sources::docker_logs::journald::JournalError;
+#[cfg(unix)]
+use crate::sources::docker_logs::podman::PodmanError;
 
 #[derive(Debug)]
 pub struct DockerLogsEventsReceived<'a> {
@@ -188,3 +191,55 @@ impl<'a> InternalEvent for DockerLogsLoggingDriverUnsupportedError<'a> {
         counter!("logging_driver_errors_total", 1);
     }
 }
+
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct DockerLogsPodMetadataFetchError<'a> {
+    pub error: PodmanError,
+    pub container_id: &'a str,
+}
+
+#[cfg(unix)]
+impl<'a> InternalEvent for DockerLogsPodMetadataFetchError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to fetch pod metadata.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            container_id = ?self.container_id,
+            internal_log_rate_secs = 10
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "container_id" => self.container_id.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct DockerLogsJournalReadError<'a> {
+    pub error: JournalError,
+    pub container_id: &'a str,
+}
+
+impl<'a> InternalEvent for DockerLogsJournalReadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read container logs from the journal.",
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            container_id = ?self.container_id,
+            internal_log_rate_secs = 10
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "container_id" => self.container_id.to_owned(),
+        );
+    }
+}
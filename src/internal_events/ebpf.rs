@@ -0,0 +1,79 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct EbpfEventsLost {
+    pub count: usize,
+}
+
+impl InternalEvent for EbpfEventsLost {
+    fn emit(self) {
+        error!(
+            message = "Kernel events were lost, as the perf buffers were full.",
+            count = %self.count,
+            error_code = "events_lost",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "events_lost",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+        counter!(
+            "component_discarded_events_total", self.count as u64,
+            "error_code" => "events_lost",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EbpfReadError<E> {
+    pub error: E,
+    pub cpu: u32,
+}
+
+impl<E: std::fmt::Display> InternalEvent for EbpfReadError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read kernel events, no longer reading events of this CPU.",
+            cpu = %self.cpu,
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EbpfParseError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for EbpfParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse kernel event.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
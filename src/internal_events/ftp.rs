@@ -0,0 +1,131 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::{
+    internal_events::prelude::{error_stage, error_type},
+    sources::ftp::ProcessingError,
+};
+
+#[derive(Debug)]
+pub struct FtpConnectError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for FtpConnectError {
+    fn emit(self) {
+        error!(
+            message = "Failed to connect to the server.",
+            error = %self.error,
+            error_code = "failed_connecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_connecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct FtpListError<'a> {
+    pub dir: &'a str,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for FtpListError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to list files.",
+            dir = %self.dir,
+            error = %self.error,
+            error_code = "failed_listing_files",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_listing_files",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct FtpFileProcessingError<'a> {
+    pub error: &'a ProcessingError,
+}
+
+impl<'a> InternalEvent for FtpFileProcessingError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to process file.",
+            error = %self.error,
+            error_code = "failed_processing_file",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_processing_file",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct FtpAfterDeliveryError<'a> {
+    pub path: &'a str,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for FtpAfterDeliveryError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to delete or move delivered file.",
+            path = %self.path,
+            error = %self.error,
+            error_code = "failed_cleaning_up_file",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_cleaning_up_file",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct FtpCheckpointError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for FtpCheckpointError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
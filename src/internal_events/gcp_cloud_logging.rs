@@ -0,0 +1,34 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::internal_events::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct GcpCloudLoggingLogEntryDecodeError {
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for GcpCloudLoggingLogEntryDecodeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode log entry.",
+            error = %self.error,
+            error_code = "failed_decoding_log_entry",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_decoding_log_entry",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "failed_decoding_log_entry",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::{
+    internal_events::prelude::{error_stage, error_type},
+    sources::gcp_cloud_monitoring::QueryError,
+};
+
+#[derive(Debug)]
+pub struct GcpCloudMonitoringQueryError<'a> {
+    pub filter: &'a str,
+    pub error: QueryError,
+}
+
+impl<'a> InternalEvent for GcpCloudMonitoringQueryError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to query time series.",
+            filter = %self.filter,
+            error = %self.error,
+            error_code = "failed_querying_time_series",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_querying_time_series",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::{
+    internal_events::prelude::{error_stage, error_type},
+    sources::gcp_cloud_storage::ProcessingError,
+};
+
+#[derive(Debug)]
+pub struct GcsNotificationPullError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for GcsNotificationPullError {
+    fn emit(self) {
+        error!(
+            message = "Failed to pull notifications.",
+            error = %self.error,
+            error_code = "failed_pulling_notifications",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_pulling_notifications",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct GcsObjectProcessingError<'a> {
+    pub error: &'a ProcessingError,
+}
+
+impl<'a> InternalEvent for GcsObjectProcessingError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to process notification.",
+            error = %self.error,
+            error_code = "failed_processing_notification",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_processing_notification",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct GcsAcknowledgeError {
+    pub count: usize,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for GcsAcknowledgeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge notifications.",
+            count = %self.count,
+            error = %self.error,
+            error_code = "failed_acknowledging_notifications",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_acknowledging_notifications",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct GcsNotificationIgnored<'a> {
+    pub bucket: &'a str,
+    pub object: &'a str,
+    pub event_type: &'a str,
+}
+
+impl<'a> InternalEvent for GcsNotificationIgnored<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Ignored notification for an event other than OBJECT_FINALIZE.",
+            bucket = %self.bucket,
+            object = %self.object,
+            event_type = %self.event_type,
+        );
+        counter!("gcs_notification_ignored_total", 1, "event_type" => self.event_type.to_owned());
+    }
+}
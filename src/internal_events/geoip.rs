@@ -35,3 +35,39 @@ impl<'a> InternalEvent for GeoipIpAddressParseError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct GeoipDatabaseReloaded<'a> {
+    pub path: &'a str,
+}
+
+impl<'a> InternalEvent for GeoipDatabaseReloaded<'a> {
+    fn emit(self) {
+        info!(message = "Reloaded GeoIP database.", path = %self.path);
+        counter!("geoip_database_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GeoipDatabaseReloadError<'a, E> {
+    pub path: &'a str,
+    pub error: E,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for GeoipDatabaseReloadError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to reload GeoIP database; keeping the previous version.",
+            path = %self.path,
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
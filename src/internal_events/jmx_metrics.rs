@@ -0,0 +1,80 @@
+use std::fmt::Display;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct JmxMetricsEventsReceived<'a> {
+    pub byte_size: usize,
+    pub count: usize,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for JmxMetricsEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            byte_size = %self.byte_size,
+            count = %self.count,
+            endpoint = self.endpoint,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+pub struct JmxMetricsRequestError<'a> {
+    pub error: crate::Error,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for JmxMetricsRequestError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Jolokia request error.",
+            endpoint = %self.endpoint,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+pub struct JmxMetricsParseError<'a, E> {
+    pub error: E,
+    pub endpoint: &'a str,
+}
+
+impl<'a, E: Display> InternalEvent for JmxMetricsParseError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse Jolokia response.",
+            endpoint = %self.endpoint,
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
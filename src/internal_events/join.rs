@@ -0,0 +1,17 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct JoinUnmatchedEventDiscarded {
+    pub side: &'static str,
+}
+
+impl InternalEvent for JoinUnmatchedEventDiscarded {
+    fn emit(self) {
+        trace!(message = "Event wasn't matched within its window; discarding.", side = %self.side);
+        counter!(
+            "events_discarded_total", 1,
+            "side" => self.side,
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct JsonSchemaValidationFailed<'a> {
+    pub error: &'a str,
+    pub rerouted: bool,
+}
+
+impl<'a> InternalEvent for JsonSchemaValidationFailed<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Event failed JSON Schema validation.",
+            error = %self.error,
+            error_code = "validation_failed",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            rerouted = %self.rerouted,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "validation_failed",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
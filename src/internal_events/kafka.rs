@@ -199,3 +199,55 @@ impl InternalEvent for KafkaNegativeAcknowledgmentError<'_> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaSchemaRegistryDecodeError<'a, E> {
+    pub error: E,
+    pub topic: &'a str,
+    pub partition: i32,
+    pub offset: i64,
+}
+
+impl<E: std::fmt::Display> InternalEvent for KafkaSchemaRegistryDecodeError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode message with the schema registry, message dropped.",
+            error = %self.error,
+            error_code = "decoding_avro",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            topic = self.topic,
+            partition = self.partition,
+            offset = self.offset,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "decoding_avro",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "decoding_avro",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaRebalanceDrainTimeout {
+    pub partitions: usize,
+    pub timeout: std::time::Duration,
+}
+
+impl InternalEvent for KafkaRebalanceDrainTimeout {
+    fn emit(self) {
+        warn!(
+            message = "Timed out waiting for the events of revoked partitions to be acknowledged, their messages may be consumed again.",
+            partitions = self.partitions,
+            timeout_ms = self.timeout.as_millis() as u64,
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct KubernetesEventsWatchError<'a, E> {
+    pub error: E,
+    pub namespace: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for KubernetesEventsWatchError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to watch Kubernetes events.",
+            error = %self.error,
+            namespace = %self.namespace,
+            error_code = "watch_failed",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "watch_failed",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesEventsCheckpointError<'a> {
+    pub error: crate::Error,
+    pub namespace: &'a str,
+}
+
+impl<'a> InternalEvent for KubernetesEventsCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            namespace = %self.namespace,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct KubernetesMetadataLookupFailed<'a> {
+    pub pod_uid: Option<&'a str>,
+    pub pod_ip: Option<&'a str>,
+}
+
+impl InternalEvent for KubernetesMetadataLookupFailed<'_> {
+    fn emit(self) {
+        debug!(
+            message = "No pod found for event.",
+            pod_uid = ?self.pod_uid,
+            pod_ip = ?self.pod_ip,
+            internal_log_rate_secs = 10,
+        );
+        counter!("kubernetes_metadata_lookup_failures_total", 1);
+    }
+}
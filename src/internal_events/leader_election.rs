@@ -0,0 +1,56 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct LeaderElectionAcquired<'a> {
+    pub lock: &'a str,
+}
+
+impl InternalEvent for LeaderElectionAcquired<'_> {
+    fn emit(self) {
+        info!(message = "Elected leader; starting source.", lock = %self.lock);
+        counter!("leader_elections_won_total", 1);
+        gauge!("leader", 1.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct LeaderElectionLost<'a> {
+    pub lock: &'a str,
+}
+
+impl InternalEvent for LeaderElectionLost<'_> {
+    fn emit(self) {
+        warn!(message = "Lost leadership; stopping source.", lock = %self.lock);
+        counter!("leader_elections_lost_total", 1);
+        gauge!("leader", 0.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct LeaderElectionError<'a> {
+    pub error: crate::Error,
+    pub lock: &'a str,
+}
+
+impl InternalEvent for LeaderElectionError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to take or renew the leader lock.",
+            error = %self.error,
+            lock = %self.lock,
+            error_code = "leader_election",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "leader_election",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
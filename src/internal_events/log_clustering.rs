@@ -0,0 +1,17 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct LogPatternCreated<'a> {
+    pub pattern_id: &'a str,
+}
+
+impl<'a> InternalEvent for LogPatternCreated<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Created log pattern.",
+            pattern_id = %self.pattern_id,
+        );
+        counter!("log_patterns_created_total", 1);
+    }
+}
@@ -0,0 +1,51 @@
+use metrics::{counter, gauge};
+use vector_common::memory_budget::{self, MemoryUse};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct MemoryBudgetUsage;
+
+impl InternalEvent for MemoryBudgetUsage {
+    fn emit(self) {
+        for usage in MemoryUse::ALL {
+            gauge!(
+                "memory_budget_used_bytes",
+                memory_budget::used_bytes_for(usage) as f64,
+                "usage" => usage.as_str(),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MemoryBudgetEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for MemoryBudgetEventsDropped {
+    fn emit(self) {
+        warn!(
+            message = "Memory budget running low; dropping low priority events.",
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+        counter!(
+            "memory_budget_shed_events_total", self.count as u64,
+            "policy" => "drop_low_priority",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MemoryBudgetSourcePaused;
+
+impl InternalEvent for MemoryBudgetSourcePaused {
+    fn emit(self) {
+        warn!(
+            message = "Memory budget exhausted; pausing source until memory is released.",
+            internal_log_rate_secs = 10,
+        );
+        counter!("memory_budget_source_pauses_total", 1);
+    }
+}
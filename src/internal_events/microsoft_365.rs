@@ -0,0 +1,52 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct Microsoft365RequestError<'a, E> {
+    pub error: E,
+    pub content_type: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for Microsoft365RequestError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to consume the content blobs of a content type.",
+            error = %self.error,
+            content_type = %self.content_type,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct Microsoft365CheckpointError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for Microsoft365CheckpointError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -2,13 +2,20 @@ pub mod prelude;
 
 mod adaptive_concurrency;
 mod aggregate;
+#[cfg(feature = "transforms-aggregate_logs")]
+mod aggregate_logs;
+#[cfg(feature = "transforms-anomaly_detection")]
+mod anomaly_detection;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
 #[cfg(feature = "aws-core")]
 mod aws;
-#[cfg(feature = "sinks-aws_cloudwatch_logs")]
+#[cfg(any(
+    feature = "sources-aws_cloudwatch_logs",
+    feature = "sinks-aws_cloudwatch_logs"
+))]
 mod aws_cloudwatch_logs;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 mod aws_ec2_metadata;
@@ -16,17 +23,22 @@ mod aws_ec2_metadata;
 mod aws_ecs_metrics;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
 mod aws_kinesis_firehose;
+#[cfg(feature = "sources-aws_kinesis_streams")]
+mod aws_kinesis_streams;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 mod aws_sqs;
 #[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
 pub(crate) mod azure_blob;
 mod batch;
+mod circuit_breaker;
 mod common;
+mod component_control;
 mod conditions;
 #[cfg(feature = "sinks-datadog_metrics")]
 mod datadog_metrics;
 #[cfg(feature = "sinks-datadog_traces")]
 mod datadog_traces;
+mod dead_letter;
 mod decoder;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
@@ -36,6 +48,8 @@ mod demo_logs;
 mod dnstap;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+mod ebpf;
 mod elasticsearch;
 mod encoding_transcode;
 #[cfg(feature = "sources-eventstoredb_metrics")]
@@ -46,6 +60,14 @@ mod exec;
 mod filter;
 #[cfg(feature = "sources-fluent")]
 mod fluent;
+#[cfg(feature = "sources-ftp")]
+mod ftp;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+mod gcp_cloud_logging;
+#[cfg(feature = "sources-gcp_cloud_monitoring")]
+mod gcp_cloud_monitoring;
+#[cfg(feature = "sources-gcp_cloud_storage")]
+mod gcp_cloud_storage;
 #[cfg(feature = "sources-gcp_pubsub")]
 mod gcp_pubsub;
 #[cfg(feature = "transforms-geoip")]
@@ -55,12 +77,26 @@ mod http;
 pub mod http_client;
 #[cfg(feature = "sources-internal_logs")]
 mod internal_logs;
+#[cfg(feature = "sources-jmx_metrics")]
+mod jmx_metrics;
+#[cfg(feature = "transforms-join")]
+mod join;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
+#[cfg(feature = "transforms-json_schema")]
+mod json_schema;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
 mod kafka;
+#[cfg(feature = "sources-kubernetes_events")]
+mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes_logs")]
 mod kubernetes_logs;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+mod kubernetes_metadata;
+#[cfg(feature = "leader-election")]
+mod leader_election;
+#[cfg(feature = "transforms-log_clustering")]
+mod log_clustering;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 mod logplex;
@@ -68,38 +104,79 @@ mod logplex;
 mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+mod memory_budget;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
+#[cfg(feature = "sources-microsoft_365")]
+mod microsoft_365;
+#[cfg(feature = "sources-mongodb_change_stream")]
+mod mongodb_change_stream;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
+#[cfg(feature = "sources-mqtt")]
+mod mqtt;
 #[cfg(feature = "sinks-nats")]
 mod nats;
+#[cfg(feature = "sources-nats")]
+mod nats_source;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
+#[cfg(feature = "sources-okta")]
+mod okta;
 mod open;
+#[cfg(feature = "sinks-pagerduty")]
+mod pagerduty;
 #[cfg(any(
     feature = "sinks-datadog_events",
     feature = "sources-kubernetes_logs",
     feature = "transforms-geoip",
+    feature = "transforms-log_clustering",
     feature = "transforms-log_to_metric",
+    feature = "transforms-protobuf",
 ))]
 mod parser;
+mod partitioned_buffer;
+#[cfg(feature = "sources-postgresql_cdc")]
+mod postgresql_cdc;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
+#[cfg(feature = "transforms-protobuf")]
+mod protobuf;
+#[cfg(feature = "sources-rabbitmq_stream")]
+mod rabbitmq_stream;
+mod rate_limit_group;
+#[cfg(feature = "transforms-redact_pii")]
+mod redact_pii;
 #[cfg(any(feature = "sources-redis", feature = "sinks-redis"))]
 mod redis;
 #[cfg(feature = "transforms-reduce")]
 mod reduce;
 mod remap;
+mod retries;
+#[cfg(feature = "transforms-rollup")]
+mod rollup;
 mod sample;
+mod schema_registry;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sources-sflow")]
+mod sflow;
+#[cfg(feature = "sources-snmp_metrics")]
+mod snmp_metrics;
+#[cfg(feature = "sources-snmp_trap")]
+mod snmp_trap;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(feature = "transforms-sql")]
+mod sql;
+#[cfg(feature = "sources-sql_query")]
+mod sql_query;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
@@ -108,14 +185,21 @@ mod statsd_source;
 mod syslog;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+mod tail_sampling;
 mod tcp;
+#[cfg(feature = "sources-opentelemetry")]
+mod telemetry_export;
 mod template;
+mod tenant;
 #[cfg(feature = "transforms-throttle")]
 mod throttle;
 mod udp;
 mod unix;
 mod vector;
-#[cfg(feature = "sinks-websocket")]
+#[cfg(feature = "transforms-wasm")]
+mod wasm;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 mod websocket;
 
 #[cfg(any(
@@ -126,18 +210,27 @@ mod websocket;
 mod file;
 mod windows;
 
+#[cfg(feature = "sources-mongodb_change_stream")]
+pub(crate) use mongodb_change_stream::*;
 #[cfg(feature = "sources-mongodb_metrics")]
 pub(crate) use mongodb_metrics::*;
 
 #[cfg(feature = "transforms-aggregate")]
 pub(crate) use self::aggregate::*;
+#[cfg(feature = "transforms-aggregate_logs")]
+pub(crate) use self::aggregate_logs::*;
+#[cfg(feature = "transforms-anomaly_detection")]
+pub(crate) use self::anomaly_detection::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub(crate) use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub(crate) use self::api::*;
 #[cfg(feature = "aws-core")]
 pub(crate) use self::aws::*;
-#[cfg(feature = "sinks-aws_cloudwatch_logs")]
+#[cfg(any(
+    feature = "sources-aws_cloudwatch_logs",
+    feature = "sinks-aws_cloudwatch_logs"
+))]
 pub(crate) use self::aws_cloudwatch_logs::*;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub(crate) use self::aws_ec2_metadata::*;
@@ -145,6 +238,8 @@ pub(crate) use self::aws_ec2_metadata::*;
 pub(crate) use self::aws_ecs_metrics::*;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
 pub(crate) use self::aws_kinesis_firehose::*;
+#[cfg(feature = "sources-aws_kinesis_streams")]
+pub(crate) use self::aws_kinesis_streams::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
 #[cfg(feature = "sinks-datadog_metrics")]
@@ -160,6 +255,8 @@ pub(crate) use self::demo_logs::*;
 pub(crate) use self::dnstap::*;
 #[cfg(feature = "sources-docker_logs")]
 pub(crate) use self::docker_logs::*;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub(crate) use self::ebpf::*;
 #[cfg(feature = "sinks-elasticsearch")]
 pub(crate) use self::elasticsearch::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
@@ -176,6 +273,14 @@ pub(crate) use self::file::*;
 pub(crate) use self::filter::*;
 #[cfg(feature = "sources-fluent")]
 pub(crate) use self::fluent::*;
+#[cfg(feature = "sources-ftp")]
+pub(crate) use self::ftp::*;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+pub(crate) use self::gcp_cloud_logging::*;
+#[cfg(feature = "sources-gcp_cloud_monitoring")]
+pub(crate) use self::gcp_cloud_monitoring::*;
+#[cfg(feature = "sources-gcp_cloud_storage")]
+pub(crate) use self::gcp_cloud_storage::*;
 #[cfg(feature = "sources-gcp_pubsub")]
 pub(crate) use self::gcp_pubsub::*;
 #[cfg(feature = "transforms-geoip")]
@@ -189,12 +294,26 @@ pub(crate) use self::geoip::*;
 pub(crate) use self::http::*;
 #[cfg(feature = "sources-internal_logs")]
 pub(crate) use self::internal_logs::*;
+#[cfg(feature = "sources-jmx_metrics")]
+pub(crate) use self::jmx_metrics::*;
+#[cfg(feature = "transforms-join")]
+pub(crate) use self::join::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
+#[cfg(feature = "transforms-json_schema")]
+pub(crate) use self::json_schema::*;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
 pub(crate) use self::kafka::*;
+#[cfg(feature = "sources-kubernetes_events")]
+pub(crate) use self::kubernetes_events::*;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub(crate) use self::kubernetes_logs::*;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+pub(crate) use self::kubernetes_metadata::*;
+#[cfg(feature = "leader-election")]
+pub(crate) use self::leader_election::*;
+#[cfg(feature = "transforms-log_clustering")]
+pub(crate) use self::log_clustering::*;
 #[cfg(feature = "transforms-log_to_metric")]
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "sources-heroku_logs")]
@@ -205,33 +324,68 @@ pub(crate) use self::loki::*;
 pub(crate) use self::lua::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
+#[cfg(feature = "sources-microsoft_365")]
+pub(crate) use self::microsoft_365::*;
+#[cfg(feature = "sources-mqtt")]
+pub(crate) use self::mqtt::*;
 #[cfg(feature = "sinks-nats")]
 pub(crate) use self::nats::*;
+#[cfg(feature = "sources-nats")]
+pub(crate) use self::nats_source::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
+#[cfg(feature = "sources-okta")]
+pub(crate) use self::okta::*;
+#[cfg(feature = "sinks-pagerduty")]
+pub(crate) use self::pagerduty::*;
 #[cfg(any(
     feature = "sinks-datadog_events",
     feature = "sources-kubernetes_logs",
     feature = "transforms-geoip",
+    feature = "transforms-log_clustering",
     feature = "transforms-log_to_metric",
+    feature = "transforms-protobuf",
 ))]
 pub(crate) use self::parser::*;
+#[cfg(feature = "sources-postgresql_cdc")]
+pub(crate) use self::postgresql_cdc::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
+#[cfg(feature = "transforms-protobuf")]
+pub(crate) use self::protobuf::*;
+#[cfg(feature = "sources-rabbitmq_stream")]
+pub(crate) use self::rabbitmq_stream::*;
+#[cfg(feature = "transforms-redact_pii")]
+pub(crate) use self::redact_pii::*;
 #[cfg(any(feature = "sources-redis", feature = "sinks-redis"))]
 pub(crate) use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-remap")]
 pub(crate) use self::remap::*;
+#[cfg(feature = "transforms-rollup")]
+pub(crate) use self::rollup::*;
 #[cfg(feature = "transforms-sample")]
 pub(crate) use self::sample::*;
+pub(crate) use self::schema_registry::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(feature = "sources-sflow")]
+pub(crate) use self::sflow::*;
+#[cfg(feature = "sources-snmp_metrics")]
+pub(crate) use self::snmp_metrics::*;
+#[cfg(feature = "sources-snmp_trap")]
+pub(crate) use self::snmp_trap::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(feature = "transforms-sql")]
+pub(crate) use self::sql::*;
+#[cfg(feature = "sources-sql_query")]
+pub(crate) use self::sql_query::*;
 #[cfg(feature = "sinks-statsd")]
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -240,6 +394,10 @@ pub(crate) use self::statsd_source::*;
 pub(crate) use self::syslog::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
+#[cfg(feature = "transforms-tail_sampling")]
+pub(crate) use self::tail_sampling::*;
+#[cfg(feature = "sources-opentelemetry")]
+pub(crate) use self::telemetry_export::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
 #[cfg(all(
@@ -257,13 +415,17 @@ pub(crate) use self::throttle::*;
 pub(crate) use self::unix::*;
 #[cfg(feature = "sources-vector")]
 pub(crate) use self::vector::*;
-#[cfg(feature = "sinks-websocket")]
+#[cfg(feature = "transforms-wasm")]
+pub(crate) use self::wasm::*;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 pub(crate) use self::websocket::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
 pub(crate) use self::{
-    adaptive_concurrency::*, batch::*, common::*, conditions::*, encoding_transcode::*,
-    heartbeat::*, open::*, process::*, socket::*, tcp::*, template::*, udp::*,
+    adaptive_concurrency::*, batch::*, circuit_breaker::*, common::*, component_control::*,
+    conditions::*, dead_letter::*, encoding_transcode::*, heartbeat::*, memory_budget::*, open::*,
+    partitioned_buffer::*, process::*, rate_limit_group::*, retries::*, socket::*, tcp::*,
+    template::*, tenant::*, udp::*,
 };
 
 // this version won't be needed once all `InternalEvent`s implement `name()`
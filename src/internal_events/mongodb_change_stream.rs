@@ -0,0 +1,71 @@
+use metrics::counter;
+use mongodb::error::Error as MongoError;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct MongoDbChangeStreamError<'a, E> {
+    pub error: E,
+    pub namespace: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for MongoDbChangeStreamError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read change stream.",
+            error = %self.error,
+            namespace = %self.namespace,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MongoDbChangeStreamHistoryLost<'a> {
+    pub error: &'a MongoError,
+    pub namespace: &'a str,
+}
+
+impl<'a> InternalEvent for MongoDbChangeStreamHistoryLost<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Resume token is no longer in the oplog, resuming from the current position. Changes in between are lost.",
+            error = %self.error,
+            namespace = %self.namespace,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MongoDbChangeStreamCheckpointError<'a> {
+    pub error: crate::Error,
+    pub namespace: &'a str,
+}
+
+impl<'a> InternalEvent for MongoDbChangeStreamCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            namespace = %self.namespace,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct MqttConnectionError<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for MqttConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "MQTT connection failed, reconnecting.",
+            error = %self.error,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttSubscribeError<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for MqttSubscribeError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to subscribe to topics.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttAcknowledgementError<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for MqttAcknowledgementError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge message.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttNegativeAcknowledgementError<'a> {
+    pub topic: &'a str,
+}
+
+impl InternalEvent for MqttNegativeAcknowledgementError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Event received a negative acknowledgment, message left unacknowledged.",
+            error_code = "negative_acknowledgement",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            topic = self.topic,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "negative_acknowledgment",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
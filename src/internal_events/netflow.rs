@@ -0,0 +1,47 @@
+use std::{fmt::Display, net::SocketAddr};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct NetflowParseError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: Display> InternalEvent for NetflowParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode flow message.",
+            peer = %self.peer,
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowTemplateMissing {
+    pub template_id: u16,
+    pub peer: SocketAddr,
+}
+
+impl InternalEvent for NetflowTemplateMissing {
+    fn emit(self) {
+        warn!(
+            message = "Dropped flow records with unknown template, waiting for the exporter to send it.",
+            peer = %self.peer,
+            template_id = %self.template_id,
+            internal_log_rate_secs = 10,
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct OktaRequestError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for OktaRequestError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read the Okta System Log.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct OktaRateLimited {
+    pub delay: Duration,
+}
+
+impl InternalEvent for OktaRateLimited {
+    fn emit(self) {
+        debug!(
+            message = "Rate limit of the Okta System Log exhausted, waiting for it to reset.",
+            delay_secs = %self.delay.as_secs(),
+            internal_log_rate_secs = 10,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct OktaCheckpointError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for OktaCheckpointError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
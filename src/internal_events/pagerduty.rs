@@ -0,0 +1,33 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct PagerDutyInvalidFieldError<'a> {
+    pub field: &'static str,
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for PagerDutyInvalidFieldError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Invalid value for PagerDuty event field; discarding event.",
+            field = %self.field,
+            value = %self.value,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
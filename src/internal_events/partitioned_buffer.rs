@@ -0,0 +1,33 @@
+use metrics::counter;
+use vector_core::{buffers::config::BufferBuildError, internal_event::InternalEvent};
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct PartitionedBufferOpenError<'a> {
+    pub error: BufferBuildError,
+    pub partition: &'a str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for PartitionedBufferOpenError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to open buffer partition; dropping events.",
+            error = %self.error,
+            partition = %self.partition,
+            count = %self.count,
+            error_code = "buffer_partition_open",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "buffer_partition_open",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
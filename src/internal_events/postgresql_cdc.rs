@@ -0,0 +1,97 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct PostgresqlCdcError<'a, E> {
+    pub error: E,
+    pub slot_name: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for PostgresqlCdcError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read changes from replication slot.",
+            error = %self.error,
+            slot_name = %self.slot_name,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcCheckpointError<'a> {
+    pub error: crate::Error,
+    pub slot_name: &'a str,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            slot_name = %self.slot_name,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcSlotCreated<'a> {
+    pub slot_name: &'a str,
+    pub lsn: &'a str,
+    pub previous_lsn: Option<&'a str>,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcSlotCreated<'a> {
+    fn emit(self) {
+        match self.previous_lsn {
+            Some(previous_lsn) => warn!(
+                message = "Re-created replication slot, changes since the last confirmed LSN are lost.",
+                slot_name = %self.slot_name,
+                lsn = %self.lsn,
+                previous_lsn = %previous_lsn,
+            ),
+            None => info!(
+                message = "Created replication slot.",
+                slot_name = %self.slot_name,
+                lsn = %self.lsn,
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcSlotPositionChanged<'a> {
+    pub slot_name: &'a str,
+    pub checkpoint_lsn: &'a str,
+    pub slot_lsn: &'a str,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcSlotPositionChanged<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Replication slot is ahead of the last confirmed LSN, changes in between are lost.",
+            slot_name = %self.slot_name,
+            checkpoint_lsn = %self.checkpoint_lsn,
+            slot_lsn = %self.slot_lsn,
+        );
+    }
+}
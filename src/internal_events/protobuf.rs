@@ -0,0 +1,37 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct ProtobufDecodeError {
+    pub error: String,
+    pub drop_event: bool,
+}
+
+impl InternalEvent for ProtobufDecodeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode protobuf message.",
+            error = %self.error,
+            error_code = "decode_failed",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "decode_failed",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        if self.drop_event {
+            counter!(
+                "component_discarded_events_total", 1,
+                "error_code" => "decode_failed",
+                "error_type" => error_type::PARSER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct RabbitMqStreamConnectionError<'a, E> {
+    pub error: E,
+    pub stream: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for RabbitMqStreamConnectionError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to consume stream, reconnecting.",
+            error = %self.error,
+            stream = %self.stream,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct RabbitMqStreamCheckpointError<'a> {
+    pub error: crate::Error,
+    pub stream: &'a str,
+}
+
+impl<'a> InternalEvent for RabbitMqStreamCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            stream = %self.stream,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct RabbitMqStreamNegativeAcknowledgementError<'a> {
+    pub offset: u64,
+    pub stream: &'a str,
+}
+
+impl<'a> InternalEvent for RabbitMqStreamNegativeAcknowledgementError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Event received a negative acknowledgment, offset checkpointed regardless.",
+            offset = %self.offset,
+            stream = %self.stream,
+            error_code = "negative_acknowledgement",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "negative_acknowledgement",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct RateLimitGroupThrottled<'a> {
+    pub group: &'a str,
+    pub limit: &'static str,
+}
+
+impl<'a> InternalEvent for RateLimitGroupThrottled<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Rate limit group budget exceeded; delaying sink.",
+            group = %self.group,
+            limit = %self.limit,
+        );
+        counter!(
+            "rate_limit_group_throttled_total", 1,
+            "group" => self.group.to_owned(),
+            "limit" => self.limit,
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct PiiDetected<'a> {
+    pub detector: &'a str,
+    pub action: &'static str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for PiiDetected<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Detected personally identifiable information.",
+            detector = %self.detector,
+            action = %self.action,
+            count = %self.count,
+        );
+        counter!(
+            "pii_detections_total", self.count as u64,
+            "detector" => self.detector.to_owned(),
+            "action" => self.action,
+        );
+    }
+}
@@ -71,3 +71,78 @@ impl InternalEvent for RedisReceiveEventError {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct RedisStreamFieldMissingError<'a> {
+    pub id: &'a str,
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for RedisStreamFieldMissingError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Stream entry has no message field, skipping it.",
+            id = %self.id,
+            field = %self.field,
+            error_code = "missing_field",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "missing_field",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisStreamAcknowledgementError {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisStreamAcknowledgementError {
+    fn emit(self) {
+        let error_code = self.error.code().unwrap_or("UNKNOWN").to_string();
+        error!(
+            message = "Failed to acknowledge stream entries.",
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisStreamNegativeAcknowledgementError {
+    pub count: usize,
+}
+
+impl InternalEvent for RedisStreamNegativeAcknowledgementError {
+    fn emit(self) {
+        error!(
+            message = "Events received a negative acknowledgment, stream entries left pending.",
+            count = %self.count,
+            error_code = "negative_acknowledgement",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "negative_acknowledgement",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
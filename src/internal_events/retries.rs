@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct SinkRequestRetrying {
+    pub backoff: Duration,
+}
+
+impl InternalEvent for SinkRequestRetrying {
+    fn emit(self) {
+        debug!(message = "Retrying request.", delay_ms = %self.backoff.as_millis());
+        counter!("component_retries_total", 1);
+        gauge!(
+            "component_retry_backoff_seconds",
+            self.backoff.as_secs_f64()
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SinkRequestRetriesDone;
+
+impl InternalEvent for SinkRequestRetriesDone {
+    fn emit(self) {
+        gauge!("component_retry_backoff_seconds", 0.0);
+    }
+}
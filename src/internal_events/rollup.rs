@@ -0,0 +1,30 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct RollupCounterReset<'a> {
+    pub name: &'a str,
+}
+
+impl<'a> InternalEvent for RollupCounterReset<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Detected counter reset.",
+            name = %self.name,
+            internal_log_rate_secs = 10,
+        );
+        counter!("rollup_counter_resets_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RollupFlushed {
+    pub count: usize,
+}
+
+impl InternalEvent for RollupFlushed {
+    fn emit(self) {
+        counter!("rollup_flushes_total", 1);
+        counter!("rollup_series_flushed_total", self.count as u64);
+    }
+}
@@ -0,0 +1,30 @@
+use std::{fmt::Display, net::SocketAddr};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SflowParseError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: Display> InternalEvent for SflowParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode sFlow datagram.",
+            peer = %self.peer,
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::{fmt::Display, net::SocketAddr};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SnmpTrapParseError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: Display> InternalEvent for SnmpTrapParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode SNMP message.",
+            peer = %self.peer,
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapRejectedError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: Display> InternalEvent for SnmpTrapRejectedError<E> {
+    fn emit(self) {
+        error!(
+            message = "Rejected SNMP message.",
+            peer = %self.peer,
+            error = %self.error,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapResponseError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: Display> InternalEvent for SnmpTrapResponseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge SNMP inform.",
+            peer = %self.peer,
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SqlEvaluationError<'a> {
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for SqlEvaluationError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to evaluate SQL query; discarding event.",
+            error = %self.error,
+            error_code = "evaluation_failed",
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "evaluation_failed",
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SqlQueryError<'a, E> {
+    pub error: E,
+    pub statement: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for SqlQueryError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "SQL query error.",
+            error = %self.error,
+            statement = %self.statement,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryCheckpointError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for SqlQueryCheckpointError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoint.",
+            error = %self.error,
+            error_code = "writing_checkpoint",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_checkpoint",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -31,3 +31,33 @@ impl<'a> InternalEvent for StatsdInvalidMetricError<'a> {
         counter!("processing_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct StatsdInvalidLogError {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for StatsdInvalidLogError {
+    fn emit(self) {
+        error!(
+            message = "Invalid log received; dropping event.",
+            reason = self.reason,
+            error_code = "invalid_log",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "invalid_log",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "invalid_log",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
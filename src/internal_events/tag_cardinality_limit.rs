@@ -1,6 +1,8 @@
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+use super::prelude::{error_stage, error_type};
+
 pub struct TagCardinalityLimitRejectingEvent<'a> {
     pub tag_key: &'a str,
     pub tag_value: &'a str,
@@ -48,3 +50,24 @@ impl<'a> InternalEvent for TagCardinalityValueLimitReached<'a> {
         counter!("value_limit_reached_total", 1);
     }
 }
+
+pub struct TagCardinalityLimitPersistenceError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for TagCardinalityLimitPersistenceError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist the accepted tag values.",
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
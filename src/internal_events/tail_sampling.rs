@@ -0,0 +1,14 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct TailSamplingTraceDiscarded {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSamplingTraceDiscarded {
+    fn emit(self) {
+        trace!(message = "Trace wasn't sampled; discarding.", count = %self.count);
+        counter!("events_discarded_total", self.count as u64);
+    }
+}
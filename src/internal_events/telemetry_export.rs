@@ -0,0 +1,34 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct TelemetryExportError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for TelemetryExportError {
+    fn emit(self) {
+        warn!(
+            message = "Failed to export telemetry to the collector.",
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+        counter!("telemetry_export_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TelemetryExportSpansDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TelemetryExportSpansDropped {
+    fn emit(self) {
+        warn!(
+            message = "Too many spans waiting to be exported; dropping spans.",
+            count = %self.count,
+            internal_log_rate_secs = 30,
+        );
+        counter!("telemetry_export_dropped_spans_total", self.count as u64);
+    }
+}
@@ -0,0 +1,22 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct TenantEventsThrottled<'a> {
+    pub tenant: &'a str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for TenantEventsThrottled<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Tenant throughput quota exceeded; delaying events.",
+            tenant = %self.tenant,
+            count = %self.count,
+        );
+        counter!(
+            "tenant_throttled_events_total", self.count as u64,
+            "tenant" => self.tenant.to_owned(),
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+use super::prelude::{error_stage, error_type};
+
 #[derive(Debug)]
 pub(crate) struct ThrottleEventDiscarded {
     pub key: String,
@@ -15,3 +17,25 @@ impl InternalEvent for ThrottleEventDiscarded {
         );
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleBackendError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ThrottleBackendError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to rate limit event with the backend; using local rate limits.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
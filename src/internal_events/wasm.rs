@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+use crate::transforms::wasm::{BuildError, ProcessError};
+
+#[derive(Debug)]
+pub struct WasmProcessingError {
+    pub error: ProcessError,
+}
+
+impl InternalEvent for WasmProcessingError {
+    fn emit(self) {
+        error!(
+            message = "Error in WebAssembly module; discarding event.",
+            error = %self.error,
+            error_code = self.error.error_code(),
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.error.error_code(),
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => self.error.error_code(),
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmModuleReloaded<'a> {
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for WasmModuleReloaded<'a> {
+    fn emit(self) {
+        info!(message = "Reloaded WebAssembly module.", path = ?self.path);
+        counter!("wasm_module_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmModuleReloadFailed {
+    pub error: BuildError,
+}
+
+impl InternalEvent for WasmModuleReloadFailed {
+    fn emit(self) {
+        error!(
+            message = "Failed to reload WebAssembly module; keeping the previous one.",
+            error = %self.error,
+            error_code = "reload_failed",
+            error_type = error_type::CONFIGURATION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reload_failed",
+            "error_type" => error_type::CONFIGURATION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -87,3 +87,59 @@ impl InternalEvent for WsConnectionError {
         Some("WsConnectionError")
     }
 }
+
+#[derive(Debug)]
+pub struct WsSourceConnectionFailedError {
+    pub error: Box<dyn Error>,
+}
+
+impl InternalEvent for WsSourceConnectionFailedError {
+    fn emit(self) {
+        error!(
+            message = "WebSocket connection failed.",
+            error = %self.error,
+            error_code = "ws_connection_failed",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "ws_connection_failed",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("WsSourceConnectionFailed")
+    }
+}
+
+#[derive(Debug)]
+pub struct WsSourceConnectionError {
+    pub error: tokio_tungstenite::tungstenite::Error,
+}
+
+impl InternalEvent for WsSourceConnectionError {
+    fn emit(self) {
+        error!(
+            message = "WebSocket connection error.",
+            error = %self.error,
+            error_code = "ws_connection_error",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "ws_connection_error",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("WsSourceConnectionError")
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta},
+};
+use kube::{
+    api::{Api, PostParams},
+    config::{self, KubeConfigOptions},
+    Client, Config as ClientConfig,
+};
+
+use super::Lock;
+
+const NOT_FOUND: u16 = 404;
+const CONFLICT: u16 = 409;
+
+/// Returns the default name of the lease of a source, which must be a valid name of a Kubernetes
+/// object unlike component IDs.
+pub(super) fn default_lease_name(component_id: &str) -> String {
+    let name: String = component_id
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '-' | '.') => c,
+            _ => '-',
+        })
+        .collect();
+    format!("vector-{}", name.trim_end_matches(&['-', '.'][..]))
+}
+
+/// A lock held by the replica set as the holder of a `Lease` object, like the leader election of
+/// Kubernetes controllers.
+pub(super) struct KubernetesLock {
+    api: Api<Lease>,
+    name: String,
+    identity: String,
+    lease_duration: Duration,
+    /// The lease as last seen held by another replica, and when it was first seen so.
+    observed: Option<(LeaseSpec, Instant)>,
+}
+
+impl KubernetesLock {
+    pub(super) async fn new(
+        namespace: Option<&str>,
+        name: String,
+        kube_config_file: Option<&PathBuf>,
+        identity: String,
+        lease_duration: Duration,
+    ) -> crate::Result<Self> {
+        let client_config = match kube_config_file {
+            Some(kc) => {
+                ClientConfig::from_custom_kubeconfig(
+                    config::Kubeconfig::read_from(kc)?,
+                    &KubeConfigOptions::default(),
+                )
+                .await?
+            }
+            None => ClientConfig::infer().await?,
+        };
+        let namespace = namespace
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| client_config.default_namespace.clone());
+        let client = Client::try_from(client_config)?;
+        Ok(Self {
+            api: Api::namespaced(client, &namespace),
+            name,
+            identity,
+            lease_duration,
+            observed: None,
+        })
+    }
+
+    fn spec(&self, previous: &LeaseSpec, now: MicroTime) -> LeaseSpec {
+        let held = previous.holder_identity.as_ref() == Some(&self.identity);
+        LeaseSpec {
+            holder_identity: Some(self.identity.clone()),
+            lease_duration_seconds: Some(self.lease_duration.as_secs() as i32),
+            acquire_time: if held {
+                previous.acquire_time.clone()
+            } else {
+                Some(now.clone())
+            },
+            renew_time: Some(now),
+            lease_transitions: Some(
+                previous.lease_transitions.unwrap_or(0) + if held { 0 } else { 1 },
+            ),
+        }
+    }
+
+    /// Returns whether the lease held by another replica expired. This is measured from when this
+    /// replica first saw it renewed, rather than from its renew time, so it doesn't depend on the
+    /// clocks of the replicas agreeing.
+    fn expired(&mut self, spec: &LeaseSpec) -> bool {
+        let lease_duration = spec
+            .lease_duration_seconds
+            .map_or(self.lease_duration, |secs| {
+                Duration::from_secs(secs.max(0) as u64)
+            });
+        if let Some((observed, at)) = &self.observed {
+            if observed == spec {
+                return at.elapsed() >= lease_duration;
+            }
+        }
+        self.observed = Some((spec.clone(), Instant::now()));
+        false
+    }
+}
+
+#[async_trait]
+impl Lock for KubernetesLock {
+    async fn try_acquire(&mut self) -> crate::Result<bool> {
+        let now = MicroTime(Utc::now());
+        let lease = match self.api.get(&self.name).await {
+            Ok(lease) => lease,
+            Err(kube::Error::Api(error)) if error.code == NOT_FOUND => {
+                let lease = Lease {
+                    metadata: ObjectMeta {
+                        name: Some(self.name.clone()),
+                        ..ObjectMeta::default()
+                    },
+                    spec: Some(self.spec(&LeaseSpec::default(), now)),
+                };
+                return match self.api.create(&PostParams::default(), &lease).await {
+                    Ok(_) => Ok(true),
+                    // Another replica created it first.
+                    Err(kube::Error::Api(error)) if error.code == CONFLICT => Ok(false),
+                    Err(error) => Err(error.into()),
+                };
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        let spec = lease.spec.unwrap_or_default();
+        let holder = spec
+            .holder_identity
+            .as_deref()
+            .filter(|holder| !holder.is_empty());
+        if holder.is_some() && holder != Some(self.identity.as_str()) && !self.expired(&spec) {
+            return Ok(false);
+        }
+
+        // Replacing the lease fails if it was changed since it was read, so that only one replica
+        // takes over an expired lease.
+        let lease = Lease {
+            metadata: lease.metadata,
+            spec: Some(self.spec(&spec, now)),
+        };
+        match self
+            .api
+            .replace(&self.name, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => {
+                self.observed = None;
+                Ok(true)
+            }
+            Err(kube::Error::Api(error)) if error.code == CONFLICT => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn release(&mut self) -> crate::Result<()> {
+        let mut lease = self.api.get(&self.name).await?;
+        if let Some(spec) = lease.spec.as_mut() {
+            if spec.holder_identity.as_ref() == Some(&self.identity) {
+                spec.holder_identity = None;
+                self.api
+                    .replace(&self.name, &PostParams::default(), &lease)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_leases_after_components() {
+        assert_eq!(default_lease_name("events"), "vector-events");
+        assert_eq!(default_lease_name("K8s_Events."), "vector-k8s-events");
+    }
+}
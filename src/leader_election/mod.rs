@@ -0,0 +1,531 @@
+//! Leader election among the replicas of Vector running the same source.
+//!
+//! Some sources, such as `kubernetes_events` or the CDC sources, must run exactly once per
+//! cluster. With leader election, every replica builds such a source, but only the one holding a
+//! shared lock runs it, and another one takes over once the lock expires.
+//!
+//! Locks are either Kubernetes `Lease` objects or Redis keys. Both let a replica take a lock only
+//! if it is free or expired, atomically, which object stores such as S3 don't support.
+
+mod kubernetes;
+mod redis;
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use stream_cancel::Trigger;
+use tokio::{
+    select,
+    time::{sleep, Instant},
+};
+
+use crate::{
+    config::{ComponentKey, GlobalOptions, ProxyConfig, SourceConfig, SourceContext},
+    internal_events::{LeaderElectionAcquired, LeaderElectionError, LeaderElectionLost},
+    schema,
+    shutdown::{ShutdownSignal, ShutdownSignalToken},
+    sources::Source,
+    SourceSender,
+};
+
+const fn default_lease_duration_secs() -> u64 {
+    15
+}
+
+const fn default_renew_interval_secs() -> u64 {
+    5
+}
+
+/// How the replicas of Vector running a source elect the only one of them running it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LeaderElectionConfig {
+    #[serde(flatten)]
+    pub lock: LockConfig,
+
+    /// The identity of this replica, which must differ between replicas. Defaults to the
+    /// hostname, which is the name of the pod on Kubernetes.
+    #[serde(default)]
+    pub identity: Option<String>,
+
+    /// How long the lock is held after it was last renewed, in seconds. Once a leader stops
+    /// renewing it, for instance because it crashed, another replica takes over after this long.
+    #[serde(default = "default_lease_duration_secs")]
+    pub lease_duration_secs: u64,
+
+    /// How often the leader renews the lock, and the other replicas try to take it, in seconds.
+    #[serde(default = "default_renew_interval_secs")]
+    pub renew_interval_secs: u64,
+}
+
+/// The lock held by the leader.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LockConfig {
+    /// A `Lease` object of the Kubernetes API.
+    Kubernetes {
+        /// The namespace of the lease. Defaults to the namespace Vector runs in.
+        #[serde(default)]
+        namespace: Option<String>,
+
+        /// The name of the lease. Defaults to `vector-<component ID>`.
+        #[serde(default)]
+        lease_name: Option<String>,
+
+        /// Optional path to a kubeconfig file readable by Vector. If not set, Vector connects to
+        /// Kubernetes using the in-cluster configuration.
+        #[serde(default)]
+        kube_config_file: Option<PathBuf>,
+    },
+
+    /// A key of Redis, expiring once the leader stops renewing it.
+    Redis {
+        /// The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]`
+        /// format.
+        url: String,
+
+        /// The key of the lock. Defaults to `vector:leader:<component ID>`.
+        #[serde(default)]
+        key: Option<String>,
+    },
+}
+
+impl LeaderElectionConfig {
+    /// Builds the elector of the leader running the source `key`.
+    pub async fn build(&self, key: &ComponentKey) -> crate::Result<LeaderElector> {
+        if self.renew_interval_secs == 0 || self.renew_interval_secs >= self.lease_duration_secs {
+            return Err(
+                "`renew_interval_secs` must be positive and less than `lease_duration_secs`".into(),
+            );
+        }
+        let identity = match &self.identity {
+            Some(identity) => identity.clone(),
+            None => crate::get_hostname()?,
+        };
+        let lease_duration = Duration::from_secs(self.lease_duration_secs);
+        let lock: Box<dyn Lock> = match &self.lock {
+            LockConfig::Kubernetes {
+                namespace,
+                lease_name,
+                kube_config_file,
+            } => {
+                let lease_name = lease_name
+                    .clone()
+                    .unwrap_or_else(|| kubernetes::default_lease_name(key.id()));
+                Box::new(
+                    kubernetes::KubernetesLock::new(
+                        namespace.as_deref(),
+                        lease_name,
+                        kube_config_file.as_ref(),
+                        identity,
+                        lease_duration,
+                    )
+                    .await?,
+                )
+            }
+            LockConfig::Redis { url, key: lock_key } => {
+                let lock_key = lock_key
+                    .clone()
+                    .unwrap_or_else(|| format!("vector:leader:{}", key.id()));
+                Box::new(redis::RedisLock::new(url, lock_key, identity, lease_duration).await?)
+            }
+        };
+        Ok(LeaderElector {
+            lock,
+            lease_duration,
+            renew_interval: Duration::from_secs(self.renew_interval_secs),
+        })
+    }
+}
+
+/// A lock held by at most one replica at a time, until it expires.
+#[async_trait]
+trait Lock: Send {
+    /// Takes the lock if it's free or expired, or renews it if this replica holds it. Returns
+    /// whether this replica holds the lock.
+    async fn try_acquire(&mut self) -> crate::Result<bool>;
+
+    /// Frees the lock if this replica holds it, so another replica takes over without waiting for
+    /// it to expire.
+    async fn release(&mut self) -> crate::Result<()>;
+
+    /// Returns the name of the lock, for logging.
+    fn name(&self) -> &str;
+}
+
+/// Elects the leader among the replicas running a source.
+pub struct LeaderElector {
+    lock: Box<dyn Lock>,
+    lease_duration: Duration,
+    renew_interval: Duration,
+}
+
+impl LeaderElector {
+    /// Tries to take or renew the lock, giving up once the next attempt is due. Returns whether
+    /// this replica holds the lock, or `None` if that's unknown because of an error.
+    async fn try_acquire(&mut self) -> Option<bool> {
+        let result = tokio::time::timeout(self.renew_interval, self.lock.try_acquire())
+            .await
+            .unwrap_or_else(|_| Err("timed out".into()));
+        match result {
+            Ok(held) => Some(held),
+            Err(error) => {
+                emit!(LeaderElectionError {
+                    error,
+                    lock: self.lock.name(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Waits until this replica is the leader.
+    async fn acquire(&mut self) {
+        while self.try_acquire().await != Some(true) {
+            sleep(self.renew_interval).await;
+        }
+        emit!(LeaderElectionAcquired {
+            lock: self.lock.name(),
+        });
+    }
+
+    /// Renews the lock until this replica is no longer the leader.
+    async fn hold(&mut self) {
+        let mut renewed = Instant::now();
+        loop {
+            sleep(self.renew_interval).await;
+            let attempted = Instant::now();
+            match self.try_acquire().await {
+                Some(true) => renewed = attempted,
+                Some(false) => break,
+                // Errors may be transient, but leadership is given up before the lock expires,
+                // since another replica may take it over then.
+                None if renewed.elapsed() + self.renew_interval >= self.lease_duration => break,
+                None => {}
+            }
+        }
+        emit!(LeaderElectionLost {
+            lock: self.lock.name(),
+        });
+    }
+
+    async fn release(&mut self) {
+        if let Err(error) = self.lock.release().await {
+            emit!(LeaderElectionError {
+                error,
+                lock: self.lock.name(),
+            });
+        }
+    }
+}
+
+/// A source built for one term of leadership, which is shut down through `trigger`.
+struct Term {
+    source: Source,
+    trigger: Trigger,
+}
+
+/// What a source is built from for each term of leadership.
+#[derive(Clone)]
+struct Parts {
+    config: Arc<Box<dyn SourceConfig>>,
+    key: ComponentKey,
+    globals: GlobalOptions,
+    out: SourceSender,
+    proxy: ProxyConfig,
+    acknowledgements: bool,
+    schema_definitions: HashMap<Option<String>, schema::Definition>,
+}
+
+impl Parts {
+    async fn build(&self) -> crate::Result<Term> {
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = self
+            .config
+            .build(SourceContext {
+                key: self.key.clone(),
+                globals: self.globals.clone(),
+                shutdown,
+                out: self.out.clone(),
+                proxy: self.proxy.clone(),
+                acknowledgements: self.acknowledgements,
+                schema_definitions: self.schema_definitions.clone(),
+            })
+            .await?;
+        Ok(Term { source, trigger })
+    }
+}
+
+/// Builds a source run only while this replica is the leader. It's built again for each term of
+/// leadership, so it starts over from its checkpoint once it was run by another replica.
+pub async fn build_source(
+    config: &LeaderElectionConfig,
+    source: &dyn SourceConfig,
+    cx: SourceContext,
+) -> crate::Result<Source> {
+    let elector = config.build(&cx.key).await?;
+    // Source configurations aren't `Clone`, but they are serializable.
+    let source: Box<dyn SourceConfig> = serde_json::from_value(serde_json::to_value(source)?)?;
+    let parts = Parts {
+        config: Arc::new(source),
+        key: cx.key,
+        globals: cx.globals,
+        out: cx.out,
+        proxy: cx.proxy,
+        acknowledgements: cx.acknowledgements,
+        schema_definitions: cx.schema_definitions,
+    };
+    // The source is built right away, so configuration errors are reported at startup even on
+    // replicas that aren't the leader.
+    let first = parts.build().await?;
+    let rebuild = move || {
+        let parts = parts.clone();
+        async move { parts.build().await }.boxed()
+    };
+    Ok(Box::pin(run(elector, first, rebuild, cx.shutdown)))
+}
+
+/// How a term of leadership ended.
+enum TermEnd {
+    Finished(Result<(), ()>),
+    Shutdown(ShutdownSignalToken),
+    Lost,
+}
+
+/// Runs the source built by `rebuild` during each term of leadership, until `shutdown`.
+async fn run(
+    mut elector: LeaderElector,
+    first: Term,
+    rebuild: impl Fn() -> BoxFuture<'static, crate::Result<Term>> + Send,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut next = Some(first);
+    loop {
+        select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = elector.acquire() => {}
+        }
+        let Term {
+            mut source,
+            trigger,
+        } = match next.take() {
+            Some(term) => term,
+            None => match rebuild().await {
+                Ok(term) => term,
+                Err(error) => {
+                    error!(message = "Failed to build source.", %error);
+                    elector.release().await;
+                    return Err(());
+                }
+            },
+        };
+
+        let end = select! {
+            result = &mut source => TermEnd::Finished(result),
+            token = &mut shutdown => TermEnd::Shutdown(token),
+            _ = elector.hold() => TermEnd::Lost,
+        };
+        match end {
+            TermEnd::Finished(result) => {
+                elector.release().await;
+                return result;
+            }
+            TermEnd::Shutdown(_token) => {
+                trigger.cancel();
+                let result = source.await;
+                elector.release().await;
+                return result;
+            }
+            TermEnd::Lost => {
+                trigger.cancel();
+                // The source is stopped but not dropped, so its acknowledged events are
+                // checkpointed before the next leader resumes from there.
+                if source.await.is_err() {
+                    return Err(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use super::*;
+
+    /// A lock held in memory, shared by the electors of a test.
+    struct MemoryLock {
+        holder: Arc<Mutex<Option<String>>>,
+        identity: String,
+    }
+
+    #[async_trait]
+    impl Lock for MemoryLock {
+        async fn try_acquire(&mut self) -> crate::Result<bool> {
+            let mut holder = self.holder.lock().unwrap();
+            match holder.as_ref() {
+                Some(holder) if *holder != self.identity => Ok(false),
+                _ => {
+                    *holder = Some(self.identity.clone());
+                    Ok(true)
+                }
+            }
+        }
+
+        async fn release(&mut self) -> crate::Result<()> {
+            let mut holder = self.holder.lock().unwrap();
+            if holder.as_ref() == Some(&self.identity) {
+                *holder = None;
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "memory"
+        }
+    }
+
+    fn elector(holder: &Arc<Mutex<Option<String>>>) -> LeaderElector {
+        LeaderElector {
+            lock: Box::new(MemoryLock {
+                holder: Arc::clone(holder),
+                identity: "vector-0".to_owned(),
+            }),
+            lease_duration: Duration::from_secs(15),
+            renew_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Returns a term whose source counts itself in `running` until it's shut down.
+    fn term(running: &Arc<AtomicUsize>) -> Term {
+        let running = Arc::clone(running);
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = async move {
+            running.fetch_add(1, Ordering::SeqCst);
+            let _token = shutdown.await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }
+        .boxed();
+        Term { source, trigger }
+    }
+
+    fn set_holder(holder: &Arc<Mutex<Option<String>>>, identity: Option<&str>) {
+        *holder.lock().unwrap() = identity.map(ToOwned::to_owned);
+    }
+
+    #[tokio::test]
+    async fn runs_source_while_leader() {
+        tokio::time::pause();
+        let holder = Arc::new(Mutex::new(Some("vector-1".to_owned())));
+        let running = Arc::new(AtomicUsize::new(0));
+        let builds = Arc::new(AtomicUsize::new(0));
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+
+        let rebuild = {
+            let running = Arc::clone(&running);
+            let builds = Arc::clone(&builds);
+            move || {
+                builds.fetch_add(1, Ordering::SeqCst);
+                let term = term(&running);
+                async move { Ok(term) }.boxed()
+            }
+        };
+        let handle = tokio::spawn(run(elector(&holder), term(&running), rebuild, shutdown));
+
+        // Another replica is the leader.
+        sleep(Duration::from_secs(12)).await;
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+
+        // It released the lock, which this replica takes.
+        set_holder(&holder, None);
+        sleep(Duration::from_secs(6)).await;
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+
+        // The lock was taken over, so the source is stopped.
+        set_holder(&holder, Some("vector-1"));
+        sleep(Duration::from_secs(6)).await;
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+
+        // It's built again once this replica is the leader again.
+        set_holder(&holder, None);
+        sleep(Duration::from_secs(6)).await;
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        trigger.cancel();
+        assert_eq!(handle.await.unwrap(), Ok(()));
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+        assert_eq!(*holder.lock().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn gives_up_leadership_before_lock_expires() {
+        tokio::time::pause();
+
+        struct FailingLock;
+
+        #[async_trait]
+        impl Lock for FailingLock {
+            async fn try_acquire(&mut self) -> crate::Result<bool> {
+                Err("unreachable".into())
+            }
+
+            async fn release(&mut self) -> crate::Result<()> {
+                Ok(())
+            }
+
+            fn name(&self) -> &str {
+                "failing"
+            }
+        }
+
+        let mut elector = LeaderElector {
+            lock: Box::new(FailingLock),
+            lease_duration: Duration::from_secs(15),
+            renew_interval: Duration::from_secs(5),
+        };
+        let start = Instant::now();
+        elector.hold().await;
+        assert!(start.elapsed() < Duration::from_secs(15));
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: LeaderElectionConfig = toml::from_str(
+            r#"
+            type = "kubernetes"
+            namespace = "observability"
+            lease_duration_secs = 30
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.lock,
+            LockConfig::Kubernetes {
+                namespace: Some("observability".to_owned()),
+                lease_name: None,
+                kube_config_file: None,
+            }
+        );
+        assert_eq!(config.lease_duration_secs, 30);
+        assert_eq!(config.renew_interval_secs, 5);
+
+        let config: LeaderElectionConfig = toml::from_str(
+            r#"
+            type = "redis"
+            url = "redis://127.0.0.1/"
+            identity = "vector-0"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.lock, LockConfig::Redis { key: None, .. }));
+        assert_eq!(config.identity.as_deref(), Some("vector-0"));
+    }
+}
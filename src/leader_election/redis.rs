@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, Script};
+
+use super::Lock;
+
+/// Takes the lock if it's free, or renews it if the replica holds it, setting it to expire after
+/// the lease duration.
+const ACQUIRE_SCRIPT: &str = r#"
+local holder = redis.call("GET", KEYS[1])
+if holder and holder ~= ARGV[1] then
+  return 0
+end
+redis.call("SET", KEYS[1], ARGV[1], "PX", ARGV[2])
+return 1
+"#;
+
+/// Frees the lock if the replica holds it.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+  redis.call("DEL", KEYS[1])
+end
+return 1
+"#;
+
+/// A lock held by the replica set as the value of a Redis key, which expires once it's no longer
+/// renewed. The expiration is handled by Redis, so it doesn't depend on the clocks of the replicas.
+pub(super) struct RedisLock {
+    connection: ConnectionManager,
+    key: String,
+    identity: String,
+    lease_duration: Duration,
+}
+
+impl RedisLock {
+    pub(super) async fn new(
+        url: &str,
+        key: String,
+        identity: String,
+        lease_duration: Duration,
+    ) -> crate::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_tokio_connection_manager().await?;
+        Ok(Self {
+            connection,
+            key,
+            identity,
+            lease_duration,
+        })
+    }
+}
+
+#[async_trait]
+impl Lock for RedisLock {
+    async fn try_acquire(&mut self) -> crate::Result<bool> {
+        let acquired: u8 = Script::new(ACQUIRE_SCRIPT)
+            .key(&self.key)
+            .arg(&self.identity)
+            .arg(self.lease_duration.as_millis() as u64)
+            .invoke_async(&mut self.connection)
+            .await?;
+        Ok(acquired == 1)
+    }
+
+    async fn release(&mut self) -> crate::Result<()> {
+        Script::new(RELEASE_SCRIPT)
+            .key(&self.key)
+            .arg(&self.identity)
+            .invoke_async::<_, u8>(&mut self.connection)
+            .await?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.key
+    }
+}
@@ -26,11 +26,18 @@ extern crate derivative;
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+/// Lets allocations be sampled for heap profiles once the API activates it, sampling every 512KiB
+/// allocated on average.
+#[cfg(feature = "api-profiling")]
+#[export_name = "_rjem_malloc_conf"]
+pub static MALLOC_CONF: &[u8; 46] = b"prof:true,prof_active:false,lg_prof_sample:19\0";
+
 #[macro_use]
 #[allow(unreachable_pub)]
 pub mod config;
 pub mod cli;
 pub mod conditions;
+pub mod convert;
 pub mod dns;
 #[cfg(feature = "docker")]
 pub mod docker;
@@ -60,6 +67,8 @@ pub mod http;
 pub(crate) mod kafka;
 #[allow(unreachable_pub)]
 pub mod kubernetes;
+#[cfg(feature = "leader-election")]
+pub mod leader_election;
 pub mod line_agg;
 pub mod list;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
@@ -67,6 +76,10 @@ pub(crate) mod nats;
 #[allow(unreachable_pub)]
 pub(crate) mod proto;
 pub mod providers;
+pub(crate) mod proxy_protocol;
+#[cfg(feature = "api-client")]
+pub(crate) mod reload;
+pub(crate) mod secrets;
 pub mod serde;
 #[cfg(windows)]
 pub mod service;
@@ -82,6 +95,8 @@ pub mod stats;
 #[allow(unreachable_pub)]
 mod tap;
 pub(crate) mod tcp;
+#[cfg(feature = "sources-opentelemetry")]
+pub mod telemetry_export;
 pub mod template;
 pub mod test_util;
 #[allow(unreachable_pub)]
@@ -15,37 +15,9 @@ use futures::{Stream, StreamExt};
 use pin_project::pin_project;
 use regex::bytes::Regex;
 use tokio_util::time::delay_queue::{DelayQueue, Key};
-use vector_config::configurable_component;
 
 /// Mode of operation of the line aggregator.
-#[configurable_component]
-#[derive(Clone, Copy, Debug, Hash, PartialEq)]
-#[serde(rename_all = "snake_case")]
-pub enum Mode {
-    /// All consecutive lines matching this pattern are included in the group.
-    ///
-    /// The first line (the line that matched the start pattern) does not need to match the `ContinueThrough` pattern.
-    ///
-    /// This is useful in cases such as a Java stack trace, where some indicator in the line (such as leading
-    /// whitespace) indicates that it is an extension of the proceeding line.
-    ContinueThrough,
-
-    /// All consecutive lines matching this pattern, plus one additional line, are included in the group.
-    ///
-    /// This is useful in cases where a log message ends with a continuation marker, such as a backslash, indicating
-    /// that the following line is part of the same message.
-    ContinuePast,
-
-    /// All consecutive lines not matching this pattern are included in the group.
-    ///
-    /// This is useful where a log line contains a marker indicating that it begins a new message.
-    HaltBefore,
-
-    /// All consecutive lines, up to and including the first line matching this pattern, are included in the group.
-    ///
-    /// This is useful where a log line ends with a termination marker, such as a semicolon.
-    HaltWith,
-}
+pub use codecs::decoding::MultilineMode as Mode;
 
 /// Configuration of multi-line aggregation.
 #[derive(Clone, Debug)]
@@ -12,6 +12,8 @@ pub enum NatsConfigError {
     TlsMissingKey,
     #[snafu(display("NATS TLS Config Error: missing cert"))]
     TlsMissingCert,
+    #[snafu(display("NATS Credentials File Error: {}", source))]
+    CredentialsFileError { source: std::io::Error },
 }
 
 /// Configuration of the authentication strategy when interacting with NATS.
@@ -131,6 +133,57 @@ impl NatsAuthConfig {
     }
 }
 
+/// Builds the options of an `async-nats` client, which unlike the `nats` client supports JetStream
+/// asynchronously.
+#[cfg(feature = "sources-nats")]
+pub(crate) async fn to_async_nats_options(
+    connection_name: &str,
+    auth_config: &Option<NatsAuthConfig>,
+    tls_config: &Option<TlsEnableableConfig>,
+) -> Result<async_nats::ConnectOptions, NatsConfigError> {
+    let options = match auth_config {
+        None => async_nats::ConnectOptions::new(),
+        Some(NatsAuthConfig::UserPassword { user_password }) => {
+            async_nats::ConnectOptions::with_user_and_password(
+                user_password.user.clone(),
+                user_password.password.clone(),
+            )
+        }
+        Some(NatsAuthConfig::CredentialsFile { credentials_file }) => {
+            async_nats::ConnectOptions::with_credentials_file(credentials_file.path.clone().into())
+                .await
+                .context(CredentialsFileSnafu)?
+        }
+        Some(NatsAuthConfig::Nkey { nkey }) => {
+            // Validate the seed upfront, as the client only uses it when connecting.
+            nkeys::KeyPair::from_seed(&nkey.seed).context(AuthConfigSnafu)?;
+            async_nats::ConnectOptions::with_nkey(nkey.seed.clone())
+        }
+        Some(NatsAuthConfig::Token { token }) => {
+            async_nats::ConnectOptions::with_token(token.value.clone())
+        }
+    };
+    let options = options.name(connection_name);
+
+    let tls_config = match tls_config {
+        Some(tls_config) if tls_config.enabled.unwrap_or(false) => &tls_config.options,
+        _ => return Ok(options),
+    };
+    let options = options.require_tls(true);
+    let options = match &tls_config.ca_file {
+        None => options,
+        Some(ca_file) => options.add_root_certificates(ca_file.clone()),
+    };
+    match (&tls_config.crt_file, &tls_config.key_file) {
+        (None, None) => Ok(options),
+        (Some(crt_file), Some(key_file)) => {
+            Ok(options.add_client_certificate(crt_file.clone(), key_file.clone()))
+        }
+        (Some(_crt_file), None) => Err(NatsConfigError::TlsMissingKey),
+        (None, Some(_key_file)) => Err(NatsConfigError::TlsMissingCert),
+    }
+}
+
 pub(crate) fn from_tls_auth_config(
     connection_name: &str,
     auth_config: &Option<NatsAuthConfig>,
@@ -0,0 +1,125 @@
+use aws_sdk_s3::Client as S3Client;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    remote::{default_poll_interval_secs, format_from_path, Fetch, Remote, VerificationConfig},
+    Result,
+};
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::s3::S3ClientBuilder,
+    config::{
+        provider::{ProviderConfig, ProviderDescription},
+        GenerateConfig, ProxyConfig,
+    },
+    signal,
+    tls::TlsConfig,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AwsS3Config {
+    bucket: String,
+    key: String,
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+    #[serde(default)]
+    auth: AwsAuthentication,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    tls_options: Option<TlsConfig>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    verification: VerificationConfig,
+}
+
+struct S3Fetcher {
+    client: S3Client,
+    bucket: String,
+    key: String,
+}
+
+#[async_trait::async_trait]
+impl Fetch for S3Fetcher {
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        let key = format!("{}{}", self.key, suffix);
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|error| format!("Couldn't get s3://{}/{}: {}", self.bucket, key, error))?;
+
+        object
+            .body
+            .collect()
+            .await
+            .map(|body| body.into_bytes())
+            .map_err(|error| format!("Couldn't read s3://{}/{}: {}", self.bucket, key, error))
+    }
+
+    fn location(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_s3")]
+impl ProviderConfig for AwsS3Config {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> Result {
+        let endpoint = self
+            .region
+            .endpoint()
+            .map_err(|error| vec![error.to_string()])?;
+        let proxy = ProxyConfig::from_env().merge(&self.proxy);
+        let client = create_client::<S3ClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            endpoint,
+            &proxy,
+            &self.tls_options,
+            false,
+        )
+        .await
+        .map_err(|error| vec![error.to_string()])?;
+
+        let fetcher = S3Fetcher {
+            client,
+            bucket: self.bucket.clone(),
+            key: self.key.clone(),
+        };
+
+        Remote::new(fetcher, format_from_path(&self.key), &self.verification)?
+            .build(self.poll_interval_secs, signal_handler)
+            .await
+    }
+
+    fn provider_type(&self) -> &'static str {
+        "aws_s3"
+    }
+}
+
+inventory::submit! {
+    ProviderDescription::new::<AwsS3Config>("aws_s3")
+}
+
+impl GenerateConfig for AwsS3Config {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"bucket = "my-bucket"
+            key = "vector.toml"
+            region = "us-east-1""#,
+        )
+        .unwrap()
+    }
+}
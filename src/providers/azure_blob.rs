@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use azure_storage_blobs::prelude::*;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    remote::{default_poll_interval_secs, format_from_path, Fetch, Remote, VerificationConfig},
+    Result,
+};
+use crate::{
+    config::{
+        provider::{ProviderConfig, ProviderDescription},
+        GenerateConfig,
+    },
+    signal,
+    sinks::azure_common,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AzureBlobConfig {
+    connection_string: Option<String>,
+    storage_account: Option<String>,
+    container_name: String,
+    blob_name: String,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    verification: VerificationConfig,
+}
+
+struct AzureBlobFetcher {
+    client: Arc<ContainerClient>,
+    container_name: String,
+    blob_name: String,
+}
+
+#[async_trait::async_trait]
+impl Fetch for AzureBlobFetcher {
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        let blob_name = format!("{}{}", self.blob_name, suffix);
+        Arc::clone(&self.client)
+            .as_blob_client(blob_name.as_str())
+            .get()
+            .execute()
+            .await
+            .map(|response| response.data)
+            .map_err(|error| {
+                format!(
+                    "Couldn't get blob {} from container {}: {}",
+                    blob_name, self.container_name, error
+                )
+            })
+    }
+
+    fn location(&self) -> String {
+        format!("{}/{}", self.container_name, self.blob_name)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_blob")]
+impl ProviderConfig for AzureBlobConfig {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> Result {
+        let client = azure_common::config::build_client(
+            self.connection_string.clone(),
+            self.storage_account.clone(),
+            self.container_name.clone(),
+        )
+        .map_err(|error| vec![error.to_string()])?;
+
+        let fetcher = AzureBlobFetcher {
+            client,
+            container_name: self.container_name.clone(),
+            blob_name: self.blob_name.clone(),
+        };
+
+        Remote::new(
+            fetcher,
+            format_from_path(&self.blob_name),
+            &self.verification,
+        )?
+        .build(self.poll_interval_secs, signal_handler)
+        .await
+    }
+
+    fn provider_type(&self) -> &'static str {
+        "azure_blob"
+    }
+}
+
+inventory::submit! {
+    ProviderDescription::new::<AzureBlobConfig>("azure_blob")
+}
+
+impl GenerateConfig for AzureBlobConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"storage_account = "some-account-name"
+            container_name = "vector"
+            blob_name = "vector.toml""#,
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,134 @@
+use bytes::Bytes;
+use http::Uri;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    remote::{default_poll_interval_secs, format_from_path, Fetch, Remote, VerificationConfig},
+    Result,
+};
+use crate::{
+    config::{
+        provider::{ProviderConfig, ProviderDescription},
+        GenerateConfig, ProxyConfig,
+    },
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
+    http::HttpClient,
+    signal,
+    tls::{TlsConfig, TlsSettings},
+};
+
+const BASE_URL: &str = "https://storage.googleapis.com/";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct GcsConfig {
+    bucket: String,
+    object: String,
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    tls: Option<TlsConfig>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    verification: VerificationConfig,
+}
+
+struct GcsFetcher {
+    client: HttpClient,
+    auth: GcpAuthenticator,
+    bucket: String,
+    object: String,
+}
+
+#[async_trait::async_trait]
+impl Fetch for GcsFetcher {
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        let uri = format!("{}{}/{}{}", BASE_URL, self.bucket, self.object, suffix)
+            .parse::<Uri>()
+            .map_err(|error| format!("Invalid object URL: {}", error))?;
+        let mut request = http::Request::get(uri)
+            .body(Body::empty())
+            .map_err(|error| format!("Couldn't create HTTP request: {}", error))?;
+        self.auth.apply(&mut request);
+
+        let location = format!("gs://{}/{}{}", self.bucket, self.object, suffix);
+        let response = self
+            .client
+            .send(request)
+            .await
+            .map_err(|error| format!("Couldn't get {}: {}", location, error))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Couldn't get {}: unexpected HTTP status {}",
+                location,
+                response.status()
+            ));
+        }
+
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|error| format!("Couldn't read {}: {}", location, error))
+    }
+
+    fn location(&self) -> String {
+        format!("gs://{}/{}", self.bucket, self.object)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_storage")]
+impl ProviderConfig for GcsConfig {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> Result {
+        let auth = self
+            .auth
+            .build(Scope::DevStorageReadOnly)
+            .await
+            .map_err(|error| vec![error.to_string()])?;
+        // Keep the authentication token fresh for as long as the provider is polling.
+        auth.spawn_regenerate_token();
+
+        let tls_settings = TlsSettings::from_options(&self.tls)
+            .map_err(|error| vec![format!("Invalid TLS options: {}", error)])?;
+        let proxy = ProxyConfig::from_env().merge(&self.proxy);
+        let client = HttpClient::new(tls_settings, &proxy)
+            .map_err(|error| vec![format!("Invalid TLS settings: {}", error)])?;
+
+        let fetcher = GcsFetcher {
+            client,
+            auth,
+            bucket: self.bucket.clone(),
+            object: self.object.clone(),
+        };
+
+        Remote::new(fetcher, format_from_path(&self.object), &self.verification)?
+            .build(self.poll_interval_secs, signal_handler)
+            .await
+    }
+
+    fn provider_type(&self) -> &'static str {
+        "gcp_cloud_storage"
+    }
+}
+
+inventory::submit! {
+    ProviderDescription::new::<GcsConfig>("gcp_cloud_storage")
+}
+
+impl GenerateConfig for GcsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"bucket = "my-bucket"
+            object = "vector.toml""#,
+        )
+        .unwrap()
+    }
+}
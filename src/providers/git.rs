@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use openssl::sha::sha256;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::{
+    remote::{default_poll_interval_secs, format_from_path, Fetch, Remote, VerificationConfig},
+    Result,
+};
+use crate::{
+    config::{
+        provider::{ProviderConfig, ProviderDescription},
+        GenerateConfig,
+    },
+    signal,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct GitConfig {
+    repository: String,
+    #[serde(default = "default_reference")]
+    reference: String,
+    path: String,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    verification: VerificationConfig,
+}
+
+fn default_reference() -> String {
+    "HEAD".to_owned()
+}
+
+/// Fetches configuration from a git repository, using the `git` command.
+///
+/// The reference is fetched into a local bare repository on every poll, and the configuration is
+/// then read from the fetched commit. This relies on the usual `git` configuration, such as
+/// credential helpers or SSH keys, to access private repositories.
+struct GitFetcher {
+    repository: String,
+    reference: String,
+    path: String,
+    local: PathBuf,
+}
+
+impl GitFetcher {
+    fn new(repository: String, reference: String, path: String) -> Self {
+        let id = sha256(repository.as_bytes())
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        let local = std::env::temp_dir().join(format!("vector-git-provider-{}", id));
+
+        Self {
+            repository,
+            reference,
+            path,
+            local,
+        }
+    }
+
+    async fn git(&self, args: &[&str]) -> std::result::Result<Vec<u8>, String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.local)
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|error| format!("Couldn't run git: {}", error))?;
+
+        if output.status.success() {
+            Ok(output.stdout)
+        } else {
+            Err(format!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Fetch for GitFetcher {
+    async fn refresh(&self) -> std::result::Result<(), String> {
+        tokio::fs::create_dir_all(&self.local)
+            .await
+            .map_err(|error| format!("Couldn't create {:?}: {}", self.local, error))?;
+        self.git(&["init", "--quiet", "--bare"]).await?;
+        self.git(&[
+            "fetch",
+            "--quiet",
+            "--depth",
+            "1",
+            &self.repository,
+            &self.reference,
+        ])
+        .await
+        .map(drop)
+    }
+
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        self.git(&["show", &format!("FETCH_HEAD:{}{}", self.path, suffix)])
+            .await
+            .map(Into::into)
+    }
+
+    fn location(&self) -> String {
+        format!("{}@{}:{}", self.repository, self.reference, self.path)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "git")]
+impl ProviderConfig for GitConfig {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> Result {
+        let fetcher = GitFetcher::new(
+            self.repository.clone(),
+            self.reference.clone(),
+            self.path.clone(),
+        );
+
+        Remote::new(fetcher, format_from_path(&self.path), &self.verification)?
+            .build(self.poll_interval_secs, signal_handler)
+            .await
+    }
+
+    fn provider_type(&self) -> &'static str {
+        "git"
+    }
+}
+
+inventory::submit! {
+    ProviderDescription::new::<GitConfig>("git")
+}
+
+impl GenerateConfig for GitConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"repository = "https://github.com/example/vector-config.git"
+            reference = "main"
+            path = "vector.toml""#,
+        )
+        .unwrap()
+    }
+}
@@ -1,16 +1,15 @@
-use async_stream::stream;
-use bytes::Buf;
-use futures::Stream;
+use bytes::Bytes;
 use hyper::Body;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use tokio::time;
 use url::Url;
 
-use super::Result;
+use super::{
+    remote::{format_from_path, Fetch, Remote, VerificationConfig},
+    Result,
+};
 use crate::{
     config::{
-        self,
         provider::{ProviderConfig, ProviderDescription},
         ProxyConfig,
     },
@@ -46,6 +45,11 @@ pub(crate) struct HttpConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     proxy: ProxyConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    verification: VerificationConfig,
 }
 
 impl Default for HttpConfig {
@@ -56,109 +60,77 @@ impl Default for HttpConfig {
             poll_interval_secs: 30,
             tls_options: None,
             proxy: Default::default(),
+            verification: Default::default(),
         }
     }
 }
 
-/// Makes an HTTP request to the provided endpoint, returning the String body.
-async fn http_request(
-    url: &Url,
-    tls_options: &Option<TlsConfig>,
-    headers: &IndexMap<String, String>,
-    proxy: &ProxyConfig,
-) -> std::result::Result<bytes::Bytes, &'static str> {
-    let tls_settings = TlsSettings::from_options(tls_options).map_err(|_| "Invalid TLS options")?;
-    let http_client =
-        HttpClient::<Body>::new(tls_settings, proxy).map_err(|_| "Invalid TLS settings")?;
-
-    // Build HTTP request.
-    let mut builder = http::request::Builder::new().uri(url.to_string());
-
-    // Augment with headers. These may be required e.g. for authentication to
-    // private endpoints.
-    for (header, value) in headers.iter() {
-        builder = builder.header(header.as_str(), value.as_str());
-    }
-
-    let request = builder
-        .body(Body::empty())
-        .map_err(|_| "Couldn't create HTTP request")?;
-
-    info!(
-        message = "Attempting to retrieve configuration.",
-        url = ?url.as_str()
-    );
-
-    let response = http_client.send(request).await.map_err(|err| {
-        let message = "HTTP error";
-        error!(
-            message = ?message,
-            error = ?err,
-            url = ?url.as_str());
-        message
-    })?;
-
-    info!(message = "Response received.", url = ?url.as_str());
-
-    hyper::body::to_bytes(response.into_body())
-        .await
-        .map_err(|err| {
-            let message = "Error interpreting response.";
-            let cause = err.into_cause();
-            error!(
-                    message = ?message,
-                    error = ?cause);
-
-            message
-        })
-}
-
-/// Calls `http_request`, serializing the result to a `ConfigBuilder`.
-async fn http_request_to_config_builder(
-    url: &Url,
-    tls_options: &Option<TlsConfig>,
-    headers: &IndexMap<String, String>,
-    proxy: &ProxyConfig,
-) -> Result {
-    let config_str = http_request(url, tls_options, headers, proxy)
-        .await
-        .map_err(|e| vec![e.to_owned()])?;
-
-    let (config_builder, warnings) =
-        config::load(config_str.chunk(), crate::config::format::Format::Toml)?;
-
-    for warning in warnings.into_iter() {
-        warn!("{}", warning);
-    }
-
-    Ok(config_builder)
-}
-
-/// Polls the HTTP endpoint after/every `poll_interval_secs`, returning a stream of `ConfigBuilder`.
-fn poll_http(
-    poll_interval_secs: u64,
+struct HttpFetcher {
     url: Url,
     tls_options: Option<TlsConfig>,
     headers: IndexMap<String, String>,
     proxy: ProxyConfig,
-) -> impl Stream<Item = signal::SignalTo> {
-    let duration = time::Duration::from_secs(poll_interval_secs);
-    let mut interval = time::interval_at(time::Instant::now() + duration, duration);
-
-    stream! {
-        loop {
-            interval.tick().await;
-
-            match http_request_to_config_builder(&url, &tls_options, &headers, &proxy).await {
-                Ok(config_builder) => yield signal::SignalTo::ReloadFromConfigBuilder(config_builder),
-                Err(_) => {},
-            };
-
-            info!(
-                message = "HTTP provider is waiting.",
-                poll_interval_secs = ?poll_interval_secs,
+}
+
+#[async_trait::async_trait]
+impl Fetch for HttpFetcher {
+    /// Makes an HTTP request to the provided endpoint, returning the body.
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        let mut url = self.url.clone();
+        url.set_path(&format!("{}{}", self.url.path(), suffix));
+
+        let tls_settings = TlsSettings::from_options(&self.tls_options)
+            .map_err(|_| "Invalid TLS options".to_owned())?;
+        let http_client = HttpClient::<Body>::new(tls_settings, &self.proxy)
+            .map_err(|_| "Invalid TLS settings".to_owned())?;
+
+        // Build HTTP request.
+        let mut builder = http::request::Builder::new().uri(url.to_string());
+
+        // Augment with headers. These may be required e.g. for authentication to
+        // private endpoints.
+        for (header, value) in self.headers.iter() {
+            builder = builder.header(header.as_str(), value.as_str());
+        }
+
+        let request = builder
+            .body(Body::empty())
+            .map_err(|_| "Couldn't create HTTP request".to_owned())?;
+
+        let response = http_client.send(request).await.map_err(|err| {
+            let message = "HTTP error";
+            error!(
+                message = ?message,
+                error = ?err,
                 url = ?url.as_str());
+            message.to_owned()
+        })?;
+
+        info!(message = "Response received.", url = ?url.as_str());
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Unexpected HTTP status {} from {}.",
+                response.status(),
+                url
+            ));
         }
+
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|err| {
+                let message = "Error interpreting response.";
+                let cause = err.into_cause();
+                error!(
+                        message = ?message,
+                        error = ?cause);
+
+                message.to_owned()
+            })
+    }
+
+    fn location(&self) -> String {
+        self.url.to_string()
     }
 }
 
@@ -171,24 +143,18 @@ impl ProviderConfig for HttpConfig {
             .take()
             .ok_or_else(|| vec!["URL is required for the `http` provider.".to_owned()])?;
 
-        let tls_options = self.tls_options.take();
-        let poll_interval_secs = self.poll_interval_secs;
-        let request = self.request.clone();
-
-        let proxy = ProxyConfig::from_env().merge(&self.proxy);
-        let config_builder =
-            http_request_to_config_builder(&url, &tls_options, &request.headers, &proxy).await?;
-
-        // Poll for changes to remote configuration.
-        signal_handler.add(poll_http(
-            poll_interval_secs,
+        let format = format_from_path(url.path());
+        let fetcher = HttpFetcher {
             url,
-            tls_options,
-            request.headers.clone(),
-            proxy.clone(),
-        ));
+            tls_options: self.tls_options.take(),
+            headers: self.request.headers.clone(),
+            proxy: ProxyConfig::from_env().merge(&self.proxy),
+        };
 
-        Ok(config_builder)
+        // Poll for changes to remote configuration.
+        Remote::new(fetcher, format, &self.verification)?
+            .build(self.poll_interval_secs, signal_handler)
+            .await
     }
 
     fn provider_type(&self) -> &'static str {
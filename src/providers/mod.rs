@@ -1,4 +1,12 @@
+#[cfg(feature = "providers-aws_s3")]
+pub mod aws_s3;
+#[cfg(feature = "providers-azure_blob")]
+pub mod azure_blob;
+#[cfg(feature = "providers-gcp_cloud_storage")]
+pub mod gcp_cloud_storage;
+pub mod git;
 pub mod http;
+mod remote;
 
 use super::config::ConfigBuilder;
 
@@ -0,0 +1,366 @@
+//! Shared support for providers that poll a remote location for configuration.
+//!
+//! The configuration is fetched on an interval, and only reloaded when it has changed since it was
+//! last fetched. Before being reloaded, it can be verified against a SHA-256 checksum and/or an
+//! Ed25519 signature stored next to it, with a `.sha256` and `.sig` suffix respectively.
+
+use async_stream::stream;
+use bytes::Bytes;
+use futures::Stream;
+use openssl::{
+    pkey::{PKey, Public},
+    sha::sha256,
+    sign::Verifier,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time;
+
+use super::Result;
+use crate::{
+    config::{self, ConfigBuilder, Format},
+    signal,
+};
+
+/// Suffix of the checksum stored next to the configuration.
+const CHECKSUM_SUFFIX: &str = ".sha256";
+
+/// Suffix of the signature stored next to the configuration.
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+pub(super) const fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct VerificationConfig {
+    /// Verify the configuration against the hex-encoded SHA-256 checksum stored next to it, with
+    /// a `.sha256` suffix. The output of `sha256sum` is accepted as-is.
+    pub sha256: bool,
+
+    /// PEM-encoded Ed25519 public key to verify the configuration with. The raw signature is
+    /// expected to be stored next to the configuration, with a `.sig` suffix.
+    pub public_key: Option<String>,
+}
+
+impl VerificationConfig {
+    fn build(&self) -> std::result::Result<Verification, String> {
+        let public_key = self
+            .public_key
+            .as_ref()
+            .map(|public_key| {
+                PKey::public_key_from_pem(public_key.as_bytes())
+                    .map_err(|error| format!("Invalid verification public key: {}.", error))
+            })
+            .transpose()?;
+
+        Ok(Verification {
+            sha256: self.sha256,
+            public_key,
+        })
+    }
+}
+
+struct Verification {
+    sha256: bool,
+    public_key: Option<PKey<Public>>,
+}
+
+impl Verification {
+    async fn verify(&self, fetcher: &dyn Fetch, payload: &[u8]) -> std::result::Result<(), String> {
+        if self.sha256 {
+            let checksum = fetcher.fetch(CHECKSUM_SUFFIX).await?;
+            let expected = std::str::from_utf8(&checksum)
+                .ok()
+                .and_then(|checksum| checksum.split_whitespace().next())
+                .ok_or_else(|| "Configuration checksum is malformed.".to_owned())?;
+
+            if !expected.eq_ignore_ascii_case(&encode_hex(&sha256(payload))) {
+                return Err("Configuration doesn't match its checksum.".to_owned());
+            }
+        }
+
+        if let Some(public_key) = &self.public_key {
+            let signature = fetcher.fetch(SIGNATURE_SUFFIX).await?;
+            let is_valid = Verifier::new_without_digest(public_key)
+                .and_then(|mut verifier| verifier.verify_oneshot(&signature, payload))
+                .map_err(|error| format!("Couldn't verify configuration signature: {}.", error))?;
+
+            if !is_valid {
+                return Err("Configuration doesn't match its signature.".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A remote location configuration can be fetched from.
+#[async_trait::async_trait]
+pub(super) trait Fetch: Send + Sync + 'static {
+    /// Updates any local state required to fetch the latest configuration. This is called once
+    /// per poll, before the configuration and its checksum or signature are fetched.
+    async fn refresh(&self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    /// Fetches the object at the configured location, with `suffix` appended to its name.
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String>;
+
+    /// Returns the configured location, for logging.
+    fn location(&self) -> String;
+}
+
+/// Configuration fetched from a remote location.
+pub(super) struct Remote<F> {
+    fetcher: F,
+    format: Format,
+    verification: Verification,
+    digest: Option<[u8; 32]>,
+}
+
+impl<F: Fetch> Remote<F> {
+    pub(super) fn new(
+        fetcher: F,
+        format: Format,
+        verification: &VerificationConfig,
+    ) -> std::result::Result<Self, Vec<String>> {
+        Ok(Self {
+            fetcher,
+            format,
+            verification: verification.build().map_err(|error| vec![error])?,
+            digest: None,
+        })
+    }
+
+    /// Fetches the configuration, returning a stream of config reload signals for any later
+    /// changes to it, polled every `poll_interval_secs`.
+    pub(super) async fn build(
+        mut self,
+        poll_interval_secs: u64,
+        signal_handler: &mut signal::SignalHandler,
+    ) -> Result {
+        let config_builder = self
+            .fetch_if_changed()
+            .await?
+            .expect("configuration is always considered changed when first fetched");
+
+        signal_handler.add(self.poll(poll_interval_secs));
+
+        Ok(config_builder)
+    }
+
+    /// Fetches and verifies the configuration, returning it if it has changed since it was last
+    /// fetched.
+    async fn fetch_if_changed(
+        &mut self,
+    ) -> std::result::Result<Option<ConfigBuilder>, Vec<String>> {
+        info!(
+            message = "Attempting to retrieve configuration.",
+            location = %self.fetcher.location()
+        );
+
+        self.fetcher.refresh().await.map_err(|error| vec![error])?;
+        let payload = self.fetcher.fetch("").await.map_err(|error| vec![error])?;
+
+        let digest = sha256(&payload);
+        if self.digest == Some(digest) {
+            debug!(message = "Configuration is unchanged.", location = %self.fetcher.location());
+            return Ok(None);
+        }
+
+        self.verification
+            .verify(&self.fetcher, &payload)
+            .await
+            .map_err(|error| vec![error])?;
+
+        let (config_builder, warnings) = config::load(payload.as_ref(), self.format)?;
+        for warning in warnings.into_iter() {
+            warn!("{}", warning);
+        }
+
+        self.digest = Some(digest);
+        Ok(Some(config_builder))
+    }
+
+    /// Polls the remote location after/every `poll_interval_secs`, returning a stream of
+    /// `ConfigBuilder` for every change to the configuration.
+    fn poll(mut self, poll_interval_secs: u64) -> impl Stream<Item = signal::SignalTo> {
+        let duration = time::Duration::from_secs(poll_interval_secs);
+        let mut interval = time::interval_at(time::Instant::now() + duration, duration);
+
+        stream! {
+            loop {
+                interval.tick().await;
+
+                match self.fetch_if_changed().await {
+                    Ok(Some(config_builder)) => yield signal::SignalTo::ReloadFromConfigBuilder(config_builder),
+                    Ok(None) => {},
+                    Err(errors) => {
+                        for error in errors {
+                            error!(message = "Failed to retrieve configuration.", %error, location = %self.fetcher.location());
+                        }
+                    }
+                };
+
+                info!(
+                    message = "Provider is waiting.",
+                    poll_interval_secs = ?poll_interval_secs,
+                    location = %self.fetcher.location());
+            }
+        }
+    }
+}
+
+/// Returns the format of the configuration at `path`, defaulting to TOML.
+pub(super) fn format_from_path(path: &str) -> Format {
+    Format::from_path(path).unwrap_or_default()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use indoc::indoc;
+    use openssl::{pkey::Private, sign::Signer};
+
+    use super::*;
+
+    const CONFIG: &str = indoc! {r#"
+        [sources.in]
+          type = "basic_source"
+
+        [sinks.out]
+          type = "basic_sink"
+          inputs = ["in"]
+    "#};
+
+    #[derive(Clone, Default)]
+    struct MockFetcher {
+        objects: Arc<Mutex<HashMap<&'static str, Bytes>>>,
+    }
+
+    impl MockFetcher {
+        fn put(&self, suffix: &'static str, object: impl Into<Bytes>) {
+            self.objects.lock().unwrap().insert(suffix, object.into());
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Fetch for MockFetcher {
+        async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+            self.objects
+                .lock()
+                .unwrap()
+                .get(suffix)
+                .cloned()
+                .ok_or_else(|| format!("No object with suffix {:?}.", suffix))
+        }
+
+        fn location(&self) -> String {
+            "mock".to_owned()
+        }
+    }
+
+    fn signing_key() -> (PKey<Private>, String) {
+        let key = PKey::generate_ed25519().unwrap();
+        let public_key = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+        (key, public_key)
+    }
+
+    fn sign(key: &PKey<Private>, payload: &str) -> Vec<u8> {
+        Signer::new_without_digest(key)
+            .unwrap()
+            .sign_oneshot_to_vec(payload.as_bytes())
+            .unwrap()
+    }
+
+    fn remote(fetcher: &MockFetcher, verification: VerificationConfig) -> Remote<MockFetcher> {
+        Remote::new(fetcher.clone(), Format::Toml, &verification).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reloads_only_changed_config() {
+        let fetcher = MockFetcher::default();
+        fetcher.put("", CONFIG);
+        let mut remote = remote(&fetcher, VerificationConfig::default());
+
+        assert!(remote.fetch_if_changed().await.unwrap().is_some());
+        assert!(remote.fetch_if_changed().await.unwrap().is_none());
+
+        fetcher.put("", format!("{}\n# changed", CONFIG));
+        assert!(remote.fetch_if_changed().await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn verifies_checksum() {
+        let fetcher = MockFetcher::default();
+        fetcher.put("", CONFIG);
+        fetcher.put(
+            ".sha256",
+            format!("{}  vector.toml\n", encode_hex(&sha256(CONFIG.as_bytes()))),
+        );
+        let verification = VerificationConfig {
+            sha256: true,
+            ..Default::default()
+        };
+        let mut remote = remote(&fetcher, verification);
+        assert!(remote.fetch_if_changed().await.unwrap().is_some());
+
+        fetcher.put("", format!("{}\n# tampered", CONFIG));
+        assert!(remote.fetch_if_changed().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verifies_signature() {
+        let (key, public_key) = signing_key();
+        let fetcher = MockFetcher::default();
+        fetcher.put("", CONFIG);
+        fetcher.put(".sig", sign(&key, CONFIG));
+        let verification = VerificationConfig {
+            public_key: Some(public_key),
+            ..Default::default()
+        };
+        let mut remote = remote(&fetcher, verification);
+        assert!(remote.fetch_if_changed().await.unwrap().is_some());
+
+        let (other_key, _) = signing_key();
+        let changed = format!("{}\n# changed", CONFIG);
+        fetcher.put("", changed.clone());
+        fetcher.put(".sig", sign(&other_key, &changed));
+        assert!(remote.fetch_if_changed().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn retries_rejected_config() {
+        let fetcher = MockFetcher::default();
+        fetcher.put("", CONFIG);
+        let verification = VerificationConfig {
+            sha256: true,
+            ..Default::default()
+        };
+        let mut remote = remote(&fetcher, verification);
+
+        // The checksum hasn't been uploaded yet, so the configuration is rejected, and picked up
+        // once it's there.
+        assert!(remote.fetch_if_changed().await.is_err());
+        fetcher.put(".sha256", encode_hex(&sha256(CONFIG.as_bytes())));
+        assert!(remote.fetch_if_changed().await.unwrap().is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_public_key() {
+        let verification = VerificationConfig {
+            public_key: Some("not a key".to_owned()),
+            ..Default::default()
+        };
+        assert!(Remote::new(MockFetcher::default(), Format::Toml, &verification).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Parsing of the [PROXY protocol][proxy_protocol] header, which load balancers such as HAProxy
+//! send at the start of the TCP connections they proxy, to pass on the address of the client.
+//!
+//! [proxy_protocol]: https://www.haproxy.org/download/2.6/doc/proxy-protocol.txt
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use snafu::{ResultExt, Snafu};
+use tokio::io::{self, AsyncRead, AsyncReadExt};
+
+/// The signature starting version 2 headers.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The maximum length of version 1 headers, including the trailing CRLF.
+const V1_MAX_LENGTH: usize = 107;
+
+#[derive(Debug, Snafu)]
+pub enum ProxyProtocolError {
+    #[snafu(display("Failed to read the PROXY protocol header: {}", source))]
+    ReadHeader { source: io::Error },
+    #[snafu(display("Connection doesn't start with a PROXY protocol header"))]
+    MissingHeader,
+    #[snafu(display("Invalid PROXY protocol header: {}", reason))]
+    InvalidHeader { reason: &'static str },
+}
+
+/// Reads the PROXY protocol header at the start of the stream, without reading any data past it.
+///
+/// Returns the address of the client, or `None` if the proxy didn't pass it on, such as for its
+/// own health checks.
+pub(crate) async fn read_header<S>(stream: &mut S) -> Result<Option<SocketAddr>, ProxyProtocolError>
+where
+    S: AsyncRead + Unpin,
+{
+    // Both versions are at least as long as the version 2 signature, so reading that many bytes
+    // can't read past the header.
+    let mut start = [0; 12];
+    stream
+        .read_exact(&mut start)
+        .await
+        .context(ReadHeaderSnafu)?;
+
+    if start == V2_SIGNATURE {
+        read_v2(stream).await
+    } else if start.starts_with(b"PROXY ") {
+        read_v1(stream, &start).await
+    } else {
+        Err(ProxyProtocolError::MissingHeader)
+    }
+}
+
+async fn read_v1<S>(stream: &mut S, start: &[u8]) -> Result<Option<SocketAddr>, ProxyProtocolError>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut header = start.to_vec();
+    while !header.ends_with(b"\r\n") {
+        if header.len() == V1_MAX_LENGTH {
+            return Err(ProxyProtocolError::InvalidHeader {
+                reason: "header is too long",
+            });
+        }
+        header.push(stream.read_u8().await.context(ReadHeaderSnafu)?);
+    }
+
+    parse_v1(&header[..header.len() - 2])
+}
+
+fn parse_v1(header: &[u8]) -> Result<Option<SocketAddr>, ProxyProtocolError> {
+    let invalid = |reason| ProxyProtocolError::InvalidHeader { reason };
+
+    let header = std::str::from_utf8(header).map_err(|_| invalid("header isn't ASCII"))?;
+    let mut fields = header.split(' ').skip(1);
+    match fields.next() {
+        Some("TCP4") | Some("TCP6") => (),
+        Some("UNKNOWN") => return Ok(None),
+        _ => return Err(invalid("unknown protocol")),
+    }
+
+    let fields = fields.collect::<Vec<_>>();
+    if fields.len() != 4 {
+        return Err(invalid("wrong number of fields"));
+    }
+    let ip = fields[0]
+        .parse::<IpAddr>()
+        .map_err(|_| invalid("invalid source address"))?;
+    let port = fields[2]
+        .parse::<u16>()
+        .map_err(|_| invalid("invalid source port"))?;
+
+    Ok(Some(SocketAddr::new(ip, port)))
+}
+
+async fn read_v2<S>(stream: &mut S) -> Result<Option<SocketAddr>, ProxyProtocolError>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut header = [0; 4];
+    stream
+        .read_exact(&mut header)
+        .await
+        .context(ReadHeaderSnafu)?;
+    let [version_command, family, length @ ..] = header;
+
+    // The addresses are followed by optional TLVs, which are read but ignored.
+    let mut addresses = vec![0; u16::from_be_bytes(length) as usize];
+    stream
+        .read_exact(&mut addresses)
+        .await
+        .context(ReadHeaderSnafu)?;
+
+    parse_v2(version_command, family, &addresses)
+}
+
+fn parse_v2(
+    version_command: u8,
+    family: u8,
+    addresses: &[u8],
+) -> Result<Option<SocketAddr>, ProxyProtocolError> {
+    let invalid = |reason| ProxyProtocolError::InvalidHeader { reason };
+
+    if version_command >> 4 != 2 {
+        return Err(invalid("unsupported version"));
+    }
+    match version_command & 0x0f {
+        // LOCAL connections are established by the proxy itself.
+        0x0 => return Ok(None),
+        0x1 => (),
+        _ => return Err(invalid("unknown command")),
+    }
+
+    match family >> 4 {
+        // IPv4: source and destination addresses, then source and destination ports.
+        0x1 => {
+            if addresses.len() < 12 {
+                return Err(invalid("addresses are too short"));
+            }
+            let mut ip = [0; 4];
+            ip.copy_from_slice(&addresses[..4]);
+            let port = u16::from_be_bytes([addresses[8], addresses[9]]);
+            Ok(Some(SocketAddr::new(Ipv4Addr::from(ip).into(), port)))
+        }
+        // IPv6: same layout as IPv4.
+        0x2 => {
+            if addresses.len() < 36 {
+                return Err(invalid("addresses are too short"));
+            }
+            let mut ip = [0; 16];
+            ip.copy_from_slice(&addresses[..16]);
+            let port = u16::from_be_bytes([addresses[32], addresses[33]]);
+            Ok(Some(SocketAddr::new(Ipv6Addr::from(ip).into(), port)))
+        }
+        // Unspecified and UNIX families have no address to report.
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn read(mut header: &[u8]) -> Result<Option<SocketAddr>, ProxyProtocolError> {
+        read_header(&mut header).await
+    }
+
+    #[tokio::test]
+    async fn reads_v1_header() {
+        let mut stream: &[u8] = b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 514\r\nmessage\n";
+        let addr = read_header(&mut stream).await.unwrap();
+        assert_eq!(addr, Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(stream, b"message\n");
+
+        let addr = read(b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 514\r\n")
+            .await
+            .unwrap();
+        assert_eq!(addr, Some("[2001:db8::1]:56324".parse().unwrap()));
+
+        let addr = read(b"PROXY UNKNOWN\r\n").await.unwrap();
+        assert_eq!(addr, None);
+    }
+
+    #[tokio::test]
+    async fn reads_v2_header() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend([0x21, 0x11, 0, 15]);
+        header.extend([192, 0, 2, 1, 198, 51, 100, 1]);
+        header.extend(56324u16.to_be_bytes());
+        header.extend(514u16.to_be_bytes());
+        // A TLV, which is skipped.
+        header.extend([0x04, 0, 0]);
+        header.extend(b"message\n");
+
+        let mut stream = &header[..];
+        let addr = read_header(&mut stream).await.unwrap();
+        assert_eq!(addr, Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(stream, b"message\n");
+
+        let mut local = V2_SIGNATURE.to_vec();
+        local.extend([0x20, 0x00, 0, 0]);
+        assert_eq!(read(&local).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_headers() {
+        assert!(matches!(
+            read(b"<13>Feb 13 20:07:26 host app: message\n").await,
+            Err(ProxyProtocolError::MissingHeader)
+        ));
+        assert!(matches!(
+            read(b"PROXY TCP4 192.0.2.1 198.51.100.1 56324\r\n").await,
+            Err(ProxyProtocolError::InvalidHeader { .. })
+        ));
+        assert!(matches!(
+            read(&[b"PROXY TCP4 ".as_slice(), &[b'1'; 100]].concat()).await,
+            Err(ProxyProtocolError::InvalidHeader { .. })
+        ));
+        assert!(matches!(
+            read(b"PROXY TCP4").await,
+            Err(ProxyProtocolError::ReadHeader { .. })
+        ));
+    }
+}
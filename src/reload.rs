@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use url::Url;
+
+use crate::{
+    config::{self, Format, ProxyConfig},
+    http::HttpClient,
+};
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Vector API server to push the configuration to. Defaults to the local API address.
+    #[clap(long)]
+    remote: Option<Url>,
+
+    /// Token configured as `api.reload_token` on the Vector instance.
+    #[clap(long, env = "VECTOR_RELOAD_TOKEN")]
+    token: String,
+
+    /// Vector config file to push. File format is detected from the file name.
+    #[clap(short, long)]
+    config: PathBuf,
+
+    /// Layer the pushed configuration on top of the configuration files the Vector instance was
+    /// started with, rather than replacing them.
+    #[clap(long)]
+    partial: bool,
+}
+
+/// CLI command func for pushing a configuration to a local/remote Vector API server, which
+/// validates it and reloads its topology in the same way as on SIGHUP.
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match push(opts).await {
+        Ok(()) => {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("Configuration accepted, reloading.");
+            }
+            exitcode::OK
+        }
+        Err((code, errors)) => {
+            #[allow(clippy::print_stderr)]
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            code
+        }
+    }
+}
+
+async fn push(opts: &Opts) -> Result<(), (exitcode::ExitCode, Vec<String>)> {
+    let mut url = opts.remote.clone().unwrap_or_else(|| {
+        let addr = config::api::default_address().unwrap();
+        Url::parse(&*format!("http://{}", addr))
+            .expect("Couldn't parse default API URL. Please report this.")
+    });
+    url.set_path("/reload");
+    url.set_query(opts.partial.then(|| "partial=true"));
+
+    let payload = std::fs::read(&opts.config).map_err(|error| {
+        (
+            exitcode::NOINPUT,
+            vec![format!("Couldn't read {:?}: {}", opts.config, error)],
+        )
+    })?;
+    let content_type = match Format::from_path(&opts.config).unwrap_or_default() {
+        Format::Toml => "application/toml",
+        Format::Json => "application/json",
+        Format::Yaml => "application/yaml",
+    };
+
+    let request = http::Request::post(url.as_str())
+        .header("Authorization", format!("Bearer {}", opts.token))
+        .header("Content-Type", content_type)
+        .body(hyper::Body::from(payload))
+        .map_err(|error| (exitcode::USAGE, vec![error.to_string()]))?;
+
+    let response = HttpClient::new(None, &ProxyConfig::from_env())
+        .map_err(|error| (exitcode::SOFTWARE, vec![error.to_string()]))?
+        .send(request)
+        .await
+        .map_err(|error| {
+            (
+                exitcode::UNAVAILABLE,
+                vec![format!("Couldn't connect to the Vector API: {}", error)],
+            )
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap_or_default();
+    let errors = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|body| {
+            body.get("errors")?
+                .as_array()?
+                .iter()
+                .map(|error| error.as_str().map(Into::into))
+                .collect::<Option<Vec<String>>>()
+        })
+        .unwrap_or_else(|| vec![format!("Vector API responded with {}.", status)]);
+
+    let code = match status {
+        http::StatusCode::BAD_REQUEST => exitcode::CONFIG,
+        http::StatusCode::UNAUTHORIZED => exitcode::NOPERM,
+        _ => exitcode::UNAVAILABLE,
+    };
+    Err((code, errors))
+}
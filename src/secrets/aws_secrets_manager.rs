@@ -0,0 +1,103 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use super::{secret_field, split_key, BackendOptions};
+use crate::{
+    aws::{create_client, AwsAuthentication, ClientBuilder, RegionOrEndpoint},
+    config::{ProxyConfig, SecretBackend},
+    signal,
+    tls::TlsConfig,
+};
+
+/// Retrieves secrets from AWS Secrets Manager. Secret keys are either the name of a secret, or
+/// formatted as `<name>.<field>` to retrieve a field of a secret stored as a JSON object.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct AwsSecretsManagerBackend {
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+
+    #[serde(default)]
+    auth: AwsAuthentication,
+
+    tls: Option<TlsConfig>,
+
+    #[serde(flatten)]
+    options: BackendOptions,
+}
+
+struct SecretsManagerClientBuilder;
+
+impl ClientBuilder for SecretsManagerClientBuilder {
+    type Config = aws_sdk_secretsmanager::config::Config;
+    type Client = aws_sdk_secretsmanager::client::Client;
+    type DefaultMiddleware = aws_sdk_secretsmanager::middleware::DefaultMiddleware;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_secretsmanager::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        aws_sdk_secretsmanager::client::Client::with_config(client, config.into())
+    }
+}
+
+impl AwsSecretsManagerBackend {
+    async fn retrieve_secrets(&self, keys: Vec<String>) -> crate::Result<HashMap<String, String>> {
+        let client = create_client::<SecretsManagerClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint()?,
+            &ProxyConfig::from_env(),
+            &self.tls,
+            false,
+        )
+        .await?;
+
+        // Each secret is only retrieved once, however many of its fields are used.
+        let mut values = HashMap::new();
+        let mut secrets = HashMap::new();
+        for key in keys {
+            let (name, field) = split_key(&key);
+            if !values.contains_key(name) {
+                let output = client
+                    .get_secret_value()
+                    .secret_id(name)
+                    .send()
+                    .await
+                    .map_err(|error| format!("couldn't get secret '{}': {}", name, error))?;
+                let value = match (output.secret_string(), output.secret_binary()) {
+                    (Some(value), _) => value.to_owned(),
+                    (None, Some(value)) => String::from_utf8(value.as_ref().to_vec())
+                        .map_err(|_| format!("secret '{}' is not valid UTF-8", name))?,
+                    (None, None) => return Err(format!("secret '{}' has no value", name).into()),
+                };
+                values.insert(name.to_owned(), value);
+            }
+            let value = secret_field(&values[name], field)
+                .map_err(|error| format!("invalid secret '{}': {}", name, error))?;
+            secrets.insert(key.clone(), value);
+        }
+        Ok(secrets)
+    }
+}
+
+#[typetag::serde(name = "aws_secrets_manager")]
+impl SecretBackend for AwsSecretsManagerBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        self.options
+            .block_on(self.retrieve_secrets(secret_keys), signal_rx)
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.options.cache_ttl()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.options.refresh_interval()
+    }
+}
@@ -0,0 +1,89 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use azure_core::auth::TokenCredential;
+use azure_identity::{AutoRefreshingTokenCredential, DefaultAzureCredential};
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+
+use super::{secret_field, send_json, split_key, BackendOptions};
+use crate::{
+    config::{ProxyConfig, SecretBackend},
+    http::HttpClient,
+    signal,
+};
+
+const KEY_VAULT_RESOURCE: &str = "https://vault.azure.net";
+const KEY_VAULT_API_VERSION: &str = "7.3";
+
+/// Retrieves the latest version of secrets from an Azure Key Vault, authenticating with the
+/// default Azure credential chain. Secret keys are either the name of a secret, or formatted as
+/// `<name>.<field>` to retrieve a field of a secret stored as a JSON object.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct AzureKeyVaultBackend {
+    /// The URL of the key vault, such as `https://my-vault.vault.azure.net`.
+    vault_url: String,
+
+    #[serde(flatten)]
+    options: BackendOptions,
+}
+
+impl AzureKeyVaultBackend {
+    async fn retrieve_secrets(&self, keys: Vec<String>) -> crate::Result<HashMap<String, String>> {
+        let client = HttpClient::new(None, &ProxyConfig::from_env())?;
+        let credential =
+            AutoRefreshingTokenCredential::new(Arc::new(DefaultAzureCredential::default()));
+        let token = credential.get_token(KEY_VAULT_RESOURCE).await?;
+        let authorization = format!("Bearer {}", token.token.secret());
+
+        // Each secret is only retrieved once, however many of its fields are used.
+        let mut values = HashMap::new();
+        let mut secrets = HashMap::new();
+        for key in keys {
+            let (name, field) = split_key(&key);
+            if !values.contains_key(name) {
+                let uri = format!(
+                    "{}/secrets/{}?api-version={}",
+                    self.vault_url.trim_end_matches('/'),
+                    name,
+                    KEY_VAULT_API_VERSION
+                );
+                let request = Request::get(uri)
+                    .header(http::header::AUTHORIZATION, &authorization)
+                    .body(Body::empty())?;
+                let reply = send_json(&client, request)
+                    .await
+                    .map_err(|error| format!("couldn't get secret '{}': {}", name, error))?;
+                let value = reply
+                    .get("value")
+                    .and_then(serde_json::Value::as_str)
+                    .ok_or_else(|| format!("secret '{}' has no value", name))?;
+                values.insert(name.to_owned(), value.to_owned());
+            }
+            let value = secret_field(&values[name], field)
+                .map_err(|error| format!("invalid secret '{}': {}", name, error))?;
+            secrets.insert(key.clone(), value);
+        }
+        Ok(secrets)
+    }
+}
+
+#[typetag::serde(name = "azure_key_vault")]
+impl SecretBackend for AzureKeyVaultBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        self.options
+            .block_on(self.retrieve_secrets(secret_keys), signal_rx)
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.options.cache_ttl()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.options.refresh_interval()
+    }
+}
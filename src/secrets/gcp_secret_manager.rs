@@ -0,0 +1,93 @@
+use std::{collections::HashMap, time::Duration};
+
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+
+use super::{secret_field, send_json, split_key, BackendOptions};
+use crate::{
+    config::{ProxyConfig, SecretBackend},
+    gcp::{GcpAuthConfig, Scope},
+    http::HttpClient,
+    signal,
+};
+
+const BASE_URL: &str = "https://secretmanager.googleapis.com/v1";
+
+fn default_version() -> String {
+    "latest".to_owned()
+}
+
+/// Retrieves secrets from GCP Secret Manager. Secret keys are either the name of a secret, or
+/// formatted as `<name>.<field>` to retrieve a field of a secret stored as a JSON object.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct GcpSecretManagerBackend {
+    /// The ID of the project the secrets are in.
+    project: String,
+
+    /// The version of the secrets to retrieve.
+    #[serde(default = "default_version")]
+    version: String,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    #[serde(flatten)]
+    options: BackendOptions,
+}
+
+impl GcpSecretManagerBackend {
+    async fn retrieve_secrets(&self, keys: Vec<String>) -> crate::Result<HashMap<String, String>> {
+        let auth = self.auth.build(Scope::CloudPlatform).await?;
+        let client = HttpClient::new(None, &ProxyConfig::from_env())?;
+
+        // Each secret is only retrieved once, however many of its fields are used.
+        let mut values = HashMap::new();
+        let mut secrets = HashMap::new();
+        for key in keys {
+            let (name, field) = split_key(&key);
+            if !values.contains_key(name) {
+                let uri = format!(
+                    "{}/projects/{}/secrets/{}/versions/{}:access",
+                    BASE_URL, self.project, name, self.version
+                );
+                let mut request = Request::get(uri).body(Body::empty())?;
+                auth.apply(&mut request);
+                let reply = send_json(&client, request)
+                    .await
+                    .map_err(|error| format!("couldn't access secret '{}': {}", name, error))?;
+                let data = reply
+                    .pointer("/payload/data")
+                    .and_then(serde_json::Value::as_str)
+                    .ok_or_else(|| format!("secret '{}' has no payload", name))?;
+                let value = String::from_utf8(base64::decode(data)?)
+                    .map_err(|_| format!("secret '{}' is not valid UTF-8", name))?;
+                values.insert(name.to_owned(), value);
+            }
+            let value = secret_field(&values[name], field)
+                .map_err(|error| format!("invalid secret '{}': {}", name, error))?;
+            secrets.insert(key.clone(), value);
+        }
+        Ok(secrets)
+    }
+}
+
+#[typetag::serde(name = "gcp_secret_manager")]
+impl SecretBackend for GcpSecretManagerBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        self.options
+            .block_on(self.retrieve_secrets(secret_keys), signal_rx)
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.options.cache_ttl()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.options.refresh_interval()
+    }
+}
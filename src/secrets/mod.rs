@@ -0,0 +1,172 @@
+//! Secret backends retrieving secrets from external secret stores.
+//!
+//! Unlike the `exec` backend, these retrieve secrets natively, and can cache and refresh them so
+//! rotated secrets are picked up without restarting Vector.
+
+#[cfg(feature = "secrets-aws_secrets_manager")]
+mod aws_secrets_manager;
+#[cfg(feature = "secrets-azure_key_vault")]
+mod azure_key_vault;
+#[cfg(feature = "secrets-gcp_secret_manager")]
+mod gcp_secret_manager;
+mod vault;
+
+use std::{future::Future, time::Duration};
+
+use futures::executor;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+
+use crate::{http::HttpClient, signal};
+
+const fn default_timeout_secs() -> u64 {
+    5
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+/// Options shared by all native secret backends.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct BackendOptions {
+    /// How long to wait for the secrets to be retrieved, in seconds.
+    #[serde(default = "default_timeout_secs")]
+    timeout: u64,
+
+    /// How long retrieved secrets are reused when the configuration is reloaded, in seconds. Set
+    /// to 0 to always retrieve secrets again.
+    #[serde(default = "default_cache_ttl_secs")]
+    cache_ttl_secs: u64,
+
+    /// How often to retrieve secrets again, in seconds, reloading the configuration once any of
+    /// them has changed.
+    #[serde(default)]
+    refresh_interval_secs: Option<u64>,
+}
+
+impl BackendOptions {
+    fn cache_ttl(&self) -> Option<Duration> {
+        (self.cache_ttl_secs > 0).then(|| Duration::from_secs(self.cache_ttl_secs))
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Blocks on `future` retrieving secrets, unless it times out or Vector is shut down first.
+    fn block_on<T>(
+        &self,
+        future: impl Future<Output = crate::Result<T>>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<T> {
+        let timeout = Duration::from_secs(self.timeout);
+        executor::block_on(async {
+            tokio::select! {
+                biased;
+                Ok(signal::SignalTo::Shutdown | signal::SignalTo::Quit) = signal_rx.recv() => {
+                    Err("Secret retrieval was interrupted.".into())
+                }
+                result = tokio::time::timeout(timeout, future) => {
+                    result.map_err(|_| "Secret retrieval timed-out.")?
+                }
+            }
+        })
+    }
+}
+
+/// Sends a request, returning the JSON reply.
+async fn send_json(
+    client: &HttpClient,
+    request: http::Request<Body>,
+) -> crate::Result<serde_json::Value> {
+    let (parts, body) = client.send(request).await?.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    if !parts.status.is_success() {
+        return Err(format!(
+            "unexpected status {}: {}",
+            parts.status,
+            String::from_utf8_lossy(&body)
+        )
+        .into());
+    }
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Returns the field of a secret stored as a JSON object, or the whole secret if there's no field.
+#[cfg(any(
+    feature = "secrets-aws_secrets_manager",
+    feature = "secrets-azure_key_vault",
+    feature = "secrets-gcp_secret_manager"
+))]
+fn secret_field(secret: &str, field: Option<&str>) -> crate::Result<String> {
+    match field {
+        None => Ok(secret.to_owned()),
+        Some(field) => {
+            let object: serde_json::Value = serde_json::from_str(secret)
+                .map_err(|error| format!("secret is not a JSON object: {}", error))?;
+            object
+                .get(field)
+                .map(value_to_string)
+                .ok_or_else(|| format!("secret has no field '{}'", field).into())
+        }
+    }
+}
+
+/// Returns a JSON value as a secret, which is the string itself for JSON strings.
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Splits a secret key into the name of the secret and the field to retrieve from it, which are
+/// separated by the last `.`.
+fn split_key(key: &str) -> (&str, Option<&str>) {
+    match key.rsplit_once('.') {
+        Some((name, field)) => (name, Some(field)),
+        None => (key, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_keys() {
+        assert_eq!(split_key("db_password"), ("db_password", None));
+        assert_eq!(split_key("prod/db.password"), ("prod/db", Some("password")));
+        assert_eq!(split_key("kv/a.b.password"), ("kv/a.b", Some("password")));
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "secrets-aws_secrets_manager",
+        feature = "secrets-azure_key_vault",
+        feature = "secrets-gcp_secret_manager"
+    ))]
+    fn extracts_secret_fields() {
+        let secret = r#"{"username": "vector", "port": 5432}"#;
+        assert_eq!(secret_field(secret, None).unwrap(), secret);
+        assert_eq!(secret_field(secret, Some("username")).unwrap(), "vector");
+        assert_eq!(secret_field(secret, Some("port")).unwrap(), "5432");
+        assert!(secret_field(secret, Some("password")).is_err());
+        assert!(secret_field("plaintext", Some("username")).is_err());
+    }
+
+    #[test]
+    fn disables_caching_and_refreshing() {
+        let options: BackendOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.cache_ttl(), Some(Duration::from_secs(300)));
+        assert_eq!(options.refresh_interval(), None);
+
+        let options: BackendOptions =
+            serde_json::from_str(r#"{"cache_ttl_secs": 0, "refresh_interval_secs": 60}"#).unwrap();
+        assert_eq!(options.cache_ttl(), None);
+        assert_eq!(options.refresh_interval(), Some(Duration::from_secs(60)));
+    }
+}
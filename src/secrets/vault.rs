@@ -0,0 +1,230 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{send_json, split_key, value_to_string, BackendOptions};
+use crate::{
+    config::{ProxyConfig, SecretBackend},
+    http::HttpClient,
+    signal,
+    tls::{TlsConfig, TlsSettings},
+};
+
+const TOKEN_HEADER: &str = "X-Vault-Token";
+const NAMESPACE_HEADER: &str = "X-Vault-Namespace";
+
+const fn default_kv_version() -> u8 {
+    2
+}
+
+fn default_mount() -> String {
+    "secret".to_owned()
+}
+
+fn default_kubernetes_mount() -> String {
+    "kubernetes".to_owned()
+}
+
+fn default_kubernetes_token_path() -> PathBuf {
+    "/var/run/secrets/kubernetes.io/serviceaccount/token".into()
+}
+
+/// Retrieves secrets from a HashiCorp Vault KV secrets engine. Secret keys are formatted as
+/// `<path>.<field>`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct VaultBackend {
+    /// The address of the Vault server, such as `https://vault.example.com:8200`.
+    address: String,
+
+    /// The Vault Enterprise namespace the secrets are in.
+    namespace: Option<String>,
+
+    auth: VaultAuth,
+
+    /// The path the KV secrets engine is mounted at.
+    #[serde(default = "default_mount")]
+    mount: String,
+
+    /// The version of the KV secrets engine, either 1 or 2.
+    #[serde(default = "default_kv_version")]
+    kv_version: u8,
+
+    tls: Option<TlsConfig>,
+
+    #[serde(flatten)]
+    options: BackendOptions,
+}
+
+/// How to authenticate with Vault.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+enum VaultAuth {
+    /// Authenticate with a token.
+    Token { token: String },
+
+    /// Authenticate with the Kubernetes auth method, using the token of the pod's service account.
+    Kubernetes {
+        role: String,
+        #[serde(default = "default_kubernetes_mount")]
+        mount: String,
+        #[serde(default = "default_kubernetes_token_path")]
+        token_path: PathBuf,
+    },
+}
+
+impl VaultBackend {
+    fn url(&self, path: &str) -> String {
+        format!("{}/v1/{}", self.address.trim_end_matches('/'), path)
+    }
+
+    fn request(&self, method: http::Method, path: &str) -> http::request::Builder {
+        let mut builder = Request::builder().method(method).uri(self.url(path));
+        if let Some(namespace) = &self.namespace {
+            builder = builder.header(NAMESPACE_HEADER, namespace);
+        }
+        builder
+    }
+
+    /// Returns a token to read secrets with, logging in if needed.
+    async fn token(&self, client: &HttpClient) -> crate::Result<String> {
+        match &self.auth {
+            VaultAuth::Token { token } => Ok(token.clone()),
+            VaultAuth::Kubernetes {
+                role,
+                mount,
+                token_path,
+            } => {
+                let jwt = tokio::fs::read_to_string(token_path)
+                    .await
+                    .map_err(|error| {
+                        format!(
+                            "couldn't read service account token {:?}: {}",
+                            token_path, error
+                        )
+                    })?;
+                let body = json!({ "role": role, "jwt": jwt.trim() });
+                let request = self
+                    .request(http::Method::POST, &format!("auth/{}/login", mount))
+                    .body(Body::from(serde_json::to_vec(&body)?))?;
+                let reply = send_json(client, request).await?;
+                reply
+                    .pointer("/auth/client_token")
+                    .and_then(serde_json::Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .ok_or_else(|| "Vault login returned no client token".into())
+            }
+        }
+    }
+
+    /// Reads the data of the secret at `path`.
+    async fn read(
+        &self,
+        client: &HttpClient,
+        token: &str,
+        path: &str,
+    ) -> crate::Result<serde_json::Map<String, serde_json::Value>> {
+        let (api_path, pointer) = match self.kv_version {
+            1 => (format!("{}/{}", self.mount, path), "/data"),
+            _ => (format!("{}/data/{}", self.mount, path), "/data/data"),
+        };
+        let request = self
+            .request(http::Method::GET, &api_path)
+            .header(TOKEN_HEADER, token)
+            .body(Body::empty())?;
+        let mut reply = send_json(client, request)
+            .await
+            .map_err(|error| format!("couldn't read secret '{}': {}", path, error))?;
+        match reply.pointer_mut(pointer).map(serde_json::Value::take) {
+            Some(serde_json::Value::Object(data)) => Ok(data),
+            _ => Err(format!("secret '{}' has no data", path).into()),
+        }
+    }
+
+    async fn retrieve_secrets(&self, keys: Vec<String>) -> crate::Result<HashMap<String, String>> {
+        if !matches!(self.kv_version, 1 | 2) {
+            return Err(format!("unsupported KV version {}", self.kv_version).into());
+        }
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, &ProxyConfig::from_env())?;
+        let token = self.token(&client).await?;
+
+        // Each path is only read once, however many of its fields are used.
+        let mut data = HashMap::new();
+        let mut secrets = HashMap::new();
+        for key in keys {
+            let (path, field) = match split_key(&key) {
+                (path, Some(field)) => (path, field),
+                (_, None) => {
+                    return Err(format!(
+                        "secret key '{}' should be formatted as `<path>.<field>`",
+                        key
+                    )
+                    .into())
+                }
+            };
+            if !data.contains_key(path) {
+                let secret = self.read(&client, &token, path).await?;
+                data.insert(path.to_owned(), secret);
+            }
+            let value = data[path]
+                .get(field)
+                .ok_or_else(|| format!("secret '{}' has no field '{}'", path, field))?;
+            secrets.insert(key.clone(), value_to_string(value));
+        }
+        Ok(secrets)
+    }
+}
+
+#[typetag::serde(name = "vault")]
+impl SecretBackend for VaultBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        self.options
+            .block_on(self.retrieve_secrets(secret_keys), signal_rx)
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.options.cache_ttl()
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        self.options.refresh_interval()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kubernetes_auth() {
+        let backend: VaultBackend = toml::from_str(
+            r#"
+            address = "https://vault.example.com:8200/"
+            auth.strategy = "kubernetes"
+            auth.role = "vector"
+            refresh_interval_secs = 60
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(backend.mount, "secret");
+        assert_eq!(backend.kv_version, 2);
+        assert_eq!(
+            backend.url("secret/data/db"),
+            "https://vault.example.com:8200/v1/secret/data/db"
+        );
+        assert!(matches!(
+            backend.auth,
+            VaultAuth::Kubernetes { ref mount, ref token_path, .. }
+                if mount == "kubernetes" && token_path == &default_kubernetes_token_path()
+        ));
+        assert_eq!(backend.refresh_interval(), Some(Duration::from_secs(60)));
+    }
+}
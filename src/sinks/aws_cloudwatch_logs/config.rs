@@ -6,10 +6,10 @@ use tower::ServiceBuilder;
 
 use crate::{
     aws::{
-        create_client, create_smithy_client, resolve_region, AwsAuthentication, ClientBuilder,
-        RegionOrEndpoint,
+        create_client, create_smithy_client, resolve_region, AwsAuthentication, RegionOrEndpoint,
     },
     codecs::Encoder,
+    common::cloudwatch_logs::CloudwatchLogsClientBuilder,
     config::{
         log_schema, AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig,
         SinkContext,
@@ -32,22 +32,6 @@ use crate::{
     tls::TlsConfig,
 };
 
-pub struct CloudwatchLogsClientBuilder;
-
-impl ClientBuilder for CloudwatchLogsClientBuilder {
-    type Config = aws_sdk_cloudwatchlogs::config::Config;
-    type Client = aws_sdk_cloudwatchlogs::client::Client;
-    type DefaultMiddleware = aws_sdk_cloudwatchlogs::middleware::DefaultMiddleware;
-
-    fn default_middleware() -> Self::DefaultMiddleware {
-        aws_sdk_cloudwatchlogs::middleware::DefaultMiddleware::new()
-    }
-
-    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
-        aws_sdk_cloudwatchlogs::client::Client::with_config(client, config.into())
-    }
-}
-
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CloudwatchLogsSinkConfig {
@@ -8,8 +8,9 @@ use tower::ServiceBuilder;
 
 use super::service::KinesisResponse;
 use crate::{
-    aws::{create_client, is_retriable_error, AwsAuthentication, ClientBuilder, RegionOrEndpoint},
+    aws::{create_client, is_retriable_error, AwsAuthentication, RegionOrEndpoint},
     codecs::Encoder,
+    common::kinesis::KinesisClientBuilder,
     config::{AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext},
     sinks::{
         aws_kinesis_streams::{
@@ -43,22 +44,6 @@ enum HealthcheckError {
     NoMatchingStreamName { stream_name: String },
 }
 
-pub struct KinesisClientBuilder;
-
-impl ClientBuilder for KinesisClientBuilder {
-    type Config = aws_sdk_kinesis::config::Config;
-    type Client = aws_sdk_kinesis::client::Client;
-    type DefaultMiddleware = aws_sdk_kinesis::middleware::DefaultMiddleware;
-
-    fn default_middleware() -> Self::DefaultMiddleware {
-        aws_sdk_kinesis::middleware::DefaultMiddleware::new()
-    }
-
-    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
-        aws_sdk_kinesis::client::Client::with_config(client, config.into())
-    }
-}
-
 #[derive(Clone, Copy, Debug, Default)]
 pub struct KinesisDefaultBatchSettings;
 
@@ -8,13 +8,11 @@ use tokio::time::{sleep, Duration};
 use super::*;
 use crate::{
     aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::kinesis::KinesisClientBuilder,
     config::{ProxyConfig, SinkConfig, SinkContext},
-    sinks::{
-        aws_kinesis_streams::config::KinesisClientBuilder,
-        util::{
-            encoding::{EncodingConfig, StandardEncodings},
-            BatchConfig, Compression,
-        },
+    sinks::util::{
+        encoding::{EncodingConfig, StandardEncodings},
+        BatchConfig, Compression,
     },
     test_util::{
         components::{run_and_assert_sink_compliance, AWS_SINK_TAGS},
@@ -33,7 +33,7 @@ pub(crate) struct SqsSink {
 
 impl SqsSink {
     pub fn new(config: SqsSinkConfig, cx: SinkContext, client: SqsClient) -> crate::Result<Self> {
-        let request = config.request;
+        let request = config.request.clone();
         Ok(SqsSink {
             acker: cx.acker(),
             request_builder: SqsRequestBuilder::new(config)?,
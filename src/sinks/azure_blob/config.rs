@@ -143,12 +143,19 @@ impl AzureBlobSinkConfig {
         let framer = match (framer, &serializer) {
             (Some(framer), _) => framer,
             (None, Serializer::Json(_)) => CharacterDelimitedEncoder::new(b',').into(),
-            (None, Serializer::Avro(_) | Serializer::Native(_)) => {
-                LengthDelimitedEncoder::new().into()
-            }
             (
                 None,
-                Serializer::Logfmt(_)
+                Serializer::Avro(_)
+                | Serializer::Bson(_)
+                | Serializer::Cbor(_)
+                | Serializer::Native(_)
+                | Serializer::Protobuf(_),
+            ) => LengthDelimitedEncoder::new().into(),
+            (
+                None,
+                Serializer::Cef(_)
+                | Serializer::Leef(_)
+                | Serializer::Logfmt(_)
                 | Serializer::NativeJson(_)
                 | Serializer::RawMessage(_)
                 | Serializer::Text(_),
@@ -67,15 +67,22 @@ impl SinkConfig for ConsoleSinkConfig {
             (Some(framer), _) => framer,
             (
                 None,
-                Serializer::Text(_)
+                Serializer::Cef(_)
+                | Serializer::Leef(_)
+                | Serializer::Text(_)
                 | Serializer::Json(_)
                 | Serializer::Logfmt(_)
                 | Serializer::NativeJson(_)
                 | Serializer::RawMessage(_),
             ) => NewlineDelimitedEncoder::new().into(),
-            (None, Serializer::Avro(_) | Serializer::Native(_)) => {
-                LengthDelimitedEncoder::new().into()
-            }
+            (
+                None,
+                Serializer::Avro(_)
+                | Serializer::Bson(_)
+                | Serializer::Cbor(_)
+                | Serializer::Native(_)
+                | Serializer::Protobuf(_),
+            ) => LengthDelimitedEncoder::new().into(),
         };
         let encoder = Encoder::<Framer>::new(framer, serializer);
 
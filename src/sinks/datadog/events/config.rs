@@ -11,13 +11,14 @@ use crate::{
         datadog::{
             events::{
                 service::{DatadogEventsResponse, DatadogEventsService},
-                sink::DatadogEventsSink,
+                sink::{DatadogEventsSink, EventFieldTemplates},
             },
             get_api_base_endpoint, get_api_validate_endpoint, healthcheck, Region,
         },
         util::{http::HttpStatusRetryLogic, ServiceBuilderExt, TowerRequestConfig},
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::{MaybeTlsSettings, TlsEnableableConfig},
 };
 
@@ -30,6 +31,14 @@ pub struct DatadogEventsConfig {
     pub site: Option<String>,
     pub default_api_key: String,
 
+    // Optional templates used to build the event payload from arbitrary log events.
+    // When set, these take precedence over the corresponding fields on the event.
+    pub title: Option<Template>,
+    pub text: Option<Template>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Template>,
+    pub alert_type: Option<Template>,
+
     pub(super) tls: Option<TlsEnableableConfig>,
 
     #[serde(default)]
@@ -61,6 +70,15 @@ impl DatadogEventsConfig {
         http::Uri::try_from(format!("{}/api/v1/events", api_base_endpoint)).expect("URI not valid")
     }
 
+    fn field_templates(&self) -> EventFieldTemplates {
+        EventFieldTemplates {
+            title: self.title.clone(),
+            text: self.text.clone(),
+            tags: self.tags.clone(),
+            alert_type: self.alert_type.clone(),
+        }
+    }
+
     fn build_client(&self, proxy: &ProxyConfig) -> crate::Result<HttpClient> {
         let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
         let client = HttpClient::new(tls, proxy)?;
@@ -80,7 +98,7 @@ impl DatadogEventsConfig {
             client,
         );
 
-        let request_opts = self.request;
+        let request_opts = &self.request;
         let request_settings = request_opts.unwrap_with(&TowerRequestConfig::default());
         let retry_logic = HttpStatusRetryLogic::new(|req: &DatadogEventsResponse| req.http_status);
 
@@ -91,6 +109,7 @@ impl DatadogEventsConfig {
         let sink = DatadogEventsSink {
             service,
             acker: cx.acker(),
+            templates: self.field_templates(),
         };
 
         Ok(VectorSink::from_event_streamsink(sink))
@@ -1,24 +1,90 @@
 use std::{fmt, num::NonZeroUsize};
 
 use async_trait::async_trait;
-use futures::{stream::BoxStream, StreamExt};
+use futures::{future, stream::BoxStream, StreamExt};
 use tower::Service;
 use vector_buffers::Acker;
 use vector_core::stream::DriverResponse;
 
 use crate::{
     config::log_schema,
-    event::{Event, LogEvent},
-    internal_events::ParserMissingFieldError,
+    event::{Event, LogEvent, Value},
+    internal_events::{ParserMissingFieldError, TemplateRenderingError},
     sinks::{
         datadog::events::request_builder::{DatadogEventsRequest, DatadogEventsRequestBuilder},
         util::{SinkBuilderExt, StreamSink},
     },
+    template::Template,
 };
 
+/// Alert types accepted by the Datadog Events API.
+const ALERT_TYPES: [&str; 4] = ["error", "warning", "info", "success"];
+
 pub struct DatadogEventsSink<S> {
     pub(super) service: S,
     pub acker: Acker,
+    pub(super) templates: EventFieldTemplates,
+}
+
+/// Templates used to populate the Datadog event fields from the incoming log event.
+#[derive(Clone, Debug, Default)]
+pub struct EventFieldTemplates {
+    pub title: Option<Template>,
+    pub text: Option<Template>,
+    pub tags: Vec<Template>,
+    pub alert_type: Option<Template>,
+}
+
+impl EventFieldTemplates {
+    fn apply(&self, mut log: LogEvent) -> Option<LogEvent> {
+        if let Some(template) = &self.title {
+            let title = render_field(template, &log, "title")?;
+            log.insert("title", title);
+        }
+
+        if let Some(template) = &self.text {
+            let text = render_field(template, &log, "text")?;
+            log.insert("text", text);
+        }
+
+        if !self.tags.is_empty() {
+            let tags = self
+                .tags
+                .iter()
+                .map(|template| render_field(template, &log, "tags").map(Value::from))
+                .collect::<Option<Vec<_>>>()?;
+            log.insert("tags", tags);
+        }
+
+        if let Some(template) = &self.alert_type {
+            let alert_type = render_field(template, &log, "alert_type")?.to_lowercase();
+            if ALERT_TYPES.contains(&alert_type.as_str()) {
+                log.insert("alert_type", alert_type);
+            } else {
+                warn!(
+                    message = "Unsupported alert_type, the Datadog default will be used.",
+                    %alert_type,
+                    internal_log_rate_secs = 30,
+                );
+                log.remove("alert_type");
+            }
+        }
+
+        Some(log)
+    }
+}
+
+fn render_field(template: &Template, log: &LogEvent, field: &'static str) -> Option<String> {
+    template
+        .render_string(log)
+        .map_err(|error| {
+            emit!(TemplateRenderingError {
+                error,
+                field: Some(field),
+                drop_event: true,
+            });
+        })
+        .ok()
 }
 
 impl<S> DatadogEventsSink<S>
@@ -30,12 +96,14 @@ where
 {
     async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let concurrency_limit = NonZeroUsize::new(50);
+        let templates = self.templates;
 
         let driver = input
             .map(|event| {
                 // Panic: This sink only accepts Logs, so this should never panic
                 event.into_log()
             })
+            .filter_map(move |log| future::ready(templates.apply(log)))
             .filter_map(ensure_required_fields)
             .request_builder(concurrency_limit, DatadogEventsRequestBuilder::new())
             .filter_map(|request| async move {
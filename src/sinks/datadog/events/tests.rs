@@ -180,3 +180,49 @@ async fn filter_out_fields() {
         assert!(json.get("invalid").is_none());
     }
 }
+
+#[tokio::test]
+async fn templated_fields() {
+    let (mut config, cx) = load_sink::<DatadogEventsConfig>(indoc! {r#"
+            default_api_key = "atoken"
+            title = "Deployment of {{ service }}"
+            text = "{{ message }}"
+            tags = ["service:{{ service }}", "env:prod"]
+            alert_type = "{{ level }}"
+        "#})
+    .unwrap();
+
+    let addr = next_addr();
+    let endpoint = format!("http://{}", addr);
+    config.endpoint = Some(endpoint.clone());
+
+    let (sink, _) = config.build(cx).await.unwrap();
+
+    let (rx, _trigger, server) = build_test_server_status(addr, StatusCode::OK);
+    tokio::spawn(server);
+
+    let (expected, events) = random_lines_with_stream(100, 10, None);
+    let events = events.map(|mut events| {
+        events.for_each_log(|log| {
+            log.insert("service", "checkout");
+            log.insert("level", "WARNING");
+        });
+        events
+    });
+
+    components::run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    let output = rx.take(expected.len()).collect::<Vec<_>>().await;
+
+    for (i, val) in output.iter().enumerate() {
+        let json: serde_json::Value = serde_json::from_slice(&val.1[..]).unwrap();
+
+        assert_eq!(json["title"], "Deployment of checkout");
+        assert_eq!(json["text"], expected[i].as_str());
+        assert_eq!(
+            json["tags"],
+            serde_json::json!(["service:checkout", "env:prod"])
+        );
+        assert_eq!(json["alert_type"], "warning");
+        assert!(json.get("service").is_none());
+    }
+}
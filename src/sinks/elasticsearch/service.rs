@@ -144,6 +144,16 @@ impl DriverResponse for ElasticsearchResponse {
             output: None,
         }
     }
+
+    fn error_class(&self) -> Option<&'static str> {
+        let status = self.http_response.status();
+        match self.event_status {
+            EventStatus::Delivered => None,
+            _ if status.is_success() => Some("bulk_item_failures"),
+            _ if status.is_server_error() => Some("http_server_error"),
+            _ => Some("http_client_error"),
+        }
+    }
 }
 
 impl Service<ElasticsearchRequest> for ElasticsearchService {
@@ -283,12 +283,19 @@ impl RequestSettings {
         let framer = match (framer, &serializer) {
             (Some(framer), _) => framer,
             (None, Serializer::Json(_)) => CharacterDelimitedEncoder::new(b',').into(),
-            (None, Serializer::Avro(_) | Serializer::Native(_)) => {
-                LengthDelimitedEncoder::new().into()
-            }
             (
                 None,
-                Serializer::Logfmt(_)
+                Serializer::Avro(_)
+                | Serializer::Bson(_)
+                | Serializer::Cbor(_)
+                | Serializer::Native(_)
+                | Serializer::Protobuf(_),
+            ) => LengthDelimitedEncoder::new().into(),
+            (
+                None,
+                Serializer::Cef(_)
+                | Serializer::Leef(_)
+                | Serializer::Logfmt(_)
                 | Serializer::NativeJson(_)
                 | Serializer::RawMessage(_)
                 | Serializer::Text(_),
@@ -445,6 +452,16 @@ mod tests {
         let req = build_request(None, false, Compression::gzip_default());
         assert_eq!(req.key, "key/date.log.gz".to_string());
 
+        let req = build_request(None, false, Compression::zstd_default());
+        assert_eq!(req.key, "key/date.log.zst".to_string());
+        assert_eq!(
+            req.settings.content_encoding,
+            Some(HeaderValue::from_static("zstd"))
+        );
+
+        let req = build_request(None, false, Compression::Snappy);
+        assert_eq!(req.key, "key/date.log.sz".to_string());
+
         let req = build_request(None, true, Compression::gzip_default());
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
@@ -6,7 +6,6 @@ use codecs::encoding::{
     JsonSerializerConfig, NewlineDelimitedEncoder, NewlineDelimitedEncoderConfig, Serializer,
     SerializerConfig, TextSerializerConfig,
 };
-use flate2::write::{GzEncoder, ZlibEncoder};
 use futures::{future, FutureExt, SinkExt};
 use http::{
     header::{self, HeaderName, HeaderValue},
@@ -32,7 +31,7 @@ use crate::{
             Transformer,
         },
         http::{BatchedHttpSink, HttpEventEncoder, RequestConfig},
-        BatchConfig, Buffer, Compression, RealtimeSizeBasedDefaultBatchSettings,
+        BatchConfig, Buffer, Compression, Compressor, RealtimeSizeBasedDefaultBatchSettings,
         TowerRequestConfig, UriSerde,
     },
     tls::{TlsConfig, TlsSettings},
@@ -297,6 +296,8 @@ impl util::http::HttpSink for HttpSink {
             use Serializer::*;
             match (self.encoder.serializer(), self.encoder.framer()) {
                 (RawMessage(_) | Text(_), _) => Some("text/plain"),
+                (Cbor(_), _) => Some("application/cbor"),
+                (Protobuf(_), _) => Some("application/x-protobuf"),
                 (Json(_), NewlineDelimited(_)) => {
                     if !body.is_empty() {
                         // Remove trailing newline for backwards-compatibility
@@ -330,24 +331,14 @@ impl util::http::HttpSink for HttpSink {
             builder = builder.header("Content-Type", content_type);
         }
 
-        match self.compression {
-            Compression::Gzip(level) => {
-                builder = builder.header("Content-Encoding", "gzip");
+        if let Some(content_encoding) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", content_encoding);
 
-                let buffer = BytesMut::new();
-                let mut w = GzEncoder::new(buffer.writer(), level);
-                w.write_all(&body).expect("Writing to Vec can't fail");
-                body = w.finish().expect("Writing to Vec can't fail").into_inner();
-            }
-            Compression::Zlib(level) => {
-                builder = builder.header("Content-Encoding", "deflate");
-
-                let buffer = BytesMut::new();
-                let mut w = ZlibEncoder::new(buffer.writer(), level);
-                w.write_all(&body).expect("Writing to Vec can't fail");
-                body = w.finish().expect("Writing to Vec can't fail").into_inner();
-            }
-            Compression::None => {}
+            let mut compressor = Compressor::from(self.compression);
+            compressor
+                .write_all(&body)
+                .expect("Writing to Vec can't fail");
+            body = compressor.finish().expect("Writing to Vec can't fail");
         }
 
         for (header, value) in self.request.headers.iter() {
@@ -738,6 +729,59 @@ mod tests {
         assert_eq!(input_lines, output_lines);
     }
 
+    #[tokio::test]
+    async fn zstd_compression() {
+        let num_lines = 1000;
+
+        let in_addr = next_addr();
+
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        compression = { algorithm = "zstd", level = 19, long_window = 27 }
+        encoding = "json"
+    "#
+        .replace("$IN_ADDR", &in_addr.to_string());
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+
+        let cx = SinkContext::new_test();
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input_lines, events) = random_lines_with_stream(100, num_lines, Some(batch));
+        let pump = sink.run(events);
+
+        tokio::spawn(server);
+
+        pump.await.unwrap();
+        drop(trigger);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let output_lines = rx
+            .flat_map(|(parts, body)| {
+                assert_eq!(
+                    Some("zstd"),
+                    parts
+                        .headers
+                        .get("Content-Encoding")
+                        .map(|value| value.to_str().unwrap())
+                );
+
+                let mut decoder = zstd::stream::read::Decoder::new(body.reader()).unwrap();
+                decoder.window_log_max(27).unwrap();
+                let lines: Vec<serde_json::Value> = serde_json::from_reader(decoder).unwrap();
+                stream::iter(lines)
+            })
+            .map(|line| line.get("message").unwrap().as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(num_lines, output_lines.len());
+        assert_eq!(input_lines, output_lines);
+    }
+
     async fn get_received(
         rx: mpsc::Receiver<(Parts, Bytes)>,
         assert_parts: impl Fn(Parts),
@@ -14,7 +14,7 @@ use crate::{
             encoding::{
                 EncodingConfig, EncodingConfigAdapter, StandardEncodings, StandardEncodingsMigrator,
             },
-            BatchConfig, NoDefaultsBatchSettings,
+            BatchConfig, ConcurrencyConfig, NoDefaultsBatchSettings,
         },
         Healthcheck, VectorSink,
     },
@@ -45,6 +45,8 @@ pub(crate) struct KafkaSinkConfig {
     pub librdkafka_options: HashMap<String, String>,
     #[serde(alias = "headers_field")] // accidentally released as `headers_field` in 0.18
     pub headers_key: Option<String>,
+    #[serde(default)]
+    pub request: ConcurrencyConfig,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -175,6 +177,7 @@ impl GenerateConfig for KafkaSinkConfig {
             message_timeout_ms: default_message_timeout_ms(),
             librdkafka_options: Default::default(),
             headers_key: None,
+            request: Default::default(),
             acknowledgements: Default::default(),
         })
         .unwrap()
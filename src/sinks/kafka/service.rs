@@ -3,7 +3,7 @@ use std::task::{Context, Poll};
 use bytes::Bytes;
 use futures::future::BoxFuture;
 use rdkafka::{
-    error::KafkaError,
+    error::{KafkaError, RDKafkaErrorCode},
     message::OwnedHeaders,
     producer::{FutureProducer, FutureRecord},
     util::Timeout,
@@ -18,6 +18,7 @@ use vector_core::{
 use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
     kafka::KafkaStatisticsContext,
+    sinks::util::retries::RetryLogic,
 };
 
 pub struct KafkaRequest {
@@ -65,6 +66,28 @@ impl Finalizable for KafkaRequest {
     }
 }
 
+/// Classifies the errors of messages rdkafka couldn't queue or deliver in time as back pressure,
+/// so adaptive concurrency lowers the number of messages in flight. rdkafka retries sending
+/// messages itself, so they aren't retried again.
+#[derive(Debug, Default, Clone)]
+pub struct KafkaRetryLogic;
+
+impl RetryLogic for KafkaRetryLogic {
+    type Error = KafkaError;
+    type Response = KafkaResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        matches!(
+            error.rdkafka_error_code(),
+            Some(
+                RDKafkaErrorCode::QueueFull
+                    | RDKafkaErrorCode::MessageTimedOut
+                    | RDKafkaErrorCode::RequestTimedOut
+            )
+        )
+    }
+}
+
 pub struct KafkaService {
     kafka_producer: FutureProducer<KafkaStatisticsContext>,
 }
@@ -10,7 +10,7 @@ use rdkafka::{
 };
 use snafu::{ResultExt, Snafu};
 use tokio::time::Duration;
-use tower::limit::ConcurrencyLimit;
+use tower::ServiceBuilder;
 use vector_core::{buffers::Acker, config::log_schema};
 
 use super::config::{KafkaRole, KafkaSinkConfig};
@@ -20,10 +20,14 @@ use crate::{
     kafka::KafkaStatisticsContext,
     sinks::{
         kafka::{
-            config::QUEUED_MIN_MESSAGES, request_builder::KafkaRequestBuilder,
-            service::KafkaService,
+            config::QUEUED_MIN_MESSAGES,
+            request_builder::KafkaRequestBuilder,
+            service::{KafkaRetryLogic, KafkaService},
+        },
+        util::{
+            builder::SinkBuilderExt, encoding::Transformer, Concurrency, ConcurrencyConfig,
+            ServiceBuilderExt, StreamSink,
         },
-        util::{builder::SinkBuilderExt, encoding::Transformer, StreamSink},
     },
     template::{Template, TemplateParseError},
 };
@@ -41,6 +45,7 @@ pub struct KafkaSink {
     encoder: Encoder<()>,
     acker: Acker,
     service: KafkaService,
+    request: ConcurrencyConfig,
     topic: Template,
     key_field: Option<String>,
     headers_key: Option<String>,
@@ -69,6 +74,7 @@ impl KafkaSink {
             encoder,
             acker,
             service: KafkaService::new(producer),
+            request: config.request,
             topic: Template::try_from(config.topic).context(TopicTemplateSnafu)?,
             key_field: config.key_field,
         })
@@ -76,7 +82,13 @@ impl KafkaSink {
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
-        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
+        let service = ServiceBuilder::new()
+            .concurrency(
+                self.request,
+                Concurrency::Fixed(QUEUED_MIN_MESSAGES as usize),
+                KafkaRetryLogic,
+            )
+            .service(self.service);
         let mut request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
             headers_key: self.headers_key,
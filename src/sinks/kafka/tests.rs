@@ -70,6 +70,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: None,
+            request: Default::default(),
             acknowledgements: Default::default(),
         };
         self::sink::healthcheck(config).await.unwrap();
@@ -125,6 +126,7 @@ mod integration_test {
             batch,
             librdkafka_options,
             headers_key: None,
+            request: Default::default(),
             acknowledgements: Default::default(),
         };
         let (acker, _ack_counter) = Acker::basic();
@@ -257,6 +259,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: Some(headers_key.clone()),
+            request: Default::default(),
             acknowledgements: Default::default(),
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
@@ -17,7 +17,11 @@ pub mod aws_s3;
 pub mod aws_sqs;
 #[cfg(feature = "sinks-azure_blob")]
 pub mod azure_blob;
-#[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
+#[cfg(any(
+    feature = "sinks-azure_blob",
+    feature = "sinks-datadog_archives",
+    feature = "providers-azure_blob"
+))]
 pub mod azure_common;
 #[cfg(feature = "sinks-azure_monitor_logs")]
 pub mod azure_monitor_logs;
@@ -64,12 +68,16 @@ pub mod nats;
 pub mod new_relic;
 #[cfg(feature = "sinks-new_relic_logs")]
 pub mod new_relic_logs;
+#[cfg(feature = "sinks-pagerduty")]
+pub mod pagerduty;
 #[cfg(feature = "sinks-papertrail")]
 pub mod papertrail;
 #[cfg(feature = "sinks-prometheus")]
 pub mod prometheus;
 #[cfg(feature = "sinks-pulsar")]
 pub mod pulsar;
+#[cfg(feature = "sinks-quickwit")]
+pub mod quickwit;
 #[cfg(feature = "sinks-redis")]
 pub mod redis;
 #[cfg(all(
@@ -125,7 +125,7 @@ impl NewRelicLogsConfig {
 
         let batch_settings = self.batch.validate()?.limit_max_bytes(MAX_PAYLOAD_SIZE)?;
 
-        let tower = TowerRequestConfig { ..self.request };
+        let tower = self.request.clone();
 
         let request = RequestConfig { tower, headers };
 
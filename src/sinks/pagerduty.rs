@@ -0,0 +1,495 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use futures::{future, FutureExt, SinkExt};
+use http::{Request, Uri};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext,
+        SinkDescription,
+    },
+    event::{Event, LogEvent, Value},
+    http::HttpClient,
+    internal_events::{PagerDutyInvalidFieldError, TemplateRenderingError},
+    sinks::{
+        util::{
+            encoding::Transformer,
+            http::{BatchedHttpSink, HttpEventEncoder, HttpSink},
+            BatchConfig, BoxedRawValue, JsonArrayBuffer, SinkBatchSettings, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsConfig, TlsSettings},
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PagerDutyConfig {
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+
+    /// The integration key of the PagerDuty service the events are routed to.
+    pub routing_key: String,
+
+    #[serde(default = "default_event_action")]
+    pub event_action: Template,
+
+    pub dedup_key: Option<Template>,
+
+    #[serde(default = "default_summary")]
+    pub summary: Template,
+
+    pub source: Option<Template>,
+
+    pub severity: Option<Template>,
+
+    /// Maps rendered `severity` values onto PagerDuty severities, e.g. `ERR = "error"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub severity_map: HashMap<String, Severity>,
+
+    #[serde(default)]
+    pub default_severity: Severity,
+
+    pub component: Option<Template>,
+
+    pub group: Option<Template>,
+
+    pub class: Option<Template>,
+
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsConfig>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub encoding: Transformer,
+
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Critical,
+    #[derivative(Default)]
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "critical" => Some(Severity::Critical),
+            "error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "info" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum EventAction {
+    Trigger,
+    Acknowledge,
+    Resolve,
+}
+
+impl EventAction {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "trigger" => Some(EventAction::Trigger),
+            "acknowledge" => Some(EventAction::Acknowledge),
+            "resolve" => Some(EventAction::Resolve),
+            _ => None,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            EventAction::Trigger => "trigger",
+            EventAction::Acknowledge => "acknowledge",
+            EventAction::Resolve => "resolve",
+        }
+    }
+}
+
+fn default_endpoint() -> String {
+    "https://events.pagerduty.com/v2/enqueue".to_string()
+}
+
+fn default_event_action() -> Template {
+    Template::try_from("trigger").expect("template should be valid")
+}
+
+fn default_summary() -> Template {
+    Template::try_from(format!("{{{{ {} }}}}", log_schema().message_key()))
+        .expect("template should be valid")
+}
+
+/// The Events API v2 accepts exactly one event per request, with a maximum payload size of 512KB.
+#[derive(Clone, Copy, Debug, Default)]
+struct PagerDutyDefaultBatchSettings;
+
+impl SinkBatchSettings for PagerDutyDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(1);
+    const MAX_BYTES: Option<usize> = Some(512 * 1024);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+inventory::submit! {
+    SinkDescription::new::<PagerDutyConfig>("pagerduty")
+}
+
+impl GenerateConfig for PagerDutyConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"routing_key = "${PAGERDUTY_ROUTING_KEY}"
+            dedup_key = "{{ host }}-{{ service }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pagerduty")]
+impl SinkConfig for PagerDutyConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let _: Uri = self.endpoint.parse()?;
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch_settings = BatchConfig::<PagerDutyDefaultBatchSettings>::default()
+            .validate()?
+            .into_batch_settings()?;
+
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(Some(tls_settings), cx.proxy())?;
+
+        let sink = BatchedHttpSink::new(
+            self.clone(),
+            JsonArrayBuffer::new(batch_settings.size),
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal pagerduty sink error.", %error));
+
+        // The Events API has no endpoint that can be queried without creating an incident.
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((VectorSink::from_event_sink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+pub struct PagerDutyEventEncoder {
+    config: PagerDutyConfig,
+}
+
+impl PagerDutyEventEncoder {
+    fn render(template: &Template, log: &LogEvent, field: &'static str) -> Option<String> {
+        template
+            .render_string(log)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some(field),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+
+    fn render_optional(
+        template: Option<&Template>,
+        log: &LogEvent,
+        field: &'static str,
+    ) -> Option<Option<String>> {
+        match template {
+            Some(template) => Self::render(template, log, field).map(Some),
+            None => Some(None),
+        }
+    }
+
+    fn severity(&self, log: &LogEvent) -> Option<Severity> {
+        let rendered = match &self.config.severity {
+            Some(template) => Self::render(template, log, "severity")?,
+            None => return Some(self.config.default_severity),
+        };
+
+        Some(
+            self.config
+                .severity_map
+                .get(&rendered)
+                .copied()
+                .or_else(|| Severity::parse(&rendered))
+                .unwrap_or(self.config.default_severity),
+        )
+    }
+}
+
+impl HttpEventEncoder<serde_json::Value> for PagerDutyEventEncoder {
+    fn encode_event(&mut self, mut event: Event) -> Option<serde_json::Value> {
+        let log = event.as_log();
+
+        let action = Self::render(&self.config.event_action, log, "event_action")?;
+        let action = match EventAction::parse(&action) {
+            Some(action) => action,
+            None => {
+                emit!(PagerDutyInvalidFieldError {
+                    field: "event_action",
+                    value: &action,
+                });
+                return None;
+            }
+        };
+
+        let dedup_key = Self::render_optional(self.config.dedup_key.as_ref(), log, "dedup_key")?;
+        if action != EventAction::Trigger && dedup_key.is_none() {
+            emit!(PagerDutyInvalidFieldError {
+                field: "dedup_key",
+                value: "",
+            });
+            return None;
+        }
+
+        let mut body = json!({
+            "routing_key": self.config.routing_key,
+            "event_action": action.as_str(),
+        });
+        if let Some(dedup_key) = dedup_key {
+            body["dedup_key"] = json!(dedup_key);
+        }
+
+        if action == EventAction::Trigger {
+            let summary = Self::render(&self.config.summary, log, "summary")?;
+            let source = match Self::render_optional(self.config.source.as_ref(), log, "source")? {
+                Some(source) => source,
+                None => log
+                    .get(log_schema().host_key())
+                    .map(|host| host.to_string_lossy())
+                    .unwrap_or_else(|| crate::get_hostname().unwrap_or_default()),
+            };
+            let severity = self.severity(log)?;
+            let component =
+                Self::render_optional(self.config.component.as_ref(), log, "component")?;
+            let group = Self::render_optional(self.config.group.as_ref(), log, "group")?;
+            let class = Self::render_optional(self.config.class.as_ref(), log, "class")?;
+
+            self.config.encoding.transform(&mut event);
+            let mut log = event.into_log();
+            let timestamp = match log.remove(log_schema().timestamp_key()) {
+                Some(Value::Timestamp(ts)) => ts,
+                _ => chrono::Utc::now(),
+            };
+
+            let mut payload = json!({
+                "summary": summary,
+                "source": source,
+                "severity": severity.as_str(),
+                "timestamp": timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                "custom_details": log,
+            });
+            for (key, value) in [("component", component), ("group", group), ("class", class)] {
+                if let Some(value) = value {
+                    payload[key] = json!(value);
+                }
+            }
+            body["payload"] = payload;
+        }
+
+        Some(body)
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for PagerDutyConfig {
+    type Input = serde_json::Value;
+    type Output = Vec<BoxedRawValue>;
+    type Encoder = PagerDutyEventEncoder;
+
+    fn build_encoder(&self) -> Self::Encoder {
+        PagerDutyEventEncoder {
+            config: self.clone(),
+        }
+    }
+
+    async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Bytes>> {
+        // Batches are limited to a single event, see `PagerDutyDefaultBatchSettings`.
+        let event = events
+            .into_iter()
+            .next()
+            .ok_or("Can't build a request without an event.")?;
+
+        Request::post(&self.endpoint)
+            .header("Content-Type", "application/json")
+            .body(Bytes::from(event.get().to_owned()))
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, StreamExt};
+    use indoc::indoc;
+    use vector_core::event::{Event, LogEvent};
+
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::{
+            components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+            next_addr,
+        },
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PagerDutyConfig>();
+    }
+
+    fn encode(config: &str, log: LogEvent) -> Option<serde_json::Value> {
+        let (config, _cx) = load_sink::<PagerDutyConfig>(config).unwrap();
+        config.build_encoder().encode_event(Event::Log(log))
+    }
+
+    #[test]
+    fn encodes_trigger_with_mapped_severity() {
+        let mut log = LogEvent::from("disk full");
+        log.insert("host", "db-1");
+        log.insert("level", "ERR");
+
+        let body = encode(
+            indoc! {r#"
+                routing_key = "key"
+                dedup_key = "{{ host }}-disk"
+                severity = "{{ level }}"
+                severity_map.ERR = "critical"
+            "#},
+            log,
+        )
+        .unwrap();
+
+        assert_eq!(body["routing_key"], "key");
+        assert_eq!(body["event_action"], "trigger");
+        assert_eq!(body["dedup_key"], "db-1-disk");
+        assert_eq!(body["payload"]["summary"], "disk full");
+        assert_eq!(body["payload"]["source"], "db-1");
+        assert_eq!(body["payload"]["severity"], "critical");
+        assert_eq!(body["payload"]["custom_details"]["level"], "ERR");
+    }
+
+    #[test]
+    fn encodes_resolve_without_payload() {
+        let mut log = LogEvent::from("disk ok");
+        log.insert("host", "db-1");
+        log.insert("action", "resolve");
+
+        let body = encode(
+            indoc! {r#"
+                routing_key = "key"
+                event_action = "{{ action }}"
+                dedup_key = "{{ host }}-disk"
+            "#},
+            log,
+        )
+        .unwrap();
+
+        assert_eq!(body["event_action"], "resolve");
+        assert_eq!(body["dedup_key"], "db-1-disk");
+        assert!(body.get("payload").is_none());
+    }
+
+    #[test]
+    fn drops_resolve_without_dedup_key() {
+        let body = encode(
+            indoc! {r#"
+                routing_key = "key"
+                event_action = "resolve"
+            "#},
+            LogEvent::from("disk ok"),
+        );
+
+        assert!(body.is_none());
+    }
+
+    #[test]
+    fn drops_invalid_event_action() {
+        let body = encode(
+            indoc! {r#"
+                routing_key = "key"
+                event_action = "escalate"
+            "#},
+            LogEvent::from("disk ok"),
+        );
+
+        assert!(body.is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_one_event_per_request() {
+        let (mut config, cx) = load_sink::<PagerDutyConfig>(indoc! {r#"
+            routing_key = "key"
+        "#})
+        .unwrap();
+
+        let addr = next_addr();
+        config.endpoint = format!("http://{}/v2/enqueue", addr);
+
+        let (sink, _healthcheck) = config.build(cx).await.unwrap();
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = vec![
+            Event::Log(LogEvent::from("first")),
+            Event::Log(LogEvent::from("second")),
+        ];
+        run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+
+        let output = rx.take(2).collect::<Vec<_>>().await;
+        let summaries = output
+            .iter()
+            .map(|(parts, body)| {
+                assert_eq!(parts.uri.path(), "/v2/enqueue");
+                let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+                json["payload"]["summary"].as_str().unwrap().to_owned()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, vec!["first", "second"]);
+    }
+}
@@ -5,6 +5,7 @@ use indexmap::map::IndexMap;
 use prometheus_parser::{proto, METRIC_NAME_LABEL};
 use vector_core::event::metric::{samples_to_buckets, MetricSketch, Quantile};
 
+use super::metadata::{Exemplar, PrometheusMetadata};
 use crate::{
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
@@ -15,7 +16,13 @@ pub(super) trait MetricCollector {
 
     fn new() -> Self;
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue);
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        prometheus: Option<&PrometheusMetadata>,
+    );
 
     fn emit_value(
         &mut self,
@@ -27,6 +34,17 @@ pub(super) trait MetricCollector {
         extra: Option<(&str, String)>,
     );
 
+    /// Attaches an exemplar to a series, which is only supported by some outputs.
+    fn emit_exemplar(
+        &mut self,
+        _name: &str,
+        _suffix: &str,
+        _tags: Option<&BTreeMap<String, String>>,
+        _extra: Option<(&str, String)>,
+        _exemplar: &Exemplar,
+    ) {
+    }
+
     fn finish(self) -> Self::Output;
 
     fn encode_metric(
@@ -42,7 +60,8 @@ pub(super) trait MetricCollector {
 
         if metric.kind() == MetricKind::Absolute {
             let tags = metric.tags();
-            self.emit_metadata(metric.name(), name, metric.value());
+            let prometheus = PrometheusMetadata::from_metric(metric);
+            self.emit_metadata(metric.name(), name, metric.value(), prometheus.as_ref());
 
             match metric.value() {
                 MetricValue::Counter { value } => {
@@ -214,6 +233,32 @@ pub(super) trait MetricCollector {
                     }
                 },
             }
+
+            let exemplars = prometheus
+                .iter()
+                .flat_map(|prometheus| &prometheus.exemplars);
+            for exemplar in exemplars {
+                match metric.value() {
+                    MetricValue::Counter { .. } | MetricValue::Gauge { .. } => {
+                        self.emit_exemplar(name, "", tags, None, exemplar);
+                    }
+                    MetricValue::AggregatedHistogram { buckets, .. } => {
+                        // Exemplars are attached to the bucket their value falls into.
+                        let le = buckets
+                            .iter()
+                            .map(|bucket| bucket.upper_limit)
+                            .find(|upper_limit| {
+                                !upper_limit.is_infinite() && exemplar.value <= *upper_limit
+                            })
+                            .map_or_else(
+                                || "+Inf".to_string(),
+                                |upper_limit| upper_limit.to_string(),
+                            );
+                        self.emit_exemplar(name, "_bucket", tags, Some(("le", le)), exemplar);
+                    }
+                    _ => (),
+                }
+            }
         }
     }
 }
@@ -231,9 +276,16 @@ impl MetricCollector for StringCollector {
         Self { processed }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        prometheus: Option<&PrometheusMetadata>,
+    ) {
         if !self.processed.contains_key(fullname) {
-            let header = Self::encode_header(name, fullname, value);
+            let help = prometheus.and_then(|prometheus| prometheus.help.as_deref());
+            let header = Self::encode_header(help.unwrap_or(name), fullname, value);
             self.processed.insert(fullname.into(), header);
         }
     }
@@ -292,11 +344,12 @@ impl StringCollector {
         .ok();
     }
 
-    fn encode_header(name: &str, fullname: &str, value: &MetricValue) -> String {
+    fn encode_header(help: &str, fullname: &str, value: &MetricValue) -> String {
         let r#type = prometheus_metric_type(value).as_str();
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
         format!(
             "# HELP {} {}\n# TYPE {} {}\n",
-            fullname, name, fullname, r#type
+            fullname, help, fullname, r#type
         )
     }
 
@@ -322,6 +375,7 @@ type Labels = Vec<proto::Label>;
 
 pub(super) struct TimeSeries {
     buffer: IndexMap<Labels, Vec<proto::Sample>>,
+    exemplars: IndexMap<Labels, Vec<proto::Exemplar>>,
     metadata: IndexMap<String, proto::MetricMetadata>,
     timestamp: Option<i64>,
 }
@@ -366,19 +420,28 @@ impl MetricCollector for TimeSeries {
     fn new() -> Self {
         Self {
             buffer: Default::default(),
+            exemplars: Default::default(),
             metadata: Default::default(),
             timestamp: None,
         }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        prometheus: Option<&PrometheusMetadata>,
+    ) {
         if !self.metadata.contains_key(name) {
             let r#type = prometheus_metric_type(value);
+            let help = prometheus.and_then(|prometheus| prometheus.help.clone());
+            let unit = prometheus.and_then(|prometheus| prometheus.unit.clone());
             let metadata = proto::MetricMetadata {
                 r#type: r#type as i32,
                 metric_family_name: fullname.into(),
-                help: name.into(),
-                unit: String::new(),
+                help: help.unwrap_or_else(|| name.into()),
+                unit: unit.unwrap_or_default(),
             };
             self.metadata.insert(name.into(), metadata);
         }
@@ -400,11 +463,46 @@ impl MetricCollector for TimeSeries {
             .push(proto::Sample { value, timestamp });
     }
 
-    fn finish(self) -> proto::WriteRequest {
+    fn emit_exemplar(
+        &mut self,
+        name: &str,
+        suffix: &str,
+        tags: Option<&BTreeMap<String, String>>,
+        extra: Option<(&str, String)>,
+        exemplar: &Exemplar,
+    ) {
+        let labels = exemplar
+            .labels
+            .iter()
+            .map(|(name, value)| proto::Label {
+                name: name.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        self.exemplars
+            .entry(Self::make_labels(tags, name, suffix, extra))
+            .or_default()
+            .push(proto::Exemplar {
+                labels,
+                value: exemplar.value,
+                timestamp: exemplar
+                    .timestamp
+                    .map_or(0, |timestamp| timestamp.timestamp_millis()),
+            });
+    }
+
+    fn finish(mut self) -> proto::WriteRequest {
         let timeseries = self
             .buffer
             .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
+            .map(|(labels, samples)| {
+                let exemplars = self.exemplars.remove(&labels).unwrap_or_default();
+                proto::TimeSeries {
+                    labels,
+                    samples,
+                    exemplars,
+                }
+            })
             .collect::<Vec<_>>();
         let metadata = self
             .metadata
@@ -495,6 +593,7 @@ mod tests {
                                 value: $svalue,
                                 timestamp: $timestamp,
                             }],
+                            exemplars: vec![],
                         },
                     )*
                 ],
@@ -772,6 +871,78 @@ mod tests {
         encode_one::<T>(Some("vector"), &[], &[], &metric)
     }
 
+    #[test]
+    fn encodes_prometheus_metadata_text() {
+        assert_eq!(
+            encode_histogram_with_metadata::<StringCollector>(),
+            indoc! {r#"
+                # HELP vector_requests The requests,\nby latency.
+                # TYPE vector_requests histogram
+                vector_requests_bucket{le="1"} 1 1612325106789
+                vector_requests_bucket{le="2.1"} 3 1612325106789
+                vector_requests_bucket{le="3"} 6 1612325106789
+                vector_requests_bucket{le="+Inf"} 6 1612325106789
+                vector_requests_sum 11.5 1612325106789
+                vector_requests_count 6 1612325106789
+            "#}
+        );
+    }
+
+    #[test]
+    fn encodes_prometheus_metadata_request() {
+        let request = encode_histogram_with_metadata::<TimeSeries>();
+
+        assert_eq!(request.metadata[0].help, "The requests,\nby latency.");
+        assert_eq!(request.metadata[0].unit, "seconds");
+        let exemplars = request
+            .timeseries
+            .iter()
+            .filter(|series| !series.exemplars.is_empty())
+            .map(|series| (&series.labels[1].value, &series.exemplars))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exemplars,
+            vec![(
+                &"2.1".to_string(),
+                &vec![proto::Exemplar {
+                    labels: vec![proto::Label {
+                        name: "trace_id".into(),
+                        value: "abc".into(),
+                    }],
+                    value: 1.75,
+                    timestamp: 1612325106000,
+                }]
+            )]
+        );
+    }
+
+    fn encode_histogram_with_metadata<T: MetricCollector>() -> T::Output {
+        let mut histogram = VariableHistogram::new(&[1.0, 2.1, 3.0]);
+        histogram.record_many(&[0.4, 2.0, 1.75, 2.6, 2.25, 2.5][..]);
+
+        let mut metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: histogram.buckets(),
+                count: histogram.count(),
+                sum: histogram.sum(),
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+        PrometheusMetadata {
+            help: Some("The requests,\nby latency.".into()),
+            unit: Some("seconds".into()),
+            exemplars: vec![Exemplar {
+                labels: [("trace_id".to_owned(), "abc".to_owned())].into(),
+                value: 1.75,
+                timestamp: Some(Utc.ymd(2021, 2, 3).and_hms(4, 5, 6)),
+            }],
+        }
+        .insert_into(&mut metric);
+        encode_one::<T>(Some("vector"), &[], &[], &metric)
+    }
+
     #[test]
     fn encodes_summary_text() {
         assert_eq!(
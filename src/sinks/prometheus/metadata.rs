@@ -0,0 +1,167 @@
+//! The Prometheus metadata of metrics, such as their help and exemplars, which has no
+//! counterpart in Vector metrics.
+//!
+//! It's kept in the metadata of the events by the `prometheus_remote_write` source, so that it's
+//! written again by the Prometheus sinks, including after a hop through a `vector` sink and source.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use ordered_float::NotNan;
+
+use crate::event::{Metric, Value};
+
+const METADATA_KEY: &str = "prometheus";
+
+/// The Prometheus metadata of a metric.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct PrometheusMetadata {
+    pub(crate) help: Option<String>,
+    pub(crate) unit: Option<String>,
+    pub(crate) exemplars: Vec<Exemplar>,
+}
+
+/// An example of a value observed by a metric, such as a request with a slow response, which
+/// is linked to its trace by its labels.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Exemplar {
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) value: f64,
+    pub(crate) timestamp: Option<DateTime<Utc>>,
+}
+
+impl PrometheusMetadata {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.help.is_none() && self.unit.is_none() && self.exemplars.is_empty()
+    }
+
+    /// Reads the Prometheus metadata of a metric, if it has any.
+    pub(crate) fn from_metric(metric: &Metric) -> Option<Self> {
+        let fields = match metric.metadata().value() {
+            Value::Object(metadata) => match metadata.get(METADATA_KEY)? {
+                Value::Object(fields) => fields,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let exemplars = match fields.get("exemplars") {
+            Some(Value::Array(exemplars)) => {
+                exemplars.iter().filter_map(Exemplar::from_value).collect()
+            }
+            _ => Vec::new(),
+        };
+        Some(Self {
+            help: fields.get("help").and_then(string),
+            unit: fields.get("unit").and_then(string),
+            exemplars,
+        })
+    }
+
+    /// Stores the Prometheus metadata into the metadata of a metric.
+    pub(crate) fn insert_into(self, metric: &mut Metric) {
+        let mut fields = BTreeMap::new();
+        if let Some(help) = self.help {
+            fields.insert("help".into(), help.into());
+        }
+        if let Some(unit) = self.unit {
+            fields.insert("unit".into(), unit.into());
+        }
+        if !self.exemplars.is_empty() {
+            let exemplars = self
+                .exemplars
+                .into_iter()
+                .filter_map(Exemplar::into_value)
+                .collect();
+            fields.insert("exemplars".into(), Value::Array(exemplars));
+        }
+
+        if let Value::Object(metadata) = metric.metadata_mut().value_mut() {
+            metadata.insert(METADATA_KEY.into(), Value::Object(fields));
+        }
+    }
+}
+
+impl Exemplar {
+    fn from_value(value: &Value) -> Option<Self> {
+        let fields = match value {
+            Value::Object(fields) => fields,
+            _ => return None,
+        };
+
+        let labels = match fields.get("labels") {
+            Some(Value::Object(labels)) => labels
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), string(value)?)))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        let value = match fields.get("value")? {
+            Value::Float(value) => value.into_inner(),
+            Value::Integer(value) => *value as f64,
+            _ => return None,
+        };
+        let timestamp = match fields.get("timestamp") {
+            Some(Value::Timestamp(timestamp)) => Some(*timestamp),
+            _ => None,
+        };
+        Some(Self {
+            labels,
+            value,
+            timestamp,
+        })
+    }
+
+    /// Converts the exemplar into a value, unless its value is NaN, which can't be stored.
+    fn into_value(self) -> Option<Value> {
+        let mut fields = BTreeMap::new();
+        fields.insert("value".into(), Value::Float(NotNan::new(self.value).ok()?));
+        let labels = self
+            .labels
+            .into_iter()
+            .map(|(name, value)| (name, value.into()))
+            .collect();
+        fields.insert("labels".into(), Value::Object(labels));
+        if let Some(timestamp) = self.timestamp {
+            fields.insert("timestamp".into(), timestamp.into());
+        }
+        Some(Value::Object(fields))
+    }
+}
+
+fn string(value: &Value) -> Option<String> {
+    match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::event::{MetricKind, MetricValue};
+
+    #[test]
+    fn roundtrips_through_metric() {
+        let mut metric = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        );
+        assert_eq!(PrometheusMetadata::from_metric(&metric), None);
+
+        let metadata = PrometheusMetadata {
+            help: Some("The requests.".into()),
+            unit: None,
+            exemplars: vec![Exemplar {
+                labels: [("trace_id".to_string(), "abc".to_string())].into(),
+                value: 0.5,
+                timestamp: Some(Utc.timestamp(1395066367, 0)),
+            }],
+        };
+        metadata.clone().insert_into(&mut metric);
+        assert_eq!(PrometheusMetadata::from_metric(&metric), Some(metadata));
+    }
+}
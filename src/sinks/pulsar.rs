@@ -196,11 +196,14 @@ impl PulsarSinkConfig {
 
         let pulsar = builder.build().await?;
         if let SerializerConfig::Avro { avro } = self.encoding.config() {
+            let schema = avro
+                .read_schema_text()
+                .map_err(|error| PulsarError::Custom(error.to_string()))?;
             pulsar
                 .producer()
                 .with_options(pulsar::producer::ProducerOptions {
                     schema: Some(proto::Schema {
-                        schema_data: avro.schema.as_bytes().into(),
+                        schema_data: schema.into_bytes(),
                         r#type: proto::schema::Type::Avro as i32,
                         ..Default::default()
                     }),
@@ -0,0 +1,330 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{FutureExt, SinkExt};
+use http::{Request, StatusCode, Uri};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::Event,
+    http::{Auth, HttpClient},
+    sinks::{
+        util::{
+            encoding::Transformer,
+            http::{HttpEventEncoder, HttpSink, PartitionHttpSink},
+            BatchConfig, BoxedRawValue, JsonArrayBuffer, PartitionBuffer, PartitionInnerBuffer,
+            SinkBatchSettings, TowerRequestConfig, UriSerde,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// Quickwit rejects ingest payloads larger than 10MB by default.
+const MAX_PAYLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+/// Index IDs may only contain alphanumerics, `-`, `_` and `.`; anything else is escaped.
+const INDEX_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuickwitConfig {
+    pub endpoint: UriSerde,
+
+    pub index: Template,
+
+    #[serde(default)]
+    pub commit: CommitMode,
+
+    pub auth: Option<Auth>,
+
+    #[serde(default)]
+    pub batch: BatchConfig<QuickwitDefaultBatchSettings>,
+
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsConfig>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub encoding: Transformer,
+
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// Controls when the documents of an ingest request become searchable.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitMode {
+    /// Documents are committed according to the index `commit_timeout_secs`.
+    #[derivative(Default)]
+    Auto,
+    /// The request returns once the documents have been committed.
+    WaitFor,
+    /// Forces a commit once the documents have been ingested.
+    Force,
+}
+
+impl CommitMode {
+    const fn as_str(self) -> &'static str {
+        match self {
+            CommitMode::Auto => "auto",
+            CommitMode::WaitFor => "wait_for",
+            CommitMode::Force => "force",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QuickwitDefaultBatchSettings;
+
+impl SinkBatchSettings for QuickwitDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = Some(MAX_PAYLOAD_SIZE);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+inventory::submit! {
+    SinkDescription::new::<QuickwitConfig>("quickwit")
+}
+
+impl GenerateConfig for QuickwitConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:7280"
+            index = "vector-logs""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "quickwit")]
+impl SinkConfig for QuickwitConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch_settings = self
+            .batch
+            .validate()?
+            .limit_max_bytes(MAX_PAYLOAD_SIZE)?
+            .into_batch_settings()?;
+
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(Some(tls_settings), cx.proxy())?;
+
+        let sink = PartitionHttpSink::new(
+            self.clone(),
+            PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size)),
+            request_settings,
+            batch_settings.timeout,
+            client.clone(),
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal quickwit sink error.", %error));
+
+        let healthcheck = healthcheck(self.clone(), client).boxed();
+
+        Ok((VectorSink::from_event_sink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "quickwit"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+pub struct QuickwitEventEncoder {
+    index: Template,
+    transformer: Transformer,
+}
+
+impl HttpEventEncoder<PartitionInnerBuffer<serde_json::Value, String>> for QuickwitEventEncoder {
+    fn encode_event(
+        &mut self,
+        mut event: Event,
+    ) -> Option<PartitionInnerBuffer<serde_json::Value, String>> {
+        let index = self
+            .index
+            .render_string(&event)
+            .map_err(|error| {
+                emit!(crate::internal_events::TemplateRenderingError {
+                    error,
+                    field: Some("index"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+
+        self.transformer.transform(&mut event);
+        let document = serde_json::to_value(event.into_log()).ok()?;
+
+        Some(PartitionInnerBuffer::new(document, index))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for QuickwitConfig {
+    type Input = PartitionInnerBuffer<serde_json::Value, String>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, String>;
+    type Encoder = QuickwitEventEncoder;
+
+    fn build_encoder(&self) -> Self::Encoder {
+        QuickwitEventEncoder {
+            index: self.index.clone(),
+            transformer: self.encoding.clone(),
+        }
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Bytes>> {
+        let (documents, index) = output.into_parts();
+
+        // The ingest API expects newline-delimited JSON documents.
+        let mut body = BytesMut::new();
+        for document in documents {
+            body.put_slice(document.get().as_bytes());
+            body.put_u8(b'\n');
+        }
+
+        let mut request = Request::post(self.ingest_uri(&index)?)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body.freeze())?;
+
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        Ok(request)
+    }
+}
+
+impl QuickwitConfig {
+    fn ingest_uri(&self, index: &str) -> crate::Result<Uri> {
+        let encoded_index = utf8_percent_encode(index, INDEX_ENCODE_SET);
+        let uri = self
+            .endpoint
+            .append_path(&format!("/api/v1/{}/ingest", encoded_index))?
+            .uri;
+
+        format!("{}?commit={}", uri, self.commit.as_str())
+            .parse::<Uri>()
+            .map_err(Into::into)
+    }
+}
+
+async fn healthcheck(config: QuickwitConfig, client: HttpClient) -> crate::Result<()> {
+    let uri = config.endpoint.append_path("/health/livez")?.uri;
+
+    let mut request = Request::get(uri).body(hyper::Body::empty())?;
+    if let Some(auth) = &config.auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        status => Err(super::HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, StreamExt};
+    use indoc::indoc;
+    use vector_core::event::{Event, LogEvent};
+
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::{
+            components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+            next_addr,
+        },
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<QuickwitConfig>();
+    }
+
+    #[test]
+    fn ingest_uri_includes_index_and_commit_mode() {
+        let (config, _cx) = load_sink::<QuickwitConfig>(indoc! {r#"
+            endpoint = "http://localhost:7280"
+            index = "logs"
+            commit = "wait_for"
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            config.ingest_uri("app-logs").unwrap().to_string(),
+            "http://localhost:7280/api/v1/app-logs/ingest?commit=wait_for"
+        );
+    }
+
+    #[tokio::test]
+    async fn partitions_documents_by_index() {
+        let addr = next_addr();
+        let (config, cx) = load_sink::<QuickwitConfig>(&format!(
+            indoc! {r#"
+                endpoint = "http://{}"
+                index = "{{{{ app }}}}"
+                batch.timeout_secs = 1
+            "#},
+            addr
+        ))
+        .unwrap();
+
+        let (sink, _healthcheck) = config.build(cx).await.unwrap();
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = ["api", "web", "api"].iter().map(|app| {
+            let mut log = LogEvent::from(format!("message from {}", app));
+            log.insert("app", *app);
+            Event::Log(log)
+        });
+        run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+
+        let mut output = rx
+            .take(2)
+            .map(|(parts, body)| {
+                let lines = String::from_utf8(body.to_vec())
+                    .unwrap()
+                    .lines()
+                    .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                    .collect::<Vec<_>>();
+                (parts.uri.path().to_owned(), lines.len())
+            })
+            .collect::<Vec<_>>()
+            .await;
+        output.sort();
+
+        assert_eq!(
+            output,
+            vec![
+                ("/api/v1/api/ingest".to_owned(), 2),
+                ("/api/v1/web/ingest".to_owned(), 1)
+            ]
+        );
+    }
+}
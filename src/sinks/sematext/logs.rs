@@ -91,7 +91,7 @@ impl SinkConfig for SematextLogsConfig {
             }),
             batch: self.batch,
             request: RequestConfig {
-                tower: self.request,
+                tower: self.request.clone(),
                 ..Default::default()
             },
             encoding: self.encoding.clone(),
@@ -1,8 +1,13 @@
+use std::io::Write;
+
+use bytes::BytesMut;
 use codecs::{
-    encoding::{Framer, FramingConfig, SerializerConfig},
-    BytesEncoder, JsonSerializerConfig, NewlineDelimitedEncoder, TextSerializerConfig,
+    encoding::{Error, Framer, FramingConfig, SerializerConfig},
+    BytesEncoder, JsonSerializerConfig, LengthDelimitedEncoder, NewlineDelimitedEncoder,
+    TextSerializerConfig,
 };
 use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder as _;
 
 #[cfg(unix)]
 use crate::sinks::util::unix::UnixSinkConfig;
@@ -11,13 +16,15 @@ use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
     },
+    event::Event,
+    internal_events::EncoderFramingFailed,
     sinks::util::{
         encoding::{
             EncodingConfig, EncodingConfigWithFramingAdapter, EncodingConfigWithFramingMigrator,
         },
         tcp::TcpSinkConfig,
         udp::UdpSinkConfig,
-        Encoding,
+        Compression, Compressor, Encoding,
     },
 };
 
@@ -45,6 +52,10 @@ pub struct SocketSinkConfig {
     pub mode: Mode,
     #[serde(flatten)]
     pub encoding: EncodingConfigWithFramingAdapter<EncodingConfig<Encoding>, Migrator>,
+    /// Compression applied to each event individually, after it is serialized and before it is
+    /// framed.
+    #[serde(default)]
+    pub compression: Compression,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -76,6 +87,7 @@ impl SocketSinkConfig {
         SocketSinkConfig {
             mode,
             encoding: EncodingConfigWithFramingAdapter::legacy(encoding),
+            compression: Compression::None,
         }
     }
 
@@ -96,13 +108,23 @@ impl SinkConfig for SocketSinkConfig {
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let transformer = self.encoding.transformer();
         let (framer, serializer) = self.encoding.encoding()?;
+        let compressed = self.compression.is_compressed();
         let framer = framer.unwrap_or_else(|| match self.mode {
+            // Compressed payloads are binary and may contain newlines, so stream based modes
+            // need to prefix them with their length instead.
+            Mode::Tcp(_) if compressed => LengthDelimitedEncoder::new().into(),
             Mode::Tcp(_) => NewlineDelimitedEncoder::new().into(),
             Mode::Udp(_) => BytesEncoder::new().into(),
             #[cfg(unix)]
+            Mode::Unix(_) if compressed => LengthDelimitedEncoder::new().into(),
+            #[cfg(unix)]
             Mode::Unix(_) => NewlineDelimitedEncoder::new().into(),
         });
-        let encoder = Encoder::<Framer>::new(framer, serializer);
+        let encoder = SocketEncoder {
+            framer: framer.clone(),
+            encoder: Encoder::<Framer>::new(framer, serializer),
+            compression: self.compression,
+        };
         match &self.mode {
             Mode::Tcp(config) => config.build(cx, transformer, encoder),
             Mode::Udp(config) => config.build(cx, transformer, encoder),
@@ -124,6 +146,43 @@ impl SinkConfig for SocketSinkConfig {
     }
 }
 
+/// Encodes events for the socket sink, compressing each serialized event before it is framed.
+#[derive(Debug, Clone)]
+struct SocketEncoder {
+    encoder: Encoder<Framer>,
+    framer: Framer,
+    compression: Compression,
+}
+
+impl tokio_util::codec::Encoder<Event> for SocketEncoder {
+    type Error = Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        if !self.compression.is_compressed() {
+            return self.encoder.encode(event, buffer);
+        }
+
+        let mut payload = BytesMut::new();
+        self.encoder.serialize(event, &mut payload)?;
+
+        let mut compressor = Compressor::from(self.compression);
+        compressor
+            .write_all(&payload)
+            .map_err(|error| Error::SerializingError(error.into()))?;
+        let mut frame = compressor
+            .finish()
+            .map_err(|error| Error::SerializingError(error.into()))?;
+
+        self.framer.encode((), &mut frame).map_err(|error| {
+            emit!(EncoderFramingFailed { error: &error });
+            Error::FramingError(error)
+        })?;
+
+        buffer.extend_from_slice(&frame);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{
@@ -139,7 +198,7 @@ mod test {
         time::{sleep, timeout, Duration},
     };
     use tokio_stream::wrappers::TcpListenerStream;
-    use tokio_util::codec::{FramedRead, LinesCodec};
+    use tokio_util::codec::{FramedRead, LengthDelimitedCodec, LinesCodec};
 
     use super::*;
     use crate::{
@@ -165,6 +224,7 @@ mod test {
                 None,
                 JsonSerializerConfig::new().into(),
             ),
+            compression: Compression::None,
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -210,6 +270,7 @@ mod test {
                 None,
                 JsonSerializerConfig::new().into(),
             ),
+            compression: Compression::None,
         };
 
         let context = SinkContext::new_test();
@@ -232,6 +293,43 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn tcp_stream_compressed() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = SocketSinkConfig {
+            mode: Mode::Tcp(TcpSinkConfig::from_address(addr.to_string())),
+            encoding: EncodingConfigWithFramingAdapter::new(
+                None,
+                JsonSerializerConfig::new().into(),
+            ),
+            compression: Compression::zstd_default(),
+        };
+
+        let context = SinkContext::new_test();
+        let (sink, _healthcheck) = config.build(context).await.unwrap();
+
+        let listener = TcpListener::bind(addr).await.unwrap();
+
+        let (lines, events) = random_lines_with_stream(10, 100, None);
+        run_and_assert_sink_compliance(sink, events, &SINK_TAGS).await;
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let output = FramedRead::new(socket, LengthDelimitedCodec::new())
+            .map(|frame| zstd::stream::decode_all(&frame.unwrap()[..]).unwrap())
+            .take(lines.len())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(lines.len(), output.len());
+        for (source, received) in lines.iter().zip(output) {
+            let json = serde_json::from_slice::<Value>(&received).expect("Invalid JSON");
+            let received = json.get("message").unwrap().as_str().unwrap();
+            assert_eq!(source, received);
+        }
+    }
+
     // This is a test that checks that we properly receive all events in the
     // case of a proper server side write side shutdown.
     //
@@ -289,6 +387,7 @@ mod test {
                 None,
                 TextSerializerConfig::new().into(),
             ),
+            compression: Compression::None,
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -408,6 +507,7 @@ mod test {
                 None,
                 TextSerializerConfig::new().into(),
             ),
+            compression: Compression::None,
         };
 
         let context = SinkContext::new_test();
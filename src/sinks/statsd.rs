@@ -17,13 +17,14 @@ use super::util::SinkBatchSettings;
 use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
     config::{
-        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig,
+        SinkContext, SinkDescription,
     },
     event::{
         metric::{Metric, MetricKind, MetricTags, MetricValue, StatisticKind},
-        Event,
+        Event, LogEvent, Value,
     },
-    internal_events::StatsdInvalidMetricError,
+    internal_events::{StatsdInvalidLogError, StatsdInvalidMetricError},
     sinks::util::{
         buffer::metrics::compress_distribution,
         encode_namespace,
@@ -43,10 +44,26 @@ pub struct StatsdSvc {
 pub struct StatsdSinkConfig {
     #[serde(alias = "namespace")]
     pub default_namespace: Option<String>,
+    #[serde(default)]
+    pub flavor: Flavor,
+    /// The metric tag holding the container ID of the emitter. With the `dogstatsd` flavor this tag is
+    /// sent as origin metadata (`|c:`) rather than as a regular tag.
+    pub container_id_tag: Option<String>,
     #[serde(flatten)]
     pub mode: Mode,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Flavor {
+    /// Plain statsd, accepting metrics only.
+    #[derivative(Default)]
+    Statsd,
+    /// DogStatsD, additionally accepting logs which are encoded as events and service checks.
+    Dogstatsd,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "mode", rename_all = "snake_case")]
 pub enum Mode {
@@ -86,6 +103,8 @@ impl GenerateConfig for StatsdSinkConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(&Self {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
             mode: Mode::Udp(StatsdUdpConfig {
                 batch: Default::default(),
                 udp: UdpSinkConfig::from_address(default_address().to_string()),
@@ -102,8 +121,11 @@ impl SinkConfig for StatsdSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let default_namespace = self.default_namespace.clone();
-        let mut encoder = StatsdEncoder { default_namespace };
+        let mut encoder = StatsdEncoder {
+            default_namespace: self.default_namespace.clone(),
+            flavor: self.flavor,
+            container_id_tag: self.container_id_tag.clone(),
+        };
         match &self.mode {
             Mode::Tcp(config) => config.build(cx, Default::default(), encoder),
             Mode::Udp(config) => {
@@ -140,7 +162,10 @@ impl SinkConfig for StatsdSinkConfig {
     }
 
     fn input(&self) -> Input {
-        Input::metric()
+        match self.flavor {
+            Flavor::Statsd => Input::metric(),
+            Flavor::Dogstatsd => Input::new(DataType::Metric | DataType::Log),
+        }
     }
 
     fn sink_type(&self) -> &'static str {
@@ -168,47 +193,134 @@ fn encode_tags(tags: &MetricTags) -> String {
 }
 
 fn push_event<V: Display>(
-    buf: &mut Vec<String>,
-    metric: &Metric,
+    lines: &mut Vec<String>,
+    name: &str,
     val: V,
     metric_type: &str,
     sample_rate: Option<u32>,
+    suffix: &str,
 ) {
-    buf.push(format!("{}:{}|{}", metric.name(), val, metric_type));
+    let mut line = format!("{}:{}|{}", name, val, metric_type);
 
     if let Some(sample_rate) = sample_rate {
         if sample_rate != 1 {
-            buf.push(format!("@{}", 1.0 / f64::from(sample_rate)))
+            line.push_str(&format!("|@{}", 1.0 / f64::from(sample_rate)));
         }
     };
 
-    if let Some(t) = metric.tags() {
-        buf.push(format!("#{}", encode_tags(t)));
+    line.push_str(suffix);
+    lines.push(line);
+}
+
+/// Escapes newlines, which would otherwise terminate the datagram line.
+fn escape_text(text: &str) -> String {
+    text.replace('\n', "\\n")
+}
+
+fn encode_log_tags(log: &LogEvent) -> Option<String> {
+    let tags = match log.get("tags")? {
+        Value::Array(tags) => tags
+            .iter()
+            .map(|tag| tag.to_string_lossy())
+            .collect::<Vec<_>>(),
+        Value::Object(tags) => tags
+            .iter()
+            .map(|(name, value)| format!("{}:{}", name, value.to_string_lossy()))
+            .collect(),
+        value => vec![value.to_string_lossy()],
     };
+    (!tags.is_empty()).then(|| tags.join(","))
+}
+
+fn log_timestamp(log: &LogEvent) -> Option<i64> {
+    match log.get(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(ts)) => Some(ts.timestamp()),
+        _ => None,
+    }
+}
+
+fn log_host(log: &LogEvent) -> Option<String> {
+    log.get(log_schema().host_key())
+        .map(|host| host.to_string_lossy())
+}
+
+fn service_check_status(value: &Value) -> Option<u8> {
+    match value {
+        Value::Integer(status @ 0..=3) => Some(*status as u8),
+        value => match value.to_string_lossy().to_lowercase().as_str() {
+            "0" | "ok" => Some(0),
+            "1" | "warning" => Some(1),
+            "2" | "critical" => Some(2),
+            "3" | "unknown" => Some(3),
+            _ => None,
+        },
+    }
 }
 
 #[derive(Debug, Clone)]
 struct StatsdEncoder {
     default_namespace: Option<String>,
+    flavor: Flavor,
+    container_id_tag: Option<String>,
 }
 
-impl Encoder<Event> for StatsdEncoder {
-    type Error = codecs::encoding::Error;
+impl StatsdEncoder {
+    /// Builds the part of a metric line following the value: tags and, for DogStatsD, the origin metadata.
+    fn metric_suffix(&self, metric: &Metric) -> String {
+        let mut suffix = String::new();
+        let mut container_id = None;
+
+        if let Some(tags) = metric.tags() {
+            let container_id_tag = self
+                .container_id_tag
+                .as_deref()
+                .filter(|_| self.flavor == Flavor::Dogstatsd);
+            let tags = match container_id_tag {
+                Some(tag) if tags.contains_key(tag) => {
+                    let mut tags = tags.clone();
+                    container_id = tags.remove(tag);
+                    tags
+                }
+                _ => tags.clone(),
+            };
+            if !tags.is_empty() {
+                suffix.push_str(&format!("|#{}", encode_tags(&tags)));
+            }
+        }
 
-    fn encode(&mut self, event: Event, bytes: &mut BytesMut) -> Result<(), Self::Error> {
-        let mut buf = Vec::new();
+        if let Some(container_id) = container_id {
+            suffix.push_str(&format!("|c:{}", container_id));
+        }
+
+        suffix
+    }
+
+    fn encode_metric(&self, metric: &Metric) -> Option<Vec<String>> {
+        let mut lines = Vec::new();
+        let name = encode_namespace(
+            metric.namespace().or(self.default_namespace.as_deref()),
+            '.',
+            metric.name(),
+        );
+        let suffix = self.metric_suffix(metric);
 
-        let metric = event.as_metric();
         match metric.value() {
             MetricValue::Counter { value } => {
-                push_event(&mut buf, metric, value, "c", None);
+                push_event(&mut lines, &name, value, "c", None, &suffix);
             }
             MetricValue::Gauge { value } => {
                 match metric.kind() {
-                    MetricKind::Incremental => {
-                        push_event(&mut buf, metric, format!("{:+}", value), "g", None)
+                    MetricKind::Incremental => push_event(
+                        &mut lines,
+                        &name,
+                        format!("{:+}", value),
+                        "g",
+                        None,
+                        &suffix,
+                    ),
+                    MetricKind::Absolute => {
+                        push_event(&mut lines, &name, value, "g", None, &suffix)
                     }
-                    MetricKind::Absolute => push_event(&mut buf, metric, value, "g", None),
                 };
             }
             MetricValue::Distribution { samples, statistic } => {
@@ -229,17 +341,18 @@ impl Encoder<Event> for StatsdEncoder {
                 let compressed_samples = compress_distribution(&mut samples);
                 for sample in compressed_samples {
                     push_event(
-                        &mut buf,
-                        metric,
+                        &mut lines,
+                        &name,
                         sample.value,
                         metric_type,
                         Some(sample.rate),
+                        &suffix,
                     );
                 }
             }
             MetricValue::Set { values } => {
                 for val in values {
-                    push_event(&mut buf, metric, val, "s", None);
+                    push_event(&mut lines, &name, val, "s", None, &suffix);
                 }
             }
             _ => {
@@ -248,18 +361,103 @@ impl Encoder<Event> for StatsdEncoder {
                     kind: &metric.kind(),
                 });
 
-                return Ok(());
+                return None;
             }
         };
 
-        let message = encode_namespace(
-            metric.namespace().or(self.default_namespace.as_deref()),
-            '.',
-            buf.join("|"),
-        );
+        Some(lines)
+    }
+
+    /// Encodes a log as a DogStatsD service check if it has a `check` field, or as a DogStatsD event otherwise.
+    fn encode_log(&self, log: &LogEvent) -> Option<String> {
+        if self.flavor != Flavor::Dogstatsd {
+            emit!(StatsdInvalidLogError {
+                reason: "logs are only supported by the `dogstatsd` flavor",
+            });
+            return None;
+        }
 
-        bytes.put_slice(&message.into_bytes());
-        bytes.put_u8(b'\n');
+        let mut line = match log.get("check") {
+            Some(check) => {
+                let status = match log.get("status").and_then(service_check_status) {
+                    Some(status) => status,
+                    None => {
+                        emit!(StatsdInvalidLogError {
+                            reason: "service check has a missing or invalid `status` field",
+                        });
+                        return None;
+                    }
+                };
+                format!("_sc|{}|{}", check.to_string_lossy(), status)
+            }
+            None => {
+                let title = match log.get("title") {
+                    Some(title) => escape_text(&title.to_string_lossy()),
+                    None => {
+                        emit!(StatsdInvalidLogError {
+                            reason: "event is missing the `title` field",
+                        });
+                        return None;
+                    }
+                };
+                let text = log
+                    .get("text")
+                    .or_else(|| log.get(log_schema().message_key()))
+                    .map(|text| escape_text(&text.to_string_lossy()))
+                    .unwrap_or_default();
+                format!("_e{{{},{}}}:{}|{}", title.len(), text.len(), title, text)
+            }
+        };
+
+        if let Some(timestamp) = log_timestamp(log) {
+            line.push_str(&format!("|d:{}", timestamp));
+        }
+        if let Some(host) = log_host(log) {
+            line.push_str(&format!("|h:{}", host));
+        }
+
+        if log.contains("check") {
+            if let Some(tags) = encode_log_tags(log) {
+                line.push_str(&format!("|#{}", tags));
+            }
+            // The message must be the last field of a service check.
+            if let Some(message) = log.get("message") {
+                line.push_str(&format!("|m:{}", escape_text(&message.to_string_lossy())));
+            }
+        } else {
+            for (field, prefix) in [
+                ("aggregation_key", "k"),
+                ("priority", "p"),
+                ("source_type_name", "s"),
+                ("alert_type", "t"),
+            ] {
+                if let Some(value) = log.get(field) {
+                    line.push_str(&format!("|{}:{}", prefix, value.to_string_lossy()));
+                }
+            }
+            if let Some(tags) = encode_log_tags(log) {
+                line.push_str(&format!("|#{}", tags));
+            }
+        }
+
+        Some(line)
+    }
+}
+
+impl Encoder<Event> for StatsdEncoder {
+    type Error = codecs::encoding::Error;
+
+    fn encode(&mut self, event: Event, bytes: &mut BytesMut) -> Result<(), Self::Error> {
+        let lines = match &event {
+            Event::Metric(metric) => self.encode_metric(metric),
+            Event::Log(log) => self.encode_log(log).map(|line| vec![line]),
+            Event::Trace(_) => None,
+        };
+
+        for line in lines.into_iter().flatten() {
+            bytes.put_slice(line.as_bytes());
+            bytes.put_u8(b'\n');
+        }
 
         Ok(())
     }
@@ -351,6 +549,8 @@ mod test {
         let event = Event::Metric(metric1.clone());
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -369,6 +569,8 @@ mod test {
         let event = Event::Metric(metric1);
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -389,6 +591,8 @@ mod test {
         let event = Event::Metric(metric1.clone());
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -408,6 +612,8 @@ mod test {
         let event = Event::Metric(metric1.clone());
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -441,6 +647,8 @@ mod test {
         let event = Event::Metric(metric1);
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -462,6 +670,8 @@ mod test {
         let event = Event::Metric(metric1.clone());
         let mut encoder = StatsdEncoder {
             default_namespace: None,
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
         };
         let mut frame = BytesMut::new();
         encoder.encode(event, &mut frame).unwrap();
@@ -469,6 +679,109 @@ mod test {
         vector_common::assert_event_data_eq!(metric1, metric2);
     }
 
+    fn dogstatsd_encoder() -> StatsdEncoder {
+        StatsdEncoder {
+            default_namespace: None,
+            flavor: Flavor::Dogstatsd,
+            container_id_tag: Some("container_id".to_owned()),
+        }
+    }
+
+    fn encode_to_string(encoder: &mut StatsdEncoder, event: Event) -> String {
+        let mut frame = BytesMut::new();
+        encoder.encode(event, &mut frame).unwrap();
+        String::from_utf8(frame.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encode_distribution_samples_as_separate_lines() {
+        let metric = Metric::new(
+            "distribution",
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 2, 2.5 => 1],
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_namespace(Some("vector"));
+
+        assert_eq!(
+            encode_to_string(&mut dogstatsd_encoder(), Event::Metric(metric)),
+            "vector.distribution:1|d|@0.5\nvector.distribution:2.5|d\n"
+        );
+    }
+
+    #[test]
+    fn encode_dogstatsd_container_id() {
+        let mut tags = tags();
+        tags.insert("container_id".to_owned(), "abc123".to_owned());
+        let metric = Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(tags));
+
+        assert_eq!(
+            encode_to_string(&mut dogstatsd_encoder(), Event::Metric(metric.clone())),
+            "counter:1|c|#empty_tag:,normal_tag:value,true_tag|c:abc123\n"
+        );
+
+        // The plain statsd flavor keeps the container ID as a regular tag.
+        let mut encoder = StatsdEncoder {
+            flavor: Flavor::Statsd,
+            ..dogstatsd_encoder()
+        };
+        assert_eq!(
+            encode_to_string(&mut encoder, Event::Metric(metric)),
+            "counter:1|c|#container_id:abc123,empty_tag:,normal_tag:value,true_tag\n"
+        );
+    }
+
+    #[test]
+    fn encode_dogstatsd_event() {
+        let mut log = LogEvent::default();
+        log.insert("title", "Deploy");
+        log.insert("text", "Deployed\nversion 2");
+        log.insert("host", "web-1");
+        log.insert("alert_type", "success");
+        log.insert(
+            "tags",
+            Value::Array(vec![Value::from("env:prod"), Value::from("team:web")]),
+        );
+
+        assert_eq!(
+            encode_to_string(&mut dogstatsd_encoder(), Event::Log(log)),
+            "_e{6,19}:Deploy|Deployed\\nversion 2|h:web-1|t:success|#env:prod,team:web\n"
+        );
+    }
+
+    #[test]
+    fn encode_dogstatsd_service_check() {
+        let mut log = LogEvent::default();
+        log.insert("check", "app.ok");
+        log.insert("status", "critical");
+        log.insert("message", "Database unreachable");
+
+        assert_eq!(
+            encode_to_string(&mut dogstatsd_encoder(), Event::Log(log)),
+            "_sc|app.ok|2|m:Database unreachable\n"
+        );
+    }
+
+    #[test]
+    fn encode_dogstatsd_invalid_logs() {
+        let mut encoder = dogstatsd_encoder();
+
+        let mut check = LogEvent::default();
+        check.insert("check", "app.ok");
+        check.insert("status", "broken");
+        assert_eq!(encode_to_string(&mut encoder, Event::Log(check)), "");
+
+        let untitled = LogEvent::from("no title");
+        assert_eq!(encode_to_string(&mut encoder, Event::Log(untitled)), "");
+    }
+
     #[tokio::test]
     async fn test_send_to_statsd() {
         trace_init();
@@ -479,6 +792,8 @@ mod test {
 
         let config = StatsdSinkConfig {
             default_namespace: Some("ns".into()),
+            flavor: Flavor::Statsd,
+            container_id_tag: None,
             mode: Mode::Udp(StatsdUdpConfig {
                 batch,
                 udp: UdpSinkConfig::from_address(addr.to_string()),
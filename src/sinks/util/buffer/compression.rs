@@ -8,6 +8,13 @@ pub const GZIP_FAST: u32 = 1;
 pub const GZIP_DEFAULT: u32 = 6;
 pub const GZIP_BEST: u32 = 9;
 
+pub const ZSTD_FAST: i32 = 1;
+pub const ZSTD_DEFAULT: i32 = 3;
+pub const ZSTD_BEST: i32 = 22;
+
+/// The range of window sizes, as a power of two, accepted for zstd long distance matching.
+const ZSTD_WINDOW_LOG_RANGE: std::ops::RangeInclusive<u32> = 10..=31;
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
@@ -15,6 +22,12 @@ pub enum Compression {
     None,
     Gzip(flate2::Compression),
     Zlib(flate2::Compression),
+    Zstd {
+        level: i32,
+        /// When set, enables long distance matching with a window of `2^long_window` bytes.
+        long_window: Option<u32>,
+    },
+    Snappy,
 }
 
 impl Compression {
@@ -44,11 +57,20 @@ impl Compression {
         Compression::Zlib(flate2::Compression::new(6))
     }
 
+    pub const fn zstd_default() -> Compression {
+        Compression::Zstd {
+            level: ZSTD_DEFAULT,
+            long_window: None,
+        }
+    }
+
     pub const fn content_encoding(self) -> Option<&'static str> {
         match self {
             Self::None => None,
             Self::Gzip(_) => Some("gzip"),
             Self::Zlib(_) => Some("deflate"),
+            Self::Zstd { .. } => Some("zstd"),
+            Self::Snappy => Some("snappy"),
         }
     }
 
@@ -57,6 +79,8 @@ impl Compression {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
             Self::Zlib(_) => "log.zz",
+            Self::Zstd { .. } => "log.zst",
+            Self::Snappy => "log.sz",
         }
     }
 }
@@ -66,6 +90,8 @@ impl fmt::Display for Compression {
             Compression::None => write!(f, "none"),
             Compression::Gzip(ref level) => write!(f, "gzip({})", level.level()),
             Compression::Zlib(ref level) => write!(f, "zlib({})", level.level()),
+            Compression::Zstd { level, .. } => write!(f, "zstd({})", level),
+            Compression::Snappy => write!(f, "snappy"),
         }
     }
 }
@@ -92,9 +118,11 @@ impl<'de> de::Deserialize<'de> for Compression {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
                     "zlib" => Ok(Compression::zlib_default()),
+                    "zstd" => Ok(Compression::zstd_default()),
+                    "snappy" => Ok(Compression::Snappy),
                     _ => Err(de::Error::invalid_value(
                         de::Unexpected::Str(s),
-                        &r#""none" or "gzip" or "zlib""#,
+                        &r#""none", "gzip", "zlib", "zstd" or "snappy""#,
                     )),
                 }
             }
@@ -103,8 +131,9 @@ impl<'de> de::Deserialize<'de> for Compression {
             where
                 A: de::MapAccess<'de>,
             {
-                let mut algorithm = None;
+                let mut algorithm: Option<String> = None;
                 let mut level = None;
+                let mut long_window = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
@@ -118,55 +147,79 @@ impl<'de> de::Deserialize<'de> for Compression {
                             if level.is_some() {
                                 return Err(de::Error::duplicate_field("level"));
                             }
-                            level = Some(match map.next_value::<Value>()? {
-                                Value::Number(level) => match level.as_u64() {
-                                    Some(value) if value <= 9 => {
-                                        flate2::Compression::new(value as u32)
-                                    }
-                                    Some(_) | None => {
-                                        return Err(de::Error::invalid_value(
-                                            de::Unexpected::Other(&level.to_string()),
-                                            &"0, 1, 2, 3, 4, 5, 6, 7, 8 or 9",
-                                        ))
-                                    }
-                                },
-                                Value::String(level) => match level.as_str() {
-                                    "none" => flate2::Compression::none(),
-                                    "fast" => flate2::Compression::fast(),
-                                    "default" => flate2::Compression::default(),
-                                    "best" => flate2::Compression::best(),
-                                    level => {
-                                        return Err(de::Error::invalid_value(
-                                            de::Unexpected::Str(level),
-                                            &r#""none", "fast", "best" or "default""#,
-                                        ))
-                                    }
-                                },
-                                value => {
-                                    return Err(de::Error::invalid_type(
-                                        de::Unexpected::Other(&value.to_string()),
-                                        &"integer or string",
-                                    ));
+                            let value = map.next_value::<Value>()?;
+                            // Report invalid levels as soon as possible when the algorithm is
+                            // already known, so the error points at the offending value.
+                            match algorithm.as_deref() {
+                                Some("gzip" | "zlib") => {
+                                    flate2_level::<A::Error>(&value)?;
                                 }
-                            });
+                                Some("zstd") => {
+                                    zstd_level::<A::Error>(&value)?;
+                                }
+                                _ => {}
+                            }
+                            level = Some(value);
+                        }
+                        "long_window" => {
+                            if long_window.is_some() {
+                                return Err(de::Error::duplicate_field("long_window"));
+                            }
+                            long_window = Some(map.next_value::<u32>()?);
+                        }
+                        _ => {
+                            return Err(de::Error::unknown_field(
+                                &key,
+                                &["algorithm", "level", "long_window"],
+                            ))
                         }
-                        _ => return Err(de::Error::unknown_field(&key, &["algorithm", "level"])),
                     };
                 }
 
-                match algorithm
-                    .ok_or_else(|| de::Error::missing_field("algorithm"))?
-                    .as_str()
-                {
-                    "none" => match level {
-                        Some(_) => Err(de::Error::unknown_field("level", &[])),
-                        None => Ok(Compression::None),
+                let algorithm = algorithm.ok_or_else(|| de::Error::missing_field("algorithm"))?;
+                match algorithm.as_str() {
+                    "none" | "snappy" => match (level, long_window) {
+                        (Some(_), _) => Err(de::Error::unknown_field("level", &[])),
+                        (None, Some(_)) => Err(de::Error::unknown_field("long_window", &[])),
+                        (None, None) if algorithm == "none" => Ok(Compression::None),
+                        (None, None) => Ok(Compression::Snappy),
                     },
-                    "gzip" => Ok(Compression::Gzip(level.unwrap_or_default())),
-                    "zlib" => Ok(Compression::Zlib(level.unwrap_or_default())),
+                    "gzip" | "zlib" => {
+                        if long_window.is_some() {
+                            return Err(de::Error::unknown_field("long_window", &["level"]));
+                        }
+                        let level = level
+                            .as_ref()
+                            .map(flate2_level::<A::Error>)
+                            .transpose()?
+                            .unwrap_or_default();
+                        Ok(if algorithm == "gzip" {
+                            Compression::Gzip(level)
+                        } else {
+                            Compression::Zlib(level)
+                        })
+                    }
+                    "zstd" => {
+                        if let Some(window_log) = long_window {
+                            if !ZSTD_WINDOW_LOG_RANGE.contains(&window_log) {
+                                return Err(de::Error::invalid_value(
+                                    de::Unexpected::Unsigned(window_log.into()),
+                                    &"an integer between 10 and 31",
+                                ));
+                            }
+                        }
+                        Ok(Compression::Zstd {
+                            level: level
+                                .as_ref()
+                                .map(zstd_level::<A::Error>)
+                                .transpose()?
+                                .unwrap_or(ZSTD_DEFAULT),
+                            long_window,
+                        })
+                    }
                     algorithm => Err(de::Error::unknown_variant(
                         algorithm,
-                        &["none", "gzip", "zlib"],
+                        &["none", "gzip", "zlib", "zstd", "snappy"],
                     )),
                 }
             }
@@ -176,6 +229,59 @@ impl<'de> de::Deserialize<'de> for Compression {
     }
 }
 
+fn flate2_level<E: de::Error>(level: &Value) -> Result<flate2::Compression, E> {
+    match level {
+        Value::Number(level) => match level.as_u64() {
+            Some(value) if value <= 9 => Ok(flate2::Compression::new(value as u32)),
+            Some(_) | None => Err(de::Error::invalid_value(
+                de::Unexpected::Other(&level.to_string()),
+                &"0, 1, 2, 3, 4, 5, 6, 7, 8 or 9",
+            )),
+        },
+        Value::String(level) => match level.as_str() {
+            "none" => Ok(flate2::Compression::none()),
+            "fast" => Ok(flate2::Compression::fast()),
+            "default" => Ok(flate2::Compression::default()),
+            "best" => Ok(flate2::Compression::best()),
+            level => Err(de::Error::invalid_value(
+                de::Unexpected::Str(level),
+                &r#""none", "fast", "best" or "default""#,
+            )),
+        },
+        value => Err(de::Error::invalid_type(
+            de::Unexpected::Other(&value.to_string()),
+            &"integer or string",
+        )),
+    }
+}
+
+fn zstd_level<E: de::Error>(level: &Value) -> Result<i32, E> {
+    match level {
+        Value::Number(level) => match level.as_u64() {
+            Some(value) if (ZSTD_FAST as u64..=ZSTD_BEST as u64).contains(&value) => {
+                Ok(value as i32)
+            }
+            Some(_) | None => Err(de::Error::invalid_value(
+                de::Unexpected::Other(&level.to_string()),
+                &"an integer between 1 and 22",
+            )),
+        },
+        Value::String(level) => match level.as_str() {
+            "fast" => Ok(ZSTD_FAST),
+            "default" => Ok(ZSTD_DEFAULT),
+            "best" => Ok(ZSTD_BEST),
+            level => Err(de::Error::invalid_value(
+                de::Unexpected::Str(level),
+                &r#""fast", "best" or "default""#,
+            )),
+        },
+        value => Err(de::Error::invalid_type(
+            de::Unexpected::Other(&value.to_string()),
+            &"integer or string",
+        )),
+    }
+}
+
 impl ser::Serialize for Compression {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -195,6 +301,20 @@ impl ser::Serialize for Compression {
                 map.serialize_entry("algorithm", "zlib")?;
                 level = Some(*zlib_level);
             }
+            Compression::Zstd {
+                level: zstd_level,
+                long_window,
+            } => {
+                map.serialize_entry("algorithm", "zstd")?;
+                // Don't serialize the default level, as with the flate2-based algorithms below.
+                if *zstd_level != ZSTD_DEFAULT {
+                    map.serialize_entry("level", zstd_level)?;
+                }
+                if let Some(long_window) = long_window {
+                    map.serialize_entry("long_window", long_window)?;
+                }
+            }
+            Compression::Snappy => map.serialize_entry("algorithm", "snappy")?,
         }
 
         if let Some(level) = level {
@@ -262,6 +382,31 @@ mod test {
                 r#"{"algorithm": "zlib", "level": 8}"#,
                 Compression::Zlib(flate2::Compression::new(8)),
             ),
+            (r#""zstd""#, Compression::zstd_default()),
+            (r#"{"algorithm": "zstd"}"#, Compression::zstd_default()),
+            (
+                r#"{"algorithm": "zstd", "level": "best"}"#,
+                Compression::Zstd {
+                    level: 22,
+                    long_window: None,
+                },
+            ),
+            (
+                r#"{"algorithm": "zstd", "level": 19, "long_window": 27}"#,
+                Compression::Zstd {
+                    level: 19,
+                    long_window: Some(27),
+                },
+            ),
+            (
+                r#"{"long_window": 27, "level": 19, "algorithm": "zstd"}"#,
+                Compression::Zstd {
+                    level: 19,
+                    long_window: Some(27),
+                },
+            ),
+            (r#""snappy""#, Compression::Snappy),
+            (r#"{"algorithm": "snappy"}"#, Compression::Snappy),
         ];
         for (sources, result) in fixtures_valid.iter() {
             let deserialized: Result<Compression, _> = serde_json::from_str(sources);
@@ -275,11 +420,11 @@ mod test {
             ),
             (
                 r#""b42""#,
-                r#"invalid value: string "b42", expected "none" or "gzip" or "zlib" at line 1 column 5"#,
+                r#"invalid value: string "b42", expected "none", "gzip", "zlib", "zstd" or "snappy" at line 1 column 5"#,
             ),
             (
                 r#"{"algorithm": "b42"}"#,
-                r#"unknown variant `b42`, expected one of `none`, `gzip`, `zlib` at line 1 column 20"#,
+                r#"unknown variant `b42`, expected one of `none`, `gzip`, `zlib`, `zstd`, `snappy` at line 1 column 20"#,
             ),
             (
                 r#"{"algorithm": "none", "level": "default"}"#,
@@ -299,7 +444,19 @@ mod test {
             ),
             (
                 r#"{"algorithm": "gzip", "level": "default", "key": 42}"#,
-                r#"unknown field `key`, expected `algorithm` or `level` at line 1 column 47"#,
+                r#"unknown field `key`, expected one of `algorithm`, `level`, `long_window` at line 1 column 47"#,
+            ),
+            (
+                r#"{"algorithm": "zstd", "level": 23}"#,
+                r#"invalid value: 23, expected an integer between 1 and 22 at line 1 column 34"#,
+            ),
+            (
+                r#"{"algorithm": "gzip", "long_window": 27}"#,
+                r#"unknown field `long_window`, expected `level` at line 1 column 40"#,
+            ),
+            (
+                r#"{"algorithm": "snappy", "level": "best"}"#,
+                r#"unknown field `level`, there are no fields at line 1 column 40"#,
             ),
         ];
         for (source, result) in fixtures_invalid.iter() {
@@ -317,6 +474,12 @@ mod test {
             Compression::Gzip(flate2::Compression::new(7)),
             Compression::Zlib(flate2::Compression::best()),
             Compression::Zlib(flate2::Compression::new(7)),
+            Compression::zstd_default(),
+            Compression::Zstd {
+                level: 19,
+                long_window: Some(27),
+            },
+            Compression::Snappy,
         ];
 
         for v in fixtures_valid {
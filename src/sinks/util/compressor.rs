@@ -1,7 +1,9 @@
-use std::io;
+use std::{fmt, io};
 
 use bytes::{BufMut, BytesMut};
 use flate2::write::{GzEncoder, ZlibEncoder};
+use snap::raw::Encoder as SnappyEncoder;
+use zstd::stream::write::Encoder as ZstdEncoder;
 
 use super::Compression;
 
@@ -9,6 +11,10 @@ enum Writer {
     Plain(bytes::buf::Writer<BytesMut>),
     Gzip(GzEncoder<bytes::buf::Writer<BytesMut>>),
     Zlib(ZlibEncoder<bytes::buf::Writer<BytesMut>>),
+    Zstd(ZstdEncoder<'static, bytes::buf::Writer<BytesMut>>),
+    /// Snappy compresses the payload as a single raw block, as expected of the `snappy` content
+    /// encoding, so the input is held as is until the writer finishes.
+    Snappy(bytes::buf::Writer<BytesMut>),
 }
 
 impl Writer {
@@ -17,17 +23,52 @@ impl Writer {
             Writer::Plain(inner) => inner.get_ref(),
             Writer::Gzip(inner) => inner.get_ref().get_ref(),
             Writer::Zlib(inner) => inner.get_ref().get_ref(),
+            Writer::Zstd(inner) => inner.get_ref().get_ref(),
+            Writer::Snappy(inner) => inner.get_ref(),
+        }
+    }
+
+    fn finish(self) -> io::Result<bytes::buf::Writer<BytesMut>> {
+        match self {
+            Writer::Plain(writer) => Ok(writer),
+            Writer::Gzip(writer) => writer.finish(),
+            Writer::Zlib(writer) => writer.finish(),
+            Writer::Zstd(writer) => writer.finish(),
+            Writer::Snappy(writer) => {
+                let compressed = SnappyEncoder::new().compress_vec(writer.get_ref())?;
+                Ok(BytesMut::from(compressed.as_slice()).writer())
+            }
         }
     }
 }
 
-impl From<Compression> for Writer {
-    fn from(compression: Compression) -> Self {
-        let writer = BytesMut::with_capacity(1_024).writer();
+fn zstd_encoder(
+    writer: bytes::buf::Writer<BytesMut>,
+    level: i32,
+    long_window: Option<u32>,
+) -> io::Result<ZstdEncoder<'static, bytes::buf::Writer<BytesMut>>> {
+    let mut encoder = ZstdEncoder::new(writer, level)?;
+    if let Some(window_log) = long_window {
+        encoder.long_distance_matching(true)?;
+        encoder.window_log(window_log)?;
+    }
+    Ok(encoder)
+}
+
+impl Writer {
+    fn with_capacity(compression: Compression, capacity: usize) -> Self {
+        let writer = BytesMut::with_capacity(capacity).writer();
         match compression {
             Compression::None => Writer::Plain(writer),
             Compression::Gzip(level) => Writer::Gzip(GzEncoder::new(writer, level)),
             Compression::Zlib(level) => Writer::Zlib(ZlibEncoder::new(writer, level)),
+            Compression::Zstd { level, long_window } => Writer::Zstd(
+                // The level and window size are validated when deserializing `Compression`, so
+                // this can only fail if the zstd context can't be allocated.
+                zstd_encoder(writer, level, long_window)
+                    .expect("zstd encoder should accept validated parameters"),
+            ),
+            Compression::Snappy => Writer::Snappy(writer),
         }
     }
 }
@@ -39,6 +80,8 @@ impl io::Write for Writer {
             Writer::Plain(inner_buf) => inner_buf.write(buf),
             Writer::Gzip(writer) => writer.write(buf),
             Writer::Zlib(writer) => writer.write(buf),
+            Writer::Zstd(writer) => writer.write(buf),
+            Writer::Snappy(writer) => writer.write(buf),
         }
     }
 
@@ -47,6 +90,8 @@ impl io::Write for Writer {
             Writer::Plain(writer) => writer.flush(),
             Writer::Gzip(writer) => writer.flush(),
             Writer::Zlib(writer) => writer.flush(),
+            Writer::Zstd(writer) => writer.flush(),
+            Writer::Snappy(writer) => writer.flush(),
         }
     }
 }
@@ -61,6 +106,11 @@ pub struct Compressor {
 
 impl Compressor {
     /// Gets a mutable reference to the underlying buffer.
+    ///
+    /// Some compression schemes, such as zstd, buffer their output internally, so the underlying
+    /// buffer may not reflect everything written so far until the compressor finishes.  Snappy
+    /// only compresses the input once the compressor finishes, so the underlying buffer holds the
+    /// uncompressed input until then.
     pub fn get_ref(&self) -> &BytesMut {
         self.inner.get_ref()
     }
@@ -84,14 +134,7 @@ impl Compressor {
     /// If the compressor encounters an I/O error while finalizing the payload, an error
     /// variant will be returned.
     pub fn finish(self) -> io::Result<BytesMut> {
-        let buf = match self.inner {
-            Writer::Plain(writer) => writer,
-            Writer::Gzip(writer) => writer.finish()?,
-            Writer::Zlib(writer) => writer.finish()?,
-        }
-        .into_inner();
-
-        Ok(buf)
+        Ok(self.inner.finish()?.into_inner())
     }
 
     /// Consumes the compressor, returning the internal buffer used by the compressor.
@@ -104,16 +147,10 @@ impl Compressor {
     ///
     /// Consider using `finish` if catching these scenarios is important.
     pub fn into_inner(self) -> BytesMut {
-        match self.inner {
-            Writer::Plain(writer) => writer,
-            Writer::Gzip(writer) => writer
-                .finish()
-                .expect("gzip writer should not fail to finish"),
-            Writer::Zlib(writer) => writer
-                .finish()
-                .expect("zlib writer should not fail to finish"),
-        }
-        .into_inner()
+        self.inner
+            .finish()
+            .expect("compressing writer should not fail to finish")
+            .into_inner()
     }
 }
 
@@ -128,11 +165,26 @@ impl io::Write for Compressor {
     }
 }
 
-impl From<Compression> for Compressor {
-    fn from(compression: Compression) -> Self {
+impl fmt::Debug for Compressor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compressor")
+            .field("compression", &self.compression)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Compressor {
+    /// Creates a compressor whose underlying buffer starts out with the given capacity.
+    pub fn with_capacity(compression: Compression, capacity: usize) -> Self {
         Compressor {
             compression,
-            inner: compression.into(),
+            inner: Writer::with_capacity(compression, capacity),
         }
     }
 }
+
+impl From<Compression> for Compressor {
+    fn from(compression: Compression) -> Self {
+        Compressor::with_capacity(compression, 1_024)
+    }
+}
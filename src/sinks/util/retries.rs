@@ -11,7 +11,10 @@ use futures::FutureExt;
 use tokio::time::{sleep, Sleep};
 use tower::{retry::Policy, timeout::error::Elapsed};
 
-use crate::Error;
+use crate::{
+    internal_events::{SinkRequestRetriesDone, SinkRequestRetrying},
+    Error,
+};
 
 pub enum RetryAction {
     /// Indicate that this request should be retried with a reason
@@ -84,19 +87,13 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
         let policy = self.advance();
         let delay = Box::pin(sleep(self.backoff()));
 
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
+        emit!(SinkRequestRetrying {
+            backoff: self.backoff()
+        });
         RetryPolicyFuture { delay, policy }
     }
-}
 
-impl<Req, Res, L> Policy<Req, Res, Error> for FixedRetryPolicy<L>
-where
-    Req: Clone,
-    L: RetryLogic<Response = Res>,
-{
-    type Future = RetryPolicyFuture<L>;
-
-    fn retry(&self, _: &Req, result: Result<&Res, &Error>) -> Option<Self::Future> {
+    fn retry_result(&self, result: Result<&L::Response, &Error>) -> Option<RetryPolicyFuture<L>> {
         match result {
             Ok(response) => match self.logic.should_retry_response(response) {
                 RetryAction::Retry(reason) => {
@@ -149,6 +146,22 @@ where
             }
         }
     }
+}
+
+impl<Req, Res, L> Policy<Req, Res, Error> for FixedRetryPolicy<L>
+where
+    Req: Clone,
+    L: RetryLogic<Response = Res>,
+{
+    type Future = RetryPolicyFuture<L>;
+
+    fn retry(&self, _: &Req, result: Result<&Res, &Error>) -> Option<Self::Future> {
+        let retry = self.retry_result(result);
+        if retry.is_none() {
+            emit!(SinkRequestRetriesDone);
+        }
+        retry
+    }
 
     fn clone_request(&self, request: &Req) -> Option<Req> {
         Some(request.clone())
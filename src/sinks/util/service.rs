@@ -13,6 +13,7 @@ use vector_buffers::Acker;
 pub use crate::sinks::util::service::{
     concurrency::{concurrency_is_none, Concurrency},
     map::Map,
+    rate_limit_group::{RateLimitGroup, RateLimitGroupLayer, RateLimitGroupService},
 };
 use crate::sinks::util::{
     adaptive_concurrency::{
@@ -26,8 +27,11 @@ use crate::sinks::util::{
 
 mod concurrency;
 mod map;
+mod rate_limit_group;
 
-pub type Svc<S, L> = RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>;
+pub type Svc<S, L> = RateLimitGroupService<
+    RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>,
+>;
 pub type TowerBatchedSink<S, B, RL> = BatchSink<Svc<S, RL>, B>;
 pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<Svc<S, RL>, B, K>;
 
@@ -41,6 +45,13 @@ pub trait ServiceBuilderExt<L> {
         settings: TowerRequestSettings,
         retry_logic: RL,
     ) -> ServiceBuilder<Stack<TowerRequestLayer<RL, Request>, L>>;
+
+    fn concurrency<RL>(
+        self,
+        config: ConcurrencyConfig,
+        default: Concurrency,
+        retry_logic: RL,
+    ) -> ServiceBuilder<Stack<AdaptiveConcurrencyLimitLayer<RL>, L>>;
 }
 
 impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
@@ -62,10 +73,36 @@ impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
             _pd: std::marker::PhantomData,
         })
     }
+
+    fn concurrency<RL>(
+        self,
+        config: ConcurrencyConfig,
+        default: Concurrency,
+        retry_logic: RL,
+    ) -> ServiceBuilder<Stack<AdaptiveConcurrencyLimitLayer<RL>, L>> {
+        self.layer(AdaptiveConcurrencyLimitLayer::new(
+            config.concurrency.parse_concurrency(default),
+            config.adaptive_concurrency,
+            retry_logic,
+        ))
+    }
+}
+
+/// Concurrency configuration, for sinks whose clients retry and time out requests themselves, so
+/// only the concurrency of their requests is limited. The errors and responses that
+/// `RetryLogic` considers retriable are treated as back pressure by adaptive concurrency.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConcurrencyConfig {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "concurrency_is_none")]
+    pub concurrency: Concurrency,
+    #[serde(default)]
+    pub adaptive_concurrency: AdaptiveConcurrencySettings,
 }
 
 /// Tower Request based configuration
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TowerRequestConfig {
     #[serde(default)]
     #[serde(skip_serializing_if = "concurrency_is_none")]
@@ -78,6 +115,9 @@ pub struct TowerRequestConfig {
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    /// The rate limit group whose budget the requests share with those of other sinks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_group: Option<String>,
 }
 
 pub const CONCURRENCY_DEFAULT: Concurrency = Concurrency::None;
@@ -105,6 +145,7 @@ impl TowerRequestConfig {
             retry_max_duration_secs: Some(RETRY_MAX_DURATION_SECONDS_DEFAULT),
             retry_initial_backoff_secs: Some(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             adaptive_concurrency: AdaptiveConcurrencySettings::const_default(),
+            rate_limit_group: None,
         }
     }
 
@@ -174,6 +215,7 @@ impl TowerRequestConfig {
                     .unwrap_or(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             ),
             adaptive_concurrency: self.adaptive_concurrency,
+            rate_limit_group: self.rate_limit_group.clone(),
         }
     }
 }
@@ -188,6 +230,7 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    pub rate_limit_group: Option<String>,
 }
 
 impl TowerRequestSettings {
@@ -270,6 +313,9 @@ where
     fn layer(&self, inner: S) -> Self::Service {
         let policy = self.settings.retry_policy(self.retry_logic.clone());
         ServiceBuilder::new()
+            .layer(RateLimitGroupLayer::new(
+                self.settings.rate_limit_group.as_deref(),
+            ))
             .rate_limit(
                 self.settings.rate_limit_num,
                 self.settings.rate_limit_duration,
@@ -294,6 +340,7 @@ mod tests {
 
     use futures::{future, stream, FutureExt, SinkExt, StreamExt};
     use tokio::time::Duration;
+    use tokio_test::{assert_pending, assert_ready_ok};
 
     use super::*;
     use crate::sinks::util::{
@@ -337,6 +384,26 @@ mod tests {
         assert_eq!(cfg.concurrency, None);
     }
 
+    #[tokio::test]
+    async fn concurrency_config_defaults_to_fixed_limit() {
+        let config = toml::from_str::<ConcurrencyConfig>("").unwrap();
+        let (service, mut handle) = tower_test::mock::pair::<(), ()>();
+        let mut service = tower_test::mock::Spawn::new(
+            ServiceBuilder::new()
+                .concurrency(config, Concurrency::Fixed(1), RetryAlways)
+                .service(service),
+        );
+
+        assert_ready_ok!(service.poll_ready());
+        let response = service.call(());
+        assert_pending!(service.poll_ready());
+
+        let (_, send_response) = handle.next_request().await.unwrap();
+        send_response.send_response(());
+        response.await.unwrap();
+        assert_ready_ok!(service.poll_ready());
+    }
+
     #[tokio::test]
     async fn partition_sink_retry_concurrency() {
         let cfg = TowerRequestConfig {
@@ -0,0 +1,257 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, ready};
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{config::RateLimitGroupConfig, internal_events::RateLimitGroupThrottled};
+
+type Limiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// The rate limit groups referenced by sinks. They are kept across reloads so sinks of a group
+/// that are rebuilt keep sharing its budget with those that are not.
+static GROUPS: Lazy<Mutex<HashMap<String, Arc<RateLimitGroup>>>> = Lazy::new(Default::default);
+
+/// Limits the requests and bytes of events per second of all of the sinks of a rate limit group.
+pub struct RateLimitGroup {
+    name: String,
+    config: RateLimitGroupConfig,
+    requests: Option<Limiter>,
+    bytes: Option<Limiter>,
+}
+
+impl RateLimitGroup {
+    /// Returns the rate limit group named `name`, which is only replaced if its budget changed.
+    pub fn get(name: &str, config: &RateLimitGroupConfig) -> Arc<Self> {
+        let mut groups = GROUPS.lock().expect("poisoned lock");
+        match groups.get(name) {
+            Some(group) if group.config == *config => Arc::clone(group),
+            _ => {
+                let limiter = |max: NonZeroU32| RateLimiter::direct(Quota::per_second(max));
+                let group = Arc::new(Self {
+                    name: name.to_owned(),
+                    config: config.clone(),
+                    requests: config.max_requests_per_sec.map(limiter),
+                    bytes: config.max_bytes_per_sec.map(limiter),
+                });
+                groups.insert(name.to_owned(), Arc::clone(&group));
+                group
+            }
+        }
+    }
+
+    /// Returns the rate limit group named `name`, once the topology has set it up.
+    fn find(name: &str) -> Option<Arc<Self>> {
+        GROUPS.lock().expect("poisoned lock").get(name).cloned()
+    }
+
+    /// Waits until the sinks of the group can send one more request.
+    async fn until_request_ready(&self) {
+        if let Some(limiter) = &self.requests {
+            if limiter.check().is_err() {
+                emit!(RateLimitGroupThrottled {
+                    group: &self.name,
+                    limit: "requests",
+                });
+                limiter.until_ready().await;
+            }
+        }
+    }
+
+    /// Waits until the sinks of the group can process `bytes` more bytes of events.
+    pub async fn until_bytes_ready(&self, bytes: usize) {
+        let (limiter, max_bytes_per_sec) = match (&self.bytes, self.config.max_bytes_per_sec) {
+            (Some(limiter), Some(max_bytes_per_sec)) => (limiter, max_bytes_per_sec),
+            _ => return,
+        };
+        let mut remaining = bytes;
+        while remaining > 0 {
+            // The limiter can't let more bytes through at once than its budget.
+            let n = remaining.min(max_bytes_per_sec.get() as usize);
+            remaining -= n;
+            let n = NonZeroU32::new(n as u32).expect("n is never zero");
+            if limiter.check_n(n).is_err() {
+                emit!(RateLimitGroupThrottled {
+                    group: &self.name,
+                    limit: "bytes",
+                });
+                // This can only fail if `n` exceeds the budget.
+                let _ = limiter.until_n_ready(n).await;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RateLimitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitGroup")
+            .field("name", &self.name)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Delays the requests of a sink until its rate limit group can send them.
+#[derive(Clone, Debug)]
+pub struct RateLimitGroupLayer {
+    group: Option<Arc<RateLimitGroup>>,
+}
+
+impl RateLimitGroupLayer {
+    pub fn new(name: Option<&str>) -> Self {
+        Self {
+            group: name.and_then(RateLimitGroup::find),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitGroupLayer {
+    type Service = RateLimitGroupService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitGroupService {
+            inner,
+            group: self.group.clone(),
+            state: State::Empty,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimitGroupService<S> {
+    inner: S,
+    group: Option<Arc<RateLimitGroup>>,
+    state: State,
+}
+
+enum State {
+    Empty,
+    Waiting(BoxFuture<'static, ()>),
+    Ready,
+}
+
+impl<S, Request> Service<Request> for RateLimitGroupService<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(group) = &self.group {
+            loop {
+                self.state = match self.state {
+                    State::Ready => break,
+                    State::Waiting(ref mut wait) => {
+                        ready!(wait.as_mut().poll(cx));
+                        State::Ready
+                    }
+                    State::Empty => {
+                        let group = Arc::clone(group);
+                        State::Waiting(Box::pin(async move { group.until_request_ready().await }))
+                    }
+                };
+            }
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.state = State::Empty;
+        self.inner.call(request)
+    }
+}
+
+impl<S: Clone> Clone for RateLimitGroupService<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            group: self.group.clone(),
+            state: State::Empty,
+        }
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Empty => f.debug_tuple("State::Empty").finish(),
+            State::Waiting(_) => f
+                .debug_tuple("State::Waiting")
+                .field(&format_args!("..."))
+                .finish(),
+            State::Ready => f.debug_tuple("State::Ready").finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use tokio_test::{assert_pending, assert_ready_ok};
+
+    use super::*;
+
+    fn config(max_requests_per_sec: u32) -> RateLimitGroupConfig {
+        RateLimitGroupConfig {
+            max_requests_per_sec: NonZeroU32::new(max_requests_per_sec),
+            max_bytes_per_sec: NonZeroU32::new(100),
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_requests_budget() {
+        let group = RateLimitGroup::get("shares_requests_budget", &config(1));
+        let layer = RateLimitGroupLayer::new(Some("shares_requests_budget"));
+        let (first, _first_handle) = tower_test::mock::pair::<(), ()>();
+        let (second, _second_handle) = tower_test::mock::pair::<(), ()>();
+        let mut first = tower_test::mock::Spawn::new(layer.layer(first));
+        let mut second = tower_test::mock::Spawn::new(layer.layer(second));
+
+        // The budget of one request per second is shared by both services.
+        assert_ready_ok!(first.poll_ready());
+        let _response = first.call(());
+        assert_pending!(second.poll_ready());
+        assert!(Arc::ptr_eq(
+            &group,
+            &RateLimitGroup::get("shares_requests_budget", &config(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn limits_bytes_per_second() {
+        let group = RateLimitGroup::get("limits_bytes_per_second", &config(1));
+
+        // The first 100 bytes are let through right away, and the next 50 within half a second.
+        let start = Instant::now();
+        group.until_bytes_ready(150).await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn replaces_groups_whose_budget_changed() {
+        let group = RateLimitGroup::get("replaces_groups", &config(1));
+        let replaced = RateLimitGroup::get("replaces_groups", &config(2));
+        assert!(!Arc::ptr_eq(&group, &replaced));
+        assert!(Arc::ptr_eq(
+            &replaced,
+            &RateLimitGroup::find("replaces_groups").unwrap()
+        ));
+    }
+}
@@ -52,7 +52,11 @@ use tower::{Service, ServiceBuilder};
 use tracing::Instrument;
 // === StreamSink<Event> ===
 pub use vector_core::sink::StreamSink;
-use vector_core::{buffers::Acker, internal_event::EventsSent};
+use vector_core::{
+    buffers::Acker,
+    internal_event::{EventsAcknowledged, EventsSent},
+    stream::error_class,
+};
 
 use super::{
     batch::{Batch, EncodedBatch, FinalizersBatch, PushResult, StatefulBatch},
@@ -451,8 +455,14 @@ where
             .call(items)
             .err_into()
             .map(move |result| {
+                let error_class = result_error_class(&result);
                 let status = result_status(result);
                 finalizers.update_status(status);
+                emit!(EventsAcknowledged {
+                    count,
+                    status,
+                    error_class,
+                });
                 if status == EventStatus::Delivered {
                     emit!(EventsSent {
                         count,
@@ -560,6 +570,16 @@ fn result_status<R: Response + Send>(result: crate::Result<R>) -> EventStatus {
     }
 }
 
+/// Classifies why the request failed to deliver its events, if it did.
+fn result_error_class<R: Response>(result: &crate::Result<R>) -> Option<&'static str> {
+    match result {
+        Ok(response) if response.is_successful() => None,
+        Ok(response) if response.is_transient() => Some("transient_response"),
+        Ok(_) => Some("failed_response"),
+        Err(error) => Some(error_class(error.as_ref())),
+    }
+}
+
 // === Response ===
 
 pub trait Response: fmt::Debug {
@@ -608,6 +628,20 @@ mod tests {
         tokio::time::resume();
     }
 
+    #[test]
+    fn classifies_request_failures() {
+        assert_eq!(result_error_class(&Ok::<_, crate::Error>(())), None);
+
+        let error = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        assert_eq!(result_error_class::<()>(&Err(error.into())), Some("io"));
+
+        let error = tower::timeout::error::Elapsed::new();
+        assert_eq!(
+            result_error_class::<()>(&Err(error.into())),
+            Some("timeout")
+        );
+    }
+
     #[tokio::test]
     async fn batch_sink_acking_sequential() {
         let (acker, ack_counter) = Acker::basic();
@@ -0,0 +1,321 @@
+use async_trait::async_trait;
+use aws_sdk_s3::Client as S3Client;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use snafu::Snafu;
+use tokio::io::AsyncReadExt;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use super::aws_s3::{
+    s3_object_decoder,
+    sqs::{self, ObjectProcessor, ProcessingError},
+    Compression,
+};
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::{s3::S3ClientBuilder, sqs::SqsClientBuilder},
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, LogEvent},
+    internal_events::{BytesReceived, EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    tls::TlsConfig,
+    SourceSender,
+};
+
+/// Configuration for the `aws_cloudtrail` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AwsCloudtrailConfig {
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+
+    /// Configuration options for SQS.
+    ///
+    /// The queue receives the notifications of the log files delivered by the trail, either sent by CloudTrail through
+    /// an SNS topic, or by the bucket.
+    sqs: Option<sqs::Config>,
+
+    /// Whether to send the digest files of the trail as events.
+    ///
+    /// Digest files are delivered when log file integrity validation is enabled. By default, they are skipped.
+    digest_files: bool,
+
+    #[configurable(derived)]
+    auth: AwsAuthentication,
+
+    #[configurable(derived)]
+    #[serde(deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+
+    #[configurable(derived)]
+    tls_options: Option<TlsConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<AwsCloudtrailConfig>("aws_cloudtrail")
+}
+
+impl_generate_config_from_default!(AwsCloudtrailConfig);
+
+#[async_trait]
+#[typetag::serde(name = "aws_cloudtrail")]
+impl SourceConfig for AwsCloudtrailConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let config = self.sqs.clone().ok_or(BuildError::SqsConfigMissing)?;
+        let region = self.region.region().ok_or(BuildError::RegionMissing)?;
+        let endpoint = self
+            .region
+            .endpoint()
+            .map_err(|_| BuildError::InvalidEndpoint)?;
+
+        let s3_client = create_client::<S3ClientBuilder>(
+            &self.auth,
+            Some(region.clone()),
+            endpoint.clone(),
+            &cx.proxy,
+            &self.tls_options,
+            false,
+        )
+        .await?;
+        let sqs_client = create_client::<SqsClientBuilder>(
+            &self.auth,
+            Some(region.clone()),
+            endpoint,
+            &cx.proxy,
+            &config.tls_options,
+            false,
+        )
+        .await?;
+
+        let processor = CloudtrailProcessor {
+            digest_files: self.digest_files,
+        };
+        let ingestor =
+            sqs::Ingestor::new(region, sqs_client, s3_client, config, Box::new(processor)).await?;
+
+        Ok(Box::pin(ingestor.run(cx, self.acknowledgements)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "aws_cloudtrail"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Configuration for `sqs` required"))]
+    SqsConfigMissing,
+    #[snafu(display("Region is required"))]
+    RegionMissing,
+    #[snafu(display("Endpoint is invalid"))]
+    InvalidEndpoint,
+}
+
+/// A CloudTrail log file, as documented in
+/// https://docs.aws.amazon.com/awscloudtrail/latest/userguide/cloudtrail-log-file-examples.html
+#[derive(Deserialize)]
+struct LogFile {
+    #[serde(rename = "Records")]
+    records: Vec<serde_json::Value>,
+}
+
+/// Sends the records of CloudTrail log files as individual events.
+struct CloudtrailProcessor {
+    digest_files: bool,
+}
+
+#[async_trait]
+impl ObjectProcessor for CloudtrailProcessor {
+    async fn process(
+        &self,
+        s3_client: &S3Client,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+        bucket: &str,
+        key: &str,
+        _region: &str,
+    ) -> Result<(), ProcessingError> {
+        let digest = is_digest_file(key);
+        if digest && !self.digest_files {
+            debug!(message = "Skipping CloudTrail digest file.", %bucket, %key);
+            return Ok(());
+        }
+
+        let object = s3_client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|source| ProcessingError::GetObject {
+                source,
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            })?;
+
+        // Log files are gzipped, which is detected from their `.gz` extension.
+        let mut reader = s3_object_decoder(
+            Compression::Auto,
+            key,
+            object.content_encoding.as_deref(),
+            object.content_type.as_deref(),
+            object.body,
+        )
+        .await;
+        let mut body = Vec::new();
+        reader
+            .read_to_end(&mut body)
+            .await
+            .map_err(|source| ProcessingError::ReadBody {
+                source,
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            })?;
+        emit!(BytesReceived {
+            byte_size: body.len(),
+            protocol: "http",
+        });
+
+        let mut events =
+            parse_object(&body, digest).map_err(|source| ProcessingError::ParseObject {
+                source,
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            })?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
+        if let Err(error) = out.send_batch(events).await {
+            emit!(StreamClosedError { error, count });
+            return Err(ProcessingError::PipelineSend {
+                source: crate::source_sender::ClosedError,
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            });
+        }
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement),
+                // Failed events cannot be retried, so the object is considered processed.
+                BatchStatus::Rejected => Ok(()),
+            },
+        }
+    }
+}
+
+/// Returns whether an object is a digest file, rather than a log file.
+///
+/// https://docs.aws.amazon.com/awscloudtrail/latest/userguide/cloudtrail-log-file-validation-digest-file-structure.html
+fn is_digest_file(key: &str) -> bool {
+    key.contains("/CloudTrail-Digest/")
+}
+
+/// Parses a log file into one event per record, or a digest file into a single event.
+fn parse_object(body: &[u8], digest: bool) -> Result<Vec<LogEvent>, serde_json::Error> {
+    let (values, timestamp_field) = if digest {
+        (vec![serde_json::from_slice(body)?], "digestEndTime")
+    } else {
+        let file: LogFile = serde_json::from_slice(body)?;
+        (file.records, "eventTime")
+    };
+
+    Ok(values
+        .into_iter()
+        .filter_map(|value| LogEvent::try_from(value).ok())
+        .map(|mut log| {
+            let timestamp = log
+                .get(timestamp_field)
+                .and_then(|value| value.as_str())
+                .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now);
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert(
+                log_schema().source_type_key(),
+                Bytes::from("aws_cloudtrail"),
+            );
+            log
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AwsCloudtrailConfig>();
+    }
+
+    #[test]
+    fn detects_digest_files() {
+        assert!(is_digest_file("AWSLogs/123456789012/CloudTrail-Digest/us-east-1/2022/08/01/123456789012_CloudTrail-Digest_us-east-1_trail_us-east-1_20220801T000000Z.json.gz"));
+        assert!(!is_digest_file("AWSLogs/123456789012/CloudTrail/us-east-1/2022/08/01/123456789012_CloudTrail_us-east-1_20220801T0000Z_a1b2c3.json.gz"));
+    }
+
+    #[test]
+    fn parses_log_file() {
+        let body = br#"{"Records": [
+            {"eventVersion": "1.08", "eventTime": "2022-08-01T00:00:01Z", "eventSource": "s3.amazonaws.com", "eventName": "GetObject", "awsRegion": "us-east-1"},
+            {"eventVersion": "1.08", "eventTime": "2022-08-01T00:00:02Z", "eventSource": "iam.amazonaws.com", "eventName": "CreateUser", "awsRegion": "us-east-1"}
+        ]}"#;
+
+        let events = parse_object(body, false).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["eventName"], "CreateUser".into());
+        assert_eq!(
+            events[1][log_schema().timestamp_key()],
+            Utc.ymd(2022, 8, 1).and_hms(0, 0, 2).into()
+        );
+        assert_eq!(
+            events[1][log_schema().source_type_key()],
+            "aws_cloudtrail".into()
+        );
+    }
+
+    #[test]
+    fn parses_digest_file() {
+        let body = br#"{"awsAccountId": "123456789012", "digestStartTime": "2022-08-01T00:00:00Z", "digestEndTime": "2022-08-01T01:00:00Z", "logFiles": []}"#;
+
+        let events = parse_object(body, true).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["awsAccountId"], "123456789012".into());
+        assert_eq!(
+            events[0][log_schema().timestamp_key()],
+            Utc.ymd(2022, 8, 1).and_hms(1, 0, 0).into()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_log_file() {
+        assert!(parse_object(br#"{"eventName": "GetObject"}"#, false).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use aws_sdk_cloudwatchlogs::{model::FilteredLogEvent, Client as CloudwatchLogsClient};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use vector_config::configurable_component;
+
+use super::{create_event, load_checkpoint, send_events};
+use crate::{
+    internal_events::{
+        AwsCloudwatchLogsCheckpointError, AwsCloudwatchLogsFilterError, BytesReceived,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::CheckpointStore,
+    SourceSender,
+};
+
+pub(super) const CHECKPOINT_FILENAME: &str = "filter.json";
+
+/// Filtering configuration options.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The names of the log groups to consume log events from.
+    pub(super) log_group_names: Vec<String>,
+
+    /// Only log events of the log streams whose name starts with this prefix are consumed.
+    #[serde(default)]
+    pub(super) log_stream_name_prefix: Option<String>,
+
+    /// The [filter pattern](\(urls.aws_cloudwatch_logs_filter_pattern)) log events must match to be consumed.
+    ///
+    /// By default, all log events are consumed.
+    #[serde(default)]
+    pub(super) filter_pattern: Option<String>,
+
+    /// How long to wait between filterings of the log groups, in seconds.
+    #[serde(default = "default_poll_secs")]
+    #[derivative(Default(value = "default_poll_secs()"))]
+    pub(super) poll_secs: u32,
+
+    /// How long to keep looking for log events after their timestamp, in seconds.
+    ///
+    /// Log events are filtered by their timestamp, which is set by their producer, so they can be ingested into
+    /// CloudWatch Logs some time after it. Log events ingested later than this are missed.
+    #[serde(default = "default_ingestion_delay_secs")]
+    #[derivative(Default(value = "default_ingestion_delay_secs()"))]
+    pub(super) ingestion_delay_secs: u32,
+}
+
+const fn default_poll_secs() -> u32 {
+    10
+}
+
+const fn default_ingestion_delay_secs() -> u32 {
+    300
+}
+
+/// The progress of each log group.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct Checkpoint {
+    pub(super) log_groups: BTreeMap<String, GroupCheckpoint>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub(super) struct GroupCheckpoint {
+    /// The timestamp from which log events are filtered, in milliseconds.
+    pub(super) start_time: i64,
+
+    /// The log events consumed in each log stream since `start_time`, to skip them when they are
+    /// filtered again.
+    pub(super) streams: BTreeMap<String, StreamCheckpoint>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct StreamCheckpoint {
+    /// The timestamp of the latest log event consumed, in milliseconds.
+    timestamp: i64,
+
+    /// The IDs of the log events consumed with that timestamp.
+    ids: Vec<String>,
+}
+
+impl GroupCheckpoint {
+    fn new(start_time: i64) -> Self {
+        Self {
+            start_time,
+            streams: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether a log event was consumed already.
+    fn contains(&self, stream: &str, timestamp: i64, id: &str) -> bool {
+        self.streams.get(stream).map_or(false, |checkpoint| {
+            timestamp < checkpoint.timestamp
+                || (timestamp == checkpoint.timestamp
+                    && checkpoint.ids.iter().any(|seen| seen == id))
+        })
+    }
+
+    fn insert(&mut self, stream: &str, timestamp: i64, id: &str) {
+        let checkpoint = self.streams.entry(stream.to_owned()).or_default();
+        if timestamp > checkpoint.timestamp {
+            checkpoint.timestamp = timestamp;
+            checkpoint.ids.clear();
+        }
+        if timestamp == checkpoint.timestamp {
+            checkpoint.ids.push(id.to_owned());
+        }
+    }
+
+    /// Moves `start_time` forward, forgetting the log streams without log events since.
+    fn advance(&mut self, start_time: i64) {
+        self.start_time = self.start_time.max(start_time);
+        let start_time = self.start_time;
+        self.streams
+            .retain(|_, checkpoint| checkpoint.timestamp >= start_time);
+    }
+}
+
+pub(super) struct Ingestor {
+    client: CloudwatchLogsClient,
+    config: Config,
+    checkpoint_store: CheckpointStore,
+}
+
+impl Ingestor {
+    pub(super) fn new(
+        client: CloudwatchLogsClient,
+        config: Config,
+        checkpoint_store: CheckpointStore,
+    ) -> Self {
+        Self {
+            client,
+            config,
+            checkpoint_store,
+        }
+    }
+
+    pub(super) async fn run(
+        self,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+        acknowledgements: bool,
+    ) -> Result<(), ()> {
+        let mut checkpoint: Checkpoint = load_checkpoint(&self.checkpoint_store).await?;
+        // Log groups no longer configured are forgotten.
+        checkpoint
+            .log_groups
+            .retain(|name, _| self.config.log_group_names.contains(name));
+
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.poll_secs.into()));
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {},
+            }
+
+            for log_group in &self.config.log_group_names {
+                let group_checkpoint = checkpoint
+                    .log_groups
+                    .entry(log_group.clone())
+                    // Log groups are consumed from the time they are first configured.
+                    .or_insert_with(|| GroupCheckpoint::new(Utc::now().timestamp_millis()));
+                select! {
+                    _ = &mut shutdown => return Ok(()),
+                    _ = self.poll(log_group, group_checkpoint, &mut out, acknowledgements) => {},
+                }
+                if let Err(error) = self.checkpoint_store.save_json(&checkpoint).await {
+                    emit!(AwsCloudwatchLogsCheckpointError { error });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the log events of a log group since its checkpoint.
+    async fn poll(
+        &self,
+        log_group: &str,
+        checkpoint: &mut GroupCheckpoint,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+    ) {
+        let now = Utc::now().timestamp_millis();
+        let mut next_token = None;
+        loop {
+            let result = self
+                .client
+                .filter_log_events()
+                .log_group_name(log_group)
+                .set_log_stream_name_prefix(self.config.log_stream_name_prefix.clone())
+                .set_filter_pattern(self.config.filter_pattern.clone())
+                .start_time(checkpoint.start_time)
+                .set_next_token(next_token)
+                .send()
+                .await;
+            let output = match result {
+                Ok(output) => output,
+                Err(error) => {
+                    emit!(AwsCloudwatchLogsFilterError {
+                        log_group,
+                        error: &error,
+                    });
+                    return;
+                }
+            };
+
+            let log_events = output.events.unwrap_or_default();
+            let mut consumed = Vec::new();
+            let mut events = Vec::new();
+            for log_event in log_events {
+                if let Some((stream, timestamp, id, message)) = parse(log_event) {
+                    if checkpoint.contains(&stream, timestamp, &id) {
+                        continue;
+                    }
+                    emit!(BytesReceived {
+                        byte_size: message.len(),
+                        protocol: "http",
+                    });
+                    events.push(create_event(message, timestamp, log_group, &stream, &id));
+                    consumed.push((stream, timestamp, id));
+                }
+            }
+
+            // Undelivered log events are filtered again on the next poll.
+            if !send_events(out, events, acknowledgements).await {
+                return;
+            }
+            for (stream, timestamp, id) in consumed {
+                checkpoint.insert(&stream, timestamp, &id);
+            }
+
+            next_token = output.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        let ingestion_delay = i64::from(self.config.ingestion_delay_secs) * 1000;
+        checkpoint.advance(now - ingestion_delay);
+    }
+}
+
+fn parse(log_event: FilteredLogEvent) -> Option<(String, i64, String, String)> {
+    Some((
+        log_event.log_stream_name?,
+        log_event.timestamp?,
+        log_event.event_id?,
+        log_event.message?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_consumed_log_events() {
+        let mut checkpoint = GroupCheckpoint::new(1_000);
+        checkpoint.insert("a", 2_000, "1");
+        checkpoint.insert("a", 2_000, "2");
+        checkpoint.insert("b", 1_500, "3");
+
+        assert!(checkpoint.contains("a", 1_999, "0"));
+        assert!(checkpoint.contains("a", 2_000, "2"));
+        // Log events with the same timestamp as the last consumed one may arrive afterwards.
+        assert!(!checkpoint.contains("a", 2_000, "4"));
+        assert!(!checkpoint.contains("a", 2_001, "5"));
+        // Log streams are checkpointed independently.
+        assert!(!checkpoint.contains("b", 1_999, "6"));
+        assert!(!checkpoint.contains("c", 1_000, "7"));
+
+        checkpoint.insert("a", 2_001, "5");
+        assert!(checkpoint.contains("a", 2_000, "4"));
+
+        checkpoint.advance(1_800);
+        assert_eq!(checkpoint.start_time, 1_800);
+        assert!(!checkpoint.streams.contains_key("b"));
+        checkpoint.advance(1_200);
+        assert_eq!(checkpoint.start_time, 1_800);
+    }
+}
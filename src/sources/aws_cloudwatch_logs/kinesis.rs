@@ -0,0 +1,282 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use aws_sdk_kinesis::{
+    model::{Shard, ShardIteratorType},
+    Client as KinesisClient,
+};
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use vector_config::configurable_component;
+
+use super::{load_checkpoint, send_events, subscription};
+use crate::{
+    internal_events::{
+        AwsCloudwatchLogsCheckpointError, AwsCloudwatchLogsKinesisError,
+        AwsCloudwatchLogsSubscriptionDecodeError, BytesReceived,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::CheckpointStore,
+    SourceSender,
+};
+
+pub(super) const CHECKPOINT_FILENAME: &str = "kinesis.json";
+
+/// Kinesis configuration options.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The name of the Kinesis data stream the subscription filters send log events to.
+    pub(super) stream_name: String,
+
+    /// How long to wait between reads of the shards of the stream, in seconds.
+    #[serde(default = "default_poll_secs")]
+    #[derivative(Default(value = "default_poll_secs()"))]
+    pub(super) poll_secs: u32,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) start_position: StartPosition,
+}
+
+const fn default_poll_secs() -> u32 {
+    1
+}
+
+/// Where to start reading the shards of the stream from, when no checkpoint exists yet.
+///
+/// Shards created afterwards, such as when the stream is resharded, are always read from their start.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum StartPosition {
+    /// Only read the records added after the source starts.
+    #[derivative(Default)]
+    Latest,
+
+    /// Read all the records retained by the stream.
+    TrimHorizon,
+}
+
+/// The progress of each shard.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct Checkpoint {
+    shards: BTreeMap<String, ShardCheckpoint>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct ShardCheckpoint {
+    /// The sequence number of the latest record consumed.
+    sequence_number: Option<String>,
+
+    /// Whether the shard was closed, and all its records consumed.
+    finished: bool,
+}
+
+pub(super) struct Ingestor {
+    client: KinesisClient,
+    config: Config,
+    checkpoint_store: CheckpointStore,
+}
+
+impl Ingestor {
+    pub(super) fn new(
+        client: KinesisClient,
+        config: Config,
+        checkpoint_store: CheckpointStore,
+    ) -> Self {
+        Self {
+            client,
+            config,
+            checkpoint_store,
+        }
+    }
+
+    pub(super) async fn run(
+        self,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+        acknowledgements: bool,
+    ) -> Result<(), ()> {
+        let mut checkpoint: Checkpoint = load_checkpoint(&self.checkpoint_store).await?;
+        // The shards existing when there is no checkpoint yet are read from `start_position`.
+        let mut initial = checkpoint.shards.is_empty();
+        let mut iterators: HashMap<String, String> = HashMap::new();
+
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.poll_secs.into()));
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {},
+            }
+
+            let shards = match self.list_shards().await {
+                Ok(shards) => shards,
+                Err(error) => {
+                    emit!(AwsCloudwatchLogsKinesisError {
+                        stream_name: &self.config.stream_name,
+                        error: &error,
+                    });
+                    continue;
+                }
+            };
+            // Shards past the retention period of the stream are no longer listed.
+            checkpoint.shards.retain(|shard_id, _| {
+                shards
+                    .iter()
+                    .any(|shard| shard.shard_id() == Some(shard_id.as_str()))
+            });
+            iterators.retain(|shard_id, _| checkpoint.shards.contains_key(shard_id));
+
+            for shard in &shards {
+                let shard_id = match shard.shard_id() {
+                    Some(shard_id) => shard_id,
+                    None => continue,
+                };
+                if checkpoint
+                    .shards
+                    .entry(shard_id.to_owned())
+                    .or_default()
+                    .finished
+                {
+                    continue;
+                }
+                // The records of a key are read in order, so a child shard is only read once its
+                // parents are.
+                let parents = [shard.parent_shard_id(), shard.adjacent_parent_shard_id()];
+                if parents.iter().flatten().any(|parent| {
+                    checkpoint
+                        .shards
+                        .get(*parent)
+                        .map_or(false, |parent| !parent.finished)
+                }) {
+                    continue;
+                }
+
+                let result = select! {
+                    _ = &mut shutdown => return Ok(()),
+                    result = self.read_shard(shard_id, &mut checkpoint, &mut iterators, initial, &mut out, acknowledgements) => result,
+                };
+                if let Err(error) = result {
+                    emit!(AwsCloudwatchLogsKinesisError {
+                        stream_name: &self.config.stream_name,
+                        error: &error,
+                    });
+                    // A new iterator is requested from the checkpoint on the next poll.
+                    iterators.remove(shard_id);
+                }
+            }
+            initial = false;
+
+            if let Err(error) = self.checkpoint_store.save_json(&checkpoint).await {
+                emit!(AwsCloudwatchLogsCheckpointError { error });
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn list_shards(&self) -> crate::Result<Vec<Shard>> {
+        let mut shards = Vec::new();
+        let mut next_token = None;
+        loop {
+            let request = self.client.list_shards();
+            // The stream name can't be set along with the token of the next page.
+            let request = match next_token {
+                Some(token) => request.next_token(token),
+                None => request.stream_name(&self.config.stream_name),
+            };
+            let output = request.send().await?;
+            shards.extend(output.shards.unwrap_or_default());
+            next_token = output.next_token;
+            if next_token.is_none() {
+                return Ok(shards);
+            }
+        }
+    }
+
+    /// Consumes the next records of a shard.
+    async fn read_shard(
+        &self,
+        shard_id: &str,
+        checkpoint: &mut Checkpoint,
+        iterators: &mut HashMap<String, String>,
+        initial: bool,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+    ) -> crate::Result<()> {
+        let shard_checkpoint = checkpoint
+            .shards
+            .get_mut(shard_id)
+            .expect("shard checkpoint must exist");
+
+        let iterator = match iterators.remove(shard_id) {
+            Some(iterator) => iterator,
+            None => {
+                let request = self
+                    .client
+                    .get_shard_iterator()
+                    .stream_name(&self.config.stream_name)
+                    .shard_id(shard_id);
+                let request = match (&shard_checkpoint.sequence_number, initial) {
+                    (Some(sequence_number), _) => request
+                        .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+                        .starting_sequence_number(sequence_number),
+                    (None, true) => request.shard_iterator_type(match self.config.start_position {
+                        StartPosition::Latest => ShardIteratorType::Latest,
+                        StartPosition::TrimHorizon => ShardIteratorType::TrimHorizon,
+                    }),
+                    (None, false) => request.shard_iterator_type(ShardIteratorType::TrimHorizon),
+                };
+                match request.send().await?.shard_iterator {
+                    Some(iterator) => iterator,
+                    None => return Ok(()),
+                }
+            }
+        };
+
+        let output = self
+            .client
+            .get_records()
+            .shard_iterator(iterator)
+            .send()
+            .await?;
+        let records = output.records.unwrap_or_default();
+        let mut events = Vec::new();
+        let mut sequence_number = None;
+        for record in records {
+            if let Some(data) = record.data {
+                let data = data.into_inner();
+                emit!(BytesReceived {
+                    byte_size: data.len(),
+                    protocol: "http",
+                });
+                match subscription::decode(&data) {
+                    Ok(decoded) => events.extend(decoded),
+                    Err(error) => emit!(AwsCloudwatchLogsSubscriptionDecodeError { error }),
+                }
+            }
+            sequence_number = record.sequence_number.or(sequence_number);
+        }
+
+        // Undelivered records are read again from the checkpoint.
+        if !send_events(out, events, acknowledgements).await {
+            return Ok(());
+        }
+        if sequence_number.is_some() {
+            shard_checkpoint.sequence_number = sequence_number;
+        }
+        match output.next_shard_iterator {
+            Some(iterator) => {
+                iterators.insert(shard_id.to_owned(), iterator);
+            }
+            None => shard_checkpoint.finished = true,
+        }
+        Ok(())
+    }
+}
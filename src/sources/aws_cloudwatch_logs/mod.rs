@@ -0,0 +1,286 @@
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use serde::de::DeserializeOwned;
+use snafu::Snafu;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::{cloudwatch_logs::CloudwatchLogsClientBuilder, kinesis::KinesisClientBuilder},
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    internal_events::{EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    tls::TlsConfig,
+    SourceSender,
+};
+
+mod filter;
+mod kinesis;
+mod subscription;
+
+/// Strategies for consuming log events from CloudWatch Logs.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+enum Strategy {
+    /// Consumes log events by periodically filtering the log events of log groups.
+    #[derivative(Default)]
+    Filter,
+
+    /// Consumes log events sent to a Kinesis data stream by subscription filters.
+    Kinesis,
+}
+
+/// Configuration for the `aws_cloudwatch_logs` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AwsCloudwatchLogsConfig {
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+
+    /// The strategy to use to consume log events from CloudWatch Logs.
+    strategy: Strategy,
+
+    /// Configuration options for filtering.
+    ///
+    /// Only relevant when `strategy = "filter"`.
+    filter: Option<filter::Config>,
+
+    /// Configuration options for Kinesis.
+    ///
+    /// Only relevant when `strategy = "kinesis"`.
+    kinesis: Option<kinesis::Config>,
+
+    #[configurable(derived)]
+    auth: AwsAuthentication,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    /// The directory used to persist the checkpoints of the log streams, or of the Kinesis shards.
+    ///
+    /// By default, the global `data_dir` option is used. Make sure the running user has write permissions to this
+    /// directory.
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the checkpoints of the log streams, or of the Kinesis shards.
+    ///
+    /// By default, they're persisted in a file of `data_dir`.
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    #[serde(deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<AwsCloudwatchLogsConfig>("aws_cloudwatch_logs")
+}
+
+impl_generate_config_from_default!(AwsCloudwatchLogsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_cloudwatch_logs")]
+impl SourceConfig for AwsCloudwatchLogsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let region = self.region.region().ok_or(BuildError::RegionMissing)?;
+        let endpoint = self
+            .region
+            .endpoint()
+            .map_err(|_| BuildError::InvalidEndpoint)?;
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        match self.strategy {
+            Strategy::Filter => {
+                let config = self.filter.clone().ok_or(BuildError::FilterConfigMissing)?;
+                let client = create_client::<CloudwatchLogsClientBuilder>(
+                    &self.auth,
+                    Some(region),
+                    endpoint,
+                    &cx.proxy,
+                    &self.tls,
+                    false,
+                )
+                .await?;
+                let checkpoint_store = self
+                    .checkpoint_store
+                    .build(&cx, self.data_dir.as_ref(), filter::CHECKPOINT_FILENAME)
+                    .await?;
+                let ingestor = filter::Ingestor::new(client, config, checkpoint_store);
+                Ok(Box::pin(ingestor.run(
+                    cx.out,
+                    cx.shutdown,
+                    acknowledgements,
+                )))
+            }
+            Strategy::Kinesis => {
+                let config = self
+                    .kinesis
+                    .clone()
+                    .ok_or(BuildError::KinesisConfigMissing)?;
+                let client = create_client::<KinesisClientBuilder>(
+                    &self.auth,
+                    Some(region),
+                    endpoint,
+                    &cx.proxy,
+                    &self.tls,
+                    false,
+                )
+                .await?;
+                let checkpoint_store = self
+                    .checkpoint_store
+                    .build(&cx, self.data_dir.as_ref(), kinesis::CHECKPOINT_FILENAME)
+                    .await?;
+                let ingestor = kinesis::Ingestor::new(client, config, checkpoint_store);
+                Ok(Box::pin(ingestor.run(
+                    cx.out,
+                    cx.shutdown,
+                    acknowledgements,
+                )))
+            }
+        }
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "aws_cloudwatch_logs"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Configuration for `filter` required when strategy=filter"))]
+    FilterConfigMissing,
+    #[snafu(display("Configuration for `kinesis` required when strategy=kinesis"))]
+    KinesisConfigMissing,
+    #[snafu(display("Region is required"))]
+    RegionMissing,
+    #[snafu(display("Endpoint is invalid"))]
+    InvalidEndpoint,
+}
+
+/// Loads the progress of a strategy, which starts over if it's invalid.
+async fn load_checkpoint<T: Default + DeserializeOwned>(store: &CheckpointStore) -> Result<T, ()> {
+    store.load_json_or_default().await.map_err(|error| {
+        error!(
+            message = "Failed to load checkpoint.",
+            location = %store.location(),
+            %error,
+        );
+    })
+}
+
+/// Creates the event of a CloudWatch Logs log event.
+fn create_event(
+    message: String,
+    timestamp_ms: i64,
+    log_group: &str,
+    log_stream: &str,
+    id: &str,
+) -> Event {
+    let mut log = LogEvent::from(message);
+    log.insert(
+        log_schema().timestamp_key(),
+        Utc.timestamp_millis_opt(timestamp_ms)
+            .single()
+            .unwrap_or_else(Utc::now),
+    );
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("aws_cloudwatch_logs"),
+    );
+    log.insert("log_group", log_group.to_owned());
+    log.insert("log_stream", log_stream.to_owned());
+    log.insert("id", id.to_owned());
+    Event::Log(log)
+}
+
+/// Sends the events, returning whether their progress can be checkpointed, which is once they are
+/// delivered when acknowledgements are enabled.
+async fn send_events(
+    out: &mut SourceSender,
+    mut events: Vec<Event>,
+    acknowledgements: bool,
+) -> bool {
+    if events.is_empty() {
+        return true;
+    }
+
+    let count = events.len();
+    emit!(EventsReceived {
+        count,
+        byte_size: events.size_of(),
+    });
+
+    let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
+    if let Err(error) = out.send_batch(events).await {
+        emit!(StreamClosedError { error, count });
+        return false;
+    }
+
+    match receiver {
+        None => true,
+        // Rejected events can't be retried, so they are checkpointed as well.
+        Some(receiver) => receiver.await != BatchStatus::Errored,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AwsCloudwatchLogsConfig>();
+    }
+
+    #[test]
+    fn parses_strategies() {
+        let config: AwsCloudwatchLogsConfig = toml::from_str(
+            r#"
+            region = "us-east-1"
+            filter.log_group_names = ["/aws/lambda/app"]
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.strategy, Strategy::Filter));
+        assert_eq!(
+            config.filter.unwrap().log_group_names,
+            vec!["/aws/lambda/app"]
+        );
+
+        let config: AwsCloudwatchLogsConfig = toml::from_str(
+            r#"
+            region = "us-east-1"
+            strategy = "kinesis"
+            kinesis.stream_name = "logs"
+            kinesis.start_position = "trim_horizon"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.strategy, Strategy::Kinesis));
+        let config = config.kinesis.unwrap();
+        assert_eq!(config.stream_name, "logs");
+        assert!(matches!(
+            config.start_position,
+            kinesis::StartPosition::TrimHorizon
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{self, Read};
+
+use flate2::read::MultiGzDecoder;
+use serde::Deserialize;
+
+use super::create_event;
+use crate::event::Event;
+
+/// The payload sent by a subscription filter, as documented in
+/// https://docs.aws.amazon.com/AmazonCloudWatch/latest/logs/SubscriptionFilters.html
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionMessage {
+    message_type: MessageType,
+    owner: String,
+    log_group: String,
+    log_stream: String,
+    #[serde(default)]
+    log_events: Vec<SubscriptionLogEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum MessageType {
+    DataMessage,
+    /// Sent to check that the destination is reachable.
+    ControlMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionLogEvent {
+    id: String,
+    timestamp: i64,
+    message: String,
+}
+
+/// Decodes the gzipped payload of a subscription filter into the events of its log events.
+pub(super) fn decode(data: &[u8]) -> io::Result<Vec<Event>> {
+    let mut decoded = Vec::new();
+    MultiGzDecoder::new(data).read_to_end(&mut decoded)?;
+    let message: SubscriptionMessage = serde_json::from_slice(&decoded)?;
+
+    Ok(match message.message_type {
+        MessageType::ControlMessage => Vec::new(),
+        MessageType::DataMessage => message
+            .log_events
+            .into_iter()
+            .map(|log_event| {
+                let mut event = create_event(
+                    log_event.message,
+                    log_event.timestamp,
+                    &message.log_group,
+                    &message.log_stream,
+                    &log_event.id,
+                );
+                event.as_mut_log().insert("owner", message.owner.clone());
+                event
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::config::log_schema;
+
+    fn gzip(data: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decodes_data_message() {
+        let data = gzip(
+            r#"{
+                "messageType": "DATA_MESSAGE",
+                "owner": "111111111111",
+                "logGroup": "/aws/lambda/app",
+                "logStream": "2022/08/01/[$LATEST]abc",
+                "subscriptionFilters": ["vector"],
+                "logEvents": [
+                    {"id": "36984", "timestamp": 1659312000000, "message": "started"},
+                    {"id": "36985", "timestamp": 1659312000001, "message": "stopped"}
+                ]
+            }"#,
+        );
+
+        let events = decode(&data).unwrap();
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "stopped".into());
+        assert_eq!(log["log_group"], "/aws/lambda/app".into());
+        assert_eq!(log["log_stream"], "2022/08/01/[$LATEST]abc".into());
+        assert_eq!(log["id"], "36985".into());
+        assert_eq!(log["owner"], "111111111111".into());
+    }
+
+    #[test]
+    fn skips_control_message() {
+        let data = gzip(
+            r#"{
+                "messageType": "CONTROL_MESSAGE",
+                "owner": "CloudwatchLogs",
+                "logGroup": "",
+                "logStream": "",
+                "subscriptionFilters": [],
+                "logEvents": [
+                    {"id": "", "timestamp": 1659312000000, "message": "CWL CONTROL MESSAGE: Checking health of destination Kinesis stream."}
+                ]
+            }"#,
+        );
+
+        assert!(decode(&data).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_uncompressed_payload() {
+        assert!(decode(br#"{"messageType": "DATA_MESSAGE"}"#).is_err());
+    }
+}
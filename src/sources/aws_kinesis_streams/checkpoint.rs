@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{model::AttributeValue, Client as DynamoDbClient};
+use serde::{Deserialize, Serialize};
+use vector_config::configurable_component;
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+
+/// The attribute of the DynamoDB items holding the name of the stream, which must be the partition
+/// key of the table.
+const STREAM_NAME_ATTRIBUTE: &str = "stream_name";
+const CHECKPOINT_ATTRIBUTE: &str = "checkpoint";
+
+/// Where to persist the progress of the shards.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(super) enum CheckpointConfig {
+    /// A file in the data directory.
+    #[derivative(Default)]
+    File {
+        /// The directory used to persist the checkpoint.
+        ///
+        /// By default, the global `data_dir` option is used. Make sure the running user has write permissions to
+        /// this directory.
+        #[serde(default)]
+        data_dir: Option<PathBuf>,
+    },
+
+    /// An item in a DynamoDB table, which outlives the host running Vector.
+    #[serde(rename = "dynamodb")]
+    DynamoDb {
+        /// The name of the table to store the checkpoint in.
+        ///
+        /// The partition key of the table must be a string attribute named `stream_name`, so that a table can
+        /// hold the checkpoints of several streams.
+        table: String,
+    },
+}
+
+/// The progress of each shard.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct Checkpoint {
+    pub(super) shards: BTreeMap<String, ShardCheckpoint>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct ShardCheckpoint {
+    /// The sequence number after which the shard is read.
+    pub(super) sequence_number: Option<String>,
+
+    /// Whether the shard was closed, and all its records consumed.
+    pub(super) finished: bool,
+}
+
+#[async_trait]
+pub(super) trait CheckpointStore: Send + Sync {
+    /// Loads the checkpoint, which is empty if none was saved yet.
+    async fn load(&self) -> crate::Result<Checkpoint>;
+
+    async fn save(&self, checkpoint: &Checkpoint) -> crate::Result<()>;
+}
+
+pub(super) struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub(super) fn new(data_dir: PathBuf) -> Self {
+        Self {
+            path: data_dir.join(CHECKPOINT_FILENAME),
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self) -> crate::Result<Checkpoint> {
+        match tokio::fs::read(&self.path).await {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Checkpoint::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn save(&self, checkpoint: &Checkpoint) -> crate::Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec(checkpoint)?).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+pub(super) struct DynamoDbCheckpointStore {
+    pub(super) client: DynamoDbClient,
+    pub(super) table: String,
+    pub(super) stream_name: String,
+}
+
+#[async_trait]
+impl CheckpointStore for DynamoDbCheckpointStore {
+    async fn load(&self) -> crate::Result<Checkpoint> {
+        let output = self
+            .client
+            .get_item()
+            .table_name(&self.table)
+            .key(
+                STREAM_NAME_ATTRIBUTE,
+                AttributeValue::S(self.stream_name.clone()),
+            )
+            .consistent_read(true)
+            .send()
+            .await?;
+        match output
+            .item
+            .as_ref()
+            .and_then(|item| item.get(CHECKPOINT_ATTRIBUTE))
+        {
+            Some(AttributeValue::S(contents)) => Ok(serde_json::from_str(contents)?),
+            Some(_) => Err(format!(
+                "Attribute `{}` of the checkpoint item is not a string",
+                CHECKPOINT_ATTRIBUTE
+            )
+            .into()),
+            None => Ok(Checkpoint::default()),
+        }
+    }
+
+    async fn save(&self, checkpoint: &Checkpoint) -> crate::Result<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table)
+            .item(
+                STREAM_NAME_ATTRIBUTE,
+                AttributeValue::S(self.stream_name.clone()),
+            )
+            .item(
+                CHECKPOINT_ATTRIBUTE,
+                AttributeValue::S(serde_json::to_string(checkpoint)?),
+            )
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_checkpoint_roundtrip() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = FileCheckpointStore::new(data_dir.path().to_owned());
+        assert_eq!(store.load().await.unwrap(), Checkpoint::default());
+
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.shards.insert(
+            "shardId-000000000000".into(),
+            ShardCheckpoint {
+                sequence_number: None,
+                finished: true,
+            },
+        );
+        checkpoint.shards.insert(
+            "shardId-000000000001".into(),
+            ShardCheckpoint {
+                sequence_number: Some(
+                    "49590338271490256608559692538361571095921575989136588898".into(),
+                ),
+                finished: false,
+            },
+        );
+        store.save(&checkpoint).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), checkpoint);
+    }
+
+    #[test]
+    fn parses_checkpoint_config() {
+        let config: CheckpointConfig = toml::from_str(
+            r#"
+            type = "dynamodb"
+            table = "vector-checkpoints"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config,
+            CheckpointConfig::DynamoDb { table } if table == "vector-checkpoints"
+        ));
+        assert!(matches!(
+            CheckpointConfig::default(),
+            CheckpointConfig::File { data_dir: None }
+        ));
+    }
+}
@@ -0,0 +1,462 @@
+use std::{collections::HashMap, time::Duration};
+
+use aws_sdk_kinesis::{
+    model::{ConsumerStatus, Shard},
+    types::SdkError,
+    Client as KinesisClient,
+};
+use tokio::{select, sync::mpsc, task::JoinHandle, time::sleep};
+
+use super::{
+    checkpoint::{Checkpoint, CheckpointStore},
+    reader::{Progress, ShardReader, StartingPoint},
+    StartPosition,
+};
+use crate::{
+    codecs::Decoder,
+    internal_events::{AwsKinesisStreamsCheckpointError, AwsKinesisStreamsListShardsError},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+/// How long to wait between checks of the status of a consumer being registered.
+const CONSUMER_STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+pub(super) enum Mode {
+    Polling {
+        poll_interval: Duration,
+        max_records: i32,
+    },
+    EnhancedFanOut {
+        consumer_name: String,
+    },
+}
+
+/// How the readers read their shard, once the consumer of the stream is registered.
+#[derive(Clone)]
+enum ReadMode {
+    Polling {
+        poll_interval: Duration,
+        max_records: i32,
+    },
+    EnhancedFanOut {
+        consumer_arn: String,
+    },
+}
+
+pub(super) struct Ingestor {
+    pub(super) client: KinesisClient,
+    pub(super) stream_name: String,
+    pub(super) mode: Mode,
+    pub(super) start_position: StartPosition,
+    pub(super) decoder: Decoder,
+    pub(super) store: Box<dyn CheckpointStore>,
+    pub(super) checkpoint_interval: Duration,
+    pub(super) shard_discovery_interval: Duration,
+    pub(super) acknowledgements: bool,
+}
+
+/// The tasks reading the shards.
+struct Readers {
+    reader: ShardReader,
+    mode: ReadMode,
+    tasks: HashMap<String, JoinHandle<()>>,
+    out: SourceSender,
+    shutdown: ShutdownSignal,
+}
+
+impl Readers {
+    fn spawn(&mut self, shard_id: &str, start: StartingPoint) {
+        let reader = self.reader.clone();
+        let shard_id = shard_id.to_owned();
+        let out = self.out.clone();
+        let shutdown = self.shutdown.clone();
+        let task = match &self.mode {
+            ReadMode::Polling {
+                poll_interval,
+                max_records,
+            } => tokio::spawn(reader.poll(
+                shard_id.clone(),
+                start,
+                *poll_interval,
+                *max_records,
+                out,
+                shutdown,
+            )),
+            ReadMode::EnhancedFanOut { consumer_arn } => tokio::spawn(reader.subscribe(
+                consumer_arn.clone(),
+                shard_id.clone(),
+                start,
+                out,
+                shutdown,
+            )),
+        };
+        self.tasks.insert(shard_id, task);
+    }
+}
+
+impl Ingestor {
+    pub(super) async fn run(
+        self,
+        out: SourceSender,
+        mut shutdown: ShutdownSignal,
+    ) -> Result<(), ()> {
+        // Without a checkpoint, the shards would be read again from `start_position`, so it is
+        // better to stop than to go on.
+        let mut checkpoint = self.store.load().await.map_err(|error| {
+            error!(message = "Failed to load checkpoint of Kinesis shards.", %error);
+        })?;
+
+        let mode = match &self.mode {
+            Mode::Polling {
+                poll_interval,
+                max_records,
+            } => ReadMode::Polling {
+                poll_interval: *poll_interval,
+                max_records: *max_records,
+            },
+            Mode::EnhancedFanOut { consumer_name } => {
+                let result = select! {
+                    _ = &mut shutdown => return Ok(()),
+                    result = self.register_consumer(consumer_name) => result,
+                };
+                let consumer_arn = result.map_err(|error| {
+                    error!(message = "Failed to register stream consumer.", %consumer_name, %error);
+                })?;
+                ReadMode::EnhancedFanOut { consumer_arn }
+            }
+        };
+
+        let (progress_sender, mut progress_receiver) = mpsc::unbounded_channel();
+        let mut readers = Readers {
+            reader: ShardReader {
+                client: self.client.clone(),
+                stream_name: self.stream_name.clone(),
+                decoder: self.decoder.clone(),
+                acknowledgements: self.acknowledgements,
+                progress: progress_sender,
+            },
+            mode,
+            tasks: HashMap::new(),
+            out,
+            shutdown: shutdown.clone(),
+        };
+        // The shards existing when there is no checkpoint yet are read from `start_position`.
+        let mut initial = checkpoint.shards.is_empty();
+        let mut dirty = false;
+
+        let mut discovery = tokio::time::interval(self.shard_discovery_interval);
+        let mut checkpointing = tokio::time::interval(self.checkpoint_interval);
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = discovery.tick() => {
+                    if self.discover_shards(&mut checkpoint, &mut readers, initial).await {
+                        initial = false;
+                        dirty = true;
+                    }
+                }
+                Some(progress) = progress_receiver.recv() => {
+                    dirty = true;
+                    if apply_progress(&mut checkpoint, progress, &mut readers.tasks) {
+                        // The children of the closed shard can be read now.
+                        self.discover_shards(&mut checkpoint, &mut readers, false).await;
+                    }
+                }
+                _ = checkpointing.tick(), if dirty => {
+                    self.save_checkpoint(&checkpoint).await;
+                    dirty = false;
+                }
+            }
+        }
+
+        // The readers stop on shutdown, and the progress they made before is saved.
+        for (_, task) in readers.tasks.drain() {
+            let _ = task.await;
+        }
+        while let Ok(progress) = progress_receiver.try_recv() {
+            apply_progress(&mut checkpoint, progress, &mut readers.tasks);
+            dirty = true;
+        }
+        if dirty {
+            self.save_checkpoint(&checkpoint).await;
+        }
+        Ok(())
+    }
+
+    /// Lists the shards, and starts reading the ones ready to be read. Returns whether the shards
+    /// were listed.
+    async fn discover_shards(
+        &self,
+        checkpoint: &mut Checkpoint,
+        readers: &mut Readers,
+        initial: bool,
+    ) -> bool {
+        let shards = match self.list_shards().await {
+            Ok(shards) => shards,
+            Err(error) => {
+                emit!(AwsKinesisStreamsListShardsError {
+                    stream_name: &self.stream_name,
+                    error: &error,
+                });
+                return false;
+            }
+        };
+
+        // Shards past the retention period of the stream are no longer listed.
+        checkpoint.shards.retain(|shard_id, _| {
+            shards
+                .iter()
+                .any(|shard| shard.shard_id() == Some(shard_id.as_str()))
+        });
+        readers.tasks.retain(|shard_id, task| {
+            let listed = checkpoint.shards.contains_key(shard_id);
+            if !listed {
+                task.abort();
+            }
+            listed
+        });
+
+        for shard_id in readable_shards(&shards, checkpoint) {
+            if readers.tasks.contains_key(&shard_id) {
+                continue;
+            }
+            let start = match (&checkpoint.shards[&shard_id].sequence_number, initial) {
+                (Some(sequence_number), _) => {
+                    StartingPoint::AfterSequenceNumber(sequence_number.clone())
+                }
+                (None, true) => match self.start_position {
+                    StartPosition::Latest => StartingPoint::Latest,
+                    StartPosition::TrimHorizon => StartingPoint::TrimHorizon,
+                },
+                (None, false) => StartingPoint::TrimHorizon,
+            };
+            readers.spawn(&shard_id, start);
+        }
+        true
+    }
+
+    async fn list_shards(&self) -> crate::Result<Vec<Shard>> {
+        let mut shards = Vec::new();
+        let mut next_token = None;
+        loop {
+            let request = self.client.list_shards();
+            // The stream name can't be set along with the token of the next page.
+            let request = match next_token {
+                Some(token) => request.next_token(token),
+                None => request.stream_name(&self.stream_name),
+            };
+            let output = request.send().await?;
+            shards.extend(output.shards.unwrap_or_default());
+            next_token = output.next_token;
+            if next_token.is_none() {
+                return Ok(shards);
+            }
+        }
+    }
+
+    /// Registers the consumer on the stream unless it already exists, and waits for it to be
+    /// active. Returns the ARN of the consumer.
+    async fn register_consumer(&self, consumer_name: &str) -> crate::Result<String> {
+        let stream_arn = self
+            .client
+            .describe_stream_summary()
+            .stream_name(&self.stream_name)
+            .send()
+            .await?
+            .stream_description_summary
+            .and_then(|summary| summary.stream_arn)
+            .ok_or("Stream description has no ARN")?;
+
+        loop {
+            let result = self
+                .client
+                .describe_stream_consumer()
+                .stream_arn(&stream_arn)
+                .consumer_name(consumer_name)
+                .send()
+                .await;
+            let consumer = match result {
+                Ok(output) => output.consumer_description,
+                Err(SdkError::ServiceError { err, .. })
+                    if err.is_resource_not_found_exception() =>
+                {
+                    info!(message = "Registering stream consumer.", %consumer_name);
+                    self.client
+                        .register_stream_consumer()
+                        .stream_arn(&stream_arn)
+                        .consumer_name(consumer_name)
+                        .send()
+                        .await?;
+                    None
+                }
+                Err(error) => return Err(error.into()),
+            };
+            if let Some(consumer) = consumer {
+                if consumer.consumer_status == Some(ConsumerStatus::Active) {
+                    return consumer
+                        .consumer_arn
+                        .ok_or_else(|| "Consumer description has no ARN".into());
+                }
+            }
+            // Consumers take a few seconds to become active once registered.
+            sleep(CONSUMER_STATUS_INTERVAL).await;
+        }
+    }
+
+    async fn save_checkpoint(&self, checkpoint: &Checkpoint) {
+        if let Err(error) = self.store.save(checkpoint).await {
+            emit!(AwsKinesisStreamsCheckpointError { error });
+        }
+    }
+}
+
+/// Records the progress of a reader, returning whether its shard is finished.
+fn apply_progress(
+    checkpoint: &mut Checkpoint,
+    progress: Progress,
+    tasks: &mut HashMap<String, JoinHandle<()>>,
+) -> bool {
+    // The shard may have expired since the progress was made.
+    if let Some(shard) = checkpoint.shards.get_mut(&progress.shard_id) {
+        if progress.sequence_number.is_some() {
+            shard.sequence_number = progress.sequence_number;
+        }
+        shard.finished |= progress.finished;
+    }
+    if progress.finished {
+        tasks.remove(&progress.shard_id);
+    }
+    progress.finished
+}
+
+/// Returns the listed shards which aren't finished and can be read, adding the new ones to the
+/// checkpoint.
+///
+/// The records of a partition key are read in order, so a child shard is only read once its parents
+/// are finished.
+fn readable_shards(shards: &[Shard], checkpoint: &mut Checkpoint) -> Vec<String> {
+    for shard_id in shards.iter().filter_map(Shard::shard_id) {
+        checkpoint.shards.entry(shard_id.to_owned()).or_default();
+    }
+    shards
+        .iter()
+        .filter(|shard| {
+            let parents = [shard.parent_shard_id(), shard.adjacent_parent_shard_id()];
+            !parents.iter().flatten().any(|parent| {
+                checkpoint
+                    .shards
+                    .get(*parent)
+                    .map_or(false, |parent| !parent.finished)
+            })
+        })
+        .filter_map(Shard::shard_id)
+        .filter(|shard_id| !checkpoint.shards[*shard_id].finished)
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::checkpoint::ShardCheckpoint, *};
+
+    fn shard(shard_id: &str, parents: &[&str]) -> Shard {
+        Shard::builder()
+            .shard_id(shard_id)
+            .set_parent_shard_id(parents.first().map(|parent| parent.to_string()))
+            .set_adjacent_parent_shard_id(parents.get(1).map(|parent| parent.to_string()))
+            .build()
+    }
+
+    #[test]
+    fn reads_children_after_parents() {
+        // Shards 0 and 1 were merged into 2, which was split into 3 and 4.
+        let shards = [
+            shard("shardId-000000000000", &[]),
+            shard("shardId-000000000001", &[]),
+            shard(
+                "shardId-000000000002",
+                &["shardId-000000000000", "shardId-000000000001"],
+            ),
+            shard("shardId-000000000003", &["shardId-000000000002"]),
+            shard("shardId-000000000004", &["shardId-000000000002"]),
+        ];
+        let mut checkpoint = Checkpoint::default();
+        assert_eq!(
+            readable_shards(&shards, &mut checkpoint),
+            vec!["shardId-000000000000", "shardId-000000000001"]
+        );
+        assert_eq!(checkpoint.shards.len(), 5);
+
+        let mut tasks = HashMap::new();
+        let finish = |checkpoint: &mut Checkpoint,
+                      tasks: &mut HashMap<String, JoinHandle<()>>,
+                      shard_id: &str| {
+            assert!(apply_progress(
+                checkpoint,
+                Progress {
+                    shard_id: shard_id.into(),
+                    sequence_number: None,
+                    finished: true,
+                },
+                tasks,
+            ));
+        };
+        finish(&mut checkpoint, &mut tasks, "shardId-000000000000");
+        assert_eq!(
+            readable_shards(&shards, &mut checkpoint),
+            vec!["shardId-000000000001"]
+        );
+
+        finish(&mut checkpoint, &mut tasks, "shardId-000000000001");
+        assert_eq!(
+            readable_shards(&shards, &mut checkpoint),
+            vec!["shardId-000000000002"]
+        );
+
+        finish(&mut checkpoint, &mut tasks, "shardId-000000000002");
+        assert_eq!(
+            readable_shards(&shards, &mut checkpoint),
+            vec!["shardId-000000000003", "shardId-000000000004"]
+        );
+    }
+
+    #[test]
+    fn applies_progress() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint
+            .shards
+            .insert("shardId-000000000000".into(), ShardCheckpoint::default());
+        let mut tasks = HashMap::new();
+
+        assert!(!apply_progress(
+            &mut checkpoint,
+            Progress {
+                shard_id: "shardId-000000000000".into(),
+                sequence_number: Some("42".into()),
+                finished: false,
+            },
+            &mut tasks,
+        ));
+        // Progress of expired shards is ignored.
+        apply_progress(
+            &mut checkpoint,
+            Progress {
+                shard_id: "shardId-000000000001".into(),
+                sequence_number: Some("43".into()),
+                finished: false,
+            },
+            &mut tasks,
+        );
+        assert_eq!(
+            checkpoint.shards.into_iter().collect::<Vec<_>>(),
+            vec![(
+                "shardId-000000000000".into(),
+                ShardCheckpoint {
+                    sequence_number: Some("42".into()),
+                    finished: false,
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "aws-kinesis-streams-integration-tests")]
+#![cfg(test)]
+
+use std::collections::HashSet;
+
+use aws_sdk_kinesis::types::Blob;
+use futures::StreamExt;
+use tokio::time::{sleep, timeout, Duration};
+
+use super::*;
+use crate::{
+    config::{log_schema, ProxyConfig},
+    event::Event,
+    test_util::{
+        components::{assert_source_compliance, HTTP_PULL_SOURCE_TAGS},
+        random_string,
+    },
+    SourceSender,
+};
+
+fn kinesis_address() -> String {
+    std::env::var("KINESIS_ADDRESS").unwrap_or_else(|_| "http://localhost:4566".into())
+}
+
+async fn client() -> aws_sdk_kinesis::Client {
+    let region = RegionOrEndpoint::with_both("us-east-1", kinesis_address());
+    create_client::<KinesisClientBuilder>(
+        &AwsAuthentication::test_auth(),
+        region.region(),
+        region.endpoint().unwrap(),
+        &ProxyConfig::default(),
+        &None,
+        false,
+    )
+    .await
+    .unwrap()
+}
+
+async fn ensure_stream(client: &aws_sdk_kinesis::Client, stream_name: &str) {
+    client
+        .create_stream()
+        .stream_name(stream_name)
+        .shard_count(2)
+        .send()
+        .await
+        .unwrap();
+    // Wait for localstack to create the shards of the stream.
+    sleep(Duration::from_secs(1)).await;
+}
+
+#[tokio::test]
+async fn polls_shards() {
+    assert_source_compliance(&HTTP_PULL_SOURCE_TAGS, async {
+        let client = client().await;
+        let stream_name = format!("test-{}", random_string(10).to_lowercase());
+        ensure_stream(&client, &stream_name).await;
+
+        let messages = (0..10)
+            .map(|index| format!("Test message: {}", index))
+            .collect::<Vec<_>>();
+        for (index, message) in messages.iter().enumerate() {
+            client
+                .put_record()
+                .stream_name(&stream_name)
+                .partition_key(index.to_string())
+                .data(Blob::new(message.as_bytes()))
+                .send()
+                .await
+                .unwrap();
+        }
+
+        let data_dir = tempfile::tempdir().unwrap();
+        let config = AwsKinesisStreamsConfig {
+            region: RegionOrEndpoint::with_both("us-east-1", kinesis_address()),
+            auth: AwsAuthentication::test_auth(),
+            stream_name: stream_name.clone(),
+            start_position: StartPosition::TrimHorizon,
+            checkpoint: CheckpointConfig::File {
+                data_dir: Some(data_dir.path().to_owned()),
+            },
+            ..Default::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test();
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap()
+                .await
+                .unwrap()
+        });
+
+        let events: Vec<Event> =
+            timeout(Duration::from_secs(10), rx.take(messages.len()).collect())
+                .await
+                .unwrap();
+
+        let mut expected = messages.into_iter().collect::<HashSet<_>>();
+        for event in events {
+            let log = event.as_log();
+            assert_eq!(log["stream_name"], stream_name.as_str().into());
+            let message = log[log_schema().message_key()].to_string_lossy();
+            assert!(
+                expected.remove(&message),
+                "Unexpected message: {:?}",
+                message
+            );
+        }
+        assert!(expected.is_empty());
+    })
+    .await;
+}
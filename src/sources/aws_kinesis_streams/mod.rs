@@ -0,0 +1,323 @@
+use std::time::Duration;
+
+use codecs::decoding::{DeserializerConfig, FramingConfig};
+use snafu::Snafu;
+use vector_config::configurable_component;
+
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    codecs::DecodingConfig,
+    common::{
+        dynamodb::DynamoDbClientBuilder,
+        kinesis::{KinesisClientBuilder, KinesisHttp2ClientBuilder},
+    },
+    config::{AcknowledgementsConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    tls::TlsConfig,
+};
+
+mod checkpoint;
+mod ingestor;
+mod integration_tests;
+mod reader;
+
+use checkpoint::{CheckpointConfig, CheckpointStore, DynamoDbCheckpointStore, FileCheckpointStore};
+
+/// How to read the records of the shards.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ModeConfig {
+    /// Periodically reads the shards with `GetRecords`, sharing the read throughput of each shard with the
+    /// other consumers of the stream.
+    #[derivative(Default)]
+    Polling {
+        /// How long to wait between reads of each shard, in seconds.
+        ///
+        /// A shard supports up to five reads per second, shared by all the consumers polling the stream.
+        #[serde(default = "default_poll_secs")]
+        #[derivative(Default(value = "default_poll_secs()"))]
+        poll_secs: u32,
+
+        /// The maximum number of records read from a shard at once.
+        #[serde(default = "default_max_records")]
+        #[derivative(Default(value = "default_max_records()"))]
+        max_records: u32,
+    },
+
+    /// Subscribes to the shards with `SubscribeToShard`, which pushes their records to a consumer with a
+    /// dedicated read throughput.
+    ///
+    /// The consumer is registered on the stream when the source starts, unless it already exists.
+    EnhancedFanOut {
+        /// The name of the consumer to register on the stream.
+        consumer_name: String,
+    },
+}
+
+const fn default_poll_secs() -> u32 {
+    1
+}
+
+const fn default_max_records() -> u32 {
+    10_000
+}
+
+/// Where to start reading the shards of the stream from, when no checkpoint exists yet.
+///
+/// Shards created afterwards, such as when the stream is resharded, are always read from their start.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum StartPosition {
+    /// Only read the records added after the source starts.
+    #[derivative(Default)]
+    Latest,
+
+    /// Read all the records retained by the stream.
+    TrimHorizon,
+}
+
+/// Configuration for the `aws_kinesis_streams` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AwsKinesisStreamsConfig {
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+
+    /// The name of the stream to read.
+    stream_name: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    mode: ModeConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    start_position: StartPosition,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    checkpoint: CheckpointConfig,
+
+    /// How long to wait between saves of the checkpoint, in seconds.
+    ///
+    /// The records read since the last save are read again when the source restarts.
+    #[serde(default = "default_checkpoint_interval_secs")]
+    #[derivative(Default(value = "default_checkpoint_interval_secs()"))]
+    checkpoint_interval_secs: u32,
+
+    /// How long to wait between listings of the shards of the stream, in seconds.
+    ///
+    /// The shards are also listed as soon as a shard is closed, so that its children are read without delay.
+    #[serde(default = "default_shard_discovery_interval_secs")]
+    #[derivative(Default(value = "default_shard_discovery_interval_secs()"))]
+    shard_discovery_interval_secs: u32,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    auth: AwsAuthentication,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_checkpoint_interval_secs() -> u32 {
+    5
+}
+
+const fn default_shard_discovery_interval_secs() -> u32 {
+    30
+}
+
+inventory::submit! {
+    SourceDescription::new::<AwsKinesisStreamsConfig>("aws_kinesis_streams")
+}
+
+impl_generate_config_from_default!(AwsKinesisStreamsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_kinesis_streams")]
+impl SourceConfig for AwsKinesisStreamsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let region = self.region.region().ok_or(BuildError::RegionMissing)?;
+        let endpoint = self.region.endpoint()?;
+        let client = match self.mode {
+            ModeConfig::Polling { .. } => {
+                create_client::<KinesisClientBuilder>(
+                    &self.auth,
+                    Some(region.clone()),
+                    endpoint,
+                    &cx.proxy,
+                    &self.tls,
+                    false,
+                )
+                .await?
+            }
+            // Subscriptions to shards are streamed over HTTP/2.
+            ModeConfig::EnhancedFanOut { .. } => {
+                create_client::<KinesisHttp2ClientBuilder>(
+                    &self.auth,
+                    Some(region.clone()),
+                    endpoint,
+                    &cx.proxy,
+                    &self.tls,
+                    false,
+                )
+                .await?
+            }
+        };
+
+        let store: Box<dyn CheckpointStore> = match &self.checkpoint {
+            CheckpointConfig::File { data_dir } => {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(data_dir.as_ref(), cx.key.id())?;
+                Box::new(FileCheckpointStore::new(data_dir))
+            }
+            CheckpointConfig::DynamoDb { table } => Box::new(DynamoDbCheckpointStore {
+                // The endpoint only overrides the one of Kinesis.
+                client: create_client::<DynamoDbClientBuilder>(
+                    &self.auth,
+                    Some(region),
+                    None,
+                    &cx.proxy,
+                    &self.tls,
+                    false,
+                )
+                .await?,
+                table: table.clone(),
+                stream_name: self.stream_name.clone(),
+            }),
+        };
+
+        let mode = match &self.mode {
+            ModeConfig::Polling {
+                poll_secs,
+                max_records,
+            } => {
+                if *max_records == 0 || *max_records > default_max_records() {
+                    return Err(BuildError::InvalidMaxRecords {
+                        max_records: *max_records,
+                    }
+                    .into());
+                }
+                ingestor::Mode::Polling {
+                    poll_interval: Duration::from_secs((*poll_secs).into()),
+                    max_records: *max_records as i32,
+                }
+            }
+            ModeConfig::EnhancedFanOut { consumer_name } => ingestor::Mode::EnhancedFanOut {
+                consumer_name: consumer_name.clone(),
+            },
+        };
+
+        let ingestor = ingestor::Ingestor {
+            client,
+            stream_name: self.stream_name.clone(),
+            mode,
+            start_position: self.start_position,
+            decoder: DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?,
+            store,
+            checkpoint_interval: Duration::from_secs(self.checkpoint_interval_secs.into()),
+            shard_discovery_interval: Duration::from_secs(
+                self.shard_discovery_interval_secs.into(),
+            ),
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+        Ok(Box::pin(ingestor.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "aws_kinesis_streams"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Region is required"))]
+    RegionMissing,
+    #[snafu(display("`max_records` must be between 1 and 10000, got {}", max_records))]
+    InvalidMaxRecords { max_records: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AwsKinesisStreamsConfig>();
+    }
+
+    #[test]
+    fn parses_modes() {
+        let config: AwsKinesisStreamsConfig = toml::from_str(
+            r#"
+            region = "us-east-1"
+            stream_name = "logs"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.mode,
+            ModeConfig::Polling {
+                poll_secs: 1,
+                max_records: 10_000
+            }
+        ));
+        assert!(matches!(config.start_position, StartPosition::Latest));
+        assert!(matches!(
+            config.checkpoint,
+            CheckpointConfig::File { data_dir: None }
+        ));
+
+        let config: AwsKinesisStreamsConfig = toml::from_str(
+            r#"
+            region = "us-east-1"
+            stream_name = "logs"
+            start_position = "trim_horizon"
+            mode.type = "enhanced_fan_out"
+            mode.consumer_name = "vector"
+            checkpoint.type = "dynamodb"
+            checkpoint.table = "checkpoints"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.mode,
+            ModeConfig::EnhancedFanOut { consumer_name } if consumer_name == "vector"
+        ));
+        assert!(matches!(config.start_position, StartPosition::TrimHorizon));
+        assert!(matches!(
+            config.checkpoint,
+            CheckpointConfig::DynamoDb { table } if table == "checkpoints"
+        ));
+    }
+}
@@ -0,0 +1,413 @@
+use std::time::Duration;
+
+use aws_sdk_kinesis::{
+    model::{Record, ShardIteratorType, StartingPosition, SubscribeToShardEventStream},
+    Client as KinesisClient,
+};
+use chrono::{TimeZone, Utc};
+use tokio::{select, sync::mpsc, time::sleep};
+
+use crate::{
+    codecs::Decoder,
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{AwsKinesisStreamsShardReadError, BytesReceived, StreamClosedError},
+    shutdown::ShutdownSignal,
+    sources::util,
+    SourceSender,
+};
+
+/// How long to wait before reading a shard again after an error.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Where a reader starts reading its shard from.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum StartingPoint {
+    AfterSequenceNumber(String),
+    Latest,
+    TrimHorizon,
+}
+
+impl StartingPoint {
+    fn iterator_type(&self) -> (ShardIteratorType, Option<&str>) {
+        match self {
+            Self::AfterSequenceNumber(sequence_number) => (
+                ShardIteratorType::AfterSequenceNumber,
+                Some(sequence_number.as_str()),
+            ),
+            Self::Latest => (ShardIteratorType::Latest, None),
+            Self::TrimHorizon => (ShardIteratorType::TrimHorizon, None),
+        }
+    }
+}
+
+/// The progress of a reader, sent once the records read are delivered.
+#[derive(Debug)]
+pub(super) struct Progress {
+    pub(super) shard_id: String,
+
+    /// The sequence number after which the shard is to be read.
+    pub(super) sequence_number: Option<String>,
+
+    /// Whether the shard was closed, and all its records read.
+    pub(super) finished: bool,
+}
+
+/// Reads the records of the shards of a stream, each from its own task.
+#[derive(Clone)]
+pub(super) struct ShardReader {
+    pub(super) client: KinesisClient,
+    pub(super) stream_name: String,
+    pub(super) decoder: Decoder,
+    pub(super) acknowledgements: bool,
+    pub(super) progress: mpsc::UnboundedSender<Progress>,
+}
+
+impl ShardReader {
+    /// Reads a shard with `GetRecords` until it's finished, or the source shuts down.
+    pub(super) async fn poll(
+        self,
+        shard_id: String,
+        start: StartingPoint,
+        poll_interval: Duration,
+        max_records: i32,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+    ) {
+        select! {
+            _ = &mut shutdown => {},
+            _ = self.poll_shard(&shard_id, start, poll_interval, max_records, &mut out) => {},
+        }
+    }
+
+    async fn poll_shard(
+        &self,
+        shard_id: &str,
+        mut start: StartingPoint,
+        poll_interval: Duration,
+        max_records: i32,
+        out: &mut SourceSender,
+    ) {
+        let mut iterator = None;
+        loop {
+            let shard_iterator = match iterator.take() {
+                Some(iterator) => iterator,
+                None => match self.shard_iterator(shard_id, &start).await {
+                    Ok(Some(iterator)) => iterator,
+                    // The shard is closed and its records expired.
+                    Ok(None) => {
+                        self.report(shard_id, None, true);
+                        return;
+                    }
+                    Err(error) => {
+                        self.emit_error(shard_id, &error);
+                        sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                },
+            };
+
+            let output = match self
+                .client
+                .get_records()
+                .shard_iterator(shard_iterator)
+                .limit(max_records)
+                .send()
+                .await
+            {
+                Ok(output) => output,
+                Err(error) => {
+                    // A new iterator is requested from the last record read, as iterators expire.
+                    self.emit_error(shard_id, &error.into());
+                    sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            let records = output.records.unwrap_or_default();
+            let sequence_number = records
+                .last()
+                .and_then(|record| record.sequence_number.clone());
+            // Undelivered records are read again.
+            if !self.send_records(shard_id, records, out).await {
+                sleep(poll_interval).await;
+                continue;
+            }
+            if let Some(sequence_number) = &sequence_number {
+                start = StartingPoint::AfterSequenceNumber(sequence_number.clone());
+            }
+            match output.next_shard_iterator {
+                Some(next_iterator) => {
+                    if sequence_number.is_some() {
+                        self.report(shard_id, sequence_number, false);
+                    }
+                    iterator = Some(next_iterator);
+                }
+                None => {
+                    self.report(shard_id, sequence_number, true);
+                    return;
+                }
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    async fn shard_iterator(
+        &self,
+        shard_id: &str,
+        start: &StartingPoint,
+    ) -> crate::Result<Option<String>> {
+        let (iterator_type, sequence_number) = start.iterator_type();
+        let output = self
+            .client
+            .get_shard_iterator()
+            .stream_name(&self.stream_name)
+            .shard_id(shard_id)
+            .shard_iterator_type(iterator_type)
+            .set_starting_sequence_number(sequence_number.map(str::to_owned))
+            .send()
+            .await?;
+        Ok(output.shard_iterator)
+    }
+
+    /// Reads a shard with `SubscribeToShard` until it's finished, or the source shuts down.
+    pub(super) async fn subscribe(
+        self,
+        consumer_arn: String,
+        shard_id: String,
+        mut start: StartingPoint,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+    ) {
+        loop {
+            let result = select! {
+                _ = &mut shutdown => return,
+                result = self.subscribe_shard(&consumer_arn, &shard_id, &mut start, &mut out) => result,
+            };
+            match result {
+                Ok(true) => return,
+                // Subscriptions expire after five minutes, and are renewed from the last records read.
+                Ok(false) => {}
+                Err(error) => {
+                    self.emit_error(&shard_id, &error);
+                    select! {
+                        _ = &mut shutdown => return,
+                        _ = sleep(RETRY_DELAY) => {},
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads the records pushed by a subscription, returning whether the shard is finished.
+    async fn subscribe_shard(
+        &self,
+        consumer_arn: &str,
+        shard_id: &str,
+        start: &mut StartingPoint,
+        out: &mut SourceSender,
+    ) -> crate::Result<bool> {
+        let (iterator_type, sequence_number) = start.iterator_type();
+        let output = self
+            .client
+            .subscribe_to_shard()
+            .consumer_arn(consumer_arn)
+            .shard_id(shard_id)
+            .starting_position(
+                StartingPosition::builder()
+                    .r#type(iterator_type)
+                    .set_sequence_number(sequence_number.map(str::to_owned))
+                    .build(),
+            )
+            .send()
+            .await?;
+
+        let mut events = output.event_stream;
+        while let Some(event) = events.recv().await? {
+            let event = match event {
+                SubscribeToShardEventStream::SubscribeToShardEvent(event) => event,
+                _ => continue,
+            };
+            if !self
+                .send_records(shard_id, event.records.unwrap_or_default(), out)
+                .await
+            {
+                // Undelivered records are read again by the next subscription.
+                return Ok(false);
+            }
+            // The continuation sequence number is only missing once the shard is closed, and all
+            // its records were pushed.
+            match event.continuation_sequence_number {
+                Some(sequence_number) => {
+                    *start = StartingPoint::AfterSequenceNumber(sequence_number.clone());
+                    self.report(shard_id, Some(sequence_number), false);
+                }
+                None => {
+                    self.report(shard_id, None, true);
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Sends the events of the records, returning whether their progress can be checkpointed, which
+    /// is once they are delivered when acknowledgements are enabled.
+    async fn send_records(
+        &self,
+        shard_id: &str,
+        records: Vec<Record>,
+        out: &mut SourceSender,
+    ) -> bool {
+        if records.is_empty() {
+            return true;
+        }
+
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let mut events = Vec::new();
+        for record in records {
+            events.extend(decode_record(
+                self.decoder.clone(),
+                &self.stream_name,
+                shard_id,
+                record,
+                &batch,
+            ));
+        }
+        drop(batch);
+
+        let count = events.len();
+        if let Err(error) = out.send_batch(events).await {
+            emit!(StreamClosedError { error, count });
+            return false;
+        }
+        match receiver {
+            None => true,
+            // Rejected events can't be retried, so they are checkpointed as well.
+            Some(receiver) => receiver.await != BatchStatus::Errored,
+        }
+    }
+
+    fn report(&self, shard_id: &str, sequence_number: Option<String>, finished: bool) {
+        // The ingestor only stops receiving progress once the readers are stopped.
+        let _ = self.progress.send(Progress {
+            shard_id: shard_id.to_owned(),
+            sequence_number,
+            finished,
+        });
+    }
+
+    fn emit_error(&self, shard_id: &str, error: &crate::Error) {
+        emit!(AwsKinesisStreamsShardReadError {
+            stream_name: &self.stream_name,
+            shard_id,
+            error,
+        });
+    }
+}
+
+/// Decodes the events of a record, with the metadata of the record.
+fn decode_record(
+    decoder: Decoder,
+    stream_name: &str,
+    shard_id: &str,
+    record: Record,
+    batch: &Option<BatchNotifier>,
+) -> Vec<Event> {
+    let data = match record.data {
+        Some(data) => data.into_inner(),
+        None => return Vec::new(),
+    };
+    emit!(BytesReceived {
+        byte_size: data.len(),
+        protocol: "http",
+    });
+
+    let timestamp = record.approximate_arrival_timestamp.and_then(|timestamp| {
+        Utc.timestamp_opt(timestamp.secs(), timestamp.subsec_nanos())
+            .single()
+    });
+    util::decode_message(decoder, "aws_kinesis_streams", &data, timestamp, batch)
+        .map(|mut event| {
+            if let Event::Log(log) = &mut event {
+                log.insert("stream_name", stream_name.to_owned());
+                log.insert("shard_id", shard_id.to_owned());
+                if let Some(partition_key) = &record.partition_key {
+                    log.insert("partition_key", partition_key.clone());
+                }
+                if let Some(sequence_number) = &record.sequence_number {
+                    log.insert("sequence_number", sequence_number.clone());
+                }
+            }
+            event
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_kinesis::types::{Blob, DateTime};
+    use codecs::decoding::{DeserializerConfig, FramingConfig};
+
+    use super::*;
+    use crate::{codecs::DecodingConfig, config::log_schema};
+
+    fn decoder() -> Decoder {
+        DecodingConfig::new(
+            FramingConfig::NewlineDelimited {
+                newline_delimited: Default::default(),
+            },
+            DeserializerConfig::Bytes,
+        )
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_records() {
+        let record = Record::builder()
+            .data(Blob::new("first\nsecond"))
+            .partition_key("host-1")
+            .sequence_number("49590338271490256608559692538361571095921575989136588898")
+            .approximate_arrival_timestamp(DateTime::from_secs(1_660_125_600))
+            .build();
+        let events = decode_record(decoder(), "logs", "shardId-000000000000", record, &None);
+        assert_eq!(events.len(), 2);
+        for (event, message) in events.iter().zip(["first", "second"]) {
+            let log = event.as_log();
+            assert_eq!(log[log_schema().message_key()], message.into());
+            assert_eq!(
+                log[log_schema().timestamp_key()],
+                Utc.timestamp(1_660_125_600, 0).into()
+            );
+            assert_eq!(
+                log[log_schema().source_type_key()],
+                "aws_kinesis_streams".into()
+            );
+            assert_eq!(log["stream_name"], "logs".into());
+            assert_eq!(log["shard_id"], "shardId-000000000000".into());
+            assert_eq!(log["partition_key"], "host-1".into());
+            assert_eq!(
+                log["sequence_number"],
+                "49590338271490256608559692538361571095921575989136588898".into()
+            );
+        }
+    }
+
+    #[test]
+    fn skips_records_without_data() {
+        let record = Record::builder().partition_key("host-1").build();
+        assert!(decode_record(decoder(), "logs", "shardId-000000000000", record, &None).is_empty());
+    }
+
+    #[test]
+    fn converts_starting_points() {
+        assert_eq!(
+            StartingPoint::AfterSequenceNumber("42".into()).iterator_type(),
+            (ShardIteratorType::AfterSequenceNumber, Some("42"))
+        );
+        assert_eq!(
+            StartingPoint::TrimHorizon.iterator_type(),
+            (ShardIteratorType::TrimHorizon, None)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
+use aws_sdk_s3::Client as S3Client;
+use aws_types::region::Region;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use vector_config::configurable_component;
+
+use super::sqs::process_object;
+use crate::{
+    config::{AcknowledgementsConfig, SourceContext},
+    internal_events::{S3CheckpointError, S3ListObjectsError, S3ObjectProcessingError},
+    line_agg,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    SourceSender,
+};
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+
+/// Listing configuration options.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The name of the bucket to list objects from.
+    pub(super) bucket: String,
+
+    /// Only objects whose key starts with this prefix are ingested.
+    ///
+    /// Listing a prefix rather than the whole bucket reduces the cost of each listing.
+    #[serde(default)]
+    pub(super) prefix: Option<String>,
+
+    /// How long to wait between listings of the bucket, in seconds.
+    #[serde(default = "default_poll_secs")]
+    #[derivative(Default(value = "default_poll_secs()"))]
+    pub(super) poll_secs: u32,
+
+    /// Where to persist the objects already ingested.
+    ///
+    /// Storing the checkpoint in S3 without a `region` or an `endpoint` of its own uses the region and credentials
+    /// of the source. The key of the checkpoint object should not start with the listed `prefix`, or the checkpoint
+    /// is ingested as well.
+    #[serde(default)]
+    pub(super) checkpoint: CheckpointStoreConfig,
+}
+
+const fn default_poll_secs() -> u32 {
+    60
+}
+
+/// The objects already ingested, along with their ETag, so that objects overwritten since are
+/// ingested again.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Checkpoint {
+    objects: BTreeMap<String, String>,
+}
+
+impl Checkpoint {
+    /// Returns the listed objects that were not ingested yet.
+    fn pending<'a>(&self, listed: &'a [ListedObject]) -> Vec<&'a ListedObject> {
+        listed
+            .iter()
+            .filter(|object| self.objects.get(&object.key) != Some(&object.e_tag))
+            .collect()
+    }
+
+    /// Forgets the objects that are no longer listed, such as deleted ones, so that the checkpoint
+    /// doesn't grow indefinitely. Returns whether any was.
+    fn prune(&mut self, listed: &[ListedObject]) -> bool {
+        let listed = listed
+            .iter()
+            .map(|object| object.key.as_str())
+            .collect::<HashSet<_>>();
+        let len = self.objects.len();
+        self.objects.retain(|key, _| listed.contains(key.as_str()));
+        self.objects.len() != len
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ListedObject {
+    key: String,
+    e_tag: String,
+}
+
+pub(super) struct Ingestor {
+    region: Region,
+    s3_client: S3Client,
+
+    multiline: Option<line_agg::Config>,
+    compression: super::Compression,
+
+    bucket: String,
+    prefix: Option<String>,
+    poll_interval: Duration,
+    store: CheckpointStore,
+}
+
+impl Ingestor {
+    pub(super) async fn new(
+        region: Region,
+        s3_client: S3Client,
+        config: Config,
+        compression: super::Compression,
+        multiline: Option<line_agg::Config>,
+        cx: &SourceContext,
+    ) -> crate::Result<Ingestor> {
+        let store = config
+            .checkpoint
+            .build_with_s3_client(cx, None, CHECKPOINT_FILENAME, Some(s3_client.clone()))
+            .await?;
+
+        Ok(Ingestor {
+            region,
+            s3_client,
+
+            multiline,
+            compression,
+
+            bucket: config.bucket,
+            prefix: config.prefix,
+            poll_interval: Duration::from_secs(config.poll_secs.into()),
+            store,
+        })
+    }
+
+    pub(super) async fn run(
+        self,
+        cx: SourceContext,
+        acknowledgements: AcknowledgementsConfig,
+    ) -> Result<(), ()> {
+        let acknowledgements = cx.do_acknowledgements(&acknowledgements);
+        let mut out = cx.out;
+        let mut shutdown = cx.shutdown;
+
+        // Without a checkpoint, every listed object would be ingested again, so it is better to
+        // stop than to go on.
+        let mut checkpoint = self
+            .store
+            .load_json::<Checkpoint>()
+            .await
+            .map_err(|error| {
+                error!(
+                    message = "Failed to load checkpoint of processed S3 objects.",
+                    location = %self.store.location(),
+                    %error,
+                );
+            })?
+            .unwrap_or_default();
+
+        let mut interval = tokio::time::interval(self.poll_interval);
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {},
+            }
+            // Objects are recorded in the checkpoint once processed, so an object interrupted by
+            // the shutdown is processed again on the next run.
+            select! {
+                _ = &mut shutdown => break,
+                _ = self.run_once(&mut checkpoint, &mut out, acknowledgements) => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_once(
+        &self,
+        checkpoint: &mut Checkpoint,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+    ) {
+        let listed = match self.list_objects().await {
+            Ok(listed) => listed,
+            Err(error) => {
+                emit!(S3ListObjectsError {
+                    bucket: &self.bucket,
+                    error: &error,
+                });
+                return;
+            }
+        };
+
+        for object in checkpoint.pending(&listed) {
+            let result = process_object(
+                &self.s3_client,
+                self.compression,
+                &self.multiline,
+                out,
+                acknowledgements,
+                &self.bucket,
+                &object.key,
+                self.region.as_ref(),
+            )
+            .await;
+            match result {
+                Ok(()) => {
+                    checkpoint
+                        .objects
+                        .insert(object.key.clone(), object.e_tag.clone());
+                    self.save(checkpoint).await;
+                }
+                // The object is not recorded in the checkpoint, so that it is retried on the next
+                // listing.
+                Err(error) => emit!(S3ObjectProcessingError { error: &error }),
+            }
+        }
+
+        if checkpoint.prune(&listed) {
+            self.save(checkpoint).await;
+        }
+    }
+
+    async fn save(&self, checkpoint: &Checkpoint) {
+        if let Err(error) = self.store.save_json(checkpoint).await {
+            emit!(S3CheckpointError { error: &error });
+        }
+    }
+
+    /// Lists all the objects under the prefix, skipping "directories".
+    async fn list_objects(&self) -> crate::Result<Vec<ListedObject>> {
+        let mut listed = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .s3_client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .set_prefix(self.prefix.clone())
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+
+            listed.extend(output.contents.unwrap_or_default().into_iter().filter_map(
+                |object| match (object.key, object.e_tag) {
+                    (Some(key), Some(e_tag)) if !key.ends_with('/') => {
+                        Some(ListedObject { key, e_tag })
+                    }
+                    _ => None,
+                },
+            ));
+
+            continuation_token = output.next_continuation_token;
+            if !output.is_truncated || continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok(listed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(objects: &[(&str, &str)]) -> Vec<ListedObject> {
+        objects
+            .iter()
+            .map(|(key, e_tag)| ListedObject {
+                key: key.to_string(),
+                e_tag: e_tag.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: Config = toml::from_str(
+            r#"
+            bucket = "logs"
+            prefix = "app/"
+            checkpoint.type = "s3"
+            checkpoint.bucket = "vector"
+            checkpoint.key = "checkpoints/logs.json"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.prefix.as_deref(), Some("app/"));
+        assert_eq!(config.poll_secs, 60);
+        assert!(matches!(
+            config.checkpoint,
+            CheckpointStoreConfig::S3 { ref bucket, ref key, region: None, .. }
+                if bucket == "vector" && key.as_deref() == Some("checkpoints/logs.json")
+        ));
+
+        let config: Config = toml::from_str(r#"bucket = "logs""#).unwrap();
+        assert!(matches!(
+            config.checkpoint,
+            CheckpointStoreConfig::File { data_dir: None }
+        ));
+    }
+
+    #[test]
+    fn detects_new_and_overwritten_objects() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.objects.insert("a.log".into(), "1".into());
+        checkpoint.objects.insert("b.log".into(), "1".into());
+        checkpoint.objects.insert("deleted.log".into(), "1".into());
+
+        let listed = listed(&[("a.log", "1"), ("b.log", "2"), ("c.log", "1")]);
+        let pending = checkpoint
+            .pending(&listed)
+            .into_iter()
+            .map(|object| object.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(pending, vec!["b.log", "c.log"]);
+
+        assert!(checkpoint.prune(&listed));
+        assert!(!checkpoint.objects.contains_key("deleted.log"));
+        assert!(!checkpoint.prune(&listed));
+    }
+
+    #[tokio::test]
+    async fn file_checkpoint_roundtrip() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::file(data_dir.path().join(CHECKPOINT_FILENAME));
+        assert_eq!(store.load_json::<Checkpoint>().await.unwrap(), None);
+
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.objects.insert("a.log".into(), "\"etag\"".into());
+        store.save_json(&checkpoint).await.unwrap();
+        assert_eq!(
+            store.load_json::<Checkpoint>().await.unwrap(),
+            Some(checkpoint)
+        );
+    }
+}
@@ -25,6 +25,7 @@ use crate::{
     serde::bool_or_struct,
 };
 
+mod list;
 pub mod sqs;
 
 /// Compression scheme for objects retrieved from S3.
@@ -58,6 +59,9 @@ enum Strategy {
     /// Consumes objects by processing bucket notification events sent to an [AWS SQS queue](\(urls.aws_sqs)).
     #[derivative(Default)]
     Sqs,
+
+    /// Consumes objects by periodically listing a bucket, for buckets whose notifications can't be configured.
+    List,
 }
 
 /// Configuration for the `aws_s3` source.
@@ -84,6 +88,11 @@ pub struct AwsS3Config {
     /// Only relevant when `strategy = "sqs"`.
     sqs: Option<sqs::Config>,
 
+    /// Configuration options for listing.
+    ///
+    /// Only relevant when `strategy = "list"`.
+    list: Option<list::Config>,
+
     /// The ARN of an [IAM role](\(urls.aws_iam_role)) to assume at startup.
     #[deprecated]
     assume_role: Option<String>,
@@ -127,6 +136,11 @@ impl SourceConfig for AwsS3Config {
                     .await?
                     .run(cx, self.acknowledgements),
             )),
+            Strategy::List => Ok(Box::pin(
+                self.create_list_ingestor(multiline_config, &cx)
+                    .await?
+                    .run(cx, self.acknowledgements),
+            )),
         }
     }
 
@@ -186,8 +200,10 @@ impl AwsS3Config {
                     sqs_client,
                     s3_client,
                     sqs.clone(),
-                    self.compression,
-                    multiline,
+                    Box::new(sqs::LinesProcessor {
+                        compression: self.compression,
+                        multiline,
+                    }),
                 )
                 .await?;
 
@@ -196,6 +212,39 @@ impl AwsS3Config {
             None => Err(CreateSqsIngestorError::ConfigMissing {}.into()),
         }
     }
+
+    async fn create_list_ingestor(
+        &self,
+        multiline: Option<line_agg::Config>,
+        cx: &SourceContext,
+    ) -> crate::Result<list::Ingestor> {
+        let config = self
+            .list
+            .clone()
+            .ok_or(CreateListIngestorError::ConfigMissing)?;
+
+        let region = self
+            .region
+            .region()
+            .ok_or(CreateListIngestorError::RegionMissing)?;
+
+        let endpoint = self
+            .region
+            .endpoint()
+            .map_err(|_| CreateListIngestorError::InvalidEndpoint)?;
+
+        let s3_client = create_client::<S3ClientBuilder>(
+            &self.auth,
+            Some(region.clone()),
+            endpoint,
+            &cx.proxy,
+            &self.tls_options,
+            false,
+        )
+        .await?;
+
+        list::Ingestor::new(region, s3_client, config, self.compression, multiline, cx).await
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -214,8 +263,18 @@ enum CreateSqsIngestorError {
     InvalidEndpoint,
 }
 
+#[derive(Debug, Snafu)]
+enum CreateListIngestorError {
+    #[snafu(display("Configuration for `list` required when strategy=list"))]
+    ConfigMissing,
+    #[snafu(display("Region is required"))]
+    RegionMissing,
+    #[snafu(display("Endpoint is invalid"))]
+    InvalidEndpoint,
+}
+
 /// None if body is empty
-async fn s3_object_decoder(
+pub(crate) async fn s3_object_decoder(
     compression: Compression,
     key: &str,
     content_encoding: Option<&str>,
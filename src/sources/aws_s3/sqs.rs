@@ -1,5 +1,6 @@
 use std::{future::ready, panic, sync::Arc};
 
+use async_trait::async_trait;
 use aws_sdk_s3::error::GetObjectError;
 use aws_sdk_s3::Client as S3Client;
 use aws_sdk_sqs::error::{DeleteMessageBatchError, ReceiveMessageError};
@@ -48,8 +49,10 @@ static SUPPORTED_S3S_EVENT_VERSION: Lazy<semver::VersionReq> =
 #[derive(Clone, Debug, Derivative)]
 #[derivative(Default)]
 #[serde(deny_unknown_fields)]
-pub(super) struct Config {
+pub(crate) struct Config {
     /// The URL of the SQS queue to poll for bucket notifications.
+    ///
+    /// Notifications can be sent directly by the bucket, through an EventBridge rule, or through an SNS topic.
     pub(super) queue_url: String,
 
     /// How long to wait while polling the queue for new messages, in seconds.
@@ -94,7 +97,7 @@ pub(super) struct Config {
     #[configurable(derived)]
     #[serde(default)]
     #[derivative(Default)]
-    pub(super) tls_options: Option<TlsConfig>,
+    pub(crate) tls_options: Option<TlsConfig>,
 }
 
 const fn default_poll_secs() -> u32 {
@@ -114,7 +117,7 @@ fn default_client_concurrency() -> u32 {
 }
 
 #[derive(Debug, Snafu)]
-pub(super) enum IngestorNewError {
+pub(crate) enum IngestorNewError {
     #[snafu(display("Invalid visibility timeout {}: {}", timeout, source))]
     InvalidVisibilityTimeout {
         source: std::num::TryFromIntError,
@@ -146,6 +149,18 @@ pub enum ProcessingError {
         bucket: String,
         key: String,
     },
+    #[snafu(display("Failed to read all of s3://{}/{}: {}", bucket, key, source))]
+    ReadBody {
+        source: std::io::Error,
+        bucket: String,
+        key: String,
+    },
+    #[snafu(display("Could not parse s3://{}/{}: {}", bucket, key, source))]
+    ParseObject {
+        source: serde_json::Error,
+        bucket: String,
+        key: String,
+    },
     #[snafu(display("Failed to flush all of s3://{}/{}: {}", bucket, key, source))]
     PipelineSend {
         source: crate::source_sender::ClosedError,
@@ -169,14 +184,62 @@ pub enum ProcessingError {
     ErrorAcknowledgement,
 }
 
+/// Processes the objects notified to the queue into events.
+#[async_trait]
+pub(crate) trait ObjectProcessor: Send + Sync {
+    /// Fetches an object, and sends its events.
+    ///
+    /// When acknowledgements are enabled, this waits for the events to be acknowledged, so that
+    /// the notification is only deleted once they are delivered.
+    async fn process(
+        &self,
+        s3_client: &S3Client,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+        bucket: &str,
+        key: &str,
+        region: &str,
+    ) -> Result<(), ProcessingError>;
+}
+
+/// Sends the lines of objects as events.
+pub(super) struct LinesProcessor {
+    pub(super) compression: super::Compression,
+    pub(super) multiline: Option<line_agg::Config>,
+}
+
+#[async_trait]
+impl ObjectProcessor for LinesProcessor {
+    async fn process(
+        &self,
+        s3_client: &S3Client,
+        out: &mut SourceSender,
+        acknowledgements: bool,
+        bucket: &str,
+        key: &str,
+        region: &str,
+    ) -> Result<(), ProcessingError> {
+        process_object(
+            s3_client,
+            self.compression,
+            &self.multiline,
+            out,
+            acknowledgements,
+            bucket,
+            key,
+            region,
+        )
+        .await
+    }
+}
+
 pub struct State {
     region: Region,
 
     s3_client: S3Client,
     sqs_client: SqsClient,
 
-    multiline: Option<line_agg::Config>,
-    compression: super::Compression,
+    processor: Box<dyn ObjectProcessor>,
 
     queue_url: String,
     poll_secs: i32,
@@ -185,18 +248,17 @@ pub struct State {
     delete_message: bool,
 }
 
-pub(super) struct Ingestor {
+pub(crate) struct Ingestor {
     state: Arc<State>,
 }
 
 impl Ingestor {
-    pub(super) async fn new(
+    pub(crate) async fn new(
         region: Region,
         sqs_client: SqsClient,
         s3_client: S3Client,
         config: Config,
-        compression: super::Compression,
-        multiline: Option<line_agg::Config>,
+        processor: Box<dyn ObjectProcessor>,
     ) -> Result<Ingestor, IngestorNewError> {
         let state = Arc::new(State {
             region,
@@ -204,8 +266,7 @@ impl Ingestor {
             s3_client,
             sqs_client,
 
-            compression,
-            multiline,
+            processor,
 
             queue_url: config.queue_url,
             poll_secs: config.poll_secs as i32,
@@ -217,7 +278,7 @@ impl Ingestor {
         Ok(Ingestor { state })
     }
 
-    pub(super) async fn run(
+    pub(crate) async fn run(
         self,
         cx: SourceContext,
         acknowledgements: AcknowledgementsConfig,
@@ -374,12 +435,19 @@ impl IngestorProcess {
     }
 
     async fn handle_sqs_message(&mut self, message: Message) -> Result<(), ProcessingError> {
-        let s3_event: S3Event = serde_json::from_str(message.body.unwrap_or_default().as_ref())
-            .context(InvalidSqsMessageSnafu {
+        let notification = parse_notification(message.body.unwrap_or_default().as_ref()).context(
+            InvalidSqsMessageSnafu {
                 message_id: message.message_id.unwrap_or_else(|| "<empty>".to_owned()),
-            })?;
-
-        self.handle_s3_event(s3_event).await
+            },
+        )?;
+
+        match notification {
+            Notification::S3(s3_event) => self.handle_s3_event(s3_event).await,
+            Notification::EventBridge(event) => self.handle_event_bridge_event(event).await,
+            Notification::CloudTrail(notification) => {
+                self.handle_cloud_trail_notification(notification).await
+            }
+        }
     }
 
     async fn handle_s3_event(&mut self, s3_event: S3Event) -> Result<(), ProcessingError> {
@@ -420,151 +488,76 @@ impl IngestorProcess {
             });
         }
 
-        let object_result = self
-            .state
-            .s3_client
-            .get_object()
-            .bucket(s3_event.s3.bucket.name.clone())
-            .key(s3_event.s3.object.key.clone())
-            .send()
+        self.state
+            .processor
+            .process(
+                &self.state.s3_client,
+                &mut self.out,
+                self.acknowledgements,
+                &s3_event.s3.bucket.name,
+                &s3_event.s3.object.key,
+                &s3_event.aws_region,
+            )
             .await
-            .context(GetObjectSnafu {
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
-            });
-
-        let object = object_result?;
-
-        let metadata = object.metadata;
-        let timestamp = object
-            .last_modified
-            .map(|ts| Utc.timestamp(ts.secs(), ts.subsec_nanos()))
-            .unwrap_or_else(Utc::now);
-
-        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
-        let object_reader = super::s3_object_decoder(
-            self.state.compression,
-            &s3_event.s3.object.key,
-            object.content_encoding.as_deref(),
-            object.content_type.as_deref(),
-            object.body,
-        )
-        .await;
-
-        // Record the read error seen to propagate up later so we avoid ack'ing the SQS
-        // message
-        //
-        // String is used as we cannot clone std::io::Error to take ownership in closure
-        //
-        // FramedRead likely stops when it gets an i/o error but I found it more clear to
-        // show that we `take_while` there hasn't been an error
-        //
-        // This can result in objects being partially processed before an error, but we
-        // prefer duplicate lines over message loss. Future work could include recording
-        // the offset of the object that has been read, but this would only be relevant in
-        // the case that the same vector instance processes the same message.
-        let mut read_error = None;
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
-            FramedRead::new(object_reader, CharacterDelimitedDecoder::new(b'\n'))
-                .map(|res| {
-                    res.map(|bytes| {
-                        emit!(BytesReceived {
-                            byte_size: bytes.len(),
-                            protocol: "http",
-                        });
-                        bytes
-                    })
-                    .map_err(|err| {
-                        read_error = Some(err);
-                    })
-                    .ok()
-                })
-                .take_while(|res| ready(res.is_some()))
-                .map(|r| r.expect("validated by take_while")),
-        );
-
-        let lines = match &self.state.multiline {
-            Some(config) => Box::new(
-                LineAgg::new(
-                    lines.map(|line| ((), line, ())),
-                    line_agg::Logic::new(config.clone()),
-                )
-                .map(|(_src, line, _context)| line),
-            ),
-            None => lines,
-        };
-
-        let bucket_name = Bytes::from(s3_event.s3.bucket.name.as_str().as_bytes().to_vec());
-        let object_key = Bytes::from(s3_event.s3.object.key.as_str().as_bytes().to_vec());
-        let aws_region = Bytes::from(s3_event.aws_region.as_str().as_bytes().to_vec());
-
-        let mut stream = lines.filter_map(move |line| {
-            let mut log = LogEvent::from(line).with_batch_notifier_option(&batch);
-
-            log.insert(path!("bucket"), bucket_name.clone());
-            log.insert(path!("object"), object_key.clone());
-            log.insert(path!("region"), aws_region.clone());
-            log.insert(log_schema().source_type_key(), Bytes::from("aws_s3"));
-            log.insert(log_schema().timestamp_key(), timestamp);
-
-            if let Some(metadata) = &metadata {
-                for (key, value) in metadata {
-                    log.insert(key.as_str(), value.clone());
-                }
-            }
+    }
 
-            emit!(OldEventsReceived {
-                count: 1,
-                byte_size: log.size_of()
+    async fn handle_event_bridge_event(
+        &mut self,
+        event: EventBridgeEvent,
+    ) -> Result<(), ProcessingError> {
+        if event.detail_type != "Object Created" {
+            emit!(SqsS3EventRecordInvalidEventIgnored {
+                bucket: &event.detail.bucket.name,
+                key: &event.detail.object.key,
+                kind: &event.detail_type,
+                name: event.detail.reason.as_deref().unwrap_or_default(),
             });
+            return Ok(());
+        }
 
-            ready(Some(log))
-        });
-
-        let send_error = match self.out.send_event_stream(&mut stream).await {
-            Ok(_) => None,
-            Err(error) => {
-                // count is set to 0 to have no discarded events considering
-                // the events are not yet acknowledged and will be retried in
-                // case of error
-                emit!(StreamClosedError { error, count: 0 });
-                Some(crate::source_sender::ClosedError)
-            }
-        };
+        // EventBridge delivers events to targets in the same region, so as with bucket
+        // notifications, this is only hit when messages are forwarded from one queue to another
+        if self.state.region.as_ref() != event.region.as_str() {
+            return Err(ProcessingError::WrongRegion {
+                bucket: event.detail.bucket.name,
+                key: event.detail.object.key,
+                region: event.region,
+            });
+        }
 
-        // Up above, `lines` captures `read_error`, and eventually is captured by `stream`,
-        // so we explicitly drop it so that we can again utilize `read_error` below.
-        drop(stream);
+        self.state
+            .processor
+            .process(
+                &self.state.s3_client,
+                &mut self.out,
+                self.acknowledgements,
+                &event.detail.bucket.name,
+                &event.detail.object.key,
+                &event.region,
+            )
+            .await
+    }
 
-        if let Some(error) = read_error {
-            Err(ProcessingError::ReadObject {
-                source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
-            })
-        } else if let Some(error) = send_error {
-            Err(ProcessingError::PipelineSend {
-                source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
-            })
-        } else {
-            match receiver {
-                None => Ok(()),
-                Some(receiver) => match receiver.await {
-                    BatchStatus::Delivered => Ok(()),
-                    BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement),
-                    BatchStatus::Rejected => {
-                        error!(
-                            message = "Sink reported events were rejected.",
-                            internal_log_rate_secs = 5,
-                        );
-                        // Failed events cannot be retried, so continue to delete the SQS source message.
-                        Ok(())
-                    }
-                },
-            }
+    async fn handle_cloud_trail_notification(
+        &mut self,
+        notification: CloudTrailNotification,
+    ) -> Result<(), ProcessingError> {
+        // CloudTrail doesn't include the region of the bucket, which is expected to be the region
+        // of the queue.
+        for key in &notification.s3_object_key {
+            self.state
+                .processor
+                .process(
+                    &self.state.s3_client,
+                    &mut self.out,
+                    self.acknowledgements,
+                    &notification.s3_bucket,
+                    key,
+                    self.state.region.as_ref(),
+                )
+                .await?;
         }
+        Ok(())
     }
 
     async fn receive_messages(&mut self) -> Result<Vec<Message>, SdkError<ReceiveMessageError>> {
@@ -594,6 +587,224 @@ impl IngestorProcess {
     }
 }
 
+/// Fetches an object, and sends its lines as events.
+///
+/// When acknowledgements are enabled, this waits for the events to be acknowledged, so that the
+/// object is only considered processed once they are delivered.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn process_object(
+    s3_client: &S3Client,
+    compression: super::Compression,
+    multiline: &Option<line_agg::Config>,
+    out: &mut SourceSender,
+    acknowledgements: bool,
+    bucket: &str,
+    key: &str,
+    region: &str,
+) -> Result<(), ProcessingError> {
+    let object_result = s3_client
+        .get_object()
+        .bucket(bucket.to_owned())
+        .key(key.to_owned())
+        .send()
+        .await
+        .context(GetObjectSnafu {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+        });
+
+    let object = object_result?;
+
+    let metadata = object.metadata;
+    let timestamp = object
+        .last_modified
+        .map(|ts| Utc.timestamp(ts.secs(), ts.subsec_nanos()))
+        .unwrap_or_else(Utc::now);
+
+    let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+    let object_reader = super::s3_object_decoder(
+        compression,
+        key,
+        object.content_encoding.as_deref(),
+        object.content_type.as_deref(),
+        object.body,
+    )
+    .await;
+
+    // Record the read error seen to propagate up later so we avoid considering the object
+    // processed
+    //
+    // String is used as we cannot clone std::io::Error to take ownership in closure
+    //
+    // FramedRead likely stops when it gets an i/o error but I found it more clear to
+    // show that we `take_while` there hasn't been an error
+    //
+    // This can result in objects being partially processed before an error, but we
+    // prefer duplicate lines over message loss. Future work could include recording
+    // the offset of the object that has been read, but this would only be relevant in
+    // the case that the same vector instance processes the same message.
+    let mut read_error = None;
+    let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
+        FramedRead::new(object_reader, CharacterDelimitedDecoder::new(b'\n'))
+            .map(|res| {
+                res.map(|bytes| {
+                    emit!(BytesReceived {
+                        byte_size: bytes.len(),
+                        protocol: "http",
+                    });
+                    bytes
+                })
+                .map_err(|err| {
+                    read_error = Some(err);
+                })
+                .ok()
+            })
+            .take_while(|res| ready(res.is_some()))
+            .map(|r| r.expect("validated by take_while")),
+    );
+
+    let lines = match multiline {
+        Some(config) => Box::new(
+            LineAgg::new(
+                lines.map(|line| ((), line, ())),
+                line_agg::Logic::new(config.clone()),
+            )
+            .map(|(_src, line, _context)| line),
+        ),
+        None => lines,
+    };
+
+    let bucket_name = Bytes::from(bucket.as_bytes().to_vec());
+    let object_key = Bytes::from(key.as_bytes().to_vec());
+    let aws_region = Bytes::from(region.as_bytes().to_vec());
+
+    let mut stream = lines.filter_map(move |line| {
+        let mut log = LogEvent::from(line).with_batch_notifier_option(&batch);
+
+        log.insert(path!("bucket"), bucket_name.clone());
+        log.insert(path!("object"), object_key.clone());
+        log.insert(path!("region"), aws_region.clone());
+        log.insert(log_schema().source_type_key(), Bytes::from("aws_s3"));
+        log.insert(log_schema().timestamp_key(), timestamp);
+
+        if let Some(metadata) = &metadata {
+            for (key, value) in metadata {
+                log.insert(key.as_str(), value.clone());
+            }
+        }
+
+        emit!(OldEventsReceived {
+            count: 1,
+            byte_size: log.size_of()
+        });
+
+        ready(Some(log))
+    });
+
+    let send_error = match out.send_event_stream(&mut stream).await {
+        Ok(_) => None,
+        Err(error) => {
+            // count is set to 0 to have no discarded events considering
+            // the events are not yet acknowledged and will be retried in
+            // case of error
+            emit!(StreamClosedError { error, count: 0 });
+            Some(crate::source_sender::ClosedError)
+        }
+    };
+
+    // Up above, `lines` captures `read_error`, and eventually is captured by `stream`,
+    // so we explicitly drop it so that we can again utilize `read_error` below.
+    drop(stream);
+
+    if let Some(error) = read_error {
+        Err(ProcessingError::ReadObject {
+            source: error,
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+        })
+    } else if let Some(error) = send_error {
+        Err(ProcessingError::PipelineSend {
+            source: error,
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+        })
+    } else {
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement),
+                BatchStatus::Rejected => {
+                    error!(
+                        message = "Sink reported events were rejected.",
+                        internal_log_rate_secs = 5,
+                    );
+                    // Failed events cannot be retried, so the object is considered processed.
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// An object notification, in any of the shapes it can be delivered to the queue in.
+#[derive(Clone, Debug)]
+pub enum Notification {
+    S3(S3Event),
+    EventBridge(EventBridgeEvent),
+    CloudTrail(CloudTrailNotification),
+}
+
+/// Parses the body of an SQS message as an object notification.
+///
+/// Notifications are either sent directly to the queue, or through an SNS topic, which wraps them
+/// in its own envelope unless raw message delivery is enabled. They are either bucket
+/// notifications, events matched by an EventBridge rule, or the notifications CloudTrail sends
+/// when it delivers log files.
+fn parse_notification(body: &str) -> Result<Notification, serde_json::Error> {
+    let value: serde_json::Value = serde_json::from_str(body)?;
+
+    // https://docs.aws.amazon.com/sns/latest/dg/sns-sqs-as-subscriber.html
+    if value.get("Type").and_then(serde_json::Value::as_str) == Some("Notification") {
+        if let Some(message) = value.get("Message").and_then(serde_json::Value::as_str) {
+            return parse_notification(message);
+        }
+    }
+
+    if value.get("detail-type").is_some() {
+        serde_json::from_value(value).map(Notification::EventBridge)
+    } else if value.get("s3Bucket").is_some() {
+        serde_json::from_value(value).map(Notification::CloudTrail)
+    } else {
+        serde_json::from_value(value).map(Notification::S3)
+    }
+}
+
+// https://docs.aws.amazon.com/awscloudtrail/latest/userguide/configure-sns-notifications-for-cloudtrail.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudTrailNotification {
+    pub s3_bucket: String,
+    pub s3_object_key: Vec<String>,
+}
+
+// https://docs.aws.amazon.com/AmazonS3/latest/userguide/ev-events.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EventBridgeEvent {
+    pub detail_type: String,
+    pub region: String,
+    pub detail: EventBridgeDetail,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EventBridgeDetail {
+    pub bucket: S3Bucket,
+    pub object: S3Object,
+    pub reason: Option<String>,
+}
+
 // https://docs.aws.amazon.com/AmazonS3/latest/dev/notification-content-structure.html
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -785,3 +996,56 @@ fn test_key_deserialize() {
         value
     );
 }
+
+#[test]
+fn test_parse_notification() {
+    let s3_event = r#"{"Records":[{"eventVersion":"2.1","eventSource":"aws:s3","awsRegion":"us-east-1","eventName":"ObjectCreated:Put","s3":{"bucket":{"name":"logs"},"object":{"key":"app/out+1.log"}}}]}"#;
+    match parse_notification(s3_event).unwrap() {
+        Notification::S3(s3_event) => {
+            assert_eq!(s3_event.records[0].s3.object.key, "app/out 1.log");
+        }
+        notification => panic!("unexpected notification: {:?}", notification),
+    }
+
+    let sns_notification = serde_json::json!({
+        "Type": "Notification",
+        "MessageId": "22b80b92-fdea-4c2c-8f9d-bdfb0c7bf324",
+        "TopicArn": "arn:aws:sns:us-east-1:123456789012:logs",
+        "Message": s3_event,
+    })
+    .to_string();
+    assert!(matches!(
+        parse_notification(&sns_notification).unwrap(),
+        Notification::S3(_)
+    ));
+
+    let event_bridge_event = r#"{"version":"0","id":"17793124-05d4-b198-2fde-7ededc63b103","detail-type":"Object Created","source":"aws.s3","account":"123456789012","time":"2021-11-12T00:00:00Z","region":"us-east-1","resources":["arn:aws:s3:::logs"],"detail":{"version":"0","bucket":{"name":"logs"},"object":{"key":"app/out.log","size":5,"etag":"b1946ac92492d2347c6235b4d2611184","sequencer":"00617F08299329D189"},"request-id":"N4N7GDK58NMKJ12R","requester":"123456789012","source-ip-address":"1.2.3.4","reason":"PutObject"}}"#;
+    for body in [
+        event_bridge_event.to_string(),
+        serde_json::json!({ "Type": "Notification", "Message": event_bridge_event }).to_string(),
+    ] {
+        match parse_notification(&body).unwrap() {
+            Notification::EventBridge(event) => {
+                assert_eq!(event.detail_type, "Object Created");
+                assert_eq!(event.region, "us-east-1");
+                assert_eq!(event.detail.bucket.name, "logs");
+                assert_eq!(event.detail.object.key, "app/out.log");
+                assert_eq!(event.detail.reason.as_deref(), Some("PutObject"));
+            }
+            notification => panic!("unexpected notification: {:?}", notification),
+        }
+    }
+
+    let cloud_trail_notification = serde_json::json!({
+        "Type": "Notification",
+        "Message": r#"{"s3Bucket":"trail","s3ObjectKey":["AWSLogs/123456789012/CloudTrail/us-east-1/2022/08/01/123456789012_CloudTrail_us-east-1_20220801T0000Z_a1b2c3.json.gz"]}"#,
+    })
+    .to_string();
+    match parse_notification(&cloud_trail_notification).unwrap() {
+        Notification::CloudTrail(notification) => {
+            assert_eq!(notification.s3_bucket, "trail");
+            assert_eq!(notification.s3_object_key.len(), 1);
+        }
+        notification => panic!("unexpected notification: {:?}", notification),
+    }
+}
@@ -139,7 +139,7 @@ impl SourceConfig for DatadogAgentConfig {
             .expect("registered metrics schema required")
             .clone();
 
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
         let source = DatadogAgentSource::new(
             self.store_api_key,
@@ -202,8 +202,16 @@ impl SourceConfig for DatadogAgentConfig {
             #[cfg(feature = "sources-syslog")]
             DeserializerConfig::Syslog => self.decoding.schema_definition(),
 
+            DeserializerConfig::AccessLog { .. } => self.decoding.schema_definition(),
+            DeserializerConfig::Avro { .. } => self.decoding.schema_definition(),
+            DeserializerConfig::Bson { .. } => self.decoding.schema_definition(),
+            DeserializerConfig::Cbor => self.decoding.schema_definition(),
+            DeserializerConfig::Cef => self.decoding.schema_definition(),
+            DeserializerConfig::Csv { .. } => self.decoding.schema_definition(),
+            DeserializerConfig::Leef { .. } => self.decoding.schema_definition(),
             DeserializerConfig::Native => self.decoding.schema_definition(),
             DeserializerConfig::NativeJson => self.decoding.schema_definition(),
+            DeserializerConfig::W3c { .. } => self.decoding.schema_definition(),
         };
 
         if self.multiple_outputs {
@@ -0,0 +1,159 @@
+//! Reading of the logs of containers using the `journald` logging driver from the journal, for
+//! daemons which can't read them back through their API, such as Docker before 20.10 or Podman
+//! built without systemd support.
+
+use std::{collections::HashMap, io, path::Path, process::Stdio};
+
+use bollard::container::LogOutput;
+use bytes::Bytes;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use serde_json::Value;
+use snafu::Snafu;
+use tokio::process::{Child, Command};
+use tokio_util::codec::{FramedRead, LinesCodec, LinesCodecError};
+
+/// The priority of the entries the `journald` logging drivers write for the standard error of
+/// containers, `LOG_ERR`, while the standard output is written with `LOG_INFO`.
+const STDERR_PRIORITY: &str = "3";
+
+#[derive(Debug, Snafu)]
+pub enum JournalError {
+    #[snafu(display("Running journalctl failed: {}", source))]
+    Spawn { source: io::Error },
+    #[snafu(display("Reading journalctl output failed: {}", source))]
+    Read { source: LinesCodecError },
+    #[snafu(display("Invalid journal entry: {}", source))]
+    InvalidEntry { source: serde_json::Error },
+}
+
+/// Follows the journal entries of a container written since the given UNIX timestamp, as Docker
+/// log outputs with timestamps.
+///
+/// The returned `journalctl` process is killed when dropped.
+pub(super) fn container_logs(
+    journalctl_path: &Path,
+    container_id: &str,
+    since: i64,
+) -> Result<
+    (
+        Child,
+        impl Stream<Item = Result<Option<LogOutput>, JournalError>>,
+    ),
+    JournalError,
+> {
+    let mut child = Command::new(journalctl_path)
+        .arg("--follow")
+        .arg("--all")
+        .arg("--output=json")
+        .arg(format!("--since=@{}", since))
+        // Both Docker and Podman write the full ID of the container in this field.
+        .arg(format!("CONTAINER_ID_FULL={}", container_id))
+        .stdout(Stdio::piped())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|source| JournalError::Spawn { source })?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    let entries = FramedRead::new(stdout, LinesCodec::new()).map(|line| {
+        let line = line.map_err(|source| JournalError::Read { source })?;
+        parse_entry(&line).map_err(|source| JournalError::InvalidEntry { source })
+    });
+    Ok((child, entries))
+}
+
+/// Converts a journal entry to the format of the Docker API, with the timestamp prepended and
+/// complete lines ending with a newline.
+fn parse_entry(line: &str) -> Result<Option<LogOutput>, serde_json::Error> {
+    let entry: HashMap<String, Value> = serde_json::from_str(line)?;
+    let text = |field: &str| entry.get(field).and_then(Value::as_str);
+
+    let message: Vec<u8> = match entry.get("MESSAGE") {
+        Some(Value::String(message)) => message.clone().into_bytes(),
+        // Messages which aren't valid UTF-8 are written as arrays of bytes.
+        Some(Value::Array(bytes)) => bytes
+            .iter()
+            .filter_map(Value::as_u64)
+            .map(|byte| byte as u8)
+            .collect(),
+        _ => return Ok(None),
+    };
+    let timestamp = match text("__REALTIME_TIMESTAMP").and_then(|micros| micros.parse::<i64>().ok())
+    {
+        Some(micros) => Utc.timestamp(
+            micros.div_euclid(1_000_000),
+            micros.rem_euclid(1_000_000) as u32 * 1_000,
+        ),
+        None => return Ok(None),
+    };
+    let partial = text("CONTAINER_PARTIAL_MESSAGE") == Some("true");
+
+    let mut line = timestamp
+        .to_rfc3339_opts(SecondsFormat::Nanos, true)
+        .into_bytes();
+    line.push(b' ');
+    line.extend(message);
+    if !partial {
+        line.push(b'\n');
+    }
+    let message = Bytes::from(line);
+
+    Ok(Some(if text("PRIORITY") == Some(STDERR_PRIORITY) {
+        LogOutput::StdErr { message }
+    } else {
+        LogOutput::StdOut { message }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(output: LogOutput) -> (&'static str, Bytes) {
+        match output {
+            LogOutput::StdOut { message } => ("stdout", message),
+            LogOutput::StdErr { message } => ("stderr", message),
+            _ => panic!("Unexpected output"),
+        }
+    }
+
+    #[test]
+    fn parses_entries() {
+        let entry = parse_entry(
+            r#"{"__REALTIME_TIMESTAMP": "1660125600123456", "PRIORITY": "6", "MESSAGE": "hello", "CONTAINER_ID_FULL": "3d4e5f", "CONTAINER_NAME": "web"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            message(entry),
+            (
+                "stdout",
+                Bytes::from("2022-08-10T10:00:00.123456000Z hello\n")
+            )
+        );
+
+        let entry = parse_entry(
+            r#"{"__REALTIME_TIMESTAMP": "1660125600000000", "PRIORITY": "3", "MESSAGE": [104, 105, 255], "CONTAINER_PARTIAL_MESSAGE": "true"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            message(entry),
+            (
+                "stderr",
+                Bytes::from(&b"2022-08-10T10:00:00.000000000Z hi\xff"[..])
+            )
+        );
+    }
+
+    #[test]
+    fn skips_entries_without_message() {
+        assert!(
+            parse_entry(r#"{"__REALTIME_TIMESTAMP": "1660125600000000", "PRIORITY": "6"}"#)
+                .unwrap()
+                .is_none()
+        );
+        assert!(parse_entry("not json").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap, convert::TryFrom, future::ready, pin::Pin, sync::Arc, time::Duration,
+    collections::HashMap, convert::TryFrom, future::ready, path::PathBuf, pin::Pin, sync::Arc,
+    time::Duration,
 };
 
 use bollard::{
@@ -20,6 +21,8 @@ use vector_config::configurable_component;
 use vector_core::ByteSizeOf;
 
 use super::util::MultilineConfig;
+#[cfg(unix)]
+use crate::internal_events::DockerLogsPodMetadataFetchError;
 use crate::{
     config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
     docker::{docker, DockerTlsConfig},
@@ -27,7 +30,7 @@ use crate::{
     internal_events::{
         BytesReceived, DockerLogsCommunicationError, DockerLogsContainerEventReceived,
         DockerLogsContainerMetadataFetchError, DockerLogsContainerUnwatch,
-        DockerLogsContainerWatch, DockerLogsEventsReceived,
+        DockerLogsContainerWatch, DockerLogsEventsReceived, DockerLogsJournalReadError,
         DockerLogsLoggingDriverUnsupportedError, DockerLogsTimestampParseError, StreamClosedError,
     },
     line_agg::{self, LineAgg},
@@ -35,11 +38,17 @@ use crate::{
     SourceSender,
 };
 
+pub mod journald;
+#[cfg(unix)]
+pub mod podman;
+
 const IMAGE: &str = "image";
 const CREATED_AT: &str = "container_created_at";
 const NAME: &str = "container_name";
 const STREAM: &str = "stream";
 const CONTAINER: &str = "container_id";
+const POD_ID: &str = "pod_id";
+const POD_NAME: &str = "pod_name";
 // Prevent short hostname from being wrongly regconized as a container's short ID.
 const MIN_HOSTNAME_LENGTH: usize = 6;
 
@@ -65,6 +74,10 @@ pub struct DockerLogsConfig {
     /// Use an HTTPS URL to enable TLS encryption.
     ///
     /// If absent, Vector will try to use `DOCKER_HOST` environment variable. If `DOCKER_HOST` is also absent, Vector will use default Docker local socket (`/var/run/docker.sock` on Unix platforms, `//./pipe/docker_engine` on Windows).
+    ///
+    /// On Unix platforms, if the default Docker socket doesn't exist, Vector will use the socket of Podman, first the
+    /// rootless one of the current user (`$XDG_RUNTIME_DIR/podman/podman.sock`), and then the rootful one
+    /// (`/run/podman/podman.sock`).
     docker_host: Option<String>,
 
     /// A list of container IDs or names of containers to exclude from log collection.
@@ -122,6 +135,12 @@ pub struct DockerLogsConfig {
 
     #[configurable(derived)]
     tls: Option<DockerTlsConfig>,
+
+    /// The path to the `journalctl` executable.
+    ///
+    /// It's used to read the logs of containers using the `journald` logging driver from the journal, when the daemon
+    /// can't read them back, such as Docker before 20.10 or Podman built without systemd support.
+    journalctl_path: PathBuf,
 }
 
 impl Default for DockerLogsConfig {
@@ -138,6 +157,7 @@ impl Default for DockerLogsConfig {
             auto_partial_merge: true,
             multiline: None,
             retry_backoff_secs: 2,
+            journalctl_path: PathBuf::from("journalctl"),
         }
     }
 }
@@ -200,6 +220,9 @@ impl SourceConfig for DockerLogsConfig {
 
         // Capture currently running containers, and do main future(run)
         let fut = async move {
+            #[cfg(unix)]
+            let source = source.detect_podman().await;
+
             match source.handle_running_containers().await {
                 Ok(source) => source.run().await,
                 Err(error) => {
@@ -398,6 +421,8 @@ impl DockerLogsSource {
             out,
             main_send,
             shutdown,
+            #[cfg(unix)]
+            podman: None,
         };
 
         Ok(DockerLogsSource {
@@ -410,6 +435,31 @@ impl DockerLogsSource {
         })
     }
 
+    /// Detects whether the daemon is Podman, to add the pods of containers to their events.
+    #[cfg(unix)]
+    async fn detect_podman(mut self) -> Self {
+        match self.esb.core.docker.version().await {
+            Ok(version) if podman::is_podman(&version) => {
+                let config = &self.esb.core.config;
+                match crate::docker::unix_socket_path(config.docker_host.clone()) {
+                    Some(socket) => {
+                        info!(message = "Detected Podman, adding pod metadata to events.");
+                        self.esb.podman = Some(Arc::new(podman::PodmanClient::new(socket)));
+                    }
+                    None => info!(
+                        message = "Detected Podman, but pod metadata is only available through a Unix socket."
+                    ),
+                }
+            }
+            Ok(_) => {}
+            Err(error) => emit!(DockerLogsCommunicationError {
+                error,
+                container_id: None,
+            }),
+        }
+        self
+    }
+
     /// Future that captures currently running containers, and starts event streams for them.
     async fn handle_running_containers(mut self) -> crate::Result<Self> {
         let mut filters = HashMap::new();
@@ -581,6 +631,9 @@ struct EventStreamBuilder {
     main_send: mpsc::UnboundedSender<Result<ContainerLogInfo, (ContainerId, ErrorPersistence)>>,
     /// Self and event streams will end on this.
     shutdown: ShutdownSignal,
+    /// Reads the pods of containers, if the daemon is Podman.
+    #[cfg(unix)]
+    podman: Option<Arc<podman::PodmanClient>>,
 }
 
 impl EventStreamBuilder {
@@ -598,7 +651,11 @@ impl EventStreamBuilder {
                 .await
             {
                 Ok(details) => match ContainerMetadata::from_details(details) {
-                    Ok(metadata) => {
+                    #[allow(unused_mut)]
+                    Ok(mut metadata) => {
+                        #[cfg(unix)]
+                        this.add_pod_metadata(&id, &mut metadata).await;
+
                         let info = ContainerLogInfo::new(id, metadata, this.core.now_timestamp);
                         this.run_event_stream(info).await;
                         return;
@@ -620,6 +677,20 @@ impl EventStreamBuilder {
         ContainerState::new_running()
     }
 
+    /// Adds the pod of the container to its metadata, if the daemon is Podman.
+    #[cfg(unix)]
+    async fn add_pod_metadata(&self, id: &ContainerId, metadata: &mut ContainerMetadata) {
+        if let Some(podman) = &self.podman {
+            match podman.pod_metadata(id.as_str()).await {
+                Ok(pod) => metadata.pod = pod,
+                Err(error) => emit!(DockerLogsPodMetadataFetchError {
+                    error,
+                    container_id: id.as_str()
+                }),
+            }
+        }
+    }
+
     /// If info is present, restarts event stream which will run until shutdown.
     fn restart(&self, container: &mut ContainerState) {
         if let Some(info) = container.take_info() {
@@ -629,6 +700,10 @@ impl EventStreamBuilder {
     }
 
     async fn run_event_stream(mut self, mut info: ContainerLogInfo) {
+        if info.read_journal {
+            return self.run_journal_stream(info).await;
+        }
+
         // Establish connection
         let options = Some(LogsOptions::<String> {
             follow: true,
@@ -650,6 +725,7 @@ impl EventStreamBuilder {
         let core = Arc::clone(&self.core);
 
         let mut error = None;
+        let mut read_journal = false;
         let events_stream = stream
             .map(|value| {
                 match value {
@@ -665,11 +741,17 @@ impl EventStreamBuilder {
                             DockerError::DockerResponseServerError { status_code, .. }
                                 if *status_code == http::StatusCode::NOT_IMPLEMENTED =>
                             {
-                                emit!(DockerLogsLoggingDriverUnsupportedError {
-                                    error,
-                                    container_id: info.id.as_str(),
-                                });
-                                Err(ErrorPersistence::Permanent)
+                                if info.metadata.log_driver.as_deref() == Some("journald") {
+                                    // The daemon can't read back the logs, but they're in the journal.
+                                    read_journal = true;
+                                    Err(ErrorPersistence::Transient)
+                                } else {
+                                    emit!(DockerLogsLoggingDriverUnsupportedError {
+                                        error,
+                                        container_id: info.id.as_str(),
+                                    });
+                                    Err(ErrorPersistence::Permanent)
+                                }
                             }
                             _ => {
                                 emit!(DockerLogsCommunicationError {
@@ -689,35 +771,22 @@ impl EventStreamBuilder {
             .filter_map(|v| ready(v.unwrap()))
             .take_until(self.shutdown.clone());
 
-        let events_stream: Box<dyn Stream<Item = LogEvent> + Unpin + Send> =
-            if let Some(ref line_agg_config) = core.line_agg_config {
-                Box::new(line_agg_adapter(
-                    events_stream,
-                    line_agg::Logic::new(line_agg_config.clone()),
-                ))
-            } else {
-                Box::new(events_stream)
-            };
-
-        let host_key = self.host_key.clone();
-        let hostname = self.hostname.clone();
-        let result = {
-            let mut stream =
-                events_stream.map(move |event| add_hostname(event, &host_key, &hostname));
-            self.out
-                .send_event_stream(&mut stream)
-                .await
-                .map_err(|error| {
-                    let (count, _) = stream.size_hint();
-                    emit!(StreamClosedError { error, count });
-                })
-        };
+        let result = self.send_log_events(events_stream).await;
 
         // End of stream
         emit!(DockerLogsContainerUnwatch {
             container_id: info.id.as_str()
         });
 
+        if read_journal && result.is_ok() {
+            info!(
+                message = "Reading container logs from the journal.",
+                container_id = %info.id.as_str()
+            );
+            info.read_journal = true;
+            return self.run_journal_stream(info).await;
+        }
+
         let result = match (result, error) {
             (Ok(()), None) => Ok(info),
             (Err(()), _) => Err((info.id, ErrorPersistence::Permanent)),
@@ -727,6 +796,91 @@ impl EventStreamBuilder {
         self.finish(result);
     }
 
+    /// Runs the event stream of a container using the `journald` logging driver, reading its logs
+    /// from the journal.
+    async fn run_journal_stream(mut self, mut info: ContainerLogInfo) {
+        let core = Arc::clone(&self.core);
+        let (_journalctl, entries) = match journald::container_logs(
+            &core.config.journalctl_path,
+            info.id.as_str(),
+            info.log_since(),
+        ) {
+            Ok(logs) => logs,
+            Err(error) => {
+                emit!(DockerLogsJournalReadError {
+                    error,
+                    container_id: info.id.as_str()
+                });
+                return self.finish(Err((info.id, ErrorPersistence::Permanent)));
+            }
+        };
+        emit!(DockerLogsContainerWatch {
+            container_id: info.id.as_str()
+        });
+
+        let mut partial_event_merge_state = None;
+        let events_stream = entries
+            .filter_map(|entry| {
+                ready(match entry {
+                    Ok(output) => output.and_then(|output| {
+                        info.new_event(
+                            output,
+                            core.config.partial_event_marker_field.clone(),
+                            core.config.auto_partial_merge,
+                            &mut partial_event_merge_state,
+                        )
+                    }),
+                    Err(error) => {
+                        emit!(DockerLogsJournalReadError {
+                            error,
+                            container_id: info.id.as_str()
+                        });
+                        None
+                    }
+                })
+            })
+            .take_until(self.shutdown.clone());
+
+        let result = self.send_log_events(events_stream).await;
+
+        emit!(DockerLogsContainerUnwatch {
+            container_id: info.id.as_str()
+        });
+
+        self.finish(match result {
+            Ok(()) => Ok(info),
+            Err(()) => Err((info.id, ErrorPersistence::Permanent)),
+        });
+    }
+
+    /// Aggregates the lines of the log events of a container if configured, and sends them.
+    async fn send_log_events(
+        &mut self,
+        events_stream: impl Stream<Item = LogEvent> + Unpin + Send,
+    ) -> Result<(), ()> {
+        let core = Arc::clone(&self.core);
+        let events_stream: Box<dyn Stream<Item = LogEvent> + Unpin + Send> =
+            if let Some(ref line_agg_config) = core.line_agg_config {
+                Box::new(line_agg_adapter(
+                    events_stream,
+                    line_agg::Logic::new(line_agg_config.clone()),
+                ))
+            } else {
+                Box::new(events_stream)
+            };
+
+        let host_key = self.host_key.clone();
+        let hostname = self.hostname.clone();
+        let mut stream = events_stream.map(move |event| add_hostname(event, &host_key, &hostname));
+        self.out
+            .send_event_stream(&mut stream)
+            .await
+            .map_err(|error| {
+                let (count, _) = stream.size_hint();
+                emit!(StreamClosedError { error, count });
+            })
+    }
+
     fn finish(self, result: Result<ContainerLogInfo, (ContainerId, ErrorPersistence)>) {
         // This can legaly fail when shutting down, and any other
         // reason should have been logged in the main future.
@@ -827,6 +981,8 @@ struct ContainerLogInfo {
     /// generation of ContainerState at event_stream creation
     generation: u64,
     metadata: ContainerMetadata,
+    /// True if logs are read from the journal, as the daemon can't read them back
+    read_journal: bool,
 }
 
 impl ContainerLogInfo {
@@ -839,6 +995,7 @@ impl ContainerLogInfo {
             last_log: None,
             generation: 0,
             metadata,
+            read_journal: false,
         }
     }
 
@@ -973,6 +1130,18 @@ impl ContainerLogInfo {
             // Container name.
             log_event.insert(NAME, self.metadata.name.clone());
 
+            // Pod, only known with Podman.
+            if let Some(pod) = &self.metadata.pod {
+                log_event.insert(POD_ID, pod.id.clone());
+                log_event.insert(POD_NAME, pod.name.clone());
+                let prefix_path = parse_path("pod_label");
+                for (key, value) in pod.labels.iter() {
+                    let mut path = prefix_path.clone().segments;
+                    path.push(OwnedSegment::Field(key.clone()));
+                    log_event.insert(&path, value.clone());
+                }
+            }
+
             // Container image.
             log_event.insert(IMAGE, self.metadata.image.clone());
 
@@ -1049,6 +1218,17 @@ struct ContainerMetadata {
     image: Value,
     /// created_at
     created_at: DateTime<Utc>,
+    /// log_driver
+    log_driver: Option<String>,
+    /// pod, only known with Podman
+    pod: Option<PodMetadata>,
+}
+
+/// The pod a container belongs to.
+struct PodMetadata {
+    id: String,
+    name: String,
+    labels: HashMap<String, String>,
 }
 
 impl ContainerMetadata {
@@ -1058,6 +1238,10 @@ impl ContainerMetadata {
         let created = details.created.unwrap();
 
         let labels = config.labels.unwrap_or_default();
+        let log_driver = details
+            .host_config
+            .and_then(|host_config| host_config.log_config)
+            .and_then(|log_config| log_config.typ);
 
         Ok(ContainerMetadata {
             labels,
@@ -1065,6 +1249,8 @@ impl ContainerMetadata {
             name_str: name,
             image: config.image.unwrap().into(),
             created_at: DateTime::parse_from_rfc3339(created.as_str())?.with_timezone(&Utc),
+            log_driver,
+            pod: None,
         })
     }
 }
@@ -1094,6 +1280,8 @@ fn line_agg_adapter(
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
 
     #[test]
@@ -1115,6 +1303,50 @@ mod tests {
         source.hostname = Some("a".to_owned());
         assert!(!source.exclude_self("a29d569bd46c"));
     }
+
+    #[test]
+    fn adds_pod_metadata() {
+        let metadata = ContainerMetadata {
+            labels: HashMap::new(),
+            name: "web-app".into(),
+            name_str: "/web-app".to_owned(),
+            image: "nginx".into(),
+            created_at: Utc.ymd(2022, 8, 10).and_hms(9, 0, 0),
+            log_driver: Some("journald".to_owned()),
+            pod: Some(PodMetadata {
+                id: "8a1b2c".to_owned(),
+                name: "web".to_owned(),
+                labels: HashMap::from([("app".to_owned(), "web".to_owned())]),
+            }),
+        };
+        let mut info = ContainerLogInfo::new(
+            ContainerId::new("3d4e5f".to_owned()),
+            metadata,
+            Utc.ymd(2022, 8, 10).and_hms(9, 0, 0),
+        );
+
+        let log = info
+            .new_event(
+                LogOutput::StdOut {
+                    message: Bytes::from("2022-08-10T10:00:00.000000000Z hello\n"),
+                },
+                None,
+                true,
+                &mut None,
+            )
+            .unwrap();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log[POD_ID], "8a1b2c".into());
+        assert_eq!(log[POD_NAME], "web".into());
+        assert_eq!(
+            log.get("pod_label")
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .get("app"),
+            Some(&Value::from("web"))
+        );
+    }
 }
 
 #[cfg(all(test, feature = "docker-logs-integration-tests"))]
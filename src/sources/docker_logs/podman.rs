@@ -0,0 +1,154 @@
+//! Support for Podman, whose Docker compatible API doesn't report the pods containers belong to, so
+//! they're read from its own API, served on the same socket.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use bollard::system::Version;
+use bytes::Buf;
+use http::{StatusCode, Uri};
+use hyper::{Body, Client};
+use hyperlocal::UnixConnector;
+use serde::{de::DeserializeOwned, Deserialize};
+use snafu::{ResultExt, Snafu};
+
+use super::PodMetadata;
+
+#[derive(Debug, Snafu)]
+pub enum PodmanError {
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: hyper::Error },
+    #[snafu(display("Request failed with status {}", status))]
+    Status { status: StatusCode },
+    #[snafu(display("Reading response failed: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Invalid response: {}", source))]
+    InvalidResponse { source: serde_json::Error },
+}
+
+/// The fields of `/libpod/containers/{id}/json` used by the source.
+#[derive(Deserialize)]
+struct LibpodContainer {
+    /// The ID of the pod of the container, empty if it doesn't belong to one.
+    #[serde(rename = "Pod", default)]
+    pod: String,
+}
+
+/// The fields of `/libpod/pods/{id}/json` used by the source.
+#[derive(Deserialize)]
+struct LibpodPod {
+    #[serde(rename = "Id")]
+    id: String,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Labels", default)]
+    labels: Option<HashMap<String, String>>,
+}
+
+/// Returns whether the daemon is Podman, from the components of its version.
+pub(super) fn is_podman(version: &Version) -> bool {
+    version
+        .components
+        .iter()
+        .flatten()
+        .any(|component| component.name == "Podman Engine")
+}
+
+pub(super) struct PodmanClient {
+    socket: PathBuf,
+    client: Client<UnixConnector, Body>,
+}
+
+impl PodmanClient {
+    pub(super) fn new(socket: PathBuf) -> Self {
+        Self {
+            socket,
+            client: Client::builder().build(UnixConnector),
+        }
+    }
+
+    /// Returns the pod of the given container, if it belongs to one.
+    pub(super) async fn pod_metadata(
+        &self,
+        container_id: &str,
+    ) -> Result<Option<PodMetadata>, PodmanError> {
+        let container: LibpodContainer = self
+            .get(&format!("/libpod/containers/{}/json", container_id))
+            .await?;
+        if container.pod.is_empty() {
+            return Ok(None);
+        }
+
+        let pod: LibpodPod = self
+            .get(&format!("/libpod/pods/{}/json", container.pod))
+            .await?;
+        Ok(Some(PodMetadata {
+            id: pod.id,
+            name: pod.name,
+            labels: pod.labels.unwrap_or_default(),
+        }))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, PodmanError> {
+        let uri: Uri = hyperlocal::Uri::new(&self.socket, path).into();
+        let response = self.client.get(uri).await.context(RequestSnafu)?;
+        if !response.status().is_success() {
+            return Err(PodmanError::Status {
+                status: response.status(),
+            });
+        }
+        let body = hyper::body::aggregate(response.into_body())
+            .await
+            .context(ReadResponseSnafu)?;
+        serde_json::from_reader(body.reader()).context(InvalidResponseSnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(components: &str) -> Version {
+        serde_json::from_str(&format!(
+            r#"{{
+                "Platform": {{"Name": "linux/amd64"}},
+                "Components": {},
+                "Version": "4.2.0",
+                "ApiVersion": "1.41",
+                "MinAPIVersion": "1.24",
+                "GitCommit": "",
+                "GoVersion": "go1.18.4",
+                "Os": "linux",
+                "Arch": "amd64",
+                "KernelVersion": "5.18.16-200.fc36.x86_64",
+                "BuildTime": "2022-08-10T14:00:00+00:00"
+            }}"#,
+            components
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn detects_podman() {
+        assert!(is_podman(&version(
+            r#"[{"Name": "Podman Engine", "Version": "4.2.0", "Details": {}}]"#
+        )));
+        assert!(!is_podman(&version(
+            r#"[{"Name": "Engine", "Version": "20.10.17", "Details": {}}, {"Name": "containerd", "Version": "1.6.6", "Details": {}}]"#
+        )));
+    }
+
+    #[test]
+    fn deserializes_pods() {
+        let pod: LibpodPod = serde_json::from_str(
+            r#"{"Id": "8a1b2c", "Name": "web", "Created": "2022-08-01T10:00:00Z", "Labels": {"app": "web"}}"#,
+        )
+        .unwrap();
+        assert_eq!(pod.id, "8a1b2c");
+        assert_eq!(pod.name, "web");
+        assert_eq!(pod.labels.unwrap()["app"], "web");
+
+        let container: LibpodContainer =
+            serde_json::from_str(r#"{"Id": "3d4e5f", "Pod": ""}"#).unwrap();
+        assert!(container.pod.is_empty());
+    }
+}
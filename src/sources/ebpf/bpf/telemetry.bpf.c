@@ -0,0 +1,421 @@
+// Programs of the `ebpf` source, reporting process executions, TCP connections and DNS queries.
+//
+// The kernel types are declared with only the fields the programs read, and relocated against the
+// BTF of the running kernel when loaded (CO-RE), so a single object runs on any kernel with BTF.
+//
+// The layouts of the events written to the `EVENTS` perf buffer must be kept in sync with their
+// parsing in `events.rs`.
+
+typedef unsigned char __u8;
+typedef unsigned short __u16;
+typedef unsigned int __u32;
+typedef unsigned long long __u64;
+typedef signed char __s8;
+typedef short __s16;
+typedef int __s32;
+typedef long long __s64;
+typedef __u16 __be16;
+typedef __u32 __be32;
+typedef __u32 __wsum;
+typedef unsigned long __kernel_size_t;
+
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_endian.h>
+#include <bpf/bpf_tracing.h>
+
+char LICENSE[] SEC("license") = "Dual MPL/GPL";
+
+#define BPF_MAP_TYPE_PERF_EVENT_ARRAY 4
+#define BPF_MAP_TYPE_PERCPU_ARRAY 6
+#define BPF_F_CURRENT_CPU 0xffffffffULL
+
+#define AF_INET 2
+#define AF_INET6 10
+#define DNS_PORT 53
+
+// Kernel types.
+
+#if defined(__TARGET_ARCH_x86)
+struct pt_regs {
+    unsigned long di;
+    unsigned long si;
+    unsigned long dx;
+    unsigned long cx;
+    unsigned long r8;
+    unsigned long r9;
+    unsigned long sp;
+    unsigned long ax;
+    unsigned long ip;
+} __attribute__((preserve_access_index));
+#elif defined(__TARGET_ARCH_arm64)
+struct pt_regs;
+struct user_pt_regs {
+    __u64 regs[31];
+    __u64 sp;
+    __u64 pc;
+    __u64 pstate;
+} __attribute__((preserve_access_index));
+#endif
+
+struct ns_common {
+    unsigned int inum;
+} __attribute__((preserve_access_index));
+
+struct mnt_namespace {
+    struct ns_common ns;
+} __attribute__((preserve_access_index));
+
+struct pid_namespace {
+    struct ns_common ns;
+} __attribute__((preserve_access_index));
+
+struct net {
+    struct ns_common ns;
+} __attribute__((preserve_access_index));
+
+struct nsproxy {
+    struct mnt_namespace *mnt_ns;
+    struct pid_namespace *pid_ns_for_children;
+    struct net *net_ns;
+} __attribute__((preserve_access_index));
+
+struct task_struct {
+    int tgid;
+    struct task_struct *real_parent;
+    struct nsproxy *nsproxy;
+} __attribute__((preserve_access_index));
+
+struct in6_addr {
+    __u8 s6_addr[16];
+} __attribute__((preserve_access_index));
+
+struct sock_common {
+    __be32 skc_daddr;
+    __be32 skc_rcv_saddr;
+    __be16 skc_dport;
+    __u16 skc_num;
+    unsigned short skc_family;
+    struct in6_addr skc_v6_daddr;
+    struct in6_addr skc_v6_rcv_saddr;
+} __attribute__((preserve_access_index));
+
+struct sock {
+    struct sock_common __sk_common;
+} __attribute__((preserve_access_index));
+
+struct sockaddr_in {
+    unsigned short sin_family;
+    __be16 sin_port;
+    __be32 sin_addr;
+};
+
+struct sockaddr_in6 {
+    unsigned short sin6_family;
+    __be16 sin6_port;
+    __be32 sin6_flowinfo;
+    __u8 sin6_addr[16];
+};
+
+struct iovec {
+    void *iov_base;
+    __kernel_size_t iov_len;
+} __attribute__((preserve_access_index));
+
+enum iter_type {
+    ITER_UBUF,
+};
+
+// The iterator of the data sent, whose buffer is either a single user buffer (since 6.0), or an
+// array of buffers, named `__iov` since 6.4.
+struct iov_iter {
+    __u8 iter_type;
+    const struct iovec *iov;
+    const struct iovec *__iov;
+    void *ubuf;
+} __attribute__((preserve_access_index));
+
+struct msghdr {
+    void *msg_name;
+    struct iov_iter msg_iter;
+} __attribute__((preserve_access_index));
+
+struct trace_entry {
+    unsigned short type;
+    unsigned char flags;
+    unsigned char preempt_count;
+    int pid;
+};
+
+struct trace_event_raw_sched_process_exec {
+    struct trace_entry ent;
+    __u32 __data_loc_filename;
+    int pid;
+    int old_pid;
+};
+
+// Events.
+
+#define TASK_COMM_LEN 16
+#define FILENAME_LEN 256
+#define DNS_PAYLOAD_LEN 256
+
+enum event_kind {
+    EVENT_EXEC = 1,
+    EVENT_TCP_CONNECT = 2,
+    EVENT_TCP_ACCEPT = 3,
+    EVENT_TCP_CLOSE = 4,
+    EVENT_DNS_QUERY = 5,
+};
+
+// The process an event happened in, starting every event.
+struct event_header {
+    __u32 kind;
+    __u32 pid;
+    __u32 tid;
+    __u32 ppid;
+    __u32 uid;
+    __u32 gid;
+    __u64 cgroup_id;
+    __u32 mnt_ns;
+    __u32 pid_ns;
+    __u32 net_ns;
+    __u32 _pad;
+    char comm[TASK_COMM_LEN];
+};
+
+struct exec_event {
+    struct event_header header;
+    char filename[FILENAME_LEN];
+};
+
+// Addresses are in network byte order, and IPv4 addresses only use the first 4 bytes.
+struct tcp_event {
+    struct event_header header;
+    __u16 family;
+    __be16 sport;
+    __be16 dport;
+    __u16 _pad;
+    __u8 saddr[16];
+    __u8 daddr[16];
+};
+
+struct dns_event {
+    struct event_header header;
+    __u16 family;
+    __be16 dport;
+    __u32 len;
+    __u8 daddr[16];
+    __u8 payload[DNS_PAYLOAD_LEN];
+};
+
+union event {
+    struct exec_event exec;
+    struct tcp_event tcp;
+    struct dns_event dns;
+};
+
+// Maps.
+
+struct bpf_map_def {
+    unsigned int type;
+    unsigned int key_size;
+    unsigned int value_size;
+    unsigned int max_entries;
+    unsigned int map_flags;
+};
+
+// Sized to the number of CPUs by the loader.
+struct bpf_map_def SEC("maps") EVENTS = {
+    .type = BPF_MAP_TYPE_PERF_EVENT_ARRAY,
+    .key_size = sizeof(__u32),
+    .value_size = sizeof(__u32),
+    .max_entries = 0,
+};
+
+// Events are too large for the stack, and are built in this scratch space instead.
+struct bpf_map_def SEC("maps") SCRATCH = {
+    .type = BPF_MAP_TYPE_PERCPU_ARRAY,
+    .key_size = sizeof(__u32),
+    .value_size = sizeof(union event),
+    .max_entries = 1,
+};
+
+static __always_inline union event *scratch_event(void) {
+    __u32 key = 0;
+    return bpf_map_lookup_elem(&SCRATCH, &key);
+}
+
+static __always_inline void fill_header(struct event_header *header, __u32 kind) {
+    struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+    __u64 pid_tgid = bpf_get_current_pid_tgid();
+    __u64 uid_gid = bpf_get_current_uid_gid();
+
+    header->kind = kind;
+    header->pid = pid_tgid >> 32;
+    header->tid = (__u32)pid_tgid;
+    header->ppid = BPF_CORE_READ(task, real_parent, tgid);
+    header->uid = (__u32)uid_gid;
+    header->gid = uid_gid >> 32;
+    header->cgroup_id = bpf_get_current_cgroup_id();
+    header->mnt_ns = BPF_CORE_READ(task, nsproxy, mnt_ns, ns.inum);
+    header->pid_ns = BPF_CORE_READ(task, nsproxy, pid_ns_for_children, ns.inum);
+    header->net_ns = BPF_CORE_READ(task, nsproxy, net_ns, ns.inum);
+    header->_pad = 0;
+    bpf_get_current_comm(&header->comm, sizeof(header->comm));
+}
+
+// Process executions.
+
+SEC("tracepoint/sched_process_exec")
+int sched_process_exec(struct trace_event_raw_sched_process_exec *ctx) {
+    union event *event = scratch_event();
+    if (!event) {
+        return 0;
+    }
+
+    fill_header(&event->exec.header, EVENT_EXEC);
+    unsigned int offset = ctx->__data_loc_filename & 0xFFFF;
+    bpf_probe_read_kernel_str(&event->exec.filename, sizeof(event->exec.filename),
+                              (void *)ctx + offset);
+
+    bpf_perf_event_output(ctx, &EVENTS, BPF_F_CURRENT_CPU, &event->exec, sizeof(event->exec));
+    return 0;
+}
+
+// TCP connections.
+
+static __always_inline int output_tcp_event(struct pt_regs *ctx, struct sock *sk, __u32 kind) {
+    union event *event = scratch_event();
+    if (!event || !sk) {
+        return 0;
+    }
+
+    __u16 family = BPF_CORE_READ(sk, __sk_common.skc_family);
+    if (family != AF_INET && family != AF_INET6) {
+        return 0;
+    }
+
+    struct tcp_event *tcp = &event->tcp;
+    fill_header(&tcp->header, kind);
+    tcp->family = family;
+    tcp->sport = bpf_htons(BPF_CORE_READ(sk, __sk_common.skc_num));
+    tcp->dport = BPF_CORE_READ(sk, __sk_common.skc_dport);
+    tcp->_pad = 0;
+    __builtin_memset(tcp->saddr, 0, sizeof(tcp->saddr));
+    __builtin_memset(tcp->daddr, 0, sizeof(tcp->daddr));
+    if (family == AF_INET) {
+        bpf_core_read(tcp->saddr, 4, &sk->__sk_common.skc_rcv_saddr);
+        bpf_core_read(tcp->daddr, 4, &sk->__sk_common.skc_daddr);
+    } else {
+        bpf_core_read(tcp->saddr, 16, &sk->__sk_common.skc_v6_rcv_saddr);
+        bpf_core_read(tcp->daddr, 16, &sk->__sk_common.skc_v6_daddr);
+    }
+
+    // Listening sockets being closed have no peer.
+    if (kind == EVENT_TCP_CLOSE && tcp->dport == 0) {
+        return 0;
+    }
+
+    bpf_perf_event_output(ctx, &EVENTS, BPF_F_CURRENT_CPU, tcp, sizeof(*tcp));
+    return 0;
+}
+
+SEC("kprobe/tcp_connect")
+int BPF_KPROBE(tcp_connect, struct sock *sk) {
+    return output_tcp_event(ctx, sk, EVENT_TCP_CONNECT);
+}
+
+SEC("kretprobe/inet_csk_accept")
+int BPF_KRETPROBE(inet_csk_accept, struct sock *sk) {
+    return output_tcp_event(ctx, sk, EVENT_TCP_ACCEPT);
+}
+
+SEC("kprobe/tcp_close")
+int BPF_KPROBE(tcp_close, struct sock *sk) {
+    return output_tcp_event(ctx, sk, EVENT_TCP_CLOSE);
+}
+
+// DNS queries, as UDP datagrams sent to port 53.
+
+static __always_inline const void *msg_buffer(struct msghdr *msg) {
+    if (bpf_core_enum_value_exists(enum iter_type, ITER_UBUF) &&
+        bpf_core_field_exists(msg->msg_iter.ubuf)) {
+        __u8 iter_type = BPF_CORE_READ(msg, msg_iter.iter_type);
+        if (iter_type == bpf_core_enum_value(enum iter_type, ITER_UBUF)) {
+            return BPF_CORE_READ(msg, msg_iter.ubuf);
+        }
+    }
+
+    const struct iovec *iov;
+    if (bpf_core_field_exists(msg->msg_iter.__iov)) {
+        iov = BPF_CORE_READ(msg, msg_iter.__iov);
+    } else {
+        iov = BPF_CORE_READ(msg, msg_iter.iov);
+    }
+    return BPF_CORE_READ(iov, iov_base);
+}
+
+static __always_inline int output_dns_event(struct pt_regs *ctx, struct sock *sk,
+                                            struct msghdr *msg, __kernel_size_t len) {
+    union event *event = scratch_event();
+    if (!event) {
+        return 0;
+    }
+
+    struct dns_event *dns = &event->dns;
+    __builtin_memset(dns->daddr, 0, sizeof(dns->daddr));
+
+    // The destination is either given to `sendto`, or the peer of a connected socket.
+    void *name = BPF_CORE_READ(msg, msg_name);
+    if (name) {
+        bpf_probe_read_user(&dns->family, sizeof(dns->family), name);
+        if (dns->family == AF_INET) {
+            struct sockaddr_in addr;
+            bpf_probe_read_user(&addr, sizeof(addr), name);
+            dns->dport = addr.sin_port;
+            __builtin_memcpy(dns->daddr, &addr.sin_addr, sizeof(addr.sin_addr));
+        } else if (dns->family == AF_INET6) {
+            struct sockaddr_in6 addr;
+            bpf_probe_read_user(&addr, sizeof(addr), name);
+            dns->dport = addr.sin6_port;
+            __builtin_memcpy(dns->daddr, addr.sin6_addr, sizeof(addr.sin6_addr));
+        } else {
+            return 0;
+        }
+    } else {
+        dns->family = BPF_CORE_READ(sk, __sk_common.skc_family);
+        dns->dport = BPF_CORE_READ(sk, __sk_common.skc_dport);
+        if (dns->family == AF_INET) {
+            bpf_core_read(dns->daddr, 4, &sk->__sk_common.skc_daddr);
+        } else {
+            bpf_core_read(dns->daddr, 16, &sk->__sk_common.skc_v6_daddr);
+        }
+    }
+    if (dns->dport != bpf_htons(DNS_PORT)) {
+        return 0;
+    }
+
+    fill_header(&dns->header, EVENT_DNS_QUERY);
+    __u32 size = len;
+    if (size > DNS_PAYLOAD_LEN) {
+        size = DNS_PAYLOAD_LEN;
+    }
+    dns->len = size;
+    if (bpf_probe_read_user(dns->payload, size, msg_buffer(msg)) != 0) {
+        return 0;
+    }
+
+    bpf_perf_event_output(ctx, &EVENTS, BPF_F_CURRENT_CPU, dns, sizeof(*dns));
+    return 0;
+}
+
+SEC("kprobe/udp_sendmsg")
+int BPF_KPROBE(udp_sendmsg, struct sock *sk, struct msghdr *msg, __kernel_size_t len) {
+    return output_dns_event(ctx, sk, msg, len);
+}
+
+SEC("kprobe/udpv6_sendmsg")
+int BPF_KPROBE(udpv6_sendmsg, struct sock *sk, struct msghdr *msg, __kernel_size_t len) {
+    return output_dns_event(ctx, sk, msg, len);
+}
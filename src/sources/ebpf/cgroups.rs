@@ -0,0 +1,124 @@
+//! Resolution of the cgroup IDs reported by the eBPF programs into cgroup paths and container IDs.
+//!
+//! On the cgroup v2 hierarchy, the ID of a cgroup is the inode number of its directory, so IDs are
+//! resolved by walking the hierarchy.
+
+use std::{
+    collections::HashMap,
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Unknown IDs trigger walking the hierarchy again at most this often, as new cgroups are created.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Matches the container IDs ending the names of the cgroups of Docker, containerd, CRI-O and
+/// Podman containers, such as `docker-<id>.scope` or `cri-containerd-<id>.scope`.
+static CONTAINER_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|[-/:])([0-9a-f]{64})(?:\.scope)?$").unwrap());
+
+pub struct CgroupResolver {
+    root: PathBuf,
+    paths: HashMap<u64, String>,
+    last_scan: Option<Instant>,
+}
+
+impl CgroupResolver {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            paths: HashMap::new(),
+            last_scan: None,
+        }
+    }
+
+    /// Returns the path of the cgroup with the given ID, relative to the root of the hierarchy.
+    pub fn resolve(&mut self, id: u64) -> Option<&str> {
+        if !self.paths.contains_key(&id)
+            && self
+                .last_scan
+                .map_or(true, |last_scan| last_scan.elapsed() >= RESCAN_INTERVAL)
+        {
+            self.scan();
+        }
+        self.paths.get(&id).map(String::as_str)
+    }
+
+    fn scan(&mut self) {
+        self.last_scan = Some(Instant::now());
+        self.paths.clear();
+        let mut directories = vec![self.root.clone()];
+        while let Some(directory) = directories.pop() {
+            if let Ok(metadata) = fs::metadata(&directory) {
+                self.paths
+                    .insert(metadata.ino(), relative_path(&self.root, &directory));
+            }
+            let entries = match fs::read_dir(&directory) {
+                Ok(entries) => entries,
+                // Cgroups can be removed while walking the hierarchy.
+                Err(_) => continue,
+            };
+            directories.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.file_type().map_or(false, |kind| kind.is_dir()))
+                    .map(|entry| entry.path()),
+            );
+        }
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    format!("/{}", relative.display())
+}
+
+/// Extracts the ID of the container a cgroup belongs to from its path, if any.
+pub fn container_id(path: &str) -> Option<&str> {
+    CONTAINER_ID
+        .captures(path)
+        .and_then(|captures| captures.get(1))
+        .map(|id| id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_cgroup_ids() {
+        let root = tempfile::tempdir().unwrap();
+        let service = root.path().join("system.slice/vector.service");
+        fs::create_dir_all(&service).unwrap();
+
+        let mut resolver = CgroupResolver::new(root.path().to_owned());
+        let id = fs::metadata(&service).unwrap().ino();
+        assert_eq!(resolver.resolve(id), Some("/system.slice/vector.service"));
+        let id = fs::metadata(root.path()).unwrap().ino();
+        assert_eq!(resolver.resolve(id), Some("/"));
+        assert_eq!(resolver.resolve(u64::MAX), None);
+    }
+
+    #[test]
+    fn extracts_container_ids() {
+        let id = "3b1a5e4c1d9f0e2a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c7d6e5f4a";
+        for path in [
+            format!("/system.slice/docker-{}.scope", id),
+            format!(
+                "/kubepods.slice/kubepods-pod1.slice/cri-containerd-{}.scope",
+                id
+            ),
+            format!("/machine.slice/libpod-{}.scope", id),
+            format!("/docker/{}", id),
+            format!("/kubepods/besteffort/pod1/crio-{}", id),
+        ] {
+            assert_eq!(container_id(&path), Some(id));
+        }
+        assert_eq!(container_id("/system.slice/vector.service"), None);
+    }
+}
@@ -0,0 +1,341 @@
+//! Parsing of the events written by the eBPF programs to the perf buffers.
+//!
+//! The layouts of the events are defined by the structures of `bpf/telemetry.bpf.c`, in the byte
+//! order of the host.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use snafu::Snafu;
+use trust_dns_proto::op::Message;
+
+const HEADER_LEN: usize = 64;
+const EXEC_LEN: usize = HEADER_LEN + 256;
+const TCP_LEN: usize = HEADER_LEN + 40;
+const DNS_LEN: usize = HEADER_LEN + 24 + 256;
+
+const AF_INET: u16 = 2;
+const AF_INET6: u16 = 10;
+
+#[derive(Debug, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Event of kind {} is truncated to {} bytes", kind, len))]
+    Truncated { kind: u32, len: usize },
+    #[snafu(display("Unknown event kind {}", kind))]
+    UnknownKind { kind: u32 },
+    #[snafu(display("Unknown address family {}", family))]
+    UnknownFamily { family: u16 },
+    #[snafu(display("Invalid DNS query: {}", source))]
+    InvalidDnsQuery {
+        source: trust_dns_proto::error::ProtoError,
+    },
+}
+
+/// The process an event happened in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    pub tid: u32,
+    pub ppid: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub comm: String,
+    pub cgroup_id: u64,
+    pub mnt_ns: u32,
+    pub pid_ns: u32,
+    pub net_ns: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TcpEventKind {
+    Connect,
+    Accept,
+    Close,
+}
+
+impl TcpEventKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Connect => "tcp_connect",
+            Self::Accept => "tcp_accept",
+            Self::Close => "tcp_close",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DnsQuestion {
+    pub name: String,
+    pub query_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KernelEvent {
+    Exec {
+        process: Process,
+        filename: String,
+    },
+    Tcp {
+        process: Process,
+        kind: TcpEventKind,
+        source: SocketAddr,
+        destination: SocketAddr,
+    },
+    DnsQuery {
+        process: Process,
+        server: SocketAddr,
+        id: u16,
+        questions: Vec<DnsQuestion>,
+    },
+}
+
+impl KernelEvent {
+    pub const fn process(&self) -> &Process {
+        match self {
+            Self::Exec { process, .. }
+            | Self::Tcp { process, .. }
+            | Self::DnsQuery { process, .. } => process,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Exec { .. } => "exec",
+            Self::Tcp { kind, .. } => kind.as_str(),
+            Self::DnsQuery { .. } => "dns_query",
+        }
+    }
+}
+
+pub fn parse(data: &[u8]) -> Result<KernelEvent, ParseError> {
+    let kind = u32_at(data, 0).ok_or(ParseError::Truncated {
+        kind: 0,
+        len: data.len(),
+    })?;
+    let expected_len = match kind {
+        1 => EXEC_LEN,
+        2..=4 => TCP_LEN,
+        5 => DNS_LEN,
+        _ => return Err(ParseError::UnknownKind { kind }),
+    };
+    if data.len() < expected_len {
+        return Err(ParseError::Truncated {
+            kind,
+            len: data.len(),
+        });
+    }
+
+    let process = parse_process(&data[..HEADER_LEN]);
+    let data = &data[HEADER_LEN..expected_len];
+    match kind {
+        1 => Ok(KernelEvent::Exec {
+            process,
+            filename: c_string(data),
+        }),
+        2..=4 => {
+            let family = u16_at(data, 0).expect("length is checked");
+            let source_port = u16::from_be_bytes([data[2], data[3]]);
+            let destination_port = u16::from_be_bytes([data[4], data[5]]);
+            let kind = match kind {
+                2 => TcpEventKind::Connect,
+                3 => TcpEventKind::Accept,
+                _ => TcpEventKind::Close,
+            };
+            Ok(KernelEvent::Tcp {
+                process,
+                kind,
+                source: SocketAddr::new(ip_addr(family, &data[8..24])?, source_port),
+                destination: SocketAddr::new(ip_addr(family, &data[24..40])?, destination_port),
+            })
+        }
+        _ => {
+            let family = u16_at(data, 0).expect("length is checked");
+            let port = u16::from_be_bytes([data[2], data[3]]);
+            let len = (u32_at(data, 4).expect("length is checked") as usize).min(256);
+            let message = Message::from_vec(&data[24..24 + len])
+                .map_err(|source| ParseError::InvalidDnsQuery { source })?;
+            Ok(KernelEvent::DnsQuery {
+                process,
+                server: SocketAddr::new(ip_addr(family, &data[8..24])?, port),
+                id: message.id(),
+                questions: message
+                    .queries()
+                    .iter()
+                    .map(|query| DnsQuestion {
+                        name: query.name().to_string(),
+                        query_type: query.query_type().to_string(),
+                    })
+                    .collect(),
+            })
+        }
+    }
+}
+
+fn parse_process(header: &[u8]) -> Process {
+    let u32_field = |offset| u32_at(header, offset).expect("length is checked");
+    Process {
+        pid: u32_field(4),
+        tid: u32_field(8),
+        ppid: u32_field(12),
+        uid: u32_field(16),
+        gid: u32_field(20),
+        cgroup_id: u64::from_ne_bytes(header[24..32].try_into().expect("length is checked")),
+        mnt_ns: u32_field(32),
+        pid_ns: u32_field(36),
+        net_ns: u32_field(40),
+        comm: c_string(&header[48..64]),
+    }
+}
+
+fn ip_addr(family: u16, address: &[u8]) -> Result<IpAddr, ParseError> {
+    match family {
+        AF_INET => Ok(Ipv4Addr::new(address[0], address[1], address[2], address[3]).into()),
+        AF_INET6 => {
+            let octets: [u8; 16] = address.try_into().expect("length is checked");
+            Ok(Ipv6Addr::from(octets).into())
+        }
+        _ => Err(ParseError::UnknownFamily { family }),
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_ne_bytes(bytes.try_into().expect("length is checked")))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_ne_bytes(bytes.try_into().expect("length is checked")))
+}
+
+/// Reads a NUL-terminated string, which fills the whole buffer if it's truncated.
+fn c_string(data: &[u8]) -> String {
+    let end = data
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use trust_dns_proto::{
+        op::{Message, Query},
+        rr::{Name, RecordType},
+    };
+
+    use super::*;
+
+    fn header(kind: u32) -> Vec<u8> {
+        let mut header = Vec::new();
+        for value in [kind, 1234, 1235, 1, 1000, 1000] {
+            header.extend(value.to_ne_bytes());
+        }
+        header.extend(42u64.to_ne_bytes());
+        for value in [4026531840u32, 4026531836, 4026531992, 0] {
+            header.extend(value.to_ne_bytes());
+        }
+        let mut comm = [0; 16];
+        comm[..4].copy_from_slice(b"curl");
+        header.extend(comm);
+        header
+    }
+
+    fn process() -> Process {
+        Process {
+            pid: 1234,
+            tid: 1235,
+            ppid: 1,
+            uid: 1000,
+            gid: 1000,
+            comm: "curl".to_owned(),
+            cgroup_id: 42,
+            mnt_ns: 4026531840,
+            pid_ns: 4026531836,
+            net_ns: 4026531992,
+        }
+    }
+
+    #[test]
+    fn parses_exec_events() {
+        let mut data = header(1);
+        let mut filename = [0; 256];
+        filename[..13].copy_from_slice(b"/usr/bin/curl");
+        data.extend(filename);
+
+        assert_eq!(
+            parse(&data).unwrap(),
+            KernelEvent::Exec {
+                process: process(),
+                filename: "/usr/bin/curl".to_owned(),
+            }
+        );
+        assert!(matches!(
+            parse(&data[..100]),
+            Err(ParseError::Truncated { kind: 1, len: 100 })
+        ));
+    }
+
+    #[test]
+    fn parses_tcp_events() {
+        let mut data = header(2);
+        data.extend(AF_INET.to_ne_bytes());
+        data.extend(43210u16.to_be_bytes());
+        data.extend(443u16.to_be_bytes());
+        data.extend([0; 2]);
+        data.extend([10, 0, 0, 1]);
+        data.extend([0; 12]);
+        data.extend([192, 0, 2, 1]);
+        data.extend([0; 12]);
+
+        assert_eq!(
+            parse(&data).unwrap(),
+            KernelEvent::Tcp {
+                process: process(),
+                kind: TcpEventKind::Connect,
+                source: "10.0.0.1:43210".parse().unwrap(),
+                destination: "192.0.2.1:443".parse().unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_dns_queries() {
+        let mut query = Message::new();
+        query.set_id(4242);
+        query.add_query(Query::query(
+            Name::from_ascii("vector.dev.").unwrap(),
+            RecordType::AAAA,
+        ));
+        let query = query.to_vec().unwrap();
+
+        let mut data = header(5);
+        data.extend(AF_INET6.to_ne_bytes());
+        data.extend(53u16.to_be_bytes());
+        data.extend((query.len() as u32).to_ne_bytes());
+        data.extend("2001:db8::53".parse::<Ipv6Addr>().unwrap().octets());
+        let mut payload = [0; 256];
+        payload[..query.len()].copy_from_slice(&query);
+        data.extend(payload);
+
+        assert_eq!(
+            parse(&data).unwrap(),
+            KernelEvent::DnsQuery {
+                process: process(),
+                server: "[2001:db8::53]:53".parse().unwrap(),
+                id: 4242,
+                questions: vec![DnsQuestion {
+                    name: "vector.dev.".to_owned(),
+                    query_type: "AAAA".to_owned(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_kinds() {
+        assert!(matches!(
+            parse(&header(9)),
+            Err(ParseError::UnknownKind { kind: 9 })
+        ));
+    }
+}
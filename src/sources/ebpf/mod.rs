@@ -0,0 +1,481 @@
+use std::{io, path::PathBuf};
+
+use aya::{
+    include_bytes_aligned,
+    maps::{
+        perf::{AsyncPerfEventArray, AsyncPerfEventArrayBuffer, PerfBufferError},
+        MapError, MapRefMut,
+    },
+    programs::{KProbe, ProgramError, TracePoint},
+    util::online_cpus,
+    Bpf, BpfError,
+};
+use bytes::BytesMut;
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use snafu::{OptionExt, ResultExt, Snafu};
+use vector_common::btreemap;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::{
+        EbpfEventsLost, EbpfParseError, EbpfReadError, EventsReceived, StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod cgroups;
+mod events;
+
+use self::{
+    cgroups::{container_id, CgroupResolver},
+    events::KernelEvent,
+};
+
+/// The programs, compiled by the build script.
+static PROGRAMS: &[u8] = include_bytes_aligned!(concat!(env!("OUT_DIR"), "/ebpf_telemetry.bpf.o"));
+
+/// The largest event written by the programs, a DNS query.
+const MAX_EVENT_SIZE: usize = 344;
+
+/// The number of events read from a perf buffer at once.
+const READ_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to load the eBPF programs: {}", source))]
+    LoadPrograms { source: BpfError },
+    #[snafu(display("Missing eBPF program {:?}", name))]
+    MissingProgram { name: &'static str },
+    #[snafu(display("Failed to attach the eBPF program {:?}: {}", name, source))]
+    AttachProgram {
+        name: &'static str,
+        source: ProgramError,
+    },
+    #[snafu(display("Failed to open the perf buffers of the eBPF programs: {}", source))]
+    OpenPerfBuffers { source: MapError },
+    #[snafu(display("Failed to list the online CPUs: {}", source))]
+    OnlineCpus { source: io::Error },
+    #[snafu(display("Failed to open the perf buffer of CPU {}: {}", cpu, source))]
+    OpenPerfBuffer { cpu: u32, source: PerfBufferError },
+}
+
+/// The kinds of kernel events to collect.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EbpfEventKind {
+    /// Executions of programs by processes.
+    Exec,
+
+    /// TCP connections established and closed by processes, in both directions.
+    Tcp,
+
+    /// DNS queries sent by processes over UDP.
+    Dns,
+}
+
+/// Configuration for the `ebpf` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EbpfConfig {
+    /// The kinds of kernel events to collect.
+    #[serde(default = "default_events")]
+    events: Vec<EbpfEventKind>,
+
+    /// Whether to emit each kernel event as a log event.
+    #[serde(default = "crate::serde::default_true")]
+    emit_logs: bool,
+
+    /// Whether to count the kernel events in metrics, tagged with the command of the process and
+    /// its container ID.
+    #[serde(default)]
+    emit_metrics: bool,
+
+    /// The mount point of the cgroup v2 hierarchy, used to resolve the cgroups of the processes.
+    #[serde(default = "default_cgroup_root")]
+    cgroup_root: PathBuf,
+
+    /// The number of memory pages of each per-CPU buffer the kernel writes events to.
+    ///
+    /// Events are lost if a buffer fills up before they're read, and it must be a power of two.
+    #[serde(default = "default_per_cpu_buffer_pages")]
+    per_cpu_buffer_pages: usize,
+}
+
+fn default_events() -> Vec<EbpfEventKind> {
+    vec![EbpfEventKind::Exec, EbpfEventKind::Tcp, EbpfEventKind::Dns]
+}
+
+fn default_cgroup_root() -> PathBuf {
+    "/sys/fs/cgroup".into()
+}
+
+const fn default_per_cpu_buffer_pages() -> usize {
+    64
+}
+
+impl Default for EbpfConfig {
+    fn default() -> Self {
+        Self {
+            events: default_events(),
+            emit_logs: true,
+            emit_metrics: false,
+            cgroup_root: default_cgroup_root(),
+            per_cpu_buffer_pages: default_per_cpu_buffer_pages(),
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<EbpfConfig>("ebpf")
+}
+
+impl_generate_config_from_default!(EbpfConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ebpf")]
+impl SourceConfig for EbpfConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let mut bpf = Bpf::load(PROGRAMS).context(LoadProgramsSnafu)?;
+        for kind in &self.events {
+            match kind {
+                EbpfEventKind::Exec => attach_tracepoint(&mut bpf, "sched", "sched_process_exec")?,
+                EbpfEventKind::Tcp => {
+                    for name in ["tcp_connect", "inet_csk_accept", "tcp_close"] {
+                        attach_kprobe(&mut bpf, name)?;
+                    }
+                }
+                EbpfEventKind::Dns => {
+                    for name in ["udp_sendmsg", "udpv6_sendmsg"] {
+                        attach_kprobe(&mut bpf, name)?;
+                    }
+                }
+            }
+        }
+
+        let mut perf_array = bpf
+            .map_mut("EVENTS")
+            .and_then(AsyncPerfEventArray::try_from)
+            .context(OpenPerfBuffersSnafu)?;
+        let mut buffers = Vec::new();
+        for cpu in online_cpus().context(OnlineCpusSnafu)? {
+            let buffer = perf_array
+                .open(cpu, Some(self.per_cpu_buffer_pages))
+                .context(OpenPerfBufferSnafu { cpu })?;
+            buffers.push(Box::pin(read_perf_buffer(cpu, buffer)));
+        }
+
+        let source = EbpfSource {
+            emit_logs: self.emit_logs,
+            emit_metrics: self.emit_metrics,
+            cgroups: CgroupResolver::new(self.cgroup_root.clone()),
+            hostname: crate::get_hostname().ok(),
+        };
+        Ok(Box::pin(source.run(
+            bpf,
+            stream::select_all(buffers),
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        let data_type = match (self.emit_logs, self.emit_metrics) {
+            (true, true) => DataType::Log | DataType::Metric,
+            (false, true) => DataType::Metric,
+            _ => DataType::Log,
+        };
+        vec![Output::default(data_type)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "ebpf"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Attaches the program named after the tracepoint.
+fn attach_tracepoint(bpf: &mut Bpf, category: &str, name: &'static str) -> Result<(), BuildError> {
+    let program: &mut TracePoint = bpf
+        .program_mut(name)
+        .context(MissingProgramSnafu { name })?
+        .try_into()
+        .context(AttachProgramSnafu { name })?;
+    program.load().context(AttachProgramSnafu { name })?;
+    program
+        .attach(category, name)
+        .context(AttachProgramSnafu { name })?;
+    Ok(())
+}
+
+/// Attaches the program named after the kernel function it probes.
+fn attach_kprobe(bpf: &mut Bpf, name: &'static str) -> Result<(), BuildError> {
+    let program: &mut KProbe = bpf
+        .program_mut(name)
+        .context(MissingProgramSnafu { name })?
+        .try_into()
+        .context(AttachProgramSnafu { name })?;
+    program.load().context(AttachProgramSnafu { name })?;
+    program
+        .attach(name, 0)
+        .context(AttachProgramSnafu { name })?;
+    Ok(())
+}
+
+/// Reads the events of a CPU from its perf buffer, along with the number of events lost.
+fn read_perf_buffer(
+    cpu: u32,
+    buffer: AsyncPerfEventArrayBuffer<MapRefMut>,
+) -> impl Stream<Item = (Vec<BytesMut>, usize)> {
+    let buffers = (0..READ_BATCH_SIZE)
+        .map(|_| BytesMut::with_capacity(MAX_EVENT_SIZE))
+        .collect::<Vec<_>>();
+    stream::unfold(Some((buffer, buffers)), move |state| async move {
+        let (mut buffer, mut buffers) = state?;
+        match buffer.read_events(&mut buffers).await {
+            Ok(events) => {
+                let records = buffers[..events.read].to_vec();
+                Some(((records, events.lost), Some((buffer, buffers))))
+            }
+            Err(error) => {
+                emit!(EbpfReadError { error, cpu });
+                None
+            }
+        }
+    })
+}
+
+struct EbpfSource {
+    emit_logs: bool,
+    emit_metrics: bool,
+    cgroups: CgroupResolver,
+    hostname: Option<String>,
+}
+
+impl EbpfSource {
+    async fn run(
+        mut self,
+        // The programs are detached when dropped, so they're kept until the source stops.
+        _bpf: Bpf,
+        buffers: impl Stream<Item = (Vec<BytesMut>, usize)> + Unpin,
+        shutdown: ShutdownSignal,
+        mut out: SourceSender,
+    ) -> Result<(), ()> {
+        let mut buffers = buffers.take_until(shutdown);
+        while let Some((records, lost)) = buffers.next().await {
+            if lost > 0 {
+                emit!(EbpfEventsLost { count: lost });
+            }
+
+            let mut events = Vec::new();
+            for record in records {
+                match events::parse(&record) {
+                    Ok(event) => self.convert(event, &mut events),
+                    Err(error) => emit!(EbpfParseError { error }),
+                }
+            }
+            if events.is_empty() {
+                continue;
+            }
+
+            let count = events.len();
+            emit!(EventsReceived {
+                count,
+                byte_size: events.size_of(),
+            });
+            if let Err(error) = out.send_batch(events).await {
+                emit!(StreamClosedError { error, count });
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
+    fn convert(&mut self, event: KernelEvent, events: &mut Vec<Event>) {
+        let now = Utc::now();
+        let cgroup = self
+            .cgroups
+            .resolve(event.process().cgroup_id)
+            .map(ToOwned::to_owned);
+        let container_id = cgroup.as_deref().and_then(container_id);
+
+        if self.emit_metrics {
+            events.push(self.metric(&event, container_id, now).into());
+        }
+        if self.emit_logs {
+            let mut log = to_log(event, cgroup.as_deref(), container_id);
+            log.insert(log_schema().source_type_key(), "ebpf");
+            log.insert(log_schema().timestamp_key(), now);
+            if let Some(hostname) = &self.hostname {
+                log.insert(log_schema().host_key(), hostname.as_str());
+            }
+            events.push(log.into());
+        }
+    }
+
+    fn metric(
+        &self,
+        event: &KernelEvent,
+        container_id: Option<&str>,
+        now: chrono::DateTime<Utc>,
+    ) -> Metric {
+        let mut tags = MetricTags::new();
+        tags.insert("comm".to_owned(), event.process().comm.clone());
+        if let Some(container_id) = container_id {
+            tags.insert("container_id".to_owned(), container_id.to_owned());
+        }
+        if let Some(hostname) = &self.hostname {
+            tags.insert("host".to_owned(), hostname.clone());
+        }
+        let name = match event {
+            KernelEvent::Exec { .. } => "process_execs_total",
+            KernelEvent::Tcp { kind, .. } => {
+                tags.insert("event".to_owned(), kind.as_str().to_owned());
+                "tcp_connections_total"
+            }
+            KernelEvent::DnsQuery { questions, .. } => {
+                if let Some(question) = questions.first() {
+                    tags.insert("query_type".to_owned(), question.query_type.clone());
+                }
+                "dns_queries_total"
+            }
+        };
+        Metric::new(
+            name,
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_namespace(Some("ebpf"))
+        .with_tags(Some(tags))
+        .with_timestamp(Some(now))
+    }
+}
+
+fn to_log(event: KernelEvent, cgroup: Option<&str>, container_id: Option<&str>) -> LogEvent {
+    let mut log = LogEvent::default();
+    log.insert("event", event.name());
+
+    let process = event.process();
+    log.insert(
+        "process",
+        Value::from(btreemap! {
+            "pid" => process.pid,
+            "tid" => process.tid,
+            "ppid" => process.ppid,
+            "uid" => process.uid,
+            "gid" => process.gid,
+            "comm" => process.comm.clone(),
+        }),
+    );
+    log.insert(
+        "namespaces",
+        Value::from(btreemap! {
+            "mnt" => process.mnt_ns,
+            "pid" => process.pid_ns,
+            "net" => process.net_ns,
+        }),
+    );
+    log.insert("cgroup.id", process.cgroup_id);
+    if let Some(cgroup) = cgroup {
+        log.insert("cgroup.path", cgroup);
+    }
+    if let Some(container_id) = container_id {
+        log.insert("container_id", container_id);
+    }
+
+    match event {
+        KernelEvent::Exec { filename, .. } => {
+            log.insert("process.filename", filename);
+        }
+        KernelEvent::Tcp {
+            source,
+            destination,
+            ..
+        } => {
+            log.insert("source.ip", source.ip().to_string());
+            log.insert("source.port", source.port());
+            log.insert("destination.ip", destination.ip().to_string());
+            log.insert("destination.port", destination.port());
+        }
+        KernelEvent::DnsQuery {
+            server,
+            id,
+            questions,
+            ..
+        } => {
+            log.insert("destination.ip", server.ip().to_string());
+            log.insert("destination.port", server.port());
+            log.insert("dns.id", id);
+            log.insert(
+                "dns.questions",
+                questions
+                    .into_iter()
+                    .map(|question| {
+                        Value::from(btreemap! {
+                            "name" => question.name,
+                            "type" => question.query_type,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{events::DnsQuestion, events::Process, *};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EbpfConfig>();
+    }
+
+    #[test]
+    fn converts_dns_queries_to_logs() {
+        let event = KernelEvent::DnsQuery {
+            process: Process {
+                pid: 1234,
+                tid: 1234,
+                ppid: 1,
+                uid: 0,
+                gid: 0,
+                comm: "curl".to_owned(),
+                cgroup_id: 42,
+                mnt_ns: 4026531840,
+                pid_ns: 4026531836,
+                net_ns: 4026531992,
+            },
+            server: "10.0.0.53:53".parse().unwrap(),
+            id: 4242,
+            questions: vec![DnsQuestion {
+                name: "vector.dev.".to_owned(),
+                query_type: "A".to_owned(),
+            }],
+        };
+
+        let log = to_log(event, Some("/system.slice/docker-abc.scope"), Some("abc"));
+        assert_eq!(log["event"], "dns_query".into());
+        assert_eq!(log["process.comm"], "curl".into());
+        assert_eq!(log["process.pid"], 1234.into());
+        assert_eq!(log["namespaces.net"], 4026531992u32.into());
+        assert_eq!(log["cgroup.path"], "/system.slice/docker-abc.scope".into());
+        assert_eq!(log["container_id"], "abc".into());
+        assert_eq!(log["destination.ip"], "10.0.0.53".into());
+        assert_eq!(log["dns.questions[0].name"], "vector.dev.".into());
+        assert_eq!(log["dns.questions[0].type"], "A".into());
+    }
+}
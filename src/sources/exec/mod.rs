@@ -1,34 +1,35 @@
 use std::{
+    collections::VecDeque,
     io::{Error, ErrorKind},
     path::PathBuf,
     process::ExitStatus,
+    str::FromStr,
 };
 
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use codecs::{
     decoding::{DeserializerConfig, FramingConfig},
     StreamDecodingError,
 };
 use futures::{FutureExt, StreamExt};
+use rand::Rng;
 use smallvec::SmallVec;
 use snafu::Snafu;
 use tokio::{
     io::{AsyncRead, BufReader},
     process::Command,
     sync::mpsc::{channel, Sender},
-    time::{self, sleep, Duration, Instant},
+    time::{self, sleep, sleep_until, Duration, Instant},
 };
-use tokio_stream::wrappers::IntervalStream;
-use tokio_util::codec::FramedRead;
 use vector_config::configurable_component;
 use vector_core::ByteSizeOf;
 
 use crate::{
     async_read::VecAsyncReadExt,
-    codecs::{Decoder, DecodingConfig},
-    config::{log_schema, Output, SourceConfig, SourceContext, SourceDescription},
-    event::Event,
+    codecs::{DeadlineFramedRead, Decoder, DecodingConfig},
+    config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
     internal_events::{
         BytesReceived, ExecCommandExecuted, ExecEventsReceived, ExecFailedError, ExecTimeoutError,
         StreamClosedError,
@@ -69,6 +70,17 @@ pub struct ExecConfig {
     #[serde(default = "default_maximum_buffer_size")]
     pub maximum_buffer_size_bytes: usize,
 
+    /// The amount of time, in seconds, after which a running command is killed.
+    ///
+    /// For scheduled commands, this defaults to the time until the next run.
+    pub kill_after_secs: Option<u64>,
+
+    /// Whether or not to generate an event when the command exits.
+    ///
+    /// The event holds the exit code of the command, or the signal which terminated it, along with the last lines
+    /// written to stderr, if `include_stderr` is enabled.
+    pub include_exit_event: bool,
+
     #[configurable(derived)]
     framing: Option<FramingConfig>,
 
@@ -99,6 +111,18 @@ pub struct ScheduledConfig {
     /// If the command takes longer than `exec_interval_secs` to run, it will be killed.
     #[serde(default = "default_exec_interval_secs")]
     exec_interval_secs: u64,
+
+    /// A cron expression, with a leading seconds field, scheduling the command runs instead of `exec_interval_secs`.
+    ///
+    /// The expression is evaluated in UTC.
+    #[serde(default)]
+    cron: Option<String>,
+
+    /// The maximum amount of time, in seconds, each run is randomly delayed by.
+    ///
+    /// This spreads the runs of many Vector instances sharing the same schedule.
+    #[serde(default)]
+    jitter_secs: u64,
 }
 
 /// Configuration options for streaming commands.
@@ -113,6 +137,13 @@ pub struct StreamingConfig {
     /// The amount of time, in seconds, that Vector will wait before rerunning a streaming command that exited.
     #[serde(default = "default_respawn_interval_secs")]
     respawn_interval_secs: u64,
+
+    /// The maximum amount of time, in seconds, that Vector will wait before rerunning a streaming command that exited.
+    ///
+    /// If set, the wait doubles each time the command exits, starting from `respawn_interval_secs`, until the
+    /// command runs for at least this amount of time.
+    #[serde(default)]
+    respawn_backoff_max_secs: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Snafu)]
@@ -121,6 +152,45 @@ pub enum ExecConfigError {
     CommandEmpty,
     #[snafu(display("The maximum buffer size must be greater than zero"))]
     ZeroBuffer,
+    #[snafu(display("Invalid cron expression: {}", message))]
+    InvalidCron { message: String },
+}
+
+/// When scheduled commands run.
+enum Schedule {
+    Interval {
+        interval: Duration,
+        next: Instant,
+    },
+    Cron {
+        schedule: cron::Schedule,
+        last: DateTime<Utc>,
+    },
+}
+
+impl Schedule {
+    /// Returns when the command runs next, along with the time until the following run, if any.
+    fn next_run(&mut self) -> Option<(Instant, Option<Duration>)> {
+        match self {
+            Schedule::Interval { interval, next } => {
+                // Runs missed while the previous one was still going are skipped.
+                let run = (*next).max(Instant::now());
+                *next = run + *interval;
+                Some((run, Some(*interval)))
+            }
+            Schedule::Cron { schedule, last } => {
+                let now = Utc::now();
+                let mut upcoming = schedule.after(&now.max(*last));
+                let run = upcoming.next()?;
+                let following = upcoming
+                    .next()
+                    .and_then(|following| (following - run).to_std().ok());
+                *last = run;
+                let delay = (run - now).to_std().unwrap_or_default();
+                Some((Instant::now() + delay, following))
+            }
+        }
+    }
 }
 
 impl Default for ExecConfig {
@@ -129,12 +199,16 @@ impl Default for ExecConfig {
             mode: Mode::Scheduled,
             scheduled: Some(ScheduledConfig {
                 exec_interval_secs: default_exec_interval_secs(),
+                cron: None,
+                jitter_secs: 0,
             }),
             streaming: None,
             command: vec!["echo".to_owned(), "Hello World!".to_owned()],
             working_directory: None,
             include_stderr: default_include_stderr(),
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
+            kill_after_secs: None,
+            include_exit_event: false,
             framing: None,
             decoding: default_decoding(),
         }
@@ -172,6 +246,14 @@ const STDERR: &str = "stderr";
 const STREAM_KEY: &str = "stream";
 const PID_KEY: &str = "pid";
 const COMMAND_KEY: &str = "command";
+const EXIT_CODE_KEY: &str = "exit_code";
+const SIGNAL_KEY: &str = "signal";
+
+/// The number of lines written to stderr kept for the exit event.
+const STDERR_LINES: usize = 10;
+
+/// The amount of time a command is given to exit once its output is closed.
+const EXIT_GRACE_PERIOD: Duration = Duration::from_secs(1);
 
 inventory::submit! {
     SourceDescription::new::<ExecConfig>("exec")
@@ -186,7 +268,28 @@ impl ExecConfig {
         } else if self.maximum_buffer_size_bytes == 0 {
             Err(ExecConfigError::ZeroBuffer)
         } else {
-            Ok(())
+            self.schedule().map(|_| ())
+        }
+    }
+
+    fn schedule(&self) -> Result<Schedule, ExecConfigError> {
+        match self
+            .scheduled
+            .as_ref()
+            .and_then(|config| config.cron.as_ref())
+        {
+            Some(expression) => cron::Schedule::from_str(expression)
+                .map(|schedule| Schedule::Cron {
+                    schedule,
+                    last: Utc::now(),
+                })
+                .map_err(|error| ExecConfigError::InvalidCron {
+                    message: error.to_string(),
+                }),
+            None => Ok(Schedule::Interval {
+                interval: Duration::from_secs(self.exec_interval_secs_or_default()),
+                next: Instant::now(),
+            }),
         }
     }
 
@@ -214,6 +317,24 @@ impl ExecConfig {
             Some(config) => config.respawn_interval_secs,
         }
     }
+
+    const fn respawn_backoff_max_secs(&self) -> Option<u64> {
+        match &self.streaming {
+            None => None,
+            Some(config) => config.respawn_backoff_max_secs,
+        }
+    }
+
+    const fn jitter_secs(&self) -> u64 {
+        match &self.scheduled {
+            None => 0,
+            Some(config) => config.jitter_secs,
+        }
+    }
+
+    fn kill_after(&self) -> Option<Duration> {
+        self.kill_after_secs.map(Duration::from_secs)
+    }
 }
 
 #[async_trait::async_trait]
@@ -227,16 +348,18 @@ impl SourceConfig for ExecConfig {
             .framing
             .clone()
             .unwrap_or_else(|| self.decoding.default_stream_framing());
-        let decoder = DecodingConfig::new(framing, self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(framing, self.decoding.clone()).build()?;
 
         match &self.mode {
             Mode::Scheduled => {
-                let exec_interval_secs = self.exec_interval_secs_or_default();
+                let schedule = self.schedule()?;
+                let jitter = Duration::from_secs(self.jitter_secs());
 
                 Ok(Box::pin(run_scheduled(
                     self.clone(),
                     hostname,
-                    exec_interval_secs,
+                    schedule,
+                    jitter,
                     decoder,
                     cx.shutdown,
                     cx.out,
@@ -245,12 +368,14 @@ impl SourceConfig for ExecConfig {
             Mode::Streaming => {
                 let respawn_on_exit = self.respawn_on_exit_or_default();
                 let respawn_interval_secs = self.respawn_interval_secs_or_default();
+                let respawn_backoff_max_secs = self.respawn_backoff_max_secs();
 
                 Ok(Box::pin(run_streaming(
                     self.clone(),
                     hostname,
                     respawn_on_exit,
                     respawn_interval_secs,
+                    respawn_backoff_max_secs,
                     decoder,
                     cx.shutdown,
                     cx.out,
@@ -260,7 +385,11 @@ impl SourceConfig for ExecConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(self.decoding.output_type())]
+        let mut output_type = self.decoding.output_type();
+        if self.include_exit_event {
+            output_type |= DataType::Log;
+        }
+        vec![Output::default(output_type)]
     }
 
     fn source_type(&self) -> &'static str {
@@ -275,47 +404,37 @@ impl SourceConfig for ExecConfig {
 async fn run_scheduled(
     config: ExecConfig,
     hostname: Option<String>,
-    exec_interval_secs: u64,
+    mut schedule: Schedule,
+    jitter: Duration,
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> Result<(), ()> {
     debug!("Starting scheduled exec runs.");
-    let schedule = Duration::from_secs(exec_interval_secs);
 
-    let mut interval = IntervalStream::new(time::interval(schedule)).take_until(shutdown.clone());
+    while let Some((run, following)) = schedule.next_run() {
+        let delay = if jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            rand::thread_rng().gen_range(Duration::ZERO..=jitter)
+        };
 
-    while interval.next().await.is_some() {
-        // Wait for our task to finish, wrapping it in a timeout
-        let timeout = tokio::time::timeout(
-            schedule,
-            run_command(
-                config.clone(),
-                hostname.clone(),
-                decoder.clone(),
-                shutdown.clone(),
-                out.clone(),
-            ),
+        tokio::select! {
+            _ = shutdown.clone() => break,
+            _ = sleep_until(run + delay) => {}
+        }
+
+        // Unless configured otherwise, the command is killed when the next one
+        // is due.
+        run_command_with_timeout(
+            &config,
+            hostname.clone(),
+            decoder.clone(),
+            shutdown.clone(),
+            out.clone(),
+            config.kill_after().or(following),
         )
         .await;
-
-        match timeout {
-            Ok(output) => {
-                if let Err(command_error) = output {
-                    emit!(ExecFailedError {
-                        command: config.command_line().as_str(),
-                        error: command_error,
-                    });
-                }
-            }
-            Err(error) => {
-                emit!(ExecTimeoutError {
-                    command: config.command_line().as_str(),
-                    elapsed_seconds: schedule.as_secs(),
-                    error,
-                });
-            }
-        }
     }
 
     debug!("Finished scheduled exec runs.");
@@ -327,32 +446,30 @@ async fn run_streaming(
     hostname: Option<String>,
     respawn_on_exit: bool,
     respawn_interval_secs: u64,
+    respawn_backoff_max_secs: Option<u64>,
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> Result<(), ()> {
     if respawn_on_exit {
-        let duration = Duration::from_secs(respawn_interval_secs);
+        let interval = Duration::from_secs(respawn_interval_secs);
+        let backoff_max = respawn_backoff_max_secs.map(Duration::from_secs);
+        let mut duration = interval;
 
         // Continue to loop while not shutdown
         loop {
+            let start = Instant::now();
+
             tokio::select! {
                 _ = shutdown.clone() => break, // will break early if a shutdown is started
-                output = run_command(
-                    config.clone(),
+                _ = run_command_with_timeout(
+                    &config,
                     hostname.clone(),
                     decoder.clone(),
                     shutdown.clone(),
-                    out.clone()
-                ) => {
-                    // handle command finished
-                    if let Err(command_error) = output {
-                        emit!(ExecFailedError {
-                            command: config.command_line().as_str(),
-                            error: command_error,
-                        });
-                    }
-                }
+                    out.clone(),
+                    config.kill_after(),
+                ) => {}
             }
 
             let mut poll_shutdown = shutdown.clone();
@@ -360,25 +477,71 @@ async fn run_streaming(
                 warn!("Streaming process ended before shutdown.");
             }
 
+            // The backoff is reset once the command runs for long enough.
+            if let Some(backoff_max) = backoff_max {
+                if start.elapsed() >= backoff_max {
+                    duration = interval;
+                }
+            }
+
             tokio::select! {
                 _ = &mut poll_shutdown => break, // will break early if a shutdown is started
                 _ = sleep(duration) => debug!("Restarting streaming process."),
             }
-        }
-    } else {
-        let output = run_command(config.clone(), hostname, decoder, shutdown, out).await;
 
-        if let Err(command_error) = output {
-            emit!(ExecFailedError {
-                command: config.command_line().as_str(),
-                error: command_error,
-            });
+            if let Some(backoff_max) = backoff_max {
+                duration = (duration * 2).min(backoff_max);
+            }
         }
+    } else {
+        run_command_with_timeout(
+            &config,
+            hostname,
+            decoder,
+            shutdown,
+            out,
+            config.kill_after(),
+        )
+        .await;
     }
 
     Ok(())
 }
 
+async fn run_command_with_timeout(
+    config: &ExecConfig,
+    hostname: Option<String>,
+    decoder: Decoder,
+    shutdown: ShutdownSignal,
+    out: SourceSender,
+    timeout: Option<Duration>,
+) {
+    let command = run_command(config.clone(), hostname, decoder, shutdown, out);
+
+    let output = match timeout {
+        // The command is killed when its future is dropped on timeout.
+        Some(timeout) => match time::timeout(timeout, command).await {
+            Ok(output) => output,
+            Err(error) => {
+                emit!(ExecTimeoutError {
+                    command: config.command_line().as_str(),
+                    elapsed_seconds: timeout.as_secs(),
+                    error,
+                });
+                return;
+            }
+        },
+        None => command.await,
+    };
+
+    if let Err(command_error) = output {
+        emit!(ExecFailedError {
+            command: config.command_line().as_str(),
+            error: command_error,
+        });
+    }
+}
+
 async fn run_command(
     config: ExecConfig,
     hostname: Option<String>,
@@ -424,6 +587,8 @@ async fn run_command(
 
     spawn_reader_thread(stdout_reader, decoder.clone(), STDOUT, sender);
 
+    let mut stderr_lines = VecDeque::with_capacity(STDERR_LINES);
+
     while let Some(((mut events, byte_size), stream)) = receiver.recv().await {
         emit!(BytesReceived {
             byte_size,
@@ -439,6 +604,18 @@ async fn run_command(
 
         for event in &mut events {
             handle_event(&config, &hostname, &Some(stream.to_string()), pid, event);
+
+            if config.include_exit_event && stream == STDERR {
+                if let Some(message) = event
+                    .maybe_as_log()
+                    .and_then(|log| log.get(log_schema().message_key()))
+                {
+                    if stderr_lines.len() == STDERR_LINES {
+                        stderr_lines.pop_front();
+                    }
+                    stderr_lines.push_back(message.clone());
+                }
+            }
         }
         if let Err(error) = out.send_batch(events).await {
             emit!(StreamClosedError { count, error });
@@ -446,28 +623,35 @@ async fn run_command(
         }
     }
 
-    let elapsed = start.elapsed();
-
-    let result = match child.try_wait() {
-        Ok(Some(exit_status)) => {
-            handle_exit_status(&config, exit_status.code(), elapsed);
-            Ok(Some(exit_status))
-        }
-        Ok(None) => {
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
-        }
-        Err(error) => {
+    // The output is closed when the command exits, which may take a moment
+    // to be reported.
+    let exit_status = match time::timeout(EXIT_GRACE_PERIOD, child.wait()).await {
+        Ok(Ok(exit_status)) => Some(exit_status),
+        Ok(Err(error)) => {
             error!(message = "Unable to obtain exit status.", %error);
-
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
+            None
         }
+        Err(_) => None,
     };
 
+    let elapsed = start.elapsed();
+    handle_exit_status(
+        &config,
+        exit_status.and_then(|exit_status| exit_status.code()),
+        elapsed,
+    );
+
+    if let Some(exit_status) = exit_status.filter(|_| config.include_exit_event) {
+        let mut event = create_exit_event(exit_status, stderr_lines);
+        handle_event(&config, &hostname, &None, pid, &mut event);
+        if let Err(error) = out.send_event(event).await {
+            emit!(StreamClosedError { count: 1, error });
+        }
+    }
+
     debug!("Finished command run.");
 
-    result
+    Ok(exit_status)
 }
 
 fn handle_exit_status(config: &ExecConfig, exit_status: Option<i32>, exec_duration: Duration) {
@@ -478,6 +662,36 @@ fn handle_exit_status(config: &ExecConfig, exit_status: Option<i32>, exec_durati
     });
 }
 
+fn create_exit_event(exit_status: ExitStatus, stderr_lines: VecDeque<Value>) -> Event {
+    let mut log = LogEvent::from(exit_status.to_string());
+
+    if let Some(code) = exit_status.code() {
+        log.insert(path!(EXIT_CODE_KEY), code);
+    }
+
+    if let Some(signal) = exit_signal(&exit_status) {
+        log.insert(path!(SIGNAL_KEY), signal);
+    }
+
+    if !stderr_lines.is_empty() {
+        log.insert(path!(STDERR), Vec::from(stderr_lines));
+    }
+
+    log.into()
+}
+
+#[cfg(unix)]
+fn exit_signal(exit_status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    exit_status.signal()
+}
+
+#[cfg(not(unix))]
+const fn exit_signal(_: &ExitStatus) -> Option<i32> {
+    None
+}
+
 fn build_command(config: &ExecConfig) -> Command {
     let command = &config.command[0];
 
@@ -554,7 +768,7 @@ fn spawn_reader_thread<R: 'static + AsyncRead + Unpin + std::marker::Send>(
     let _ = Box::pin(tokio::spawn(async move {
         debug!("Start capturing {} command output.", origin);
 
-        let mut stream = FramedRead::new(reader, decoder);
+        let mut stream = DeadlineFramedRead::new(reader, decoder);
         while let Some(result) = stream.next().await {
             match result {
                 Ok(next) => {
@@ -642,11 +856,14 @@ mod tests {
             streaming: Some(StreamingConfig {
                 respawn_on_exit: default_respawn_on_exit(),
                 respawn_interval_secs: default_respawn_interval_secs(),
+                respawn_backoff_max_secs: None,
             }),
             command: vec!["./runner".to_owned(), "arg1".to_owned(), "arg2".to_owned()],
             working_directory: Some(PathBuf::from("/tmp")),
             include_stderr: default_include_stderr(),
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
+            kill_after_secs: None,
+            include_exit_event: false,
             framing: None,
             decoding: default_decoding(),
         };
@@ -743,6 +960,74 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_run_command_exit_event() {
+        let config = ExecConfig {
+            command: vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo first >&2; echo second >&2; exit 3".to_owned(),
+            ],
+            include_exit_event: true,
+            ..Default::default()
+        };
+        let (tx, rx) = SourceSender::new_test();
+
+        let exit_status = run_command(config, None, Default::default(), ShutdownSignal::noop(), tx)
+            .await
+            .expect("command error");
+        assert_eq!(3_i32, exit_status.unwrap().code().unwrap());
+
+        let events = rx.collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 3);
+        let log = events[2].as_log();
+        assert_eq!(log[EXIT_CODE_KEY], 3.into());
+        assert!(log.get(SIGNAL_KEY).is_none());
+        assert_eq!(
+            log[STDERR],
+            vec![Bytes::from("first"), Bytes::from("second")].into()
+        );
+        assert!(log.get(PID_KEY).is_some());
+        assert!(log.get(STREAM_KEY).is_none());
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        let config = ExecConfig {
+            scheduled: Some(ScheduledConfig {
+                exec_interval_secs: default_exec_interval_secs(),
+                cron: Some("*/2 * * * * *".to_owned()),
+                jitter_secs: 0,
+            }),
+            ..Default::default()
+        };
+
+        let mut schedule = config.schedule().unwrap();
+        let (first, following) = schedule.next_run().unwrap();
+        let (second, _) = schedule.next_run().unwrap();
+        assert_eq!(following, Some(Duration::from_secs(2)));
+        assert!(first <= Instant::now() + Duration::from_secs(2));
+        assert!(second > first);
+    }
+
+    #[test]
+    fn test_invalid_cron_schedule() {
+        let config = ExecConfig {
+            scheduled: Some(ScheduledConfig {
+                exec_interval_secs: default_exec_interval_secs(),
+                cron: Some("every minute".to_owned()),
+                jitter_secs: 0,
+            }),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate(),
+            Err(ExecConfigError::InvalidCron { .. })
+        ));
+    }
+
     fn standard_scheduled_test_config() -> ExecConfig {
         Default::default()
     }
@@ -754,11 +1039,14 @@ mod tests {
             streaming: Some(StreamingConfig {
                 respawn_on_exit: default_respawn_on_exit(),
                 respawn_interval_secs: default_respawn_interval_secs(),
+                respawn_backoff_max_secs: None,
             }),
             command: vec!["yes".to_owned()],
             working_directory: None,
             include_stderr: default_include_stderr(),
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
+            kill_after_secs: None,
+            include_exit_event: false,
             framing: None,
             decoding: default_decoding(),
         }
@@ -1,12 +1,18 @@
 use std::{
-    collections::HashSet, convert::TryInto, path::PathBuf, sync::Arc, sync::Mutex, time::Duration,
+    collections::HashSet,
+    convert::TryInto,
+    path::{Path, PathBuf},
+    sync::Arc,
+    sync::Mutex,
+    time::Duration,
 };
 
 use bytes::Bytes;
 use chrono::Utc;
 use file_source::{
     paths_provider::glob::{Glob, MatchOptions},
-    Checkpointer, FileFingerprint, FileServer, FingerprintStrategy, Fingerprinter, Line, ReadFrom,
+    Checkpointer, CheckpointsStore, FileFingerprint, FileServer, FingerprintStrategy,
+    Fingerprinter, Line, ReadFrom, CHECKPOINT_FILE_NAME,
 };
 use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
 use regex::bytes::Regex;
@@ -16,7 +22,7 @@ use tracing::{Instrument, Span};
 use vector_common::finalizer::OrderedFinalizer;
 use vector_config::configurable_component;
 
-use super::util::{EncodingConfig, MultilineConfig};
+use super::util::{CheckpointStore, CheckpointStoreConfig, EncodingConfig, MultilineConfig};
 use crate::{
     config::{
         log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
@@ -121,12 +127,34 @@ pub struct FileConfig {
     /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
     pub data_dir: Option<PathBuf>,
 
+    /// Where to persist file checkpoint positions.
+    ///
+    /// By default, they're persisted in a file of `data_dir`. With another store, that file is restored from the
+    /// store when it doesn't exist yet, such as on a new host, and the checkpoints are written through to the store
+    /// whenever they are persisted.
+    pub checkpoint_store: CheckpointStoreConfig,
+
     /// Delay between file discovery calls, in milliseconds.
     ///
     /// This controls the interval at which Vector searches for files. Higher value result in greater chances of some short living files being missed between searches, but lower value increases the performance impact of file discovery.
     #[serde(alias = "glob_minimum_cooldown")]
     pub glob_minimum_cooldown_ms: u64,
 
+    /// Whether to read files when their changes are notified by the operating system, rather than polling them.
+    ///
+    /// This relies on inotify on Linux, FSEvents on macOS, and `ReadDirectoryChangesW` on Windows, watching the
+    /// directories holding the files. With many files, this reduces CPU usage and the delay before new lines are read.
+    /// Files are still polled along with the search for new files, every `glob_minimum_cooldown_ms`, which also covers
+    /// file systems not supporting notifications, such as network file systems.
+    pub use_notifications: bool,
+
+    /// Whether to read files through an io_uring, on Linux.
+    ///
+    /// The files to read, and to fingerprint, are read in batches submitted at once, into buffers registered with the
+    /// kernel, which reduces the system calls made when tailing many files. If io_uring is not supported by the
+    /// kernel, or can't be set up, such as when the limit of locked memory is too low, files are read as usual.
+    pub use_io_uring: bool,
+
     #[configurable(derived)]
     #[serde(alias = "fingerprinting")]
     fingerprint: FingerprintConfig,
@@ -292,7 +320,10 @@ impl Default for FileConfig {
             ignore_not_found: false,
             host_key: None,
             data_dir: None,
+            checkpoint_store: CheckpointStoreConfig::default(),
             glob_minimum_cooldown_ms: 1000, // millis
+            use_notifications: false,
+            use_io_uring: false,
             message_start_indicator: None,
             multi_line_timeout: 1000, // millis
             multiline: None,
@@ -320,10 +351,22 @@ impl SourceConfig for FileConfig {
         // operate within the same given data_dir (e.g. the global one)
         // without the file servers' checkpointers interfering with each
         // other
-        let data_dir = cx
-            .globals
+        let data_dir = cx.globals.resolve_and_make_data_subdir(
             // source are only global, name can be used for subdir
-            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+            self.checkpoint_store.data_dir(self.data_dir.as_ref()),
+            cx.key.id(),
+        )?;
+
+        let checkpoint_store = if self.checkpoint_store.is_local() {
+            None
+        } else {
+            let store = self
+                .checkpoint_store
+                .build(&cx, self.data_dir.as_ref(), CHECKPOINT_FILE_NAME)
+                .await?;
+            restore_checkpoints(&store, &data_dir.join(CHECKPOINT_FILE_NAME)).await?;
+            Some(store)
+        };
 
         // Clippy rule, because async_trait?
         #[allow(clippy::suspicious_else_formatting)]
@@ -343,6 +386,7 @@ impl SourceConfig for FileConfig {
         Ok(file_source(
             self,
             data_dir,
+            checkpoint_store,
             cx.shutdown,
             cx.out,
             acknowledgements,
@@ -365,6 +409,7 @@ impl SourceConfig for FileConfig {
 pub fn file_source(
     config: &FileConfig,
     data_dir: PathBuf,
+    checkpoint_store: Option<CheckpointStore>,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
     acknowledgements: bool,
@@ -396,7 +441,13 @@ pub fn file_source(
         None => Bytes::from(config.line_delimiter.clone()),
     };
 
-    let checkpointer = Checkpointer::new(&data_dir);
+    let mut checkpointer = Checkpointer::new(&data_dir);
+    if let Some(store) = checkpoint_store {
+        checkpointer = checkpointer.with_store(Box::new(WriteThroughStore {
+            store,
+            handle: tokio::runtime::Handle::current(),
+        }));
+    }
     let file_server = FileServer {
         paths_provider,
         max_read_bytes: config.max_read_bytes,
@@ -414,6 +465,8 @@ pub fn file_source(
         },
         oldest_first: config.oldest_first,
         remove_after: config.remove_after_secs.map(Duration::from_secs),
+        use_notifications: config.use_notifications,
+        use_io_uring: config.use_io_uring,
         emitter: FileSourceInternalEventsEmitter,
         handle: tokio::runtime::Handle::current(),
     };
@@ -569,6 +622,44 @@ pub fn file_source(
     })
 }
 
+/// Restores the checkpoints file from the checkpoint store, unless it already exists, such as when
+/// the source runs on a new host.
+async fn restore_checkpoints(store: &CheckpointStore, path: &Path) -> crate::Result<()> {
+    if tokio::fs::metadata(path).await.is_ok() {
+        return Ok(());
+    }
+    // Without the checkpoints, every file would be read again, so it is better not to start.
+    let data = store.load().await.map_err(|error| {
+        format!(
+            "could not restore checkpoints from {}: {}",
+            store.location(),
+            error
+        )
+    })?;
+    if let Some(data) = data {
+        info!(
+            message = "Restored checkpoints from the checkpoint store.",
+            location = %store.location(),
+        );
+        tokio::fs::write(path, data).await?;
+    }
+    Ok(())
+}
+
+/// Writes the checkpoints of the file server through to the checkpoint store.
+struct WriteThroughStore {
+    store: CheckpointStore,
+    handle: tokio::runtime::Handle,
+}
+
+impl CheckpointsStore for WriteThroughStore {
+    fn save(&self, data: Vec<u8>) -> std::io::Result<()> {
+        self.handle
+            .block_on(self.store.save(data))
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error))
+    }
+}
+
 /// Emit deprecation warning if the old option is used, and take it into account when determining
 /// defaults. Any of the newer options will override it when set directly.
 fn reconcile_position_options(
@@ -688,6 +779,33 @@ mod tests {
         sleep(Duration::from_millis(500)).await;
     }
 
+    #[tokio::test]
+    async fn restores_and_writes_through_checkpoints() {
+        let dir = tempdir().unwrap();
+        let store = CheckpointStore::file(dir.path().join("store.json"));
+        let path = dir.path().join(CHECKPOINT_FILE_NAME);
+
+        restore_checkpoints(&store, &path).await.unwrap();
+        assert!(!path.exists());
+
+        let write_through = WriteThroughStore {
+            store: store.clone(),
+            handle: tokio::runtime::Handle::current(),
+        };
+        spawn_blocking(move || write_through.save(b"checkpoints".to_vec()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(b"checkpoints".to_vec()));
+
+        // The checkpoints file is restored on a new host, but not overwritten otherwise.
+        restore_checkpoints(&store, &path).await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"checkpoints");
+        store.save(b"other".to_vec()).await.unwrap();
+        restore_checkpoints(&store, &path).await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"checkpoints");
+    }
+
     #[test]
     fn parse_config() {
         let config: FileConfig = toml::from_str(
@@ -847,6 +965,83 @@ mod tests {
         assert_eq!(goodbye_i, n);
     }
 
+    #[tokio::test]
+    async fn file_happy_path_with_notifications() {
+        let n = 5;
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            use_notifications: true,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+
+        let received = run_file_source(&config, false, NoAcks, async {
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The file must be observed at its original length before writing to it
+
+            for i in 0..n {
+                writeln!(&mut file, "hello {}", i).unwrap();
+            }
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let lines = received
+            .into_iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            (0..n).map(|i| format!("hello {}", i)).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn file_happy_path_with_io_uring() {
+        let n = 5;
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            use_io_uring: true,
+            ..test_default_file_config(&dir)
+        };
+
+        let path1 = dir.path().join("file1");
+        let path2 = dir.path().join("file2");
+
+        let received = run_file_source(&config, false, NoAcks, async {
+            let mut file1 = File::create(&path1).unwrap();
+            let mut file2 = File::create(&path2).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at their original lengths before writing to them
+
+            for i in 0..n {
+                writeln!(&mut file1, "hello {}", i).unwrap();
+                writeln!(&mut file2, "goodbye {}", i).unwrap();
+            }
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let mut lines = received
+            .into_iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        lines.sort();
+        let mut expected = (0..n)
+            .flat_map(|i| vec![format!("goodbye {}", i), format!("hello {}", i)])
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(lines, expected);
+    }
+
     // https://github.com/vectordotdev/vector/issues/8363
     #[tokio::test]
     async fn file_read_empty_lines() {
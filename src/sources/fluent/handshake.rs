@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rmpv::Value;
+use sha2::{Digest, Sha512};
+use vector_config::configurable_component;
+
+use super::message::FluentPing;
+
+/// Authentication of the clients with the shared-key handshake of the forward protocol.
+///
+/// https://github.com/fluent/fluentd/wiki/Forward-Protocol-Specification-v1#handshake-messages
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct FluentSecurityConfig {
+    /// The hostname the source identifies itself with to the clients.
+    ///
+    /// Defaults to the hostname of the machine.
+    self_hostname: Option<String>,
+
+    /// The key shared with the clients, which they have to prove they know to connect.
+    shared_key: String,
+
+    /// The users the clients can authenticate as.
+    ///
+    /// If empty, the clients are only authenticated with the shared key.
+    #[serde(default)]
+    users: Vec<FluentUserConfig>,
+}
+
+/// A user the clients can authenticate as.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct FluentUserConfig {
+    /// The name of the user.
+    username: String,
+
+    /// The password of the user.
+    password: String,
+}
+
+impl FluentSecurityConfig {
+    pub(super) fn build(&self) -> crate::Result<Arc<FluentSecurity>> {
+        let self_hostname = match &self.self_hostname {
+            Some(self_hostname) => self_hostname.clone(),
+            None => crate::get_hostname()?,
+        };
+        Ok(Arc::new(FluentSecurity {
+            self_hostname,
+            shared_key: self.shared_key.clone(),
+            users: self.users.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct FluentSecurity {
+    self_hostname: String,
+    shared_key: String,
+    users: Vec<FluentUserConfig>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum HandshakeState {
+    Pending,
+    Authenticated,
+    Failed,
+}
+
+/// The server side of the handshake of a single connection.
+///
+/// The server sends a HELO with a nonce as soon as the client connects, the client replies with a
+/// PING proving it knows the shared key, and the password of a user if asked for, and the server
+/// answers with a PONG telling whether the client is authenticated.
+#[derive(Debug)]
+pub(super) struct Handshake {
+    security: Arc<FluentSecurity>,
+    nonce: [u8; 16],
+    auth_salt: [u8; 16],
+    state: HandshakeState,
+}
+
+impl Handshake {
+    pub(super) fn new(security: Arc<FluentSecurity>) -> Self {
+        Self {
+            security,
+            nonce: rand::random(),
+            auth_salt: rand::random(),
+            state: HandshakeState::Pending,
+        }
+    }
+
+    pub(super) fn is_authenticated(&self) -> bool {
+        self.state == HandshakeState::Authenticated
+    }
+
+    pub(super) fn is_pending(&self) -> bool {
+        self.state == HandshakeState::Pending
+    }
+
+    /// Builds the HELO message sent to the client when it connects.
+    pub(super) fn helo(&self) -> Bytes {
+        // The salt of the password digests is only sent if the clients have to authenticate as a
+        // user.
+        let auth_salt = if self.security.users.is_empty() {
+            Vec::new()
+        } else {
+            self.auth_salt.to_vec()
+        };
+        encode(Value::Array(vec![
+            "HELO".into(),
+            Value::Map(vec![
+                ("nonce".into(), Value::Binary(self.nonce.to_vec())),
+                ("auth".into(), Value::Binary(auth_salt)),
+                ("keepalive".into(), true.into()),
+            ]),
+        ]))
+    }
+
+    /// Authenticates the client with its PING, and builds the PONG message answering it.
+    pub(super) fn authenticate(&mut self, ping: &FluentPing) -> Bytes {
+        let result = self.check(ping);
+        self.state = match result {
+            Ok(()) => HandshakeState::Authenticated,
+            Err(_) => HandshakeState::Failed,
+        };
+
+        let digest = sha512_hex(&[
+            &ping.shared_key_salt,
+            self.security.self_hostname.as_bytes(),
+            &self.nonce,
+            self.security.shared_key.as_bytes(),
+        ]);
+        encode(Value::Array(vec![
+            "PONG".into(),
+            result.is_ok().into(),
+            result.err().unwrap_or_default().into(),
+            self.security.self_hostname.as_str().into(),
+            digest.into(),
+        ]))
+    }
+
+    fn check(&self, ping: &FluentPing) -> Result<(), &'static str> {
+        if ping.kind != "PING" {
+            return Err("expected a PING message");
+        }
+
+        let shared_key_digest = sha512_hex(&[
+            &ping.shared_key_salt,
+            ping.hostname.as_bytes(),
+            &self.nonce,
+            self.security.shared_key.as_bytes(),
+        ]);
+        if ping.shared_key_digest != shared_key_digest {
+            return Err("shared key mismatch");
+        }
+
+        if !self.security.users.is_empty() {
+            let authenticated = self.security.users.iter().any(|user| {
+                user.username == ping.username
+                    && ping.password_digest
+                        == sha512_hex(&[
+                            &self.auth_salt,
+                            user.username.as_bytes(),
+                            user.password.as_bytes(),
+                        ])
+            });
+            if !authenticated {
+                return Err("username/password mismatch");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(super) fn sha512_hex(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn encode(value: Value) -> Bytes {
+    let mut buf = Vec::new();
+    rmpv::encode::write_value(&mut buf, &value).expect("writing to a vec can't fail");
+    buf.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_bytes::ByteBuf;
+
+    use super::*;
+
+    fn handshake(users: Vec<FluentUserConfig>) -> Handshake {
+        let security = FluentSecurityConfig {
+            self_hostname: Some("vector".to_owned()),
+            shared_key: "secret".to_owned(),
+            users,
+        };
+        Handshake::new(security.build().unwrap())
+    }
+
+    fn helo(handshake: &Handshake) -> (Vec<u8>, Vec<u8>) {
+        let helo = rmpv::decode::read_value(&mut &handshake.helo()[..]).unwrap();
+        assert_eq!(helo[0], Value::from("HELO"));
+        let options = helo[1].as_map().unwrap();
+        let option = |key: &str| {
+            options
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .and_then(|(_, v)| v.as_slice())
+                .unwrap()
+                .to_vec()
+        };
+        (option("nonce"), option("auth"))
+    }
+
+    fn ping(nonce: &[u8], shared_key: &str, auth: Option<(&[u8], &str, &str)>) -> FluentPing {
+        let (username, password_digest) = match auth {
+            Some((auth_salt, username, password)) => (
+                username.to_owned(),
+                sha512_hex(&[auth_salt, username.as_bytes(), password.as_bytes()]),
+            ),
+            None => (String::new(), String::new()),
+        };
+        FluentPing {
+            kind: "PING".to_owned(),
+            hostname: "client".to_owned(),
+            shared_key_salt: ByteBuf::from(b"salt".to_vec()),
+            shared_key_digest: sha512_hex(&[b"salt", b"client", nonce, shared_key.as_bytes()]),
+            username,
+            password_digest,
+        }
+    }
+
+    fn pong(pong: Bytes) -> (bool, String, String) {
+        let pong = rmpv::decode::read_value(&mut &pong[..]).unwrap();
+        assert_eq!(pong[0], Value::from("PONG"));
+        assert_eq!(pong[3], Value::from("vector"));
+        (
+            pong[1].as_bool().unwrap(),
+            pong[2].as_str().unwrap().to_owned(),
+            pong[4].as_str().unwrap().to_owned(),
+        )
+    }
+
+    #[test]
+    fn authenticates_with_shared_key() {
+        let mut handshake = handshake(Vec::new());
+        let (nonce, auth_salt) = helo(&handshake);
+        assert!(auth_salt.is_empty());
+        assert!(handshake.is_pending());
+
+        let (ok, reason, digest) = pong(handshake.authenticate(&ping(&nonce, "secret", None)));
+        assert!(ok);
+        assert_eq!(reason, "");
+        assert_eq!(digest, sha512_hex(&[b"salt", b"vector", &nonce, b"secret"]));
+        assert!(handshake.is_authenticated());
+    }
+
+    #[test]
+    fn rejects_wrong_shared_key() {
+        let mut handshake = handshake(Vec::new());
+        let (nonce, _) = helo(&handshake);
+
+        let (ok, reason, _) = pong(handshake.authenticate(&ping(&nonce, "guess", None)));
+        assert!(!ok);
+        assert_eq!(reason, "shared key mismatch");
+        assert!(!handshake.is_authenticated());
+        assert!(!handshake.is_pending());
+    }
+
+    #[test]
+    fn authenticates_users() {
+        let users = vec![FluentUserConfig {
+            username: "alice".to_owned(),
+            password: "hunter2".to_owned(),
+        }];
+
+        let mut accepted = handshake(users.clone());
+        let (nonce, auth_salt) = helo(&accepted);
+        assert_eq!(auth_salt.len(), 16);
+        let alice = ping(&nonce, "secret", Some((&auth_salt, "alice", "hunter2")));
+        assert!(pong(accepted.authenticate(&alice)).0);
+
+        let mut rejected = handshake(users);
+        let (nonce, auth_salt) = helo(&rejected);
+        let impostor = ping(&nonce, "secret", Some((&auth_salt, "alice", "guess")));
+        let (ok, reason, _) = pong(rejected.authenticate(&impostor));
+        assert!(!ok);
+        assert_eq!(reason, "username/password mismatch");
+    }
+}
@@ -1,5 +1,6 @@
 use std::io::{self, Read};
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use bytes::{Buf, Bytes, BytesMut};
 use codecs::StreamDecodingError;
@@ -13,6 +14,7 @@ use vector_config::configurable_component;
 
 use super::util::{SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker};
 use crate::{
+    codecs::DecoderDeadline,
     config::{
         log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource,
         SourceConfig, SourceContext, SourceDescription,
@@ -24,7 +26,9 @@ use crate::{
     tls::{MaybeTlsSettings, TlsSourceConfig},
 };
 
+mod handshake;
 mod message;
+use self::handshake::{FluentSecurity, FluentSecurityConfig, Handshake};
 use self::message::{FluentEntry, FluentMessage, FluentRecord, FluentTag, FluentTimestamp};
 
 /// Configuration for the `fluent` source.
@@ -48,6 +52,9 @@ pub struct FluentConfig {
     #[configurable(derived)]
     tls: Option<TlsSourceConfig>,
 
+    #[configurable(derived)]
+    security: Option<FluentSecurityConfig>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
@@ -63,6 +70,7 @@ impl GenerateConfig for FluentConfig {
             address: SocketListenAddr::SocketAddr("0.0.0.0:24224".parse().unwrap()),
             keepalive: None,
             tls: None,
+            security: None,
             receive_buffer_bytes: None,
             acknowledgements: Default::default(),
             connection_limit: Some(2),
@@ -75,7 +83,12 @@ impl GenerateConfig for FluentConfig {
 #[typetag::serde(name = "fluent")]
 impl SourceConfig for FluentConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let source = FluentSource {};
+        let security = self
+            .security
+            .as_ref()
+            .map(FluentSecurityConfig::build)
+            .transpose()?;
+        let source = FluentSource { security };
         let shutdown_secs = 30;
         let tls_config = self.tls.as_ref().map(|tls| tls.tls_config.clone());
         let tls_client_metadata_key = self
@@ -93,6 +106,7 @@ impl SourceConfig for FluentConfig {
             cx,
             self.acknowledgements,
             self.connection_limit,
+            false,
         )
     }
 
@@ -114,7 +128,9 @@ impl SourceConfig for FluentConfig {
 }
 
 #[derive(Debug, Clone)]
-struct FluentSource;
+struct FluentSource {
+    security: Option<Arc<FluentSecurity>>,
+}
 
 impl TcpSource for FluentSource {
     type Error = DecodeError;
@@ -123,7 +139,11 @@ impl TcpSource for FluentSource {
     type Acker = FluentAcker;
 
     fn decoder(&self) -> Self::Decoder {
-        FluentDecoder::new()
+        FluentDecoder::new(self.security.clone().map(Handshake::new))
+    }
+
+    fn build_greeting(&self, decoder: &Self::Decoder) -> Option<Bytes> {
+        decoder.handshake.as_ref().map(Handshake::helo)
     }
 
     fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
@@ -147,6 +167,8 @@ pub enum DecodeError {
     Decode(decode::Error),
     UnknownCompression(String),
     UnexpectedValue(rmpv::Value),
+    UnexpectedPing,
+    Unauthenticated,
 }
 
 impl std::fmt::Display for DecodeError {
@@ -160,6 +182,8 @@ impl std::fmt::Display for DecodeError {
             DecodeError::UnexpectedValue(value) => {
                 write!(f, "unexpected msgpack value, ignoring: {}", value)
             }
+            DecodeError::UnexpectedPing => write!(f, "unexpected PING message, ignoring"),
+            DecodeError::Unauthenticated => write!(f, "client isn't authenticated"),
         }
     }
 }
@@ -171,6 +195,8 @@ impl StreamDecodingError for DecodeError {
             DecodeError::Decode(_) => true,
             DecodeError::UnknownCompression(_) => true,
             DecodeError::UnexpectedValue(_) => true,
+            DecodeError::UnexpectedPing => true,
+            DecodeError::Unauthenticated => false,
         }
     }
 }
@@ -188,11 +214,13 @@ impl From<decode::Error> for DecodeError {
 }
 
 #[derive(Debug)]
-struct FluentDecoder;
+struct FluentDecoder {
+    handshake: Option<Handshake>,
+}
 
 impl FluentDecoder {
-    const fn new() -> Self {
-        FluentDecoder
+    const fn new(handshake: Option<Handshake>) -> Self {
+        FluentDecoder { handshake }
     }
 
     fn handle_message(
@@ -200,7 +228,26 @@ impl FluentDecoder {
         message: Result<FluentMessage, DecodeError>,
         byte_size: usize,
     ) -> Result<Option<(FluentFrame, usize)>, DecodeError> {
-        match message? {
+        let message = message?;
+
+        // With a shared key, the client has to authenticate with a PING before sending anything
+        // else.
+        if let Some(handshake) = &mut self.handshake {
+            match &message {
+                FluentMessage::Ping(ping) if handshake.is_pending() => {
+                    let frame = FluentFrame {
+                        events: smallvec![],
+                        chunk: None,
+                        response: Some(handshake.authenticate(ping)),
+                    };
+                    return Ok(Some((frame, byte_size)));
+                }
+                _ if !handshake.is_authenticated() => return Err(DecodeError::Unauthenticated),
+                _ => (),
+            }
+        }
+
+        match message {
             FluentMessage::Message(tag, timestamp, record) => {
                 let event = Event::from(FluentEvent {
                     tag,
@@ -210,6 +257,7 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events: smallvec![event],
                     chunk: None,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
@@ -222,6 +270,7 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events: smallvec![event],
                     chunk: options.chunk,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
@@ -239,6 +288,7 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events,
                     chunk: None,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
@@ -256,6 +306,7 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events,
                     chunk: options.chunk,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
@@ -275,6 +326,7 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events,
                     chunk: None,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
@@ -306,15 +358,19 @@ impl FluentDecoder {
                 let frame = FluentFrame {
                     events,
                     chunk: options.chunk,
+                    response: None,
                 };
                 Ok(Some((frame, byte_size)))
             }
+            FluentMessage::Ping(_) => Err(DecodeError::UnexpectedPing),
             FluentMessage::Heartbeat(rmpv::Value::Nil) => Ok(None),
             FluentMessage::Heartbeat(value) => Err(DecodeError::UnexpectedValue(value)),
         }
     }
 }
 
+impl DecoderDeadline for FluentDecoder {}
+
 impl Decoder for FluentDecoder {
     type Item = (FluentFrame, usize);
     type Error = DecodeError;
@@ -399,12 +455,14 @@ impl Decoder for FluentEntryStreamDecoder {
 }
 
 struct FluentAcker {
+    responses: Vec<Bytes>,
     chunks: Vec<String>,
 }
 
 impl FluentAcker {
     fn new(frames: &[FluentFrame]) -> Self {
         Self {
+            responses: frames.iter().filter_map(|f| f.response.clone()).collect(),
             chunks: frames.iter().filter_map(|f| f.chunk.clone()).collect(),
         }
     }
@@ -412,19 +470,23 @@ impl FluentAcker {
 
 impl TcpSourceAcker for FluentAcker {
     fn build_ack(self, ack: TcpSourceAck) -> Option<Bytes> {
-        if self.chunks.is_empty() {
+        if self.responses.is_empty() && self.chunks.is_empty() {
             return None;
         }
 
-        let mut acks = String::new();
+        // Handshake responses are sent whatever happened to the events.
+        let mut acks = BytesMut::new();
+        for response in self.responses {
+            acks.extend_from_slice(&response);
+        }
         for chunk in self.chunks {
             let ack = match ack {
                 TcpSourceAck::Ack => format!(r#"{{"ack": "{}"}}"#, chunk),
                 _ => String::from("{}"),
             };
-            acks.push_str(&ack);
+            acks.extend_from_slice(ack.as_bytes());
         }
-        Some(acks.into())
+        Some(acks.freeze())
     }
 }
 
@@ -445,6 +507,8 @@ impl From<FluentEvent> for Event {
 struct FluentFrame {
     events: SmallVec<[Event; 1]>,
     chunk: Option<String>,
+    /// Handshake message to send back to the client.
+    response: Option<Bytes>,
 }
 
 impl From<FluentFrame> for SmallVec<[Event; 1]> {
@@ -486,7 +550,11 @@ mod tests {
     use vector_common::assert_event_data_eq;
     use vector_core::event::Value;
 
-    use super::{message::FluentMessageOptions, *};
+    use super::{
+        handshake::sha512_hex,
+        message::{FluentMessageOptions, FluentPing},
+        *,
+    };
     use crate::{
         config::{SourceConfig, SourceContext},
         event::EventStatus,
@@ -831,7 +899,7 @@ mod tests {
     fn decode_all(message: Vec<u8>) -> Result<(SmallVec<[Event; 1]>, usize), DecodeError> {
         let mut buf = BytesMut::from(&message[..]);
 
-        let mut decoder = FluentDecoder::new();
+        let mut decoder = FluentDecoder::new(None);
 
         let (frame, byte_size) = decoder.decode(&mut buf)?.unwrap();
         Ok((frame.into(), byte_size))
@@ -876,6 +944,7 @@ mod tests {
         let source = FluentConfig {
             address: address.into(),
             tls: None,
+            security: None,
             keepalive: None,
             receive_buffer_bytes: None,
             acknowledgements: true.into(),
@@ -914,6 +983,82 @@ mod tests {
         (result, output.into())
     }
 
+    #[tokio::test]
+    async fn authenticates_clients_with_shared_key() {
+        trace_init();
+
+        let (sender, recv) = SourceSender::new_test();
+        let address = next_addr();
+        let source = FluentConfig {
+            address: address.into(),
+            tls: None,
+            security: Some(
+                toml::from_str(
+                    r#"
+                    self_hostname = "vector"
+                    shared_key = "secret"
+                    "#,
+                )
+                .unwrap(),
+            ),
+            keepalive: None,
+            receive_buffer_bytes: None,
+            acknowledgements: false.into(),
+            connection_limit: None,
+        }
+        .build(SourceContext::new_test(sender, None))
+        .await
+        .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+
+        let mut socket = tokio::net::TcpStream::connect(address).await.unwrap();
+        let helo = read_value(&mut socket).await;
+        assert_eq!(helo[0], rmpv::Value::from("HELO"));
+        let nonce = helo[1]
+            .as_map()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| key.as_str() == Some("nonce"))
+            .and_then(|(_, nonce)| nonce.as_slice())
+            .unwrap()
+            .to_vec();
+
+        let ping = FluentMessage::Ping(FluentPing {
+            kind: "PING".to_owned(),
+            hostname: "client".to_owned(),
+            shared_key_salt: b"salt".to_vec().into(),
+            shared_key_digest: sha512_hex(&[b"salt", b"client", &nonce, b"secret"]),
+            username: String::new(),
+            password_digest: String::new(),
+        });
+        let mut buf = Vec::new();
+        ping.serialize(&mut Serializer::new(&mut buf)).unwrap();
+        socket.write_all(&buf).await.unwrap();
+
+        let pong = read_value(&mut socket).await;
+        assert_eq!(pong[0], rmpv::Value::from("PONG"));
+        assert_eq!(pong[1], rmpv::Value::from(true));
+        assert_eq!(pong[3], rmpv::Value::from("vector"));
+
+        let msg = uuid::Uuid::new_v4().to_string();
+        let req = build_req("tag", &[("field", &msg)], false);
+        socket.write_all(&req).await.unwrap();
+
+        let events = test_util::collect_n(recv, 1).await;
+        assert_eq!(events[0].as_log()["field"], msg.into());
+    }
+
+    async fn read_value(socket: &mut tokio::net::TcpStream) -> rmpv::Value {
+        let mut buf = BytesMut::new();
+        loop {
+            assert_ne!(socket.read_buf(&mut buf).await.unwrap(), 0);
+            if let Ok(value) = rmpv::decode::read_value(&mut &buf[..]) {
+                return value;
+            }
+        }
+    }
+
     fn build_req(tag: &str, fields: &[(&str, &str)], with_chunk: bool) -> Vec<u8> {
         let mut record = FluentRecord::default();
         for (tag, value) in fields {
@@ -1125,6 +1270,7 @@ mod integration_tests {
             FluentConfig {
                 address: address.into(),
                 tls: None,
+                security: None,
                 keepalive: None,
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
@@ -0,0 +1,300 @@
+use std::{
+    fs,
+    io::{self, Read},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use native_tls::{Certificate, Identity, TlsConnector};
+use snafu::{ResultExt, Snafu};
+use ssh2::{CheckResult, KnownHostFileKind, Session, Sftp};
+use suppaftp::{list, types::FileType, FtpStream};
+
+use crate::tls::TlsConfig;
+
+#[derive(Debug, Snafu)]
+pub(super) enum ConnectError {
+    #[snafu(display("Could not connect to {}:{}: {}", host, port, source))]
+    Connect {
+        source: io::Error,
+        host: String,
+        port: u16,
+    },
+    #[snafu(display("SSH handshake failed: {}", source))]
+    Handshake { source: ssh2::Error },
+    #[snafu(display("Could not read known hosts {:?}: {}", path, source))]
+    KnownHosts { source: ssh2::Error, path: PathBuf },
+    #[snafu(display("Host key of {} is not in the known hosts", host))]
+    UnknownHostKey { host: String },
+    #[snafu(display("Authentication failed: {}", source))]
+    Authentication { source: ssh2::Error },
+    #[snafu(display("Could not start SFTP session: {}", source))]
+    SftpSession { source: ssh2::Error },
+    #[snafu(display("Could not read TLS file {:?}: {}", path, source))]
+    TlsFile { source: io::Error, path: PathBuf },
+    #[snafu(display("Invalid TLS settings: {}", source))]
+    TlsSettings { source: native_tls::Error },
+    #[snafu(display("FTP session failed: {}", source))]
+    Ftp { source: suppaftp::FtpError },
+}
+
+/// An entry of a remote directory.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct RemoteEntry {
+    pub(super) path: String,
+    pub(super) is_dir: bool,
+    pub(super) size: u64,
+
+    /// The modification time, in seconds since the Unix epoch.
+    pub(super) modified: Option<i64>,
+}
+
+/// A session with the remote server.
+///
+/// Sessions are blocking, and must only be used from blocking tasks.
+pub(super) trait Connection: Send {
+    fn list(&mut self, dir: &str) -> crate::Result<Vec<RemoteEntry>>;
+
+    /// Downloads a file into a local file, returning the number of bytes downloaded.
+    fn download(&mut self, path: &str, to: &mut fs::File) -> crate::Result<u64>;
+
+    fn remove(&mut self, path: &str) -> crate::Result<()>;
+
+    fn rename(&mut self, from: &str, to: &str) -> crate::Result<()>;
+}
+
+/// The settings of an SFTP session.
+#[derive(Clone, Debug)]
+pub(super) struct SftpSettings {
+    pub(super) host: String,
+    pub(super) port: u16,
+    pub(super) username: String,
+    pub(super) password: Option<String>,
+    pub(super) private_key_path: Option<PathBuf>,
+    pub(super) private_key_passphrase: Option<String>,
+    pub(super) known_hosts_path: PathBuf,
+    pub(super) timeout: Duration,
+}
+
+/// The settings of an FTP session.
+#[derive(Clone, Debug)]
+pub(super) struct FtpSettings {
+    pub(super) host: String,
+    pub(super) port: u16,
+    pub(super) username: String,
+    pub(super) password: Option<String>,
+    pub(super) tls: Option<TlsConfig>,
+    pub(super) timeout: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum ConnectionSettings {
+    Sftp(SftpSettings),
+    Ftp(FtpSettings),
+}
+
+impl ConnectionSettings {
+    /// The protocol of the sessions, as reported by the internal metrics.
+    pub(super) const fn protocol(&self) -> &'static str {
+        match self {
+            Self::Sftp(_) => "sftp",
+            Self::Ftp(_) => "ftp",
+        }
+    }
+
+    pub(super) fn connect(&self) -> Result<Box<dyn Connection>, ConnectError> {
+        Ok(match self {
+            Self::Sftp(settings) => Box::new(SftpConnection::connect(settings)?),
+            Self::Ftp(settings) => Box::new(FtpConnection::connect(settings)?),
+        })
+    }
+}
+
+fn connect_tcp(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, ConnectError> {
+    let stream = TcpStream::connect((host, port)).context(ConnectSnafu { host, port })?;
+    set_timeouts(&stream, timeout).context(ConnectSnafu { host, port })?;
+    Ok(stream)
+}
+
+fn set_timeouts(stream: &TcpStream, timeout: Duration) -> io::Result<()> {
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))
+}
+
+struct SftpConnection {
+    // The session must outlive its SFTP channel.
+    _session: Session,
+    sftp: Sftp,
+}
+
+impl SftpConnection {
+    fn connect(settings: &SftpSettings) -> Result<Self, ConnectError> {
+        let stream = connect_tcp(&settings.host, settings.port, settings.timeout)?;
+        let mut session = Session::new().context(HandshakeSnafu)?;
+        session.set_tcp_stream(stream);
+        session.set_timeout(settings.timeout.as_millis() as u32);
+        session.handshake().context(HandshakeSnafu)?;
+
+        // The host key is always verified, as credentials would otherwise be sent to whoever
+        // answers.
+        let mut known_hosts = session.known_hosts().context(HandshakeSnafu)?;
+        known_hosts
+            .read_file(&settings.known_hosts_path, KnownHostFileKind::OpenSSH)
+            .context(KnownHostsSnafu {
+                path: settings.known_hosts_path.clone(),
+            })?;
+        let (key, _) = session.host_key().ok_or(ConnectError::UnknownHostKey {
+            host: settings.host.clone(),
+        })?;
+        if known_hosts.check_port(&settings.host, settings.port, key) != CheckResult::Match {
+            return Err(ConnectError::UnknownHostKey {
+                host: settings.host.clone(),
+            });
+        }
+
+        match &settings.private_key_path {
+            Some(private_key_path) => session.userauth_pubkey_file(
+                &settings.username,
+                None,
+                private_key_path,
+                settings.private_key_passphrase.as_deref(),
+            ),
+            None => session.userauth_password(
+                &settings.username,
+                settings.password.as_deref().unwrap_or_default(),
+            ),
+        }
+        .context(AuthenticationSnafu)?;
+
+        let sftp = session.sftp().context(SftpSessionSnafu)?;
+        Ok(Self {
+            _session: session,
+            sftp,
+        })
+    }
+}
+
+impl Connection for SftpConnection {
+    fn list(&mut self, dir: &str) -> crate::Result<Vec<RemoteEntry>> {
+        Ok(self
+            .sftp
+            .readdir(Path::new(dir))?
+            .into_iter()
+            .filter_map(|(path, stat)| {
+                Some(RemoteEntry {
+                    path: path.to_str()?.to_owned(),
+                    is_dir: stat.is_dir(),
+                    size: stat.size.unwrap_or(0),
+                    modified: stat.mtime.map(|mtime| mtime as i64),
+                })
+            })
+            .collect())
+    }
+
+    fn download(&mut self, path: &str, to: &mut fs::File) -> crate::Result<u64> {
+        let mut file = self.sftp.open(Path::new(path))?;
+        Ok(io::copy(&mut file, to)?)
+    }
+
+    fn remove(&mut self, path: &str) -> crate::Result<()> {
+        Ok(self.sftp.unlink(Path::new(path))?)
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> crate::Result<()> {
+        Ok(self.sftp.rename(Path::new(from), Path::new(to), None)?)
+    }
+}
+
+struct FtpConnection {
+    stream: FtpStream,
+}
+
+impl FtpConnection {
+    fn connect(settings: &FtpSettings) -> Result<Self, ConnectError> {
+        let (host, port) = (settings.host.as_str(), settings.port);
+        let mut ftp = FtpStream::connect((host, port)).context(FtpSnafu)?;
+        set_timeouts(ftp.get_ref(), settings.timeout).context(ConnectSnafu { host, port })?;
+        if let Some(tls) = &settings.tls {
+            ftp = ftp
+                .into_secure(tls_connector(tls)?, &settings.host)
+                .context(FtpSnafu)?;
+        }
+        ftp.login(
+            &settings.username,
+            settings.password.as_deref().unwrap_or_default(),
+        )
+        .context(FtpSnafu)?;
+        ftp.transfer_type(FileType::Binary).context(FtpSnafu)?;
+        Ok(Self { stream: ftp })
+    }
+}
+
+/// Builds the connector of FTPS sessions, which are negotiated with `AUTH TLS`.
+fn tls_connector(tls: &TlsConfig) -> Result<TlsConnector, ConnectError> {
+    let read = |path: &PathBuf| fs::read(path).context(TlsFileSnafu { path: path.clone() });
+    let mut builder = TlsConnector::builder();
+    builder
+        .danger_accept_invalid_certs(!tls.verify_certificate.unwrap_or(true))
+        .danger_accept_invalid_hostnames(!tls.verify_hostname.unwrap_or(true));
+    if let Some(ca_file) = &tls.ca_file {
+        builder.add_root_certificate(
+            Certificate::from_pem(&read(ca_file)?).context(TlsSettingsSnafu)?,
+        );
+    }
+    if let (Some(crt_file), Some(key_file)) = (&tls.crt_file, &tls.key_file) {
+        builder.identity(
+            Identity::from_pkcs8(&read(crt_file)?, &read(key_file)?).context(TlsSettingsSnafu)?,
+        );
+    }
+    builder.build().context(TlsSettingsSnafu)
+}
+
+impl Connection for FtpConnection {
+    fn list(&mut self, dir: &str) -> crate::Result<Vec<RemoteEntry>> {
+        Ok(self
+            .stream
+            .list(Some(dir))?
+            .iter()
+            // Lines that aren't entries, such as the `total` line of some servers, are skipped.
+            .filter_map(|line| list::File::from_str(line).ok())
+            .filter(|file| file.name() != "." && file.name() != "..")
+            .map(|file| RemoteEntry {
+                path: join(dir, file.name()),
+                is_dir: file.is_directory(),
+                size: file.size() as u64,
+                modified: file
+                    .modified()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|modified| modified.as_secs() as i64),
+            })
+            .collect())
+    }
+
+    fn download(&mut self, path: &str, to: &mut fs::File) -> crate::Result<u64> {
+        Ok(self.stream.retr(path, |reader: &mut dyn Read| {
+            io::copy(reader, to).map_err(suppaftp::FtpError::ConnectionError)
+        })?)
+    }
+
+    fn remove(&mut self, path: &str) -> crate::Result<()> {
+        Ok(self.stream.rm(path)?)
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> crate::Result<()> {
+        Ok(self.stream.rename(from, to)?)
+    }
+}
+
+impl Drop for FtpConnection {
+    fn drop(&mut self) {
+        let _ = self.stream.quit();
+    }
+}
+
+/// Joins a remote directory and the name of one of its entries.
+pub(super) fn join(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
@@ -0,0 +1,847 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::{
+    decoding::{DeserializerConfig, FramingConfig},
+    StreamDecodingError,
+};
+use futures::StreamExt;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::{select, time};
+use tokio_stream::wrappers::IntervalStream;
+use tokio_util::codec::FramedRead;
+use url::Url;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, Output, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        BytesReceived, EventsReceived, FtpAfterDeliveryError, FtpCheckpointError, FtpConnectError,
+        FtpFileProcessingError, FtpListError, StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding},
+    shutdown::ShutdownSignal,
+    source_sender::ClosedError,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    tls::TlsEnableableConfig,
+    SourceSender,
+};
+
+mod client;
+
+use client::{Connection, ConnectionSettings, FtpSettings, RemoteEntry, SftpSettings};
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+const DOWNLOAD_FILENAME: &str = "download.tmp";
+
+/// The number of events of a file sent at once.
+const BATCH_SIZE: usize = 1000;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// What to do with the files read, once their events are delivered.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum AfterDelivery {
+    /// Leave the files as they are.
+    ///
+    /// They are read again if they change.
+    #[derivative(Default)]
+    Keep,
+
+    /// Delete the files.
+    Delete,
+
+    /// Move the files to another directory.
+    Move {
+        /// The directory to move the files to.
+        ///
+        /// It must not be matched by `include`, or the files would be read again.
+        directory: String,
+    },
+}
+
+/// Configuration for the `ftp` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct FtpConfig {
+    /// The URL of the server, such as `sftp://files.example.com` or `ftp://files.example.com:2121`.
+    ///
+    /// The scheme selects the protocol, either `sftp` or `ftp`. FTP sessions are secured with `AUTH TLS` when
+    /// `tls.enabled` is set.
+    endpoint: String,
+
+    /// The user to log in as.
+    username: String,
+
+    /// The password of the user.
+    password: Option<String>,
+
+    /// The path of the private key to authenticate with, instead of a password.
+    ///
+    /// Only used with SFTP.
+    private_key_path: Option<PathBuf>,
+
+    /// The passphrase of the private key.
+    private_key_passphrase: Option<String>,
+
+    /// The path of the `known_hosts` file the host key of the server is verified against.
+    ///
+    /// Only used with SFTP. Defaults to `~/.ssh/known_hosts`.
+    known_hosts_path: Option<PathBuf>,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    /// Absolute glob patterns of the remote files to read, such as `/outgoing/**/*.log`.
+    include: Vec<String>,
+
+    /// Absolute glob patterns of the remote files to skip.
+    #[serde(default)]
+    exclude: Vec<String>,
+
+    /// The interval between listings of the remote files, in seconds.
+    #[serde(default = "default_poll_interval_secs")]
+    #[derivative(Default(value = "default_poll_interval_secs()"))]
+    poll_interval_secs: u64,
+
+    /// The minimum time since a file was last modified before it is read, in seconds.
+    ///
+    /// This keeps files that are still being uploaded from being read.
+    #[serde(default)]
+    min_age_secs: u64,
+
+    /// The timeout of the operations on the server, in seconds.
+    #[serde(default = "default_timeout_secs")]
+    #[derivative(Default(value = "default_timeout_secs()"))]
+    timeout_secs: u64,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    after_delivery: AfterDelivery,
+
+    /// The directory used to persist the files read, and to download them.
+    ///
+    /// By default, the global `data_dir` option is used. Make sure the running user has write permissions to
+    /// this directory.
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the files read.
+    ///
+    /// By default, they're persisted in a file of `data_dir`.
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    framing: Option<FramingConfig>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_poll_interval_secs() -> u64 {
+    60
+}
+
+const fn default_timeout_secs() -> u64 {
+    30
+}
+
+inventory::submit! {
+    SourceDescription::new::<FtpConfig>("ftp")
+}
+
+impl_generate_config_from_default!(FtpConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ftp")]
+impl SourceConfig for FtpConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.include.is_empty() {
+            return Err(Box::new(BuildError::NoIncludes));
+        }
+        let includes = self
+            .include
+            .iter()
+            .map(|pattern| Include::new(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        let excludes = self
+            .exclude
+            .iter()
+            .map(|pattern| Pattern::new(pattern).context(InvalidPatternSnafu { pattern }))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let checkpoint_store = self
+            .checkpoint_store
+            .build(&cx, self.data_dir.as_ref(), CHECKPOINT_FILENAME)
+            .await?;
+
+        let framing = self
+            .framing
+            .clone()
+            .unwrap_or_else(|| self.decoding.default_stream_framing());
+        let poller = Poller {
+            settings: self.connection_settings()?,
+            includes,
+            excludes,
+            min_age: self.min_age_secs as i64,
+            after_delivery: self.after_delivery.clone(),
+            decoder: DecodingConfig::new(framing, self.decoding.clone()).build()?,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+            checkpoint: checkpoint_store.load_json_or_default().await?,
+            checkpoint_store,
+            download_path: data_dir.join(DOWNLOAD_FILENAME),
+            out: cx.out,
+        };
+        Ok(Box::pin(poller.run(
+            Duration::from_secs(self.poll_interval_secs),
+            cx.shutdown,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "ftp"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl FtpConfig {
+    fn connection_settings(&self) -> Result<ConnectionSettings, BuildError> {
+        let endpoint = Url::parse(&self.endpoint).context(InvalidEndpointSnafu)?;
+        let host = endpoint
+            .host_str()
+            .ok_or(BuildError::MissingHost)?
+            .to_owned();
+        let timeout = Duration::from_secs(self.timeout_secs);
+        match endpoint.scheme() {
+            "sftp" => Ok(ConnectionSettings::Sftp(SftpSettings {
+                host,
+                port: endpoint.port().unwrap_or(22),
+                username: self.username.clone(),
+                password: self.password.clone(),
+                private_key_path: self.private_key_path.clone(),
+                private_key_passphrase: self.private_key_passphrase.clone(),
+                known_hosts_path: match &self.known_hosts_path {
+                    Some(path) => path.clone(),
+                    None => dirs_next::home_dir()
+                        .ok_or(BuildError::MissingKnownHosts)?
+                        .join(".ssh")
+                        .join("known_hosts"),
+                },
+                timeout,
+            })),
+            "ftp" => Ok(ConnectionSettings::Ftp(FtpSettings {
+                host,
+                port: endpoint.port().unwrap_or(21),
+                username: self.username.clone(),
+                password: self.password.clone(),
+                tls: self
+                    .tls
+                    .as_ref()
+                    .filter(|tls| tls.enabled.unwrap_or(false))
+                    .map(|tls| tls.options.clone()),
+                timeout,
+            })),
+            scheme => Err(BuildError::UnsupportedScheme {
+                scheme: scheme.to_owned(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one `include` pattern must be configured"))]
+    NoIncludes,
+    #[snafu(display("Invalid pattern {:?}: {}", pattern, source))]
+    InvalidPattern {
+        source: glob::PatternError,
+        pattern: String,
+    },
+    #[snafu(display("Pattern {:?} is not absolute", pattern))]
+    RelativePattern { pattern: String },
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: url::ParseError },
+    #[snafu(display("Endpoint has no host"))]
+    MissingHost,
+    #[snafu(display("Unsupported scheme {:?}, expected `sftp` or `ftp`", scheme))]
+    UnsupportedScheme { scheme: String },
+    #[snafu(display("No home directory to find `.ssh/known_hosts` in, set `known_hosts_path`"))]
+    MissingKnownHosts,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ProcessingError {
+    #[snafu(display("Failed to download {}: {}", path, source))]
+    Download { source: crate::Error, path: String },
+    #[snafu(display("Failed to read all of {}: {}", path, source))]
+    Read {
+        source: codecs::decoding::Error,
+        path: String,
+    },
+    #[snafu(display("Failed to flush all of {}: {}", path, source))]
+    PipelineSend { source: ClosedError, path: String },
+    #[snafu(display("Sink reported an error delivering the events of {}", path))]
+    ErrorAcknowledgement { path: String },
+}
+
+/// A pattern of the files to read, with the directory they are listed from.
+#[derive(Debug)]
+struct Include {
+    pattern: Pattern,
+
+    /// The deepest directory of the pattern without wildcards.
+    base_dir: String,
+
+    /// How deep to list the subdirectories of `base_dir`, unlimited for patterns with `**`.
+    max_depth: Option<usize>,
+}
+
+impl Include {
+    fn new(pattern: &str) -> Result<Self, BuildError> {
+        if !pattern.starts_with('/') {
+            return Err(BuildError::RelativePattern {
+                pattern: pattern.to_owned(),
+            });
+        }
+        let compiled = Pattern::new(pattern).context(InvalidPatternSnafu { pattern })?;
+
+        let components = pattern.split('/').collect::<Vec<_>>();
+        // The file name is listed from its directory, even without wildcards.
+        let literal = components
+            .iter()
+            .take_while(|component| !component.contains(|c: char| matches!(c, '*' | '?' | '[')))
+            .count()
+            .min(components.len() - 1);
+        let base_dir = match components[..literal].join("/") {
+            base_dir if base_dir.is_empty() => "/".to_owned(),
+            base_dir => base_dir,
+        };
+        let remaining = &components[literal..];
+        let max_depth = if remaining.iter().any(|component| component.contains("**")) {
+            None
+        } else {
+            Some(remaining.len() - 1)
+        };
+
+        Ok(Self {
+            pattern: compiled,
+            base_dir,
+            max_depth,
+        })
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        self.pattern.matches_with(path, MATCH_OPTIONS)
+    }
+}
+
+/// The files read, to skip them until they change.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Checkpoint {
+    files: BTreeMap<String, FileVersion>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+struct FileVersion {
+    size: u64,
+    modified: Option<i64>,
+}
+
+impl From<&RemoteEntry> for FileVersion {
+    fn from(entry: &RemoteEntry) -> Self {
+        Self {
+            size: entry.size,
+            modified: entry.modified,
+        }
+    }
+}
+
+impl Checkpoint {
+    /// Returns whether a file was read, and hasn't changed since.
+    fn contains(&self, entry: &RemoteEntry) -> bool {
+        self.files.get(&entry.path) == Some(&FileVersion::from(entry))
+    }
+}
+
+/// A connection shared by the blocking tasks using it, one at a time.
+#[derive(Clone)]
+struct SharedConnection(Arc<Mutex<Box<dyn Connection>>>);
+
+impl SharedConnection {
+    async fn run<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn Connection) -> crate::Result<T> + Send + 'static,
+    {
+        let connection = Arc::clone(&self.0);
+        tokio::task::spawn_blocking(move || {
+            f(connection
+                .lock()
+                .expect("connection lock poisoned")
+                .as_mut())
+        })
+        .await?
+    }
+}
+
+struct Poller {
+    settings: ConnectionSettings,
+    includes: Vec<Include>,
+    excludes: Vec<Pattern>,
+    min_age: i64,
+    after_delivery: AfterDelivery,
+    decoder: Decoder,
+    acknowledgements: bool,
+    checkpoint: Checkpoint,
+    checkpoint_store: CheckpointStore,
+    download_path: PathBuf,
+    out: SourceSender,
+}
+
+impl Poller {
+    async fn run(mut self, interval: Duration, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut interval =
+            IntervalStream::new(time::interval(interval)).take_until(shutdown.clone());
+        while interval.next().await.is_some() {
+            select! {
+                _ = &mut shutdown => break,
+                result = self.poll() => result?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the new and changed files, only failing if the pipeline is closed.
+    async fn poll(&mut self) -> Result<(), ()> {
+        let settings = self.settings.clone();
+        let connection = match tokio::task::spawn_blocking(move || settings.connect())
+            .await
+            .map_err(crate::Error::from)
+            .and_then(|result| result.map_err(Into::into))
+        {
+            Ok(connection) => SharedConnection(Arc::new(Mutex::new(connection))),
+            Err(error) => {
+                emit!(FtpConnectError { error });
+                return Ok(());
+            }
+        };
+
+        let mut listed = BTreeMap::new();
+        let mut complete = true;
+        for include in &self.includes {
+            match list_files(&connection, include).await {
+                Ok(files) => listed.extend(
+                    files
+                        .into_iter()
+                        .filter(|file| !self.is_excluded(&file.path))
+                        .map(|file| (file.path.clone(), file)),
+                ),
+                Err(error) => {
+                    emit!(FtpListError {
+                        dir: &include.base_dir,
+                        error,
+                    });
+                    complete = false;
+                }
+            }
+        }
+
+        let now = Utc::now().timestamp();
+        for file in listed.values() {
+            if self.checkpoint.contains(file) {
+                // The files kept are those whose deletion or move failed.
+                self.after_delivery(&connection, file).await;
+                continue;
+            }
+            // Files still being uploaded are read once they're old enough.
+            if file
+                .modified
+                .map_or(false, |modified| modified > now - self.min_age)
+            {
+                continue;
+            }
+            match self.process_file(&connection, file).await {
+                Ok(()) => self.after_delivery(&connection, file).await,
+                Err(error) => {
+                    emit!(FtpFileProcessingError { error: &error });
+                    if matches!(error, ProcessingError::PipelineSend { .. }) {
+                        return Err(());
+                    }
+                }
+            }
+        }
+
+        // Files that are gone are forgotten, unless they may have been missed by a failed listing.
+        if complete {
+            let files = self.checkpoint.files.len();
+            self.checkpoint
+                .files
+                .retain(|path, _| listed.contains_key(path));
+            if self.checkpoint.files.len() != files {
+                self.write_checkpoint().await;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        self.excludes
+            .iter()
+            .any(|pattern| pattern.matches_with(path, MATCH_OPTIONS))
+    }
+
+    async fn process_file(
+        &mut self,
+        connection: &SharedConnection,
+        file: &RemoteEntry,
+    ) -> Result<(), ProcessingError> {
+        // Files are downloaded before being decoded, so that large files aren't held in memory.
+        let path = file.path.clone();
+        let download_path = self.download_path.clone();
+        let byte_size = connection
+            .run(move |connection| {
+                let mut download = fs::File::create(&download_path)?;
+                connection.download(&path, &mut download)
+            })
+            .await
+            .context(DownloadSnafu { path: &file.path })?;
+        emit!(BytesReceived {
+            byte_size: byte_size as usize,
+            protocol: self.settings.protocol(),
+        });
+
+        let download = tokio::fs::File::open(&self.download_path)
+            .await
+            .map_err(|error| ProcessingError::Download {
+                source: error.into(),
+                path: file.path.clone(),
+            })?;
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let mut frames = FramedRead::new(download, self.decoder.clone());
+        let now = Utc::now();
+        let mut events = Vec::new();
+        // Events read before an error are still sent, as duplicates are preferred over data loss.
+        let mut read_error = None;
+        while let Some(result) = frames.next().await {
+            match result {
+                Ok((decoded, _)) => {
+                    events.extend(decoded.into_iter().map(|event| {
+                        enrich_event(event.with_batch_notifier_option(&batch), &file.path, now)
+                    }));
+                    if events.len() >= BATCH_SIZE {
+                        self.send(std::mem::take(&mut events), &file.path).await?;
+                    }
+                }
+                Err(error) => {
+                    // Error is logged by `crate::codecs::Decoder`, no further handling is needed
+                    // here.
+                    if !error.can_continue() {
+                        read_error = Some(error);
+                        break;
+                    }
+                }
+            }
+        }
+        self.send(events, &file.path).await?;
+        drop(batch);
+
+        if let Some(error) = read_error {
+            return Err(ProcessingError::Read {
+                source: error,
+                path: file.path.clone(),
+            });
+        }
+        if let Some(receiver) = receiver {
+            // Rejected events can't be retried, so their file is read as well.
+            if receiver.await == BatchStatus::Errored {
+                return Err(ProcessingError::ErrorAcknowledgement {
+                    path: file.path.clone(),
+                });
+            }
+        }
+
+        self.checkpoint
+            .files
+            .insert(file.path.clone(), FileVersion::from(file));
+        self.write_checkpoint().await;
+        Ok(())
+    }
+
+    async fn send(&mut self, events: Vec<Event>, path: &str) -> Result<(), ProcessingError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+        self.out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError {
+                error: error.clone(),
+                count
+            });
+            ProcessingError::PipelineSend {
+                source: error,
+                path: path.to_owned(),
+            }
+        })
+    }
+
+    /// Deletes or moves a file read, forgetting it once it's gone.
+    async fn after_delivery(&mut self, connection: &SharedConnection, file: &RemoteEntry) {
+        let path = file.path.clone();
+        let result = match &self.after_delivery {
+            AfterDelivery::Keep => return,
+            AfterDelivery::Delete => {
+                connection
+                    .run(move |connection| connection.remove(&path))
+                    .await
+            }
+            AfterDelivery::Move { directory } => {
+                let name = path.rsplit('/').next().unwrap_or_default();
+                let destination = client::join(directory, name);
+                connection
+                    .run(move |connection| connection.rename(&path, &destination))
+                    .await
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.checkpoint.files.remove(&file.path);
+                self.write_checkpoint().await;
+            }
+            Err(error) => emit!(FtpAfterDeliveryError {
+                path: &file.path,
+                error,
+            }),
+        }
+    }
+
+    async fn write_checkpoint(&self) {
+        if let Err(error) = self.checkpoint_store.save_json(&self.checkpoint).await {
+            emit!(FtpCheckpointError { error });
+        }
+    }
+}
+
+/// Lists the files matching a pattern, walking the subdirectories of its base directory.
+async fn list_files(
+    connection: &SharedConnection,
+    include: &Include,
+) -> crate::Result<Vec<RemoteEntry>> {
+    let mut dirs = vec![(include.base_dir.clone(), 0)];
+    let mut files = Vec::new();
+    while let Some((dir, depth)) = dirs.pop() {
+        for entry in connection
+            .run(move |connection| connection.list(&dir))
+            .await?
+        {
+            if entry.is_dir {
+                if include
+                    .max_depth
+                    .map_or(true, |max_depth| depth < max_depth)
+                {
+                    dirs.push((entry.path, depth + 1));
+                }
+            } else if include.matches(&entry.path) {
+                files.push(entry);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn enrich_event(mut event: Event, path: &str, now: chrono::DateTime<Utc>) -> Event {
+    if let Event::Log(log) = &mut event {
+        log.try_insert(log_schema().timestamp_key(), now);
+        log.try_insert(log_schema().source_type_key(), Bytes::from("ftp"));
+        log.insert("path", path.to_owned());
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<FtpConfig>();
+    }
+
+    fn config(toml: &str) -> FtpConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn parses_endpoints() {
+        let settings = config(
+            r#"
+            endpoint = "sftp://files.example.com"
+            username = "vector"
+            known_hosts_path = "/etc/vector/known_hosts"
+            include = ["/outgoing/*.log"]
+            "#,
+        )
+        .connection_settings()
+        .unwrap();
+        assert!(matches!(
+            settings,
+            ConnectionSettings::Sftp(SftpSettings { host, port: 22, .. }) if host == "files.example.com"
+        ));
+
+        let settings = config(
+            r#"
+            endpoint = "ftp://files.example.com:2121"
+            username = "vector"
+            password = "secret"
+            include = ["/outgoing/*.log"]
+            tls.enabled = true
+            "#,
+        )
+        .connection_settings()
+        .unwrap();
+        assert!(matches!(
+            settings,
+            ConnectionSettings::Ftp(FtpSettings {
+                port: 2121,
+                tls: Some(_),
+                ..
+            })
+        ));
+
+        let error = config(
+            r#"
+            endpoint = "https://files.example.com"
+            username = "vector"
+            include = ["/outgoing/*.log"]
+            "#,
+        )
+        .connection_settings()
+        .unwrap_err();
+        assert!(matches!(error, BuildError::UnsupportedScheme { scheme } if scheme == "https"));
+    }
+
+    #[test]
+    fn parses_after_delivery() {
+        let config = config(
+            r#"
+            endpoint = "sftp://files.example.com"
+            username = "vector"
+            include = ["/outgoing/*.log"]
+            after_delivery.type = "move"
+            after_delivery.directory = "/archive"
+            "#,
+        );
+        assert!(matches!(
+            config.after_delivery,
+            AfterDelivery::Move { directory } if directory == "/archive"
+        ));
+    }
+
+    #[test]
+    fn splits_include_patterns() {
+        let include = Include::new("/outgoing/*.log").unwrap();
+        assert_eq!(include.base_dir, "/outgoing");
+        assert_eq!(include.max_depth, Some(0));
+        assert!(include.matches("/outgoing/app.log"));
+        assert!(!include.matches("/outgoing/old/app.log"));
+
+        let include = Include::new("/outgoing/report.csv").unwrap();
+        assert_eq!(include.base_dir, "/outgoing");
+        assert_eq!(include.max_depth, Some(0));
+
+        let include = Include::new("/vendors/*/daily/*.csv").unwrap();
+        assert_eq!(include.base_dir, "/vendors");
+        assert_eq!(include.max_depth, Some(2));
+        assert!(include.matches("/vendors/acme/daily/2022-08-10.csv"));
+
+        let include = Include::new("/*.log").unwrap();
+        assert_eq!(include.base_dir, "/");
+        assert_eq!(include.max_depth, Some(0));
+
+        let include = Include::new("/outgoing/**/*.log").unwrap();
+        assert_eq!(include.base_dir, "/outgoing");
+        assert_eq!(include.max_depth, None);
+        assert!(include.matches("/outgoing/a/b/app.log"));
+
+        assert!(matches!(
+            Include::new("outgoing/*.log"),
+            Err(BuildError::RelativePattern { .. })
+        ));
+    }
+
+    #[test]
+    fn skips_unchanged_files() {
+        let file = RemoteEntry {
+            path: "/outgoing/app.log".into(),
+            is_dir: false,
+            size: 42,
+            modified: Some(1_660_125_600),
+        };
+        let mut checkpoint = Checkpoint::default();
+        assert!(!checkpoint.contains(&file));
+
+        checkpoint
+            .files
+            .insert(file.path.clone(), FileVersion::from(&file));
+        assert!(checkpoint.contains(&file));
+
+        let changed = RemoteEntry {
+            size: 84,
+            ..file.clone()
+        };
+        assert!(!checkpoint.contains(&changed));
+    }
+
+    #[test]
+    fn enriches_events() {
+        let now = Utc::now();
+        let event = enrich_event(Event::from("first"), "/outgoing/app.log", now);
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "first".into());
+        assert_eq!(log[log_schema().timestamp_key()], now.into());
+        assert_eq!(log[log_schema().source_type_key()], "ftp".into());
+        assert_eq!(log["path"], "/outgoing/app.log".into());
+    }
+}
@@ -0,0 +1,369 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use super::gcp_pubsub::{
+    default_full_response, default_keepalive, default_max_concurrency, default_poll_time,
+    MessageDecoder, PubsubConfig,
+};
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, Event, LogEvent, Value},
+    gcp::GcpAuthConfig,
+    internal_events::{EventsReceived, GcpCloudLoggingLogEntryDecodeError},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    tls::TlsConfig,
+};
+
+/// The type of the `protoPayload` of audit logs.
+const AUDIT_LOG_TYPE: &str = "type.googleapis.com/google.cloud.audit.AuditLog";
+
+/// Configuration for the `gcp_cloud_logging` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct GcpCloudLoggingConfig {
+    /// The project of the subscription.
+    project: String,
+
+    /// The Pub/Sub subscription receiving the log entries exported by a log sink.
+    subscription: String,
+
+    /// The endpoint of Pub/Sub, from which to pull log entries.
+    endpoint: Option<String>,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    /// The maximum number of concurrent stream connections to open at once.
+    #[serde(default = "default_max_concurrency")]
+    #[derivative(Default(value = "default_max_concurrency()"))]
+    max_concurrency: usize,
+
+    /// The number of messages in a response to mark a stream as "busy". This is used to determine if more streams
+    /// should be started.
+    #[serde(default = "default_full_response")]
+    #[derivative(Default(value = "default_full_response()"))]
+    full_response_size: usize,
+
+    /// How often to poll the currently active streams to see if they are all busy and so open a new stream.
+    #[serde(default = "default_poll_time")]
+    #[derivative(Default(value = "default_poll_time()"))]
+    poll_time_seconds: f64,
+
+    /// The acknowledgement deadline, in seconds, to use for this stream.
+    ///
+    /// Messages that are not acknowledged when this deadline expires may be retransmitted.
+    ack_deadline_secs: Option<i32>,
+
+    /// The amount of time, in seconds, to wait between retry attempts after an error.
+    retry_delay_secs: Option<f64>,
+
+    /// The amount of time, in seconds, with no received activity before sending a keepalive request.
+    #[serde(default = "default_keepalive")]
+    #[derivative(Default(value = "default_keepalive()"))]
+    keepalive_secs: f64,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<GcpCloudLoggingConfig>("gcp_cloud_logging")
+}
+
+impl_generate_config_from_default!(GcpCloudLoggingConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_logging")]
+impl SourceConfig for GcpCloudLoggingConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        // Log entries are pulled the same way as by the `gcp_pubsub` source, only decoded differently.
+        let pubsub = PubsubConfig {
+            project: self.project.clone(),
+            subscription: self.subscription.clone(),
+            endpoint: self.endpoint.clone(),
+            auth: self.auth.clone(),
+            tls: self.tls.clone(),
+            max_concurrency: self.max_concurrency,
+            full_response_size: self.full_response_size,
+            poll_time_seconds: self.poll_time_seconds,
+            ack_deadline_secs: self.ack_deadline_secs,
+            ack_deadline_seconds: None,
+            retry_delay_secs: self.retry_delay_secs,
+            retry_delay_seconds: None,
+            keepalive_secs: self.keepalive_secs,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            acknowledgements: self.acknowledgements,
+        };
+        pubsub.build_source(cx, MessageDecoder::LogEntry).await
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_cloud_logging"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// A log entry, in the JSON format it is exported to Pub/Sub in.
+// https://cloud.google.com/logging/docs/reference/v2/rest/v2/LogEntry
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogEntry {
+    log_name: Option<String>,
+    resource: Option<serde_json::Value>,
+    timestamp: Option<DateTime<Utc>>,
+    receive_timestamp: Option<DateTime<Utc>>,
+    severity: Option<String>,
+    insert_id: Option<String>,
+    http_request: Option<serde_json::Value>,
+    labels: Option<serde_json::Value>,
+    operation: Option<serde_json::Value>,
+    trace: Option<String>,
+    span_id: Option<String>,
+    trace_sampled: Option<bool>,
+    source_location: Option<serde_json::Value>,
+    text_payload: Option<String>,
+    json_payload: Option<serde_json::Value>,
+    proto_payload: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Decodes the data of a message into the event of its log entry.
+pub(super) fn decode_log_entry(data: &[u8], batch: &Option<BatchNotifier>) -> Option<Event> {
+    match serde_json::from_slice::<LogEntry>(data) {
+        Ok(entry) => {
+            let log = log_entry_to_event(entry).with_batch_notifier_option(batch);
+            emit!(EventsReceived {
+                count: 1,
+                byte_size: log.size_of(),
+            });
+            Some(log.into())
+        }
+        Err(error) => {
+            emit!(GcpCloudLoggingLogEntryDecodeError { error });
+            None
+        }
+    }
+}
+
+fn log_entry_to_event(entry: LogEntry) -> LogEvent {
+    let mut log = LogEvent::default();
+    let schema = log_schema();
+
+    log.insert(
+        schema.timestamp_key(),
+        entry
+            .timestamp
+            .or(entry.receive_timestamp)
+            .unwrap_or_else(Utc::now),
+    );
+    log.insert(schema.source_type_key(), Bytes::from("gcp_cloud_logging"));
+
+    if let Some(text) = entry.text_payload {
+        log.insert(schema.message_key(), text);
+    }
+    if let Some(payload) = entry.json_payload {
+        if let Some(message) = payload.get("message").and_then(|message| message.as_str()) {
+            log.insert(schema.message_key(), message.to_owned());
+        }
+        log.insert("payload", Value::from(payload));
+    }
+    if let Some(mut payload) = entry.proto_payload {
+        if let Some(serde_json::Value::String(payload_type)) = payload.remove("@type") {
+            if payload_type == AUDIT_LOG_TYPE {
+                log.insert("audit", audit_log_fields(&payload));
+            }
+            log.insert("payload_type", payload_type);
+        }
+        log.insert("payload", Value::from(serde_json::Value::Object(payload)));
+    }
+
+    if let Some(trace) = entry.trace {
+        // Traces are formatted as `projects/{project}/traces/{trace_id}`.
+        if let Some(trace_id) = trace.rsplit('/').next() {
+            log.insert("trace_id", trace_id.to_owned());
+        }
+        log.insert("trace", trace);
+    }
+
+    let strings = [
+        ("log_name", entry.log_name),
+        ("severity", entry.severity),
+        ("insert_id", entry.insert_id),
+        ("span_id", entry.span_id),
+    ];
+    for (field, value) in strings
+        .into_iter()
+        .filter_map(|(field, value)| Some((field, value?)))
+    {
+        log.insert(field, value);
+    }
+    let objects = [
+        ("resource", entry.resource),
+        ("labels", entry.labels),
+        ("http_request", entry.http_request),
+        ("operation", entry.operation),
+        ("source_location", entry.source_location),
+    ];
+    for (field, value) in objects
+        .into_iter()
+        .filter_map(|(field, value)| Some((field, value?)))
+    {
+        log.insert(field, Value::from(value));
+    }
+    if let Some(receive_timestamp) = entry.receive_timestamp {
+        log.insert("receive_timestamp", receive_timestamp);
+    }
+    if let Some(trace_sampled) = entry.trace_sampled {
+        log.insert("trace_sampled", trace_sampled);
+    }
+
+    log
+}
+
+/// Extracts the commonly used fields of an audit log, which are otherwise deeply nested in its payload.
+// https://cloud.google.com/logging/docs/reference/audit/auditlog/rest/Shared.Types/AuditLog
+fn audit_log_fields(payload: &serde_json::Map<String, serde_json::Value>) -> Value {
+    let get = |path: &[&str]| {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(payload.get(*first)?, |value, key| value.get(key))
+            .cloned()
+    };
+
+    let fields = [
+        ("service_name", get(&["serviceName"])),
+        ("method_name", get(&["methodName"])),
+        ("resource_name", get(&["resourceName"])),
+        (
+            "principal_email",
+            get(&["authenticationInfo", "principalEmail"]),
+        ),
+        ("caller_ip", get(&["requestMetadata", "callerIp"])),
+        ("status_code", get(&["status", "code"])),
+        ("status_message", get(&["status", "message"])),
+    ];
+    Value::Object(
+        fields
+            .into_iter()
+            .filter_map(|(field, value)| Some((field.to_owned(), Value::from(value?))))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcpCloudLoggingConfig>();
+    }
+
+    fn decode(data: &str) -> LogEvent {
+        decode_log_entry(data.as_bytes(), &None).unwrap().into_log()
+    }
+
+    #[test]
+    fn decodes_text_payload() {
+        let log = decode(
+            r#"{
+                "insertId": "1x2y3z",
+                "logName": "projects/my-project/logs/stdout",
+                "resource": {"type": "k8s_container", "labels": {"cluster_name": "prod", "namespace_name": "default"}},
+                "timestamp": "2022-08-01T00:00:00.123Z",
+                "receiveTimestamp": "2022-08-01T00:00:01Z",
+                "severity": "ERROR",
+                "labels": {"k8s-pod/app": "web"},
+                "trace": "projects/my-project/traces/0123456789abcdef",
+                "spanId": "000000000000004a",
+                "traceSampled": true,
+                "textPayload": "connection refused"
+            }"#,
+        );
+
+        assert_eq!(log[log_schema().message_key()], "connection refused".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2022, 8, 1).and_hms_milli(0, 0, 0, 123).into()
+        );
+        assert_eq!(log["severity"], "ERROR".into());
+        assert_eq!(log["resource.type"], "k8s_container".into());
+        assert_eq!(log["resource.labels.cluster_name"], "prod".into());
+        assert_eq!(
+            log["trace"],
+            "projects/my-project/traces/0123456789abcdef".into()
+        );
+        assert_eq!(log["trace_id"], "0123456789abcdef".into());
+        assert_eq!(log["span_id"], "000000000000004a".into());
+        assert_eq!(log["trace_sampled"], true.into());
+        assert_eq!(log["insert_id"], "1x2y3z".into());
+        assert_eq!(log["log_name"], "projects/my-project/logs/stdout".into());
+    }
+
+    #[test]
+    fn decodes_json_payload() {
+        let log = decode(
+            r#"{
+                "timestamp": "2022-08-01T00:00:00Z",
+                "jsonPayload": {"message": "request served", "latency_ms": 12}
+            }"#,
+        );
+
+        assert_eq!(log[log_schema().message_key()], "request served".into());
+        assert_eq!(log["payload.latency_ms"], 12.into());
+    }
+
+    #[test]
+    fn decodes_audit_log() {
+        let log = decode(
+            r#"{
+                "timestamp": "2022-08-01T00:00:00Z",
+                "severity": "NOTICE",
+                "protoPayload": {
+                    "@type": "type.googleapis.com/google.cloud.audit.AuditLog",
+                    "serviceName": "storage.googleapis.com",
+                    "methodName": "storage.buckets.delete",
+                    "resourceName": "projects/_/buckets/logs",
+                    "authenticationInfo": {"principalEmail": "admin@example.com"},
+                    "requestMetadata": {"callerIp": "10.0.0.1"},
+                    "status": {}
+                }
+            }"#,
+        );
+
+        assert_eq!(log["payload_type"], AUDIT_LOG_TYPE.into());
+        assert_eq!(log["payload.serviceName"], "storage.googleapis.com".into());
+        assert_eq!(log["audit.service_name"], "storage.googleapis.com".into());
+        assert_eq!(log["audit.method_name"], "storage.buckets.delete".into());
+        assert_eq!(log["audit.resource_name"], "projects/_/buckets/logs".into());
+        assert_eq!(log["audit.principal_email"], "admin@example.com".into());
+        assert_eq!(log["audit.caller_ip"], "10.0.0.1".into());
+        assert!(log.get("audit.status_code").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_log_entry() {
+        assert!(decode_log_entry(b"not json", &None).is_none());
+    }
+}
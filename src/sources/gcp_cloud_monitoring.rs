@@ -0,0 +1,779 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use bytes::Bytes;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use futures::StreamExt;
+use http::{Request, StatusCode};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use url::form_urlencoded;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricValue},
+        Event,
+    },
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
+    http::{HttpClient, HttpError},
+    internal_events::{
+        BytesReceived, CollectionCompleted, EventsReceived, GcpCloudMonitoringQueryError,
+        StreamClosedError,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+const MONITORING_URL: &str = "https://monitoring.googleapis.com";
+
+/// Configuration for the `gcp_cloud_monitoring` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct GcpCloudMonitoringConfig {
+    /// The project whose time series are queried.
+    project: String,
+
+    /// The queries selecting the time series to collect.
+    queries: Vec<QueryConfig>,
+
+    /// The interval between queries, in seconds.
+    ///
+    /// Each query reads the data points written since the previous one.
+    #[serde(default = "default_scrape_interval_secs")]
+    #[derivative(Default(value = "default_scrape_interval_secs()"))]
+    scrape_interval_secs: u64,
+
+    /// How far behind the current time the queried window ends, in seconds.
+    ///
+    /// Data points can take a few minutes to be queryable after they are written, and are missed if they land
+    /// in a window that was already queried.
+    #[serde(default)]
+    delay_secs: u64,
+
+    /// The namespace of the metrics.
+    ///
+    /// Disabled if empty.
+    #[serde(default = "default_namespace")]
+    #[derivative(Default(value = "default_namespace()"))]
+    namespace: String,
+
+    /// The endpoint of Cloud Monitoring, from which to query time series.
+    endpoint: Option<String>,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+}
+
+/// A query of time series.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct QueryConfig {
+    /// The [monitoring filter][filter] selecting the time series.
+    ///
+    /// The filter must select a single metric type, such as
+    /// `metric.type = "compute.googleapis.com/instance/cpu/utilization"`.
+    ///
+    /// [filter]: https://cloud.google.com/monitoring/api/v3/filters
+    filter: String,
+
+    #[configurable(derived)]
+    aggregation: Option<AggregationConfig>,
+}
+
+/// How the data points of the time series are aligned and combined.
+///
+/// When unset, the raw data points are collected.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    /// The period, in seconds, over which the data points of each time series are aligned.
+    #[serde(default = "default_alignment_period_secs")]
+    alignment_period_secs: u64,
+
+    /// How the data points of each time series are aligned, such as `ALIGN_RATE` or `ALIGN_MEAN`.
+    per_series_aligner: Option<String>,
+
+    /// How the aligned time series are combined, such as `REDUCE_SUM`.
+    ///
+    /// Requires `per_series_aligner` to be set.
+    cross_series_reducer: Option<String>,
+
+    /// The labels preserved when the time series are combined, such as `resource.label.zone`.
+    #[serde(default)]
+    group_by_fields: Vec<String>,
+}
+
+const fn default_scrape_interval_secs() -> u64 {
+    60
+}
+
+const fn default_alignment_period_secs() -> u64 {
+    60
+}
+
+fn default_namespace() -> String {
+    "gcp".to_string()
+}
+
+inventory::submit! {
+    SourceDescription::new::<GcpCloudMonitoringConfig>("gcp_cloud_monitoring")
+}
+
+impl_generate_config_from_default!(GcpCloudMonitoringConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_monitoring")]
+impl SourceConfig for GcpCloudMonitoringConfig {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<super::Source> {
+        if self.queries.is_empty() {
+            return Err(Box::new(BuildError::NoQueries));
+        }
+
+        let auth = self.auth.build(Scope::MonitoringRead).await?;
+        auth.spawn_regenerate_token();
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let collector = Collector {
+            client,
+            auth,
+            url: format!(
+                "{}/v3/projects/{}/timeSeries",
+                self.endpoint.as_deref().unwrap_or(MONITORING_URL),
+                self.project,
+            ),
+            namespace: Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty()),
+            interval: Duration::seconds(self.scrape_interval_secs as i64),
+            delay: Duration::seconds(self.delay_secs as i64),
+        };
+        let mut queries = self
+            .queries
+            .iter()
+            .cloned()
+            .map(|config| Query {
+                config,
+                last_end: None,
+            })
+            .collect::<Vec<_>>();
+
+        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let mut interval = IntervalStream::new(time::interval(duration)).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let start = Instant::now();
+                let end = Utc::now() - collector.delay;
+                let mut metrics = Vec::new();
+                for query in queries.iter_mut() {
+                    metrics.extend(collector.collect(query, end).await);
+                }
+                emit!(CollectionCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let count = metrics.len();
+                if let Err(error) = cx.out.send_batch(metrics).await {
+                    emit!(StreamClosedError { error, count });
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_cloud_monitoring"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one query must be configured"))]
+    NoQueries,
+}
+
+#[derive(Debug, Snafu)]
+pub enum QueryError {
+    #[snafu(display("Failed to send request: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("Failed to read response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Unexpected status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("Failed to parse response: {}", source))]
+    ParseResponse { source: serde_json::Error },
+}
+
+struct Query {
+    config: QueryConfig,
+
+    /// The end of the last window successfully queried, from which the next window starts.
+    last_end: Option<DateTime<Utc>>,
+}
+
+struct Collector {
+    client: HttpClient,
+    auth: GcpAuthenticator,
+    url: String,
+    namespace: Option<String>,
+    interval: Duration,
+    delay: Duration,
+}
+
+impl Collector {
+    /// Collects the data points of a query written since its last window, up to `end`.
+    async fn collect(&self, query: &mut Query, end: DateTime<Utc>) -> Vec<Event> {
+        let start = query.last_end.unwrap_or(end - self.interval);
+        match self.list_time_series(&query.config, start, end).await {
+            Ok(time_series) => {
+                // Failed windows are queried again by the next collection.
+                query.last_end = Some(end);
+                let metrics = time_series
+                    .into_iter()
+                    .flat_map(|time_series| {
+                        time_series_to_metrics(time_series, self.namespace.as_deref(), start)
+                    })
+                    .map(Event::Metric)
+                    .collect::<Vec<_>>();
+                emit!(EventsReceived {
+                    count: metrics.len(),
+                    byte_size: metrics.size_of(),
+                });
+                metrics
+            }
+            Err(error) => {
+                emit!(GcpCloudMonitoringQueryError {
+                    filter: &query.config.filter,
+                    error,
+                });
+                Vec::new()
+            }
+        }
+    }
+
+    async fn list_time_series(
+        &self,
+        query: &QueryConfig,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<TimeSeries>, QueryError> {
+        let mut time_series = Vec::new();
+        let mut page_token = None;
+        loop {
+            let url = format!(
+                "{}?{}",
+                self.url,
+                query_params(query, start, end, page_token.as_deref())
+            );
+            let response: ListTimeSeriesResponse =
+                serde_json::from_slice(&self.send(url).await?).context(ParseResponseSnafu)?;
+            time_series.extend(response.time_series);
+            if response.next_page_token.is_empty() {
+                return Ok(time_series);
+            }
+            page_token = Some(response.next_page_token);
+        }
+    }
+
+    async fn send(&self, url: String) -> Result<Bytes, QueryError> {
+        let mut request = Request::get(url)
+            .body(Body::empty())
+            .expect("error creating request");
+        self.auth.apply(&mut request);
+        let response = self.client.send(request).await.context(RequestSnafu)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadResponseSnafu)?;
+        emit!(BytesReceived {
+            byte_size: body.len(),
+            protocol: "http",
+        });
+        if parts.status.is_success() {
+            Ok(body)
+        } else {
+            Err(QueryError::UnexpectedStatus {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+    }
+}
+
+// https://cloud.google.com/monitoring/api/ref_v3/rest/v3/projects.timeSeries/list
+fn query_params(
+    query: &QueryConfig,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    page_token: Option<&str>,
+) -> String {
+    let mut params = form_urlencoded::Serializer::new(String::new());
+    params
+        .append_pair("filter", &query.filter)
+        .append_pair(
+            "interval.startTime",
+            &start.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        )
+        .append_pair(
+            "interval.endTime",
+            &end.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        );
+    if let Some(aggregation) = &query.aggregation {
+        params.append_pair(
+            "aggregation.alignmentPeriod",
+            &format!("{}s", aggregation.alignment_period_secs),
+        );
+        if let Some(aligner) = &aggregation.per_series_aligner {
+            params.append_pair("aggregation.perSeriesAligner", aligner);
+        }
+        if let Some(reducer) = &aggregation.cross_series_reducer {
+            params.append_pair("aggregation.crossSeriesReducer", reducer);
+        }
+        for field in &aggregation.group_by_fields {
+            params.append_pair("aggregation.groupByFields", field);
+        }
+    }
+    if let Some(page_token) = page_token {
+        params.append_pair("pageToken", page_token);
+    }
+    params.finish()
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ListTimeSeriesResponse {
+    time_series: Vec<TimeSeries>,
+    next_page_token: String,
+}
+
+// https://cloud.google.com/monitoring/api/ref_v3/rest/v3/TimeSeries
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeSeries {
+    metric: TypedLabels,
+    #[serde(default)]
+    resource: TypedLabels,
+    metric_kind: String,
+    #[serde(default)]
+    points: Vec<Point>,
+}
+
+/// A metric or monitored resource, identified by its type and labels.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TypedLabels {
+    r#type: String,
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Point {
+    interval: TimeInterval,
+    value: TypedValue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeInterval {
+    end_time: DateTime<Utc>,
+}
+
+/// The value of a data point, of which only one field is set. 64-bit integers are encoded as strings.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedValue {
+    bool_value: Option<bool>,
+    int64_value: Option<String>,
+    double_value: Option<f64>,
+    distribution_value: Option<Distribution>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Distribution {
+    count: Option<String>,
+    #[serde(default)]
+    mean: f64,
+    bucket_options: Option<BucketOptions>,
+    #[serde(default)]
+    bucket_counts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum BucketOptions {
+    #[serde(rename_all = "camelCase")]
+    LinearBuckets {
+        num_finite_buckets: u32,
+        width: f64,
+        offset: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExponentialBuckets {
+        num_finite_buckets: u32,
+        growth_factor: f64,
+        scale: f64,
+    },
+    ExplicitBuckets {
+        bounds: Vec<f64>,
+    },
+}
+
+impl BucketOptions {
+    /// The upper bounds of the buckets, excluding the overflow bucket.
+    fn upper_bounds(&self) -> Vec<f64> {
+        match self {
+            Self::LinearBuckets {
+                num_finite_buckets,
+                width,
+                offset,
+            } => (0..=*num_finite_buckets)
+                .map(|i| offset + width * i as f64)
+                .collect(),
+            Self::ExponentialBuckets {
+                num_finite_buckets,
+                growth_factor,
+                scale,
+            } => (0..=*num_finite_buckets)
+                .map(|i| scale * growth_factor.powi(i as i32))
+                .collect(),
+            Self::ExplicitBuckets { bounds } => bounds.clone(),
+        }
+    }
+}
+
+/// Converts the data points of a time series ending after `after` into metrics.
+///
+/// The metric is named after its type, without the domain of the service, such as `instance_cpu_utilization`
+/// for `compute.googleapis.com/instance/cpu/utilization`. String values aren't supported, and are skipped.
+fn time_series_to_metrics(
+    time_series: TimeSeries,
+    namespace: Option<&str>,
+    after: DateTime<Utc>,
+) -> Vec<Metric> {
+    let metric_type = time_series.metric.r#type;
+    let name = metric_type
+        .split_once('/')
+        .map_or(metric_type.as_str(), |(_, name)| name)
+        .replace('/', "_");
+
+    let mut tags = time_series.metric.labels;
+    tags.insert("metric_type".into(), metric_type.clone());
+    tags.insert("resource_type".into(), time_series.resource.r#type);
+    for (key, value) in time_series.resource.labels {
+        tags.insert(format!("resource_{}", key), value);
+    }
+
+    let kind = match time_series.metric_kind.as_str() {
+        "DELTA" => MetricKind::Incremental,
+        _ => MetricKind::Absolute,
+    };
+    let is_counter = matches!(time_series.metric_kind.as_str(), "DELTA" | "CUMULATIVE");
+
+    // Points are listed from the newest to the oldest.
+    time_series
+        .points
+        .into_iter()
+        .rev()
+        .filter(|point| point.interval.end_time > after)
+        .filter_map(|point| {
+            let value = point_value(point.value, is_counter)?;
+            Some(
+                Metric::new(name.clone(), kind, value)
+                    .with_namespace(namespace)
+                    .with_tags(Some(tags.clone()))
+                    .with_timestamp(Some(point.interval.end_time)),
+            )
+        })
+        .collect()
+}
+
+fn point_value(value: TypedValue, is_counter: bool) -> Option<MetricValue> {
+    let number = |value: f64| {
+        if is_counter {
+            MetricValue::Counter { value }
+        } else {
+            MetricValue::Gauge { value }
+        }
+    };
+
+    if let Some(value) = value.double_value {
+        Some(number(value))
+    } else if let Some(value) = value.int64_value {
+        value.parse::<i64>().ok().map(|value| number(value as f64))
+    } else if let Some(value) = value.bool_value {
+        Some(MetricValue::Gauge {
+            value: if value { 1.0 } else { 0.0 },
+        })
+    } else {
+        value.distribution_value.map(distribution_value)
+    }
+}
+
+fn distribution_value(distribution: Distribution) -> MetricValue {
+    let count = distribution
+        .count
+        .and_then(|count| count.parse::<u32>().ok())
+        .unwrap_or(0);
+    // Trailing empty buckets are omitted from the counts.
+    let buckets = distribution
+        .bucket_options
+        .map(|options| options.upper_bounds())
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(i, upper_limit)| Bucket {
+            upper_limit,
+            count: distribution
+                .bucket_counts
+                .get(i)
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(0),
+        })
+        .collect();
+
+    MetricValue::AggregatedHistogram {
+        buckets,
+        count,
+        sum: distribution.mean * count as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::event::metric::MetricTags;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcpCloudMonitoringConfig>();
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: GcpCloudMonitoringConfig = toml::from_str(
+            r#"
+            project = "my-project"
+
+            [[queries]]
+            filter = 'metric.type = "compute.googleapis.com/instance/cpu/utilization"'
+
+            [[queries]]
+            filter = 'metric.type = "loadbalancing.googleapis.com/https/request_count"'
+            aggregation.per_series_aligner = "ALIGN_RATE"
+            aggregation.cross_series_reducer = "REDUCE_SUM"
+            aggregation.group_by_fields = ["resource.label.url_map_name"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.scrape_interval_secs, 60);
+        assert_eq!(config.namespace, "gcp");
+        assert_eq!(config.queries.len(), 2);
+        assert!(config.queries[0].aggregation.is_none());
+        let aggregation = config.queries[1].aggregation.as_ref().unwrap();
+        assert_eq!(aggregation.alignment_period_secs, 60);
+        assert_eq!(
+            aggregation.per_series_aligner.as_deref(),
+            Some("ALIGN_RATE")
+        );
+    }
+
+    #[test]
+    fn builds_query_params() {
+        let query = QueryConfig {
+            filter: r#"metric.type = "compute.googleapis.com/instance/cpu/utilization""#.into(),
+            aggregation: Some(AggregationConfig {
+                alignment_period_secs: 300,
+                per_series_aligner: Some("ALIGN_MEAN".into()),
+                cross_series_reducer: None,
+                group_by_fields: vec!["resource.label.zone".into()],
+            }),
+        };
+        let start = Utc.ymd(2022, 8, 10).and_hms(10, 0, 0);
+        assert_eq!(
+            query_params(&query, start, start + Duration::minutes(1), Some("next")),
+            "filter=metric.type+%3D+%22compute.googleapis.com%2Finstance%2Fcpu%2Futilization%22\
+             &interval.startTime=2022-08-10T10%3A00%3A00Z\
+             &interval.endTime=2022-08-10T10%3A01%3A00Z\
+             &aggregation.alignmentPeriod=300s\
+             &aggregation.perSeriesAligner=ALIGN_MEAN\
+             &aggregation.groupByFields=resource.label.zone\
+             &pageToken=next"
+        );
+    }
+
+    fn time_series(value: serde_json::Value) -> TimeSeries {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn converts_gauges() {
+        let after = Utc.ymd(2022, 8, 10).and_hms(10, 0, 0);
+        let metrics = time_series_to_metrics(
+            time_series(json!({
+                "metric": {
+                    "type": "compute.googleapis.com/instance/cpu/utilization",
+                    "labels": { "instance_name": "web-1" }
+                },
+                "resource": {
+                    "type": "gce_instance",
+                    "labels": { "project_id": "my-project", "zone": "us-central1-a" }
+                },
+                "metricKind": "GAUGE",
+                "valueType": "DOUBLE",
+                "points": [
+                    {
+                        "interval": { "endTime": "2022-08-10T10:02:00Z" },
+                        "value": { "doubleValue": 0.25 }
+                    },
+                    {
+                        "interval": { "endTime": "2022-08-10T10:01:00Z" },
+                        "value": { "doubleValue": 0.5 }
+                    },
+                    {
+                        "interval": { "endTime": "2022-08-10T10:00:00Z" },
+                        "value": { "doubleValue": 0.75 }
+                    }
+                ]
+            })),
+            Some("gcp"),
+            after,
+        );
+
+        let tags = MetricTags::from([
+            ("instance_name".into(), "web-1".into()),
+            (
+                "metric_type".into(),
+                "compute.googleapis.com/instance/cpu/utilization".into(),
+            ),
+            ("resource_type".into(), "gce_instance".into()),
+            ("resource_project_id".into(), "my-project".into()),
+            ("resource_zone".into(), "us-central1-a".into()),
+        ]);
+        assert_eq!(
+            metrics,
+            vec![
+                Metric::new(
+                    "instance_cpu_utilization",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 0.5 },
+                )
+                .with_namespace(Some("gcp"))
+                .with_tags(Some(tags.clone()))
+                .with_timestamp(Some(after + Duration::minutes(1))),
+                Metric::new(
+                    "instance_cpu_utilization",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 0.25 },
+                )
+                .with_namespace(Some("gcp"))
+                .with_tags(Some(tags))
+                .with_timestamp(Some(after + Duration::minutes(2))),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_counters() {
+        let after = Utc.ymd(2022, 8, 10).and_hms(10, 0, 0);
+        let delta = time_series_to_metrics(
+            time_series(json!({
+                "metric": { "type": "pubsub.googleapis.com/topic/send_message_operation_count" },
+                "metricKind": "DELTA",
+                "points": [{
+                    "interval": { "endTime": "2022-08-10T10:01:00Z" },
+                    "value": { "int64Value": "42" }
+                }]
+            })),
+            None,
+            after,
+        );
+        assert_eq!(delta[0].kind(), MetricKind::Incremental);
+        assert_eq!(delta[0].value(), &MetricValue::Counter { value: 42.0 });
+
+        let cumulative = time_series_to_metrics(
+            time_series(json!({
+                "metric": { "type": "custom.googleapis.com/requests" },
+                "metricKind": "CUMULATIVE",
+                "points": [{
+                    "interval": { "endTime": "2022-08-10T10:01:00Z" },
+                    "value": { "int64Value": "1337" }
+                }]
+            })),
+            None,
+            after,
+        );
+        assert_eq!(cumulative[0].name(), "requests");
+        assert_eq!(cumulative[0].kind(), MetricKind::Absolute);
+        assert_eq!(
+            cumulative[0].value(),
+            &MetricValue::Counter { value: 1337.0 }
+        );
+    }
+
+    #[test]
+    fn converts_distributions() {
+        let value = point_value(
+            serde_json::from_value(json!({
+                "distributionValue": {
+                    "count": "10",
+                    "mean": 2.5,
+                    "bucketOptions": {
+                        "exponentialBuckets": {
+                            "numFiniteBuckets": 2,
+                            "growthFactor": 2.0,
+                            "scale": 1.0
+                        }
+                    },
+                    "bucketCounts": ["1", "4", "5"]
+                }
+            }))
+            .unwrap(),
+            false,
+        );
+        assert_eq!(
+            value,
+            Some(MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 1, 2.0 => 4, 4.0 => 5],
+                count: 10,
+                sum: 25.0,
+            })
+        );
+    }
+
+    #[test]
+    fn skips_string_values() {
+        assert_eq!(
+            point_value(
+                serde_json::from_value(json!({ "stringValue": "running" })).unwrap(),
+                false
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,709 @@
+use std::{collections::HashMap, future::ready, io, panic, sync::Arc, time::Duration};
+
+use async_compression::tokio::bufread;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use codecs::{decoding::FramingError, CharacterDelimitedDecoder};
+use futures::{FutureExt, Stream, StreamExt, TryStreamExt};
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    HeaderMap, Request, StatusCode,
+};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::{pin, select};
+use tokio_util::{codec::FramedRead, io::StreamReader};
+use tracing::Instrument;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, LogEvent},
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope, PUBSUB_URL},
+    http::{HttpClient, HttpError},
+    internal_events::{
+        BytesReceived, GcsAcknowledgeError, GcsNotificationIgnored, GcsNotificationPullError,
+        GcsObjectProcessingError, OldEventsReceived, StreamClosedError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    tls::{TlsConfig, TlsSettings},
+    SourceSender,
+};
+
+const STORAGE_URL: &str = "https://storage.googleapis.com";
+
+// https://cloud.google.com/storage/docs/pubsub-notifications#events
+const OBJECT_FINALIZE: &str = "OBJECT_FINALIZE";
+
+/// Compression scheme for objects retrieved from Cloud Storage.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum Compression {
+    /// Automatically attempt to determine the compression scheme.
+    ///
+    /// Vector will try to determine the compression scheme of the object from its: `Content-Encoding` and
+    /// `Content-Type` metadata, as well as the key suffix (e.g. `.gz`).
+    ///
+    /// It will fallback to 'none' if the compression scheme cannot be determined.
+    #[derivative(Default)]
+    Auto,
+    /// Uncompressed.
+    None,
+    /// GZIP.
+    Gzip,
+}
+
+/// Configuration for the `gcp_cloud_storage` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct GcsConfig {
+    /// The project of the subscription.
+    project: String,
+
+    /// The Pub/Sub subscription receiving the notifications of the bucket.
+    subscription: String,
+
+    /// The endpoint of Pub/Sub, from which to pull notifications.
+    pubsub_endpoint: Option<String>,
+
+    /// The endpoint of Cloud Storage, from which to download objects.
+    storage_endpoint: Option<String>,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    /// The compression scheme used for decompressing objects retrieved from Cloud Storage.
+    #[serde(default)]
+    compression: Compression,
+
+    /// The maximum number of notifications to pull at once.
+    #[serde(default = "default_max_messages")]
+    #[derivative(Default(value = "default_max_messages()"))]
+    max_messages: u32,
+
+    /// Number of concurrent tasks to create for pulling notifications.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[serde(default = "default_client_concurrency")]
+    #[derivative(Default(value = "default_client_concurrency()"))]
+    client_concurrency: u32,
+
+    /// The amount of time, in seconds, to wait between retry attempts after an error.
+    #[serde(default = "default_retry_delay_secs")]
+    #[derivative(Default(value = "default_retry_delay_secs()"))]
+    retry_delay_secs: f64,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_max_messages() -> u32 {
+    10
+}
+
+fn default_client_concurrency() -> u32 {
+    crate::num_threads() as u32
+}
+
+const fn default_retry_delay_secs() -> f64 {
+    1.0
+}
+
+inventory::submit! {
+    SourceDescription::new::<GcsConfig>("gcp_cloud_storage")
+}
+
+impl_generate_config_from_default!(GcsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_storage")]
+impl SourceConfig for GcsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        // Both Pub/Sub and Cloud Storage are accessed with the same credentials.
+        let auth = self.auth.build(Scope::CloudPlatform).await?;
+        auth.spawn_regenerate_token();
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let state = Arc::new(State {
+            client,
+            auth,
+            subscription_url: format!(
+                "{}/v1/projects/{}/subscriptions/{}",
+                self.pubsub_endpoint.as_deref().unwrap_or(PUBSUB_URL),
+                self.project,
+                self.subscription,
+            ),
+            storage_url: self
+                .storage_endpoint
+                .clone()
+                .unwrap_or_else(|| STORAGE_URL.to_string()),
+            compression: self.compression,
+            max_messages: self.max_messages,
+            retry_delay: Duration::from_secs_f64(self.retry_delay_secs),
+        });
+
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        let client_concurrency = self.client_concurrency;
+        Ok(Box::pin(async move {
+            let mut handles = Vec::new();
+            for _ in 0..client_concurrency {
+                let process = Process {
+                    state: Arc::clone(&state),
+                    out: cx.out.clone(),
+                    shutdown: cx.shutdown.clone(),
+                    acknowledgements,
+                };
+                handles.push(tokio::spawn(process.run().in_current_span()));
+            }
+
+            // Wait for all of the processes to finish.  If any one of them panics, we resume
+            // that panic here to properly shutdown Vector.
+            for handle in handles {
+                if let Err(error) = handle.await {
+                    if error.is_panic() {
+                        panic::resume_unwind(error.into_panic());
+                    }
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_cloud_storage"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum GcsError {
+    #[snafu(display("Failed to send request: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("Failed to read response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Unexpected status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+}
+
+#[derive(Debug, Snafu)]
+pub enum ProcessingError {
+    #[snafu(display("Notification is missing the `{}` attribute", attribute))]
+    MissingAttribute { attribute: &'static str },
+    #[snafu(display("Failed to fetch gs://{}/{}: {}", bucket, object, source))]
+    GetObject {
+        source: GcsError,
+        bucket: String,
+        object: String,
+    },
+    #[snafu(display("Failed to read all of gs://{}/{}: {}", bucket, object, source))]
+    ReadObject {
+        source: Box<dyn FramingError>,
+        bucket: String,
+        object: String,
+    },
+    #[snafu(display("Failed to flush all of gs://{}/{}: {}", bucket, object, source))]
+    PipelineSend {
+        source: crate::source_sender::ClosedError,
+        bucket: String,
+        object: String,
+    },
+    #[snafu(display("Sink reported an error sending events"))]
+    ErrorAcknowledgement,
+}
+
+// https://cloud.google.com/pubsub/docs/reference/rest/v1/projects.subscriptions/pull
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    max_messages: u32,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PullResponse {
+    received_messages: Vec<ReceivedMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReceivedMessage {
+    ack_id: String,
+    message: PubsubMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PubsubMessage {
+    #[serde(default)]
+    attributes: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AcknowledgeRequest {
+    ack_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModifyAckDeadlineRequest {
+    ack_ids: Vec<String>,
+    ack_deadline_seconds: u32,
+}
+
+/// A Cloud Storage notification, read from the attributes of its message, which are set whatever
+/// the payload format.
+// https://cloud.google.com/storage/docs/pubsub-notifications#attributes
+#[derive(Debug, PartialEq)]
+struct Notification {
+    event_type: String,
+    bucket: String,
+    object: String,
+    generation: String,
+    event_time: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    fn from_attributes(attributes: &HashMap<String, String>) -> Result<Self, ProcessingError> {
+        let attribute = |attribute| {
+            attributes
+                .get(attribute)
+                .cloned()
+                .ok_or(ProcessingError::MissingAttribute { attribute })
+        };
+        Ok(Self {
+            event_type: attribute("eventType")?,
+            bucket: attribute("bucketId")?,
+            object: attribute("objectId")?,
+            generation: attribute("objectGeneration")?,
+            event_time: attributes
+                .get("eventTime")
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                .map(|time| time.with_timezone(&Utc)),
+        })
+    }
+}
+
+struct State {
+    client: HttpClient,
+    auth: GcpAuthenticator,
+    subscription_url: String,
+    storage_url: String,
+    compression: Compression,
+    max_messages: u32,
+    retry_delay: Duration,
+}
+
+impl State {
+    async fn send(&self, mut request: Request<Body>) -> Result<Bytes, GcsError> {
+        self.auth.apply(&mut request);
+        let response = self.client.send(request).await.context(RequestSnafu)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadResponseSnafu)?;
+        if parts.status.is_success() {
+            Ok(body)
+        } else {
+            Err(GcsError::UnexpectedStatus {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+    }
+
+    async fn call<T: Serialize>(&self, method: &str, body: &T) -> crate::Result<Bytes> {
+        let request = Request::post(format!("{}:{}", self.subscription_url, method))
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(body)?))?;
+        Ok(self.send(request).await?)
+    }
+
+    async fn pull(&self) -> crate::Result<Vec<ReceivedMessage>> {
+        let request = PullRequest {
+            max_messages: self.max_messages,
+        };
+        let response: PullResponse = serde_json::from_slice(&self.call("pull", &request).await?)?;
+        Ok(response.received_messages)
+    }
+
+    async fn acknowledge(&self, ack_ids: Vec<String>) {
+        if ack_ids.is_empty() {
+            return;
+        }
+        let count = ack_ids.len();
+        if let Err(error) = self
+            .call("acknowledge", &AcknowledgeRequest { ack_ids })
+            .await
+        {
+            emit!(GcsAcknowledgeError { count, error });
+        }
+    }
+
+    /// Makes the messages available again right away, rather than once their deadline expires.
+    async fn reject(&self, ack_ids: Vec<String>) {
+        if ack_ids.is_empty() {
+            return;
+        }
+        let count = ack_ids.len();
+        let request = ModifyAckDeadlineRequest {
+            ack_ids,
+            ack_deadline_seconds: 0,
+        };
+        if let Err(error) = self.call("modifyAckDeadline", &request).await {
+            emit!(GcsAcknowledgeError { count, error });
+        }
+    }
+}
+
+struct Process {
+    state: Arc<State>,
+    out: SourceSender,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+}
+
+impl Process {
+    async fn run(mut self) {
+        let shutdown = self.shutdown.clone().fuse();
+        pin!(shutdown);
+
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = self.run_once() => {},
+            }
+        }
+    }
+
+    async fn run_once(&mut self) {
+        let messages = match self.state.pull().await {
+            Ok(messages) => messages,
+            Err(error) => {
+                emit!(GcsNotificationPullError { error });
+                tokio::time::sleep(self.state.retry_delay).await;
+                return;
+            }
+        };
+
+        // Messages are only acknowledged once their object is processed, so that they are
+        // delivered again otherwise.
+        let mut ack_ids = Vec::new();
+        let mut reject_ids = Vec::new();
+        for message in messages {
+            match self.handle_message(&message.message).await {
+                Ok(()) => ack_ids.push(message.ack_id),
+                // Invalid notifications would never become valid.
+                Err(error @ ProcessingError::MissingAttribute { .. }) => {
+                    emit!(GcsObjectProcessingError { error: &error });
+                    ack_ids.push(message.ack_id);
+                }
+                Err(error) => {
+                    emit!(GcsObjectProcessingError { error: &error });
+                    reject_ids.push(message.ack_id);
+                }
+            }
+        }
+
+        self.state.acknowledge(ack_ids).await;
+        self.state.reject(reject_ids).await;
+    }
+
+    async fn handle_message(&mut self, message: &PubsubMessage) -> Result<(), ProcessingError> {
+        let notification = Notification::from_attributes(&message.attributes)?;
+        if notification.event_type != OBJECT_FINALIZE {
+            emit!(GcsNotificationIgnored {
+                bucket: &notification.bucket,
+                object: &notification.object,
+                event_type: &notification.event_type,
+            });
+            return Ok(());
+        }
+
+        self.process_object(&notification).await
+    }
+
+    async fn process_object(&mut self, notification: &Notification) -> Result<(), ProcessingError> {
+        let bucket = &notification.bucket;
+        let object = &notification.object;
+
+        // The generation is requested, so that the object notified about is processed even if it
+        // was overwritten since.
+        // https://cloud.google.com/storage/docs/json_api/v1/objects/get
+        let mut request = Request::get(format!(
+            "{}/storage/v1/b/{}/o/{}?alt=media&generation={}",
+            self.state.storage_url,
+            utf8_percent_encode(bucket, NON_ALPHANUMERIC),
+            utf8_percent_encode(object, NON_ALPHANUMERIC),
+            notification.generation,
+        ))
+        .body(Body::empty())
+        .expect("Building request failed");
+        self.state.auth.apply(&mut request);
+
+        let response = self
+            .state
+            .client
+            .send(request)
+            .await
+            .context(RequestSnafu)
+            .context(GetObjectSnafu { bucket, object })?;
+        let (parts, body) = response.into_parts();
+        if !parts.status.is_success() {
+            let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+            return Err(ProcessingError::GetObject {
+                source: GcsError::UnexpectedStatus {
+                    status: parts.status,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                },
+                bucket: bucket.clone(),
+                object: object.clone(),
+            });
+        }
+
+        let compression = match self.state.compression {
+            Compression::Auto => determine_compression(&parts.headers, object),
+            compression => compression,
+        };
+        let reader = tokio::io::BufReader::new(StreamReader::new(
+            body.map_err(|error| io::Error::new(io::ErrorKind::Other, error)),
+        ));
+        let reader: Box<dyn tokio::io::AsyncRead + Send + Unpin> = match compression {
+            Compression::Gzip => Box::new({
+                let mut decoder = bufread::GzipDecoder::new(reader);
+                decoder.multiple_members(true);
+                decoder
+            }),
+            Compression::Auto | Compression::None => Box::new(reader),
+        };
+
+        let timestamp = notification.event_time.unwrap_or_else(Utc::now);
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+
+        // Record the read error seen to propagate up later, so that the notification is not
+        // acknowledged. This can result in objects being partially processed before an error,
+        // but duplicate lines are preferred over data loss.
+        let mut read_error = None;
+        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
+            FramedRead::new(reader, CharacterDelimitedDecoder::new(b'\n'))
+                .map(|result| {
+                    result
+                        .map(|bytes| {
+                            emit!(BytesReceived {
+                                byte_size: bytes.len(),
+                                protocol: "http",
+                            });
+                            bytes
+                        })
+                        .map_err(|error| {
+                            read_error = Some(error);
+                        })
+                        .ok()
+                })
+                .take_while(|result| ready(result.is_some()))
+                .map(|result| result.expect("validated by take_while")),
+        );
+
+        let bucket_name = Bytes::from(bucket.clone());
+        let object_name = Bytes::from(object.clone());
+        let mut stream = lines.map(move |line| {
+            let mut log = LogEvent::from(line).with_batch_notifier_option(&batch);
+
+            log.insert("bucket", bucket_name.clone());
+            log.insert("object", object_name.clone());
+            log.insert(
+                log_schema().source_type_key(),
+                Bytes::from("gcp_cloud_storage"),
+            );
+            log.insert(log_schema().timestamp_key(), timestamp);
+
+            emit!(OldEventsReceived {
+                count: 1,
+                byte_size: log.size_of()
+            });
+
+            log
+        });
+
+        let send_error = match self.out.send_event_stream(&mut stream).await {
+            Ok(()) => None,
+            Err(error) => {
+                // The notification is delivered again, so no events are discarded.
+                emit!(StreamClosedError { error, count: 0 });
+                Some(crate::source_sender::ClosedError)
+            }
+        };
+
+        // `read_error` is captured by `stream`, which must be dropped to use it again.
+        drop(stream);
+
+        if let Some(error) = read_error {
+            Err(ProcessingError::ReadObject {
+                source: error,
+                bucket: bucket.clone(),
+                object: object.clone(),
+            })
+        } else if let Some(error) = send_error {
+            Err(ProcessingError::PipelineSend {
+                source: error,
+                bucket: bucket.clone(),
+                object: object.clone(),
+            })
+        } else {
+            match receiver {
+                None => Ok(()),
+                Some(receiver) => match receiver.await {
+                    BatchStatus::Delivered => Ok(()),
+                    BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement),
+                    BatchStatus::Rejected => {
+                        error!(
+                            message = "Sink reported events were rejected.",
+                            internal_log_rate_secs = 5,
+                        );
+                        // Failed events cannot be retried, so the notification is acknowledged.
+                        Ok(())
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Determines the compression of an object from the headers of its download, or its name.
+fn determine_compression(headers: &HeaderMap, object: &str) -> Compression {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if header(CONTENT_ENCODING.as_str()) == Some("gzip") {
+        return Compression::Gzip;
+    }
+    // Objects stored with `Content-Encoding: gzip` are decompressed when downloaded, unless the
+    // client accepts gzip responses.
+    // https://cloud.google.com/storage/docs/transcoding
+    if header("x-goog-stored-content-encoding") == Some("gzip") {
+        return Compression::None;
+    }
+    match header(CONTENT_TYPE.as_str()) {
+        Some("application/gzip" | "application/x-gzip") => Compression::Gzip,
+        _ if object.ends_with(".gz") => Compression::Gzip,
+        _ => Compression::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcsConfig>();
+    }
+
+    #[test]
+    fn parses_notification_attributes() {
+        let attributes = [
+            (
+                "notificationConfig",
+                "projects/_/buckets/logs/notificationConfigs/1",
+            ),
+            ("eventType", "OBJECT_FINALIZE"),
+            ("payloadFormat", "NONE"),
+            ("bucketId", "logs"),
+            ("objectId", "app/2022/08/01/out.log.gz"),
+            ("objectGeneration", "1659312000000000"),
+            ("eventTime", "2022-08-01T00:00:00.000000Z"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+
+        let notification = Notification::from_attributes(&attributes).unwrap();
+        assert_eq!(notification.event_type, OBJECT_FINALIZE);
+        assert_eq!(notification.bucket, "logs");
+        assert_eq!(notification.object, "app/2022/08/01/out.log.gz");
+        assert_eq!(notification.generation, "1659312000000000");
+        assert_eq!(
+            notification.event_time,
+            Some(Utc.ymd(2022, 8, 1).and_hms(0, 0, 0))
+        );
+
+        let mut attributes = attributes;
+        attributes.remove("objectGeneration");
+        assert!(matches!(
+            Notification::from_attributes(&attributes),
+            Err(ProcessingError::MissingAttribute {
+                attribute: "objectGeneration"
+            })
+        ));
+    }
+
+    #[test]
+    fn determines_compression() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        http::header::HeaderName::from_static(name),
+                        HeaderValue::from_static(value),
+                    )
+                })
+                .collect::<HeaderMap>()
+        };
+
+        let cases = [
+            (
+                headers(&[("content-encoding", "gzip")]),
+                "out.log",
+                Compression::Gzip,
+            ),
+            (
+                headers(&[("x-goog-stored-content-encoding", "gzip")]),
+                "out.log.gz",
+                Compression::None,
+            ),
+            (
+                headers(&[("content-type", "application/gzip")]),
+                "out.log",
+                Compression::Gzip,
+            ),
+            (headers(&[]), "out.log.gz", Compression::Gzip),
+            (headers(&[]), "out.log", Compression::None),
+        ];
+        for (headers, object, expected) in cases {
+            assert_eq!(
+                determine_compression(&headers, object),
+                expected,
+                "{}",
+                object
+            );
+        }
+    }
+}
@@ -180,27 +180,27 @@ pub struct PubsubConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
-const fn default_ack_deadline() -> i32 {
+pub(super) const fn default_ack_deadline() -> i32 {
     600
 }
 
-const fn default_retry_delay() -> f64 {
+pub(super) const fn default_retry_delay() -> f64 {
     1.0
 }
 
-const fn default_keepalive() -> f64 {
+pub(super) const fn default_keepalive() -> f64 {
     60.0
 }
 
-const fn default_max_concurrency() -> usize {
+pub(super) const fn default_max_concurrency() -> usize {
     10
 }
 
-const fn default_full_response() -> usize {
+pub(super) const fn default_full_response() -> usize {
     100
 }
 
-const fn default_poll_time() -> f64 {
+pub(super) const fn default_poll_time() -> f64 {
     2.0
 }
 
@@ -208,6 +208,45 @@ const fn default_poll_time() -> f64 {
 #[typetag::serde(name = "gcp_pubsub")]
 impl SourceConfig for PubsubConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
+        let decoder = MessageDecoder::Codec(
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?,
+        );
+        self.build_source(cx, decoder).await
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_pubsub"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl_generate_config_from_default!(PubsubConfig);
+
+/// How the data of messages is decoded into events.
+#[derive(Clone)]
+pub(super) enum MessageDecoder {
+    /// With the configured `framing` and `decoding`.
+    Codec(Decoder),
+
+    /// As Cloud Logging log entries, exported by a log sink.
+    #[cfg(feature = "sources-gcp_cloud_logging")]
+    LogEntry,
+}
+
+impl PubsubConfig {
+    /// Builds a source pulling messages from the subscription, decoded with `decoder`.
+    pub(super) async fn build_source(
+        &self,
+        cx: SourceContext,
+        decoder: MessageDecoder,
+    ) -> crate::Result<crate::sources::Source> {
         let ack_deadline_secs = match (self.ack_deadline_secs, self.ack_deadline_seconds) {
             (Some(ads), None) => ads,
             (None, Some(ads)) => {
@@ -267,7 +306,7 @@ impl SourceConfig for PubsubConfig {
                 "projects/{}/subscriptions/{}",
                 self.project, self.subscription
             ),
-            decoder: DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build(),
+            decoder,
             acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
             shutdown: cx.shutdown,
             out: cx.out,
@@ -284,22 +323,8 @@ impl SourceConfig for PubsubConfig {
         .map_err(|error| error!(message = "Source failed.", %error));
         Ok(Box::pin(source))
     }
-
-    fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
-    }
-
-    fn source_type(&self) -> &'static str {
-        "gcp_pubsub"
-    }
-
-    fn can_acknowledge(&self) -> bool {
-        true
-    }
 }
 
-impl_generate_config_from_default!(PubsubConfig);
-
 #[derive(Clone)]
 struct PubsubSource {
     endpoint: Endpoint,
@@ -307,7 +332,7 @@ struct PubsubSource {
     auth: GcpAuthenticator,
     token_generator: watch::Receiver<()>,
     subscription: String,
-    decoder: Decoder,
+    decoder: MessageDecoder,
     acknowledgements: bool,
     ack_deadline_secs: i32,
     shutdown: ShutdownSignal,
@@ -589,7 +614,17 @@ impl PubsubSource {
         &self,
         message: proto::PubsubMessage,
         batch: &'a Option<BatchNotifier>,
-    ) -> impl Iterator<Item = Event> + 'a {
+    ) -> Box<dyn Iterator<Item = Event> + 'a> {
+        let decoder = match &self.decoder {
+            MessageDecoder::Codec(decoder) => decoder.clone(),
+            #[cfg(feature = "sources-gcp_cloud_logging")]
+            MessageDecoder::LogEntry => {
+                return Box::new(
+                    super::gcp_cloud_logging::decode_log_entry(&message.data, batch).into_iter(),
+                )
+            }
+        };
+
         let attributes = Value::Object(
             message
                 .attributes
@@ -597,25 +632,27 @@ impl PubsubSource {
                 .map(|(key, value)| (key, Value::Bytes(value.into())))
                 .collect(),
         );
-        util::decode_message(
-            self.decoder.clone(),
-            "gcp_pubsub",
-            &message.data,
-            message.publish_time.map(|dt| {
-                DateTime::from_utc(
-                    NaiveDateTime::from_timestamp(dt.seconds, dt.nanos as u32),
-                    Utc,
-                )
+        Box::new(
+            util::decode_message(
+                decoder,
+                "gcp_pubsub",
+                &message.data,
+                message.publish_time.map(|dt| {
+                    DateTime::from_utc(
+                        NaiveDateTime::from_timestamp(dt.seconds, dt.nanos as u32),
+                        Utc,
+                    )
+                }),
+                batch,
+            )
+            .map(move |mut event| {
+                if let Some(log) = event.maybe_as_log_mut() {
+                    log.insert("message_id", message.message_id.clone());
+                    log.insert("attributes", attributes.clone());
+                }
+                event
             }),
-            batch,
         )
-        .map(move |mut event| {
-            if let Some(log) = event.maybe_as_log_mut() {
-                log.insert("message_id", message.message_id.clone());
-                log.insert("attributes", attributes.clone());
-            }
-            event
-        })
     }
 }
 
@@ -0,0 +1,525 @@
+//! This mod implements the `grpc` source.
+//! It serves a gRPC service described by a user-provided file descriptor set, and decodes each
+//! request message it receives into a log event, with the fields of the message.
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::{Buf, Bytes};
+use chrono::Utc;
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Server,
+};
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor, SerializeOptions,
+};
+use snafu::{OptionExt, ResultExt, Snafu};
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    server::{ClientStreamingService, Grpc, UnaryService},
+    Status, Streaming,
+};
+use tracing::{Instrument, Span};
+use vector_config::configurable_component;
+use vector_core::{
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    ByteSizeOf,
+};
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource,
+        SourceConfig, SourceContext, SourceDescription,
+    },
+    internal_events::{BytesReceived, EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    SourceSender,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("failed to read descriptor set {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("failed to decode descriptor set {:?}: {}", path, source))]
+    DecodeDescriptorSet {
+        path: PathBuf,
+        source: prost_reflect::DescriptorError,
+    },
+    #[snafu(display("service {:?} is not defined in the descriptor set", service))]
+    UnknownService { service: String },
+}
+
+/// Configuration for the `grpc` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcConfig {
+    /// The address to listen for connections on.
+    ///
+    /// It _must_ include a port.
+    address: SocketAddr,
+
+    /// The path to the file descriptor set defining the service.
+    ///
+    /// The file descriptor set can be generated with `protoc --include_imports --descriptor_set_out`.
+    desc_file: PathBuf,
+
+    /// The fully qualified name of the service to serve, such as `mypackage.MyService`.
+    ///
+    /// The request messages of its unary and client streaming methods are decoded into events, and the responses are
+    /// empty messages. Server streaming methods are not supported.
+    service: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<GrpcConfig>("grpc")
+}
+
+impl GenerateConfig for GrpcConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "0.0.0.0:6100"
+            desc_file = "/etc/vector/protos/events.desc"
+            service = "mypackage.MyService""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc")]
+impl SourceConfig for GrpcConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let methods = load_methods(&self.desc_file, &self.service)?;
+        let tls_settings = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let service = GrpcService {
+            methods: Arc::new(methods),
+            out: cx.out,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+
+        let source =
+            run_server(self.address, tls_settings, service, cx.shutdown).map_err(|error| {
+                error!(message = "Source future failed.", %error);
+            });
+
+        Ok(Box::pin(source))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "grpc"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// Loads the methods of the service from the descriptor set, keyed by their request path.
+fn load_methods(
+    desc_file: &Path,
+    service: &str,
+) -> Result<HashMap<String, MethodDescriptor>, BuildError> {
+    let descriptor_set = std::fs::read(desc_file).context(ReadDescriptorSetSnafu {
+        path: desc_file.to_path_buf(),
+    })?;
+    let pool =
+        DescriptorPool::decode(descriptor_set.as_slice()).context(DecodeDescriptorSetSnafu {
+            path: desc_file.to_path_buf(),
+        })?;
+    let service = pool
+        .get_service_by_name(service)
+        .context(UnknownServiceSnafu { service })?;
+
+    Ok(service
+        .methods()
+        .filter(|method| !method.is_server_streaming())
+        .map(|method| {
+            (
+                format!("/{}/{}", service.full_name(), method.name()),
+                method,
+            )
+        })
+        .collect())
+}
+
+async fn run_server(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    service: GrpcService,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let span = Span::current();
+    let listener = tls_settings.bind(&address).await?;
+
+    // The methods are only known at runtime, so requests are routed here rather than by `tonic`,
+    // which only handles their encoding.
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                service.clone().call(request).map(Ok::<_, Infallible>)
+            }))
+        }
+    });
+
+    Server::builder(hyper::server::accept::from_stream(listener.accept_stream()))
+        .http2_only(true)
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown.map(|_| ()))
+        .instrument(span)
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Clone)]
+struct GrpcService {
+    methods: Arc<HashMap<String, MethodDescriptor>>,
+    out: SourceSender,
+    acknowledgements: bool,
+}
+
+impl GrpcService {
+    async fn call(self, request: http::Request<Body>) -> http::Response<BoxBody> {
+        let method = match self.methods.get(request.uri().path()) {
+            Some(method) => method.clone(),
+            None => {
+                return Status::unimplemented(format!(
+                    "Method {:?} is not served.",
+                    request.uri().path()
+                ))
+                .to_http()
+            }
+        };
+
+        let mut grpc = Grpc::new(MessageCodec {
+            descriptor: method.input(),
+        });
+        if method.is_client_streaming() {
+            grpc.client_streaming(StreamingPush(self), request).await
+        } else {
+            grpc.unary(UnaryPush(self), request).await
+        }
+    }
+
+    async fn push(&self, message: DynamicMessage) -> Result<(), Status> {
+        let mut events = vec![message_to_event(&message)?];
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(self.acknowledgements, &mut events);
+        self.out.clone().send_batch(events).await.map_err(|error| {
+            let message = error.to_string();
+            emit!(StreamClosedError { error, count });
+            Status::unavailable(message)
+        })?;
+
+        let status = match receiver {
+            Some(receiver) => receiver.await,
+            None => BatchStatus::Delivered,
+        };
+        match status {
+            BatchStatus::Errored => Err(Status::internal("Delivery error")),
+            BatchStatus::Rejected => Err(Status::data_loss("Delivery failed")),
+            BatchStatus::Delivered => Ok(()),
+        }
+    }
+}
+
+struct UnaryPush(GrpcService);
+
+impl UnaryService<DynamicMessage> for UnaryPush {
+    type Response = ();
+    type Future = BoxFuture<'static, Result<tonic::Response<()>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+        let service = self.0.clone();
+        Box::pin(async move {
+            service.push(request.into_inner()).await?;
+            Ok(tonic::Response::new(()))
+        })
+    }
+}
+
+struct StreamingPush(GrpcService);
+
+impl ClientStreamingService<DynamicMessage> for StreamingPush {
+    type Response = ();
+    type Future = BoxFuture<'static, Result<tonic::Response<()>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<Streaming<DynamicMessage>>) -> Self::Future {
+        let service = self.0.clone();
+        Box::pin(async move {
+            let mut messages = request.into_inner();
+            while let Some(message) = messages.message().await? {
+                service.push(message).await?;
+            }
+            Ok(tonic::Response::new(()))
+        })
+    }
+}
+
+fn message_to_event(message: &DynamicMessage) -> Result<Event, Status> {
+    let options = SerializeOptions::new().use_proto_field_name(true);
+    let fields = message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|error| Status::invalid_argument(error.to_string()))?;
+    let mut log =
+        LogEvent::try_from(fields).map_err(|error| Status::invalid_argument(error.to_string()))?;
+
+    log.try_insert(log_schema().source_type_key(), Bytes::from("grpc"));
+    log.try_insert(log_schema().timestamp_key(), Utc::now());
+    Ok(log.into())
+}
+
+/// Decodes the request messages of a method, and encodes empty responses.
+struct MessageCodec {
+    descriptor: MessageDescriptor,
+}
+
+impl Codec for MessageCodec {
+    type Encode = ();
+    type Decode = DynamicMessage;
+    type Encoder = EmptyEncoder;
+    type Decoder = MessageDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        EmptyEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        MessageDecoder {
+            descriptor: self.descriptor.clone(),
+        }
+    }
+}
+
+/// Encodes empty messages, which are valid for any message type.
+struct EmptyEncoder;
+
+impl Encoder for EmptyEncoder {
+    type Item = ();
+    type Error = Status;
+
+    fn encode(&mut self, _item: (), _dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        Ok(())
+    }
+}
+
+struct MessageDecoder {
+    descriptor: MessageDescriptor,
+}
+
+impl Decoder for MessageDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<DynamicMessage>, Status> {
+        emit!(BytesReceived {
+            byte_size: src.remaining(),
+            protocol: "grpc",
+        });
+
+        DynamicMessage::decode(self.descriptor.clone(), src)
+            .map(Some)
+            .map_err(|error| Status::invalid_argument(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::body::HttpBody;
+    use prost::Message;
+    use prost_reflect::Value as MessageValue;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+        MethodDescriptorProto, ServiceDescriptorProto,
+    };
+
+    use super::*;
+    use crate::test_util::{
+        collect_ready,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr, wait_for_tcp,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GrpcConfig>();
+    }
+
+    fn write_descriptor_set(dir: &std::path::Path) -> PathBuf {
+        let field = |name: &str, number, r#type: Type| FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            json_name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(r#type as i32),
+            ..Default::default()
+        };
+        let method = |name: &str, client_streaming, server_streaming| MethodDescriptorProto {
+            name: Some(name.to_owned()),
+            input_type: Some(".test.Event".to_owned()),
+            output_type: Some(".test.Event".to_owned()),
+            client_streaming: Some(client_streaming),
+            server_streaming: Some(server_streaming),
+            ..Default::default()
+        };
+
+        let descriptor_set = FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".to_owned()),
+                package: Some("test".to_owned()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Event".to_owned()),
+                    field: vec![
+                        field("message", 1, Type::String),
+                        field("severity_level", 2, Type::Int32),
+                    ],
+                    ..Default::default()
+                }],
+                service: vec![ServiceDescriptorProto {
+                    name: Some("Collector".to_owned()),
+                    method: vec![
+                        method("Push", false, false),
+                        method("Stream", true, false),
+                        method("Watch", false, true),
+                    ],
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".to_owned()),
+                ..Default::default()
+            }],
+        };
+
+        let path = dir.join("test.desc");
+        std::fs::write(&path, descriptor_set.encode_to_vec()).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_methods() {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = write_descriptor_set(dir.path());
+
+        let methods = load_methods(&desc_file, "test.Collector").unwrap();
+        let mut paths = methods.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec!["/test.Collector/Push", "/test.Collector/Stream"]
+        );
+
+        assert!(matches!(
+            load_methods(&desc_file, "test.Unknown"),
+            Err(BuildError::UnknownService { .. })
+        ));
+    }
+
+    fn frame(message: &DynamicMessage) -> Bytes {
+        let message = message.encode_to_vec();
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&message);
+        frame.into()
+    }
+
+    #[tokio::test]
+    async fn receives_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = write_descriptor_set(dir.path());
+        let descriptor =
+            load_methods(&desc_file, "test.Collector").unwrap()["/test.Collector/Push"].input();
+
+        let mut message = DynamicMessage::new(descriptor);
+        message.set_field_by_name("message", MessageValue::String("hello".to_owned()));
+        message.set_field_by_name("severity_level", MessageValue::I32(3));
+
+        let address = next_addr();
+        let config: GrpcConfig = toml::from_str(&format!(
+            r#"
+            address = "{}"
+            desc_file = "{}"
+            service = "test.Collector"
+            "#,
+            address,
+            desc_file.display()
+        ))
+        .unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            wait_for_tcp(address).await;
+
+            let client = hyper::Client::builder().http2_only(true).build_http();
+            for path in ["/test.Collector/Push", "/test.Collector/Watch"] {
+                let request = http::Request::post(format!("http://{}{}", address, path))
+                    .header("content-type", "application/grpc")
+                    .header("te", "trailers")
+                    .body(Body::from(frame(&message)))
+                    .unwrap();
+                let response = client.request(request).await.unwrap();
+                assert_eq!(response.status(), http::StatusCode::OK);
+
+                // Unserved methods get a trailers-only response, whose status is in the headers.
+                let (parts, mut body) = response.into_parts();
+                while body.data().await.is_some() {}
+                let status = match body.trailers().await.unwrap() {
+                    Some(trailers) => trailers["grpc-status"].clone(),
+                    None => parts.headers["grpc-status"].clone(),
+                };
+                let expected = if path.ends_with("Push") { "0" } else { "12" };
+                assert_eq!(status, expected, "unexpected status for {}", path);
+            }
+
+            collect_ready(rx).await
+        })
+        .await;
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["severity_level"], 3.into());
+        assert_eq!(log[log_schema().source_type_key()], "grpc".into());
+    }
+}
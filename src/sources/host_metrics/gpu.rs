@@ -0,0 +1,377 @@
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use nvml_wrapper::{enum_wrappers::device::TemperatureSensor, error::NvmlError, Nvml};
+use serde_json::Value;
+use tokio::process::Command;
+use vector_common::btreemap;
+use vector_config::configurable_component;
+
+use super::{FilterList, HostMetrics};
+use crate::event::metric::Metric;
+
+/// Options for the “gpu” metrics collector.
+///
+/// This collector is only available on Linux systems. NVIDIA GPUs are read through NVML, and AMD GPUs
+/// through `rocm-smi` when NVML isn't available.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(default)]
+pub(crate) struct GpuConfig {
+    /// Lists of device index patterns to include or exclude.
+    devices: FilterList,
+
+    /// The path to the `rocm-smi` command, used to read the metrics of AMD GPUs.
+    #[derivative(Default(value = "default_rocm_smi_path()"))]
+    rocm_smi_path: PathBuf,
+}
+
+fn default_rocm_smi_path() -> PathBuf {
+    PathBuf::from("rocm-smi")
+}
+
+/// The library the GPU metrics are read from, chosen once when the source starts.
+pub(super) enum GpuBackend {
+    Nvml(Nvml),
+    RocmSmi,
+}
+
+impl GpuBackend {
+    pub(super) fn new() -> Self {
+        match Nvml::init() {
+            Ok(nvml) => Self::Nvml(nvml),
+            Err(error) => {
+                debug!(message = "NVML is unavailable, falling back to rocm-smi.", %error);
+                Self::RocmSmi
+            }
+        }
+    }
+}
+
+/// The readings of a GPU, any of which may be unsupported by a device.
+#[derive(Debug, Default, PartialEq)]
+struct GpuStats {
+    utilization_percent: Option<f64>,
+    memory_utilization_percent: Option<f64>,
+    memory_total_bytes: Option<f64>,
+    memory_used_bytes: Option<f64>,
+    temperature_celsius: Option<f64>,
+    power_watts: Option<f64>,
+}
+
+/// A GPU as reported by `rocm-smi`.
+#[derive(Debug, PartialEq)]
+struct RocmSmiDevice {
+    index: String,
+    model: Option<String>,
+    stats: GpuStats,
+}
+
+impl HostMetrics {
+    pub async fn gpu_metrics(&self) -> Vec<Metric> {
+        match &self.gpu {
+            Some(GpuBackend::Nvml(nvml)) => self.nvml_metrics(nvml),
+            Some(GpuBackend::RocmSmi) => self.rocm_smi_metrics().await,
+            None => Vec::new(),
+        }
+    }
+
+    fn nvml_metrics(&self, nvml: &Nvml) -> Vec<Metric> {
+        let count = match nvml.device_count() {
+            Ok(count) => count,
+            Err(error) => {
+                error!(message = "Failed to load GPU device count.", %error, internal_log_rate_secs = 60);
+                return Vec::new();
+            }
+        };
+
+        let timestamp = Utc::now();
+        let mut metrics = Vec::new();
+        for index in 0..count {
+            let index_str = index.to_string();
+            if !self.config.gpu.devices.contains_str(Some(&index_str)) {
+                continue;
+            }
+            let device = match nvml_value(nvml.device_by_index(index), "Failed to load GPU info.") {
+                Some(device) => device,
+                None => continue,
+            };
+
+            let mut tags = btreemap! {
+                "device" => index_str,
+                "vendor" => "nvidia",
+            };
+            if let Some(model) = nvml_value(device.name(), "Failed to load GPU name.") {
+                tags.insert("model".into(), model);
+            }
+
+            let utilization = nvml_value(
+                device.utilization_rates(),
+                "Failed to load GPU utilization.",
+            );
+            let memory = nvml_value(device.memory_info(), "Failed to load GPU memory info.");
+            let stats = GpuStats {
+                utilization_percent: utilization.as_ref().map(|rates| rates.gpu as f64),
+                memory_utilization_percent: utilization.as_ref().map(|rates| rates.memory as f64),
+                memory_total_bytes: memory.as_ref().map(|memory| memory.total as f64),
+                memory_used_bytes: memory.as_ref().map(|memory| memory.used as f64),
+                temperature_celsius: nvml_value(
+                    device.temperature(TemperatureSensor::Gpu),
+                    "Failed to load GPU temperature.",
+                )
+                .map(|celsius| celsius as f64),
+                power_watts: nvml_value(device.power_usage(), "Failed to load GPU power usage.")
+                    .map(|milliwatts| milliwatts as f64 / 1000.0),
+            };
+            metrics.extend(self.gpu_device_metrics(timestamp, stats, tags));
+        }
+        metrics
+    }
+
+    async fn rocm_smi_metrics(&self) -> Vec<Metric> {
+        let output = Command::new(&self.config.gpu.rocm_smi_path)
+            .args([
+                "--showuse",
+                "--showmemuse",
+                "--showmeminfo",
+                "vram",
+                "--showtemp",
+                "--showpower",
+                "--showproductname",
+                "--json",
+            ])
+            .output()
+            .await;
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                error!(
+                    message = "Failed to run rocm-smi.",
+                    status = %output.status,
+                    stderr = %String::from_utf8_lossy(&output.stderr),
+                    internal_log_rate_secs = 60
+                );
+                return Vec::new();
+            }
+            // Neither NVML nor `rocm-smi` is installed, so the host has no supported GPUs.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Vec::new(),
+            Err(error) => {
+                error!(message = "Failed to run rocm-smi.", %error, internal_log_rate_secs = 60);
+                return Vec::new();
+            }
+        };
+
+        let devices = match parse_rocm_smi(&output.stdout) {
+            Ok(devices) => devices,
+            Err(error) => {
+                error!(message = "Failed to parse rocm-smi output.", %error, internal_log_rate_secs = 60);
+                return Vec::new();
+            }
+        };
+
+        let timestamp = Utc::now();
+        devices
+            .into_iter()
+            .filter(|device| self.config.gpu.devices.contains_str(Some(&device.index)))
+            .flat_map(|device| {
+                let mut tags = btreemap! {
+                    "device" => device.index,
+                    "vendor" => "amd",
+                };
+                if let Some(model) = device.model {
+                    tags.insert("model".into(), model);
+                }
+                self.gpu_device_metrics(timestamp, device.stats, tags)
+            })
+            .collect()
+    }
+
+    fn gpu_device_metrics(
+        &self,
+        timestamp: DateTime<Utc>,
+        stats: GpuStats,
+        tags: BTreeMap<String, String>,
+    ) -> Vec<Metric> {
+        let memory_free_bytes = stats
+            .memory_total_bytes
+            .zip(stats.memory_used_bytes)
+            .map(|(total, used)| total - used);
+        [
+            (
+                "gpu_utilization_ratio",
+                stats.utilization_percent.map(|percent| percent / 100.0),
+            ),
+            (
+                "gpu_memory_utilization_ratio",
+                stats
+                    .memory_utilization_percent
+                    .map(|percent| percent / 100.0),
+            ),
+            ("gpu_memory_total_bytes", stats.memory_total_bytes),
+            ("gpu_memory_used_bytes", stats.memory_used_bytes),
+            ("gpu_memory_free_bytes", memory_free_bytes),
+            ("gpu_temperature_celsius", stats.temperature_celsius),
+            ("gpu_power_watts", stats.power_watts),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value.map(|value| self.gauge(name, timestamp, value, tags.clone()))
+        })
+        .collect()
+    }
+}
+
+/// Like `filter_result_sync`, but doesn't log readings the device doesn't support, as they fail on
+/// every scrape.
+fn nvml_value<T>(result: Result<T, NvmlError>, message: &'static str) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(NvmlError::NotSupported) => None,
+        Err(error) => {
+            error!(message, %error, internal_log_rate_secs = 60);
+            None
+        }
+    }
+}
+
+/// Parses the JSON output of `rocm-smi`, whose keys changed between ROCm releases.
+fn parse_rocm_smi(output: &[u8]) -> Result<Vec<RocmSmiDevice>, serde_json::Error> {
+    let cards: BTreeMap<String, BTreeMap<String, Value>> = serde_json::from_slice(output)?;
+    Ok(cards
+        .into_iter()
+        .filter_map(|(card, fields)| {
+            let index = card.strip_prefix("card")?.to_owned();
+            let field = |name: &str| fields.get(name).and_then(parse_number);
+            let field_ending_with = |suffix: &str| {
+                fields
+                    .iter()
+                    .find(|(name, _)| name.ends_with(suffix))
+                    .and_then(|(_, value)| parse_number(value))
+            };
+            let stats = GpuStats {
+                utilization_percent: field("GPU use (%)"),
+                memory_utilization_percent: field("GPU memory use (%)"),
+                memory_total_bytes: field("VRAM Total Memory (B)"),
+                memory_used_bytes: field("VRAM Total Used Memory (B)"),
+                temperature_celsius: field("Temperature (Sensor edge) (C)")
+                    .or_else(|| field("Temperature (Sensor junction) (C)")),
+                power_watts: field_ending_with("Graphics Package Power (W)"),
+            };
+            let model = fields
+                .get("Card series")
+                .and_then(Value::as_str)
+                .map(str::to_owned);
+            Some(RocmSmiDevice {
+                index,
+                model,
+                stats,
+            })
+        })
+        .collect())
+}
+
+fn parse_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            tests::{all_gauges, count_tag},
+            HostMetrics, HostMetricsConfig,
+        },
+        *,
+    };
+    use crate::event::metric::MetricValue;
+
+    #[test]
+    fn parses_rocm_smi_output() {
+        let output = br#"{
+            "card0": {
+                "GPU use (%)": "42",
+                "GPU memory use (%)": "7",
+                "VRAM Total Memory (B)": "68702699520",
+                "VRAM Total Used Memory (B)": "10485760",
+                "Temperature (Sensor edge) (C)": "35.0",
+                "Temperature (Sensor junction) (C)": "38.0",
+                "Average Graphics Package Power (W)": "43.0",
+                "Card series": "AMD Instinct MI210"
+            },
+            "card1": {
+                "GPU use (%)": "0",
+                "Temperature (Sensor junction) (C)": "30.0",
+                "Current Socket Graphics Package Power (W)": "N/A"
+            },
+            "system": {
+                "Driver version": "6.2.4"
+            }
+        }"#;
+
+        assert_eq!(
+            parse_rocm_smi(output).unwrap(),
+            vec![
+                RocmSmiDevice {
+                    index: "0".into(),
+                    model: Some("AMD Instinct MI210".into()),
+                    stats: GpuStats {
+                        utilization_percent: Some(42.0),
+                        memory_utilization_percent: Some(7.0),
+                        memory_total_bytes: Some(68702699520.0),
+                        memory_used_bytes: Some(10485760.0),
+                        temperature_celsius: Some(35.0),
+                        power_watts: Some(43.0),
+                    },
+                },
+                RocmSmiDevice {
+                    index: "1".into(),
+                    model: None,
+                    stats: GpuStats {
+                        utilization_percent: Some(0.0),
+                        temperature_celsius: Some(30.0),
+                        ..Default::default()
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn generates_gpu_device_metrics() {
+        let stats = GpuStats {
+            utilization_percent: Some(42.0),
+            memory_total_bytes: Some(1024.0),
+            memory_used_bytes: Some(256.0),
+            ..Default::default()
+        };
+        let tags = btreemap! { "device" => "0", "vendor" => "amd" };
+        let metrics = HostMetrics::new(HostMetricsConfig::default()).gpu_device_metrics(
+            Utc::now(),
+            stats,
+            tags,
+        );
+
+        assert_eq!(metrics.len(), 4);
+        assert!(all_gauges(&metrics));
+        assert_eq!(count_tag(&metrics, "device"), 4);
+        let value = |name| {
+            metrics
+                .iter()
+                .find(|metric| metric.name() == name)
+                .map(|metric| metric.value().clone())
+        };
+        assert_eq!(
+            value("gpu_utilization_ratio"),
+            Some(MetricValue::Gauge { value: 0.42 })
+        );
+        assert_eq!(
+            value("gpu_memory_free_bytes"),
+            Some(MetricValue::Gauge { value: 768.0 })
+        );
+    }
+}
@@ -33,8 +33,12 @@ mod cgroups;
 mod cpu;
 mod disk;
 mod filesystem;
+#[cfg(target_os = "linux")]
+mod gpu;
 mod memory;
 mod network;
+#[cfg(target_os = "linux")]
+mod process;
 
 /// Collector types.
 #[configurable_component]
@@ -54,6 +58,10 @@ pub enum Collector {
     /// Filesystem.
     Filesystem,
 
+    /// GPU.
+    #[cfg(target_os = "linux")]
+    Gpu,
+
     /// Load average.
     Load,
 
@@ -65,6 +73,23 @@ pub enum Collector {
 
     /// Network.
     Network,
+
+    /// Process.
+    #[cfg(target_os = "linux")]
+    Process,
+}
+
+impl Collector {
+    /// Whether the collector is used when `collectors` isn't set.
+    const fn is_default(self) -> bool {
+        // The process collector reports metrics for each process, so it has to be enabled
+        // explicitly.
+        #[cfg(target_os = "linux")]
+        if matches!(self, Self::Process) {
+            return false;
+        }
+        true
+    }
 }
 
 /// Filtering configuration.
@@ -90,7 +115,7 @@ pub struct HostMetricsConfig {
 
     /// The list of host metric collector services to use.
     ///
-    /// Defaults to all collectors.
+    /// Defaults to all collectors, except `process`.
     pub collectors: Option<Vec<Collector>>,
 
     /// Overrides the default namespace for the metrics emitted by the source.
@@ -113,9 +138,19 @@ pub struct HostMetricsConfig {
     #[serde(default)]
     pub filesystem: filesystem::FilesystemConfig,
 
+    #[cfg(target_os = "linux")]
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) gpu: gpu::GpuConfig,
+
     #[configurable(derived)]
     #[serde(default)]
     pub network: network::NetworkConfig,
+
+    #[cfg(target_os = "linux")]
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) process: process::ProcessConfig,
 }
 
 const fn default_scrape_interval() -> f64 {
@@ -191,7 +226,7 @@ impl HostMetricsConfig {
 
     fn has_collector(&self, collector: Collector) -> bool {
         match &self.collectors {
-            None => true,
+            None => collector.is_default(),
             Some(collectors) => collectors.iter().any(|&c| c == collector),
         }
     }
@@ -201,6 +236,8 @@ pub struct HostMetrics {
     config: HostMetricsConfig,
     #[cfg(target_os = "linux")]
     root_cgroup: Option<cgroups::CGroup>,
+    #[cfg(target_os = "linux")]
+    gpu: Option<gpu::GpuBackend>,
 }
 
 impl HostMetrics {
@@ -212,9 +249,13 @@ impl HostMetrics {
     #[cfg(target_os = "linux")]
     pub fn new(config: HostMetricsConfig) -> Self {
         let root_cgroup = cgroups::CGroup::root(config.cgroups.base.as_deref());
+        let gpu = config
+            .has_collector(Collector::Gpu)
+            .then(gpu::GpuBackend::new);
         Self {
             config,
             root_cgroup,
+            gpu,
         }
     }
 
@@ -235,6 +276,10 @@ impl HostMetrics {
         if self.config.has_collector(Collector::Filesystem) {
             metrics.extend(add_collector("filesystem", self.filesystem_metrics().await));
         }
+        #[cfg(target_os = "linux")]
+        if self.config.has_collector(Collector::Gpu) {
+            metrics.extend(add_collector("gpu", self.gpu_metrics().await));
+        }
         if self.config.has_collector(Collector::Load) {
             metrics.extend(add_collector("load", self.loadavg_metrics().await));
         }
@@ -248,6 +293,10 @@ impl HostMetrics {
         if self.config.has_collector(Collector::Network) {
             metrics.extend(add_collector("network", self.network_metrics().await));
         }
+        #[cfg(target_os = "linux")]
+        if self.config.has_collector(Collector::Process) {
+            metrics.extend(add_collector("process", self.process_metrics().await));
+        }
         if let Ok(hostname) = &hostname {
             for metric in &mut metrics {
                 metric.insert_tag("host".into(), hostname.into());
@@ -602,6 +651,8 @@ pub(self) mod tests {
             Collector::Cpu,
             Collector::Disk,
             Collector::Filesystem,
+            #[cfg(target_os = "linux")]
+            Collector::Gpu,
             Collector::Load,
             Collector::Host,
             Collector::Memory,
@@ -0,0 +1,343 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use nix::unistd::{Uid, User};
+use tokio::fs;
+use vector_common::btreemap;
+use vector_config::configurable_component;
+
+use super::{FilterList, HostMetrics};
+use crate::event::metric::Metric;
+
+/// The unit of the CPU times in `/proc/<pid>/stat`, `USER_HZ`, which is 100 on all the
+/// architectures supported by Linux.
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// Options for the “process” metrics collector.
+///
+/// This collector is only available on Linux systems. As it reports metrics for each process, it
+/// is only enabled when listed in `collectors`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(default)]
+pub(crate) struct ProcessConfig {
+    /// Lists of process name patterns to include or exclude.
+    ///
+    /// The name of a process is the file name of its executable, truncated to 15 characters.
+    names: FilterList,
+
+    /// Lists of command line patterns to include or exclude.
+    ///
+    /// The command line of a process is its arguments joined with spaces.
+    cmdlines: FilterList,
+
+    /// Lists of user name patterns to include or exclude.
+    ///
+    /// The processes of users without a name are matched on their user ID.
+    users: FilterList,
+}
+
+/// The statistics of a process read from procfs.
+///
+/// The statistics only readable by the owner of the process, or root, are optional.
+#[derive(Debug, Default, PartialEq)]
+struct ProcessStats {
+    name: String,
+    cmdline: String,
+    uid: u32,
+    user_ticks: u64,
+    system_ticks: u64,
+    rss_bytes: Option<u64>,
+    open_fds: Option<u64>,
+    read_bytes: Option<u64>,
+    written_bytes: Option<u64>,
+}
+
+impl HostMetrics {
+    pub async fn process_metrics(&self) -> Vec<Metric> {
+        let mut entries = match fs::read_dir(heim::os::linux::procfs_root()).await {
+            Ok(entries) => entries,
+            Err(error) => {
+                error!(message = "Failed to list processes.", %error, internal_log_rate_secs = 60);
+                return Vec::new();
+            }
+        };
+
+        let timestamp = Utc::now();
+        let config = &self.config.process;
+        let mut users = HashMap::new();
+        let mut metrics = Vec::new();
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(error) => {
+                    error!(message = "Failed to list processes.", %error, internal_log_rate_secs = 60);
+                    break;
+                }
+            };
+            let pid = match entry.file_name().to_str().map(str::parse::<u32>) {
+                Some(Ok(pid)) => pid,
+                _ => continue,
+            };
+            // Processes can exit while they're read.
+            let stats = match load_process(&entry.path()).await {
+                Some(stats) => stats,
+                None => continue,
+            };
+            let user = users
+                .entry(stats.uid)
+                .or_insert_with(|| user_name(stats.uid))
+                .clone();
+            if config.names.contains_str(Some(&stats.name))
+                && config.cmdlines.contains_str(Some(&stats.cmdline))
+                && config.users.contains_str(Some(&user))
+            {
+                let tags = btreemap! {
+                    "pid" => pid.to_string(),
+                    "name" => stats.name.clone(),
+                    "user" => user,
+                };
+                metrics.extend(self.single_process_metrics(timestamp, stats, tags));
+            }
+        }
+        metrics
+    }
+
+    fn single_process_metrics(
+        &self,
+        timestamp: DateTime<Utc>,
+        stats: ProcessStats,
+        tags: BTreeMap<String, String>,
+    ) -> Vec<Metric> {
+        let mut metrics = Vec::new();
+        for (mode, ticks) in [("user", stats.user_ticks), ("system", stats.system_ticks)] {
+            let mut tags = tags.clone();
+            tags.insert("mode".into(), mode.into());
+            metrics.push(self.counter(
+                "process_cpu_seconds_total",
+                timestamp,
+                ticks as f64 / CLOCK_TICKS_PER_SECOND,
+                tags,
+            ));
+        }
+        if let Some(rss_bytes) = stats.rss_bytes {
+            metrics.push(self.gauge(
+                "process_memory_rss_bytes",
+                timestamp,
+                rss_bytes as f64,
+                tags.clone(),
+            ));
+        }
+        if let Some(open_fds) = stats.open_fds {
+            metrics.push(self.gauge("process_open_fds", timestamp, open_fds as f64, tags.clone()));
+        }
+        if let Some(read_bytes) = stats.read_bytes {
+            metrics.push(self.counter(
+                "process_read_bytes_total",
+                timestamp,
+                read_bytes as f64,
+                tags.clone(),
+            ));
+        }
+        if let Some(written_bytes) = stats.written_bytes {
+            metrics.push(self.counter(
+                "process_written_bytes_total",
+                timestamp,
+                written_bytes as f64,
+                tags,
+            ));
+        }
+        metrics
+    }
+}
+
+async fn load_process(directory: &Path) -> Option<ProcessStats> {
+    let stat = fs::read_to_string(directory.join("stat")).await.ok()?;
+    let status = fs::read_to_string(directory.join("status")).await.ok()?;
+    let cmdline = fs::read(directory.join("cmdline")).await.ok()?;
+
+    let mut stats = parse_stat(&stat)?;
+    parse_status(&status, &mut stats)?;
+    stats.cmdline = parse_cmdline(&cmdline);
+    stats.open_fds = count_entries(&directory.join("fd")).await;
+    if let Ok(io) = fs::read_to_string(directory.join("io")).await {
+        parse_io(&io, &mut stats);
+    }
+    Some(stats)
+}
+
+async fn count_entries(directory: &Path) -> Option<u64> {
+    let mut entries = fs::read_dir(directory).await.ok()?;
+    let mut count = 0;
+    while entries.next_entry().await.ok()?.is_some() {
+        count += 1;
+    }
+    Some(count)
+}
+
+fn user_name(uid: u32) -> String {
+    match User::from_uid(Uid::from_raw(uid)) {
+        Ok(Some(user)) => user.name,
+        _ => uid.to_string(),
+    }
+}
+
+/// Parses the name and CPU times of `/proc/<pid>/stat`.
+fn parse_stat(stat: &str) -> Option<ProcessStats> {
+    // The name is between parentheses, and can itself contain spaces and parentheses.
+    let start = stat.find('(')?;
+    let end = stat.rfind(')')?;
+    let name = stat.get(start + 1..end)?.to_owned();
+    // The fields following the name start with the state, the third field.
+    let mut fields = stat.get(end + 1..)?.split_whitespace().skip(11);
+    let user_ticks = fields.next()?.parse().ok()?;
+    let system_ticks = fields.next()?.parse().ok()?;
+    Some(ProcessStats {
+        name,
+        user_ticks,
+        system_ticks,
+        ..Default::default()
+    })
+}
+
+/// Parses the real user ID of `/proc/<pid>/status`, and the resident memory, which kernel threads
+/// don't have.
+fn parse_status(status: &str, stats: &mut ProcessStats) -> Option<()> {
+    let mut uid = None;
+    for line in status.lines() {
+        if let Some(value) = line.strip_prefix("Uid:") {
+            uid = value.split_whitespace().next()?.parse().ok();
+        } else if let Some(value) = line.strip_prefix("VmRSS:") {
+            let kilobytes: u64 = value.trim().trim_end_matches("kB").trim().parse().ok()?;
+            stats.rss_bytes = Some(kilobytes * 1024);
+        }
+    }
+    stats.uid = uid?;
+    Some(())
+}
+
+fn parse_cmdline(cmdline: &[u8]) -> String {
+    cmdline
+        .split(|&byte| byte == 0)
+        .filter(|argument| !argument.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses the storage I/O of `/proc/<pid>/io`.
+fn parse_io(io: &str, stats: &mut ProcessStats) {
+    for line in io.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().parse().ok();
+            match key {
+                "read_bytes" => stats.read_bytes = value,
+                "write_bytes" => stats.written_bytes = value,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            tests::{all_counters, all_gauges, count_name},
+            Collector, HostMetrics, HostMetricsConfig, PatternWrapper,
+        },
+        *,
+    };
+
+    #[test]
+    fn parses_proc_files() {
+        let mut stats = parse_stat(
+            "1234 (tmux: server) S 1 1234 1234 0 -1 4194560 1417 0 0 0 250 75 0 0 20 0 1 0 \
+             3070 9068544 1068 18446744073709551615 1 1 0 0 0 0 0 4096 1260 0 0 0 17 3 0 0",
+        )
+        .unwrap();
+        parse_status(
+            "Name:\ttmux: server\nUmask:\t0022\nState:\tS (sleeping)\nUid:\t1000\t1000\t1000\t1000\n\
+             Gid:\t1000\t1000\t1000\t1000\nVmRSS:\t    4272 kB\n",
+            &mut stats,
+        )
+        .unwrap();
+        stats.cmdline = parse_cmdline(b"tmux\0new-session\0-d\0");
+        parse_io(
+            "rchar: 1948\nwchar: 0\nsyscr: 7\nsyscw: 0\nread_bytes: 8192\nwrite_bytes: 4096\n\
+             cancelled_write_bytes: 0\n",
+            &mut stats,
+        );
+
+        assert_eq!(
+            stats,
+            ProcessStats {
+                name: "tmux: server".into(),
+                cmdline: "tmux new-session -d".into(),
+                uid: 1000,
+                user_ticks: 250,
+                system_ticks: 75,
+                rss_bytes: Some(4272 * 1024),
+                open_fds: None,
+                read_bytes: Some(8192),
+                written_bytes: Some(4096),
+            }
+        );
+    }
+
+    #[test]
+    fn kernel_threads_have_no_rss() {
+        let mut stats = ProcessStats::default();
+        parse_status("Name:\tkthreadd\nUid:\t0\t0\t0\t0\n", &mut stats).unwrap();
+        assert_eq!(stats.rss_bytes, None);
+        assert!(parse_status("Name:\tkthreadd\n", &mut stats).is_none());
+    }
+
+    #[tokio::test]
+    async fn generates_process_metrics() {
+        let mut config = HostMetricsConfig {
+            collectors: Some(vec![Collector::Process]),
+            ..Default::default()
+        };
+        config.process.names.includes = Some(vec![PatternWrapper::new("vector*").unwrap()]);
+        let pid = std::process::id().to_string();
+        let metrics = HostMetrics::new(config).process_metrics().await;
+        let own_metrics = metrics
+            .iter()
+            .filter(|metric| metric.tags().unwrap().get("pid") == Some(&pid))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        assert_eq!(count_name(&own_metrics, "process_cpu_seconds_total"), 2);
+        assert_eq!(count_name(&own_metrics, "process_memory_rss_bytes"), 1);
+        assert_eq!(count_name(&own_metrics, "process_open_fds"), 1);
+        assert!(all_counters(
+            &own_metrics
+                .iter()
+                .filter(|metric| metric.name().ends_with("_total"))
+                .cloned()
+                .collect::<Vec<_>>()
+        ));
+        assert!(all_gauges(
+            &own_metrics
+                .iter()
+                .filter(|metric| !metric.name().ends_with("_total"))
+                .cloned()
+                .collect::<Vec<_>>()
+        ));
+    }
+
+    #[test]
+    fn is_not_a_default_collector() {
+        assert!(!HostMetricsConfig::default().has_collector(Collector::Process));
+        assert!(HostMetricsConfig {
+            collectors: Some(vec![Collector::Process]),
+            ..Default::default()
+        }
+        .has_collector(Collector::Process));
+    }
+}
@@ -0,0 +1,391 @@
+//! Requests and responses of the [Jolokia protocol](https://jolokia.org/reference/html/protocol.html),
+//! and the conversion of MBean attributes into metrics.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::{BeanConfig, JolokiaTargetConfig, MetricType};
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("invalid ObjectName {:?}", name))]
+    InvalidObjectName { name: String },
+    #[snafu(display("reading {:?} failed with status {}: {}", mbean, status, error))]
+    ReadFailed {
+        mbean: String,
+        status: u16,
+        error: String,
+    },
+}
+
+/// A bulk read request for a bean.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReadRequest<'a> {
+    r#type: &'static str,
+    mbean: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attribute: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<&'a JolokiaTargetConfig>,
+}
+
+impl<'a> ReadRequest<'a> {
+    pub fn new(bean: &'a BeanConfig, target: Option<&'a JolokiaTargetConfig>) -> Self {
+        Self {
+            r#type: "read",
+            mbean: &bean.mbean,
+            // Attributes are always requested as a list, so that values are always keyed by
+            // attribute name.
+            attribute: (!bean.attributes.is_empty()).then(|| bean.attributes.as_slice()),
+            target,
+        }
+    }
+}
+
+/// A response to a read request.
+#[derive(Debug, Deserialize)]
+pub struct ReadResponse {
+    status: u16,
+    #[serde(default)]
+    value: serde_json::Value,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A MBean attribute converted into a metric.
+#[derive(Debug, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub tags: BTreeMap<String, String>,
+    pub value: f64,
+    pub metric_type: MetricType,
+}
+
+/// Converts the responses of the read requests of `beans`, in the same order, into samples.
+pub fn parse_responses(
+    beans: &[BeanConfig],
+    responses: Vec<ReadResponse>,
+) -> Result<Vec<Sample>, ParseError> {
+    let mut samples = Vec::new();
+    for (bean, response) in beans.iter().zip(responses) {
+        if response.status != 200 {
+            return Err(ParseError::ReadFailed {
+                mbean: bean.mbean.clone(),
+                status: response.status,
+                error: response.error.unwrap_or_default(),
+            });
+        }
+
+        // Reading a pattern returns the attributes of each matching bean, keyed by name.
+        let values = if is_pattern(&bean.mbean) {
+            match response.value {
+                serde_json::Value::Object(beans) => beans.into_iter().collect(),
+                _ => vec![],
+            }
+        } else {
+            vec![(bean.mbean.clone(), response.value)]
+        };
+
+        for (name, attributes) in values {
+            let name = ObjectName::parse(&name)?;
+            let attributes = match attributes {
+                serde_json::Value::Object(attributes) => attributes,
+                _ => continue,
+            };
+            for (attribute, value) in attributes {
+                if bean.exclude_attributes.contains(&attribute) {
+                    continue;
+                }
+                name.samples(bean, &attribute, &value, &mut samples);
+            }
+        }
+    }
+    Ok(samples)
+}
+
+fn is_pattern(mbean: &str) -> bool {
+    mbean.contains('*') || mbean.contains('?')
+}
+
+/// A parsed `domain:key=value,...` MBean ObjectName.
+#[derive(Debug, PartialEq)]
+pub struct ObjectName {
+    domain: String,
+    properties: Vec<(String, String)>,
+}
+
+impl ObjectName {
+    pub fn parse(name: &str) -> Result<Self, ParseError> {
+        let invalid = || ParseError::InvalidObjectName {
+            name: name.to_owned(),
+        };
+        let (domain, rest) = name.split_once(':').ok_or_else(invalid)?;
+
+        let mut properties = Vec::new();
+        let mut chars = rest.chars().peekable();
+        while chars.peek().is_some() {
+            let key = chars.by_ref().take_while(|&c| c != '=').collect::<String>();
+            let mut value = String::new();
+            if chars.peek() == Some(&'"') {
+                // Quoted values may contain commas and escaped characters.
+                chars.next();
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    match c {
+                        _ if escaped => {
+                            value.push(c);
+                            escaped = false;
+                        }
+                        '\\' => escaped = true,
+                        '"' => break,
+                        c => value.push(c),
+                    }
+                }
+                match chars.next() {
+                    None | Some(',') => {}
+                    Some(_) => return Err(invalid()),
+                }
+            } else {
+                value = chars.by_ref().take_while(|&c| c != ',').collect();
+            }
+            if key.is_empty() {
+                return Err(invalid());
+            }
+            properties.push((key, value));
+        }
+
+        Ok(Self {
+            domain: domain.to_owned(),
+            properties,
+        })
+    }
+
+    /// The metric name prefix, made of the domain and the `type` property.
+    fn prefix(&self) -> String {
+        match self.properties.iter().find(|(key, _)| key == "type") {
+            Some((_, kind)) => format!("{}_{}", sanitize(&self.domain), sanitize(kind)),
+            None => sanitize(&self.domain),
+        }
+    }
+
+    /// The tags, made of the properties besides `type`.
+    fn tags(&self) -> BTreeMap<String, String> {
+        self.properties
+            .iter()
+            .filter(|(key, _)| key != "type")
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Converts an attribute into samples, flattening composite values by appending their keys
+    /// to the name. Values which are not numbers or booleans are skipped.
+    fn samples(
+        &self,
+        bean: &BeanConfig,
+        attribute: &str,
+        value: &serde_json::Value,
+        samples: &mut Vec<Sample>,
+    ) {
+        let name = format!("{}_{}", self.prefix(), sanitize(attribute));
+        self.flatten(bean, name, value, samples);
+    }
+
+    fn flatten(
+        &self,
+        bean: &BeanConfig,
+        name: String,
+        value: &serde_json::Value,
+        samples: &mut Vec<Sample>,
+    ) {
+        let value = match value {
+            serde_json::Value::Number(number) => number.as_f64(),
+            serde_json::Value::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            serde_json::Value::Object(fields) => {
+                for (key, value) in fields {
+                    self.flatten(bean, format!("{}_{}", name, sanitize(key)), value, samples);
+                }
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(value) = value {
+            samples.push(Sample {
+                name,
+                tags: self.tags(),
+                value,
+                metric_type: bean.metric_type,
+            });
+        }
+    }
+}
+
+/// Converts a name into `snake_case`, replacing the characters not allowed in metric names.
+fn sanitize(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if previous_lowercase {
+                sanitized.push('_');
+            }
+            sanitized.push(c.to_ascii_lowercase());
+            previous_lowercase = false;
+        } else if c.is_ascii_alphanumeric() {
+            sanitized.push(c);
+            previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !sanitized.ends_with('_') {
+                sanitized.push('_');
+            }
+            previous_lowercase = false;
+        }
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn bean(mbean: &str) -> BeanConfig {
+        BeanConfig {
+            mbean: mbean.into(),
+            attributes: vec![],
+            exclude_attributes: vec![],
+            metric_type: MetricType::Gauge,
+        }
+    }
+
+    #[test]
+    fn parses_object_names() {
+        assert_eq!(
+            ObjectName::parse(
+                r#"kafka.server:type=BrokerTopicMetrics,name=MessagesInPerSec,topic="a,b""#
+            )
+            .unwrap(),
+            ObjectName {
+                domain: "kafka.server".into(),
+                properties: vec![
+                    ("type".into(), "BrokerTopicMetrics".into()),
+                    ("name".into(), "MessagesInPerSec".into()),
+                    ("topic".into(), "a,b".into()),
+                ],
+            }
+        );
+        assert!(ObjectName::parse("java.lang").is_err());
+        assert!(ObjectName::parse("java.lang:=Memory").is_err());
+    }
+
+    #[test]
+    fn sanitizes_names() {
+        assert_eq!(sanitize("HeapMemoryUsage"), "heap_memory_usage");
+        assert_eq!(sanitize("java.lang"), "java_lang");
+        assert_eq!(sanitize("OneMinuteRate"), "one_minute_rate");
+        assert_eq!(sanitize("G1 Young Generation"), "g1_young_generation");
+    }
+
+    #[test]
+    fn serializes_read_requests() {
+        let mut bean = bean("java.lang:type=Memory");
+        bean.attributes = vec!["HeapMemoryUsage".into()];
+        let target = JolokiaTargetConfig {
+            url: "service:jmx:rmi:///jndi/rmi://kafka:9999/jmxrmi".into(),
+            user: None,
+            password: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(ReadRequest::new(&bean, Some(&target))).unwrap(),
+            json!({
+                "type": "read",
+                "mbean": "java.lang:type=Memory",
+                "attribute": ["HeapMemoryUsage"],
+                "target": { "url": "service:jmx:rmi:///jndi/rmi://kafka:9999/jmxrmi" },
+            })
+        );
+    }
+
+    #[test]
+    fn converts_responses_into_samples() {
+        let mut counter_bean = bean("kafka.server:type=BrokerTopicMetrics,name=*");
+        counter_bean.metric_type = MetricType::Counter;
+        counter_bean.exclude_attributes = vec!["RateUnit".into()];
+        let beans = vec![bean("java.lang:type=Memory"), counter_bean];
+
+        let responses = serde_json::from_value(json!([
+            {
+                "status": 200,
+                "value": {
+                    "HeapMemoryUsage": { "used": 1024, "max": 4096 },
+                    "Verbose": false,
+                    "ObjectName": { "objectName": "java.lang:type=Memory" },
+                },
+            },
+            {
+                "status": 200,
+                "value": {
+                    "kafka.server:name=MessagesInPerSec,type=BrokerTopicMetrics": {
+                        "Count": 42,
+                        "RateUnit": "SECONDS",
+                    },
+                },
+            },
+        ]))
+        .unwrap();
+
+        let mut samples = parse_responses(&beans, responses).unwrap();
+        samples.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            samples,
+            vec![
+                Sample {
+                    name: "java_lang_memory_heap_memory_usage_max".into(),
+                    tags: BTreeMap::new(),
+                    value: 4096.0,
+                    metric_type: MetricType::Gauge,
+                },
+                Sample {
+                    name: "java_lang_memory_heap_memory_usage_used".into(),
+                    tags: BTreeMap::new(),
+                    value: 1024.0,
+                    metric_type: MetricType::Gauge,
+                },
+                Sample {
+                    name: "java_lang_memory_verbose".into(),
+                    tags: BTreeMap::new(),
+                    value: 0.0,
+                    metric_type: MetricType::Gauge,
+                },
+                Sample {
+                    name: "kafka_server_broker_topic_metrics_count".into(),
+                    tags: BTreeMap::from([("name".into(), "MessagesInPerSec".into())]),
+                    value: 42.0,
+                    metric_type: MetricType::Counter,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_failed_reads() {
+        let responses = serde_json::from_value(json!([{
+            "status": 404,
+            "error": "javax.management.InstanceNotFoundException : java.lang:type=Missing",
+        }]))
+        .unwrap();
+
+        assert_eq!(
+            parse_responses(&[bean("java.lang:type=Missing")], responses),
+            Err(ParseError::ReadFailed {
+                mbean: "java.lang:type=Missing".into(),
+                status: 404,
+                error: "javax.management.InstanceNotFoundException : java.lang:type=Missing".into(),
+            })
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{future::join_all, StreamExt, TryFutureExt};
+use http::{header, Request, StatusCode};
+use hyper::{body::to_bytes as body_to_bytes, Body, Uri};
+use snafu::{ResultExt, Snafu};
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    http::{Auth, HttpClient},
+    internal_events::{
+        CollectionCompleted, EndpointBytesReceived, JmxMetricsEventsReceived, JmxMetricsParseError,
+        JmxMetricsRequestError, StreamClosedError,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+pub mod jolokia;
+use jolokia::{ReadRequest, ReadResponse, Sample};
+
+#[derive(Debug, Snafu)]
+enum JmxMetricsBuildError {
+    #[snafu(display("Failed to parse endpoint: {}", source))]
+    HostInvalidUri { source: http::uri::InvalidUri },
+    #[snafu(display("At least one bean must be configured"))]
+    NoBeans,
+}
+
+#[derive(Debug, Snafu)]
+enum JmxMetricsError {
+    #[snafu(display("Invalid response status: {}", status))]
+    InvalidResponseStatus { status: StatusCode },
+}
+
+/// Configuration for the `jmx_metrics` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JmxMetricsConfig {
+    /// A list of Jolokia agent endpoints to scrape.
+    ///
+    /// Each endpoint must be a valid HTTP/HTTPS URI pointing to a [Jolokia][jolokia] agent, such as
+    /// `http://localhost:8778/jolokia`. The agent can either run inside the JVM to scrape, or as a
+    /// proxy in front of JVMs only exposing JMX over RMI, in which case `target` must be set.
+    ///
+    /// [jolokia]: https://jolokia.org/
+    endpoints: Vec<String>,
+
+    /// The interval between scrapes, in seconds.
+    #[serde(default = "default_scrape_interval_secs")]
+    scrape_interval_secs: u64,
+
+    /// Overrides the default namespace for the metrics emitted by the source.
+    ///
+    /// If set to an empty string, no namespace is added to the metrics.
+    ///
+    /// By default, `jmx` is used.
+    #[serde(default = "default_namespace")]
+    namespace: String,
+
+    /// The MBeans to collect attributes from.
+    #[serde(default = "default_beans")]
+    beans: Vec<BeanConfig>,
+
+    #[configurable(derived)]
+    target: Option<JolokiaTargetConfig>,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    auth: Option<Auth>,
+}
+
+/// An MBean filtering rule.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BeanConfig {
+    /// The ObjectName of the MBean.
+    ///
+    /// Patterns such as `kafka.server:type=BrokerTopicMetrics,name=*` can be used to collect the
+    /// attributes of all the matching MBeans.
+    mbean: String,
+
+    /// The attributes to collect.
+    ///
+    /// If empty, all the attributes of the MBean are collected.
+    #[serde(default)]
+    attributes: Vec<String>,
+
+    /// The attributes not to collect.
+    #[serde(default)]
+    exclude_attributes: Vec<String>,
+
+    /// The type of the metrics the attributes are mapped into.
+    #[serde(default)]
+    metric_type: MetricType,
+}
+
+/// The type of the metrics MBean attributes are mapped into.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricType {
+    /// The attributes are mapped into gauges.
+    #[derivative(Default)]
+    Gauge,
+
+    /// The attributes are mapped into counters, for monotonically increasing values.
+    Counter,
+}
+
+/// The JVM to scrape through a Jolokia agent running in proxy mode.
+///
+/// This allows scraping JVMs which only expose JMX over RMI.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct JolokiaTargetConfig {
+    /// The JMX service URL of the JVM.
+    ///
+    /// For example, `service:jmx:rmi:///jndi/rmi://kafka:9999/jmxrmi`.
+    url: String,
+
+    /// The JMX username.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+
+    /// The JMX password.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+impl Default for JmxMetricsConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: vec!["http://localhost:8778/jolokia".to_owned()],
+            scrape_interval_secs: default_scrape_interval_secs(),
+            namespace: default_namespace(),
+            beans: default_beans(),
+            target: None,
+            tls: None,
+            auth: None,
+        }
+    }
+}
+
+const fn default_scrape_interval_secs() -> u64 {
+    15
+}
+
+fn default_namespace() -> String {
+    "jmx".to_string()
+}
+
+fn default_beans() -> Vec<BeanConfig> {
+    ["java.lang:type=Memory", "java.lang:type=Threading"]
+        .into_iter()
+        .map(|mbean| BeanConfig {
+            mbean: mbean.to_owned(),
+            attributes: vec![],
+            exclude_attributes: vec![],
+            metric_type: MetricType::Gauge,
+        })
+        .collect()
+}
+
+inventory::submit! {
+    SourceDescription::new::<JmxMetricsConfig>("jmx_metrics")
+}
+
+impl_generate_config_from_default!(JmxMetricsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "jmx_metrics")]
+impl SourceConfig for JmxMetricsConfig {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<super::Source> {
+        if self.beans.is_empty() {
+            return Err(Box::new(JmxMetricsBuildError::NoBeans));
+        }
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let http_client = HttpClient::new(tls, &cx.proxy)?;
+
+        let requests = self
+            .beans
+            .iter()
+            .map(|bean| ReadRequest::new(bean, self.target.as_ref()))
+            .collect::<Vec<_>>();
+        let body = Bytes::from(serde_json::to_vec(&requests)?);
+
+        let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let mut sources = Vec::with_capacity(self.endpoints.len());
+        for endpoint in self.endpoints.iter() {
+            sources.push(JmxMetrics::new(
+                http_client.clone(),
+                endpoint.clone(),
+                self.auth.clone(),
+                namespace.clone(),
+                self.beans.clone(),
+                body.clone(),
+            )?);
+        }
+
+        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let mut interval = IntervalStream::new(time::interval(duration)).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let start = Instant::now();
+                let metrics = join_all(sources.iter().map(|jmx| jmx.collect())).await;
+                emit!(CollectionCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let metrics = metrics.into_iter().flatten().collect::<Vec<_>>();
+                let count = metrics.len();
+
+                if let Err(error) = cx.out.send_batch(metrics).await {
+                    emit!(StreamClosedError { error, count });
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "jmx_metrics"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+struct JmxMetrics {
+    http_client: HttpClient,
+    endpoint: String,
+    auth: Option<Auth>,
+    namespace: Option<String>,
+    beans: Vec<BeanConfig>,
+    body: Bytes,
+    tags: BTreeMap<String, String>,
+}
+
+impl JmxMetrics {
+    fn new(
+        http_client: HttpClient,
+        endpoint: String,
+        auth: Option<Auth>,
+        namespace: Option<String>,
+        beans: Vec<BeanConfig>,
+        body: Bytes,
+    ) -> crate::Result<Self> {
+        let mut tags = BTreeMap::new();
+        tags.insert("endpoint".into(), endpoint.clone());
+        tags.insert("host".into(), Self::get_endpoint_host(&endpoint)?);
+
+        Ok(Self {
+            http_client,
+            endpoint,
+            auth,
+            namespace,
+            beans,
+            body,
+            tags,
+        })
+    }
+
+    fn get_endpoint_host(endpoint: &str) -> crate::Result<String> {
+        let uri: Uri = endpoint.parse().context(HostInvalidUriSnafu)?;
+        Ok(match (uri.host().unwrap_or(""), uri.port()) {
+            (host, None) => host.to_owned(),
+            (host, Some(port)) => format!("{}:{}", host, port),
+        })
+    }
+
+    async fn collect(&self) -> Vec<Metric> {
+        let (up_value, mut metrics) = match self.collect_metrics().await {
+            Ok(metrics) => (1.0, metrics),
+            Err(()) => (0.0, vec![]),
+        };
+
+        let byte_size = metrics.size_of();
+
+        metrics.push(self.create_metric(
+            "up".to_owned(),
+            BTreeMap::new(),
+            MetricValue::Gauge { value: up_value },
+        ));
+
+        emit!(JmxMetricsEventsReceived {
+            count: metrics.len(),
+            byte_size,
+            endpoint: &self.endpoint
+        });
+
+        metrics
+    }
+
+    async fn collect_metrics(&self) -> Result<Vec<Metric>, ()> {
+        let response = self.get_jolokia_response().await.map_err(|error| {
+            emit!(JmxMetricsRequestError {
+                error,
+                endpoint: &self.endpoint,
+            })
+        })?;
+        emit!(EndpointBytesReceived {
+            byte_size: response.len(),
+            protocol: "http",
+            endpoint: &self.endpoint,
+        });
+
+        let samples = serde_json::from_slice::<Vec<ReadResponse>>(&response)
+            .map_err(|error| {
+                emit!(JmxMetricsParseError {
+                    error,
+                    endpoint: &self.endpoint,
+                })
+            })
+            .and_then(|responses| {
+                jolokia::parse_responses(&self.beans, responses).map_err(|error| {
+                    emit!(JmxMetricsParseError {
+                        error,
+                        endpoint: &self.endpoint,
+                    })
+                })
+            })?;
+
+        Ok(samples
+            .into_iter()
+            .map(|sample| self.create_sample_metric(sample))
+            .collect())
+    }
+
+    async fn get_jolokia_response(&self) -> crate::Result<Bytes> {
+        let mut request = Request::post(&self.endpoint)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(self.body.clone()))?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.http_client.send(request).await?;
+        let (parts, body) = response.into_parts();
+        match parts.status {
+            StatusCode::OK => body_to_bytes(body).err_into().await,
+            status => Err(Box::new(JmxMetricsError::InvalidResponseStatus { status })),
+        }
+    }
+
+    fn create_sample_metric(&self, sample: Sample) -> Metric {
+        let value = match sample.metric_type {
+            MetricType::Gauge => MetricValue::Gauge {
+                value: sample.value,
+            },
+            MetricType::Counter => MetricValue::Counter {
+                value: sample.value,
+            },
+        };
+        self.create_metric(sample.name, sample.tags, value)
+    }
+
+    fn create_metric(
+        &self,
+        name: String,
+        tags: BTreeMap<String, String>,
+        value: MetricValue,
+    ) -> Metric {
+        let mut tags = tags;
+        tags.extend(self.tags.clone());
+        Metric::new(name, MetricKind::Absolute, value)
+            .with_namespace(self.namespace.clone())
+            .with_tags(Some(tags))
+            .with_timestamp(Some(Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time::Duration;
+    use warp::Filter;
+
+    use super::*;
+    use crate::test_util::{
+        components::{run_and_assert_source_compliance, HTTP_PULL_SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JmxMetricsConfig>();
+    }
+
+    #[tokio::test]
+    async fn scrapes_jolokia() {
+        let address = next_addr();
+
+        let dummy_endpoint = warp::path!("jolokia")
+            .and(warp::post())
+            .and(warp::body::json())
+            .map(|requests: serde_json::Value| {
+                assert_eq!(
+                    requests,
+                    serde_json::json!([{
+                        "type": "read",
+                        "mbean": "java.lang:type=Memory",
+                        "attribute": ["HeapMemoryUsage"],
+                    }])
+                );
+                warp::reply::json(&serde_json::json!([{
+                    "request": requests[0],
+                    "status": 200,
+                    "timestamp": 1660000000,
+                    "value": { "HeapMemoryUsage": { "used": 1024, "max": 4096 } },
+                }]))
+            });
+
+        tokio::spawn(warp::serve(dummy_endpoint).run(address));
+
+        let endpoint = format!("http://{}/jolokia", address);
+        let config = JmxMetricsConfig {
+            endpoints: vec![endpoint.clone()],
+            scrape_interval_secs: 1,
+            beans: vec![BeanConfig {
+                mbean: "java.lang:type=Memory".to_owned(),
+                attributes: vec!["HeapMemoryUsage".to_owned()],
+                exclude_attributes: vec![],
+                metric_type: MetricType::Gauge,
+            }],
+            ..Default::default()
+        };
+
+        let events = run_and_assert_source_compliance(
+            config,
+            Duration::from_secs(1),
+            &HTTP_PULL_SOURCE_TAGS,
+        )
+        .await;
+        assert!(!events.is_empty());
+
+        let metrics = events
+            .into_iter()
+            .map(|event| event.into_metric())
+            .collect::<Vec<_>>();
+        let used = metrics
+            .iter()
+            .find(|metric| metric.name() == "java_lang_memory_heap_memory_usage_used")
+            .expect("missing heap usage metric");
+        assert_eq!(used.namespace(), Some("jmx"));
+        assert_eq!(used.value(), &MetricValue::Gauge { value: 1024.0 });
+        assert_eq!(used.tag_value("endpoint"), Some(endpoint));
+        let up = metrics
+            .iter()
+            .find(|metric| metric.name() == "up")
+            .expect("missing up metric");
+        assert_eq!(up.value(), &MetricValue::Gauge { value: 1.0 });
+    }
+}
@@ -1,6 +1,5 @@
 use std::{
     collections::{HashMap, HashSet},
-    io::SeekFrom,
     iter::FromIterator,
     path::PathBuf,
     process::Stdio,
@@ -23,8 +22,7 @@ use once_cell::sync::Lazy;
 use serde_json::{Error as JsonError, Value as JsonValue};
 use snafu::{ResultExt, Snafu};
 use tokio::{
-    fs::{File, OpenOptions},
-    io::{self, AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    io,
     process::{Child, Command},
     sync::{oneshot, Mutex, MutexGuard},
     time::sleep,
@@ -45,6 +43,7 @@ use crate::{
     },
     serde::bool_or_struct,
     shutdown::ShutdownSignal,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
     SourceSender,
 };
 
@@ -80,6 +79,10 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("Cannot use both `journal_directory` and `journal_files`"))]
+    BothDirectoryAndFiles,
+    #[snafu(display("Cannot use `namespace` along with `journal_directory` or `journal_files`"))]
+    NamespaceWithJournalPaths,
 }
 
 type Matches = HashMap<String, HashSet<String>>;
@@ -127,6 +130,11 @@ pub struct JournaldConfig {
     /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
     pub data_dir: Option<PathBuf>,
 
+    /// Where to persist the cursor of the last entry read.
+    ///
+    /// By default, it's persisted in a file of `data_dir`.
+    pub checkpoint_store: CheckpointStoreConfig,
+
     /// The `systemd` journal is read in batches, and a checkpoint is set at the end of each batch. This option limits the size of the batch.
     pub batch_size: Option<usize>,
 
@@ -140,6 +148,24 @@ pub struct JournaldConfig {
     /// If not set, `journalctl` will use the default system journal paths.
     pub journal_directory: Option<PathBuf>,
 
+    /// A list of journal files to read, such as journals exported from other hosts.
+    ///
+    /// The paths may contain glob patterns. Can't be used along with `journal_directory`.
+    pub journal_files: Vec<PathBuf>,
+
+    /// The journal namespace to read.
+    ///
+    /// Use `*` to read all namespaces, or a namespace prefixed with `+` to read both that namespace and the default
+    /// one. Each namespace has its own checkpoint, so that changing it doesn't resume from a cursor of another
+    /// namespace.
+    ///
+    /// If not set, only the default namespace is read.
+    pub namespace: Option<String>,
+
+    /// Whether to read the journals of all hosts, such as those uploaded by `systemd-journal-remote`, rather than only
+    /// those of the local host.
+    pub merge: bool,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
@@ -196,11 +222,6 @@ impl SourceConfig for JournaldConfig {
             warn!("Option `remap_priority` has been deprecated. Please use the `remap` transform and function `to_syslog_level` instead.");
         }
 
-        let data_dir = cx
-            .globals
-            // source are only global, name can be used for subdir
-            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
-
         if let Some(unit) = self
             .include_units
             .iter()
@@ -217,8 +238,23 @@ impl SourceConfig for JournaldConfig {
             return Err(BuildError::DuplicatedMatches { field, value }.into());
         }
 
-        let mut checkpoint_path = data_dir;
-        checkpoint_path.push(CHECKPOINT_FILENAME);
+        if self.journal_directory.is_some() && !self.journal_files.is_empty() {
+            return Err(BuildError::BothDirectoryAndFiles.into());
+        }
+        if self.namespace.is_some()
+            && (self.journal_directory.is_some() || !self.journal_files.is_empty())
+        {
+            return Err(BuildError::NamespaceWithJournalPaths.into());
+        }
+
+        let checkpoint_store = self
+            .checkpoint_store
+            .build(
+                &cx,
+                self.data_dir.as_ref(),
+                &checkpoint_filename(self.namespace.as_deref()),
+            )
+            .await?;
 
         let journalctl_path = self
             .journalctl_path
@@ -227,7 +263,12 @@ impl SourceConfig for JournaldConfig {
 
         let starter = StartJournalctl::new(
             journalctl_path,
-            self.journal_directory.clone(),
+            JournalSelection {
+                directory: self.journal_directory.clone(),
+                files: self.journal_files.clone(),
+                namespace: self.namespace.clone(),
+                merge: self.merge,
+            },
             self.current_boot_only.unwrap_or(true),
             self.since_now.unwrap_or(false),
         );
@@ -239,7 +280,7 @@ impl SourceConfig for JournaldConfig {
             JournaldSource {
                 include_matches,
                 exclude_matches,
-                checkpoint_path,
+                checkpoint_store,
                 batch_size,
                 remap_priority: self.remap_priority,
                 out: cx.out,
@@ -266,7 +307,7 @@ impl SourceConfig for JournaldConfig {
 struct JournaldSource {
     include_matches: Matches,
     exclude_matches: Matches,
-    checkpoint_path: PathBuf,
+    checkpoint_store: CheckpointStore,
     batch_size: usize,
     remap_priority: bool,
     out: SourceSender,
@@ -276,12 +317,12 @@ struct JournaldSource {
 
 impl JournaldSource {
     async fn run_shutdown(self, shutdown: ShutdownSignal) -> Result<(), ()> {
-        let checkpointer = StatefulCheckpointer::new(self.checkpoint_path.clone())
+        let checkpointer = StatefulCheckpointer::new(self.checkpoint_store.clone())
             .await
             .map_err(|error| {
                 error!(
-                    message = "Unable to open checkpoint file.",
-                    path = ?self.checkpoint_path,
+                    message = "Unable to load checkpoint.",
+                    location = %self.checkpoint_store.location(),
                     %error,
                 );
             })?;
@@ -477,9 +518,18 @@ impl<'a> Batch<'a> {
 
 type JournalStream = BoxStream<'static, Result<Bytes, BoxedFramingError>>;
 
+/// The journals read by `journalctl`.
+#[derive(Default)]
+struct JournalSelection {
+    directory: Option<PathBuf>,
+    files: Vec<PathBuf>,
+    namespace: Option<String>,
+    merge: bool,
+}
+
 struct StartJournalctl {
     path: PathBuf,
-    journal_dir: Option<PathBuf>,
+    journals: JournalSelection,
     current_boot_only: bool,
     since_now: bool,
 }
@@ -487,13 +537,13 @@ struct StartJournalctl {
 impl StartJournalctl {
     const fn new(
         path: PathBuf,
-        journal_dir: Option<PathBuf>,
+        journals: JournalSelection,
         current_boot_only: bool,
         since_now: bool,
     ) -> Self {
         Self {
             path,
-            journal_dir,
+            journals,
             current_boot_only,
             since_now,
         }
@@ -507,9 +557,18 @@ impl StartJournalctl {
         command.arg("--show-cursor");
         command.arg("--output=json");
 
-        if let Some(dir) = &self.journal_dir {
+        if let Some(dir) = &self.journals.directory {
             command.arg(format!("--directory={}", dir.display()));
         }
+        for file in &self.journals.files {
+            command.arg(format!("--file={}", file.display()));
+        }
+        if let Some(namespace) = &self.journals.namespace {
+            command.arg(format!("--namespace={}", namespace));
+        }
+        if self.journals.merge {
+            command.arg("--merge");
+        }
 
         if self.current_boot_only {
             command.arg("--boot");
@@ -584,6 +643,28 @@ fn create_event(record: Record, batch: &Option<BatchNotifier>) -> LogEvent {
     log
 }
 
+/// The name of the checkpoint file of a journal namespace, so that each namespace is read from its
+/// own cursor.
+fn checkpoint_filename(namespace: Option<&str>) -> String {
+    match namespace {
+        None => CHECKPOINT_FILENAME.into(),
+        Some(namespace) => {
+            let namespace = namespace
+                .chars()
+                .map(|c| match c {
+                    '*' => "all".into(),
+                    '+' => "default+".into(),
+                    c if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' => {
+                        c.to_string()
+                    }
+                    _ => "_".into(),
+                })
+                .collect::<String>();
+            format!("checkpoint-{}.txt", namespace)
+        }
+    }
+}
+
 /// Map the given unit name into a valid systemd unit
 /// by appending ".service" if no extension is present.
 fn fixup_unit(unit: &str) -> String {
@@ -740,33 +821,22 @@ impl Finalizer {
 }
 
 struct Checkpointer {
-    file: File,
-    filename: PathBuf,
+    store: CheckpointStore,
 }
 
 impl Checkpointer {
-    async fn new(filename: PathBuf) -> Result<Self, io::Error> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(&filename)
-            .await?;
-        Ok(Checkpointer { file, filename })
+    const fn new(store: CheckpointStore) -> Self {
+        Checkpointer { store }
     }
 
-    async fn set(&mut self, token: &str) -> Result<(), io::Error> {
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file.write_all(format!("{}\n", token).as_bytes()).await
+    async fn set(&mut self, token: &str) -> crate::Result<()> {
+        self.store.save(format!("{}\n", token).into_bytes()).await
     }
 
-    async fn get(&mut self) -> Result<Option<String>, io::Error> {
-        let mut buf = Vec::<u8>::new();
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file.read_to_end(&mut buf).await?;
-        match buf.len() {
-            0 => Ok(None),
-            _ => {
+    async fn get(&mut self) -> crate::Result<Option<String>> {
+        match self.store.load().await? {
+            None => Ok(None),
+            Some(buf) => {
                 let text = String::from_utf8_lossy(&buf);
                 match text.find('\n') {
                     Some(nl) => Ok(Some(String::from(&text[..nl]))),
@@ -783,8 +853,8 @@ struct StatefulCheckpointer {
 }
 
 impl StatefulCheckpointer {
-    async fn new(filename: PathBuf) -> Result<Self, io::Error> {
-        let mut checkpointer = Checkpointer::new(filename).await?;
+    async fn new(store: CheckpointStore) -> crate::Result<Self> {
+        let mut checkpointer = Checkpointer::new(store);
         let cursor = checkpointer.get().await?;
         Ok(Self {
             checkpointer,
@@ -797,7 +867,7 @@ impl StatefulCheckpointer {
             error!(
                 message = "Could not set journald checkpoint.",
                 %error,
-                filename = ?self.checkpointer.filename,
+                location = %self.checkpointer.store.location(),
             );
         }
         self.cursor = Some(token);
@@ -834,9 +904,7 @@ mod checkpointer_tests {
         let tempdir = tempdir().unwrap();
         let mut filename = tempdir.path().to_path_buf();
         filename.push(CHECKPOINT_FILENAME);
-        let mut checkpointer = Checkpointer::new(filename.clone())
-            .await
-            .expect("Creating checkpointer failed!");
+        let mut checkpointer = Checkpointer::new(CheckpointStore::file(filename.clone()));
 
         assert!(checkpointer.get().await.unwrap().is_none());
 
@@ -902,9 +970,8 @@ mod tests {
                 fs::create_dir(&checkpoint_path).unwrap();
                 checkpoint_path.push(CHECKPOINT_FILENAME);
 
-                let mut checkpointer = Checkpointer::new(checkpoint_path.clone())
-                    .await
-                    .expect("Creating checkpointer failed!");
+                let mut checkpointer =
+                    Checkpointer::new(CheckpointStore::file(checkpoint_path.clone()));
 
                 checkpointer
                     .set(cursor)
@@ -1110,9 +1177,7 @@ mod tests {
         fs::create_dir(&checkpoint_path).unwrap();
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
-        let mut checkpointer = Checkpointer::new(checkpoint_path.clone())
-            .await
-            .expect("Creating checkpointer failed!");
+        let mut checkpointer = Checkpointer::new(CheckpointStore::file(checkpoint_path.clone()));
 
         let config = JournaldConfig {
             journalctl_path: Some(TEST_JOURNALCTL.into()),
@@ -1242,6 +1307,9 @@ mod tests {
         let command = create_command(&path, journal_dir, current_boot_only, since_now, cursor);
         let cmd_line = format!("{:?}", command);
         assert!(!cmd_line.contains("--directory="));
+        assert!(!cmd_line.contains("--file="));
+        assert!(!cmd_line.contains("--namespace="));
+        assert!(!cmd_line.contains("--merge"));
         assert!(!cmd_line.contains("--boot"));
         assert!(cmd_line.contains("--since=2000-01-01"));
 
@@ -1261,6 +1329,76 @@ mod tests {
         assert!(cmd_line.contains("--directory=/tmp/journal-dir"));
         assert!(cmd_line.contains("--boot"));
         assert!(cmd_line.contains("--after-cursor="));
+
+        let journals = JournalSelection {
+            files: vec!["/tmp/exported/*.journal".into()],
+            merge: true,
+            ..Default::default()
+        };
+        let command = StartJournalctl::new(path.clone(), journals, false, false).make_command(None);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--file=/tmp/exported/*.journal"));
+        assert!(cmd_line.contains("--merge"));
+
+        let journals = JournalSelection {
+            namespace: Some("app".into()),
+            ..Default::default()
+        };
+        let command = StartJournalctl::new(path, journals, false, false).make_command(None);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--namespace=app"));
+    }
+
+    #[test]
+    fn checkpoint_filename_depends_on_namespace() {
+        assert_eq!(checkpoint_filename(None), CHECKPOINT_FILENAME);
+        assert_eq!(checkpoint_filename(Some("app")), "checkpoint-app.txt");
+        assert_eq!(checkpoint_filename(Some("*")), "checkpoint-all.txt");
+        assert_eq!(
+            checkpoint_filename(Some("+app")),
+            "checkpoint-default+app.txt"
+        );
+        assert_eq!(checkpoint_filename(Some("a/b")), "checkpoint-a_b.txt");
+    }
+
+    #[tokio::test]
+    async fn rejects_conflicting_journal_paths() {
+        let build = |config: JournaldConfig| async move {
+            let tempdir = tempdir().unwrap();
+            let config = JournaldConfig {
+                data_dir: Some(tempdir.path().to_path_buf()),
+                ..config
+            };
+            config
+                .build(SourceContext::new_test(SourceSender::new_test().0, None))
+                .await
+        };
+
+        let error = build(JournaldConfig {
+            journal_directory: Some("/var/log/journal/remote".into()),
+            journal_files: vec!["/tmp/exported.journal".into()],
+            ..Default::default()
+        })
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            BuildError::BothDirectoryAndFiles.to_string()
+        );
+
+        let error = build(JournaldConfig {
+            journal_directory: Some("/var/log/journal/remote".into()),
+            namespace: Some("app".into()),
+            ..Default::default()
+        })
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            BuildError::NamespaceWithJournalPaths.to_string()
+        );
     }
 
     fn create_command(
@@ -1270,7 +1408,11 @@ mod tests {
         since_now: bool,
         cursor: Option<&str>,
     ) -> Command {
-        StartJournalctl::new(path.into(), journal_dir, current_boot_only, since_now)
+        let journals = JournalSelection {
+            directory: journal_dir,
+            ..Default::default()
+        };
+        StartJournalctl::new(path.into(), journals, current_boot_only, since_now)
             .make_command(cursor)
     }
 
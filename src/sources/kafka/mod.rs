@@ -1,7 +1,8 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     io::Cursor,
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use async_stream::stream;
@@ -11,7 +12,7 @@ use codecs::{
     decoding::{DeserializerConfig, FramingConfig},
     StreamDecodingError,
 };
-use futures::{Stream, StreamExt};
+use futures::{future::ready, stream::BoxStream, Stream, StreamExt};
 use rdkafka::{
     config::ClientConfig,
     consumer::{Consumer, StreamConsumer},
@@ -23,22 +24,28 @@ use vector_common::{byte_size_of::ByteSizeOf, finalizer::OrderedFinalizer};
 use vector_config::configurable_component;
 
 use crate::{
-    codecs::{Decoder, DecodingConfig},
+    codecs::{
+        schema_registry::{SchemaRegistry, SchemaRegistryConfig},
+        Decoder, DecodingConfig,
+    },
     config::{
-        log_schema, AcknowledgementsConfig, LogSchema, Output, SourceConfig, SourceContext,
-        SourceDescription,
+        log_schema, AcknowledgementsConfig, DataType, LogSchema, Output, SourceConfig,
+        SourceContext, SourceDescription,
     },
-    event::{BatchNotifier, BatchStatus, Event, Value},
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, Value},
     internal_events::{
         KafkaBytesReceived, KafkaEventsReceived, KafkaNegativeAcknowledgmentError,
-        KafkaOffsetUpdateError, KafkaReadError, StreamClosedError,
+        KafkaOffsetUpdateError, KafkaReadError, KafkaSchemaRegistryDecodeError, StreamClosedError,
     },
-    kafka::{KafkaAuthConfig, KafkaStatisticsContext},
+    kafka::KafkaAuthConfig,
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     SourceSender,
 };
 
+mod rebalance;
+use rebalance::KafkaSourceContext;
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Could not create Kafka consumer: {}", source))]
@@ -69,6 +76,26 @@ pub struct KafkaSourceConfig {
     /// The consumer group name to be used to consume events from Kafka.
     group_id: String,
 
+    /// The static membership ID of this consumer within the consumer group.
+    ///
+    /// Consumers with a static membership keep their partitions when they restart within the
+    /// session timeout, instead of triggering a rebalance of the consumer group. It must be
+    /// unique within the consumer group, and stay the same across restarts.
+    group_instance_id: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    rebalance_strategy: RebalanceStrategy,
+
+    /// The maximum time, in milliseconds, to wait for the events of partitions being revoked to be
+    /// acknowledged, before letting the consumer group rebalance.
+    ///
+    /// Only relevant when acknowledgements are enabled. Messages whose events are not acknowledged
+    /// by then are consumed again by the next owner of their partition.
+    #[serde(default = "default_drain_timeout_ms")]
+    #[derivative(Default(value = "default_drain_timeout_ms()"))]
+    drain_timeout_ms: u64,
+
     /// If offsets for consumer group do not exist, set them using this strategy.
     ///
     /// See the [librdkafka documentation](https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md) for the `auto.offset.reset` option for further clarification.
@@ -139,6 +166,9 @@ pub struct KafkaSourceConfig {
     #[serde(flatten)]
     auth: KafkaAuthConfig,
 
+    #[configurable(derived)]
+    schema_registry: Option<SchemaRegistryConfig>,
+
     #[configurable(derived)]
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
@@ -154,6 +184,26 @@ pub struct KafkaSourceConfig {
     acknowledgements: AcknowledgementsConfig,
 }
 
+/// The strategy used to assign partitions to the members of the consumer group.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RebalanceStrategy {
+    /// Revokes all partitions from all members on each rebalance, before assigning them again.
+    ///
+    /// This uses the `range` and `roundrobin` assignors, the default of librdkafka.
+    #[derivative(Default)]
+    Eager,
+
+    /// Only revokes the partitions moving to another member on each rebalance, while the other
+    /// members keep consuming.
+    ///
+    /// This uses the `cooperative-sticky` assignor, and can't be used in a consumer group whose
+    /// members use an eager strategy.
+    CooperativeSticky,
+}
+
 const fn default_session_timeout_ms() -> u64 {
     10000 // default in librdkafka
 }
@@ -170,6 +220,10 @@ const fn default_commit_interval_ms() -> u64 {
     5000 // default in librdkafka
 }
 
+const fn default_drain_timeout_ms() -> u64 {
+    10000
+}
+
 fn default_auto_offset_reset() -> String {
     "largest".into() // default in librdkafka
 }
@@ -205,13 +259,19 @@ impl_generate_config_from_default!(KafkaSourceConfig);
 impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let consumer = create_consumer(self)?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        let registry = self
+            .schema_registry
+            .as_ref()
+            .map(|registry| registry.build(&cx.proxy))
+            .transpose()?;
 
         Ok(Box::pin(kafka_source(
             self.clone(),
             consumer,
             decoder,
+            registry,
             cx.shutdown,
             cx.out,
             acknowledgements,
@@ -219,7 +279,12 @@ impl SourceConfig for KafkaSourceConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(self.decoding.output_type())]
+        // Messages decoded with the schema registry replace the configured decoding.
+        let output_type = match self.schema_registry {
+            Some(_) => DataType::Log,
+            None => self.decoding.output_type(),
+        };
+        vec![Output::default(output_type)]
     }
 
     fn source_type(&self) -> &'static str {
@@ -233,26 +298,32 @@ impl SourceConfig for KafkaSourceConfig {
 
 async fn kafka_source(
     config: KafkaSourceConfig,
-    consumer: StreamConsumer<KafkaStatisticsContext>,
+    consumer: StreamConsumer<KafkaSourceContext>,
     decoder: Decoder,
+    mut registry: Option<SchemaRegistry>,
     mut shutdown: ShutdownSignal,
     mut out: SourceSender,
     acknowledgements: bool,
 ) -> Result<(), ()> {
     let consumer = Arc::new(consumer);
-    let (finalizer, mut ack_stream) =
+    let (finalizer, ack_stream) =
         OrderedFinalizer::<FinalizerEntry>::maybe_new(acknowledgements, shutdown.clone());
-    let mut stream = consumer.stream();
     let keys = Keys::from(log_schema(), &config);
 
-    let mut topics = Topics::new(&config);
+    let topics = Arc::new(Mutex::new(Topics::new(&config)));
+
+    // Acknowledgements are handled in a separate task, so that offsets keep being stored while
+    // the consumer waits for the partitions being revoked to be drained.
+    tokio::spawn(handle_acks(
+        Arc::clone(&consumer),
+        Arc::clone(&topics),
+        ack_stream,
+    ));
 
+    let mut stream = consumer.stream();
     loop {
         tokio::select! {
             _ = &mut shutdown => break,
-            entry = ack_stream.next() => if let Some((status, entry)) = entry {
-                handle_ack(&mut topics, status, entry, &consumer);
-            },
             message = stream.next() => match message {
                 None => break,  // WHY?
                 Some(Err(error)) => emit!(KafkaReadError { error }),
@@ -264,7 +335,7 @@ async fn kafka_source(
                         partition: msg.partition(),
                     });
 
-                    parse_message(msg, &decoder, keys, &finalizer, &mut out, &consumer, &topics).await;
+                    parse_message(msg, &decoder, registry.as_mut(), keys, &finalizer, &mut out, &consumer, &topics).await;
                 }
             },
         }
@@ -287,11 +358,28 @@ impl Topics {
     }
 }
 
+async fn handle_acks(
+    consumer: Arc<StreamConsumer<KafkaSourceContext>>,
+    topics: Arc<Mutex<Topics>>,
+    mut ack_stream: impl Stream<Item = (BatchStatus, FinalizerEntry)> + Unpin,
+) {
+    while let Some((status, entry)) = ack_stream.next().await {
+        let (topic, partition) = (entry.topic.clone(), entry.partition);
+        handle_ack(
+            &mut topics.lock().expect("poisoned lock"),
+            status,
+            entry,
+            &consumer,
+        );
+        consumer.context().in_flight.remove(&topic, partition);
+    }
+}
+
 fn handle_ack(
     topics: &mut Topics,
     status: BatchStatus,
     entry: FinalizerEntry,
-    consumer: &StreamConsumer<KafkaStatisticsContext>,
+    consumer: &StreamConsumer<KafkaSourceContext>,
 ) {
     if !topics.failed.contains(&entry.topic) {
         if status == BatchStatus::Delivered {
@@ -332,13 +420,27 @@ fn handle_ack(
 async fn parse_message(
     msg: BorrowedMessage<'_>,
     decoder: &Decoder,
+    registry: Option<&mut SchemaRegistry>,
     keys: Keys<'_>,
     finalizer: &Option<OrderedFinalizer<FinalizerEntry>>,
     out: &mut SourceSender,
-    consumer: &Arc<StreamConsumer<KafkaStatisticsContext>>,
-    topics: &Topics,
+    consumer: &Arc<StreamConsumer<KafkaSourceContext>>,
+    topics: &Mutex<Topics>,
 ) {
-    if let Some((count, mut stream)) = parse_stream(&msg, decoder, keys, topics) {
+    if topics
+        .lock()
+        .expect("poisoned lock")
+        .failed
+        .contains(msg.topic())
+    {
+        return;
+    }
+
+    let parsed = match registry {
+        Some(registry) => parse_with_registry(&msg, registry, keys).await,
+        None => parse_stream(&msg, decoder, keys),
+    };
+    if let Some((count, mut stream)) = parsed {
         match finalizer {
             Some(finalizer) => {
                 let (batch, receiver) = BatchNotifier::new_with_receiver();
@@ -351,6 +453,10 @@ async fn parse_message(
                         // Drop stream to avoid borrowing `msg`: "[...] borrow might be used
                         // here, when `stream` is dropped and runs the destructor [...]".
                         drop(stream);
+                        consumer
+                            .context()
+                            .in_flight
+                            .add(msg.topic(), msg.partition());
                         finalizer.add(msg.into(), receiver);
                     }
                 }
@@ -376,12 +482,7 @@ fn parse_stream<'a>(
     msg: &BorrowedMessage<'a>,
     decoder: &Decoder,
     keys: Keys<'a>,
-    topics: &Topics,
-) -> Option<(usize, impl Stream<Item = Event> + 'a)> {
-    if topics.failed.contains(msg.topic()) {
-        return None;
-    }
-
+) -> Option<(usize, BoxStream<'a, Event>)> {
     let payload = msg.payload()?; // skip messages with empty payload
 
     let rmsg = ReceivedMessage::from(msg);
@@ -419,6 +520,45 @@ fn parse_stream<'a>(
     Some((count, stream))
 }
 
+// Decode the received message with the schema registry into a single event.
+async fn parse_with_registry<'a>(
+    msg: &BorrowedMessage<'a>,
+    registry: &mut SchemaRegistry,
+    keys: Keys<'a>,
+) -> Option<(usize, BoxStream<'a, Event>)> {
+    let payload = msg.payload()?; // skip messages with empty payload
+
+    let rmsg = ReceivedMessage::from(msg);
+
+    let log = match registry.decode(payload).await {
+        Ok(Value::Object(fields)) => LogEvent::from(fields),
+        Ok(value) => {
+            let mut log = LogEvent::default();
+            log.insert(log_schema().message_key(), value);
+            log
+        }
+        Err(error) => {
+            emit!(KafkaSchemaRegistryDecodeError {
+                error,
+                topic: &rmsg.topic,
+                partition: rmsg.partition,
+                offset: rmsg.offset,
+            });
+            return None;
+        }
+    };
+    let mut event = Event::from(log);
+    emit!(KafkaEventsReceived {
+        count: 1,
+        byte_size: event.size_of(),
+        topic: &rmsg.topic,
+        partition: rmsg.partition,
+    });
+    rmsg.apply(&keys, &mut event);
+
+    Some((1, futures::stream::once(ready(event)).boxed()))
+}
+
 #[derive(Clone, Copy)]
 struct Keys<'a> {
     source_type: &'a str,
@@ -523,7 +663,7 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
 
 fn create_consumer(
     config: &KafkaSourceConfig,
-) -> crate::Result<StreamConsumer<KafkaStatisticsContext>> {
+) -> crate::Result<StreamConsumer<KafkaSourceContext>> {
     let mut client_config = ClientConfig::new();
     client_config
         .set("group.id", &config.group_id)
@@ -542,6 +682,13 @@ fn create_consumer(
         .set("statistics.interval.ms", "1000")
         .set("client.id", "vector");
 
+    if let Some(group_instance_id) = &config.group_instance_id {
+        client_config.set("group.instance.id", group_instance_id);
+    }
+    if config.rebalance_strategy == RebalanceStrategy::CooperativeSticky {
+        client_config.set("partition.assignment.strategy", "cooperative-sticky");
+    }
+
     config.auth.apply(&mut client_config)?;
 
     if let Some(librdkafka_options) = &config.librdkafka_options {
@@ -551,7 +698,9 @@ fn create_consumer(
     }
 
     let consumer = client_config
-        .create_with_context::<_, StreamConsumer<_>>(KafkaStatisticsContext)
+        .create_with_context::<_, StreamConsumer<_>>(KafkaSourceContext::new(
+            Duration::from_millis(config.drain_timeout_ms),
+        ))
         .context(KafkaCreateSnafu)?;
     let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
     consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
@@ -601,6 +750,27 @@ mod test {
         assert!(create_consumer(&config).is_ok());
     }
 
+    #[test]
+    fn parses_rebalance_options() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topics = ["logs"]
+            group_id = "vector"
+            group_instance_id = "vector-0"
+            rebalance_strategy = "cooperative_sticky"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.group_instance_id.as_deref(), Some("vector-0"));
+        assert_eq!(
+            config.rebalance_strategy,
+            RebalanceStrategy::CooperativeSticky
+        );
+        assert_eq!(config.drain_timeout_ms, default_drain_timeout_ms());
+        assert!(create_consumer(&config).is_ok());
+    }
+
     #[tokio::test]
     async fn consumer_create_incorrect_auto_offset_reset() {
         let config = KafkaSourceConfig {
@@ -713,6 +883,7 @@ mod integration_test {
                 config,
                 consumer,
                 crate::codecs::Decoder::default(),
+                None,
                 shutdown,
                 tx,
                 acknowledgements,
@@ -0,0 +1,132 @@
+//! Coordination of the consumer with the rebalances of its consumer group.
+//!
+//! Before partitions are revoked, the consumer waits for the events of their messages to be
+//! acknowledged, so that their offsets are stored and committed along with the revocation. The
+//! next owner of the partitions then resumes after them, instead of consuming them again.
+
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+
+use rdkafka::{
+    consumer::{ConsumerContext, Rebalance},
+    ClientContext, Statistics,
+};
+
+use crate::{internal_events::KafkaRebalanceDrainTimeout, kafka::KafkaStatisticsContext};
+
+/// The number of messages whose events are not yet acknowledged, for each partition.
+#[derive(Debug, Default)]
+pub(super) struct InFlight {
+    counts: Mutex<HashMap<(String, i32), usize>>,
+    drained: Condvar,
+}
+
+impl InFlight {
+    pub(super) fn add(&self, topic: &str, partition: i32) {
+        let mut counts = self.counts.lock().expect("poisoned lock");
+        *counts.entry((topic.to_string(), partition)).or_default() += 1;
+    }
+
+    pub(super) fn remove(&self, topic: &str, partition: i32) {
+        let mut counts = self.counts.lock().expect("poisoned lock");
+        let key = (topic.to_string(), partition);
+        if let Some(count) = counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&key);
+                self.drained.notify_all();
+            }
+        }
+    }
+
+    /// Waits for the messages of the given partitions to be acknowledged, returning whether they
+    /// all were before the timeout.
+    fn drain(&self, partitions: &[(String, i32)], timeout: Duration) -> bool {
+        let counts = self.counts.lock().expect("poisoned lock");
+        let (_counts, result) = self
+            .drained
+            .wait_timeout_while(counts, timeout, |counts| {
+                partitions
+                    .iter()
+                    .any(|partition| counts.contains_key(partition))
+            })
+            .expect("poisoned lock");
+        !result.timed_out()
+    }
+}
+
+/// The context of the consumer, which drains the partitions being revoked.
+pub(super) struct KafkaSourceContext {
+    pub(super) in_flight: InFlight,
+    drain_timeout: Duration,
+}
+
+impl KafkaSourceContext {
+    pub(super) fn new(drain_timeout: Duration) -> Self {
+        Self {
+            in_flight: InFlight::default(),
+            drain_timeout,
+        }
+    }
+}
+
+impl ClientContext for KafkaSourceContext {
+    fn stats(&self, statistics: Statistics) {
+        KafkaStatisticsContext.stats(statistics);
+    }
+}
+
+impl ConsumerContext for KafkaSourceContext {
+    fn pre_rebalance(&self, rebalance: &Rebalance) {
+        if let Rebalance::Revoke(partitions) = rebalance {
+            let partitions = partitions
+                .elements()
+                .iter()
+                .map(|element| (element.topic().to_string(), element.partition()))
+                .collect::<Vec<_>>();
+            // Acknowledgements are handled in their own task, which keeps storing offsets while
+            // this blocks the consumer. The stored offsets are then committed by librdkafka once
+            // the partitions are revoked.
+            if !self.in_flight.drain(&partitions, self.drain_timeout) {
+                emit!(KafkaRebalanceDrainTimeout {
+                    partitions: partitions.len(),
+                    timeout: self.drain_timeout,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn drains_revoked_partitions() {
+        let in_flight = Arc::new(InFlight::default());
+        in_flight.add("logs", 0);
+        in_flight.add("logs", 0);
+        in_flight.add("logs", 1);
+
+        let revoked = [("logs".to_string(), 0)];
+        assert!(!in_flight.drain(&revoked, Duration::from_millis(10)));
+
+        let acks = {
+            let in_flight = Arc::clone(&in_flight);
+            std::thread::spawn(move || {
+                in_flight.remove("logs", 0);
+                in_flight.remove("logs", 0);
+            })
+        };
+        // The messages of other partitions are not waited for.
+        assert!(in_flight.drain(&revoked, Duration::from_secs(10)));
+        acks.join().unwrap();
+
+        assert!(!in_flight.drain(&[("logs".to_string(), 1)], Duration::from_millis(10)));
+    }
+}
@@ -0,0 +1,487 @@
+//! This mod implements the `kubernetes_events` source.
+//! It watches `Event` objects through the Kubernetes API, either cluster-wide
+//! or in a set of namespaces, and emits each new or updated event as a log.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{future, FutureExt, StreamExt};
+use k8s_openapi::api::core::v1::Event as KubeEvent;
+use kube::{
+    api::{Api, ListParams, WatchEvent},
+    config::{self, KubeConfigOptions},
+    Client, Config as ClientConfig,
+};
+use serde::{Deserialize, Serialize};
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        EventsReceived, KubernetesEventsCheckpointError, KubernetesEventsWatchError,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    sources::{
+        self,
+        util::{CheckpointStore, CheckpointStoreConfig},
+    },
+    SourceSender,
+};
+
+/// The watch timeout requested from the API server, which must stay below its 295 seconds limit.
+const WATCH_TIMEOUT_SECS: u32 = 290;
+
+/// How long to wait before watching again after a failed request.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The HTTP status the API server answers with when a watched resource version is too old.
+const GONE: u16 = 410;
+
+/// Configuration for the `kubernetes_events` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct KubernetesEventsConfig {
+    /// The namespaces to watch events in.
+    ///
+    /// If empty, events are watched cluster-wide.
+    namespaces: Vec<String>,
+
+    /// Specifies the field selector to filter `Event`s with, for example `involvedObject.kind=Pod`.
+    field_selector: String,
+
+    /// Specifies the label selector to filter `Event`s with.
+    label_selector: String,
+
+    /// The directory used to persist the `resourceVersion` checkpoints.
+    ///
+    /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the `resourceVersion` checkpoints.
+    ///
+    /// By default, they're persisted in files of `data_dir`.
+    checkpoint_store: CheckpointStoreConfig,
+
+    /// Optional path to a kubeconfig file readable by Vector. If not set,
+    /// Vector will try to connect to Kubernetes using in-cluster configuration.
+    kube_config_file: Option<PathBuf>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<KubernetesEventsConfig>("kubernetes_events")
+}
+
+impl GenerateConfig for KubernetesEventsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(&Self::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_events")]
+impl SourceConfig for KubernetesEventsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+        // If the user passed a custom Kubeconfig use it, otherwise
+        // we attempt to load the local kubec-config, followed by the
+        // in-cluster environment variables
+        let client_config = match &self.kube_config_file {
+            Some(kc) => {
+                ClientConfig::from_custom_kubeconfig(
+                    config::Kubeconfig::read_from(kc)?,
+                    &KubeConfigOptions::default(),
+                )
+                .await?
+            }
+            None => ClientConfig::infer().await?,
+        };
+        let client = Client::try_from(client_config)?;
+
+        let mut list_params = ListParams::default().timeout(WATCH_TIMEOUT_SECS);
+        if !self.field_selector.is_empty() {
+            list_params = list_params.fields(&self.field_selector);
+        }
+        if !self.label_selector.is_empty() {
+            list_params = list_params.labels(&self.label_selector);
+        }
+
+        let namespaces = if self.namespaces.is_empty() {
+            vec![None]
+        } else {
+            self.namespaces.iter().cloned().map(Some).collect()
+        };
+        let mut watchers = Vec::with_capacity(namespaces.len());
+        for namespace in namespaces {
+            let api = match &namespace {
+                Some(namespace) => Api::namespaced(client.clone(), namespace),
+                None => Api::all(client.clone()),
+            };
+            let checkpoint_name =
+                format!("checkpoint-{}.json", namespace.as_deref().unwrap_or("_all"));
+            let checkpoint_store = self
+                .checkpoint_store
+                .build(&cx, self.data_dir.as_ref(), &checkpoint_name)
+                .await?;
+            watchers.push(Watcher {
+                api,
+                namespace,
+                checkpoint_store,
+                list_params: list_params.clone(),
+            });
+        }
+
+        let out = cx.out;
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(
+            future::join_all(
+                watchers
+                    .into_iter()
+                    .map(move |watcher| watcher.run(out.clone(), shutdown.clone())),
+            )
+            .map(|_| Ok(())),
+        ))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "kubernetes_events"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// The position a watcher resumes from after a restart.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Checkpoint {
+    /// The `resourceVersion` to resume the watch from.
+    resource_version: Option<String>,
+    /// The most recent event timestamp emitted, used to skip already emitted events when the
+    /// checkpointed `resourceVersion` has expired and the events have to be listed again.
+    last_timestamp: Option<DateTime<Utc>>,
+}
+
+/// Tracks the `count` and `lastTimestamp` of the events seen so far, so that updates which do not
+/// represent a new occurrence of an event are not emitted again.
+#[derive(Debug, Default)]
+struct Deduplicator {
+    seen: HashMap<String, (Option<i32>, Option<DateTime<Utc>>)>,
+}
+
+impl Deduplicator {
+    /// Returns `true` if the event has not been seen with the same count and last timestamp.
+    fn is_new(&mut self, event: &KubeEvent) -> bool {
+        let uid = match &event.metadata.uid {
+            Some(uid) => uid.clone(),
+            None => return true,
+        };
+        let state = (event.count, event_timestamp(event));
+        self.seen.insert(uid, state) != Some(state)
+    }
+
+    fn remove(&mut self, event: &KubeEvent) {
+        if let Some(uid) = &event.metadata.uid {
+            self.seen.remove(uid);
+        }
+    }
+}
+
+struct Watcher {
+    api: Api<KubeEvent>,
+    namespace: Option<String>,
+    checkpoint_store: CheckpointStore,
+    list_params: ListParams,
+}
+
+impl Watcher {
+    fn namespace(&self) -> &str {
+        self.namespace.as_deref().unwrap_or("")
+    }
+
+    async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) {
+        let mut checkpoint = match self
+            .checkpoint_store
+            .load_json_or_default::<Checkpoint>()
+            .await
+        {
+            Ok(checkpoint) => checkpoint,
+            Err(error) => {
+                error!(
+                    message = "Failed to load checkpoint.",
+                    location = %self.checkpoint_store.location(),
+                    namespace = self.namespace(),
+                    %error,
+                );
+                return;
+            }
+        };
+        let mut deduplicator = Deduplicator::default();
+
+        loop {
+            let result = tokio::select! {
+                result = self.watch(&mut checkpoint, &mut deduplicator, &mut out) => result,
+                _ = &mut shutdown => break,
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(WatchError::Closed) => break,
+                Err(WatchError::Request(error)) => {
+                    emit!(KubernetesEventsWatchError {
+                        error,
+                        namespace: self.namespace(),
+                    });
+                    tokio::select! {
+                        _ = tokio::time::sleep(RETRY_DELAY) => {}
+                        _ = &mut shutdown => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Watches events from the checkpointed `resourceVersion`, listing them first if there is
+    /// none. Returns once the watch times out or the `resourceVersion` expired.
+    async fn watch(
+        &self,
+        checkpoint: &mut Checkpoint,
+        deduplicator: &mut Deduplicator,
+        out: &mut SourceSender,
+    ) -> Result<(), WatchError> {
+        let resource_version = match &checkpoint.resource_version {
+            Some(resource_version) => resource_version.clone(),
+            None => self.list(checkpoint, deduplicator, out).await?,
+        };
+
+        let mut stream = self
+            .api
+            .watch(&self.list_params, &resource_version)
+            .await
+            .map_err(WatchError::Request)?
+            .boxed();
+
+        while let Some(event) = stream.next().await {
+            match event.map_err(WatchError::Request)? {
+                WatchEvent::Added(event) | WatchEvent::Modified(event) => {
+                    let resource_version = event.metadata.resource_version.clone();
+                    if deduplicator.is_new(&event) {
+                        self.send(vec![event], checkpoint, out).await?;
+                    }
+                    self.update_checkpoint(checkpoint, resource_version).await;
+                }
+                WatchEvent::Deleted(event) => deduplicator.remove(&event),
+                WatchEvent::Bookmark(bookmark) => {
+                    self.update_checkpoint(checkpoint, Some(bookmark.metadata.resource_version))
+                        .await;
+                }
+                WatchEvent::Error(error) if error.code == GONE => {
+                    debug!(
+                        message = "Checkpointed resource version expired, listing events again.",
+                        namespace = %self.namespace(),
+                    );
+                    checkpoint.resource_version = None;
+                    return Ok(());
+                }
+                WatchEvent::Error(error) => {
+                    return Err(WatchError::Request(kube::Error::Api(error)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lists the current events, emitting those that are more recent than the checkpoint, and
+    /// returns the `resourceVersion` to watch from.
+    async fn list(
+        &self,
+        checkpoint: &mut Checkpoint,
+        deduplicator: &mut Deduplicator,
+        out: &mut SourceSender,
+    ) -> Result<String, WatchError> {
+        let list = self
+            .api
+            .list(&self.list_params)
+            .await
+            .map_err(WatchError::Request)?;
+
+        let last_timestamp = checkpoint.last_timestamp;
+        let events = list
+            .items
+            .into_iter()
+            .filter(|event| deduplicator.is_new(event))
+            .filter(|event| match (event_timestamp(event), last_timestamp) {
+                (Some(timestamp), Some(last_timestamp)) => timestamp > last_timestamp,
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        self.send(events, checkpoint, out).await?;
+
+        let resource_version = list.metadata.resource_version.unwrap_or_default();
+        self.update_checkpoint(checkpoint, Some(resource_version.clone()))
+            .await;
+        Ok(resource_version)
+    }
+
+    async fn send(
+        &self,
+        events: Vec<KubeEvent>,
+        checkpoint: &mut Checkpoint,
+        out: &mut SourceSender,
+    ) -> Result<(), WatchError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        for event in &events {
+            if let Some(timestamp) = event_timestamp(event) {
+                checkpoint.last_timestamp = checkpoint.last_timestamp.max(Some(timestamp));
+            }
+        }
+
+        let events = events.into_iter().map(into_log).collect::<Vec<_>>();
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+            WatchError::Closed
+        })
+    }
+
+    async fn update_checkpoint(
+        &self,
+        checkpoint: &mut Checkpoint,
+        resource_version: Option<String>,
+    ) {
+        if resource_version.is_none() {
+            return;
+        }
+        checkpoint.resource_version = resource_version;
+        if let Err(error) = self.checkpoint_store.save_json(checkpoint).await {
+            emit!(KubernetesEventsCheckpointError {
+                error,
+                namespace: self.namespace(),
+            });
+        }
+    }
+}
+
+enum WatchError {
+    Closed,
+    Request(kube::Error),
+}
+
+/// The time of the most recent occurrence of the event.
+fn event_timestamp(event: &KubeEvent) -> Option<DateTime<Utc>> {
+    event
+        .last_timestamp
+        .as_ref()
+        .map(|time| time.0)
+        .or_else(|| event.event_time.as_ref().map(|time| time.0))
+        .or_else(|| event.first_timestamp.as_ref().map(|time| time.0))
+        .or_else(|| {
+            event
+                .metadata
+                .creation_timestamp
+                .as_ref()
+                .map(|time| time.0)
+        })
+}
+
+fn into_log(mut event: KubeEvent) -> LogEvent {
+    // Managed fields are bookkeeping of the API server and only add noise.
+    event.metadata.managed_fields = None;
+
+    let timestamp = event_timestamp(&event).unwrap_or_else(Utc::now);
+    let message = event.message.clone().unwrap_or_default();
+
+    let mut log = match serde_json::to_value(&event).map(Value::from) {
+        Ok(Value::Object(fields)) => LogEvent::from(fields),
+        _ => LogEvent::default(),
+    };
+    log.insert(log_schema().message_key(), message);
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("kubernetes_events"),
+    );
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KubernetesEventsConfig>();
+    }
+
+    fn event(count: i32, last_timestamp: i64) -> KubeEvent {
+        KubeEvent {
+            metadata: ObjectMeta {
+                uid: Some("4f3c1f5e".into()),
+                name: Some("web-1.16b6c9cd0c5e3b8f".into()),
+                namespace: Some("default".into()),
+                ..Default::default()
+            },
+            message: Some("Back-off restarting failed container".into()),
+            reason: Some("BackOff".into()),
+            type_: Some("Warning".into()),
+            count: Some(count),
+            last_timestamp: Some(Time(Utc.timestamp(last_timestamp, 0))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn deduplicates_by_count_and_last_timestamp() {
+        let mut deduplicator = Deduplicator::default();
+
+        assert!(deduplicator.is_new(&event(1, 1_650_000_000)));
+        assert!(!deduplicator.is_new(&event(1, 1_650_000_000)));
+        assert!(deduplicator.is_new(&event(2, 1_650_000_060)));
+
+        deduplicator.remove(&event(2, 1_650_000_060));
+        assert!(deduplicator.is_new(&event(2, 1_650_000_060)));
+    }
+
+    #[test]
+    fn converts_event_into_log() {
+        let log = into_log(event(3, 1_650_000_000));
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Back-off restarting failed container".into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_650_000_000, 0).into()
+        );
+        assert_eq!(log["reason"], "BackOff".into());
+        assert_eq!(log["count"], 3.into());
+        assert_eq!(log["metadata.namespace"], "default".into());
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "kubernetes_events".into()
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use file_source::paths_provider::PathsProvider;
 use k8s_openapi::api::core::v1::{Namespace, Pod};
@@ -17,6 +17,7 @@ pub struct K8sPathsProvider {
     pod_state: Store<Pod>,
     namespace_state: Store<Namespace>,
     exclude_paths: Vec<glob::Pattern>,
+    pod_logs_dir: PathBuf,
 }
 
 impl K8sPathsProvider {
@@ -25,11 +26,13 @@ impl K8sPathsProvider {
         pod_state: Store<Pod>,
         namespace_state: Store<Namespace>,
         exclude_paths: Vec<glob::Pattern>,
+        pod_logs_dir: PathBuf,
     ) -> Self {
         Self {
             pod_state,
             namespace_state,
             exclude_paths,
+            pod_logs_dir,
         }
     }
 }
@@ -56,7 +59,7 @@ impl PathsProvider for K8sPathsProvider {
             })
             .flat_map(|pod| {
                 trace!(message = "Providing log paths for pod.", pod = ?pod.metadata.name);
-                let paths_iter = list_pod_log_paths(real_glob, pod.as_ref());
+                let paths_iter = list_pod_log_paths(real_glob, &self.pod_logs_dir, pod.as_ref());
                 exclude_paths(paths_iter, &self.exclude_paths).collect::<Vec<_>>()
             })
             .collect()
@@ -82,7 +85,7 @@ impl PathsProvider for K8sPathsProvider {
 /// See https://github.com/vectordotdev/vector/issues/6001
 /// See https://github.com/kubernetes/kubernetes/blob/ef3337a443b402756c9f0bfb1f844b1b45ce289d/pkg/kubelet/pod/pod_manager.go#L30-L44
 /// See https://github.com/kubernetes/kubernetes/blob/cea1d4e20b4a7886d8ff65f34c6d4f95efcb4742/pkg/kubelet/pod/mirror_client.go#L80-L81
-fn extract_pod_logs_directory(pod: &Pod) -> Option<PathBuf> {
+fn extract_pod_logs_directory(pod_logs_dir: &Path, pod: &Pod) -> Option<PathBuf> {
     let metadata = &pod.metadata;
     let namespace = metadata.namespace.as_ref()?;
     let name = metadata.name.as_ref()?;
@@ -95,7 +98,7 @@ fn extract_pod_logs_directory(pod: &Pod) -> Option<PathBuf> {
         metadata.uid.as_ref()?
     };
 
-    Some(build_pod_logs_directory(namespace, name, uid))
+    Some(build_pod_logs_directory(pod_logs_dir, namespace, name, uid))
 }
 
 const CONTAINER_EXCLUSION_ANNOTATION_KEY: &str = "vector.dev/exclude-containers";
@@ -128,13 +131,14 @@ fn build_container_exclusion_patterns<'a>(
 
 fn list_pod_log_paths<'a, G, GI>(
     mut glob_impl: G,
+    pod_logs_dir: &Path,
     pod: &'a Pod,
 ) -> impl Iterator<Item = PathBuf> + 'a
 where
     G: FnMut(&str) -> GI + 'a,
     GI: Iterator<Item = PathBuf> + 'a,
 {
-    extract_pod_logs_directory(pod)
+    extract_pod_logs_directory(pod_logs_dir, pod)
         .into_iter()
         .flat_map(move |dir| {
             let dir = dir
@@ -194,13 +198,13 @@ fn exclude_paths<'a>(
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
 
     use super::{
-        build_container_exclusion_patterns, exclude_paths, extract_excluded_containers_for_pod,
-        extract_pod_logs_directory, list_pod_log_paths,
+        super::path_helpers::K8S_LOGS_DIR, build_container_exclusion_patterns, exclude_paths,
+        extract_excluded_containers_for_pod, extract_pod_logs_directory, list_pod_log_paths,
     };
 
     #[test]
@@ -282,7 +286,7 @@ mod tests {
 
         for (pod, expected) in cases {
             assert_eq!(
-                extract_pod_logs_directory(&pod),
+                extract_pod_logs_directory(Path::new(K8S_LOGS_DIR), &pod),
                 expected.map(PathBuf::from)
             );
         }
@@ -439,7 +443,8 @@ mod tests {
                 paths_to_return.into_iter().map(PathBuf::from)
             };
 
-            let actual_paths: Vec<_> = list_pod_log_paths(mock_glob, &pod).collect();
+            let actual_paths: Vec<_> =
+                list_pod_log_paths(mock_glob, Path::new(K8S_LOGS_DIR), &pod).collect();
             let expected_paths: Vec<_> = expected_paths.into_iter().map(PathBuf::from).collect();
             assert_eq!(actual_paths, expected_paths)
         }
@@ -61,6 +61,7 @@ mod util;
 use self::namespace_metadata_annotator::NamespaceMetadataAnnotator;
 use self::node_metadata_annotator::NodeMetadataAnnotator;
 use self::parser::Parser;
+use self::path_helpers::K8S_LOGS_DIR;
 use self::pod_metadata_annotator::PodMetadataAnnotator;
 use futures::{future::FutureExt, stream::StreamExt};
 use k8s_paths_provider::K8sPathsProvider;
@@ -69,6 +70,9 @@ use lifecycle::Lifecycle;
 /// The key we use for `file` field.
 const FILE_KEY: &str = "file";
 
+/// The key we use for the cluster name field.
+const CLUSTER_NAME_KEY: &str = "kubernetes.cluster_name";
+
 /// The `self_node_name` value env var key.
 const SELF_NODE_NAME_ENV_KEY: &str = "VECTOR_SELF_NODE_NAME";
 
@@ -146,6 +150,27 @@ pub struct Config {
     /// Vector will try to connect to Kubernetes using in-cluster configuration.
     kube_config_file: Option<PathBuf>,
 
+    /// The name of the kubeconfig context to use, instead of its current context.
+    ///
+    /// The context is taken from `kube_config_file`, or from the kubeconfig file of the environment if it's not set,
+    /// which allows a central Vector to enrich the logs of other clusters than its own.
+    kube_config_context: Option<String>,
+
+    /// The URL of the Kubernetes API server, overriding the one of the kubeconfig file or of the in-cluster
+    /// configuration.
+    kube_api_endpoint: Option<String>,
+
+    /// The directory holding the pod log directories.
+    ///
+    /// This is where `kubelet` writes them by default, but it can be set to collect the logs shipped from another
+    /// cluster, along with `kube_config_context` or `kube_api_endpoint`.
+    pod_logs_dir: PathBuf,
+
+    /// The name of the cluster, added to each event in the `kubernetes.cluster_name` field.
+    ///
+    /// This tells apart the events of the clusters collected by a central Vector.
+    cluster_name: Option<String>,
+
     /// How long to delay removing entries from our map when we receive a deletion
     /// event from the watched stream.
     delay_deletion_ms: usize,
@@ -186,6 +211,10 @@ impl Default for Config {
             ingestion_timestamp_field: None,
             timezone: None,
             kube_config_file: None,
+            kube_config_context: None,
+            kube_api_endpoint: None,
+            pod_logs_dir: PathBuf::from(K8S_LOGS_DIR),
+            cluster_name: None,
             delay_deletion_ms: default_delay_deletion_ms(),
         }
     }
@@ -238,6 +267,8 @@ struct Source {
     glob_minimum_cooldown: Duration,
     ingestion_timestamp_field: Option<String>,
     delay_deletion: Duration,
+    pod_logs_dir: PathBuf,
+    cluster_name: Option<String>,
 }
 
 impl Source {
@@ -265,19 +296,7 @@ impl Source {
             prepare_label_selector(config.extra_namespace_label_selector.as_ref());
         let node_selector = prepare_node_selector(self_node_name.as_str())?;
 
-        // If the user passed a custom Kubeconfig use it, otherwise
-        // we attempt to load the local kubec-config, followed by the
-        // in-cluster environment variables
-        let client_config = match &config.kube_config_file {
-            Some(kc) => {
-                ClientConfig::from_custom_kubeconfig(
-                    config::Kubeconfig::read_from(kc)?,
-                    &KubeConfigOptions::default(),
-                )
-                .await?
-            }
-            None => ClientConfig::infer().await?,
-        };
+        let client_config = prepare_client_config(config).await?;
         let client = Client::try_from(client_config)?;
 
         let data_dir = globals.resolve_and_make_data_subdir(config.data_dir.as_ref(), key.id())?;
@@ -315,6 +334,8 @@ impl Source {
             glob_minimum_cooldown,
             ingestion_timestamp_field: config.ingestion_timestamp_field.clone(),
             delay_deletion,
+            pod_logs_dir: config.pod_logs_dir.clone(),
+            cluster_name: config.cluster_name.clone(),
         })
     }
 
@@ -342,6 +363,8 @@ impl Source {
             glob_minimum_cooldown,
             ingestion_timestamp_field,
             delay_deletion,
+            pod_logs_dir,
+            cluster_name,
         } = self;
 
         let mut reflectors = Vec::new();
@@ -402,8 +425,12 @@ impl Source {
             delay_deletion,
         )));
 
-        let paths_provider =
-            K8sPathsProvider::new(pod_state.clone(), ns_state.clone(), exclude_paths);
+        let paths_provider = K8sPathsProvider::new(
+            pod_state.clone(),
+            ns_state.clone(),
+            exclude_paths,
+            pod_logs_dir,
+        );
         let annotator = PodMetadataAnnotator::new(pod_state, pod_fields_spec);
         let ns_annotator = NamespaceMetadataAnnotator::new(ns_state, namespace_fields_spec);
         let node_annotator = NodeMetadataAnnotator::new(node_state, node_field_spec);
@@ -458,6 +485,11 @@ impl Source {
             oldest_first: true,
             // We do not remove the log files, `kubelet` is responsible for it.
             remove_after: None,
+            // Pod log directories are polled, as the source isn't limited to
+            // the few directories known at start-up.
+            use_notifications: false,
+            // Pod logs are read with system calls.
+            use_io_uring: false,
             // The standard emitter.
             emitter: FileSourceInternalEventsEmitter,
             // A handle to the current tokio runtime
@@ -482,6 +514,7 @@ impl Source {
                 line.text,
                 &line.filename,
                 ingestion_timestamp_field.as_deref(),
+                cluster_name.as_deref(),
             );
             let file_info = annotator.annotate(&mut event, &line.filename);
 
@@ -573,7 +606,12 @@ impl Source {
     }
 }
 
-fn create_event(line: Bytes, file: &str, ingestion_timestamp_field: Option<&str>) -> Event {
+fn create_event(
+    line: Bytes,
+    file: &str,
+    ingestion_timestamp_field: Option<&str>,
+    cluster_name: Option<&str>,
+) -> Event {
     let mut event = LogEvent::from(line);
 
     // Add source type.
@@ -582,6 +620,11 @@ fn create_event(line: Bytes, file: &str, ingestion_timestamp_field: Option<&str>
     // Add file.
     event.insert(FILE_KEY, file.to_owned());
 
+    // Add cluster name if configured.
+    if let Some(cluster_name) = cluster_name {
+        event.insert(CLUSTER_NAME_KEY, cluster_name.to_owned());
+    }
+
     // Add ingestion timestamp if requested.
     if let Some(ingestion_timestamp_field) = ingestion_timestamp_field {
         event.insert(ingestion_timestamp_field, Utc::now());
@@ -631,6 +674,31 @@ const fn default_delay_deletion_ms() -> usize {
     60_000
 }
 
+// This function constructs the configuration of the Kubernetes API client. If
+// the user passed a custom kubeconfig, or a context, it is used, otherwise we
+// attempt to load the local kubeconfig, followed by the in-cluster environment
+// variables. The API server URL can be overridden in any case.
+async fn prepare_client_config(config: &Config) -> crate::Result<ClientConfig> {
+    let options = KubeConfigOptions {
+        context: config.kube_config_context.clone(),
+        ..Default::default()
+    };
+    let mut client_config = match (&config.kube_config_file, &config.kube_config_context) {
+        (Some(kc), _) => {
+            ClientConfig::from_custom_kubeconfig(config::Kubeconfig::read_from(kc)?, &options)
+                .await?
+        }
+        (None, Some(_)) => ClientConfig::from_kubeconfig(&options).await?,
+        (None, None) => ClientConfig::infer().await?,
+    };
+
+    if let Some(endpoint) = &config.kube_api_endpoint {
+        client_config.cluster_url = endpoint.parse::<http::Uri>()?;
+    }
+
+    Ok(client_config)
+}
+
 // This function constructs the patterns we exclude from file watching, created
 // from the defaults or user provided configuration.
 fn prepare_exclude_paths(config: &Config) -> crate::Result<Vec<glob::Pattern>> {
@@ -744,6 +812,96 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn prepare_client_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let kube_config_file = dir.path().join("kubeconfig");
+        std::fs::write(
+            &kube_config_file,
+            r#"
+apiVersion: v1
+kind: Config
+current-context: cluster0
+clusters:
+- name: cluster0
+  cluster:
+    server: https://cluster0.example.com:6443
+- name: cluster1
+  cluster:
+    server: https://cluster1.example.com:6443
+contexts:
+- name: cluster0
+  context:
+    cluster: cluster0
+    user: vector
+- name: cluster1
+  context:
+    cluster: cluster1
+    user: vector
+users:
+- name: vector
+  user:
+    token: secret
+"#,
+        )
+        .unwrap();
+
+        let cases = vec![
+            (
+                Config {
+                    kube_config_file: Some(kube_config_file.clone()),
+                    ..Default::default()
+                },
+                "https://cluster0.example.com:6443/",
+            ),
+            (
+                Config {
+                    kube_config_file: Some(kube_config_file.clone()),
+                    kube_config_context: Some("cluster1".to_owned()),
+                    ..Default::default()
+                },
+                "https://cluster1.example.com:6443/",
+            ),
+            (
+                Config {
+                    kube_config_file: Some(kube_config_file.clone()),
+                    kube_config_context: Some("cluster1".to_owned()),
+                    kube_api_endpoint: Some("https://proxy.example.com:8443".to_owned()),
+                    ..Default::default()
+                },
+                "https://proxy.example.com:8443/",
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let output = super::prepare_client_config(&input).await.unwrap();
+            assert_eq!(
+                expected,
+                output.cluster_url.to_string(),
+                "expected left, actual right"
+            );
+        }
+    }
+
+    #[test]
+    fn create_event_with_cluster_name() {
+        let event = super::create_event(
+            "hello".into(),
+            "/var/log/pods/ns_name_uid/container/0.log",
+            None,
+            Some("cluster0"),
+        );
+        assert_eq!(event.as_log()[super::CLUSTER_NAME_KEY], "cluster0".into());
+
+        let event = super::create_event(
+            "hello".into(),
+            "/var/log/pods/ns_name_uid/container/0.log",
+            None,
+            None,
+        );
+        assert!(event.as_log().get(super::CLUSTER_NAME_KEY).is_none());
+    }
+
     #[test]
     fn prepare_field_selector() {
         let cases = vec![
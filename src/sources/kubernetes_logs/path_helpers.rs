@@ -4,10 +4,10 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// The root directory for pod logs.
-const K8S_LOGS_DIR: &str = "/var/log/pods";
+/// The default root directory for pod logs.
+pub(super) const K8S_LOGS_DIR: &str = "/var/log/pods";
 
 /// The delimiter used in the log path.
 const LOG_PATH_DELIMITER: &str = "_";
@@ -16,16 +16,12 @@ const LOG_PATH_DELIMITER: &str = "_";
 ///
 /// Based on https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go#L178
 pub(super) fn build_pod_logs_directory(
+    pod_logs_dir: &Path,
     pod_namespace: &str,
     pod_name: &str,
     pod_uid: &str,
 ) -> PathBuf {
-    [
-        K8S_LOGS_DIR,
-        &[pod_namespace, pod_name, pod_uid].join(LOG_PATH_DELIMITER),
-    ]
-    .join("/")
-    .into()
+    pod_logs_dir.join([pod_namespace, pod_name, pod_uid].join(LOG_PATH_DELIMITER))
 }
 
 /// Parses pod log file path and returns the log file info.
@@ -72,16 +68,25 @@ mod tests {
         let cases = vec![
             // Valid inputs.
             (
-                ("sandbox0-ns", "sandbox0-name", "sandbox0-uid"),
+                (K8S_LOGS_DIR, "sandbox0-ns", "sandbox0-name", "sandbox0-uid"),
                 "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid",
             ),
+            (
+                (
+                    "/mnt/cluster0/pods",
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid",
+                ),
+                "/mnt/cluster0/pods/sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
             // Invalid inputs.
-            (("", "", ""), "/var/log/pods/__"),
+            ((K8S_LOGS_DIR, "", "", ""), "/var/log/pods/__"),
         ];
 
-        for ((in_namespace, in_name, in_uid), expected) in cases.into_iter() {
+        for ((in_dir, in_namespace, in_name, in_uid), expected) in cases.into_iter() {
             assert_eq!(
-                build_pod_logs_directory(in_namespace, in_name, in_uid),
+                build_pod_logs_directory(Path::new(in_dir), in_namespace, in_name, in_uid),
                 PathBuf::from(expected)
             );
         }
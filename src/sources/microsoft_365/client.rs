@@ -0,0 +1,301 @@
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::{
+    header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE},
+    HeaderMap, Request, StatusCode,
+};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use url::{form_urlencoded, Url};
+
+use super::ContentType;
+use crate::{
+    http::{HttpClient, HttpError},
+    internal_events::BytesReceived,
+};
+
+/// The error code returned when starting a subscription that is already enabled.
+const SUBSCRIPTION_ENABLED: &str = "AF20024";
+
+/// The header holding the URL of the next page of content blobs.
+const NEXT_PAGE_URI: &str = "NextPageUri";
+
+/// Access tokens are renewed this long before they expire.
+const TOKEN_EXPIRATION_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+pub(super) enum ApiError {
+    #[snafu(display("failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("request failed: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("failed to read response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("unexpected status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("failed to parse response: {}", source))]
+    Parse { source: serde_json::Error },
+}
+
+/// An access token of the client credentials grant.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// A content blob, holding the audit records of a content type available since its creation.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ContentBlob {
+    pub(super) content_id: String,
+    pub(super) content_uri: String,
+    pub(super) content_created: DateTime<Utc>,
+}
+
+/// A client of the Office 365 Management Activity API, authenticated as an Azure AD application.
+///
+/// https://docs.microsoft.com/en-us/office/office-365-management-api/office-365-management-activity-api-reference
+pub(super) struct Client {
+    client: HttpClient,
+    token_url: Url,
+    feed_base_url: Url,
+    tenant_id: String,
+    client_id: String,
+    client_secret: String,
+    scope: String,
+    token: Option<(String, Instant)>,
+}
+
+impl Client {
+    pub(super) fn new(
+        client: HttpClient,
+        endpoint: &Url,
+        login_endpoint: &Url,
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    ) -> Result<Self, url::ParseError> {
+        Ok(Self {
+            client,
+            token_url: login_endpoint.join(&format!("/{}/oauth2/v2.0/token", tenant_id))?,
+            feed_base_url: endpoint.join(&format!("/api/v1.0/{}/activity/feed/", tenant_id))?,
+            scope: endpoint.join("/.default")?.to_string(),
+            tenant_id,
+            client_id,
+            client_secret,
+            token: None,
+        })
+    }
+
+    /// Starts the subscription to a content type, which is a no-op if it's already enabled.
+    pub(super) async fn start_subscription(
+        &mut self,
+        content_type: ContentType,
+    ) -> Result<(), ApiError> {
+        let url = self.feed_url(
+            "subscriptions/start",
+            &[("contentType", content_type.as_str())],
+        );
+        let request = Request::post(url.as_str())
+            .header(CONTENT_LENGTH, 0)
+            .body(Body::empty())
+            .context(BuildRequestSnafu)?;
+        match self.send(request).await {
+            Err(ApiError::UnexpectedStatus { status, body })
+                if status == StatusCode::BAD_REQUEST && body.contains(SUBSCRIPTION_ENABLED) =>
+            {
+                Ok(())
+            }
+            result => result.map(|_| ()),
+        }
+    }
+
+    /// Lists the content blobs of a content type created in a window of at most 24 hours.
+    pub(super) async fn list_content(
+        &mut self,
+        content_type: ContentType,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ContentBlob>, ApiError> {
+        let mut url = self.feed_url(
+            "subscriptions/content",
+            &[
+                ("contentType", content_type.as_str()),
+                ("startTime", &format_time(start)),
+                ("endTime", &format_time(end)),
+            ],
+        );
+        let mut blobs = Vec::new();
+        loop {
+            let request = Request::get(url.as_str())
+                .body(Body::empty())
+                .context(BuildRequestSnafu)?;
+            let (headers, body) = self.send(request).await?;
+            let page: Vec<ContentBlob> = serde_json::from_slice(&body).context(ParseSnafu)?;
+            blobs.extend(page);
+
+            match next_page(&headers) {
+                Some(next) => url = next,
+                None => return Ok(blobs),
+            }
+        }
+    }
+
+    /// Fetches the audit records of a content blob.
+    pub(super) async fn fetch_content(
+        &mut self,
+        blob: &ContentBlob,
+    ) -> Result<Vec<serde_json::Value>, ApiError> {
+        let request = Request::get(blob.content_uri.as_str())
+            .body(Body::empty())
+            .context(BuildRequestSnafu)?;
+        let (_, body) = self.send(request).await?;
+        emit!(BytesReceived {
+            byte_size: body.len(),
+            protocol: "http",
+        });
+        serde_json::from_slice(&body).context(ParseSnafu)
+    }
+
+    fn feed_url(&self, path: &str, query: &[(&str, &str)]) -> Url {
+        let mut url = self
+            .feed_base_url
+            .join(path)
+            .expect("feed path must be valid");
+        url.query_pairs_mut()
+            .extend_pairs(query)
+            // Attributes the requests to the tenant, so that it gets its own throttling quota.
+            .append_pair("PublisherIdentifier", &self.tenant_id);
+        url
+    }
+
+    async fn send(&mut self, mut request: Request<Body>) -> Result<(HeaderMap, Bytes), ApiError> {
+        let token = self.access_token().await?;
+        request.headers_mut().insert(
+            AUTHORIZATION,
+            format!("Bearer {}", token)
+                .parse()
+                .expect("access token must be a valid header value"),
+        );
+        self.execute(request).await
+    }
+
+    /// Returns the cached access token, requesting a new one when it's about to expire.
+    async fn access_token(&mut self) -> Result<String, ApiError> {
+        if let Some((token, expiration)) = &self.token {
+            if Instant::now() + TOKEN_EXPIRATION_MARGIN < *expiration {
+                return Ok(token.clone());
+            }
+        }
+
+        let body = form_urlencoded::Serializer::new(String::new())
+            .append_pair("client_id", &self.client_id)
+            .append_pair("client_secret", &self.client_secret)
+            .append_pair("scope", &self.scope)
+            .append_pair("grant_type", "client_credentials")
+            .finish();
+        let request = Request::post(self.token_url.as_str())
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(body))
+            .context(BuildRequestSnafu)?;
+        let requested = Instant::now();
+        let (_, body) = self.execute(request).await?;
+        let response: TokenResponse = serde_json::from_slice(&body).context(ParseSnafu)?;
+
+        let expiration = requested + Duration::from_secs(response.expires_in);
+        self.token = Some((response.access_token.clone(), expiration));
+        Ok(response.access_token)
+    }
+
+    async fn execute(&self, request: Request<Body>) -> Result<(HeaderMap, Bytes), ApiError> {
+        let response = self.client.send(request).await.context(RequestSnafu)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadResponseSnafu)?;
+        if parts.status.is_success() {
+            Ok((parts.headers, body))
+        } else {
+            Err(ApiError::UnexpectedStatus {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+    }
+}
+
+/// Formats a time as expected by the `startTime` and `endTime` parameters.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+fn next_page(headers: &HeaderMap) -> Option<Url> {
+    headers
+        .get(NEXT_PAGE_URI)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Url::parse(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::config::ProxyConfig;
+
+    #[test]
+    fn builds_urls() {
+        let client = Client::new(
+            HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            &Url::parse("https://manage.office.com").unwrap(),
+            &Url::parse("https://login.microsoftonline.com").unwrap(),
+            "41463f53-8812-40f4-890f-865bf6e35190".into(),
+            "client".into(),
+            "secret".into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            client.token_url.as_str(),
+            "https://login.microsoftonline.com/41463f53-8812-40f4-890f-865bf6e35190/oauth2/v2.0/token"
+        );
+        assert_eq!(client.scope, "https://manage.office.com/.default");
+        assert_eq!(
+            client
+                .feed_url(
+                    "subscriptions/content",
+                    &[
+                        ("contentType", ContentType::AuditGeneral.as_str()),
+                        (
+                            "startTime",
+                            &format_time(Utc.ymd(2022, 8, 1).and_hms(0, 0, 0))
+                        ),
+                    ]
+                )
+                .as_str(),
+            "https://manage.office.com/api/v1.0/41463f53-8812-40f4-890f-865bf6e35190/activity/feed/subscriptions/content?contentType=Audit.General&startTime=2022-08-01T00%3A00%3A00&PublisherIdentifier=41463f53-8812-40f4-890f-865bf6e35190"
+        );
+    }
+
+    #[test]
+    fn parses_content_blobs() {
+        let body = br#"[{
+            "contentType": "Audit.SharePoint",
+            "contentId": "492638008028$492638008028$f28ab78ad40140608012736e373933ebspo2015043022$4a81a7c326fc4aed89c62e6039ab833b$04",
+            "contentUri": "https://manage.office.com/api/v1.0/41463f53-8812-40f4-890f-865bf6e35190/activity/feed/audit/492638008028$492638008028$f28ab78ad40140608012736e373933ebspo2015043022$4a81a7c326fc4aed89c62e6039ab833b$04",
+            "contentCreated": "2015-05-23T17:35:00.000Z",
+            "contentExpiration": "2015-05-30T17:35:00.000Z"
+        }]"#;
+
+        let blobs: Vec<ContentBlob> = serde_json::from_slice(body).unwrap();
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(
+            blobs[0].content_created,
+            Utc.ymd(2015, 5, 23).and_hms(17, 35, 0)
+        );
+    }
+}
@@ -0,0 +1,579 @@
+//! This mod implements the `microsoft_365` source.
+//! It polls the Office 365 Management Activity API for the content blobs of the subscribed content
+//! types, and emits each audit record of the blobs as a log. As content blobs can be listed some
+//! time after their creation, their listing windows overlap, and the blobs already consumed are
+//! skipped.
+
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::select;
+use url::Url;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    http::HttpClient,
+    internal_events::{
+        EventsReceived, Microsoft365CheckpointError, Microsoft365RequestError, StreamClosedError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    tls::{TlsConfig, TlsSettings},
+    SourceSender,
+};
+
+mod client;
+
+use client::{ApiError, Client, ContentBlob};
+
+/// Content blobs can only be listed in windows of at most 24 hours.
+const MAX_WINDOW_HOURS: i64 = 24;
+
+/// Content blobs can only be listed for the last 7 days.
+const RETENTION_DAYS: i64 = 7;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum PollError {
+    #[snafu(display("{}", source))]
+    Api { source: ApiError },
+    #[snafu(display("events were not delivered"))]
+    NotDelivered,
+    #[snafu(display("source is shutting down"))]
+    Closed,
+}
+
+/// A content type of the Office 365 Management Activity API.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ContentType {
+    /// Azure Active Directory audit records.
+    #[serde(rename = "Audit.AzureActiveDirectory")]
+    AuditAzureActiveDirectory,
+
+    /// Exchange audit records.
+    #[serde(rename = "Audit.Exchange")]
+    AuditExchange,
+
+    /// SharePoint audit records.
+    #[serde(rename = "Audit.SharePoint")]
+    AuditSharePoint,
+
+    /// The audit records of the other workloads.
+    #[serde(rename = "Audit.General")]
+    AuditGeneral,
+
+    /// Data loss prevention events.
+    #[serde(rename = "DLP.All")]
+    DlpAll,
+}
+
+impl ContentType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::AuditAzureActiveDirectory => "Audit.AzureActiveDirectory",
+            Self::AuditExchange => "Audit.Exchange",
+            Self::AuditSharePoint => "Audit.SharePoint",
+            Self::AuditGeneral => "Audit.General",
+            Self::DlpAll => "DLP.All",
+        }
+    }
+}
+
+/// Configuration for the `microsoft_365` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Microsoft365Config {
+    /// The ID of the Azure AD tenant of the organization.
+    tenant_id: String,
+
+    /// The ID of the Azure AD application used to authenticate, with the client credentials grant.
+    ///
+    /// The application needs the `ActivityFeed.Read` permission of the Office 365 Management APIs, and the
+    /// `ActivityFeed.ReadDlp` permission to read `DLP.All`.
+    client_id: String,
+
+    /// A client secret of the Azure AD application.
+    client_secret: String,
+
+    /// The content types to subscribe to.
+    ///
+    /// By default, all content types are subscribed to.
+    #[serde(default = "default_content_types")]
+    content_types: Vec<ContentType>,
+
+    /// The endpoint of the Office 365 Management Activity API.
+    ///
+    /// The endpoint differs for the government clouds, such as `https://manage-gcc.office.com`.
+    #[serde(default = "default_endpoint")]
+    endpoint: String,
+
+    /// The endpoint of Azure AD, from which access tokens are requested.
+    #[serde(default = "default_login_endpoint")]
+    login_endpoint: String,
+
+    /// The interval between polls of the content blobs, in seconds.
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+
+    /// How long to keep looking for content blobs after their creation time, in seconds.
+    ///
+    /// Content blobs are listed by their creation time, but can become available some time after it. Content blobs
+    /// available later than this are missed.
+    #[serde(default = "default_content_delay_secs")]
+    content_delay_secs: u64,
+
+    /// The directory used to persist the progress of each content type.
+    ///
+    /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
+    #[serde(default)]
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the progress of each content type.
+    ///
+    /// By default, it's persisted in files of `data_dir`.
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_content_types() -> Vec<ContentType> {
+    vec![
+        ContentType::AuditAzureActiveDirectory,
+        ContentType::AuditExchange,
+        ContentType::AuditSharePoint,
+        ContentType::AuditGeneral,
+        ContentType::DlpAll,
+    ]
+}
+
+fn default_endpoint() -> String {
+    "https://manage.office.com".to_string()
+}
+
+fn default_login_endpoint() -> String {
+    "https://login.microsoftonline.com".to_string()
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+const fn default_content_delay_secs() -> u64 {
+    3600
+}
+
+inventory::submit! {
+    SourceDescription::new::<Microsoft365Config>("microsoft_365")
+}
+
+impl GenerateConfig for Microsoft365Config {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"tenant_id = "41463f53-8812-40f4-890f-865bf6e35190"
+            client_id = "c5d3a2f0-8d0c-4f3e-9e2c-1f6a0f0b5e7d"
+            client_secret = "${MICROSOFT_365_CLIENT_SECRET}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "microsoft_365")]
+impl SourceConfig for Microsoft365Config {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let parse = |endpoint: &String| {
+            Url::parse(endpoint).context(InvalidEndpointSnafu {
+                endpoint: endpoint.clone(),
+            })
+        };
+        let endpoint = parse(&self.endpoint)?;
+        let login_endpoint = parse(&self.login_endpoint)?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = Client::new(
+            HttpClient::new(tls, &cx.proxy)?,
+            &endpoint,
+            &login_endpoint,
+            self.tenant_id.clone(),
+            self.client_id.clone(),
+            self.client_secret.clone(),
+        )
+        .context(InvalidEndpointSnafu {
+            endpoint: self.endpoint.clone(),
+        })?;
+
+        let mut checkpoint_stores = Vec::<(ContentType, CheckpointStore)>::new();
+        for &content_type in &self.content_types {
+            if checkpoint_stores
+                .iter()
+                .any(|(subscribed, _)| *subscribed == content_type)
+            {
+                continue;
+            }
+            let checkpoint_store = self
+                .checkpoint_store
+                .build(
+                    &cx,
+                    self.data_dir.as_ref(),
+                    &format!("checkpoint-{}.json", content_type.as_str()),
+                )
+                .await?;
+            checkpoint_stores.push((content_type, checkpoint_store));
+        }
+
+        let source = Microsoft365 {
+            client,
+            interval: Duration::from_secs(self.interval_secs),
+            content_delay: chrono::Duration::seconds(self.content_delay_secs as i64),
+            checkpoint_stores,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "microsoft_365"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// The progress of a content type.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Checkpoint {
+    /// The end of the latest window whose content blobs were all consumed.
+    end_time: Option<DateTime<Utc>>,
+
+    /// The content blobs consumed, with their creation time, to skip them when they are listed
+    /// again by an overlapping window.
+    blobs: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl Checkpoint {
+    /// Returns the next window to list content blobs in, starting `content_delay` before the end of
+    /// the previous one.
+    ///
+    /// Without a previous window, the content blobs of the last 24 hours are listed.
+    fn window(
+        &self,
+        now: DateTime<Utc>,
+        content_delay: chrono::Duration,
+    ) -> (DateTime<Utc>, DateTime<Utc>) {
+        let max_window = chrono::Duration::hours(MAX_WINDOW_HOURS);
+        let start = match self.end_time {
+            Some(end_time) => end_time - content_delay,
+            None => now - max_window,
+        };
+        // Leave some leeway so that the start isn't past the retention when the request is handled.
+        let start =
+            start.max(now - chrono::Duration::days(RETENTION_DAYS) + chrono::Duration::minutes(5));
+        let end = (start + max_window).min(now);
+        (start, end)
+    }
+
+    fn contains(&self, blob: &ContentBlob) -> bool {
+        self.blobs.contains_key(&blob.content_id)
+    }
+
+    fn insert(&mut self, blob: &ContentBlob) {
+        self.blobs
+            .insert(blob.content_id.clone(), blob.content_created);
+    }
+
+    /// Moves past a window whose content blobs were all consumed, forgetting the blobs that won't be
+    /// listed again.
+    fn advance(&mut self, end_time: DateTime<Utc>, content_delay: chrono::Duration) {
+        self.end_time = Some(end_time);
+        let start = end_time - content_delay;
+        self.blobs.retain(|_, created| *created >= start);
+    }
+}
+
+/// A content type subscribed to, and its progress.
+struct Subscription {
+    content_type: ContentType,
+    started: bool,
+    checkpoint: Checkpoint,
+    checkpoint_store: CheckpointStore,
+}
+
+struct Microsoft365 {
+    client: Client,
+    interval: Duration,
+    content_delay: chrono::Duration,
+    /// The store of the progress of each content type subscribed to.
+    checkpoint_stores: Vec<(ContentType, CheckpointStore)>,
+    acknowledgements: bool,
+}
+
+impl Microsoft365 {
+    async fn run(mut self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut subscriptions = Vec::<Subscription>::new();
+        for (content_type, checkpoint_store) in std::mem::take(&mut self.checkpoint_stores) {
+            let checkpoint = checkpoint_store
+                .load_json_or_default()
+                .await
+                .map_err(|error| {
+                    error!(
+                        message = "Failed to load checkpoint.",
+                        location = %checkpoint_store.location(),
+                        %error,
+                    );
+                })?;
+            subscriptions.push(Subscription {
+                content_type,
+                started: false,
+                checkpoint,
+                checkpoint_store,
+            });
+        }
+
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {},
+            }
+
+            for subscription in &mut subscriptions {
+                // The progress of a content type is only checkpointed once its content blobs are
+                // delivered, so a poll can be interrupted at any point.
+                let result = select! {
+                    _ = &mut shutdown => return Ok(()),
+                    result = self.poll(subscription, &mut out) => result,
+                };
+                match result {
+                    Ok(()) => {}
+                    Err(PollError::Closed) => return Err(()),
+                    Err(error) => emit!(Microsoft365RequestError {
+                        error,
+                        content_type: subscription.content_type.as_str(),
+                    }),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the content blobs of a content type created since the previous poll.
+    async fn poll(
+        &mut self,
+        subscription: &mut Subscription,
+        out: &mut SourceSender,
+    ) -> Result<(), PollError> {
+        if !subscription.started {
+            self.client
+                .start_subscription(subscription.content_type)
+                .await
+                .context(ApiSnafu)?;
+            subscription.started = true;
+        }
+
+        loop {
+            let now = Utc::now();
+            let (start, end) = subscription.checkpoint.window(now, self.content_delay);
+            let blobs = self
+                .client
+                .list_content(subscription.content_type, start, end)
+                .await
+                .context(ApiSnafu)?;
+
+            for blob in blobs {
+                if subscription.checkpoint.contains(&blob) {
+                    continue;
+                }
+                let records = self.client.fetch_content(&blob).await.context(ApiSnafu)?;
+                self.send(records.into_iter().filter_map(into_event).collect(), out)
+                    .await?;
+                subscription.checkpoint.insert(&blob);
+                write_checkpoint(subscription).await;
+            }
+            subscription.checkpoint.advance(end, self.content_delay);
+            write_checkpoint(subscription).await;
+
+            if end >= now {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn send(&self, mut events: Vec<Event>, out: &mut SourceSender) -> Result<(), PollError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(self.acknowledgements, &mut events);
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+            PollError::Closed
+        })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored | BatchStatus::Rejected => Err(PollError::NotDelivered),
+            },
+        }
+    }
+}
+
+async fn write_checkpoint(subscription: &Subscription) {
+    if let Err(error) = subscription
+        .checkpoint_store
+        .save_json(&subscription.checkpoint)
+        .await
+    {
+        emit!(Microsoft365CheckpointError { error });
+    }
+}
+
+/// Converts an audit record into an event, timestamped with its `CreationTime`, which is in UTC
+/// without a time zone.
+fn into_event(record: serde_json::Value) -> Option<Event> {
+    let mut log = LogEvent::try_from(record).ok()?;
+    let timestamp = log
+        .get("CreationTime")
+        .and_then(|value| value.as_str())
+        .and_then(|value| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        .map(|timestamp| DateTime::<Utc>::from_utc(timestamp, Utc))
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(log_schema().source_type_key(), Bytes::from("microsoft_365"));
+    Some(log.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<Microsoft365Config>();
+    }
+
+    fn blob(content_id: &str, created: DateTime<Utc>) -> ContentBlob {
+        ContentBlob {
+            content_id: content_id.to_owned(),
+            content_uri: format!(
+                "https://manage.office.com/api/v1.0/tenant/activity/feed/audit/{}",
+                content_id
+            ),
+            content_created: created,
+        }
+    }
+
+    #[test]
+    fn computes_windows() {
+        let now = Utc.ymd(2022, 8, 10).and_hms(12, 0, 0);
+        let delay = chrono::Duration::hours(1);
+
+        assert_eq!(
+            Checkpoint::default().window(now, delay),
+            (Utc.ymd(2022, 8, 9).and_hms(12, 0, 0), now)
+        );
+
+        let checkpoint = Checkpoint {
+            end_time: Some(Utc.ymd(2022, 8, 10).and_hms(11, 59, 0)),
+            blobs: BTreeMap::new(),
+        };
+        assert_eq!(
+            checkpoint.window(now, delay),
+            (Utc.ymd(2022, 8, 10).and_hms(10, 59, 0), now)
+        );
+
+        // Catching up is done in windows of at most 24 hours, within the retention.
+        let checkpoint = Checkpoint {
+            end_time: Some(Utc.ymd(2022, 7, 1).and_hms(0, 0, 0)),
+            blobs: BTreeMap::new(),
+        };
+        assert_eq!(
+            checkpoint.window(now, delay),
+            (
+                Utc.ymd(2022, 8, 3).and_hms(12, 5, 0),
+                Utc.ymd(2022, 8, 4).and_hms(12, 5, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn skips_consumed_blobs_of_overlapping_windows() {
+        let delay = chrono::Duration::hours(1);
+        let mut checkpoint = Checkpoint::default();
+        let old = blob("old", Utc.ymd(2022, 8, 10).and_hms(9, 30, 0));
+        let recent = blob("recent", Utc.ymd(2022, 8, 10).and_hms(11, 30, 0));
+        checkpoint.insert(&old);
+        checkpoint.insert(&recent);
+        assert!(checkpoint.contains(&old));
+        assert!(!checkpoint.contains(&blob("new", Utc.ymd(2022, 8, 10).and_hms(11, 45, 0))));
+
+        // The blobs created before the start of the next window are forgotten.
+        checkpoint.advance(Utc.ymd(2022, 8, 10).and_hms(12, 0, 0), delay);
+        assert!(!checkpoint.contains(&old));
+        assert!(checkpoint.contains(&recent));
+    }
+
+    #[test]
+    fn converts_audit_record() {
+        let record = serde_json::json!({
+            "Id": "80c76bd2-9d81-4c57-a97a-accfc3443dca",
+            "RecordType": 15,
+            "CreationTime": "2022-08-01T00:00:02",
+            "Operation": "UserLoggedIn",
+            "Workload": "AzureActiveDirectory",
+            "UserId": "user@example.com"
+        });
+
+        let event = into_event(record).unwrap();
+        let log = event.as_log();
+        assert_eq!(log["Operation"], "UserLoggedIn".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2022, 8, 1).and_hms(0, 0, 2).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "microsoft_365".into());
+    }
+}
@@ -2,10 +2,16 @@ use snafu::Snafu;
 
 #[cfg(feature = "sources-apache_metrics")]
 pub mod apache_metrics;
+#[cfg(feature = "sources-aws_cloudtrail")]
+pub mod aws_cloudtrail;
+#[cfg(feature = "sources-aws_cloudwatch_logs")]
+pub mod aws_cloudwatch_logs;
 #[cfg(feature = "sources-aws_ecs_metrics")]
 pub mod aws_ecs_metrics;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
 pub mod aws_kinesis_firehose;
+#[cfg(feature = "sources-aws_kinesis_streams")]
+pub mod aws_kinesis_streams;
 #[cfg(feature = "sources-aws_s3")]
 pub mod aws_s3;
 #[cfg(feature = "sources-aws_sqs")]
@@ -18,6 +24,8 @@ pub mod demo_logs;
 pub mod dnstap;
 #[cfg(feature = "sources-docker_logs")]
 pub mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub mod ebpf;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
@@ -26,8 +34,18 @@ pub mod exec;
 pub mod file;
 #[cfg(feature = "sources-fluent")]
 pub mod fluent;
+#[cfg(feature = "sources-ftp")]
+pub mod ftp;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+pub mod gcp_cloud_logging;
+#[cfg(feature = "sources-gcp_cloud_monitoring")]
+pub mod gcp_cloud_monitoring;
+#[cfg(feature = "sources-gcp_cloud_storage")]
+pub mod gcp_cloud_storage;
 #[cfg(feature = "sources-gcp_pubsub")]
 pub mod gcp_pubsub;
+#[cfg(feature = "sources-grpc")]
+pub mod grpc;
 #[cfg(feature = "sources-heroku_logs")]
 pub mod heroku_logs;
 #[cfg(feature = "sources-host_metrics")]
@@ -38,30 +56,60 @@ pub mod http;
 pub mod internal_logs;
 #[cfg(feature = "sources-internal_metrics")]
 pub mod internal_metrics;
+#[cfg(feature = "sources-jmx_metrics")]
+pub mod jmx_metrics;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub mod journald;
 #[cfg(feature = "sources-kafka")]
 pub mod kafka;
+#[cfg(feature = "sources-kubernetes_events")]
+pub mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub mod kubernetes_logs;
 #[cfg(all(feature = "sources-logstash"))]
 pub mod logstash;
+#[cfg(feature = "sources-microsoft_365")]
+pub mod microsoft_365;
+#[cfg(feature = "sources-mongodb_change_stream")]
+pub mod mongodb_change_stream;
 #[cfg(feature = "sources-mongodb_metrics")]
 pub mod mongodb_metrics;
+#[cfg(feature = "sources-mqtt")]
+pub mod mqtt;
 #[cfg(all(feature = "sources-nats"))]
 pub mod nats;
+#[cfg(feature = "sources-netflow")]
+pub mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 pub mod nginx_metrics;
+#[cfg(feature = "sources-okta")]
+pub mod okta;
+#[cfg(feature = "sources-opentelemetry")]
+pub mod opentelemetry;
+#[cfg(feature = "sources-postgresql_cdc")]
+pub mod postgresql_cdc;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub mod postgresql_metrics;
 #[cfg(feature = "sources-prometheus")]
 pub mod prometheus;
+#[cfg(feature = "sources-rabbitmq_stream")]
+pub mod rabbitmq_stream;
 #[cfg(feature = "sources-redis")]
 pub mod redis;
+#[cfg(feature = "sources-relp")]
+pub mod relp;
+#[cfg(feature = "sources-sflow")]
+pub mod sflow;
+#[cfg(feature = "sources-snmp_metrics")]
+pub mod snmp_metrics;
+#[cfg(feature = "sources-snmp_trap")]
+pub mod snmp_trap;
 #[cfg(feature = "sources-socket")]
 pub mod socket;
 #[cfg(feature = "sources-splunk_hec")]
 pub mod splunk_hec;
+#[cfg(feature = "sources-sql_query")]
+pub mod sql_query;
 #[cfg(feature = "sources-statsd")]
 pub mod statsd;
 #[cfg(feature = "sources-stdin")]
@@ -70,6 +118,8 @@ pub mod stdin;
 pub mod syslog;
 #[cfg(feature = "sources-vector")]
 pub mod vector;
+#[cfg(feature = "sources-websocket")]
+pub mod websocket;
 
 pub(crate) mod util;
 
@@ -92,6 +142,14 @@ pub enum Sources {
     #[cfg(feature = "sources-apache_metrics")]
     ApacheMetrics(#[configurable(derived)] apache_metrics::ApacheMetricsConfig),
 
+    /// AWS CloudTrail.
+    #[cfg(feature = "sources-aws_cloudtrail")]
+    AwsCloudtrail(#[configurable(derived)] aws_cloudtrail::AwsCloudtrailConfig),
+
+    /// AWS CloudWatch Logs.
+    #[cfg(feature = "sources-aws_cloudwatch_logs")]
+    AwsCloudwatchLogs(#[configurable(derived)] aws_cloudwatch_logs::AwsCloudwatchLogsConfig),
+
     /// AWS ECS Metrics.
     #[cfg(feature = "sources-aws_ecs_metrics")]
     AwsEcsMetrics(#[configurable(derived)] aws_ecs_metrics::AwsEcsMetricsSourceConfig),
@@ -100,6 +158,10 @@ pub enum Sources {
     #[cfg(feature = "sources-aws_kinesis_firehose")]
     AwsKinesisFirehose(#[configurable(derived)] aws_kinesis_firehose::AwsKinesisFirehoseConfig),
 
+    /// AWS Kinesis Data Streams.
+    #[cfg(feature = "sources-aws_kinesis_streams")]
+    AwsKinesisStreams(#[configurable(derived)] aws_kinesis_streams::AwsKinesisStreamsConfig),
+
     /// AWS S3.
     #[cfg(feature = "sources-aws_s3")]
     AwsS3(#[configurable(derived)] aws_s3::AwsS3Config),
@@ -124,6 +186,10 @@ pub enum Sources {
     #[cfg(feature = "sources-docker_logs")]
     DockerLogs(#[configurable(derived)] docker_logs::DockerLogsConfig),
 
+    /// eBPF.
+    #[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+    Ebpf(#[configurable(derived)] ebpf::EbpfConfig),
+
     /// EventStoreDB Metrics.
     #[cfg(feature = "sources-eventstoredb_metrics")]
     EventstoreDbMetrics(#[configurable(derived)] eventstoredb_metrics::EventStoreDbConfig),
@@ -140,6 +206,22 @@ pub enum Sources {
     #[cfg(feature = "sources-fluent")]
     Fluent(#[configurable(derived)] fluent::FluentConfig),
 
+    /// FTP and SFTP.
+    #[cfg(feature = "sources-ftp")]
+    Ftp(#[configurable(derived)] ftp::FtpConfig),
+
+    /// GCP Cloud Logging.
+    #[cfg(feature = "sources-gcp_cloud_logging")]
+    GcpCloudLogging(#[configurable(derived)] gcp_cloud_logging::GcpCloudLoggingConfig),
+
+    /// GCP Cloud Monitoring.
+    #[cfg(feature = "sources-gcp_cloud_monitoring")]
+    GcpCloudMonitoring(#[configurable(derived)] gcp_cloud_monitoring::GcpCloudMonitoringConfig),
+
+    /// GCP Cloud Storage.
+    #[cfg(feature = "sources-gcp_cloud_storage")]
+    GcpCloudStorage(#[configurable(derived)] gcp_cloud_storage::GcsConfig),
+
     /// GCP Pub/Sub.
     #[cfg(feature = "sources-gcp_pubsub")]
     GcpPubsub(#[configurable(derived)] gcp_pubsub::PubsubConfig),
@@ -148,6 +230,10 @@ pub enum Sources {
     #[cfg(feature = "sources-demo_logs")]
     Generator(#[configurable(derived)] demo_logs::DemoLogsCompatConfig),
 
+    /// gRPC.
+    #[cfg(feature = "sources-grpc")]
+    Grpc(#[configurable(derived)] grpc::GrpcConfig),
+
     /// Heroku Logs.
     #[cfg(feature = "sources-heroku_logs")]
     HerokuLogs(#[configurable(derived)] heroku_logs::LogplexConfig),
@@ -168,6 +254,10 @@ pub enum Sources {
     #[cfg(feature = "sources-internal_metrics")]
     InternalMetrics(#[configurable(derived)] internal_metrics::InternalMetricsConfig),
 
+    /// JMX Metrics.
+    #[cfg(feature = "sources-jmx_metrics")]
+    JmxMetrics(#[configurable(derived)] jmx_metrics::JmxMetricsConfig),
+
     /// Journald.
     #[cfg(all(unix, feature = "sources-journald"))]
     Journald(#[configurable(derived)] journald::JournaldConfig),
@@ -176,6 +266,10 @@ pub enum Sources {
     #[cfg(feature = "sources-kafka")]
     Kafka(#[configurable(derived)] kafka::KafkaSourceConfig),
 
+    /// Kubernetes Events.
+    #[cfg(feature = "sources-kubernetes_events")]
+    KubernetesEvents(#[configurable(derived)] kubernetes_events::KubernetesEventsConfig),
+
     /// Kubernetes Logs.
     #[cfg(feature = "sources-kubernetes_logs")]
     KubernetesLogs(#[configurable(derived)] kubernetes_logs::Config),
@@ -188,18 +282,46 @@ pub enum Sources {
     #[cfg(all(feature = "sources-logstash"))]
     Logstash(#[configurable(derived)] logstash::LogstashConfig),
 
+    /// Microsoft 365.
+    #[cfg(feature = "sources-microsoft_365")]
+    Microsoft365(#[configurable(derived)] microsoft_365::Microsoft365Config),
+
+    /// MongoDB Change Stream.
+    #[cfg(feature = "sources-mongodb_change_stream")]
+    MongodbChangeStream(#[configurable(derived)] mongodb_change_stream::MongoDbChangeStreamConfig),
+
     /// MongoDB Metrics.
     #[cfg(feature = "sources-mongodb_metrics")]
     MongodbMetrics(#[configurable(derived)] mongodb_metrics::MongoDbMetricsConfig),
 
+    /// MQTT.
+    #[cfg(feature = "sources-mqtt")]
+    Mqtt(#[configurable(derived)] mqtt::MqttSourceConfig),
+
     /// NATS.
     #[cfg(all(feature = "sources-nats"))]
     Nats(#[configurable(derived)] nats::NatsSourceConfig),
 
+    /// NetFlow.
+    #[cfg(feature = "sources-netflow")]
+    Netflow(#[configurable(derived)] netflow::NetflowConfig),
+
     /// NGINX Metrics.
     #[cfg(feature = "sources-nginx_metrics")]
     NginxMetrics(#[configurable(derived)] nginx_metrics::NginxMetricsConfig),
 
+    /// Okta.
+    #[cfg(feature = "sources-okta")]
+    Okta(#[configurable(derived)] okta::OktaConfig),
+
+    /// OpenTelemetry.
+    #[cfg(feature = "sources-opentelemetry")]
+    Opentelemetry(#[configurable(derived)] opentelemetry::OpentelemetryConfig),
+
+    /// PostgreSQL CDC.
+    #[cfg(feature = "sources-postgresql_cdc")]
+    PostgresqlCdc(#[configurable(derived)] postgresql_cdc::PostgresqlCdcConfig),
+
     /// PostgreSQL Metrics.
     #[cfg(feature = "sources-postgresql_metrics")]
     PostgresqlMetrics(#[configurable(derived)] postgresql_metrics::PostgresqlMetricsConfig),
@@ -212,10 +334,30 @@ pub enum Sources {
     #[cfg(feature = "sources-prometheus")]
     PrometheusRemoteWrite(#[configurable(derived)] prometheus::PrometheusRemoteWriteConfig),
 
+    /// RabbitMQ Stream.
+    #[cfg(feature = "sources-rabbitmq_stream")]
+    RabbitmqStream(#[configurable(derived)] rabbitmq_stream::RabbitMqStreamConfig),
+
     /// Redis.
     #[cfg(feature = "sources-redis")]
     Redis(#[configurable(derived)] redis::RedisSourceConfig),
 
+    /// RELP.
+    #[cfg(feature = "sources-relp")]
+    Relp(#[configurable(derived)] relp::RelpConfig),
+
+    /// sFlow.
+    #[cfg(feature = "sources-sflow")]
+    Sflow(#[configurable(derived)] sflow::SflowConfig),
+
+    /// SNMP Metrics.
+    #[cfg(feature = "sources-snmp_metrics")]
+    SnmpMetrics(#[configurable(derived)] snmp_metrics::SnmpMetricsConfig),
+
+    /// SNMP Trap.
+    #[cfg(feature = "sources-snmp_trap")]
+    SnmpTrap(#[configurable(derived)] snmp_trap::SnmpTrapConfig),
+
     /// Socket.
     #[cfg(feature = "sources-socket")]
     Socket(#[configurable(derived)] socket::SocketConfig),
@@ -224,6 +366,10 @@ pub enum Sources {
     #[cfg(feature = "sources-splunk_hec")]
     SplunkHec(#[configurable(derived)] splunk_hec::SplunkConfig),
 
+    /// SQL Query.
+    #[cfg(feature = "sources-sql_query")]
+    SqlQuery(#[configurable(derived)] sql_query::SqlQueryConfig),
+
     /// Statsd.
     #[cfg(feature = "sources-statsd")]
     Statsd(#[configurable(derived)] statsd::StatsdConfig),
@@ -239,6 +385,10 @@ pub enum Sources {
     /// Vector.
     #[cfg(feature = "sources-vector")]
     Vector(#[configurable(derived)] vector::VectorConfig),
+
+    /// WebSocket.
+    #[cfg(feature = "sources-websocket")]
+    WebSocket(#[configurable(derived)] websocket::WebSocketSourceConfig),
 }
 
 #[cfg(test)]
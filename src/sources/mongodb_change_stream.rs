@@ -0,0 +1,537 @@
+//! This mod implements the `mongodb_change_stream` source.
+//! It opens a change stream on a collection, a database or a whole deployment, and emits each
+//! change as a log, persisting the resume token of the last delivered batch.
+
+use std::{path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use mongodb::{
+    bson::{self, Bson, Document},
+    change_stream::{event::ResumeToken, ChangeStream},
+    error::{Error as MongoError, ErrorKind},
+    options::{ChangeStreamOptions, ClientOptions, FullDocumentType},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, Value},
+    internal_events::{
+        EventsReceived, MongoDbChangeStreamCheckpointError, MongoDbChangeStreamError,
+        MongoDbChangeStreamHistoryLost, StreamClosedError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    SourceSender,
+};
+
+/// How long to wait before opening the change stream again after an error.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The server error codes returned when the resume token is no longer in the oplog.
+const CHANGE_STREAM_FATAL_ERROR: i32 = 280;
+const CHANGE_STREAM_HISTORY_LOST: i32 = 286;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint: {}", source))]
+    InvalidEndpoint { source: MongoError },
+    #[snafu(display("invalid client options: {}", source))]
+    InvalidClientOptions { source: MongoError },
+    #[snafu(display("`database` must be set when `collection` is set"))]
+    DatabaseMissing,
+}
+
+/// Whether change events of updates contain the full document.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FullDocument {
+    /// Update events only contain the `updateDescription` delta.
+    Default,
+
+    /// Update events also contain the current version of the full document, looked up when the
+    /// event is read.
+    UpdateLookup,
+}
+
+/// Configuration for the `mongodb_change_stream` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbChangeStreamConfig {
+    /// The MongoDB deployment to watch, which must be a replica set or a sharded cluster.
+    ///
+    /// Must be in the [Connection String URI Format](https://www.mongodb.com/docs/manual/reference/connection-string/).
+    endpoint: String,
+
+    /// The database to watch.
+    ///
+    /// If not set, all the databases of the deployment are watched.
+    #[serde(default)]
+    database: Option<String>,
+
+    /// The collection to watch.
+    ///
+    /// If not set, all the collections of `database` are watched.
+    #[serde(default)]
+    collection: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_full_document")]
+    full_document: FullDocument,
+
+    /// The maximum number of change events to send downstream at once.
+    ///
+    /// Events already received from the server are batched together, up to this number.
+    #[serde(default = "default_batch_size")]
+    batch_size: u32,
+
+    /// The directory used to persist the resume token.
+    ///
+    /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
+    #[serde(default)]
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the resume token.
+    ///
+    /// By default, it's persisted in a file of `data_dir`.
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_full_document() -> FullDocument {
+    FullDocument::Default
+}
+
+const fn default_batch_size() -> u32 {
+    100
+}
+
+inventory::submit! {
+    SourceDescription::new::<MongoDbChangeStreamConfig>("mongodb_change_stream")
+}
+
+impl GenerateConfig for MongoDbChangeStreamConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "mongodb://localhost:27017/?replicaSet=rs0"
+            database = "app"
+            collection = "orders""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mongodb_change_stream")]
+impl SourceConfig for MongoDbChangeStreamConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.collection.is_some() && self.database.is_none() {
+            return Err(BuildError::DatabaseMissing.into());
+        }
+
+        let client_options = ClientOptions::parse(&self.endpoint)
+            .await
+            .context(InvalidEndpointSnafu)?;
+        let client = Client::with_options(client_options).context(InvalidClientOptionsSnafu)?;
+
+        let checkpoint_store = self
+            .checkpoint_store
+            .build(&cx, self.data_dir.as_ref(), "checkpoint.json")
+            .await?;
+
+        let source = MongoDbChangeStream {
+            client,
+            database: self.database.clone(),
+            collection: self.collection.clone(),
+            full_document: self.full_document,
+            batch_size: self.batch_size.max(1),
+            checkpoint_store,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mongodb_change_stream"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// The position the source resumes from after a restart.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Checkpoint {
+    resume_token: Option<ResumeToken>,
+}
+
+/// The on-disk format of the checkpoint, with the resume token as relaxed extended JSON.
+#[derive(Deserialize, Serialize)]
+struct CheckpointFile {
+    resume_token: Option<serde_json::Value>,
+}
+
+impl Checkpoint {
+    async fn load(store: &CheckpointStore) -> crate::Result<Self> {
+        let data = match store.load().await? {
+            Some(data) => data,
+            None => return Ok(Self::default()),
+        };
+
+        let resume_token = serde_json::from_slice::<CheckpointFile>(&data)
+            .map_err(|error| error.to_string())
+            .and_then(|file| {
+                file.resume_token
+                    .map(|token| {
+                        Bson::try_from(token)
+                            .map_err(|error| error.to_string())
+                            .and_then(|token| {
+                                bson::from_bson(token).map_err(|error| error.to_string())
+                            })
+                    })
+                    .transpose()
+            });
+        match resume_token {
+            Ok(resume_token) => Ok(Self { resume_token }),
+            Err(error) => {
+                warn!(message = "Ignoring invalid checkpoint.", location = %store.location(), %error);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    async fn write(&self, store: &CheckpointStore) -> crate::Result<()> {
+        let resume_token = self
+            .resume_token
+            .as_ref()
+            .map(bson::to_bson)
+            .transpose()?
+            .map(Bson::into_relaxed_extjson);
+
+        store.save_json(&CheckpointFile { resume_token }).await
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum StreamError {
+    #[snafu(display("change stream failed: {}", source))]
+    Mongo { source: MongoError },
+    #[snafu(display("events were not delivered"))]
+    NotDelivered,
+    #[snafu(display("source is shutting down"))]
+    Closed,
+}
+
+struct MongoDbChangeStream {
+    client: Client,
+    database: Option<String>,
+    collection: Option<String>,
+    full_document: FullDocument,
+    batch_size: u32,
+    checkpoint_store: CheckpointStore,
+    acknowledgements: bool,
+}
+
+impl MongoDbChangeStream {
+    fn namespace(&self) -> String {
+        match (&self.database, &self.collection) {
+            (Some(database), Some(collection)) => format!("{}.{}", database, collection),
+            (Some(database), None) => database.clone(),
+            _ => String::new(),
+        }
+    }
+
+    async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        // Without the resume token, the changes made meanwhile would be missed.
+        let mut checkpoint = Checkpoint::load(&self.checkpoint_store)
+            .await
+            .map_err(|error| {
+                error!(
+                    message = "Failed to load checkpoint.",
+                    location = %self.checkpoint_store.location(),
+                    %error,
+                );
+            })?;
+        let namespace = self.namespace();
+
+        loop {
+            let result = tokio::select! {
+                result = self.watch(&mut checkpoint, &mut out) => result,
+                _ = &mut shutdown => return Ok(()),
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(StreamError::Closed) => return Err(()),
+                Err(StreamError::Mongo { source }) if is_history_lost(&source) => {
+                    // The changes since the resume token are no longer in the oplog, so the only
+                    // way forward is to start again from the current position.
+                    emit!(MongoDbChangeStreamHistoryLost {
+                        error: &source,
+                        namespace: &namespace,
+                    });
+                    checkpoint.resume_token = None;
+                    self.write_checkpoint(&checkpoint, &namespace).await;
+                    continue;
+                }
+                Err(error) => {
+                    emit!(MongoDbChangeStreamError {
+                        error,
+                        namespace: &namespace,
+                    });
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(RETRY_DELAY) => {}
+                _ = &mut shutdown => return Ok(()),
+            }
+        }
+    }
+
+    async fn open(&self, checkpoint: &Checkpoint) -> Result<ChangeStream<Document>, MongoError> {
+        let options = ChangeStreamOptions::builder()
+            .full_document(match self.full_document {
+                FullDocument::Default => None,
+                FullDocument::UpdateLookup => Some(FullDocumentType::UpdateLookup),
+            })
+            .resume_after(checkpoint.resume_token.clone())
+            .batch_size(self.batch_size)
+            .build();
+
+        let stream = match (&self.database, &self.collection) {
+            (Some(database), Some(collection)) => {
+                self.client
+                    .database(database)
+                    .collection::<Document>(collection)
+                    .watch(None, options)
+                    .await?
+            }
+            (Some(database), None) => self.client.database(database).watch(None, options).await?,
+            _ => self.client.watch(None, options).await?,
+        };
+
+        // The raw change events are kept, so that they are emitted as they were received.
+        Ok(stream.with_type::<Document>())
+    }
+
+    /// Reads the change stream in batches, only checkpointing the resume token of a batch once
+    /// it was delivered downstream.
+    async fn watch(
+        &self,
+        checkpoint: &mut Checkpoint,
+        out: &mut SourceSender,
+    ) -> Result<(), StreamError> {
+        let namespace = self.namespace();
+        let stream = self.open(checkpoint).await.context(MongoSnafu)?;
+
+        // Only the events already received are batched, the stream is not read further until
+        // the batch is sent, which propagates backpressure to the server cursor.
+        let mut batches = stream.ready_chunks(self.batch_size as usize);
+        while let Some(batch) = batches.next().await {
+            let documents = batch
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .context(MongoSnafu)?;
+            let resume_token = batches.get_ref().resume_token();
+
+            self.send(documents.into_iter().map(into_event).collect(), out)
+                .await?;
+
+            if resume_token.is_some() && resume_token != checkpoint.resume_token {
+                checkpoint.resume_token = resume_token;
+                self.write_checkpoint(checkpoint, &namespace).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        mut events: Vec<Event>,
+        out: &mut SourceSender,
+    ) -> Result<(), StreamError> {
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(self.acknowledgements, &mut events);
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+            StreamError::Closed
+        })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored | BatchStatus::Rejected => Err(StreamError::NotDelivered),
+            },
+        }
+    }
+
+    async fn write_checkpoint(&self, checkpoint: &Checkpoint, namespace: &str) {
+        if let Err(error) = checkpoint.write(&self.checkpoint_store).await {
+            emit!(MongoDbChangeStreamCheckpointError { error, namespace });
+        }
+    }
+}
+
+fn is_history_lost(error: &MongoError) -> bool {
+    matches!(
+        *error.kind,
+        ErrorKind::Command(ref error)
+            if error.code == CHANGE_STREAM_FATAL_ERROR || error.code == CHANGE_STREAM_HISTORY_LOST
+    )
+}
+
+fn into_event(mut document: Document) -> Event {
+    // The `_id` is the resume token, which is only meaningful to the server.
+    document.remove("_id");
+
+    let timestamp = match document.get("wallTime") {
+        Some(Bson::DateTime(time)) => Some(Utc.timestamp_millis(time.timestamp_millis())),
+        _ => match document.get("clusterTime") {
+            Some(Bson::Timestamp(time)) => Some(Utc.timestamp(time.time.into(), 0)),
+            _ => None,
+        },
+    };
+
+    let mut log = match bson_to_value(Bson::Document(document)) {
+        Value::Object(fields) => LogEvent::from(fields),
+        _ => unreachable!("documents are converted into objects"),
+    };
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp.unwrap_or_else(Utc::now),
+    );
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("mongodb_change_stream"),
+    );
+    log.into()
+}
+
+/// Converts BSON into the equivalent value, falling back to relaxed extended JSON for the types
+/// without one.
+fn bson_to_value(bson: Bson) -> Value {
+    match bson {
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Boolean(value) => Value::Boolean(value),
+        Bson::Int32(value) => Value::Integer(value.into()),
+        Bson::Int64(value) => Value::Integer(value),
+        Bson::Double(value) => Value::from(value),
+        Bson::String(value) => Value::from(value),
+        Bson::ObjectId(value) => Value::from(value.to_hex()),
+        Bson::DateTime(value) => Value::Timestamp(Utc.timestamp_millis(value.timestamp_millis())),
+        Bson::Decimal128(value) => Value::from(value.to_string()),
+        Bson::Array(values) => Value::Array(values.into_iter().map(bson_to_value).collect()),
+        Bson::Document(document) => Value::Object(
+            document
+                .into_iter()
+                .map(|(key, value)| (key, bson_to_value(value)))
+                .collect(),
+        ),
+        bson => Value::from(bson.into_relaxed_extjson()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, oid::ObjectId, DateTime, Timestamp};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MongoDbChangeStreamConfig>();
+    }
+
+    #[test]
+    fn converts_change_event() {
+        let id = ObjectId::parse_str("62a0c4f4b5a3d2c1e0f9a8b7").unwrap();
+        let event = into_event(doc! {
+            "_id": { "_data": "8262A0C4F4000000012B022C0100296E5A1004" },
+            "operationType": "update",
+            "clusterTime": Timestamp { time: 1_654_703_348, increment: 1 },
+            "ns": { "db": "app", "coll": "orders" },
+            "documentKey": { "_id": id },
+            "updateDescription": {
+                "updatedFields": { "status": "shipped", "total": 12.5 },
+                "removedFields": ["note"],
+            },
+            "fullDocument": {
+                "_id": id,
+                "status": "shipped",
+                "items": [1_i32, 2_i64],
+                "createdAt": DateTime::from_millis(1_654_703_000_000),
+            },
+        });
+
+        let log = event.as_log();
+        assert!(log.get("_id").is_none());
+        assert_eq!(log["operationType"], "update".into());
+        assert_eq!(log["ns.coll"], "orders".into());
+        assert_eq!(log["documentKey._id"], "62a0c4f4b5a3d2c1e0f9a8b7".into());
+        assert_eq!(log["updateDescription.updatedFields.total"], 12.5.into());
+        assert_eq!(log["fullDocument.items[1]"], 2.into());
+        assert_eq!(
+            log["fullDocument.createdAt"],
+            Utc.timestamp_millis(1_654_703_000_000).into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_654_703_348, 0).into()
+        );
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "mongodb_change_stream".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::file(dir.path().join("checkpoint.json"));
+        assert_eq!(
+            Checkpoint::load(&store).await.unwrap(),
+            Checkpoint::default()
+        );
+
+        let checkpoint = Checkpoint {
+            resume_token: Some(
+                bson::from_bson(Bson::Document(
+                    doc! { "_data": "8262A0C4F4000000012B022C0100296E5A1004" },
+                ))
+                .unwrap(),
+            ),
+        };
+        checkpoint.write(&store).await.unwrap();
+        assert_eq!(Checkpoint::load(&store).await.unwrap(), checkpoint);
+    }
+}
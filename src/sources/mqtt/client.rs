@@ -0,0 +1,127 @@
+//! A thin layer over the MQTT 3.1.1 and MQTT 5 clients of `rumqttc`, which have distinct APIs.
+
+use bytes::Bytes;
+use rumqttc::v5;
+
+use super::MqttQos;
+
+/// A handle to send requests, such as subscriptions and acknowledgements, to the event loop.
+#[derive(Clone)]
+pub enum Client {
+    V3(rumqttc::AsyncClient),
+    V5(v5::AsyncClient),
+}
+
+/// The event loop, which maintains the connection and must be polled to make progress.
+pub enum EventLoop {
+    V3(rumqttc::EventLoop),
+    V5(v5::EventLoop),
+}
+
+/// A received message, kept to acknowledge it once its events are delivered.
+#[derive(Debug)]
+pub enum Publish {
+    V3(rumqttc::Publish),
+    V5(v5::mqttbytes::v5::Publish),
+}
+
+pub enum Incoming {
+    Connected { session_present: bool },
+    Publish(Publish),
+    Other,
+}
+
+impl Publish {
+    pub fn topic(&self) -> String {
+        match self {
+            Self::V3(publish) => publish.topic.clone(),
+            Self::V5(publish) => String::from_utf8_lossy(&publish.topic).into_owned(),
+        }
+    }
+
+    pub const fn payload(&self) -> &Bytes {
+        match self {
+            Self::V3(publish) => &publish.payload,
+            Self::V5(publish) => &publish.payload,
+        }
+    }
+}
+
+impl Client {
+    /// Queues the subscription to the given topic filters.
+    ///
+    /// This doesn't wait for the event loop, which must keep being polled to send it.
+    pub fn subscribe(&self, topics: &[String], qos: MqttQos) -> crate::Result<()> {
+        match self {
+            Self::V3(client) => client.try_subscribe_many(
+                topics
+                    .iter()
+                    .map(|topic| rumqttc::SubscribeFilter::new(topic.clone(), qos.into())),
+            )?,
+            Self::V5(client) => client.try_subscribe_many(
+                topics
+                    .iter()
+                    .map(|topic| v5::mqttbytes::v5::Filter::new(topic.clone(), qos.into())),
+            )?,
+        }
+        Ok(())
+    }
+
+    pub async fn ack(&self, publish: &Publish) -> crate::Result<()> {
+        match (self, publish) {
+            (Self::V3(client), Publish::V3(publish)) => client.ack(publish).await?,
+            (Self::V5(client), Publish::V5(publish)) => client.ack(publish).await?,
+            _ => unreachable!("messages are received with the same protocol version"),
+        }
+        Ok(())
+    }
+}
+
+impl EventLoop {
+    pub async fn poll(&mut self) -> crate::Result<Incoming> {
+        Ok(match self {
+            Self::V3(eventloop) => match eventloop.poll().await? {
+                rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(connack)) => {
+                    Incoming::Connected {
+                        session_present: connack.session_present,
+                    }
+                }
+                rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish)) => {
+                    Incoming::Publish(Publish::V3(publish))
+                }
+                _ => Incoming::Other,
+            },
+            Self::V5(eventloop) => match eventloop.poll().await? {
+                v5::Event::Incoming(v5::mqttbytes::v5::Packet::ConnAck(connack)) => {
+                    Incoming::Connected {
+                        session_present: connack.session_present,
+                    }
+                }
+                v5::Event::Incoming(v5::mqttbytes::v5::Packet::Publish(publish)) => {
+                    Incoming::Publish(Publish::V5(publish))
+                }
+                _ => Incoming::Other,
+            },
+        })
+    }
+}
+
+impl From<MqttQos> for rumqttc::QoS {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => Self::AtMostOnce,
+            MqttQos::AtLeastOnce => Self::AtLeastOnce,
+            MqttQos::ExactlyOnce => Self::ExactlyOnce,
+        }
+    }
+}
+
+impl From<MqttQos> for v5::mqttbytes::QoS {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => Self::AtMostOnce,
+            MqttQos::AtLeastOnce => Self::AtLeastOnce,
+            MqttQos::ExactlyOnce => Self::ExactlyOnce,
+        }
+    }
+}
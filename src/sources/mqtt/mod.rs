@@ -0,0 +1,483 @@
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::{Stream, StreamExt};
+use rumqttc::{v5, Transport};
+use snafu::{ResultExt, Snafu};
+use tokio_util::codec::FramedRead;
+use vector_common::finalizer::OrderedFinalizer;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        BytesReceived, EventsReceived, MqttAcknowledgementError, MqttConnectionError,
+        MqttNegativeAcknowledgementError, MqttSubscribeError, StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    tls::TlsEnableableConfig,
+    SourceSender,
+};
+
+mod client;
+use client::{Client, EventLoop, Incoming, Publish};
+
+/// The capacity of the channel carrying requests, such as acknowledgements, to the event loop.
+const REQUEST_CHANNEL_CAPACITY: usize = 1024;
+
+/// The delay before reconnecting after the connection failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one topic must be subscribed to"))]
+    NoTopics,
+    #[snafu(display("A `client_id` must be set to use persistent sessions"))]
+    MissingClientId,
+    #[snafu(display("Could not read TLS file {:?}: {}", path, source))]
+    TlsFile { source: io::Error, path: PathBuf },
+}
+
+/// Configuration for the `mqtt` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSourceConfig {
+    /// The host of the MQTT broker to connect to.
+    host: String,
+
+    /// The port of the MQTT broker to connect to.
+    #[serde(default = "default_port")]
+    #[derivative(Default(value = "default_port()"))]
+    port: u16,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    version: MqttVersion,
+
+    /// The topic filters to subscribe to.
+    ///
+    /// The `+` and `#` wildcards are supported, such as in `sensors/+/temperature`.
+    topics: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    qos: MqttQos,
+
+    /// The client ID identifying this client to the broker.
+    ///
+    /// If not set, a random client ID is generated. It must be set to use persistent sessions,
+    /// which are bound to it.
+    client_id: Option<String>,
+
+    /// Whether or not to keep the session across connections.
+    ///
+    /// The broker keeps the subscriptions of persistent sessions, and the messages of subscriptions
+    /// with a QoS above 0 while the client is disconnected, including those whose events were not
+    /// yet acknowledged.
+    #[serde(default)]
+    persistent_session: bool,
+
+    /// The time, in seconds, the broker keeps a persistent session after the client disconnected.
+    ///
+    /// Only relevant for MQTT 5, as MQTT 3.1.1 brokers keep persistent sessions indefinitely.
+    #[serde(default = "default_session_expiry_interval_secs")]
+    #[derivative(Default(value = "default_session_expiry_interval_secs()"))]
+    session_expiry_interval_secs: u32,
+
+    /// The interval, in seconds, at which the connection is kept alive.
+    #[serde(default = "default_keep_alive_secs")]
+    #[derivative(Default(value = "default_keep_alive_secs()"))]
+    keep_alive_secs: u64,
+
+    /// The username to authenticate with.
+    user: Option<String>,
+
+    /// The password to authenticate with.
+    password: Option<String>,
+
+    /// Overrides the name of the log field used to add the topic to each event.
+    ///
+    /// The value will be the topic the message was published to.
+    ///
+    /// By default, `"topic"` is used.
+    #[serde(default = "default_topic_key")]
+    #[derivative(Default(value = "default_topic_key()"))]
+    topic_key: String,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// The version of the MQTT protocol.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+pub enum MqttVersion {
+    /// MQTT 3.1.1.
+    #[derivative(Default)]
+    #[serde(rename = "3.1.1")]
+    V3,
+
+    /// MQTT 5.
+    #[serde(rename = "5")]
+    V5,
+}
+
+/// The quality of service of subscriptions.
+///
+/// Messages are delivered with the lowest of this QoS and the QoS they were published with.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttQos {
+    /// QoS 0, messages are delivered at most once.
+    AtMostOnce,
+
+    /// QoS 1, messages are delivered at least once.
+    ///
+    /// With acknowledgements enabled, messages are acknowledged to the broker once their events
+    /// are delivered.
+    #[derivative(Default)]
+    AtLeastOnce,
+
+    /// QoS 2, messages are delivered exactly once.
+    ExactlyOnce,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+const fn default_session_expiry_interval_secs() -> u32 {
+    86400
+}
+
+const fn default_keep_alive_secs() -> u64 {
+    60
+}
+
+fn default_topic_key() -> String {
+    "topic".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<MqttSourceConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "localhost"
+            topics = ["vector/#"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        // Messages are acknowledged by the client as soon as they are received, unless their
+        // acknowledgement is deferred until their events are delivered.
+        let (client, eventloop) = self.connect(acknowledgements)?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+
+        Ok(Box::pin(mqtt_source(
+            self.clone(),
+            client,
+            eventloop,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl MqttSourceConfig {
+    fn connect(&self, manual_acks: bool) -> Result<(Client, EventLoop), BuildError> {
+        if self.topics.is_empty() {
+            return Err(BuildError::NoTopics);
+        }
+        let client_id = match &self.client_id {
+            Some(client_id) => client_id.clone(),
+            None if self.persistent_session => return Err(BuildError::MissingClientId),
+            None => format!("vector-{:08x}", rand::random::<u32>()),
+        };
+        let keep_alive = Duration::from_secs(self.keep_alive_secs);
+        let transport = self.transport()?;
+
+        Ok(match self.version {
+            MqttVersion::V3 => {
+                let mut options = rumqttc::MqttOptions::new(client_id, &self.host, self.port);
+                options
+                    .set_keep_alive(keep_alive)
+                    .set_clean_session(!self.persistent_session)
+                    .set_manual_acks(manual_acks)
+                    .set_transport(transport);
+                if let Some(user) = &self.user {
+                    options.set_credentials(user, self.password.as_deref().unwrap_or_default());
+                }
+                let (client, eventloop) =
+                    rumqttc::AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY);
+                (Client::V3(client), EventLoop::V3(eventloop))
+            }
+            MqttVersion::V5 => {
+                let mut options = v5::MqttOptions::new(client_id, &self.host, self.port);
+                options
+                    .set_keep_alive(keep_alive)
+                    .set_clean_start(!self.persistent_session)
+                    .set_manual_acks(manual_acks)
+                    .set_transport(transport);
+                if self.persistent_session {
+                    options.set_session_expiry_interval(Some(self.session_expiry_interval_secs));
+                }
+                if let Some(user) = &self.user {
+                    options.set_credentials(user, self.password.as_deref().unwrap_or_default());
+                }
+                let (client, eventloop) = v5::AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY);
+                (Client::V5(client), EventLoop::V5(eventloop))
+            }
+        })
+    }
+
+    fn transport(&self) -> Result<Transport, BuildError> {
+        let tls = match &self.tls {
+            Some(tls) if tls.enabled.unwrap_or(false) => &tls.options,
+            _ => return Ok(Transport::Tcp),
+        };
+        let read = |path: &PathBuf| fs::read(path).context(TlsFileSnafu { path: path.clone() });
+        let client_auth = match (&tls.crt_file, &tls.key_file) {
+            (Some(crt_file), Some(key_file)) => Some((read(crt_file)?, read(key_file)?)),
+            _ => None,
+        };
+        Ok(match &tls.ca_file {
+            Some(ca_file) => Transport::tls(read(ca_file)?, client_auth, None),
+            None => Transport::tls_with_default_config(),
+        })
+    }
+}
+
+async fn mqtt_source(
+    config: MqttSourceConfig,
+    client: Client,
+    mut eventloop: EventLoop,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let (finalizer, ack_stream) =
+        OrderedFinalizer::<Publish>::maybe_new(acknowledgements, shutdown.clone());
+    // Acknowledgements are sent through the event loop, so they are handled in a separate task to
+    // not wait on the event loop while it is not being polled.
+    tokio::spawn(handle_acks(client.clone(), ack_stream));
+
+    loop {
+        let incoming = tokio::select! {
+            _ = &mut shutdown => break,
+            incoming = eventloop.poll() => incoming,
+        };
+        match incoming {
+            Ok(Incoming::Connected { session_present }) => {
+                // Persistent sessions keep their subscriptions across connections.
+                if !session_present {
+                    if let Err(error) = client.subscribe(&config.topics, config.qos) {
+                        emit!(MqttSubscribeError { error });
+                    }
+                }
+            }
+            Ok(Incoming::Publish(publish)) => {
+                handle_publish(&config, publish, &decoder, &finalizer, &mut out).await?;
+            }
+            Ok(Incoming::Other) => {}
+            Err(error) => {
+                emit!(MqttConnectionError { error });
+                // The event loop reconnects when it is polled again.
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_publish(
+    config: &MqttSourceConfig,
+    publish: Publish,
+    decoder: &Decoder,
+    finalizer: &Option<OrderedFinalizer<Publish>>,
+    out: &mut SourceSender,
+) -> Result<(), ()> {
+    emit!(BytesReceived {
+        byte_size: publish.payload().len(),
+        protocol: "tcp",
+    });
+
+    let topic = publish.topic();
+    let payload = publish.payload().clone();
+    let now = Utc::now();
+    let mut events = Vec::new();
+    let mut stream = FramedRead::new(payload.as_ref(), decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((decoded, _byte_size)) => {
+                for mut event in decoded {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(log_schema().source_type_key(), Bytes::from("mqtt"));
+                        log.try_insert(log_schema().timestamp_key(), now);
+                        log.insert(config.topic_key.as_str(), topic.clone());
+                    }
+                    events.push(event);
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let count = events.len();
+    emit!(EventsReceived {
+        count,
+        byte_size: events.size_of(),
+    });
+
+    let receiver = BatchNotifier::maybe_apply_to(finalizer.is_some(), &mut events);
+    out.send_batch(events).await.map_err(|error| {
+        emit!(StreamClosedError { error, count });
+    })?;
+    // Messages without events are acknowledged as well, once the preceding ones are.
+    if let (Some(finalizer), Some(receiver)) = (finalizer, receiver) {
+        finalizer.add(publish, receiver);
+    }
+    Ok(())
+}
+
+async fn handle_acks(
+    client: Client,
+    mut ack_stream: impl Stream<Item = (BatchStatus, Publish)> + Unpin,
+) {
+    while let Some((status, publish)) = ack_stream.next().await {
+        if status == BatchStatus::Delivered {
+            if let Err(error) = client.ack(&publish).await {
+                emit!(MqttAcknowledgementError { error });
+            }
+        } else {
+            // The message is left unacknowledged, so that the broker delivers it again when
+            // the session is resumed.
+            emit!(MqttNegativeAcknowledgementError {
+                topic: &publish.topic(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    fn config(toml: &str) -> MqttSourceConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn parses_versions() {
+        let config = config(
+            r#"
+            host = "localhost"
+            topics = ["sensors/+/temperature"]
+            version = "5"
+            qos = "exactly_once"
+            "#,
+        );
+        assert_eq!(config.version, MqttVersion::V5);
+        assert_eq!(config.qos, MqttQos::ExactlyOnce);
+        assert_eq!(config.port, 1883);
+    }
+
+    #[tokio::test]
+    async fn persistent_sessions_require_client_id() {
+        let without_client_id = config(
+            r#"
+            host = "localhost"
+            topics = ["vector/#"]
+            persistent_session = true
+            "#,
+        );
+        assert!(matches!(
+            without_client_id.connect(true),
+            Err(BuildError::MissingClientId)
+        ));
+
+        let with_client_id = MqttSourceConfig {
+            client_id: Some("vector".into()),
+            ..without_client_id
+        };
+        assert!(with_client_id.connect(true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn requires_topics() {
+        let config = config(
+            r#"
+            host = "localhost"
+            topics = []
+            "#,
+        );
+        assert!(matches!(config.connect(false), Err(BuildError::NoTopics)));
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy},
+    AckKind,
+};
+use futures::{pin_mut, Stream, StreamExt};
+use snafu::ResultExt;
+use vector_common::finalizer::UnorderedFinalizer;
+use vector_config::configurable_component;
+
+use super::{decode_message, ConfigSnafu, ConnectSnafu, NatsSourceConfig};
+use crate::{
+    codecs::Decoder,
+    config::SourceContext,
+    event::{BatchNotifier, BatchStatus},
+    internal_events::{NatsAcknowledgementError, NatsReadError, StreamClosedError},
+    nats::to_async_nats_options,
+    shutdown::ShutdownSignal,
+    sources::Source,
+    SourceSender,
+};
+
+/// Configuration for consuming messages from a JetStream stream.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JetStreamConfig {
+    /// The name of the stream to consume messages from.
+    stream: String,
+
+    /// The name of the durable pull consumer to consume messages as.
+    ///
+    /// The consumer is created if it doesn't exist. The server keeps track of the messages it
+    /// acknowledged, so that consumption resumes where it left off when Vector is restarted. Vector
+    /// instances using the same consumer share its messages.
+    consumer: String,
+
+    /// The maximum number of messages delivered to the consumer but not yet acknowledged.
+    ///
+    /// Delivery is paused once this many messages are awaiting acknowledgement.
+    #[serde(default = "default_max_ack_pending")]
+    max_ack_pending: i64,
+
+    /// The time, in seconds, after which messages not yet acknowledged are delivered again.
+    #[serde(default = "default_ack_wait_secs")]
+    ack_wait_secs: u64,
+
+    /// The maximum number of times a message is delivered.
+    ///
+    /// By default, messages are delivered until they are acknowledged.
+    max_deliver: Option<i64>,
+}
+
+const fn default_max_ack_pending() -> i64 {
+    1000
+}
+
+const fn default_ack_wait_secs() -> u64 {
+    30
+}
+
+pub(super) async fn build(
+    config: &NatsSourceConfig,
+    jetstream: &JetStreamConfig,
+    decoder: Decoder,
+    cx: SourceContext,
+) -> crate::Result<Source> {
+    let options = to_async_nats_options(&config.connection_name, &config.auth, &config.tls)
+        .await
+        .context(ConfigSnafu)?;
+    let client = options.connect(&config.url).await.context(ConnectSnafu)?;
+    let context = jetstream::new(client);
+
+    let stream = context
+        .get_stream(&jetstream.stream)
+        .await
+        .map_err(|error| format!("Failed to get stream {:?}: {}", jetstream.stream, error))?;
+    let consumer: jetstream::consumer::Consumer<pull::Config> = stream
+        .get_or_create_consumer(
+            &jetstream.consumer,
+            pull::Config {
+                durable_name: Some(jetstream.consumer.clone()),
+                filter_subject: config.subject.clone(),
+                ack_policy: AckPolicy::Explicit,
+                ack_wait: Duration::from_secs(jetstream.ack_wait_secs),
+                max_ack_pending: jetstream.max_ack_pending,
+                max_deliver: jetstream.max_deliver.unwrap_or(-1),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|error| {
+            format!(
+                "Failed to create consumer {:?}: {}",
+                jetstream.consumer, error
+            )
+        })?;
+    let messages = consumer.messages().await?;
+
+    let acknowledgements = cx.do_acknowledgements(&config.acknowledgements);
+    Ok(Box::pin(jetstream_source(
+        messages,
+        decoder,
+        acknowledgements,
+        cx.shutdown,
+        cx.out,
+    )))
+}
+
+async fn jetstream_source(
+    messages: impl Stream<Item = Result<jetstream::Message, async_nats::Error>>,
+    decoder: Decoder,
+    acknowledgements: bool,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) =
+        UnorderedFinalizer::<jetstream::Message>::maybe_new(acknowledgements, shutdown.clone());
+    pin_mut!(messages);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => if let Some((status, message)) = entry {
+                handle_ack(status, &message).await;
+            },
+            message = messages.next() => match message {
+                None => break,
+                Some(Err(error)) => emit!(NatsReadError { error }),
+                Some(Ok(message)) => {
+                    let mut events = decode_message(&message.payload, &decoder).await;
+                    let count = events.len();
+                    let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
+                    if let Err(error) = out.send_batch(events).await {
+                        emit!(StreamClosedError { error, count });
+                        break;
+                    }
+                    match (&finalizer, receiver) {
+                        (Some(finalizer), Some(receiver)) => finalizer.add(message, receiver),
+                        _ => handle_ack(BatchStatus::Delivered, &message).await,
+                    }
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_ack(status: BatchStatus, message: &jetstream::Message) {
+    let kind = match status {
+        BatchStatus::Delivered => AckKind::Ack,
+        // The message is delivered again right away, rather than once the acknowledgement wait
+        // expires.
+        BatchStatus::Errored => AckKind::Nak(None),
+        // The message is not delivered again, as its events would be rejected again.
+        BatchStatus::Rejected => AckKind::Term,
+    };
+    if let Err(error) = message.ack_with(kind).await {
+        emit!(NatsAcknowledgementError { error });
+    }
+}
@@ -9,16 +9,22 @@ use vector_core::ByteSizeOf;
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{log_schema, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
     event::Event,
     internal_events::{BytesReceived, OldEventsReceived, StreamClosedError},
     nats::{from_tls_auth_config, NatsAuthConfig, NatsConfigError},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     tls::TlsEnableableConfig,
     SourceSender,
 };
 
+mod jetstream;
+use jetstream::JetStreamConfig;
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("NATS Config Error: {}", source))]
@@ -50,8 +56,13 @@ pub struct NatsSourceConfig {
     subject: String,
 
     /// NATS Queue Group to join.
+    ///
+    /// Not supported when consuming from JetStream.
     queue: Option<String>,
 
+    #[configurable(derived)]
+    jetstream: Option<JetStreamConfig>,
+
     #[configurable(derived)]
     tls: Option<TlsEnableableConfig>,
 
@@ -67,6 +78,13 @@ pub struct NatsSourceConfig {
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
+
+    /// Controls how acknowledgements are handled by this source.
+    ///
+    /// Only supported when consuming from JetStream, whose messages are acknowledged once their events are delivered.
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
 }
 
 inventory::submit! {
@@ -89,8 +107,15 @@ impl GenerateConfig for NatsSourceConfig {
 #[typetag::serde(name = "nats")]
 impl SourceConfig for NatsSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        if let Some(jetstream) = &self.jetstream {
+            if self.queue.is_some() {
+                return Err("`queue` is not supported when consuming from JetStream.".into());
+            }
+            return jetstream::build(self, jetstream, decoder, cx).await;
+        }
+
         let (connection, subscription) = create_subscription(self).await?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
 
         Ok(Box::pin(nats_source(
             connection,
@@ -110,7 +135,7 @@ impl SourceConfig for NatsSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        self.jetstream.is_some()
     }
 }
 
@@ -148,45 +173,52 @@ async fn nats_source(
     let stream = get_subscription_stream(subscription).take_until(shutdown);
     pin_mut!(stream);
     while let Some(msg) = stream.next().await {
-        emit!(BytesReceived {
-            byte_size: msg.data.len(),
-            protocol: "tcp",
-        });
-        let mut stream = FramedRead::new(msg.data.as_ref(), decoder.clone());
-        while let Some(next) = stream.next().await {
-            match next {
-                Ok((events, _byte_size)) => {
-                    let count = events.len();
-                    emit!(OldEventsReceived {
-                        byte_size: events.size_of(),
-                        count
-                    });
-
-                    let now = Utc::now();
-
-                    let events = events.into_iter().map(|mut event| {
-                        if let Event::Log(ref mut log) = event {
-                            log.try_insert(log_schema().source_type_key(), Bytes::from("nats"));
-                            log.try_insert(log_schema().timestamp_key(), now);
-                        }
-                        event
-                    });
-
-                    out.send_batch(events).await.map_err(|error| {
-                        emit!(StreamClosedError { error, count });
-                    })?;
-                }
-                Err(error) => {
-                    // Error is logged by `crate::codecs`, no further
-                    // handling is needed here.
-                    if !error.can_continue() {
-                        break;
+        let events = decode_message(&msg.data, &decoder).await;
+        let count = events.len();
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+        })?;
+    }
+    Ok(())
+}
+
+/// Decodes the payload of a message into events.
+async fn decode_message(payload: &[u8], decoder: &Decoder) -> Vec<Event> {
+    emit!(BytesReceived {
+        byte_size: payload.len(),
+        protocol: "tcp",
+    });
+
+    let mut decoded = Vec::new();
+    let mut stream = FramedRead::new(payload, decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                emit!(OldEventsReceived {
+                    byte_size: events.size_of(),
+                    count: events.len(),
+                });
+
+                let now = Utc::now();
+
+                decoded.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(log_schema().source_type_key(), Bytes::from("nats"));
+                        log.try_insert(log_schema().timestamp_key(), now);
                     }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
                 }
             }
         }
     }
-    Ok(())
+    decoded
 }
 
 async fn create_subscription(
@@ -214,6 +246,30 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<NatsSourceConfig>();
     }
+
+    #[tokio::test]
+    async fn jetstream_rejects_queue() {
+        let config: NatsSourceConfig = toml::from_str(
+            r#"
+            url = "nats://127.0.0.1:4222"
+            connection_name = "vector"
+            subject = "from.vector"
+            queue = "vector"
+            jetstream.stream = "vector"
+            jetstream.consumer = "vector"
+            "#,
+        )
+        .unwrap();
+        assert!(config.can_acknowledge());
+
+        let (tx, _rx) = SourceSender::new_test();
+        let error = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("`queue`"));
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
@@ -238,7 +294,9 @@ mod integration_tests {
 
         let events = assert_source_compliance(&SOURCE_TAGS, async move {
             let (tx, rx) = SourceSender::new_test();
-            let decoder = DecodingConfig::new(conf.framing.clone(), conf.decoding.clone()).build();
+            let decoder = DecodingConfig::new(conf.framing.clone(), conf.decoding.clone())
+                .build()
+                .unwrap();
             tokio::spawn(nats_source(nc, sub, decoder, ShutdownSignal::noop(), tx));
             nc_pub.publish(&subject, msg).await.unwrap();
 
@@ -262,10 +320,12 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
             auth: None,
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -287,6 +347,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -296,6 +357,7 @@ mod integration_tests {
                     password: "natspass".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -317,6 +379,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -326,6 +389,7 @@ mod integration_tests {
                     password: "wrongpass".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -347,6 +411,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -355,6 +420,7 @@ mod integration_tests {
                     value: "secret".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -376,6 +442,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -384,6 +451,7 @@ mod integration_tests {
                     value: "wrongsecret".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -405,6 +473,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -414,6 +483,7 @@ mod integration_tests {
                     seed: "SUANIRXEZUROTXNFN3TJYMT27K7ZZVMD46FRIHF6KXKS4KGNVBS57YAFGY".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -435,6 +505,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -444,6 +515,7 @@ mod integration_tests {
                     seed: "SBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -465,6 +537,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -475,6 +548,7 @@ mod integration_tests {
                 },
             }),
             auth: None,
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -496,10 +570,12 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
             auth: None,
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -521,6 +597,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -533,6 +610,7 @@ mod integration_tests {
                 },
             }),
             auth: None,
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -554,6 +632,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -564,6 +643,7 @@ mod integration_tests {
                 },
             }),
             auth: None,
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -585,6 +665,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -599,6 +680,7 @@ mod integration_tests {
                     path: "tests/data/nats/nats.creds".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
@@ -620,6 +702,7 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -634,6 +717,7 @@ mod integration_tests {
                     path: "tests/data/nats/nats-bad.creds".into(),
                 },
             }),
+            acknowledgements: Default::default(),
         };
 
         let r = publish_and_check(conf).await;
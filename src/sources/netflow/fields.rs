@@ -0,0 +1,354 @@
+//! Decoding of the fields of NetFlow v9 and IPFIX records.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use chrono::{TimeZone, Utc};
+
+use crate::event::Value;
+
+/// The number of seconds between the NTP epoch, in 1900, and the Unix epoch.
+const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+
+/// The abstract data type of an information element.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    Unsigned,
+    Ipv4Address,
+    Ipv6Address,
+    MacAddress,
+    String,
+    OctetArray,
+    DateTimeSeconds,
+    DateTimeMilliseconds,
+    DateTimeMicroseconds,
+    DateTimeNanoseconds,
+}
+
+/// The information elements with a known name and type, from the IANA IPFIX registry.
+///
+/// NetFlow v9 field types share their numbers with these elements.
+const FIELDS: &[(u16, &str, FieldType)] = &[
+    (1, "octet_delta_count", FieldType::Unsigned),
+    (2, "packet_delta_count", FieldType::Unsigned),
+    (3, "delta_flow_count", FieldType::Unsigned),
+    (4, "protocol_identifier", FieldType::Unsigned),
+    (5, "ip_class_of_service", FieldType::Unsigned),
+    (6, "tcp_control_bits", FieldType::Unsigned),
+    (7, "source_transport_port", FieldType::Unsigned),
+    (8, "source_ipv4_address", FieldType::Ipv4Address),
+    (9, "source_ipv4_prefix_length", FieldType::Unsigned),
+    (10, "ingress_interface", FieldType::Unsigned),
+    (11, "destination_transport_port", FieldType::Unsigned),
+    (12, "destination_ipv4_address", FieldType::Ipv4Address),
+    (13, "destination_ipv4_prefix_length", FieldType::Unsigned),
+    (14, "egress_interface", FieldType::Unsigned),
+    (15, "ip_next_hop_ipv4_address", FieldType::Ipv4Address),
+    (16, "bgp_source_as_number", FieldType::Unsigned),
+    (17, "bgp_destination_as_number", FieldType::Unsigned),
+    (18, "bgp_next_hop_ipv4_address", FieldType::Ipv4Address),
+    (19, "post_mcast_packet_delta_count", FieldType::Unsigned),
+    (20, "post_mcast_octet_delta_count", FieldType::Unsigned),
+    (21, "flow_end_sys_up_time", FieldType::Unsigned),
+    (22, "flow_start_sys_up_time", FieldType::Unsigned),
+    (23, "post_octet_delta_count", FieldType::Unsigned),
+    (24, "post_packet_delta_count", FieldType::Unsigned),
+    (25, "minimum_ip_total_length", FieldType::Unsigned),
+    (26, "maximum_ip_total_length", FieldType::Unsigned),
+    (27, "source_ipv6_address", FieldType::Ipv6Address),
+    (28, "destination_ipv6_address", FieldType::Ipv6Address),
+    (29, "source_ipv6_prefix_length", FieldType::Unsigned),
+    (30, "destination_ipv6_prefix_length", FieldType::Unsigned),
+    (31, "flow_label_ipv6", FieldType::Unsigned),
+    (32, "icmp_type_code_ipv4", FieldType::Unsigned),
+    (33, "igmp_type", FieldType::Unsigned),
+    (34, "sampling_interval", FieldType::Unsigned),
+    (35, "sampling_algorithm", FieldType::Unsigned),
+    (36, "flow_active_timeout", FieldType::Unsigned),
+    (37, "flow_idle_timeout", FieldType::Unsigned),
+    (38, "engine_type", FieldType::Unsigned),
+    (39, "engine_id", FieldType::Unsigned),
+    (40, "exported_octet_total_count", FieldType::Unsigned),
+    (41, "exported_message_total_count", FieldType::Unsigned),
+    (42, "exported_flow_record_total_count", FieldType::Unsigned),
+    (44, "source_ipv4_prefix", FieldType::Ipv4Address),
+    (45, "destination_ipv4_prefix", FieldType::Ipv4Address),
+    (46, "mpls_top_label_type", FieldType::Unsigned),
+    (47, "mpls_top_label_ipv4_address", FieldType::Ipv4Address),
+    (52, "minimum_ttl", FieldType::Unsigned),
+    (53, "maximum_ttl", FieldType::Unsigned),
+    (54, "fragment_identification", FieldType::Unsigned),
+    (55, "post_ip_class_of_service", FieldType::Unsigned),
+    (56, "source_mac_address", FieldType::MacAddress),
+    (57, "post_destination_mac_address", FieldType::MacAddress),
+    (58, "vlan_id", FieldType::Unsigned),
+    (59, "post_vlan_id", FieldType::Unsigned),
+    (60, "ip_version", FieldType::Unsigned),
+    (61, "flow_direction", FieldType::Unsigned),
+    (62, "ip_next_hop_ipv6_address", FieldType::Ipv6Address),
+    (63, "bgp_next_hop_ipv6_address", FieldType::Ipv6Address),
+    (64, "ipv6_extension_headers", FieldType::Unsigned),
+    (70, "mpls_top_label_stack_section", FieldType::OctetArray),
+    (80, "destination_mac_address", FieldType::MacAddress),
+    (81, "post_source_mac_address", FieldType::MacAddress),
+    (82, "interface_name", FieldType::String),
+    (83, "interface_description", FieldType::String),
+    (85, "octet_total_count", FieldType::Unsigned),
+    (86, "packet_total_count", FieldType::Unsigned),
+    (88, "fragment_offset", FieldType::Unsigned),
+    (89, "forwarding_status", FieldType::Unsigned),
+    (90, "mpls_vpn_route_distinguisher", FieldType::OctetArray),
+    (95, "application_id", FieldType::OctetArray),
+    (96, "application_name", FieldType::String),
+    (128, "bgp_next_adjacent_as_number", FieldType::Unsigned),
+    (129, "bgp_prev_adjacent_as_number", FieldType::Unsigned),
+    (130, "exporter_ipv4_address", FieldType::Ipv4Address),
+    (131, "exporter_ipv6_address", FieldType::Ipv6Address),
+    (136, "flow_end_reason", FieldType::Unsigned),
+    (139, "icmp_type_code_ipv6", FieldType::Unsigned),
+    (144, "exporting_process_id", FieldType::Unsigned),
+    (148, "flow_id", FieldType::Unsigned),
+    (149, "observation_domain_id", FieldType::Unsigned),
+    (150, "flow_start_seconds", FieldType::DateTimeSeconds),
+    (151, "flow_end_seconds", FieldType::DateTimeSeconds),
+    (
+        152,
+        "flow_start_milliseconds",
+        FieldType::DateTimeMilliseconds,
+    ),
+    (
+        153,
+        "flow_end_milliseconds",
+        FieldType::DateTimeMilliseconds,
+    ),
+    (
+        154,
+        "flow_start_microseconds",
+        FieldType::DateTimeMicroseconds,
+    ),
+    (
+        155,
+        "flow_end_microseconds",
+        FieldType::DateTimeMicroseconds,
+    ),
+    (
+        156,
+        "flow_start_nanoseconds",
+        FieldType::DateTimeNanoseconds,
+    ),
+    (157, "flow_end_nanoseconds", FieldType::DateTimeNanoseconds),
+    (
+        160,
+        "system_init_time_milliseconds",
+        FieldType::DateTimeMilliseconds,
+    ),
+    (161, "flow_duration_milliseconds", FieldType::Unsigned),
+    (176, "icmp_type_ipv4", FieldType::Unsigned),
+    (177, "icmp_code_ipv4", FieldType::Unsigned),
+    (178, "icmp_type_ipv6", FieldType::Unsigned),
+    (179, "icmp_code_ipv6", FieldType::Unsigned),
+    (180, "udp_source_port", FieldType::Unsigned),
+    (181, "udp_destination_port", FieldType::Unsigned),
+    (182, "tcp_source_port", FieldType::Unsigned),
+    (183, "tcp_destination_port", FieldType::Unsigned),
+    (184, "tcp_sequence_number", FieldType::Unsigned),
+    (185, "tcp_acknowledgement_number", FieldType::Unsigned),
+    (186, "tcp_window_size", FieldType::Unsigned),
+    (192, "ip_ttl", FieldType::Unsigned),
+    (225, "post_nat_source_ipv4_address", FieldType::Ipv4Address),
+    (
+        226,
+        "post_nat_destination_ipv4_address",
+        FieldType::Ipv4Address,
+    ),
+    (227, "post_napt_source_transport_port", FieldType::Unsigned),
+    (
+        228,
+        "post_napt_destination_transport_port",
+        FieldType::Unsigned,
+    ),
+    (230, "nat_event", FieldType::Unsigned),
+    (231, "initiator_octets", FieldType::Unsigned),
+    (232, "responder_octets", FieldType::Unsigned),
+    (233, "firewall_event", FieldType::Unsigned),
+    (234, "ingress_vrfid", FieldType::Unsigned),
+    (235, "egress_vrfid", FieldType::Unsigned),
+    (243, "dot1q_vlan_id", FieldType::Unsigned),
+    (254, "post_dot1q_vlan_id", FieldType::Unsigned),
+    (281, "post_nat_source_ipv6_address", FieldType::Ipv6Address),
+    (
+        282,
+        "post_nat_destination_ipv6_address",
+        FieldType::Ipv6Address,
+    ),
+    (298, "initiator_packets", FieldType::Unsigned),
+    (299, "responder_packets", FieldType::Unsigned),
+    (
+        323,
+        "observation_time_milliseconds",
+        FieldType::DateTimeMilliseconds,
+    ),
+    (
+        324,
+        "observation_time_microseconds",
+        FieldType::DateTimeMicroseconds,
+    ),
+    (
+        325,
+        "observation_time_nanoseconds",
+        FieldType::DateTimeNanoseconds,
+    ),
+];
+
+/// The scope field types of NetFlow v9 options templates, which do not share their numbers with
+/// information elements.
+const V9_SCOPE_FIELDS: &[(u16, &str)] = &[
+    (1, "scope_system"),
+    (2, "scope_interface"),
+    (3, "scope_line_card"),
+    (4, "scope_cache"),
+    (5, "scope_template"),
+];
+
+/// Names a field, and finds its type.
+///
+/// Fields missing from the registry are named after their number, and decoded as octet arrays.
+pub fn lookup(id: u16, enterprise: Option<u32>, v9_scope: bool) -> (String, FieldType) {
+    if v9_scope {
+        if let Some((_, name)) = V9_SCOPE_FIELDS.iter().find(|(scope, _)| *scope == id) {
+            return ((*name).to_owned(), FieldType::OctetArray);
+        }
+    }
+    match enterprise {
+        Some(enterprise) => (
+            format!("enterprise_{}_{}", enterprise, id),
+            FieldType::OctetArray,
+        ),
+        None => match FIELDS.binary_search_by_key(&id, |(id, _, _)| *id) {
+            Ok(index) => (FIELDS[index].1.to_owned(), FIELDS[index].2),
+            Err(_) => (format!("field_{}", id), FieldType::OctetArray),
+        },
+    }
+}
+
+/// Decodes the value of a field, falling back to hexadecimal when its length does not match its
+/// type.
+pub fn decode(field_type: FieldType, value: &[u8]) -> Value {
+    match (field_type, value.len()) {
+        (FieldType::Unsigned, 1..=8) => unsigned_value(decode_unsigned(value)),
+        (FieldType::Ipv4Address, 4) => {
+            let octets: [u8; 4] = value.try_into().expect("length is checked");
+            Value::from(Ipv4Addr::from(octets).to_string())
+        }
+        (FieldType::Ipv6Address, 16) => {
+            let octets: [u8; 16] = value.try_into().expect("length is checked");
+            Value::from(Ipv6Addr::from(octets).to_string())
+        }
+        (FieldType::MacAddress, 6) => Value::from(
+            value
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(":"),
+        ),
+        (FieldType::String, _) => Value::from(
+            String::from_utf8_lossy(value)
+                .trim_end_matches('\0')
+                .to_owned(),
+        ),
+        (FieldType::DateTimeSeconds, 4) => timestamp_value(decode_unsigned(value) as i64, 0),
+        (FieldType::DateTimeMilliseconds, 8) => {
+            let millis = decode_unsigned(value) as i64;
+            timestamp_value(
+                millis.div_euclid(1000),
+                millis.rem_euclid(1000) as u32 * 1_000_000,
+            )
+        }
+        (FieldType::DateTimeMicroseconds | FieldType::DateTimeNanoseconds, 8) => {
+            // These are encoded in the NTP timestamp format.
+            let seconds = decode_unsigned(&value[..4]) as i64 - NTP_UNIX_OFFSET;
+            let fraction = decode_unsigned(&value[4..]);
+            timestamp_value(seconds, ((fraction * 1_000_000_000) >> 32) as u32)
+        }
+        _ => Value::from(hex::encode(value)),
+    }
+}
+
+pub fn decode_unsigned(value: &[u8]) -> u64 {
+    value
+        .iter()
+        .fold(0, |decoded, byte| (decoded << 8) | *byte as u64)
+}
+
+/// Converts unsigned integers into integer values, or float values when they overflow.
+pub fn unsigned_value(value: u64) -> Value {
+    i64::try_from(value)
+        .map(Value::from)
+        .unwrap_or_else(|_| Value::from(value as f64))
+}
+
+pub fn timestamp_value(seconds: i64, nanoseconds: u32) -> Value {
+    Utc.timestamp_opt(seconds, nanoseconds)
+        .single()
+        .map(Value::from)
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_sorted() {
+        assert!(FIELDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn decodes_fields() {
+        assert_eq!(decode(FieldType::Unsigned, &[0x01, 0x00]), Value::from(256));
+        assert_eq!(
+            decode(FieldType::Ipv4Address, &[192, 0, 2, 1]),
+            Value::from("192.0.2.1")
+        );
+        assert_eq!(
+            decode(FieldType::MacAddress, &[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]),
+            Value::from("00:1a:2b:3c:4d:5e")
+        );
+        assert_eq!(decode(FieldType::String, b"eth0\0\0"), Value::from("eth0"));
+        assert_eq!(
+            decode(FieldType::Ipv4Address, &[192, 0, 2]),
+            Value::from("c00002")
+        );
+        assert_eq!(
+            decode(
+                FieldType::DateTimeMilliseconds,
+                &1_660_000_000_123u64.to_be_bytes()
+            ),
+            Value::from(Utc.timestamp(1_660_000_000, 123_000_000))
+        );
+        let ntp = (((1_660_000_000 + NTP_UNIX_OFFSET) as u64) << 32) | (1 << 31);
+        assert_eq!(
+            decode(FieldType::DateTimeMicroseconds, &ntp.to_be_bytes()),
+            Value::from(Utc.timestamp(1_660_000_000, 500_000_000))
+        );
+    }
+
+    #[test]
+    fn names_fields() {
+        assert_eq!(
+            lookup(8, None, false),
+            ("source_ipv4_address".to_owned(), FieldType::Ipv4Address)
+        );
+        assert_eq!(
+            lookup(1, None, true),
+            ("scope_system".to_owned(), FieldType::OctetArray)
+        );
+        assert_eq!(
+            lookup(1, Some(9), false),
+            ("enterprise_9_1".to_owned(), FieldType::OctetArray)
+        );
+        assert_eq!(
+            lookup(43, None, false),
+            ("field_43".to_owned(), FieldType::OctetArray)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use bytes::Bytes;
+use chrono::Utc;
+use tokio::net::UdpSocket;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, Output, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        EventsReceived, NetflowParseError, NetflowTemplateMissing, SocketBytesReceived, SocketMode,
+        StreamClosedError, UdpSocketError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod fields;
+mod parser;
+use parser::Decoder;
+
+/// The largest message that can be received over UDP.
+const MAX_MESSAGE_SIZE: usize = 65_507;
+
+/// Configuration for the `netflow` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    /// The address to listen for flows on.
+    #[serde(default = "default_address")]
+    address: SocketAddr,
+
+    /// The maximum number of templates kept across all exporters.
+    ///
+    /// NetFlow v9 and IPFIX exporters describe their records with templates, which are kept for
+    /// each exporter and observation domain. Templates beyond this limit are ignored, so that
+    /// misbehaving exporters cannot exhaust memory.
+    #[serde(default = "default_max_templates")]
+    max_templates: usize,
+
+    /// Overrides the name of the log field used to add the exporter host to each event.
+    ///
+    /// The value will be the exporter host's address, i.e. `1.2.3.4`.
+    ///
+    /// By default, the [global `host_key` option](https://vector.dev/docs/reference/configuration//global-options#log_schema.host_key) is used.
+    host_key: Option<String>,
+
+    /// The size, in bytes, of the receive buffer used for the listening socket.
+    ///
+    /// This should not typically needed to be changed.
+    receive_buffer_bytes: Option<usize>,
+}
+
+fn default_address() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 2055)
+}
+
+const fn default_max_templates() -> usize {
+    10_000
+}
+
+impl Default for NetflowConfig {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            max_templates: default_max_templates(),
+            host_key: None,
+            receive_buffer_bytes: None,
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl_generate_config_from_default!(NetflowConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+
+        Ok(Box::pin(run(
+            self.address,
+            self.receive_buffer_bytes,
+            Decoder::new(self.max_templates),
+            host_key,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn run(
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    mut decoder: Decoder,
+    host_key: String,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&address)
+        .await
+        .expect("Failed to bind to UDP listener socket");
+
+    if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %address);
+
+    let mut buf = vec![0; MAX_MESSAGE_SIZE];
+    loop {
+        let (byte_size, peer) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(received) => received,
+                Err(error) => {
+                    emit!(UdpSocketError { error });
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+        emit!(SocketBytesReceived {
+            mode: SocketMode::Udp,
+            byte_size,
+        });
+
+        let decoded = match decoder.decode(peer, &buf[..byte_size]) {
+            Ok(decoded) => decoded,
+            Err(error) => {
+                emit!(NetflowParseError { error, peer });
+                continue;
+            }
+        };
+        for template_id in decoded.missing_templates {
+            emit!(NetflowTemplateMissing { template_id, peer });
+        }
+        if decoded.records.is_empty() {
+            continue;
+        }
+
+        let now = Utc::now();
+        let events = decoded
+            .records
+            .into_iter()
+            .map(|record| {
+                let timestamp = record
+                    .get("export_time")
+                    .cloned()
+                    .unwrap_or_else(|| Value::from(now));
+                let mut log = LogEvent::from(record);
+                log.insert(log_schema().source_type_key(), Bytes::from("netflow"));
+                log.insert(log_schema().timestamp_key(), timestamp);
+                log.insert(host_key.as_str(), peer.ip().to_string());
+                Event::from(log)
+            })
+            .collect::<Vec<_>>();
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        tokio::select! {
+            result = out.send_batch(events) => {
+                if let Err(error) = result {
+                    emit!(StreamClosedError { error, count });
+                    return Ok(());
+                }
+            }
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_flows() {
+        assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
+            let address = next_addr();
+            let config = NetflowConfig {
+                address,
+                ..Default::default()
+            };
+            let (tx, rx) = SourceSender::new_test();
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+            // A NetFlow v5 message with two records.
+            let mut message = vec![
+                0, 5, 0, 2, 0, 0, 0x27, 0x10, 0x62, 0xf2, 0x26, 0x80, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0,
+                0, 0,
+            ];
+            for source in [1, 2] {
+                let mut record = vec![0; 48];
+                record[..4].copy_from_slice(&[10, 0, 0, source]);
+                record[38] = 17;
+                message.extend(record);
+            }
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            socket.send_to(&message, address).await.unwrap();
+
+            let events = collect_n(rx, 2).await;
+            let log = events[1].as_log();
+            assert_eq!(log["source_type"], "netflow".into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+            assert_eq!(log["source_ipv4_address"], "10.0.0.2".into());
+            assert_eq!(log["protocol_identifier"], 17.into());
+            assert_eq!(
+                log[log_schema().timestamp_key()],
+                Value::from(chrono::TimeZone::timestamp(&Utc, 1_660_000_000, 0))
+            );
+        })
+        .await;
+    }
+}
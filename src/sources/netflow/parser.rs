@@ -0,0 +1,608 @@
+//! Decoding of NetFlow v5, NetFlow v9 and IPFIX messages.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+};
+
+use snafu::Snafu;
+
+use super::fields::{self, FieldType};
+use crate::event::Value;
+
+const V9_TEMPLATE_SET: u16 = 0;
+const V9_OPTIONS_TEMPLATE_SET: u16 = 1;
+const IPFIX_TEMPLATE_SET: u16 = 2;
+const IPFIX_OPTIONS_TEMPLATE_SET: u16 = 3;
+const MIN_DATA_SET: u16 = 256;
+
+/// The length of variable-length IPFIX fields is encoded before their value.
+const VARIABLE_LENGTH: u16 = 65_535;
+
+const V5_HEADER_LENGTH: usize = 24;
+const V5_RECORD_LENGTH: usize = 48;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Message is truncated"))]
+    Truncated,
+    #[snafu(display("Unsupported NetFlow version {}", version))]
+    UnsupportedVersion { version: u16 },
+    #[snafu(display("Invalid length {} of set {}", length, set_id))]
+    InvalidSetLength { set_id: u16, length: u16 },
+    #[snafu(display("Invalid template {}", template_id))]
+    InvalidTemplate { template_id: u16 },
+}
+
+/// A reader of big-endian integers.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        if self.data.len() < len {
+            return Err(ParseError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, ParseError> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ParseError> {
+        self.bytes(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        self.bytes(4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// A field of a template.
+#[derive(Clone, Debug, PartialEq)]
+struct TemplateField {
+    name: String,
+    field_type: FieldType,
+    length: u16,
+}
+
+/// A template, describing the records of the data sets using its ID.
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    fields: Vec<TemplateField>,
+    options: bool,
+}
+
+impl Template {
+    /// The minimum length of a record, used to detect the padding ending data sets.
+    fn min_record_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum::<usize>()
+            .max(1)
+    }
+}
+
+/// Templates are scoped to the exporter, and to the observation domain within the exporter.
+type TemplateKey = (SocketAddr, u32, u16);
+
+/// The records of a message.
+#[derive(Debug, Default, PartialEq)]
+pub struct Decoded {
+    pub records: Vec<BTreeMap<String, Value>>,
+    /// The IDs of the templates of data sets which could not be decoded.
+    pub missing_templates: Vec<u16>,
+}
+
+/// Decodes messages, keeping the templates they define.
+#[derive(Debug)]
+pub struct Decoder {
+    templates: HashMap<TemplateKey, Template>,
+    max_templates: usize,
+}
+
+impl Decoder {
+    pub fn new(max_templates: usize) -> Self {
+        Self {
+            templates: HashMap::new(),
+            max_templates,
+        }
+    }
+
+    pub fn decode(&mut self, exporter: SocketAddr, data: &[u8]) -> Result<Decoded, ParseError> {
+        let version = Reader::new(data).u16()?;
+        match version {
+            5 => decode_v5(data),
+            9 | 10 => self.decode_templated(exporter, version, data),
+            version => Err(ParseError::UnsupportedVersion { version }),
+        }
+    }
+
+    /// Decodes NetFlow v9 and IPFIX messages, which share their structure.
+    fn decode_templated(
+        &mut self,
+        exporter: SocketAddr,
+        version: u16,
+        data: &[u8],
+    ) -> Result<Decoded, ParseError> {
+        let mut reader = Reader::new(data);
+        reader.u16()?;
+        let mut header = BTreeMap::new();
+        header.insert("version".to_owned(), Value::from(version as i64));
+        let (sys_up_time, export_time, domain, sets) = if version == 9 {
+            reader.u16()?; // The count of records, which some exporters get wrong.
+            let sys_up_time = reader.u32()?;
+            let export_time = reader.u32()?;
+            header.insert(
+                "sequence_number".to_owned(),
+                Value::from(reader.u32()? as i64),
+            );
+            let domain = reader.u32()?;
+            (Some(sys_up_time), export_time, domain, reader)
+        } else {
+            let length = reader.u16()? as usize;
+            if length < 16 || length > data.len() {
+                return Err(ParseError::Truncated);
+            }
+            let export_time = reader.u32()?;
+            header.insert(
+                "sequence_number".to_owned(),
+                Value::from(reader.u32()? as i64),
+            );
+            let domain = reader.u32()?;
+            (None, export_time, domain, Reader::new(&data[16..length]))
+        };
+        header.insert(
+            "observation_domain_id".to_owned(),
+            Value::from(domain as i64),
+        );
+        header.insert(
+            "export_time".to_owned(),
+            fields::timestamp_value(export_time as i64, 0),
+        );
+
+        let mut decoded = Decoded::default();
+        let mut sets = sets;
+        while sets.remaining() > 0 {
+            let set_id = sets.u16()?;
+            let length = sets.u16()?;
+            if length < 4 {
+                return Err(ParseError::InvalidSetLength { set_id, length });
+            }
+            let mut set = Reader::new(sets.bytes(length as usize - 4)?);
+
+            match (version, set_id) {
+                (9, V9_TEMPLATE_SET) | (10, IPFIX_TEMPLATE_SET) => {
+                    while set.remaining() >= 4 {
+                        self.decode_template(exporter, version, domain, &mut set, false)?;
+                    }
+                }
+                (9, V9_OPTIONS_TEMPLATE_SET) | (10, IPFIX_OPTIONS_TEMPLATE_SET) => {
+                    // NetFlow v9 options templates are padded to 4 bytes.
+                    while set.remaining() >= 6 {
+                        self.decode_template(exporter, version, domain, &mut set, true)?;
+                    }
+                }
+                (_, set_id) if set_id >= MIN_DATA_SET => {
+                    let template = match self.templates.get(&(exporter, domain, set_id)) {
+                        Some(template) => template,
+                        None => {
+                            decoded.missing_templates.push(set_id);
+                            continue;
+                        }
+                    };
+                    while set.remaining() >= template.min_record_length() {
+                        let mut record = header.clone();
+                        record.insert(
+                            "record_type".to_owned(),
+                            Value::from(if template.options { "options" } else { "flow" }),
+                        );
+                        for field in template.fields.iter() {
+                            let mut length = field.length as usize;
+                            if field.length == VARIABLE_LENGTH {
+                                length = set.u8()? as usize;
+                                if length == 255 {
+                                    length = set.u16()? as usize;
+                                }
+                            }
+                            let value = fields::decode(field.field_type, set.bytes(length)?);
+                            record.insert(field.name.clone(), value);
+                        }
+                        if let Some(sys_up_time) = sys_up_time {
+                            convert_sys_up_times(
+                                &mut record,
+                                sys_up_time,
+                                export_time as i64 * 1000,
+                            );
+                        }
+                        decoded.records.push(record);
+                    }
+                }
+                // Sets with reserved IDs are skipped.
+                _ => {}
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    fn decode_template(
+        &mut self,
+        exporter: SocketAddr,
+        version: u16,
+        domain: u32,
+        set: &mut Reader<'_>,
+        options: bool,
+    ) -> Result<(), ParseError> {
+        let template_id = set.u16()?;
+        let (field_count, scope_field_count) = match (version, options) {
+            // NetFlow v9 options templates specify the length of their fields, in bytes.
+            (9, true) => {
+                let scope_length = set.u16()? / 4;
+                let option_length = set.u16()? / 4;
+                (scope_length + option_length, scope_length)
+            }
+            (_, true) => {
+                let field_count = set.u16()?;
+                (field_count, set.u16()?)
+            }
+            (_, false) => (set.u16()?, 0),
+        };
+
+        // IPFIX templates without fields withdraw previous templates.
+        if field_count == 0 {
+            if version == 10
+                && (template_id == IPFIX_TEMPLATE_SET || template_id == IPFIX_OPTIONS_TEMPLATE_SET)
+            {
+                self.templates
+                    .retain(|(key_exporter, key_domain, _), template| {
+                        *key_exporter != exporter
+                            || *key_domain != domain
+                            || template.options != options
+                    });
+            } else {
+                self.templates.remove(&(exporter, domain, template_id));
+            }
+            return Ok(());
+        }
+        if template_id < MIN_DATA_SET || scope_field_count > field_count {
+            return Err(ParseError::InvalidTemplate { template_id });
+        }
+
+        let mut fields = Vec::with_capacity(field_count as usize);
+        for index in 0..field_count {
+            let id = set.u16()?;
+            let length = set.u16()?;
+            let (id, enterprise) = if version == 10 && id & 0x8000 != 0 {
+                (id & 0x7fff, Some(set.u32()?))
+            } else {
+                (id, None)
+            };
+            if version == 9 && length == VARIABLE_LENGTH {
+                return Err(ParseError::InvalidTemplate { template_id });
+            }
+            let v9_scope = version == 9 && index < scope_field_count;
+            let (name, field_type) = fields::lookup(id, enterprise, v9_scope);
+            fields.push(TemplateField {
+                name,
+                field_type,
+                length,
+            });
+        }
+
+        let key = (exporter, domain, template_id);
+        if self.templates.len() >= self.max_templates && !self.templates.contains_key(&key) {
+            return Err(ParseError::InvalidTemplate { template_id });
+        }
+        self.templates.insert(key, Template { fields, options });
+        Ok(())
+    }
+}
+
+/// Converts the flow times relative to the uptime of NetFlow v9 exporters into timestamps.
+fn convert_sys_up_times(
+    record: &mut BTreeMap<String, Value>,
+    sys_up_time: u32,
+    export_millis: i64,
+) {
+    for (relative, absolute) in [
+        ("flow_start_sys_up_time", "flow_start_milliseconds"),
+        ("flow_end_sys_up_time", "flow_end_milliseconds"),
+    ] {
+        if let Some(Value::Integer(time)) = record.get(relative) {
+            let millis = export_millis - sys_up_time.wrapping_sub(*time as u32) as i64;
+            record.remove(relative);
+            record.insert(
+                absolute.to_owned(),
+                fields::timestamp_value(
+                    millis.div_euclid(1000),
+                    millis.rem_euclid(1000) as u32 * 1_000_000,
+                ),
+            );
+        }
+    }
+}
+
+fn decode_v5(data: &[u8]) -> Result<Decoded, ParseError> {
+    let mut reader = Reader::new(data);
+    reader.u16()?;
+    let count = reader.u16()? as usize;
+    let sys_up_time = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+    if data.len() < V5_HEADER_LENGTH + count * V5_RECORD_LENGTH {
+        return Err(ParseError::Truncated);
+    }
+
+    let export_millis = unix_secs as i64 * 1000 + unix_nsecs as i64 / 1_000_000;
+    let mut header = BTreeMap::new();
+    header.insert("version".to_owned(), Value::from(5));
+    header.insert(
+        "sequence_number".to_owned(),
+        Value::from(sequence_number as i64),
+    );
+    header.insert(
+        "export_time".to_owned(),
+        fields::timestamp_value(unix_secs as i64, unix_nsecs),
+    );
+    header.insert("engine_type".to_owned(), Value::from(engine_type as i64));
+    header.insert("engine_id".to_owned(), Value::from(engine_id as i64));
+    header.insert(
+        "sampling_interval".to_owned(),
+        Value::from((sampling & 0x3fff) as i64),
+    );
+    header.insert("record_type".to_owned(), Value::from("flow"));
+
+    let mut records = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut record = header.clone();
+        // The fields of NetFlow v5 records, named after their equivalent information elements.
+        for (name, field_type, length) in [
+            ("source_ipv4_address", FieldType::Ipv4Address, 4),
+            ("destination_ipv4_address", FieldType::Ipv4Address, 4),
+            ("ip_next_hop_ipv4_address", FieldType::Ipv4Address, 4),
+            ("ingress_interface", FieldType::Unsigned, 2),
+            ("egress_interface", FieldType::Unsigned, 2),
+            ("packet_delta_count", FieldType::Unsigned, 4),
+            ("octet_delta_count", FieldType::Unsigned, 4),
+            ("flow_start_sys_up_time", FieldType::Unsigned, 4),
+            ("flow_end_sys_up_time", FieldType::Unsigned, 4),
+            ("source_transport_port", FieldType::Unsigned, 2),
+            ("destination_transport_port", FieldType::Unsigned, 2),
+            ("", FieldType::OctetArray, 1),
+            ("tcp_control_bits", FieldType::Unsigned, 1),
+            ("protocol_identifier", FieldType::Unsigned, 1),
+            ("ip_class_of_service", FieldType::Unsigned, 1),
+            ("bgp_source_as_number", FieldType::Unsigned, 2),
+            ("bgp_destination_as_number", FieldType::Unsigned, 2),
+            ("source_ipv4_prefix_length", FieldType::Unsigned, 1),
+            ("destination_ipv4_prefix_length", FieldType::Unsigned, 1),
+            ("", FieldType::OctetArray, 2),
+        ] {
+            let value = reader.bytes(length)?;
+            if !name.is_empty() {
+                record.insert(name.to_owned(), fields::decode(field_type, value));
+            }
+        }
+        convert_sys_up_times(&mut record, sys_up_time, export_millis);
+        records.push(record);
+    }
+
+    Ok(Decoded {
+        records,
+        missing_templates: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    fn set(id: u16, content: &[u8]) -> Vec<u8> {
+        [
+            &id.to_be_bytes()[..],
+            &(content.len() as u16 + 4).to_be_bytes(),
+            content,
+        ]
+        .concat()
+    }
+
+    fn ipfix(sets: &[Vec<u8>]) -> Vec<u8> {
+        let sets = sets.concat();
+        [
+            &10u16.to_be_bytes()[..],
+            &(sets.len() as u16 + 16).to_be_bytes(),
+            &1_660_000_000u32.to_be_bytes(),
+            &7u32.to_be_bytes(),
+            &1u32.to_be_bytes(),
+            &sets,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_v5() {
+        let mut message = vec![
+            0, 5, 0, 1, // version, count
+            0, 0, 0x27, 0x10, // sys_uptime: 10s
+            0x62, 0xf2, 0x26, 0x80, // unix_secs: 1660000000
+            0, 0, 0, 0, // unix_nsecs
+            0, 0, 0, 42, // flow_sequence
+            1, 2, 0x40, 0x0a, // engine_type, engine_id, sampling
+        ];
+        message.extend_from_slice(&[
+            10, 0, 0, 1, // srcaddr
+            10, 0, 0, 2, // dstaddr
+            0, 0, 0, 0, // nexthop
+            0, 1, 0, 2, // input, output
+            0, 0, 0, 5, // dPkts
+            0, 0, 1, 0, // dOctets
+            0, 0, 0x1b, 0x58, // first: 7s
+            0, 0, 0x23, 0x28, // last: 9s
+            0x1f, 0x90, 0, 80, // srcport, dstport
+            0, 0x12, 6, 0, // pad, tcp_flags, prot, tos
+            0, 0, 0, 0, // src_as, dst_as
+            24, 24, 0, 0, // src_mask, dst_mask, pad
+        ]);
+
+        let decoded = Decoder::new(10).decode(exporter(), &message).unwrap();
+        assert_eq!(decoded.records.len(), 1);
+        let record = &decoded.records[0];
+        assert_eq!(record["version"], Value::from(5));
+        assert_eq!(record["sequence_number"], Value::from(42));
+        assert_eq!(record["sampling_interval"], Value::from(10));
+        assert_eq!(record["source_ipv4_address"], Value::from("10.0.0.1"));
+        assert_eq!(record["destination_transport_port"], Value::from(80));
+        assert_eq!(record["octet_delta_count"], Value::from(256));
+        assert_eq!(record["protocol_identifier"], Value::from(6));
+        assert_eq!(
+            record["flow_start_milliseconds"],
+            Value::from(Utc.timestamp(1_659_999_997, 0))
+        );
+        assert!(!record.contains_key("flow_start_sys_up_time"));
+
+        assert_eq!(
+            Decoder::new(10).decode(exporter(), &message[..60]),
+            Err(ParseError::Truncated)
+        );
+    }
+
+    #[test]
+    fn decodes_ipfix_with_templates() {
+        let template = set(
+            IPFIX_TEMPLATE_SET,
+            &[
+                1, 0, 0, 4, // template 256, 4 fields
+                0, 8, 0, 4, // sourceIPv4Address
+                0, 7, 0, 2, // sourceTransportPort
+                0, 96, 0xff, 0xff, // applicationName, variable length
+                0x80, 1, 0, 2, 0, 0, 0x7a, 0x69, // enterprise field 1 of PEN 31337
+            ],
+        );
+        let data = set(
+            256,
+            &[
+                192, 0, 2, 10, 0x01, 0xbb, 5, b'h', b't', b't', b'p', b's', 0xab,
+                0xcd, // record
+                0, 0, // padding
+            ],
+        );
+
+        let mut decoder = Decoder::new(10);
+        // Data sets are skipped until their template is known.
+        let decoded = decoder.decode(exporter(), &ipfix(&[data.clone()])).unwrap();
+        assert!(decoded.records.is_empty());
+        assert_eq!(decoded.missing_templates, vec![256]);
+
+        let decoded = decoder
+            .decode(exporter(), &ipfix(&[template, data.clone()]))
+            .unwrap();
+        assert_eq!(decoded.records.len(), 1);
+        let record = &decoded.records[0];
+        assert_eq!(record["version"], Value::from(10));
+        assert_eq!(record["observation_domain_id"], Value::from(1));
+        assert_eq!(record["record_type"], Value::from("flow"));
+        assert_eq!(record["source_ipv4_address"], Value::from("192.0.2.10"));
+        assert_eq!(record["source_transport_port"], Value::from(443));
+        assert_eq!(record["application_name"], Value::from("https"));
+        assert_eq!(record["enterprise_31337_1"], Value::from("abcd"));
+
+        // Templates are scoped to their exporter.
+        let other: SocketAddr = "192.0.2.2:2055".parse().unwrap();
+        let decoded = decoder.decode(other, &ipfix(&[data.clone()])).unwrap();
+        assert_eq!(decoded.missing_templates, vec![256]);
+
+        // Templates are withdrawn by templates without fields.
+        let withdrawal = set(IPFIX_TEMPLATE_SET, &[1, 0, 0, 0]);
+        let decoded = decoder
+            .decode(exporter(), &ipfix(&[withdrawal, data]))
+            .unwrap();
+        assert_eq!(decoded.missing_templates, vec![256]);
+    }
+
+    #[test]
+    fn decodes_v9_options() {
+        let options_template = set(
+            V9_OPTIONS_TEMPLATE_SET,
+            &[
+                1, 4, 0, 4, 0, 8, // template 260, scope length 4, options length 8
+                0, 1, 0, 4, // scope system
+                0, 34, 0, 4, // samplingInterval
+                0, 35, 0, 1, // samplingAlgorithm
+                0, 0, // padding
+            ],
+        );
+        let data = set(260, &[192, 0, 2, 1, 0, 0, 0, 100, 2, 0, 0, 0]);
+        let message = [
+            &[0, 9, 0, 2][..],
+            &[0, 0, 0x27, 0x10],
+            &1_660_000_000u32.to_be_bytes(),
+            &[0, 0, 0, 3],
+            &[0, 0, 0, 9],
+            &options_template,
+            &data,
+        ]
+        .concat();
+
+        let decoded = Decoder::new(10).decode(exporter(), &message).unwrap();
+        assert_eq!(decoded.records.len(), 1);
+        let record = &decoded.records[0];
+        assert_eq!(record["version"], Value::from(9));
+        assert_eq!(record["observation_domain_id"], Value::from(9));
+        assert_eq!(record["record_type"], Value::from("options"));
+        assert_eq!(record["scope_system"], Value::from("c0000201"));
+        assert_eq!(record["sampling_interval"], Value::from(100));
+        assert_eq!(record["sampling_algorithm"], Value::from(2));
+    }
+
+    #[test]
+    fn limits_templates() {
+        let templates = set(
+            IPFIX_TEMPLATE_SET,
+            &[
+                1, 0, 0, 1, 0, 8, 0, 4, // template 256
+                1, 1, 0, 1, 0, 8, 0, 4, // template 257
+            ],
+        );
+        assert_eq!(
+            Decoder::new(1).decode(exporter(), &ipfix(&[templates])),
+            Err(ParseError::InvalidTemplate { template_id: 257 })
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        assert_eq!(
+            Decoder::new(10).decode(exporter(), &[0, 7, 0, 0]),
+            Err(ParseError::UnsupportedVersion { version: 7 })
+        );
+    }
+}
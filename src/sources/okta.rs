@@ -0,0 +1,525 @@
+//! This mod implements the `okta` source.
+//! It polls the Okta System Log API, following the `next` links of its pages, and emits each log
+//! event as a log. The cursor of the latest delivered page is persisted, so that restarts resume
+//! where they left off.
+
+use std::{path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use http::{
+    header::{ACCEPT, AUTHORIZATION, LINK},
+    HeaderMap, Request, StatusCode,
+};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::select;
+use url::Url;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    http::{HttpClient, HttpError},
+    internal_events::{
+        BytesReceived, EventsReceived, OktaCheckpointError, OktaRateLimited, OktaRequestError,
+        StreamClosedError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    tls::{TlsConfig, TlsSettings},
+    SourceSender,
+};
+
+const LOGS_PATH: &str = "/api/v1/logs";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("`limit` must be between 1 and 1000"))]
+    InvalidLimit,
+}
+
+#[derive(Debug, Snafu)]
+enum OktaError {
+    #[snafu(display("failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("request failed: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("failed to read response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("unexpected status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("failed to parse log events: {}", source))]
+    Parse { source: serde_json::Error },
+    #[snafu(display("events were not delivered"))]
+    NotDelivered,
+    #[snafu(display("source is shutting down"))]
+    Closed,
+}
+
+/// Configuration for the `okta` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OktaConfig {
+    /// The URL of the Okta organization, such as `https://example.okta.com`.
+    endpoint: String,
+
+    /// The API token used to authenticate the requests.
+    ///
+    /// The token needs to belong to an administrator allowed to read the System Log.
+    token: String,
+
+    /// The filter expression the log events must match, such as `eventType eq "user.session.start"`.
+    ///
+    /// By default, all log events are collected.
+    #[serde(default)]
+    filter: Option<String>,
+
+    /// The maximum number of log events to request per page.
+    #[serde(default = "default_limit")]
+    limit: u32,
+
+    /// The interval between polls of the System Log, in seconds.
+    ///
+    /// Every page available is read at each poll.
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+
+    /// The directory used to persist the cursor of the System Log.
+    ///
+    /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
+    #[serde(default)]
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the cursor of the System Log.
+    ///
+    /// By default, it's persisted in a file of `data_dir`.
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_limit() -> u32 {
+    1000
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<OktaConfig>("okta")
+}
+
+impl GenerateConfig for OktaConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "https://example.okta.com"
+            token = "${OKTA_API_TOKEN}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "okta")]
+impl SourceConfig for OktaConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.limit == 0 || self.limit > 1000 {
+            return Err(BuildError::InvalidLimit.into());
+        }
+        let logs_url = Url::parse(&self.endpoint)
+            .and_then(|endpoint| endpoint.join(LOGS_PATH))
+            .context(InvalidEndpointSnafu {
+                endpoint: self.endpoint.clone(),
+            })?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let checkpoint_store = self
+            .checkpoint_store
+            .build(&cx, self.data_dir.as_ref(), "checkpoint.json")
+            .await?;
+
+        let source = Okta {
+            client,
+            logs_url,
+            token: self.token.clone(),
+            filter: self.filter.clone(),
+            limit: self.limit,
+            interval: Duration::from_secs(self.interval_secs),
+            checkpoint_store,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "okta"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// The cursor of the next page of the System Log.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Checkpoint {
+    after: Option<String>,
+}
+
+/// A page of the System Log.
+struct Page {
+    events: Vec<Event>,
+    /// The cursor of the next page, from the `next` link.
+    after: Option<String>,
+    /// How long to wait before the next request, once the rate limit is exhausted.
+    delay: Option<Duration>,
+}
+
+struct Okta {
+    client: HttpClient,
+    logs_url: Url,
+    token: String,
+    filter: Option<String>,
+    limit: u32,
+    interval: Duration,
+    checkpoint_store: CheckpointStore,
+    acknowledgements: bool,
+}
+
+impl Okta {
+    async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut checkpoint = self
+            .checkpoint_store
+            .load_json_or_default::<Checkpoint>()
+            .await
+            .map_err(|error| {
+                error!(
+                    message = "Failed to load checkpoint.",
+                    location = %self.checkpoint_store.location(),
+                    %error,
+                );
+            })?;
+
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {},
+            }
+
+            // The checkpoint is only advanced once a page is delivered, so a poll can be
+            // interrupted at any point.
+            let result = select! {
+                _ = &mut shutdown => break,
+                result = self.poll(&mut checkpoint, &mut out) => result,
+            };
+            match result {
+                Ok(()) => {}
+                Err(OktaError::Closed) => return Err(()),
+                Err(error) => emit!(OktaRequestError { error }),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the pages of the System Log until the latest one.
+    async fn poll(
+        &self,
+        checkpoint: &mut Checkpoint,
+        out: &mut SourceSender,
+    ) -> Result<(), OktaError> {
+        loop {
+            let page = self.fetch(checkpoint.after.as_deref()).await?;
+            let count = page.events.len();
+            self.send(page.events, out).await?;
+
+            let after = match page.after {
+                Some(after) => after,
+                None => return Ok(()),
+            };
+            if checkpoint.after.as_ref() != Some(&after) {
+                checkpoint.after = Some(after);
+                if let Err(error) = self.checkpoint_store.save_json(checkpoint).await {
+                    emit!(OktaCheckpointError { error });
+                }
+            }
+
+            if let Some(delay) = page.delay {
+                emit!(OktaRateLimited { delay });
+                tokio::time::sleep(delay).await;
+            }
+            // The `next` link is always present when polling, a partial page being the latest.
+            if count < self.limit as usize {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Requests a page, waiting for the rate limit to reset when it's exceeded.
+    async fn fetch(&self, after: Option<&str>) -> Result<Page, OktaError> {
+        let url = logs_url(&self.logs_url, self.filter.as_deref(), self.limit, after);
+        loop {
+            let request = Request::get(url.as_str())
+                .header(AUTHORIZATION, format!("SSWS {}", self.token))
+                .header(ACCEPT, "application/json")
+                .body(Body::empty())
+                .context(BuildRequestSnafu)?;
+            let response = self.client.send(request).await.context(RequestSnafu)?;
+            let (parts, body) = response.into_parts();
+            let delay = rate_limit_delay(&parts.headers, Utc::now());
+
+            if parts.status == StatusCode::TOO_MANY_REQUESTS {
+                let delay = delay.unwrap_or(self.interval);
+                emit!(OktaRateLimited { delay });
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            let body = hyper::body::to_bytes(body)
+                .await
+                .context(ReadResponseSnafu)?;
+            if !parts.status.is_success() {
+                return Err(OktaError::UnexpectedStatus {
+                    status: parts.status,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                });
+            }
+            emit!(BytesReceived {
+                byte_size: body.len(),
+                protocol: "http",
+            });
+
+            return Ok(Page {
+                events: parse_events(&body).context(ParseSnafu)?,
+                after: next_cursor(&parts.headers),
+                delay,
+            });
+        }
+    }
+
+    async fn send(&self, mut events: Vec<Event>, out: &mut SourceSender) -> Result<(), OktaError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(self.acknowledgements, &mut events);
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+            OktaError::Closed
+        })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored | BatchStatus::Rejected => Err(OktaError::NotDelivered),
+            },
+        }
+    }
+}
+
+/// Builds the URL of a page of the System Log.
+///
+/// Without a cursor, the first page starts seven days ago, which is the default of the API.
+fn logs_url(base: &Url, filter: Option<&str>, limit: u32, after: Option<&str>) -> Url {
+    let mut url = base.clone();
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("limit", &limit.to_string());
+        if let Some(filter) = filter {
+            query.append_pair("filter", filter);
+        }
+        if let Some(after) = after {
+            query.append_pair("after", after);
+        }
+    }
+    url
+}
+
+/// Extracts the cursor of the next page from the `Link` headers.
+///
+/// https://developer.okta.com/docs/reference/core-okta-api/#link-header
+fn next_cursor(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| {
+            let start = link.find('<')? + 1;
+            let end = link.find('>')?;
+            Url::parse(link.get(start..end)?).ok()
+        })
+        .and_then(|url| {
+            url.query_pairs()
+                .find(|(key, _)| key == "after")
+                .map(|(_, value)| value.into_owned())
+        })
+}
+
+/// Returns how long to wait before the next request, if the rate limit is exhausted.
+///
+/// https://developer.okta.com/docs/reference/rl-best-practices/
+fn rate_limit_delay(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name: &str| -> Option<i64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+
+    if header("x-rate-limit-remaining")? > 0 {
+        return None;
+    }
+    let reset = Utc.timestamp(header("x-rate-limit-reset")?, 0);
+    // The reset time has a precision of a second, so a second is added to not retry too early.
+    let delay = (reset - now).to_std().unwrap_or_default() + Duration::from_secs(1);
+    Some(delay)
+}
+
+fn parse_events(body: &[u8]) -> Result<Vec<Event>, serde_json::Error> {
+    let values: Vec<serde_json::Value> = serde_json::from_slice(body)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| LogEvent::try_from(value).ok())
+        .map(|mut log| {
+            let timestamp = log
+                .get("published")
+                .and_then(|value| value.as_str())
+                .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now);
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert(log_schema().source_type_key(), Bytes::from("okta"));
+            log.into()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OktaConfig>();
+    }
+
+    #[test]
+    fn builds_logs_url() {
+        let base = Url::parse("https://example.okta.com")
+            .unwrap()
+            .join(LOGS_PATH)
+            .unwrap();
+        assert_eq!(
+            logs_url(&base, None, 1000, None).as_str(),
+            "https://example.okta.com/api/v1/logs?limit=1000"
+        );
+        assert_eq!(
+            logs_url(
+                &base,
+                Some("eventType eq \"user.session.start\""),
+                100,
+                Some("1659312000000_1")
+            )
+            .as_str(),
+            "https://example.okta.com/api/v1/logs?limit=100&filter=eventType+eq+%22user.session.start%22&after=1659312000000_1"
+        );
+    }
+
+    #[test]
+    fn extracts_next_cursor() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            LINK,
+            HeaderValue::from_static(
+                "<https://example.okta.com/api/v1/logs?limit=100>; rel=\"self\"",
+            ),
+        );
+        assert_eq!(next_cursor(&headers), None);
+
+        headers.append(
+            LINK,
+            HeaderValue::from_static(
+                "<https://example.okta.com/api/v1/logs?limit=100&after=1659312000000_1>; rel=\"next\"",
+            ),
+        );
+        assert_eq!(next_cursor(&headers), Some("1659312000000_1".to_owned()));
+    }
+
+    #[test]
+    fn computes_rate_limit_delay() {
+        let now = Utc.timestamp(1659312000, 0);
+        let mut headers = HeaderMap::new();
+        assert_eq!(rate_limit_delay(&headers, now), None);
+
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("5"));
+        headers.insert("x-rate-limit-reset", HeaderValue::from_static("1659312030"));
+        assert_eq!(rate_limit_delay(&headers, now), None);
+
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("0"));
+        assert_eq!(
+            rate_limit_delay(&headers, now),
+            Some(Duration::from_secs(31))
+        );
+
+        headers.insert("x-rate-limit-reset", HeaderValue::from_static("1659311990"));
+        assert_eq!(
+            rate_limit_delay(&headers, now),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn parses_log_events() {
+        let body = br#"[
+            {"uuid": "a1", "published": "2022-08-01T00:00:01.000Z", "eventType": "user.session.start", "displayMessage": "User login to Okta"},
+            {"uuid": "a2", "published": "2022-08-01T00:00:02.000Z", "eventType": "user.session.end", "displayMessage": "User logout from Okta"}
+        ]"#;
+
+        let events = parse_events(body).unwrap();
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log["eventType"], "user.session.end".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2022, 8, 1).and_hms(0, 0, 2).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "okta".into());
+
+        assert!(parse_events(br#"{"errorCode": "E0000011"}"#).is_err());
+    }
+}
@@ -0,0 +1,531 @@
+//! Conversion of OTLP export requests into Vector events.
+//!
+//! Resource and instrumentation scope attributes are carried consistently across signals: logs and
+//! traces store them under the `resources` and `scope` fields, while metrics, which only carry
+//! string tags, store them as tags prefixed with `resource.` and `scope.`.
+
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use ordered_float::NotNan;
+
+use super::proto::{
+    collector::{
+        logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest,
+        trace::v1::ExportTraceServiceRequest,
+    },
+    common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+    metrics::v1::{
+        exponential_histogram_data_point::Buckets, metric, number_data_point,
+        AggregationTemporality, ExponentialHistogramDataPoint, HistogramDataPoint, NumberDataPoint,
+        SummaryDataPoint,
+    },
+    resource::v1::Resource,
+    trace::v1::{span::SpanKind, status::StatusCode},
+};
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricTags, MetricValue, Quantile},
+        Event, LogEvent, TraceEvent, Value,
+    },
+};
+
+const SOURCE_TYPE: &str = "opentelemetry";
+
+pub(super) fn logs_into_events(request: ExportLogsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    let now = Utc::now();
+
+    for resource_logs in request.resource_logs {
+        let resources = resource_into_value(resource_logs.resource);
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_into_value(scope_logs.scope);
+            for record in scope_logs.log_records {
+                let mut log = LogEvent::default();
+
+                log.insert(
+                    log_schema().message_key(),
+                    record.body.map(any_value_into_value).unwrap_or(Value::Null),
+                );
+                insert_non_empty(
+                    &mut log,
+                    "attributes",
+                    kv_list_into_value(record.attributes),
+                );
+                insert_non_empty(&mut log, "resources", resources.clone());
+                insert_non_empty(&mut log, "scope", scope.clone());
+                insert_id(&mut log, "trace_id", &record.trace_id);
+                insert_id(&mut log, "span_id", &record.span_id);
+                if !record.severity_text.is_empty() {
+                    log.insert("severity_text", record.severity_text);
+                }
+                if record.severity_number != 0 {
+                    log.insert("severity_number", record.severity_number);
+                }
+                if record.flags != 0 {
+                    log.insert("flags", record.flags);
+                }
+                if record.dropped_attributes_count != 0 {
+                    log.insert("dropped_attributes_count", record.dropped_attributes_count);
+                }
+
+                let observed_timestamp = nanos_into_timestamp(record.observed_time_unix_nano);
+                if let Some(observed_timestamp) = observed_timestamp {
+                    log.insert("observed_timestamp", observed_timestamp);
+                }
+                log.insert(
+                    log_schema().timestamp_key(),
+                    nanos_into_timestamp(record.time_unix_nano)
+                        .or(observed_timestamp)
+                        .unwrap_or(now),
+                );
+                log.insert(log_schema().source_type_key(), Bytes::from(SOURCE_TYPE));
+
+                events.push(Event::Log(log));
+            }
+        }
+    }
+
+    events
+}
+
+pub(super) fn metrics_into_events(request: ExportMetricsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for resource_metrics in request.resource_metrics {
+        let mut common_tags = MetricTags::new();
+        if let Some(resource) = resource_metrics.resource {
+            extend_tags(&mut common_tags, "resource.", resource.attributes);
+        }
+
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut common_tags = common_tags.clone();
+            if let Some(scope) = scope_metrics.scope {
+                if !scope.name.is_empty() {
+                    common_tags.insert("scope.name".into(), scope.name);
+                }
+                if !scope.version.is_empty() {
+                    common_tags.insert("scope.version".into(), scope.version);
+                }
+                extend_tags(&mut common_tags, "scope.", scope.attributes);
+            }
+
+            for metric in scope_metrics.metrics {
+                let converter = MetricConverter {
+                    name: &metric.name,
+                    common_tags: &common_tags,
+                };
+                match metric.data {
+                    Some(metric::Data::Gauge(gauge)) => {
+                        events.extend(gauge.data_points.into_iter().filter_map(|point| {
+                            converter.number(point, MetricKind::Absolute, false)
+                        }))
+                    }
+                    Some(metric::Data::Sum(sum)) => {
+                        let kind = temporality_into_kind(sum.aggregation_temporality);
+                        events.extend(
+                            sum.data_points.into_iter().filter_map(|point| {
+                                converter.number(point, kind, sum.is_monotonic)
+                            }),
+                        )
+                    }
+                    Some(metric::Data::Histogram(histogram)) => {
+                        let kind = temporality_into_kind(histogram.aggregation_temporality);
+                        events.extend(
+                            histogram
+                                .data_points
+                                .into_iter()
+                                .map(|point| converter.histogram(point, kind)),
+                        )
+                    }
+                    Some(metric::Data::ExponentialHistogram(histogram)) => {
+                        let kind = temporality_into_kind(histogram.aggregation_temporality);
+                        events.extend(
+                            histogram
+                                .data_points
+                                .into_iter()
+                                .map(|point| converter.exponential_histogram(point, kind)),
+                        )
+                    }
+                    Some(metric::Data::Summary(summary)) => events.extend(
+                        summary
+                            .data_points
+                            .into_iter()
+                            .map(|point| converter.summary(point)),
+                    ),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    events
+}
+
+pub(super) fn traces_into_events(request: ExportTraceServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for resource_spans in request.resource_spans {
+        let resources = resource_into_value(resource_spans.resource);
+        for scope_spans in resource_spans.scope_spans {
+            let scope = scope_into_value(scope_spans.scope);
+            for span in scope_spans.spans {
+                let mut trace = LogEvent::default();
+
+                insert_id(&mut trace, "trace_id", &span.trace_id);
+                insert_id(&mut trace, "span_id", &span.span_id);
+                insert_id(&mut trace, "parent_span_id", &span.parent_span_id);
+                if !span.trace_state.is_empty() {
+                    trace.insert("trace_state", span.trace_state);
+                }
+                trace.insert("name", span.name);
+                trace.insert("kind", span_kind_name(span.kind));
+
+                let start_timestamp = nanos_into_timestamp(span.start_time_unix_nano);
+                if let Some(start_timestamp) = start_timestamp {
+                    trace.insert("start_timestamp", start_timestamp);
+                    trace.insert(log_schema().timestamp_key(), start_timestamp);
+                }
+                if let Some(end_timestamp) = nanos_into_timestamp(span.end_time_unix_nano) {
+                    trace.insert("end_timestamp", end_timestamp);
+                }
+
+                insert_non_empty(
+                    &mut trace,
+                    "attributes",
+                    kv_list_into_value(span.attributes),
+                );
+                insert_non_empty(&mut trace, "resources", resources.clone());
+                insert_non_empty(&mut trace, "scope", scope.clone());
+
+                let span_events = span
+                    .events
+                    .into_iter()
+                    .map(|event| {
+                        let mut value = BTreeMap::new();
+                        value.insert("name".to_owned(), Value::from(event.name));
+                        if let Some(timestamp) = nanos_into_timestamp(event.time_unix_nano) {
+                            value.insert("timestamp".to_owned(), Value::from(timestamp));
+                        }
+                        insert_non_empty_map(
+                            &mut value,
+                            "attributes",
+                            kv_list_into_value(event.attributes),
+                        );
+                        Value::Object(value)
+                    })
+                    .collect::<Vec<_>>();
+                if !span_events.is_empty() {
+                    trace.insert("events", span_events);
+                }
+
+                let links = span
+                    .links
+                    .into_iter()
+                    .map(|link| {
+                        let mut value = BTreeMap::new();
+                        value.insert(
+                            "trace_id".to_owned(),
+                            Value::from(hex::encode(link.trace_id)),
+                        );
+                        value.insert("span_id".to_owned(), Value::from(hex::encode(link.span_id)));
+                        if !link.trace_state.is_empty() {
+                            value.insert("trace_state".to_owned(), Value::from(link.trace_state));
+                        }
+                        insert_non_empty_map(
+                            &mut value,
+                            "attributes",
+                            kv_list_into_value(link.attributes),
+                        );
+                        Value::Object(value)
+                    })
+                    .collect::<Vec<_>>();
+                if !links.is_empty() {
+                    trace.insert("links", links);
+                }
+
+                if let Some(status) = span.status {
+                    let mut value = BTreeMap::new();
+                    value.insert(
+                        "code".to_owned(),
+                        Value::from(status_code_name(status.code)),
+                    );
+                    if !status.message.is_empty() {
+                        value.insert("message".to_owned(), Value::from(status.message));
+                    }
+                    trace.insert("status", Value::Object(value));
+                }
+
+                for (field, count) in [
+                    ("dropped_attributes_count", span.dropped_attributes_count),
+                    ("dropped_events_count", span.dropped_events_count),
+                    ("dropped_links_count", span.dropped_links_count),
+                ] {
+                    if count != 0 {
+                        trace.insert(field, count);
+                    }
+                }
+
+                trace.insert(log_schema().source_type_key(), Bytes::from(SOURCE_TYPE));
+
+                events.push(Event::Trace(TraceEvent::from(trace)));
+            }
+        }
+    }
+
+    events
+}
+
+struct MetricConverter<'a> {
+    name: &'a str,
+    common_tags: &'a MetricTags,
+}
+
+impl<'a> MetricConverter<'a> {
+    fn metric(
+        &self,
+        kind: MetricKind,
+        value: MetricValue,
+        attributes: Vec<KeyValue>,
+        time_unix_nano: u64,
+    ) -> Event {
+        let mut tags = self.common_tags.clone();
+        extend_tags(&mut tags, "", attributes);
+
+        Metric::new(self.name, kind, value)
+            .with_tags((!tags.is_empty()).then(|| tags))
+            .with_timestamp(nanos_into_timestamp(time_unix_nano))
+            .into()
+    }
+
+    /// Converts a gauge or sum point. Monotonic sums are counters, everything else is a gauge.
+    fn number(&self, point: NumberDataPoint, kind: MetricKind, monotonic: bool) -> Option<Event> {
+        let value = match point.value? {
+            number_data_point::Value::AsDouble(value) => value,
+            number_data_point::Value::AsInt(value) => value as f64,
+        };
+        let value = if monotonic {
+            MetricValue::Counter { value }
+        } else {
+            MetricValue::Gauge { value }
+        };
+
+        Some(self.metric(kind, value, point.attributes, point.time_unix_nano))
+    }
+
+    fn histogram(&self, point: HistogramDataPoint, kind: MetricKind) -> Event {
+        let buckets = point
+            .bucket_counts
+            .iter()
+            .enumerate()
+            .map(|(index, count)| Bucket {
+                upper_limit: point
+                    .explicit_bounds
+                    .get(index)
+                    .copied()
+                    .unwrap_or(f64::INFINITY),
+                count: *count as u32,
+            })
+            .collect();
+        let value = MetricValue::AggregatedHistogram {
+            buckets,
+            count: point.count as u32,
+            sum: point.sum.unwrap_or_default(),
+        };
+
+        self.metric(kind, value, point.attributes, point.time_unix_nano)
+    }
+
+    /// Exponential buckets are converted to explicit ones, using the upper boundary of each
+    /// exponential bucket as limit.
+    fn exponential_histogram(
+        &self,
+        point: ExponentialHistogramDataPoint,
+        kind: MetricKind,
+    ) -> Event {
+        let base = 2f64.powf(2f64.powi(-point.scale));
+        let bucket_indices = |buckets: Option<Buckets>| {
+            buckets
+                .map(|buckets| {
+                    let offset = buckets.offset;
+                    buckets
+                        .bucket_counts
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, count)| (offset + index as i32, count as u32))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut buckets = bucket_indices(point.negative)
+            .into_iter()
+            .rev()
+            .map(|(index, count)| Bucket {
+                upper_limit: -base.powi(index),
+                count,
+            })
+            .collect::<Vec<_>>();
+        buckets.push(Bucket {
+            upper_limit: 0.0,
+            count: point.zero_count as u32,
+        });
+        buckets.extend(
+            bucket_indices(point.positive)
+                .into_iter()
+                .map(|(index, count)| Bucket {
+                    upper_limit: base.powi(index + 1),
+                    count,
+                }),
+        );
+
+        let value = MetricValue::AggregatedHistogram {
+            buckets,
+            count: point.count as u32,
+            sum: point.sum.unwrap_or_default(),
+        };
+
+        self.metric(kind, value, point.attributes, point.time_unix_nano)
+    }
+
+    fn summary(&self, point: SummaryDataPoint) -> Event {
+        let quantiles = point
+            .quantile_values
+            .into_iter()
+            .map(|quantile| Quantile {
+                quantile: quantile.quantile,
+                value: quantile.value,
+            })
+            .collect();
+        let value = MetricValue::AggregatedSummary {
+            quantiles,
+            count: point.count as u32,
+            sum: point.sum,
+        };
+
+        self.metric(
+            MetricKind::Absolute,
+            value,
+            point.attributes,
+            point.time_unix_nano,
+        )
+    }
+}
+
+fn temporality_into_kind(temporality: i32) -> MetricKind {
+    if temporality == AggregationTemporality::Delta as i32 {
+        MetricKind::Incremental
+    } else {
+        MetricKind::Absolute
+    }
+}
+
+fn span_kind_name(kind: i32) -> &'static str {
+    match SpanKind::from_i32(kind) {
+        Some(SpanKind::Internal) => "internal",
+        Some(SpanKind::Server) => "server",
+        Some(SpanKind::Client) => "client",
+        Some(SpanKind::Producer) => "producer",
+        Some(SpanKind::Consumer) => "consumer",
+        Some(SpanKind::Unspecified) | None => "unspecified",
+    }
+}
+
+fn status_code_name(code: i32) -> &'static str {
+    match StatusCode::from_i32(code) {
+        Some(StatusCode::Ok) => "ok",
+        Some(StatusCode::Error) => "error",
+        Some(StatusCode::Unset) | None => "unset",
+    }
+}
+
+fn nanos_into_timestamp(nanos: u64) -> Option<DateTime<Utc>> {
+    (nanos != 0).then(|| Utc.timestamp_nanos(nanos as i64))
+}
+
+fn any_value_into_value(value: AnyValue) -> Value {
+    match value.value {
+        Some(any_value::Value::StringValue(value)) => Value::from(value),
+        Some(any_value::Value::BoolValue(value)) => Value::from(value),
+        Some(any_value::Value::IntValue(value)) => Value::from(value),
+        Some(any_value::Value::DoubleValue(value)) => {
+            NotNan::new(value).map(Value::Float).unwrap_or(Value::Null)
+        }
+        Some(any_value::Value::BytesValue(value)) => Value::from(Bytes::from(value)),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.into_iter().map(any_value_into_value).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => kv_list_into_value(list.values),
+        None => Value::Null,
+    }
+}
+
+fn kv_list_into_value(list: Vec<KeyValue>) -> Value {
+    Value::Object(
+        list.into_iter()
+            .map(|kv| {
+                (
+                    kv.key,
+                    kv.value.map(any_value_into_value).unwrap_or(Value::Null),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn resource_into_value(resource: Option<Resource>) -> Value {
+    kv_list_into_value(
+        resource
+            .map(|resource| resource.attributes)
+            .unwrap_or_default(),
+    )
+}
+
+fn scope_into_value(scope: Option<InstrumentationScope>) -> Value {
+    let mut value = BTreeMap::new();
+    if let Some(scope) = scope {
+        if !scope.name.is_empty() {
+            value.insert("name".to_owned(), Value::from(scope.name));
+        }
+        if !scope.version.is_empty() {
+            value.insert("version".to_owned(), Value::from(scope.version));
+        }
+        insert_non_empty_map(
+            &mut value,
+            "attributes",
+            kv_list_into_value(scope.attributes),
+        );
+    }
+    Value::Object(value)
+}
+
+fn extend_tags(tags: &mut MetricTags, prefix: &str, attributes: Vec<KeyValue>) {
+    for kv in attributes {
+        let value = kv.value.map(any_value_into_value).unwrap_or(Value::Null);
+        tags.insert(format!("{}{}", prefix, kv.key), value.to_string_lossy());
+    }
+}
+
+fn insert_id(log: &mut LogEvent, field: &str, id: &[u8]) {
+    if !id.is_empty() {
+        log.insert(field, hex::encode(id));
+    }
+}
+
+fn insert_non_empty(log: &mut LogEvent, field: &str, value: Value) {
+    if !is_empty_object(&value) {
+        log.insert(field, value);
+    }
+}
+
+fn insert_non_empty_map(map: &mut BTreeMap<String, Value>, field: &str, value: Value) {
+    if !is_empty_object(&value) {
+        map.insert(field.to_owned(), value);
+    }
+}
+
+fn is_empty_object(value: &Value) -> bool {
+    matches!(value, Value::Object(map) if map.is_empty())
+}
@@ -0,0 +1,145 @@
+use std::net::SocketAddr;
+
+use futures::FutureExt;
+use tonic::{
+    service::interceptor::InterceptedService, transport::Server, Request, Response, Status,
+};
+use tracing::{Instrument, Span};
+
+use super::{
+    convert,
+    proto::collector::{
+        logs::v1::{
+            logs_service_server::{LogsService, LogsServiceServer},
+            ExportLogsServiceRequest, ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        },
+        trace::v1::{
+            trace_service_server::{TraceService, TraceServiceServer},
+            ExportTraceServiceRequest, ExportTraceServiceResponse,
+        },
+    },
+    DeliveryError, OpentelemetrySender, LOGS, METRICS, TRACES,
+};
+use crate::{
+    shutdown::{ShutdownSignal, ShutdownSignalToken},
+    sources::util::{grpc::DecompressionAndMetricsLayer, HttpSourceAuth},
+    tls::MaybeTlsSettings,
+};
+
+#[derive(Clone)]
+struct Service {
+    sender: OpentelemetrySender,
+}
+
+#[tonic::async_trait]
+impl LogsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+        let events = convert::logs_into_events(request.into_inner());
+        self.sender.send(LOGS, events).await.map_err(into_status)?;
+
+        Ok(Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let events = convert::metrics_into_events(request.into_inner());
+        self.sender
+            .send(METRICS, events)
+            .await
+            .map_err(into_status)?;
+
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportTraceServiceRequest>,
+    ) -> Result<Response<ExportTraceServiceResponse>, Status> {
+        let events = convert::traces_into_events(request.into_inner());
+        self.sender
+            .send(TRACES, events)
+            .await
+            .map_err(into_status)?;
+
+        Ok(Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+fn into_status(error: DeliveryError) -> Status {
+    let message = error.to_string();
+    match error {
+        DeliveryError::Closed => Status::unavailable(message),
+        DeliveryError::Errored => Status::internal(message),
+        DeliveryError::Rejected => Status::data_loss(message),
+    }
+}
+
+fn check_auth(auth: &HttpSourceAuth, request: Request<()>) -> Result<Request<()>, Status> {
+    let header = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned);
+
+    auth.is_valid(&header)
+        .map_err(|error| Status::unauthenticated(error.to_string()))?;
+
+    Ok(request)
+}
+
+/// Serves the OTLP logs, metrics and traces services on the same address.
+///
+/// This mirrors `sources::util::grpc::run_grpc_server`, which only supports a single service.
+pub(super) async fn run_grpc_server(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    sender: OpentelemetrySender,
+    auth: HttpSourceAuth,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let span = Span::current();
+    let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
+    let listener = tls_settings.bind(&address).await?;
+    let stream = listener.accept_stream();
+
+    let service = Service { sender };
+    let interceptor = move |request: Request<()>| check_auth(&auth, request);
+
+    Server::builder()
+        .trace_fn(move |_| span.clone())
+        .layer(DecompressionAndMetricsLayer::default())
+        .add_service(InterceptedService::new(
+            LogsServiceServer::new(service.clone()).accept_gzip(),
+            interceptor.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            MetricsServiceServer::new(service.clone()).accept_gzip(),
+            interceptor.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            TraceServiceServer::new(service).accept_gzip(),
+            interceptor,
+        ))
+        .serve_with_incoming_shutdown(stream, shutdown.map(|token| tx.send(token).unwrap()))
+        .in_current_span()
+        .await?;
+
+    drop(rx.await);
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use http::StatusCode;
+use prost::Message;
+use tracing::Span;
+use warp::{
+    filters::BoxedFilter, http::HeaderValue, reject::Rejection, reply::Response, Filter, Reply,
+};
+
+use super::{
+    convert,
+    proto::collector::{
+        logs::v1::{ExportLogsServiceRequest, ExportLogsServiceResponse},
+        metrics::v1::{ExportMetricsServiceRequest, ExportMetricsServiceResponse},
+        trace::v1::{ExportTraceServiceRequest, ExportTraceServiceResponse},
+    },
+    DeliveryError, OpentelemetrySender, LOGS, METRICS, TRACES,
+};
+use crate::{
+    event::Event,
+    internal_events::HttpBytesReceived,
+    shutdown::ShutdownSignal,
+    sources::util::{decode, ErrorMessage, HttpSourceAuth},
+    tls::MaybeTlsSettings,
+};
+
+/// Serves the OTLP/HTTP endpoints `/v1/logs`, `/v1/metrics` and `/v1/traces`, accepting
+/// binary protobuf payloads.
+pub(super) async fn run_http_server(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    sender: OpentelemetrySender,
+    auth: HttpSourceAuth,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let span = Span::current();
+    let protocol = tls_settings.http_protocol_name();
+    let listener = tls_settings.bind(&address).await?;
+
+    let routes = build_warp_filter::<ExportLogsServiceRequest, ExportLogsServiceResponse>(
+        LOGS,
+        convert::logs_into_events,
+        sender.clone(),
+        auth.clone(),
+        protocol,
+    )
+    .or(build_warp_filter::<
+        ExportMetricsServiceRequest,
+        ExportMetricsServiceResponse,
+    >(
+        METRICS,
+        convert::metrics_into_events,
+        sender.clone(),
+        auth.clone(),
+        protocol,
+    ))
+    .unify()
+    .or(build_warp_filter::<
+        ExportTraceServiceRequest,
+        ExportTraceServiceResponse,
+    >(
+        TRACES,
+        convert::traces_into_events,
+        sender,
+        auth,
+        protocol,
+    ))
+    .unify()
+    .with(warp::trace(move |_info| span.clone()))
+    .recover(|r: Rejection| async move {
+        if let Some(e_msg) = r.find::<ErrorMessage>() {
+            let json = warp::reply::json(e_msg);
+            Ok(warp::reply::with_status(json, e_msg.status_code()))
+        } else {
+            // other internal error - will return 500 internal server error
+            Err(r)
+        }
+    });
+
+    warp::serve(routes)
+        .serve_incoming_with_graceful_shutdown(listener.accept_stream(), shutdown.map(|_| ()))
+        .await;
+
+    Ok(())
+}
+
+/// Builds the filter for the `/v1/<signal>` endpoint, whose events are sent to the output of the
+/// same name.
+fn build_warp_filter<Req, Resp>(
+    signal: &'static str,
+    into_events: fn(Req) -> Vec<Event>,
+    sender: OpentelemetrySender,
+    auth: HttpSourceAuth,
+    protocol: &'static str,
+) -> BoxedFilter<(Response,)>
+where
+    Req: Message + Default + 'static,
+    Resp: Message + Default + 'static,
+{
+    warp::post()
+        .and(warp::path("v1"))
+        .and(warp::path(signal))
+        .and(warp::path::end())
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::header::optional::<String>("content-encoding"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |path: warp::path::FullPath,
+                  auth_header: Option<String>,
+                  encoding_header: Option<String>,
+                  body: Bytes| {
+                let sender = sender.clone();
+                let auth = auth.clone();
+                async move {
+                    auth.is_valid(&auth_header)?;
+
+                    let body = decode(&encoding_header, body)?;
+                    emit!(HttpBytesReceived {
+                        byte_size: body.len(),
+                        http_path: path.as_str(),
+                        protocol,
+                    });
+
+                    let request = Req::decode(body).map_err(|error| {
+                        ErrorMessage::new(
+                            StatusCode::BAD_REQUEST,
+                            format!("Could not decode OTLP request: {}", error),
+                        )
+                    })?;
+
+                    sender
+                        .send(signal, into_events(request))
+                        .await
+                        .map_err(into_error_message)?;
+
+                    Ok::<_, ErrorMessage>(protobuf_response(Resp::default()))
+                }
+                .map(|result| result.map_err(warp::reject::custom))
+            },
+        )
+        .boxed()
+}
+
+fn protobuf_response(message: impl Message) -> Response {
+    let mut response = Response::new(message.encode_to_vec().into());
+    response.headers_mut().insert(
+        "content-type",
+        HeaderValue::from_static("application/x-protobuf"),
+    );
+    response.into_response()
+}
+
+fn into_error_message(error: DeliveryError) -> ErrorMessage {
+    let status = match error {
+        DeliveryError::Closed => StatusCode::SERVICE_UNAVAILABLE,
+        DeliveryError::Errored => StatusCode::INTERNAL_SERVER_ERROR,
+        DeliveryError::Rejected => StatusCode::BAD_REQUEST,
+    };
+    ErrorMessage::new(status, error.to_string())
+}
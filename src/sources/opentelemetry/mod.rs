@@ -0,0 +1,290 @@
+mod convert;
+mod grpc;
+mod http;
+#[cfg(test)]
+mod tests;
+
+use std::{convert::TryFrom, net::SocketAddr};
+
+use futures::{future, FutureExt, TryFutureExt};
+use snafu::Snafu;
+use vector_config::configurable_component;
+use vector_core::{
+    event::{BatchNotifier, BatchStatus, Event},
+    ByteSizeOf,
+};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    internal_events::{EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    sources::{
+        util::{HttpSourceAuth, HttpSourceAuthConfig},
+        Source,
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    SourceSender,
+};
+
+pub const LOGS: &str = "logs";
+pub const METRICS: &str = "metrics";
+pub const TRACES: &str = "traces";
+
+/// The generated OTLP protobuf definitions, nested to mirror their `opentelemetry.proto` packages.
+#[allow(clippy::clone_on_ref_ptr)]
+pub(crate) mod proto {
+    pub mod common {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.common.v1.rs"
+            ));
+        }
+    }
+
+    pub mod resource {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.resource.v1.rs"
+            ));
+        }
+    }
+
+    pub mod logs {
+        pub mod v1 {
+            include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.rs"));
+        }
+    }
+
+    pub mod metrics {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.metrics.v1.rs"
+            ));
+        }
+    }
+
+    pub mod trace {
+        pub mod v1 {
+            include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.trace.v1.rs"));
+        }
+    }
+
+    pub mod collector {
+        pub mod logs {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.logs.v1.rs"
+                ));
+            }
+        }
+
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.metrics.v1.rs"
+                ));
+            }
+        }
+
+        pub mod trace {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.trace.v1.rs"
+                ));
+            }
+        }
+    }
+}
+
+/// Configuration for the `opentelemetry` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetryConfig {
+    #[configurable(derived)]
+    grpc: GrpcConfig,
+
+    #[configurable(derived)]
+    http: HttpConfig,
+
+    #[configurable(derived)]
+    auth: Option<HttpSourceAuthConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// Configuration for the `opentelemetry` gRPC server.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcConfig {
+    /// The address to listen for connections on.
+    ///
+    /// It _must_ include a port.
+    address: SocketAddr,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+}
+
+/// Configuration for the `opentelemetry` HTTP server.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    /// The address to listen for connections on.
+    ///
+    /// It _must_ include a port.
+    address: SocketAddr,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+}
+
+impl GenerateConfig for OpentelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            grpc: GrpcConfig {
+                address: "0.0.0.0:4317".parse().unwrap(),
+                tls: None,
+            },
+            http: HttpConfig {
+                address: "0.0.0.0:4318".parse().unwrap(),
+                tls: None,
+            },
+            auth: None,
+            acknowledgements: Default::default(),
+        })
+        .unwrap()
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<OpentelemetryConfig>("opentelemetry")
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SourceConfig for OpentelemetryConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        let auth = HttpSourceAuth::try_from(self.auth.as_ref())?;
+        let sender = OpentelemetrySender {
+            pipeline: cx.out,
+            acknowledgements,
+        };
+
+        let grpc_tls_settings = MaybeTlsSettings::from_config(&self.grpc.tls, true)?;
+        let grpc_source = grpc::run_grpc_server(
+            self.grpc.address,
+            grpc_tls_settings,
+            sender.clone(),
+            auth.clone(),
+            cx.shutdown.clone(),
+        );
+
+        let http_tls_settings = MaybeTlsSettings::from_config(&self.http.tls, true)?;
+        let http_source = http::run_http_server(
+            self.http.address,
+            http_tls_settings,
+            sender,
+            auth,
+            cx.shutdown,
+        );
+
+        Ok(future::try_join(grpc_source, http_source)
+            .map_ok(|_| ())
+            .map_err(|error| {
+                error!(message = "Source future failed.", %error);
+            })
+            .boxed())
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![
+            Output::default(DataType::Log).with_port(LOGS),
+            Output::default(DataType::Metric).with_port(METRICS),
+            Output::default(DataType::Trace).with_port(TRACES),
+        ]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![
+            Resource::tcp(self.grpc.address),
+            Resource::tcp(self.http.address),
+        ]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum DeliveryError {
+    #[snafu(display("Source is shutting down"))]
+    Closed,
+    #[snafu(display("Delivery error"))]
+    Errored,
+    #[snafu(display("Delivery failed"))]
+    Rejected,
+}
+
+/// Forwards the events decoded from a single export request to the output of their signal, shared
+/// by the gRPC and HTTP servers.
+#[derive(Clone)]
+struct OpentelemetrySender {
+    pipeline: SourceSender,
+    acknowledgements: bool,
+}
+
+impl OpentelemetrySender {
+    async fn send(&self, output: &str, mut events: Vec<Event>) -> Result<(), DeliveryError> {
+        let count = events.len();
+        if count == 0 {
+            return Ok(());
+        }
+
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to(self.acknowledgements, &mut events);
+
+        self.pipeline
+            .clone()
+            .send_batch_named(output, events)
+            .await
+            .map_err(|error| {
+                emit!(StreamClosedError { error, count });
+                DeliveryError::Closed
+            })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => Ok(()),
+                BatchStatus::Errored => Err(DeliveryError::Errored),
+                BatchStatus::Rejected => Err(DeliveryError::Rejected),
+            },
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use std::net::SocketAddr;
+
+use chrono::{TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use prost::Message;
+
+use super::{
+    convert,
+    proto::{
+        collector::{
+            logs::v1::ExportLogsServiceRequest,
+            metrics::v1::ExportMetricsServiceRequest,
+            trace::v1::{trace_service_client::TraceServiceClient, ExportTraceServiceRequest},
+        },
+        common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+        logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+        metrics::v1::{
+            metric, number_data_point, AggregationTemporality, Histogram, HistogramDataPoint,
+            Metric as OtlpMetric, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+        },
+        resource::v1::Resource,
+        trace::v1::{span::SpanKind, ResourceSpans, ScopeSpans, Span},
+    },
+    OpentelemetryConfig, LOGS, TRACES,
+};
+use crate::{
+    config::{log_schema, SourceConfig, SourceContext},
+    event::{
+        into_event_stream,
+        metric::{Bucket, MetricKind, MetricValue},
+        Event, EventStatus, Value,
+    },
+    test_util::{
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr, spawn_collect_n, wait_for_tcp,
+    },
+    SourceSender,
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpentelemetryConfig>();
+}
+
+fn string_kv(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        }),
+    }
+}
+
+fn resource() -> Option<Resource> {
+    Some(Resource {
+        attributes: vec![string_kv("service.name", "checkout")],
+        dropped_attributes_count: 0,
+    })
+}
+
+fn scope() -> Option<InstrumentationScope> {
+    Some(InstrumentationScope {
+        name: "io.opentelemetry.test".into(),
+        version: "1.0.0".into(),
+        attributes: vec![],
+        dropped_attributes_count: 0,
+    })
+}
+
+fn logs_request() -> ExportLogsServiceRequest {
+    ExportLogsServiceRequest {
+        resource_logs: vec![ResourceLogs {
+            resource: resource(),
+            scope_logs: vec![ScopeLogs {
+                scope: scope(),
+                log_records: vec![LogRecord {
+                    time_unix_nano: 1_579_134_612_000_000_011,
+                    severity_text: "INFO".into(),
+                    severity_number: 9,
+                    body: Some(AnyValue {
+                        value: Some(any_value::Value::StringValue("user logged in".into())),
+                    }),
+                    attributes: vec![string_kv("user", "alice")],
+                    trace_id: vec![1; 16],
+                    span_id: vec![2; 8],
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+fn traces_request() -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: resource(),
+            scope_spans: vec![ScopeSpans {
+                scope: scope(),
+                spans: vec![Span {
+                    trace_id: vec![1; 16],
+                    span_id: vec![2; 8],
+                    name: "GET /cart".into(),
+                    kind: SpanKind::Server as i32,
+                    start_time_unix_nano: 1_579_134_612_000_000_000,
+                    end_time_unix_nano: 1_579_134_613_000_000_000,
+                    attributes: vec![string_kv("http.method", "GET")],
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+#[test]
+fn converts_logs() {
+    let events = convert::logs_into_events(logs_request());
+    assert_eq!(events.len(), 1);
+
+    let log = events[0].as_log();
+    assert_eq!(log[log_schema().message_key()], "user logged in".into());
+    assert_eq!(log["attributes.user"], "alice".into());
+    assert_eq!(log["resources.\"service.name\""], "checkout".into());
+    assert_eq!(log["scope.name"], "io.opentelemetry.test".into());
+    assert_eq!(log["scope.version"], "1.0.0".into());
+    assert_eq!(log["severity_text"], "INFO".into());
+    assert_eq!(log["severity_number"], 9.into());
+    assert_eq!(log["trace_id"], "01010101010101010101010101010101".into());
+    assert_eq!(log["span_id"], "0202020202020202".into());
+    assert_eq!(
+        log[log_schema().timestamp_key()],
+        Value::from(Utc.timestamp_nanos(1_579_134_612_000_000_011))
+    );
+    assert_eq!(log[log_schema().source_type_key()], "opentelemetry".into());
+}
+
+#[test]
+fn converts_metrics() {
+    let request = ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: resource(),
+            scope_metrics: vec![ScopeMetrics {
+                scope: scope(),
+                metrics: vec![
+                    OtlpMetric {
+                        name: "requests".into(),
+                        data: Some(metric::Data::Sum(Sum {
+                            data_points: vec![NumberDataPoint {
+                                attributes: vec![string_kv("code", "200")],
+                                time_unix_nano: 1_579_134_612_000_000_000,
+                                value: Some(number_data_point::Value::AsInt(42)),
+                                ..Default::default()
+                            }],
+                            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                            is_monotonic: true,
+                        })),
+                        ..Default::default()
+                    },
+                    OtlpMetric {
+                        name: "latency".into(),
+                        data: Some(metric::Data::Histogram(Histogram {
+                            data_points: vec![HistogramDataPoint {
+                                count: 3,
+                                sum: Some(1.5),
+                                bucket_counts: vec![1, 2],
+                                explicit_bounds: vec![0.5],
+                                ..Default::default()
+                            }],
+                            aggregation_temporality: AggregationTemporality::Delta as i32,
+                        })),
+                        ..Default::default()
+                    },
+                ],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    };
+
+    let events = convert::metrics_into_events(request);
+    assert_eq!(events.len(), 2);
+
+    let counter = events[0].as_metric();
+    assert_eq!(counter.name(), "requests");
+    assert_eq!(counter.kind(), MetricKind::Absolute);
+    assert_eq!(counter.value(), &MetricValue::Counter { value: 42.0 });
+    let tags = counter.tags().unwrap();
+    assert_eq!(tags["code"], "200");
+    assert_eq!(tags["resource.service.name"], "checkout");
+    assert_eq!(tags["scope.name"], "io.opentelemetry.test");
+    assert_eq!(tags["scope.version"], "1.0.0");
+
+    let histogram = events[1].as_metric();
+    assert_eq!(histogram.kind(), MetricKind::Incremental);
+    assert_eq!(
+        histogram.value(),
+        &MetricValue::AggregatedHistogram {
+            buckets: vec![
+                Bucket {
+                    upper_limit: 0.5,
+                    count: 1
+                },
+                Bucket {
+                    upper_limit: f64::INFINITY,
+                    count: 2
+                },
+            ],
+            count: 3,
+            sum: 1.5,
+        }
+    );
+}
+
+#[test]
+fn converts_traces() {
+    let events = convert::traces_into_events(traces_request());
+    assert_eq!(events.len(), 1);
+
+    let trace = events[0].as_trace();
+    assert_eq!(
+        trace.get("trace_id"),
+        Some(&"01010101010101010101010101010101".into())
+    );
+    assert_eq!(trace.get("name"), Some(&"GET /cart".into()));
+    assert_eq!(trace.get("kind"), Some(&"server".into()));
+    assert_eq!(trace.get("attributes.\"http.method\""), Some(&"GET".into()));
+    assert_eq!(
+        trace.get("resources.\"service.name\""),
+        Some(&"checkout".into())
+    );
+    assert_eq!(
+        trace.get("end_timestamp"),
+        Some(&Value::from(Utc.timestamp_nanos(1_579_134_613_000_000_000)))
+    );
+}
+
+async fn source(
+    auth: &str,
+) -> (
+    impl Stream<Item = Event>,
+    impl Stream<Item = Event>,
+    SocketAddr,
+    SocketAddr,
+) {
+    let (mut sender, _) = SourceSender::new_test_finalize(EventStatus::Delivered);
+    let logs_output = sender
+        .add_outputs(EventStatus::Delivered, LOGS.to_string())
+        .flat_map(into_event_stream);
+    let traces_output = sender
+        .add_outputs(EventStatus::Delivered, TRACES.to_string())
+        .flat_map(into_event_stream);
+
+    let grpc_address = next_addr();
+    let http_address = next_addr();
+    let config = toml::from_str::<OpentelemetryConfig>(&format!(
+        indoc! { r#"
+            grpc.address = "{}"
+            http.address = "{}"
+            {}
+        "#},
+        grpc_address, http_address, auth
+    ))
+    .unwrap();
+    let context = SourceContext::new_test(sender, None);
+    tokio::spawn(async move {
+        config.build(context).await.unwrap().await.unwrap();
+    });
+    wait_for_tcp(grpc_address).await;
+    wait_for_tcp(http_address).await;
+
+    (logs_output, traces_output, grpc_address, http_address)
+}
+
+#[tokio::test]
+async fn receives_logs_over_http() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let (logs, _traces, _, address) = source("").await;
+
+        let events = spawn_collect_n(
+            async move {
+                let response = reqwest::Client::new()
+                    .post(&format!("http://{}/v1/logs", address))
+                    .header("content-type", "application/x-protobuf")
+                    .body(logs_request().encode_to_vec())
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status().as_u16(), 200);
+                assert_eq!(response.headers()["content-type"], "application/x-protobuf");
+            },
+            logs,
+            1,
+        )
+        .await;
+
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "user logged in".into()
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn receives_traces_over_grpc() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let (_logs, traces, address, _) = source("").await;
+
+        let events = spawn_collect_n(
+            async move {
+                let mut client = TraceServiceClient::connect(format!("http://{}", address))
+                    .await
+                    .unwrap();
+                client.export(traces_request()).await.unwrap();
+            },
+            traces,
+            1,
+        )
+        .await;
+
+        assert_eq!(events[0].as_trace().get("name"), Some(&"GET /cart".into()));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn rejects_unauthenticated_requests() {
+    let (_logs, _traces, grpc_address, http_address) = source(indoc! { r#"
+        auth.username = "user"
+        auth.password = "secret"
+    "#})
+    .await;
+
+    let status = reqwest::Client::new()
+        .post(&format!("http://{}/v1/logs", http_address))
+        .body(logs_request().encode_to_vec())
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status.as_u16(), 401);
+
+    let status = reqwest::Client::new()
+        .post(&format!("http://{}/v1/logs", http_address))
+        .basic_auth("user", Some("secret"))
+        .body(logs_request().encode_to_vec())
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status.as_u16(), 200);
+
+    let mut client = TraceServiceClient::connect(format!("http://{}", grpc_address))
+        .await
+        .unwrap();
+    let error = client.export(traces_request()).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::Unauthenticated);
+}
@@ -0,0 +1,419 @@
+//! A decoder of the binary [`pgoutput` logical replication
+//! protocol](https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html),
+//! version 1.
+
+use std::collections::{BTreeMap, HashMap};
+
+use bytes::Buf;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ordered_float::NotNan;
+use snafu::ResultExt;
+
+use super::{Change, DecodeError, Decoder, InvalidUtf8Snafu, Message, Operation};
+use crate::event::Value;
+
+// Type OIDs from `pg_type.dat`.
+const BOOL_OID: u32 = 16;
+const INT8_OID: u32 = 20;
+const INT2_OID: u32 = 21;
+const INT4_OID: u32 = 23;
+const OID_OID: u32 = 26;
+const FLOAT4_OID: u32 = 700;
+const FLOAT8_OID: u32 = 701;
+
+#[derive(Debug)]
+struct Column {
+    name: String,
+    type_oid: u32,
+}
+
+#[derive(Debug)]
+struct Relation {
+    schema: String,
+    table: String,
+    columns: Vec<Column>,
+}
+
+/// Decodes `pgoutput` messages, tracking the relations announced in the current session.
+#[derive(Debug, Default)]
+pub(super) struct PgoutputDecoder {
+    relations: HashMap<u32, Relation>,
+}
+
+impl Decoder for PgoutputDecoder {
+    fn decode(&mut self, mut data: &[u8]) -> Result<Option<Message>, DecodeError> {
+        let buf = &mut data;
+        match get_u8(buf)? {
+            b'B' => {
+                let _final_lsn = get_u64(buf)?;
+                let timestamp = get_timestamp(buf)?;
+                Ok(Some(Message::Begin {
+                    timestamp: Some(timestamp),
+                }))
+            }
+            b'C' => Ok(Some(Message::Commit)),
+            b'R' => {
+                let id = get_u32(buf)?;
+                let schema = get_string(buf)?;
+                let table = get_string(buf)?;
+                let _replica_identity = get_u8(buf)?;
+                let columns = (0..get_u16(buf)?)
+                    .map(|_| {
+                        let _flags = get_u8(buf)?;
+                        let name = get_string(buf)?;
+                        let type_oid = get_u32(buf)?;
+                        let _type_modifier = get_u32(buf)?;
+                        Ok(Column { name, type_oid })
+                    })
+                    .collect::<Result<_, DecodeError>>()?;
+                self.relations.insert(
+                    id,
+                    Relation {
+                        schema,
+                        table,
+                        columns,
+                    },
+                );
+                Ok(None)
+            }
+            b'I' => {
+                let relation = self.relation(get_u32(buf)?)?;
+                expect_tag(buf, b'N')?;
+                let after = decode_tuple(buf, relation)?;
+                Ok(Some(Message::Changes(vec![relation.change(
+                    Operation::Insert,
+                    None,
+                    Some(after),
+                )])))
+            }
+            b'U' => {
+                let relation = self.relation(get_u32(buf)?)?;
+                let before = match get_u8(buf)? {
+                    b'K' | b'O' => {
+                        let before = decode_tuple(buf, relation)?;
+                        expect_tag(buf, b'N')?;
+                        Some(before)
+                    }
+                    b'N' => None,
+                    tag => return Err(DecodeError::UnknownTuple { tag }),
+                };
+                let after = decode_tuple(buf, relation)?;
+                Ok(Some(Message::Changes(vec![relation.change(
+                    Operation::Update,
+                    before,
+                    Some(after),
+                )])))
+            }
+            b'D' => {
+                let relation = self.relation(get_u32(buf)?)?;
+                match get_u8(buf)? {
+                    b'K' | b'O' => {}
+                    tag => return Err(DecodeError::UnknownTuple { tag }),
+                }
+                let before = decode_tuple(buf, relation)?;
+                Ok(Some(Message::Changes(vec![relation.change(
+                    Operation::Delete,
+                    Some(before),
+                    None,
+                )])))
+            }
+            b'T' => {
+                let count = get_u32(buf)?;
+                let _options = get_u8(buf)?;
+                let changes = (0..count)
+                    .map(|_| {
+                        let relation = self.relation(get_u32(buf)?)?;
+                        Ok(relation.change(Operation::Truncate, None, None))
+                    })
+                    .collect::<Result<_, DecodeError>>()?;
+                Ok(Some(Message::Changes(changes)))
+            }
+            // Types and origins are not needed to decode the changes.
+            b'Y' | b'O' => Ok(None),
+            tag => Err(DecodeError::UnknownMessage { tag }),
+        }
+    }
+}
+
+impl PgoutputDecoder {
+    fn relation(&self, id: u32) -> Result<&Relation, DecodeError> {
+        self.relations
+            .get(&id)
+            .ok_or(DecodeError::UnknownRelation { id })
+    }
+}
+
+impl Relation {
+    fn change(
+        &self,
+        op: Operation,
+        before: Option<BTreeMap<String, Value>>,
+        after: Option<BTreeMap<String, Value>>,
+    ) -> Change {
+        Change {
+            op,
+            schema: self.schema.clone(),
+            table: self.table.clone(),
+            before,
+            after,
+        }
+    }
+}
+
+fn decode_tuple(
+    buf: &mut &[u8],
+    relation: &Relation,
+) -> Result<BTreeMap<String, Value>, DecodeError> {
+    let count = get_u16(buf)? as usize;
+    let mut tuple = BTreeMap::new();
+    for column in relation.columns.iter().take(count) {
+        match get_u8(buf)? {
+            b'n' => {
+                tuple.insert(column.name.clone(), Value::Null);
+            }
+            // Unchanged TOASTed values are not sent, so they are left out.
+            b'u' => {}
+            b't' => {
+                let len = get_u32(buf)? as usize;
+                if buf.remaining() < len {
+                    return Err(DecodeError::UnexpectedEof);
+                }
+                let text = std::str::from_utf8(&buf[..len]).context(InvalidUtf8Snafu)?;
+                tuple.insert(column.name.clone(), parse_value(column.type_oid, text));
+                buf.advance(len);
+            }
+            tag => return Err(DecodeError::UnknownTuple { tag }),
+        }
+    }
+    Ok(tuple)
+}
+
+/// Converts the text representation of a value, keeping it as a string unless its type has a
+/// direct equivalent.
+fn parse_value(type_oid: u32, text: &str) -> Value {
+    let value = match type_oid {
+        BOOL_OID => match text {
+            "t" => Some(Value::Boolean(true)),
+            "f" => Some(Value::Boolean(false)),
+            _ => None,
+        },
+        INT2_OID | INT4_OID | INT8_OID | OID_OID => text.parse::<i64>().ok().map(Value::Integer),
+        FLOAT4_OID | FLOAT8_OID => text
+            .parse::<f64>()
+            .ok()
+            .and_then(|value| NotNan::new(value).ok())
+            .map(Value::Float),
+        _ => None,
+    };
+    value.unwrap_or_else(|| Value::from(text))
+}
+
+fn expect_tag(buf: &mut &[u8], expected: u8) -> Result<(), DecodeError> {
+    match get_u8(buf)? {
+        tag if tag == expected => Ok(()),
+        tag => Err(DecodeError::UnknownTuple { tag }),
+    }
+}
+
+fn get_u8(buf: &mut &[u8]) -> Result<u8, DecodeError> {
+    (buf.remaining() >= 1)
+        .then(|| buf.get_u8())
+        .ok_or(DecodeError::UnexpectedEof)
+}
+
+fn get_u16(buf: &mut &[u8]) -> Result<u16, DecodeError> {
+    (buf.remaining() >= 2)
+        .then(|| buf.get_u16())
+        .ok_or(DecodeError::UnexpectedEof)
+}
+
+fn get_u32(buf: &mut &[u8]) -> Result<u32, DecodeError> {
+    (buf.remaining() >= 4)
+        .then(|| buf.get_u32())
+        .ok_or(DecodeError::UnexpectedEof)
+}
+
+fn get_u64(buf: &mut &[u8]) -> Result<u64, DecodeError> {
+    (buf.remaining() >= 8)
+        .then(|| buf.get_u64())
+        .ok_or(DecodeError::UnexpectedEof)
+}
+
+fn get_string(buf: &mut &[u8]) -> Result<String, DecodeError> {
+    let len = buf
+        .iter()
+        .position(|&byte| byte == 0)
+        .ok_or(DecodeError::UnexpectedEof)?;
+    let string = std::str::from_utf8(&buf[..len])
+        .context(InvalidUtf8Snafu)?
+        .to_owned();
+    buf.advance(len + 1);
+    Ok(string)
+}
+
+/// Timestamps are microseconds since the PostgreSQL epoch, 2000-01-01.
+fn get_timestamp(buf: &mut &[u8]) -> Result<DateTime<Utc>, DecodeError> {
+    let micros = get_u64(buf)? as i64;
+    Ok(Utc.ymd(2000, 1, 1).and_hms(0, 0, 0) + Duration::microseconds(micros))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+
+    use super::*;
+
+    fn relation_message() -> Vec<u8> {
+        let mut data = vec![b'R'];
+        data.put_u32(16386);
+        data.put_slice(b"public\0users\0");
+        data.put_u8(b'd');
+        data.put_u16(3);
+        for (name, type_oid) in [("id", INT4_OID), ("name", 25), ("active", BOOL_OID)] {
+            data.put_u8(1);
+            data.put_slice(name.as_bytes());
+            data.put_u8(0);
+            data.put_u32(type_oid);
+            data.put_u32(u32::MAX);
+        }
+        data
+    }
+
+    fn put_text(data: &mut Vec<u8>, text: &str) {
+        data.put_u8(b't');
+        data.put_u32(text.len() as u32);
+        data.put_slice(text.as_bytes());
+    }
+
+    #[test]
+    fn decodes_transaction() {
+        let mut decoder = PgoutputDecoder::default();
+
+        let mut begin = vec![b'B'];
+        begin.put_u64(0x16_B374_D900);
+        begin.put_u64(1_000_000);
+        begin.put_u32(742);
+        assert_eq!(
+            decoder.decode(&begin).unwrap(),
+            Some(Message::Begin {
+                timestamp: Some(Utc.ymd(2000, 1, 1).and_hms(0, 0, 1))
+            })
+        );
+
+        assert_eq!(decoder.decode(&relation_message()).unwrap(), None);
+
+        let mut update = vec![b'U'];
+        update.put_u32(16386);
+        update.put_u8(b'K');
+        update.put_u16(3);
+        put_text(&mut update, "1");
+        update.put_u8(b'n');
+        update.put_u8(b'n');
+        update.put_u8(b'N');
+        update.put_u16(3);
+        put_text(&mut update, "1");
+        put_text(&mut update, "alice");
+        update.put_u8(b'u');
+
+        assert_eq!(
+            decoder.decode(&update).unwrap(),
+            Some(Message::Changes(vec![Change {
+                op: Operation::Update,
+                schema: "public".into(),
+                table: "users".into(),
+                before: Some(BTreeMap::from([
+                    ("id".into(), Value::from(1)),
+                    ("name".into(), Value::Null),
+                    ("active".into(), Value::Null),
+                ])),
+                after: Some(BTreeMap::from([
+                    ("id".into(), Value::from(1)),
+                    ("name".into(), Value::from("alice")),
+                ])),
+            }]))
+        );
+
+        let mut commit = vec![b'C', 0];
+        commit.put_u64(0x16_B374_D8F0);
+        commit.put_u64(0x16_B374_D900);
+        commit.put_u64(1_000_000);
+        assert_eq!(decoder.decode(&commit).unwrap(), Some(Message::Commit));
+    }
+
+    #[test]
+    fn decodes_insert_delete_and_truncate() {
+        let mut decoder = PgoutputDecoder::default();
+        decoder.decode(&relation_message()).unwrap();
+
+        let mut insert = vec![b'I'];
+        insert.put_u32(16386);
+        insert.put_u8(b'N');
+        insert.put_u16(3);
+        put_text(&mut insert, "2");
+        put_text(&mut insert, "bob");
+        put_text(&mut insert, "t");
+        match decoder.decode(&insert).unwrap() {
+            Some(Message::Changes(changes)) => {
+                assert_eq!(changes[0].op, Operation::Insert);
+                assert_eq!(changes[0].before, None);
+                let after = changes[0].after.as_ref().unwrap();
+                assert_eq!(after["id"], Value::from(2));
+                assert_eq!(after["active"], Value::Boolean(true));
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+
+        let mut delete = vec![b'D'];
+        delete.put_u32(16386);
+        delete.put_u8(b'K');
+        delete.put_u16(1);
+        put_text(&mut delete, "2");
+        match decoder.decode(&delete).unwrap() {
+            Some(Message::Changes(changes)) => {
+                assert_eq!(changes[0].op, Operation::Delete);
+                assert_eq!(
+                    changes[0].before,
+                    Some(BTreeMap::from([("id".into(), Value::from(2))]))
+                );
+                assert_eq!(changes[0].after, None);
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+
+        let mut truncate = vec![b'T'];
+        truncate.put_u32(1);
+        truncate.put_u8(0);
+        truncate.put_u32(16386);
+        match decoder.decode(&truncate).unwrap() {
+            Some(Message::Changes(changes)) => {
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].op, Operation::Truncate);
+                assert_eq!(changes[0].table, "users");
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_relation_and_truncated_messages() {
+        let mut decoder = PgoutputDecoder::default();
+
+        let mut insert = vec![b'I'];
+        insert.put_u32(16386);
+        assert!(matches!(
+            decoder.decode(&insert),
+            Err(DecodeError::UnknownRelation { id: 16386 })
+        ));
+
+        decoder.decode(&relation_message()).unwrap();
+        insert.put_u8(b'N');
+        insert.put_u16(3);
+        insert.put_u8(b't');
+        insert.put_u32(10);
+        insert.put_slice(b"1");
+        assert!(matches!(
+            decoder.decode(&insert),
+            Err(DecodeError::UnexpectedEof)
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+//! A decoder of the [`wal2json`](https://github.com/eulerto/wal2json) output, format version 2.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use super::{Change, DecodeError, Decoder, InvalidJsonSnafu, Message, Operation};
+use crate::event::Value;
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    action: char,
+    timestamp: Option<String>,
+    #[serde(default)]
+    schema: String,
+    #[serde(default)]
+    table: String,
+    columns: Option<Vec<Column>>,
+    identity: Option<Vec<Column>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Column {
+    name: String,
+    value: serde_json::Value,
+}
+
+/// Decodes `wal2json` rows, which are self-contained.
+#[derive(Debug)]
+pub(super) struct Wal2jsonDecoder;
+
+impl Decoder for Wal2jsonDecoder {
+    fn decode(&mut self, data: &[u8]) -> Result<Option<Message>, DecodeError> {
+        let row = serde_json::from_slice::<Row>(data).context(InvalidJsonSnafu)?;
+
+        let op = match row.action {
+            'B' => {
+                let timestamp = row.timestamp.as_deref().map(parse_timestamp).transpose()?;
+                return Ok(Some(Message::Begin { timestamp }));
+            }
+            'C' => return Ok(Some(Message::Commit)),
+            'I' => Operation::Insert,
+            'U' => Operation::Update,
+            'D' => Operation::Delete,
+            'T' => Operation::Truncate,
+            // Logical decoding messages are not row changes.
+            'M' => return Ok(None),
+            action => return Err(DecodeError::UnknownMessage { tag: action as u8 }),
+        };
+
+        Ok(Some(Message::Changes(vec![Change {
+            op,
+            schema: row.schema,
+            table: row.table,
+            before: row.identity.map(into_tuple),
+            after: row.columns.map(into_tuple),
+        }])))
+    }
+}
+
+fn into_tuple(columns: Vec<Column>) -> BTreeMap<String, Value> {
+    columns
+        .into_iter()
+        .map(|column| (column.name, Value::from(column.value)))
+        .collect()
+}
+
+/// Parses timestamps formatted like `2019-12-29 04:58:34.806671+00`.
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, DecodeError> {
+    DateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f%#z")
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| DecodeError::InvalidTimestamp {
+            timestamp: timestamp.to_owned(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn decode(data: &str) -> Option<Message> {
+        Wal2jsonDecoder.decode(data.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn decodes_transaction() {
+        assert_eq!(
+            decode(r#"{"action":"B","timestamp":"2019-12-29 04:58:34.806671+00"}"#),
+            Some(Message::Begin {
+                timestamp: Some(Utc.ymd(2019, 12, 29).and_hms_micro(4, 58, 34, 806671))
+            })
+        );
+        assert_eq!(
+            decode(
+                r#"{"action":"U","schema":"public","table":"users","columns":[{"name":"id","type":"integer","value":1},{"name":"name","type":"text","value":"alice"}],"identity":[{"name":"id","type":"integer","value":1}]}"#
+            ),
+            Some(Message::Changes(vec![Change {
+                op: Operation::Update,
+                schema: "public".into(),
+                table: "users".into(),
+                before: Some(BTreeMap::from([("id".into(), Value::from(1))])),
+                after: Some(BTreeMap::from([
+                    ("id".into(), Value::from(1)),
+                    ("name".into(), Value::from("alice")),
+                ])),
+            }]))
+        );
+        assert_eq!(
+            decode(r#"{"action":"C","timestamp":"2019-12-29 04:58:34.806671+00"}"#),
+            Some(Message::Commit)
+        );
+    }
+
+    #[test]
+    fn decodes_delete_and_truncate() {
+        match decode(
+            r#"{"action":"D","schema":"public","table":"users","identity":[{"name":"id","type":"integer","value":2}]}"#,
+        ) {
+            Some(Message::Changes(changes)) => {
+                assert_eq!(changes[0].op, Operation::Delete);
+                assert_eq!(
+                    changes[0].before,
+                    Some(BTreeMap::from([("id".into(), Value::from(2))]))
+                );
+                assert_eq!(changes[0].after, None);
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+
+        match decode(r#"{"action":"T","schema":"public","table":"users"}"#) {
+            Some(Message::Changes(changes)) => assert_eq!(changes[0].op, Operation::Truncate),
+            message => panic!("unexpected message: {:?}", message),
+        }
+
+        assert_eq!(
+            decode(r#"{"action":"M","transactional":false,"prefix":"vector","content":"hi"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rows() {
+        assert!(matches!(
+            Wal2jsonDecoder.decode(b"not json"),
+            Err(DecodeError::InvalidJson { .. })
+        ));
+        assert!(matches!(
+            Wal2jsonDecoder.decode(br#"{"action":"X"}"#),
+            Err(DecodeError::UnknownMessage { tag: b'X' })
+        ));
+    }
+}
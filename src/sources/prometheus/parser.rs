@@ -1,11 +1,17 @@
-use std::{cmp::Ordering, collections::BTreeMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+};
 
 use chrono::{DateTime, TimeZone, Utc};
-use prometheus_parser::{proto, GroupKind, MetricGroup, ParserError};
-
-use crate::event::{
-    metric::{Bucket, Metric, MetricKind, MetricValue, Quantile},
-    Event,
+use prometheus_parser::{proto, GroupKind, MetricGroup, ParserError, METRIC_NAME_LABEL};
+
+use crate::{
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricValue, Quantile},
+        Event,
+    },
+    sinks::prometheus::metadata::{Exemplar, PrometheusMetadata},
 };
 
 fn has_values_or_none(tags: BTreeMap<String, String>) -> Option<BTreeMap<String, String>> {
@@ -29,8 +35,121 @@ pub(super) fn parse_text(packet: &str) -> Result<Vec<Event>, ParserError> {
     prometheus_parser::parse_text(packet).map(reparse_groups)
 }
 
+pub(super) fn parse_protobuf(body: &[u8]) -> Result<Vec<Event>, ParserError> {
+    prometheus_parser::parse_protobuf(body).map(reparse_groups)
+}
+
+/// Parses a remote_write request, keeping the help, unit, and exemplars of its metrics in the
+/// metadata of their events.
 pub(super) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>, ParserError> {
-    prometheus_parser::parse_request(request).map(reparse_groups)
+    let mut metadata = RequestMetadata::new(&request);
+    let mut events = prometheus_parser::parse_request(request).map(reparse_groups)?;
+    if !metadata.is_empty() {
+        for event in &mut events {
+            metadata.insert_into(event.as_mut_metric());
+        }
+    }
+    Ok(events)
+}
+
+type SeriesKey = (String, BTreeMap<String, String>);
+
+/// The Prometheus metadata of the metrics of a remote_write request.
+struct RequestMetadata {
+    /// The help and unit of each metric family.
+    families: HashMap<String, (Option<String>, Option<String>)>,
+
+    /// The exemplars of each metric, by its name and tags.
+    exemplars: HashMap<SeriesKey, Vec<Exemplar>>,
+}
+
+impl RequestMetadata {
+    fn new(request: &proto::WriteRequest) -> Self {
+        let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_owned());
+        let families = request
+            .metadata
+            .iter()
+            .filter(|metadata| !metadata.help.is_empty() || !metadata.unit.is_empty())
+            .map(|metadata| {
+                (
+                    metadata.metric_family_name.clone(),
+                    (non_empty(&metadata.help), non_empty(&metadata.unit)),
+                )
+            })
+            .collect();
+
+        let mut exemplars = HashMap::<_, Vec<_>>::new();
+        for series in request
+            .timeseries
+            .iter()
+            .filter(|series| !series.exemplars.is_empty())
+        {
+            if let Some(key) = series_key(&series.labels) {
+                exemplars
+                    .entry(key)
+                    .or_default()
+                    .extend(series.exemplars.iter().map(|exemplar| {
+                        Exemplar {
+                            labels: exemplar
+                                .labels
+                                .iter()
+                                .map(|label| (label.name.clone(), label.value.clone()))
+                                .collect(),
+                            value: exemplar.value,
+                            timestamp: (exemplar.timestamp != 0)
+                                .then(|| Utc.timestamp_millis_opt(exemplar.timestamp).latest())
+                                .flatten(),
+                        }
+                    }));
+            }
+        }
+
+        Self {
+            families,
+            exemplars,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.families.is_empty() && self.exemplars.is_empty()
+    }
+
+    fn insert_into(&mut self, metric: &mut Metric) {
+        let (help, unit) = self
+            .families
+            .get(metric.name())
+            .cloned()
+            .unwrap_or_default();
+        let key = (
+            metric.name().to_owned(),
+            metric.tags().cloned().unwrap_or_default(),
+        );
+        let metadata = PrometheusMetadata {
+            help,
+            unit,
+            exemplars: self.exemplars.remove(&key).unwrap_or_default(),
+        };
+        if !metadata.is_empty() {
+            metadata.insert_into(metric);
+        }
+    }
+}
+
+/// Gets the name and tags of the metric of a series, whose histogram buckets are merged into a
+/// single metric.
+fn series_key(labels: &[proto::Label]) -> Option<SeriesKey> {
+    let mut labels = labels
+        .iter()
+        .map(|label| (label.name.clone(), label.value.clone()))
+        .collect::<BTreeMap<_, _>>();
+    let mut name = labels.remove(METRIC_NAME_LABEL)?;
+    if labels.contains_key("le") {
+        if let Some(basename) = name.strip_suffix("_bucket") {
+            name = basename.to_owned();
+            labels.remove("le");
+        }
+    }
+    Some((name, labels))
 }
 
 fn reparse_groups(groups: Vec<MetricGroup>) -> Vec<Event> {
@@ -1056,4 +1175,129 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_native_histogram() {
+        use prometheus_parser::proto::client;
+        use prost::Message;
+
+        let family = client::MetricFamily {
+            name: Some("duration".into()),
+            help: None,
+            r#type: Some(client::MetricType::Histogram as i32),
+            metric: vec![client::Metric {
+                histogram: Some(client::Histogram {
+                    sample_count: Some(4),
+                    sample_sum: Some(3.5),
+                    schema: Some(0),
+                    zero_threshold: Some(0.001),
+                    zero_count: Some(1),
+                    positive_span: vec![client::BucketSpan {
+                        offset: Some(0),
+                        length: Some(2),
+                    }],
+                    positive_delta: vec![1, 0],
+                    ..Default::default()
+                }),
+                timestamp_ms: Some(1612411506789),
+                ..Default::default()
+            }],
+        };
+        let mut body = Vec::new();
+        family.encode_length_delimited(&mut body).unwrap();
+
+        assert_event_data_eq!(
+            parse_protobuf(&body).map(|events| events
+                .into_iter()
+                .map(Event::into_metric)
+                .collect::<Vec<_>>()),
+            Ok(vec![Metric::new(
+                "duration",
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![0.001 => 1, 1.0 => 1, 2.0 => 1],
+                    count: 4,
+                    sum: 3.5,
+                },
+            )
+            .with_timestamp(Some(*TIMESTAMP))]),
+        );
+    }
+
+    #[test]
+    fn parse_request_keeps_prometheus_metadata() {
+        let label = |name: &str, value: &str| proto::Label {
+            name: name.into(),
+            value: value.into(),
+        };
+        let series = |labels, value, exemplars| proto::TimeSeries {
+            labels,
+            samples: vec![proto::Sample {
+                value,
+                timestamp: 1612411506789,
+            }],
+            exemplars,
+        };
+        let exemplar = proto::Exemplar {
+            labels: vec![label("trace_id", "abc")],
+            value: 0.75,
+            timestamp: 1612411506000,
+        };
+        let request = proto::WriteRequest {
+            timeseries: vec![
+                series(
+                    vec![
+                        label("__name__", "latency_bucket"),
+                        label("code", "200"),
+                        label("le", "1"),
+                    ],
+                    2.0,
+                    vec![exemplar],
+                ),
+                series(
+                    vec![
+                        label("__name__", "latency_bucket"),
+                        label("code", "200"),
+                        label("le", "+Inf"),
+                    ],
+                    2.0,
+                    vec![],
+                ),
+                series(vec![label("__name__", "up")], 1.0, vec![]),
+            ],
+            metadata: vec![proto::MetricMetadata {
+                r#type: proto::MetricType::Histogram as i32,
+                metric_family_name: "latency".into(),
+                help: "The latency.".into(),
+                unit: "seconds".into(),
+            }],
+        };
+
+        let metrics = parse_request(request)
+            .unwrap()
+            .into_iter()
+            .map(|event| {
+                let metric = event.into_metric();
+                (
+                    metric.name().to_owned(),
+                    PrometheusMetadata::from_metric(&metric),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            metrics,
+            btreemap! {
+                "latency" => Some(PrometheusMetadata {
+                    help: Some("The latency.".into()),
+                    unit: Some("seconds".into()),
+                    exemplars: vec![Exemplar {
+                        labels: btreemap! { "trace_id" => "abc" },
+                        value: 0.75,
+                        timestamp: Some(Utc.timestamp(1612411506, 0)),
+                    }],
+                }),
+                "up" => None,
+            }
+        );
+    }
 }
@@ -1,10 +1,10 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{collections::HashMap, convert::TryFrom, net::SocketAddr};
 
 use bytes::Bytes;
 use prometheus_parser::proto;
 use prost::Message;
 use vector_config::configurable_component;
-use warp::http::{HeaderMap, StatusCode};
+use warp::http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
 
 use super::parser;
 use crate::{
@@ -103,12 +103,47 @@ impl SourceConfig for PrometheusRemoteWriteConfig {
     }
 }
 
+/// The versions of the Remote Write protocol, whose requests are different messages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ProtocolVersion {
+    V1,
+    V2,
+}
+
+impl ProtocolVersion {
+    /// Gets the version of a request from the `proto` parameter of its content type, which is
+    /// only set by senders of newer versions.
+    fn from_headers(header_map: &HeaderMap) -> Result<Self, ErrorMessage> {
+        let proto = header_map
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(|content_type| {
+                content_type
+                    .split(';')
+                    .filter_map(|parameter| parameter.trim().strip_prefix("proto="))
+                    .next()
+            });
+        match proto {
+            None | Some("prometheus.WriteRequest") => Ok(Self::V1),
+            Some("io.prometheus.write.v2.Request") => Ok(Self::V2),
+            Some(proto) => Err(ErrorMessage::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported write request message: {}", proto),
+            )),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct RemoteWriteSource;
 
 impl RemoteWriteSource {
-    fn decode_body(&self, body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
-        let request = proto::WriteRequest::decode(body).map_err(|error| {
+    fn decode_body(
+        &self,
+        body: Bytes,
+        version: ProtocolVersion,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let decode_error = |error: prost::DecodeError| {
             emit!(PrometheusRemoteWriteParseError {
                 error: error.clone()
             });
@@ -116,7 +151,19 @@ impl RemoteWriteSource {
                 StatusCode::BAD_REQUEST,
                 format!("Could not decode write request: {}", error),
             )
-        })?;
+        };
+        let request = match version {
+            ProtocolVersion::V1 => proto::WriteRequest::decode(body).map_err(decode_error)?,
+            ProtocolVersion::V2 => {
+                let request = proto::v2::Request::decode(body).map_err(decode_error)?;
+                proto::WriteRequest::try_from(request).map_err(|error| {
+                    ErrorMessage::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("Could not decode write request: {}", error),
+                    )
+                })?
+            }
+        };
         parser::parse_request(request).map_err(|error| {
             ErrorMessage::new(
                 StatusCode::BAD_REQUEST,
@@ -143,14 +190,15 @@ impl HttpSource for RemoteWriteSource {
         {
             body = decode(&Some("snappy".to_string()), body)?;
         }
-        let events = self.decode_body(body)?;
+        let version = ProtocolVersion::from_headers(&header_map)?;
+        let events = self.decode_body(body, version)?;
         Ok(events)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use chrono::{SubsecRound as _, Utc};
+    use chrono::{SubsecRound as _, TimeZone, Utc};
     use vector_core::event::{EventStatus, Metric, MetricKind, MetricValue};
 
     use super::*;
@@ -230,6 +278,71 @@ mod test {
         .await;
     }
 
+    #[test]
+    fn detects_protocol_version() {
+        let version = |content_type: Option<&str>| {
+            let mut header_map = HeaderMap::new();
+            if let Some(content_type) = content_type {
+                header_map.insert(CONTENT_TYPE, content_type.parse().unwrap());
+            }
+            ProtocolVersion::from_headers(&header_map).map_err(|error| error.code())
+        };
+
+        assert_eq!(version(None), Ok(ProtocolVersion::V1));
+        assert_eq!(
+            version(Some("application/x-protobuf")),
+            Ok(ProtocolVersion::V1)
+        );
+        assert_eq!(
+            version(Some("application/x-protobuf;proto=prometheus.WriteRequest")),
+            Ok(ProtocolVersion::V1)
+        );
+        assert_eq!(
+            version(Some(
+                "application/x-protobuf; proto=io.prometheus.write.v2.Request"
+            )),
+            Ok(ProtocolVersion::V2)
+        );
+        assert_eq!(
+            version(Some(
+                "application/x-protobuf;proto=io.prometheus.write.v3.Request"
+            )),
+            Err(415)
+        );
+    }
+
+    #[test]
+    fn decodes_v2_requests() {
+        let symbols = ["", "__name__", "up", "job", "node"];
+        let request = proto::v2::Request {
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            timeseries: vec![proto::v2::TimeSeries {
+                labels_refs: vec![1, 2, 3, 4],
+                samples: vec![proto::v2::Sample {
+                    value: 1.0,
+                    timestamp: 1612411506789,
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let events = RemoteWriteSource
+            .decode_body(request.encode_to_vec().into(), ProtocolVersion::V2)
+            .unwrap();
+        vector_common::assert_event_data_eq!(
+            events,
+            vec![Event::from(
+                Metric::new(
+                    "up",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 1.0 }
+                )
+                .with_tags(Some([("job".to_owned(), "node".to_owned())].into()))
+                .with_timestamp(Some(Utc.timestamp_millis(1612411506789)))
+            )]
+        );
+    }
+
     fn make_events() -> Vec<Event> {
         let timestamp = || Utc::now().trunc_subsecs(3);
         vec![
@@ -36,6 +36,13 @@ static NOT_FOUND_NO_PATH: &str = "No path is set on the endpoint and we got a 40
                                   did you mean to use /metrics?\
                                   This behavior changed in version 0.11.";
 
+/// The content type of the delimited protobuf exposition format.
+static PROTOBUF_CONTENT_TYPE: &str = "application/vnd.google.protobuf";
+
+/// Prefers the protobuf exposition format, falling back to the text format for targets which
+/// don't support it.
+static PROTOBUF_ACCEPT: &str = "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited,text/plain;version=0.0.4;q=0.5";
+
 #[derive(Debug, Snafu)]
 enum ConfigError {
     #[snafu(display("Cannot set both `endpoints` and `hosts`"))]
@@ -84,6 +91,14 @@ pub struct PrometheusScrapeConfig {
     /// scraping the `/federate` endpoint.
     query: Option<HashMap<String, Vec<String>>>,
 
+    /// Whether to request the protobuf exposition format, in order to scrape native histograms.
+    ///
+    /// Native histograms are only exposed in the protobuf format. Their exponential buckets are converted into
+    /// histogram buckets bounded by the boundaries of their schema. Targets not supporting the protobuf format are
+    /// scraped in the text format.
+    #[serde(default = "crate::serde::default_false")]
+    native_histograms: bool,
+
     #[configurable(derived)]
     tls: Option<TlsConfig>,
 
@@ -112,6 +127,7 @@ impl GenerateConfig for PrometheusScrapeConfig {
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
             query: None,
+            native_histograms: false,
             tls: None,
             auth: None,
         })
@@ -195,6 +211,8 @@ struct PrometheusCompatConfig {
     query: Option<HashMap<String, Vec<String>>>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
+    #[serde(default = "crate::serde::default_false")]
+    native_histograms: bool,
     tls: Option<TlsConfig>,
     auth: Option<Auth>,
 }
@@ -212,6 +230,7 @@ impl SourceConfig for PrometheusCompatConfig {
             honor_labels: self.honor_labels,
             query: self.query.clone(),
             scrape_interval_secs: self.scrape_interval_secs,
+            native_histograms: self.native_histograms,
             tls: self.tls.clone(),
             auth: self.auth.clone(),
         };
@@ -269,8 +288,13 @@ async fn prometheus(
         let client = HttpClient::new(tls.clone(), &proxy).expect("Building HTTP client failed");
         let endpoint = url.to_string();
 
+        let accept = if config.native_histograms {
+            PROTOBUF_ACCEPT
+        } else {
+            "text/plain"
+        };
         let mut request = Request::get(&url)
-            .header(http::header::ACCEPT, "text/plain")
+            .header(http::header::ACCEPT, accept)
             .body(Body::empty())
             .expect("error creating request");
         if let Some(auth) = &config.auth {
@@ -325,9 +349,13 @@ async fn prometheus(
                             end: Instant::now()
                         });
 
-                        let body = String::from_utf8_lossy(&body);
+                        let parsed = if is_protobuf(&header.headers) {
+                            parser::parse_protobuf(&body)
+                        } else {
+                            parser::parse_text(&String::from_utf8_lossy(&body))
+                        };
 
-                        match parser::parse_text(&body) {
+                        match parsed {
                             Ok(events) => {
                                 emit!(PrometheusEventsReceived {
                                     byte_size: events.size_of(),
@@ -390,7 +418,7 @@ async fn prometheus(
                                 emit!(PrometheusParseError {
                                     error,
                                     url: url.clone(),
-                                    body,
+                                    body: String::from_utf8_lossy(&body),
                                 });
                                 None
                             }
@@ -437,6 +465,13 @@ async fn prometheus(
     }
 }
 
+fn is_protobuf(headers: &http::HeaderMap) -> bool {
+    headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with(PROTOBUF_CONTENT_TYPE))
+}
+
 #[cfg(all(test, feature = "sinks-prometheus"))]
 mod test {
     use hyper::{
@@ -484,6 +519,71 @@ mod test {
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
             query: None,
+            native_histograms: false,
+            auth: None,
+            tls: None,
+        };
+
+        let events = run_and_assert_source_compliance(
+            config,
+            Duration::from_secs(1),
+            &HTTP_PULL_SOURCE_TAGS,
+        )
+        .await;
+        assert!(!events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_native_histograms() {
+        use prometheus_parser::proto::client;
+        use prost::Message;
+
+        use crate::event::MetricValue;
+
+        let in_addr = next_addr();
+
+        let family = client::MetricFamily {
+            name: Some("duration".into()),
+            help: None,
+            r#type: Some(client::MetricType::Histogram as i32),
+            metric: vec![client::Metric {
+                histogram: Some(client::Histogram {
+                    sample_count: Some(3),
+                    sample_sum: Some(3.5),
+                    schema: Some(0),
+                    positive_span: vec![client::BucketSpan {
+                        offset: Some(0),
+                        length: Some(2),
+                    }],
+                    positive_delta: vec![1, 1],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        };
+        let mut body = Vec::new();
+        family.encode_length_delimited(&mut body).unwrap();
+
+        let dummy_endpoint = warp::path!("metrics")
+            .and(warp::header::exact("Accept", PROTOBUF_ACCEPT))
+            .map(move || {
+                warp::reply::with_header(
+                    body.clone(),
+                    "Content-Type",
+                    "application/vnd.google.protobuf; proto=io.prometheus.client.MetricFamily; encoding=delimited",
+                )
+            });
+
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+
+        let config = PrometheusScrapeConfig {
+            endpoints: vec![format!("http://{}/metrics", in_addr)],
+            scrape_interval_secs: 1,
+            instance_tag: None,
+            endpoint_tag: None,
+            honor_labels: false,
+            query: None,
+            native_histograms: true,
             auth: None,
             tls: None,
         };
@@ -495,6 +595,17 @@ mod test {
         )
         .await;
         assert!(!events.is_empty());
+
+        let metric = events[0].as_metric();
+        assert_eq!(metric.name(), "duration");
+        assert_eq!(
+            metric.value(),
+            &MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 1, 2.0 => 2],
+                count: 3,
+                sum: 3.5,
+            }
+        );
     }
 
     #[tokio::test]
@@ -516,6 +627,7 @@ mod test {
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
             query: None,
+            native_histograms: false,
             auth: None,
             tls: None,
         };
@@ -566,6 +678,7 @@ mod test {
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
             query: None,
+            native_histograms: false,
             auth: None,
             tls: None,
         };
@@ -635,6 +748,7 @@ mod test {
                     vec!["val1".to_string(), "val2".to_string()],
                 ),
             ])),
+            native_histograms: false,
             auth: None,
             tls: None,
         };
@@ -735,6 +849,7 @@ mod test {
                 query: None,
                 scrape_interval_secs: 1,
                 tls: None,
+                native_histograms: false,
                 auth: None,
             },
         );
@@ -823,6 +938,7 @@ mod integration_tests {
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
             query: None,
+            native_histograms: false,
             auth: None,
             tls: None,
         };
@@ -0,0 +1,486 @@
+//! This mod implements the `rabbitmq_stream` source.
+//! It consumes a RabbitMQ stream queue with the stream protocol, and persists the offset of the
+//! last delivered message in a checkpoint, which it resumes from after a restart.
+
+use std::{path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::{Stream, StreamExt};
+use rabbitmq_stream_client::{types::OffsetSpecification, Consumer, Environment};
+use tokio_util::codec::FramedRead;
+use vector_common::finalizer::OrderedFinalizer;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        BytesReceived, EventsReceived, RabbitMqStreamCheckpointError,
+        RabbitMqStreamConnectionError, RabbitMqStreamNegativeAcknowledgementError,
+        StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sources::util::{CheckpointStore, CheckpointStoreConfig},
+    SourceSender,
+};
+
+/// The name of the checkpoint the offset is persisted to, a file in the data directory by default.
+const CHECKPOINT_FILENAME: &str = "checkpoint.txt";
+
+/// How long to wait before connecting again after an error.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Configuration for the `rabbitmq_stream` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct RabbitMqStreamConfig {
+    /// The host of the RabbitMQ node to connect to.
+    #[serde(default = "default_host")]
+    #[derivative(Default(value = "default_host()"))]
+    host: String,
+
+    /// The port of the stream protocol on the RabbitMQ node.
+    #[serde(default = "default_port")]
+    #[derivative(Default(value = "default_port()"))]
+    port: u16,
+
+    /// The username to authenticate with.
+    #[serde(default = "default_credential")]
+    #[derivative(Default(value = "default_credential()"))]
+    user: String,
+
+    /// The password to authenticate with.
+    #[serde(default = "default_credential")]
+    #[derivative(Default(value = "default_credential()"))]
+    password: String,
+
+    /// The virtual host the stream belongs to.
+    #[serde(default = "default_virtual_host")]
+    #[derivative(Default(value = "default_virtual_host()"))]
+    virtual_host: String,
+
+    /// The name of the stream to consume.
+    stream: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    offset: OffsetConfig,
+
+    /// The maximum number of messages to send downstream at once.
+    ///
+    /// Messages already received from the node are batched together, up to this number.
+    #[serde(default = "default_batch_size")]
+    #[derivative(Default(value = "default_batch_size()"))]
+    batch_size: usize,
+
+    /// Overrides the name of the log field used to add the offset to each event.
+    ///
+    /// The value will be the offset of the message in the stream.
+    ///
+    /// By default, `"offset"` is used.
+    #[serde(default = "default_offset_key")]
+    #[derivative(Default(value = "default_offset_key()"))]
+    offset_key: String,
+
+    /// The directory used to persist the offset of the last delivered message.
+    ///
+    /// By default, the global `data_dir` option is used. Please make sure the user Vector is running as has write permissions to this directory.
+    #[serde(default)]
+    data_dir: Option<PathBuf>,
+
+    /// Where to persist the offset of the last delivered message.
+    ///
+    /// By default, it's persisted in a file of `data_dir`.
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// Where to start consuming the stream.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OffsetConfig {
+    /// Resume after the last delivered message, as persisted in the checkpoint.
+    ///
+    /// Without a checkpoint, such as on the first start, the stream is consumed from the first
+    /// message available.
+    #[derivative(Default)]
+    Stored,
+
+    /// Start from the first message available in the stream.
+    First,
+
+    /// Start from the last chunk of messages written to the stream.
+    Last,
+
+    /// Start from the next message written to the stream.
+    Next,
+
+    /// Start from the first chunk of messages written at or after a point in time.
+    Timestamp {
+        /// The point in time, as a Unix timestamp in seconds.
+        timestamp: i64,
+    },
+}
+
+impl OffsetConfig {
+    /// Returns where to start consuming, given the offset of the last delivered message.
+    fn specification(self, checkpoint: Option<u64>) -> OffsetSpecification {
+        match self {
+            Self::Stored => match checkpoint {
+                Some(offset) => OffsetSpecification::Offset(offset + 1),
+                None => OffsetSpecification::First,
+            },
+            Self::First => OffsetSpecification::First,
+            Self::Last => OffsetSpecification::Last,
+            Self::Next => OffsetSpecification::Next,
+            Self::Timestamp { timestamp } => OffsetSpecification::Timestamp(timestamp * 1000),
+        }
+    }
+}
+
+fn default_host() -> String {
+    "localhost".into()
+}
+
+const fn default_port() -> u16 {
+    5552
+}
+
+fn default_credential() -> String {
+    "guest".into()
+}
+
+fn default_virtual_host() -> String {
+    "/".into()
+}
+
+const fn default_batch_size() -> usize {
+    100
+}
+
+fn default_offset_key() -> String {
+    "offset".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<RabbitMqStreamConfig>("rabbitmq_stream")
+}
+
+impl GenerateConfig for RabbitMqStreamConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"host = "localhost"
+            stream = "vector""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "rabbitmq_stream")]
+impl SourceConfig for RabbitMqStreamConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let checkpoint_store = self
+            .checkpoint_store
+            .build(&cx, self.data_dir.as_ref(), CHECKPOINT_FILENAME)
+            .await?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+
+        let source = RabbitMqStream {
+            config: self.clone(),
+            decoder,
+            checkpoint_store,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "rabbitmq_stream"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+struct RabbitMqStream {
+    config: RabbitMqStreamConfig,
+    decoder: Decoder,
+    /// Persists the offset of the last delivered message.
+    checkpoint_store: CheckpointStore,
+    acknowledgements: bool,
+}
+
+impl RabbitMqStream {
+    async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let checkpoint = self
+            .checkpoint_store
+            .load_json_or_default::<Option<u64>>()
+            .await
+            .map_err(|error| {
+                error!(
+                    message = "Failed to load checkpoint.",
+                    location = %self.checkpoint_store.location(),
+                    %error,
+                );
+            })?;
+        let stream = self.config.stream.as_str();
+
+        let (finalizer, ack_stream) =
+            OrderedFinalizer::<u64>::maybe_new(self.acknowledgements, shutdown.clone());
+        tokio::spawn(handle_acks(
+            self.checkpoint_store.clone(),
+            self.config.stream.clone(),
+            ack_stream,
+        ));
+
+        // The offset of the last message read, to resume from once connected again, so that
+        // messages already read are not read again after an error.
+        let mut last_offset = None;
+        loop {
+            let specification = match last_offset {
+                Some(offset) => OffsetSpecification::Offset(offset + 1),
+                None => self.config.offset.specification(checkpoint),
+            };
+            let mut consumer = match self.connect(specification).await {
+                Ok(consumer) => consumer,
+                Err(error) => {
+                    emit!(RabbitMqStreamConnectionError { error, stream });
+                    tokio::select! {
+                        _ = tokio::time::sleep(RETRY_DELAY) => continue,
+                        _ = &mut shutdown => return Ok(()),
+                    }
+                }
+            };
+
+            let result = tokio::select! {
+                result = self.consume(&mut consumer, &mut last_offset, &finalizer, &mut out) => result,
+                _ = &mut shutdown => {
+                    let _ = consumer.handle().close().await;
+                    return Ok(());
+                }
+            };
+            let _ = consumer.handle().close().await;
+            result?;
+
+            tokio::select! {
+                _ = tokio::time::sleep(RETRY_DELAY) => {}
+                _ = &mut shutdown => return Ok(()),
+            }
+        }
+    }
+
+    async fn connect(
+        &self,
+        specification: OffsetSpecification,
+    ) -> Result<Consumer, Box<dyn std::error::Error + Send + Sync>> {
+        let environment = Environment::builder()
+            .host(&self.config.host)
+            .port(self.config.port)
+            .username(&self.config.user)
+            .password(&self.config.password)
+            .virtual_host(&self.config.virtual_host)
+            .build()
+            .await?;
+        let consumer = environment
+            .consumer()
+            .offset(specification)
+            .build(&self.config.stream)
+            .await?;
+        Ok(consumer)
+    }
+
+    /// Consumes the stream in batches until an error occurs, keeping track of the offset of the
+    /// last message read.
+    async fn consume(
+        &self,
+        consumer: &mut Consumer,
+        last_offset: &mut Option<u64>,
+        finalizer: &Option<OrderedFinalizer<u64>>,
+        out: &mut SourceSender,
+    ) -> Result<(), ()> {
+        let stream = self.config.stream.as_str();
+
+        // Only the messages already received are batched, the consumer is not read further until
+        // the batch is sent, which propagates backpressure to the node.
+        let mut batches = consumer.ready_chunks(self.config.batch_size.max(1));
+        while let Some(batch) = batches.next().await {
+            let mut events = Vec::new();
+            for delivery in batch {
+                let delivery = match delivery {
+                    Ok(delivery) => delivery,
+                    Err(error) => {
+                        emit!(RabbitMqStreamConnectionError { error, stream });
+                        return Ok(());
+                    }
+                };
+                let payload = delivery.message().data().unwrap_or_default();
+                emit!(BytesReceived {
+                    byte_size: payload.len(),
+                    protocol: "tcp",
+                });
+                self.decode(payload, delivery.offset(), &mut events).await;
+                *last_offset = Some(delivery.offset());
+            }
+            let offset = match *last_offset {
+                Some(offset) => offset,
+                None => continue,
+            };
+
+            let count = events.len();
+            emit!(EventsReceived {
+                count,
+                byte_size: events.size_of(),
+            });
+
+            let receiver = BatchNotifier::maybe_apply_to(finalizer.is_some(), &mut events);
+            out.send_batch(events).await.map_err(|error| {
+                emit!(StreamClosedError { error, count });
+            })?;
+
+            match (finalizer, receiver) {
+                (Some(finalizer), Some(receiver)) => finalizer.add(offset, receiver),
+                // Batches without events are checkpointed along with the next one.
+                (Some(_), None) => {}
+                (None, _) => {
+                    if let Err(error) = self.checkpoint_store.save_json(&offset).await {
+                        emit!(RabbitMqStreamCheckpointError { error, stream });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn decode(&self, payload: &[u8], offset: u64, events: &mut Vec<Event>) {
+        let now = Utc::now();
+        let mut stream = FramedRead::new(payload, self.decoder.clone());
+        while let Some(next) = stream.next().await {
+            match next {
+                Ok((decoded, _byte_size)) => {
+                    for mut event in decoded {
+                        if let Event::Log(ref mut log) = event {
+                            log.try_insert(
+                                log_schema().source_type_key(),
+                                Bytes::from("rabbitmq_stream"),
+                            );
+                            log.try_insert(log_schema().timestamp_key(), now);
+                            log.insert(self.config.offset_key.as_str(), offset);
+                        }
+                        events.push(event);
+                    }
+                }
+                Err(error) => {
+                    // Error is logged by `crate::codecs`, no further
+                    // handling is needed here.
+                    if !error.can_continue() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn handle_acks(
+    checkpoint_store: CheckpointStore,
+    stream: String,
+    mut ack_stream: impl Stream<Item = (BatchStatus, u64)> + Unpin,
+) {
+    while let Some((status, offset)) = ack_stream.next().await {
+        if status != BatchStatus::Delivered {
+            // The stream cannot be consumed again from an earlier offset without also reading the
+            // messages delivered since, so the rejected ones are only reported.
+            emit!(RabbitMqStreamNegativeAcknowledgementError {
+                offset,
+                stream: &stream,
+            });
+        }
+        if let Err(error) = checkpoint_store.save_json(&offset).await {
+            emit!(RabbitMqStreamCheckpointError {
+                error,
+                stream: &stream,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RabbitMqStreamConfig>();
+    }
+
+    #[test]
+    fn parses_offsets() {
+        let config: RabbitMqStreamConfig = toml::from_str(
+            r#"
+            stream = "vector"
+            offset.type = "timestamp"
+            offset.timestamp = 1660000000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.offset,
+            OffsetConfig::Timestamp {
+                timestamp: 1_660_000_000
+            }
+        );
+
+        let config: RabbitMqStreamConfig = toml::from_str(r#"stream = "vector""#).unwrap();
+        assert_eq!(config.offset, OffsetConfig::Stored);
+    }
+
+    #[test]
+    fn stored_offset_resumes_after_checkpoint() {
+        assert!(matches!(
+            OffsetConfig::Stored.specification(Some(41)),
+            OffsetSpecification::Offset(42)
+        ));
+        assert!(matches!(
+            OffsetConfig::Stored.specification(None),
+            OffsetSpecification::First
+        ));
+        assert!(matches!(
+            OffsetConfig::Next.specification(Some(41)),
+            OffsetSpecification::Next
+        ));
+    }
+}
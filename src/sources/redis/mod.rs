@@ -12,15 +12,19 @@ use vector_core::ByteSizeOf;
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{log_schema, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
     event::Event,
     internal_events::{BytesReceived, EventsReceived, StreamClosedError},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
     SourceSender,
 };
 
 mod channel;
 mod list;
+mod stream;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -42,6 +46,11 @@ pub enum DataTypeConfig {
     ///
     /// This is based on Redis' Pub/Sub capabilities.
     Channel,
+
+    /// The `stream` data type.
+    ///
+    /// Entries are read as a member of a consumer group.
+    Stream,
 }
 
 /// Options for the Redis `list` data type.
@@ -67,6 +76,65 @@ pub enum Method {
     Rpop,
 }
 
+/// Options for the Redis `stream` data type.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// The consumer group to read entries as.
+    ///
+    /// The group is created if it doesn't exist, along with the stream.
+    group: String,
+
+    /// The name of this consumer within the group.
+    ///
+    /// Each Vector instance must use a distinct name. By default, the hostname is used.
+    consumer: Option<String>,
+
+    /// The ID of the last entry considered delivered to the group when it is created.
+    ///
+    /// By default, `$` is used, so that only entries added after the group is created are read. Use `0` to read the
+    /// whole stream.
+    #[serde(default = "default_start_id")]
+    start_id: String,
+
+    /// The field of entries holding the message to decode.
+    #[serde(default = "default_field")]
+    field: String,
+
+    /// Sets the name of the log field to use to add the entry ID to each event.
+    ///
+    /// By default, this is not set and the field will not be automatically added.
+    id_key: Option<String>,
+
+    /// The maximum number of entries to read at once.
+    #[serde(default = "default_count")]
+    count: usize,
+
+    /// The time, in seconds, after which pending entries are claimed from other consumers of the group.
+    ///
+    /// Entries read but never acknowledged by a consumer, such as one that crashed, are claimed and delivered again
+    /// once they have been pending for this long. This should be longer than it takes to deliver events.
+    #[serde(default = "default_claim_min_idle_secs")]
+    claim_min_idle_secs: u64,
+}
+
+fn default_start_id() -> String {
+    "$".into()
+}
+
+fn default_field() -> String {
+    "message".into()
+}
+
+const fn default_count() -> usize {
+    100
+}
+
+const fn default_claim_min_idle_secs() -> u64 {
+    300
+}
+
 pub struct ConnectionInfo {
     protocol: &'static str,
     endpoint: String,
@@ -91,13 +159,16 @@ impl From<&redis::ConnectionInfo> for ConnectionInfo {
 #[derive(Clone, Debug, Derivative)]
 #[serde(deny_unknown_fields)]
 pub struct RedisSourceConfig {
-    /// The Redis data type (`list` or `channel`) to use.
+    /// The Redis data type (`list`, `channel` or `stream`) to use.
     #[serde(default)]
     data_type: DataTypeConfig,
 
     #[configurable(derived)]
     list: Option<ListOption>,
 
+    #[configurable(derived)]
+    stream: Option<StreamOption>,
+
     /// The Redis URL to connect to.
     ///
     /// The URL must take the form of `protocol://server:port/db` where the `protocol` can either be `redis` or `rediss` for connections secured via TLS.
@@ -122,6 +193,13 @@ pub struct RedisSourceConfig {
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
+
+    /// Controls how acknowledgements are handled by this source.
+    ///
+    /// Only supported by the `stream` data type, whose entries are acknowledged once their events are delivered.
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
 }
 
 impl GenerateConfig for RedisSourceConfig {
@@ -154,7 +232,7 @@ impl SourceConfig for RedisSourceConfig {
 
         let client = redis::Client::open(self.url.as_str()).context(ClientSnafu {})?;
         let connection_info = client.get_connection_info().into();
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
 
         match self.data_type {
             DataTypeConfig::List => {
@@ -181,6 +259,24 @@ impl SourceConfig for RedisSourceConfig {
                 )
                 .await
             }
+            DataTypeConfig::Stream => {
+                let options = self
+                    .stream
+                    .clone()
+                    .ok_or("`stream` options must be set for the `stream` data type.")?;
+                let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+                stream::consume(
+                    client,
+                    connection_info,
+                    self.key.clone(),
+                    self.redis_key.clone(),
+                    options,
+                    decoder,
+                    acknowledgements,
+                    cx,
+                )
+                .await
+            }
         }
     }
 
@@ -193,7 +289,7 @@ impl SourceConfig for RedisSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        matches!(self.data_type, DataTypeConfig::Stream)
     }
 }
 
@@ -205,24 +301,41 @@ async fn handle_line(
     decoder: Decoder,
     out: &mut SourceSender,
 ) -> Result<(), ()> {
+    let events = decode_message(connection_info, line.as_bytes(), key, redis_key, decoder).await;
+    let count = events.len();
+    if let Err(error) = out.send_batch(events).await {
+        emit!(StreamClosedError { error, count });
+        return Err(());
+    }
+    Ok(())
+}
+
+/// Decodes a message read from Redis into events.
+async fn decode_message(
+    connection_info: &ConnectionInfo,
+    message: &[u8],
+    key: &str,
+    redis_key: Option<&str>,
+    decoder: Decoder,
+) -> Vec<Event> {
     let now = Utc::now();
 
     emit!(BytesReceived {
-        byte_size: line.len(),
+        byte_size: message.len(),
         protocol: connection_info.protocol,
     });
 
-    let mut stream = FramedRead::new(line.as_ref(), decoder.clone());
+    let mut decoded = Vec::new();
+    let mut stream = FramedRead::new(message, decoder);
     while let Some(next) = stream.next().await {
         match next {
             Ok((events, _byte_size)) => {
-                let count = events.len();
                 emit!(EventsReceived {
                     byte_size: events.size_of(),
-                    count,
+                    count: events.len(),
                 });
 
-                let events = events.into_iter().map(|mut event| {
+                decoded.extend(events.into_iter().map(|mut event| {
                     if let Event::Log(ref mut log) = event {
                         log.try_insert(log_schema().source_type_key(), Bytes::from("redis"));
                         log.try_insert(log_schema().timestamp_key(), now);
@@ -231,12 +344,7 @@ async fn handle_line(
                         }
                     }
                     event
-                });
-
-                if let Err(error) = out.send_batch(events).await {
-                    emit!(StreamClosedError { error, count });
-                    return Err(());
-                }
+                }));
             }
             Err(error) => {
                 // Error is logged by `crate::codecs::Decoder`, no further
@@ -247,7 +355,7 @@ async fn handle_line(
             }
         }
     }
-    Ok(())
+    decoded
 }
 
 #[cfg(test)]
@@ -295,9 +403,11 @@ mod integration_test {
             }),
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
+            stream: None,
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
         };
 
         let events = run_and_assert_source_compliance_n(config, 3, &SOURCE_TAGS).await;
@@ -328,9 +438,59 @@ mod integration_test {
             }),
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
+            stream: None,
+            redis_key: None,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            acknowledgements: Default::default(),
+        };
+
+        let events = run_and_assert_source_compliance_n(config, 3, &SOURCE_TAGS).await;
+
+        assert_eq!(events[0].as_log()[log_schema().message_key()], "1".into());
+        assert_eq!(events[1].as_log()[log_schema().message_key()], "2".into());
+        assert_eq!(events[2].as_log()[log_schema().message_key()], "3".into());
+    }
+
+    #[tokio::test]
+    async fn redis_source_stream_consumer_group() {
+        // Add some entries to a stream which we'll read from as a consumer group.
+        let client = redis::Client::open(REDIS_SERVER).unwrap();
+        let mut conn = client.get_tokio_connection_manager().await.unwrap();
+
+        let key = format!("test-stream-{}", random_string(10));
+        debug!("Test key name: {}.", key);
+
+        for message in ["1", "2", "3"] {
+            let _: String = redis::cmd("XADD")
+                .arg(&key)
+                .arg("*")
+                .arg("message")
+                .arg(message)
+                .query_async(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        // Now run the source and make sure we get all three events.
+        let config = RedisSourceConfig {
+            data_type: DataTypeConfig::Stream,
+            list: None,
+            stream: Some(StreamOption {
+                group: "vector".to_owned(),
+                consumer: Some("vector-test".to_owned()),
+                start_id: "0".to_owned(),
+                field: default_field(),
+                id_key: Some("id".to_owned()),
+                count: default_count(),
+                claim_min_idle_secs: default_claim_min_idle_secs(),
+            }),
+            url: REDIS_SERVER.to_owned(),
+            key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
         };
 
         let events = run_and_assert_source_compliance_n(config, 3, &SOURCE_TAGS).await;
@@ -338,6 +498,7 @@ mod integration_test {
         assert_eq!(events[0].as_log()[log_schema().message_key()], "1".into());
         assert_eq!(events[1].as_log()[log_schema().message_key()], "2".into());
         assert_eq!(events[2].as_log()[log_schema().message_key()], "3".into());
+        assert!(events[0].as_log().get("id").is_some());
     }
 
     #[tokio::test]
@@ -351,9 +512,11 @@ mod integration_test {
             list: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
+            stream: None,
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
         };
 
         let (tx, rx) = SourceSender::new_test();
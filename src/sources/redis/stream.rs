@@ -0,0 +1,318 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt};
+use redis::{aio::ConnectionManager, RedisResult};
+use snafu::{ResultExt, Snafu};
+use tokio::time::Instant;
+use vector_common::finalizer::UnorderedFinalizer;
+
+use super::{decode_message, StreamOption};
+use crate::{
+    codecs,
+    config::SourceContext,
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        RedisReceiveEventError, RedisStreamAcknowledgementError, RedisStreamFieldMissingError,
+        RedisStreamNegativeAcknowledgementError, StreamClosedError,
+    },
+    sources::{redis::ConnectionInfo, Source},
+};
+
+/// How long a read blocks waiting for new entries.
+const BLOCK_MILLIS: u64 = 5000;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to create connection: {}", source))]
+    Connection { source: redis::RedisError },
+    #[snafu(display("Failed to create consumer group: {}", source))]
+    CreateGroup { source: redis::RedisError },
+    #[snafu(display("Failed to get hostname to use as consumer name: {}", source))]
+    Hostname { source: std::io::Error },
+}
+
+/// An entry of the stream, along with its fields.
+type Entry = (String, HashMap<String, redis::Value>);
+
+/// The IDs of entries whose events are being delivered.
+type InFlight = Arc<Mutex<HashSet<String>>>;
+
+struct Consumer {
+    conn: ConnectionManager,
+    key: String,
+    group: String,
+    name: String,
+    count: usize,
+    claim_min_idle: Duration,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn consume(
+    client: redis::Client,
+    connection_info: ConnectionInfo,
+    key: String,
+    redis_key: Option<String>,
+    options: StreamOption,
+    decoder: codecs::Decoder,
+    acknowledgements: bool,
+    cx: SourceContext,
+) -> crate::Result<Source> {
+    let name = match options.consumer {
+        Some(name) => name,
+        None => crate::get_hostname().context(HostnameSnafu)?,
+    };
+    // Reads block the connection they are sent on, so acknowledgements are sent on their own.
+    let mut conn = client
+        .get_tokio_connection_manager()
+        .await
+        .context(ConnectionSnafu)?;
+    let ack_conn = if acknowledgements {
+        client
+            .get_tokio_connection_manager()
+            .await
+            .context(ConnectionSnafu)?
+    } else {
+        conn.clone()
+    };
+
+    let created: RedisResult<()> = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(&key)
+        .arg(&options.group)
+        .arg(&options.start_id)
+        .arg("MKSTREAM")
+        .query_async(&mut conn)
+        .await;
+    match created {
+        Err(error) if error.code() != Some("BUSYGROUP") => {
+            return Err(BuildError::CreateGroup { source: error }.into())
+        }
+        _ => {}
+    }
+
+    let mut consumer = Consumer {
+        conn,
+        key,
+        group: options.group,
+        name,
+        count: options.count,
+        claim_min_idle: Duration::from_secs(options.claim_min_idle_secs),
+    };
+
+    Ok(Box::pin(async move {
+        let mut shutdown = cx.shutdown;
+        let mut out = cx.out;
+        let (finalizer, ack_stream) =
+            UnorderedFinalizer::<Vec<String>>::maybe_new(acknowledgements, shutdown.clone());
+        let in_flight = InFlight::default();
+        tokio::spawn(handle_acks(
+            ack_conn,
+            consumer.key.clone(),
+            consumer.group.clone(),
+            Arc::clone(&in_flight),
+            ack_stream,
+        ));
+
+        // Entries delivered to this consumer before it was restarted are read from its pending
+        // entries first, then new entries.
+        let mut pending_cursor = Some("0".to_owned());
+        let mut claim_cursor = "0-0".to_owned();
+        let mut next_claim = Instant::now();
+        loop {
+            let entries = if Instant::now() >= next_claim {
+                consumer.claim(&mut claim_cursor).await.map(|entries| {
+                    // A full scan of the pending entries is done once per idle period.
+                    if claim_cursor == "0-0" {
+                        next_claim = Instant::now() + consumer.claim_min_idle;
+                    }
+                    // Entries of this consumer being delivered are claimed as well, which keeps
+                    // them from being claimed by other consumers.
+                    let in_flight = in_flight.lock().expect("mutex poisoned");
+                    entries
+                        .into_iter()
+                        .filter(|(id, _)| !in_flight.contains(id))
+                        .collect()
+                })
+            } else {
+                let cursor = pending_cursor.as_deref().unwrap_or(">");
+                let entries = tokio::select! {
+                    entries = consumer.read(cursor) => entries,
+                    _ = &mut shutdown => break,
+                };
+                entries.map(|entries| {
+                    if pending_cursor.is_some() {
+                        pending_cursor = entries.last().map(|(id, _)| id.clone());
+                    }
+                    entries
+                })
+            };
+
+            let entries = match entries {
+                Ok(entries) => entries,
+                Err(error) => {
+                    emit!(RedisReceiveEventError::from(error));
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs(1)) => continue,
+                        _ = &mut shutdown => break,
+                    }
+                }
+            };
+            if entries.is_empty() {
+                continue;
+            }
+
+            let mut ids = Vec::with_capacity(entries.len());
+            let mut events = Vec::new();
+            for (id, fields) in entries {
+                match fields
+                    .get(&options.field)
+                    .and_then(|value| redis::from_redis_value::<Vec<u8>>(value).ok())
+                {
+                    Some(message) => {
+                        let mut decoded = decode_message(
+                            &connection_info,
+                            &message,
+                            &consumer.key,
+                            redis_key.as_deref(),
+                            decoder.clone(),
+                        )
+                        .await;
+                        if let Some(id_key) = &options.id_key {
+                            for event in &mut decoded {
+                                if let Event::Log(log) = event {
+                                    log.insert(id_key.as_str(), id.clone());
+                                }
+                            }
+                        }
+                        events.extend(decoded);
+                    }
+                    // Entries without a message are acknowledged along with the others, so
+                    // that they are not claimed over and over.
+                    None => emit!(RedisStreamFieldMissingError {
+                        id: &id,
+                        field: &options.field,
+                    }),
+                }
+                ids.push(id);
+            }
+
+            let count = events.len();
+            let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
+            if let Err(error) = out.send_batch(events).await {
+                emit!(StreamClosedError { error, count });
+                break;
+            }
+            match (&finalizer, receiver) {
+                (Some(finalizer), Some(receiver)) => {
+                    in_flight
+                        .lock()
+                        .expect("mutex poisoned")
+                        .extend(ids.iter().cloned());
+                    finalizer.add(ids, receiver);
+                }
+                _ => {
+                    if let Err(error) = consumer.ack(&ids).await {
+                        emit!(RedisStreamAcknowledgementError { error });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }))
+}
+
+impl Consumer {
+    /// Reads entries of the stream, after the given cursor.
+    ///
+    /// The `>` cursor reads entries never delivered to the group, waiting for them if there are
+    /// none, while other cursors read the pending entries of this consumer.
+    async fn read(&mut self, cursor: &str) -> RedisResult<Vec<Entry>> {
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP")
+            .arg(&self.group)
+            .arg(&self.name)
+            .arg("COUNT")
+            .arg(self.count);
+        if cursor == ">" {
+            cmd.arg("BLOCK").arg(BLOCK_MILLIS);
+        }
+        let reply: Option<Vec<(String, Vec<Entry>)>> = cmd
+            .arg("STREAMS")
+            .arg(&self.key)
+            .arg(cursor)
+            .query_async(&mut self.conn)
+            .await?;
+        Ok(reply
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, entries)| entries)
+            .collect())
+    }
+
+    /// Claims the entries that have been pending for too long, after the given cursor, which is
+    /// advanced to the next entries to claim, or `0-0` once all of them have been scanned.
+    async fn claim(&mut self, cursor: &mut String) -> RedisResult<Vec<Entry>> {
+        // The reply has a third element, deleted entries, since Redis 7.0.
+        let reply: Vec<redis::Value> = redis::cmd("XAUTOCLAIM")
+            .arg(&self.key)
+            .arg(&self.group)
+            .arg(&self.name)
+            .arg(self.claim_min_idle.as_millis() as u64)
+            .arg(cursor.as_str())
+            .arg("COUNT")
+            .arg(self.count)
+            .query_async(&mut self.conn)
+            .await?;
+        let mut reply = reply.into_iter();
+        *cursor = redis::from_redis_value(&reply.next().unwrap_or(redis::Value::Nil))?;
+        // Entries deleted from the stream while pending are claimed as `nil` before Redis 7.0.
+        let entries: Vec<Option<Entry>> =
+            redis::from_redis_value(&reply.next().unwrap_or(redis::Value::Nil))?;
+        Ok(entries.into_iter().flatten().collect())
+    }
+
+    async fn ack(&mut self, ids: &[String]) -> RedisResult<()> {
+        xack(&mut self.conn, &self.key, &self.group, ids).await
+    }
+}
+
+async fn xack(
+    conn: &mut ConnectionManager,
+    key: &str,
+    group: &str,
+    ids: &[String],
+) -> RedisResult<()> {
+    redis::cmd("XACK")
+        .arg(key)
+        .arg(group)
+        .arg(ids)
+        .query_async(conn)
+        .await
+}
+
+async fn handle_acks(
+    mut conn: ConnectionManager,
+    key: String,
+    group: String,
+    in_flight: InFlight,
+    mut ack_stream: impl Stream<Item = (BatchStatus, Vec<String>)> + Unpin,
+) {
+    while let Some((status, ids)) = ack_stream.next().await {
+        if status == BatchStatus::Delivered {
+            if let Err(error) = xack(&mut conn, &key, &group, &ids).await {
+                emit!(RedisStreamAcknowledgementError { error });
+            }
+        } else {
+            // The entries are left pending, to be claimed and delivered again.
+            emit!(RedisStreamNegativeAcknowledgementError { count: ids.len() });
+        }
+        let mut in_flight = in_flight.lock().expect("mutex poisoned");
+        for id in &ids {
+            in_flight.remove(id);
+        }
+    }
+}
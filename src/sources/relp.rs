@@ -0,0 +1,560 @@
+use std::{io, net::SocketAddr, str};
+
+use bytes::{Buf, Bytes, BytesMut};
+use chrono::Utc;
+use codecs::{decoding::format::Deserializer, StreamDecodingError, SyslogDeserializer};
+use smallvec::{smallvec, SmallVec};
+use snafu::Snafu;
+use tokio_util::codec::Decoder;
+use vector_config::configurable_component;
+
+use super::util::{SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker};
+use crate::{
+    codecs::DecoderDeadline,
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource,
+        SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{Event, LogEvent},
+    serde::bool_or_struct,
+    tcp::TcpKeepaliveConfig,
+    tls::{MaybeTlsSettings, TlsSourceConfig},
+};
+
+/// The maximum number of digits of transaction numbers and data lengths.
+const MAX_NUMBER_DIGITS: usize = 9;
+
+/// The maximum length of command names.
+const MAX_COMMAND_LENGTH: usize = 32;
+
+/// Configuration for the `relp` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+pub struct RelpConfig {
+    /// The address to listen for connections on.
+    address: SocketListenAddr,
+
+    #[configurable(derived)]
+    keepalive: Option<TcpKeepaliveConfig>,
+
+    #[configurable(derived)]
+    tls: Option<TlsSourceConfig>,
+
+    /// The size, in bytes, of the receive buffer used for each connection.
+    ///
+    /// This should not typically needed to be changed.
+    receive_buffer_bytes: Option<usize>,
+
+    /// The maximum number of TCP connections that will be allowed at any given time.
+    connection_limit: Option<u32>,
+
+    /// The maximum size, in bytes, of the messages of RELP frames.
+    ///
+    /// Connections sending larger messages are closed.
+    #[serde(default = "crate::serde::default_max_length")]
+    max_length: usize,
+
+    /// Overrides the name of the log field used to add the peer host to each event.
+    ///
+    /// The value will be the hostname of the syslog message if there's one, or else the address of the peer host.
+    ///
+    /// By default, the [global `host_key` option](https://vector.dev/docs/reference/configuration//global-options#log_schema.host_key) is used.
+    host_key: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<RelpConfig>("relp")
+}
+
+impl GenerateConfig for RelpConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: SocketListenAddr::SocketAddr("0.0.0.0:2514".parse().unwrap()),
+            keepalive: None,
+            tls: None,
+            receive_buffer_bytes: None,
+            connection_limit: None,
+            max_length: crate::serde::default_max_length(),
+            host_key: None,
+            acknowledgements: Default::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "relp")]
+impl SourceConfig for RelpConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let source = RelpSource {
+            max_length: self.max_length,
+            host_key: self
+                .host_key
+                .clone()
+                .unwrap_or_else(|| log_schema().host_key().to_string()),
+        };
+        let shutdown_secs = 30;
+        let tls_config = self.tls.as_ref().map(|tls| tls.tls_config.clone());
+        let tls_client_metadata_key = self
+            .tls
+            .as_ref()
+            .and_then(|tls| tls.client_metadata_key.clone());
+        let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+        source.run(
+            self.address,
+            self.keepalive,
+            shutdown_secs,
+            tls,
+            tls_client_metadata_key,
+            self.receive_buffer_bytes,
+            cx,
+            self.acknowledgements,
+            self.connection_limit,
+            false,
+        )
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "relp"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.into()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RelpSource {
+    max_length: usize,
+    host_key: String,
+}
+
+impl TcpSource for RelpSource {
+    type Error = DecodeError;
+    type Item = RelpFrame;
+    type Decoder = RelpDecoder;
+    type Acker = RelpAcker;
+
+    fn decoder(&self) -> Self::Decoder {
+        RelpDecoder {
+            max_length: self.max_length,
+        }
+    }
+
+    fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
+        let source_ip = host.ip().to_string();
+        for event in events {
+            let log = event.as_mut_log();
+            log.insert(log_schema().source_type_key(), "relp");
+            log.insert("source_ip", source_ip.clone());
+
+            let hostname = log
+                .get("hostname")
+                .map(|hostname| hostname.coerce_to_bytes())
+                .unwrap_or_else(|| source_ip.clone().into());
+            log.insert(self.host_key.as_str(), hostname);
+
+            let timestamp = log
+                .get("timestamp")
+                .and_then(|timestamp| timestamp.as_timestamp().cloned())
+                .unwrap_or_else(Utc::now);
+            log.insert(log_schema().timestamp_key(), timestamp);
+        }
+    }
+
+    fn build_acker(&self, frames: &[Self::Item]) -> Self::Acker {
+        RelpAcker {
+            transactions: frames
+                .iter()
+                .map(|frame| (frame.txnr, frame.command.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Answers each transaction of a batch of frames, once the events of their messages are
+/// acknowledged.
+struct RelpAcker {
+    transactions: Vec<(u32, RelpCommand)>,
+}
+
+impl TcpSourceAcker for RelpAcker {
+    // https://github.com/rsyslog/librelp/blob/master/doc/relp.html
+    fn build_ack(self, ack: TcpSourceAck) -> Option<Bytes> {
+        let mut responses = Vec::new();
+        for (txnr, command) in self.transactions {
+            let data = match command {
+                RelpCommand::Open => {
+                    "200 OK\nrelp_version=0\nrelp_software=vector\ncommands=syslog".to_string()
+                }
+                RelpCommand::Syslog => match ack {
+                    TcpSourceAck::Ack => "200 OK".to_string(),
+                    TcpSourceAck::Error => "500 error delivering events".to_string(),
+                    TcpSourceAck::Reject => "500 events rejected".to_string(),
+                },
+                RelpCommand::Close => String::new(),
+                RelpCommand::Other(command) => format!("500 unknown command {}", command),
+            };
+
+            responses.extend(format!("{} rsp {}", txnr, data.len()).into_bytes());
+            if !data.is_empty() {
+                responses.push(b' ');
+                responses.extend(data.into_bytes());
+            }
+            responses.push(b'\n');
+        }
+        Some(responses.into())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RelpCommand {
+    Open,
+    Syslog,
+    Close,
+    Other(String),
+}
+
+impl From<&str> for RelpCommand {
+    fn from(command: &str) -> Self {
+        match command {
+            "open" => Self::Open,
+            "syslog" => Self::Syslog,
+            "close" => Self::Close,
+            command => Self::Other(command.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RelpFrame {
+    txnr: u32,
+    command: RelpCommand,
+    data: Bytes,
+}
+
+impl From<RelpFrame> for SmallVec<[Event; 1]> {
+    fn from(frame: RelpFrame) -> Self {
+        if frame.command != RelpCommand::Syslog {
+            return SmallVec::new();
+        }
+
+        // Messages which aren't valid syslog messages are still acknowledged, or the client would
+        // send them again and again, so they are kept as they are.
+        SyslogDeserializer
+            .parse(frame.data.clone())
+            .unwrap_or_else(|_| smallvec![LogEvent::from(frame.data).into()])
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("i/o error: {}", source))]
+    IO { source: io::Error },
+    #[snafu(display("Invalid RELP frame: {}", reason))]
+    InvalidFrame { reason: &'static str },
+    #[snafu(display("RELP frame of {} bytes is larger than the maximum length", length))]
+    FrameTooLarge { length: usize },
+}
+
+impl StreamDecodingError for DecodeError {
+    fn can_continue(&self) -> bool {
+        // Frames are only delimited by their header, so no frame can be read after an invalid one.
+        false
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(source: io::Error) -> Self {
+        DecodeError::IO { source }
+    }
+}
+
+#[derive(Debug)]
+struct RelpDecoder {
+    max_length: usize,
+}
+
+/// Finds the field of a frame header starting at `start`, returning its end, if it's complete.
+fn header_field(
+    src: &[u8],
+    start: usize,
+    max_length: usize,
+    delimiters: &[u8],
+) -> Result<Option<usize>, DecodeError> {
+    let available = &src[start.min(src.len())..];
+    match available
+        .iter()
+        .take(max_length + 1)
+        .position(|byte| delimiters.contains(byte))
+    {
+        Some(0) => Err(DecodeError::InvalidFrame {
+            reason: "empty header field",
+        }),
+        Some(length) => Ok(Some(start + length)),
+        None if available.len() > max_length => Err(DecodeError::InvalidFrame {
+            reason: "header field is too long",
+        }),
+        None => Ok(None),
+    }
+}
+
+fn parse_number(field: &[u8]) -> Result<usize, DecodeError> {
+    str::from_utf8(field)
+        .ok()
+        .and_then(|field| field.parse().ok())
+        .ok_or(DecodeError::InvalidFrame {
+            reason: "invalid number",
+        })
+}
+
+impl DecoderDeadline for RelpDecoder {}
+
+// Frames are made of `TXNR SP COMMAND SP DATALEN [SP DATA] LF`, where the data is omitted when
+// its length is zero.
+//
+// https://github.com/rsyslog/librelp/blob/master/doc/relp.html
+impl Decoder for RelpDecoder {
+    type Item = (RelpFrame, usize);
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let txnr_end = match header_field(src, 0, MAX_NUMBER_DIGITS, b" ")? {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+        let command_end = match header_field(src, txnr_end + 1, MAX_COMMAND_LENGTH, b" ")? {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+        let length_end = match header_field(src, command_end + 1, MAX_NUMBER_DIGITS, b" \n")? {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+
+        let txnr = parse_number(&src[..txnr_end])? as u32;
+        let command = str::from_utf8(&src[txnr_end + 1..command_end])
+            .map_err(|_| DecodeError::InvalidFrame {
+                reason: "invalid command",
+            })?
+            .into();
+        let length = parse_number(&src[command_end + 1..length_end])?;
+        if length > self.max_length {
+            return Err(DecodeError::FrameTooLarge { length });
+        }
+
+        let (data_start, frame_length) = if src[length_end] == b'\n' {
+            if length != 0 {
+                return Err(DecodeError::InvalidFrame {
+                    reason: "missing data",
+                });
+            }
+            (length_end, length_end + 1)
+        } else {
+            (length_end + 1, length_end + 1 + length + 1)
+        };
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+        if src[frame_length - 1] != b'\n' {
+            return Err(DecodeError::InvalidFrame {
+                reason: "missing trailer",
+            });
+        }
+
+        let mut frame = src.split_to(frame_length);
+        frame.advance(data_start);
+        frame.truncate(length);
+        let frame = RelpFrame {
+            txnr,
+            command,
+            data: frame.freeze(),
+        };
+        Ok(Some((frame, frame_length)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{
+            components::{assert_source_compliance, SOCKET_PUSH_SOURCE_TAGS},
+            next_addr, spawn_collect_n, wait_for_tcp,
+        },
+        SourceSender,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RelpConfig>();
+    }
+
+    fn decode(frames: &[u8]) -> Result<Vec<RelpFrame>, DecodeError> {
+        let mut decoder = RelpDecoder { max_length: 1024 };
+        let mut src = BytesMut::from(frames);
+        let mut decoded = Vec::new();
+        while let Some((frame, _)) = decoder.decode(&mut src)? {
+            decoded.push(frame);
+        }
+        assert!(src.is_empty(), "undecoded bytes: {:?}", src);
+        Ok(decoded)
+    }
+
+    #[test]
+    fn decodes_frames() {
+        let frames = decode(b"1 open 14 relp_version=0\n2 syslog 5 hello\n3 close 0\n").unwrap();
+        assert_eq!(
+            frames,
+            vec![
+                RelpFrame {
+                    txnr: 1,
+                    command: RelpCommand::Open,
+                    data: "relp_version=0".into(),
+                },
+                RelpFrame {
+                    txnr: 2,
+                    command: RelpCommand::Syslog,
+                    data: "hello".into(),
+                },
+                RelpFrame {
+                    txnr: 3,
+                    command: RelpCommand::Close,
+                    data: Bytes::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn waits_for_complete_frames() {
+        let mut decoder = RelpDecoder { max_length: 1024 };
+        let mut src = BytesMut::from("2 syslog 11 hello");
+        assert!(decoder.decode(&mut src).unwrap().is_none());
+        src.extend_from_slice(b" world\n");
+        let (frame, length) = decoder.decode(&mut src).unwrap().unwrap();
+        assert_eq!(frame.data, "hello world");
+        assert_eq!(length, 24);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_frames() {
+        assert!(matches!(
+            decode(b"2 syslog 5 hello world\n"),
+            Err(DecodeError::InvalidFrame { .. })
+        ));
+        assert!(matches!(
+            decode(b"two syslog 5 hello\n"),
+            Err(DecodeError::InvalidFrame { .. })
+        ));
+        assert!(matches!(
+            decode(b"2 syslog 2048 hello\n"),
+            Err(DecodeError::FrameTooLarge { length: 2048 })
+        ));
+    }
+
+    #[test]
+    fn builds_responses() {
+        let acker = RelpAcker {
+            transactions: vec![
+                (1, RelpCommand::Open),
+                (2, RelpCommand::Syslog),
+                (3, RelpCommand::Close),
+            ],
+        };
+        assert_eq!(
+            acker.build_ack(TcpSourceAck::Ack).unwrap(),
+            "1 rsp 58 200 OK\nrelp_version=0\nrelp_software=vector\ncommands=syslog\n2 rsp 6 200 OK\n3 rsp 0\n"
+        );
+
+        let acker = RelpAcker {
+            transactions: vec![(2, RelpCommand::Syslog)],
+        };
+        assert_eq!(
+            acker.build_ack(TcpSourceAck::Reject).unwrap(),
+            "2 rsp 19 500 events rejected\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivered() {
+        test_protocol(EventStatus::Delivered, "2 rsp 6 200 OK\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_rejected() {
+        test_protocol(EventStatus::Rejected, "2 rsp 19 500 events rejected\n").await;
+    }
+
+    async fn test_protocol(status: EventStatus, response: &'static str) {
+        let events = assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let (sender, recv) = SourceSender::new_test_finalize(status);
+            let address = next_addr();
+            let source = RelpConfig {
+                address: address.into(),
+                keepalive: None,
+                tls: None,
+                receive_buffer_bytes: None,
+                connection_limit: None,
+                max_length: crate::serde::default_max_length(),
+                host_key: None,
+                acknowledgements: true.into(),
+            }
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+            tokio::spawn(source);
+            wait_for_tcp(address).await;
+
+            spawn_collect_n(send_message(address, response), recv, 1).await
+        })
+        .await;
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["message"], "Hello, world!".into());
+        assert_eq!(log["appname"], "app".into());
+        assert_eq!(log["host"], "myhost".into());
+        assert_eq!(log["source_type"], "relp".into());
+        assert_eq!(log["source_ip"], "127.0.0.1".into());
+    }
+
+    async fn send_message(address: SocketAddr, response: &str) {
+        let mut socket = tokio::net::TcpStream::connect(address).await.unwrap();
+
+        socket
+            .write_all(b"1 open 14 relp_version=0\n")
+            .await
+            .unwrap();
+        let expected = "1 rsp 58 200 OK\nrelp_version=0\nrelp_software=vector\ncommands=syslog\n";
+        let mut output = vec![0; expected.len()];
+        socket.read_exact(&mut output).await.unwrap();
+        assert_eq!(output, expected.as_bytes());
+
+        let message = "<13>1 2022-01-01T12:00:00Z myhost app 123 - - Hello, world!";
+        socket
+            .write_all(format!("2 syslog {} {}\n", message.len(), message).as_bytes())
+            .await
+            .unwrap();
+        let mut output = vec![0; response.len()];
+        socket.read_exact(&mut output).await.unwrap();
+        assert_eq!(output, response.as_bytes());
+    }
+}
@@ -0,0 +1,239 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use bytes::Bytes;
+use chrono::Utc;
+use tokio::net::UdpSocket;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, Output, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::{
+        EventsReceived, SflowParseError, SocketBytesReceived, SocketMode, StreamClosedError,
+        UdpSocketError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod parser;
+
+/// The largest message that can be received over UDP.
+const MAX_MESSAGE_SIZE: usize = 65_507;
+
+/// Configuration for the `sflow` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SflowConfig {
+    /// The address to listen for sFlow datagrams on.
+    #[serde(default = "default_address")]
+    address: SocketAddr,
+
+    /// Overrides the name of the log field used to add the sending host to each event.
+    ///
+    /// The value will be the sending host's address, i.e. `1.2.3.4`.
+    ///
+    /// By default, the [global `host_key` option](https://vector.dev/docs/reference/configuration//global-options#log_schema.host_key) is used.
+    host_key: Option<String>,
+
+    /// The size, in bytes, of the receive buffer used for the listening socket.
+    ///
+    /// This should not typically needed to be changed.
+    receive_buffer_bytes: Option<usize>,
+}
+
+fn default_address() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 6343)
+}
+
+impl Default for SflowConfig {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            host_key: None,
+            receive_buffer_bytes: None,
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<SflowConfig>("sflow")
+}
+
+impl_generate_config_from_default!(SflowConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sflow")]
+impl SourceConfig for SflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+
+        Ok(Box::pin(run(
+            self.address,
+            self.receive_buffer_bytes,
+            host_key,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "sflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn run(
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    host_key: String,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&address)
+        .await
+        .expect("Failed to bind to UDP listener socket");
+
+    if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %address);
+
+    let mut buf = vec![0; MAX_MESSAGE_SIZE];
+    loop {
+        let (byte_size, peer) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(received) => received,
+                Err(error) => {
+                    emit!(UdpSocketError { error });
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+        emit!(SocketBytesReceived {
+            mode: SocketMode::Udp,
+            byte_size,
+        });
+
+        let records = match parser::decode(&buf[..byte_size]) {
+            Ok(records) => records,
+            Err(error) => {
+                emit!(SflowParseError { error, peer });
+                continue;
+            }
+        };
+        if records.is_empty() {
+            continue;
+        }
+
+        // sFlow datagrams carry no wall clock time, only the agent's uptime.
+        let now = Utc::now();
+        let events = records
+            .into_iter()
+            .map(|record| {
+                let mut log = LogEvent::from(record);
+                log.insert(log_schema().source_type_key(), Bytes::from("sflow"));
+                log.insert(log_schema().timestamp_key(), now);
+                log.insert(host_key.as_str(), peer.ip().to_string());
+                Event::from(log)
+            })
+            .collect::<Vec<_>>();
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        tokio::select! {
+            result = out.send_batch(events) => {
+                if let Err(error) = result {
+                    emit!(StreamClosedError { error, count });
+                    return Ok(());
+                }
+            }
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SflowConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_samples() {
+        assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
+            let address = next_addr();
+            let config = SflowConfig {
+                address,
+                ..Default::default()
+            };
+            let (tx, rx) = SourceSender::new_test();
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+            // A datagram with a flow sample carrying an IPv4 record, and an empty counter sample.
+            let ipv4 = [32, 17, 0x0a00_0001, 0x0a00_0002, 5353, 53, 0, 0];
+            let flow = [&[1, 18 * 4, 7, 1, 256, 2560, 0, 1, 2, 1, 3, 32][..], &ipv4].concat();
+            let counter = [2, 12, 8, 1, 0];
+            let header = [5, 1, 0xc000_0201, 0, 1, 1000, 2];
+            let message = [&header[..], &flow, &counter]
+                .concat()
+                .into_iter()
+                .flat_map(u32::to_be_bytes)
+                .collect::<Vec<_>>();
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            socket.send_to(&message, address).await.unwrap();
+
+            let events = collect_n(rx, 2).await;
+            let log = events[0].as_log();
+            assert_eq!(log["source_type"], "sflow".into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+            assert_eq!(log["agent_address"], "192.0.2.1".into());
+            assert_eq!(log["sampling_rate"], 256.into());
+            assert_eq!(log["ipv4.source_ip"], "10.0.0.1".into());
+            assert_eq!(log["ipv4.destination_port"], 53.into());
+            let log = events[1].as_log();
+            assert_eq!(log["sample_type"], "counter".into());
+            assert_eq!(log["sample_sequence_number"], 8.into());
+        })
+        .await;
+    }
+}
@@ -0,0 +1,552 @@
+//! Decoding of sFlow v5 datagrams.
+//!
+//! Each flow or counter sample of a datagram is decoded into a record, along with the fields of
+//! the datagram header. Record types unknown to this module are skipped.
+
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use snafu::Snafu;
+
+use crate::event::Value;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Datagram is truncated"))]
+    Truncated,
+    #[snafu(display("Unsupported sFlow version {}", version))]
+    UnsupportedVersion { version: u32 },
+    #[snafu(display("Unsupported address type {}", address_type))]
+    UnsupportedAddressType { address_type: u32 },
+}
+
+/// A reader of XDR-encoded data.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        if self.data.len() < len {
+            return Err(ParseError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        self.bytes(4)
+            .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("length is checked")))
+    }
+
+    fn u64(&mut self) -> Result<u64, ParseError> {
+        self.bytes(8)
+            .map(|bytes| u64::from_be_bytes(bytes.try_into().expect("length is checked")))
+    }
+
+    /// Reads variable-length opaque data, which is padded to a multiple of 4 bytes.
+    fn opaque(&mut self) -> Result<&'a [u8], ParseError> {
+        let len = self.u32()? as usize;
+        let opaque = self.bytes(len)?;
+        self.bytes((4 - len % 4) % 4)?;
+        Ok(opaque)
+    }
+
+    fn ipv4(&mut self) -> Result<String, ParseError> {
+        let octets: [u8; 4] = self.bytes(4)?.try_into().expect("length is checked");
+        Ok(Ipv4Addr::from(octets).to_string())
+    }
+
+    fn ipv6(&mut self) -> Result<String, ParseError> {
+        let octets: [u8; 16] = self.bytes(16)?.try_into().expect("length is checked");
+        Ok(Ipv6Addr::from(octets).to_string())
+    }
+
+    fn address(&mut self) -> Result<String, ParseError> {
+        match self.u32()? {
+            1 => self.ipv4(),
+            2 => self.ipv6(),
+            address_type => Err(ParseError::UnsupportedAddressType { address_type }),
+        }
+    }
+
+    /// Reads the structures of samples and records, which are prefixed by their format and length.
+    fn structure(&mut self) -> Result<(u32, u32, Reader<'a>), ParseError> {
+        let format = self.u32()?;
+        let data = self.opaque()?;
+        Ok((format >> 12, format & 0xfff, Reader::new(data)))
+    }
+}
+
+type Record = BTreeMap<String, Value>;
+
+fn insert(record: &mut Record, key: &str, value: impl Into<Value>) {
+    record.insert(key.to_owned(), value.into());
+}
+
+fn insert_u64(record: &mut Record, key: &str, value: u64) {
+    let value = i64::try_from(value)
+        .map(Value::from)
+        .unwrap_or_else(|_| Value::from(value as f64));
+    record.insert(key.to_owned(), value);
+}
+
+/// Decodes a datagram into one record per sample.
+pub fn decode(data: &[u8]) -> Result<Vec<Record>, ParseError> {
+    let mut reader = Reader::new(data);
+    let version = reader.u32()?;
+    if version != 5 {
+        return Err(ParseError::UnsupportedVersion { version });
+    }
+
+    let mut header = Record::new();
+    insert(&mut header, "agent_address", reader.address()?);
+    insert(&mut header, "sub_agent_id", reader.u32()? as i64);
+    insert(&mut header, "sequence_number", reader.u32()? as i64);
+    insert(&mut header, "uptime", reader.u32()? as i64);
+
+    let count = reader.u32()?;
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let (enterprise, format, mut sample) = reader.structure()?;
+        let mut record = header.clone();
+        match (enterprise, format) {
+            (0, 1 | 3) => decode_flow_sample(&mut record, &mut sample, format == 3)?,
+            (0, 2 | 4) => decode_counter_sample(&mut record, &mut sample, format == 4)?,
+            _ => continue,
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn decode_source_id(
+    record: &mut Record,
+    sample: &mut Reader<'_>,
+    expanded: bool,
+) -> Result<(), ParseError> {
+    insert(record, "sample_sequence_number", sample.u32()? as i64);
+    let (source_id_type, source_id_index) = if expanded {
+        (sample.u32()?, sample.u32()?)
+    } else {
+        let source_id = sample.u32()?;
+        (source_id >> 24, source_id & 0x00ff_ffff)
+    };
+    insert(record, "source_id_type", source_id_type as i64);
+    insert(record, "source_id_index", source_id_index as i64);
+    Ok(())
+}
+
+fn decode_flow_sample(
+    record: &mut Record,
+    sample: &mut Reader<'_>,
+    expanded: bool,
+) -> Result<(), ParseError> {
+    insert(record, "sample_type", "flow");
+    decode_source_id(record, sample, expanded)?;
+    insert(record, "sampling_rate", sample.u32()? as i64);
+    insert(record, "sample_pool", sample.u32()? as i64);
+    insert(record, "drops", sample.u32()? as i64);
+    for direction in ["input", "output"] {
+        let (format, value) = if expanded {
+            (sample.u32()?, sample.u32()?)
+        } else {
+            let interface = sample.u32()?;
+            (interface >> 30, interface & 0x3fff_ffff)
+        };
+        insert(
+            record,
+            &format!("{}_interface_format", direction),
+            format as i64,
+        );
+        insert(record, &format!("{}_interface", direction), value as i64);
+    }
+
+    for _ in 0..sample.u32()? {
+        let (enterprise, format, mut data) = sample.structure()?;
+        let (name, fields) = match (enterprise, format) {
+            (0, 1) => ("raw_packet_header", decode_raw_packet_header(&mut data)?),
+            (0, 2) => ("ethernet", decode_ethernet(&mut data)?),
+            (0, 3) => ("ipv4", decode_ip(&mut data, false)?),
+            (0, 4) => ("ipv6", decode_ip(&mut data, true)?),
+            (0, 1001) => ("extended_switch", decode_extended_switch(&mut data)?),
+            (0, 1002) => ("extended_router", decode_extended_router(&mut data)?),
+            _ => continue,
+        };
+        record.insert(name.to_owned(), Value::Object(fields));
+    }
+    Ok(())
+}
+
+fn decode_counter_sample(
+    record: &mut Record,
+    sample: &mut Reader<'_>,
+    expanded: bool,
+) -> Result<(), ParseError> {
+    insert(record, "sample_type", "counter");
+    decode_source_id(record, sample, expanded)?;
+
+    for _ in 0..sample.u32()? {
+        let (enterprise, format, mut data) = sample.structure()?;
+        let (name, fields) = match (enterprise, format) {
+            (0, 1) => ("generic_interface", decode_generic_interface(&mut data)?),
+            (0, 2) => ("ethernet_interface", decode_ethernet_interface(&mut data)?),
+            (0, 1001) => ("processor", decode_processor(&mut data)?),
+            _ => continue,
+        };
+        record.insert(name.to_owned(), Value::Object(fields));
+    }
+    Ok(())
+}
+
+fn decode_raw_packet_header(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    let header_protocol = data.u32()?;
+    insert(&mut fields, "header_protocol", header_protocol as i64);
+    insert(&mut fields, "frame_length", data.u32()? as i64);
+    insert(&mut fields, "stripped", data.u32()? as i64);
+    let header = data.opaque()?;
+    // Only Ethernet headers are decoded, which are the vast majority.
+    if header_protocol == 1 {
+        decode_ethernet_header(&mut fields, header);
+    }
+    Ok(fields)
+}
+
+fn mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Decodes the fields of a sampled Ethernet frame, as far as its header was captured.
+fn decode_ethernet_header(fields: &mut Record, header: &[u8]) {
+    if header.len() < 14 {
+        return;
+    }
+    insert(fields, "destination_mac", mac(&header[0..6]));
+    insert(fields, "source_mac", mac(&header[6..12]));
+    let mut ether_type = u16::from_be_bytes([header[12], header[13]]);
+    let mut offset = 14;
+    if ether_type == 0x8100 && header.len() >= 18 {
+        insert(
+            fields,
+            "vlan_id",
+            (u16::from_be_bytes([header[14], header[15]]) & 0x0fff) as i64,
+        );
+        ether_type = u16::from_be_bytes([header[16], header[17]]);
+        offset = 18;
+    }
+    insert(fields, "ether_type", ether_type as i64);
+
+    let packet = &header[offset..];
+    let (protocol, transport) = match ether_type {
+        0x0800 if packet.len() >= 20 => {
+            let header_length = (packet[0] & 0x0f) as usize * 4;
+            insert(fields, "ip_tos", packet[1] as i64);
+            insert(fields, "ip_ttl", packet[8] as i64);
+            insert(
+                fields,
+                "source_ip",
+                Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]).to_string(),
+            );
+            insert(
+                fields,
+                "destination_ip",
+                Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]).to_string(),
+            );
+            (packet[9], packet.get(header_length..).unwrap_or_default())
+        }
+        0x86dd if packet.len() >= 40 => {
+            let address = |bytes: &[u8]| {
+                let octets: [u8; 16] = bytes.try_into().expect("length is checked");
+                Ipv6Addr::from(octets).to_string()
+            };
+            insert(fields, "ip_ttl", packet[7] as i64);
+            insert(fields, "source_ip", address(&packet[8..24]));
+            insert(fields, "destination_ip", address(&packet[24..40]));
+            (packet[6], &packet[40..])
+        }
+        _ => return,
+    };
+    insert(fields, "ip_protocol", protocol as i64);
+
+    // TCP and UDP
+    if matches!(protocol, 6 | 17) && transport.len() >= 4 {
+        insert(
+            fields,
+            "source_port",
+            u16::from_be_bytes([transport[0], transport[1]]) as i64,
+        );
+        insert(
+            fields,
+            "destination_port",
+            u16::from_be_bytes([transport[2], transport[3]]) as i64,
+        );
+        if protocol == 6 && transport.len() >= 14 {
+            insert(fields, "tcp_flags", transport[13] as i64);
+        }
+    }
+}
+
+fn decode_ethernet(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    insert(&mut fields, "length", data.u32()? as i64);
+    // MAC addresses are padded to 8 bytes.
+    insert(&mut fields, "source_mac", mac(&data.bytes(8)?[..6]));
+    insert(&mut fields, "destination_mac", mac(&data.bytes(8)?[..6]));
+    insert(&mut fields, "ether_type", data.u32()? as i64);
+    Ok(fields)
+}
+
+fn decode_ip(data: &mut Reader<'_>, ipv6: bool) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    insert(&mut fields, "length", data.u32()? as i64);
+    insert(&mut fields, "ip_protocol", data.u32()? as i64);
+    let (source, destination) = if ipv6 {
+        (data.ipv6()?, data.ipv6()?)
+    } else {
+        (data.ipv4()?, data.ipv4()?)
+    };
+    insert(&mut fields, "source_ip", source);
+    insert(&mut fields, "destination_ip", destination);
+    insert(&mut fields, "source_port", data.u32()? as i64);
+    insert(&mut fields, "destination_port", data.u32()? as i64);
+    insert(&mut fields, "tcp_flags", data.u32()? as i64);
+    insert(
+        &mut fields,
+        if ipv6 { "ip_priority" } else { "ip_tos" },
+        data.u32()? as i64,
+    );
+    Ok(fields)
+}
+
+fn decode_extended_switch(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    insert(&mut fields, "source_vlan", data.u32()? as i64);
+    insert(&mut fields, "source_priority", data.u32()? as i64);
+    insert(&mut fields, "destination_vlan", data.u32()? as i64);
+    insert(&mut fields, "destination_priority", data.u32()? as i64);
+    Ok(fields)
+}
+
+fn decode_extended_router(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    insert(&mut fields, "next_hop", data.address()?);
+    insert(&mut fields, "source_mask_length", data.u32()? as i64);
+    insert(&mut fields, "destination_mask_length", data.u32()? as i64);
+    Ok(fields)
+}
+
+fn decode_generic_interface(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    insert(&mut fields, "if_index", data.u32()? as i64);
+    insert(&mut fields, "if_type", data.u32()? as i64);
+    insert_u64(&mut fields, "if_speed", data.u64()?);
+    insert(&mut fields, "if_direction", data.u32()? as i64);
+    let status = data.u32()?;
+    insert(&mut fields, "if_admin_status_up", status & 1 != 0);
+    insert(&mut fields, "if_oper_status_up", status & 2 != 0);
+    insert_u64(&mut fields, "if_in_octets", data.u64()?);
+    for key in [
+        "if_in_ucast_pkts",
+        "if_in_multicast_pkts",
+        "if_in_broadcast_pkts",
+        "if_in_discards",
+        "if_in_errors",
+        "if_in_unknown_protos",
+    ] {
+        insert(&mut fields, key, data.u32()? as i64);
+    }
+    insert_u64(&mut fields, "if_out_octets", data.u64()?);
+    for key in [
+        "if_out_ucast_pkts",
+        "if_out_multicast_pkts",
+        "if_out_broadcast_pkts",
+        "if_out_discards",
+        "if_out_errors",
+    ] {
+        insert(&mut fields, key, data.u32()? as i64);
+    }
+    insert(&mut fields, "if_promiscuous_mode", data.u32()? != 0);
+    Ok(fields)
+}
+
+fn decode_ethernet_interface(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    for key in [
+        "dot3_stats_alignment_errors",
+        "dot3_stats_fcs_errors",
+        "dot3_stats_single_collision_frames",
+        "dot3_stats_multiple_collision_frames",
+        "dot3_stats_sqe_test_errors",
+        "dot3_stats_deferred_transmissions",
+        "dot3_stats_late_collisions",
+        "dot3_stats_excessive_collisions",
+        "dot3_stats_internal_mac_transmit_errors",
+        "dot3_stats_carrier_sense_errors",
+        "dot3_stats_frame_too_longs",
+        "dot3_stats_internal_mac_receive_errors",
+        "dot3_stats_symbol_errors",
+    ] {
+        insert(&mut fields, key, data.u32()? as i64);
+    }
+    Ok(fields)
+}
+
+fn decode_processor(data: &mut Reader<'_>) -> Result<Record, ParseError> {
+    let mut fields = Record::new();
+    // CPU utilizations are expressed in hundredths of percent.
+    for key in ["cpu_5s", "cpu_1m", "cpu_5m"] {
+        insert(&mut fields, key, data.u32()? as f64 / 100.0);
+    }
+    insert_u64(&mut fields, "total_memory", data.u64()?);
+    insert_u64(&mut fields, "free_memory", data.u64()?);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    fn structure(format: u32, data: &[u8]) -> Vec<u8> {
+        [u32s(&[format, data.len() as u32]), data.to_vec()].concat()
+    }
+
+    fn datagram(samples: &[Vec<u8>]) -> Vec<u8> {
+        [
+            u32s(&[5, 1]),
+            vec![192, 0, 2, 1],
+            u32s(&[0, 42, 60_000, samples.len() as u32]),
+            samples.concat(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_flow_samples() {
+        let mut frame = vec![
+            0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e, // destination MAC
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // source MAC
+            0x81, 0x00, 0x00, 0x0a, // VLAN 10
+            0x08, 0x00, // IPv4
+        ];
+        frame.extend([
+            0x45, 0x00, 0x00, 0x3c, 0, 0, 0, 0, 64, 6, 0, 0, // IPv4 header
+            10, 0, 0, 1, // source
+            10, 0, 0, 2, // destination
+        ]);
+        frame.extend([
+            0x1f, 0x90, 0x00, 0x50, 0, 0, 0, 0, 0, 0, 0, 0, 0x50, 0x12, // TCP header
+        ]);
+        let padding = (4 - frame.len() % 4) % 4;
+        let raw = [
+            u32s(&[1, 1500, 4, frame.len() as u32]),
+            frame.clone(),
+            vec![0; padding],
+        ]
+        .concat();
+        let switch = u32s(&[10, 0, 20, 0]);
+        let unknown = u32s(&[1, 2, 3]);
+
+        let sample = [
+            u32s(&[7, 0x0000_0003, 512, 1024, 0, 3, 0x4000_0001, 3]),
+            structure(1, &raw),
+            structure(1001, &switch),
+            structure((9 << 12) | 1, &unknown),
+        ]
+        .concat();
+        let records = decode(&datagram(&[structure(1, &sample)])).unwrap();
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record["agent_address"], Value::from("192.0.2.1"));
+        assert_eq!(record["sequence_number"], Value::from(42));
+        assert_eq!(record["sample_type"], Value::from("flow"));
+        assert_eq!(record["source_id_index"], Value::from(3));
+        assert_eq!(record["sampling_rate"], Value::from(512));
+        assert_eq!(record["sample_pool"], Value::from(1024));
+        assert_eq!(record["input_interface"], Value::from(3));
+        assert_eq!(record["output_interface_format"], Value::from(1));
+
+        let raw = record["raw_packet_header"].as_object().unwrap();
+        assert_eq!(raw["frame_length"], Value::from(1500));
+        assert_eq!(raw["source_mac"], Value::from("00:11:22:33:44:55"));
+        assert_eq!(raw["vlan_id"], Value::from(10));
+        assert_eq!(raw["source_ip"], Value::from("10.0.0.1"));
+        assert_eq!(raw["destination_ip"], Value::from("10.0.0.2"));
+        assert_eq!(raw["ip_protocol"], Value::from(6));
+        assert_eq!(raw["source_port"], Value::from(8080));
+        assert_eq!(raw["destination_port"], Value::from(80));
+        assert_eq!(raw["tcp_flags"], Value::from(0x12));
+
+        let switch = record["extended_switch"].as_object().unwrap();
+        assert_eq!(switch["destination_vlan"], Value::from(20));
+    }
+
+    #[test]
+    fn decodes_counter_samples() {
+        let generic = [
+            u32s(&[5, 6]),
+            10_000_000_000u64.to_be_bytes().to_vec(),
+            u32s(&[1, 3]),
+            123_456u64.to_be_bytes().to_vec(),
+            u32s(&[1, 2, 3, 4, 5, 6]),
+            654_321u64.to_be_bytes().to_vec(),
+            u32s(&[7, 8, 9, 10, 11, 0]),
+        ]
+        .concat();
+        let processor = [
+            u32s(&[1250, 1000, 875]),
+            8192u64.to_be_bytes().to_vec(),
+            4096u64.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        let sample = [
+            u32s(&[9, 0, 5, 2]),
+            structure(1, &generic),
+            structure(1001, &processor),
+        ]
+        .concat();
+        let records = decode(&datagram(&[structure(4, &sample)])).unwrap();
+
+        let record = &records[0];
+        assert_eq!(record["sample_type"], Value::from("counter"));
+        assert_eq!(record["source_id_index"], Value::from(5));
+        let generic = record["generic_interface"].as_object().unwrap();
+        assert_eq!(generic["if_speed"], Value::from(10_000_000_000i64));
+        assert_eq!(generic["if_oper_status_up"], Value::from(true));
+        assert_eq!(generic["if_in_octets"], Value::from(123_456));
+        assert_eq!(generic["if_out_errors"], Value::from(11));
+        let processor = record["processor"].as_object().unwrap();
+        assert_eq!(processor["cpu_5s"], Value::from(12.5));
+        assert_eq!(processor["free_memory"], Value::from(4096));
+    }
+
+    #[test]
+    fn rejects_invalid_datagrams() {
+        assert_eq!(
+            decode(&u32s(&[4, 1])),
+            Err(ParseError::UnsupportedVersion { version: 4 })
+        );
+        let mut truncated = datagram(&[structure(1, &u32s(&[1, 2, 3]))]);
+        truncated.truncate(truncated.len() - 2);
+        assert_eq!(decode(&truncated), Err(ParseError::Truncated));
+    }
+}
@@ -0,0 +1,382 @@
+use std::{io, time::Duration};
+
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    net::{lookup_host, UdpSocket},
+    time::Instant,
+};
+
+use crate::{
+    internal_events::EndpointBytesReceived,
+    sources::util::snmp::{
+        ber::{tag, BerError, Oid, VarBind, VarBindValue},
+        encode_community_message,
+        usm::{encode_v3, ScopedPdu, UsmError, UsmUser, V3Header, FLAG_AUTH},
+        Message, MessageError, Pdu, Version,
+    },
+};
+
+/// The largest message that can be received over UDP.
+const MAX_MESSAGE_SIZE: usize = 65_507;
+
+/// The `noSuchName` error status of SNMPv1, returned at the end of walks.
+const NO_SUCH_NAME: i64 = 2;
+
+#[derive(Debug, Snafu)]
+pub enum SnmpError {
+    #[snafu(display("Socket error: {}", source))]
+    Io { source: io::Error },
+    #[snafu(display("No response after {} attempts", attempts))]
+    Timeout { attempts: usize },
+    #[snafu(display("Invalid response: {}", source))]
+    InvalidResponse { source: MessageError },
+    #[snafu(display("{}", source))]
+    Usm { source: UsmError },
+    #[snafu(display("Agent reported {}", oid))]
+    Report { oid: String },
+    #[snafu(display("Agent did not report its engine ID"))]
+    Discovery,
+    #[snafu(display("Agent returned error status {} for varbind {}", status, index))]
+    ErrorStatus { status: i64, index: i64 },
+}
+
+impl From<MessageError> for SnmpError {
+    fn from(source: MessageError) -> Self {
+        Self::InvalidResponse { source }
+    }
+}
+
+impl From<BerError> for SnmpError {
+    fn from(source: BerError) -> Self {
+        Self::InvalidResponse {
+            source: source.into(),
+        }
+    }
+}
+
+/// The credentials used to authenticate requests.
+#[derive(Debug)]
+pub enum Credentials {
+    Community {
+        version: Version,
+        community: Vec<u8>,
+    },
+    Usm {
+        user: UsmUser,
+        context_name: Vec<u8>,
+    },
+}
+
+/// The authoritative engine of an agent, discovered before sending SNMPv3 requests.
+#[derive(Debug)]
+struct Engine {
+    id: Vec<u8>,
+    boots: i64,
+    time: i64,
+    discovered_at: Instant,
+}
+
+impl Engine {
+    fn time(&self) -> i64 {
+        self.time + self.discovered_at.elapsed().as_secs() as i64
+    }
+}
+
+/// Sends requests to an agent, retrying them on timeouts.
+#[derive(Debug)]
+pub struct Client {
+    address: String,
+    credentials: Credentials,
+    timeout: Duration,
+    retries: usize,
+    max_repetitions: u32,
+    engine: Option<Engine>,
+    request_id: i32,
+}
+
+impl Client {
+    pub fn new(
+        address: String,
+        credentials: Credentials,
+        timeout: Duration,
+        retries: usize,
+        max_repetitions: u32,
+    ) -> Self {
+        Self {
+            address,
+            credentials,
+            timeout,
+            retries,
+            max_repetitions,
+            engine: None,
+            request_id: rand::random::<i32>() & 0x7fff_ffff,
+        }
+    }
+
+    /// Opens a socket connected to the agent.
+    pub async fn connect(&self) -> Result<UdpSocket, SnmpError> {
+        let address = lookup_host(&self.address)
+            .await
+            .context(IoSnafu)?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address found"))
+            .context(IoSnafu)?;
+        let local = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local).await.context(IoSnafu)?;
+        socket.connect(address).await.context(IoSnafu)?;
+        Ok(socket)
+    }
+
+    /// Gets the values of the given objects.
+    pub async fn get(
+        &mut self,
+        socket: &UdpSocket,
+        oids: &[Oid],
+    ) -> Result<Vec<VarBind>, SnmpError> {
+        let varbinds = oids
+            .iter()
+            .map(|oid| VarBind::new(oid.clone(), VarBindValue::Null))
+            .collect();
+        let response = self
+            .request(socket, tag::GET_REQUEST, 0, 0, varbinds)
+            .await?;
+        check_error_status(&response)?;
+        Ok(response.varbinds)
+    }
+
+    /// Gets the values of all the objects prefixed by `root`, such as the cells of a table column.
+    pub async fn walk(
+        &mut self,
+        socket: &UdpSocket,
+        root: &Oid,
+    ) -> Result<Vec<VarBind>, SnmpError> {
+        let mut walked = Vec::new();
+        let mut current = root.clone();
+        loop {
+            let varbinds = vec![VarBind::new(current.clone(), VarBindValue::Null)];
+            let response = match &self.credentials {
+                Credentials::Community {
+                    version: Version::V1,
+                    ..
+                } => {
+                    let response = self
+                        .request(socket, tag::GET_NEXT_REQUEST, 0, 0, varbinds)
+                        .await?;
+                    if response.error_status == NO_SUCH_NAME {
+                        return Ok(walked);
+                    }
+                    response
+                }
+                _ => {
+                    let max_repetitions = self.max_repetitions as i64;
+                    self.request(socket, tag::GET_BULK_REQUEST, 0, max_repetitions, varbinds)
+                        .await?
+                }
+            };
+            check_error_status(&response)?;
+            if response.varbinds.is_empty() {
+                return Ok(walked);
+            }
+
+            for varbind in response.varbinds {
+                // Agents must return increasing OIDs, which guarantees walks to terminate.
+                if !varbind.oid.starts_with(root)
+                    || varbind.oid <= current
+                    || varbind.value == VarBindValue::EndOfMibView
+                {
+                    return Ok(walked);
+                }
+                current = varbind.oid.clone();
+                walked.push(varbind);
+            }
+        }
+    }
+
+    async fn request(
+        &mut self,
+        socket: &UdpSocket,
+        pdu_tag: u8,
+        error_status: i64,
+        error_index: i64,
+        varbinds: Vec<VarBind>,
+    ) -> Result<Pdu, SnmpError> {
+        let mut pdu = Pdu {
+            request_id: 0,
+            error_status,
+            error_index,
+            varbinds,
+        };
+        if !matches!(self.credentials, Credentials::Usm { .. }) {
+            return self.exchange(socket, pdu_tag, &mut pdu).await;
+        }
+
+        if self.engine.is_none() {
+            self.discover(socket).await?;
+        }
+        match self.exchange(socket, pdu_tag, &mut pdu).await {
+            // The engine time is resynchronized when the agent rebooted or its clock drifted,
+            // as described in RFC 3414 section 4.
+            Err(SnmpError::Report { oid })
+                if oid == usm_stats_not_in_time_windows().to_string() =>
+            {
+                self.exchange(socket, pdu_tag, &mut pdu).await
+            }
+            result => result,
+        }
+    }
+
+    /// Discovers the authoritative engine of the agent.
+    async fn discover(&mut self, socket: &UdpSocket) -> Result<(), SnmpError> {
+        let mut pdu = Pdu {
+            request_id: 0,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![],
+        };
+        match self.exchange(socket, tag::GET_REQUEST, &mut pdu).await {
+            Err(SnmpError::Report { .. }) if self.engine.is_some() => Ok(()),
+            Err(error) => Err(error),
+            Err(SnmpError::Report { .. }) | Ok(_) => Err(SnmpError::Discovery),
+        }
+    }
+
+    /// Sends a request and waits for its response, retrying on timeouts.
+    async fn exchange(
+        &mut self,
+        socket: &UdpSocket,
+        pdu_tag: u8,
+        pdu: &mut Pdu,
+    ) -> Result<Pdu, SnmpError> {
+        let mut buf = vec![0; MAX_MESSAGE_SIZE];
+        for _ in 0..=self.retries {
+            self.request_id = self.request_id.wrapping_add(1) & 0x7fff_ffff;
+            pdu.request_id = self.request_id as i64;
+            let message = self.encode(pdu_tag, pdu)?;
+            socket.send(&message).await.context(IoSnafu)?;
+
+            let deadline = Instant::now() + self.timeout;
+            while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await
+            {
+                let byte_size = received.context(IoSnafu)?;
+                emit!(EndpointBytesReceived {
+                    byte_size,
+                    protocol: "udp",
+                    endpoint: &self.address,
+                });
+                // Responses to previous attempts are ignored.
+                if let Some(response) = self.decode(&buf[..byte_size], pdu.request_id)? {
+                    return Ok(response);
+                }
+            }
+        }
+        Err(SnmpError::Timeout {
+            attempts: self.retries + 1,
+        })
+    }
+
+    fn encode(&self, pdu_tag: u8, pdu: &Pdu) -> Result<Vec<u8>, SnmpError> {
+        match &self.credentials {
+            Credentials::Community { version, community } => {
+                Ok(encode_community_message(*version, community, pdu_tag, pdu))
+            }
+            Credentials::Usm { user, context_name } => {
+                let (engine_id, engine_boots, engine_time) = match &self.engine {
+                    Some(engine) => (engine.id.as_slice(), engine.boots, engine.time()),
+                    None => (&[][..], 0, 0),
+                };
+                encode_v3(
+                    &V3Header {
+                        msg_id: pdu.request_id,
+                        max_size: MAX_MESSAGE_SIZE as i64,
+                        reportable: true,
+                        engine_id,
+                        engine_boots,
+                        engine_time,
+                    },
+                    // Discovery requests are neither authenticated nor encrypted.
+                    self.engine.as_ref().map(|_| user),
+                    &ScopedPdu {
+                        context_engine_id: engine_id.to_vec(),
+                        context_name: context_name.clone(),
+                        pdu_tag,
+                        pdu: pdu.encode(),
+                    },
+                )
+                .context(UsmSnafu)
+            }
+        }
+    }
+
+    /// Decodes a response, returning `None` if it answers another request.
+    fn decode(&mut self, data: &[u8], request_id: i64) -> Result<Option<Pdu>, SnmpError> {
+        let (pdu_tag, pdu) = match (Message::decode(data)?, &self.credentials) {
+            (Message::Community { pdu_tag, pdu, .. }, Credentials::Community { .. }) => {
+                (pdu_tag, Pdu::decode(pdu)?)
+            }
+            (Message::V3(message), Credentials::Usm { user, .. }) => {
+                if message.msg_id != request_id {
+                    return Ok(None);
+                }
+                // Reports of failed authentication are not authenticated themselves.
+                let scoped = if message.flags & FLAG_AUTH == 0 {
+                    message.open_unauthenticated()
+                } else {
+                    message.open(user)
+                }
+                .context(UsmSnafu)?;
+
+                if scoped.pdu_tag == tag::REPORT {
+                    self.engine = Some(Engine {
+                        id: message.engine_id.to_vec(),
+                        boots: message.engine_boots,
+                        time: message.engine_time,
+                        discovered_at: Instant::now(),
+                    });
+                }
+                (scoped.pdu_tag, Pdu::decode(&scoped.pdu)?)
+            }
+            _ => {
+                return Err(MessageError::UnsupportedVersion {
+                    version: match &self.credentials {
+                        Credentials::Community { version, .. } => version.number(),
+                        Credentials::Usm { .. } => Version::V3.number(),
+                    },
+                }
+                .into())
+            }
+        };
+
+        if pdu.request_id != request_id {
+            return Ok(None);
+        }
+        match pdu_tag {
+            tag::RESPONSE => Ok(Some(pdu)),
+            tag::REPORT => Err(SnmpError::Report {
+                oid: pdu
+                    .varbinds
+                    .first()
+                    .map(|varbind| varbind.oid.to_string())
+                    .unwrap_or_default(),
+            }),
+            pdu_tag => Err(MessageError::UnexpectedPdu { pdu_tag }.into()),
+        }
+    }
+}
+
+fn check_error_status(pdu: &Pdu) -> Result<(), SnmpError> {
+    match pdu.error_status {
+        0 => Ok(()),
+        status => Err(SnmpError::ErrorStatus {
+            status,
+            index: pdu.error_index,
+        }),
+    }
+}
+
+fn usm_stats_not_in_time_windows() -> Oid {
+    Oid::from(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 2, 0])
+}
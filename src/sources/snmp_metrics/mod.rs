@@ -0,0 +1,861 @@
+use std::{collections::BTreeMap, net::Ipv4Addr, time::Instant};
+
+use chrono::Utc;
+use futures::{stream, StreamExt};
+use snafu::{ResultExt, Snafu};
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    internal_events::{
+        CollectionCompleted, SnmpMetricsEventsReceived, SnmpMetricsRequestError, StreamClosedError,
+    },
+    sources::util::snmp::{
+        ber::{BerError, Oid, VarBindValue},
+        usm::{UsmConfigError, UsmUser, UsmUserConfig},
+        Version,
+    },
+};
+
+mod client;
+use client::{Client, Credentials, SnmpError};
+
+#[derive(Debug, Snafu)]
+enum SnmpMetricsBuildError {
+    #[snafu(display("At least one metric or table must be configured"))]
+    NoObjects,
+    #[snafu(display("Invalid OID {:?}: {}", oid, source))]
+    InvalidOid { oid: String, source: BerError },
+    #[snafu(display("Target {:?} uses SNMPv3 but has no `user`", address))]
+    MissingUser { address: String },
+    #[snafu(display("Invalid SNMPv3 user of target {:?}: {}", address, source))]
+    InvalidUser {
+        address: String,
+        source: UsmConfigError,
+    },
+    #[snafu(display("`concurrency` must be greater than zero"))]
+    ZeroConcurrency,
+}
+
+/// Configuration for the `snmp_metrics` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpMetricsConfig {
+    /// The SNMP agents to poll.
+    targets: Vec<TargetConfig>,
+
+    /// The interval between scrapes, in seconds.
+    #[serde(default = "default_scrape_interval_secs")]
+    scrape_interval_secs: u64,
+
+    /// The time to wait for the response to a request, in seconds, before retrying it.
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+
+    /// The number of times a request is retried when it times out.
+    #[serde(default = "default_retries")]
+    retries: usize,
+
+    /// The maximum number of targets polled concurrently.
+    #[serde(default = "default_concurrency")]
+    concurrency: usize,
+
+    /// The maximum number of objects requested at once when walking tables.
+    ///
+    /// This is not used with SNMPv1 targets, whose tables are walked one object at a time.
+    #[serde(default = "default_max_repetitions")]
+    max_repetitions: u32,
+
+    /// Overrides the default namespace for the metrics emitted by the source.
+    ///
+    /// If set to an empty string, no namespace is added to the metrics.
+    ///
+    /// By default, `snmp` is used.
+    #[serde(default = "default_namespace")]
+    namespace: String,
+
+    /// The scalar objects to poll, each emitted as one metric per target.
+    #[serde(default = "default_metrics")]
+    metrics: Vec<ScalarConfig>,
+
+    /// The tables to poll, whose rows are emitted as metrics tagged with their index.
+    #[serde(default)]
+    tables: Vec<TableConfig>,
+}
+
+/// An SNMP agent to poll.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TargetConfig {
+    /// The address of the agent, including its port.
+    ///
+    /// For example, `192.0.2.1:161`.
+    address: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    version: SnmpVersion,
+
+    /// The community of SNMPv1 and SNMPv2c requests.
+    #[serde(default = "default_community")]
+    community: String,
+
+    #[configurable(derived)]
+    user: Option<UsmUserConfig>,
+
+    /// The context name of SNMPv3 requests.
+    #[serde(default)]
+    context_name: String,
+}
+
+/// The SNMP version used to poll a target.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SnmpVersion {
+    /// SNMPv1, authenticated with a community.
+    V1,
+
+    /// SNMPv2c, authenticated with a community.
+    #[derivative(Default)]
+    V2c,
+
+    /// SNMPv3, authenticated with the credentials of a user.
+    V3,
+}
+
+/// A scalar object mapped into a metric.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ScalarConfig {
+    /// The name of the metric.
+    name: String,
+
+    /// The numeric OID of the object instance, such as `1.3.6.1.2.1.1.3.0`.
+    oid: String,
+
+    /// The type of the metric.
+    #[serde(default)]
+    metric_type: MetricType,
+}
+
+/// A table whose columns are mapped into metrics or tags.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    /// The prefix of the names of the metrics of the table.
+    name: String,
+
+    /// The columns of the table to poll.
+    columns: Vec<ColumnConfig>,
+}
+
+/// A column of a table.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// The name of the column.
+    ///
+    /// Metrics are named after both the table and the column, like `interface_in_octets`.
+    name: String,
+
+    /// The numeric OID of the column, such as `1.3.6.1.2.1.2.2.1.10`.
+    oid: String,
+
+    /// How the cells of the column are mapped.
+    #[serde(default, rename = "type")]
+    column_type: ColumnType,
+}
+
+/// The type of the metrics objects are mapped into.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricType {
+    /// The objects are mapped into gauges.
+    #[derivative(Default)]
+    Gauge,
+
+    /// The objects are mapped into counters, for monotonically increasing values.
+    Counter,
+}
+
+/// How the cells of a column are mapped.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    /// The cells are mapped into gauges.
+    #[derivative(Default)]
+    Gauge,
+
+    /// The cells are mapped into counters, for monotonically increasing values.
+    Counter,
+
+    /// The cells are added as tags to the metrics of their row.
+    Tag,
+}
+
+impl Default for SnmpMetricsConfig {
+    fn default() -> Self {
+        Self {
+            targets: vec![TargetConfig {
+                address: "127.0.0.1:161".to_owned(),
+                version: SnmpVersion::V2c,
+                community: default_community(),
+                user: None,
+                context_name: String::new(),
+            }],
+            scrape_interval_secs: default_scrape_interval_secs(),
+            timeout_secs: default_timeout_secs(),
+            retries: default_retries(),
+            concurrency: default_concurrency(),
+            max_repetitions: default_max_repetitions(),
+            namespace: default_namespace(),
+            metrics: default_metrics(),
+            tables: vec![],
+        }
+    }
+}
+
+const fn default_scrape_interval_secs() -> u64 {
+    15
+}
+
+const fn default_timeout_secs() -> u64 {
+    5
+}
+
+const fn default_retries() -> usize {
+    1
+}
+
+const fn default_concurrency() -> usize {
+    10
+}
+
+const fn default_max_repetitions() -> u32 {
+    25
+}
+
+fn default_namespace() -> String {
+    "snmp".to_string()
+}
+
+fn default_community() -> String {
+    "public".to_string()
+}
+
+fn default_metrics() -> Vec<ScalarConfig> {
+    vec![ScalarConfig {
+        name: "sys_up_time".to_owned(),
+        oid: "1.3.6.1.2.1.1.3.0".to_owned(),
+        metric_type: MetricType::Gauge,
+    }]
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpMetricsConfig>("snmp_metrics")
+}
+
+impl_generate_config_from_default!(SnmpMetricsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_metrics")]
+impl SourceConfig for SnmpMetricsConfig {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<super::Source> {
+        if self.metrics.is_empty() && self.tables.is_empty() {
+            return Err(Box::new(SnmpMetricsBuildError::NoObjects));
+        }
+        if self.concurrency == 0 {
+            return Err(Box::new(SnmpMetricsBuildError::ZeroConcurrency));
+        }
+
+        let objects = Objects::new(self)?;
+        let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let timeout = time::Duration::from_secs(self.timeout_secs);
+        let mut targets = Vec::with_capacity(self.targets.len());
+        for target in self.targets.iter() {
+            let credentials =
+                match target.version {
+                    SnmpVersion::V1 | SnmpVersion::V2c => Credentials::Community {
+                        version: if target.version == SnmpVersion::V1 {
+                            Version::V1
+                        } else {
+                            Version::V2c
+                        },
+                        community: target.community.as_bytes().to_vec(),
+                    },
+                    SnmpVersion::V3 => {
+                        let user = target.user.as_ref().ok_or_else(|| {
+                            SnmpMetricsBuildError::MissingUser {
+                                address: target.address.clone(),
+                            }
+                        })?;
+                        Credentials::Usm {
+                            user: UsmUser::new(user).context(InvalidUserSnafu {
+                                address: target.address.clone(),
+                            })?,
+                            context_name: target.context_name.as_bytes().to_vec(),
+                        }
+                    }
+                };
+            targets.push(SnmpTarget {
+                client: Client::new(
+                    target.address.clone(),
+                    credentials,
+                    timeout,
+                    self.retries,
+                    self.max_repetitions,
+                ),
+                address: target.address.clone(),
+                namespace: namespace.clone(),
+            });
+        }
+
+        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        let concurrency = self.concurrency;
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let mut interval = IntervalStream::new(time::interval(duration)).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let start = Instant::now();
+                let metrics = stream::iter(targets.iter_mut())
+                    .map(|target| target.collect(&objects))
+                    .buffer_unordered(concurrency)
+                    .concat()
+                    .await;
+                emit!(CollectionCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let count = metrics.len();
+                if let Err(error) = cx.out.send_batch(metrics).await {
+                    emit!(StreamClosedError { error, count });
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp_metrics"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// The objects to poll, with their parsed OIDs.
+#[derive(Debug)]
+struct Objects {
+    scalars: Vec<(Oid, ScalarConfig)>,
+    tables: Vec<(String, Vec<(Oid, ColumnConfig)>)>,
+}
+
+impl Objects {
+    fn new(config: &SnmpMetricsConfig) -> Result<Self, SnmpMetricsBuildError> {
+        let parse = |oid: &String| {
+            oid.parse::<Oid>()
+                .context(InvalidOidSnafu { oid: oid.clone() })
+        };
+        Ok(Self {
+            scalars: config
+                .metrics
+                .iter()
+                .map(|scalar| Ok((parse(&scalar.oid)?, scalar.clone())))
+                .collect::<Result<_, _>>()?,
+            tables: config
+                .tables
+                .iter()
+                .map(|table| {
+                    let columns = table
+                        .columns
+                        .iter()
+                        .map(|column| Ok((parse(&column.oid)?, column.clone())))
+                        .collect::<Result<_, _>>()?;
+                    Ok((table.name.clone(), columns))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The cells of a table row.
+#[derive(Debug, Default)]
+struct Row<'a> {
+    tags: BTreeMap<String, String>,
+    values: Vec<(&'a ColumnConfig, f64)>,
+}
+
+#[derive(Debug)]
+struct SnmpTarget {
+    client: Client,
+    address: String,
+    namespace: Option<String>,
+}
+
+impl SnmpTarget {
+    async fn collect(&mut self, objects: &Objects) -> Vec<Metric> {
+        let (up_value, mut metrics) = match self.collect_metrics(objects).await {
+            Ok(metrics) => (1.0, metrics),
+            Err(error) => {
+                emit!(SnmpMetricsRequestError {
+                    error,
+                    endpoint: &self.address,
+                });
+                (0.0, vec![])
+            }
+        };
+
+        let byte_size = metrics.size_of();
+
+        metrics.push(self.create_metric(
+            "up".to_owned(),
+            BTreeMap::new(),
+            MetricValue::Gauge { value: up_value },
+        ));
+
+        emit!(SnmpMetricsEventsReceived {
+            count: metrics.len(),
+            byte_size,
+            endpoint: &self.address,
+        });
+
+        metrics
+    }
+
+    async fn collect_metrics(&mut self, objects: &Objects) -> Result<Vec<Metric>, SnmpError> {
+        let socket = self.client.connect().await?;
+        let mut metrics = Vec::new();
+
+        if !objects.scalars.is_empty() {
+            let oids = objects
+                .scalars
+                .iter()
+                .map(|(oid, _)| oid.clone())
+                .collect::<Vec<_>>();
+            let varbinds = self.client.get(&socket, &oids).await?;
+            for ((_, scalar), varbind) in objects.scalars.iter().zip(varbinds) {
+                if let Some(value) = numeric_value(&varbind.value) {
+                    let value = match scalar.metric_type {
+                        MetricType::Gauge => MetricValue::Gauge { value },
+                        MetricType::Counter => MetricValue::Counter { value },
+                    };
+                    metrics.push(self.create_metric(scalar.name.clone(), BTreeMap::new(), value));
+                }
+            }
+        }
+
+        for (name, columns) in objects.tables.iter() {
+            let mut rows = BTreeMap::<Vec<u32>, Row>::new();
+            for (oid, column) in columns {
+                for varbind in self.client.walk(&socket, oid).await? {
+                    let index = varbind.oid.arcs()[oid.arcs().len()..].to_vec();
+                    let row = rows.entry(index).or_default();
+                    match column.column_type {
+                        ColumnType::Tag => {
+                            if let Some(value) = tag_value(&varbind.value) {
+                                row.tags.insert(column.name.clone(), value);
+                            }
+                        }
+                        ColumnType::Gauge | ColumnType::Counter => {
+                            if let Some(value) = numeric_value(&varbind.value) {
+                                row.values.push((column, value));
+                            }
+                        }
+                    }
+                }
+            }
+
+            for (index, row) in rows {
+                let mut tags = row.tags;
+                tags.insert("index".to_owned(), Oid::from(index).to_string());
+                for (column, value) in row.values {
+                    let value = match column.column_type {
+                        ColumnType::Counter => MetricValue::Counter { value },
+                        _ => MetricValue::Gauge { value },
+                    };
+                    metrics.push(self.create_metric(
+                        format!("{}_{}", name, column.name),
+                        tags.clone(),
+                        value,
+                    ));
+                }
+            }
+        }
+
+        Ok(metrics)
+    }
+
+    fn create_metric(
+        &self,
+        name: String,
+        mut tags: BTreeMap<String, String>,
+        value: MetricValue,
+    ) -> Metric {
+        tags.insert("host".to_owned(), self.address.clone());
+        Metric::new(name, MetricKind::Absolute, value)
+            .with_namespace(self.namespace.clone())
+            .with_tags(Some(tags))
+            .with_timestamp(Some(Utc::now()))
+    }
+}
+
+/// Converts numeric values, and strings formatting numbers, into metric values.
+fn numeric_value(value: &VarBindValue) -> Option<f64> {
+    match value {
+        VarBindValue::Integer(value) => Some(*value as f64),
+        VarBindValue::Counter32(value)
+        | VarBindValue::Gauge32(value)
+        | VarBindValue::TimeTicks(value) => Some(*value as f64),
+        VarBindValue::Counter64(value) => Some(*value as f64),
+        VarBindValue::OctetString(value) => std::str::from_utf8(value).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn tag_value(value: &VarBindValue) -> Option<String> {
+    match value {
+        VarBindValue::Integer(value) => Some(value.to_string()),
+        VarBindValue::OctetString(value) => Some(
+            String::from_utf8_lossy(value)
+                .trim_end_matches('\0')
+                .to_owned(),
+        ),
+        VarBindValue::Oid(oid) => Some(oid.to_string()),
+        VarBindValue::IpAddress(address) => Some(Ipv4Addr::from(*address).to_string()),
+        VarBindValue::Counter32(value)
+        | VarBindValue::Gauge32(value)
+        | VarBindValue::TimeTicks(value) => Some(value.to_string()),
+        VarBindValue::Counter64(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use tokio::{net::UdpSocket, time::Duration};
+
+    use super::*;
+    use crate::{
+        sources::util::snmp::{
+            ber::{tag, VarBind},
+            encode_community_message,
+            usm::{encode_v3, AuthProtocol, PrivProtocol, ScopedPdu, V3Header},
+            Message, Pdu,
+        },
+        test_util::components::{run_and_assert_source_compliance, PULL_SOURCE_TAGS},
+    };
+
+    const ENGINE_ID: &[u8] = b"\x80\x00\x1f\x88\x04agent";
+
+    fn oid(oid: &str) -> Oid {
+        oid.parse().unwrap()
+    }
+
+    fn mib() -> BTreeMap<Oid, VarBindValue> {
+        BTreeMap::from([
+            (oid("1.3.6.1.2.1.1.3.0"), VarBindValue::TimeTicks(4200)),
+            (
+                oid("1.3.6.1.2.1.2.2.1.2.1"),
+                VarBindValue::OctetString(b"lo".to_vec()),
+            ),
+            (
+                oid("1.3.6.1.2.1.2.2.1.2.2"),
+                VarBindValue::OctetString(b"eth0".to_vec()),
+            ),
+            (oid("1.3.6.1.2.1.2.2.1.10.1"), VarBindValue::Counter32(100)),
+            (oid("1.3.6.1.2.1.2.2.1.10.2"), VarBindValue::Counter32(200)),
+            (
+                oid("1.3.6.1.2.1.31.1.1.1.1.1"),
+                VarBindValue::OctetString(b"lo".to_vec()),
+            ),
+        ])
+    }
+
+    fn respond(mib: &BTreeMap<Oid, VarBindValue>, pdu_tag: u8, request: Pdu) -> Pdu {
+        let varbinds = match pdu_tag {
+            tag::GET_REQUEST => request
+                .varbinds
+                .into_iter()
+                .map(|varbind| {
+                    let value = mib
+                        .get(&varbind.oid)
+                        .cloned()
+                        .unwrap_or(VarBindValue::NoSuchObject);
+                    VarBind::new(varbind.oid, value)
+                })
+                .collect(),
+            tag::GET_BULK_REQUEST => {
+                let start = request.varbinds[0].oid.clone();
+                let varbinds = mib
+                    .range((Bound::Excluded(start.clone()), Bound::Unbounded))
+                    .take(request.error_index as usize)
+                    .map(|(oid, value)| VarBind::new(oid.clone(), value.clone()))
+                    .collect::<Vec<_>>();
+                if varbinds.is_empty() {
+                    vec![VarBind::new(start, VarBindValue::EndOfMibView)]
+                } else {
+                    varbinds
+                }
+            }
+            pdu_tag => panic!("unexpected PDU type {:#04x}", pdu_tag),
+        };
+        Pdu {
+            request_id: request.request_id,
+            error_status: 0,
+            error_index: 0,
+            varbinds,
+        }
+    }
+
+    /// Runs an agent answering requests from `mib`.
+    async fn agent(user: Option<UsmUserConfig>) -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap().to_string();
+        let user = user.map(|user| UsmUser::new(&user).unwrap());
+        let mib = mib();
+
+        tokio::spawn(async move {
+            let mut buf = vec![0; 65_507];
+            loop {
+                let (size, peer) = socket.recv_from(&mut buf).await.unwrap();
+                let response = match Message::decode(&buf[..size]).unwrap() {
+                    Message::Community {
+                        version,
+                        community,
+                        pdu_tag,
+                        pdu,
+                    } => {
+                        assert_eq!(community, b"public");
+                        let response = respond(&mib, pdu_tag, Pdu::decode(pdu).unwrap());
+                        encode_community_message(version, community, tag::RESPONSE, &response)
+                    }
+                    Message::V3(message) => {
+                        let header = V3Header {
+                            msg_id: message.msg_id,
+                            max_size: 65_507,
+                            reportable: false,
+                            engine_id: ENGINE_ID,
+                            engine_boots: 3,
+                            engine_time: 1000,
+                        };
+                        let user = user.as_ref().unwrap();
+                        if message.engine_id.is_empty() {
+                            let scoped = message.open_unauthenticated().unwrap();
+                            let request = Pdu::decode(&scoped.pdu).unwrap();
+                            let report = Pdu {
+                                request_id: request.request_id,
+                                error_status: 0,
+                                error_index: 0,
+                                varbinds: vec![VarBind::new(
+                                    oid("1.3.6.1.6.3.15.1.1.4.0"),
+                                    VarBindValue::Counter32(1),
+                                )],
+                            };
+                            let scoped = ScopedPdu {
+                                context_engine_id: ENGINE_ID.to_vec(),
+                                context_name: vec![],
+                                pdu_tag: tag::REPORT,
+                                pdu: report.encode(),
+                            };
+                            encode_v3(&header, None, &scoped).unwrap()
+                        } else {
+                            assert_eq!(message.engine_id, ENGINE_ID);
+                            assert_eq!(message.engine_boots, 3);
+                            let scoped = message.open(user).unwrap();
+                            let request = Pdu::decode(&scoped.pdu).unwrap();
+                            let response = respond(&mib, scoped.pdu_tag, request);
+                            let scoped = ScopedPdu {
+                                pdu_tag: tag::RESPONSE,
+                                pdu: response.encode(),
+                                ..scoped
+                            };
+                            encode_v3(&header, Some(user), &scoped).unwrap()
+                        }
+                    }
+                };
+                socket.send_to(&response, peer).await.unwrap();
+            }
+        });
+
+        address
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpMetricsConfig>();
+    }
+
+    #[tokio::test]
+    async fn polls_scalars_and_tables() {
+        let address = agent(None).await;
+        let config = SnmpMetricsConfig {
+            targets: vec![TargetConfig {
+                address: address.clone(),
+                version: SnmpVersion::V2c,
+                community: default_community(),
+                user: None,
+                context_name: String::new(),
+            }],
+            scrape_interval_secs: 1,
+            max_repetitions: 1,
+            tables: vec![TableConfig {
+                name: "interface".to_owned(),
+                columns: vec![
+                    ColumnConfig {
+                        name: "descr".to_owned(),
+                        oid: "1.3.6.1.2.1.2.2.1.2".to_owned(),
+                        column_type: ColumnType::Tag,
+                    },
+                    ColumnConfig {
+                        name: "in_octets".to_owned(),
+                        oid: "1.3.6.1.2.1.2.2.1.10".to_owned(),
+                        column_type: ColumnType::Counter,
+                    },
+                ],
+            }],
+            ..Default::default()
+        };
+
+        let events =
+            run_and_assert_source_compliance(config, Duration::from_secs(1), &PULL_SOURCE_TAGS)
+                .await;
+        let metrics = events
+            .into_iter()
+            .map(|event| event.into_metric())
+            .collect::<Vec<_>>();
+
+        let uptime = metrics
+            .iter()
+            .find(|metric| metric.name() == "sys_up_time")
+            .expect("missing uptime metric");
+        assert_eq!(uptime.namespace(), Some("snmp"));
+        assert_eq!(uptime.value(), &MetricValue::Gauge { value: 4200.0 });
+        assert_eq!(uptime.tag_value("host"), Some(address));
+
+        let in_octets = metrics
+            .iter()
+            .filter(|metric| metric.name() == "interface_in_octets")
+            .map(|metric| {
+                (
+                    metric.tag_value("index").unwrap(),
+                    metric.tag_value("descr").unwrap(),
+                    metric.value().clone(),
+                )
+            })
+            .take(2)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            in_octets,
+            vec![
+                (
+                    "1".to_owned(),
+                    "lo".to_owned(),
+                    MetricValue::Counter { value: 100.0 }
+                ),
+                (
+                    "2".to_owned(),
+                    "eth0".to_owned(),
+                    MetricValue::Counter { value: 200.0 }
+                ),
+            ]
+        );
+
+        let up = metrics
+            .iter()
+            .find(|metric| metric.name() == "up")
+            .expect("missing up metric");
+        assert_eq!(up.value(), &MetricValue::Gauge { value: 1.0 });
+    }
+
+    #[tokio::test]
+    async fn polls_with_usm() {
+        let user = UsmUserConfig {
+            name: "vector".to_owned(),
+            auth_protocol: Some(AuthProtocol::Sha256),
+            auth_password: Some("authpassword".to_owned()),
+            priv_protocol: Some(PrivProtocol::Aes),
+            priv_password: Some("privpassword".to_owned()),
+        };
+        let address = agent(Some(user.clone())).await;
+        let mut client = Client::new(
+            address,
+            Credentials::Usm {
+                user: UsmUser::new(&user).unwrap(),
+                context_name: vec![],
+            },
+            Duration::from_secs(1),
+            0,
+            10,
+        );
+        let socket = client.connect().await.unwrap();
+
+        let varbinds = client
+            .get(&socket, &[oid("1.3.6.1.2.1.1.3.0")])
+            .await
+            .unwrap();
+        assert_eq!(varbinds[0].value, VarBindValue::TimeTicks(4200));
+
+        let varbinds = client
+            .walk(&socket, &oid("1.3.6.1.2.1.2.2.1.2"))
+            .await
+            .unwrap();
+        assert_eq!(
+            varbinds,
+            vec![
+                VarBind::new(
+                    oid("1.3.6.1.2.1.2.2.1.2.1"),
+                    VarBindValue::OctetString(b"lo".to_vec())
+                ),
+                VarBind::new(
+                    oid("1.3.6.1.2.1.2.2.1.2.2"),
+                    VarBindValue::OctetString(b"eth0".to_vec())
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_unreachable_targets() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut client = Client::new(
+            socket.local_addr().unwrap().to_string(),
+            Credentials::Community {
+                version: Version::V2c,
+                community: b"public".to_vec(),
+            },
+            Duration::from_millis(50),
+            1,
+            10,
+        );
+        let connected = client.connect().await.unwrap();
+        assert!(matches!(
+            client.get(&connected, &[oid("1.3.6.1.2.1.1.3.0")]).await,
+            Err(SnmpError::Timeout { attempts: 2 })
+        ));
+    }
+}
@@ -0,0 +1,926 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Instant,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use snafu::{ResultExt, Snafu};
+use tokio::net::UdpSocket;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use super::util::snmp::{
+    ber::{tag, BerError, Oid, VarBind, VarBindValue},
+    encode_community_message,
+    mib::{Mib, MibError},
+    usm::{
+        encode_v3, ScopedPdu, UsmConfigError, UsmError, UsmUser, UsmUserConfig, V3Header,
+        V3Message, FLAG_REPORTABLE,
+    },
+    Message, MessageError, Pdu, TrapV1Pdu, Version,
+};
+use crate::{
+    config::{
+        log_schema, DataType, Output, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        EventsReceived, SnmpTrapParseError, SnmpTrapRejectedError, SnmpTrapResponseError,
+        SocketBytesReceived, SocketMode, StreamClosedError, UdpSocketError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+/// The largest message that can be received over UDP.
+const MAX_MESSAGE_SIZE: usize = 65_507;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to load MIB files: {}", source))]
+    LoadMibs { source: MibError },
+    #[snafu(display("Invalid SNMPv3 user: {}", source))]
+    InvalidUser { source: UsmConfigError },
+    #[snafu(display("The engine ID must be a hexadecimal string of 5 to 32 bytes"))]
+    InvalidEngineId,
+}
+
+#[derive(Debug, Snafu)]
+enum TrapError {
+    #[snafu(display("{}", source))]
+    InvalidMessage { source: MessageError },
+    #[snafu(display("Unknown community {:?}", community))]
+    UnknownCommunity { community: String },
+    #[snafu(display("Authentication failed: {}", source))]
+    Authentication { source: UsmError },
+    #[snafu(display("SNMPv3 informs cannot be acknowledged without `engine_id`"))]
+    MissingEngineId,
+    #[snafu(display("SNMPv3 inform sent to unknown engine ID {:?}", engine_id))]
+    UnknownEngineId { engine_id: String },
+    #[snafu(display("Failed to encode response: {}", source))]
+    EncodeResponse { source: UsmError },
+}
+
+impl From<MessageError> for TrapError {
+    fn from(source: MessageError) -> Self {
+        Self::InvalidMessage { source }
+    }
+}
+
+impl From<BerError> for TrapError {
+    fn from(source: BerError) -> Self {
+        Self::InvalidMessage {
+            source: source.into(),
+        }
+    }
+}
+
+/// Configuration for the `snmp_trap` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTrapConfig {
+    /// The address to listen for traps and informs on.
+    #[serde(default = "default_address")]
+    address: SocketAddr,
+
+    /// A list of MIB files, or directories of MIB files, used to name OIDs.
+    ///
+    /// The OIDs of varbinds, and their enumerated values, are converted into names using the
+    /// objects defined in these MIB modules. Objects of modules missing from this list are left
+    /// numeric.
+    #[serde(default)]
+    mib_paths: Vec<PathBuf>,
+
+    /// The communities accepted in SNMPv1 and SNMPv2c messages.
+    ///
+    /// If empty, all communities are accepted.
+    #[serde(default)]
+    communities: Vec<String>,
+
+    /// The users accepted in SNMPv3 messages.
+    ///
+    /// Messages must use the security level of their user: authenticated if the user has an
+    /// authentication protocol, and encrypted if it also has a privacy protocol.
+    #[serde(default)]
+    users: Vec<UsmUserConfig>,
+
+    /// The SNMPv3 engine ID of Vector, as a hexadecimal string.
+    ///
+    /// Vector is the authoritative engine of the SNMPv3 informs it receives, so their senders
+    /// must either be configured with this engine ID, or discover it. If not set, SNMPv3 informs
+    /// are rejected, while SNMPv3 traps are accepted.
+    engine_id: Option<String>,
+
+    /// Overrides the name of the log field used to add the peer host to each event.
+    ///
+    /// The value will be the peer host's address, i.e. `1.2.3.4`.
+    ///
+    /// By default, the [global `host_key` option](https://vector.dev/docs/reference/configuration//global-options#log_schema.host_key) is used.
+    host_key: Option<String>,
+
+    /// The size, in bytes, of the receive buffer used for the listening socket.
+    ///
+    /// This should not typically needed to be changed.
+    receive_buffer_bytes: Option<usize>,
+}
+
+fn default_address() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 162)
+}
+
+impl Default for SnmpTrapConfig {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            mib_paths: vec![],
+            communities: vec![],
+            users: vec![],
+            engine_id: None,
+            host_key: None,
+            receive_buffer_bytes: None,
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpTrapConfig>("snmp_trap")
+}
+
+impl_generate_config_from_default!(SnmpTrapConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let receiver = TrapReceiver::new(self)?;
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+
+        Ok(Box::pin(run(
+            self.address,
+            self.receive_buffer_bytes,
+            receiver,
+            host_key,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp_trap"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn run(
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    mut receiver: TrapReceiver,
+    host_key: String,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&address)
+        .await
+        .expect("Failed to bind to UDP listener socket");
+
+    if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %address);
+
+    let mut buf = vec![0; MAX_MESSAGE_SIZE];
+    loop {
+        let (byte_size, peer) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(received) => received,
+                Err(error) => {
+                    emit!(UdpSocketError { error });
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+        emit!(SocketBytesReceived {
+            mode: SocketMode::Udp,
+            byte_size,
+        });
+
+        let received = match receiver.receive(&buf[..byte_size]) {
+            Ok(received) => received,
+            Err(error @ TrapError::InvalidMessage { .. }) => {
+                emit!(SnmpTrapParseError { error, peer });
+                continue;
+            }
+            Err(error @ TrapError::EncodeResponse { .. }) => {
+                emit!(SnmpTrapResponseError { error, peer });
+                continue;
+            }
+            Err(error) => {
+                emit!(SnmpTrapRejectedError { error, peer });
+                continue;
+            }
+        };
+
+        if let Some(response) = received.response {
+            if let Err(error) = socket.send_to(&response, peer).await {
+                emit!(SnmpTrapResponseError { error, peer });
+            }
+        }
+
+        if let Some(mut log) = received.log {
+            log.insert(log_schema().source_type_key(), Bytes::from("snmp_trap"));
+            log.insert(log_schema().timestamp_key(), Utc::now());
+            log.insert(host_key.as_str(), peer.ip().to_string());
+
+            emit!(EventsReceived {
+                count: 1,
+                byte_size: log.size_of(),
+            });
+
+            tokio::select! {
+                result = out.send_event(Event::from(log)) => {
+                    if let Err(error) = result {
+                        emit!(StreamClosedError { error, count: 1 });
+                        return Ok(());
+                    }
+                }
+                _ = &mut shutdown => return Ok(()),
+            }
+        }
+    }
+}
+
+/// The event and response resulting from a received message.
+#[derive(Debug, Default)]
+struct Received {
+    log: Option<LogEvent>,
+    response: Option<Vec<u8>>,
+}
+
+/// Authenticates and decodes traps and informs, and builds the responses to informs.
+struct TrapReceiver {
+    mib: Mib,
+    communities: Vec<Vec<u8>>,
+    users: HashMap<Vec<u8>, UsmUser>,
+    engine_id: Option<Vec<u8>>,
+    started: Instant,
+    unknown_engine_ids: u32,
+}
+
+impl TrapReceiver {
+    fn new(config: &SnmpTrapConfig) -> crate::Result<Self> {
+        let mib = Mib::load(&config.mib_paths).context(LoadMibsSnafu)?;
+        let users = config
+            .users
+            .iter()
+            .map(|user| UsmUser::new(user).map(|decoded| (user.name.as_bytes().to_vec(), decoded)))
+            .collect::<Result<_, _>>()
+            .context(InvalidUserSnafu)?;
+        let engine_id = config
+            .engine_id
+            .as_ref()
+            .map(|engine_id| match hex::decode(engine_id) {
+                Ok(engine_id) if (5..=32).contains(&engine_id.len()) => Ok(engine_id),
+                _ => Err(BuildError::InvalidEngineId),
+            })
+            .transpose()?;
+
+        Ok(Self {
+            mib,
+            communities: config
+                .communities
+                .iter()
+                .map(|community| community.as_bytes().to_vec())
+                .collect(),
+            users,
+            engine_id,
+            started: Instant::now(),
+            unknown_engine_ids: 0,
+        })
+    }
+
+    fn receive(&mut self, data: &[u8]) -> Result<Received, TrapError> {
+        match Message::decode(data)? {
+            Message::Community {
+                version,
+                community,
+                pdu_tag,
+                pdu,
+            } => self.receive_community(version, community, pdu_tag, pdu),
+            Message::V3(message) => self.receive_v3(&message),
+        }
+    }
+
+    fn receive_community(
+        &self,
+        version: Version,
+        community: &[u8],
+        pdu_tag: u8,
+        pdu: &[u8],
+    ) -> Result<Received, TrapError> {
+        if !self.communities.is_empty() && !self.communities.iter().any(|c| c == community) {
+            return Err(TrapError::UnknownCommunity {
+                community: String::from_utf8_lossy(community).into_owned(),
+            });
+        }
+
+        let mut fields = BTreeMap::new();
+        fields.insert("version".to_owned(), Value::from(version.as_str()));
+        fields.insert(
+            "community".to_owned(),
+            Value::from(String::from_utf8_lossy(community).into_owned()),
+        );
+
+        match (version, pdu_tag) {
+            (Version::V1, tag::TRAP_V1) => {
+                self.insert_v1_trap(&mut fields, TrapV1Pdu::decode(pdu)?);
+                Ok(Received {
+                    log: Some(LogEvent::from(fields)),
+                    response: None,
+                })
+            }
+            (Version::V2c, tag::TRAP_V2 | tag::INFORM_REQUEST) => {
+                let pdu = Pdu::decode(pdu)?;
+                let response = (pdu_tag == tag::INFORM_REQUEST).then(|| {
+                    encode_community_message(version, community, tag::RESPONSE, &response_pdu(&pdu))
+                });
+                self.insert_pdu(&mut fields, pdu_tag, pdu);
+                Ok(Received {
+                    log: Some(LogEvent::from(fields)),
+                    response,
+                })
+            }
+            (_, pdu_tag) => Err(MessageError::UnexpectedPdu { pdu_tag }.into()),
+        }
+    }
+
+    fn receive_v3(&mut self, message: &V3Message<'_>) -> Result<Received, TrapError> {
+        if message.engine_id.is_empty() {
+            return self.report_engine_id(message);
+        }
+
+        let user = self
+            .users
+            .get(message.user_name)
+            .ok_or_else(|| UsmError::UnknownUser {
+                user: String::from_utf8_lossy(message.user_name).into_owned(),
+            })
+            .context(AuthenticationSnafu)?;
+        let scoped = message.open(user).map_err(|error| match error {
+            UsmError::InvalidMessage { source } => MessageError::from(source).into(),
+            source => TrapError::Authentication { source },
+        })?;
+
+        let mut fields = BTreeMap::new();
+        fields.insert("version".to_owned(), Value::from(Version::V3.as_str()));
+        fields.insert("user".to_owned(), Value::from(user.name().to_owned()));
+
+        match scoped.pdu_tag {
+            tag::TRAP_V2 => {
+                self.insert_pdu(&mut fields, scoped.pdu_tag, Pdu::decode(&scoped.pdu)?);
+                Ok(Received {
+                    log: Some(LogEvent::from(fields)),
+                    response: None,
+                })
+            }
+            tag::INFORM_REQUEST => {
+                // Informs are sent to Vector as the authoritative engine.
+                let engine_id = self.engine_id.as_ref().ok_or(TrapError::MissingEngineId)?;
+                if message.engine_id != engine_id.as_slice() {
+                    return Err(TrapError::UnknownEngineId {
+                        engine_id: hex::encode(message.engine_id),
+                    });
+                }
+
+                let pdu = Pdu::decode(&scoped.pdu)?;
+                let response = encode_v3(
+                    &self.header(message.msg_id, engine_id),
+                    Some(user),
+                    &ScopedPdu {
+                        context_engine_id: scoped.context_engine_id,
+                        context_name: scoped.context_name,
+                        pdu_tag: tag::RESPONSE,
+                        pdu: response_pdu(&pdu).encode(),
+                    },
+                )
+                .context(EncodeResponseSnafu)?;
+                self.insert_pdu(&mut fields, scoped.pdu_tag, pdu);
+                Ok(Received {
+                    log: Some(LogEvent::from(fields)),
+                    response: Some(response),
+                })
+            }
+            pdu_tag => Err(MessageError::UnexpectedPdu { pdu_tag }.into()),
+        }
+    }
+
+    /// Answers engine ID discovery requests, as described in RFC 3414 section 4.
+    fn report_engine_id(&mut self, message: &V3Message<'_>) -> Result<Received, TrapError> {
+        if message.flags & FLAG_REPORTABLE == 0 {
+            return Ok(Received::default());
+        }
+        let engine_id = self.engine_id.as_ref().ok_or(TrapError::MissingEngineId)?;
+        let scoped = message
+            .open_unauthenticated()
+            .map_err(|error| match error {
+                UsmError::InvalidMessage { source } => MessageError::from(source).into(),
+                source => TrapError::Authentication { source },
+            })?;
+        let request = Pdu::decode(&scoped.pdu)?;
+
+        self.unknown_engine_ids = self.unknown_engine_ids.wrapping_add(1);
+        let report = Pdu {
+            request_id: request.request_id,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![VarBind::new(
+                // usmStatsUnknownEngineIDs.0
+                Oid::from(vec![1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0]),
+                VarBindValue::Counter32(self.unknown_engine_ids),
+            )],
+        };
+        let response = encode_v3(
+            &self.header(message.msg_id, engine_id),
+            None,
+            &ScopedPdu {
+                context_engine_id: engine_id.clone(),
+                context_name: vec![],
+                pdu_tag: tag::REPORT,
+                pdu: report.encode(),
+            },
+        )
+        .context(EncodeResponseSnafu)?;
+        Ok(Received {
+            log: None,
+            response: Some(response),
+        })
+    }
+
+    fn header<'a>(&self, msg_id: i64, engine_id: &'a [u8]) -> V3Header<'a> {
+        V3Header {
+            msg_id,
+            max_size: MAX_MESSAGE_SIZE as i64,
+            reportable: false,
+            engine_id,
+            engine_boots: 1,
+            engine_time: self.started.elapsed().as_secs() as i64,
+        }
+    }
+
+    fn insert_v1_trap(&self, fields: &mut BTreeMap<String, Value>, trap: TrapV1Pdu) {
+        let trap_oid = trap.trap_oid();
+        fields.insert("pdu_type".to_owned(), Value::from("trap"));
+        fields.insert("trap_oid".to_owned(), Value::from(trap_oid.to_string()));
+        fields.insert(
+            "trap_name".to_owned(),
+            Value::from(self.mib.name(&trap_oid)),
+        );
+        fields.insert(
+            "enterprise".to_owned(),
+            Value::from(self.mib.name(&trap.enterprise)),
+        );
+        fields.insert(
+            "agent_address".to_owned(),
+            Value::from(Ipv4Addr::from(trap.agent_address).to_string()),
+        );
+        fields.insert("generic_trap".to_owned(), Value::from(trap.generic_trap));
+        fields.insert("specific_trap".to_owned(), Value::from(trap.specific_trap));
+        fields.insert("uptime".to_owned(), Value::from(trap.timestamp as i64));
+        fields.insert("varbinds".to_owned(), self.convert_varbinds(trap.varbinds));
+    }
+
+    fn insert_pdu(&self, fields: &mut BTreeMap<String, Value>, pdu_tag: u8, pdu: Pdu) {
+        let pdu_type = if pdu_tag == tag::INFORM_REQUEST {
+            "inform"
+        } else {
+            "trap"
+        };
+        fields.insert("pdu_type".to_owned(), Value::from(pdu_type));
+
+        // The first two varbinds of SNMPv2 notifications are `sysUpTime.0` and `snmpTrapOID.0`.
+        let mut varbinds = pdu.varbinds.into_iter().peekable();
+        if let Some(VarBind {
+            value: VarBindValue::TimeTicks(uptime),
+            ..
+        }) = varbinds.next_if(|varbind| varbind.oid == sys_up_time())
+        {
+            fields.insert("uptime".to_owned(), Value::from(uptime as i64));
+        }
+        if let Some(VarBind {
+            value: VarBindValue::Oid(trap_oid),
+            ..
+        }) = varbinds.next_if(|varbind| varbind.oid == snmp_trap_oid())
+        {
+            fields.insert("trap_oid".to_owned(), Value::from(trap_oid.to_string()));
+            fields.insert(
+                "trap_name".to_owned(),
+                Value::from(self.mib.name(&trap_oid)),
+            );
+        }
+        fields.insert(
+            "varbinds".to_owned(),
+            self.convert_varbinds(varbinds.collect()),
+        );
+    }
+
+    /// Converts varbinds into an object keyed by the names of their OIDs.
+    fn convert_varbinds(&self, varbinds: Vec<VarBind>) -> Value {
+        Value::Object(
+            varbinds
+                .into_iter()
+                .map(|varbind| {
+                    let value = self.convert_value(&varbind.oid, varbind.value);
+                    (self.mib.name(&varbind.oid), value)
+                })
+                .collect(),
+        )
+    }
+
+    fn convert_value(&self, oid: &Oid, value: VarBindValue) -> Value {
+        match value {
+            VarBindValue::Integer(value) => self
+                .mib
+                .lookup(oid)
+                .and_then(|(object, _)| object.enums.get(&value))
+                .map(|name| Value::from(name.clone()))
+                .unwrap_or_else(|| Value::from(value)),
+            VarBindValue::OctetString(value) => octet_string_value(value),
+            VarBindValue::Oid(value) => Value::from(self.mib.name(&value)),
+            VarBindValue::IpAddress(address) => Value::from(Ipv4Addr::from(address).to_string()),
+            VarBindValue::Counter32(value)
+            | VarBindValue::Gauge32(value)
+            | VarBindValue::TimeTicks(value) => Value::from(value as i64),
+            VarBindValue::Counter64(value) => i64::try_from(value)
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::from(value as f64)),
+            VarBindValue::Opaque(value) => Value::from(hex_string(&value)),
+            VarBindValue::Null
+            | VarBindValue::NoSuchObject
+            | VarBindValue::NoSuchInstance
+            | VarBindValue::EndOfMibView => Value::Null,
+        }
+    }
+}
+
+fn sys_up_time() -> Oid {
+    Oid::from(vec![1, 3, 6, 1, 2, 1, 1, 3, 0])
+}
+
+fn snmp_trap_oid() -> Oid {
+    Oid::from(vec![1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0])
+}
+
+/// Informs are acknowledged by returning their varbinds.
+fn response_pdu(inform: &Pdu) -> Pdu {
+    Pdu {
+        request_id: inform.request_id,
+        error_status: 0,
+        error_index: 0,
+        varbinds: inform.varbinds.clone(),
+    }
+}
+
+/// Octet strings are kept as text when printable, and otherwise formatted as hexadecimal bytes,
+/// like `00:1a:2b`.
+fn octet_string_value(value: Vec<u8>) -> Value {
+    match String::from_utf8(value) {
+        Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+            Value::from(text)
+        }
+        Ok(text) => Value::from(hex_string(text.as_bytes())),
+        Err(error) => Value::from(hex_string(error.as_bytes())),
+    }
+}
+
+fn hex_string(value: &[u8]) -> String {
+    value
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sources::util::snmp::{
+            ber::Reader,
+            usm::{AuthProtocol, PrivProtocol},
+        },
+        test_util::{
+            components::{assert_source_compliance, SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS},
+            next_addr,
+        },
+    };
+
+    const ENGINE_ID: &str = "80001f8804766563746f72";
+
+    fn receiver(communities: &[&str], users: Vec<UsmUserConfig>) -> TrapReceiver {
+        TrapReceiver::new(&SnmpTrapConfig {
+            communities: communities.iter().map(|c| c.to_string()).collect(),
+            users,
+            engine_id: Some(ENGINE_ID.to_owned()),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn link_down() -> Pdu {
+        Pdu {
+            request_id: 42,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![
+                VarBind::new(sys_up_time(), VarBindValue::TimeTicks(1234)),
+                VarBind::new(
+                    snmp_trap_oid(),
+                    VarBindValue::Oid("1.3.6.1.6.3.1.1.5.3".parse().unwrap()),
+                ),
+                VarBind::new(
+                    "1.3.6.1.2.1.2.2.1.1.2".parse().unwrap(),
+                    VarBindValue::Integer(2),
+                ),
+                VarBind::new(
+                    "1.3.6.1.2.1.2.2.1.2.2".parse().unwrap(),
+                    VarBindValue::OctetString(b"eth1".to_vec()),
+                ),
+                VarBind::new(
+                    "1.3.6.1.4.1.99999.1".parse().unwrap(),
+                    VarBindValue::OctetString(vec![0x00, 0x1a, 0x2b]),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    #[test]
+    fn decodes_v2c_traps() {
+        let mut receiver = receiver(&["public"], vec![]);
+        let message = encode_community_message(Version::V2c, b"public", tag::TRAP_V2, &link_down());
+        let received = receiver.receive(&message).unwrap();
+        assert!(received.response.is_none());
+
+        let log = received.log.unwrap();
+        assert_eq!(log["version"], "2c".into());
+        assert_eq!(log["community"], "public".into());
+        assert_eq!(log["pdu_type"], "trap".into());
+        assert_eq!(log["uptime"], 1234.into());
+        assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.3".into());
+        assert_eq!(log["trap_name"], "IF-MIB::linkDown".into());
+        assert_eq!(
+            log.get("varbinds"),
+            Some(&Value::Object(BTreeMap::from([
+                ("SNMPv2-SMI::mib-2.2.2.1.1.2".into(), Value::from(2)),
+                ("SNMPv2-SMI::mib-2.2.2.1.2.2".into(), Value::from("eth1")),
+                (
+                    "SNMPv2-SMI::enterprises.99999.1".into(),
+                    Value::from("00:1a:2b")
+                ),
+            ])))
+        );
+    }
+
+    #[test]
+    fn names_varbinds_with_mibs() {
+        let mut receiver = receiver(&[], vec![]);
+        receiver.mib = Mib::parse([r#"
+IF-MIB DEFINITIONS ::= BEGIN
+ifEntry OBJECT IDENTIFIER ::= { mib-2 2 2 1 }
+ifIndex OBJECT-TYPE
+    SYNTAX      Integer32
+    ::= { ifEntry 1 }
+ifDescr OBJECT-TYPE
+    SYNTAX      DisplayString
+    ::= { ifEntry 2 }
+ifAdminStatus OBJECT-TYPE
+    SYNTAX  INTEGER { up(1), down(2), testing(3) }
+    ::= { ifEntry 7 }
+END
+"#]);
+        let mut pdu = link_down();
+        pdu.varbinds.push(VarBind::new(
+            "1.3.6.1.2.1.2.2.1.7.2".parse().unwrap(),
+            VarBindValue::Integer(2),
+        ));
+        let message = encode_community_message(Version::V2c, b"private", tag::TRAP_V2, &pdu);
+        let log = receiver.receive(&message).unwrap().log.unwrap();
+
+        let varbinds = log["varbinds"].as_object().unwrap();
+        assert_eq!(varbinds["IF-MIB::ifIndex.2"], Value::from(2));
+        assert_eq!(varbinds["IF-MIB::ifDescr.2"], Value::from("eth1"));
+        assert_eq!(varbinds["IF-MIB::ifAdminStatus.2"], Value::from("down"));
+    }
+
+    #[test]
+    fn rejects_unknown_communities() {
+        let mut receiver = receiver(&["public"], vec![]);
+        let message =
+            encode_community_message(Version::V2c, b"private", tag::TRAP_V2, &link_down());
+        assert!(matches!(
+            receiver.receive(&message),
+            Err(TrapError::UnknownCommunity { .. })
+        ));
+    }
+
+    #[test]
+    fn acknowledges_v2c_informs() {
+        let mut receiver = receiver(&[], vec![]);
+        let message =
+            encode_community_message(Version::V2c, b"public", tag::INFORM_REQUEST, &link_down());
+        let received = receiver.receive(&message).unwrap();
+        assert_eq!(received.log.unwrap()["pdu_type"], "inform".into());
+
+        match Message::decode(&received.response.unwrap()).unwrap() {
+            Message::Community { pdu_tag, pdu, .. } => {
+                assert_eq!(pdu_tag, tag::RESPONSE);
+                let pdu = Pdu::decode(pdu).unwrap();
+                assert_eq!(pdu.request_id, 42);
+                assert_eq!(pdu.varbinds, link_down().varbinds);
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    fn user(name: &str) -> UsmUserConfig {
+        UsmUserConfig {
+            name: name.to_owned(),
+            auth_protocol: Some(AuthProtocol::Sha),
+            auth_password: Some("authpassword".to_owned()),
+            priv_protocol: Some(PrivProtocol::Aes),
+            priv_password: Some("privpassword".to_owned()),
+        }
+    }
+
+    fn v3_message(user: &UsmUserConfig, engine_id: &[u8], pdu_tag: u8) -> Vec<u8> {
+        let user = UsmUser::new(user).unwrap();
+        encode_v3(
+            &V3Header {
+                msg_id: 7,
+                max_size: 65507,
+                reportable: pdu_tag == tag::INFORM_REQUEST,
+                engine_id,
+                engine_boots: 1,
+                engine_time: 100,
+            },
+            Some(&user),
+            &ScopedPdu {
+                context_engine_id: engine_id.to_vec(),
+                context_name: vec![],
+                pdu_tag,
+                pdu: link_down().encode(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_v3_traps() {
+        let mut receiver = receiver(&[], vec![user("vector")]);
+        // Traps are sent by the authoritative engine, whose ID is not Vector's.
+        let message = v3_message(&user("vector"), b"\x80\x00\x1f\x88\x04agent", tag::TRAP_V2);
+        let log = receiver.receive(&message).unwrap().log.unwrap();
+        assert_eq!(log["version"], "3".into());
+        assert_eq!(log["user"], "vector".into());
+        assert_eq!(log["trap_name"], "IF-MIB::linkDown".into());
+
+        let mut wrong_password = user("vector");
+        wrong_password.auth_password = Some("wrongpassword".to_owned());
+        let message = v3_message(&wrong_password, b"\x80\x00\x1f\x88\x04agent", tag::TRAP_V2);
+        assert!(matches!(
+            receiver.receive(&message),
+            Err(TrapError::Authentication {
+                source: UsmError::WrongDigest
+            })
+        ));
+
+        let message = v3_message(&user("unknown"), b"\x80\x00\x1f\x88\x04agent", tag::TRAP_V2);
+        assert!(matches!(
+            receiver.receive(&message),
+            Err(TrapError::Authentication {
+                source: UsmError::UnknownUser { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn acknowledges_v3_informs() {
+        let mut receiver = receiver(&[], vec![user("vector")]);
+        let engine_id = hex::decode(ENGINE_ID).unwrap();
+
+        // Discovery
+        let discovery = encode_v3(
+            &V3Header {
+                msg_id: 1,
+                max_size: 65507,
+                reportable: true,
+                engine_id: &[],
+                engine_boots: 0,
+                engine_time: 0,
+            },
+            None,
+            &ScopedPdu {
+                context_engine_id: vec![],
+                context_name: vec![],
+                pdu_tag: tag::GET_REQUEST,
+                pdu: Pdu {
+                    request_id: 1,
+                    error_status: 0,
+                    error_index: 0,
+                    varbinds: vec![],
+                }
+                .encode(),
+            },
+        )
+        .unwrap();
+        let received = receiver.receive(&discovery).unwrap();
+        assert!(received.log.is_none());
+        let report = received.response.unwrap();
+        let mut reader = Reader::new(&report).read_sequence().unwrap();
+        assert_eq!(reader.read_integer(), Ok(3));
+        let report = V3Message::decode(&report, reader).unwrap();
+        assert_eq!(report.engine_id, engine_id.as_slice());
+        assert_eq!(report.open_unauthenticated().unwrap().pdu_tag, tag::REPORT);
+
+        // Inform
+        let message = v3_message(&user("vector"), &engine_id, tag::INFORM_REQUEST);
+        let received = receiver.receive(&message).unwrap();
+        assert_eq!(received.log.unwrap()["pdu_type"], "inform".into());
+        let response = received.response.unwrap();
+        let mut reader = Reader::new(&response).read_sequence().unwrap();
+        reader.read_integer().unwrap();
+        let response = V3Message::decode(&response, reader).unwrap();
+        assert_eq!(response.msg_id, 7);
+        let scoped = response
+            .open(&UsmUser::new(&user("vector")).unwrap())
+            .unwrap();
+        assert_eq!(scoped.pdu_tag, tag::RESPONSE);
+        assert_eq!(Pdu::decode(&scoped.pdu).unwrap().request_id, 42);
+
+        // Informs sent to another engine are rejected.
+        let message = v3_message(
+            &user("vector"),
+            b"\x80\x00\x1f\x88\x04other",
+            tag::INFORM_REQUEST,
+        );
+        assert!(matches!(
+            receiver.receive(&message),
+            Err(TrapError::UnknownEngineId { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn receives_traps() {
+        assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
+            let address = next_addr();
+            let config = SnmpTrapConfig {
+                address,
+                ..Default::default()
+            };
+            let (tx, rx) = SourceSender::new_test();
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let message =
+                encode_community_message(Version::V2c, b"public", tag::TRAP_V2, &link_down());
+            socket.send_to(&message, address).await.unwrap();
+
+            let events = crate::test_util::collect_n(rx, 1).await;
+            let log = events[0].as_log();
+            assert_eq!(log["source_type"], "snmp_trap".into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+            assert_eq!(log["trap_name"], "IF-MIB::linkDown".into());
+        })
+        .await;
+    }
+}
@@ -126,7 +126,7 @@ impl SourceConfig for SocketConfig {
                     }
                 };
 
-                let decoder = DecodingConfig::new(framing, decoding).build();
+                let decoder = DecodingConfig::new(framing, decoding).build()?;
 
                 let tcp = tcp::RawTcpSource::new(config.clone(), decoder);
                 let tls_config = config.tls().as_ref().map(|tls| tls.tls_config.clone());
@@ -145,6 +145,7 @@ impl SourceConfig for SocketConfig {
                     cx,
                     false.into(),
                     config.connection_limit,
+                    config.proxy_protocol,
                 )
             }
             Mode::Udp(config) => {
@@ -154,7 +155,7 @@ impl SourceConfig for SocketConfig {
                     .unwrap_or_else(|| log_schema().host_key().to_string());
                 let decoder =
                     DecodingConfig::new(config.framing().clone(), config.decoding().clone())
-                        .build();
+                        .build()?;
                 Ok(udp::udp(config, host_key, decoder, cx.shutdown, cx.out))
             }
             #[cfg(unix)]
@@ -166,7 +167,7 @@ impl SourceConfig for SocketConfig {
                     config.framing.unwrap_or_else(default_framing_message_based),
                     config.decoding.clone(),
                 )
-                .build();
+                .build()?;
                 unix::unix_datagram(
                     config.path,
                     config.socket_file_mode,
@@ -174,6 +175,7 @@ impl SourceConfig for SocketConfig {
                         .max_length
                         .unwrap_or_else(crate::serde::default_max_length),
                     host_key,
+                    config.peer_credentials,
                     decoder,
                     cx.shutdown,
                     cx.out,
@@ -202,7 +204,7 @@ impl SourceConfig for SocketConfig {
                     }
                 };
 
-                let decoder = DecodingConfig::new(framing, decoding).build();
+                let decoder = DecodingConfig::new(framing, decoding).build()?;
 
                 let host_key = config
                     .host_key
@@ -211,6 +213,7 @@ impl SourceConfig for SocketConfig {
                     config.path,
                     config.socket_file_mode,
                     host_key,
+                    config.peer_credentials,
                     decoder,
                     cx.shutdown,
                     cx.out,
@@ -332,6 +335,34 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn tcp_it_includes_proxy_protocol_client() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.proxy_protocol = true;
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        tokio::io::AsyncWriteExt::write_all(
+            &mut stream,
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 9000\r\ntest\n",
+        )
+        .await
+        .unwrap();
+
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "test".into());
+        assert_eq!(event.as_log()[log_schema().host_key()], "192.0.2.1".into());
+        assert_eq!(event.as_log()["port"], 56324_u16.into());
+    }
+
     #[tokio::test]
     async fn tcp_splits_on_newline() {
         let (tx, rx) = SourceSender::new_test();
@@ -932,8 +963,16 @@ mod test {
     #[cfg(unix)]
     async fn init_unix(sender: SourceSender, stream: bool) -> PathBuf {
         let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
+        init_unix_with_config(sender, UnixConfig::new(in_path), stream).await
+    }
 
-        let config = UnixConfig::new(in_path.clone());
+    #[cfg(unix)]
+    async fn init_unix_with_config(
+        sender: SourceSender,
+        config: UnixConfig,
+        stream: bool,
+    ) -> PathBuf {
+        let in_path = config.path.clone();
         let mode = if stream {
             Mode::UnixStream(config)
         } else {
@@ -976,6 +1015,34 @@ mod test {
         rx
     }
 
+    #[cfg(unix)]
+    async fn unix_peer_credentials(stream: bool) {
+        let (tx, rx) = SourceSender::new_test();
+        let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
+        let mut config = UnixConfig::new(in_path);
+        config.peer_credentials.enabled = true;
+        config.peer_credentials.process_name = cfg!(target_os = "linux");
+        config.decoding = codecs::decoding::DeserializerConfig::Json;
+        let path = init_unix_with_config(tx, config, stream).await;
+
+        // The fields decoded from the message are overwritten.
+        unix_send_lines(stream, path, &[r#"{"peer": {"uid": 1234}}"#]).await;
+        let events = collect_n(rx, 1).await;
+
+        let log = events[0].as_log();
+        assert_eq!(log["peer.pid"], i64::from(std::process::id()).into());
+        assert_eq!(
+            log["peer.uid"],
+            i64::from(nix::unistd::getuid().as_raw()).into()
+        );
+        assert_eq!(
+            log["peer.gid"],
+            i64::from(nix::unistd::getgid().as_raw()).into()
+        );
+        #[cfg(target_os = "linux")]
+        assert!(log.contains("peer.process_name"));
+    }
+
     #[cfg(unix)]
     async fn unix_multiple_packets(stream: bool) {
         let (tx, rx) = SourceSender::new_test();
@@ -1078,6 +1145,12 @@ mod test {
         .await;
     }
 
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn unix_datagram_peer_credentials() {
+        unix_peer_credentials(false).await;
+    }
+
     #[cfg(unix)]
     #[test]
     fn parses_unix_datagram_config() {
@@ -1187,6 +1260,12 @@ mod test {
         .await;
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_stream_peer_credentials() {
+        unix_peer_credentials(true).await;
+    }
+
     #[cfg(unix)]
     #[test]
     fn parses_new_unix_stream_config() {
@@ -58,6 +58,16 @@ pub struct TcpConfig {
     /// The maximum number of TCP connections that will be allowed at any given time.
     pub connection_limit: Option<u32>,
 
+    /// Whether connections start with a [PROXY protocol][proxy_protocol] header, as sent by load balancers such as
+    /// HAProxy, holding the address of the client.
+    ///
+    /// When enabled, the address and port of the client are used as the peer host and port rather than those of the
+    /// load balancer, and connections not starting with a valid header are closed.
+    ///
+    /// [proxy_protocol]: https://www.haproxy.org/download/2.6/doc/proxy-protocol.txt
+    #[serde(default)]
+    pub proxy_protocol: bool,
+
     #[configurable(derived)]
     framing: Option<FramingConfig>,
 
@@ -84,6 +94,7 @@ impl TcpConfig {
             framing: None,
             decoding: default_decoding(),
             connection_limit: None,
+            proxy_protocol: false,
         }
     }
 
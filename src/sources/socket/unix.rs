@@ -3,16 +3,20 @@ use std::path::PathBuf;
 use bytes::Bytes;
 use chrono::Utc;
 use codecs::decoding::{DeserializerConfig, FramingConfig};
+use derivative::Derivative;
 use vector_config::configurable_component;
 
 use crate::{
     codecs::Decoder,
     config::log_schema,
-    event::Event,
+    event::{Event, LogEvent},
     serde::default_decoding,
     shutdown::ShutdownSignal,
     sources::{
-        util::{build_unix_datagram_source, build_unix_stream_source},
+        util::{
+            build_unix_datagram_source, build_unix_stream_source, PeerCredentials,
+            PeerCredentialsCapture,
+        },
         Source,
     },
     SourceSender,
@@ -46,6 +50,10 @@ pub struct UnixConfig {
     /// By default, the [global `host_key` option](https://vector.dev/docs/reference/configuration//global-options#log_schema.host_key) is used.pub host_key: Option<String>,
     pub host_key: Option<String>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub peer_credentials: PeerCredentialsConfig,
+
     #[configurable(derived)]
     #[serde(default)]
     pub framing: Option<FramingConfig>,
@@ -62,15 +70,63 @@ impl UnixConfig {
             socket_file_mode: None,
             max_length: Some(crate::serde::default_max_length()),
             host_key: None,
+            peer_credentials: PeerCredentialsConfig::default(),
             framing: None,
             decoding: default_decoding(),
         }
     }
 }
 
+/// Configuration of the credentials of the peer processes added to events.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct PeerCredentialsConfig {
+    /// Whether to add the credentials of the peer process to each event.
+    ///
+    /// The process ID, user ID, and group ID of the peer are added under `key`, as `pid`, `uid`, and `gid`. They
+    /// are reported by the kernel, so they can't be forged by the peer. With the `unix_datagram` mode, this is only
+    /// supported on Linux.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The name of the log field the credentials are added under.
+    #[serde(default = "default_peer_credentials_key")]
+    #[derivative(Default(value = "default_peer_credentials_key()"))]
+    pub key: String,
+
+    /// Whether to also add the name of the peer process, as `process_name`.
+    ///
+    /// The name is read from `/proc/<pid>/comm`, so it's only supported on Linux. It's missing if the process has
+    /// already exited, and unlike the IDs, it can be changed by the process itself.
+    #[serde(default)]
+    pub process_name: bool,
+}
+
+fn default_peer_credentials_key() -> String {
+    "peer".to_owned()
+}
+
+impl PeerCredentialsConfig {
+    const fn capture(&self) -> PeerCredentialsCapture {
+        match (self.enabled, self.process_name) {
+            (false, _) => PeerCredentialsCapture::Disabled,
+            (true, false) => PeerCredentialsCapture::Ids,
+            (true, true) => PeerCredentialsCapture::IdsAndProcessName,
+        }
+    }
+}
+
 /// Function to pass to `build_unix_*_source`, specific to the basic unix source
 /// Takes a single line of a received message and handles an `Event` object.
-fn handle_events(events: &mut [Event], host_key: &str, received_from: Option<Bytes>) {
+fn handle_events(
+    events: &mut [Event],
+    host_key: &str,
+    peer_credentials_key: &str,
+    received_from: Option<Bytes>,
+    credentials: Option<&PeerCredentials>,
+) {
     let now = Utc::now();
 
     for event in events {
@@ -82,6 +138,24 @@ fn handle_events(events: &mut [Event], host_key: &str, received_from: Option<Byt
         if let Some(ref host) = received_from {
             log.try_insert(host_key, host.clone());
         }
+
+        if let Some(credentials) = credentials {
+            insert_peer_credentials(log, peer_credentials_key, credentials);
+        }
+    }
+}
+
+/// Adds the credentials of the peer, overwriting any fields decoded from the message
+/// so that they can't be forged.
+fn insert_peer_credentials(log: &mut LogEvent, key: &str, credentials: &PeerCredentials) {
+    let field = |name: &str| format!("{}.{}", key, name);
+    if let Some(pid) = credentials.pid {
+        log.insert(field("pid").as_str(), i64::from(pid));
+    }
+    log.insert(field("uid").as_str(), i64::from(credentials.uid));
+    log.insert(field("gid").as_str(), i64::from(credentials.gid));
+    if let Some(process_name) = &credentials.process_name {
+        log.insert(field("process_name").as_str(), process_name.clone());
     }
 }
 
@@ -90,6 +164,7 @@ pub(super) fn unix_datagram(
     socket_file_mode: Option<u32>,
     max_length: usize,
     host_key: String,
+    peer_credentials: PeerCredentialsConfig,
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
@@ -98,8 +173,17 @@ pub(super) fn unix_datagram(
         path,
         socket_file_mode,
         max_length,
+        peer_credentials.capture(),
         decoder,
-        move |events, received_from| handle_events(events, &host_key, received_from),
+        move |events, received_from, credentials| {
+            handle_events(
+                events,
+                &host_key,
+                &peer_credentials.key,
+                received_from,
+                credentials,
+            )
+        },
         shutdown,
         out,
     )
@@ -109,6 +193,7 @@ pub(super) fn unix_stream(
     path: PathBuf,
     socket_file_mode: Option<u32>,
     host_key: String,
+    peer_credentials: PeerCredentialsConfig,
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
@@ -116,8 +201,17 @@ pub(super) fn unix_stream(
     build_unix_stream_source(
         path,
         socket_file_mode,
+        peer_credentials.capture(),
         decoder,
-        move |events, received_from| handle_events(events, &host_key, received_from),
+        move |events, received_from, credentials| {
+            handle_events(
+                events,
+                &host_key,
+                &peer_credentials.key,
+                received_from,
+                credentials,
+            )
+        },
         shutdown,
         out,
     )
@@ -34,7 +34,7 @@ pub mod parser;
 #[cfg(unix)]
 mod unix;
 
-use parser::parse;
+use parser::{parse, parse_event, parse_service_check};
 #[cfg(unix)]
 use unix::{statsd_unix, UnixConfig};
 
@@ -65,6 +65,12 @@ pub struct UdpConfig {
     ///
     /// This should not typically needed to be changed.
     receive_buffer_bytes: Option<usize>,
+
+    /// Whether to decode DogStatsD events and service checks into log events.
+    ///
+    /// Otherwise, they are rejected as invalid records.
+    #[serde(default)]
+    dogstatsd_events: bool,
 }
 
 impl UdpConfig {
@@ -72,6 +78,7 @@ impl UdpConfig {
         Self {
             address,
             receive_buffer_bytes: None,
+            dogstatsd_events: false,
         }
     }
 }
@@ -101,6 +108,12 @@ pub struct TcpConfig {
 
     /// The maximum number of TCP connections that will be allowed at any given time.
     connection_limit: Option<u32>,
+
+    /// Whether to decode DogStatsD events and service checks into log events.
+    ///
+    /// Otherwise, they are rejected as invalid records.
+    #[serde(default)]
+    dogstatsd_events: bool,
 }
 
 impl TcpConfig {
@@ -114,6 +127,7 @@ impl TcpConfig {
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
             connection_limit: None,
+            dogstatsd_events: false,
         }
     }
 }
@@ -150,7 +164,10 @@ impl SourceConfig for StatsdConfig {
                     .as_ref()
                     .and_then(|tls| tls.client_metadata_key.clone());
                 let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
-                StatsdTcpSource.run(
+                let source = StatsdTcpSource {
+                    dogstatsd_events: config.dogstatsd_events,
+                };
+                source.run(
                     config.address,
                     config.keepalive,
                     config.shutdown_timeout_secs,
@@ -160,6 +177,7 @@ impl SourceConfig for StatsdConfig {
                     cx,
                     false.into(),
                     config.connection_limit,
+                    false,
                 )
             }
             #[cfg(unix)]
@@ -168,7 +186,19 @@ impl SourceConfig for StatsdConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(config::DataType::Metric)]
+        let dogstatsd_events = match self {
+            Self::Tcp(config) => config.dogstatsd_events,
+            Self::Udp(config) => config.dogstatsd_events,
+            #[cfg(unix)]
+            Self::Unix(config) => config.dogstatsd_events,
+        };
+        if dogstatsd_events {
+            vec![Output::default(
+                config::DataType::Metric | config::DataType::Log,
+            )]
+        } else {
+            vec![Output::default(config::DataType::Metric)]
+        }
     }
 
     fn source_type(&self) -> &'static str {
@@ -192,19 +222,48 @@ impl SourceConfig for StatsdConfig {
 #[derive(Debug, Default, Clone)]
 pub(crate) struct StatsdDeserializer {
     socket_mode: Option<SocketMode>,
+    dogstatsd_events: bool,
 }
 
 impl StatsdDeserializer {
-    pub const fn udp() -> Self {
+    pub const fn udp(dogstatsd_events: bool) -> Self {
         Self {
             socket_mode: Some(SocketMode::Udp),
+            dogstatsd_events,
         }
     }
 
     #[cfg(unix)]
-    pub const fn unix() -> Self {
+    pub const fn unix(dogstatsd_events: bool) -> Self {
         Self {
             socket_mode: Some(SocketMode::Unix),
+            dogstatsd_events,
+        }
+    }
+
+    pub const fn tcp(dogstatsd_events: bool) -> Self {
+        Self {
+            socket_mode: None,
+            dogstatsd_events,
+        }
+    }
+
+    fn parse_packet(&self, packet: &str) -> Result<Event, ParseError> {
+        if packet.starts_with("_e{") || packet.starts_with("_sc|") {
+            if !self.dogstatsd_events {
+                return Err(ParseError::Malformed(
+                    "DogStatsD events and service checks aren't enabled",
+                ));
+            }
+            let mut log = if packet.starts_with("_e{") {
+                parse_event(packet)?
+            } else {
+                parse_service_check(packet)?
+            };
+            log.insert(config::log_schema().source_type_key(), "statsd");
+            Ok(log.into())
+        } else {
+            parse(packet).map(Event::Metric)
         }
     }
 }
@@ -220,10 +279,9 @@ impl decoding::format::Deserializer for StatsdDeserializer {
 
         match std::str::from_utf8(&bytes)
             .map_err(ParseError::InvalidUtf8)
-            .and_then(parse)
+            .and_then(|packet| self.parse_packet(packet))
         {
-            Ok(metric) => {
-                let event = Event::Metric(metric);
+            Ok(event) => {
                 emit!(EventsReceived {
                     count: 1,
                     byte_size: event.size_of(),
@@ -266,7 +324,7 @@ async fn statsd_udp(
 
     let codec = Decoder::new(
         Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-        Deserializer::Boxed(Box::new(StatsdDeserializer::udp())),
+        Deserializer::Boxed(Box::new(StatsdDeserializer::udp(config.dogstatsd_events))),
     );
     let mut stream = UdpFramed::new(socket, codec).take_until(shutdown);
     while let Some(frame) = stream.next().await {
@@ -287,7 +345,9 @@ async fn statsd_udp(
 }
 
 #[derive(Clone)]
-struct StatsdTcpSource;
+struct StatsdTcpSource {
+    dogstatsd_events: bool,
+}
 
 impl TcpSource for StatsdTcpSource {
     type Error = codecs::decoding::Error;
@@ -298,7 +358,7 @@ impl TcpSource for StatsdTcpSource {
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
             Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-            Deserializer::Boxed(Box::new(StatsdDeserializer::default())),
+            Deserializer::Boxed(Box::new(StatsdDeserializer::tcp(self.dogstatsd_events))),
         )
     }
 
@@ -331,6 +391,27 @@ mod test {
         crate::test_util::test_generate_config::<StatsdConfig>();
     }
 
+    #[test]
+    fn decodes_dogstatsd_events_when_enabled() {
+        use codecs::decoding::format::Deserializer as _;
+
+        let packet = Bytes::from("_sc|app.health|2|h:web-1|m:Connection refused");
+        assert!(StatsdDeserializer::tcp(false)
+            .parse(packet.clone())
+            .is_err());
+
+        let events = StatsdDeserializer::tcp(true).parse(packet).unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["name"], "app.health".into());
+        assert_eq!(log["status"], "critical".into());
+        assert_eq!(log["source_type"], "statsd".into());
+
+        let events = StatsdDeserializer::tcp(true)
+            .parse(Bytes::from("foo:1|c"))
+            .unwrap();
+        assert!(events[0].as_metric().name() == "foo");
+    }
+
     #[tokio::test]
     async fn test_statsd_udp() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async move {
@@ -378,6 +459,7 @@ mod test {
             let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
             let config = StatsdConfig::Unix(UnixConfig {
                 path: in_path.clone(),
+                dogstatsd_events: false,
             });
             let (sender, mut receiver) = mpsc::channel(200);
             tokio::spawn(async move {
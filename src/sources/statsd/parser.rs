@@ -5,10 +5,17 @@ use std::{
     str::Utf8Error,
 };
 
+use chrono::{DateTime, TimeZone, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue, Sample, StatisticKind},
+        LogEvent, Value,
+    },
+};
 
 static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
 static NONALPHANUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z_\-0-9\.]").unwrap());
@@ -33,26 +40,27 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     let name = sanitize_key(key);
     let metric_type = parts[1];
 
-    // sampling part is optional and comes after metric type part
-    let sampling = parts.get(2).filter(|s| s.starts_with('@'));
-    let sample_rate = if let Some(s) = sampling {
-        1.0 / sanitize_sampling(parse_sampling(s)?)
-    } else {
-        1.0
-    };
+    // sampling, tags and the DogStatsD extensions are optional and come after the metric type
+    // part, unknown ones being ignored like the Datadog agent does
+    let mut sample_rate = 1.0;
+    let mut tags = None;
+    let mut timestamp = None;
+    for part in &parts[2..] {
+        if part.starts_with('@') {
+            sample_rate = 1.0 / sanitize_sampling(parse_sampling(part)?);
+        } else if part.starts_with('#') {
+            tags = Some(parse_tags(part)?);
+        } else if let Some(seconds) = part.strip_prefix('T') {
+            timestamp = Some(parse_timestamp(seconds)?);
+        }
+    }
 
-    // tags are optional and could be found either after sampling of after metric type part
-    let tags = if sampling.is_none() {
-        parts.get(2)
-    } else {
-        parts.get(3)
-    };
-    let tags = tags.filter(|s| s.starts_with('#'));
-    let tags = if let Some(t) = tags {
-        Some(parse_tags(t)?)
-    } else {
-        None
-    };
+    // DogStatsD packs multiple values of histograms, timers and distributions in a single packet
+    if parts[0].contains(':') && !matches!(metric_type, "h" | "ms" | "d") {
+        return Err(ParseError::Malformed(
+            "only histograms, timers and distributions can have multiple values",
+        ));
+    }
 
     let metric = match metric_type {
         "c" => {
@@ -67,13 +75,24 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
             .with_tags(tags)
         }
         unit @ "h" | unit @ "ms" | unit @ "d" => {
-            let val: f64 = parts[0].parse()?;
+            let samples = parts[0]
+                .split(':')
+                .map(|value| {
+                    Ok(Sample {
+                        value: convert_to_base_units(unit, value.parse()?),
+                        rate: sample_rate as u32,
+                    })
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?;
             Metric::new(
-                name, MetricKind::Incremental, MetricValue::Distribution {
-                    samples: vector_core::samples![convert_to_base_units(unit, val) => sample_rate as u32],
+                name,
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples,
                     statistic: convert_to_statistic(unit),
                 },
-            ).with_tags(tags)
+            )
+            .with_tags(tags)
         }
         "g" => {
             let value = if parts[0]
@@ -110,7 +129,124 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
         .with_tags(tags),
         other => return Err(ParseError::UnknownMetricType(other.into())),
     };
-    Ok(metric)
+    Ok(metric.with_timestamp(timestamp))
+}
+
+/// Parses a DogStatsD event into a log event.
+///
+/// https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/?tab=events#the-dogstatsd-protocol
+pub fn parse_event(packet: &str) -> Result<LogEvent, ParseError> {
+    let (lengths, body) = packet
+        .strip_prefix("_e{")
+        .and_then(|packet| packet.split_once("}:"))
+        .ok_or(ParseError::Malformed(
+            "event should start with the lengths of its title and text",
+        ))?;
+    let (title_length, text_length) = lengths.split_once(',').ok_or(ParseError::Malformed(
+        "event lengths should be separated by ','",
+    ))?;
+    let title_length: usize = title_length.parse()?;
+    let text_end = title_length + 1 + text_length.parse::<usize>()?;
+
+    let title = body.get(..title_length);
+    let text = body.get(title_length + 1..text_end);
+    let (title, text) = match (title, text) {
+        (Some(title), Some(text)) if body[title_length..].starts_with('|') => (title, text),
+        _ => {
+            return Err(ParseError::Malformed(
+                "event title and text don't match their lengths",
+            ))
+        }
+    };
+
+    let mut log = LogEvent::default();
+    log.insert("title", title);
+    log.insert(log_schema().message_key(), text.replace("\\n", "\n"));
+    log.insert("priority", "normal");
+    log.insert("alert_type", "info");
+    log.insert(log_schema().timestamp_key(), Utc::now());
+
+    for part in body[text_end..].split('|').skip(1) {
+        if let Some(timestamp) = part.strip_prefix("d:") {
+            log.insert(log_schema().timestamp_key(), parse_timestamp(timestamp)?);
+        } else if let Some(host) = part.strip_prefix("h:") {
+            log.insert(log_schema().host_key(), host);
+        } else if let Some(priority) = part.strip_prefix("p:") {
+            log.insert("priority", priority);
+        } else if let Some(alert_type) = part.strip_prefix("t:") {
+            log.insert("alert_type", alert_type);
+        } else if let Some(aggregation_key) = part.strip_prefix("k:") {
+            log.insert("aggregation_key", aggregation_key);
+        } else if let Some(source_type_name) = part.strip_prefix("s:") {
+            log.insert("source_type_name", source_type_name);
+        } else if part.starts_with('#') {
+            log.insert("tags", tags_value(parse_tags(part)?));
+        }
+    }
+
+    Ok(log)
+}
+
+/// Parses a DogStatsD service check into a log event.
+///
+/// https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/?tab=servicechecks#the-dogstatsd-protocol
+pub fn parse_service_check(packet: &str) -> Result<LogEvent, ParseError> {
+    let body = packet.strip_prefix("_sc|").ok_or(ParseError::Malformed(
+        "service check should start with '_sc|'",
+    ))?;
+
+    // the message comes last, and may contain pipes
+    let (body, message) = match body.split_once("|m:") {
+        Some((body, message)) => (body, Some(message)),
+        None => (body, None),
+    };
+
+    let parts = body.split('|').collect::<Vec<_>>();
+    if parts.len() < 2 {
+        return Err(ParseError::Malformed(
+            "service check should have a name and a status",
+        ));
+    }
+    let status = match parts[1] {
+        "0" => "ok",
+        "1" => "warning",
+        "2" => "critical",
+        "3" => "unknown",
+        _ => return Err(ParseError::Malformed("unknown service check status")),
+    };
+
+    let mut log = LogEvent::default();
+    log.insert("name", parts[0]);
+    log.insert("status", status);
+    if let Some(message) = message {
+        log.insert(log_schema().message_key(), message);
+    }
+    log.insert(log_schema().timestamp_key(), Utc::now());
+
+    for part in &parts[2..] {
+        if let Some(timestamp) = part.strip_prefix("d:") {
+            log.insert(log_schema().timestamp_key(), parse_timestamp(timestamp)?);
+        } else if let Some(host) = part.strip_prefix("h:") {
+            log.insert(log_schema().host_key(), host);
+        } else if part.starts_with('#') {
+            log.insert("tags", tags_value(parse_tags(part)?));
+        }
+    }
+
+    Ok(log)
+}
+
+fn parse_timestamp(input: &str) -> Result<DateTime<Utc>, ParseError> {
+    Utc.timestamp_opt(input.parse()?, 0)
+        .single()
+        .ok_or(ParseError::Malformed("timestamp out of range"))
+}
+
+fn tags_value(tags: BTreeMap<String, String>) -> Value {
+    tags.into_iter()
+        .map(|(key, value)| (key, Value::from(value)))
+        .collect::<BTreeMap<_, _>>()
+        .into()
 }
 
 fn parse_sampling(input: &str) -> Result<f64, ParseError> {
@@ -226,10 +362,14 @@ impl From<ParseFloatError> for ParseError {
 
 #[cfg(test)]
 mod test {
+    use chrono::{TimeZone, Utc};
     use vector_common::assert_event_data_eq;
 
-    use super::{parse, sanitize_key, sanitize_sampling};
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use super::{parse, parse_event, parse_service_check, sanitize_key, sanitize_sampling};
+    use crate::event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Value,
+    };
 
     #[test]
     fn basic_counter() {
@@ -396,6 +536,88 @@ mod test {
         );
     }
 
+    #[test]
+    fn multiple_values_distribution() {
+        assert_event_data_eq!(
+            parse("glork:320:400|d|@0.5|#region:us-west1"),
+            Ok(Metric::new(
+                "glork",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: vector_core::samples![320.0 => 2, 400.0 => 2],
+                    statistic: StatisticKind::Summary
+                },
+            )
+            .with_tags(Some(
+                vec![("region".to_owned(), "us-west1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ))),
+        );
+        assert!(parse("foo:1:2|c").is_err());
+    }
+
+    #[test]
+    fn timestamped_counter_with_extensions() {
+        assert_event_data_eq!(
+            parse("foo:1|c|#tag1|c:container-id|T1656581400"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(
+                vec![("tag1".to_owned(), "true".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ))
+            .with_timestamp(Some(Utc.timestamp(1656581400, 0)))),
+        );
+    }
+
+    #[test]
+    fn dogstatsd_event() {
+        let log = parse_event(
+            "_e{5,14}:title|text\\nof event|d:1656581400|h:web-1|p:low|t:error|k:deploy|#env:prod",
+        )
+        .unwrap();
+        assert_eq!(log["title"], "title".into());
+        assert_eq!(log["message"], "text\nof event".into());
+        assert_eq!(
+            log["timestamp"],
+            Value::Timestamp(Utc.timestamp(1656581400, 0))
+        );
+        assert_eq!(log["host"], "web-1".into());
+        assert_eq!(log["priority"], "low".into());
+        assert_eq!(log["alert_type"], "error".into());
+        assert_eq!(log["aggregation_key"], "deploy".into());
+        assert_eq!(log["tags.env"], "prod".into());
+
+        let log = parse_event("_e{3,0}:hé|").unwrap();
+        assert_eq!(log["title"], "hé".into());
+        assert_eq!(log["priority"], "normal".into());
+        assert_eq!(log["alert_type"], "info".into());
+
+        assert!(parse_event("_e{6,4}:title|text").is_err());
+        assert!(parse_event("_e{5}:title|text").is_err());
+    }
+
+    #[test]
+    fn dogstatsd_service_check() {
+        let log = parse_service_check(
+            "_sc|app.health|1|d:1656581400|h:web-1|#env:prod|m:slow | retrying",
+        )
+        .unwrap();
+        assert_eq!(log["name"], "app.health".into());
+        assert_eq!(log["status"], "warning".into());
+        assert_eq!(log["message"], "slow | retrying".into());
+        assert_eq!(log["host"], "web-1".into());
+        assert_eq!(log["tags.env"], "prod".into());
+
+        assert!(parse_service_check("_sc|app.health|5").is_err());
+        assert!(parse_service_check("_sc|app.health").is_err());
+    }
+
     #[test]
     fn sanitizing_keys() {
         assert_eq!("foo-bar-baz", sanitize_key("foo/bar/baz"));
@@ -10,7 +10,10 @@ use super::StatsdDeserializer;
 use crate::{
     codecs::Decoder,
     shutdown::ShutdownSignal,
-    sources::{util::build_unix_stream_source, Source},
+    sources::{
+        util::{build_unix_stream_source, PeerCredentialsCapture},
+        Source,
+    },
     SourceSender,
 };
 
@@ -22,6 +25,12 @@ pub struct UnixConfig {
     ///
     /// This should be an absolute path.
     pub path: PathBuf,
+
+    /// Whether to decode DogStatsD events and service checks into log events.
+    ///
+    /// Otherwise, they are rejected as invalid records.
+    #[serde(default)]
+    pub dogstatsd_events: bool,
 }
 
 pub fn statsd_unix(
@@ -31,14 +40,15 @@ pub fn statsd_unix(
 ) -> crate::Result<Source> {
     let decoder = Decoder::new(
         Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-        Deserializer::Boxed(Box::new(StatsdDeserializer::unix())),
+        Deserializer::Boxed(Box::new(StatsdDeserializer::unix(config.dogstatsd_events))),
     );
 
     build_unix_stream_source(
         config.path,
         None,
+        PeerCredentialsCapture::Disabled,
         decoder,
-        |_events, _host| {},
+        |_events, _host, _credentials| {},
         shutdown,
         out,
     )
@@ -8,12 +8,12 @@ use codecs::{
     StreamDecodingError,
 };
 use futures::{channel::mpsc, executor, SinkExt, StreamExt};
-use tokio_util::{codec::FramedRead, io::StreamReader};
+use tokio_util::io::StreamReader;
 use vector_config::configurable_component;
 use vector_core::ByteSizeOf;
 
 use crate::{
-    codecs::DecodingConfig,
+    codecs::{DeadlineFramedRead, DecodingConfig},
     config::{log_schema, Output, Resource, SourceConfig, SourceContext, SourceDescription},
     internal_events::{BytesReceived, OldEventsReceived, StreamClosedError},
     serde::default_decoding,
@@ -109,7 +109,7 @@ where
     let framing = config
         .framing
         .unwrap_or_else(|| config.decoding.default_stream_framing());
-    let decoder = DecodingConfig::new(framing, config.decoding).build();
+    let decoder = DecodingConfig::new(framing, config.decoding).build()?;
 
     let (mut sender, receiver) = mpsc::channel(1024);
 
@@ -140,7 +140,7 @@ where
 
     Ok(Box::pin(async move {
         let stream = StreamReader::new(receiver);
-        let mut stream = FramedRead::new(stream, decoder).take_until(shutdown);
+        let mut stream = DeadlineFramedRead::new(stream, decoder).take_until(shutdown);
         let mut stream = stream! {
             while let Some(result) = stream.next().await {
                 match result {
@@ -16,7 +16,7 @@ use vector_config::configurable_component;
 
 use crate::codecs::Decoder;
 #[cfg(unix)]
-use crate::sources::util::build_unix_stream_source;
+use crate::sources::util::{build_unix_stream_source, PeerCredentialsCapture};
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
@@ -76,6 +76,16 @@ pub enum Mode {
 
         /// The maximum number of TCP connections that will be allowed at any given time.
         connection_limit: Option<u32>,
+
+        /// Whether connections start with a [PROXY protocol][proxy_protocol] header, as sent by load balancers such
+        /// as HAProxy, holding the address of the client.
+        ///
+        /// When enabled, the address of the client is used as the peer host rather than that of the load balancer,
+        /// and connections not starting with a valid header are closed.
+        ///
+        /// [proxy_protocol]: https://www.haproxy.org/download/2.6/doc/proxy-protocol.txt
+        #[serde(default)]
+        proxy_protocol: bool,
     },
 
     /// Listen on UDP.
@@ -128,6 +138,7 @@ impl GenerateConfig for SyslogConfig {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                proxy_protocol: false,
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
@@ -152,6 +163,7 @@ impl SourceConfig for SyslogConfig {
                 tls,
                 receive_buffer_bytes,
                 connection_limit,
+                proxy_protocol,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
@@ -172,6 +184,7 @@ impl SourceConfig for SyslogConfig {
                     cx,
                     false.into(),
                     connection_limit,
+                    proxy_protocol,
                 )
             }
             Mode::Udp {
@@ -200,8 +213,9 @@ impl SourceConfig for SyslogConfig {
                 build_unix_stream_source(
                     path,
                     socket_file_mode,
+                    PeerCredentialsCapture::Disabled,
                     decoder,
-                    move |events, host| handle_events(events, &host_key, host),
+                    move |events, host, _credentials| handle_events(events, &host_key, host),
                     cx.shutdown,
                     cx.out,
                 )
@@ -801,6 +815,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                proxy_protocol: false,
             });
 
             let key = ComponentKey::from("in");
@@ -944,6 +959,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                proxy_protocol: false,
             });
 
             let key = ComponentKey::from("in");
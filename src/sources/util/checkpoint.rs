@@ -0,0 +1,370 @@
+//! Stores for the checkpoints of sources, which record where they left off so that they resume
+//! from there after a restart.
+//!
+//! Checkpoints are stored as opaque bytes, in a file of the data directory by default. Storing them
+//! in Redis or S3 instead lets sources of stateless or ephemeral deployments resume on another
+//! host.
+
+use std::path::PathBuf;
+
+use aws_sdk_s3::{types::ByteStream, Client as S3Client};
+use aws_smithy_client::SdkError;
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::{de::DeserializeOwned, Serialize};
+use vector_config::configurable_component;
+
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::s3::S3ClientBuilder,
+    config::SourceContext,
+};
+
+/// Where to persist the checkpoint of the source.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative, PartialEq)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CheckpointStoreConfig {
+    /// A file in the data directory.
+    #[derivative(Default)]
+    File {
+        /// The directory used to persist the checkpoint.
+        ///
+        /// By default, the `data_dir` option of the source, or else the global `data_dir` option, is used. Make
+        /// sure the running user has write permissions to this directory.
+        #[serde(default)]
+        data_dir: Option<PathBuf>,
+    },
+
+    /// A key in Redis, which outlives the host running Vector.
+    Redis {
+        /// The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format.
+        url: String,
+
+        /// The prefix of the key of the checkpoint, which is followed by the name of the checkpoint.
+        ///
+        /// By default, it's `vector:checkpoints:<component ID>:`, so that an instance replacing another one with
+        /// the same configuration resumes from its checkpoint.
+        #[serde(default)]
+        key_prefix: Option<String>,
+
+        /// The key of the checkpoint, overriding `key_prefix`.
+        #[serde(default)]
+        key: Option<String>,
+    },
+
+    /// An object in S3, which outlives the host running Vector.
+    S3 {
+        /// The name of the bucket to store the checkpoint in.
+        bucket: String,
+
+        /// The prefix of the key of the checkpoint object, which is followed by the name of the checkpoint.
+        ///
+        /// By default, it's `vector/checkpoints/<component ID>/`, so that an instance replacing another one with
+        /// the same configuration resumes from its checkpoint.
+        #[serde(default)]
+        key_prefix: Option<String>,
+
+        /// The key of the checkpoint object, overriding `key_prefix`.
+        #[serde(default)]
+        key: Option<String>,
+
+        /// The AWS region of the bucket.
+        #[serde(default)]
+        region: Option<String>,
+
+        /// The API endpoint of S3.
+        #[serde(default)]
+        endpoint: Option<String>,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        auth: AwsAuthentication,
+    },
+}
+
+impl CheckpointStoreConfig {
+    /// Builds the store of the checkpoint `name` of a source, whose own data directory option is
+    /// `data_dir`.
+    pub async fn build(
+        &self,
+        cx: &SourceContext,
+        data_dir: Option<&PathBuf>,
+        name: &str,
+    ) -> crate::Result<CheckpointStore> {
+        self.build_with_s3_client(cx, data_dir, name, None).await
+    }
+
+    /// Builds the store of the checkpoint `name` of a source, storing it in S3 with `s3_client`
+    /// unless a region or an endpoint is configured for the store.
+    pub async fn build_with_s3_client(
+        &self,
+        cx: &SourceContext,
+        data_dir: Option<&PathBuf>,
+        name: &str,
+        s3_client: Option<S3Client>,
+    ) -> crate::Result<CheckpointStore> {
+        let backend = match self {
+            Self::File { .. } => {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(self.data_dir(data_dir), cx.key.id())?;
+                Backend::File(data_dir.join(name))
+            }
+            Self::Redis {
+                url,
+                key_prefix,
+                key,
+            } => {
+                let client = redis::Client::open(url.as_str())?;
+                let connection = client.get_tokio_connection_manager().await?;
+                let key = key.clone().unwrap_or_else(|| match key_prefix {
+                    Some(key_prefix) => format!("{}{}", key_prefix, name),
+                    None => format!("vector:checkpoints:{}:{}", cx.key.id(), name),
+                });
+                Backend::Redis { connection, key }
+            }
+            Self::S3 {
+                bucket,
+                key_prefix,
+                key,
+                region,
+                endpoint,
+                auth,
+            } => {
+                let region = RegionOrEndpoint {
+                    region: region.clone(),
+                    endpoint: endpoint.clone(),
+                };
+                let client = match s3_client {
+                    Some(client) if region == RegionOrEndpoint::default() => client,
+                    _ => {
+                        create_client::<S3ClientBuilder>(
+                            auth,
+                            region.region(),
+                            region.endpoint()?,
+                            &cx.proxy,
+                            &None,
+                            false,
+                        )
+                        .await?
+                    }
+                };
+                let key = key.clone().unwrap_or_else(|| match key_prefix {
+                    Some(key_prefix) => format!("{}{}", key_prefix, name),
+                    None => format!("vector/checkpoints/{}/{}", cx.key.id(), name),
+                });
+                Backend::S3 {
+                    client,
+                    bucket: bucket.clone(),
+                    key,
+                }
+            }
+        };
+        Ok(CheckpointStore { backend })
+    }
+
+    /// Returns the directory of file checkpoints, which is `data_dir`, the data directory option of
+    /// the source, unless the store has one.
+    pub fn data_dir<'a>(&'a self, data_dir: Option<&'a PathBuf>) -> Option<&'a PathBuf> {
+        match self {
+            Self::File {
+                data_dir: Some(store_data_dir),
+            } => Some(store_data_dir),
+            _ => data_dir,
+        }
+    }
+
+    /// Returns whether checkpoints are stored on the host running Vector.
+    pub const fn is_local(&self) -> bool {
+        matches!(self, Self::File { .. })
+    }
+}
+
+/// The store of a checkpoint of a source.
+#[derive(Clone)]
+pub struct CheckpointStore {
+    backend: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    File(PathBuf),
+    Redis {
+        connection: ConnectionManager,
+        key: String,
+    },
+    S3 {
+        client: S3Client,
+        bucket: String,
+        key: String,
+    },
+}
+
+impl CheckpointStore {
+    /// Returns a store keeping the checkpoint in the file at `path`.
+    pub const fn file(path: PathBuf) -> Self {
+        Self {
+            backend: Backend::File(path),
+        }
+    }
+
+    /// Loads the checkpoint, which is `None` if none was saved yet.
+    pub async fn load(&self) -> crate::Result<Option<Vec<u8>>> {
+        match &self.backend {
+            Backend::File(path) => match tokio::fs::read(path).await {
+                Ok(data) => Ok(Some(data)),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Backend::Redis { connection, key } => {
+                Ok(connection.clone().get::<_, Option<Vec<u8>>>(key).await?)
+            }
+            Backend::S3 {
+                client,
+                bucket,
+                key,
+            } => match client.get_object().bucket(bucket).key(key).send().await {
+                Ok(object) => Ok(Some(object.body.collect().await?.into_bytes().to_vec())),
+                Err(SdkError::ServiceError { err, .. }) if err.is_no_such_key() => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+        }
+    }
+
+    /// Saves the checkpoint, replacing the previous one at once.
+    pub async fn save(&self, data: Vec<u8>) -> crate::Result<()> {
+        match &self.backend {
+            Backend::File(path) => {
+                let tmp_path = path.with_extension("tmp");
+                tokio::fs::write(&tmp_path, data).await?;
+                tokio::fs::rename(&tmp_path, path).await?;
+            }
+            Backend::Redis { connection, key } => {
+                connection.clone().set::<_, _, ()>(key, data).await?;
+            }
+            Backend::S3 {
+                client,
+                bucket,
+                key,
+            } => {
+                client
+                    .put_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .body(ByteStream::from(data))
+                    .send()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a checkpoint saved as JSON, which is `None` if none was saved yet.
+    pub async fn load_json<T: DeserializeOwned>(&self) -> crate::Result<Option<T>> {
+        match self.load().await? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads a checkpoint saved as JSON, which is the default one if none was saved yet or the
+    /// saved one is invalid.
+    pub async fn load_json_or_default<T: DeserializeOwned + Default>(&self) -> crate::Result<T> {
+        Ok(match self.load().await? {
+            Some(data) => serde_json::from_slice(&data).unwrap_or_else(|error| {
+                warn!(message = "Ignoring invalid checkpoint.", location = %self.location(), %error);
+                T::default()
+            }),
+            None => T::default(),
+        })
+    }
+
+    /// Saves a checkpoint as JSON.
+    pub async fn save_json<T: Serialize>(&self, checkpoint: &T) -> crate::Result<()> {
+        self.save(serde_json::to_vec(checkpoint)?).await
+    }
+
+    /// Returns where the checkpoint is stored, for logging.
+    pub fn location(&self) -> String {
+        match &self.backend {
+            Backend::File(path) => path.display().to_string(),
+            Backend::Redis { key, .. } => format!("redis key {:?}", key),
+            Backend::S3 { bucket, key, .. } => format!("s3://{}/{}", bucket, key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config() {
+        let config: CheckpointStoreConfig = toml::from_str(
+            r#"
+            type = "redis"
+            url = "redis://127.0.0.1/"
+            key_prefix = "fargate:"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config,
+            CheckpointStoreConfig::Redis { ref key_prefix, key: None, .. }
+                if key_prefix.as_deref() == Some("fargate:")
+        ));
+        assert!(!config.is_local());
+
+        let config: CheckpointStoreConfig = toml::from_str(
+            r#"
+            type = "s3"
+            bucket = "vector"
+            region = "us-east-1"
+            "#,
+        )
+        .unwrap();
+        assert!(
+            matches!(config, CheckpointStoreConfig::S3 { ref bucket, .. } if bucket == "vector")
+        );
+
+        assert!(CheckpointStoreConfig::default().is_local());
+    }
+
+    #[tokio::test]
+    async fn file_roundtrip() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::file(data_dir.path().join("checkpoint.json"));
+        assert_eq!(store.load().await.unwrap(), None);
+        assert_eq!(store.load_json::<Vec<u32>>().await.unwrap(), None);
+
+        store.save_json(&vec![1, 2]).await.unwrap();
+        assert_eq!(
+            store.load_json::<Vec<u32>>().await.unwrap(),
+            Some(vec![1, 2])
+        );
+        assert!(!data_dir.path().join("checkpoint.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn ignores_invalid_json() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::file(data_dir.path().join("checkpoint.json"));
+        assert_eq!(
+            store.load_json_or_default::<Vec<u32>>().await.unwrap(),
+            Vec::<u32>::new()
+        );
+
+        store.save(b"[1,".to_vec()).await.unwrap();
+        assert_eq!(
+            store.load_json_or_default::<Vec<u32>>().await.unwrap(),
+            Vec::<u32>::new()
+        );
+
+        store.save_json(&vec![3]).await.unwrap();
+        assert_eq!(
+            store.load_json_or_default::<Vec<u32>>().await.unwrap(),
+            vec![3]
+        );
+    }
+}
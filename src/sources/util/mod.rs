@@ -1,9 +1,11 @@
 #[cfg(any(feature = "sources-http"))]
 mod body_decoding;
+#[cfg(feature = "sources-utils-checkpoint")]
+pub mod checkpoint;
 mod encoding_config;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
-#[cfg(feature = "sources-vector")]
+#[cfg(any(feature = "sources-opentelemetry", feature = "sources-vector"))]
 pub mod grpc;
 #[cfg(any(
     feature = "sources-utils-http-auth",
@@ -16,6 +18,8 @@ mod http;
 #[cfg(any(feature = "sources-aws_sqs", feature = "sources-gcp_pubsub"))]
 mod message_decoding;
 pub mod multiline_config;
+#[cfg(feature = "sources-utils-snmp")]
+pub mod snmp;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
 #[cfg(all(unix, any(feature = "sources-socket", feature = "sources-utils-unix",)))]
@@ -37,7 +41,7 @@ pub use multiline_config::MultilineConfig;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub use tcp::{SocketListenAddr, TcpNullAcker, TcpSource, TcpSourceAck, TcpSourceAcker};
 #[cfg(all(unix, any(feature = "sources-socket", feature = "sources-utils-unix",)))]
-pub use unix::change_socket_permissions;
+pub use unix::{change_socket_permissions, PeerCredentials, PeerCredentialsCapture};
 #[cfg(all(unix, feature = "sources-socket",))]
 pub use unix_datagram::build_unix_datagram_source;
 #[cfg(all(unix, feature = "sources-utils-unix",))]
@@ -51,6 +55,8 @@ pub use wrappers::{AfterRead, AfterReadExt};
 
 #[cfg(any(feature = "sources-http"))]
 pub use self::body_decoding::Encoding;
+#[cfg(feature = "sources-utils-checkpoint")]
+pub use self::checkpoint::{CheckpointStore, CheckpointStoreConfig};
 #[cfg(feature = "sources-utils-http-query")]
 pub use self::http::add_query_parameters;
 #[cfg(any(
@@ -63,6 +69,6 @@ pub use self::http::ErrorMessage;
 #[cfg(feature = "sources-utils-http-prelude")]
 pub use self::http::HttpSource;
 #[cfg(feature = "sources-utils-http-auth")]
-pub use self::http::HttpSourceAuthConfig;
+pub use self::http::{HttpSourceAuth, HttpSourceAuthConfig};
 #[cfg(any(feature = "sources-aws_sqs", feature = "sources-gcp_pubsub"))]
 pub use self::message_decoding::decode_message;
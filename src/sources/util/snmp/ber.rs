@@ -0,0 +1,491 @@
+//! A codec for the subset of the ASN.1 Basic Encoding Rules used by SNMP messages.
+
+use std::{fmt, str::FromStr};
+
+use snafu::Snafu;
+
+pub mod tag {
+    pub const INTEGER: u8 = 0x02;
+    pub const OCTET_STRING: u8 = 0x04;
+    pub const NULL: u8 = 0x05;
+    pub const OBJECT_IDENTIFIER: u8 = 0x06;
+    pub const SEQUENCE: u8 = 0x30;
+    pub const IP_ADDRESS: u8 = 0x40;
+    pub const COUNTER32: u8 = 0x41;
+    pub const GAUGE32: u8 = 0x42;
+    pub const TIMETICKS: u8 = 0x43;
+    pub const OPAQUE: u8 = 0x44;
+    pub const COUNTER64: u8 = 0x46;
+    pub const NO_SUCH_OBJECT: u8 = 0x80;
+    pub const NO_SUCH_INSTANCE: u8 = 0x81;
+    pub const END_OF_MIB_VIEW: u8 = 0x82;
+
+    pub const GET_REQUEST: u8 = 0xa0;
+    pub const GET_NEXT_REQUEST: u8 = 0xa1;
+    pub const RESPONSE: u8 = 0xa2;
+    pub const TRAP_V1: u8 = 0xa4;
+    pub const GET_BULK_REQUEST: u8 = 0xa5;
+    pub const INFORM_REQUEST: u8 = 0xa6;
+    pub const TRAP_V2: u8 = 0xa7;
+    pub const REPORT: u8 = 0xa8;
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum BerError {
+    #[snafu(display("unexpected end of data"))]
+    Truncated,
+    #[snafu(display("expected tag {:#04x}, found {:#04x}", expected, found))]
+    UnexpectedTag { expected: u8, found: u8 },
+    #[snafu(display("unsupported length encoding"))]
+    InvalidLength,
+    #[snafu(display("integer out of range"))]
+    IntegerOverflow,
+    #[snafu(display("invalid object identifier"))]
+    InvalidOid,
+}
+
+/// Reads consecutive TLVs from a buffer.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of bytes left to read.
+    pub const fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Reads the next TLV, returning its tag and value.
+    pub fn read_any(&mut self) -> Result<(u8, &'a [u8]), BerError> {
+        let (&tag, rest) = self.data.split_first().ok_or(BerError::Truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or(BerError::Truncated)?;
+        let len = if first & 0x80 == 0 {
+            first as usize
+        } else {
+            // The indefinite form is not allowed in SNMP messages.
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return Err(BerError::InvalidLength);
+            }
+            if rest.len() < count {
+                return Err(BerError::Truncated);
+            }
+            let (bytes, remaining) = rest.split_at(count);
+            rest = remaining;
+            bytes
+                .iter()
+                .fold(0usize, |len, &byte| (len << 8) | byte as usize)
+        };
+        if rest.len() < len {
+            return Err(BerError::Truncated);
+        }
+        let (value, rest) = rest.split_at(len);
+        self.data = rest;
+        Ok((tag, value))
+    }
+
+    /// Reads the next TLV, which must have the `expected` tag, returning its value.
+    pub fn read(&mut self, expected: u8) -> Result<&'a [u8], BerError> {
+        match self.read_any()? {
+            (tag, value) if tag == expected => Ok(value),
+            (found, _) => Err(BerError::UnexpectedTag { expected, found }),
+        }
+    }
+
+    pub fn read_sequence(&mut self) -> Result<Reader<'a>, BerError> {
+        self.read(tag::SEQUENCE).map(Reader::new)
+    }
+
+    pub fn read_integer(&mut self) -> Result<i64, BerError> {
+        self.read(tag::INTEGER).and_then(decode_signed)
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        self.read(tag::OCTET_STRING)
+    }
+
+    pub fn read_oid(&mut self) -> Result<Oid, BerError> {
+        self.read(tag::OBJECT_IDENTIFIER).and_then(Oid::decode)
+    }
+}
+
+pub fn decode_signed(value: &[u8]) -> Result<i64, BerError> {
+    if value.is_empty() || value.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    let initial = if value[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(value
+        .iter()
+        .fold(initial, |result, &byte| (result << 8) | byte as i64))
+}
+
+pub fn decode_unsigned(value: &[u8]) -> Result<u64, BerError> {
+    // Unsigned values have a leading zero byte when their high bit is set.
+    let value = match value {
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        value => value,
+    };
+    if value.is_empty() || value.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(value
+        .iter()
+        .fold(0, |result, &byte| (result << 8) | byte as u64))
+}
+
+/// Encodes a TLV.
+pub fn encode(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(value.len() + 6);
+    encoded.push(tag);
+    encode_length(value.len(), &mut encoded);
+    encoded.extend_from_slice(value);
+    encoded
+}
+
+/// The length of the tag and length header of a TLV with a value of `len` bytes.
+pub const fn header_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 2,
+        0x80..=0xff => 3,
+        0x100..=0xffff => 4,
+        0x10000..=0xff_ffff => 5,
+        _ => 6,
+    }
+}
+
+fn encode_length(len: usize, encoded: &mut Vec<u8>) {
+    if len < 0x80 {
+        encoded.push(len as u8);
+    } else {
+        let bytes = (len as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|&&byte| byte == 0).count();
+        encoded.push(0x80 | (4 - skip) as u8);
+        encoded.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+pub fn encode_sequence(tag: u8, items: &[&[u8]]) -> Vec<u8> {
+    encode(tag, &items.concat())
+}
+
+pub fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Strip the redundant leading bytes, keeping the sign bit.
+    let mut skip = 0;
+    while skip < 7
+        && ((bytes[skip] == 0 && bytes[skip + 1] & 0x80 == 0)
+            || (bytes[skip] == 0xff && bytes[skip + 1] & 0x80 != 0))
+    {
+        skip += 1;
+    }
+    encode(tag::INTEGER, &bytes[skip..])
+}
+
+pub fn encode_unsigned(tag: u8, value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes[..7].iter().take_while(|&&byte| byte == 0).count();
+    if bytes[skip] & 0x80 != 0 {
+        let mut value = vec![0];
+        value.extend_from_slice(&bytes[skip..]);
+        encode(tag, &value)
+    } else {
+        encode(tag, &bytes[skip..])
+    }
+}
+
+pub fn encode_octet_string(value: &[u8]) -> Vec<u8> {
+    encode(tag::OCTET_STRING, value)
+}
+
+/// An object identifier.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Oid(Vec<u32>);
+
+impl Oid {
+    pub fn decode(value: &[u8]) -> Result<Self, BerError> {
+        let mut arcs = Vec::with_capacity(value.len() + 1);
+        let mut arc = 0u32;
+        for (i, &byte) in value.iter().enumerate() {
+            if arc == 0 && byte == 0x80 {
+                return Err(BerError::InvalidOid);
+            }
+            arc = arc
+                .checked_mul(128)
+                .ok_or(BerError::InvalidOid)?
+                .checked_add((byte & 0x7f) as u32)
+                .ok_or(BerError::InvalidOid)?;
+            if byte & 0x80 == 0 {
+                if arcs.is_empty() {
+                    // The first subidentifier combines the first two arcs.
+                    let first = (arc / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(arc - first * 40);
+                } else {
+                    arcs.push(arc);
+                }
+                arc = 0;
+            } else if i == value.len() - 1 {
+                return Err(BerError::InvalidOid);
+            }
+        }
+        Ok(Self(arcs))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut value = Vec::with_capacity(self.0.len() + 4);
+        let (first, rest) = match self.0.as_slice() {
+            [] => (0, &[][..]),
+            [first] => (first * 40, &[][..]),
+            [first, second, rest @ ..] => (first * 40 + second, rest),
+        };
+        for &arc in std::iter::once(&first).chain(rest) {
+            let mut bytes = vec![(arc & 0x7f) as u8];
+            let mut arc = arc >> 7;
+            while arc > 0 {
+                bytes.push(0x80 | (arc & 0x7f) as u8);
+                arc >>= 7;
+            }
+            value.extend(bytes.into_iter().rev());
+        }
+        encode(tag::OBJECT_IDENTIFIER, &value)
+    }
+
+    pub fn arcs(&self) -> &[u32] {
+        &self.0
+    }
+
+    pub fn starts_with(&self, prefix: &Oid) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    pub fn child(&self, arcs: &[u32]) -> Self {
+        let mut oid = self.0.clone();
+        oid.extend_from_slice(arcs);
+        Self(oid)
+    }
+}
+
+impl From<Vec<u32>> for Oid {
+    fn from(arcs: Vec<u32>) -> Self {
+        Self(arcs)
+    }
+}
+
+impl FromStr for Oid {
+    type Err = BerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim_start_matches('.')
+            .split('.')
+            .map(|arc| arc.parse().map_err(|_| BerError::InvalidOid))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, arc) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of a variable binding.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VarBindValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    Oid(Oid),
+    IpAddress([u8; 4]),
+    Counter32(u32),
+    Gauge32(u32),
+    TimeTicks(u32),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl VarBindValue {
+    fn decode(tag: u8, value: &[u8]) -> Result<Self, BerError> {
+        let unsigned32 = |value| {
+            decode_unsigned(value)
+                .and_then(|value| u32::try_from(value).map_err(|_| BerError::IntegerOverflow))
+        };
+        Ok(match tag {
+            tag::INTEGER => Self::Integer(decode_signed(value)?),
+            tag::OCTET_STRING => Self::OctetString(value.to_vec()),
+            tag::NULL => Self::Null,
+            tag::OBJECT_IDENTIFIER => Self::Oid(Oid::decode(value)?),
+            tag::IP_ADDRESS => {
+                Self::IpAddress(value.try_into().map_err(|_| BerError::InvalidLength)?)
+            }
+            tag::COUNTER32 => Self::Counter32(unsigned32(value)?),
+            tag::GAUGE32 => Self::Gauge32(unsigned32(value)?),
+            tag::TIMETICKS => Self::TimeTicks(unsigned32(value)?),
+            tag::OPAQUE => Self::Opaque(value.to_vec()),
+            tag::COUNTER64 => Self::Counter64(decode_unsigned(value)?),
+            tag::NO_SUCH_OBJECT => Self::NoSuchObject,
+            tag::NO_SUCH_INSTANCE => Self::NoSuchInstance,
+            tag::END_OF_MIB_VIEW => Self::EndOfMibView,
+            found => {
+                return Err(BerError::UnexpectedTag {
+                    expected: tag::NULL,
+                    found,
+                })
+            }
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Integer(value) => encode_integer(*value),
+            Self::OctetString(value) => encode_octet_string(value),
+            Self::Null => encode(tag::NULL, &[]),
+            Self::Oid(oid) => oid.encode(),
+            Self::IpAddress(address) => encode(tag::IP_ADDRESS, address),
+            Self::Counter32(value) => encode_unsigned(tag::COUNTER32, *value as u64),
+            Self::Gauge32(value) => encode_unsigned(tag::GAUGE32, *value as u64),
+            Self::TimeTicks(value) => encode_unsigned(tag::TIMETICKS, *value as u64),
+            Self::Opaque(value) => encode(tag::OPAQUE, value),
+            Self::Counter64(value) => encode_unsigned(tag::COUNTER64, *value),
+            Self::NoSuchObject => encode(tag::NO_SUCH_OBJECT, &[]),
+            Self::NoSuchInstance => encode(tag::NO_SUCH_INSTANCE, &[]),
+            Self::EndOfMibView => encode(tag::END_OF_MIB_VIEW, &[]),
+        }
+    }
+}
+
+/// A variable binding, associating an object instance with its value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VarBind {
+    pub oid: Oid,
+    pub value: VarBindValue,
+}
+
+impl VarBind {
+    pub const fn new(oid: Oid, value: VarBindValue) -> Self {
+        Self { oid, value }
+    }
+
+    pub fn decode_list(value: &[u8]) -> Result<Vec<Self>, BerError> {
+        let mut reader = Reader::new(value);
+        let mut varbinds = Vec::new();
+        while !reader.is_empty() {
+            let mut varbind = reader.read_sequence()?;
+            let oid = varbind.read_oid()?;
+            let (tag, value) = varbind.read_any()?;
+            varbinds.push(Self::new(oid, VarBindValue::decode(tag, value)?));
+        }
+        Ok(varbinds)
+    }
+
+    pub fn encode_list(varbinds: &[Self]) -> Vec<u8> {
+        let items = varbinds
+            .iter()
+            .map(|varbind| {
+                encode_sequence(
+                    tag::SEQUENCE,
+                    &[&varbind.oid.encode(), &varbind.value.encode()],
+                )
+            })
+            .collect::<Vec<_>>();
+        encode(tag::SEQUENCE, &items.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips_integers() {
+        for value in [0, 1, -1, 127, 128, -128, -129, 65535, i64::MAX, i64::MIN] {
+            let encoded = encode_integer(value);
+            assert_eq!(Reader::new(&encoded).read_integer(), Ok(value));
+        }
+        assert_eq!(encode_integer(128), vec![0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(encode_integer(-128), vec![0x02, 0x01, 0x80]);
+    }
+
+    #[test]
+    fn roundtrips_unsigned() {
+        for value in [0, 127, 128, u32::MAX as u64, u64::MAX] {
+            let encoded = encode_unsigned(tag::COUNTER64, value);
+            let (tag, value_bytes) = Reader::new(&encoded).read_any().unwrap();
+            assert_eq!(tag, tag::COUNTER64);
+            assert_eq!(decode_unsigned(value_bytes), Ok(value));
+        }
+    }
+
+    #[test]
+    fn roundtrips_oids() {
+        let oid = "1.3.6.1.4.1.8072.2.3.0.1".parse::<Oid>().unwrap();
+        let encoded = oid.encode();
+        assert_eq!(
+            encoded,
+            vec![0x06, 0x0b, 0x2b, 0x06, 0x01, 0x04, 0x01, 0xbf, 0x08, 0x02, 0x03, 0x00, 0x01]
+        );
+        assert_eq!(Reader::new(&encoded).read_oid(), Ok(oid.clone()));
+        assert_eq!(oid.to_string(), "1.3.6.1.4.1.8072.2.3.0.1");
+        assert_eq!(Oid::decode(&[0x2b, 0x86]), Err(BerError::InvalidOid));
+    }
+
+    #[test]
+    fn decodes_long_lengths() {
+        let value = vec![0x42; 300];
+        let encoded = encode_octet_string(&value);
+        assert_eq!(&encoded[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(header_len(value.len()), 4);
+        assert_eq!(Reader::new(&encoded).read_octet_string(), Ok(&value[..]));
+        assert_eq!(
+            Reader::new(&encoded[..100]).read_octet_string(),
+            Err(BerError::Truncated)
+        );
+    }
+
+    #[test]
+    fn roundtrips_varbinds() {
+        let varbinds = vec![
+            VarBind::new(
+                "1.3.6.1.2.1.1.3.0".parse().unwrap(),
+                VarBindValue::TimeTicks(123456),
+            ),
+            VarBind::new(
+                "1.3.6.1.6.3.1.1.4.1.0".parse().unwrap(),
+                VarBindValue::Oid("1.3.6.1.6.3.1.1.5.3".parse().unwrap()),
+            ),
+            VarBind::new(
+                "1.3.6.1.2.1.2.2.1.2.1".parse().unwrap(),
+                VarBindValue::OctetString(b"eth0".to_vec()),
+            ),
+            VarBind::new(
+                "1.3.6.1.2.1.31.1.1.1.6.1".parse().unwrap(),
+                VarBindValue::Counter64(u64::MAX),
+            ),
+            VarBind::new(
+                "1.3.6.1.6.3.18.1.3.0".parse().unwrap(),
+                VarBindValue::IpAddress([192, 0, 2, 1]),
+            ),
+        ];
+        let encoded = VarBind::encode_list(&varbinds);
+        let value = Reader::new(&encoded).read(tag::SEQUENCE).unwrap();
+        assert_eq!(VarBind::decode_list(value), Ok(varbinds));
+    }
+}
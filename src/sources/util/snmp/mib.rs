@@ -0,0 +1,625 @@
+//! A loader of the object definitions of SMIv1 and SMIv2 MIB modules, used to name OIDs.
+//!
+//! This is not a complete ASN.1 parser: only the OID assignments, the named numbers of `INTEGER`
+//! syntaxes and the textual conventions defining them are read, which is all that is needed to
+//! turn OIDs and enumerated values into names.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+use super::ber::Oid;
+
+#[derive(Debug, Snafu)]
+pub enum MibError {
+    #[snafu(display("Failed to read MIB file {:?}: {}", path, source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// A named object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MibObject {
+    pub module: String,
+    pub name: String,
+    pub enums: BTreeMap<i64, String>,
+}
+
+/// The objects loaded from MIB modules, indexed by OID.
+#[derive(Clone, Debug)]
+pub struct Mib {
+    objects: BTreeMap<Oid, MibObject>,
+}
+
+/// The objects commonly found in traps, which are known without loading their MIB modules.
+const BUILTIN_OBJECTS: &[(&str, &str, &str)] = &[
+    ("SNMPv2-SMI", "iso", "1"),
+    ("SNMPv2-SMI", "org", "1.3"),
+    ("SNMPv2-SMI", "dod", "1.3.6"),
+    ("SNMPv2-SMI", "internet", "1.3.6.1"),
+    ("SNMPv2-SMI", "directory", "1.3.6.1.1"),
+    ("SNMPv2-SMI", "mgmt", "1.3.6.1.2"),
+    ("SNMPv2-SMI", "mib-2", "1.3.6.1.2.1"),
+    ("SNMPv2-SMI", "transmission", "1.3.6.1.2.1.10"),
+    ("SNMPv2-SMI", "experimental", "1.3.6.1.3"),
+    ("SNMPv2-SMI", "private", "1.3.6.1.4"),
+    ("SNMPv2-SMI", "enterprises", "1.3.6.1.4.1"),
+    ("SNMPv2-SMI", "security", "1.3.6.1.5"),
+    ("SNMPv2-SMI", "snmpV2", "1.3.6.1.6"),
+    ("SNMPv2-SMI", "snmpDomains", "1.3.6.1.6.1"),
+    ("SNMPv2-SMI", "snmpProxys", "1.3.6.1.6.2"),
+    ("SNMPv2-SMI", "snmpModules", "1.3.6.1.6.3"),
+    ("SNMPv2-MIB", "system", "1.3.6.1.2.1.1"),
+    ("SNMPv2-MIB", "sysDescr", "1.3.6.1.2.1.1.1"),
+    ("SNMPv2-MIB", "sysObjectID", "1.3.6.1.2.1.1.2"),
+    ("SNMPv2-MIB", "sysUpTime", "1.3.6.1.2.1.1.3"),
+    ("SNMPv2-MIB", "sysContact", "1.3.6.1.2.1.1.4"),
+    ("SNMPv2-MIB", "sysName", "1.3.6.1.2.1.1.5"),
+    ("SNMPv2-MIB", "sysLocation", "1.3.6.1.2.1.1.6"),
+    ("SNMPv2-MIB", "snmpTrapOID", "1.3.6.1.6.3.1.1.4.1"),
+    ("SNMPv2-MIB", "snmpTrapEnterprise", "1.3.6.1.6.3.1.1.4.3"),
+    ("SNMPv2-MIB", "coldStart", "1.3.6.1.6.3.1.1.5.1"),
+    ("SNMPv2-MIB", "warmStart", "1.3.6.1.6.3.1.1.5.2"),
+    ("IF-MIB", "linkDown", "1.3.6.1.6.3.1.1.5.3"),
+    ("IF-MIB", "linkUp", "1.3.6.1.6.3.1.1.5.4"),
+    ("SNMPv2-MIB", "authenticationFailure", "1.3.6.1.6.3.1.1.5.5"),
+    ("RFC1213-MIB", "egpNeighborLoss", "1.3.6.1.6.3.1.1.5.6"),
+    (
+        "SNMP-COMMUNITY-MIB",
+        "snmpTrapAddress",
+        "1.3.6.1.6.3.18.1.3",
+    ),
+    (
+        "SNMP-COMMUNITY-MIB",
+        "snmpTrapCommunity",
+        "1.3.6.1.6.3.18.1.4",
+    ),
+];
+
+/// The macros defining objects with an OID.
+const OBJECT_MACROS: &[&str] = &[
+    "AGENT-CAPABILITIES",
+    "MODULE-COMPLIANCE",
+    "MODULE-IDENTITY",
+    "NOTIFICATION-GROUP",
+    "NOTIFICATION-TYPE",
+    "OBJECT-GROUP",
+    "OBJECT-IDENTITY",
+    "OBJECT-TYPE",
+    "TRAP-TYPE",
+];
+
+/// An object definition, with an OID relative to a parent which may be defined in another module.
+#[derive(Debug)]
+struct Definition {
+    module: String,
+    name: String,
+    parent: Option<String>,
+    arcs: Vec<u32>,
+    syntax: Option<String>,
+    enums: BTreeMap<i64, String>,
+}
+
+impl Mib {
+    /// Loads the MIB modules found at the given paths, which are either files or directories of
+    /// files.
+    pub fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut sources = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let mut entries = fs::read_dir(path)
+                    .context(ReadSnafu { path })?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ReadSnafu { path })?;
+                entries.sort();
+                for entry in entries.into_iter().filter(|entry| entry.is_file()) {
+                    sources.push(read(&entry)?);
+                }
+            } else {
+                sources.push(read(path)?);
+            }
+        }
+        Ok(Self::parse(sources.iter().map(String::as_str)))
+    }
+
+    /// Parses the MIB modules, resolving definitions across them.
+    pub fn parse<'a>(sources: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut definitions = Vec::new();
+        let mut conventions = HashMap::new();
+        for source in sources {
+            parse_module(&tokenize(source), &mut definitions, &mut conventions);
+        }
+
+        let mut objects = BTreeMap::new();
+        let mut oids = HashMap::new();
+        for (module, name, oid) in BUILTIN_OBJECTS {
+            let oid = oid.parse::<Oid>().expect("valid builtin OID");
+            oids.insert(name.to_string(), oid.clone());
+            objects.insert(
+                oid,
+                MibObject {
+                    module: module.to_string(),
+                    name: name.to_string(),
+                    enums: BTreeMap::new(),
+                },
+            );
+        }
+
+        // Definitions can refer to parents defined later, or in modules loaded later, so they are
+        // resolved until no progress is made.
+        loop {
+            let count = definitions.len();
+            definitions.retain_mut(|definition: &mut Definition| {
+                let parent = match &definition.parent {
+                    None => Oid::default(),
+                    Some(parent) => match oids.get(parent) {
+                        Some(oid) => oid.clone(),
+                        None => return true,
+                    },
+                };
+                let oid = parent.child(&definition.arcs);
+                let mut enums = std::mem::take(&mut definition.enums);
+                if enums.is_empty() {
+                    if let Some(convention) = definition
+                        .syntax
+                        .as_ref()
+                        .and_then(|syntax| conventions.get(syntax))
+                    {
+                        enums = convention.clone();
+                    }
+                }
+                oids.insert(definition.name.clone(), oid.clone());
+                objects.insert(
+                    oid,
+                    MibObject {
+                        module: definition.module.clone(),
+                        name: definition.name.clone(),
+                        enums,
+                    },
+                );
+                false
+            });
+            if definitions.is_empty() || definitions.len() == count {
+                break;
+            }
+        }
+        if !definitions.is_empty() {
+            warn!(
+                message = "Some MIB objects could not be resolved, as their parent is not defined.",
+                objects = ?definitions.iter().map(|definition| definition.name.as_str()).collect::<Vec<_>>(),
+            );
+        }
+
+        Self { objects }
+    }
+
+    /// Finds the object with the longest OID prefixing `oid`, and returns it with the remaining
+    /// arcs, which form the instance index.
+    pub fn lookup<'a>(&self, oid: &'a Oid) -> Option<(&MibObject, &'a [u32])> {
+        (1..=oid.arcs().len()).rev().find_map(|len| {
+            self.objects
+                .get(&Oid::from(oid.arcs()[..len].to_vec()))
+                .map(|object| (object, &oid.arcs()[len..]))
+        })
+    }
+
+    /// Names an OID `MODULE::object.index`, or formats it numerically when it is unknown.
+    pub fn name(&self, oid: &Oid) -> String {
+        match self.lookup(oid) {
+            Some((object, index)) => {
+                let mut name = format!("{}::{}", object.module, object.name);
+                for arc in index {
+                    name.push('.');
+                    name.push_str(&arc.to_string());
+                }
+                name
+            }
+            None => oid.to_string(),
+        }
+    }
+}
+
+impl Default for Mib {
+    fn default() -> Self {
+        Self::parse([])
+    }
+}
+
+fn read(path: &Path) -> Result<String, MibError> {
+    fs::read(path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .context(ReadSnafu { path })
+}
+
+/// Splits a module into tokens, skipping comments and strings.
+fn tokenize(source: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("--") {
+            // Comments end at the end of the line, or at the next `--`.
+            i += 2;
+            while i < bytes.len() && bytes[i] != b'\n' && !source[i..].starts_with("--") {
+                i += 1;
+            }
+            i += 2.min(bytes.len() - i);
+        } else if c == b'"' || c == b'\'' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != c {
+                i += 1;
+            }
+            i += 1;
+        } else if source[i..].starts_with("::=") {
+            tokens.push("::=");
+            i += 3;
+        } else if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' {
+            let start = i;
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-' || bytes[i] == b'_')
+                && !source[i..].starts_with("--")
+            {
+                i += 1;
+            }
+            tokens.push(&source[start..i]);
+        } else {
+            tokens.push(&source[i..i + 1]);
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn is_value_name(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_lowercase())
+}
+
+fn is_type_name(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn parse_module(
+    tokens: &[&str],
+    definitions: &mut Vec<Definition>,
+    conventions: &mut HashMap<String, BTreeMap<i64, String>>,
+) {
+    let mut module = String::new();
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        let (token, next) = (tokens[i], tokens[i + 1]);
+
+        if next == "DEFINITIONS" {
+            module = token.to_owned();
+            i += 2;
+        } else if is_value_name(token) && OBJECT_MACROS.contains(&next) {
+            i = parse_object(tokens, i, &module, definitions);
+        } else if is_value_name(token)
+            && tokens[i + 1..].starts_with(&["OBJECT", "IDENTIFIER", "::="])
+        {
+            if let Some((parent, arcs, end)) = parse_oid_value(tokens, i + 4) {
+                definitions.push(Definition {
+                    module: module.clone(),
+                    name: token.to_owned(),
+                    parent,
+                    arcs,
+                    syntax: None,
+                    enums: BTreeMap::new(),
+                });
+                i = end;
+            } else {
+                i += 4;
+            }
+        } else if is_type_name(token) && next == "::=" {
+            // Textual conventions, and types, with named numbers.
+            let mut j = i + 2;
+            if tokens.get(j) == Some(&"TEXTUAL-CONVENTION") {
+                while j < tokens.len() && tokens[j] != "SYNTAX" && tokens[j] != "::=" {
+                    j += 1;
+                }
+                j += 1;
+            }
+            if let Some((enums, end)) = parse_enums(tokens, j) {
+                conventions.insert(token.to_owned(), enums);
+                i = end;
+            } else {
+                i = j;
+            }
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Parses an object defined with a macro, returning the index following its definition.
+fn parse_object(
+    tokens: &[&str],
+    start: usize,
+    module: &str,
+    definitions: &mut Vec<Definition>,
+) -> usize {
+    let name = tokens[start];
+    let is_trap = tokens[start + 1] == "TRAP-TYPE";
+    let mut syntax = None;
+    let mut enums = BTreeMap::new();
+    let mut enterprise = None;
+
+    let mut i = start + 2;
+    while i < tokens.len() && tokens[i] != "::=" {
+        match tokens[i] {
+            "SYNTAX" if i + 1 < tokens.len() => {
+                syntax = Some(tokens[i + 1].to_owned());
+                if let Some((parsed, end)) = parse_enums(tokens, i + 1) {
+                    enums = parsed;
+                    i = end;
+                    continue;
+                }
+            }
+            "ENTERPRISE" if i + 1 < tokens.len() => enterprise = Some(tokens[i + 1].to_owned()),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if is_trap {
+        // SMIv1 traps are numbered under their enterprise, which maps to `enterprise.0.number`
+        // as described in RFC 3584.
+        let number = tokens.get(i + 1).and_then(|number| number.parse().ok());
+        if let (Some(enterprise), Some(number)) = (enterprise, number) {
+            definitions.push(Definition {
+                module: module.to_owned(),
+                name: name.to_owned(),
+                parent: Some(enterprise),
+                arcs: vec![0, number],
+                syntax,
+                enums,
+            });
+        }
+        return i + 2;
+    }
+
+    match parse_oid_value(tokens, i + 1) {
+        Some((parent, arcs, end)) => {
+            definitions.push(Definition {
+                module: module.to_owned(),
+                name: name.to_owned(),
+                parent,
+                arcs,
+                syntax,
+                enums,
+            });
+            end
+        }
+        None => i + 1,
+    }
+}
+
+/// Parses a `{ parent 1 2 }` or `{ iso org(3) 6 }` OID value, returning the parent, the arcs
+/// relative to it, and the index following the value.
+fn parse_oid_value(tokens: &[&str], start: usize) -> Option<(Option<String>, Vec<u32>, usize)> {
+    if tokens.get(start) != Some(&"{") {
+        return None;
+    }
+    let mut parent = None;
+    let mut arcs = Vec::new();
+    let mut i = start + 1;
+    while i < tokens.len() && tokens[i] != "}" {
+        let token = tokens[i];
+        if let Ok(arc) = token.parse() {
+            arcs.push(arc);
+        } else if tokens.get(i + 1) == Some(&"(") {
+            // `name(number)`
+            arcs.push(tokens.get(i + 2)?.parse().ok()?);
+            i += 3;
+        } else if i == start + 1 {
+            parent = Some(token.to_owned());
+        } else {
+            return None;
+        }
+        i += 1;
+    }
+    Some((parent, arcs, i + 1))
+}
+
+/// Parses the `INTEGER { name(1), ... }` named numbers of a syntax, returning them with the
+/// index following them.
+fn parse_enums(tokens: &[&str], start: usize) -> Option<(BTreeMap<i64, String>, usize)> {
+    if !matches!(tokens.get(start), Some(&"INTEGER") | Some(&"Integer32"))
+        || tokens.get(start + 1) != Some(&"{")
+    {
+        return None;
+    }
+    let mut enums = BTreeMap::new();
+    let mut i = start + 2;
+    while i + 3 < tokens.len() && tokens[i] != "}" {
+        // `name ( number )`
+        if let Ok(number) = tokens[i + 2].parse() {
+            enums.insert(number, tokens[i].to_owned());
+        }
+        i += 4;
+        if tokens.get(i) == Some(&",") {
+            i += 1;
+        }
+    }
+    Some((enums, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IF_MIB: &str = r#"
+IF-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Counter32, Gauge32, Integer32,
+    mib-2, NOTIFICATION-TYPE                 FROM SNMPv2-SMI
+    TEXTUAL-CONVENTION, DisplayString        FROM SNMPv2-TC;
+
+ifMIB MODULE-IDENTITY
+    LAST-UPDATED "200006140000Z"
+    ORGANIZATION "IETF Interfaces MIB Working Group"
+    DESCRIPTION
+            "The MIB module to describe generic objects for network
+            interface sub-layers. -- not a comment ::= { nothing }"
+    ::= { mib-2 31 }
+
+InterfaceIndex ::= TEXTUAL-CONVENTION
+    DISPLAY-HINT "d"
+    STATUS       current
+    DESCRIPTION  "A unique value, greater than zero, for each interface."
+    SYNTAX       Integer32 (1..2147483647)
+
+IfOperStatus ::= TEXTUAL-CONVENTION
+    STATUS       current
+    DESCRIPTION  "The operational status of an interface."
+    SYNTAX       INTEGER {
+                    up(1),        -- ready to pass packets
+                    down(2),
+                    testing(3)
+                 }
+
+interfaces   OBJECT IDENTIFIER ::= { mib-2 2 }
+
+ifTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A list of interface entries."
+    ::= { interfaces 2 }
+
+ifEntry OBJECT-TYPE
+    SYNTAX      IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "An entry containing management information."
+    INDEX   { ifIndex }
+    ::= { ifTable 1 }
+
+ifIndex OBJECT-TYPE
+    SYNTAX      InterfaceIndex
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "A unique value, greater than zero, for each interface."
+    ::= { ifEntry 1 }
+
+ifAdminStatus OBJECT-TYPE
+    SYNTAX  INTEGER {
+                up(1),       -- ready to pass packets
+                down(2),
+                testing(3)   -- in some test mode
+            }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The desired state of the interface."
+    ::= { ifEntry 7 }
+
+ifOperStatus OBJECT-TYPE
+    SYNTAX      IfOperStatus
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The current operational state of the interface."
+    ::= { ifEntry 8 }
+
+END
+"#;
+
+    const V1_MIB: &str = r#"
+ACME-TRAP-MIB DEFINITIONS ::= BEGIN
+
+acme OBJECT IDENTIFIER ::= { iso org(3) dod(6) internet(1) private(4) enterprises(1) 99999 }
+
+acmeFanFailure TRAP-TYPE
+    ENTERPRISE  acme
+    VARIABLES   { acmeFanIndex }
+    DESCRIPTION "A fan failed."
+    ::= 7
+
+acmeFanIndex OBJECT-TYPE
+    SYNTAX  INTEGER
+    ACCESS  read-only
+    STATUS  mandatory
+    ::= { acme 1 1 }
+
+END
+"#;
+
+    #[test]
+    fn resolves_objects() {
+        let mib = Mib::parse([IF_MIB]);
+
+        let oid = "1.3.6.1.2.1.2.2.1.7.3".parse::<Oid>().unwrap();
+        let (object, index) = mib.lookup(&oid).unwrap();
+        assert_eq!(object.module, "IF-MIB");
+        assert_eq!(object.name, "ifAdminStatus");
+        assert_eq!(index, &[3]);
+        assert_eq!(object.enums.get(&2).map(String::as_str), Some("down"));
+        assert_eq!(mib.name(&oid), "IF-MIB::ifAdminStatus.3");
+
+        let oid = "1.3.6.1.2.1.2.2.1.8.3".parse::<Oid>().unwrap();
+        assert_eq!(
+            mib.lookup(&oid)
+                .unwrap()
+                .0
+                .enums
+                .get(&3)
+                .map(String::as_str),
+            Some("testing")
+        );
+        assert_eq!(
+            mib.name(&"1.3.6.1.2.1.31".parse().unwrap()),
+            "IF-MIB::ifMIB"
+        );
+    }
+
+    #[test]
+    fn resolves_builtin_objects() {
+        let mib = Mib::default();
+        assert_eq!(
+            mib.name(&"1.3.6.1.2.1.1.3.0".parse().unwrap()),
+            "SNMPv2-MIB::sysUpTime.0"
+        );
+        assert_eq!(
+            mib.name(&"1.3.6.1.4.1.99999.1".parse().unwrap()),
+            "SNMPv2-SMI::enterprises.99999.1"
+        );
+        assert_eq!(mib.name(&"2.5.4".parse().unwrap()), "2.5.4");
+    }
+
+    #[test]
+    fn resolves_objects_across_modules() {
+        let dependent = r#"
+ACME-IF-MIB DEFINITIONS ::= BEGIN
+acmeIfExtension OBJECT IDENTIFIER ::= { ifMIB 99 }
+END
+"#;
+        // The order in which modules are loaded does not matter.
+        let mib = Mib::parse([dependent, IF_MIB]);
+        assert_eq!(
+            mib.name(&"1.3.6.1.2.1.31.99.1".parse().unwrap()),
+            "ACME-IF-MIB::acmeIfExtension.1"
+        );
+    }
+
+    #[test]
+    fn resolves_v1_traps() {
+        let mib = Mib::parse([V1_MIB]);
+        assert_eq!(
+            mib.name(&"1.3.6.1.4.1.99999.0.7".parse().unwrap()),
+            "ACME-TRAP-MIB::acmeFanFailure"
+        );
+        assert_eq!(
+            mib.name(&"1.3.6.1.4.1.99999.1.1.0".parse().unwrap()),
+            "ACME-TRAP-MIB::acmeFanIndex.0"
+        );
+    }
+}
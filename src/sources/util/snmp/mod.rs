@@ -0,0 +1,300 @@
+//! Decoding and encoding of SNMP messages, shared by the SNMP sources.
+
+pub mod ber;
+pub mod mib;
+pub mod usm;
+
+use snafu::Snafu;
+
+use self::{
+    ber::{tag, BerError, Oid, Reader, VarBind},
+    usm::{UsmError, V3Message},
+};
+
+#[derive(Debug, Snafu)]
+pub enum MessageError {
+    #[snafu(display("Invalid message: {}", source))]
+    InvalidBer { source: BerError },
+    #[snafu(display("Unsupported SNMP version {}", version))]
+    UnsupportedVersion { version: i64 },
+    #[snafu(display("Unexpected PDU type {:#04x}", pdu_tag))]
+    UnexpectedPdu { pdu_tag: u8 },
+    #[snafu(display("{}", source))]
+    Usm { source: UsmError },
+}
+
+impl From<BerError> for MessageError {
+    fn from(source: BerError) -> Self {
+        Self::InvalidBer { source }
+    }
+}
+
+impl From<UsmError> for MessageError {
+    fn from(source: UsmError) -> Self {
+        Self::Usm { source }
+    }
+}
+
+/// An SNMP version.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Version {
+    V1,
+    V2c,
+    V3,
+}
+
+impl Version {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "1",
+            Self::V2c => "2c",
+            Self::V3 => "3",
+        }
+    }
+
+    /// The number of the version, as encoded in messages.
+    pub const fn number(self) -> i64 {
+        match self {
+            Self::V1 => 0,
+            Self::V2c => 1,
+            Self::V3 => 3,
+        }
+    }
+}
+
+/// A received message, whose PDU is not decoded yet.
+#[derive(Debug)]
+pub enum Message<'a> {
+    Community {
+        version: Version,
+        community: &'a [u8],
+        pdu_tag: u8,
+        pdu: &'a [u8],
+    },
+    V3(V3Message<'a>),
+}
+
+impl<'a> Message<'a> {
+    pub fn decode(data: &'a [u8]) -> Result<Self, MessageError> {
+        let (message, message_value) = {
+            let mut reader = Reader::new(data);
+            let (found, value) = reader.read_any()?;
+            if found != tag::SEQUENCE {
+                return Err(BerError::UnexpectedTag {
+                    expected: tag::SEQUENCE,
+                    found,
+                }
+                .into());
+            }
+            // Only the message itself is covered by authentication, not trailing bytes.
+            (&data[..data.len() - reader.remaining()], value)
+        };
+
+        let mut reader = Reader::new(message_value);
+        let version = match reader.read_integer()? {
+            0 => Version::V1,
+            1 => Version::V2c,
+            3 => return Ok(Self::V3(V3Message::decode(message, reader)?)),
+            version => return Err(MessageError::UnsupportedVersion { version }),
+        };
+        let community = reader.read_octet_string()?;
+        let (pdu_tag, pdu) = reader.read_any()?;
+        Ok(Self::Community {
+            version,
+            community,
+            pdu_tag,
+            pdu,
+        })
+    }
+}
+
+/// A PDU of any type but the SNMPv1 trap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pdu {
+    pub request_id: i64,
+    pub error_status: i64,
+    pub error_index: i64,
+    pub varbinds: Vec<VarBind>,
+}
+
+impl Pdu {
+    pub fn decode(value: &[u8]) -> Result<Self, BerError> {
+        let mut reader = Reader::new(value);
+        Ok(Self {
+            request_id: reader.read_integer()?,
+            error_status: reader.read_integer()?,
+            error_index: reader.read_integer()?,
+            varbinds: VarBind::decode_list(reader.read(tag::SEQUENCE)?)?,
+        })
+    }
+
+    /// Encodes the value of the PDU, without its tag.
+    pub fn encode(&self) -> Vec<u8> {
+        [
+            ber::encode_integer(self.request_id),
+            ber::encode_integer(self.error_status),
+            ber::encode_integer(self.error_index),
+            VarBind::encode_list(&self.varbinds),
+        ]
+        .concat()
+    }
+}
+
+/// An SNMPv1 trap PDU.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrapV1Pdu {
+    pub enterprise: Oid,
+    pub agent_address: [u8; 4],
+    pub generic_trap: i64,
+    pub specific_trap: i64,
+    pub timestamp: u32,
+    pub varbinds: Vec<VarBind>,
+}
+
+impl TrapV1Pdu {
+    pub fn decode(value: &[u8]) -> Result<Self, BerError> {
+        let mut reader = Reader::new(value);
+        let enterprise = reader.read_oid()?;
+        let agent_address = reader
+            .read(tag::IP_ADDRESS)?
+            .try_into()
+            .map_err(|_| BerError::InvalidLength)?;
+        let generic_trap = reader.read_integer()?;
+        let specific_trap = reader.read_integer()?;
+        let timestamp = ber::decode_unsigned(reader.read(tag::TIMETICKS)?)?
+            .try_into()
+            .map_err(|_| BerError::IntegerOverflow)?;
+        Ok(Self {
+            enterprise,
+            agent_address,
+            generic_trap,
+            specific_trap,
+            timestamp,
+            varbinds: VarBind::decode_list(reader.read(tag::SEQUENCE)?)?,
+        })
+    }
+
+    /// The SNMPv2 trap OID, as described in RFC 3584 section 3.1.
+    pub fn trap_oid(&self) -> Oid {
+        if (0..6).contains(&self.generic_trap) {
+            Oid::from(vec![
+                1,
+                3,
+                6,
+                1,
+                6,
+                3,
+                1,
+                1,
+                5,
+                self.generic_trap as u32 + 1,
+            ])
+        } else {
+            self.enterprise.child(&[0, self.specific_trap as u32])
+        }
+    }
+}
+
+/// Encodes an SNMPv1 or SNMPv2c message.
+pub fn encode_community_message(
+    version: Version,
+    community: &[u8],
+    pdu_tag: u8,
+    pdu: &Pdu,
+) -> Vec<u8> {
+    ber::encode_sequence(
+        tag::SEQUENCE,
+        &[
+            &ber::encode_integer(version.number()),
+            &ber::encode_octet_string(community),
+            &ber::encode(pdu_tag, &pdu.encode()),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ber::VarBindValue, *};
+
+    #[test]
+    fn roundtrips_community_messages() {
+        let pdu = Pdu {
+            request_id: 1234,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![VarBind::new(
+                "1.3.6.1.2.1.1.3.0".parse().unwrap(),
+                VarBindValue::TimeTicks(42),
+            )],
+        };
+        let encoded = encode_community_message(Version::V2c, b"public", tag::INFORM_REQUEST, &pdu);
+
+        match Message::decode(&encoded).unwrap() {
+            Message::Community {
+                version,
+                community,
+                pdu_tag,
+                pdu: value,
+            } => {
+                assert_eq!(version, Version::V2c);
+                assert_eq!(community, b"public");
+                assert_eq!(pdu_tag, tag::INFORM_REQUEST);
+                assert_eq!(Pdu::decode(value).unwrap(), pdu);
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn decodes_v1_traps() {
+        let trap = TrapV1Pdu {
+            enterprise: "1.3.6.1.4.1.8072.2.3.1".parse().unwrap(),
+            agent_address: [192, 0, 2, 1],
+            generic_trap: 2,
+            specific_trap: 0,
+            timestamp: 100,
+            varbinds: vec![VarBind::new(
+                "1.3.6.1.2.1.2.2.1.1.3".parse().unwrap(),
+                VarBindValue::Integer(3),
+            )],
+        };
+        let value = [
+            trap.enterprise.encode(),
+            ber::encode(tag::IP_ADDRESS, &trap.agent_address),
+            ber::encode_integer(trap.generic_trap),
+            ber::encode_integer(trap.specific_trap),
+            ber::encode_unsigned(tag::TIMETICKS, trap.timestamp as u64),
+            VarBind::encode_list(&trap.varbinds),
+        ]
+        .concat();
+
+        let decoded = TrapV1Pdu::decode(&value).unwrap();
+        assert_eq!(decoded, trap);
+        assert_eq!(decoded.trap_oid().to_string(), "1.3.6.1.6.3.1.1.5.3");
+
+        let specific = TrapV1Pdu {
+            generic_trap: 6,
+            specific_trap: 7,
+            ..trap
+        };
+        assert_eq!(
+            specific.trap_oid().to_string(),
+            "1.3.6.1.4.1.8072.2.3.1.0.7"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let encoded = ber::encode_sequence(
+            tag::SEQUENCE,
+            &[
+                &ber::encode_integer(2),
+                &ber::encode_octet_string(b"public"),
+            ],
+        );
+        assert!(matches!(
+            Message::decode(&encoded),
+            Err(MessageError::UnsupportedVersion { version: 2 })
+        ));
+    }
+}
@@ -0,0 +1,693 @@
+//! The SNMPv3 [user-based security model](https://datatracker.ietf.org/doc/html/rfc3414), with the
+//! SHA-2 authentication protocols of [RFC 7860](https://datatracker.ietf.org/doc/html/rfc7860) and
+//! the AES privacy protocol of [RFC 3826](https://datatracker.ietf.org/doc/html/rfc3826).
+
+use openssl::{
+    error::ErrorStack,
+    hash::{hash, Hasher, MessageDigest},
+    memcmp,
+    pkey::PKey,
+    rand::rand_bytes,
+    sign::Signer,
+    symm::{Cipher, Crypter, Mode},
+};
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+
+use super::ber::{self, tag, BerError, Reader};
+
+pub const FLAG_AUTH: u8 = 0x01;
+pub const FLAG_PRIV: u8 = 0x02;
+pub const FLAG_REPORTABLE: u8 = 0x04;
+
+const SECURITY_MODEL_USM: i64 = 3;
+
+#[derive(Debug, Snafu)]
+pub enum UsmError {
+    #[snafu(display("Unknown user {:?}", user))]
+    UnknownUser { user: String },
+    #[snafu(display("Security level of the message does not match user {:?}", user))]
+    SecurityLevelMismatch { user: String },
+    #[snafu(display("Unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+    #[snafu(display("Wrong message digest"))]
+    WrongDigest,
+    #[snafu(display("Invalid privacy parameters"))]
+    InvalidPrivParameters,
+    #[snafu(display("Invalid message: {}", source))]
+    InvalidMessage { source: BerError },
+    #[snafu(display("Cryptographic operation failed: {}", source))]
+    Crypto { source: ErrorStack },
+}
+
+#[derive(Debug, Snafu)]
+pub enum UsmConfigError {
+    #[snafu(display("The passwords of user {:?} must be at least 8 characters long", user))]
+    PasswordTooShort { user: String },
+    #[snafu(display(
+        "User {:?} must set both a protocol and a password for {}",
+        user,
+        service
+    ))]
+    IncompleteCredentials { user: String, service: &'static str },
+    #[snafu(display("User {:?} cannot use privacy without authentication", user))]
+    PrivWithoutAuth { user: String },
+    #[snafu(display("Failed to derive the keys of user {:?}: {}", user, source))]
+    KeyDerivation { user: String, source: ErrorStack },
+}
+
+/// An SNMPv3 authentication protocol.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProtocol {
+    /// HMAC-MD5-96.
+    Md5,
+
+    /// HMAC-SHA-96.
+    Sha,
+
+    /// HMAC-SHA-224-128.
+    Sha224,
+
+    /// HMAC-SHA-256-192.
+    Sha256,
+
+    /// HMAC-SHA-384-256.
+    Sha384,
+
+    /// HMAC-SHA-512-384.
+    Sha512,
+}
+
+impl AuthProtocol {
+    fn digest(self) -> MessageDigest {
+        match self {
+            Self::Md5 => MessageDigest::md5(),
+            Self::Sha => MessageDigest::sha1(),
+            Self::Sha224 => MessageDigest::sha224(),
+            Self::Sha256 => MessageDigest::sha256(),
+            Self::Sha384 => MessageDigest::sha384(),
+            Self::Sha512 => MessageDigest::sha512(),
+        }
+    }
+
+    /// The length of the truncated HMAC sent in messages.
+    const fn mac_len(self) -> usize {
+        match self {
+            Self::Md5 | Self::Sha => 12,
+            Self::Sha224 => 16,
+            Self::Sha256 => 24,
+            Self::Sha384 => 32,
+            Self::Sha512 => 48,
+        }
+    }
+}
+
+/// An SNMPv3 privacy protocol.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivProtocol {
+    /// CBC-DES.
+    ///
+    /// DES is insecure, and may not be supported by the system cryptographic library.
+    Des,
+
+    /// CFB128-AES-128.
+    Aes,
+}
+
+/// The credentials of an SNMPv3 user.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UsmUserConfig {
+    /// The name of the user.
+    pub name: String,
+
+    #[configurable(derived)]
+    pub auth_protocol: Option<AuthProtocol>,
+
+    /// The authentication password.
+    pub auth_password: Option<String>,
+
+    #[configurable(derived)]
+    pub priv_protocol: Option<PrivProtocol>,
+
+    /// The privacy password.
+    pub priv_password: Option<String>,
+}
+
+/// An SNMPv3 user with its password-derived keys, which are localized to each engine.
+#[derive(Clone)]
+pub struct UsmUser {
+    name: String,
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivProtocol, Vec<u8>)>,
+}
+
+impl std::fmt::Debug for UsmUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UsmUser")
+            .field("name", &self.name)
+            .field("auth", &self.auth.as_ref().map(|(protocol, _)| protocol))
+            .field(
+                "privacy",
+                &self.privacy.as_ref().map(|(protocol, _)| protocol),
+            )
+            .finish()
+    }
+}
+
+impl UsmUser {
+    pub fn new(config: &UsmUserConfig) -> Result<Self, UsmConfigError> {
+        let user = || config.name.clone();
+        let derive_key = |protocol: AuthProtocol, password: &str| {
+            if password.len() < 8 {
+                return Err(UsmConfigError::PasswordTooShort { user: user() });
+            }
+            password_to_key(protocol.digest(), password.as_bytes())
+                .context(KeyDerivationSnafu { user: user() })
+        };
+
+        let auth = match (config.auth_protocol, &config.auth_password) {
+            (Some(protocol), Some(password)) => Some((protocol, derive_key(protocol, password)?)),
+            (None, None) => None,
+            _ => {
+                return Err(UsmConfigError::IncompleteCredentials {
+                    user: user(),
+                    service: "authentication",
+                })
+            }
+        };
+        let privacy = match (config.priv_protocol, &config.priv_password, &auth) {
+            (Some(protocol), Some(password), Some((auth_protocol, _))) => {
+                Some((protocol, derive_key(*auth_protocol, password)?))
+            }
+            (Some(_), Some(_), None) => {
+                return Err(UsmConfigError::PrivWithoutAuth { user: user() })
+            }
+            (None, None, _) => None,
+            _ => {
+                return Err(UsmConfigError::IncompleteCredentials {
+                    user: user(),
+                    service: "privacy",
+                })
+            }
+        };
+
+        Ok(Self {
+            name: config.name.clone(),
+            auth,
+            privacy,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The message flags matching the security level of the user.
+    pub fn flags(&self) -> u8 {
+        match (&self.auth, &self.privacy) {
+            (None, _) => 0,
+            (Some(_), None) => FLAG_AUTH,
+            (Some(_), Some(_)) => FLAG_AUTH | FLAG_PRIV,
+        }
+    }
+
+    fn localize(&self, engine_id: &[u8]) -> Result<LocalizedKeys, ErrorStack> {
+        let auth = self
+            .auth
+            .as_ref()
+            .map(|(protocol, key)| {
+                localize_key(protocol.digest(), key, engine_id).map(|key| (*protocol, key))
+            })
+            .transpose()?;
+        let privacy = match (&self.privacy, &auth) {
+            (Some((protocol, key)), Some((auth_protocol, _))) => Some((
+                *protocol,
+                localize_key(auth_protocol.digest(), key, engine_id)?,
+            )),
+            _ => None,
+        };
+        Ok(LocalizedKeys { auth, privacy })
+    }
+}
+
+struct LocalizedKeys {
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivProtocol, Vec<u8>)>,
+}
+
+impl LocalizedKeys {
+    fn mac(&self, message: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        let (protocol, key) = self.auth.as_ref().expect("authentication key");
+        let key = PKey::hmac(key)?;
+        let mut signer = Signer::new(protocol.digest(), &key)?;
+        signer.update(message)?;
+        let mut mac = signer.sign_to_vec()?;
+        mac.truncate(protocol.mac_len());
+        Ok(mac)
+    }
+
+    fn crypt(
+        &self,
+        mode: Mode,
+        engine_boots: i64,
+        engine_time: i64,
+        salt: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, UsmError> {
+        let (protocol, key) = self.privacy.as_ref().expect("privacy key");
+        if salt.len() != 8 {
+            return Err(UsmError::InvalidPrivParameters);
+        }
+        let (cipher, key, iv) = match protocol {
+            PrivProtocol::Des => {
+                if data.len() % 8 != 0 {
+                    return Err(UsmError::InvalidPrivParameters);
+                }
+                let iv = key[8..16]
+                    .iter()
+                    .zip(salt)
+                    .map(|(pre_iv, salt)| pre_iv ^ salt)
+                    .collect::<Vec<_>>();
+                (Cipher::des_cbc(), &key[..8], iv)
+            }
+            PrivProtocol::Aes => {
+                let mut iv = Vec::with_capacity(16);
+                iv.extend_from_slice(&(engine_boots as u32).to_be_bytes());
+                iv.extend_from_slice(&(engine_time as u32).to_be_bytes());
+                iv.extend_from_slice(salt);
+                (Cipher::aes_128_cfb128(), &key[..16], iv)
+            }
+        };
+
+        let mut crypter = Crypter::new(cipher, mode, key, Some(&iv)).context(CryptoSnafu)?;
+        crypter.pad(false);
+        let mut output = vec![0; data.len() + cipher.block_size()];
+        let mut len = crypter.update(data, &mut output).context(CryptoSnafu)?;
+        len += crypter.finalize(&mut output[len..]).context(CryptoSnafu)?;
+        output.truncate(len);
+        Ok(output)
+    }
+}
+
+/// Converts a password into a key, as described in RFC 3414 section A.2.
+fn password_to_key(digest: MessageDigest, password: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let mut hasher = Hasher::new(digest)?;
+    let mut block = [0; 64];
+    let mut index = 0;
+    for _ in 0..1_048_576 / block.len() {
+        for byte in block.iter_mut() {
+            *byte = password[index % password.len()];
+            index += 1;
+        }
+        hasher.update(&block)?;
+    }
+    Ok(hasher.finish()?.to_vec())
+}
+
+fn localize_key(
+    digest: MessageDigest,
+    key: &[u8],
+    engine_id: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    hash(digest, &[key, engine_id, key].concat()).map(|key| key.to_vec())
+}
+
+/// The security parameters and data of a received SNMPv3 message.
+#[derive(Debug)]
+pub struct V3Message<'a> {
+    pub msg_id: i64,
+    pub max_size: i64,
+    pub flags: u8,
+    pub engine_id: &'a [u8],
+    pub engine_boots: i64,
+    pub engine_time: i64,
+    pub user_name: &'a [u8],
+    auth_params: &'a [u8],
+    priv_params: &'a [u8],
+    data: &'a [u8],
+    message: &'a [u8],
+}
+
+impl<'a> V3Message<'a> {
+    /// Decodes the fields of a message following its version.
+    pub fn decode(message: &'a [u8], reader: Reader<'a>) -> Result<Self, UsmError> {
+        let (decoded, model) = Self::decode_fields(message, reader).context(InvalidMessageSnafu)?;
+        if model != SECURITY_MODEL_USM {
+            return Err(UsmError::UnsupportedSecurityModel { model });
+        }
+        Ok(decoded)
+    }
+
+    fn decode_fields(message: &'a [u8], mut reader: Reader<'a>) -> Result<(Self, i64), BerError> {
+        let mut header = reader.read_sequence()?;
+        let msg_id = header.read_integer()?;
+        let max_size = header.read_integer()?;
+        let flags = header.read_octet_string()?;
+        let model = header.read_integer()?;
+
+        let mut usm = Reader::new(reader.read_octet_string()?).read_sequence()?;
+        let engine_id = usm.read_octet_string()?;
+        let engine_boots = usm.read_integer()?;
+        let engine_time = usm.read_integer()?;
+        let user_name = usm.read_octet_string()?;
+        let auth_params = usm.read_octet_string()?;
+        let priv_params = usm.read_octet_string()?;
+
+        // Scoped PDUs are sequences, and encrypted ones octet strings.
+        let (_, data) = reader.read_any()?;
+
+        Ok((
+            Self {
+                msg_id,
+                max_size,
+                flags: flags.first().copied().unwrap_or_default(),
+                engine_id,
+                engine_boots,
+                engine_time,
+                user_name,
+                auth_params,
+                priv_params,
+                data,
+                message,
+            },
+            model,
+        ))
+    }
+
+    /// Authenticates and decrypts the message, returning the scoped PDU.
+    pub fn open(&self, user: &UsmUser) -> Result<ScopedPdu, UsmError> {
+        if self.flags & (FLAG_AUTH | FLAG_PRIV) != user.flags() {
+            return Err(UsmError::SecurityLevelMismatch {
+                user: user.name.clone(),
+            });
+        }
+        let keys = user.localize(self.engine_id).context(CryptoSnafu)?;
+
+        if self.flags & FLAG_AUTH != 0 {
+            // The digest is computed with the authentication parameters zeroed.
+            let offset = self.auth_params.as_ptr() as usize - self.message.as_ptr() as usize;
+            let mut message = self.message.to_vec();
+            message[offset..offset + self.auth_params.len()].fill(0);
+            let mac = keys.mac(&message).context(CryptoSnafu)?;
+            if mac.len() != self.auth_params.len() || !memcmp::eq(&mac, self.auth_params) {
+                return Err(UsmError::WrongDigest);
+            }
+        }
+
+        let scoped = if self.flags & FLAG_PRIV != 0 {
+            keys.crypt(
+                Mode::Decrypt,
+                self.engine_boots,
+                self.engine_time,
+                self.priv_params,
+                self.data,
+            )?
+        } else {
+            // Plaintext scoped PDUs are sequences, whose value has been read as the data.
+            ber::encode(tag::SEQUENCE, self.data)
+        };
+        ScopedPdu::decode(&scoped).context(InvalidMessageSnafu)
+    }
+
+    /// Decodes the scoped PDU of a message without authentication nor privacy.
+    pub fn open_unauthenticated(&self) -> Result<ScopedPdu, UsmError> {
+        if self.flags & (FLAG_AUTH | FLAG_PRIV) != 0 {
+            return Err(UsmError::SecurityLevelMismatch {
+                user: String::from_utf8_lossy(self.user_name).into_owned(),
+            });
+        }
+        ScopedPdu::decode(&ber::encode(tag::SEQUENCE, self.data)).context(InvalidMessageSnafu)
+    }
+}
+
+/// A PDU with its context.
+#[derive(Debug, PartialEq)]
+pub struct ScopedPdu {
+    pub context_engine_id: Vec<u8>,
+    pub context_name: Vec<u8>,
+    pub pdu_tag: u8,
+    pub pdu: Vec<u8>,
+}
+
+impl ScopedPdu {
+    fn decode(data: &[u8]) -> Result<Self, BerError> {
+        let mut scoped = Reader::new(data).read_sequence()?;
+        let context_engine_id = scoped.read_octet_string()?.to_vec();
+        let context_name = scoped.read_octet_string()?.to_vec();
+        let (pdu_tag, pdu) = scoped.read_any()?;
+        Ok(Self {
+            context_engine_id,
+            context_name,
+            pdu_tag,
+            pdu: pdu.to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        ber::encode_sequence(
+            tag::SEQUENCE,
+            &[
+                &ber::encode_octet_string(&self.context_engine_id),
+                &ber::encode_octet_string(&self.context_name),
+                &ber::encode(self.pdu_tag, &self.pdu),
+            ],
+        )
+    }
+}
+
+/// The header fields of an SNMPv3 message to send.
+#[derive(Debug)]
+pub struct V3Header<'a> {
+    pub msg_id: i64,
+    pub max_size: i64,
+    pub reportable: bool,
+    pub engine_id: &'a [u8],
+    pub engine_boots: i64,
+    pub engine_time: i64,
+}
+
+/// Encodes an SNMPv3 message, authenticated and encrypted according to the level of `user`.
+///
+/// Without user, the message is sent without authentication nor privacy, as needed for discovery.
+pub fn encode_v3(
+    header: &V3Header<'_>,
+    user: Option<&UsmUser>,
+    scoped: &ScopedPdu,
+) -> Result<Vec<u8>, UsmError> {
+    let keys = user
+        .map(|user| user.localize(header.engine_id))
+        .transpose()
+        .context(CryptoSnafu)?;
+    let mut flags = user.map(UsmUser::flags).unwrap_or_default();
+    if header.reportable {
+        flags |= FLAG_REPORTABLE;
+    }
+
+    let scoped = scoped.encode();
+    let (priv_params, data) = match keys.as_ref().filter(|keys| keys.privacy.is_some()) {
+        Some(keys) => {
+            let mut salt = [0; 8];
+            rand_bytes(&mut salt).context(CryptoSnafu)?;
+            let mut scoped = scoped;
+            if matches!(keys.privacy, Some((PrivProtocol::Des, _))) {
+                scoped.resize((scoped.len() + 7) / 8 * 8, 0);
+            }
+            let encrypted = keys.crypt(
+                Mode::Encrypt,
+                header.engine_boots,
+                header.engine_time,
+                &salt,
+                &scoped,
+            )?;
+            (salt.to_vec(), ber::encode_octet_string(&encrypted))
+        }
+        None => (vec![], scoped),
+    };
+    let mac_len = keys
+        .as_ref()
+        .and_then(|keys| keys.auth.as_ref())
+        .map(|(protocol, _)| protocol.mac_len())
+        .unwrap_or_default();
+
+    let user_name =
+        ber::encode_octet_string(user.map(UsmUser::name).unwrap_or_default().as_bytes());
+    let usm_prefix = [
+        ber::encode_octet_string(header.engine_id),
+        ber::encode_integer(header.engine_boots),
+        ber::encode_integer(header.engine_time),
+        user_name,
+    ]
+    .concat();
+    let usm_value = [
+        usm_prefix.as_slice(),
+        &ber::encode_octet_string(&vec![0; mac_len]),
+        &ber::encode_octet_string(&priv_params),
+    ]
+    .concat();
+    let usm = ber::encode(tag::SEQUENCE, &usm_value);
+    let version = ber::encode_integer(3);
+    let global = ber::encode_sequence(
+        tag::SEQUENCE,
+        &[
+            &ber::encode_integer(header.msg_id),
+            &ber::encode_integer(header.max_size),
+            &ber::encode_octet_string(&[flags]),
+            &ber::encode_integer(SECURITY_MODEL_USM),
+        ],
+    );
+    let security = ber::encode_octet_string(&usm);
+    let body_len = version.len() + global.len() + security.len() + data.len();
+    let mut message = ber::encode_sequence(tag::SEQUENCE, &[&version, &global, &security, &data]);
+
+    if let Some(keys) = keys.filter(|keys| keys.auth.is_some()) {
+        // The digest is computed over the whole message, and then written in place of the zeroed
+        // authentication parameters.
+        let offset = ber::header_len(body_len)
+            + version.len()
+            + global.len()
+            + ber::header_len(usm.len())
+            + ber::header_len(usm_value.len())
+            + usm_prefix.len()
+            + ber::header_len(mac_len);
+        let mac = keys.mac(&message).context(CryptoSnafu)?;
+        message[offset..offset + mac_len].copy_from_slice(&mac);
+    }
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(auth: Option<AuthProtocol>, privacy: Option<PrivProtocol>) -> UsmUser {
+        UsmUser::new(&UsmUserConfig {
+            name: "vector".into(),
+            auth_protocol: auth,
+            auth_password: auth.map(|_| "authpassword".into()),
+            priv_protocol: privacy,
+            priv_password: privacy.map(|_| "privpassword".into()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn derives_keys() {
+        // RFC 3414 section A.3.
+        let engine_id = hex::decode("000000000000000000000002").unwrap();
+        let md5 = password_to_key(MessageDigest::md5(), b"maplesyrup").unwrap();
+        assert_eq!(
+            hex::encode(localize_key(MessageDigest::md5(), &md5, &engine_id).unwrap()),
+            "526f5eed9fcce26f8964c2930787d82b"
+        );
+        let sha = password_to_key(MessageDigest::sha1(), b"maplesyrup").unwrap();
+        assert_eq!(
+            hex::encode(localize_key(MessageDigest::sha1(), &sha, &engine_id).unwrap()),
+            "6695febc9288e36282235fc7151f128497b38f3f"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_credentials() {
+        let config = |auth_password: Option<&str>, priv_protocol| UsmUserConfig {
+            name: "vector".into(),
+            auth_protocol: Some(AuthProtocol::Sha),
+            auth_password: auth_password.map(Into::into),
+            priv_protocol,
+            priv_password: None,
+        };
+        assert!(matches!(
+            UsmUser::new(&config(Some("short"), None)),
+            Err(UsmConfigError::PasswordTooShort { .. })
+        ));
+        assert!(matches!(
+            UsmUser::new(&config(None, None)),
+            Err(UsmConfigError::IncompleteCredentials { .. })
+        ));
+        assert!(matches!(
+            UsmUser::new(&config(Some("authpassword"), Some(PrivProtocol::Aes))),
+            Err(UsmConfigError::IncompleteCredentials { .. })
+        ));
+    }
+
+    fn roundtrip(user: &UsmUser) {
+        let engine_id = b"\x80\x00\x1f\x88\x04vector";
+        let scoped = ScopedPdu {
+            context_engine_id: engine_id.to_vec(),
+            context_name: vec![],
+            pdu_tag: tag::TRAP_V2,
+            pdu: vec![0x02, 0x01, 0x01],
+        };
+        let header = V3Header {
+            msg_id: 42,
+            max_size: 65507,
+            reportable: false,
+            engine_id,
+            engine_boots: 3,
+            engine_time: 1234,
+        };
+        let encoded = encode_v3(&header, Some(user), &scoped).unwrap();
+
+        let mut reader = Reader::new(&encoded).read_sequence().unwrap();
+        assert_eq!(reader.read_integer(), Ok(3));
+        let message = V3Message::decode(&encoded, reader).unwrap();
+        assert_eq!(message.msg_id, 42);
+        assert_eq!(message.engine_id, engine_id);
+        assert_eq!(message.user_name, b"vector");
+        assert_eq!(message.flags, user.flags());
+        assert_eq!(message.open(user).unwrap(), scoped);
+
+        if user.flags() & FLAG_AUTH != 0 {
+            let mut tampered = encoded.clone();
+            let last = tampered.len() - 1;
+            tampered[last] ^= 0xff;
+            let mut reader = Reader::new(&tampered).read_sequence().unwrap();
+            reader.read_integer().unwrap();
+            let message = V3Message::decode(&tampered, reader).unwrap();
+            assert!(matches!(message.open(user), Err(UsmError::WrongDigest)));
+        }
+    }
+
+    #[test]
+    fn roundtrips_messages() {
+        roundtrip(&user(None, None));
+        roundtrip(&user(Some(AuthProtocol::Md5), None));
+        roundtrip(&user(Some(AuthProtocol::Sha256), None));
+        roundtrip(&user(Some(AuthProtocol::Sha), Some(PrivProtocol::Aes)));
+        roundtrip(&user(Some(AuthProtocol::Sha512), Some(PrivProtocol::Aes)));
+    }
+
+    #[test]
+    fn rejects_mismatched_security_levels() {
+        let engine_id = b"engine";
+        let scoped = ScopedPdu {
+            context_engine_id: vec![],
+            context_name: vec![],
+            pdu_tag: tag::TRAP_V2,
+            pdu: vec![],
+        };
+        let header = V3Header {
+            msg_id: 1,
+            max_size: 65507,
+            reportable: false,
+            engine_id,
+            engine_boots: 0,
+            engine_time: 0,
+        };
+        let encoded = encode_v3(&header, None, &scoped).unwrap();
+        let mut reader = Reader::new(&encoded).read_sequence().unwrap();
+        reader.read_integer().unwrap();
+        let message = V3Message::decode(&encoded, reader).unwrap();
+        assert!(matches!(
+            message.open(&user(Some(AuthProtocol::Sha), None)),
+            Err(UsmError::SecurityLevelMismatch { .. })
+        ));
+        assert_eq!(message.open_unauthenticated().unwrap(), scoped);
+    }
+}
@@ -16,7 +16,7 @@ use tokio::{
     net::{TcpListener, TcpStream},
     time::sleep,
 };
-use tokio_util::codec::{Decoder, FramedRead};
+use tokio_util::codec::Decoder;
 use tracing::Instrument;
 use vector_common::finalization::AddBatchNotifier;
 use vector_config::configurable_component;
@@ -25,7 +25,7 @@ use vector_core::ByteSizeOf;
 use super::AfterReadExt as _;
 use crate::sources::util::tcp::request_limiter::RequestLimiter;
 use crate::{
-    codecs::ReadyFrames,
+    codecs::{DeadlineFramedRead, DecoderDeadline, ReadyFrames},
     config::{AcknowledgementsConfig, Resource, SourceContext},
     event::{BatchNotifier, BatchStatus, Event},
     internal_events::{
@@ -107,11 +107,20 @@ where
         + Send
         + Unpin;
     type Item: Into<SmallVec<[Event; 1]>> + Send + Unpin;
-    type Decoder: Decoder<Item = (Self::Item, usize), Error = Self::Error> + Send + 'static;
+    type Decoder: Decoder<Item = (Self::Item, usize), Error = Self::Error>
+        + DecoderDeadline
+        + Send
+        + 'static;
     type Acker: TcpSourceAcker + Send;
 
     fn decoder(&self) -> Self::Decoder;
 
+    /// Builds the bytes sent to the client as soon as it's connected, for protocols in which the
+    /// server speaks first.
+    fn build_greeting(&self, _decoder: &Self::Decoder) -> Option<Bytes> {
+        None
+    }
+
     fn handle_events(&self, _events: &mut [Event], _host: std::net::SocketAddr) {}
 
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
@@ -128,6 +137,7 @@ where
         cx: SourceContext,
         acknowledgements: AcknowledgementsConfig,
         max_connections: Option<u32>,
+        proxy_protocol: bool,
     ) -> crate::Result<crate::sources::Source> {
         let acknowledgements = cx.do_acknowledgements(&acknowledgements);
 
@@ -136,7 +146,7 @@ where
         Ok(Box::pin(async move {
             let listener = match make_listener(addr, listenfd, &tls).await {
                 None => return Err(()),
-                Some(listener) => listener,
+                Some(listener) => listener.with_proxy_protocol(proxy_protocol),
             };
 
             info!(
@@ -209,7 +219,6 @@ where
                                 receive_buffer_bytes,
                                 source,
                                 tripwire,
-                                peer_addr,
                                 out,
                                 acknowledgements,
                                 request_limiter,
@@ -240,7 +249,6 @@ async fn handle_stream<T>(
     receive_buffer_bytes: Option<usize>,
     source: T,
     mut tripwire: BoxFuture<'static, ()>,
-    peer_addr: SocketAddr,
     mut out: SourceSender,
     acknowledgements: bool,
     request_limiter: RequestLimiter,
@@ -261,6 +269,9 @@ async fn handle_stream<T>(
         }
     };
 
+    // Once handshaking is done, this is the client address of the PROXY protocol header, if any.
+    let peer_addr = socket.peer_addr();
+
     if let Some(keepalive) = keepalive {
         if let Err(error) = socket.set_keepalive(keepalive) {
             warn!(message = "Failed configuring TCP keepalive.", %error);
@@ -273,7 +284,7 @@ async fn handle_stream<T>(
         }
     }
 
-    let socket = socket.after_read(move |byte_size| {
+    let mut socket = socket.after_read(move |byte_size| {
         emit!(TcpBytesReceived {
             byte_size,
             peer_addr
@@ -286,14 +297,22 @@ async fn handle_stream<T>(
         .and_then(|stream| stream.ssl().peer_certificate())
         .map(CertificateMetadata::from_x509);
 
-    let reader = FramedRead::new(socket, source.decoder());
+    let decoder = source.decoder();
+    if let Some(greeting) = source.build_greeting(&decoder) {
+        if let Err(error) = socket.write_all(&greeting).await {
+            emit!(TcpSendAckError { error });
+            return;
+        }
+    }
+
+    let reader = DeadlineFramedRead::new(socket, decoder);
     let mut reader = ReadyFrames::new(reader);
 
     loop {
         let mut permit = tokio::select! {
             _ = &mut tripwire => break,
             _ = &mut shutdown_signal => {
-                if close_socket(reader.get_ref().get_ref().get_ref()) {
+                if close_socket(reader.get_ref().get_ref().get_ref().get_ref()) {
                     break;
                 }
                 None
@@ -310,7 +329,7 @@ async fn handle_stream<T>(
         tokio::select! {
             _ = &mut tripwire => break,
             _ = &mut shutdown_signal => {
-                if close_socket(reader.get_ref().get_ref().get_ref()) {
+                if close_socket(reader.get_ref().get_ref().get_ref().get_ref()) {
                     break;
                 }
             },
@@ -381,7 +400,7 @@ async fn handle_stream<T>(
                                         }
                                 };
                                 if let Some(ack_bytes) = acker.build_ack(ack){
-                                    let stream = reader.get_mut().get_mut();
+                                    let stream = reader.get_mut().get_mut().get_mut();
                                     if let Err(error) = stream.write_all(&ack_bytes).await {
                                         emit!(TcpSendAckError{ error });
                                         break;
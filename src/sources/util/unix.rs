@@ -1,6 +1,8 @@
 use std::os::unix::fs::PermissionsExt;
 use std::{fs, fs::remove_file, path::Path};
 
+use tokio::net::UnixStream;
+
 use crate::internal_events::UnixSocketFileDeleteError;
 
 pub fn change_socket_permissions(path: &Path, perms: Option<u32>) -> crate::Result<()> {
@@ -17,3 +19,81 @@ pub fn change_socket_permissions(path: &Path, perms: Option<u32>) -> crate::Resu
     }
     Ok(())
 }
+
+/// Which credentials of the peers of a Unix socket are captured.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PeerCredentialsCapture {
+    /// No credentials are captured.
+    Disabled,
+
+    /// The process, user, and group IDs of the peers are captured.
+    Ids,
+
+    /// The IDs of the peers are captured, and the names of their processes are resolved.
+    IdsAndProcessName,
+}
+
+/// The credentials of the process on the other end of a Unix socket, as reported by the kernel.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PeerCredentials {
+    pub pid: Option<i32>,
+    pub uid: u32,
+    pub gid: u32,
+    pub process_name: Option<String>,
+}
+
+impl PeerCredentials {
+    pub(super) fn new(
+        pid: Option<i32>,
+        uid: u32,
+        gid: u32,
+        capture: PeerCredentialsCapture,
+    ) -> Self {
+        let process_name = match (capture, pid) {
+            (PeerCredentialsCapture::IdsAndProcessName, Some(pid)) => process_name(pid),
+            _ => None,
+        };
+        Self {
+            pid,
+            uid,
+            gid,
+            process_name,
+        }
+    }
+
+    /// Gets the credentials of the peer of a connected stream.
+    pub(super) fn from_stream(
+        stream: &UnixStream,
+        capture: PeerCredentialsCapture,
+    ) -> Option<Self> {
+        if capture == PeerCredentialsCapture::Disabled {
+            return None;
+        }
+        match stream.peer_cred() {
+            Ok(cred) => Some(Self::new(cred.pid(), cred.uid(), cred.gid(), capture)),
+            Err(error) => {
+                warn!(
+                    message = "Failed to get peer credentials.",
+                    %error,
+                    internal_log_rate_secs = 10
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Resolves the name of a process, which is only possible on Linux.
+///
+/// The process may have exited, or may have changed its own name.
+#[cfg(target_os = "linux")]
+fn process_name(pid: i32) -> Option<String> {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim_end().to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+const fn process_name(_pid: i32) -> Option<String> {
+    None
+}
@@ -1,4 +1,8 @@
-use std::{fs::remove_file, path::PathBuf};
+use std::{
+    fs::remove_file,
+    io,
+    path::{Path, PathBuf},
+};
 
 use bytes::{Bytes, BytesMut};
 use codecs::StreamDecodingError;
@@ -16,7 +20,7 @@ use crate::{
         UnixSocketFileDeleteError,
     },
     shutdown::ShutdownSignal,
-    sources::util::change_socket_permissions,
+    sources::util::{change_socket_permissions, PeerCredentials, PeerCredentialsCapture},
     sources::Source,
     SourceSender,
 };
@@ -25,15 +29,27 @@ use crate::{
 /// Passing in different functions for `decoder` and `handle_events` can allow
 /// for different source-specific logic (such as decoding syslog messages in the
 /// syslog source).
+///
+/// The credentials of the sender of each datagram are captured as set by
+/// `peer_credentials`, which is only supported on Linux.
 pub fn build_unix_datagram_source(
     listen_path: PathBuf,
     socket_file_mode: Option<u32>,
     max_length: usize,
+    peer_credentials: PeerCredentialsCapture,
     decoder: Decoder,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut [Event], Option<Bytes>, Option<&PeerCredentials>)
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> crate::Result<Source> {
+    if peer_credentials != PeerCredentialsCapture::Disabled && !cfg!(target_os = "linux") {
+        return Err("Peer credentials of datagram sockets are only supported on Linux.".into());
+    }
+
     Ok(Box::pin(async move {
         let socket = UnixDatagram::bind(&listen_path).expect("Failed to bind to datagram socket");
         info!(message = "Listening.", path = ?listen_path, r#type = "unix_datagram");
@@ -41,7 +57,21 @@ pub fn build_unix_datagram_source(
         change_socket_permissions(&listen_path, socket_file_mode)
             .expect("Failed to set socket permissions");
 
-        let result = listen(socket, max_length, decoder, shutdown, handle_events, out).await;
+        #[cfg(target_os = "linux")]
+        if peer_credentials != PeerCredentialsCapture::Disabled {
+            linux::enable_peer_credentials(&socket).expect("Failed to enable peer credentials");
+        }
+
+        let result = listen(
+            socket,
+            max_length,
+            peer_credentials,
+            decoder,
+            shutdown,
+            handle_events,
+            out,
+        )
+        .await;
 
         // Delete socket file.
         if let Err(error) = remove_file(&listen_path) {
@@ -58,17 +88,22 @@ pub fn build_unix_datagram_source(
 async fn listen(
     socket: UnixDatagram,
     max_length: usize,
+    peer_credentials: PeerCredentialsCapture,
     decoder: Decoder,
     mut shutdown: ShutdownSignal,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut [Event], Option<Bytes>, Option<&PeerCredentials>)
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     mut out: SourceSender,
 ) -> Result<(), ()> {
     let mut buf = BytesMut::with_capacity(max_length);
     loop {
         buf.resize(max_length, 0);
         tokio::select! {
-            recv = socket.recv_from(&mut buf) => {
-                let (byte_size, address) = recv.map_err(|error| {
+            recv = recv_from(&socket, &mut buf, peer_credentials) => {
+                let (byte_size, path, credentials) = recv.map_err(|error| {
                     let error = codecs::decoding::Error::FramingError(error.into());
                     emit!(SocketReceiveError {
                         mode: SocketMode::Unix,
@@ -84,7 +119,7 @@ async fn listen(
                 let payload = buf.split_to(byte_size);
 
                 let span = info_span!("datagram");
-                let path = address.as_pathname().map(|e| e.to_owned()).map(|path| {
+                let path = path.map(|path| {
                     span.record("peer_path", &field::debug(&path));
                     path
                 });
@@ -103,7 +138,7 @@ async fn listen(
                                 count: events.len()
                             });
 
-                            handle_events(&mut events, received_from.clone());
+                            handle_events(&mut events, received_from.clone(), credentials.as_ref());
 
                             let count = events.len();
                             if let Err(error) = out.send_batch(events).await {
@@ -127,3 +162,93 @@ async fn listen(
         }
     }
 }
+
+/// Receives a datagram, with the path and the credentials of its sender.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+async fn recv_from(
+    socket: &UnixDatagram,
+    buf: &mut [u8],
+    peer_credentials: PeerCredentialsCapture,
+) -> io::Result<(usize, Option<PathBuf>, Option<PeerCredentials>)> {
+    #[cfg(target_os = "linux")]
+    if peer_credentials != PeerCredentialsCapture::Disabled {
+        return linux::recv_with_credentials(socket, buf, peer_credentials).await;
+    }
+
+    let (byte_size, address) = socket.recv_from(buf).await?;
+    Ok((byte_size, address.as_pathname().map(Path::to_owned), None))
+}
+
+/// The credentials of the senders of datagrams are passed as `SCM_CREDENTIALS`
+/// control messages, which must be enabled on the socket with `SO_PASSCRED`.
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        io::{self, IoSliceMut},
+        os::unix::io::AsRawFd,
+        path::{Path, PathBuf},
+    };
+
+    use nix::sys::socket::{
+        recvmsg, setsockopt, sockopt::PassCred, ControlMessageOwned, MsgFlags, UnixAddr,
+        UnixCredentials,
+    };
+    use tokio::{io::Interest, net::UnixDatagram};
+
+    use crate::sources::util::{PeerCredentials, PeerCredentialsCapture};
+
+    pub(super) fn enable_peer_credentials(socket: &UnixDatagram) -> io::Result<()> {
+        Ok(setsockopt(socket.as_raw_fd(), PassCred, &true)?)
+    }
+
+    pub(super) async fn recv_with_credentials(
+        socket: &UnixDatagram,
+        buf: &mut [u8],
+        capture: PeerCredentialsCapture,
+    ) -> io::Result<(usize, Option<PathBuf>, Option<PeerCredentials>)> {
+        loop {
+            socket.readable().await?;
+            match socket.try_io(Interest::READABLE, || recvmsg_with_credentials(socket, buf)) {
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => continue,
+                result => {
+                    return result.map(|(byte_size, path, credentials)| {
+                        let credentials = credentials.map(|credentials| {
+                            PeerCredentials::new(
+                                Some(credentials.pid()),
+                                credentials.uid(),
+                                credentials.gid(),
+                                capture,
+                            )
+                        });
+                        (byte_size, path, credentials)
+                    })
+                }
+            }
+        }
+    }
+
+    fn recvmsg_with_credentials(
+        socket: &UnixDatagram,
+        buf: &mut [u8],
+    ) -> io::Result<(usize, Option<PathBuf>, Option<UnixCredentials>)> {
+        let mut iov = [IoSliceMut::new(buf)];
+        let mut cmsg_buffer = nix::cmsg_space!(UnixCredentials);
+        let message = recvmsg::<UnixAddr>(
+            socket.as_raw_fd(),
+            &mut iov,
+            Some(&mut cmsg_buffer),
+            MsgFlags::empty(),
+        )?;
+
+        let credentials = message.cmsgs().find_map(|cmsg| match cmsg {
+            ControlMessageOwned::ScmCredentials(credentials) => Some(credentials),
+            _ => None,
+        });
+        let path = message
+            .address
+            .as_ref()
+            .and_then(UnixAddr::path)
+            .map(Path::to_owned);
+        Ok((message.bytes, path, credentials))
+    }
+}
@@ -9,21 +9,20 @@ use tokio::{
     time::sleep,
 };
 use tokio_stream::wrappers::UnixListenerStream;
-use tokio_util::codec::FramedRead;
 use tracing::{field, Instrument};
 use vector_core::ByteSizeOf;
 
 use super::AfterReadExt;
 use crate::{
     async_read::VecAsyncReadExt,
-    codecs::Decoder,
+    codecs::{DeadlineFramedRead, Decoder},
     event::Event,
     internal_events::{
         BytesReceived, ConnectionOpen, OpenGauge, SocketEventsReceived, SocketMode,
         StreamClosedError, UnixSocketError, UnixSocketFileDeleteError,
     },
     shutdown::ShutdownSignal,
-    sources::util::change_socket_permissions,
+    sources::util::{change_socket_permissions, PeerCredentials, PeerCredentialsCapture},
     sources::Source,
     SourceSender,
 };
@@ -32,11 +31,19 @@ use crate::{
 /// Passing in different functions for `decoder` and `handle_events` can allow
 /// for different source-specific logic (such as decoding syslog messages in the
 /// syslog source).
+///
+/// The credentials of the peer of each connection are captured once it's accepted,
+/// as set by `peer_credentials`.
 pub fn build_unix_stream_source(
     listen_path: PathBuf,
     socket_file_mode: Option<u32>,
+    peer_credentials: PeerCredentialsCapture,
     decoder: Decoder,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut [Event], Option<Bytes>, Option<&PeerCredentials>)
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> crate::Result<Source> {
@@ -76,6 +83,7 @@ pub fn build_unix_stream_source(
             let handle_events = handle_events.clone();
             let received_from: Option<Bytes> =
                 path.map(|p| p.to_string_lossy().into_owned().into());
+            let credentials = PeerCredentials::from_stream(&socket, peer_credentials);
 
             let stream = socket
                 .after_read(|byte_size| {
@@ -85,7 +93,7 @@ pub fn build_unix_stream_source(
                     });
                 })
                 .allow_read_until(shutdown.clone().map(|_| ()));
-            let mut stream = FramedRead::new(stream, decoder.clone());
+            let mut stream = DeadlineFramedRead::new(stream, decoder.clone());
 
             let connection_open = connection_open.clone();
             let mut out = out.clone();
@@ -102,7 +110,11 @@ pub fn build_unix_stream_source(
                                     count: events.len(),
                                 });
 
-                                handle_events(&mut events, received_from.clone());
+                                handle_events(
+                                    &mut events,
+                                    received_from.clone(),
+                                    credentials.as_ref(),
+                                );
 
                                 let count = events.len();
                                 if let Err(error) = out.send_batch(events).await {
@@ -124,7 +136,8 @@ pub fn build_unix_stream_source(
 
                     info!("Finished sending.");
 
-                    let socket: &mut UnixStream = stream.get_mut().get_mut().get_mut_ref();
+                    let socket: &mut UnixStream =
+                        stream.get_mut().get_mut().get_mut().get_mut_ref();
                     if let Err(error) = socket.shutdown().await {
                         error!(message = "Failed shutting down socket.", %error);
                     }
@@ -99,6 +99,7 @@ impl VectorConfig {
             cx,
             false.into(),
             None,
+            false,
         )
     }
 
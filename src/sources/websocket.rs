@@ -0,0 +1,559 @@
+//! This mod implements the `websocket` source.
+//! It connects to a WebSocket endpoint and decodes each text or binary message it receives into
+//! events. The connection is kept alive with pings, and reestablished when it's lost, optionally
+//! resuming the stream from the last event received.
+
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::{SinkExt, StreamExt};
+use http::header::{HeaderName, HeaderValue};
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    net::TcpStream,
+    select,
+    time::{self, Instant},
+};
+use tokio_tungstenite::{
+    client_async,
+    tungstenite::{
+        client::IntoClientRequest, error::Error as WsError, handshake::client::Request,
+        protocol::Message,
+    },
+    WebSocketStream,
+};
+use tokio_util::codec::FramedRead;
+use url::Url;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{log_schema, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    dns,
+    event::Event,
+    http::Auth,
+    internal_events::{
+        BytesReceived, OldEventsReceived, StreamClosedError, WsConnectionEstablished,
+        WsConnectionShutdown, WsSourceConnectionError, WsSourceConnectionFailedError,
+    },
+    serde::{default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsEnableableConfig, TlsError},
+    SourceSender,
+};
+
+const PING: &[u8] = b"PING";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid URI {:?}: {}", uri, source))]
+    InvalidUri {
+        uri: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("URI {:?} must use the `ws` or `wss` scheme", uri))]
+    InvalidScheme { uri: String },
+    #[snafu(display("invalid header {:?}", name))]
+    InvalidHeader { name: String },
+}
+
+#[derive(Debug, Snafu)]
+enum ConnectError {
+    #[snafu(display("failed to create request: {}", source))]
+    CreateRequest { source: WsError },
+    #[snafu(display("unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("no addresses returned"))]
+    NoAddresses,
+    #[snafu(display("connect error: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("handshake failed: {}", source))]
+    Handshake { source: WsError },
+}
+
+/// Configuration for the `websocket` source.
+#[configurable_component(source)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSourceConfig {
+    /// The WebSocket URI to connect to.
+    ///
+    /// The URI must take the form of `ws://host:port/path` or `wss://host:port/path`.
+    uri: String,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    auth: Option<Auth>,
+
+    /// Additional headers to send with the handshake request.
+    #[serde(default)]
+    headers: HashMap<String, String>,
+
+    /// The interval, in seconds, between pings sent to keep the connection alive.
+    ping_interval: Option<u64>,
+
+    /// The amount of time, in seconds, to wait for a pong before reconnecting.
+    ///
+    /// Only relevant when `ping_interval` is set.
+    ping_timeout: Option<u64>,
+
+    #[configurable(derived)]
+    resume: Option<ResumeConfig>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    decoding: DeserializerConfig,
+}
+
+/// Configuration for resuming the stream after reconnecting.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ResumeConfig {
+    /// The field of the events holding their position in the stream, such as a sequence number or an ID.
+    field: String,
+
+    /// The query parameter set to the value of `field` in the last event received, when reconnecting.
+    query_parameter: String,
+}
+
+inventory::submit! {
+    SourceDescription::new::<WebSocketSourceConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"uri = "ws://127.0.0.1:9000/endpoint""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SourceConfig for WebSocketSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let uri = Url::parse(&self.uri).context(InvalidUriSnafu {
+            uri: self.uri.clone(),
+        })?;
+        if uri.scheme() != "ws" && uri.scheme() != "wss" {
+            return Err(BuildError::InvalidScheme {
+                uri: self.uri.clone(),
+            }
+            .into());
+        }
+
+        let mut headers = Vec::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .ok()
+                .zip(HeaderValue::from_str(value).ok())
+                .ok_or_else(|| BuildError::InvalidHeader { name: name.clone() })?;
+            headers.push(header);
+        }
+
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+
+        let source = WebSocketSource {
+            uri,
+            tls,
+            auth: self.auth.clone(),
+            headers,
+            ping_interval: self
+                .ping_interval
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            ping_timeout: self
+                .ping_timeout
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            resume: self.resume.clone(),
+            decoder,
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+struct WebSocketSource {
+    uri: Url,
+    tls: MaybeTlsSettings,
+    auth: Option<Auth>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    ping_interval: Option<Duration>,
+    ping_timeout: Option<Duration>,
+    resume: Option<ResumeConfig>,
+    decoder: Decoder,
+}
+
+impl WebSocketSource {
+    async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut backoff = fresh_backoff();
+        // The position of the last event received, sent when reconnecting.
+        let mut position = None;
+
+        loop {
+            let connection = select! {
+                _ = &mut shutdown => break,
+                connection = self.connect(position.as_deref()) => connection,
+            };
+            match connection {
+                Ok(ws_stream) => {
+                    emit!(WsConnectionEstablished);
+                    backoff = fresh_backoff();
+
+                    match self
+                        .receive(ws_stream, &mut position, &mut out, &mut shutdown)
+                        .await
+                    {
+                        Ok(Received::Shutdown) => break,
+                        Ok(Received::Closed) => emit!(WsConnectionShutdown),
+                        Err(Some(error)) => emit!(WsSourceConnectionError { error }),
+                        Err(None) => return Err(()),
+                    }
+                }
+                Err(error) => emit!(WsSourceConnectionFailedError {
+                    error: Box::new(error)
+                }),
+            }
+
+            select! {
+                _ = &mut shutdown => break,
+                _ = time::sleep(backoff.next().unwrap()) => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn connect(
+        &self,
+        position: Option<&str>,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, ConnectError> {
+        let request = self.request(position)?;
+        let uri = request.uri();
+        let host = uri.host().unwrap_or_default().to_owned();
+        let port = uri
+            .port_u16()
+            .unwrap_or(if uri.scheme_str() == Some("wss") {
+                443
+            } else {
+                80
+            });
+
+        let ip = dns::Resolver
+            .lookup_ip(host.clone())
+            .await
+            .context(DnsSnafu)?
+            .next()
+            .ok_or(ConnectError::NoAddresses)?;
+        let stream = self
+            .tls
+            .connect(&host, &SocketAddr::new(ip, port))
+            .await
+            .context(ConnectSnafu)?;
+
+        let (ws_stream, _response) = client_async(request, stream)
+            .await
+            .context(HandshakeSnafu)?;
+        Ok(ws_stream)
+    }
+
+    /// Builds the handshake request, resuming the stream from the given position, if any.
+    fn request(&self, position: Option<&str>) -> Result<Request, ConnectError> {
+        let uri = match (&self.resume, position) {
+            (Some(resume), Some(position)) => {
+                resume_uri(&self.uri, &resume.query_parameter, position)
+            }
+            _ => self.uri.clone(),
+        };
+
+        let mut request = uri
+            .as_str()
+            .into_client_request()
+            .context(CreateRequestSnafu)?;
+        for (name, value) in &self.headers {
+            request.headers_mut().insert(name.clone(), value.clone());
+        }
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+        Ok(request)
+    }
+
+    /// Receives the messages of a connection until it's closed. An error is returned
+    /// when the connection fails, or without one when the source can't send events anymore.
+    async fn receive(
+        &self,
+        ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        position: &mut Option<String>,
+        out: &mut SourceSender,
+        shutdown: &mut ShutdownSignal,
+    ) -> Result<Received, Option<WsError>> {
+        let (mut ws_sink, mut ws_stream) = ws_stream.split();
+
+        let mut ping_interval = self.ping_interval.map(time::interval);
+        let mut last_pong = Instant::now();
+
+        loop {
+            let message = select! {
+                _ = &mut *shutdown => {
+                    let _ = ws_sink.close().await;
+                    return Ok(Received::Shutdown);
+                }
+                _ = tick(&mut ping_interval) => {
+                    if let Some(ping_timeout) = self.ping_timeout {
+                        if last_pong.elapsed() > ping_timeout {
+                            return Err(Some(WsError::Io(std::io::Error::new(
+                                std::io::ErrorKind::TimedOut,
+                                "Pong not received in time",
+                            ))));
+                        }
+                    }
+                    ws_sink.send(Message::Ping(PING.to_vec())).await.map_err(Some)?;
+                    continue;
+                }
+                message = ws_stream.next() => message,
+            };
+
+            // Pings are answered automatically by tungstenite while reading from the stream.
+            let payload = match message {
+                Some(Ok(Message::Text(text))) => Bytes::from(text),
+                Some(Ok(Message::Binary(data))) => Bytes::from(data),
+                Some(Ok(Message::Pong(_))) => {
+                    last_pong = Instant::now();
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(Received::Closed),
+                Some(Ok(_)) => continue,
+                Some(Err(error)) if is_closed(&error) => return Ok(Received::Closed),
+                Some(Err(error)) => return Err(Some(error)),
+            };
+
+            let events = decode_message(&payload, &self.decoder).await;
+            if events.is_empty() {
+                continue;
+            }
+            if let Some(resume) = &self.resume {
+                if let Some(value) = events
+                    .iter()
+                    .rev()
+                    .filter_map(Event::maybe_as_log)
+                    .find_map(|log| log.get(resume.field.as_str()))
+                {
+                    *position = Some(value.to_string_lossy());
+                }
+            }
+
+            let count = events.len();
+            if let Err(error) = out.send_batch(events).await {
+                emit!(StreamClosedError { error, count });
+                return Err(None);
+            }
+        }
+    }
+}
+
+/// How receiving the messages of a connection ended.
+enum Received {
+    /// The connection was closed by the server.
+    Closed,
+    /// The source is shutting down.
+    Shutdown,
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+async fn tick(interval: &mut Option<time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => futures::future::pending().await,
+    }
+}
+
+/// Sets the query parameter resuming the stream in the URI, replacing any configured value.
+fn resume_uri(uri: &Url, query_parameter: &str, position: &str) -> Url {
+    let mut resumed = uri.clone();
+    resumed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(uri.query_pairs().filter(|(key, _)| key != query_parameter))
+        .append_pair(query_parameter, position);
+    resumed
+}
+
+const fn is_closed(error: &WsError) -> bool {
+    matches!(error, WsError::ConnectionClosed | WsError::AlreadyClosed)
+}
+
+/// Decodes the payload of a message into events.
+async fn decode_message(payload: &[u8], decoder: &Decoder) -> Vec<Event> {
+    emit!(BytesReceived {
+        byte_size: payload.len(),
+        protocol: "websocket",
+    });
+
+    let mut decoded = Vec::new();
+    let mut stream = FramedRead::new(payload, decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                emit!(OldEventsReceived {
+                    byte_size: events.size_of(),
+                    count: events.len(),
+                });
+
+                let now = Utc::now();
+
+                decoded.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(log_schema().source_type_key(), Bytes::from("websocket"));
+                        log.try_insert(log_schema().timestamp_key(), now);
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{
+        accept_async, accept_hdr_async,
+        tungstenite::handshake::server::{Request as ServerRequest, Response},
+    };
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSourceConfig>();
+    }
+
+    #[test]
+    fn resumes_uri() {
+        let uri = Url::parse("wss://example.com/stream?token=abc&since=0").unwrap();
+        assert_eq!(
+            resume_uri(&uri, "since", "42").as_str(),
+            "wss://example.com/stream?token=abc&since=42"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_scheme() {
+        let config: WebSocketSourceConfig =
+            toml::from_str(r#"uri = "http://127.0.0.1:9000/endpoint""#).unwrap();
+        assert!(config
+            .build(SourceContext::new_test(SourceSender::new_test().0, None))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn receives_messages_and_resumes() {
+        let addr = next_addr();
+        let listener = TcpListener::bind(addr).await.unwrap();
+
+        let (uri_tx, mut uri_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            // The first connection sends two messages, then closes.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            ws.send(Message::text(r#"{"id":1,"message":"first"}"#))
+                .await
+                .unwrap();
+            ws.send(Message::binary(r#"{"id":2,"message":"second"}"#.as_bytes()))
+                .await
+                .unwrap();
+            ws.close(None).await.unwrap();
+
+            // The second connection resumes after the last event received.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_hdr_async(stream, |request: &ServerRequest, response: Response| {
+                uri_tx.send(request.uri().to_string()).unwrap();
+                Ok(response)
+            })
+            .await
+            .unwrap();
+            ws.send(Message::text(r#"{"id":3,"message":"third"}"#))
+                .await
+                .unwrap();
+            futures::future::pending::<()>().await;
+        });
+
+        let config: WebSocketSourceConfig = toml::from_str(&format!(
+            r#"
+            uri = "ws://{}/stream"
+            decoding.codec = "json"
+            resume.field = "id"
+            resume.query_parameter = "after"
+            "#,
+            addr
+        ))
+        .unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async move {
+            let (tx, rx) = SourceSender::new_test();
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            collect_n(rx, 3).await
+        })
+        .await;
+
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["first", "second", "third"]);
+        assert_eq!(
+            events[0].as_log()[log_schema().source_type_key()],
+            "websocket".into()
+        );
+        assert_eq!(uri_rx.recv().await.unwrap(), "/stream?after=2");
+    }
+}
@@ -103,6 +103,8 @@ async fn run(
             opts.format,
             opts.limit as i64,
             opts.interval as i64,
+            opts.filter.clone(),
+            i64::from(opts.sample_rate.get()),
         );
     };
 
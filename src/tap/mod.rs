@@ -1,5 +1,7 @@
 mod cmd;
 
+use std::num::NonZeroU32;
+
 use clap::Parser;
 pub(crate) use cmd::cmd;
 use url::Url;
@@ -20,6 +22,14 @@ pub struct Opts {
     #[clap(default_value = "100", short = 'l', long)]
     limit: u32,
 
+    /// VRL condition events must match to be observed, such as `.tenant == "acme"`
+    #[clap(long)]
+    filter: Option<String>,
+
+    /// Observe only one out of every `sample-rate` events, after filtering
+    #[clap(default_value = "1", short = 's', long)]
+    sample_rate: NonZeroU32,
+
     /// Encoding format for events printed to screen
     #[clap(default_value = "json", possible_values = &["json", "yaml", "logfmt"], short = 'f', long)]
     format: TapEncodingFormat,
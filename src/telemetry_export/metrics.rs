@@ -0,0 +1,229 @@
+use indexmap::IndexMap;
+
+use crate::{
+    event::{metric::MetricValue, Metric},
+    sources::opentelemetry::proto::{
+        common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+        metrics::v1::{
+            metric::Data, number_data_point, summary_data_point::ValueAtQuantile,
+            AggregationTemporality, Gauge, Histogram, HistogramDataPoint, Metric as OtlpMetric,
+            NumberDataPoint, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+        },
+    },
+};
+
+/// Converts the internal metrics of Vector to OTLP metrics, grouping the series of each metric.
+///
+/// Internal metrics are cumulative since the exporter started at `start_time`, so counters and
+/// histograms are exported with a cumulative temporality.
+pub(super) fn to_scope_metrics(metrics: Vec<Metric>, start_time: u64, time: u64) -> ScopeMetrics {
+    let mut grouped = IndexMap::<String, OtlpMetric>::new();
+
+    for metric in metrics {
+        let name = format!(
+            "{}_{}",
+            metric.namespace().unwrap_or("vector"),
+            metric.name()
+        );
+        let attributes = metric
+            .tags()
+            .map(|tags| {
+                tags.iter()
+                    .map(|(key, value)| KeyValue {
+                        key: key.clone(),
+                        value: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue(value.clone())),
+                        }),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let data = match metric.value() {
+            MetricValue::Counter { value } => Data::Sum(Sum {
+                data_points: vec![NumberDataPoint {
+                    attributes,
+                    start_time_unix_nano: start_time,
+                    time_unix_nano: time,
+                    value: Some(number_data_point::Value::AsDouble(*value)),
+                    ..Default::default()
+                }],
+                aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                is_monotonic: true,
+            }),
+            MetricValue::Gauge { value } => Data::Gauge(Gauge {
+                data_points: vec![NumberDataPoint {
+                    attributes,
+                    time_unix_nano: time,
+                    value: Some(number_data_point::Value::AsDouble(*value)),
+                    ..Default::default()
+                }],
+            }),
+            MetricValue::AggregatedHistogram {
+                buckets,
+                count,
+                sum,
+            } => {
+                let explicit_bounds = buckets
+                    .iter()
+                    .map(|bucket| bucket.upper_limit)
+                    .filter(|upper_limit| upper_limit.is_finite())
+                    .collect::<Vec<_>>();
+                let mut bucket_counts = buckets
+                    .iter()
+                    .map(|bucket| bucket.count as u64)
+                    .collect::<Vec<_>>();
+                // OTLP histograms always have an overflow bucket past the last bound.
+                if bucket_counts.len() == explicit_bounds.len() {
+                    let bucketed = bucket_counts.iter().sum::<u64>();
+                    bucket_counts.push((*count as u64).saturating_sub(bucketed));
+                }
+
+                Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        attributes,
+                        start_time_unix_nano: start_time,
+                        time_unix_nano: time,
+                        count: *count as u64,
+                        sum: Some(*sum),
+                        bucket_counts,
+                        explicit_bounds,
+                        ..Default::default()
+                    }],
+                    aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                })
+            }
+            MetricValue::AggregatedSummary {
+                quantiles,
+                count,
+                sum,
+            } => Data::Summary(Summary {
+                data_points: vec![SummaryDataPoint {
+                    attributes,
+                    start_time_unix_nano: start_time,
+                    time_unix_nano: time,
+                    count: *count as u64,
+                    sum: *sum,
+                    quantile_values: quantiles
+                        .iter()
+                        .map(|quantile| ValueAtQuantile {
+                            quantile: quantile.quantile,
+                            value: quantile.value,
+                        })
+                        .collect(),
+                    ..Default::default()
+                }],
+            }),
+            // Internal metrics are never of the other kinds.
+            _ => continue,
+        };
+
+        let otlp = grouped.entry(name).or_insert_with_key(|name| OtlpMetric {
+            name: name.clone(),
+            ..Default::default()
+        });
+        match (&mut otlp.data, data) {
+            (Some(Data::Sum(existing)), Data::Sum(new)) => {
+                existing.data_points.extend(new.data_points)
+            }
+            (Some(Data::Gauge(existing)), Data::Gauge(new)) => {
+                existing.data_points.extend(new.data_points)
+            }
+            (Some(Data::Histogram(existing)), Data::Histogram(new)) => {
+                existing.data_points.extend(new.data_points)
+            }
+            (Some(Data::Summary(existing)), Data::Summary(new)) => {
+                existing.data_points.extend(new.data_points)
+            }
+            (existing @ None, data) => *existing = Some(data),
+            // A metric name is only ever used for one kind of metric.
+            (Some(_), _) => {}
+        }
+    }
+
+    ScopeMetrics {
+        scope: Some(InstrumentationScope {
+            name: "vector".to_owned(),
+            version: crate::vector_version().to_string(),
+            ..Default::default()
+        }),
+        metrics: grouped.into_values().collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{Bucket, MetricKind};
+
+    #[test]
+    fn converts_internal_metrics() {
+        let metrics = vec![
+            Metric::new(
+                "component_sent_events_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 5.0 },
+            )
+            .with_tags(Some(
+                vec![("component_id".to_owned(), "in".to_owned())]
+                    .into_iter()
+                    .collect(),
+            )),
+            Metric::new(
+                "component_sent_events_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 3.0 },
+            ),
+            Metric::new(
+                "request_duration_seconds",
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vec![
+                        Bucket {
+                            upper_limit: 0.5,
+                            count: 2,
+                        },
+                        Bucket {
+                            upper_limit: 1.0,
+                            count: 1,
+                        },
+                    ],
+                    count: 4,
+                    sum: 4.5,
+                },
+            ),
+        ];
+
+        let scope_metrics = to_scope_metrics(metrics, 1, 2);
+        assert_eq!(scope_metrics.metrics.len(), 2);
+
+        let counter = &scope_metrics.metrics[0];
+        assert_eq!(counter.name, "vector_component_sent_events_total");
+        match &counter.data {
+            Some(Data::Sum(sum)) => {
+                assert!(sum.is_monotonic);
+                assert_eq!(sum.data_points.len(), 2);
+                assert_eq!(sum.data_points[0].attributes[0].key, "component_id");
+                assert_eq!(sum.data_points[0].start_time_unix_nano, 1);
+                assert_eq!(
+                    sum.data_points[1].value,
+                    Some(number_data_point::Value::AsDouble(3.0))
+                );
+            }
+            data => panic!("expected a sum, got {:?}", data),
+        }
+
+        let histogram = &scope_metrics.metrics[1];
+        assert_eq!(histogram.name, "vector_request_duration_seconds");
+        match &histogram.data {
+            Some(Data::Histogram(histogram)) => {
+                let data_point = &histogram.data_points[0];
+                assert_eq!(data_point.explicit_bounds, vec![0.5, 1.0]);
+                assert_eq!(data_point.bucket_counts, vec![2, 1, 1]);
+                assert_eq!(data_point.sum, Some(4.5));
+            }
+            data => panic!("expected a histogram, got {:?}", data),
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Exports the internal metrics and spans of Vector via OTLP, as configured by the top-level
+//! `telemetry_export` option.
+
+mod metrics;
+mod spans;
+
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Request, Uri};
+use hyper::Body;
+use once_cell::sync::Lazy;
+use prost::Message;
+use tokio::task::JoinHandle;
+use tonic::{
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    transport::{Channel, ClientTlsConfig, Endpoint},
+};
+use tracing::Instrument;
+
+pub use self::spans::SpanLayer;
+use crate::{
+    config::{OtlpProtocol, TelemetryExportConfig},
+    http::HttpClient,
+    internal_events::{TelemetryExportError, TelemetryExportSpansDropped},
+    sources::opentelemetry::proto::{
+        collector::{
+            metrics::v1::{
+                metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
+            },
+            trace::v1::{trace_service_client::TraceServiceClient, ExportTraceServiceRequest},
+        },
+        common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+        metrics::v1::ResourceMetrics,
+        resource::v1::Resource,
+        trace::v1::{ResourceSpans, ScopeSpans},
+    },
+};
+
+/// The running exporter, along with the configuration it was started with.
+static EXPORTER: Lazy<Mutex<Option<(TelemetryExportConfig, JoinHandle<()>)>>> =
+    Lazy::new(Default::default);
+
+/// Starts, restarts or stops exporting telemetry, so that it matches the given configuration.
+///
+/// Must be called from within a Tokio runtime.
+pub fn configure(config: Option<&TelemetryExportConfig>) {
+    let mut exporter = EXPORTER.lock().expect("poisoned lock");
+    if exporter.as_ref().map(|(current, _)| current) == config {
+        return;
+    }
+
+    if let Some((_, task)) = exporter.take() {
+        task.abort();
+    }
+    spans::set_enabled(config.map_or(false, |config| config.spans));
+
+    *exporter = config.map(|config| {
+        let task =
+            tokio::spawn(export(config.clone()).instrument(info_span!(spans::EXPORTER_SPAN_NAME)));
+        (config.clone(), task)
+    });
+}
+
+pub(crate) fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+async fn export(config: TelemetryExportConfig) {
+    let client = match Client::new(&config) {
+        Ok(client) => client,
+        Err(error) => {
+            emit!(TelemetryExportError { error });
+            return;
+        }
+    };
+    let resource = resource();
+    let start_time = unix_nanos(SystemTime::now());
+
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+    loop {
+        interval.tick().await;
+
+        if config.metrics {
+            match crate::metrics::Controller::get() {
+                Ok(controller) => {
+                    let scope_metrics = metrics::to_scope_metrics(
+                        controller.capture_metrics(),
+                        start_time,
+                        unix_nanos(SystemTime::now()),
+                    );
+                    let request = ExportMetricsServiceRequest {
+                        resource_metrics: vec![ResourceMetrics {
+                            resource: Some(resource.clone()),
+                            scope_metrics: vec![scope_metrics],
+                            ..Default::default()
+                        }],
+                    };
+                    if let Err(error) = client.export_metrics(request).await {
+                        emit!(TelemetryExportError { error });
+                    }
+                }
+                Err(error) => emit!(TelemetryExportError {
+                    error: error.into()
+                }),
+            }
+        }
+
+        if config.spans {
+            let (spans, dropped) = spans::take();
+            if dropped > 0 {
+                emit!(TelemetryExportSpansDropped { count: dropped });
+            }
+            if !spans.is_empty() {
+                let request = ExportTraceServiceRequest {
+                    resource_spans: vec![ResourceSpans {
+                        resource: Some(resource.clone()),
+                        scope_spans: vec![ScopeSpans {
+                            scope: Some(scope()),
+                            spans,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                };
+                if let Err(error) = client.export_traces(request).await {
+                    emit!(TelemetryExportError { error });
+                }
+            }
+        }
+    }
+}
+
+/// Describes this instance of Vector to the collector.
+fn resource() -> Resource {
+    let mut attributes = vec![
+        string_attribute("service.name", "vector".to_owned()),
+        string_attribute("service.version", crate::vector_version().to_string()),
+    ];
+    if let Ok(hostname) = crate::get_hostname() {
+        attributes.push(string_attribute("host.name", hostname));
+    }
+    Resource {
+        attributes,
+        ..Default::default()
+    }
+}
+
+fn scope() -> InstrumentationScope {
+    InstrumentationScope {
+        name: "vector".to_owned(),
+        version: crate::vector_version().to_string(),
+        ..Default::default()
+    }
+}
+
+fn string_attribute(key: &str, value: String) -> KeyValue {
+    KeyValue {
+        key: key.to_owned(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value)),
+        }),
+    }
+}
+
+type GrpcHeader = (AsciiMetadataKey, AsciiMetadataValue);
+
+/// Sends telemetry to the collector, over the configured protocol.
+enum Client {
+    Grpc {
+        metrics: MetricsServiceClient<Channel>,
+        traces: TraceServiceClient<Channel>,
+        headers: Vec<GrpcHeader>,
+    },
+    Http {
+        client: HttpClient,
+        endpoint: String,
+        headers: Vec<(HeaderName, HeaderValue)>,
+    },
+}
+
+impl Client {
+    fn new(config: &TelemetryExportConfig) -> crate::Result<Self> {
+        match config.protocol {
+            OtlpProtocol::Grpc => {
+                let uri = config.endpoint.parse::<Uri>()?;
+                let mut endpoint = Endpoint::from(uri.clone());
+                if uri.scheme_str() == Some("https") {
+                    endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+                }
+                let channel = endpoint.connect_lazy();
+                let headers = config
+                    .headers
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok((
+                            AsciiMetadataKey::from_bytes(key.to_lowercase().as_bytes())?,
+                            value.parse::<AsciiMetadataValue>()?,
+                        ))
+                    })
+                    .collect::<crate::Result<_>>()?;
+                Ok(Self::Grpc {
+                    metrics: MetricsServiceClient::new(channel.clone()),
+                    traces: TraceServiceClient::new(channel),
+                    headers,
+                })
+            }
+            OtlpProtocol::Http => {
+                let headers = config
+                    .headers
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok((
+                            HeaderName::from_bytes(key.as_bytes())?,
+                            HeaderValue::from_str(value)?,
+                        ))
+                    })
+                    .collect::<crate::Result<_>>()?;
+                Ok(Self::Http {
+                    client: HttpClient::new(None, &Default::default())?,
+                    endpoint: config.endpoint.trim_end_matches('/').to_owned(),
+                    headers,
+                })
+            }
+        }
+    }
+
+    async fn export_metrics(&self, request: ExportMetricsServiceRequest) -> crate::Result<()> {
+        match self {
+            Self::Grpc {
+                metrics, headers, ..
+            } => {
+                metrics
+                    .clone()
+                    .export(grpc_request(request, headers))
+                    .await?;
+                Ok(())
+            }
+            Self::Http { .. } => self.post("/v1/metrics", request.encode_to_vec()).await,
+        }
+    }
+
+    async fn export_traces(&self, request: ExportTraceServiceRequest) -> crate::Result<()> {
+        match self {
+            Self::Grpc {
+                traces, headers, ..
+            } => {
+                traces
+                    .clone()
+                    .export(grpc_request(request, headers))
+                    .await?;
+                Ok(())
+            }
+            Self::Http { .. } => self.post("/v1/traces", request.encode_to_vec()).await,
+        }
+    }
+
+    async fn post(&self, path: &str, body: Vec<u8>) -> crate::Result<()> {
+        if let Self::Http {
+            client,
+            endpoint,
+            headers,
+        } = self
+        {
+            let mut request = Request::post(format!("{}{}", endpoint, path))
+                .header(CONTENT_TYPE, "application/x-protobuf")
+                .body(Body::from(body))?;
+            request.headers_mut().extend(headers.iter().cloned());
+
+            let response = client.send(request).await?;
+            if !response.status().is_success() {
+                return Err(format!("collector responded with {}", response.status()).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn grpc_request<T>(message: T, headers: &[GrpcHeader]) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    for (key, value) in headers {
+        request.metadata_mut().insert(key.clone(), value.clone());
+    }
+    request
+}
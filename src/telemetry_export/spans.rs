@@ -0,0 +1,239 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+use uuid::Uuid;
+
+use super::unix_nanos;
+use crate::sources::opentelemetry::proto::{
+    common::v1::{any_value, AnyValue, KeyValue},
+    trace::v1::{span::SpanKind, Span},
+};
+
+/// The maximum number of closed spans waiting to be exported, past which spans are dropped.
+const MAX_QUEUED_SPANS: usize = 4096;
+
+/// The name of the span the exporter runs in. Its spans, and those of its children, aren't
+/// exported, so that exporting spans doesn't produce more of them.
+pub(super) const EXPORTER_SPAN_NAME: &str = "telemetry_export";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static QUEUE: Lazy<Mutex<Vec<Span>>> = Lazy::new(Default::default);
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// Starts or stops recording spans to export.
+pub(super) fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        QUEUE.lock().expect("poisoned lock").clear();
+    }
+}
+
+/// Takes the spans closed since the last call, and the number of spans dropped since then as
+/// too many were waiting to be exported.
+pub(super) fn take() -> (Vec<Span>, usize) {
+    let spans = std::mem::take(&mut *QUEUE.lock().expect("poisoned lock"));
+    (spans, DROPPED.swap(0, Ordering::Relaxed))
+}
+
+/// The data recorded about an open span.
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    start: SystemTime,
+    attributes: Vec<KeyValue>,
+    suppressed: bool,
+}
+
+/// Records the spans of Vector, to be exported once closed.
+pub struct SpanLayer;
+
+impl<S> Layer<S> for SpanLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        // Spans are part of the trace of their parent, unless they're the root of a new one.
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|data| (data.trace_id, data.span_id, data.suppressed))
+        });
+        let (trace_id, parent_span_id, suppressed) = match parent {
+            Some((trace_id, span_id, suppressed)) => (trace_id, Some(span_id), suppressed),
+            None => (Uuid::new_v4().into_bytes(), None, false),
+        };
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+
+        let mut span_id = [0; 8];
+        span_id.copy_from_slice(&Uuid::new_v4().as_bytes()[..8]);
+
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id,
+            parent_span_id,
+            start: SystemTime::now(),
+            attributes,
+            suppressed: suppressed || attrs.metadata().name() == EXPORTER_SPAN_NAME,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+                values.record(&mut AttributeVisitor(&mut data.attributes));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let data = match span.extensions_mut().remove::<SpanData>() {
+            Some(data) if !data.suppressed && ENABLED.load(Ordering::Relaxed) => data,
+            _ => return,
+        };
+
+        let mut queue = QUEUE.lock().expect("poisoned lock");
+        if queue.len() >= MAX_QUEUED_SPANS {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        queue.push(Span {
+            trace_id: data.trace_id.to_vec(),
+            span_id: data.span_id.to_vec(),
+            parent_span_id: data
+                .parent_span_id
+                .map(|id| id.to_vec())
+                .unwrap_or_default(),
+            name: span.name().to_owned(),
+            kind: SpanKind::Internal as i32,
+            start_time_unix_nano: unix_nanos(data.start),
+            end_time_unix_nano: unix_nanos(SystemTime::now()),
+            attributes: data.attributes,
+            ..Default::default()
+        });
+    }
+}
+
+/// Records the fields of a span as attributes, replacing those already recorded.
+struct AttributeVisitor<'a>(&'a mut Vec<KeyValue>);
+
+impl AttributeVisitor<'_> {
+    fn insert(&mut self, field: &Field, value: any_value::Value) {
+        let value = Some(AnyValue { value: Some(value) });
+        match self.0.iter_mut().find(|kv| kv.key == field.name()) {
+            Some(kv) => kv.value = value,
+            None => self.0.push(KeyValue {
+                key: field.name().to_owned(),
+                value,
+            }),
+        }
+    }
+}
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, any_value::Value::DoubleValue(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, any_value::Value::IntValue(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.insert(field, any_value::Value::IntValue(value)),
+            Err(_) => self.insert(field, any_value::Value::StringValue(value.to_string())),
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, any_value::Value::BoolValue(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, any_value::Value::StringValue(value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, any_value::Value::StringValue(format!("{:?}", value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn records_closed_spans() {
+        let subscriber = tracing_subscriber::registry().with(SpanLayer);
+        set_enabled(true);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let parent = tracing::info_span!("parent", component_id = "in");
+            let _entered = parent.enter();
+            tracing::info_span!("child", count = 3).in_scope(|| {});
+            tracing::info_span!(EXPORTER_SPAN_NAME).in_scope(|| {
+                tracing::info_span!("exporting").in_scope(|| {});
+            });
+        });
+
+        let (spans, _) = take();
+        set_enabled(false);
+
+        // Spans of other tests may be recorded as well.
+        let spans = spans
+            .into_iter()
+            .filter(|span| {
+                ["parent", "child", "exporting", EXPORTER_SPAN_NAME].contains(&span.name.as_str())
+            })
+            .collect::<Vec<_>>();
+        let names = spans
+            .iter()
+            .map(|span| span.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["child", "parent"]);
+        let (child, parent) = (&spans[0], &spans[1]);
+        assert_eq!(child.trace_id, parent.trace_id);
+        assert_eq!(child.parent_span_id, parent.span_id);
+        assert!(parent.parent_span_id.is_empty());
+        assert_eq!(
+            child.attributes,
+            vec![KeyValue {
+                key: "count".to_owned(),
+                value: Some(AnyValue {
+                    value: Some(any_value::Value::IntValue(3)),
+                }),
+            }]
+        );
+    }
+}
@@ -69,6 +69,11 @@ pub fn basic_sink_failing_healthcheck(
     (rx.into_stream(), sink)
 }
 
+/// Returns a sink which never reads its input nor finishes.
+pub fn dead_sink() -> BasicSinkConfig {
+    BasicSinkConfig::default()
+}
+
 pub fn error_sink() -> ErrorSinkConfig {
     ErrorSinkConfig::default()
 }
@@ -18,8 +18,9 @@ use tokio_openssl::SslStream;
 
 use super::{
     CreateAcceptorSnafu, HandshakeSnafu, IncomingListenerSnafu, MaybeTlsSettings, MaybeTlsStream,
-    SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings,
+    ProxyProtocolSnafu, SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings,
 };
+use crate::proxy_protocol;
 #[cfg(feature = "sources-utils-tcp-keepalive")]
 use crate::tcp::TcpKeepaliveConfig;
 #[cfg(feature = "sources-utils-tcp-socket")]
@@ -48,22 +49,40 @@ impl MaybeTlsSettings {
             Self::Raw(()) => None,
         };
 
-        Ok(MaybeTlsListener { listener, acceptor })
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            proxy_protocol: false,
+        })
     }
 }
 
 pub struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
+    proxy_protocol: bool,
 }
 
 impl MaybeTlsListener {
+    /// Expects accepted connections to start with a PROXY protocol header, read before the TLS
+    /// handshake, and reports the client address it holds as their peer address.
+    #[allow(unused)]
+    pub(crate) const fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
     pub(crate) async fn accept(&mut self) -> crate::tls::Result<MaybeTlsIncomingStream<TcpStream>> {
         self.listener
             .accept()
             .await
             .map(|(stream, peer_addr)| {
-                MaybeTlsIncomingStream::new(stream, peer_addr, self.acceptor.clone())
+                MaybeTlsIncomingStream::new(
+                    stream,
+                    peer_addr,
+                    self.acceptor.clone(),
+                    self.proxy_protocol,
+                )
             })
             .context(IncomingListenerSnafu)
     }
@@ -137,6 +156,7 @@ impl From<TcpListener> for MaybeTlsListener {
         Self {
             listener,
             acceptor: None,
+            proxy_protocol: false,
         }
     }
 }
@@ -145,13 +165,18 @@ pub struct MaybeTlsIncomingStream<S> {
     state: StreamState<S>,
     // BoxFuture doesn't allow access to the inner stream, but users
     // of MaybeTlsIncomingStream want access to the peer address while
-    // still handshaking, so we have to cache it here.
+    // still handshaking, so we have to cache it here. Once handshaking
+    // is done, this is the client address of the PROXY protocol header,
+    // if any.
     peer_addr: SocketAddr,
 }
 
+/// The stream once handshaking is done, along with the client address of its PROXY protocol header.
+type Handshaked<S> = (MaybeTlsStream<S>, Option<SocketAddr>);
+
 enum StreamState<S> {
     Accepted(MaybeTlsStream<S>),
-    Accepting(BoxFuture<'static, Result<SslStream<S>, TlsError>>),
+    Accepting(BoxFuture<'static, Result<Handshaked<S>, TlsError>>),
     AcceptError(String),
     Closed,
 }
@@ -218,34 +243,59 @@ impl<S> MaybeTlsIncomingStream<S> {
 
 impl MaybeTlsIncomingStream<TcpStream> {
     pub(super) fn new(
-        stream: TcpStream,
+        mut stream: TcpStream,
         peer_addr: SocketAddr,
         acceptor: Option<SslAcceptor>,
+        proxy_protocol: bool,
     ) -> Self {
-        let state = match acceptor {
-            Some(acceptor) => StreamState::Accepting(
+        let state = if acceptor.is_none() && !proxy_protocol {
+            StreamState::Accepted(MaybeTlsStream::Raw(stream))
+        } else {
+            StreamState::Accepting(
                 async move {
-                    let ssl = Ssl::new(acceptor.context()).context(SslBuildSnafu)?;
-                    let mut stream = SslStream::new(ssl, stream).context(SslBuildSnafu)?;
-                    Pin::new(&mut stream)
-                        .accept()
-                        .await
-                        .context(HandshakeSnafu)?;
-                    Ok(stream)
+                    // The PROXY protocol header is sent by the proxy in the clear, before the
+                    // client's own TLS handshake.
+                    let client_addr = if proxy_protocol {
+                        proxy_protocol::read_header(&mut stream)
+                            .await
+                            .context(ProxyProtocolSnafu)?
+                    } else {
+                        None
+                    };
+
+                    let stream = match acceptor {
+                        Some(acceptor) => {
+                            let ssl = Ssl::new(acceptor.context()).context(SslBuildSnafu)?;
+                            let mut stream = SslStream::new(ssl, stream).context(SslBuildSnafu)?;
+                            Pin::new(&mut stream)
+                                .accept()
+                                .await
+                                .context(HandshakeSnafu)?;
+                            MaybeTlsStream::Tls(stream)
+                        }
+                        None => MaybeTlsStream::Raw(stream),
+                    };
+                    Ok((stream, client_addr))
                 }
                 .boxed(),
-            ),
-            None => StreamState::Accepted(MaybeTlsStream::Raw(stream)),
+            )
         };
         Self { state, peer_addr }
     }
 
+    fn handshaked(&mut self, (stream, client_addr): Handshaked<TcpStream>) {
+        if let Some(client_addr) = client_addr {
+            self.peer_addr = client_addr;
+        }
+        self.state = StreamState::Accepted(stream);
+    }
+
     // Explicit handshake method
     #[cfg(feature = "listenfd")]
     pub(crate) async fn handshake(&mut self) -> crate::tls::Result<()> {
         if let StreamState::Accepting(fut) = &mut self.state {
-            let stream = fut.await?;
-            self.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+            let handshaked = fut.await?;
+            self.handshaked(handshaked);
         }
 
         Ok(())
@@ -291,8 +341,8 @@ impl MaybeTlsIncomingStream<TcpStream> {
             return match &mut this.state {
                 StreamState::Accepted(stream) => poll_fn(Pin::new(stream), cx),
                 StreamState::Accepting(fut) => match futures::ready!(fut.as_mut().poll(cx)) {
-                    Ok(stream) => {
-                        this.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+                    Ok(handshaked) => {
+                        this.handshaked(handshaked);
                         continue;
                     }
                     Err(error) => {
@@ -340,8 +390,8 @@ impl AsyncWrite for MaybeTlsIncomingStream<TcpStream> {
                 poll_result => poll_result,
             },
             StreamState::Accepting(fut) => match futures::ready!(fut.as_mut().poll(cx)) {
-                Ok(stream) => {
-                    this.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+                Ok(handshaked) => {
+                    this.handshaked(handshaked);
                     Poll::Pending
                 }
                 Err(error) => {
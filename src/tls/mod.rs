@@ -85,6 +85,10 @@ pub enum TlsError {
     MissingRequiredIdentity,
     #[snafu(display("TLS handshake failed: {}", source))]
     Handshake { source: openssl::ssl::Error },
+    #[snafu(display("{}", source))]
+    ProxyProtocol {
+        source: crate::proxy_protocol::ProxyProtocolError,
+    },
     #[snafu(display("Incoming listener failed: {}", source))]
     IncomingListener { source: tokio::io::Error },
     #[snafu(display("Creating the TLS acceptor failed: {}", source))]
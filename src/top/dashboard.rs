@@ -13,7 +13,7 @@ use number_prefix::NumberPrefix;
 use tokio::sync::oneshot;
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
@@ -24,6 +24,7 @@ use super::{
     events::capture_key_press,
     state::{self, ConnectionStatus},
 };
+use crate::config::ComponentKey;
 
 /// Format metrics, with thousands separation
 trait ThousandsFormatter {
@@ -104,7 +105,45 @@ fn format_metric(total: i64, throughput: i64, human_metrics: bool) -> String {
     }
 }
 
-const NUM_COLUMNS: usize = 8;
+/// Format a buffer's usage as its number of events, along with how full it is if it's bounded
+fn format_buffer(buffer: Option<&state::BufferUsage>, human_metrics: bool) -> String {
+    match buffer {
+        None => "N/A".to_string(),
+        Some(buffer) => {
+            let events = if human_metrics {
+                buffer.events.human_format()
+            } else {
+                buffer.events.thousands_format()
+            };
+            match buffer.percent_full() {
+                Some(percent) => format!("{} ({:.0}%)", events, percent),
+                None => events,
+            }
+        }
+    }
+}
+
+/// Moves the selection to the next or previous component, wrapping around
+fn move_selection(
+    state: &state::State,
+    selected: Option<&ComponentKey>,
+    next: bool,
+) -> Option<ComponentKey> {
+    let keys = state.components.keys().collect::<Vec<_>>();
+    if keys.is_empty() {
+        return None;
+    }
+
+    let index = match selected.and_then(|selected| keys.iter().position(|key| *key == selected)) {
+        Some(index) if next => (index + 1) % keys.len(),
+        Some(index) => (index + keys.len() - 1) % keys.len(),
+        None if next => 0,
+        None => keys.len() - 1,
+    };
+    Some(keys[index].clone())
+}
+
+const NUM_COLUMNS: usize = 9;
 static HEADER: [&str; NUM_COLUMNS] = [
     "ID",
     "Output",
@@ -114,6 +153,7 @@ static HEADER: [&str; NUM_COLUMNS] = [
     "Events Out",
     "Bytes",
     "Errors",
+    "Buffer",
 ];
 
 struct Widgets<'a> {
@@ -127,7 +167,8 @@ impl<'a> Widgets<'a> {
     pub fn new(url_string: &'a str, opts: &'a super::Opts) -> Self {
         let constraints = vec![
             Constraint::Length(3),
-            Constraint::Max(90),
+            Constraint::Min(8),
+            Constraint::Length(8),
             Constraint::Length(3),
         ];
 
@@ -166,9 +207,24 @@ impl<'a> Widgets<'a> {
         f.render_widget(w, area);
     }
 
+    /// Formats a count, humanized if requested.
+    fn format_count(&self, count: i64) -> String {
+        if self.opts.human_metrics {
+            count.human_format()
+        } else {
+            count.thousands_format()
+        }
+    }
+
     /// Renders a components table, showing sources, transforms and sinks in tabular form, with
     /// statistics pulled from `ComponentsState`,
-    fn components_table<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, area: Rect) {
+    fn components_table<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        selected: Option<&ComponentKey>,
+        area: Rect,
+    ) {
         // Header columns
         let header = HEADER
             .iter()
@@ -204,15 +260,17 @@ impl<'a> Widgets<'a> {
                     r.processed_bytes_throughput_sec,
                     self.opts.human_metrics,
                 ),
-                if self.opts.human_metrics {
-                    r.errors.human_format()
-                } else {
-                    r.errors.thousands_format()
-                },
+                self.format_count(r.errors),
+                format_buffer(r.buffer.as_ref(), self.opts.human_metrics),
             ];
 
             data.extend_from_slice(&formatted_metrics);
-            items.push(Row::new(data).style(Style::default()));
+            let style = if selected == Some(&r.key) {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            items.push(Row::new(data).style(style));
 
             // Add output rows
             if r.has_displayable_outputs() {
@@ -239,13 +297,14 @@ impl<'a> Widgets<'a> {
             .column_spacing(2)
             .widths(&[
                 Constraint::Percentage(15), // ID
-                Constraint::Percentage(15), // Output
-                Constraint::Percentage(10), // Kind
+                Constraint::Percentage(10), // Output
+                Constraint::Percentage(8),  // Kind
                 Constraint::Percentage(10), // Type
-                Constraint::Percentage(10), // Events In
-                Constraint::Percentage(10), // Events Out
-                Constraint::Percentage(10), // Bytes
-                Constraint::Percentage(10), // Errors
+                Constraint::Percentage(12), // Events In
+                Constraint::Percentage(12), // Events Out
+                Constraint::Percentage(12), // Bytes
+                Constraint::Percentage(8),  // Errors
+                Constraint::Percentage(10), // Buffer
             ]);
 
         f.render_widget(w, area);
@@ -261,9 +320,143 @@ impl<'a> Widgets<'a> {
         f.render_widget(w, area);
     }
 
+    /// Renders the buffer, retries and errors of the selected component.
+    fn details<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        selected: Option<&ComponentKey>,
+        area: Rect,
+    ) {
+        let row = match selected.and_then(|key| state.components.get(key)) {
+            Some(row) => row,
+            None => {
+                let block = Block::default().borders(Borders::ALL).title("Details");
+                let w = Paragraph::new(
+                    "Select a component with the up and down arrow keys to view its buffer, retries and errors",
+                )
+                .block(block)
+                .wrap(Wrap { trim: true });
+
+                f.render_widget(w, area);
+                return;
+            }
+        };
+
+        let rects = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+
+        self.buffer_details(f, row, rects[0]);
+        self.error_details(f, row, rects[1]);
+    }
+
+    /// Renders the buffer usage and retry state of a component.
+    fn buffer_details<B: Backend>(&self, f: &mut Frame<B>, row: &state::ComponentRow, area: Rect) {
+        let mut text = match &row.buffer {
+            Some(buffer) => vec![
+                Spans::from(format!(
+                    "Buffered events: {}{}",
+                    self.format_count(buffer.events),
+                    buffer
+                        .max_events
+                        .map(|max| format!(" / {}", self.format_count(max)))
+                        .unwrap_or_default()
+                )),
+                Spans::from(format!(
+                    "Buffered bytes: {}{}",
+                    buffer.byte_size.human_format_bytes(),
+                    buffer
+                        .max_byte_size
+                        .map(|max| format!(" / {}", max.human_format_bytes()))
+                        .unwrap_or_default()
+                )),
+                Spans::from(format!(
+                    "Buffer usage: {}",
+                    buffer
+                        .percent_full()
+                        .map(|percent| format!("{:.1}%", percent))
+                        .unwrap_or_else(|| "--".to_string())
+                )),
+            ],
+            None => vec![Spans::from("Buffer: N/A")],
+        };
+
+        text.push(Spans::from(format!(
+            "Retried requests: {}",
+            self.format_count(row.retries_total)
+        )));
+        text.push(if row.retry_backoff_secs > 0.0 {
+            Spans::from(Span::styled(
+                format!("Backing off for {:.1}s", row.retry_backoff_secs),
+                Style::default().fg(Color::Yellow),
+            ))
+        } else {
+            Spans::from("Backoff: --")
+        });
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Buffer and retries: {}", row.key.id()));
+        let w = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+        f.render_widget(w, area);
+    }
+
+    /// Renders the errors of a component by type, the most recent first.
+    fn error_details<B: Backend>(&self, f: &mut Frame<B>, row: &state::ComponentRow, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Errors: {}", row.key.id()));
+
+        if row.error_types.is_empty() {
+            let w = Paragraph::new("No errors").block(block);
+            f.render_widget(w, area);
+            return;
+        }
+
+        let mut error_types = row.error_types.iter().collect::<Vec<_>>();
+        error_types.sort_by(|a, b| (b.recent, b.total).cmp(&(a.recent, a.total)));
+
+        let header = ["Error type", "Stage", "Recent", "Total"]
+            .iter()
+            .map(|s| Cell::from(*s).style(Style::default().add_modifier(Modifier::BOLD)))
+            .collect::<Vec<_>>();
+        let items = error_types.into_iter().map(|error_type| {
+            let style = if error_type.recent > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                error_type.error_type.clone(),
+                error_type.stage.clone().unwrap_or_else(|| "--".to_string()),
+                self.format_count(error_type.recent),
+                self.format_count(error_type.total),
+            ])
+            .style(style)
+        });
+
+        let w = Table::new(items)
+            .header(Row::new(header))
+            .block(block)
+            .column_spacing(2)
+            .widths(&[
+                Constraint::Percentage(40), // Error type
+                Constraint::Percentage(20), // Stage
+                Constraint::Percentage(20), // Recent
+                Constraint::Percentage(20), // Total
+            ]);
+
+        f.render_widget(w, area);
+    }
+
     /// Renders a box showing instructions on how to exit from `vector top`.
     fn quit_box<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let text = vec![Spans::from("To quit, press ESC or 'q'")];
+        let text = vec![Spans::from(
+            "To quit, press ESC or 'q'. To select a component, press the up and down arrow keys",
+        )];
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -277,7 +470,12 @@ impl<'a> Widgets<'a> {
     }
 
     /// Draw a single frame. Creates a layout and renders widgets into it.
-    fn draw<B: Backend>(&self, f: &mut Frame<B>, state: state::State) {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        selected: Option<&ComponentKey>,
+    ) {
         let size = f.size();
         let rects = Layout::default()
             .constraints(self.constraints.clone())
@@ -287,12 +485,13 @@ impl<'a> Widgets<'a> {
 
         // Require a minimum of 80 chars of line width to display the table
         if size.width >= 80 {
-            self.components_table(f, &state, rects[1]);
+            self.components_table(f, state, selected, rects[1]);
         } else {
             self.components_resize_window(f, rects[1]);
         }
 
-        self.quit_box(f, rects[2]);
+        self.details(f, state, selected, rects[2]);
+        self.quit_box(f, rects[3]);
     }
 }
 
@@ -332,15 +531,29 @@ pub async fn init_dashboard<'a>(
 
     let widgets = Widgets::new(url, opts);
 
+    // The latest state, to redraw the dashboard when the selected component changes
+    let mut last_state: Option<state::State> = None;
+    let mut selected: Option<ComponentKey> = None;
+
     loop {
         tokio::select! {
             Some(state) = state_rx.recv() => {
-                terminal.draw(|f| widgets.draw(f, state))?;
+                terminal.draw(|f| widgets.draw(f, &state, selected.as_ref()))?;
+                last_state = Some(state);
             },
             k = key_press_rx.recv() => {
-                if let KeyCode::Esc | KeyCode::Char('q') = k.unwrap() {
-                    let _ = key_press_kill_tx.send(());
-                    break
+                match k.unwrap() {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        let _ = key_press_kill_tx.send(());
+                        break
+                    }
+                    key @ (KeyCode::Up | KeyCode::Down) => {
+                        if let Some(state) = &last_state {
+                            selected = move_selection(state, selected.as_ref(), key == KeyCode::Down);
+                            terminal.draw(|f| widgets.draw(f, state, selected.as_ref()))?;
+                        }
+                    }
+                    _ => {}
                 }
             }
             _ = &mut shutdown_rx => {
@@ -445,6 +658,29 @@ mod tests {
         assert_eq!(N.human_format(), "1.10 T");
     }
 
+    #[test]
+    /// Buffers should show how full they are when bounded
+    fn format_buffers() {
+        let mut buffer = state::BufferUsage {
+            events: 1_500,
+            byte_size: 512,
+            max_events: Some(6_000),
+            max_byte_size: None,
+        };
+
+        assert_eq!(format_buffer(None, false), "N/A");
+        assert_eq!(format_buffer(Some(&buffer), false), "1,500 (25%)");
+        assert_eq!(format_buffer(Some(&buffer), true), "1.50 k (25%)");
+
+        // The fullest of the events and bytes limits wins.
+        buffer.max_byte_size = Some(1024);
+        assert_eq!(format_buffer(Some(&buffer), false), "1,500 (50%)");
+
+        buffer.max_events = None;
+        buffer.max_byte_size = None;
+        assert_eq!(format_buffer(Some(&buffer), false), "1,500");
+    }
+
     #[test]
     /// Should format bytes
     fn format_bytes() {
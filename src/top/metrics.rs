@@ -10,7 +10,7 @@ use vector_api_client::{
     Client, SubscriptionClient,
 };
 
-use super::state::{self, OutputMetrics};
+use super::state::{self, BufferUsage, ErrorTypeMetrics, OutputMetrics};
 use crate::{config::ComponentKey, top::state::SentEventsMetric};
 
 /// Components that have been added
@@ -36,6 +36,10 @@ async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
                     processed_bytes_total: 0,
                     processed_bytes_throughput_sec: 0,
                     errors: 0,
+                    error_types: Vec::new(),
+                    buffer: None,
+                    retries_total: 0,
+                    retry_backoff_secs: 0.0,
                 }))
                 .await;
         }
@@ -236,6 +240,92 @@ async fn errors_totals(client: Arc<SubscriptionClient>, tx: state::EventTx, inte
     }
 }
 
+async fn error_types(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_error_types_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_error_types;
+            let _ = tx
+                .send(state::EventType::ErrorTypes(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(c.component_id.as_str()),
+                                c.error_types
+                                    .into_iter()
+                                    .map(|e| ErrorTypeMetrics {
+                                        error_type: e.error_type,
+                                        stage: e.stage,
+                                        total: e.total as i64,
+                                        recent: e.recent as i64,
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn buffer_usages(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_buffer_usages_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_buffer_usages;
+            let _ = tx
+                .send(state::EventType::BufferUsages(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(c.component_id.as_str()),
+                                BufferUsage {
+                                    events: c.events as i64,
+                                    byte_size: c.byte_size as i64,
+                                    max_events: c.max_events.map(|max| max as i64),
+                                    max_byte_size: c.max_byte_size.map(|max| max as i64),
+                                },
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn retries(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_retries_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_retries;
+            let _ = tx
+                .send(state::EventType::Retries(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(c.component_id.as_str()),
+                                c.retries_total as i64,
+                                c.backoff_seconds,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(
@@ -278,7 +368,10 @@ pub fn subscribe(
             tx.clone(),
             interval,
         )),
-        tokio::spawn(errors_totals(Arc::clone(&client), tx, interval)),
+        tokio::spawn(errors_totals(Arc::clone(&client), tx.clone(), interval)),
+        tokio::spawn(error_types(Arc::clone(&client), tx.clone(), interval)),
+        tokio::spawn(buffer_usages(Arc::clone(&client), tx.clone(), interval)),
+        tokio::spawn(retries(Arc::clone(&client), tx, interval)),
     ]
 }
 
@@ -322,6 +415,10 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
                         processed_bytes_total: d.on.processed_bytes_total(),
                         processed_bytes_throughput_sec: 0,
                         errors: 0,
+                        error_types: Vec::new(),
+                        buffer: None,
+                        retries_total: 0,
+                        retry_backoff_secs: 0.0,
                     },
                 ))
             })
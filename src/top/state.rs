@@ -32,6 +32,10 @@ pub enum EventType {
     /// Interval + identified metric
     ProcessedBytesThroughputs(i64, Vec<IdentifiedMetric>),
     ErrorsTotals(Vec<IdentifiedMetric>),
+    ErrorTypes(Vec<(ComponentKey, Vec<ErrorTypeMetrics>)>),
+    BufferUsages(Vec<(ComponentKey, BufferUsage)>),
+    /// Identified retries total + current backoff in seconds
+    Retries(Vec<(ComponentKey, i64, f64)>),
     ComponentAdded(ComponentRow),
     ComponentRemoved(ComponentKey),
     ConnectionUpdated(ConnectionStatus),
@@ -106,6 +110,44 @@ impl From<i64> for OutputMetrics {
     }
 }
 
+/// Number and size of the events in a component's buffer, along with its capacity.
+#[derive(Debug, Clone, Default)]
+pub struct BufferUsage {
+    pub events: i64,
+    pub byte_size: i64,
+    pub max_events: Option<i64>,
+    pub max_byte_size: Option<i64>,
+}
+
+impl BufferUsage {
+    /// How full the buffer is as a percentage of its capacity, by events or by size depending
+    /// on how it's bounded.
+    pub fn percent_full(&self) -> Option<f64> {
+        let events = self
+            .max_events
+            .filter(|max| *max > 0)
+            .map(|max| self.events as f64 * 100.0 / max as f64);
+        let bytes = self
+            .max_byte_size
+            .filter(|max| *max > 0)
+            .map(|max| self.byte_size as f64 * 100.0 / max as f64);
+
+        match (events, bytes) {
+            (Some(events), Some(bytes)) => Some(events.max(bytes)),
+            (events, bytes) => events.or(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorTypeMetrics {
+    pub error_type: String,
+    pub stage: Option<String>,
+    pub total: i64,
+    /// Errors since the previous sample
+    pub recent: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ComponentRow {
     pub key: ComponentKey,
@@ -119,6 +161,10 @@ pub struct ComponentRow {
     pub sent_events_total: i64,
     pub sent_events_throughput_sec: i64,
     pub errors: i64,
+    pub error_types: Vec<ErrorTypeMetrics>,
+    pub buffer: Option<BufferUsage>,
+    pub retries_total: i64,
+    pub retry_backoff_secs: f64,
 }
 
 impl ComponentRow {
@@ -208,6 +254,28 @@ pub async fn updater(mut event_rx: EventRx) -> StateRx {
                         }
                     }
                 }
+                EventType::ErrorTypes(rows) => {
+                    for (key, error_types) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.error_types = error_types;
+                        }
+                    }
+                }
+                EventType::BufferUsages(rows) => {
+                    for (key, buffer) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.buffer = Some(buffer);
+                        }
+                    }
+                }
+                EventType::Retries(rows) => {
+                    for (key, total, backoff_secs) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.retries_total = total;
+                            r.retry_backoff_secs = backoff_secs;
+                        }
+                    }
+                }
                 EventType::ComponentAdded(c) => {
                     let _ = state.components.insert(c.key.clone(), c);
                 }
@@ -14,13 +14,14 @@ use tokio::{
     time::{timeout, Duration},
 };
 use tracing::Instrument;
+use vector_common::memory_budget;
 use vector_core::{
     buffers::{
         topology::{
             builder::TopologyBuilder,
             channel::{BufferReceiver, BufferSender},
         },
-        BufferType, WhenFull,
+        BufferType, EventPriority, Priority, WhenFull,
     },
     internal_event::EventsSent,
     schema::Definition,
@@ -28,21 +29,28 @@ use vector_core::{
 };
 
 use super::{
+    circuit_breaker::{Admission, CircuitBreaker},
+    control::ComponentControl,
+    dead_letter, delivery_audit,
     fanout::{self, Fanout},
-    schema,
+    health::{self, HealthcheckStatus},
+    partitioned_buffer, schema,
     task::{Task, TaskOutput},
+    tenant::TenantLimiter,
     BuiltBuffer, ConfigDiff,
 };
 use crate::{
     config::{
         ComponentKey, DataType, Input, Output, OutputId, ProxyConfig, SinkContext, SourceContext,
-        TransformContext, TransformOuter,
+        TransformContext, TransformOuter, DEAD_LETTER_OUTPUT,
     },
     event::{EventArray, EventContainer},
-    internal_events::EventsReceived,
+    internal_events::{EventsReceived, MemoryBudgetEventsDropped, MemoryBudgetSourcePaused},
     shutdown::SourceShutdownCoordinator,
+    sinks::util::RateLimitGroup,
     source_sender::CHUNK_SIZE,
     spawn_named,
+    template::Template,
     transforms::{SyncTransform, TaskTransform, Transform, TransformOutputs, TransformOutputsBuf},
     utilization::wrap,
     SourceSender,
@@ -128,6 +136,7 @@ pub struct Pieces {
     pub(super) healthchecks: HashMap<ComponentKey, Task>,
     pub(crate) shutdown_coordinator: SourceShutdownCoordinator,
     pub(crate) detach_triggers: HashMap<ComponentKey, Trigger>,
+    pub(super) tenants: HashMap<ComponentKey, String>,
 }
 
 /// Builds only the new pieces, and doesn't check their topology.
@@ -166,7 +175,19 @@ pub async fn build_pieces(
             component_type = %source.inner.source_type(),
             // maintained for compatibility
             component_name = %key.id(),
+            tenant = tracing::field::Empty,
         );
+        let tenant = config.tenant(key);
+        if let Some((name, _)) = tenant {
+            span.record("tenant", &name);
+        }
+        let limiter = tenant.and_then(|(name, tenant)| {
+            tenant
+                .max_events_per_sec
+                .map(|max_events_per_sec| TenantLimiter::get(name, max_events_per_sec))
+        });
+        let component_control = ComponentControl::get(key);
+        let activity = health::source_activity(key);
         let task_name = format!(">> {} ({}, pump) >>", source.inner.source_type(), key.id());
 
         let mut builder = SourceSender::builder().with_buffer(*SOURCE_SENDER_BUFFER_SIZE);
@@ -174,13 +195,41 @@ pub async fn build_pieces(
         let mut controls = HashMap::new();
         let mut schema_definitions = HashMap::with_capacity(source_outputs.len());
 
+        let priority = source.priority;
         for output in source_outputs {
             let mut rx = builder.add_output(output.clone());
 
             let (mut fanout, control) = Fanout::new();
+            let limiter = limiter.clone();
+            let component_control = Arc::clone(&component_control);
+            let activity = Arc::clone(&activity);
+            let source_key = key.clone();
             let pump = async move {
                 debug!("Source pump starting.");
-                while let Some(array) = rx.next().await {
+                while let Some(mut array) = rx.next().await {
+                    if let Some(priority) = priority {
+                        array.for_each_event(|mut event| {
+                            event.metadata_mut().set_priority(priority);
+                        });
+                    }
+                    // Load is shed as the memory budget of the process runs low: low priority
+                    // events are dropped first, then sources are paused.
+                    if array.event_priority() == Priority::Low
+                        && memory_budget::should_drop_low_priority()
+                    {
+                        emit!(MemoryBudgetEventsDropped { count: array.len() });
+                        continue;
+                    }
+                    if memory_budget::should_pause_sources() {
+                        emit!(MemoryBudgetSourcePaused);
+                        memory_budget::wait_for_sources_to_resume().await;
+                    }
+                    if let Some(limiter) = &limiter {
+                        limiter.until_ready(array.len()).await;
+                    }
+                    component_control.until_ready(array.len()).await;
+                    delivery_audit::sample(&source_key, &mut array);
+                    activity.record();
                     fanout.send(array).await;
                 }
                 debug!("Source pump finished.");
@@ -228,7 +277,21 @@ pub async fn build_pieces(
             acknowledgements: source.sink_acknowledgements,
             schema_definitions,
         };
-        let server = match source.inner.build(context).await {
+        #[cfg(feature = "leader-election")]
+        let server = match &source.leader_election {
+            Some(leader_election) => {
+                crate::leader_election::build_source(
+                    leader_election,
+                    source.inner.as_ref(),
+                    context,
+                )
+                .await
+            }
+            None => source.inner.build(context).await,
+        };
+        #[cfg(not(feature = "leader-election"))]
+        let server = source.inner.build(context).await;
+        let server = match server {
             Err(error) => {
                 errors.push(format!("Source \"{}\": {}", key, error));
                 continue;
@@ -357,11 +420,66 @@ pub async fn build_pieces(
                 component_type = typetag,
                 component_name = %key.id(),
                 buffer_type = buffer_type,
+                tenant = tracing::field::Empty,
             );
-            let buffer = sink
-                .buffer
-                .build(config.global.data_dir.clone(), key.to_string(), buffer_span)
-                .await;
+            // The disk buffers of the sinks of a tenant are isolated in its own data directory.
+            let data_dir = match config.tenant(key) {
+                Some((name, tenant)) => {
+                    buffer_span.record("tenant", &name);
+                    let data_dir = tenant.data_dir(name, &config.global);
+                    let uses_disk = sink
+                        .buffer
+                        .stages()
+                        .iter()
+                        .any(|stage| !matches!(stage, BufferType::Memory { .. }));
+                    if let (Some(data_dir), true) = (&data_dir, uses_disk) {
+                        if let Err(error) = std::fs::create_dir_all(data_dir) {
+                            errors.push(format!(
+                                "Sink \"{}\": could not create the data directory of tenant \"{}\": {}",
+                                key, name, error
+                            ));
+                            continue;
+                        }
+                    }
+                    data_dir
+                }
+                None => config.global.data_dir.clone(),
+            };
+            let buffer = match sink.buffer.stages() {
+                [stage @ BufferType::DiskV2 {
+                    partition_by: Some(partition_by),
+                    ..
+                }] => match Template::try_from(partition_by.as_str()) {
+                    Ok(partition_by) => partitioned_buffer::build(
+                        stage,
+                        partition_by,
+                        data_dir,
+                        key.to_string(),
+                        buffer_span,
+                    )
+                    .await
+                    .map_err(|error| error.to_string()),
+                    Err(error) => Err(format!("invalid `partition_by` template: {}", error)),
+                },
+                stages
+                    if stages.iter().any(|stage| {
+                        matches!(
+                            stage,
+                            BufferType::DiskV2 {
+                                partition_by: Some(_),
+                                ..
+                            }
+                        )
+                    }) =>
+                {
+                    Err("`partition_by` is only supported by buffers with a single stage".into())
+                }
+                _ => sink
+                    .buffer
+                    .build(data_dir, key.to_string(), buffer_span)
+                    .await
+                    .map_err(|error| error.to_string()),
+            };
             match buffer {
                 Err(error) => {
                     errors.push(format!("Sink \"{}\": {}", key, error));
@@ -379,6 +497,15 @@ pub async fn build_pieces(
             schema: config.schema,
         };
 
+        // The rate limit group is set up before building the sink, so its requests share the
+        // budget of the group.
+        let rate_limit_group = config
+            .rate_limit_group(key)
+            .map(|(name, group)| RateLimitGroup::get(name, group));
+        let component_control = ComponentControl::get(key);
+        let dead_letter = sink.dead_letter.is_some();
+        let circuit_breaker = sink.circuit_breaker.map(CircuitBreaker::new);
+
         let (sink, healthcheck) = match sink.inner.build(cx).await {
             Err(error) => {
                 errors.push(format!("Sink \"{}\": {}", key, error));
@@ -387,8 +514,22 @@ pub async fn build_pieces(
             Ok(built) => built,
         };
 
+        // The events the sink fails to deliver are routed to its dead letter component through
+        // an output of its own.
+        let (dead_letter_tracker, dead_letter_forwarder) = if dead_letter {
+            let (tracker, forwarder, control) = dead_letter::new(key, typetag);
+            outputs.insert(
+                OutputId::from((key, DEAD_LETTER_OUTPUT.to_owned())),
+                control,
+            );
+            (Some(tracker), Some(forwarder))
+        } else {
+            (None, None)
+        };
+
         let (trigger, tripwire) = Tripwire::new();
 
+        let sink_key = key.clone();
         let sink = async move {
             // Why is this Arc<Mutex<Option<_>>> needed you ask.
             // In case when this function build_pieces errors
@@ -404,19 +545,66 @@ pub async fn build_pieces(
 
             let mut rx = wrap(rx);
 
-            sink.run(
+            let run = sink.run(
                 rx.by_ref()
                     .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
-                    .inspect(|events| {
+                    .inspect(move |events| {
                         emit!(EventsReceived {
                             count: events.len(),
                             byte_size: events.size_of(),
-                        })
+                        });
+                        delivery_audit::record_hops(&sink_key, "sink", events);
+                    })
+                    .then(move |events| {
+                        let rate_limit_group = rate_limit_group.clone();
+                        let control = Arc::clone(&component_control);
+                        async move {
+                            if let Some(rate_limit_group) = rate_limit_group {
+                                rate_limit_group.until_bytes_ready(events.size_of()).await;
+                            }
+                            control.until_ready(events.len()).await;
+                            events
+                        }
+                    })
+                    .filter_map(move |events| {
+                        let circuit_breaker = circuit_breaker.clone();
+                        let dead_letter_tracker = dead_letter_tracker.clone();
+                        async move {
+                            // While its circuit breaker is open, the sink is paused or its events
+                            // are diverted to its dead letter component.
+                            let probe = match &circuit_breaker {
+                                Some(circuit_breaker) => match circuit_breaker.admit().await {
+                                    Admission::Send { probe } => probe,
+                                    Admission::Divert => {
+                                        if let Some(tracker) = &dead_letter_tracker {
+                                            tracker.divert(events);
+                                        }
+                                        return None;
+                                    }
+                                },
+                                None => false,
+                            };
+                            let mut events = match &dead_letter_tracker {
+                                Some(tracker) => tracker.track(events),
+                                None => events,
+                            };
+                            // Observed after being tracked, as the tracked copies are the ones
+                            // handed to the sink.
+                            if let Some(circuit_breaker) = &circuit_breaker {
+                                circuit_breaker.observe(&mut events, probe);
+                            }
+                            Some(events)
+                        }
                     })
                     .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+            );
+            // The sink is only done once the events it failed to deliver have been routed.
+            let result = match dead_letter_forwarder {
+                Some(forwarder) => futures::future::join(run, forwarder.run()).await.0,
+                None => run.await,
+            };
+
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
@@ -432,9 +620,11 @@ pub async fn build_pieces(
                     .map(|result| match result {
                         Ok(Ok(_)) => {
                             info!("Healthcheck: Passed.");
+                            health::set_healthcheck(&component_key, HealthcheckStatus::Passed);
                             Ok(TaskOutput::Healthcheck)
                         }
                         Ok(Err(error)) => {
+                            health::set_healthcheck(&component_key, HealthcheckStatus::Failed);
                             error!(
                                 msg = "Healthcheck: Failed Reason.",
                                 %error,
@@ -447,6 +637,7 @@ pub async fn build_pieces(
                             Err(())
                         }
                         Err(_) => {
+                            health::set_healthcheck(&component_key, HealthcheckStatus::TimedOut);
                             error!(
                                 msg = "Healthcheck: timeout.",
                                 component_kind = "sink",
@@ -461,6 +652,7 @@ pub async fn build_pieces(
                     .await
             } else {
                 info!("Healthcheck: Disabled.");
+                health::set_healthcheck(&component_key, HealthcheckStatus::Disabled);
                 Ok(TaskOutput::Healthcheck)
             }
         };
@@ -485,6 +677,16 @@ pub async fn build_pieces(
         entry.insert(id.port, output);
     }
 
+    // The tasks of the components are tagged with their tenant once spawned.
+    let tenants = tasks
+        .keys()
+        .filter_map(|key| {
+            config
+                .tenant(key)
+                .map(|(name, _)| (key.clone(), name.to_owned()))
+        })
+        .collect();
+
     if errors.is_empty() {
         let pieces = Pieces {
             inputs,
@@ -494,6 +696,7 @@ pub async fn build_pieces(
             healthchecks,
             shutdown_coordinator,
             detach_triggers,
+            tenants,
         };
 
         Ok(pieces)
@@ -563,7 +766,14 @@ fn build_sync_transform(
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (outputs, controls) = TransformOutputs::new(node.outputs);
 
-    let runner = Runner::new(t, input_rx, node.input_details.data_type(), outputs);
+    let runner = Runner::new(
+        t,
+        input_rx,
+        node.input_details.data_type(),
+        outputs,
+        ComponentControl::get(&node.key),
+        node.key.clone(),
+    );
     let transform = if node.enable_concurrency {
         runner.run_concurrently().boxed()
     } else {
@@ -588,6 +798,8 @@ struct Runner {
     input_rx: Option<BufferReceiver<EventArray>>,
     input_type: DataType,
     outputs: TransformOutputs,
+    control: Arc<ComponentControl>,
+    key: ComponentKey,
     timer: crate::utilization::Timer,
     last_report: Instant,
 }
@@ -598,12 +810,16 @@ impl Runner {
         input_rx: BufferReceiver<EventArray>,
         input_type: DataType,
         outputs: TransformOutputs,
+        control: Arc<ComponentControl>,
+        key: ComponentKey,
     ) -> Self {
         Self {
             transform,
             input_rx: Some(input_rx),
             input_type,
             outputs,
+            control,
+            key,
             timer: crate::utilization::Timer::new(),
             last_report: Instant::now(),
         }
@@ -620,6 +836,7 @@ impl Runner {
             count: events.len(),
             byte_size: events.size_of(),
         });
+        delivery_audit::record_hops(&self.key, "transform", events);
     }
 
     async fn send_outputs(&mut self, outputs_buf: &mut TransformOutputsBuf) {
@@ -642,6 +859,7 @@ impl Runner {
 
         self.timer.start_wait();
         while let Some(events) = input_rx.next().await {
+            self.control.until_ready(events.len()).await;
             self.on_events_received(&events);
             self.transform.transform_all(events, &mut outputs_buf);
             self.send_outputs(&mut outputs_buf).await;
@@ -683,10 +901,10 @@ impl Runner {
                 input_arrays = input_rx.next(), if in_flight.len() < *TRANSFORM_CONCURRENCY_LIMIT && !shutting_down => {
                     match input_arrays {
                         Some(input_arrays) => {
-                            let mut len = 0;
+                            let len = input_arrays.iter().map(|events| events.len()).sum();
+                            self.control.until_ready(len).await;
                             for events in &input_arrays {
                                 self.on_events_received(events);
-                                len += events.len();
                             }
 
                             let mut t = self.transform.clone();
@@ -729,14 +947,24 @@ fn build_task_transform(
     let (mut fanout, control) = Fanout::new();
 
     let input_rx = crate::utilization::wrap(input_rx.into_stream());
+    let component_control = ComponentControl::get(key);
+    let transform_key = key.clone();
 
     let filtered = input_rx
         .filter(move |events| ready(filter_events_type(events, input_type)))
-        .inspect(|events| {
+        .then(move |events| {
+            let control = Arc::clone(&component_control);
+            async move {
+                control.until_ready(events.len()).await;
+                events
+            }
+        })
+        .inspect(move |events| {
             emit!(EventsReceived {
                 count: events.len(),
                 byte_size: events.size_of(),
-            })
+            });
+            delivery_audit::record_hops(&transform_key, "transform", events);
         });
     let stream = t
         .transform(Box::pin(filtered))
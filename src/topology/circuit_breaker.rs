@@ -0,0 +1,253 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    sync::Notify,
+    time::{sleep_until, Instant},
+};
+use tracing::Instrument;
+
+use crate::{
+    config::{CircuitBreakerConfig, CircuitBreakerFallback},
+    event::{BatchNotifier, BatchStatus, EventArray, EventFinalizer},
+    internal_events::{CircuitBreakerClosed, CircuitBreakerOpened, CircuitBreakerProbing},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Circuit {
+    /// The events are handed to the sink.
+    Closed,
+    /// The events are held back or diverted until the next probe.
+    Open { probe_at: Instant },
+    /// A probe is in flight, the other events are held back or diverted until it's finalized.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct State {
+    circuit: Circuit,
+    failures: u32,
+}
+
+/// What to do with the next events bound to the sink.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Admission {
+    /// Hand them to the sink, observing their status, whether they are a probe or not.
+    Send { probe: bool },
+    /// Route them to the dead letter component of the sink.
+    Divert,
+}
+
+/// Trips a sink into its fallback after it failed to deliver a number of consecutive arrays of
+/// events, until a probe is delivered.
+#[derive(Clone, Debug)]
+pub(super) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Arc<Mutex<State>>,
+    changed: Arc<Notify>,
+}
+
+impl CircuitBreaker {
+    pub(super) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(State {
+                circuit: Circuit::Closed,
+                failures: 0,
+            })),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Waits for the next events to be admitted to the sink, while it's paused.
+    pub(super) async fn admit(&self) -> Admission {
+        loop {
+            // Registered before checking the state so no change is missed.
+            let changed = self.changed.notified();
+            let probe_at = {
+                let mut state = self.state.lock().expect("poisoned lock");
+                match state.circuit {
+                    Circuit::Closed => return Admission::Send { probe: false },
+                    Circuit::Open { probe_at } if probe_at <= Instant::now() => {
+                        state.circuit = Circuit::HalfOpen;
+                        emit!(CircuitBreakerProbing);
+                        return Admission::Send { probe: true };
+                    }
+                    _ if self.config.fallback == CircuitBreakerFallback::DeadLetter => {
+                        return Admission::Divert
+                    }
+                    Circuit::Open { probe_at } => Some(probe_at),
+                    Circuit::HalfOpen => None,
+                }
+            };
+
+            match probe_at {
+                Some(probe_at) => {
+                    tokio::select! {
+                        _ = sleep_until(probe_at) => {},
+                        _ = changed => {},
+                    }
+                }
+                None => changed.await,
+            }
+        }
+    }
+
+    /// Observes whether the sink delivers the given events, which have been admitted.
+    pub(super) fn observe(&self, events: &mut EventArray, probe: bool) {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        events.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(batch.clone()));
+        });
+        drop(batch);
+
+        let breaker = self.clone();
+        tokio::spawn(
+            async move {
+                let status = receiver.await;
+                breaker.record(status, probe);
+            }
+            .in_current_span(),
+        );
+    }
+
+    fn record(&self, status: BatchStatus, probe: bool) {
+        let mut state = self.state.lock().expect("poisoned lock");
+        let delivered = status == BatchStatus::Delivered;
+        match state.circuit {
+            Circuit::Closed if delivered => state.failures = 0,
+            Circuit::Closed => {
+                state.failures += 1;
+                if state.failures >= self.config.failure_threshold {
+                    self.open(&mut state);
+                }
+            }
+            // Only the probe decides whether the sink recovered, the outcome of the events handed
+            // to it before the circuit breaker opened doesn't matter anymore.
+            Circuit::HalfOpen if probe && delivered => {
+                state.circuit = Circuit::Closed;
+                state.failures = 0;
+                emit!(CircuitBreakerClosed);
+                self.changed.notify_waiters();
+            }
+            Circuit::HalfOpen if probe => {
+                state.failures += 1;
+                self.open(&mut state);
+            }
+            Circuit::HalfOpen | Circuit::Open { .. } => {}
+        }
+    }
+
+    fn open(&self, state: &mut State) {
+        state.circuit = Circuit::Open {
+            probe_at: Instant::now() + Duration::from_secs(self.config.probe_interval_secs),
+        };
+        emit!(CircuitBreakerOpened {
+            failures: state.failures,
+            fallback: match self.config.fallback {
+                CircuitBreakerFallback::Pause => "pause",
+                CircuitBreakerFallback::DeadLetter => "dead_letter",
+            },
+        });
+        self.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+    use crate::event::{Event, EventStatus, LogEvent};
+
+    fn breaker(fallback: CircuitBreakerFallback) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            probe_interval_secs: 30,
+            fallback,
+        })
+    }
+
+    fn events() -> EventArray {
+        Event::from(LogEvent::from("message")).into()
+    }
+
+    fn circuit(breaker: &CircuitBreaker) -> Circuit {
+        breaker.state.lock().unwrap().circuit
+    }
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        let breaker = breaker(CircuitBreakerFallback::Pause);
+
+        breaker.record(BatchStatus::Errored, false);
+        breaker.record(BatchStatus::Delivered, false);
+        breaker.record(BatchStatus::Rejected, false);
+        assert_eq!(circuit(&breaker), Circuit::Closed);
+        assert_eq!(breaker.admit().await, Admission::Send { probe: false });
+
+        breaker.record(BatchStatus::Errored, false);
+        assert!(matches!(circuit(&breaker), Circuit::Open { .. }));
+    }
+
+    #[tokio::test]
+    async fn pauses_until_probe_delivered() {
+        tokio::time::pause();
+        let breaker = breaker(CircuitBreakerFallback::Pause);
+        breaker.record(BatchStatus::Errored, false);
+        breaker.record(BatchStatus::Errored, false);
+
+        // Paused until the probe interval elapsed.
+        assert!(breaker.admit().now_or_never().is_none());
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(breaker.admit().await, Admission::Send { probe: true });
+        assert_eq!(circuit(&breaker), Circuit::HalfOpen);
+
+        // Other events wait for the outcome of the probe.
+        let mut waiting = Box::pin(breaker.admit());
+        assert!((&mut waiting).now_or_never().is_none());
+
+        // A failed probe reopens the circuit breaker, a delivered one closes it.
+        breaker.record(BatchStatus::Errored, true);
+        assert!(matches!(circuit(&breaker), Circuit::Open { .. }));
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(waiting.await, Admission::Send { probe: true });
+
+        breaker.record(BatchStatus::Delivered, true);
+        assert_eq!(circuit(&breaker), Circuit::Closed);
+        assert_eq!(breaker.admit().await, Admission::Send { probe: false });
+    }
+
+    #[tokio::test]
+    async fn diverts_while_open() {
+        tokio::time::pause();
+        let breaker = breaker(CircuitBreakerFallback::DeadLetter);
+        breaker.record(BatchStatus::Rejected, false);
+        breaker.record(BatchStatus::Rejected, false);
+
+        assert_eq!(breaker.admit().await, Admission::Divert);
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(breaker.admit().await, Admission::Send { probe: true });
+        assert_eq!(breaker.admit().await, Admission::Divert);
+    }
+
+    #[tokio::test]
+    async fn observes_status_of_events() {
+        let breaker = breaker(CircuitBreakerFallback::Pause);
+
+        for _ in 0..2 {
+            let mut events = events();
+            breaker.observe(&mut events, false);
+            events.for_each_event(|event| event.metadata().update_status(EventStatus::Errored));
+            drop(events);
+            tokio::task::yield_now().await;
+        }
+        tokio::task::yield_now().await;
+
+        assert!(matches!(circuit(&breaker), Circuit::Open { .. }));
+    }
+}
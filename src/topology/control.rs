@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+
+use crate::{
+    config::ComponentKey,
+    internal_events::{ComponentEventsThrottled, ComponentPaused},
+};
+
+type Limiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// The controls of the components, set through the API. They are kept across reloads so a
+/// component that is rebuilt stays paused or throttled.
+static CONTROLS: Lazy<Mutex<HashMap<ComponentKey, Arc<ComponentControl>>>> =
+    Lazy::new(Default::default);
+
+/// Pauses or throttles the events going through a component at runtime, without changing its
+/// configuration.
+pub struct ComponentControl {
+    paused_tx: watch::Sender<bool>,
+    paused_rx: watch::Receiver<bool>,
+    throttle: Mutex<Option<Throttle>>,
+}
+
+struct Throttle {
+    max_events_per_sec: NonZeroU32,
+    limiter: Arc<Limiter>,
+    expires_at: Instant,
+}
+
+impl ComponentControl {
+    /// Returns the controls of a component.
+    pub fn get(key: &ComponentKey) -> Arc<Self> {
+        let mut controls = CONTROLS.lock().expect("poisoned lock");
+        let control = controls.entry(key.clone()).or_insert_with(|| {
+            let (paused_tx, paused_rx) = watch::channel(false);
+            Arc::new(Self {
+                paused_tx,
+                paused_rx,
+                throttle: Mutex::new(None),
+            })
+        });
+        Arc::clone(control)
+    }
+
+    /// Stops events from going through the component until it's resumed.
+    pub fn pause(&self) {
+        let _ = self.paused_tx.send(true);
+    }
+
+    /// Lets events go through the component again.
+    pub fn resume(&self) {
+        let _ = self.paused_tx.send(false);
+    }
+
+    /// Whether events are stopped from going through the component.
+    pub fn is_paused(&self) -> bool {
+        *self.paused_rx.borrow()
+    }
+
+    /// Limits the number of events per second going through the component, for the given
+    /// duration.
+    pub fn throttle(&self, max_events_per_sec: NonZeroU32, duration: Duration) {
+        *self.throttle.lock().expect("poisoned lock") = Some(Throttle {
+            max_events_per_sec,
+            limiter: Arc::new(RateLimiter::direct(Quota::per_second(max_events_per_sec))),
+            expires_at: Instant::now() + duration,
+        });
+    }
+
+    /// Removes the limit on the number of events per second going through the component.
+    pub fn unthrottle(&self) {
+        *self.throttle.lock().expect("poisoned lock") = None;
+    }
+
+    /// Returns the limit on the number of events per second going through the component, unless
+    /// there is none or it expired.
+    fn current_throttle(&self) -> Option<(NonZeroU32, Arc<Limiter>)> {
+        let mut throttle = self.throttle.lock().expect("poisoned lock");
+        match &*throttle {
+            Some(current) if current.expires_at > Instant::now() => {
+                Some((current.max_events_per_sec, Arc::clone(&current.limiter)))
+            }
+            Some(_) => {
+                *throttle = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Waits until `count` more events can go through the component.
+    pub(super) async fn until_ready(&self, count: usize) {
+        if self.is_paused() {
+            emit!(ComponentPaused);
+            let mut paused_rx = self.paused_rx.clone();
+            while *paused_rx.borrow_and_update() {
+                // The sender is never dropped, as it's owned by `self`.
+                let _ = paused_rx.changed().await;
+            }
+        }
+
+        if let Some((max_events_per_sec, limiter)) = self.current_throttle() {
+            let mut remaining = count;
+            while remaining > 0 {
+                // The limiter can't let more events through at once than its quota.
+                let n = remaining.min(max_events_per_sec.get() as usize);
+                remaining -= n;
+                let n = NonZeroU32::new(n as u32).expect("n is never zero");
+                if limiter.check_n(n).is_err() {
+                    emit!(ComponentEventsThrottled {
+                        count: n.get() as usize
+                    });
+                    // This can only fail if `n` exceeds the quota.
+                    let _ = limiter.until_n_ready(n).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pauses_until_resumed() {
+        let control = ComponentControl::get(&ComponentKey::from("pauses_until_resumed"));
+        control.pause();
+        assert!(ComponentControl::get(&ComponentKey::from("pauses_until_resumed")).is_paused());
+
+        let mut waiting = tokio::spawn({
+            let control = Arc::clone(&control);
+            async move { control.until_ready(1).await }
+        });
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut waiting)
+                .await
+                .is_err()
+        );
+
+        control.resume();
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("should resume")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn throttles_until_expired() {
+        let control = ComponentControl::get(&ComponentKey::from("throttles_until_expired"));
+        control.throttle(NonZeroU32::new(100).unwrap(), Duration::from_secs(2));
+
+        // The first 100 events are let through right away, and the next 50 within half a second.
+        let start = Instant::now();
+        control.until_ready(150).await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+
+        control.throttle(NonZeroU32::new(100).unwrap(), Duration::ZERO);
+        let start = Instant::now();
+        control.until_ready(1000).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use futures::{future, stream::FuturesUnordered, StreamExt};
+use tokio::sync::mpsc;
+
+use super::fanout::{self, Fanout};
+use crate::{
+    config::ComponentKey,
+    event::{
+        array::events_into_arrays, BatchNotifier, BatchStatus, BatchStatusReceiver, Event,
+        EventArray, EventContainer, EventFinalizer, EventStatus, Value,
+    },
+    internal_events::SinkEventsDeadLettered,
+};
+
+/// The events handed to a sink, along with the receivers of their statuses once it finalizes
+/// them.
+struct Pending {
+    originals: EventArray,
+    statuses: Vec<BatchStatusReceiver>,
+}
+
+/// Hands copies of its events to a sink with a `dead_letter` component, keeping the originals,
+/// which hold the finalizers of their sources, until the sink finalizes the copies.
+#[derive(Clone)]
+pub(super) struct DeadLetterTracker {
+    pending_tx: mpsc::UnboundedSender<Pending>,
+    diverted_tx: mpsc::UnboundedSender<EventArray>,
+}
+
+/// Routes the events a sink failed to deliver to the output feeding its `dead_letter` component.
+pub(super) struct DeadLetterForwarder {
+    key: ComponentKey,
+    sink_type: &'static str,
+    pending_rx: mpsc::UnboundedReceiver<Pending>,
+    diverted_rx: mpsc::UnboundedReceiver<EventArray>,
+    fanout: Fanout,
+}
+
+pub(super) fn new(
+    key: &ComponentKey,
+    sink_type: &'static str,
+) -> (
+    DeadLetterTracker,
+    DeadLetterForwarder,
+    fanout::ControlChannel,
+) {
+    let (pending_tx, pending_rx) = mpsc::unbounded_channel();
+    let (diverted_tx, diverted_rx) = mpsc::unbounded_channel();
+    let (fanout, control) = Fanout::new();
+    (
+        DeadLetterTracker {
+            pending_tx,
+            diverted_tx,
+        },
+        DeadLetterForwarder {
+            key: key.clone(),
+            sink_type,
+            pending_rx,
+            diverted_rx,
+            fanout,
+        },
+        control,
+    )
+}
+
+impl DeadLetterTracker {
+    /// Returns the copies of the events to hand to the sink, which report their own status.
+    pub(super) fn track(&self, mut events: EventArray) -> EventArray {
+        let originals = events.clone();
+        let mut statuses = Vec::with_capacity(events.len());
+        events.for_each_event(|mut event| {
+            // The finalizers of the sources stay with the originals.
+            drop(event.metadata_mut().take_finalizers());
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(batch));
+            statuses.push(receiver);
+        });
+
+        // The forwarder only stops once this tracker is dropped.
+        let _ = self.pending_tx.send(Pending {
+            originals,
+            statuses,
+        });
+        events
+    }
+
+    /// Routes the events straight to the dead letter component, bypassing the sink while its
+    /// circuit breaker is open.
+    pub(super) fn divert(&self, events: EventArray) {
+        // As with `track`, the forwarder only stops once this tracker is dropped.
+        let _ = self.diverted_tx.send(events);
+    }
+}
+
+impl DeadLetterForwarder {
+    /// Waits for the events handed to the sink to be finalized, until the sink is done with them
+    /// all.
+    pub(super) async fn run(mut self) {
+        let mut in_flight = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                Some(pending) = self.pending_rx.recv() => {
+                    in_flight.push(finalized(pending));
+                },
+                Some(diverted) = self.diverted_rx.recv() => {
+                    self.route(diverted.into_events().collect(), "circuit_open").await;
+                },
+                Some(failed) = in_flight.next() => {
+                    self.forward(failed).await;
+                },
+                else => break,
+            }
+        }
+    }
+
+    async fn forward(&mut self, failed: Vec<(Event, BatchStatus)>) {
+        let (errored, rejected): (Vec<_>, Vec<_>) = failed
+            .into_iter()
+            .partition(|(_, status)| *status == BatchStatus::Errored);
+
+        for (reason, events) in [("errored", errored), ("rejected", rejected)] {
+            if !events.is_empty() {
+                let events = events.into_iter().map(|(event, _)| event).collect();
+                self.route(events, reason).await;
+            }
+        }
+    }
+
+    async fn route(&mut self, events: Vec<Event>, reason: &'static str) {
+        emit!(SinkEventsDeadLettered {
+            count: events.len(),
+            reason,
+        });
+        let events = events
+            .into_iter()
+            .map(|event| with_context(event, &self.key, self.sink_type, reason));
+        for array in events_into_arrays(events, None) {
+            self.fanout.send(array).await;
+        }
+    }
+}
+
+/// Waits for the sink to finalize the copies of the given events, returning the originals it
+/// failed to deliver. The others are acknowledged to their sources.
+async fn finalized(pending: Pending) -> Vec<(Event, BatchStatus)> {
+    let statuses = future::join_all(pending.statuses).await;
+    pending
+        .originals
+        .into_events()
+        .zip(statuses)
+        .filter_map(|(event, status)| match status {
+            BatchStatus::Delivered => {
+                event.metadata().update_status(EventStatus::Delivered);
+                None
+            }
+            BatchStatus::Errored | BatchStatus::Rejected => Some((event, status)),
+        })
+        .collect()
+}
+
+/// Attaches why the sink failed to deliver the event: to logs and traces as a `dead_letter`
+/// field, and to metrics as tags.
+fn with_context(mut event: Event, key: &ComponentKey, sink_type: &str, reason: &str) -> Event {
+    match &mut event {
+        Event::Log(log) => {
+            log.insert("dead_letter", context(key, sink_type, reason));
+        }
+        Event::Trace(trace) => {
+            trace.insert("dead_letter", context(key, sink_type, reason));
+        }
+        Event::Metric(metric) => {
+            metric.insert_tag("dead_letter_component_id".to_owned(), key.to_string());
+            metric.insert_tag(
+                "dead_letter_component_type".to_owned(),
+                sink_type.to_owned(),
+            );
+            metric.insert_tag("dead_letter_reason".to_owned(), reason.to_owned());
+        }
+    }
+    event
+}
+
+fn context(key: &ComponentKey, sink_type: &str, reason: &str) -> Value {
+    let mut context = BTreeMap::new();
+    context.insert("component_id".to_owned(), Value::from(key.to_string()));
+    context.insert("component_type".to_owned(), Value::from(sink_type));
+    context.insert("reason".to_owned(), Value::from(reason));
+    context.insert("timestamp".to_owned(), Value::from(Utc::now()));
+    Value::Object(context)
+}
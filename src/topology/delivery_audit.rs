@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+
+use crate::{
+    config::{ComponentKey, DeliveryAuditConfig},
+    event::{BatchNotifier, BatchStatus, EventArray, EventContainer, EventFinalizer},
+};
+
+/// One out of every `SAMPLE_RATE` events coming out of the sources is audited, unless it's zero,
+/// in which case auditing is disabled.
+static SAMPLE_RATE: AtomicU64 = AtomicU64::new(0);
+static SEEN: AtomicU64 = AtomicU64::new(0);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static AUDIT: Lazy<Mutex<Audit>> = Lazy::new(Default::default);
+
+/// The path through the topology of an audited event.
+#[derive(Clone, Debug)]
+pub struct DeliveryRecord {
+    pub id: u64,
+    /// The components the event went through, starting with its source.
+    pub hops: Vec<Hop>,
+    /// The final status of the event, once it has been finalized by all the sinks it went to.
+    pub status: Option<BatchStatus>,
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+/// An audited event going through a component.
+#[derive(Clone, Debug)]
+pub struct Hop {
+    pub component_key: ComponentKey,
+    pub component_kind: &'static str,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The most recent delivery records, oldest first as identifiers only ever increase.
+#[derive(Default)]
+struct Audit {
+    max_records: usize,
+    records: BTreeMap<u64, DeliveryRecord>,
+}
+
+impl Audit {
+    fn start(&mut self, id: u64, hop: Hop) {
+        self.records.insert(
+            id,
+            DeliveryRecord {
+                id,
+                hops: vec![hop],
+                status: None,
+                finalized_at: None,
+            },
+        );
+        self.evict();
+    }
+
+    fn add_hop(&mut self, id: u64, hop: Hop) {
+        if let Some(record) = self.records.get_mut(&id) {
+            record.hops.push(hop);
+        }
+    }
+
+    fn finalize(&mut self, id: u64, status: BatchStatus) {
+        if let Some(record) = self.records.get_mut(&id) {
+            record.status = Some(status);
+            record.finalized_at = Some(Utc::now());
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.records.len() > self.max_records {
+            let oldest = *self.records.keys().next().expect("records aren't empty");
+            self.records.remove(&oldest);
+        }
+    }
+}
+
+/// Starts, reconfigures or stops auditing the delivery of events. Records are discarded once
+/// auditing is stopped.
+pub fn configure(config: Option<&DeliveryAuditConfig>) {
+    let mut audit = AUDIT.lock().expect("poisoned lock");
+    match config {
+        Some(config) => {
+            audit.max_records = config.max_records;
+            audit.evict();
+            SAMPLE_RATE.store(config.sample_rate, Ordering::Relaxed);
+        }
+        None => {
+            SAMPLE_RATE.store(0, Ordering::Relaxed);
+            audit.records.clear();
+        }
+    }
+}
+
+/// Returns the delivery records kept, most recent first.
+pub fn records() -> Vec<DeliveryRecord> {
+    let audit = AUDIT.lock().expect("poisoned lock");
+    audit.records.values().rev().cloned().collect()
+}
+
+/// Returns the delivery record with the given identifier, if it's still kept.
+pub fn record(id: u64) -> Option<DeliveryRecord> {
+    let audit = AUDIT.lock().expect("poisoned lock");
+    audit.records.get(&id).cloned()
+}
+
+/// Samples the events coming out of a source to be audited. Sampled events are given a finalizer
+/// of their own, so that their final status is recorded once all the sinks they went to are done
+/// with them.
+///
+/// Must be called from within a Tokio runtime.
+pub(super) fn sample(key: &ComponentKey, events: &mut EventArray) {
+    let sample_rate = SAMPLE_RATE.load(Ordering::Relaxed);
+    if sample_rate == 0 {
+        return;
+    }
+
+    events.for_each_event(|mut event| {
+        if SEEN.fetch_add(1, Ordering::Relaxed) % sample_rate != 0
+            || event.metadata().audit_id().is_some()
+        {
+            return;
+        }
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        event.metadata_mut().set_audit_id(id);
+        event
+            .metadata_mut()
+            .add_finalizer(EventFinalizer::new(batch));
+
+        AUDIT
+            .lock()
+            .expect("poisoned lock")
+            .start(id, hop(key, "source"));
+        tokio::spawn(async move {
+            let status = receiver.await;
+            AUDIT.lock().expect("poisoned lock").finalize(id, status);
+        });
+    });
+}
+
+/// Records audited events going through a transform or sink.
+pub(super) fn record_hops(key: &ComponentKey, component_kind: &'static str, events: &EventArray) {
+    if SAMPLE_RATE.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    let mut audit = None;
+    for id in events
+        .iter_events()
+        .filter_map(|event| event.metadata().audit_id())
+    {
+        audit
+            .get_or_insert_with(|| AUDIT.lock().expect("poisoned lock"))
+            .add_hop(id, hop(key, component_kind));
+    }
+}
+
+fn hop(key: &ComponentKey, component_kind: &'static str) -> Hop {
+    Hop {
+        component_key: key.clone(),
+        component_kind,
+        timestamp: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_hops_and_status() {
+        let mut audit = Audit {
+            max_records: 10,
+            ..Default::default()
+        };
+        audit.start(1, hop(&ComponentKey::from("in"), "source"));
+        audit.add_hop(1, hop(&ComponentKey::from("parse"), "transform"));
+        audit.add_hop(1, hop(&ComponentKey::from("out"), "sink"));
+        audit.add_hop(2, hop(&ComponentKey::from("out"), "sink"));
+        audit.finalize(1, BatchStatus::Rejected);
+
+        let record = &audit.records[&1];
+        let components = record
+            .hops
+            .iter()
+            .map(|hop| (hop.component_key.id(), hop.component_kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            components,
+            [("in", "source"), ("parse", "transform"), ("out", "sink")]
+        );
+        assert_eq!(record.status, Some(BatchStatus::Rejected));
+        assert!(record.finalized_at.unwrap() >= record.hops[0].timestamp);
+        assert!(!audit.records.contains_key(&2));
+    }
+
+    #[test]
+    fn evicts_oldest_records() {
+        let mut audit = Audit {
+            max_records: 2,
+            ..Default::default()
+        };
+        for id in 1..=3 {
+            audit.start(id, hop(&ComponentKey::from("in"), "source"));
+        }
+        assert_eq!(audit.records.keys().copied().collect::<Vec<_>>(), [2, 3]);
+
+        audit.max_records = 1;
+        audit.evict();
+        assert_eq!(audit.records.keys().copied().collect::<Vec<_>>(), [3]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::config::ComponentKey;
+
+/// The health of the running components, reported by the API. Entries are added as components
+/// are spawned, and removed along with them.
+static HEALTH: Lazy<Mutex<HashMap<ComponentKey, Entry>>> = Lazy::new(Default::default);
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+static STARTED_AT: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// The outcome of the healthcheck of a sink.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthcheckStatus {
+    /// The healthcheck hasn't completed yet.
+    Pending,
+    Passed,
+    Failed,
+    TimedOut,
+    /// Healthchecks are disabled, globally or for the sink.
+    Disabled,
+}
+
+/// The health of a component.
+#[derive(Clone, Debug)]
+pub struct ComponentHealth {
+    pub kind: &'static str,
+    /// Whether the task of the component is still running.
+    pub running: bool,
+    /// The outcome of the healthcheck of the component, for sinks.
+    pub healthcheck: Option<HealthcheckStatus>,
+    /// When the component last sent events, for sources.
+    pub activity: Arc<SourceActivity>,
+}
+
+struct Entry {
+    health: ComponentHealth,
+    /// Tells apart the successive tasks of a component that is rebuilt on reload, so the previous
+    /// task finishing doesn't mark the new one as stopped. Zero until the component is spawned.
+    generation: u64,
+}
+
+impl Entry {
+    fn new(kind: &'static str) -> Self {
+        Self {
+            health: ComponentHealth {
+                kind,
+                running: false,
+                healthcheck: None,
+                activity: Default::default(),
+            },
+            generation: 0,
+        }
+    }
+}
+
+/// Records when a source last sent events.
+#[derive(Debug, Default)]
+pub struct SourceActivity {
+    /// Milliseconds since the process started, plus one, or zero if no events were sent yet.
+    last_event: AtomicU64,
+}
+
+impl SourceActivity {
+    pub(super) fn record(&self) {
+        let elapsed = STARTED_AT.elapsed().as_millis() as u64 + 1;
+        self.last_event.store(elapsed, Ordering::Relaxed);
+    }
+
+    /// How long ago the source last sent events, if it ever did.
+    pub fn idle(&self) -> Option<Duration> {
+        match self.last_event.load(Ordering::Relaxed) {
+            0 => None,
+            last_event => Some(
+                STARTED_AT
+                    .elapsed()
+                    .saturating_sub(Duration::from_millis(last_event - 1)),
+            ),
+        }
+    }
+}
+
+/// Returns the activity of a source, kept across reloads like its health.
+pub(super) fn source_activity(key: &ComponentKey) -> Arc<SourceActivity> {
+    let mut health = HEALTH.lock().expect("poisoned lock");
+    let entry = health
+        .entry(key.clone())
+        .or_insert_with(|| Entry::new("source"));
+    Arc::clone(&entry.health.activity)
+}
+
+/// Marks the task of a component as running, and returns its generation, with which it's marked
+/// as stopped once it finishes.
+pub(super) fn spawned(key: &ComponentKey, kind: &'static str) -> u64 {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let mut health = HEALTH.lock().expect("poisoned lock");
+    let entry = health
+        .entry(key.clone())
+        .or_insert_with(|| Entry::new(kind));
+    entry.health.kind = kind;
+    entry.health.running = true;
+    entry.generation = generation;
+    generation
+}
+
+/// Marks the task of a component as stopped, unless it has been spawned again since.
+pub(super) fn stopped(key: &ComponentKey, generation: u64) {
+    let mut health = HEALTH.lock().expect("poisoned lock");
+    if let Some(entry) = health.get_mut(key) {
+        if entry.generation == generation {
+            entry.health.running = false;
+        }
+    }
+}
+
+/// Records the outcome of the healthcheck of a sink.
+pub(super) fn set_healthcheck(key: &ComponentKey, status: HealthcheckStatus) {
+    let mut health = HEALTH.lock().expect("poisoned lock");
+    health
+        .entry(key.clone())
+        .or_insert_with(|| Entry::new("sink"))
+        .health
+        .healthcheck = Some(status);
+}
+
+/// Forgets a component that has been removed from the topology.
+pub(super) fn remove(key: &ComponentKey) {
+    HEALTH.lock().expect("poisoned lock").remove(key);
+}
+
+/// Returns the health of the components that have been spawned.
+pub fn components() -> Vec<(ComponentKey, ComponentHealth)> {
+    let health = HEALTH.lock().expect("poisoned lock");
+    health
+        .iter()
+        .filter(|(_, entry)| entry.generation != 0)
+        .map(|(key, entry)| (key.clone(), entry.health.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previous_task_does_not_stop_respawned_component() {
+        let key = ComponentKey::from("health_respawned");
+        let previous = spawned(&key, "sink");
+        let current = spawned(&key, "sink");
+
+        stopped(&key, previous);
+        assert!(HEALTH.lock().unwrap()[&key].health.running);
+
+        stopped(&key, current);
+        assert!(!HEALTH.lock().unwrap()[&key].health.running);
+
+        remove(&key);
+        assert!(!HEALTH.lock().unwrap().contains_key(&key));
+    }
+
+    #[test]
+    fn records_source_activity() {
+        let activity = SourceActivity::default();
+        assert_eq!(activity.idle(), None);
+
+        activity.record();
+        assert!(activity.idle().unwrap() < Duration::from_secs(1));
+    }
+}
@@ -9,10 +9,17 @@
 pub(super) use vector_core::fanout;
 
 pub mod builder;
+mod circuit_breaker;
+pub mod control;
+mod dead_letter;
+pub mod delivery_audit;
+pub mod health;
+mod partitioned_buffer;
 mod ready_arrays;
 mod running;
 mod schema;
 mod task;
+mod tenant;
 
 #[cfg(test)]
 mod test;
@@ -32,7 +39,7 @@ use vector_buffers::{
 };
 
 use crate::{
-    config::{ComponentKey, Config, ConfigDiff, OutputId},
+    config::{ComponentKey, Config, ConfigDiff, MemoryBudgetConfig, OutputId},
     event::EventArray,
     topology::{
         builder::Pieces,
@@ -91,12 +98,24 @@ pub async fn start_validated(
     {
         return None;
     }
+    configure_memory_budget(&running_topology.config);
+    delivery_audit::configure(running_topology.config.delivery_audit.as_ref());
     running_topology.connect_diff(&diff, &mut pieces).await;
     running_topology.spawn_diff(&diff, pieces);
 
     Some((running_topology, abort_rx))
 }
 
+/// Sets the memory budget of the process, against which components reserve the memory they hold.
+fn configure_memory_budget(config: &Config) {
+    vector_common::memory_budget::configure(
+        config
+            .memory_budget
+            .as_ref()
+            .map(MemoryBudgetConfig::budget),
+    );
+}
+
 pub async fn build_or_log_errors(
     config: &Config,
     diff: &ConfigDiff,
@@ -137,15 +156,3 @@ async fn handle_errors(
             let _ = abort_tx.send(());
         })
 }
-
-/// If the closure returns false, then the element is removed
-fn retain<T>(vec: &mut Vec<T>, mut retain_filter: impl FnMut(&mut T) -> bool) {
-    let mut i = 0;
-    while let Some(data) = vec.get_mut(i) {
-        if retain_filter(data) {
-            i += 1;
-        } else {
-            let _ = vec.remove(i);
-        }
-    }
-}
@@ -0,0 +1,332 @@
+//! Disk buffers partitioned by a templated key, such as a tenant or a topic.
+//!
+//! Each partition is a disk buffer of its own, with its own `max_size` and reader progress, so the
+//! backlog of a slow partition doesn't delay the events of the other partitions behind it. The
+//! events sent to the sink are split into partitions by a task, and another task reads the
+//! partitions in turn, passing their events on to the sink. Acknowledgements of the sink are
+//! passed back to the partition each event was read from.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use futures::{stream::SelectAll, StreamExt};
+use indexmap::IndexMap;
+use tokio::{select, sync::mpsc};
+use tracing::{Instrument, Span};
+use vector_core::{
+    buffers::{
+        config::BufferBuildError,
+        topology::{
+            builder::TopologyBuilder,
+            channel::{BufferReceiver, BufferReceiverStream, BufferSender},
+        },
+        Acker, BufferConfig, BufferType, WhenFull,
+    },
+    event::EventRef,
+};
+
+use super::builder::TOPOLOGY_BUFFER_SIZE;
+use crate::{
+    event::{EventArray, EventContainer},
+    internal_events::{PartitionedBufferOpenError, TemplateRenderingError},
+    spawn_named,
+    template::Template,
+};
+
+/// The partition of events whose key can't be rendered.
+const DEFAULT_PARTITION: &str = "_default";
+
+/// Builds a disk buffer partitioned by `partition_by`, whose partitions are built from `stage`
+/// and kept in the `partitions/<id>` subdirectory of `data_dir`.
+///
+/// Partitions left over by a previous run are opened right away, so their events are delivered
+/// even if no more events are sent to them.
+pub(super) async fn build(
+    stage: &BufferType,
+    partition_by: Template,
+    data_dir: Option<PathBuf>,
+    id: String,
+    span: Span,
+) -> Result<(BufferSender<EventArray>, BufferReceiver<EventArray>, Acker), BufferBuildError> {
+    let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
+    let mut stage = stage.clone();
+    if let BufferType::DiskV2 { partition_by, .. } = &mut stage {
+        *partition_by = None;
+    }
+
+    let (readers_tx, readers_rx) = mpsc::unbounded_channel();
+    let mut partitions = Partitions {
+        config: BufferConfig {
+            stages: vec![stage],
+        },
+        dir: data_dir.join("partitions").join(&id),
+        id,
+        span: span.clone(),
+        senders: HashMap::new(),
+        readers: readers_tx,
+    };
+    if let Ok(entries) = std::fs::read_dir(&partitions.dir) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                partitions.open(name).await?;
+            }
+        }
+    }
+
+    let (tx, rx) = TopologyBuilder::standalone_memory(TOPOLOGY_BUFFER_SIZE, WhenFull::Block).await;
+    let (sink_tx, sink_rx) =
+        TopologyBuilder::standalone_memory(TOPOLOGY_BUFFER_SIZE, WhenFull::Block).await;
+    let pending = Arc::new(Mutex::new(VecDeque::new()));
+
+    spawn_named(
+        split(rx.into_stream(), partitions, partition_by).instrument(span.clone()),
+        "partitioned buffer writer",
+    );
+    spawn_named(
+        read(readers_rx, sink_tx, Arc::clone(&pending)).instrument(span),
+        "partitioned buffer reader",
+    );
+
+    let acker = Acker::segmented(move |amount| ack(&pending, amount));
+    Ok((tx, sink_rx, acker))
+}
+
+/// A partition to be read, along with the acker of its disk buffer.
+struct Reader {
+    stream: BufferReceiverStream<EventArray>,
+    acker: Acker,
+}
+
+/// The partitions of a buffer, which are built when events are first sent to them.
+struct Partitions {
+    config: BufferConfig,
+    dir: PathBuf,
+    id: String,
+    span: Span,
+    senders: HashMap<String, BufferSender<EventArray>>,
+    readers: mpsc::UnboundedSender<Reader>,
+}
+
+impl Partitions {
+    /// Returns the sender of the partition `name`, building the partition if needed.
+    async fn open(
+        &mut self,
+        name: String,
+    ) -> Result<&mut BufferSender<EventArray>, BufferBuildError> {
+        if !self.senders.contains_key(&name) {
+            let span = error_span!(parent: &self.span, "partition", partition = %name);
+            let (tx, rx, acker) = self
+                .config
+                .build(Some(self.dir.join(&name)), self.id.clone(), span)
+                .await?;
+            // The reader only goes away along with the sink.
+            let _ = self.readers.send(Reader {
+                stream: rx.into_stream(),
+                acker,
+            });
+            self.senders.insert(name.clone(), tx);
+        }
+        Ok(self
+            .senders
+            .get_mut(&name)
+            .expect("partition was just built"))
+    }
+}
+
+/// Sends the events of `rx` to their partition.
+async fn split(
+    mut rx: BufferReceiverStream<EventArray>,
+    mut partitions: Partitions,
+    partition_by: Template,
+) {
+    while let Some(array) = rx.next().await {
+        for (name, array) in partition(array, &partition_by) {
+            match partitions.open(name.clone()).await {
+                Ok(tx) => {
+                    if tx.send(array).await.is_err() {
+                        return;
+                    }
+                }
+                Err(error) => emit!(PartitionedBufferOpenError {
+                    error,
+                    partition: &name,
+                    count: array.len(),
+                }),
+            }
+        }
+    }
+}
+
+/// Reads the partitions in turn, sending their events to the sink through `tx`, and recording
+/// which partition they came from in `pending`.
+async fn read(
+    mut readers: mpsc::UnboundedReceiver<Reader>,
+    mut tx: BufferSender<EventArray>,
+    pending: Arc<Mutex<VecDeque<(Acker, usize)>>>,
+) {
+    let mut streams = SelectAll::new();
+    loop {
+        let (acker, array) = select! {
+            Some(reader) = readers.recv() => {
+                let acker = reader.acker;
+                streams.push(reader.stream.map(move |array| (acker.clone(), array)));
+                continue;
+            }
+            Some(next) = streams.next(), if !streams.is_empty() => next,
+            else => break,
+        };
+        if !array.is_empty() {
+            pending
+                .lock()
+                .expect("poisoned lock")
+                .push_back((acker, array.len()));
+        }
+        if tx.send(array).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Acknowledges `amount` events read by the sink, which are the oldest pending ones, to their
+/// partitions.
+fn ack(pending: &Mutex<VecDeque<(Acker, usize)>>, mut amount: usize) {
+    let mut pending = pending.lock().expect("poisoned lock");
+    while amount > 0 {
+        let done = match pending.front_mut() {
+            Some((acker, count)) => {
+                let acked = amount.min(*count);
+                acker.ack(acked);
+                *count -= acked;
+                amount -= acked;
+                *count == 0
+            }
+            None => break,
+        };
+        if done {
+            pending.pop_front();
+        }
+    }
+}
+
+/// Splits events into the partitions given by their key, keeping their order within each one.
+fn partition(array: EventArray, partition_by: &Template) -> IndexMap<String, EventArray> {
+    match array {
+        EventArray::Logs(logs) => group(logs, partition_by)
+            .into_iter()
+            .map(|(name, logs)| (name, EventArray::Logs(logs)))
+            .collect(),
+        EventArray::Metrics(metrics) => group(metrics, partition_by)
+            .into_iter()
+            .map(|(name, metrics)| (name, EventArray::Metrics(metrics)))
+            .collect(),
+        EventArray::Traces(traces) => group(traces, partition_by)
+            .into_iter()
+            .map(|(name, traces)| (name, EventArray::Traces(traces)))
+            .collect(),
+    }
+}
+
+fn group<T>(events: Vec<T>, partition_by: &Template) -> IndexMap<String, Vec<T>>
+where
+    for<'a> &'a T: Into<EventRef<'a>>,
+{
+    let mut groups = IndexMap::<String, Vec<T>>::new();
+    for event in events {
+        let name = match partition_by.render_string(&event) {
+            Ok(key) => partition_name(&key),
+            Err(error) => {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("partition_by"),
+                    drop_event: false,
+                });
+                DEFAULT_PARTITION.to_owned()
+            }
+        };
+        groups.entry(name).or_default().push(event);
+    }
+    groups
+}
+
+/// Returns the name of the partition of a key, which is also the name of its directory.
+fn partition_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .take(200)
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        DEFAULT_PARTITION.to_owned()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn log(tenant: &str, message: &str) -> LogEvent {
+        let mut log = LogEvent::from(message);
+        log.insert("tenant", tenant);
+        log
+    }
+
+    #[test]
+    fn partitions_events() {
+        let template = Template::try_from("{{ tenant }}").unwrap();
+        let array = EventArray::Logs(vec![
+            log("a", "1"),
+            log("b/c", "2"),
+            log("a", "3"),
+            LogEvent::from("4"),
+        ]);
+
+        let partitions = partition(array, &template);
+        let names: Vec<_> = partitions.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["a", "b_c", DEFAULT_PARTITION]);
+        assert_eq!(partitions["a"].len(), 2);
+    }
+
+    #[test]
+    fn names_partitions() {
+        assert_eq!(partition_name("team-a_1.prod"), "team-a_1.prod");
+        assert_eq!(partition_name("../etc"), ".._etc");
+        assert_eq!(partition_name(".."), DEFAULT_PARTITION);
+        assert_eq!(partition_name(""), DEFAULT_PARTITION);
+    }
+
+    #[test]
+    fn acks_partitions_in_order() {
+        let counter = |acked: &Arc<AtomicUsize>| {
+            let acked = Arc::clone(acked);
+            Acker::segmented(move |amount| {
+                acked.fetch_add(amount, Ordering::SeqCst);
+            })
+        };
+        let (a, b) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let pending = Mutex::new(VecDeque::from(vec![
+            (counter(&a), 2),
+            (counter(&b), 3),
+            (counter(&a), 1),
+        ]));
+
+        ack(&pending, 3);
+        assert_eq!(a.load(Ordering::SeqCst), 2);
+        assert_eq!(b.load(Ordering::SeqCst), 1);
+        ack(&pending, 3);
+        assert_eq!(a.load(Ordering::SeqCst), 3);
+        assert_eq!(b.load(Ordering::SeqCst), 3);
+        assert!(pending.lock().unwrap().is_empty());
+    }
+}
@@ -6,10 +6,10 @@ use std::{
     },
 };
 
-use futures::{future, Future, FutureExt};
+use futures::{future, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use tokio::{
     sync::{mpsc, watch},
-    time::{interval, sleep_until, Duration, Instant},
+    time::{interval_at, timeout_at, Duration, Instant},
 };
 use tracing::Instrument;
 use vector_buffers::topology::channel::BufferSender;
@@ -24,13 +24,19 @@ use crate::{
     topology::{
         build_or_log_errors, builder,
         builder::Pieces,
+        configure_memory_budget, delivery_audit,
         fanout::{ControlChannel, ControlMessage},
-        handle_errors, retain, take_healthchecks,
+        handle_errors,
+        health::{self, HealthcheckStatus},
+        take_healthchecks,
         task::TaskOutput,
         BuiltBuffer, TaskHandle, WatchRx, WatchTx,
     },
 };
 
+/// How often the components still running on shutdown are reported.
+const SHUTDOWN_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
 #[allow(dead_code)]
 pub struct RunningTopology {
     inputs: HashMap<ComponentKey, BufferSender<EventArray>>,
@@ -91,6 +97,12 @@ impl RunningTopology {
     /// transforms, and sinks) have finished shutting down. Transforms and sinks
     /// will shut down automatically once their input tasks finish.
     ///
+    /// Components are drained in order: sources are stopped first, then
+    /// transforms are given time to flush their state, then sinks are given
+    /// time to flush their buffers. Each component has its own deadline, given
+    /// by its `shutdown_timeout_secs` option or else by the `shutdown` options,
+    /// counted from the start of its phase, after which it's killed.
+    ///
     /// This function takes ownership of `self`, so once it returns everything
     /// in the [`RunningTopology`] instance has been dropped except for the
     /// `tasks` map. This map gets moved into the returned future and is used to
@@ -100,92 +112,72 @@ impl RunningTopology {
     pub fn stop(self) -> impl Future<Output = ()> {
         // Update the API's health endpoint to signal shutdown
         self.running.store(false, Ordering::Relaxed);
-        // Create handy handles collections of all tasks for the subsequent
-        // operations.
-        let mut wait_handles = Vec::new();
-        // We need a Vec here since source components have two tasks. One for
-        // pump in self.tasks, and the other for source in self.source_tasks.
-        let mut check_handles = HashMap::<ComponentKey, Vec<_>>::new();
-
-        // We need to give some time to the sources to gracefully shutdown, so
-        // we will merge them with other tasks.
-        for (key, task) in self.tasks.into_iter().chain(self.source_tasks.into_iter()) {
-            let task = task.map(|_result| ()).shared();
-
-            wait_handles.push(task.clone());
-            check_handles.entry(key).or_default().push(task);
-        }
-
-        // If we reach this, we will forcefully shutdown the sources.
-        let deadline = Instant::now() + Duration::from_secs(60);
-
-        // If we reach the deadline, this future will print out which components
-        // won't gracefully shutdown since we will start to forcefully shutdown
-        // the sources.
-        let mut check_handles2 = check_handles.clone();
-        let timeout = async move {
-            sleep_until(deadline).await;
-            // Remove all tasks that have shutdown.
-            check_handles2.retain(|_key, handles| {
-                retain(handles, |handle| handle.peek().is_none());
-                !handles.is_empty()
-            });
-            let remaining_components = check_handles2
-                .keys()
-                .map(|item| item.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
 
-            error!(
-                components = ?remaining_components,
-                "Failed to gracefully shut down in time. Killing components."
-            );
+        let config = self.config;
+        let options = config.shutdown;
+        let timeout_of = |default_secs: u64, secs: Option<u64>| {
+            Duration::from_secs(secs.unwrap_or(default_secs))
         };
 
-        // Reports in intervals which components are still running.
-        let mut interval = interval(Duration::from_secs(5));
-        let reporter = async move {
-            loop {
-                interval.tick().await;
+        // Sort the tasks by phase. Sources have two tasks, the source itself
+        // in `source_tasks` and its pump in `tasks`.
+        let mut sources = HashMap::<ComponentKey, (Vec<TaskHandle>, Duration)>::new();
+        let mut transforms = HashMap::new();
+        let mut sinks = HashMap::new();
+        for (key, task) in self.source_tasks.into_iter().chain(self.tasks.into_iter()) {
+            let (phase, timeout) = if let Some(transform) = config.transform(&key) {
+                (
+                    &mut transforms,
+                    timeout_of(
+                        options.transforms_timeout_secs,
+                        transform.shutdown_timeout_secs,
+                    ),
+                )
+            } else if let Some(sink) = config.sink(&key) {
+                (
+                    &mut sinks,
+                    timeout_of(options.sinks_timeout_secs, sink.shutdown_timeout_secs),
+                )
+            } else {
+                (
+                    &mut sources,
+                    timeout_of(
+                        options.sources_timeout_secs,
+                        config
+                            .source(&key)
+                            .and_then(|source| source.shutdown_timeout_secs),
+                    ),
+                )
+            };
+            phase
+                .entry(key)
+                .or_insert_with(|| (Vec::new(), timeout))
+                .0
+                .push(task);
+        }
 
-                // Remove all tasks that have shutdown.
-                check_handles.retain(|_key, handles| {
-                    retain(handles, |handle| handle.peek().is_none());
-                    !handles.is_empty()
+        // Kick off the shutdown process by shutting down the sources, which
+        // are forced to once their deadline is reached.
+        let start = Instant::now();
+        let source_deadlines = sources
+            .iter()
+            .map(|(key, (_, timeout))| (key.clone(), start + *timeout))
+            .collect::<HashMap<_, _>>();
+        let source_shutdown_complete =
+            self.shutdown_coordinator
+                .shutdown_all_with_deadlines(move |key| {
+                    source_deadlines
+                        .get(key)
+                        .copied()
+                        .unwrap_or(start + Duration::from_secs(options.sources_timeout_secs))
                 });
-                let remaining_components = check_handles
-                    .keys()
-                    .map(|item| item.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                let time_remaining = match deadline.checked_duration_since(Instant::now()) {
-                    Some(remaining) => format!("{} seconds left", remaining.as_secs()),
-                    None => "overdue".to_string(),
-                };
 
-                info!(
-                    remaining_components = ?remaining_components,
-                    time_remaining = ?time_remaining,
-                    "Shutting down... Waiting on running components."
-                );
-            }
-        };
-
-        // Finishes once all tasks have shutdown.
-        let success = futures::future::join_all(wait_handles).map(|_| ());
-
-        // Aggregate future that ends once anything detects that all tasks have shutdown.
-        let shutdown_complete_future = future::select_all(vec![
-            Box::pin(timeout) as future::BoxFuture<'static, ()>,
-            Box::pin(reporter) as future::BoxFuture<'static, ()>,
-            Box::pin(success) as future::BoxFuture<'static, ()>,
-        ]);
-
-        // Now kick off the shutdown process by shutting down the sources.
-        let source_shutdown_complete = self.shutdown_coordinator.shutdown_all(deadline);
-
-        futures::future::join(source_shutdown_complete, shutdown_complete_future).map(|_| ())
+        async move {
+            futures::future::join(source_shutdown_complete, drain("sources", sources)).await;
+            drain("transforms", transforms).await;
+            drain("sinks", sinks).await;
+            info!("All components have shut down.");
+        }
     }
 
     /// Attempts to load a new configuration and update this running topology.
@@ -242,6 +234,8 @@ impl RunningTopology {
                 .run_healthchecks(&diff, &mut new_pieces, new_config.healthchecks)
                 .await
             {
+                configure_memory_budget(&new_config);
+                delivery_audit::configure(new_config.delivery_audit.as_ref());
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
                 self.config = new_config;
@@ -286,7 +280,10 @@ impl RunningTopology {
         if options.enabled {
             let healthchecks = take_healthchecks(diff, pieces)
                 .into_iter()
-                .map(|(_, task)| task);
+                .map(|(key, task)| {
+                    health::set_healthcheck(&key, HealthcheckStatus::Pending);
+                    task
+                });
             let healthchecks = future::try_join_all(healthchecks);
 
             info!("Running healthchecks.");
@@ -305,6 +302,9 @@ impl RunningTopology {
                 true
             }
         } else {
+            for key in diff.sinks.changed_and_added() {
+                health::set_healthcheck(key, HealthcheckStatus::Disabled);
+            }
             true
         }
     }
@@ -329,6 +329,7 @@ impl RunningTopology {
 
                 let previous = self.tasks.remove(key).unwrap();
                 drop(previous); // detach and forget
+                health::remove(key);
 
                 self.remove_outputs(key);
                 source_shutdown_handles
@@ -369,6 +370,7 @@ impl RunningTopology {
 
             let previous = self.tasks.remove(key).unwrap();
             drop(previous); // detach and forget
+            health::remove(key);
 
             self.remove_inputs(key, diff).await;
             self.remove_outputs(key);
@@ -436,6 +438,7 @@ impl RunningTopology {
         for key in &diff.sinks.to_remove {
             debug!(component = %key, "Removing sink.");
             self.remove_inputs(key, diff).await;
+            self.remove_outputs(key);
         }
 
         // After that, for any changed sinks, we temporarily detach their inputs (not remove) so
@@ -464,6 +467,7 @@ impl RunningTopology {
                 buffer_tx.insert(key.clone(), self.inputs.get(key).unwrap().clone());
             }
             self.remove_inputs(key, diff).await;
+            self.remove_outputs(key);
         }
 
         // Now that we've disconnected or temporarily detached the inputs to all changed/removed
@@ -480,6 +484,7 @@ impl RunningTopology {
             } else {
                 drop(previous); // detach and forget
             }
+            health::remove(key);
         }
 
         let mut buffers = HashMap::<ComponentKey, BuiltBuffer>::new();
@@ -547,6 +552,15 @@ impl RunningTopology {
             self.setup_outputs(key, new_pieces).await;
         }
 
+        // The only outputs of sinks are the dead letter outputs of those having a dead letter
+        // component, which can be the input of any transform or sink.
+        for key in diff.sinks.changed_and_added() {
+            if new_pieces.outputs.contains_key(key) {
+                debug!(component = %key, "Configuring dead letter output for sink.");
+                self.setup_outputs(key, new_pieces).await;
+            }
+        }
+
         // Now that all possible outputs are configured, we can start wiring up inputs, starting
         // with transforms.
         for key in diff.transforms.changed_and_added() {
@@ -785,9 +799,14 @@ impl RunningTopology {
             component_type = %task.typetag(),
             // maintained for compatibility
             component_name = %task.id(),
+            tenant = tracing::field::Empty,
         );
+        if let Some(tenant) = new_pieces.tenants.get(key) {
+            span.record("tenant", &tenant.as_str());
+        }
         let task_name = format!(">> {} ({})", task.typetag(), task.id());
-        let task = handle_errors(task, self.abort_tx.clone()).instrument(span.or_current());
+        let task = track_health(key, "sink", handle_errors(task, self.abort_tx.clone()))
+            .instrument(span.or_current());
         let spawned = spawn_named(task, task_name.as_ref());
         if let Some(previous) = self.tasks.insert(key.clone(), spawned) {
             drop(previous); // detach and forget
@@ -803,9 +822,14 @@ impl RunningTopology {
             component_type = %task.typetag(),
             // maintained for compatibility
             component_name = %task.id(),
+            tenant = tracing::field::Empty,
         );
+        if let Some(tenant) = new_pieces.tenants.get(key) {
+            span.record("tenant", &tenant.as_str());
+        }
         let task_name = format!(">> {} ({}) >>", task.typetag(), task.id());
-        let task = handle_errors(task, self.abort_tx.clone()).instrument(span.or_current());
+        let task = track_health(key, "transform", handle_errors(task, self.abort_tx.clone()))
+            .instrument(span.or_current());
         let spawned = spawn_named(task, task_name.as_ref());
         if let Some(previous) = self.tasks.insert(key.clone(), spawned) {
             drop(previous); // detach and forget
@@ -821,7 +845,11 @@ impl RunningTopology {
             component_type = %task.typetag(),
             // maintained for compatibility
             component_name = %task.id(),
+            tenant = tracing::field::Empty,
         );
+        if let Some(tenant) = new_pieces.tenants.get(key) {
+            span.record("tenant", &tenant.as_str());
+        }
         let task_name = format!("{} ({}) >>", task.typetag(), task.id());
         let task = handle_errors(task, self.abort_tx.clone()).instrument(span.clone().or_current());
         let spawned = spawn_named(task, task_name.as_ref());
@@ -833,13 +861,28 @@ impl RunningTopology {
             .takeover_source(key, &mut new_pieces.shutdown_coordinator);
 
         let source_task = new_pieces.source_tasks.remove(key).unwrap();
-        let source_task =
-            handle_errors(source_task, self.abort_tx.clone()).instrument(span.or_current());
+        let source_task = track_health(
+            key,
+            "source",
+            handle_errors(source_task, self.abort_tx.clone()),
+        )
+        .instrument(span.or_current());
         self.source_tasks
             .insert(key.clone(), spawn_named(source_task, task_name.as_ref()));
     }
 }
 
+/// Marks the component as running until its task finishes, for the health endpoints of the API.
+fn track_health<F: Future>(
+    key: &ComponentKey,
+    kind: &'static str,
+    task: F,
+) -> impl Future<Output = F::Output> {
+    let generation = health::spawned(key, kind);
+    let key = key.clone();
+    task.inspect(move |_| health::stopped(&key, generation))
+}
+
 fn get_changed_outputs(diff: &ConfigDiff, output_ids: Vec<OutputId>) -> Vec<OutputId> {
     let mut changed_outputs = Vec::new();
 
@@ -861,5 +904,88 @@ fn get_changed_outputs(diff: &ConfigDiff, output_ids: Vec<OutputId>) -> Vec<Outp
         );
     }
 
+    for sink_key in &diff.sinks.to_change {
+        changed_outputs.extend(
+            output_ids
+                .iter()
+                .filter(|id| &id.component == sink_key)
+                .cloned(),
+        );
+    }
+
     changed_outputs
 }
+
+/// Waits for the tasks of the components of a shutdown phase to finish, reporting in intervals
+/// which ones are still running, and killing those still running once their timeout, counted from
+/// the start of the phase, is over.
+async fn drain(
+    phase: &'static str,
+    components: HashMap<ComponentKey, (Vec<TaskHandle>, Duration)>,
+) {
+    if components.is_empty() {
+        return;
+    }
+
+    let start = Instant::now();
+    let mut deadlines = HashMap::new();
+    let mut running = FuturesUnordered::new();
+    for (key, (tasks, timeout)) in components {
+        let deadline = start + timeout;
+        deadlines.insert(key.clone(), deadline);
+        running.push(async move {
+            let mut killed = false;
+            for mut task in tasks {
+                if timeout_at(deadline, &mut task).await.is_err() {
+                    task.abort();
+                    // Wait for the task to be dropped, so that its outputs are closed before the
+                    // next phase starts.
+                    let _ = task.await;
+                    killed = true;
+                }
+            }
+            (key, killed)
+        });
+    }
+    debug!(phase, "Shutting down components.");
+
+    // Reports in intervals which components are still running.
+    let mut interval = interval_at(start + SHUTDOWN_REPORT_INTERVAL, SHUTDOWN_REPORT_INTERVAL);
+    loop {
+        tokio::select! {
+            next = running.next() => match next {
+                Some((key, killed)) => {
+                    deadlines.remove(&key);
+                    if killed {
+                        error!(
+                            component = %key,
+                            phase,
+                            "Failed to gracefully shut down in time. Killing component."
+                        );
+                    }
+                }
+                None => break,
+            },
+            _ = interval.tick() => {
+                let remaining_components = deadlines
+                    .keys()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let deadline = deadlines.values().max().copied().unwrap_or(start);
+                let time_remaining = match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) => format!("{} seconds left", remaining.as_secs()),
+                    None => "overdue".to_string(),
+                };
+
+                info!(
+                    phase,
+                    remaining_components = ?remaining_components,
+                    time_remaining = ?time_remaining,
+                    "Shutting down... Waiting on running components."
+                );
+            }
+        }
+    }
+}
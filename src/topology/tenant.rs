@@ -0,0 +1,97 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
+
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use once_cell::sync::Lazy;
+
+use crate::internal_events::TenantEventsThrottled;
+
+/// The limiters of the tenants with a throughput quota. They are kept across reloads so sources
+/// of a tenant that are rebuilt keep sharing the quota with those that are not.
+static LIMITERS: Lazy<Mutex<HashMap<String, Arc<TenantLimiter>>>> = Lazy::new(Default::default);
+
+/// Limits the number of events per second sent by all of the sources of a tenant.
+pub(super) struct TenantLimiter {
+    tenant: String,
+    max_events_per_sec: NonZeroU32,
+    limiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+}
+
+impl TenantLimiter {
+    /// Returns the limiter of a tenant, which is only replaced if its quota changed.
+    pub(super) fn get(tenant: &str, max_events_per_sec: NonZeroU32) -> Arc<Self> {
+        let mut limiters = LIMITERS.lock().expect("poisoned lock");
+        match limiters.get(tenant) {
+            Some(limiter) if limiter.max_events_per_sec == max_events_per_sec => {
+                Arc::clone(limiter)
+            }
+            _ => {
+                let limiter = Arc::new(Self {
+                    tenant: tenant.to_owned(),
+                    max_events_per_sec,
+                    limiter: RateLimiter::direct(Quota::per_second(max_events_per_sec)),
+                });
+                limiters.insert(tenant.to_owned(), Arc::clone(&limiter));
+                limiter
+            }
+        }
+    }
+
+    /// Waits until the sources of the tenant can send `count` more events.
+    pub(super) async fn until_ready(&self, count: usize) {
+        let mut remaining = count;
+        while remaining > 0 {
+            // The limiter can't let more events through at once than its quota.
+            let n = remaining.min(self.max_events_per_sec.get() as usize);
+            remaining -= n;
+            let n = NonZeroU32::new(n as u32).expect("n is never zero");
+            if self.limiter.check_n(n).is_err() {
+                emit!(TenantEventsThrottled {
+                    tenant: &self.tenant,
+                    count: n.get() as usize,
+                });
+                // This can only fail if `n` exceeds the quota.
+                let _ = self.limiter.until_n_ready(n).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_events_per_second() {
+        let max_events_per_sec = NonZeroU32::new(100).unwrap();
+        let limiter = TenantLimiter::get("limits_events_per_second", max_events_per_sec);
+        assert!(Arc::ptr_eq(
+            &limiter,
+            &TenantLimiter::get("limits_events_per_second", max_events_per_sec)
+        ));
+
+        // The first 100 events are let through right away, and the next 50 within half a second.
+        let start = Instant::now();
+        limiter.until_ready(150).await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn replaces_limiters_whose_quota_changed() {
+        let limiter = TenantLimiter::get("replaces_limiters", NonZeroU32::new(100).unwrap());
+        let replaced = TenantLimiter::get("replaces_limiters", NonZeroU32::new(200).unwrap());
+        assert!(!Arc::ptr_eq(&limiter, &replaced));
+        assert_eq!(replaced.max_events_per_sec.get(), 200);
+    }
+}
@@ -8,12 +8,12 @@ use std::{
 };
 
 use crate::{
-    config::{Config, ConfigDiff, SinkOuter},
+    config::{ComponentKey, Config, ConfigDiff, SinkOuter},
     event::{into_event_stream, Event, EventArray, EventContainer, LogEvent},
     test_util::{
         mock::{
             basic_sink, basic_sink_failing_healthcheck, basic_sink_with_data, basic_source,
-            basic_source_with_data, basic_source_with_event_counter, basic_transform,
+            basic_source_with_data, basic_source_with_event_counter, basic_transform, dead_sink,
         },
         start_topology, trace_init,
     },
@@ -22,7 +22,7 @@ use crate::{
 use futures::{future, stream, StreamExt};
 use tokio::{
     task::yield_now,
-    time::{sleep, Duration},
+    time::{sleep, timeout, Duration},
 };
 use vector_buffers::{BufferConfig, BufferType, WhenFull};
 
@@ -151,6 +151,36 @@ async fn topology_source_and_sink() {
     assert_eq!(vec![event], res);
 }
 
+#[tokio::test]
+async fn topology_stop_kills_stuck_sink() {
+    let (mut in1, source1) = basic_source();
+    let (out1, sink1) = basic_sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink("out1", &["in1"], sink1);
+    config.add_sink("stuck", &["in1"], dead_sink());
+    config
+        .sinks
+        .get_mut(&ComponentKey::from("stuck"))
+        .unwrap()
+        .shutdown_timeout_secs = Some(1);
+
+    let (topology, _crash) = start_topology(config.build().unwrap(), false).await;
+
+    let event = Event::Log(LogEvent::from("this"));
+    in1.send_event(event.clone()).await.unwrap();
+
+    // The stuck sink is killed once its own timeout is over, rather than the one of all sinks.
+    timeout(Duration::from_secs(10), topology.stop())
+        .await
+        .unwrap();
+
+    let res = out1.flat_map(into_event_stream).collect::<Vec<_>>().await;
+
+    assert_eq!(vec![event], res);
+}
+
 #[tokio::test]
 async fn topology_multiple_sources() {
     let (mut in1, source1) = basic_source();
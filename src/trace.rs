@@ -79,6 +79,12 @@ pub fn init(color: bool, json: bool, levels: &str) {
         subscriber.with(console_layer)
     };
 
+    #[cfg(feature = "sources-opentelemetry")]
+    let subscriber = subscriber.with(
+        crate::telemetry_export::SpanLayer
+            .with_filter(tracing_subscriber::filter::LevelFilter::INFO),
+    );
+
     if json {
         let formatter = tracing_subscriber::fmt::layer().json().flatten_event(true);
 
@@ -6,7 +6,9 @@ use std::{
 
 use async_stream::stream;
 use futures::{Stream, StreamExt};
+use vector_common::memory_budget::{MemoryUse, Reservation};
 use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
 
 use crate::{
     config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
@@ -64,6 +66,9 @@ type MetricEntry = (metric::MetricData, EventMetadata);
 pub struct Aggregate {
     interval: Duration,
     map: BTreeMap<metric::MetricSeries, MetricEntry>,
+    /// The size of the metrics aggregated, reserved against the memory budget of the process until
+    /// they're flushed.
+    memory: Reservation,
 }
 
 impl Aggregate {
@@ -71,6 +76,7 @@ impl Aggregate {
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
             map: BTreeMap::new(),
+            memory: Reservation::new(MemoryUse::TransformState, 0),
         })
     }
 
@@ -81,6 +87,7 @@ impl Aggregate {
             metric::MetricKind::Incremental => match self.map.entry(series) {
                 Entry::Occupied(mut entry) => {
                     let existing = entry.get_mut();
+                    self.memory.shrink(existing.0.size_of());
                     // In order to update (add) the new and old kind's must match
                     if existing.0.kind == data.kind && existing.0.update(&data) {
                         existing.1.merge(metadata);
@@ -88,14 +95,21 @@ impl Aggregate {
                         emit!(AggregateUpdateFailed);
                         *existing = (data, metadata);
                     }
+                    self.memory.grow(existing.0.size_of());
                 }
                 Entry::Vacant(entry) => {
+                    self.memory.grow(entry.key().size_of() + data.size_of());
                     entry.insert((data, metadata));
                 }
             },
             metric::MetricKind::Absolute => {
                 // Always replace/store
-                self.map.insert(series, (data, metadata));
+                let series_size = series.size_of();
+                self.memory.grow(data.size_of());
+                match self.map.insert(series, (data, metadata)) {
+                    Some((replaced, _)) => self.memory.shrink(replaced.size_of()),
+                    None => self.memory.grow(series_size),
+                }
             }
         };
 
@@ -104,6 +118,7 @@ impl Aggregate {
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
         let map = std::mem::take(&mut self.map);
+        self.memory.resize(0);
         for (series, entry) in map.into_iter() {
             let metric = metric::Metric::from_parts(series, entry.0, entry.1);
             output.push(Event::Metric(metric));
@@ -171,6 +186,37 @@ mod tests {
         Event::Metric(Metric::new(name, kind, value))
     }
 
+    #[test]
+    fn reserves_memory_until_flushed() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+        })
+        .unwrap();
+
+        let counter = make_metric(
+            "counter",
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Counter { value: 1.0 },
+        );
+        agg.record(counter.clone());
+        let reserved = agg.memory.bytes();
+        assert!(reserved > 0);
+
+        // Updating a counter doesn't grow it.
+        agg.record(counter);
+        assert_eq!(reserved, agg.memory.bytes());
+
+        agg.record(make_metric(
+            "gauge",
+            metric::MetricKind::Absolute,
+            metric::MetricValue::Gauge { value: 1.0 },
+        ));
+        assert!(agg.memory.bytes() > reserved);
+
+        agg.flush_into(&mut vec![]);
+        assert_eq!(0, agg.memory.bytes());
+    }
+
     #[test]
     fn incremental() {
         let mut agg = Aggregate::new(&AggregateConfig {
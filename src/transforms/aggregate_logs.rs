@@ -0,0 +1,525 @@
+use std::{collections::BTreeMap, pin::Pin, time::Duration};
+
+use async_stream::stream;
+use chrono::{TimeZone, Utc};
+use futures::{stream, Stream, StreamExt};
+use indexmap::IndexMap;
+use ordered_float::NotNan;
+use snafu::Snafu;
+use vector_config::configurable_component;
+
+use crate::{
+    config::{
+        log_schema, DataType, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, EventMetadata, LogEvent, Value},
+    internal_events::{AggregateLogsGroupEvicted, TemplateRenderingError},
+    schema,
+    template::Template,
+    transforms::{TaskTransform, Transform},
+};
+
+/// Configuration for the `aggregate_logs` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct AggregateLogsConfig {
+    /// The fields by which to group events, mapped to the template rendering their value.
+    ///
+    /// Each group of events with the same rendered values is aggregated separately, and its fields are added to
+    /// its aggregated event. When a template can't be rendered, such as when its field is missing, the event is
+    /// grouped without that field. When no fields are specified, all events are aggregated in a single group.
+    pub group_by: IndexMap<String, Template>,
+
+    /// The length of the windows over which events are aggregated, in seconds.
+    ///
+    /// Windows are aligned on the wall clock, and events are assigned to windows by the time they are received.
+    #[serde(default = "default_window_secs")]
+    #[derivative(Default(value = "default_window_secs()"))]
+    pub window_secs: u64,
+
+    /// The interval between the starts of successive windows, in seconds.
+    ///
+    /// When smaller than `window_secs`, windows overlap, and each event is aggregated in every window it falls into.
+    /// By default, windows don't overlap.
+    pub slide_secs: Option<u64>,
+
+    /// The numeric fields to sum.
+    pub sum: Vec<String>,
+
+    /// The numeric fields whose minimum is kept.
+    pub min: Vec<String>,
+
+    /// The numeric fields whose maximum is kept.
+    pub max: Vec<String>,
+
+    /// The number of messages of each group kept in its aggregated event.
+    ///
+    /// The first messages of each window are kept.
+    #[serde(default = "default_sample_size")]
+    #[derivative(Default(value = "default_sample_size()"))]
+    pub sample_size: usize,
+
+    /// The maximum number of groups held at once, across all open windows.
+    ///
+    /// When a new group would exceed it, the group of the oldest window is flushed early.
+    #[serde(default = "default_max_groups")]
+    #[derivative(Default(value = "default_max_groups()"))]
+    pub max_groups: usize,
+}
+
+const fn default_window_secs() -> u64 {
+    60
+}
+
+const fn default_sample_size() -> usize {
+    3
+}
+
+const fn default_max_groups() -> usize {
+    10_000
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`window_secs` must be greater than zero"))]
+    ZeroWindow,
+    #[snafu(display("`slide_secs` must be greater than zero, and at most `window_secs`"))]
+    InvalidSlide,
+    #[snafu(display("`max_groups` must be greater than zero"))]
+    ZeroMaxGroups,
+}
+
+inventory::submit! {
+    TransformDescription::new::<AggregateLogsConfig>("aggregate_logs")
+}
+
+impl_generate_config_from_default!(AggregateLogsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aggregate_logs")]
+impl TransformConfig for AggregateLogsConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::event_task(AggregateLogs::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "aggregate_logs"
+    }
+}
+
+/// The rendered values of the `group_by` templates of an event, in order.
+type GroupKey = Vec<Option<String>>;
+
+/// The aggregated values of a group, in a single window.
+#[derive(Debug)]
+struct GroupState {
+    count: u64,
+    sum: Vec<Option<f64>>,
+    min: Vec<Option<f64>>,
+    max: Vec<Option<f64>>,
+    samples: Vec<Value>,
+    metadata: EventMetadata,
+}
+
+impl GroupState {
+    fn new(config: &AggregateLogsConfig, metadata: EventMetadata) -> Self {
+        Self {
+            count: 0,
+            sum: vec![None; config.sum.len()],
+            min: vec![None; config.min.len()],
+            max: vec![None; config.max.len()],
+            samples: Vec::new(),
+            metadata,
+        }
+    }
+
+    fn add(&mut self, config: &AggregateLogsConfig, event: &LogEvent) {
+        self.count += 1;
+        update(&mut self.sum, &config.sum, event, |a, b| a + b);
+        update(&mut self.min, &config.min, event, f64::min);
+        update(&mut self.max, &config.max, event, f64::max);
+        if self.samples.len() < config.sample_size {
+            if let Some(message) = event.get(log_schema().message_key()) {
+                self.samples.push(message.clone());
+            }
+        }
+    }
+}
+
+/// Folds the numeric values of the fields of an event into their aggregated values.
+fn update(
+    values: &mut [Option<f64>],
+    fields: &[String],
+    event: &LogEvent,
+    fold: impl Fn(f64, f64) -> f64,
+) {
+    for (value, field) in values.iter_mut().zip(fields) {
+        let number = match event.get(field.as_str()) {
+            Some(Value::Integer(number)) => *number as f64,
+            Some(Value::Float(number)) => number.into_inner(),
+            _ => continue,
+        };
+        *value = Some(value.map_or(number, |value| fold(value, number)));
+    }
+}
+
+/// Turns aggregated values into an object, keyed by their field.
+fn stats(fields: &[String], values: Vec<Option<f64>>) -> Option<Value> {
+    let stats = fields
+        .iter()
+        .zip(values)
+        .filter_map(|(field, value)| {
+            let value = NotNan::new(value?).ok()?;
+            Some((field.clone(), Value::Float(value)))
+        })
+        .collect::<BTreeMap<_, _>>();
+    (!stats.is_empty()).then(|| Value::Object(stats))
+}
+
+pub struct AggregateLogs {
+    config: AggregateLogsConfig,
+    window_ms: i64,
+    slide_ms: i64,
+
+    /// The groups of the open windows, by the start of their window.
+    groups: BTreeMap<(i64, GroupKey), GroupState>,
+}
+
+impl AggregateLogs {
+    pub fn new(config: &AggregateLogsConfig) -> crate::Result<Self> {
+        let window_secs = config.window_secs;
+        let slide_secs = config.slide_secs.unwrap_or(window_secs);
+        if window_secs == 0 {
+            return Err(Box::new(BuildError::ZeroWindow));
+        }
+        if slide_secs == 0 || slide_secs > window_secs {
+            return Err(Box::new(BuildError::InvalidSlide));
+        }
+        if config.max_groups == 0 {
+            return Err(Box::new(BuildError::ZeroMaxGroups));
+        }
+
+        Ok(Self {
+            config: config.clone(),
+            window_ms: window_secs as i64 * 1000,
+            slide_ms: slide_secs as i64 * 1000,
+            groups: BTreeMap::new(),
+        })
+    }
+
+    fn group_key(&self, event: &LogEvent) -> GroupKey {
+        self.config
+            .group_by
+            .iter()
+            .map(|(field, template)| {
+                template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some(field.as_str()),
+                            drop_event: false,
+                        })
+                    })
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Aggregates an event, received at `now_ms`, into the groups of every window it falls into.
+    fn record(&mut self, event: Event, now_ms: i64, output: &mut Vec<Event>) {
+        let event = event.into_log();
+        let key = self.group_key(&event);
+
+        let last_start = now_ms - now_ms.rem_euclid(self.slide_ms);
+        let starts = (0..)
+            .map(|index| last_start - index * self.slide_ms)
+            .take_while(|start| start + self.window_ms > now_ms)
+            .collect::<Vec<_>>();
+
+        for start in starts {
+            let group_key = (start, key.clone());
+            if !self.groups.contains_key(&group_key) && self.groups.len() >= self.config.max_groups
+            {
+                if let Some(oldest) = self.groups.keys().next().cloned() {
+                    let group = self.groups.remove(&oldest).expect("group exists");
+                    emit!(AggregateLogsGroupEvicted);
+                    output.push(self.flush_group(oldest, group));
+                }
+            }
+
+            let config = &self.config;
+            self.groups
+                .entry(group_key)
+                .or_insert_with(|| GroupState::new(config, event.metadata().clone()))
+                .add(config, &event);
+        }
+    }
+
+    /// Flushes the groups of the windows that ended by `now_ms`.
+    fn flush_closed_into(&mut self, now_ms: i64, output: &mut Vec<Event>) {
+        let open = self
+            .groups
+            .split_off(&(now_ms - self.window_ms + 1, GroupKey::new()));
+        let closed = std::mem::replace(&mut self.groups, open);
+        for (key, group) in closed {
+            output.push(self.flush_group(key, group));
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        for (key, group) in std::mem::take(&mut self.groups) {
+            output.push(self.flush_group(key, group));
+        }
+    }
+
+    fn flush_group(&self, (start, key): (i64, GroupKey), group: GroupState) -> Event {
+        let mut log = LogEvent::new_with_metadata(group.metadata);
+        for (field, value) in self.config.group_by.keys().zip(key) {
+            if let Some(value) = value {
+                log.insert(field.as_str(), value);
+            }
+        }
+        log.insert("count", group.count as i64);
+        log.insert("window_start", Utc.timestamp_millis(start));
+        log.insert("window_end", Utc.timestamp_millis(start + self.window_ms));
+        for (name, fields, values) in [
+            ("sum", &self.config.sum, group.sum),
+            ("min", &self.config.min, group.min),
+            ("max", &self.config.max, group.max),
+        ] {
+            if let Some(stats) = stats(fields, values) {
+                log.insert(name, stats);
+            }
+        }
+        if !group.samples.is_empty() {
+            log.insert("samples", group.samples);
+        }
+        log.into()
+    }
+}
+
+impl TaskTransform<Event> for AggregateLogs {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(
+            stream! {
+                loop {
+                    let mut output = Vec::new();
+                    let done = tokio::select! {
+                        _ = flush_stream.tick() => {
+                            self.flush_closed_into(Utc::now().timestamp_millis(), &mut output);
+                            false
+                        }
+                        maybe_event = input_rx.next() => {
+                            match maybe_event {
+                                None => {
+                                    self.flush_all_into(&mut output);
+                                    true
+                                }
+                                Some(event) => {
+                                    self.record(event, Utc::now().timestamp_millis(), &mut output);
+                                    false
+                                }
+                            }
+                        }
+                    };
+                    yield stream::iter(output.into_iter());
+                    if done { break }
+                }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AggregateLogsConfig>();
+    }
+
+    fn aggregate_logs(config: &str) -> AggregateLogs {
+        AggregateLogs::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn make_log(service: &str, message: &str, duration: impl Into<Value>) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("service", service);
+        log.insert("duration", duration.into());
+        log.into()
+    }
+
+    fn timestamp(ms: i64) -> Value {
+        Utc.timestamp_millis(ms).into()
+    }
+
+    #[test]
+    fn aggregates_groups_over_tumbling_windows() {
+        let mut aggregate = aggregate_logs(
+            r#"
+            group_by.service = "{{ service }}"
+            window_secs = 10
+            sum = ["duration"]
+            min = ["duration"]
+            max = ["duration", "missing"]
+            sample_size = 2
+            "#,
+        );
+
+        let mut output = Vec::new();
+        aggregate.record(make_log("api", "first", 3), 1_000, &mut output);
+        aggregate.record(make_log("api", "second", 1.5), 2_000, &mut output);
+        aggregate.record(make_log("web", "third", "slow"), 3_000, &mut output);
+        aggregate.record(make_log("api", "fourth", 4), 4_000, &mut output);
+        aggregate.record(make_log("api", "fifth", 1), 12_000, &mut output);
+        aggregate.flush_closed_into(9_999, &mut output);
+        assert!(output.is_empty());
+
+        aggregate.flush_closed_into(10_000, &mut output);
+        let output = output.into_iter().map(Event::into_log).collect::<Vec<_>>();
+        assert_eq!(output.len(), 2);
+
+        let api = &output[0];
+        assert_eq!(api["service"], "api".into());
+        assert_eq!(api["count"], 3.into());
+        assert_eq!(api["window_start"], timestamp(0));
+        assert_eq!(api["window_end"], timestamp(10_000));
+        assert_eq!(api["sum.duration"], 8.5.into());
+        assert_eq!(api["min.duration"], 1.5.into());
+        assert_eq!(api["max.duration"], 4.0.into());
+        assert!(!api.contains("max.missing"));
+        assert_eq!(
+            api["samples"],
+            Value::Array(vec!["first".into(), "second".into()])
+        );
+
+        let web = &output[1];
+        assert_eq!(web["service"], "web".into());
+        assert_eq!(web["count"], 1.into());
+        assert!(!web.contains("sum"));
+
+        assert_eq!(aggregate.groups.len(), 1);
+    }
+
+    #[test]
+    fn aggregates_events_in_every_sliding_window() {
+        let mut aggregate = aggregate_logs(
+            r#"
+            window_secs = 10
+            slide_secs = 5
+            "#,
+        );
+
+        let mut output = Vec::new();
+        aggregate.record(make_log("api", "first", 1), 7_000, &mut output);
+        aggregate.record(make_log("api", "second", 1), 12_000, &mut output);
+        aggregate.flush_all_into(&mut output);
+
+        let windows = output
+            .into_iter()
+            .map(Event::into_log)
+            .map(|log| (log["window_start"].clone(), log["count"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            windows,
+            vec![
+                (timestamp(0), 1.into()),
+                (timestamp(5_000), 2.into()),
+                (timestamp(10_000), 1.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn flushes_oldest_group_when_full() {
+        let mut aggregate = aggregate_logs(
+            r#"
+            group_by.service = "{{ service }}"
+            max_groups = 2
+            "#,
+        );
+
+        let mut output = Vec::new();
+        aggregate.record(make_log("a", "first", 1), 1_000, &mut output);
+        aggregate.record(make_log("b", "second", 1), 1_000, &mut output);
+        aggregate.record(make_log("b", "third", 1), 1_000, &mut output);
+        assert!(output.is_empty());
+
+        aggregate.record(make_log("c", "fourth", 1), 1_000, &mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["service"], "a".into());
+        assert_eq!(aggregate.groups.len(), 2);
+    }
+
+    #[test]
+    fn groups_events_without_unrenderable_fields() {
+        let mut aggregate = aggregate_logs(r#"group_by.region = "{{ region }}""#);
+
+        let mut output = Vec::new();
+        aggregate.record(make_log("api", "first", 1), 1_000, &mut output);
+        aggregate.flush_all_into(&mut output);
+
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert!(!log.contains("region"));
+        assert_eq!(log["count"], 1.into());
+    }
+
+    #[tokio::test]
+    async fn flushes_on_shutdown() {
+        let aggregate = Transform::event_task(aggregate_logs("window_secs = 3600")).into_task();
+
+        let events = vec![make_log("api", "first", 1), make_log("api", "second", 1)];
+        let output = aggregate
+            .transform_events(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], 2.into());
+    }
+
+    #[test]
+    fn rejects_invalid_windows() {
+        let error = |config: &str| {
+            AggregateLogs::new(&toml::from_str(config).unwrap())
+                .err()
+                .map(|error| error.to_string())
+        };
+        assert_eq!(
+            error("window_secs = 0"),
+            Some(BuildError::ZeroWindow.to_string())
+        );
+        assert_eq!(
+            error("window_secs = 10\nslide_secs = 20"),
+            Some(BuildError::InvalidSlide.to_string())
+        );
+        assert_eq!(
+            error("max_groups = 0"),
+            Some(BuildError::ZeroMaxGroups.to_string())
+        );
+        assert_eq!(error("window_secs = 10\nslide_secs = 5"), None);
+    }
+}
@@ -0,0 +1,387 @@
+use chrono::Utc;
+use lru::LruCache;
+use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricSeries, MetricValue},
+        Event, LogEvent,
+    },
+    internal_events::AnomalyDetected,
+    schema,
+    transforms::Transform,
+};
+
+const ALERTS: &str = "alerts";
+
+/// Configuration for the `anomaly_detection` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AnomalyDetectionConfig {
+    /// The weight of each new value in the moving average and variance of its series, between 0 and 1.
+    ///
+    /// Higher weights adapt faster to changes in the series, but make its deviations less significant.
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+
+    /// The number of standard deviations from the moving average beyond which a value is anomalous.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// The number of values of a series to learn from before detecting its anomalies.
+    #[serde(default = "default_warmup_samples")]
+    pub warmup_samples: u64,
+
+    /// The maximum number of series to keep the state of.
+    ///
+    /// Once reached, the state of the least recently seen series is forgotten, and it's learned again if it's seen
+    /// again.
+    #[serde(default = "default_max_series")]
+    pub max_series: usize,
+
+    /// Whether to add the `anomaly` and `anomaly_score` tags to the anomalous metrics.
+    #[serde(default = "crate::serde::default_true")]
+    pub annotate: bool,
+}
+
+const fn default_alpha() -> f64 {
+    0.1
+}
+
+const fn default_threshold() -> f64 {
+    3.0
+}
+
+const fn default_warmup_samples() -> u64 {
+    30
+}
+
+const fn default_max_series() -> usize {
+    10_000
+}
+
+inventory::submit! {
+    TransformDescription::new::<AnomalyDetectionConfig>("anomaly_detection")
+}
+
+impl GenerateConfig for AnomalyDetectionConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"alpha = 0.1
+            threshold = 3.0"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "anomaly_detection")]
+impl TransformConfig for AnomalyDetectionConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        AnomalyDetection::new(self).map(Transform::synchronous)
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![
+            Output::default(DataType::Metric),
+            Output::default(DataType::Log).with_port(ALERTS),
+        ]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "anomaly_detection"
+    }
+}
+
+/// The exponentially weighted moving average and variance of a series.
+#[derive(Clone, Debug, Default)]
+struct SeriesState {
+    mean: f64,
+    variance: f64,
+    samples: u64,
+    /// The last value of an absolute counter, whose differences are analyzed.
+    last_absolute: Option<f64>,
+}
+
+/// The deviation of a value from its series.
+#[derive(Clone, Copy, Debug)]
+struct Deviation {
+    value: f64,
+    expected: f64,
+    stddev: f64,
+    score: f64,
+}
+
+impl SeriesState {
+    /// Records a value, returning its deviation from the previous ones.
+    fn record(&mut self, value: f64, alpha: f64) -> Deviation {
+        if self.samples == 0 {
+            self.mean = value;
+        }
+        let expected = self.mean;
+        let stddev = self.variance.sqrt();
+        let diff = value - self.mean;
+        let score = if stddev > 0.0 { diff / stddev } else { 0.0 };
+
+        let increment = alpha * diff;
+        self.mean += increment;
+        self.variance = (1.0 - alpha) * (self.variance + diff * increment);
+        self.samples += 1;
+
+        Deviation {
+            value,
+            expected,
+            stddev,
+            score,
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct AnomalyDetection {
+    alpha: f64,
+    threshold: f64,
+    warmup_samples: u64,
+    annotate: bool,
+    #[derivative(Debug = "ignore")]
+    series: LruCache<MetricSeries, SeriesState>,
+}
+
+impl Clone for AnomalyDetection {
+    fn clone(&self) -> Self {
+        // The cache isn't cloneable, so its entries are copied from the least to the most recently used.
+        let mut series = LruCache::new(self.series.cap());
+        for (key, state) in self.series.iter().rev() {
+            series.put(key.clone(), state.clone());
+        }
+        Self {
+            alpha: self.alpha,
+            threshold: self.threshold,
+            warmup_samples: self.warmup_samples,
+            annotate: self.annotate,
+            series,
+        }
+    }
+}
+
+impl AnomalyDetection {
+    pub fn new(config: &AnomalyDetectionConfig) -> crate::Result<Self> {
+        if !(config.alpha > 0.0 && config.alpha <= 1.0) {
+            return Err("`alpha` must be greater than 0 and at most 1".into());
+        }
+        if config.max_series == 0 {
+            return Err("`max_series` must be greater than zero".into());
+        }
+
+        Ok(Self {
+            alpha: config.alpha,
+            threshold: config.threshold,
+            warmup_samples: config.warmup_samples,
+            annotate: config.annotate,
+            series: LruCache::new(config.max_series),
+        })
+    }
+
+    /// Returns the state of a series, which is created if it's not known.
+    fn state(&mut self, series: &MetricSeries) -> &mut SeriesState {
+        if !self.series.contains(series) {
+            self.series.put(series.clone(), SeriesState::default());
+        }
+        self.series
+            .get_mut(series)
+            .expect("state was just inserted")
+    }
+
+    /// Returns the value of a metric to analyze, if its kind of value is supported.
+    fn sample(&mut self, metric: &Metric) -> Option<f64> {
+        match (metric.kind(), metric.value()) {
+            (_, MetricValue::Gauge { value })
+            | (MetricKind::Incremental, MetricValue::Counter { value }) => Some(*value),
+            // Absolute counters only increase, so the differences between their values are analyzed instead.
+            (MetricKind::Absolute, MetricValue::Counter { value }) => {
+                let state = self.state(metric.series());
+                let last = state.last_absolute.replace(*value);
+                last.map(|last| *value - last).filter(|diff| *diff >= 0.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Analyzes a metric, returning its deviation if it's anomalous.
+    fn analyze(&mut self, metric: &Metric) -> Option<Deviation> {
+        let value = self.sample(metric)?;
+        let state = self.state(metric.series());
+        let deviation = state.record(value, self.alpha);
+
+        (state.samples > self.warmup_samples && deviation.score.abs() >= self.threshold)
+            .then(|| deviation)
+    }
+
+    fn alert(metric: &Metric, deviation: Deviation) -> LogEvent {
+        let mut log = LogEvent::from(format!(
+            "Anomalous value for metric {:?}: {} instead of {} (score {:.2}).",
+            metric.name(),
+            deviation.value,
+            deviation.expected,
+            deviation.score
+        ));
+        log.insert(
+            log_schema().timestamp_key(),
+            metric.timestamp().unwrap_or_else(Utc::now),
+        );
+        log.insert("metric.name", metric.name());
+        if let Some(namespace) = metric.namespace() {
+            log.insert("metric.namespace", namespace);
+        }
+        if let Some(tags) = metric.tags() {
+            for (key, value) in tags {
+                log.insert(format!("metric.tags.{}", key).as_str(), value.as_str());
+            }
+        }
+        log.insert("value", deviation.value);
+        log.insert("expected", deviation.expected);
+        log.insert("stddev", deviation.stddev);
+        log.insert("score", deviation.score);
+        log
+    }
+}
+
+impl SyncTransform for AnomalyDetection {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let mut metric = event.into_metric();
+        let deviation = self.analyze(&metric);
+
+        if let Some(deviation) = deviation {
+            emit!(AnomalyDetected {
+                name: metric.name(),
+                score: deviation.score,
+            });
+            output.push_named(ALERTS, Self::alert(&metric, deviation).into());
+
+            if self.annotate {
+                metric.insert_tag("anomaly".to_owned(), "true".to_owned());
+                metric.insert_tag(
+                    "anomaly_score".to_owned(),
+                    format!("{:.2}", deviation.score),
+                );
+            }
+        }
+
+        output.push(metric.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AnomalyDetectionConfig>();
+    }
+
+    fn transform(warmup_samples: u64) -> AnomalyDetection {
+        AnomalyDetection::new(&AnomalyDetectionConfig {
+            alpha: default_alpha(),
+            threshold: default_threshold(),
+            warmup_samples,
+            max_series: 2,
+            annotate: true,
+        })
+        .unwrap()
+    }
+
+    fn gauge(name: &str, value: f64) -> Event {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value }).into()
+    }
+
+    fn run(transform: &mut AnomalyDetection, event: Event) -> (Metric, Vec<Event>) {
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::Metric),
+                Output::default(DataType::Log).with_port(ALERTS),
+            ],
+            1,
+        );
+        transform.transform(event, &mut outputs);
+        let metric = outputs.drain().next().unwrap().into_metric();
+        (metric, outputs.drain_named(ALERTS).collect())
+    }
+
+    #[test]
+    fn detects_anomalies() {
+        let mut transform = transform(10);
+        for i in 0..50 {
+            let value = if i % 2 == 0 { 99.0 } else { 101.0 };
+            let (metric, alerts) = run(&mut transform, gauge("latency", value));
+            assert!(alerts.is_empty());
+            assert!(metric.tag_value("anomaly").is_none());
+        }
+
+        let (metric, alerts) = run(&mut transform, gauge("latency", 150.0));
+        assert_eq!(metric.tag_value("anomaly"), Some("true".to_owned()));
+        assert_eq!(alerts.len(), 1);
+        let alert = alerts[0].as_log();
+        assert_eq!(alert["metric.name"], "latency".into());
+        assert_eq!(alert["value"], Value::from(150.0));
+    }
+
+    #[test]
+    fn waits_for_warmup() {
+        let mut transform = transform(10);
+        run(&mut transform, gauge("latency", 1.0));
+        run(&mut transform, gauge("latency", 2.0));
+
+        let (_, alerts) = run(&mut transform, gauge("latency", 1000.0));
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn analyzes_absolute_counter_differences() {
+        let mut transform = transform(5);
+        let counter = |value| -> Event {
+            Metric::new(
+                "requests",
+                MetricKind::Absolute,
+                MetricValue::Counter { value },
+            )
+            .into()
+        };
+
+        let mut total = 0.0;
+        for i in 0..30 {
+            total += if i % 2 == 0 { 9.0 } else { 11.0 };
+            let (_, alerts) = run(&mut transform, counter(total));
+            assert!(alerts.is_empty());
+        }
+
+        let (_, alerts) = run(&mut transform, counter(total + 100.0));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].as_log()["value"], Value::from(100.0));
+    }
+
+    #[test]
+    fn bounds_series() {
+        let mut transform = transform(0);
+        run(&mut transform, gauge("a", 1.0));
+        run(&mut transform, gauge("b", 1.0));
+        run(&mut transform, gauge("c", 1.0));
+
+        assert_eq!(transform.series.len(), 2);
+        assert!(!transform
+            .series
+            .contains(gauge("a", 1.0).as_metric().series()));
+    }
+}
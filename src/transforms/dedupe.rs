@@ -1,4 +1,11 @@
-use std::{future::ready, pin::Pin};
+use std::{
+    collections::hash_map::DefaultHasher,
+    f64::consts::LN_2,
+    future::ready,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    time::{Duration, Instant},
+};
 
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
@@ -34,6 +41,21 @@ pub enum FieldMatchConfig {
     IgnoreFields(#[configurable(transparent)] Vec<String>),
 }
 
+/// The backend remembering the events seen.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheBackend {
+    /// Remembers the last `num_events` distinct events, evicting the least recently seen ones first.
+    Lru,
+
+    /// Remembers the events in two rotating bloom filters, sized for `num_events` events each.
+    ///
+    /// Their size doesn't depend on the size of the events, but events are wrongly considered
+    /// duplicates at the rate of `false_positive_rate`.
+    Bloom,
+}
+
 /// Caching configuration for deduplication.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -41,6 +63,21 @@ pub enum FieldMatchConfig {
 pub struct CacheConfig {
     /// Number of events to cache and use for comparing incoming events to previously seen events.
     pub num_events: usize,
+
+    /// The time window of deduplication, in seconds.
+    ///
+    /// When set, events are only duplicates of the events first seen less than `window_secs` ago.
+    /// With the `bloom` backend, events are remembered for between one and two windows.
+    #[serde(default)]
+    pub window_secs: Option<u64>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_cache_backend")]
+    pub backend: CacheBackend,
+
+    /// The rate at which the `bloom` backend wrongly considers events duplicates, between 0 and 1.
+    #[serde(default = "default_false_positive_rate")]
+    pub false_positive_rate: f64,
 }
 
 /// Configuration for the `dedupe` transform.
@@ -58,7 +95,20 @@ pub struct DedupeConfig {
 }
 
 const fn default_cache_config() -> CacheConfig {
-    CacheConfig { num_events: 5000 }
+    CacheConfig {
+        num_events: 5000,
+        window_secs: None,
+        backend: default_cache_backend(),
+        false_positive_rate: default_false_positive_rate(),
+    }
+}
+
+const fn default_cache_backend() -> CacheBackend {
+    CacheBackend::Lru
+}
+
+const fn default_false_positive_rate() -> f64 {
+    0.0001
 }
 
 impl DedupeConfig {
@@ -80,7 +130,7 @@ impl DedupeConfig {
 
 pub struct Dedupe {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    cache: Cache,
 }
 
 inventory::submit! {
@@ -101,6 +151,17 @@ impl GenerateConfig for DedupeConfig {
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.cache.window_secs == Some(0) {
+            return Err("`cache.window_secs` must be greater than zero".into());
+        }
+        if self.cache.backend == CacheBackend::Bloom {
+            if self.cache.num_events == 0 {
+                return Err("`cache.num_events` must be greater than zero".into());
+            }
+            if !(self.cache.false_positive_rate > 0.0 && self.cache.false_positive_rate < 1.0) {
+                return Err("`cache.false_positive_rate` must be between 0 and 1".into());
+            }
+        }
         Ok(Transform::event_task(Dedupe::new(self.clone())))
     }
 
@@ -163,19 +224,177 @@ const fn type_id_for_value(val: &Value) -> TypeId {
     }
 }
 
+/// Remembers the events seen, as cache entries.
+enum Cache {
+    Lru {
+        /// The time each entry was first seen.
+        entries: LruCache<CacheEntry, Instant>,
+        window: Option<Duration>,
+    },
+    Bloom(RotatingBloomFilter),
+}
+
+impl Cache {
+    fn new(config: &CacheConfig) -> Self {
+        let window = config.window_secs.map(Duration::from_secs);
+        match config.backend {
+            CacheBackend::Lru => Self::Lru {
+                entries: LruCache::new(config.num_events),
+                window,
+            },
+            CacheBackend::Bloom => Self::Bloom(RotatingBloomFilter::new(
+                config.num_events,
+                config.false_positive_rate,
+                window,
+            )),
+        }
+    }
+
+    /// Remembers the entry, returning whether it was already seen.
+    fn insert(&mut self, entry: CacheEntry, now: Instant) -> bool {
+        match self {
+            Self::Lru { entries, window } => {
+                let seen = matches!(
+                    entries.get(&entry),
+                    Some(first_seen) if window.map_or(true, |window| now.duration_since(*first_seen) < window)
+                );
+                if !seen {
+                    entries.put(entry, now);
+                }
+                seen
+            }
+            Self::Bloom(filter) => filter.insert(&entry, now),
+        }
+    }
+}
+
+/// Two bloom filters, the current one receiving the new entries. Once it's full, or once the
+/// window has elapsed, it replaces the previous one, so the memory used never grows.
+struct RotatingBloomFilter {
+    current: BloomFilter,
+    previous: BloomFilter,
+    capacity: usize,
+    false_positive_rate: f64,
+    window: Option<Duration>,
+    rotated_at: Instant,
+}
+
+impl RotatingBloomFilter {
+    fn new(capacity: usize, false_positive_rate: f64, window: Option<Duration>) -> Self {
+        Self {
+            current: BloomFilter::new(capacity, false_positive_rate),
+            previous: BloomFilter::new(capacity, false_positive_rate),
+            capacity,
+            false_positive_rate,
+            window,
+            rotated_at: Instant::now(),
+        }
+    }
+
+    fn insert(&mut self, entry: &CacheEntry, now: Instant) -> bool {
+        if let Some(window) = self.window {
+            let elapsed = now.saturating_duration_since(self.rotated_at);
+            if elapsed >= window * 2 {
+                // Both filters only hold entries of past windows.
+                self.current = BloomFilter::new(self.capacity, self.false_positive_rate);
+                self.rotate(now);
+            } else if elapsed >= window {
+                self.rotate(now);
+            }
+        }
+
+        let hashes = bloom_hashes(entry);
+        if self.current.contains(hashes) {
+            return true;
+        }
+        let seen = self.previous.contains(hashes);
+        // Without a window, entries seen again are kept like in the `lru` backend, while with a
+        // window, they're only remembered from when they were first seen.
+        if !seen || self.window.is_none() {
+            if self.current.len == self.capacity {
+                self.rotate(now);
+            }
+            self.current.insert(hashes);
+        }
+        seen
+    }
+
+    fn rotate(&mut self, now: Instant) {
+        let current = BloomFilter::new(self.capacity, self.false_positive_rate);
+        self.previous = std::mem::replace(&mut self.current, current);
+        self.rotated_at = now;
+    }
+}
+
+/// A bloom filter sized for a number of entries and a rate of false positives.
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u64,
+    len: usize,
+}
+
+impl BloomFilter {
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let num_bits = (-capacity * false_positive_rate.ln() / (LN_2 * LN_2))
+            .ceil()
+            .max(64.0) as u64;
+        let num_hashes = (-false_positive_rate.ln() / LN_2).round().max(1.0) as u64;
+        Self {
+            bits: vec![0; ((num_bits + 63) / 64) as usize],
+            num_bits,
+            num_hashes,
+            len: 0,
+        }
+    }
+
+    /// The bits of an entry, derived from two hashes.
+    fn indexes(&self, (hash1, hash2): (u64, u64)) -> impl Iterator<Item = u64> {
+        let num_bits = self.num_bits;
+        (0..self.num_hashes).map(move |i| hash1.wrapping_add(i.wrapping_mul(hash2)) % num_bits)
+    }
+
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.indexes(hashes)
+            .all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    fn insert(&mut self, hashes: (u64, u64)) {
+        for index in self.indexes(hashes).collect::<Vec<_>>() {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+        self.len += 1;
+    }
+}
+
+fn bloom_hashes(entry: &CacheEntry) -> (u64, u64) {
+    let hash = |seed: u8| {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        entry.hash(&mut hasher);
+        hasher.finish()
+    };
+    // The second hash is odd, so that it's coprime with power-of-two sizes.
+    (hash(0), hash(1) | 1)
+}
+
 impl Dedupe {
     pub fn new(config: DedupeConfig) -> Self {
-        let num_entries = config.cache.num_events;
         let fields = config.fill_default_fields_match();
         Self {
             fields,
-            cache: LruCache::new(num_entries),
+            cache: Cache::new(&config.cache),
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
+        self.transform_one_at(event, Instant::now())
+    }
+
+    fn transform_one_at(&mut self, event: Event, now: Instant) -> Option<Event> {
         let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        if self.cache.insert(cache_entry, now) {
             emit!(DedupeEventDiscarded { event });
             None
         } else {
@@ -250,7 +469,10 @@ mod tests {
 
     fn make_match_transform(num_events: usize, fields: Vec<String>) -> Dedupe {
         Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
+            cache: CacheConfig {
+                num_events,
+                ..default_cache_config()
+            },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
         })
     }
@@ -261,7 +483,10 @@ mod tests {
         fields.extend(given_fields);
 
         Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
+            cache: CacheConfig {
+                num_events,
+                ..default_cache_config()
+            },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
         })
     }
@@ -306,6 +531,97 @@ mod tests {
         assert_eq!(None, transform.transform_one(event3));
     }
 
+    fn make_windowed_transform(backend: CacheBackend, window_secs: u64) -> Dedupe {
+        Dedupe::new(DedupeConfig {
+            cache: CacheConfig {
+                num_events: 5,
+                window_secs: Some(window_secs),
+                backend,
+                ..default_cache_config()
+            },
+            fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+        })
+    }
+
+    fn make_bloom_transform(num_events: usize) -> Dedupe {
+        Dedupe::new(DedupeConfig {
+            cache: CacheConfig {
+                num_events,
+                backend: CacheBackend::Bloom,
+                ..default_cache_config()
+            },
+            fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+        })
+    }
+
+    #[test]
+    fn dedupe_bloom_basic() {
+        basic(make_bloom_transform(5));
+    }
+
+    #[test]
+    fn dedupe_bloom_age_out() {
+        // Each filter only holds a single entry, so the first event is forgotten once the second
+        // filter is full too.
+        let mut transform = make_bloom_transform(1);
+        let events = (0..3)
+            .map(|i| {
+                let mut event = Event::Log(LogEvent::from("message"));
+                event.as_mut_log().insert("matched", i);
+                event
+            })
+            .collect::<Vec<_>>();
+
+        for event in &events {
+            assert!(transform.transform_one(event.clone()).is_some());
+        }
+        assert!(transform.transform_one(events[2].clone()).is_none());
+        assert!(transform.transform_one(events[0].clone()).is_some());
+    }
+
+    #[test]
+    fn dedupe_lru_window() {
+        window(make_windowed_transform(CacheBackend::Lru, 10));
+    }
+
+    #[test]
+    fn dedupe_bloom_window() {
+        window(make_windowed_transform(CacheBackend::Bloom, 10));
+    }
+
+    fn window(mut transform: Dedupe) {
+        let mut event = Event::Log(LogEvent::from("message"));
+        event.as_mut_log().insert("matched", "some value");
+        let start = Instant::now();
+
+        assert!(transform.transform_one_at(event.clone(), start).is_some());
+        assert!(transform
+            .transform_one_at(event.clone(), start + Duration::from_secs(9))
+            .is_none());
+        // Duplicates don't extend the window.
+        assert!(transform
+            .transform_one_at(event.clone(), start + Duration::from_secs(21))
+            .is_some());
+        assert!(transform
+            .transform_one_at(event, start + Duration::from_secs(22))
+            .is_none());
+    }
+
+    #[test]
+    fn bloom_filter_false_positive_rate() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        let entry = |i: i64| CacheEntry::Match(vec![Some((2, Bytes::from(i.to_string())))]);
+        for i in 0..1000 {
+            filter.insert(bloom_hashes(&entry(i)));
+        }
+        assert!((0..1000).all(|i| filter.contains(bloom_hashes(&entry(i)))));
+
+        let false_positives = (1000..11000)
+            .filter(|i| filter.contains(bloom_hashes(&entry(*i))))
+            .count();
+        assert!(false_positives < 200, "{} false positives", false_positives);
+    }
+
     #[test]
     fn dedupe_match_field_name_matters() {
         let transform = make_match_transform(5, vec!["matched1".into(), "matched2".into()]);
@@ -1,6 +1,7 @@
 use std::time::{Duration, Instant};
 
 use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
 
 use crate::{
     conditions::{AnyCondition, Condition},
@@ -14,6 +15,8 @@ use crate::{
     transforms::{FunctionTransform, OutputBuffer, Transform},
 };
 
+const DROPPED: &str = "dropped";
+
 /// Configuration for the `filter` transform.
 #[configurable_component(transform)]
 #[derive(Clone, Debug)]
@@ -21,11 +24,21 @@ use crate::{
 pub struct FilterConfig {
     #[configurable(derived)]
     condition: AnyCondition,
+
+    /// Reroutes the events not matching the condition to a named output instead of dropping them.
+    ///
+    /// When set, the events not matching the condition are forwarded, unmodified, to the `dropped` output. This allows
+    /// the discarded events to be archived or audited.
+    #[serde(default)]
+    reroute_dropped: bool,
 }
 
 impl From<AnyCondition> for FilterConfig {
     fn from(condition: AnyCondition) -> Self {
-        Self { condition }
+        Self {
+            condition,
+            reroute_dropped: false,
+        }
     }
 }
 
@@ -47,9 +60,12 @@ impl GenerateConfig for FilterConfig {
 #[typetag::serde(name = "filter")]
 impl TransformConfig for FilterConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(Filter::new(
-            self.condition.build(&context.enrichment_tables)?,
-        )))
+        let filter = Filter::new(self.condition.build(&context.enrichment_tables)?);
+        Ok(if self.reroute_dropped {
+            Transform::synchronous(RerouteDropped(filter))
+        } else {
+            Transform::function(filter)
+        })
     }
 
     fn input(&self) -> Input {
@@ -57,7 +73,14 @@ impl TransformConfig for FilterConfig {
     }
 
     fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
-        vec![Output::default(DataType::all())]
+        if self.reroute_dropped {
+            vec![
+                Output::default(DataType::all()),
+                Output::default(DataType::all()).with_port(DROPPED),
+            ]
+        } else {
+            vec![Output::default(DataType::all())]
+        }
     }
 
     fn enable_concurrency(&self) -> bool {
@@ -107,10 +130,28 @@ impl FunctionTransform for Filter {
     }
 }
 
+/// A filter forwarding the events not matching its condition to the `dropped` output.
+#[derive(Clone, Debug)]
+struct RerouteDropped(Filter);
+
+impl SyncTransform for RerouteDropped {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let (result, event) = self.0.condition.check(event);
+        if result {
+            output.push(event);
+        } else {
+            output.push_named(DROPPED, event);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::event::{Event, LogEvent};
+    use crate::event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent,
+    };
     use crate::{conditions::Condition, transforms::test::transform_one};
 
     #[test]
@@ -126,4 +167,31 @@ mod test {
         let result = transform_one(&mut filter, event).unwrap();
         assert_eq!(result.metadata(), &metadata);
     }
+
+    #[test]
+    fn reroutes_dropped_events() {
+        let mut filter = RerouteDropped(Filter::new(Condition::IsLog));
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::all()),
+                Output::default(DataType::all()).with_port(DROPPED),
+            ],
+            2,
+        );
+
+        let log = Event::from(LogEvent::from("message"));
+        let metric = Event::from(Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        ));
+        filter.transform(log.clone(), &mut outputs);
+        filter.transform(metric.clone(), &mut outputs);
+
+        assert_eq!(outputs.drain().collect::<Vec<_>>(), vec![log]);
+        assert_eq!(
+            outputs.drain_named(DROPPED).collect::<Vec<_>>(),
+            vec![metric]
+        );
+    }
 }
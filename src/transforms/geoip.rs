@@ -1,6 +1,15 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    str::FromStr,
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
 
+use arc_swap::ArcSwap;
+use indexmap::IndexMap;
 use serde::Serialize;
+use snafu::Snafu;
 use vector_config::configurable_component;
 
 use crate::{
@@ -9,7 +18,10 @@ use crate::{
         TransformDescription,
     },
     event::Event,
-    internal_events::{GeoipIpAddressParseError, ParserMissingFieldError},
+    internal_events::{
+        GeoipDatabaseReloadError, GeoipDatabaseReloaded, GeoipIpAddressParseError,
+        ParserMissingFieldError,
+    },
     schema,
     transforms::{FunctionTransform, OutputBuffer, Transform},
     Result,
@@ -22,14 +34,33 @@ use crate::{
 pub struct GeoipConfig {
     /// The field name that contains the IP address.
     ///
-    /// This field should contain a valid IPv4 or IPv6 address.
-    pub source: String,
+    /// This field should contain a valid IPv4 or IPv6 address. Its GeoIP data is inserted into `target`. Either
+    /// `source` or `fields` must be set.
+    pub source: Option<String>,
+
+    /// The IP fields to enrich, in addition to `source`, mapped to the field to insert their GeoIP data into.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub fields: IndexMap<String, String>,
 
     /// Path to the [MaxMind GeoIP2](https://dev.maxmind.com/geoip/geoip2/downloadable) or [GeoLite2 binary city
     /// database file](https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access) (**GeoLite2-City.mmdb**).
     ///
-    /// Other databases, such as the country database, are not supported.
-    pub database: String,
+    /// ASN, ISP, and Connection-Type databases are also supported, as well as the MMDB databases of IPinfo. Other
+    /// databases, such as the country database, are not supported.
+    pub database: Option<String>,
+
+    /// Paths to additional databases, looked up along with `database`.
+    ///
+    /// The GeoIP data of all the databases is merged, so that, for example, a City and an ASN database can be used
+    /// together. When several databases provide the same field, the value of the last one is kept.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub databases: Vec<String>,
 
     /// The default field to insert the resulting GeoIP data into.
     ///
@@ -47,17 +78,32 @@ pub struct GeoipConfig {
     /// [here](https://support.maxmind.com/hc/en-us/articles/4414877149467-IP-Geolocation-Data#h_01FRRGRYTGZB29ERDBZCX3MR8Q).
     #[serde(default = "default_locale")]
     pub locale: String,
+
+    /// The interval between the checks for changes of the databases, in seconds.
+    ///
+    /// When a database file is modified, it's reloaded, and the lookups use the previous version of the database until
+    /// the new one is loaded. By default, the databases are not reloaded.
+    pub reload_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one of `database` or `databases` must be set"))]
+    MissingDatabase,
+    #[snafu(display("At least one of `source` or `fields` must be set"))]
+    MissingSource,
+    #[snafu(display("`reload_interval_secs` must be greater than zero"))]
+    ZeroReloadInterval,
 }
 
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
 pub struct Geoip {
     #[derivative(Debug = "ignore")]
-    pub dbreader: Arc<maxminddb::Reader<Vec<u8>>>,
-    pub database: String,
-    pub source: String,
-    pub target: String,
-    pub locale: String,
+    databases: Arc<ArcSwap<Vec<Arc<Database>>>>,
+    /// The IP fields, with the field to insert their GeoIP data into.
+    fields: Vec<(String, String)>,
+    locale: String,
 }
 
 fn default_geoip_target_field() -> String {
@@ -80,10 +126,13 @@ inventory::submit! {
 impl GenerateConfig for GeoipConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            database: "/path/to/GeoLite2-City.mmdb".to_string(),
-            source: "ip address".to_owned(),
+            database: Some("/path/to/GeoLite2-City.mmdb".to_string()),
+            databases: Vec::new(),
+            source: Some("ip address".to_owned()),
+            fields: IndexMap::new(),
             target: default_geoip_target_field(),
             locale: "en".to_owned(),
+            reload_interval_secs: None,
         })
         .unwrap()
     }
@@ -93,12 +142,14 @@ impl GenerateConfig for GeoipConfig {
 #[typetag::serde(name = "geoip")]
 impl TransformConfig for GeoipConfig {
     async fn build(&self, _context: &TransformContext) -> Result<Transform> {
-        Ok(Transform::function(Geoip::new(
-            self.database.clone(),
-            self.source.clone(),
-            self.target.clone(),
-            self.locale.clone(),
-        )?))
+        let geoip = Geoip::new(self)?;
+        if let Some(reload_interval_secs) = self.reload_interval_secs {
+            if reload_interval_secs == 0 {
+                return Err(Box::new(BuildError::ZeroReloadInterval));
+            }
+            geoip.spawn_reload(Duration::from_secs(reload_interval_secs));
+        }
+        Ok(Transform::function(geoip))
     }
 
     fn input(&self) -> Input {
@@ -116,32 +167,317 @@ impl TransformConfig for GeoipConfig {
 
 // MaxMind GeoIP database files have a type field we can use to recognize specific
 // products. If we encounter one of these two types, we look for ASN/ISP information;
-// otherwise we expect to be working with a City database.
+// if we encounter a Connection-Type or an IPinfo database, we look for its own
+// information; otherwise we expect to be working with a City database.
 const ASN_DATABASE_TYPE: &str = "GeoLite2-ASN";
 const ISP_DATABASE_TYPE: &str = "GeoIP2-ISP";
+const CONNECTION_TYPE_DATABASE_TYPE: &str = "GeoIP2-Connection-Type";
+const IPINFO_DATABASE_TYPE_PREFIX: &str = "ipinfo";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DatabaseKind {
+    City,
+    Isp,
+    ConnectionType,
+    Ipinfo,
+}
+
+impl DatabaseKind {
+    fn of(database_type: &str) -> Self {
+        match database_type {
+            ASN_DATABASE_TYPE | ISP_DATABASE_TYPE => Self::Isp,
+            CONNECTION_TYPE_DATABASE_TYPE => Self::ConnectionType,
+            _ if database_type.starts_with(IPINFO_DATABASE_TYPE_PREFIX) => Self::Ipinfo,
+            _ => Self::City,
+        }
+    }
+}
+
+/// A database file, with the time it was modified when it was loaded.
+struct Database {
+    path: String,
+    kind: DatabaseKind,
+    reader: maxminddb::Reader<Vec<u8>>,
+    modified: Option<SystemTime>,
+}
+
+impl Database {
+    fn open(path: &str) -> crate::Result<Self> {
+        let modified = modified(path);
+        let reader = maxminddb::Reader::open_readfile(path)?;
+        Ok(Self {
+            path: path.to_owned(),
+            kind: DatabaseKind::of(&reader.metadata.database_type),
+            reader,
+            modified,
+        })
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
 
 impl Geoip {
-    pub fn new(
-        database: String,
-        source: String,
-        target: String,
-        locale: String,
-    ) -> crate::Result<Self> {
+    pub fn new(config: &GeoipConfig) -> crate::Result<Self> {
+        let paths: Vec<&String> = config.database.iter().chain(&config.databases).collect();
+        if paths.is_empty() {
+            return Err(Box::new(BuildError::MissingDatabase));
+        }
+        let fields: Vec<(String, String)> = config
+            .source
+            .iter()
+            .map(|source| (source.clone(), config.target.clone()))
+            .chain(config.fields.clone())
+            .collect();
+        if fields.is_empty() {
+            return Err(Box::new(BuildError::MissingSource));
+        }
+
+        let databases = paths
+            .into_iter()
+            .map(|path| Database::open(path).map(Arc::new))
+            .collect::<crate::Result<Vec<_>>>()?;
         Ok(Geoip {
-            dbreader: Arc::new(maxminddb::Reader::open_readfile(database.clone())?),
-            database,
-            source,
-            target,
-            locale,
+            databases: Arc::new(ArcSwap::from_pointee(databases)),
+            fields,
+            locale: config.locale.clone(),
+        })
+    }
+
+    /// Reloads the databases periodically, until the transform is dropped.
+    fn spawn_reload(&self, interval: Duration) {
+        let databases = Arc::downgrade(&self.databases);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match Weak::upgrade(&databases) {
+                    Some(databases) => reload(&databases),
+                    None => break,
+                }
+            }
+        });
+    }
+
+    /// Looks an IP address up in all the databases, returning its merged GeoIP data.
+    ///
+    /// Without an IP address, the fields of the MaxMind databases are returned with their default values.
+    fn lookup(
+        &self,
+        databases: &[Arc<Database>],
+        ip: Option<IpAddr>,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut data = serde_json::Map::new();
+        for database in databases {
+            let json_value = match database.kind {
+                DatabaseKind::City => serde_json::to_value(self.lookup_city(database, ip)),
+                DatabaseKind::Isp => serde_json::to_value(lookup_isp(database, ip)),
+                DatabaseKind::ConnectionType => {
+                    serde_json::to_value(lookup_connection_type(database, ip))
+                }
+                DatabaseKind::Ipinfo => Ok(lookup_ipinfo(database, ip)),
+            };
+            if let Ok(serde_json::Value::Object(fields)) = json_value {
+                data.extend(fields);
+            }
+        }
+        data
+    }
+
+    fn lookup_city<'a>(&self, database: &'a Database, ip: Option<IpAddr>) -> City<'a> {
+        let mut city: City = Default::default();
+        if let Some(data) =
+            ip.and_then(|ip| database.reader.lookup::<maxminddb::geoip2::City>(ip).ok())
+        {
+            if let Some(city_names) = data.city.and_then(|c| c.names) {
+                if let Some(city_name) = city_names.get("en") {
+                    city.city_name = city_name;
+                }
+            }
+
+            if let Some(continent_code) = data.continent.and_then(|c| c.code) {
+                city.continent_code = continent_code;
+            }
+
+            if let Some(country) = data.country {
+                if let Some(country_code) = country.iso_code {
+                    city.country_code = country_code;
+                }
+                if let Some(country_name) = country
+                    .names
+                    .as_ref()
+                    .and_then(|names| names.get(&*self.locale))
+                {
+                    city.country_name = country_name;
+                }
+            }
+
+            if let Some(location) = data.location {
+                if let Some(time_zone) = location.time_zone {
+                    city.timezone = time_zone;
+                }
+                if let Some(latitude) = location.latitude {
+                    city.latitude = latitude.to_string();
+                }
+
+                if let Some(longitude) = location.longitude {
+                    city.longitude = longitude.to_string();
+                }
+
+                if let Some(metro_code) = location.metro_code {
+                    city.metro_code = metro_code.to_string();
+                }
+            }
+
+            // last subdivision is most specific per https://github.com/maxmind/GeoIP2-java/blob/39385c6ce645374039450f57208b886cf87ade47/src/main/java/com/maxmind/geoip2/model/AbstractCityResponse.java#L96-L107
+            if let Some(subdivision) = data.subdivisions.as_ref().and_then(|s| s.last()) {
+                if let Some(name) = subdivision
+                    .names
+                    .as_ref()
+                    .and_then(|names| names.get(&*self.locale))
+                {
+                    city.region_name = name;
+                }
+
+                if let Some(iso_code) = subdivision.iso_code {
+                    city.region_code = iso_code
+                }
+            }
+
+            if let Some(postal_code) = data.postal.and_then(|p| p.code) {
+                city.postal_code = postal_code;
+            }
+        }
+        city
+    }
+}
+
+/// Reopens the databases modified since they were loaded, keeping the previous version of those failing to load.
+fn reload(databases: &ArcSwap<Vec<Arc<Database>>>) {
+    let current = databases.load();
+    if current
+        .iter()
+        .all(|database| modified(&database.path) == database.modified)
+    {
+        return;
+    }
+
+    let reloaded = current
+        .iter()
+        .map(|database| {
+            if modified(&database.path) == database.modified {
+                return Arc::clone(database);
+            }
+            match Database::open(&database.path) {
+                Ok(reloaded) => {
+                    emit!(GeoipDatabaseReloaded {
+                        path: &database.path
+                    });
+                    Arc::new(reloaded)
+                }
+                Err(error) => {
+                    emit!(GeoipDatabaseReloadError {
+                        path: &database.path,
+                        error,
+                    });
+                    Arc::clone(database)
+                }
+            }
         })
+        .collect();
+    databases.store(Arc::new(reloaded));
+}
+
+fn lookup_isp(database: &Database, ip: Option<IpAddr>) -> Isp<'_> {
+    let mut isp: Isp = Default::default();
+    if let Some(data) = ip.and_then(|ip| database.reader.lookup::<maxminddb::geoip2::Isp>(ip).ok())
+    {
+        if let Some(as_number) = data.autonomous_system_number {
+            isp.autonomous_system_number = as_number as i64;
+        }
+        if let Some(as_organization) = data.autonomous_system_organization {
+            isp.autonomous_system_organization = as_organization;
+        }
+        if let Some(isp_name) = data.isp {
+            isp.isp = isp_name;
+        }
+        if let Some(organization) = data.organization {
+            isp.organization = organization;
+        }
+    }
+    isp
+}
+
+fn lookup_connection_type(database: &Database, ip: Option<IpAddr>) -> ConnectionType<'_> {
+    let mut connection_type: ConnectionType = Default::default();
+    if let Some(data) = ip.and_then(|ip| {
+        database
+            .reader
+            .lookup::<maxminddb::geoip2::ConnectionType>(ip)
+            .ok()
+    }) {
+        if let Some(name) = data.connection_type {
+            connection_type.connection_type = name;
+        }
     }
+    connection_type
+}
 
-    fn has_isp_db(&self) -> bool {
-        self.dbreader.metadata.database_type == ASN_DATABASE_TYPE
-            || self.dbreader.metadata.database_type == ISP_DATABASE_TYPE
+/// The fields of the IPinfo databases, and the fields of the MaxMind databases they match.
+const IPINFO_FIELDS: &[(&str, &str)] = &[
+    ("city", "city_name"),
+    ("region", "region_name"),
+    ("country", "country_code"),
+    ("continent", "continent_code"),
+    ("postal_code", "postal_code"),
+    ("timezone", "timezone"),
+    ("name", "autonomous_system_organization"),
+    ("as_name", "autonomous_system_organization"),
+];
+
+fn lookup_ipinfo(database: &Database, ip: Option<IpAddr>) -> serde_json::Value {
+    // The fields of the records depend on the database, so they are decoded as they are.
+    match ip.and_then(|ip| {
+        database
+            .reader
+            .lookup::<BTreeMap<String, serde_json::Value>>(ip)
+            .ok()
+    }) {
+        Some(record) => ipinfo_fields(record),
+        None => serde_json::Value::Object(serde_json::Map::new()),
     }
 }
 
+/// Renames the fields of an IPinfo record to those of the MaxMind databases.
+///
+/// Fields without a MaxMind equivalent are kept as they are.
+fn ipinfo_fields(record: BTreeMap<String, serde_json::Value>) -> serde_json::Value {
+    let mut fields = serde_json::Map::new();
+    for (key, value) in record {
+        if key == "asn" {
+            // IPinfo prefixes the numbers of the autonomous systems, such as `AS15169`.
+            if let Some(number) = value
+                .as_str()
+                .and_then(|asn| asn.trim_start_matches("AS").parse::<i64>().ok())
+            {
+                fields.insert("autonomous_system_number".to_owned(), number.into());
+            }
+            continue;
+        }
+        let key = IPINFO_FIELDS
+            .iter()
+            .find(|(ipinfo, _)| *ipinfo == key)
+            .map_or(key.as_str(), |(_, maxmind)| maxmind)
+            .to_owned();
+        fields.insert(key, value);
+    }
+    serde_json::Value::Object(fields)
+}
+
 #[derive(Default, Serialize)]
 struct Isp<'a> {
     autonomous_system_number: i64,
@@ -165,115 +501,40 @@ struct City<'a> {
     metro_code: String, // converted from u16 for consistency
 }
 
+#[derive(Default, Serialize)]
+struct ConnectionType<'a> {
+    connection_type: &'a str,
+}
+
 impl FunctionTransform for Geoip {
     fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
-        let mut isp: Isp = Default::default();
-        let mut city: City = Default::default();
-        let target_field = self.target.clone();
-        let ipaddress = event
-            .as_log()
-            .get(self.source.as_str())
-            .map(|s| s.to_string_lossy());
-        if let Some(ipaddress) = &ipaddress {
-            match FromStr::from_str(ipaddress) {
-                Ok(ip) => {
-                    if self.has_isp_db() {
-                        if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::Isp>(ip) {
-                            if let Some(as_number) = data.autonomous_system_number {
-                                isp.autonomous_system_number = as_number as i64;
-                            }
-                            if let Some(as_organization) = data.autonomous_system_organization {
-                                isp.autonomous_system_organization = as_organization;
-                            }
-                            if let Some(isp_name) = data.isp {
-                                isp.isp = isp_name;
-                            }
-                            if let Some(organization) = data.organization {
-                                isp.organization = organization;
-                            }
-                        }
-                    } else if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::City>(ip) {
-                        if let Some(city_names) = data.city.and_then(|c| c.names) {
-                            if let Some(city_name) = city_names.get("en") {
-                                city.city_name = city_name;
-                            }
-                        }
-
-                        if let Some(continent_code) = data.continent.and_then(|c| c.code) {
-                            city.continent_code = continent_code;
-                        }
-
-                        if let Some(country) = data.country {
-                            if let Some(country_code) = country.iso_code {
-                                city.country_code = country_code;
-                            }
-                            if let Some(country_name) = country
-                                .names
-                                .as_ref()
-                                .and_then(|names| names.get(&*self.locale))
-                            {
-                                city.country_name = country_name;
-                            }
-                        }
-
-                        if let Some(location) = data.location {
-                            if let Some(time_zone) = location.time_zone {
-                                city.timezone = time_zone;
-                            }
-                            if let Some(latitude) = location.latitude {
-                                city.latitude = latitude.to_string();
-                            }
-
-                            if let Some(longitude) = location.longitude {
-                                city.longitude = longitude.to_string();
-                            }
-
-                            if let Some(metro_code) = location.metro_code {
-                                city.metro_code = metro_code.to_string();
-                            }
-                        }
-
-                        // last subdivision is most specific per https://github.com/maxmind/GeoIP2-java/blob/39385c6ce645374039450f57208b886cf87ade47/src/main/java/com/maxmind/geoip2/model/AbstractCityResponse.java#L96-L107
-                        if let Some(subdivision) = data.subdivisions.as_ref().and_then(|s| s.last())
-                        {
-                            if let Some(name) = subdivision
-                                .names
-                                .as_ref()
-                                .and_then(|names| names.get(&*self.locale))
-                            {
-                                city.region_name = name;
-                            }
-
-                            if let Some(iso_code) = subdivision.iso_code {
-                                city.region_code = iso_code
-                            }
-                        }
-
-                        if let Some(postal_code) = data.postal.and_then(|p| p.code) {
-                            city.postal_code = postal_code;
-                        }
+        // The databases are loaded once per event, so that a reload doesn't interrupt its lookups.
+        let databases = self.databases.load();
+        for (source, target) in &self.fields {
+            let ipaddress = event
+                .as_log()
+                .get(source.as_str())
+                .map(|s| s.to_string_lossy());
+            let ip = match &ipaddress {
+                Some(ipaddress) => match FromStr::from_str(ipaddress) {
+                    Ok(ip) => Some(ip),
+                    Err(error) => {
+                        emit!(GeoipIpAddressParseError {
+                            error,
+                            address: ipaddress
+                        });
+                        None
                     }
+                },
+                None => {
+                    emit!(ParserMissingFieldError { field: source });
+                    None
                 }
-                Err(error) => {
-                    emit!(GeoipIpAddressParseError {
-                        error,
-                        address: ipaddress
-                    });
-                }
-            }
-        } else {
-            emit!(ParserMissingFieldError {
-                field: &self.source
-            });
-        };
-
-        let json_value = if self.has_isp_db() {
-            serde_json::to_value(isp)
-        } else {
-            serde_json::to_value(city)
-        };
-        if let Ok(json_value) = json_value {
-            event.as_mut_log().insert(target_field.as_str(), json_value);
+            };
+            let data = self.lookup(&databases, ip);
+            event
+                .as_mut_log()
+                .insert(target.as_str(), serde_json::Value::Object(data));
         }
 
         output.push(event);
@@ -468,14 +729,93 @@ mod tests {
         }
     }
 
+    fn config(databases: &[&str]) -> GeoipConfig {
+        GeoipConfig {
+            source: Some("remote_addr".to_string()),
+            fields: IndexMap::new(),
+            database: None,
+            databases: databases.iter().map(|path| path.to_string()).collect(),
+            target: "geo".to_string(),
+            locale: "en".to_string(),
+            reload_interval_secs: None,
+        }
+    }
+
     fn parse_one(event: Event, database: &str) -> Event {
-        let mut augment = Geoip::new(
-            database.to_string(),
-            "remote_addr".to_string(),
-            "geo".to_string(),
-            "en".to_string(),
-        )
-        .unwrap();
+        let mut augment = Geoip::new(&config(&[database])).unwrap();
         transform_one(&mut augment, event).unwrap()
     }
+
+    #[test]
+    fn geoip_multiple_databases_and_fields() {
+        let mut log = LogEvent::default();
+        let _ = log.insert("remote_addr", "2.125.160.216");
+        let _ = log.insert("forwarded_for", "2600:7000::1");
+
+        let mut config = config(&[
+            "tests/data/GeoIP2-City-Test.mmdb",
+            "tests/data/GeoLite2-ASN-Test.mmdb",
+        ]);
+        config
+            .fields
+            .insert("forwarded_for".to_string(), "forwarded_geo".to_string());
+        let mut augment = Geoip::new(&config).unwrap();
+        let new_event = transform_one(&mut augment, log.into()).unwrap();
+        let log = new_event.as_log();
+
+        assert_eq!(log["geo.city_name"], "Boxford".into());
+        assert_eq!(log["geo.autonomous_system_number"], 0.into());
+        assert_eq!(log["forwarded_geo.autonomous_system_number"], 6939.into());
+        assert_eq!(
+            log["forwarded_geo.autonomous_system_organization"],
+            "Hurricane Electric, Inc.".into()
+        );
+    }
+
+    #[test]
+    fn geoip_requires_database_and_source() {
+        assert!(Geoip::new(&config(&[])).is_err());
+
+        let mut config = config(&["tests/data/GeoIP2-City-Test.mmdb"]);
+        config.source = None;
+        assert!(Geoip::new(&config).is_err());
+    }
+
+    #[test]
+    fn geoip_reloads_modified_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GeoIP.mmdb");
+        std::fs::copy("tests/data/GeoLite2-ASN-Test.mmdb", &path).unwrap();
+        let augment = Geoip::new(&config(&[path.to_str().unwrap()])).unwrap();
+        assert_eq!(augment.databases.load()[0].kind, DatabaseKind::Isp);
+
+        // The modification time is only compared for equality, so it's moved back rather than waiting for it to change.
+        let mut database = Database::open(path.to_str().unwrap()).unwrap();
+        database.modified = Some(SystemTime::UNIX_EPOCH);
+        augment.databases.store(Arc::new(vec![Arc::new(database)]));
+        std::fs::copy("tests/data/GeoIP2-City-Test.mmdb", &path).unwrap();
+
+        reload(&augment.databases);
+        assert_eq!(augment.databases.load()[0].kind, DatabaseKind::City);
+    }
+
+    #[test]
+    fn geoip_renames_ipinfo_fields() {
+        let record = BTreeMap::from([
+            ("asn".to_owned(), serde_json::json!("AS15169")),
+            ("as_name".to_owned(), serde_json::json!("Google LLC")),
+            ("country".to_owned(), serde_json::json!("US")),
+            ("as_domain".to_owned(), serde_json::json!("google.com")),
+        ]);
+
+        assert_eq!(
+            ipinfo_fields(record),
+            serde_json::json!({
+                "autonomous_system_number": 15169,
+                "autonomous_system_organization": "Google LLC",
+                "country_code": "US",
+                "as_domain": "google.com",
+            })
+        );
+    }
 }
@@ -0,0 +1,561 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use vector_config::configurable_component;
+use vector_core::{
+    config::{DataType, Input, Output},
+    event::{Event, Value},
+    transform::{
+        FunctionTransform, OutputBuffer, SyncTransform, TaskTransform, Transform, TransformConfig,
+        TransformContext, TransformOutputsBuf,
+    },
+};
+
+use crate::{
+    internal_events::{JoinUnmatchedEventDiscarded, TemplateRenderingError},
+    schema,
+    template::Template,
+};
+
+/// The key of the metadata holding the side of an event, until it's matched.
+const SIDE_KEY: &str = "join_side";
+
+const UNMATCHED_LEFT_OUTPUT: &str = "unmatched_left";
+const UNMATCHED_RIGHT_OUTPUT: &str = "unmatched_right";
+
+/// A side of a join.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum Side {
+    /// The left side.
+    Left,
+
+    /// The right side.
+    Right,
+}
+
+impl Side {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    const fn other(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Reads and removes the side of an event.
+    fn take(event: &mut Event) -> Option<Self> {
+        let metadata = event.metadata_mut().value_mut().as_object_mut()?;
+        match metadata.remove(SIDE_KEY)? {
+            Value::Bytes(side) if side == "left" => Some(Self::Left),
+            Value::Bytes(side) if side == "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    fn insert_into(self, event: &mut Event) {
+        if let Some(metadata) = event.metadata_mut().value_mut().as_object_mut() {
+            metadata.insert(SIDE_KEY.to_owned(), self.as_str().into());
+        }
+    }
+}
+
+/// Marks the events of one side of a join.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub(super) struct TagSideConfig {
+    /// The side of the events.
+    side: Side,
+}
+
+impl TagSideConfig {
+    pub(super) const fn new(side: Side) -> Self {
+        Self { side }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join_side")]
+impl TransformConfig for TagSideConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(TagSide { side: self.side }))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join_side"
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+struct TagSide {
+    side: Side,
+}
+
+impl FunctionTransform for TagSide {
+    fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
+        self.side.insert_into(&mut event);
+        output.push(event);
+    }
+}
+
+/// Buffers the events of both sides of a join until they are matched, or their window ends.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub(super) struct MatcherConfig {
+    /// The key of the events of the left side.
+    left_key: Template,
+
+    /// The key of the events of the right side.
+    right_key: Template,
+
+    /// How long an event waits for an event of the other side, in seconds.
+    window_secs: u64,
+
+    /// Whether to keep the unmatched events.
+    emit_unmatched: bool,
+}
+
+impl MatcherConfig {
+    pub(super) const fn new(
+        left_key: Template,
+        right_key: Template,
+        window_secs: u64,
+        emit_unmatched: bool,
+    ) -> Self {
+        Self {
+            left_key,
+            right_key,
+            window_secs,
+            emit_unmatched,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join_matcher")]
+impl TransformConfig for MatcherConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::event_task(Matcher::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join_matcher"
+    }
+}
+
+struct Matcher {
+    config: MatcherConfig,
+    window: Duration,
+
+    /// The unmatched events of each side and key, in the order they were received.
+    buffered: HashMap<(Side, String), VecDeque<(Instant, Event)>>,
+
+    /// The side and key of the buffered events, in the order they were received.
+    received: VecDeque<(Instant, Side, String)>,
+}
+
+impl Matcher {
+    fn new(config: &MatcherConfig) -> Self {
+        Self {
+            config: config.clone(),
+            window: Duration::from_secs(config.window_secs),
+            buffered: HashMap::new(),
+            received: VecDeque::new(),
+        }
+    }
+
+    fn key(&self, side: Side, event: &Event) -> Option<String> {
+        let template = match side {
+            Side::Left => &self.config.left_key,
+            Side::Right => &self.config.right_key,
+        };
+        template
+            .render_string(event)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some(match side {
+                        Side::Left => "left.key",
+                        Side::Right => "right.key",
+                    }),
+                    drop_event: false,
+                })
+            })
+            .ok()
+    }
+
+    fn record(&mut self, mut event: Event, now: Instant, output: &mut Vec<Event>) {
+        // Events whose window ended must not be matched, even when they weren't flushed yet.
+        self.flush_expired_into(now, output);
+
+        let side = match Side::take(&mut event) {
+            Some(side) => side,
+            // Events reach the matcher through the transforms marking their side.
+            None => return,
+        };
+        let key = match self.key(side, &event) {
+            Some(key) => key,
+            None => return unmatched(self.config.emit_unmatched, side, event, output),
+        };
+
+        let other = (side.other(), key);
+        if let Some(events) = self.buffered.get_mut(&other) {
+            if let Some((_, other_event)) = events.pop_front() {
+                if events.is_empty() {
+                    self.buffered.remove(&other);
+                }
+                let (left, right) = match side {
+                    Side::Left => (event, other_event),
+                    Side::Right => (other_event, event),
+                };
+                output.push(merge(left, right));
+                return;
+            }
+        }
+
+        let (_, key) = other;
+        self.received.push_back((now, side, key.clone()));
+        self.buffered
+            .entry((side, key))
+            .or_default()
+            .push_back((now, event));
+    }
+
+    /// Flushes the buffered events whose window ended by `now`.
+    fn flush_expired_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        let (window, emit_unmatched) = (self.window, self.config.emit_unmatched);
+        while let Some((received_at, _, _)) = self.received.front() {
+            if *received_at + window > now {
+                break;
+            }
+            let (_, side, key) = self.received.pop_front().expect("front exists");
+            let entry = (side, key);
+            // The events of a key are matched in the order they were received, so its expired
+            // events are at the front, unless they were matched already.
+            if let Some(events) = self.buffered.get_mut(&entry) {
+                while events
+                    .front()
+                    .map_or(false, |(received_at, _)| *received_at + window <= now)
+                {
+                    let (_, event) = events.pop_front().expect("front exists");
+                    unmatched(emit_unmatched, side, event, output);
+                }
+                if events.is_empty() {
+                    self.buffered.remove(&entry);
+                }
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        self.received.clear();
+        for ((side, _), events) in std::mem::take(&mut self.buffered) {
+            for (_, event) in events {
+                unmatched(self.config.emit_unmatched, side, event, output);
+            }
+        }
+    }
+}
+
+fn unmatched(emit_unmatched: bool, side: Side, mut event: Event, output: &mut Vec<Event>) {
+    if emit_unmatched {
+        side.insert_into(&mut event);
+        output.push(event);
+    } else {
+        emit!(JoinUnmatchedEventDiscarded {
+            side: side.as_str()
+        });
+    }
+}
+
+/// Merges the fields of the right event into the left event, keeping the fields of the left
+/// event on conflicts.
+fn merge(left: Event, right: Event) -> Event {
+    let mut left = left.into_log();
+    let (value, metadata) = right.into_log().into_parts();
+    left.metadata_mut().merge(metadata);
+    if let (Some(fields), Value::Object(right_fields)) = (left.as_map_mut(), value) {
+        for (name, value) in right_fields {
+            fields.entry(name).or_insert(value);
+        }
+    }
+    left.into()
+}
+
+impl TaskTransform<Event> for Matcher {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(
+            stream! {
+                loop {
+                    let mut output = Vec::new();
+                    let done = tokio::select! {
+                        _ = flush_stream.tick() => {
+                            self.flush_expired_into(Instant::now(), &mut output);
+                            false
+                        }
+                        maybe_event = input_rx.next() => {
+                            match maybe_event {
+                                None => {
+                                    self.flush_all_into(&mut output);
+                                    true
+                                }
+                                Some(event) => {
+                                    self.record(event, Instant::now(), &mut output);
+                                    false
+                                }
+                            }
+                        }
+                    };
+                    yield stream::iter(output.into_iter());
+                    if done { break }
+                }
+            }
+            .flatten(),
+        )
+    }
+}
+
+/// Sends the matched events to the default output, and the unmatched events to the output of
+/// their side.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub(super) struct SplitConfig {
+    /// Whether to declare the outputs of unmatched events.
+    emit_unmatched: bool,
+}
+
+impl SplitConfig {
+    pub(super) const fn new(emit_unmatched: bool) -> Self {
+        Self { emit_unmatched }
+    }
+}
+
+/// The outputs of a join, which only has outputs for unmatched events when they are emitted.
+pub(super) fn join_outputs(emit_unmatched: bool) -> Vec<Output> {
+    let mut outputs = vec![Output::default(DataType::Log)];
+    if emit_unmatched {
+        outputs.push(Output::default(DataType::Log).with_port(UNMATCHED_LEFT_OUTPUT));
+        outputs.push(Output::default(DataType::Log).with_port(UNMATCHED_RIGHT_OUTPUT));
+    }
+    outputs
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join_split")]
+impl TransformConfig for SplitConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(Split))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        join_outputs(self.emit_unmatched)
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join"
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+struct Split;
+
+impl SyncTransform for Split {
+    fn transform(&mut self, mut event: Event, output: &mut TransformOutputsBuf) {
+        match Side::take(&mut event) {
+            None => output.push(event),
+            Some(Side::Left) => output.push_named(UNMATCHED_LEFT_OUTPUT, event),
+            Some(Side::Right) => output.push_named(UNMATCHED_RIGHT_OUTPUT, event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn matcher(window_secs: u64, emit_unmatched: bool) -> Matcher {
+        Matcher::new(&MatcherConfig::new(
+            Template::try_from("{{ request_id }}").unwrap(),
+            Template::try_from("{{ id }}").unwrap(),
+            window_secs,
+            emit_unmatched,
+        ))
+    }
+
+    fn make_event(side: Side, key_field: &str, key: &str, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert(key_field, key);
+        log.insert(side.as_str(), true);
+        let mut event = Event::from(log);
+        side.insert_into(&mut event);
+        event
+    }
+
+    fn left(key: &str, message: &str) -> Event {
+        make_event(Side::Left, "request_id", key, message)
+    }
+
+    fn right(key: &str, message: &str) -> Event {
+        make_event(Side::Right, "id", key, message)
+    }
+
+    #[test]
+    fn joins_events_with_the_same_key() {
+        let mut matcher = matcher(10, false);
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        matcher.record(right("1", "authenticated"), now, &mut output);
+        matcher.record(left("2", "GET /orders"), now, &mut output);
+        assert!(output.is_empty());
+
+        matcher.record(left("1", "GET /users"), now, &mut output);
+        assert_eq!(output.len(), 1);
+        let joined = output.remove(0);
+        assert!(joined.metadata().value().get(SIDE_KEY).is_none());
+        let joined = joined.into_log();
+        assert_eq!(joined["message"], "GET /users".into());
+        assert_eq!(joined["request_id"], "1".into());
+        assert_eq!(joined["id"], "1".into());
+        assert_eq!(joined["left"], true.into());
+        assert_eq!(joined["right"], true.into());
+
+        assert_eq!(matcher.buffered.len(), 1);
+    }
+
+    #[test]
+    fn matches_each_event_once() {
+        let mut matcher = matcher(10, false);
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        matcher.record(right("1", "first"), now, &mut output);
+        matcher.record(right("1", "second"), now, &mut output);
+        matcher.record(left("1", "request"), now, &mut output);
+        matcher.record(left("1", "retry"), now, &mut output);
+
+        let messages = output
+            .into_iter()
+            .map(|event| event.into_log()["message"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["request".into(), "retry".into()]);
+        assert!(matcher.buffered.is_empty());
+    }
+
+    #[test]
+    fn flushes_unmatched_events_after_their_window() {
+        let mut matcher = matcher(10, true);
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        matcher.record(left("1", "GET /users"), now, &mut output);
+        matcher.record(
+            right("2", "authenticated"),
+            now + Duration::from_secs(5),
+            &mut output,
+        );
+        matcher.flush_expired_into(now + Duration::from_secs(9), &mut output);
+        assert!(output.is_empty());
+
+        matcher.flush_expired_into(now + Duration::from_secs(10), &mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(Side::take(&mut output[0]), Some(Side::Left));
+
+        // Matched events aren't flushed when their window ends.
+        matcher.record(
+            left("2", "GET /orders"),
+            now + Duration::from_secs(11),
+            &mut output,
+        );
+        assert_eq!(output.len(), 2);
+        matcher.flush_expired_into(now + Duration::from_secs(60), &mut output);
+        assert_eq!(output.len(), 2);
+        assert!(matcher.received.is_empty());
+    }
+
+    #[test]
+    fn discards_unmatched_events() {
+        let mut matcher = matcher(10, false);
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        matcher.record(left("1", "GET /users"), now, &mut output);
+        matcher.record(
+            make_event(Side::Right, "missing", "1", "authenticated"),
+            now,
+            &mut output,
+        );
+        matcher.flush_all_into(&mut output);
+        assert!(output.is_empty());
+        assert!(matcher.buffered.is_empty());
+    }
+
+    #[tokio::test]
+    async fn flushes_buffered_events_on_shutdown() {
+        let matcher = Transform::event_task(matcher(3600, true)).into_task();
+
+        let events = vec![left("1", "GET /users"), right("2", "authenticated")];
+        let output = matcher
+            .transform_events(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut split = Split;
+        let mut buf = TransformOutputsBuf::new_with_capacity(join_outputs(true), output.len());
+        for event in output {
+            split.transform(event, &mut buf);
+        }
+        assert_eq!(buf.drain().count(), 0);
+        assert_eq!(buf.drain_named(UNMATCHED_LEFT_OUTPUT).count(), 1);
+        assert_eq!(buf.drain_named(UNMATCHED_RIGHT_OUTPUT).count(), 1);
+    }
+}
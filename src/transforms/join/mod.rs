@@ -0,0 +1,318 @@
+//! The `join` transform joins the events of two sets of inputs on a key, within a time window.
+//!
+//! It's a macro transform, which expands into the following inner transforms, as no transform can
+//! tell which input an event comes from:
+//!
+//! ```toml
+//! [transforms.my_join.left]
+//! inputs = ["requests"] # the inputs of the left side
+//! # marks events as coming from the left side
+//!
+//! [transforms.my_join.right]
+//! inputs = ["auth"] # the inputs of the right side
+//! # marks events as coming from the right side
+//!
+//! [transforms.my_join.matcher]
+//! inputs = ["my_join.left", "my_join.right"]
+//! # buffers events until they are matched, or their window ends
+//!
+//! [transforms.my_join]
+//! inputs = ["my_join.matcher"]
+//! # sends the unmatched events to the `unmatched_left` and `unmatched_right` outputs
+//! ```
+mod matcher;
+
+use std::collections::HashSet;
+
+use vector_config::configurable_component;
+use vector_core::{
+    config::{ComponentKey, DataType, Input, Output},
+    transform::{
+        InnerTopology, InnerTopologyTransform, Transform, TransformConfig, TransformContext,
+    },
+};
+
+use self::matcher::{join_outputs, MatcherConfig, Side, SplitConfig, TagSideConfig};
+use crate::{
+    config::{GenerateConfig, TransformDescription},
+    schema,
+    template::Template,
+};
+
+inventory::submit! {
+    TransformDescription::new::<JoinConfig>("join")
+}
+
+/// Configuration for the `join` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JoinConfig {
+    /// The left side of the join, into whose events the events of the right side are merged.
+    left: JoinInputConfig,
+
+    /// The right side of the join.
+    right: JoinInputConfig,
+
+    /// How long an event waits for an event of the other side with the same key, in seconds.
+    ///
+    /// Events that aren't matched by the end of their window are unmatched.
+    #[serde(default = "default_window_secs")]
+    window_secs: u64,
+
+    /// Whether to send unmatched events to the `<transform_name>.unmatched_left` and
+    /// `<transform_name>.unmatched_right` outputs.
+    ///
+    /// Otherwise, unmatched events are discarded.
+    #[serde(default)]
+    emit_unmatched: bool,
+}
+
+/// One side of a join.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JoinInputConfig {
+    /// The components whose events make up this side of the join.
+    inputs: Vec<String>,
+
+    /// The key on which events are joined.
+    ///
+    /// Events of both sides whose keys render to the same value are joined. Events whose key can't be rendered are
+    /// unmatched.
+    #[configurable(metadata(templatable))]
+    key: Template,
+}
+
+const fn default_window_secs() -> u64 {
+    60
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join")]
+impl TransformConfig for JoinConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Err("this transform must be expanded".into())
+    }
+
+    fn expand(
+        &mut self,
+        name: &ComponentKey,
+        inputs: &[String],
+    ) -> crate::Result<Option<InnerTopology>> {
+        if !inputs.is_empty() {
+            return Err(
+                "the inputs of the `join` transform are set by `left.inputs` and `right.inputs`"
+                    .into(),
+            );
+        }
+        if self.window_secs == 0 {
+            return Err("`window_secs` must be greater than zero".into());
+        }
+
+        let left_name = name.join("left");
+        let right_name = name.join("right");
+        let matcher_name = name.join("matcher");
+        let mut result = InnerTopology {
+            inner: Default::default(),
+            outputs: vec![(name.clone(), vec![Output::default(DataType::Log)])],
+        };
+        result.inner.insert(
+            left_name.clone(),
+            InnerTopologyTransform {
+                inputs: self.left.inputs.clone(),
+                inner: Box::new(TagSideConfig::new(Side::Left)),
+            },
+        );
+        result.inner.insert(
+            right_name.clone(),
+            InnerTopologyTransform {
+                inputs: self.right.inputs.clone(),
+                inner: Box::new(TagSideConfig::new(Side::Right)),
+            },
+        );
+        result.inner.insert(
+            matcher_name.clone(),
+            InnerTopologyTransform {
+                inputs: vec![left_name.to_string(), right_name.to_string()],
+                inner: Box::new(MatcherConfig::new(
+                    self.left.key.clone(),
+                    self.right.key.clone(),
+                    self.window_secs,
+                    self.emit_unmatched,
+                )),
+            },
+        );
+        result.inner.insert(
+            name.clone(),
+            InnerTopologyTransform {
+                inputs: vec![matcher_name.to_string()],
+                inner: Box::new(SplitConfig::new(self.emit_unmatched)),
+            },
+        );
+        Ok(Some(result))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        join_outputs(self.emit_unmatched)
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join"
+    }
+
+    fn nestable(&self, parents: &HashSet<&'static str>) -> bool {
+        // Inner transforms of other transforms only have a single set of inputs.
+        parents.is_empty()
+    }
+}
+
+impl GenerateConfig for JoinConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc::indoc! {r#"
+            window_secs = 60
+
+            [left]
+            inputs = ["requests"]
+            key = "{{ request_id }}"
+
+            [right]
+            inputs = ["auth"]
+            key = "{{ request_id }}"
+        "#})
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JoinConfig>();
+    }
+
+    fn join_config(emit_unmatched: bool) -> JoinConfig {
+        let mut config = toml::from_str::<JoinConfig>(
+            r#"
+            left.inputs = ["requests"]
+            left.key = "{{ request_id }}"
+            right.inputs = ["auth", "audit"]
+            right.key = "{{ id }}"
+            "#,
+        )
+        .unwrap();
+        config.emit_unmatched = emit_unmatched;
+        config
+    }
+
+    #[test]
+    fn expands_into_inner_transforms() {
+        let topology = join_config(true)
+            .expand(&ComponentKey::from("my_join"), &[])
+            .unwrap()
+            .unwrap();
+
+        let inner = topology
+            .inner
+            .iter()
+            .map(|(name, transform)| {
+                (
+                    name.to_string(),
+                    transform.inner.transform_type(),
+                    transform.inputs.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inner,
+            vec![
+                ("my_join.left".into(), "join_side", vec!["requests".into()]),
+                (
+                    "my_join.right".into(),
+                    "join_side",
+                    vec!["auth".into(), "audit".into()]
+                ),
+                (
+                    "my_join.matcher".into(),
+                    "join_matcher",
+                    vec!["my_join.left".into(), "my_join.right".into()]
+                ),
+                ("my_join".into(), "join", vec!["my_join.matcher".into()]),
+            ]
+        );
+        assert_eq!(topology.outputs(), vec!["my_join".to_owned()]);
+    }
+
+    #[test]
+    fn rejects_outer_inputs() {
+        assert!(join_config(false)
+            .expand(&ComponentKey::from("my_join"), &["in".into()])
+            .is_err());
+    }
+
+    #[test]
+    fn declares_unmatched_outputs() {
+        let ports = |emit_unmatched| {
+            join_config(emit_unmatched)
+                .outputs(&schema::Definition::empty())
+                .into_iter()
+                .map(|output| output.port)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ports(false), vec![None]);
+        assert_eq!(
+            ports(true),
+            vec![
+                None,
+                Some("unmatched_left".into()),
+                Some("unmatched_right".into())
+            ]
+        );
+    }
+}
+
+#[cfg(all(test, feature = "sources-stdin", feature = "sinks-console"))]
+mod config_tests {
+    use indoc::indoc;
+
+    use crate::config::{load_from_str, ComponentKey, Format};
+
+    #[test]
+    fn loads_join_with_unmatched_outputs() {
+        let config = load_from_str(
+            indoc! {r#"
+                [sources.requests]
+                  type = "stdin"
+
+                [sources.auth]
+                  type = "stdin"
+
+                [transforms.joined]
+                  type = "join"
+                  left.inputs = ["requests"]
+                  left.key = "{{ request_id }}"
+                  right.inputs = ["auth"]
+                  right.key = "{{ request_id }}"
+                  emit_unmatched = true
+
+                [sinks.out]
+                  type = "console"
+                  inputs = ["joined", "joined.unmatched_left"]
+                  encoding = "json"
+            "#},
+            Format::Toml,
+        )
+        .unwrap();
+
+        assert!(config.transform(&ComponentKey::from("joined")).is_some());
+        assert!(config
+            .transform(&ComponentKey::from("joined.matcher"))
+            .is_some());
+    }
+}
@@ -0,0 +1,332 @@
+use std::{path::PathBuf, sync::Arc};
+
+use jsonschema::{Draft, JSONSchema};
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
+
+use crate::{
+    config::{
+        log_schema, ComponentKey, DataType, GenerateConfig, Input, Output, TransformConfig,
+        TransformContext, TransformDescription,
+    },
+    event::Event,
+    internal_events::JsonSchemaValidationFailed,
+    schema,
+    transforms::Transform,
+};
+
+const REJECTED: &str = "rejected";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not read JSON Schema {:?}: {}", path, source))]
+    ReadSchema {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Could not parse JSON Schema {:?}: {}", path, source))]
+    ParseSchema {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Invalid JSON Schema {:?}: {}", path, message))]
+    InvalidSchema { path: PathBuf, message: String },
+}
+
+/// Configuration for the `json_schema` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSchemaConfig {
+    /// The path to the JSON Schema to validate the events against.
+    ///
+    /// Schemas follow draft 2020-12, unless they declare another draft with `$schema`. References to other documents
+    /// are not resolved.
+    pub schema_file: PathBuf,
+
+    /// Reroutes the events failing validation to a named output instead of forwarding them.
+    ///
+    /// When set, the events failing validation are forwarded to the `rejected` output, rather than to the default
+    /// output. In both cases, they're annotated with the validation errors.
+    #[serde(default)]
+    pub reroute_rejected: bool,
+
+    /// The maximum number of validation errors annotating an event.
+    #[serde(default = "default_max_errors")]
+    pub max_errors: usize,
+}
+
+const fn default_max_errors() -> usize {
+    10
+}
+
+inventory::submit! {
+    TransformDescription::new::<JsonSchemaConfig>("json_schema")
+}
+
+impl GenerateConfig for JsonSchemaConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"schema_file = "/etc/vector/schemas/event.json""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "json_schema")]
+impl TransformConfig for JsonSchemaConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(JsonSchema::new(
+            self,
+            context.key.clone(),
+        )?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        if self.reroute_rejected {
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Log).with_port(REJECTED),
+            ]
+        } else {
+            vec![Output::default(DataType::Log)]
+        }
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "json_schema"
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct JsonSchema {
+    #[derivative(Debug = "ignore")]
+    schema: Arc<JSONSchema>,
+    reroute_rejected: bool,
+    max_errors: usize,
+    component_key: Option<ComponentKey>,
+}
+
+impl JsonSchema {
+    fn new(
+        config: &JsonSchemaConfig,
+        component_key: Option<ComponentKey>,
+    ) -> Result<Self, BuildError> {
+        let path = &config.schema_file;
+        let schema = std::fs::read(path).context(ReadSchemaSnafu { path })?;
+        let schema: serde_json::Value =
+            serde_json::from_slice(&schema).context(ParseSchemaSnafu { path })?;
+        let mut options = JSONSchema::options();
+        if schema.get("$schema").is_none() {
+            options.with_draft(Draft::Draft202012);
+        }
+        let schema = options
+            .compile(&schema)
+            .map_err(|error| BuildError::InvalidSchema {
+                path: path.clone(),
+                message: error.to_string(),
+            })?;
+
+        Ok(Self {
+            schema: Arc::new(schema),
+            reroute_rejected: config.reroute_rejected,
+            max_errors: config.max_errors,
+            component_key,
+        })
+    }
+
+    /// Validates an event, returning its validation errors.
+    fn validate(&self, event: &Event) -> Vec<serde_json::Value> {
+        let instance = serde_json::to_value(event.as_log()).expect("events are serializable");
+        let errors = match self.schema.validate(&instance) {
+            Ok(()) => return Vec::new(),
+            Err(errors) => errors,
+        };
+
+        errors
+            .take(self.max_errors.max(1))
+            .map(|error| {
+                serde_json::json!({
+                    "path": error.instance_path.to_string(),
+                    "message": error.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn annotate_rejected(&self, event: &mut Event, errors: Vec<serde_json::Value>) {
+        event.as_mut_log().insert(
+            log_schema().metadata_key(),
+            serde_json::json!({
+                "rejected": {
+                    "reason": "json_schema",
+                    "errors": errors,
+                    "component_id": self.component_key,
+                    "component_type": "json_schema",
+                    "component_kind": "transform",
+                }
+            }),
+        );
+    }
+}
+
+impl SyncTransform for JsonSchema {
+    fn transform(&mut self, mut event: Event, output: &mut TransformOutputsBuf) {
+        let errors = self.validate(&event);
+        if errors.is_empty() {
+            output.push(event);
+            return;
+        }
+
+        emit!(JsonSchemaValidationFailed {
+            error: errors[0]["message"].as_str().unwrap_or_default(),
+            rerouted: self.reroute_rejected,
+        });
+        self.annotate_rejected(&mut event, errors);
+        if self.reroute_rejected {
+            output.push_named(REJECTED, event);
+        } else {
+            output.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::event::{LogEvent, Value};
+
+    const SCHEMA: &str = r#"{
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "properties": {
+            "message": { "type": "string" },
+            "status": { "type": "integer", "minimum": 100, "maximum": 599 },
+            "tags": { "type": "array", "prefixItems": [{ "type": "string" }], "items": false }
+        },
+        "required": ["message", "status"]
+    }"#;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JsonSchemaConfig>();
+    }
+
+    fn schema_file(schema: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(schema.as_bytes()).unwrap();
+        file
+    }
+
+    fn transform(schema_file: &NamedTempFile, reroute_rejected: bool) -> JsonSchema {
+        JsonSchema::new(
+            &JsonSchemaConfig {
+                schema_file: schema_file.path().into(),
+                reroute_rejected,
+                max_errors: default_max_errors(),
+            },
+            Some(ComponentKey::from("validate")),
+        )
+        .unwrap()
+    }
+
+    fn run(transform: &mut JsonSchema, event: Event) -> (Vec<Event>, Vec<Event>) {
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Log).with_port(REJECTED),
+            ],
+            1,
+        );
+        transform.transform(event, &mut outputs);
+        (
+            outputs.drain().collect(),
+            outputs.drain_named(REJECTED).collect(),
+        )
+    }
+
+    fn event(json: serde_json::Value) -> Event {
+        Event::from(LogEvent::try_from(json).unwrap())
+    }
+
+    #[test]
+    fn forwards_valid_events() {
+        let schema_file = schema_file(SCHEMA);
+        let mut transform = transform(&schema_file, true);
+
+        let valid = event(serde_json::json!({ "message": "ok", "status": 200, "tags": ["a"] }));
+        let (forwarded, rejected) = run(&mut transform, valid.clone());
+        assert_eq!(forwarded, vec![valid]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn reroutes_rejected_events() {
+        let schema_file = schema_file(SCHEMA);
+        let mut transform = transform(&schema_file, true);
+
+        // `tags` only allows one item, which is only checked with the keywords of draft 2020-12.
+        let invalid =
+            event(serde_json::json!({ "message": "ok", "status": 200, "tags": ["a", "b"] }));
+        let (forwarded, rejected) = run(&mut transform, invalid);
+        assert!(forwarded.is_empty());
+        assert_eq!(rejected.len(), 1);
+
+        let metadata = rejected[0].as_log().get("metadata.rejected").unwrap();
+        assert_eq!(
+            metadata.get("component_id").unwrap(),
+            &Value::from("validate")
+        );
+        assert!(metadata
+            .get("errors[0].path")
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("/tags"));
+    }
+
+    #[test]
+    fn annotates_rejected_events() {
+        let schema_file = schema_file(SCHEMA);
+        let mut transform = transform(&schema_file, false);
+
+        let invalid = event(serde_json::json!({ "status": 1000 }));
+        let (forwarded, rejected) = run(&mut transform, invalid);
+        assert!(rejected.is_empty());
+        assert_eq!(forwarded.len(), 1);
+
+        let errors = forwarded[0]
+            .as_log()
+            .get("metadata.rejected.errors")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        let schema_file = schema_file(r#"{ "type": "unknown" }"#);
+        let error = JsonSchema::new(
+            &JsonSchemaConfig {
+                schema_file: schema_file.path().into(),
+                reroute_rejected: false,
+                max_errors: default_max_errors(),
+            },
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(error, BuildError::InvalidSchema { .. }));
+    }
+}
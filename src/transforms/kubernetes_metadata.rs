@@ -0,0 +1,531 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt};
+use k8s_openapi::{
+    api::core::v1::{Namespace, Node, Pod},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
+use kube::{
+    api::{Api, ListParams},
+    config::{self, KubeConfigOptions},
+    runtime::{
+        reflector::{self, store::Store, ObjectRef},
+        watcher,
+    },
+    Client, Config as ClientConfig,
+};
+use lookup::lookup_v2::{parse_path, OwnedSegment};
+use tokio::task::JoinHandle;
+use vector_config::configurable_component;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::KubernetesMetadataLookupFailed,
+    kubernetes::custom_reflector,
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+/// The minimum interval between two rebuilds of the pod index, so that events from unknown pods don't rebuild it
+/// continuously.
+const MIN_INDEX_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration for the `kubernetes_metadata` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesMetadataConfig {
+    /// The field holding the IP address of the pod which sent the event.
+    ///
+    /// Pods using the host network are never matched by their IP address, as it is shared with their node.
+    pub pod_ip_field: Option<String>,
+
+    /// The field holding the UID of the pod which sent the event.
+    ///
+    /// When both `pod_uid_field` and `pod_ip_field` are set, the UID is looked up first.
+    pub pod_uid_field: Option<String>,
+
+    /// The field under which the metadata is inserted.
+    #[serde(default = "default_target")]
+    pub target: String,
+
+    /// Optional path to a readable [kubeconfig][kubeconfig] file.
+    ///
+    /// If not set, a connection to Kubernetes is made using the in-cluster configuration.
+    ///
+    /// [kubeconfig]: https://kubernetes.io/docs/concepts/configuration/organize-cluster-access-kubeconfig/
+    pub kube_config_file: Option<PathBuf>,
+
+    /// How long to keep the metadata of deleted objects, in milliseconds.
+    ///
+    /// This allows enriching the events sent by a pod right before it got deleted.
+    #[serde(default = "default_delay_deletion_ms")]
+    pub delay_deletion_ms: u64,
+}
+
+fn default_target() -> String {
+    "kubernetes".to_owned()
+}
+
+const fn default_delay_deletion_ms() -> u64 {
+    60_000
+}
+
+inventory::submit! {
+    TransformDescription::new::<KubernetesMetadataConfig>("kubernetes_metadata")
+}
+
+impl GenerateConfig for KubernetesMetadataConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"pod_ip_field = "source_ip"
+            target = "kubernetes""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_metadata")]
+impl TransformConfig for KubernetesMetadataConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.pod_ip_field.is_none() && self.pod_uid_field.is_none() {
+            return Err("at least one of `pod_ip_field` and `pod_uid_field` must be set".into());
+        }
+
+        let client_config = match &self.kube_config_file {
+            Some(kc) => {
+                ClientConfig::from_custom_kubeconfig(
+                    config::Kubeconfig::read_from(kc)?,
+                    &KubeConfigOptions::default(),
+                )
+                .await?
+            }
+            None => ClientConfig::infer().await?,
+        };
+        let client = Client::try_from(client_config)?;
+        let delay_deletion = Duration::from_millis(self.delay_deletion_ms);
+
+        let (pods, pods_reflector) = reflect::<Pod>(client.clone(), delay_deletion);
+        let (namespaces, namespaces_reflector) =
+            reflect::<Namespace>(client.clone(), delay_deletion);
+        let (nodes, nodes_reflector) = reflect::<Node>(client, delay_deletion);
+
+        Ok(Transform::event_task(KubernetesMetadata {
+            pod_ip_field: self.pod_ip_field.clone(),
+            pod_uid_field: self.pod_uid_field.clone(),
+            fields: FieldsSpec::new(&self.target),
+            pods,
+            namespaces,
+            nodes,
+            index: PodIndex::default(),
+            reflectors: vec![pods_reflector, namespaces_reflector, nodes_reflector],
+        }))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "kubernetes_metadata"
+    }
+}
+
+/// Watches all the objects of a kind in the cluster, returning their store.
+fn reflect<K>(client: Client, delay_deletion: Duration) -> (Store<K>, JoinHandle<()>)
+where
+    K: kube::Resource<DynamicType = ()>
+        + Clone
+        + std::fmt::Debug
+        + serde::de::DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+{
+    let watcher = watcher(Api::<K>::all(client), ListParams::default());
+    let writer = reflector::store::Writer::default();
+    let store = writer.as_reader();
+    let handle = tokio::spawn(custom_reflector(writer, watcher, delay_deletion));
+    (store, handle)
+}
+
+/// The fields the metadata is inserted into.
+#[derive(Debug)]
+struct FieldsSpec {
+    pod_name: String,
+    pod_namespace: String,
+    pod_uid: String,
+    pod_ip: String,
+    pod_node_name: String,
+    pod_labels: String,
+    pod_annotations: String,
+    namespace_labels: String,
+    namespace_annotations: String,
+    node_labels: String,
+    node_annotations: String,
+}
+
+impl FieldsSpec {
+    fn new(target: &str) -> Self {
+        let field = |name: &str| format!("{}.{}", target, name);
+        Self {
+            pod_name: field("pod_name"),
+            pod_namespace: field("pod_namespace"),
+            pod_uid: field("pod_uid"),
+            pod_ip: field("pod_ip"),
+            pod_node_name: field("pod_node_name"),
+            pod_labels: field("pod_labels"),
+            pod_annotations: field("pod_annotations"),
+            namespace_labels: field("namespace_labels"),
+            namespace_annotations: field("namespace_annotations"),
+            node_labels: field("node_labels"),
+            node_annotations: field("node_annotations"),
+        }
+    }
+}
+
+/// Finds the pods by IP address and UID.
+///
+/// The pods store is only keyed by namespace and name, so the index is rebuilt from it whenever an event refers to
+/// a pod it doesn't know about.
+#[derive(Debug, Default)]
+struct PodIndex {
+    by_ip: HashMap<String, ObjectRef<Pod>>,
+    by_uid: HashMap<String, ObjectRef<Pod>>,
+    refreshed_at: Option<Instant>,
+}
+
+impl PodIndex {
+    fn refresh(&mut self, pods: &Store<Pod>) -> bool {
+        let now = Instant::now();
+        if matches!(self.refreshed_at, Some(at) if now.duration_since(at) < MIN_INDEX_REFRESH_INTERVAL)
+        {
+            return false;
+        }
+        self.refreshed_at = Some(now);
+
+        self.by_ip.clear();
+        self.by_uid.clear();
+        for pod in pods.state() {
+            let obj = ObjectRef::from_obj(pod.as_ref());
+            if let Some(uid) = &pod.metadata.uid {
+                self.by_uid.insert(uid.clone(), obj.clone());
+            }
+            if let Some(ip) = pod_ip(&pod) {
+                self.by_ip.insert(ip.to_owned(), obj);
+            }
+        }
+        true
+    }
+}
+
+/// Returns the IP address of a pod, unless it uses the host network.
+fn pod_ip(pod: &Pod) -> Option<&str> {
+    let host_network = pod
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.host_network)
+        .unwrap_or(false);
+    if host_network {
+        return None;
+    }
+    pod.status.as_ref()?.pod_ip.as_deref()
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PodKey<'a> {
+    Ip(&'a str),
+    Uid(&'a str),
+}
+
+/// Enriches the events with the metadata of the pods which sent them.
+pub struct KubernetesMetadata {
+    pod_ip_field: Option<String>,
+    pod_uid_field: Option<String>,
+    fields: FieldsSpec,
+    pods: Store<Pod>,
+    namespaces: Store<Namespace>,
+    nodes: Store<Node>,
+    index: PodIndex,
+    reflectors: Vec<JoinHandle<()>>,
+}
+
+impl Drop for KubernetesMetadata {
+    fn drop(&mut self) {
+        // Stop the reflectors to avoid their leak on reload.
+        for reflector in &self.reflectors {
+            reflector.abort();
+        }
+    }
+}
+
+impl KubernetesMetadata {
+    fn find_pod(&mut self, key: PodKey<'_>) -> Option<Pod> {
+        if let Some(pod) = self.lookup(key) {
+            return Some(pod);
+        }
+        if self.index.refresh(&self.pods) {
+            self.lookup(key)
+        } else {
+            None
+        }
+    }
+
+    fn lookup(&self, key: PodKey<'_>) -> Option<Pod> {
+        let obj = match key {
+            PodKey::Ip(ip) => self.index.by_ip.get(ip),
+            PodKey::Uid(uid) => self.index.by_uid.get(uid),
+        }?;
+        let pod = self.pods.get(obj)?;
+        // The pod may have been replaced since the index was built.
+        let matches = match key {
+            PodKey::Ip(ip) => pod_ip(&pod) == Some(ip),
+            PodKey::Uid(uid) => pod.metadata.uid.as_deref() == Some(uid),
+        };
+        matches.then(|| pod.as_ref().clone())
+    }
+
+    fn annotate(&mut self, log: &mut LogEvent) {
+        let uid = self
+            .pod_uid_field
+            .as_ref()
+            .and_then(|field| log.get(field.as_str()))
+            .map(|value| value.to_string_lossy());
+        let ip = self
+            .pod_ip_field
+            .as_ref()
+            .and_then(|field| log.get(field.as_str()))
+            .map(|value| value.to_string_lossy());
+        if uid.is_none() && ip.is_none() {
+            return;
+        }
+
+        let pod = uid
+            .as_deref()
+            .and_then(|uid| self.find_pod(PodKey::Uid(uid)))
+            .or_else(|| ip.as_deref().and_then(|ip| self.find_pod(PodKey::Ip(ip))));
+        let pod = match pod {
+            Some(pod) => pod,
+            None => {
+                emit!(KubernetesMetadataLookupFailed {
+                    pod_uid: uid.as_deref(),
+                    pod_ip: ip.as_deref(),
+                });
+                return;
+            }
+        };
+
+        let fields = &self.fields;
+        for (key, val) in [
+            (&fields.pod_name, &pod.metadata.name),
+            (&fields.pod_namespace, &pod.metadata.namespace),
+            (&fields.pod_uid, &pod.metadata.uid),
+        ] {
+            if let Some(val) = val {
+                log.insert(key.as_str(), val.to_owned());
+            }
+        }
+        if let Some(ip) = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.pod_ip.as_ref())
+        {
+            log.insert(fields.pod_ip.as_str(), ip.to_owned());
+        }
+        annotate_from_metadata(
+            log,
+            &fields.pod_labels,
+            &fields.pod_annotations,
+            &pod.metadata,
+        );
+
+        if let Some(namespace) = &pod.metadata.namespace {
+            if let Some(namespace) = self.namespaces.get(&ObjectRef::new(namespace)) {
+                annotate_from_metadata(
+                    log,
+                    &fields.namespace_labels,
+                    &fields.namespace_annotations,
+                    &namespace.metadata,
+                );
+            }
+        }
+
+        if let Some(node_name) = pod.spec.as_ref().and_then(|spec| spec.node_name.as_ref()) {
+            log.insert(fields.pod_node_name.as_str(), node_name.to_owned());
+            if let Some(node) = self.nodes.get(&ObjectRef::new(node_name)) {
+                annotate_from_metadata(
+                    log,
+                    &fields.node_labels,
+                    &fields.node_annotations,
+                    &node.metadata,
+                );
+            }
+        }
+    }
+}
+
+fn annotate_from_metadata(
+    log: &mut LogEvent,
+    labels_field: &str,
+    annotations_field: &str,
+    metadata: &ObjectMeta,
+) {
+    for (field, map) in [
+        (labels_field, &metadata.labels),
+        (annotations_field, &metadata.annotations),
+    ] {
+        if let Some(map) = map {
+            let prefix_path = parse_path(field);
+            for (key, val) in map.iter() {
+                let mut path = prefix_path.clone().segments;
+                path.push(OwnedSegment::Field(key.clone()));
+                log.insert(&path, val.to_owned());
+            }
+        }
+    }
+}
+
+impl TaskTransform<Event> for KubernetesMetadata {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut inner = self;
+        Box::pin(task.map(move |mut event| {
+            inner.annotate(event.as_mut_log());
+            event
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{PodSpec, PodStatus};
+
+    use super::*;
+    use crate::event::Value;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KubernetesMetadataConfig>();
+    }
+
+    fn metadata(name: &str, namespace: Option<&str>, labels: &[(&str, &str)]) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(name.to_owned()),
+            namespace: namespace.map(ToOwned::to_owned),
+            uid: Some(format!("{}-uid", name)),
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|(key, val)| (key.to_string(), val.to_string()))
+                    .collect(),
+            ),
+            ..ObjectMeta::default()
+        }
+    }
+
+    fn pod(name: &str, ip: &str, host_network: bool) -> Pod {
+        Pod {
+            metadata: metadata(name, Some("sandbox"), &[("app.kubernetes.io/name", name)]),
+            spec: Some(PodSpec {
+                node_name: Some("node0".to_owned()),
+                host_network: Some(host_network),
+                ..PodSpec::default()
+            }),
+            status: Some(PodStatus {
+                pod_ip: Some(ip.to_owned()),
+                ..PodStatus::default()
+            }),
+        }
+    }
+
+    fn store<K>(objects: Vec<K>) -> Store<K>
+    where
+        K: kube::Resource<DynamicType = ()> + Clone,
+    {
+        let mut writer = reflector::store::Writer::default();
+        writer.apply_watcher_event(&watcher::Event::Restarted(objects));
+        writer.as_reader()
+    }
+
+    fn transform() -> KubernetesMetadata {
+        KubernetesMetadata {
+            pod_ip_field: Some("source_ip".to_owned()),
+            pod_uid_field: Some("pod_uid".to_owned()),
+            fields: FieldsSpec::new("kubernetes"),
+            pods: store(vec![
+                pod("web", "10.0.0.1", false),
+                pod("agent", "192.168.0.1", true),
+            ]),
+            namespaces: store(vec![Namespace {
+                metadata: metadata("sandbox", None, &[("team", "core")]),
+                ..Namespace::default()
+            }]),
+            nodes: store(vec![Node {
+                metadata: metadata("node0", None, &[("zone", "a")]),
+                ..Node::default()
+            }]),
+            index: PodIndex::default(),
+            reflectors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn annotates_events_by_pod_ip() {
+        let mut transform = transform();
+        let mut log = LogEvent::from("message");
+        log.insert("source_ip", "10.0.0.1");
+        transform.annotate(&mut log);
+
+        assert_eq!(log["kubernetes.pod_name"], Value::from("web"));
+        assert_eq!(log["kubernetes.pod_namespace"], Value::from("sandbox"));
+        assert_eq!(log["kubernetes.pod_uid"], Value::from("web-uid"));
+        assert_eq!(log["kubernetes.pod_ip"], Value::from("10.0.0.1"));
+        assert_eq!(log["kubernetes.pod_node_name"], Value::from("node0"));
+        assert_eq!(
+            log["kubernetes.pod_labels.\"app.kubernetes.io/name\""],
+            Value::from("web")
+        );
+        assert_eq!(log["kubernetes.namespace_labels.team"], Value::from("core"));
+        assert_eq!(log["kubernetes.node_labels.zone"], Value::from("a"));
+    }
+
+    #[test]
+    fn annotates_events_by_pod_uid() {
+        let mut transform = transform();
+        let mut log = LogEvent::from("message");
+        log.insert("pod_uid", "agent-uid");
+        transform.annotate(&mut log);
+
+        assert_eq!(log["kubernetes.pod_name"], Value::from("agent"));
+    }
+
+    #[test]
+    fn ignores_host_network_pod_ips() {
+        let mut transform = transform();
+        let mut log = LogEvent::from("message");
+        log.insert("source_ip", "192.168.0.1");
+        let expected = log.clone();
+        transform.annotate(&mut log);
+
+        assert_eq!(log, expected);
+    }
+}
@@ -0,0 +1,565 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use snafu::Snafu;
+use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
+
+use crate::{
+    config::{
+        log_schema, DataType, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, Value,
+    },
+    internal_events::{LogPatternCreated, ParserMissingFieldError},
+    schema,
+    transforms::Transform,
+};
+
+const METRICS: &str = "metrics";
+
+/// The token standing for the variable parts of the patterns.
+const WILDCARD: &str = "<*>";
+
+/// Configuration for the `log_clustering` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct LogClusteringConfig {
+    /// The field holding the message to cluster.
+    #[serde(default = "default_field")]
+    #[derivative(Default(value = "default_field()"))]
+    pub field: String,
+
+    /// The field to insert the ID of the pattern of the message into.
+    #[serde(default = "default_pattern_id_field")]
+    #[derivative(Default(value = "default_pattern_id_field()"))]
+    pub pattern_id_field: String,
+
+    /// The field to insert the pattern of the message into.
+    ///
+    /// The variable parts of the pattern are replaced by `<*>`.
+    #[serde(default = "default_pattern_field")]
+    #[derivative(Default(value = "default_pattern_field()"))]
+    pub pattern_field: String,
+
+    /// The field to insert the variable parts of the message into, in order.
+    #[serde(default = "default_parameters_field")]
+    #[derivative(Default(value = "default_parameters_field()"))]
+    pub parameters_field: String,
+
+    /// The depth of the parse tree, including its root and its leaves.
+    ///
+    /// Messages are first grouped by their number of tokens, then by their first `depth - 2` tokens, before being
+    /// compared to the patterns of their group.
+    #[serde(default = "default_depth")]
+    #[derivative(Default(value = "default_depth()"))]
+    pub depth: usize,
+
+    /// The fraction of the tokens of a message that must match a pattern for the message to be part of it.
+    #[serde(default = "default_similarity_threshold")]
+    #[derivative(Default(value = "default_similarity_threshold()"))]
+    pub similarity_threshold: f64,
+
+    /// The maximum number of children of each node of the parse tree.
+    ///
+    /// Once reached, the tokens without their own node are grouped under a `<*>` node.
+    #[serde(default = "default_max_children")]
+    #[derivative(Default(value = "default_max_children()"))]
+    pub max_children: usize,
+
+    /// The maximum number of patterns to keep.
+    ///
+    /// Once reached, the least recently matched pattern is forgotten when a new one is created.
+    #[serde(default = "default_max_patterns")]
+    #[derivative(Default(value = "default_max_patterns()"))]
+    pub max_patterns: usize,
+
+    /// Whether to count the events of each pattern, and emit the counts to the `metrics` output.
+    pub emit_metrics: bool,
+
+    /// The interval between the emissions of the counts of events of each pattern, in seconds.
+    #[serde(default = "default_metrics_interval_secs")]
+    #[derivative(Default(value = "default_metrics_interval_secs()"))]
+    pub metrics_interval_secs: u64,
+}
+
+fn default_field() -> String {
+    log_schema().message_key().to_owned()
+}
+
+fn default_pattern_id_field() -> String {
+    "pattern_id".to_owned()
+}
+
+fn default_pattern_field() -> String {
+    "pattern".to_owned()
+}
+
+fn default_parameters_field() -> String {
+    "pattern_parameters".to_owned()
+}
+
+const fn default_depth() -> usize {
+    4
+}
+
+const fn default_similarity_threshold() -> f64 {
+    0.4
+}
+
+const fn default_max_children() -> usize {
+    100
+}
+
+const fn default_max_patterns() -> usize {
+    10_000
+}
+
+const fn default_metrics_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`depth` must be at least 3"))]
+    InvalidDepth,
+    #[snafu(display("`similarity_threshold` must be between 0 and 1"))]
+    InvalidSimilarityThreshold,
+    #[snafu(display("`max_children` must be greater than zero"))]
+    ZeroMaxChildren,
+    #[snafu(display("`max_patterns` must be greater than zero"))]
+    ZeroMaxPatterns,
+}
+
+inventory::submit! {
+    TransformDescription::new::<LogClusteringConfig>("log_clustering")
+}
+
+impl_generate_config_from_default!(LogClusteringConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "log_clustering")]
+impl TransformConfig for LogClusteringConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(LogClustering::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        if self.emit_metrics {
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Metric).with_port(METRICS),
+            ]
+        } else {
+            vec![Output::default(DataType::Log)]
+        }
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "log_clustering"
+    }
+}
+
+/// A pattern of messages, with wildcards in place of their variable tokens.
+#[derive(Clone, Debug)]
+struct Cluster {
+    id: String,
+    template: Vec<String>,
+    /// The number of events matched since the counts were last emitted.
+    count: u64,
+    /// The tick of the last event matched, to forget the least recently matched patterns.
+    last_matched: u64,
+}
+
+impl Cluster {
+    /// Returns the fraction of the tokens of a message matched by the pattern, and its number of wildcards.
+    fn similarity(&self, tokens: &[&str]) -> (f64, usize) {
+        if tokens.is_empty() {
+            return (1.0, 0);
+        }
+
+        let mut matching = 0;
+        let mut wildcards = 0;
+        for (token, template) in tokens.iter().zip(&self.template) {
+            if template == WILDCARD {
+                matching += 1;
+                wildcards += 1;
+            } else if token == template {
+                matching += 1;
+            }
+        }
+        (matching as f64 / tokens.len() as f64, wildcards)
+    }
+
+    /// Generalizes the pattern to a message, replacing the tokens that differ by wildcards.
+    fn merge(&mut self, tokens: &[&str]) {
+        for (template, token) in self.template.iter_mut().zip(tokens) {
+            if template != token {
+                *template = WILDCARD.to_owned();
+            }
+        }
+    }
+
+    fn parameters(&self, tokens: &[&str]) -> Vec<Value> {
+        self.template
+            .iter()
+            .zip(tokens)
+            .filter(|(template, _)| *template == WILDCARD)
+            .map(|(_, token)| Value::from(*token))
+            .collect()
+    }
+
+    fn pattern(&self) -> String {
+        self.template.join(" ")
+    }
+}
+
+/// A node of the parse tree, either leading to other nodes by token, or holding the patterns of its messages.
+#[derive(Clone, Debug, Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    clusters: Vec<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogClustering {
+    field: String,
+    pattern_id_field: String,
+    pattern_field: String,
+    parameters_field: String,
+    depth: usize,
+    similarity_threshold: f64,
+    max_children: usize,
+    max_patterns: usize,
+    metrics_interval: Option<Duration>,
+
+    /// The parse tree, by number of tokens.
+    tree: HashMap<usize, Node>,
+    clusters: HashMap<u64, Cluster>,
+    next_cluster: u64,
+    tick: u64,
+    last_emitted: Instant,
+}
+
+impl LogClustering {
+    fn new(config: &LogClusteringConfig) -> Result<Self, BuildError> {
+        if config.depth < 3 {
+            return Err(BuildError::InvalidDepth);
+        }
+        if !(0.0..=1.0).contains(&config.similarity_threshold) {
+            return Err(BuildError::InvalidSimilarityThreshold);
+        }
+        if config.max_children == 0 {
+            return Err(BuildError::ZeroMaxChildren);
+        }
+        if config.max_patterns == 0 {
+            return Err(BuildError::ZeroMaxPatterns);
+        }
+
+        Ok(Self {
+            field: config.field.clone(),
+            pattern_id_field: config.pattern_id_field.clone(),
+            pattern_field: config.pattern_field.clone(),
+            parameters_field: config.parameters_field.clone(),
+            depth: config.depth,
+            similarity_threshold: config.similarity_threshold,
+            max_children: config.max_children,
+            max_patterns: config.max_patterns,
+            metrics_interval: config
+                .emit_metrics
+                .then(|| Duration::from_secs(config.metrics_interval_secs)),
+            tree: HashMap::new(),
+            clusters: HashMap::new(),
+            next_cluster: 0,
+            tick: 0,
+            last_emitted: Instant::now(),
+        })
+    }
+
+    /// Returns the leaf of the parse tree of a message, creating its nodes as needed.
+    fn leaf(&mut self, tokens: &[&str]) -> &mut Node {
+        let max_children = self.max_children;
+        let mut node = self.tree.entry(tokens.len()).or_default();
+        for token in tokens.iter().take(self.depth - 2) {
+            // Tokens with digits are most likely variable, so they are not given their own nodes.
+            let key = if token.chars().any(|c| c.is_ascii_digit()) {
+                WILDCARD
+            } else if node.children.contains_key(*token) || node.children.len() + 1 < max_children {
+                *token
+            } else {
+                WILDCARD
+            };
+            node = node.children.entry(key.to_owned()).or_default();
+        }
+        node
+    }
+
+    /// Returns the ID of the pattern of a message, creating it if none matches.
+    fn cluster(&mut self, tokens: &[&str]) -> u64 {
+        self.tick += 1;
+        let tick = self.tick;
+        let threshold = self.similarity_threshold;
+
+        // The leaf is borrowed along with the patterns, so it is taken out of the tree while they are compared.
+        let mut candidates = std::mem::take(&mut self.leaf(tokens).clusters);
+        candidates.retain(|id| self.clusters.contains_key(id));
+        let matched = candidates
+            .iter()
+            .map(|id| (*id, self.clusters[id].similarity(tokens)))
+            .filter(|(_, (similarity, _))| *similarity >= threshold)
+            // The most similar pattern is preferred, and the most specific one among them.
+            .max_by(|(_, (a, a_wildcards)), (_, (b, b_wildcards))| {
+                a.partial_cmp(b)
+                    .unwrap_or(Ordering::Equal)
+                    .then(b_wildcards.cmp(a_wildcards))
+            })
+            .map(|(id, _)| id);
+
+        let id = match matched {
+            Some(id) => {
+                let cluster = self.clusters.get_mut(&id).expect("candidates exist");
+                cluster.merge(tokens);
+                id
+            }
+            None => {
+                let id = self.create(tokens);
+                candidates.push(id);
+                id
+            }
+        };
+        self.leaf(tokens).clusters = candidates;
+
+        let cluster = self.clusters.get_mut(&id).expect("cluster exists");
+        cluster.count += 1;
+        cluster.last_matched = tick;
+        id
+    }
+
+    fn create(&mut self, tokens: &[&str]) -> u64 {
+        if self.clusters.len() >= self.max_patterns {
+            if let Some(oldest) = self
+                .clusters
+                .iter()
+                .min_by_key(|(_, cluster)| cluster.last_matched)
+                .map(|(id, _)| *id)
+            {
+                self.clusters.remove(&oldest);
+            }
+        }
+
+        let template: Vec<String> = tokens
+            .iter()
+            .map(|token| {
+                if token.chars().any(|c| c.is_ascii_digit()) {
+                    WILDCARD.to_owned()
+                } else {
+                    (*token).to_owned()
+                }
+            })
+            .collect();
+        // The ID is derived from the first message of the pattern, so that it's stable across restarts.
+        let cluster = Cluster {
+            id: format!("{:016x}", seahash::hash(template.join(" ").as_bytes())),
+            template,
+            count: 0,
+            last_matched: 0,
+        };
+        emit!(LogPatternCreated {
+            pattern_id: &cluster.id
+        });
+
+        let id = self.next_cluster;
+        self.next_cluster += 1;
+        self.clusters.insert(id, cluster);
+        id
+    }
+
+    /// Emits the counts of events of each pattern, if they are due.
+    fn emit_metrics(&mut self, output: &mut TransformOutputsBuf) {
+        let interval = match self.metrics_interval {
+            Some(interval) => interval,
+            None => return,
+        };
+        if self.last_emitted.elapsed() < interval {
+            return;
+        }
+        self.last_emitted = Instant::now();
+
+        let timestamp = Utc::now();
+        for cluster in self.clusters.values_mut() {
+            if cluster.count == 0 {
+                continue;
+            }
+            let tags = BTreeMap::from([
+                ("pattern_id".to_owned(), cluster.id.clone()),
+                ("pattern".to_owned(), cluster.pattern()),
+            ]);
+            let metric = Metric::new(
+                "log_pattern_events_total",
+                MetricKind::Incremental,
+                MetricValue::Counter {
+                    value: cluster.count as f64,
+                },
+            )
+            .with_tags(Some(tags))
+            .with_timestamp(Some(timestamp));
+            output.push_named(METRICS, metric.into());
+            cluster.count = 0;
+        }
+    }
+}
+
+impl SyncTransform for LogClustering {
+    fn transform(&mut self, mut event: Event, output: &mut TransformOutputsBuf) {
+        let log = event.as_mut_log();
+        let message = match log.get(self.field.as_str()) {
+            Some(message) => message.to_string_lossy(),
+            None => {
+                emit!(ParserMissingFieldError { field: &self.field });
+                output.push(event);
+                return;
+            }
+        };
+
+        let tokens: Vec<&str> = message.split_whitespace().collect();
+        let id = self.cluster(&tokens);
+        let cluster = &self.clusters[&id];
+        log.insert(self.pattern_id_field.as_str(), cluster.id.clone());
+        log.insert(self.pattern_field.as_str(), cluster.pattern());
+        log.insert(self.parameters_field.as_str(), cluster.parameters(&tokens));
+        output.push(event);
+
+        self.emit_metrics(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LogClusteringConfig>();
+    }
+
+    fn run(transform: &mut LogClustering, message: &str) -> (LogEvent, Vec<Event>) {
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Metric).with_port(METRICS),
+            ],
+            1,
+        );
+        transform.transform(Event::from(LogEvent::from(message)), &mut outputs);
+        let log = outputs.drain().next().unwrap().into_log();
+        (log, outputs.drain_named(METRICS).collect())
+    }
+
+    #[test]
+    fn clusters_messages() {
+        let mut transform = LogClustering::new(&LogClusteringConfig::default()).unwrap();
+
+        let (first, _) = run(&mut transform, "Connection from alice closed after 10 ms");
+        assert_eq!(
+            first["pattern"],
+            "Connection from alice closed after <*> ms".into()
+        );
+        assert_eq!(first["pattern_parameters"], Value::from(vec!["10"]));
+
+        let (second, _) = run(&mut transform, "Connection from bob closed after 250 ms");
+        assert_eq!(second["pattern_id"], first["pattern_id"]);
+        assert_eq!(
+            second["pattern"],
+            "Connection from <*> closed after <*> ms".into()
+        );
+        assert_eq!(
+            second["pattern_parameters"],
+            Value::from(vec!["bob", "250"])
+        );
+
+        let (other, _) = run(&mut transform, "Disk usage is above the limit");
+        assert_ne!(other["pattern_id"], first["pattern_id"]);
+        assert_eq!(
+            other["pattern_parameters"],
+            Value::from(Vec::<Value>::new())
+        );
+    }
+
+    #[test]
+    fn forgets_least_recently_matched_patterns() {
+        let mut transform = LogClustering::new(&LogClusteringConfig {
+            max_patterns: 2,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let (a, _) = run(&mut transform, "alpha message");
+        run(&mut transform, "beta event happened here");
+        run(&mut transform, "alpha message");
+        run(&mut transform, "gamma");
+
+        assert_eq!(transform.clusters.len(), 2);
+        let ids: Vec<_> = transform.clusters.values().map(|c| c.id.clone()).collect();
+        assert!(ids.contains(&a["pattern_id"].to_string_lossy()));
+    }
+
+    #[test]
+    fn emits_pattern_metrics() {
+        let mut transform = LogClustering::new(&LogClusteringConfig {
+            emit_metrics: true,
+            metrics_interval_secs: 3600,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let (log, metrics) = run(&mut transform, "User 1 logged in");
+        assert!(metrics.is_empty());
+        run(&mut transform, "User 2 logged in");
+
+        transform.metrics_interval = Some(Duration::ZERO);
+        let (_, metrics) = run(&mut transform, "User 3 logged in");
+        assert_eq!(metrics.len(), 1);
+        let metric = metrics[0].as_metric();
+        assert_eq!(metric.value(), &MetricValue::Counter { value: 3.0 });
+        assert_eq!(
+            metric.tag_value("pattern_id"),
+            Some(log["pattern_id"].to_string_lossy())
+        );
+        assert_eq!(
+            metric.tag_value("pattern"),
+            Some("User <*> logged in".to_owned())
+        );
+
+        // The counts are reset once emitted.
+        let (_, metrics) = run(&mut transform, "User 4 logged in");
+        assert_eq!(
+            metrics[0].as_metric().value(),
+            &MetricValue::Counter { value: 1.0 }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(matches!(
+            LogClustering::new(&LogClusteringConfig {
+                depth: 2,
+                ..Default::default()
+            }),
+            Err(BuildError::InvalidDepth)
+        ));
+    }
+}
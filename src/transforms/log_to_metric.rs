@@ -2,6 +2,7 @@ use std::{collections::BTreeMap, convert::TryFrom, num::ParseFloatError};
 
 use indexmap::IndexMap;
 use vector_config::configurable_component;
+use vector_core::metrics::AgentDDSketch;
 
 use crate::{
     config::{
@@ -9,7 +10,7 @@ use crate::{
         TransformDescription,
     },
     event::{
-        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        metric::{Metric, MetricKind, MetricSketch, MetricValue, StatisticKind},
         Event, Value,
     },
     internal_events::{
@@ -112,6 +113,13 @@ pub struct HistogramConfig {
 
     /// Tags to apply to the histogram.
     tags: Option<IndexMap<String, String>>,
+
+    /// The upper limits of the buckets of the histogram, in increasing order.
+    ///
+    /// When set, an aggregated histogram with these buckets is generated, instead of a distribution with the value of
+    /// `field` as its single sample. Values greater than the last upper limit are only counted in the total count and
+    /// sum of the histogram.
+    buckets: Option<Vec<f64>>,
 }
 
 /// Specification of a summary derived from a log event.
@@ -133,6 +141,25 @@ pub struct SummaryConfig {
     tags: Option<IndexMap<String, String>>,
 }
 
+/// Specification of a sketch derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct SketchConfig {
+    /// Name of the field in the event to generate the sketch from.
+    field: String,
+
+    /// Overrides the name of the sketch.
+    ///
+    /// If not specified, `field` is used as the name of the sketch.
+    name: Option<String>,
+
+    /// Sets the namespace for the sketch.
+    namespace: Option<String>,
+
+    /// Tags to apply to the sketch.
+    tags: Option<IndexMap<String, String>>,
+}
+
 /// Specification of a metric derived from a log event.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -150,6 +177,12 @@ pub enum MetricConfig {
     /// A set.
     Set(#[configurable(derived)] SetConfig),
 
+    /// A sketch.
+    ///
+    /// The sketch is a DDSketch with the relative accuracy of the Datadog Agent, so that it can be merged with the
+    /// sketches of the Agent.
+    Sketch(#[configurable(derived)] SketchConfig),
+
     /// A summary.
     Summary(#[configurable(derived)] SummaryConfig),
 }
@@ -161,6 +194,7 @@ impl MetricConfig {
             MetricConfig::Histogram(HistogramConfig { field, .. }) => field,
             MetricConfig::Gauge(GaugeConfig { field, .. }) => field,
             MetricConfig::Set(SetConfig { field, .. }) => field,
+            MetricConfig::Sketch(SketchConfig { field, .. }) => field,
             MetricConfig::Summary(SummaryConfig { field, .. }) => field,
         }
     }
@@ -203,6 +237,20 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        for metric in &self.metrics {
+            if let MetricConfig::Histogram(HistogramConfig {
+                buckets: Some(buckets),
+                ..
+            }) = metric
+            {
+                if buckets.is_empty() || !buckets.windows(2).all(|pair| pair[0] < pair[1]) {
+                    return Err(
+                        "histogram `buckets` must not be empty, and must be in increasing order"
+                            .into(),
+                    );
+                }
+            }
+        }
         Ok(Transform::function(LogToMetric::new(self.clone())))
     }
 
@@ -355,12 +403,48 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
 
             let tags = render_tags(&hist.tags, event)?;
 
+            let distribution = MetricValue::Distribution {
+                samples: vector_core::samples![value => 1],
+                statistic: StatisticKind::Histogram,
+            };
+            let value = match &hist.buckets {
+                Some(buckets) => distribution
+                    .distribution_to_agg_histogram(buckets)
+                    .expect("value is a distribution"),
+                None => distribution,
+            };
+
+            Ok(
+                Metric::new_with_metadata(name, MetricKind::Incremental, value, metadata)
+                    .with_namespace(namespace)
+                    .with_tags(tags)
+                    .with_timestamp(timestamp),
+            )
+        }
+        MetricConfig::Summary(summary) => {
+            let value = value.to_string_lossy().parse().map_err(|error| {
+                TransformError::ParseFloatError {
+                    field: field.to_string(),
+                    error,
+                }
+            })?;
+
+            let name = summary.name.as_ref().unwrap_or(&summary.field);
+            let name = render_template(name, event)?;
+
+            let namespace = summary.namespace.as_ref();
+            let namespace = namespace
+                .map(|namespace| render_template(namespace, event))
+                .transpose()?;
+
+            let tags = render_tags(&summary.tags, event)?;
+
             Ok(Metric::new_with_metadata(
                 name,
                 MetricKind::Incremental,
                 MetricValue::Distribution {
                     samples: vector_core::samples![value => 1],
-                    statistic: StatisticKind::Histogram,
+                    statistic: StatisticKind::Summary,
                 },
                 metadata,
             )
@@ -368,7 +452,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_tags(tags)
             .with_timestamp(timestamp))
         }
-        MetricConfig::Summary(summary) => {
+        MetricConfig::Sketch(sketch_config) => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     field: field.to_string(),
@@ -376,22 +460,24 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 }
             })?;
 
-            let name = summary.name.as_ref().unwrap_or(&summary.field);
+            let name = sketch_config.name.as_ref().unwrap_or(&sketch_config.field);
             let name = render_template(name, event)?;
 
-            let namespace = summary.namespace.as_ref();
+            let namespace = sketch_config.namespace.as_ref();
             let namespace = namespace
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&summary.tags, event)?;
+            let tags = render_tags(&sketch_config.tags, event)?;
+
+            let mut sketch = AgentDDSketch::with_agent_defaults();
+            sketch.insert(value);
 
             Ok(Metric::new_with_metadata(
                 name,
                 MetricKind::Incremental,
-                MetricValue::Distribution {
-                    samples: vector_core::samples![value => 1],
-                    statistic: StatisticKind::Summary,
+                MetricValue::Sketch {
+                    sketch: MetricSketch::AgentDDSketch(sketch),
                 },
                 metadata,
             )
@@ -947,6 +1033,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_time_histogram_with_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [1.0, 2.5, 5.0]
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config);
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![1.0 => 0, 2.5 => 1, 5.0 => 0],
+                    count: 1,
+                    sum: 2.5,
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unordered_histogram_buckets() {
+        for buckets in ["[]", "[5.0, 1.0]", "[1.0, 1.0]"] {
+            let config = parse_config(&format!(
+                r#"
+                [[metrics]]
+                type = "histogram"
+                field = "response_time"
+                buckets = {}
+                "#,
+                buckets
+            ));
+            assert!(config.build(&TransformContext::default()).await.is_err());
+        }
+    }
+
+    #[test]
+    fn response_time_sketch() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "sketch"
+            field = "response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config);
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        let mut sketch = AgentDDSketch::with_agent_defaults();
+        sketch.insert(2.5);
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::Sketch {
+                    sketch: MetricSketch::AgentDDSketch(sketch),
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
     #[test]
     fn response_time_summary() {
         let config = parse_config(
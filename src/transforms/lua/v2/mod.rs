@@ -1,6 +1,17 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
+use futures::{Stream, StreamExt};
+use once_cell::sync::Lazy;
 use snafu::{ResultExt, Snafu};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use vector_config::configurable_component;
 pub use vector_core::event::lua;
 use vector_core::transform::runtime_transform::{RuntimeTransform, Timer};
@@ -10,7 +21,7 @@ use crate::{
     event::Event,
     internal_events::{LuaBuildError, LuaGcTriggered},
     schema,
-    transforms::Transform,
+    transforms::{TaskTransform, Transform},
 };
 
 #[derive(Debug, Snafu)]
@@ -66,6 +77,25 @@ pub struct LuaConfig {
     /// A list of timers which should be configured and executed periodically.
     #[serde(default)]
     timers: Vec<TimerConfig>,
+
+    /// The maximum number of Lua instructions a hook can execute for a single invocation.
+    ///
+    /// When a hook exceeds it, such as `hooks.process` for an event, it's aborted with an error, and the event is
+    /// discarded. The instructions are counted in batches of 1000, so a hook may slightly exceed it.
+    max_instructions: Option<u64>,
+
+    /// The maximum amount of memory the Lua runtime can allocate, in bytes.
+    ///
+    /// When the Lua runtime fails to allocate memory beyond it, the hook being invoked is aborted with an error, and
+    /// the event is discarded.
+    max_memory_bytes: Option<usize>,
+
+    /// Whether to run the Lua runtime on a dedicated pool of threads.
+    ///
+    /// This prevents a slow or runaway script from stalling the threads running the rest of the topology, at the cost
+    /// of passing events between threads.
+    #[serde(default)]
+    dedicated_threads: bool,
 }
 
 fn default_config_paths() -> Vec<PathBuf> {
@@ -142,7 +172,12 @@ struct TimerConfig {
 // be exposed to users.
 impl LuaConfig {
     pub fn build(&self) -> crate::Result<Transform> {
-        Lua::new(self).map(Transform::event_task)
+        let lua = Lua::new(self)?;
+        Ok(if self.dedicated_threads {
+            Transform::event_task(DedicatedLua(Box::new(lua)))
+        } else {
+            Transform::event_task(lua)
+        })
     }
 
     pub fn input(&self) -> Input {
@@ -166,8 +201,26 @@ impl LuaConfig {
 // after each transform would have significant footprint on the performance.
 const GC_INTERVAL: usize = 16;
 
+// The number of instructions between two checks of `max_instructions`. Checking after each instruction would slow
+// scripts down significantly.
+const INSTRUCTION_CHECK_INTERVAL: u32 = 1000;
+
+// The number of events buffered between the Lua runtime and the topology when it runs on its dedicated threads.
+const DEDICATED_BUFFER_SIZE: usize = 100;
+
+// The runtime shared by the Lua transforms running on dedicated threads.
+static DEDICATED_RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_time()
+        .thread_name("vector-lua")
+        .build()
+        .expect("Unable to create the Lua runtime")
+});
+
 pub struct Lua {
     lua: mlua::Lua,
+    /// The number of instructions executed by the current invocation of a hook, if they're limited.
+    instructions: Option<Arc<AtomicU64>>,
     invocations_after_gc: usize,
     hook_init: Option<mlua::RegistryKey>,
     hook_process: mlua::RegistryKey,
@@ -199,6 +252,35 @@ impl Lua {
 
         let mut timers = Vec::new();
 
+        if let Some(max_memory_bytes) = config.max_memory_bytes {
+            lua.set_memory_limit(max_memory_bytes)?;
+        }
+
+        let instructions = match config.max_instructions {
+            Some(max_instructions) => {
+                let instructions = Arc::new(AtomicU64::new(0));
+                let counter = Arc::clone(&instructions);
+                let triggers = mlua::HookTriggers {
+                    every_nth_instruction: Some(INSTRUCTION_CHECK_INTERVAL),
+                    ..Default::default()
+                };
+                lua.set_hook(triggers, move |_, _| {
+                    let executed =
+                        counter.fetch_add(INSTRUCTION_CHECK_INTERVAL as u64, Ordering::Relaxed);
+                    if executed >= max_instructions {
+                        Err(mlua::Error::RuntimeError(format!(
+                            "exceeded the limit of {} instructions",
+                            max_instructions
+                        )))
+                    } else {
+                        Ok(())
+                    }
+                })?;
+                Some(instructions)
+            }
+            None => None,
+        };
+
         if !additional_paths.is_empty() {
             let package = lua.globals().get::<_, mlua::Table<'_>>("package")?;
             let current_paths = package
@@ -243,6 +325,7 @@ impl Lua {
 
         Ok(Self {
             lua,
+            instructions,
             invocations_after_gc: 0,
             timers,
             hook_init,
@@ -253,6 +336,7 @@ impl Lua {
 
     #[cfg(test)]
     fn process(&mut self, event: Event, output: &mut Vec<Event>) -> Result<(), mlua::Error> {
+        self.reset_instructions();
         let lua = &self.lua;
         let result = lua.scope(|scope| {
             let emit = scope.create_function_mut(|_, event: Event| {
@@ -276,6 +360,13 @@ impl Lua {
         Ok(out.into_iter().next())
     }
 
+    /// Gives the next invocation of a hook its own budget of instructions.
+    fn reset_instructions(&self) {
+        if let Some(instructions) = &self.instructions {
+            instructions.store(0, Ordering::Relaxed);
+        }
+    }
+
     fn attempt_gc(&mut self) {
         self.invocations_after_gc += 1;
         if self.invocations_after_gc % GC_INTERVAL == 0 {
@@ -311,6 +402,7 @@ impl RuntimeTransform for Lua {
     where
         F: FnMut(Event),
     {
+        self.reset_instructions();
         let lua = &self.lua;
         let _ = lua
             .scope(|scope| -> mlua::Result<()> {
@@ -327,6 +419,7 @@ impl RuntimeTransform for Lua {
     where
         F: FnMut(Event),
     {
+        self.reset_instructions();
         let lua = &self.lua;
         let _ = lua
             .scope(|scope| -> mlua::Result<()> {
@@ -347,6 +440,7 @@ impl RuntimeTransform for Lua {
     where
         F: FnMut(Event),
     {
+        self.reset_instructions();
         let lua = &self.lua;
         let _ = lua
             .scope(|scope| -> mlua::Result<()> {
@@ -367,6 +461,7 @@ impl RuntimeTransform for Lua {
     where
         F: FnMut(Event),
     {
+        self.reset_instructions();
         let lua = &self.lua;
         let _ = lua
             .scope(|scope| -> mlua::Result<()> {
@@ -385,6 +480,30 @@ impl RuntimeTransform for Lua {
     }
 }
 
+/// A Lua transform running on the threads of a dedicated runtime, rather than on the threads of the topology.
+struct DedicatedLua(Box<Lua>);
+
+impl TaskTransform<Event> for DedicatedLua {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let (tx, rx) = mpsc::channel(DEDICATED_BUFFER_SIZE);
+        DEDICATED_RUNTIME.spawn(async move {
+            let mut output = TaskTransform::transform(self.0, input_rx);
+            while let Some(event) = output.next().await {
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Box::pin(ReceiverStream::new(rx))
+    }
+}
+
 #[cfg(test)]
 fn format_error(error: &mlua::Error) -> String {
     match error {
@@ -885,4 +1004,89 @@ mod tests {
         assert_eq!(output.len(), n);
         Ok(())
     }
+
+    #[test]
+    fn lua_instruction_limit() {
+        trace_init();
+
+        let mut transform = from_config(
+            r#"
+            max_instructions = 100000
+            hooks.process = """function (event, emit)
+                if event.log.loop then
+                    while true do end
+                end
+                emit(event)
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let mut event = LogEvent::default();
+        event.insert("loop", true);
+        let error = transform.process_single(event.into()).unwrap_err();
+        assert!(format_error(&error).contains("exceeded the limit of 100000 instructions"));
+
+        // Each event gets its own budget.
+        let event = Event::Log(LogEvent::from("program me"));
+        assert!(transform.process_single(event).unwrap().is_some());
+    }
+
+    #[test]
+    fn lua_memory_limit() {
+        trace_init();
+
+        let mut transform = from_config(
+            r#"
+            max_memory_bytes = 1048576
+            hooks.process = """function (event, emit)
+                if event.log.allocate then
+                    event.log.message = string.rep("x", 10 * 1024 * 1024)
+                end
+                emit(event)
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let mut event = LogEvent::default();
+        event.insert("allocate", true);
+        assert!(transform.process_single(event.into()).is_err());
+
+        let event = Event::Log(LogEvent::from("program me"));
+        assert!(transform.process_single(event).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn lua_dedicated_threads() {
+        trace_init();
+
+        let transform = toml::from_str::<LuaConfig>(
+            r#"
+            dedicated_threads = true
+            hooks.process = """function (event, emit)
+                event["log"]["hello"] = "goodbye"
+                emit(event)
+            end
+            """
+            "#,
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+        .into_task();
+
+        let events = (0..10).map(|i| Event::Log(LogEvent::from(format!("program me {}", i))));
+        let output = transform
+            .transform_events(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 10);
+        assert!(output
+            .iter()
+            .all(|event| event.as_log()["hello"] == "goodbye".into()));
+    }
 }
@@ -6,6 +6,10 @@ use snafu::Snafu;
 
 #[cfg(feature = "transforms-aggregate")]
 pub mod aggregate;
+#[cfg(feature = "transforms-aggregate_logs")]
+pub mod aggregate_logs;
+#[cfg(feature = "transforms-anomaly_detection")]
+pub mod anomaly_detection;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub mod aws_ec2_metadata;
 #[cfg(feature = "transforms-dedupe")]
@@ -14,6 +18,14 @@ pub mod dedupe;
 pub mod filter;
 #[cfg(feature = "transforms-geoip")]
 pub mod geoip;
+#[cfg(feature = "transforms-join")]
+pub mod join;
+#[cfg(feature = "transforms-json_schema")]
+pub mod json_schema;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+pub mod kubernetes_metadata;
+#[cfg(feature = "transforms-log_clustering")]
+pub mod log_clustering;
 #[cfg(feature = "transforms-log_to_metric")]
 pub mod log_to_metric;
 #[cfg(feature = "transforms-lua")]
@@ -22,18 +34,32 @@ pub mod lua;
 pub mod metric_to_log;
 #[cfg(feature = "transforms-pipelines")]
 pub mod pipelines;
+#[cfg(feature = "transforms-priority")]
+pub mod priority;
+#[cfg(feature = "transforms-protobuf")]
+pub mod protobuf;
+#[cfg(feature = "transforms-redact_pii")]
+pub mod redact_pii;
 #[cfg(feature = "transforms-reduce")]
 pub mod reduce;
 #[cfg(feature = "transforms-remap")]
 pub mod remap;
+#[cfg(feature = "transforms-rollup")]
+pub mod rollup;
 #[cfg(feature = "transforms-route")]
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
+#[cfg(feature = "transforms-sql")]
+pub mod sql;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+pub mod tail_sampling;
 #[cfg(feature = "transforms-throttle")]
 pub mod throttle;
+#[cfg(feature = "transforms-wasm")]
+pub mod wasm;
 
 use vector_config::configurable_component;
 pub use vector_core::transform::{
@@ -64,6 +90,14 @@ pub enum Transforms {
     #[cfg(feature = "transforms-aggregate")]
     Aggregate(#[configurable(derived)] aggregate::AggregateConfig),
 
+    /// Aggregate logs.
+    #[cfg(feature = "transforms-aggregate_logs")]
+    AggregateLogs(#[configurable(derived)] aggregate_logs::AggregateLogsConfig),
+
+    /// Anomaly detection.
+    #[cfg(feature = "transforms-anomaly_detection")]
+    AnomalyDetection(#[configurable(derived)] anomaly_detection::AnomalyDetectionConfig),
+
     /// AWS EC2 metadata.
     #[cfg(feature = "transforms-aws_ec2_metadata")]
     AwsEc2Metadata(#[configurable(derived)] aws_ec2_metadata::Ec2Metadata),
@@ -80,6 +114,22 @@ pub enum Transforms {
     #[cfg(feature = "transforms-geoip")]
     Geoip(#[configurable(derived)] geoip::GeoipConfig),
 
+    /// Join.
+    #[cfg(feature = "transforms-join")]
+    Join(#[configurable(derived)] join::JoinConfig),
+
+    /// JSON Schema.
+    #[cfg(feature = "transforms-json_schema")]
+    JsonSchema(#[configurable(derived)] json_schema::JsonSchemaConfig),
+
+    /// Kubernetes metadata.
+    #[cfg(feature = "transforms-kubernetes_metadata")]
+    KubernetesMetadata(#[configurable(derived)] kubernetes_metadata::KubernetesMetadataConfig),
+
+    /// Log clustering.
+    #[cfg(feature = "transforms-log_clustering")]
+    LogClustering(#[configurable(derived)] log_clustering::LogClusteringConfig),
+
     /// Log to metric.
     #[cfg(feature = "transforms-log_to_metric")]
     LogToMetric(#[configurable(derived)] log_to_metric::LogToMetricConfig),
@@ -96,6 +146,18 @@ pub enum Transforms {
     #[cfg(feature = "transforms-pipelines")]
     Pipelines(#[configurable(derived)] pipelines::PipelinesConfig),
 
+    /// Priority.
+    #[cfg(feature = "transforms-priority")]
+    Priority(#[configurable(derived)] priority::PriorityConfig),
+
+    /// Protobuf.
+    #[cfg(feature = "transforms-protobuf")]
+    Protobuf(#[configurable(derived)] protobuf::ProtobufConfig),
+
+    /// Redact PII.
+    #[cfg(feature = "transforms-redact_pii")]
+    RedactPii(#[configurable(derived)] redact_pii::RedactPiiConfig),
+
     /// Reduce.
     #[cfg(feature = "transforms-reduce")]
     Reduce(#[configurable(derived)] reduce::ReduceConfig),
@@ -104,6 +166,10 @@ pub enum Transforms {
     #[cfg(feature = "transforms-remap")]
     Remap(#[configurable(derived)] remap::RemapConfig),
 
+    /// Rollup.
+    #[cfg(feature = "transforms-rollup")]
+    Rollup(#[configurable(derived)] rollup::RollupConfig),
+
     /// Route.
     #[cfg(feature = "transforms-route")]
     #[serde(alias = "swimlanes")]
@@ -114,13 +180,25 @@ pub enum Transforms {
     #[serde(alias = "sampler")]
     Sample(#[configurable(derived)] sample::SampleConfig),
 
+    /// SQL.
+    #[cfg(feature = "transforms-sql")]
+    Sql(#[configurable(derived)] sql::SqlConfig),
+
     /// Tag cardinality limit.
     #[cfg(feature = "transforms-tag_cardinality_limit")]
     TagCardinalityLimit(#[configurable(derived)] tag_cardinality_limit::TagCardinalityLimitConfig),
 
+    /// Tail sampling.
+    #[cfg(feature = "transforms-tail_sampling")]
+    TailSampling(#[configurable(derived)] tail_sampling::TailSamplingConfig),
+
     /// Throttle.
     #[cfg(feature = "transforms-throttle")]
     Throttle(#[configurable(derived)] throttle::ThrottleConfig),
+
+    /// WebAssembly.
+    #[cfg(feature = "transforms-wasm")]
+    Wasm(#[configurable(derived)] wasm::WasmConfig),
 }
 
 #[async_trait]
@@ -130,6 +208,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-dedupe")]
@@ -138,6 +220,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-lua")]
@@ -146,18 +236,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.build(globals).await,
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.build(globals).await,
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.build(globals).await,
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -167,6 +271,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.input(),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.input(),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.input(),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.input(),
             #[cfg(feature = "transforms-dedupe")]
@@ -175,6 +283,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.input(),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.input(),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.input(),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.input(),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.input(),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.input(),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.input(),
             #[cfg(feature = "transforms-lua")]
@@ -183,18 +299,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.input(),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.input(),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.input(),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.input(),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.input(),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.input(),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.input(),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.input(),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.input(),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.input(),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.input(),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.input(),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.input(),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.input(),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.input(),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -205,6 +335,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-dedupe")]
@@ -213,6 +347,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-lua")]
@@ -221,18 +363,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.outputs(merged_definition),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.outputs(merged_definition),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.outputs(merged_definition),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -242,6 +398,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-dedupe")]
@@ -250,6 +410,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-lua")]
@@ -258,18 +426,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.transform_type(),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.transform_type(),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.transform_type(),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -279,6 +461,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-dedupe")]
@@ -287,6 +473,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-lua")]
@@ -295,18 +489,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.typetag_name(),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.typetag_name(),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.typetag_name(),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -316,6 +524,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-dedupe")]
@@ -324,6 +536,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-lua")]
@@ -332,18 +552,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.typetag_deserialize(),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.typetag_deserialize(),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.typetag_deserialize(),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -354,6 +588,10 @@ impl TransformConfig for Transforms {
         match self {
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-aggregate_logs")]
+            Transforms::AggregateLogs(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-anomaly_detection")]
+            Transforms::AnomalyDetection(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
             Transforms::AwsEc2Metadata(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-dedupe")]
@@ -362,6 +600,14 @@ impl TransformConfig for Transforms {
             Transforms::Filter(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-geoip")]
             Transforms::Geoip(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-json_schema")]
+            Transforms::JsonSchema(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-kubernetes_metadata")]
+            Transforms::KubernetesMetadata(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-log_clustering")]
+            Transforms::LogClustering(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-log_to_metric")]
             Transforms::LogToMetric(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-lua")]
@@ -370,18 +616,32 @@ impl TransformConfig for Transforms {
             Transforms::MetricToLog(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-pipelines")]
             Transforms::Pipelines(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-priority")]
+            Transforms::Priority(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-protobuf")]
+            Transforms::Protobuf(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-redact_pii")]
+            Transforms::RedactPii(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-reduce")]
             Transforms::Reduce(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-remap")]
             Transforms::Remap(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-rollup")]
+            Transforms::Rollup(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-route")]
             Transforms::Route(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-sample")]
             Transforms::Sample(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-sql")]
+            Transforms::Sql(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(inner) => inner.nestable(parents),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(inner) => inner.nestable(parents),
+            #[cfg(feature = "transforms-wasm")]
+            Transforms::Wasm(inner) => inner.nestable(parents),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
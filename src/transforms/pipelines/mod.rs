@@ -303,6 +303,7 @@ mod tests {
         let config: PipelinesConfig = config.try_into().unwrap();
         let outer = TransformOuter {
             inputs: vec!["source".to_string()],
+            shutdown_timeout_secs: None,
             inner: Box::new(config),
         };
         let name = ComponentKey::from("foo");
@@ -0,0 +1,178 @@
+use vector_config::configurable_component;
+use vector_core::buffers::Priority;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::Event,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `priority` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityConfig {
+    /// A condition matching the events to give a high priority.
+    ///
+    /// High priority events are never dropped by buffers, and wait for the buffer, or its overflow
+    /// buffer, to have room instead.
+    /// This is checked before the `low` condition, and events matching neither condition keep their
+    /// current priority.
+    high: Option<AnyCondition>,
+
+    /// A condition matching the events to give a low priority.
+    ///
+    /// Low priority events are shed by buffers configured to drop events, or to overflow, as soon as
+    /// the buffer is half full.
+    low: Option<AnyCondition>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<PriorityConfig>("priority")
+}
+
+impl GenerateConfig for PriorityConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"high.type = "vrl"
+            high.source = '.level == "error"'
+            low.type = "vrl"
+            low.source = '.level == "debug"'"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "priority")]
+impl TransformConfig for PriorityConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let build = |condition: &Option<AnyCondition>| {
+            condition
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()
+        };
+
+        Ok(Transform::function(Prioritize {
+            high: build(&self.high)?,
+            low: build(&self.low)?,
+        }))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::all())]
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "priority"
+    }
+}
+
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub struct Prioritize {
+    #[derivative(Debug = "ignore")]
+    high: Option<Condition>,
+    #[derivative(Debug = "ignore")]
+    low: Option<Condition>,
+}
+
+fn matches(condition: &Option<Condition>, event: Event) -> (bool, Event) {
+    match condition {
+        Some(condition) => condition.check(event),
+        None => (false, event),
+    }
+}
+
+impl FunctionTransform for Prioritize {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        let (is_high, mut event) = matches(&self.high, event);
+        if is_high {
+            event.metadata_mut().set_priority(Priority::High);
+        } else {
+            let (is_low, low_event) = matches(&self.low, event);
+            event = low_event;
+            if is_low {
+                event.metadata_mut().set_priority(Priority::Low);
+            }
+        }
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conditions::{ConditionalConfig, VrlConfig},
+        event::LogEvent,
+        transforms::test::transform_one,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PriorityConfig>();
+    }
+
+    fn level_condition(level: &str) -> Condition {
+        let vrl_config = VrlConfig {
+            source: format!(r#".level == "{}""#, level),
+            runtime: Default::default(),
+        };
+
+        vrl_config
+            .build(&Default::default())
+            .expect("should not fail to build VRL condition")
+    }
+
+    fn log(level: &str) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("level", level);
+        log.into()
+    }
+
+    #[test]
+    fn sets_priority_from_conditions() {
+        let mut transform = Prioritize {
+            high: Some(level_condition("error")),
+            low: Some(level_condition("debug")),
+        };
+
+        for (level, priority) in [
+            ("error", Priority::High),
+            ("debug", Priority::Low),
+            ("info", Priority::Normal),
+        ] {
+            let event = transform_one(&mut transform, log(level)).unwrap();
+            assert_eq!(event.metadata().priority(), priority);
+        }
+    }
+
+    #[test]
+    fn keeps_priority_of_unmatched_events() {
+        let mut transform = Prioritize {
+            high: None,
+            low: Some(level_condition("debug")),
+        };
+
+        let mut event = log("error");
+        event.metadata_mut().set_priority(Priority::High);
+        let event = transform_one(&mut transform, event).unwrap();
+        assert_eq!(event.metadata().priority(), Priority::High);
+    }
+}
@@ -0,0 +1,329 @@
+use std::path::{Path, PathBuf};
+
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+use snafu::{OptionExt, ResultExt, Snafu};
+use vector_config::configurable_component;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{ParserMissingFieldError, ProtobufDecodeError},
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("failed to read descriptor set {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("failed to decode descriptor set {:?}: {}", path, source))]
+    DecodeDescriptorSet {
+        path: PathBuf,
+        source: prost_reflect::DescriptorError,
+    },
+    #[snafu(display("message type {:?} is not defined in the descriptor set", message_type))]
+    UnknownMessageType { message_type: String },
+}
+
+/// Configuration for the `protobuf` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProtobufConfig {
+    /// The path to the file descriptor set defining the message.
+    ///
+    /// The file descriptor set can be generated with `protoc --include_imports --descriptor_set_out`.
+    pub desc_file: PathBuf,
+
+    /// The fully qualified name of the message, such as `mypackage.MyMessage`.
+    pub message_type: String,
+
+    /// The field holding the encoded message.
+    ///
+    /// The message is expected without any framing, such as the length prefix of gRPC or the schema ID of the Confluent
+    /// wire format.
+    #[serde(default = "default_field")]
+    pub field: String,
+
+    /// The field to insert the fields of the decoded message into.
+    ///
+    /// By default, the fields of the message are inserted at the root of the event, overwriting the existing ones.
+    pub target_field: Option<String>,
+
+    /// Whether to remove the field holding the encoded message once it's decoded.
+    #[serde(default = "crate::serde::default_true")]
+    pub drop_field: bool,
+
+    /// Whether to drop the events whose message fails to decode.
+    ///
+    /// By default, they are forwarded unchanged.
+    #[serde(default)]
+    pub drop_invalid: bool,
+}
+
+fn default_field() -> String {
+    log_schema().message_key().to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<ProtobufConfig>("protobuf")
+}
+
+impl GenerateConfig for ProtobufConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"desc_file = "/etc/vector/protos/events.desc"
+            message_type = "mypackage.MyMessage""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "protobuf")]
+impl TransformConfig for ProtobufConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        let descriptor = load_message(&self.desc_file, &self.message_type)?;
+        Ok(Transform::function(Protobuf {
+            descriptor,
+            field: self.field.clone(),
+            target_field: self.target_field.clone(),
+            drop_field: self.drop_field,
+            drop_invalid: self.drop_invalid,
+        }))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "protobuf"
+    }
+}
+
+fn load_message(desc_file: &Path, message_type: &str) -> Result<MessageDescriptor, BuildError> {
+    let descriptor_set = std::fs::read(desc_file).context(ReadDescriptorSetSnafu {
+        path: desc_file.to_path_buf(),
+    })?;
+    let pool =
+        DescriptorPool::decode(descriptor_set.as_slice()).context(DecodeDescriptorSetSnafu {
+            path: desc_file.to_path_buf(),
+        })?;
+    pool.get_message_by_name(message_type)
+        .context(UnknownMessageTypeSnafu { message_type })
+}
+
+#[derive(Clone, Debug)]
+pub struct Protobuf {
+    descriptor: MessageDescriptor,
+    field: String,
+    target_field: Option<String>,
+    drop_field: bool,
+    drop_invalid: bool,
+}
+
+impl Protobuf {
+    /// Decodes the message of an event into its fields, returning whether it was decoded.
+    fn decode(&self, log: &mut LogEvent) -> bool {
+        let bytes = match log.get(self.field.as_str()) {
+            Some(Value::Bytes(bytes)) => bytes.clone(),
+            _ => {
+                emit!(ParserMissingFieldError { field: &self.field });
+                return false;
+            }
+        };
+
+        // Integers are kept as numbers, rather than the strings of the JSON mapping of 64-bit integers.
+        let options = SerializeOptions::new()
+            .use_proto_field_name(true)
+            .stringify_64_bit_integers(false);
+        let fields = match DynamicMessage::decode(self.descriptor.clone(), bytes)
+            .map_err(|error| error.to_string())
+            .and_then(|message| {
+                message
+                    .serialize_with_options(serde_json::value::Serializer, &options)
+                    .map_err(|error| error.to_string())
+            }) {
+            Ok(fields) => Value::from(fields),
+            Err(error) => {
+                emit!(ProtobufDecodeError {
+                    error,
+                    drop_event: self.drop_invalid,
+                });
+                return false;
+            }
+        };
+
+        if self.drop_field {
+            log.remove(self.field.as_str());
+        }
+        match (&self.target_field, fields) {
+            (Some(target_field), fields) => {
+                log.insert(target_field.as_str(), fields);
+            }
+            (None, Value::Object(fields)) => {
+                for (key, value) in fields {
+                    log.insert(key.as_str(), value);
+                }
+            }
+            (None, _) => unreachable!("messages are serialized as objects"),
+        }
+        true
+    }
+}
+
+impl FunctionTransform for Protobuf {
+    fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
+        if self.decode(event.as_mut_log()) || !self.drop_invalid {
+            output.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use prost::Message;
+    use prost_reflect::Value as MessageValue;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+
+    use super::*;
+    use crate::transforms::test::transform_one;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ProtobufConfig>();
+    }
+
+    fn write_descriptor_set(dir: &Path) -> PathBuf {
+        let field = |name: &str, number, r#type: Type| FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            json_name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(r#type as i32),
+            ..Default::default()
+        };
+
+        let descriptor_set = FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".to_owned()),
+                package: Some("test".to_owned()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Event".to_owned()),
+                    field: vec![
+                        field("message", 1, Type::String),
+                        field("severity_level", 2, Type::Int64),
+                    ],
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".to_owned()),
+                ..Default::default()
+            }],
+        };
+
+        let path = dir.join("test.desc");
+        std::fs::write(&path, descriptor_set.encode_to_vec()).unwrap();
+        path
+    }
+
+    fn transform(dir: &Path, target_field: Option<&str>, drop_invalid: bool) -> Protobuf {
+        Protobuf {
+            descriptor: load_message(&write_descriptor_set(dir), "test.Event").unwrap(),
+            field: "payload".to_owned(),
+            target_field: target_field.map(str::to_owned),
+            drop_field: true,
+            drop_invalid,
+        }
+    }
+
+    fn event(payload: impl Into<Value> + std::fmt::Debug) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("payload", payload);
+        log.insert("host", "localhost");
+        log.into()
+    }
+
+    fn encoded_message(descriptor: &MessageDescriptor) -> Bytes {
+        let mut message = DynamicMessage::new(descriptor.clone());
+        message.set_field_by_name("message", MessageValue::String("hello".to_owned()));
+        message.set_field_by_name("severity_level", MessageValue::I64(5_000_000_000));
+        message.encode_to_vec().into()
+    }
+
+    #[test]
+    fn loads_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = write_descriptor_set(dir.path());
+
+        assert!(load_message(&desc_file, "test.Event").is_ok());
+        assert!(matches!(
+            load_message(&desc_file, "test.Unknown"),
+            Err(BuildError::UnknownMessageType { .. })
+        ));
+        assert!(matches!(
+            load_message(&dir.path().join("missing.desc"), "test.Event"),
+            Err(BuildError::ReadDescriptorSet { .. })
+        ));
+    }
+
+    #[test]
+    fn decodes_messages_into_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut transform = transform(dir.path(), None, false);
+        let payload = encoded_message(&transform.descriptor);
+
+        let output = transform_one(&mut transform, event(payload)).unwrap();
+        let log = output.as_log();
+        assert_eq!(log.get("message"), Some(&Value::from("hello")));
+        assert_eq!(
+            log.get("severity_level"),
+            Some(&Value::from(5_000_000_000_i64))
+        );
+        assert_eq!(log.get("host"), Some(&Value::from("localhost")));
+        assert!(log.get("payload").is_none());
+    }
+
+    #[test]
+    fn decodes_messages_into_a_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut transform = transform(dir.path(), Some("payload"), false);
+        let payload = encoded_message(&transform.descriptor);
+
+        let output = transform_one(&mut transform, event(payload)).unwrap();
+        let log = output.as_log();
+        assert_eq!(log.get("payload.message"), Some(&Value::from("hello")));
+        assert!(log.get("message").is_none());
+    }
+
+    #[test]
+    fn handles_invalid_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let invalid = Bytes::from_static(&[0xff, 0xff, 0xff]);
+
+        let mut transform = transform(dir.path(), None, false);
+        let output = transform_one(&mut transform, event(invalid.clone())).unwrap();
+        assert_eq!(
+            output.as_log().get("payload"),
+            Some(&Value::from(invalid.clone()))
+        );
+
+        let mut transform = self::transform(dir.path(), None, true);
+        assert!(transform_one(&mut transform, event(invalid)).is_none());
+        assert!(transform_one(&mut transform, Event::from(LogEvent::default())).is_none());
+    }
+}
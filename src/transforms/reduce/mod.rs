@@ -8,7 +8,9 @@ use std::{
 use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
+use vector_common::memory_budget::{MemoryUse, Reservation};
 use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
 
 use crate::{
     conditions::{AnyCondition, Condition},
@@ -66,6 +68,23 @@ pub struct ReduceConfig {
     ///
     /// If this condition resolves to `true` for an event, the previous transaction is flushed (without this event) and a new transaction is started.
     pub starts_when: Option<AnyCondition>,
+
+    /// A condition used to distinguish a complete transaction, evaluated against the combined event.
+    ///
+    /// Once an event is merged, if this condition resolves to `true` for the combined event of its transaction, the
+    /// transaction is immediately flushed.
+    pub flush_when: Option<AnyCondition>,
+
+    /// The maximum size of a combined event, in bytes.
+    ///
+    /// The size is estimated from the in-memory size of the events merged into it. If merging an event would exceed it,
+    /// the transaction is flushed (without this event) and a new transaction is started.
+    pub max_bytes: Option<usize>,
+
+    /// The maximum number of events merged into a combined event.
+    ///
+    /// Once a transaction reaches it, it's immediately flushed.
+    pub max_events: Option<usize>,
 }
 
 inventory::submit! {
@@ -99,10 +118,15 @@ struct ReduceState {
     fields: HashMap<String, Box<dyn ReduceValueMerger>>,
     stale_since: Instant,
     metadata: EventMetadata,
+    events: usize,
+    /// The size of the events reduced, reserved against the memory budget of the process until
+    /// the state is flushed.
+    memory: Reservation,
 }
 
 impl ReduceState {
     fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) -> Self {
+        let memory = Reservation::new(MemoryUse::TransformState, e.size_of());
         let (value, metadata) = e.into_parts();
 
         let fields = if let Value::Object(fields) = value {
@@ -130,10 +154,14 @@ impl ReduceState {
             stale_since: Instant::now(),
             fields,
             metadata,
+            events: 1,
+            memory,
         }
     }
 
     fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) {
+        self.events += 1;
+        self.memory.grow(e.size_of());
         let (value, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
@@ -170,6 +198,16 @@ impl ReduceState {
         self.stale_since = Instant::now();
     }
 
+    /// Returns the combined event, without flushing the state.
+    fn combined(&self) -> LogEvent {
+        let mut event = LogEvent::new_with_metadata(self.metadata.clone());
+        for (k, v) in &self.fields {
+            // Failures are reported when the state is flushed.
+            let _ = v.clone().insert_into(k.clone(), &mut event);
+        }
+        event
+    }
+
     fn flush(mut self) -> LogEvent {
         let mut event = LogEvent::new_with_metadata(self.metadata);
         for (k, v) in self.fields.drain() {
@@ -189,6 +227,9 @@ pub struct Reduce {
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Condition>,
     starts_when: Option<Condition>,
+    flush_when: Option<Condition>,
+    max_bytes: Option<usize>,
+    max_events: Option<usize>,
 }
 
 impl Reduce {
@@ -210,6 +251,11 @@ impl Reduce {
             .as_ref()
             .map(|c| c.build(enrichment_tables))
             .transpose()?;
+        let flush_when = config
+            .flush_when
+            .as_ref()
+            .map(|c| c.build(enrichment_tables))
+            .transpose()?;
         let group_by = config.group_by.clone().into_iter().collect();
 
         Ok(Reduce {
@@ -220,6 +266,9 @@ impl Reduce {
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
+            flush_when,
+            max_bytes: config.max_bytes,
+            max_events: config.max_events,
         })
     }
 
@@ -244,13 +293,49 @@ impl Reduce {
             .for_each(|(_, s)| output.push(Event::from(s.flush())));
     }
 
-    fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
+    fn push_or_new_reduce_state(
+        &mut self,
+        output: &mut Vec<Event>,
+        event: LogEvent,
+        discriminant: Discriminant,
+    ) {
         match self.reduce_merge_states.entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
                 entry.insert(ReduceState::new(event, &self.merge_strategies));
             }
             hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().add_event(event, &self.merge_strategies);
+                let exceeds_max_bytes = self.max_bytes.map_or(false, |max_bytes| {
+                    entry.get().memory.bytes() + event.size_of() > max_bytes
+                });
+                if exceeds_max_bytes {
+                    let state = std::mem::replace(
+                        entry.get_mut(),
+                        ReduceState::new(event, &self.merge_strategies),
+                    );
+                    output.push(state.flush().into());
+                } else {
+                    entry.get_mut().add_event(event, &self.merge_strategies);
+                }
+            }
+        }
+    }
+
+    /// Flushes the state of a group if it reached its maximum number of events or satisfies `flush_when`.
+    fn flush_if_complete(&mut self, output: &mut Vec<Event>, discriminant: &Discriminant) {
+        let complete = match self.reduce_merge_states.get(discriminant) {
+            Some(state) => {
+                self.max_events
+                    .map_or(false, |max_events| state.events >= max_events)
+                    || self.flush_when.as_ref().map_or(false, |condition| {
+                        condition.check(state.combined().into()).0
+                    })
+            }
+            None => false,
+        };
+
+        if complete {
+            if let Some(state) = self.reduce_merge_states.remove(discriminant) {
+                output.push(state.flush().into());
             }
         }
     }
@@ -274,19 +359,16 @@ impl Reduce {
                 output.push(state.flush().into());
             }
 
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant.clone());
+            self.flush_if_complete(output, &discriminant);
         } else if ends_here {
-            output.push(match self.reduce_merge_states.remove(&discriminant) {
-                Some(mut state) => {
-                    state.add_event(event, &self.merge_strategies);
-                    state.flush().into()
-                }
-                None => ReduceState::new(event, &self.merge_strategies)
-                    .flush()
-                    .into(),
-            })
+            self.push_or_new_reduce_state(output, event, discriminant.clone());
+            if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
+                output.push(state.flush().into());
+            }
         } else {
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant.clone());
+            self.flush_if_complete(output, &discriminant);
         }
 
         self.flush_into(output);
@@ -598,4 +680,94 @@ merge_strategies.bar = "concat"
         assert_eq!(output_2["bar"], json!([2, 4, 6, 8, "done"]).into());
         assert_eq!(output_2.metadata(), &metadata_2);
     }
+
+    async fn reduce_all(config: &str, inputs: Vec<Event>) -> Vec<LogEvent> {
+        let reduce = toml::from_str::<ReduceConfig>(config)
+            .unwrap()
+            .build(&TransformContext::default())
+            .await
+            .unwrap();
+        let reduce = reduce.into_task();
+
+        let in_stream = Box::pin(stream::iter(inputs));
+        reduce
+            .transform_events(in_stream)
+            .map(Event::into_log)
+            .collect()
+            .await
+    }
+
+    fn counter_event(counter: i64) -> Event {
+        let mut event = LogEvent::from(format!("test message {}", counter));
+        event.insert("counter", counter);
+        event.insert("request_id", "1");
+        event.into()
+    }
+
+    #[tokio::test]
+    async fn max_events() {
+        let inputs = (1..=5).map(counter_event).collect();
+        let outputs = reduce_all(
+            r#"
+group_by = [ "request_id" ]
+max_events = 2
+"#,
+            inputs,
+        )
+        .await;
+
+        let counters: Vec<_> = outputs.iter().map(|e| e["counter"].clone()).collect();
+        assert_eq!(
+            counters,
+            vec![Value::from(3), Value::from(7), Value::from(5)]
+        );
+    }
+
+    #[tokio::test]
+    async fn max_bytes() {
+        let inputs: Vec<Event> = (1..=4).map(counter_event).collect();
+        let max_bytes = inputs[0].size_of() * 2;
+        let outputs = reduce_all(
+            &format!(
+                r#"
+group_by = [ "request_id" ]
+max_bytes = {}
+merge_strategies.message = "concat"
+"#,
+                max_bytes
+            ),
+            inputs,
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(
+            outputs[0]["message"],
+            "test message 1 test message 2".into()
+        );
+        assert_eq!(
+            outputs[1]["message"],
+            "test message 3 test message 4".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn flush_when() {
+        let inputs = (1..=5).map(counter_event).collect();
+        let outputs = reduce_all(
+            r#"
+group_by = [ "request_id" ]
+
+[flush_when]
+  type = "vrl"
+  source = ".counter >= 5"
+"#,
+            inputs,
+        )
+        .await;
+
+        // The condition is checked against the combined event, whose counter is the sum of the counters.
+        let counters: Vec<_> = outputs.iter().map(|e| e["counter"].clone()).collect();
+        assert_eq!(counters, vec![Value::from(6), Value::from(9)]);
+    }
 }
@@ -0,0 +1,70 @@
+//! The `emit` function, which lets a `remap` program emit additional events for the event it processes.
+//!
+//! The events are collected in a buffer local to the thread running the program. Transforms process each event
+//! synchronously, so the buffer is drained right after the program runs, before another program can run on the thread.
+use std::cell::RefCell;
+
+use value::Value;
+use vrl::prelude::*;
+
+thread_local! {
+    static EMITTED: RefCell<Vec<Value>> = RefCell::new(Vec::new());
+}
+
+/// Takes the events emitted since the last call.
+pub(super) fn take_emitted() -> Vec<Value> {
+    EMITTED.with(|emitted| std::mem::take(&mut *emitted.borrow_mut()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Emit;
+
+impl Function for Emit {
+    fn identifier(&self) -> &'static str {
+        "emit"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "event",
+            kind: kind::OBJECT,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "emit an event",
+            source: r#"emit({"message": "login", "user": "alice"})"#,
+            result: Ok("null"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let event = arguments.required("event");
+
+        Ok(Box::new(EmitFn { event }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EmitFn {
+    event: Box<dyn Expression>,
+}
+
+impl Expression for EmitFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let event = self.event.resolve(ctx)?;
+        EMITTED.with(|emitted| emitted.borrow_mut().push(event));
+        Ok(Value::Null)
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::null().infallible()
+    }
+}
@@ -0,0 +1,839 @@
+//! VRL functions encrypting values with data keys wrapped by a key management service, which are only available to
+//! programs for the keys configured in the `encryption_keys` option of the `remap` transform.
+//!
+//! Each value is encrypted with AES-256-GCM under a random data key. The data key is wrapped by the key management
+//! service, and stored with the ciphertext in an envelope. Data keys are replaced once `data_key_ttl_secs` elapses, and
+//! unwrapped data keys are cached, so that the service is only called when keys rotate.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use aws_sdk_kms::types::Blob;
+use azure_core::auth::TokenCredential;
+use azure_identity::{AutoRefreshingTokenCredential, DefaultAzureCredential};
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, Request};
+use hyper::Body;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde_json::json;
+use tokio::sync::OnceCell;
+use value::Value;
+use vector_config::configurable_component;
+use vrl::prelude::*;
+
+use crate::{
+    aws::{auth::AwsAuthentication, create_client, region::RegionOrEndpoint, ClientBuilder},
+    config::ProxyConfig,
+    enrichment_tables::cache::Cache,
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
+    http::HttpClient,
+};
+
+const ENVELOPE_VERSION: u8 = 1;
+const DATA_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const MAX_CACHED_DATA_KEYS: usize = 1000;
+
+const GCP_KMS_URL: &str = "https://cloudkms.googleapis.com/v1";
+const AZURE_KEY_VAULT_RESOURCE: &str = "https://vault.azure.net";
+const AZURE_KEY_VAULT_API_VERSION: &str = "7.3";
+const AZURE_KEY_VAULT_ALGORITHM: &str = "RSA-OAEP-256";
+
+/// Configuration of a key wrapping the data keys of the `encrypt_envelope` and `decrypt_envelope` functions.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct EncryptionKeyConfig {
+    #[configurable(derived)]
+    #[serde(flatten)]
+    provider: KeyProviderConfig,
+
+    /// How long a data key encrypts values before it's replaced, in seconds.
+    ///
+    /// Unwrapped data keys are cached for as long, so that values are decrypted without calling the key management
+    /// service for each of them.
+    #[serde(default = "default_data_key_ttl_secs")]
+    data_key_ttl_secs: u64,
+
+    /// How long to wait for the key management service, in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+}
+
+const fn default_data_key_ttl_secs() -> u64 {
+    3600
+}
+
+const fn default_timeout_ms() -> u64 {
+    5000
+}
+
+/// The key management service holding the key.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum KeyProviderConfig {
+    /// A key of the AWS Key Management Service.
+    AwsKms(#[configurable(derived)] AwsKmsConfig),
+
+    /// A key of the Google Cloud Key Management Service.
+    GcpKms(#[configurable(derived)] GcpKmsConfig),
+
+    /// An RSA key of Azure Key Vault.
+    AzureKeyVault(#[configurable(derived)] AzureKeyVaultConfig),
+}
+
+/// Configuration of a key of the AWS Key Management Service.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct AwsKmsConfig {
+    /// The ID, ARN, or alias of the key.
+    key_id: String,
+
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    auth: AwsAuthentication,
+}
+
+/// Configuration of a key of the Google Cloud Key Management Service.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct GcpKmsConfig {
+    /// The resource name of the key, in the
+    /// `projects/<project>/locations/<location>/keyRings/<key_ring>/cryptoKeys/<key>` format.
+    key_name: String,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+}
+
+/// Configuration of an RSA key of Azure Key Vault.
+///
+/// Vector authenticates with the default Azure credentials of its environment.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct AzureKeyVaultConfig {
+    /// The URL of the vault, such as `https://my-vault.vault.azure.net`.
+    vault_url: String,
+
+    /// The name of the key. Its latest version wraps new data keys.
+    key_name: String,
+}
+
+/// Builds the encryption functions for the configured keys.
+pub(super) fn functions(
+    keys: &HashMap<String, EncryptionKeyConfig>,
+    proxy: &ProxyConfig,
+) -> crate::Result<Vec<Box<dyn Function>>> {
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut built = HashMap::new();
+    for (name, config) in keys {
+        if config.timeout_ms == 0 {
+            return Err(format!(
+                "`timeout_ms` of encryption key {:?} must be greater than zero",
+                name
+            )
+            .into());
+        }
+        built.insert(
+            name.clone(),
+            Arc::new(EncryptionKey::new(config, proxy.clone(), OnceCell::new())),
+        );
+    }
+    let keys = Arc::new(built);
+
+    Ok(vec![
+        Box::new(EncryptEnvelope {
+            keys: Arc::clone(&keys),
+        }),
+        Box::new(DecryptEnvelope { keys }),
+    ])
+}
+
+/// Wraps and unwraps data keys with a key held by a key management service.
+#[async_trait::async_trait]
+trait KeyProvider: Send + Sync {
+    async fn wrap(&self, data_key: &[u8]) -> crate::Result<Vec<u8>>;
+
+    async fn unwrap(&self, wrapped: &[u8]) -> crate::Result<Vec<u8>>;
+}
+
+impl KeyProviderConfig {
+    async fn build(&self, proxy: &ProxyConfig) -> crate::Result<Box<dyn KeyProvider>> {
+        Ok(match self {
+            Self::AwsKms(config) => Box::new(AwsKms {
+                client: create_client::<KmsClientBuilder>(
+                    &config.auth,
+                    config.region.region(),
+                    config.region.endpoint()?,
+                    proxy,
+                    &None,
+                    false,
+                )
+                .await?,
+                key_id: config.key_id.clone(),
+            }),
+            Self::GcpKms(config) => {
+                let auth = config.auth.build(Scope::CloudPlatform).await?;
+                auth.spawn_regenerate_token();
+                Box::new(GcpKms {
+                    client: HttpClient::new(None, proxy)?,
+                    auth,
+                    key_name: config.key_name.clone(),
+                })
+            }
+            Self::AzureKeyVault(config) => Box::new(AzureKeyVault {
+                client: HttpClient::new(None, proxy)?,
+                credential: AutoRefreshingTokenCredential::new(Arc::new(
+                    DefaultAzureCredential::default(),
+                )),
+                key_url: format!(
+                    "{}/keys/{}",
+                    config.vault_url.trim_end_matches('/'),
+                    config.key_name
+                ),
+            }),
+        })
+    }
+}
+
+struct KmsClientBuilder;
+
+impl ClientBuilder for KmsClientBuilder {
+    type Config = aws_sdk_kms::config::Config;
+    type Client = aws_sdk_kms::client::Client;
+    type DefaultMiddleware = aws_sdk_kms::middleware::DefaultMiddleware;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_kms::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        aws_sdk_kms::client::Client::with_config(client, config.into())
+    }
+}
+
+struct AwsKms {
+    client: aws_sdk_kms::Client,
+    key_id: String,
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for AwsKms {
+    async fn wrap(&self, data_key: &[u8]) -> crate::Result<Vec<u8>> {
+        let output = self
+            .client
+            .encrypt()
+            .key_id(&self.key_id)
+            .plaintext(Blob::new(data_key))
+            .send()
+            .await?;
+        output
+            .ciphertext_blob()
+            .map(|blob| blob.as_ref().to_vec())
+            .ok_or_else(|| "AWS KMS returned no ciphertext".into())
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> crate::Result<Vec<u8>> {
+        let output = self
+            .client
+            .decrypt()
+            .key_id(&self.key_id)
+            .ciphertext_blob(Blob::new(wrapped))
+            .send()
+            .await?;
+        output
+            .plaintext()
+            .map(|blob| blob.as_ref().to_vec())
+            .ok_or_else(|| "AWS KMS returned no plaintext".into())
+    }
+}
+
+/// Sends a JSON request, returning the JSON reply.
+async fn post_json(
+    client: &HttpClient,
+    uri: &str,
+    body: serde_json::Value,
+    authorize: impl FnOnce(&mut Request<Body>),
+) -> crate::Result<serde_json::Value> {
+    let mut request = Request::post(uri)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&body)?))?;
+    authorize(&mut request);
+
+    let (parts, body) = client.send(request).await?.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    if !parts.status.is_success() {
+        return Err(format!(
+            "unexpected status {}: {}",
+            parts.status,
+            String::from_utf8_lossy(&body)
+        )
+        .into());
+    }
+    Ok(serde_json::from_slice(&body)?)
+}
+
+fn string_field<'a>(reply: &'a serde_json::Value, field: &str) -> crate::Result<&'a str> {
+    reply
+        .get(field)
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| format!("missing `{}` in the reply", field).into())
+}
+
+struct GcpKms {
+    client: HttpClient,
+    auth: GcpAuthenticator,
+    key_name: String,
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for GcpKms {
+    async fn wrap(&self, data_key: &[u8]) -> crate::Result<Vec<u8>> {
+        let uri = format!("{}/{}:encrypt", GCP_KMS_URL, self.key_name);
+        let body = json!({ "plaintext": base64::encode(data_key) });
+        let reply = post_json(&self.client, &uri, body, |request| self.auth.apply(request)).await?;
+        Ok(base64::decode(string_field(&reply, "ciphertext")?)?)
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> crate::Result<Vec<u8>> {
+        let uri = format!("{}/{}:decrypt", GCP_KMS_URL, self.key_name);
+        let body = json!({ "ciphertext": base64::encode(wrapped) });
+        let reply = post_json(&self.client, &uri, body, |request| self.auth.apply(request)).await?;
+        Ok(base64::decode(string_field(&reply, "plaintext")?)?)
+    }
+}
+
+struct AzureKeyVault {
+    client: HttpClient,
+    credential: AutoRefreshingTokenCredential,
+    key_url: String,
+}
+
+impl AzureKeyVault {
+    async fn call(&self, uri: &str, value: &str) -> crate::Result<serde_json::Value> {
+        let token = self.credential.get_token(AZURE_KEY_VAULT_RESOURCE).await?;
+        let authorization = format!("Bearer {}", token.token.secret());
+        let uri = format!("{}?api-version={}", uri, AZURE_KEY_VAULT_API_VERSION);
+        let body = json!({ "alg": AZURE_KEY_VAULT_ALGORITHM, "value": value });
+        post_json(&self.client, &uri, body, |request| {
+            request
+                .headers_mut()
+                .insert(http::header::AUTHORIZATION, authorization.parse().unwrap());
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for AzureKeyVault {
+    /// Wraps a data key with the latest version of the key, which is kept with the wrapped key, so that data keys
+    /// remain unwrappable once the key rotates.
+    async fn wrap(&self, data_key: &[u8]) -> crate::Result<Vec<u8>> {
+        let value = base64::encode_config(data_key, base64::URL_SAFE_NO_PAD);
+        let reply = self
+            .call(&format!("{}/wrapkey", self.key_url), &value)
+            .await?;
+        let wrapped = json!({
+            "kid": string_field(&reply, "kid")?,
+            "value": string_field(&reply, "value")?,
+        });
+        Ok(serde_json::to_vec(&wrapped)?)
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> crate::Result<Vec<u8>> {
+        let wrapped: serde_json::Value = serde_json::from_slice(wrapped)?;
+        let kid = string_field(&wrapped, "kid")?;
+        // The key ID comes from the envelope, so it's checked before the credentials are sent to it.
+        if !kid.starts_with(&format!("{}/", self.key_url)) {
+            return Err(format!("data key was wrapped by another key: {}", kid).into());
+        }
+        let reply = self
+            .call(
+                &format!("{}/unwrapkey", kid),
+                string_field(&wrapped, "value")?,
+            )
+            .await?;
+        Ok(base64::decode_config(
+            string_field(&reply, "value")?,
+            base64::URL_SAFE_NO_PAD,
+        )?)
+    }
+}
+
+/// The data key currently encrypting values.
+struct DataKey {
+    plaintext: Bytes,
+    wrapped: Bytes,
+    created_at: Instant,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct EncryptionKey {
+    provider_config: KeyProviderConfig,
+    #[derivative(Debug = "ignore")]
+    proxy: ProxyConfig,
+    #[derivative(Debug = "ignore")]
+    provider: OnceCell<Box<dyn KeyProvider>>,
+    timeout: Duration,
+    data_key_ttl: Duration,
+    #[derivative(Debug = "ignore")]
+    current: Mutex<Option<DataKey>>,
+    #[derivative(Debug = "ignore")]
+    unwrapped: Cache<Bytes, Bytes>,
+}
+
+impl EncryptionKey {
+    fn new(
+        config: &EncryptionKeyConfig,
+        proxy: ProxyConfig,
+        provider: OnceCell<Box<dyn KeyProvider>>,
+    ) -> Self {
+        let data_key_ttl = Duration::from_secs(config.data_key_ttl_secs);
+        Self {
+            provider_config: config.provider.clone(),
+            proxy,
+            provider,
+            timeout: Duration::from_millis(config.timeout_ms),
+            data_key_ttl,
+            current: Mutex::new(None),
+            unwrapped: Cache::new(MAX_CACHED_DATA_KEYS, data_key_ttl),
+        }
+    }
+
+    /// Waits for the key management service, creating its client on first use.
+    fn call<T>(
+        &self,
+        call: impl for<'a> FnOnce(
+            &'a dyn KeyProvider,
+        ) -> futures::future::BoxFuture<'a, crate::Result<T>>,
+    ) -> Result<T> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(tokio::time::timeout(self.timeout, async {
+                let provider = self
+                    .provider
+                    .get_or_try_init(|| self.provider_config.build(&self.proxy))
+                    .await?;
+                call(provider.as_ref()).await
+            }))
+        })
+        .map_err(|_| {
+            format!(
+                "key management service timed out after {}ms",
+                self.timeout.as_millis()
+            )
+        })?
+        .map_err(|error| format!("key management service failed: {}", error).into())
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let now = Instant::now();
+        let mut current = self.current.lock().expect("mutex poisoned");
+        let expired = current.as_ref().map_or(true, |data_key| {
+            now.duration_since(data_key.created_at) >= self.data_key_ttl
+        });
+        if expired {
+            let data_key = Bytes::from(random_bytes(DATA_KEY_LEN)?);
+            let wrapping = data_key.clone();
+            let wrapped = Bytes::from(
+                self.call(|provider| Box::pin(async move { provider.wrap(&wrapping).await }))?,
+            );
+            self.unwrapped.put(wrapped.clone(), data_key.clone(), now);
+            *current = Some(DataKey {
+                plaintext: data_key,
+                wrapped,
+                created_at: now,
+            });
+        }
+
+        let data_key = current.as_ref().expect("data key was just created");
+        seal(&data_key.plaintext, &data_key.wrapped, plaintext)
+    }
+
+    fn decrypt(&self, envelope: &[u8]) -> Result<Vec<u8>> {
+        let envelope = Envelope::parse(envelope)?;
+        let wrapped = Bytes::copy_from_slice(envelope.wrapped);
+
+        let now = Instant::now();
+        let data_key = match self.unwrapped.get(&wrapped, now) {
+            Some(data_key) => data_key,
+            None => {
+                let unwrapped = wrapped.clone();
+                let data_key =
+                    Bytes::from(self.call(|provider| {
+                        Box::pin(async move { provider.unwrap(&unwrapped).await })
+                    })?);
+                self.unwrapped.put(wrapped, data_key.clone(), now);
+                data_key
+            }
+        };
+        envelope.open(&data_key)
+    }
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    openssl::rand::rand_bytes(&mut bytes)
+        .map_err(|error| format!("unable to generate random bytes: {}", error))?;
+    Ok(bytes)
+}
+
+/// Encrypts a value with a data key, into an envelope holding the wrapped data key, the nonce, and the ciphertext with
+/// its authentication tag.
+fn seal(data_key: &[u8], wrapped: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let wrapped_len = u16::try_from(wrapped.len()).map_err(|_| "wrapped data key is too long")?;
+    let nonce = random_bytes(NONCE_LEN)?;
+    let mut tag = [0; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        data_key,
+        Some(&nonce),
+        &[],
+        plaintext,
+        &mut tag,
+    )
+    .map_err(|error| format!("unable to encrypt: {}", error))?;
+
+    let mut envelope =
+        Vec::with_capacity(3 + wrapped.len() + NONCE_LEN + ciphertext.len() + TAG_LEN);
+    envelope.push(ENVELOPE_VERSION);
+    envelope.extend_from_slice(&wrapped_len.to_be_bytes());
+    envelope.extend_from_slice(wrapped);
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+    envelope.extend_from_slice(&tag);
+    Ok(envelope)
+}
+
+struct Envelope<'a> {
+    wrapped: &'a [u8],
+    nonce: &'a [u8],
+    ciphertext: &'a [u8],
+    tag: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(envelope: &'a [u8]) -> Result<Self> {
+        let invalid = || ExpressionError::from("invalid envelope");
+        let (&version, rest) = envelope.split_first().ok_or_else(invalid)?;
+        if version != ENVELOPE_VERSION {
+            return Err(format!("unsupported envelope version {}", version).into());
+        }
+        if rest.len() < 2 {
+            return Err(invalid());
+        }
+        let (wrapped_len, rest) = rest.split_at(2);
+        let wrapped_len = u16::from_be_bytes([wrapped_len[0], wrapped_len[1]]) as usize;
+        if rest.len() < wrapped_len + NONCE_LEN + TAG_LEN {
+            return Err(invalid());
+        }
+        let (wrapped, rest) = rest.split_at(wrapped_len);
+        let (nonce, rest) = rest.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        Ok(Self {
+            wrapped,
+            nonce,
+            ciphertext,
+            tag,
+        })
+    }
+
+    fn open(&self, data_key: &[u8]) -> Result<Vec<u8>> {
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            data_key,
+            Some(self.nonce),
+            &[],
+            self.ciphertext,
+            self.tag,
+        )
+        .map_err(|_| {
+            "unable to decrypt: the envelope was tampered with, or wrapped by another key".into()
+        })
+    }
+}
+
+fn compile_key(
+    keys: &HashMap<String, Arc<EncryptionKey>>,
+    arguments: &mut ArgumentList,
+) -> std::result::Result<Arc<EncryptionKey>, Box<dyn DiagnosticMessage>> {
+    let mut names = keys
+        .keys()
+        .map(|name| Value::from(name.as_str()))
+        .collect::<Vec<_>>();
+    names.sort();
+    let name = arguments
+        .required_enum("key", &names)?
+        .try_bytes_utf8_lossy()
+        .expect("key not bytes")
+        .into_owned();
+    Ok(Arc::clone(&keys[&name]))
+}
+
+#[derive(Clone, Debug)]
+struct EncryptEnvelope {
+    keys: Arc<HashMap<String, Arc<EncryptionKey>>>,
+}
+
+impl Function for EncryptEnvelope {
+    fn identifier(&self) -> &'static str {
+        "encrypt_envelope"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "plaintext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encrypt a field",
+            source: r#"encode_base64(encrypt_envelope!("123-45-6789", key: "pii"))"#,
+            result: Ok("AQC4AQIDAHh..."),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let plaintext = arguments.required("plaintext");
+        let key = compile_key(&self.keys, &mut arguments)?;
+
+        Ok(Box::new(EncryptEnvelopeFn { plaintext, key }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptEnvelopeFn {
+    plaintext: Box<dyn Expression>,
+    key: Arc<EncryptionKey>,
+}
+
+impl Expression for EncryptEnvelopeFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let plaintext = self.plaintext.resolve(ctx)?.try_bytes()?;
+        Ok(Value::Bytes(self.key.encrypt(&plaintext)?.into()))
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecryptEnvelope {
+    keys: Arc<HashMap<String, Arc<EncryptionKey>>>,
+}
+
+impl Function for DecryptEnvelope {
+    fn identifier(&self) -> &'static str {
+        "decrypt_envelope"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "ciphertext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "decrypt a field",
+            source: r#"decrypt_envelope!(decode_base64!(.ssn), key: "pii")"#,
+            result: Ok("123-45-6789"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let ciphertext = arguments.required("ciphertext");
+        let key = compile_key(&self.keys, &mut arguments)?;
+
+        Ok(Box::new(DecryptEnvelopeFn { ciphertext, key }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecryptEnvelopeFn {
+    ciphertext: Box<dyn Expression>,
+    key: Arc<EncryptionKey>,
+}
+
+impl Expression for DecryptEnvelopeFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ciphertext = self.ciphertext.resolve(ctx)?.try_bytes()?;
+        Ok(Value::Bytes(self.key.decrypt(&ciphertext)?.into()))
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Wraps data keys by reversing them, counting the calls.
+    #[derive(Default)]
+    struct Reversing {
+        wraps: Arc<AtomicUsize>,
+        unwraps: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl KeyProvider for Reversing {
+        async fn wrap(&self, data_key: &[u8]) -> crate::Result<Vec<u8>> {
+            self.wraps.fetch_add(1, Ordering::SeqCst);
+            Ok(data_key.iter().rev().copied().collect())
+        }
+
+        async fn unwrap(&self, wrapped: &[u8]) -> crate::Result<Vec<u8>> {
+            self.unwraps.fetch_add(1, Ordering::SeqCst);
+            Ok(wrapped.iter().rev().copied().collect())
+        }
+    }
+
+    fn key(data_key_ttl_secs: u64) -> (EncryptionKey, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let config: EncryptionKeyConfig = toml::from_str(&format!(
+            r#"
+            provider = "aws_kms"
+            key_id = "alias/pii"
+            region = "us-east-1"
+            data_key_ttl_secs = {}
+            "#,
+            data_key_ttl_secs
+        ))
+        .unwrap();
+        let provider = Reversing::default();
+        let (wraps, unwraps) = (Arc::clone(&provider.wraps), Arc::clone(&provider.unwraps));
+        let key = EncryptionKey::new(
+            &config,
+            ProxyConfig::default(),
+            OnceCell::new_with(Some(Box::new(provider) as Box<dyn KeyProvider>)),
+        );
+        (key, wraps, unwraps)
+    }
+
+    #[test]
+    fn parses_providers() {
+        for config in [
+            r#"
+            provider = "aws_kms"
+            key_id = "alias/pii"
+            region = "us-east-1"
+            "#,
+            r#"
+            provider = "gcp_kms"
+            key_name = "projects/p/locations/global/keyRings/r/cryptoKeys/pii"
+            credentials_path = "/etc/vector/gcp.json"
+            "#,
+            r#"
+            provider = "azure_key_vault"
+            vault_url = "https://my-vault.vault.azure.net"
+            key_name = "pii"
+            "#,
+        ] {
+            toml::from_str::<EncryptionKeyConfig>(config).unwrap();
+        }
+        assert!(toml::from_str::<EncryptionKeyConfig>(r#"provider = "vault""#).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn round_trips_values() {
+        let (key, wraps, unwraps) = key(3600);
+        let first = key.encrypt(b"123-45-6789").unwrap();
+        let second = key.encrypt(b"123-45-6789").unwrap();
+        assert_ne!(first, second);
+
+        assert_eq!(key.decrypt(&first).unwrap(), b"123-45-6789");
+        assert_eq!(key.decrypt(&second).unwrap(), b"123-45-6789");
+        assert_eq!(wraps.load(Ordering::SeqCst), 1);
+        assert_eq!(unwraps.load(Ordering::SeqCst), 0);
+
+        // Other instances unwrap the data key once.
+        let (other, _, unwraps) = self::key(3600);
+        assert_eq!(other.decrypt(&first).unwrap(), b"123-45-6789");
+        assert_eq!(other.decrypt(&second).unwrap(), b"123-45-6789");
+        assert_eq!(unwraps.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rotates_data_keys() {
+        let (key, wraps, _) = key(0);
+        let first = key.encrypt(b"a").unwrap();
+        let second = key.encrypt(b"b").unwrap();
+        assert_eq!(wraps.load(Ordering::SeqCst), 2);
+        assert_ne!(
+            Envelope::parse(&first).unwrap().wrapped,
+            Envelope::parse(&second).unwrap().wrapped
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_tampered_envelopes() {
+        let (key, _, _) = key(3600);
+        let mut envelope = key.encrypt(b"123-45-6789").unwrap();
+
+        let last = envelope.len() - 1;
+        envelope[last] ^= 1;
+        assert!(key.decrypt(&envelope).is_err());
+        assert!(key.decrypt(&envelope[..10]).is_err());
+        assert!(key.decrypt(b"").is_err());
+    }
+
+    #[test]
+    fn only_configured_keys_are_accepted() {
+        let keys: HashMap<String, EncryptionKeyConfig> = toml::from_str(
+            r#"
+            pii = { provider = "aws_kms", key_id = "alias/pii", region = "us-east-1" }
+            "#,
+        )
+        .unwrap();
+        let mut functions = vrl_stdlib::all();
+        functions.append(&mut super::functions(&keys, &ProxyConfig::default()).unwrap());
+
+        assert!(vrl::compile(r#"encrypt_envelope!("a", key: "pii")"#, &functions).is_ok());
+        assert!(vrl::compile(r#"encrypt_envelope!("a", key: "other")"#, &functions).is_err());
+        assert!(super::functions(&HashMap::new(), &ProxyConfig::default())
+            .unwrap()
+            .is_empty());
+    }
+}
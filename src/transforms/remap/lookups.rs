@@ -0,0 +1,522 @@
+//! VRL functions looking up external services, which are only available to programs when enabled in the `lookups`
+//! option of the `remap` transform.
+//!
+//! VRL programs are resolved synchronously, so the worker thread is blocked until a lookup completes, or times out.
+//! The results of lookups are cached, to keep these waits rare.
+use std::{
+    future::Future,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use hyper::{Body, Request};
+use tokio::sync::Semaphore;
+use value::Value;
+use vector_config::configurable_component;
+use vrl::prelude::*;
+
+use crate::{config::ProxyConfig, enrichment_tables::cache::Cache, http::HttpClient};
+
+/// Configuration of an external lookup function.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct LookupConfig {
+    /// Whether the function is available to the program.
+    pub enabled: bool,
+
+    /// How long a lookup may take, including its wait for a free slot, in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    #[derivative(Default(value = "default_timeout_ms()"))]
+    pub timeout_ms: u64,
+
+    /// The maximum number of lookups of the function in flight at once.
+    #[serde(default = "default_max_concurrency")]
+    #[derivative(Default(value = "default_max_concurrency()"))]
+    pub max_concurrency: usize,
+
+    /// How long the results of lookups are cached, in seconds.
+    ///
+    /// Failed lookups aren't cached.
+    #[serde(default = "default_cache_ttl_secs")]
+    #[derivative(Default(value = "default_cache_ttl_secs()"))]
+    pub cache_ttl_secs: u64,
+
+    /// The maximum number of results cached.
+    #[serde(default = "default_cache_max_entries")]
+    #[derivative(Default(value = "default_cache_max_entries()"))]
+    pub cache_max_entries: usize,
+}
+
+const fn default_timeout_ms() -> u64 {
+    1000
+}
+
+const fn default_max_concurrency() -> usize {
+    16
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+const fn default_cache_max_entries() -> usize {
+    10_000
+}
+
+/// External lookup functions available to the program.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct LookupsConfig {
+    /// The `dns_lookup` function, resolving host names to IP addresses, or IP addresses to host names.
+    pub dns: LookupConfig,
+
+    /// The `http_get` function, fetching the body of a URL.
+    pub http: LookupConfig,
+}
+
+impl LookupsConfig {
+    /// Builds the enabled lookup functions.
+    pub(super) fn functions(&self, proxy: &ProxyConfig) -> crate::Result<Vec<Box<dyn Function>>> {
+        let mut functions = Vec::<Box<dyn Function>>::new();
+        if self.dns.enabled {
+            functions.push(Box::new(DnsLookup {
+                lookups: Arc::new(Lookups::new(&self.dns)?),
+            }));
+        }
+        if self.http.enabled {
+            functions.push(Box::new(HttpGet {
+                lookups: Arc::new(Lookups::new(&self.http)?),
+                client: HttpClient::new(None, proxy)?,
+            }));
+        }
+        Ok(functions)
+    }
+}
+
+/// Bounds the lookups of a function, and caches their results.
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Lookups {
+    timeout: Duration,
+    semaphore: Semaphore,
+    #[derivative(Debug = "ignore")]
+    cache: Cache<String, Value>,
+}
+
+impl Lookups {
+    fn new(config: &LookupConfig) -> crate::Result<Self> {
+        if config.timeout_ms == 0 || config.max_concurrency == 0 || config.cache_max_entries == 0 {
+            return Err(
+                "`timeout_ms`, `max_concurrency`, and `cache_max_entries` of lookups must be greater than zero"
+                    .into(),
+            );
+        }
+        Ok(Self {
+            timeout: Duration::from_millis(config.timeout_ms),
+            semaphore: Semaphore::new(config.max_concurrency),
+            cache: Cache::new(
+                config.cache_max_entries,
+                Duration::from_secs(config.cache_ttl_secs),
+            ),
+        })
+    }
+
+    /// Returns the cached result of a lookup, or waits for the lookup.
+    fn lookup(
+        &self,
+        key: String,
+        lookup: impl Future<Output = std::result::Result<Value, String>>,
+    ) -> Resolved {
+        let now = Instant::now();
+        if let Some(value) = self.cache.get(&key, now) {
+            return Ok(value);
+        }
+
+        let value = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(tokio::time::timeout(self.timeout, async {
+                let _permit = self
+                    .semaphore
+                    .acquire()
+                    .await
+                    .expect("semaphore is never closed");
+                lookup.await
+            }))
+        })
+        .map_err(|_| format!("lookup timed out after {}ms", self.timeout.as_millis()))??;
+
+        self.cache.put(key, value.clone(), now);
+        Ok(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DnsLookup {
+    lookups: Arc<Lookups>,
+}
+
+impl Function for DnsLookup {
+    fn identifier(&self) -> &'static str {
+        "dns_lookup"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "reverse",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "resolve a host name",
+                source: r#"dns_lookup!("localhost")"#,
+                result: Ok(r#"["127.0.0.1"]"#),
+            },
+            Example {
+                title: "resolve an IP address",
+                source: r#"dns_lookup!("127.0.0.1", reverse: true)"#,
+                result: Ok("localhost"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let reverse = arguments
+            .optional("reverse")
+            .unwrap_or_else(|| expr!(false));
+
+        Ok(Box::new(DnsLookupFn {
+            lookups: Arc::clone(&self.lookups),
+            value,
+            reverse,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DnsLookupFn {
+    lookups: Arc<Lookups>,
+    value: Box<dyn Expression>,
+    reverse: Box<dyn Expression>,
+}
+
+impl Expression for DnsLookupFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let value = value.try_bytes_utf8_lossy()?.into_owned();
+        let reverse = self.reverse.resolve(ctx)?.try_boolean()?;
+
+        if reverse {
+            let ip: IpAddr = value
+                .parse()
+                .map_err(|err| format!("unable to parse IP address: {}", err))?;
+            self.lookups.lookup(format!("reverse:{}", ip), async move {
+                tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip))
+                    .await
+                    .map_err(|err| format!("unable to perform a lookup: {}", err))?
+                    .map(Value::from)
+                    .map_err(|err| format!("unable to perform a lookup: {}", err))
+            })
+        } else {
+            self.lookups.lookup(format!("forward:{}", value), async {
+                let mut addresses = Vec::new();
+                for address in tokio::net::lookup_host((value.as_str(), 0))
+                    .await
+                    .map_err(|err| format!("unable to perform a lookup: {}", err))?
+                {
+                    let address = Value::from(address.ip().to_string());
+                    if !addresses.contains(&address) {
+                        addresses.push(address);
+                    }
+                }
+                Ok(Value::Array(addresses))
+            })
+        }
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::bytes()
+            .add_array(Collection::from_unknown(Kind::bytes()))
+            .fallible()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct HttpGet {
+    lookups: Arc<Lookups>,
+    client: HttpClient,
+}
+
+impl Function for HttpGet {
+    fn identifier(&self) -> &'static str {
+        "http_get"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "url",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "fetch a URL",
+            source: r#"http_get!("http://localhost:8080/owners/web-01")"#,
+            result: Ok("team-a"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let url = arguments.required("url");
+
+        Ok(Box::new(HttpGetFn {
+            lookups: Arc::clone(&self.lookups),
+            client: self.client.clone(),
+            url,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HttpGetFn {
+    lookups: Arc<Lookups>,
+    client: HttpClient,
+    url: Box<dyn Expression>,
+}
+
+impl Expression for HttpGetFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let url = self.url.resolve(ctx)?;
+        let url = url.try_bytes_utf8_lossy()?.into_owned();
+        let request = Request::get(url.as_str())
+            .body(Body::empty())
+            .map_err(|err| format!("invalid URL: {}", err))?;
+
+        self.lookups.lookup(url, async {
+            let response = self
+                .client
+                .send(request)
+                .await
+                .map_err(|err| format!("request failed: {}", err))?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(format!("unexpected status {}", status));
+            }
+            let body = hyper::body::to_bytes(response.into_body())
+                .await
+                .map_err(|err| format!("unable to read response: {}", err))?;
+            Ok(Value::Bytes(body))
+        })
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server, StatusCode,
+    };
+
+    use value::Secrets;
+    use vector_common::TimeZone;
+    use vrl::TargetValue;
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    fn lookups(cache_ttl_secs: u64) -> Lookups {
+        Lookups::new(&LookupConfig {
+            enabled: true,
+            cache_ttl_secs,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn functions(config: &str) -> Vec<&'static str> {
+        toml::from_str::<LookupsConfig>(config)
+            .unwrap()
+            .functions(&ProxyConfig::default())
+            .unwrap()
+            .iter()
+            .map(|function| function.identifier())
+            .collect()
+    }
+
+    #[test]
+    fn only_enabled_functions_are_available() {
+        assert!(functions("").is_empty());
+        assert_eq!(functions("dns.enabled = true"), vec!["dns_lookup"]);
+        assert_eq!(
+            functions("dns.enabled = true\nhttp.enabled = true"),
+            vec!["dns_lookup", "http_get"]
+        );
+    }
+
+    #[test]
+    fn rejects_zero_limits() {
+        let config = LookupConfig {
+            enabled: true,
+            max_concurrency: 0,
+            ..Default::default()
+        };
+        assert!(Lookups::new(&config).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn caches_successful_lookups() {
+        let lookups = lookups(60);
+        let calls = AtomicUsize::new(0);
+        let lookup = |result: std::result::Result<Value, String>| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { result }
+        };
+
+        assert!(lookups
+            .lookup("a".to_owned(), lookup(Err("unavailable".to_owned())))
+            .is_err());
+        assert_eq!(
+            lookups
+                .lookup("a".to_owned(), lookup(Ok("first".into())))
+                .unwrap(),
+            "first".into()
+        );
+        assert_eq!(
+            lookups
+                .lookup("a".to_owned(), lookup(Ok("second".into())))
+                .unwrap(),
+            "first".into()
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let lookups = self::lookups(0);
+        lookups
+            .lookup("a".to_owned(), async { Ok("first".into()) })
+            .unwrap();
+        assert_eq!(
+            lookups
+                .lookup("a".to_owned(), async { Ok("second".into()) })
+                .unwrap(),
+            "second".into()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn times_out_slow_lookups() {
+        let lookups = Lookups::new(&LookupConfig {
+            enabled: true,
+            timeout_ms: 10,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let error = lookups
+            .lookup("a".to_owned(), async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok("late".into())
+            })
+            .unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{}", error);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resolves_ip_addresses() {
+        let function = DnsLookupFn {
+            lookups: Arc::new(lookups(60)),
+            value: expr!("127.0.0.1"),
+            reverse: expr!(false),
+        };
+        let mut state = vrl::state::Runtime::default();
+        let mut target = TargetValue {
+            value: value!({}),
+            metadata: value!({}),
+            secrets: Secrets::new(),
+        };
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+        assert_eq!(function.resolve(&mut ctx).unwrap(), value!(["127.0.0.1"]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetches_urls() {
+        let addr: SocketAddr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_service = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        Ok::<_, Infallible>(match request.uri().path() {
+                            "/owners/web-01" => Response::new(Body::from("team-a")),
+                            _ => Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::empty())
+                                .unwrap(),
+                        })
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_service));
+
+        let function = |url: String| HttpGetFn {
+            lookups: Arc::new(lookups(60)),
+            client: HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            url: expr!(url),
+        };
+        let mut state = vrl::state::Runtime::default();
+        let mut target = TargetValue {
+            value: value!({}),
+            metadata: value!({}),
+            secrets: Secrets::new(),
+        };
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        let found = function(format!("http://{}/owners/web-01", addr));
+        assert_eq!(found.resolve(&mut ctx).unwrap(), "team-a".into());
+        assert_eq!(found.resolve(&mut ctx).unwrap(), "team-a".into());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let missing = function(format!("http://{}/owners/web-02", addr));
+        assert!(missing.resolve(&mut ctx).is_err());
+    }
+}
@@ -19,16 +19,25 @@ use vrl::{
 
 use crate::{
     config::{
-        log_schema, ComponentKey, DataType, Input, Output, TransformConfig, TransformContext,
-        TransformDescription,
+        log_schema, ComponentKey, DataType, Input, Output, ProxyConfig, TransformConfig,
+        TransformContext, TransformDescription,
     },
-    event::{Event, TargetEvents, VrlTarget},
+    event::{Event, LogEvent, TargetEvents, Value, VrlTarget},
     internal_events::{RemapMappingAbort, RemapMappingError},
     schema,
     transforms::{SyncTransform, Transform, TransformOutputsBuf},
     Result,
 };
 
+mod emit;
+#[cfg(feature = "transforms-remap-kms")]
+mod encryption;
+#[cfg(feature = "transforms-remap-lookups")]
+mod lookups;
+mod modules;
+#[cfg(feature = "transforms-remap-state")]
+mod state;
+
 const DROPPED: &str = "dropped";
 
 /// Configuration for the `remap` transform.
@@ -95,6 +104,29 @@ pub struct RemapConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub runtime: VrlRuntime,
+
+    /// Functions looking up external services, such as DNS servers or HTTP endpoints, made available to the program.
+    ///
+    /// These functions are disabled by default. Processing of an event blocks until its lookups complete or time out,
+    /// so their results are cached. Only available when built with the `transforms-remap-lookups` feature.
+    #[cfg(feature = "transforms-remap-lookups")]
+    #[configurable(derived)]
+    pub lookups: lookups::LookupsConfig,
+
+    /// Functions keeping per-key state across events, such as counters, made available to the program.
+    ///
+    /// These functions are disabled by default. The state is kept in memory, and survives reloads of the configuration
+    /// as long as the ID of the transform is unchanged. Only available when built with the `transforms-remap-state` feature.
+    #[cfg(feature = "transforms-remap-state")]
+    #[configurable(derived)]
+    pub state: state::StateConfig,
+
+    /// Keys of key management services available to the `encrypt_envelope` and `decrypt_envelope` functions, by name.
+    ///
+    /// Values are encrypted with data keys wrapped by these keys, so that raw keys never appear in the configuration.
+    /// Only available when built with the `transforms-remap-kms` feature.
+    #[cfg(feature = "transforms-remap-kms")]
+    pub encryption_keys: std::collections::HashMap<String, encryption::EncryptionKeyConfig>,
 }
 
 impl RemapConfig {
@@ -102,6 +134,13 @@ impl RemapConfig {
         &self,
         enrichment_tables: enrichment::TableRegistry,
         merged_schema_definition: schema::Definition,
+        #[cfg_attr(
+            not(any(feature = "transforms-remap-lookups", feature = "transforms-remap-kms")),
+            allow(unused_variables)
+        )]
+        proxy: &ProxyConfig,
+        #[cfg_attr(not(feature = "transforms-remap-state"), allow(unused_variables))]
+        component_key: Option<&ComponentKey>,
     ) -> Result<(
         vrl::Program,
         String,
@@ -126,6 +165,14 @@ impl RemapConfig {
         let mut functions = vrl_stdlib::all();
         functions.append(&mut enrichment::vrl_functions());
         functions.append(&mut vector_vrl_functions::vrl_functions());
+        #[cfg(feature = "transforms-remap-lookups")]
+        functions.append(&mut self.lookups.functions(proxy)?);
+        #[cfg(feature = "transforms-remap-state")]
+        functions.append(&mut self.state.functions(component_key)?);
+        #[cfg(feature = "transforms-remap-kms")]
+        functions.append(&mut encryption::functions(&self.encryption_keys, proxy)?);
+        functions.push(Box::new(emit::Emit));
+        let source = modules::import(&source, &mut functions, &enrichment_tables)?;
 
         let mut state = vrl::state::ExternalEnv::new_with_kind(
             merged_schema_definition.collection().clone().into(),
@@ -191,6 +238,8 @@ impl TransformConfig for RemapConfig {
             .compile_vrl_program(
                 enrichment::TableRegistry::default(),
                 merged_definition.clone(),
+                &ProxyConfig::default(),
+                None,
             )
             .ok()
             .and_then(|(_, _, _, state)| {
@@ -311,6 +360,8 @@ impl Remap<AstRunner> {
         let (program, warnings, _, _) = config.compile_vrl_program(
             context.enrichment_tables.clone(),
             context.merged_schema_definition.clone(),
+            &context.globals.proxy,
+            context.key.as_ref(),
         )?;
 
         let runtime = Runtime::default();
@@ -413,6 +464,18 @@ where
     fn run_vrl(&mut self, target: &mut VrlTarget) -> std::result::Result<value::Value, Terminate> {
         self.runner.run(target, &self.program, &self.timezone)
     }
+
+    fn push_target_events(&self, target: VrlTarget, output: &mut TransformOutputsBuf) {
+        match target.into_events() {
+            TargetEvents::One(event) => {
+                push_default(event, output, &self.default_schema_definition)
+            }
+            TargetEvents::Logs(events) => events
+                .for_each(|event| push_default(event, output, &self.default_schema_definition)),
+            TargetEvents::Traces(events) => events
+                .for_each(|event| push_default(event, output, &self.default_schema_definition)),
+        }
+    }
 }
 
 impl<Runner> SyncTransform for Remap<Runner>
@@ -442,17 +505,21 @@ where
 
         let mut target = VrlTarget::new(event, self.program.info());
         let result = self.run_vrl(&mut target);
+        let emitted = emit::take_emitted();
 
         match result {
-            Ok(_) => match target.into_events() {
-                TargetEvents::One(event) => {
-                    push_default(event, output, &self.default_schema_definition)
+            Ok(_) => {
+                if !emitted.is_empty() {
+                    let metadata = target.metadata();
+                    for event in emitted {
+                        if let Value::Object(fields) = event {
+                            let event = LogEvent::from_map(fields, metadata.clone());
+                            push_default(event.into(), output, &self.default_schema_definition);
+                        }
+                    }
                 }
-                TargetEvents::Logs(events) => events
-                    .for_each(|event| push_default(event, output, &self.default_schema_definition)),
-                TargetEvents::Traces(events) => events
-                    .for_each(|event| push_default(event, output, &self.default_schema_definition)),
-            },
+                self.push_target_events(target, output);
+            }
             Err(reason) => {
                 let (reason, error, drop) = match reason {
                     Terminate::Abort(error) => {
@@ -731,6 +798,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_remap_emit() {
+        let event = {
+            let mut event = LogEvent::from("augment me");
+            event.insert("users", vec!["alice", "bob"]);
+            Event::from(event)
+        };
+
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                for_each(array!(.users)) -> |_index, user| {
+                    emit({"message": "login", "user": user})
+                }
+                del(.users)
+            "#}
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+
+        let out = collect_outputs(&mut tform, event);
+        assert_eq!(3, out.primary.len());
+        let mut result = out.primary.into_events();
+
+        // The emitted events come first, followed by the event itself.
+        let r = result.next().unwrap();
+        assert_eq!(get_field_string(&r, "user"), "alice");
+        assert_eq!(
+            r.metadata().schema_definition(),
+            &test_default_schema_definition()
+        );
+        assert_eq!(get_field_string(&result.next().unwrap(), "user"), "bob");
+        let r = result.next().unwrap();
+        assert_eq!(get_field_string(&r, "message"), "augment me");
+        assert!(r.as_log().get("users").is_none());
+    }
+
+    #[test]
+    fn check_remap_emit_error() {
+        let event = Event::from(LogEvent::from("augment me"));
+
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                emit({"message": "emitted"})
+                .not_an_int = int!(.message)
+            "#}
+                .to_owned(),
+            ),
+            drop_on_error: true,
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+
+        // The events emitted by a failed program are discarded with the event.
+        assert!(transform_one(&mut tform, event.clone()).is_none());
+
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                emit({"message": "emitted"})
+                . = []
+            "#}
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+
+        let event = transform_one(&mut tform, event).unwrap();
+        assert_eq!(get_field_string(&event, "message"), "emitted");
+    }
+
     #[test]
     fn check_remap_error() {
         let event = {
@@ -0,0 +1,592 @@
+//! Libraries of VRL functions imported by `remap` programs.
+//!
+//! A program imports libraries with `import "<path>"` statements at its top, each optionally followed by
+//! `as <namespace>`. The namespace defaults to the name of the file, without its extension. Libraries define functions
+//! as:
+//!
+//! ```text
+//! fn parse_user(value) {
+//!     parse_json!(value).user
+//! }
+//! ```
+//!
+//! which programs call as `parsers::parse_user(.message)`. The body of a function is a VRL program, in which the
+//! arguments are variables, and whose last expression is the result. It can call the functions defined before it in
+//! the library, but can't access the event.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use once_cell::sync::Lazy;
+use snafu::{ResultExt, Snafu};
+use value::{Secrets, Value};
+use vector_vrl_functions::set_semantic_meaning::MeaningList;
+use vrl::{diagnostic::Formatter, prelude::*, Program, TargetValue};
+
+/// The names and parameters of VRL functions are static, so those of library functions are leaked once per distinct
+/// function, rather than each time the configuration is reloaded.
+static SIGNATURES: Lazy<Mutex<HashMap<(String, Vec<String>), Signature>>> =
+    Lazy::new(Mutex::default);
+
+type Signature = (&'static str, &'static [Parameter]);
+
+#[derive(Debug, Snafu)]
+pub(super) enum ImportError {
+    #[snafu(display(
+        "Invalid import on line {}: expected `import \"<path>\"`, optionally followed by `as <namespace>`",
+        line
+    ))]
+    InvalidImport { line: usize },
+    #[snafu(display("Library {:?} needs a namespace: import it `as <namespace>`", path))]
+    MissingNamespace { path: PathBuf },
+    #[snafu(display("Namespace `{}` is imported twice", namespace))]
+    DuplicateNamespace { namespace: String },
+    #[snafu(display("Could not read library {:?}: {}", path, source))]
+    ReadLibrary { path: PathBuf, source: io::Error },
+    #[snafu(display("Invalid library {:?} on line {}: {}", path, line, message))]
+    InvalidLibrary {
+        path: PathBuf,
+        line: usize,
+        message: &'static str,
+    },
+    #[snafu(display("Function `{}` of library {:?} is defined twice", name, path))]
+    DuplicateFunction { path: PathBuf, name: String },
+    #[snafu(display(
+        "Function `{}` of library {:?} failed to compile:\n{}",
+        name,
+        path,
+        diagnostics
+    ))]
+    CompileFunction {
+        path: PathBuf,
+        name: String,
+        diagnostics: String,
+    },
+}
+
+/// Loads the libraries imported by a program into its functions, returning the program without its imports.
+///
+/// The lines of the imports are left empty, so that diagnostics keep pointing at the right lines.
+pub(super) fn import(
+    source: &str,
+    functions: &mut Vec<Box<dyn Function>>,
+    enrichment_tables: &enrichment::TableRegistry,
+) -> std::result::Result<String, ImportError> {
+    let mut lines = Vec::new();
+    let mut namespaces = HashSet::new();
+    let mut importing = true;
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if importing && is_import(trimmed) {
+            let (path, namespace) =
+                parse_import(trimmed).context(InvalidImportSnafu { line: index + 1 })?;
+            let path = Path::new(path);
+            let namespace = match namespace.or_else(|| path.file_stem().and_then(OsStr::to_str)) {
+                Some(namespace) if is_identifier(namespace) => namespace.to_owned(),
+                _ => return Err(ImportError::MissingNamespace { path: path.into() }),
+            };
+            if !namespaces.insert(namespace.clone()) {
+                return Err(ImportError::DuplicateNamespace { namespace });
+            }
+
+            load_library(path, &namespace, functions, enrichment_tables)?;
+            lines.push("");
+            continue;
+        }
+
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            importing = false;
+        }
+        lines.push(line);
+    }
+
+    Ok(if namespaces.is_empty() {
+        source.to_owned()
+    } else {
+        lines.join("\n")
+    })
+}
+
+fn is_import(line: &str) -> bool {
+    line.strip_prefix("import").map_or(false, |rest| {
+        rest.starts_with(char::is_whitespace) && rest.trim_start().starts_with('"')
+    })
+}
+
+/// Parses the path and namespace of an import.
+fn parse_import(line: &str) -> Option<(&str, Option<&str>)> {
+    let rest = line
+        .strip_prefix("import")?
+        .trim_start()
+        .strip_prefix('"')?;
+    let (path, rest) = rest.split_once('"')?;
+    let rest = match rest.split_once('#') {
+        Some((rest, _comment)) => rest.trim(),
+        None => rest.trim(),
+    };
+    if rest.is_empty() {
+        return Some((path, None));
+    }
+
+    let namespace = rest
+        .strip_prefix("as")?
+        .strip_prefix(char::is_whitespace)?
+        .trim();
+    is_identifier(namespace).then(|| (path, Some(namespace)))
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn load_library(
+    path: &Path,
+    namespace: &str,
+    functions: &mut Vec<Box<dyn Function>>,
+    enrichment_tables: &enrichment::TableRegistry,
+) -> std::result::Result<(), ImportError> {
+    let library = fs::read_to_string(path).context(ReadLibrarySnafu { path })?;
+    let definitions =
+        parse_library(&library).map_err(|(line, message)| ImportError::InvalidLibrary {
+            path: path.into(),
+            line,
+            message,
+        })?;
+
+    for definition in definitions {
+        let identifier = format!("{}::{}", namespace, definition.name);
+        if functions
+            .iter()
+            .any(|function| function.identifier() == identifier)
+        {
+            return Err(ImportError::DuplicateFunction {
+                path: path.into(),
+                name: definition.name.to_owned(),
+            });
+        }
+
+        // The arguments are passed as the fields of the target, and assigned to variables on the line of the opening
+        // brace, so that the lines of the body are unchanged.
+        let source = definition
+            .parameters
+            .iter()
+            .map(|parameter| format!("{0} = .{0}; ", parameter))
+            .chain(std::iter::once(definition.body.to_owned()))
+            .collect::<String>();
+
+        let mut state = vrl::state::ExternalEnv::default();
+        state.set_external_context(enrichment_tables.clone());
+        state.set_external_context(MeaningList::default());
+        let (program, _) =
+            vrl::compile_with_state(&source, functions, &mut state).map_err(|diagnostics| {
+                ImportError::CompileFunction {
+                    path: path.into(),
+                    name: definition.name.to_owned(),
+                    diagnostics: Formatter::new(&source, diagnostics).colored().to_string(),
+                }
+            })?;
+
+        let (identifier, parameters) = signature(identifier, &definition.parameters);
+        functions.push(Box::new(LibraryFunction {
+            identifier,
+            parameters,
+            program: Arc::new(program),
+        }));
+    }
+
+    Ok(())
+}
+
+fn signature(identifier: String, parameters: &[&str]) -> Signature {
+    let key = (
+        identifier,
+        parameters
+            .iter()
+            .map(|&parameter| parameter.to_owned())
+            .collect(),
+    );
+    *SIGNATURES
+        .lock()
+        .expect("mutex poisoned")
+        .entry(key)
+        .or_insert_with_key(|(identifier, parameters)| {
+            let parameters = parameters
+                .iter()
+                .map(|keyword| Parameter {
+                    keyword: Box::leak(keyword.clone().into_boxed_str()),
+                    kind: kind::ANY,
+                    required: true,
+                })
+                .collect::<Vec<_>>();
+            (
+                Box::leak(identifier.clone().into_boxed_str()),
+                Box::leak(parameters.into_boxed_slice()),
+            )
+        })
+}
+
+#[derive(Debug, PartialEq)]
+struct Definition<'a> {
+    name: &'a str,
+    parameters: Vec<&'a str>,
+    body: &'a str,
+}
+
+/// Parses the function definitions of a library, or returns the line of the first error.
+fn parse_library(library: &str) -> std::result::Result<Vec<Definition<'_>>, (usize, &'static str)> {
+    let mut definitions = Vec::new();
+    let mut rest = skip_blank(library);
+    while !rest.is_empty() {
+        let line = library[..library.len() - rest.len()].matches('\n').count() + 1;
+        let error = |message| (line, message);
+
+        let name_start = rest
+            .strip_prefix("fn")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .ok_or_else(|| error("expected a function definition"))?
+            .trim_start();
+        let name_len = name_start
+            .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+            .unwrap_or(name_start.len());
+        let (name, rest_of_definition) = name_start.split_at(name_len);
+        if !is_identifier(name) {
+            return Err(error("expected the name of the function"));
+        }
+
+        let (parameters, rest_of_definition) = rest_of_definition
+            .trim_start()
+            .strip_prefix('(')
+            .and_then(|rest| rest.split_once(')'))
+            .ok_or_else(|| error("expected the parameters of the function"))?;
+        let parameters = parameters
+            .split(',')
+            .map(str::trim)
+            .filter(|parameter| !parameter.is_empty())
+            .collect::<Vec<_>>();
+        if !parameters.iter().all(|parameter| is_identifier(parameter)) {
+            return Err(error("invalid parameter name"));
+        }
+
+        let body = rest_of_definition
+            .trim_start()
+            .strip_prefix('{')
+            .ok_or_else(|| error("expected the body of the function"))?;
+        let body_len = block_len(body).ok_or_else(|| error("unclosed body of the function"))?;
+
+        definitions.push(Definition {
+            name,
+            parameters,
+            body: &body[..body_len],
+        });
+        rest = skip_blank(&body[body_len + 1..]);
+    }
+    Ok(definitions)
+}
+
+/// Skips whitespace and comments.
+fn skip_blank(mut input: &str) -> &str {
+    loop {
+        input = input.trim_start();
+        match input.strip_prefix('#') {
+            Some(comment) => input = comment.split_once('\n').map_or("", |(_, rest)| rest),
+            None => return input,
+        }
+    }
+}
+
+/// Returns the position of the brace closing a block, skipping the braces of literals and comments.
+fn block_len(block: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut previous = None;
+    let mut chars = block.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '#' => {
+                for (_, ch) in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => skip_literal(&mut chars, '"')?,
+            's' | 'r' | 't'
+                if chars.peek().map(|&(_, ch)| ch) == Some('\'')
+                    && !previous
+                        .map_or(false, |ch: char| ch.is_ascii_alphanumeric() || ch == '_') =>
+            {
+                chars.next();
+                skip_literal(&mut chars, '\'')?;
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        previous = Some(ch);
+    }
+    None
+}
+
+fn skip_literal(chars: &mut impl Iterator<Item = (usize, char)>, quote: char) -> Option<()> {
+    while let Some((_, ch)) = chars.next() {
+        if ch == '\\' {
+            chars.next();
+        } else if ch == quote {
+            return Some(());
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug)]
+struct LibraryFunction {
+    identifier: &'static str,
+    parameters: &'static [Parameter],
+    program: Arc<Program>,
+}
+
+impl Function for LibraryFunction {
+    fn identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let arguments = self
+            .parameters
+            .iter()
+            .map(|parameter| (parameter.keyword, arguments.required(parameter.keyword)))
+            .collect();
+
+        Ok(Box::new(LibraryFunctionFn {
+            arguments,
+            program: Arc::clone(&self.program),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LibraryFunctionFn {
+    arguments: Vec<(&'static str, Box<dyn Expression>)>,
+    program: Arc<Program>,
+}
+
+impl Expression for LibraryFunctionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let mut arguments = BTreeMap::new();
+        for (name, argument) in &self.arguments {
+            arguments.insert((*name).to_owned(), argument.resolve(ctx)?);
+        }
+
+        let mut target = TargetValue {
+            value: Value::Object(arguments),
+            metadata: Value::Object(BTreeMap::new()),
+            secrets: Secrets::new(),
+        };
+        let mut state = vrl::state::Runtime::default();
+        let mut function_ctx = Context::new(&mut target, &mut state, ctx.timezone());
+        self.program.resolve(&mut function_ctx)
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        if self.program.info().fallible {
+            TypeDef::any().fallible()
+        } else {
+            TypeDef::any().infallible()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use indoc::indoc;
+    use tempfile::NamedTempFile;
+    use vector_common::TimeZone;
+
+    use super::*;
+
+    fn library(contents: &str) -> NamedTempFile {
+        let mut file = tempfile::Builder::new()
+            .prefix("parsers")
+            .suffix(".vrl")
+            .tempfile()
+            .unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn run(source: &str, event: Value) -> std::result::Result<Value, String> {
+        let mut functions = vrl_stdlib::all();
+        let source = import(
+            source,
+            &mut functions,
+            &enrichment::TableRegistry::default(),
+        )
+        .map_err(|error| error.to_string())?;
+        let program = vrl::compile(&source, &functions)
+            .map_err(|diagnostics| Formatter::new(&source, diagnostics).to_string())?
+            .0;
+
+        let mut target = TargetValue {
+            value: event,
+            metadata: value!({}),
+            secrets: Secrets::new(),
+        };
+        vrl::Runtime::default()
+            .resolve(&mut target, &program, &TimeZone::default())
+            .map_err(|error| error.to_string())?;
+        Ok(target.value)
+    }
+
+    #[test]
+    fn parses_definitions() {
+        let definitions = parse_library(indoc! {r#"
+            # Parsers shared by all pipelines.
+            fn first(value) { value }
+
+            fn braces(a, b) {
+                # }
+                x = { "}": "{" }
+                y = s'}\'' + r'\}' + "\"}"
+                [a, b, x, y]
+            }
+        "#})
+        .unwrap();
+
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(
+            definitions[0],
+            Definition {
+                name: "first",
+                parameters: vec!["value"],
+                body: " value ",
+            }
+        );
+        assert_eq!(definitions[1].name, "braces");
+        assert_eq!(definitions[1].parameters, vec!["a", "b"]);
+        assert!(definitions[1].body.ends_with("[a, b, x, y]\n"));
+
+        assert_eq!(
+            parse_library("fn first(value) { value }\nfn second(value) {"),
+            Err((2, "unclosed body of the function"))
+        );
+        assert_eq!(
+            parse_library("first = 1"),
+            Err((1, "expected a function definition"))
+        );
+    }
+
+    #[test]
+    fn parses_imports() {
+        assert_eq!(parse_import(r#"import "a.vrl""#), Some(("a.vrl", None)));
+        assert_eq!(
+            parse_import(r#"import "lib/a.vrl" as parsers # shared"#),
+            Some(("lib/a.vrl", Some("parsers")))
+        );
+        assert_eq!(parse_import(r#"import "a.vrl" as"#), None);
+        assert_eq!(parse_import(r#"import "a.vrl" as a-b"#), None);
+        assert!(!is_import("import = 1"));
+    }
+
+    #[test]
+    fn calls_library_functions() {
+        let library = library(indoc! {r#"
+            fn parse_user(value) {
+                user = parse_json!(value).user
+                normalize_name(user)
+            }
+
+            fn normalize_name(name) {
+                downcase(string!(name))
+            }
+        "#});
+        let path = library.path().to_str().unwrap();
+
+        // Functions can only call the functions defined before them.
+        let source = format!("import \"{}\" as p\n.user = p::parse_user!(.message)", path);
+        assert!(run(&source, value!({}))
+            .unwrap_err()
+            .contains("Function `parse_user`"));
+
+        let library = self::library(indoc! {r#"
+            fn normalize_name(name) {
+                downcase(string!(name))
+            }
+
+            fn parse_user(value) {
+                user = parse_json!(value).user
+                normalize_name(user)
+            }
+        "#});
+        let path = library.path().to_str().unwrap();
+        let namespace = library.path().file_stem().unwrap().to_str().unwrap();
+        let source = format!(
+            "import \"{}\"\n\n.user = {}::parse_user!(.message)\n.name = {}::normalize_name!(\"Bob\")",
+            path, namespace, namespace
+        );
+        assert_eq!(
+            run(&source, value!({ "message": r#"{"user": "ALICE"}"# })).unwrap(),
+            value!({ "message": r#"{"user": "ALICE"}"#, "user": "alice", "name": "bob" })
+        );
+    }
+
+    #[test]
+    fn checks_calls_at_compile_time() {
+        let library = library("fn parse_user(value) {\n  parse_json!(value).user\n}\n");
+        let path = library.path().to_str().unwrap();
+
+        // Missing arguments and unhandled errors are reported when the program is compiled.
+        let source = format!("import \"{}\" as p\n.user = p::parse_user()", path);
+        assert!(run(&source, value!({})).is_err());
+        let source = format!("import \"{}\" as p\n.user = p::parse_user(.message)", path);
+        assert!(run(&source, value!({})).is_err());
+        let source = format!("import \"{}\" as p\n.user = p::other(.message)", path);
+        assert!(run(&source, value!({})).is_err());
+
+        let library = self::library("fn broken(value) {\n  value +\n}\n");
+        let source = format!(
+            "import \"{}\" as p\n.user = 1",
+            library.path().to_str().unwrap()
+        );
+        assert!(run(&source, value!({}))
+            .unwrap_err()
+            .contains("Function `broken`"));
+    }
+
+    #[test]
+    fn rejects_invalid_imports() {
+        assert!(run("import \"missing.vrl\"\n.a = 1", value!({})).is_err());
+
+        let library = library("fn first(value) { value }");
+        let path = library.path().to_str().unwrap();
+        let source = format!("import \"{0}\" as p\nimport \"{0}\" as p\n.a = 1", path);
+        assert!(run(&source, value!({}))
+            .unwrap_err()
+            .contains("imported twice"));
+
+        // Imports must come first.
+        let source = format!(".a = 1\nimport \"{}\" as p", path);
+        assert!(run(&source, value!({})).is_err());
+    }
+}
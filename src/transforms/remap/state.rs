@@ -0,0 +1,561 @@
+//! VRL functions keeping per-key state across the events processed by a `remap` transform, which are only available
+//! to programs when enabled in the `state` option.
+//!
+//! The state of a transform is kept in a registry keyed by its component ID, so that it survives the reloads of the
+//! configuration rebuilding the transform.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use value::Value;
+use vector_config::configurable_component;
+use vrl::prelude::*;
+
+use crate::config::ComponentKey;
+
+static STATES: Lazy<Mutex<HashMap<ComponentKey, Arc<State>>>> = Lazy::new(Mutex::default);
+
+/// Per-key state kept by the program across events.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct StateConfig {
+    /// Whether the `counter`, `rate`, and `remember` functions are available to the program.
+    pub enabled: bool,
+
+    /// The maximum number of keys kept, shared by all functions.
+    ///
+    /// Once reached, the least recently used keys are forgotten.
+    #[serde(default = "default_max_keys")]
+    #[derivative(Default(value = "default_max_keys()"))]
+    pub max_keys: usize,
+}
+
+const fn default_max_keys() -> usize {
+    100_000
+}
+
+impl StateConfig {
+    /// Builds the state functions, sharing the state of the transform with the given ID if it was built before.
+    pub(super) fn functions(
+        &self,
+        component_key: Option<&ComponentKey>,
+    ) -> crate::Result<Vec<Box<dyn Function>>> {
+        if !self.enabled {
+            return Ok(Vec::new());
+        }
+        if self.max_keys == 0 {
+            return Err("`max_keys` of the state must be greater than zero".into());
+        }
+
+        let state = match component_key {
+            Some(key) => {
+                let mut states = STATES.lock().expect("mutex poisoned");
+                let state = states
+                    .entry(key.clone())
+                    .or_insert_with(|| Arc::new(State::new(self.max_keys)));
+                state.resize(self.max_keys);
+                Arc::clone(state)
+            }
+            None => Arc::new(State::new(self.max_keys)),
+        };
+
+        Ok(vec![
+            Box::new(Counter {
+                state: Arc::clone(&state),
+            }),
+            Box::new(Rate {
+                state: Arc::clone(&state),
+            }),
+            Box::new(Remember { state }),
+        ])
+    }
+}
+
+#[derive(Debug)]
+enum Entry {
+    Counter(i64),
+    Rate(Window),
+    Remembered {
+        value: Value,
+        expires_at: Option<Instant>,
+    },
+}
+
+/// Estimates the number of occurrences over a sliding window from the counts of the current and previous windows.
+#[derive(Debug)]
+struct Window {
+    length: Duration,
+    start: Instant,
+    current: u64,
+    previous: u64,
+}
+
+impl Window {
+    const fn new(length: Duration, now: Instant) -> Self {
+        Self {
+            length,
+            start: now,
+            current: 0,
+            previous: 0,
+        }
+    }
+
+    /// Records an occurrence, returning the rate of occurrences per second over the window ending now.
+    fn record(&mut self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.start);
+        if elapsed >= self.length * 2 {
+            *self = Self::new(self.length, now);
+        } else if elapsed >= self.length {
+            self.previous = self.current;
+            self.current = 0;
+            self.start += self.length;
+        }
+        self.current += 1;
+
+        let length = self.length.as_secs_f64();
+        let overlap = 1.0 - now.saturating_duration_since(self.start).as_secs_f64() / length;
+        (self.previous as f64 * overlap + self.current as f64) / length
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct State {
+    #[derivative(Debug = "ignore")]
+    entries: Mutex<LruCache<String, Entry>>,
+}
+
+impl State {
+    fn new(max_keys: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(max_keys)),
+        }
+    }
+
+    fn resize(&self, max_keys: usize) {
+        self.entries
+            .lock()
+            .expect("mutex poisoned")
+            .resize(max_keys);
+    }
+
+    /// Updates the entry of a key, creating it first if it's missing or of another kind.
+    fn update<T>(
+        &self,
+        key: String,
+        matches: impl Fn(&Entry) -> bool,
+        create: impl FnOnce() -> Entry,
+        update: impl FnOnce(&mut Entry) -> T,
+    ) -> T {
+        let mut entries = self.entries.lock().expect("mutex poisoned");
+        match entries.get_mut(&key) {
+            Some(entry) if matches(entry) => update(entry),
+            _ => {
+                let mut entry = create();
+                let result = update(&mut entry);
+                entries.put(key, entry);
+                result
+            }
+        }
+    }
+}
+
+fn state_key(function: &str, key: &Value) -> Result<String> {
+    Ok(format!("{}:{}", function, key.try_bytes_utf8_lossy()?))
+}
+
+#[derive(Clone, Debug)]
+struct Counter {
+    state: Arc<State>,
+}
+
+impl Function for Counter {
+    fn identifier(&self) -> &'static str {
+        "counter"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "count the events of a user",
+            source: r#"counter("alice")"#,
+            result: Ok("1"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+
+        Ok(Box::new(CounterFn {
+            state: Arc::clone(&self.state),
+            key,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CounterFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+}
+
+impl Expression for CounterFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = state_key("counter", &self.key.resolve(ctx)?)?;
+        let count = self.state.update(
+            key,
+            |entry| matches!(entry, Entry::Counter(_)),
+            || Entry::Counter(0),
+            |entry| match entry {
+                Entry::Counter(count) => {
+                    *count += 1;
+                    *count
+                }
+                _ => unreachable!("entry is a counter"),
+            },
+        );
+        Ok(count.into())
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rate {
+    state: Arc<State>,
+}
+
+impl Function for Rate {
+    fn identifier(&self) -> &'static str {
+        "rate"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "window",
+                kind: kind::INTEGER,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "rate of the failed logins of a user over a minute",
+            source: r#"rate!("alice", window: 60)"#,
+            result: Ok("0.016666666666666666"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let window = arguments.required("window");
+
+        Ok(Box::new(RateFn {
+            state: Arc::clone(&self.state),
+            key,
+            window,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RateFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+    window: Box<dyn Expression>,
+}
+
+impl Expression for RateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = state_key("rate", &self.key.resolve(ctx)?)?;
+        let window = self.window.resolve(ctx)?.try_integer()?;
+        if window <= 0 {
+            return Err("window must be a positive number of seconds".into());
+        }
+        let length = Duration::from_secs(window as u64);
+
+        let now = Instant::now();
+        let rate = self.state.update(
+            key,
+            |entry| matches!(entry, Entry::Rate(window) if window.length == length),
+            || Entry::Rate(Window::new(length, now)),
+            |entry| match entry {
+                Entry::Rate(window) => window.record(now),
+                _ => unreachable!("entry is a rate"),
+            },
+        );
+        Ok(Value::from_f64_or_zero(rate))
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::float().fallible()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Remember {
+    state: Arc<State>,
+}
+
+impl Function for Remember {
+    fn identifier(&self) -> &'static str {
+        "remember"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "ttl",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "first time a user is seen in a day",
+            source: r#"remember!("alice", true, ttl: 86400) == null"#,
+            result: Ok("true"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let value = arguments.required("value");
+        let ttl = arguments.optional("ttl");
+
+        Ok(Box::new(RememberFn {
+            state: Arc::clone(&self.state),
+            key,
+            value,
+            ttl,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RememberFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    ttl: Option<Box<dyn Expression>>,
+}
+
+impl Expression for RememberFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = state_key("remember", &self.key.resolve(ctx)?)?;
+        let value = self.value.resolve(ctx)?;
+        let ttl = match &self.ttl {
+            Some(ttl) => match ttl.resolve(ctx)?.try_integer()? {
+                ttl if ttl > 0 => Some(Duration::from_secs(ttl as u64)),
+                _ => return Err("ttl must be a positive number of seconds".into()),
+            },
+            None => None,
+        };
+
+        let now = Instant::now();
+        let entry = Entry::Remembered {
+            value,
+            expires_at: ttl.map(|ttl| now + ttl),
+        };
+        let previous = self.state.update(
+            key,
+            |entry| matches!(entry, Entry::Remembered { .. }),
+            || Entry::Remembered {
+                value: Value::Null,
+                expires_at: None,
+            },
+            |previous| match std::mem::replace(previous, entry) {
+                Entry::Remembered { value, expires_at } => match expires_at {
+                    Some(expires_at) if expires_at <= now => Value::Null,
+                    _ => value,
+                },
+                _ => unreachable!("entry is remembered"),
+            },
+        );
+        Ok(previous)
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::any().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use value::Secrets;
+    use vector_common::TimeZone;
+    use vrl::TargetValue;
+
+    use super::*;
+
+    fn resolve(function: &dyn Expression) -> Resolved {
+        let mut state = vrl::state::Runtime::default();
+        let mut target = TargetValue {
+            value: value!({}),
+            metadata: value!({}),
+            secrets: Secrets::new(),
+        };
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+        function.resolve(&mut ctx)
+    }
+
+    fn counter(state: &Arc<State>, key: &str) -> Value {
+        resolve(&CounterFn {
+            state: Arc::clone(state),
+            key: expr!(key),
+        })
+        .unwrap()
+    }
+
+    fn remember(state: &Arc<State>, key: &str, value: Value) -> Value {
+        resolve(&RememberFn {
+            state: Arc::clone(state),
+            key: expr!(key),
+            value: expr!(value),
+            ttl: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_per_key() {
+        let state = Arc::new(State::new(10));
+        assert_eq!(counter(&state, "a"), value!(1));
+        assert_eq!(counter(&state, "a"), value!(2));
+        assert_eq!(counter(&state, "b"), value!(1));
+    }
+
+    #[test]
+    fn remembers_previous_values() {
+        let state = Arc::new(State::new(10));
+        assert_eq!(remember(&state, "a", value!("x")), value!(null));
+        assert_eq!(remember(&state, "a", value!("y")), value!("x"));
+        assert_eq!(remember(&state, "b", value!("z")), value!(null));
+
+        // Functions keep their keys apart.
+        assert_eq!(counter(&state, "a"), value!(1));
+        assert_eq!(remember(&state, "a", value!("z")), value!("y"));
+    }
+
+    #[test]
+    fn forgets_expired_values() {
+        let state = Arc::new(State::new(10));
+        let function = |value: &str, ttl: i64| RememberFn {
+            state: Arc::clone(&state),
+            key: expr!("a"),
+            value: expr!(value),
+            ttl: Some(expr!(ttl)),
+        };
+
+        assert_eq!(resolve(&function("x", 1)).unwrap(), value!(null));
+        assert_eq!(resolve(&function("y", 1)).unwrap(), value!("x"));
+        std::thread::sleep(Duration::from_millis(1100));
+        assert_eq!(resolve(&function("z", 1)).unwrap(), value!(null));
+        assert!(resolve(&function("z", 0)).is_err());
+    }
+
+    #[test]
+    fn bounds_the_number_of_keys() {
+        let state = Arc::new(State::new(2));
+        counter(&state, "a");
+        counter(&state, "b");
+        counter(&state, "a");
+        counter(&state, "c");
+
+        assert_eq!(counter(&state, "a"), value!(3));
+        assert_eq!(counter(&state, "b"), value!(1));
+    }
+
+    #[test]
+    fn estimates_rates_over_sliding_windows() {
+        let start = Instant::now();
+        let mut window = Window::new(Duration::from_secs(10), start);
+        assert_eq!(window.record(start), 0.1);
+        assert_eq!(window.record(start + Duration::from_secs(5)), 0.2);
+
+        // Half of the previous window overlaps the window ending now.
+        assert_eq!(window.record(start + Duration::from_secs(15)), 0.2);
+
+        // Occurrences older than the window are forgotten.
+        assert_eq!(window.record(start + Duration::from_secs(40)), 0.1);
+    }
+
+    #[test]
+    fn keeps_state_across_rebuilds() {
+        let config = StateConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let key = ComponentKey::from("keeps_state_across_rebuilds");
+        let identifiers = |functions: &[Box<dyn Function>]| {
+            functions
+                .iter()
+                .map(|function| function.identifier())
+                .collect::<Vec<_>>()
+        };
+
+        let functions = config.functions(Some(&key)).unwrap();
+        assert_eq!(identifiers(&functions), vec!["counter", "rate", "remember"]);
+        let state = Arc::clone(&STATES.lock().unwrap()[&key]);
+        counter(&state, "a");
+        drop(functions);
+
+        config.functions(Some(&key)).unwrap();
+        assert_eq!(counter(&STATES.lock().unwrap()[&key], "a"), value!(2));
+
+        assert!(StateConfig::default()
+            .functions(Some(&key))
+            .unwrap()
+            .is_empty());
+    }
+}
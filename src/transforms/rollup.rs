@@ -0,0 +1,483 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    pin::Pin,
+    time::Duration,
+};
+
+use async_stream::stream;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use vector_config::configurable_component;
+
+use crate::{
+    config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue},
+        Event, EventMetadata,
+    },
+    internal_events::{RollupCounterReset, RollupFlushed},
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+/// The number of intervals after which the state of an absolute counter that's no longer seen is forgotten.
+const COUNTER_EXPIRATION_INTERVALS: u64 = 10;
+
+/// Configuration for the `rollup` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct RollupConfig {
+    /// The resolution of the rolled up metrics, in seconds.
+    ///
+    /// Each series is emitted at most once per interval.
+    #[derivative(Default(value = "default_interval_secs()"))]
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+
+    /// The aggregations of the values of the absolute gauges over each interval.
+    ///
+    /// When several aggregations are set, a series is emitted for each of them, with the name of the aggregation
+    /// appended to its name, as in `cpu_usage_max`.
+    #[derivative(Default(value = "default_gauge_aggregations()"))]
+    #[serde(default = "default_gauge_aggregations")]
+    pub gauge_aggregations: Vec<GaugeAggregation>,
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_gauge_aggregations() -> Vec<GaugeAggregation> {
+    vec![GaugeAggregation::Avg]
+}
+
+/// An aggregation of the values of a gauge.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GaugeAggregation {
+    /// The average of the values.
+    Avg,
+
+    /// The maximum of the values.
+    Max,
+
+    /// The minimum of the values.
+    Min,
+
+    /// The sum of the values.
+    Sum,
+
+    /// The last value.
+    Last,
+}
+
+impl GaugeAggregation {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Avg => "avg",
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Sum => "sum",
+            Self::Last => "last",
+        }
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<RollupConfig>("rollup")
+}
+
+impl_generate_config_from_default!(RollupConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "rollup")]
+impl TransformConfig for RollupConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Rollup::new(self).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "rollup"
+    }
+}
+
+/// The statistics of the values of an absolute gauge over an interval.
+#[derive(Clone, Copy, Debug)]
+struct GaugeStats {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    last: f64,
+}
+
+impl GaugeStats {
+    const fn new(value: f64) -> Self {
+        Self {
+            count: 1,
+            sum: value,
+            min: value,
+            max: value,
+            last: value,
+        }
+    }
+
+    fn record(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.last = value;
+    }
+
+    fn aggregate(&self, aggregation: GaugeAggregation) -> f64 {
+        match aggregation {
+            GaugeAggregation::Avg => self.sum / self.count as f64,
+            GaugeAggregation::Max => self.max,
+            GaugeAggregation::Min => self.min,
+            GaugeAggregation::Sum => self.sum,
+            GaugeAggregation::Last => self.last,
+        }
+    }
+}
+
+/// The state of an absolute counter, kept across intervals to detect its resets.
+#[derive(Clone, Copy, Debug)]
+struct CounterState {
+    /// The last value of the counter, as received.
+    last: f64,
+    /// The sum of the values of the counter before each of its resets.
+    offset: f64,
+    /// The interval in which the counter was last seen.
+    seen: u64,
+}
+
+#[derive(Debug)]
+enum Rolled {
+    Gauge {
+        stats: GaugeStats,
+        timestamp: Option<DateTime<Utc>>,
+    },
+    /// Incremental metrics and absolute counters, whose value is corrected for resets.
+    Data(MetricData),
+}
+
+type RolledEntry = (Rolled, EventMetadata);
+
+#[derive(Debug)]
+pub struct Rollup {
+    interval: Duration,
+    gauge_aggregations: Vec<GaugeAggregation>,
+    map: BTreeMap<MetricSeries, RolledEntry>,
+    counters: BTreeMap<MetricSeries, CounterState>,
+    intervals: u64,
+}
+
+impl Rollup {
+    pub fn new(config: &RollupConfig) -> crate::Result<Self> {
+        if config.interval_secs == 0 {
+            return Err("`interval_secs` must be greater than zero".into());
+        }
+        if config.gauge_aggregations.is_empty() {
+            return Err("`gauge_aggregations` must not be empty".into());
+        }
+
+        Ok(Self {
+            interval: Duration::from_secs(config.interval_secs),
+            gauge_aggregations: config.gauge_aggregations.clone(),
+            map: BTreeMap::new(),
+            counters: BTreeMap::new(),
+            intervals: 0,
+        })
+    }
+
+    /// Returns the value of an absolute counter, increased by the values it had before its resets.
+    fn correct_counter(&mut self, series: &MetricSeries, value: f64) -> f64 {
+        let intervals = self.intervals;
+        match self.counters.entry(series.clone()) {
+            Entry::Occupied(mut entry) => {
+                let state = entry.get_mut();
+                if value < state.last {
+                    emit!(RollupCounterReset {
+                        name: &series.name.name,
+                    });
+                    state.offset += state.last;
+                }
+                state.last = value;
+                state.seen = intervals;
+                state.offset + value
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(CounterState {
+                    last: value,
+                    offset: 0.0,
+                    seen: intervals,
+                });
+                value
+            }
+        }
+    }
+
+    fn record(&mut self, event: Event) {
+        let (series, mut data, metadata) = event.into_metric().into_parts();
+
+        let rolled = match (data.kind, &data.value) {
+            (MetricKind::Absolute, MetricValue::Gauge { value }) => {
+                if let Some((Rolled::Gauge { stats, timestamp }, existing_metadata)) =
+                    self.map.get_mut(&series)
+                {
+                    stats.record(*value);
+                    *timestamp = data.timestamp.or(*timestamp);
+                    existing_metadata.merge(metadata);
+                    return;
+                }
+                Rolled::Gauge {
+                    stats: GaugeStats::new(*value),
+                    timestamp: data.timestamp,
+                }
+            }
+            (MetricKind::Absolute, MetricValue::Counter { value }) => {
+                let value = self.correct_counter(&series, *value);
+                data.value = MetricValue::Counter { value };
+                Rolled::Data(data)
+            }
+            (MetricKind::Incremental, _) => {
+                if let Some((Rolled::Data(existing), existing_metadata)) = self.map.get_mut(&series)
+                {
+                    if existing.kind == MetricKind::Incremental && existing.update(&data) {
+                        existing_metadata.merge(metadata);
+                        return;
+                    }
+                }
+                Rolled::Data(data)
+            }
+            // The other absolute metrics can't be combined, so their last value is kept.
+            (MetricKind::Absolute, _) => Rolled::Data(data),
+        };
+
+        match self.map.entry(series) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                existing.0 = rolled;
+                existing.1.merge(metadata);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((rolled, metadata));
+            }
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let map = std::mem::take(&mut self.map);
+        let count = map.len();
+        for (series, (rolled, metadata)) in map {
+            match rolled {
+                Rolled::Gauge { stats, timestamp } => {
+                    let suffix = self.gauge_aggregations.len() > 1;
+                    for aggregation in &self.gauge_aggregations {
+                        let mut series = series.clone();
+                        if suffix {
+                            series.name.name =
+                                format!("{}_{}", series.name.name, aggregation.as_str());
+                        }
+                        let data = MetricData::from_parts(
+                            timestamp,
+                            MetricKind::Absolute,
+                            MetricValue::Gauge {
+                                value: stats.aggregate(*aggregation),
+                            },
+                        );
+                        output.push(Metric::from_parts(series, data, metadata.clone()).into());
+                    }
+                }
+                Rolled::Data(data) => {
+                    output.push(Metric::from_parts(series, data, metadata).into());
+                }
+            }
+        }
+
+        let intervals = self.intervals;
+        self.counters
+            .retain(|_, state| intervals - state.seen < COUNTER_EXPIRATION_INTERVALS);
+        self.intervals += 1;
+
+        emit!(RollupFlushed { count });
+    }
+}
+
+impl TaskTransform<Event> for Rollup {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(self.interval);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output);
+                                done = true;
+                            }
+                            Some(event) => self.record(event),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RollupConfig>();
+    }
+
+    fn rollup(gauge_aggregations: Vec<GaugeAggregation>) -> Rollup {
+        Rollup::new(&RollupConfig {
+            interval_secs: 60,
+            gauge_aggregations,
+        })
+        .unwrap()
+    }
+
+    fn metric(name: &str, kind: MetricKind, value: MetricValue) -> Event {
+        Metric::new(name, kind, value).into()
+    }
+
+    fn flush(rollup: &mut Rollup) -> BTreeMap<String, MetricValue> {
+        let mut output = Vec::new();
+        rollup.flush_into(&mut output);
+        output
+            .into_iter()
+            .map(|event| {
+                let metric = event.into_metric();
+                (metric.name().to_owned(), metric.value().clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregates_gauges() {
+        let mut rollup = rollup(vec![
+            GaugeAggregation::Avg,
+            GaugeAggregation::Max,
+            GaugeAggregation::Min,
+            GaugeAggregation::Sum,
+            GaugeAggregation::Last,
+        ]);
+        for value in [2.0, 6.0, 4.0] {
+            rollup.record(metric(
+                "cpu",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value },
+            ));
+        }
+
+        let output = flush(&mut rollup);
+        let gauge = |value| MetricValue::Gauge { value };
+        assert_eq!(output["cpu_avg"], gauge(4.0));
+        assert_eq!(output["cpu_max"], gauge(6.0));
+        assert_eq!(output["cpu_min"], gauge(2.0));
+        assert_eq!(output["cpu_sum"], gauge(12.0));
+        assert_eq!(output["cpu_last"], gauge(4.0));
+        assert!(flush(&mut rollup).is_empty());
+    }
+
+    #[test]
+    fn keeps_gauge_names_with_one_aggregation() {
+        let mut rollup = rollup(vec![GaugeAggregation::Max]);
+        rollup.record(metric(
+            "cpu",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        ));
+
+        let output = flush(&mut rollup);
+        assert_eq!(output["cpu"], MetricValue::Gauge { value: 1.0 });
+    }
+
+    #[test]
+    fn sums_incremental_metrics() {
+        let mut rollup = rollup(default_gauge_aggregations());
+        for value in [1.0, 2.0, 3.0] {
+            rollup.record(metric(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            ));
+        }
+
+        let output = flush(&mut rollup);
+        assert_eq!(output["requests"], MetricValue::Counter { value: 6.0 });
+    }
+
+    #[test]
+    fn handles_counter_resets() {
+        let mut rollup = rollup(default_gauge_aggregations());
+        let counter = |value| {
+            metric(
+                "requests",
+                MetricKind::Absolute,
+                MetricValue::Counter { value },
+            )
+        };
+
+        rollup.record(counter(10.0));
+        rollup.record(counter(15.0));
+        assert_eq!(
+            flush(&mut rollup)["requests"],
+            MetricValue::Counter { value: 15.0 }
+        );
+
+        // The counter restarts from zero, but the rolled up counter keeps increasing.
+        rollup.record(counter(3.0));
+        rollup.record(counter(5.0));
+        assert_eq!(
+            flush(&mut rollup)["requests"],
+            MetricValue::Counter { value: 20.0 }
+        );
+    }
+
+    #[test]
+    fn forgets_expired_counters() {
+        let mut rollup = rollup(default_gauge_aggregations());
+        rollup.record(metric(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        ));
+        for _ in 0..COUNTER_EXPIRATION_INTERVALS {
+            flush(&mut rollup);
+        }
+        assert!(rollup.counters.is_empty());
+    }
+}
@@ -1,4 +1,7 @@
+use std::hash::Hasher;
+
 use indexmap::IndexMap;
+use seahash::SeaHasher;
 use serde::{Deserialize, Serialize};
 use vector_config::configurable_component;
 use vector_core::transform::SyncTransform;
@@ -19,6 +22,52 @@ pub(crate) const UNMATCHED_ROUTE: &str = "_unmatched";
 #[derive(Clone)]
 pub struct Route {
     conditions: Vec<(String, Condition)>,
+    weighted: Vec<WeightedRoute>,
+    weighted_key_field: Option<String>,
+}
+
+/// A route receiving a fixed percentage of the events.
+#[derive(Clone)]
+struct WeightedRoute {
+    name: String,
+    percentage: f64,
+    /// The hashes of the keys of the events the route receives are below this threshold.
+    threshold: u64,
+    /// The events the route is owed, when the events don't have a key.
+    credit: f64,
+}
+
+impl WeightedRoute {
+    fn new(name: String, percentage: f64) -> Self {
+        Self {
+            name,
+            percentage,
+            threshold: (percentage / 100.0 * u64::MAX as f64) as u64,
+            credit: 0.0,
+        }
+    }
+
+    fn selects(&mut self, key: Option<&str>) -> bool {
+        match key {
+            // The name of the route is hashed with the key, so that the routes receive independent fractions of the
+            // events.
+            Some(key) => {
+                let mut hasher = SeaHasher::new();
+                hasher.write(self.name.as_bytes());
+                hasher.write(key.as_bytes());
+                hasher.finish() < self.threshold
+            }
+            None => {
+                self.credit += self.percentage;
+                if self.credit >= 100.0 {
+                    self.credit -= 100.0;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
 }
 
 impl Route {
@@ -28,7 +77,25 @@ impl Route {
             let condition = condition.build(&context.enrichment_tables)?;
             conditions.push((output_name.clone(), condition));
         }
-        Ok(Self { conditions })
+        let weighted = config
+            .weighted
+            .iter()
+            .map(|(output_name, percentage)| WeightedRoute::new(output_name.clone(), *percentage))
+            .collect();
+        Ok(Self {
+            conditions,
+            weighted,
+            weighted_key_field: config.weighted_key_field.clone(),
+        })
+    }
+
+    fn weighted_key(&self, event: &Event) -> Option<String> {
+        let field = self.weighted_key_field.as_deref()?;
+        match event {
+            Event::Log(log) => log.get(field).map(|value| value.to_string_lossy()),
+            Event::Metric(metric) => metric.tag_value(field),
+            Event::Trace(trace) => trace.get(field).map(|value| value.to_string_lossy()),
+        }
     }
 }
 
@@ -38,6 +105,15 @@ impl SyncTransform for Route {
         event: Event,
         output: &mut vector_core::transform::TransformOutputsBuf,
     ) {
+        if !self.weighted.is_empty() {
+            let key = self.weighted_key(&event);
+            for route in &mut self.weighted {
+                if route.selects(key.as_deref()) {
+                    output.push_named(&route.name, event.clone());
+                }
+            }
+        }
+
         let mut check_failed: usize = 0;
         for (output_name, condition) in &self.conditions {
             let (result, event) = condition.check(event.clone());
@@ -64,14 +140,40 @@ pub struct RouteConfig {
     /// an event doesn’t match any route, it will be sent to the `<transform_name>._unmatched` output.
     ///
     /// Both `_unmatched`, as well as `_default`, are reserved output names and cannot be used as a route name.
-    #[serde(alias = "lanes")]
+    #[serde(alias = "lanes", default)]
     route: IndexMap<String, AnyCondition>,
+
+    /// A table of route identifiers to the percentage of the events they receive, regardless of their conditions.
+    ///
+    /// Weighted routes receive their events in addition to the routes they match, such as to mirror a fraction of the
+    /// traffic to a canary sink. They don't change which events are sent to the `<transform_name>._unmatched` output.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    weighted: IndexMap<String, f64>,
+
+    /// The name of the field whose value is hashed to decide which events the weighted routes receive.
+    ///
+    /// The events with the same value are consistently sent to the same weighted routes, across restarts and Vector
+    /// instances. The actual percentage may differ from the configured one if the values of the field are not uniformly
+    /// distributed. For metrics, it's the name of a tag. If left unspecified, or if the event doesn't have the field,
+    /// every Nth event is sent to the weighted routes.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    weighted_key_field: Option<String>,
 }
 
 #[cfg(feature = "transforms-pipelines")]
 impl RouteConfig {
-    pub(crate) const fn new(route: IndexMap<String, AnyCondition>) -> Self {
-        Self { route }
+    pub(crate) fn new(route: IndexMap<String, AnyCondition>) -> Self {
+        Self {
+            route,
+            weighted: IndexMap::new(),
+            weighted_key_field: None,
+        }
     }
 }
 
@@ -87,6 +189,8 @@ impl GenerateConfig for RouteConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             route: IndexMap::new(),
+            weighted: IndexMap::new(),
+            weighted_key_field: None,
         })
         .unwrap()
     }
@@ -105,12 +209,29 @@ impl TransformConfig for RouteConfig {
     }
 
     fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
-        if self.route.contains_key(UNMATCHED_ROUTE) {
-            Err(vec![format!(
+        let mut errors = Vec::new();
+        if self.route.contains_key(UNMATCHED_ROUTE) || self.weighted.contains_key(UNMATCHED_ROUTE) {
+            errors.push(format!(
                 "cannot have a named output with reserved name: `{UNMATCHED_ROUTE}`"
-            )])
-        } else {
+            ));
+        }
+        for (output_name, percentage) in &self.weighted {
+            if self.route.contains_key(output_name) {
+                errors.push(format!(
+                    "route `{output_name}` cannot be both a conditional and a weighted route"
+                ));
+            }
+            if !(0.0..=100.0).contains(percentage) {
+                errors.push(format!(
+                    "the percentage of weighted route `{output_name}` must be between 0 and 100"
+                ));
+            }
+        }
+
+        if errors.is_empty() {
             Ok(())
+        } else {
+            Err(errors)
         }
     }
 
@@ -118,6 +239,7 @@ impl TransformConfig for RouteConfig {
         let mut result: Vec<Output> = self
             .route
             .keys()
+            .chain(self.weighted.keys())
             .map(|output_name| Output::default(DataType::all()).with_port(output_name))
             .collect();
         result.push(Output::default(DataType::all()).with_port(UNMATCHED_ROUTE));
@@ -344,6 +466,110 @@ mod test {
         }
     }
 
+    fn weighted_outputs(output_names: &[&str]) -> TransformOutputsBuf {
+        TransformOutputsBuf::new_with_capacity(
+            output_names
+                .iter()
+                .map(|output_name| Output::default(DataType::all()).with_port(*output_name))
+                .collect(),
+            1,
+        )
+    }
+
+    #[test]
+    fn can_serialize_weighted() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            weighted.canary = 5.0
+            weighted_key_field = "user_id"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&config).unwrap(),
+            r#"{"route":{},"weighted":{"canary":5.0},"weighted_key_field":"user_id"}"#
+        );
+    }
+
+    #[test]
+    fn validates_weighted_routes() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first.type = "is_log"
+            weighted.first = 10.0
+            weighted.canary = 120.0
+            weighted._unmatched = 1.0
+        "#,
+        )
+        .unwrap();
+
+        let errors = config.validate(&schema::Definition::empty()).unwrap_err();
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn route_weighted_fraction() {
+        let output_names = vec!["first", "canary", UNMATCHED_ROUTE];
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first.type = "vrl"
+            route.first.source = '.message == "hello world"'
+
+            weighted.canary = 25.0
+        "#,
+        )
+        .unwrap();
+
+        let mut transform = Route::new(&config, &Default::default()).unwrap();
+        let mut outputs = weighted_outputs(&output_names);
+        let mut canary = 0;
+        let mut first = 0;
+        let mut unmatched = 0;
+        for i in 0..100 {
+            let message = if i % 2 == 0 { "hello world" } else { "NOPE" };
+            let event = Event::try_from(serde_json::json!({ "message": message })).unwrap();
+            transform.transform(event, &mut outputs);
+            canary += outputs.drain_named("canary").count();
+            first += outputs.drain_named("first").count();
+            unmatched += outputs.drain_named(UNMATCHED_ROUTE).count();
+        }
+
+        // Weighted routes don't change where the events are routed otherwise.
+        assert_eq!(canary, 25);
+        assert_eq!(first, 50);
+        assert_eq!(unmatched, 50);
+    }
+
+    #[test]
+    fn route_weighted_key_field() {
+        let output_names = vec!["canary", UNMATCHED_ROUTE];
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            weighted.canary = 50.0
+            weighted_key_field = "user_id"
+        "#,
+        )
+        .unwrap();
+
+        let mut transform = Route::new(&config, &Default::default()).unwrap();
+        let mut outputs = weighted_outputs(&output_names);
+        let mut routed = |transform: &mut Route, user_id: usize| {
+            let event = Event::try_from(serde_json::json!({ "user_id": user_id })).unwrap();
+            transform.transform(event, &mut outputs);
+            outputs.drain_named(UNMATCHED_ROUTE).for_each(drop);
+            outputs.drain_named("canary").count() == 1
+        };
+
+        let selected: Vec<_> = (0..1000).map(|i| routed(&mut transform, i)).collect();
+        // The same keys are consistently sent to the same routes.
+        for (i, selected) in selected.iter().enumerate() {
+            assert_eq!(routed(&mut transform, i), *selected);
+        }
+        let count = selected.iter().filter(|selected| **selected).count();
+        assert!((400..600).contains(&count), "{} keys selected", count);
+    }
+
     #[tokio::test]
     async fn route_metrics_with_output_tag() {
         init_test();
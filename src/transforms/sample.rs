@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
 
 use crate::{
     conditions::{AnyCondition, Condition},
@@ -13,6 +14,8 @@ use crate::{
     transforms::{FunctionTransform, OutputBuffer, Transform},
 };
 
+const DROPPED: &str = "dropped";
+
 /// Configuration for the `sample` transform.
 #[configurable_component(transform)]
 #[derive(Clone, Debug)]
@@ -32,6 +35,13 @@ pub struct SampleConfig {
 
     /// A logical condition used to exclude events from sampling.
     pub exclude: Option<AnyCondition>,
+
+    /// Reroutes the events not sampled to a named output instead of dropping them.
+    ///
+    /// When set, the events not sampled are forwarded, unmodified, to the `dropped` output. This allows the discarded
+    /// events to be archived or audited.
+    #[serde(default)]
+    pub reroute_dropped: bool,
 }
 
 inventory::submit! {
@@ -48,6 +58,7 @@ impl GenerateConfig for SampleConfig {
             rate: 10,
             key_field: None,
             exclude: None::<AnyCondition>,
+            reroute_dropped: false,
         })
         .unwrap()
     }
@@ -57,14 +68,19 @@ impl GenerateConfig for SampleConfig {
 #[typetag::serde(name = "sample")]
 impl TransformConfig for SampleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(Sample::new(
+        let sample = Sample::new(
             self.rate,
             self.key_field.clone(),
             self.exclude
                 .as_ref()
                 .map(|condition| condition.build(&context.enrichment_tables))
                 .transpose()?,
-        )))
+        );
+        Ok(if self.reroute_dropped {
+            Transform::synchronous(RerouteDropped(sample))
+        } else {
+            Transform::function(sample)
+        })
     }
 
     fn input(&self) -> Input {
@@ -72,7 +88,14 @@ impl TransformConfig for SampleConfig {
     }
 
     fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
+        if self.reroute_dropped {
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Log).with_port(DROPPED),
+            ]
+        } else {
+            vec![Output::default(DataType::Log)]
+        }
     }
 
     fn transform_type(&self) -> &'static str {
@@ -123,14 +146,14 @@ impl Sample {
     }
 }
 
-impl FunctionTransform for Sample {
-    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+impl Sample {
+    /// Samples an event, returning it as an error if it's not sampled.
+    fn sample(&mut self, event: Event) -> Result<Event, Event> {
         let mut event = {
             if let Some(condition) = self.exclude.as_ref() {
                 let (result, event) = condition.check(event);
                 if result {
-                    output.push(event);
-                    return;
+                    return Ok(event);
                 } else {
                     event
                 }
@@ -157,9 +180,31 @@ impl FunctionTransform for Sample {
             event
                 .as_mut_log()
                 .insert("sample_rate", self.rate.to_string());
-            output.push(event);
+            Ok(event)
         } else {
-            emit!(SampleEventDiscarded);
+            Err(event)
+        }
+    }
+}
+
+impl FunctionTransform for Sample {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        match self.sample(event) {
+            Ok(event) => output.push(event),
+            Err(_) => emit!(SampleEventDiscarded),
+        }
+    }
+}
+
+/// A sample forwarding the events not sampled to the `dropped` output.
+#[derive(Clone)]
+struct RerouteDropped(Sample);
+
+impl SyncTransform for RerouteDropped {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        match self.0.sample(event) {
+            Ok(event) => output.push(event),
+            Err(event) => output.push_named(DROPPED, event),
         }
     }
 }
@@ -264,6 +309,38 @@ mod tests {
         assert_eq!(first_run, second_run);
     }
 
+    #[test]
+    fn hash_samples_consistently_across_instances() {
+        // Events sharing a key are kept or dropped together, whatever the order they arrive in at
+        // each instance.
+        let events = (0..1000)
+            .map(|i| {
+                let mut event = Event::Log(LogEvent::from(format!("span {}", i)));
+                event
+                    .as_mut_log()
+                    .insert("trace_id", format!("trace-{}", i % 100));
+                event
+            })
+            .collect::<Vec<_>>();
+        let sample = |events: Vec<Event>| {
+            let mut sampler = Sample::new(4, Some("trace_id".into()), None);
+            events
+                .into_iter()
+                .filter_map(|event| {
+                    let mut buf = OutputBuffer::with_capacity(1);
+                    sampler.transform(&mut buf, event);
+                    buf.into_events().next()
+                })
+                .map(|event| event.as_log()["trace_id"].to_string_lossy())
+                .collect::<std::collections::BTreeSet<_>>()
+        };
+
+        let kept = sample(events.clone());
+        let kept_reversed = sample(events.into_iter().rev().collect());
+        assert_eq!(kept, kept_reversed);
+        assert!(!kept.is_empty() && kept.len() < 100);
+    }
+
     #[test]
     fn always_passes_events_matching_pass_list() {
         for key_field in &[None, Some(log_schema().message_key().into())] {
@@ -355,6 +432,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reroutes_dropped_events() {
+        let mut sampler = RerouteDropped(Sample::new(2, None, None));
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Log).with_port(DROPPED),
+            ],
+            10,
+        );
+        for event in random_events(10) {
+            sampler.transform(event, &mut outputs);
+        }
+
+        let sampled = outputs.drain().collect::<Vec<_>>();
+        let dropped = outputs.drain_named(DROPPED).collect::<Vec<_>>();
+        assert_eq!(sampled.len(), 5);
+        assert_eq!(dropped.len(), 5);
+        assert!(dropped
+            .iter()
+            .all(|event| event.as_log().get("sample_rate").is_none()));
+    }
+
     fn random_events(n: usize) -> Vec<Event> {
         random_lines(10)
             .take(n)
@@ -0,0 +1,560 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use crate::event::{LogEvent, Value};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Concat,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum ScalarFunction {
+    Abs,
+    Coalesce,
+    Concat,
+    Length,
+    Lower,
+    Round,
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum AggregateFunction {
+    Avg,
+    Count,
+    Max,
+    Min,
+    Sum,
+}
+
+/// A compiled SQL expression.
+#[derive(Clone, Debug)]
+pub(super) enum Expr {
+    Field(String),
+    Literal(Value),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary {
+        left: Box<Expr>,
+        op: BinaryOp,
+        right: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    Like {
+        expr: Box<Expr>,
+        pattern: Regex,
+        negated: bool,
+    },
+    Case {
+        operand: Option<Box<Expr>>,
+        branches: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+    Function {
+        function: ScalarFunction,
+        args: Vec<Expr>,
+    },
+    /// The value of the expression of the `GROUP BY` clause at this index.
+    GroupKey(usize),
+    /// The result of the aggregate function at this index.
+    Aggregate(usize),
+    WindowStart,
+    WindowEnd,
+}
+
+/// What an expression is evaluated on.
+pub(super) enum Row<'a> {
+    /// A single event.
+    Event(&'a LogEvent),
+    /// A group of events aggregated over a window.
+    Group {
+        keys: &'a [Value],
+        aggregates: &'a [Value],
+        window: (DateTime<Utc>, DateTime<Utc>),
+    },
+}
+
+pub(super) type EvalResult = Result<Value, String>;
+
+impl Expr {
+    pub(super) fn evaluate(&self, row: &Row<'_>) -> EvalResult {
+        Ok(match self {
+            Self::Field(path) => match row {
+                Row::Event(log) => log.get(path.as_str()).cloned().unwrap_or(Value::Null),
+                Row::Group { .. } => Value::Null,
+            },
+            Self::Literal(value) => value.clone(),
+            Self::Negate(expr) => match expr.evaluate(row)? {
+                Value::Null => Value::Null,
+                Value::Integer(value) => value
+                    .checked_neg()
+                    .map(Value::Integer)
+                    .ok_or_else(|| "integer overflow".to_owned())?,
+                Value::Float(value) => Value::Float(-value),
+                value => return Err(format!("can't negate {}", type_name(&value))),
+            },
+            Self::Not(expr) => match expr.evaluate(row)? {
+                Value::Null => Value::Null,
+                Value::Boolean(value) => Value::Boolean(!value),
+                value => return Err(format!("can't negate {}", type_name(&value))),
+            },
+            Self::Binary { left, op, right } => binary(row, left, *op, right)?,
+            Self::IsNull { expr, negated } => {
+                Value::Boolean(expr.evaluate(row)?.is_null() != *negated)
+            }
+            Self::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = expr.evaluate(row)?;
+                if value.is_null() {
+                    return Ok(Value::Null);
+                }
+                let mut found = Value::Boolean(false);
+                for item in list {
+                    match equals(&value, &item.evaluate(row)?)? {
+                        Value::Boolean(true) => {
+                            found = Value::Boolean(true);
+                            break;
+                        }
+                        Value::Null => found = Value::Null,
+                        _ => {}
+                    }
+                }
+                negate_if(found, *negated)
+            }
+            Self::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                let value = expr.evaluate(row)?;
+                let above = compare(&value, &low.evaluate(row)?)?.map(Ordering::is_ge);
+                let below = compare(&value, &high.evaluate(row)?)?.map(Ordering::is_le);
+                let between = match (above, below) {
+                    (Some(false), _) | (_, Some(false)) => Value::Boolean(false),
+                    (Some(true), Some(true)) => Value::Boolean(true),
+                    _ => Value::Null,
+                };
+                negate_if(between, *negated)
+            }
+            Self::Like {
+                expr,
+                pattern,
+                negated,
+            } => match expr.evaluate(row)? {
+                Value::Null => Value::Null,
+                Value::Bytes(bytes) => {
+                    let matches = pattern.is_match(&String::from_utf8_lossy(&bytes));
+                    Value::Boolean(matches != *negated)
+                }
+                value => return Err(format!("can't match {} with LIKE", type_name(&value))),
+            },
+            Self::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                let operand = operand
+                    .as_ref()
+                    .map(|operand| operand.evaluate(row))
+                    .transpose()?;
+                for (condition, result) in branches {
+                    let condition = condition.evaluate(row)?;
+                    let matches = match &operand {
+                        Some(operand) => equals(operand, &condition)?,
+                        None => condition,
+                    };
+                    if is_true(&matches) {
+                        return result.evaluate(row);
+                    }
+                }
+                match else_result {
+                    Some(else_result) => else_result.evaluate(row)?,
+                    None => Value::Null,
+                }
+            }
+            Self::Function { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.evaluate(row))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(*function, args)?
+            }
+            Self::GroupKey(index) => match row {
+                Row::Group { keys, .. } => keys[*index].clone(),
+                Row::Event(_) => Value::Null,
+            },
+            Self::Aggregate(index) => match row {
+                Row::Group { aggregates, .. } => aggregates[*index].clone(),
+                Row::Event(_) => Value::Null,
+            },
+            Self::WindowStart => match row {
+                Row::Group { window, .. } => Value::Timestamp(window.0),
+                Row::Event(_) => Value::Null,
+            },
+            Self::WindowEnd => match row {
+                Row::Group { window, .. } => Value::Timestamp(window.1),
+                Row::Event(_) => Value::Null,
+            },
+        })
+    }
+}
+
+/// Returns whether a value is true, as required by the `WHERE` and `HAVING` clauses.
+pub(super) const fn is_true(value: &Value) -> bool {
+    matches!(value, Value::Boolean(true))
+}
+
+fn negate_if(value: Value, negated: bool) -> Value {
+    match value {
+        Value::Boolean(value) => Value::Boolean(value != negated),
+        value => value,
+    }
+}
+
+const fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Bytes(_) | Value::Regex(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+        Value::Timestamp(_) => "timestamp",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::Null => "null",
+    }
+}
+
+fn binary(row: &Row<'_>, left: &Expr, op: BinaryOp, right: &Expr) -> EvalResult {
+    let left = left.evaluate(row)?;
+    match op {
+        // `AND` and `OR` follow three-valued logic, and only evaluate their right operand if needed.
+        BinaryOp::And | BinaryOp::Or => {
+            let short_circuit = op == BinaryOp::Or;
+            let left = logical(left)?;
+            if left == Some(short_circuit) {
+                return Ok(Value::Boolean(short_circuit));
+            }
+            let right = logical(right.evaluate(row)?)?;
+            Ok(match (left, right) {
+                (_, Some(right)) if right == short_circuit => Value::Boolean(short_circuit),
+                (Some(_), Some(right)) => Value::Boolean(right),
+                _ => Value::Null,
+            })
+        }
+        _ => {
+            let right = right.evaluate(row)?;
+            if left.is_null() || right.is_null() {
+                return Ok(Value::Null);
+            }
+            match op {
+                BinaryOp::Add
+                | BinaryOp::Subtract
+                | BinaryOp::Multiply
+                | BinaryOp::Divide
+                | BinaryOp::Modulo => arithmetic(&left, op, &right),
+                BinaryOp::Concat => Ok(Value::from(format!(
+                    "{}{}",
+                    left.to_string_lossy(),
+                    right.to_string_lossy()
+                ))),
+                BinaryOp::Eq => equals(&left, &right),
+                BinaryOp::NotEq => equals(&left, &right).map(|value| negate_if(value, true)),
+                BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+                    let ordering = compare(&left, &right)?.expect("values aren't null");
+                    Ok(Value::Boolean(match op {
+                        BinaryOp::Lt => ordering.is_lt(),
+                        BinaryOp::LtEq => ordering.is_le(),
+                        BinaryOp::Gt => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    }))
+                }
+                BinaryOp::And | BinaryOp::Or => unreachable!("logical operators are handled above"),
+            }
+        }
+    }
+}
+
+fn logical(value: Value) -> Result<Option<bool>, String> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Boolean(value) => Ok(Some(value)),
+        value => Err(format!("expected a boolean, got {}", type_name(&value))),
+    }
+}
+
+fn arithmetic(left: &Value, op: BinaryOp, right: &Value) -> EvalResult {
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => {
+            let result = match op {
+                BinaryOp::Add => left.checked_add(*right),
+                BinaryOp::Subtract => left.checked_sub(*right),
+                BinaryOp::Multiply => left.checked_mul(*right),
+                BinaryOp::Divide | BinaryOp::Modulo if *right == 0 => {
+                    return Err("division by zero".to_owned())
+                }
+                BinaryOp::Divide => left.checked_div(*right),
+                _ => left.checked_rem(*right),
+            };
+            result
+                .map(Value::Integer)
+                .ok_or_else(|| "integer overflow".to_owned())
+        }
+        _ => {
+            let (left, right) = match (as_f64(left), as_f64(right)) {
+                (Some(left), Some(right)) => (left, right),
+                _ => {
+                    return Err(format!(
+                        "can't compute {} and {}",
+                        type_name(left),
+                        type_name(right)
+                    ))
+                }
+            };
+            if matches!(op, BinaryOp::Divide | BinaryOp::Modulo) && right == 0.0 {
+                return Err("division by zero".to_owned());
+            }
+            Ok(Value::from_f64_or_zero(match op {
+                BinaryOp::Add => left + right,
+                BinaryOp::Subtract => left - right,
+                BinaryOp::Multiply => left * right,
+                BinaryOp::Divide => left / right,
+                _ => left % right,
+            }))
+        }
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(value) => Some(*value as f64),
+        Value::Float(value) => Some(value.into_inner()),
+        _ => None,
+    }
+}
+
+/// Compares two values, returning `None` if either of them is null.
+fn compare(left: &Value, right: &Value) -> Result<Option<Ordering>, String> {
+    Ok(Some(match (left, right) {
+        (Value::Null, _) | (_, Value::Null) => return Ok(None),
+        (Value::Integer(left), Value::Integer(right)) => left.cmp(right),
+        (Value::Bytes(left), Value::Bytes(right)) => left.cmp(right),
+        (Value::Boolean(left), Value::Boolean(right)) => left.cmp(right),
+        (Value::Timestamp(left), Value::Timestamp(right)) => left.cmp(right),
+        _ => match (as_f64(left), as_f64(right)) {
+            (Some(left), Some(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+            _ => {
+                return Err(format!(
+                    "can't compare {} and {}",
+                    type_name(left),
+                    type_name(right)
+                ))
+            }
+        },
+    }))
+}
+
+fn equals(left: &Value, right: &Value) -> EvalResult {
+    match (left, right) {
+        (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+        (Value::Object(_), _) | (Value::Array(_), _) => Ok(Value::Boolean(left == right)),
+        _ => compare(left, right).map(|ordering| Value::Boolean(ordering == Some(Ordering::Equal))),
+    }
+}
+
+fn call(function: ScalarFunction, mut args: Vec<Value>) -> EvalResult {
+    if function == ScalarFunction::Coalesce {
+        return Ok(args
+            .into_iter()
+            .find(|arg| !arg.is_null())
+            .unwrap_or(Value::Null));
+    }
+    if function == ScalarFunction::Concat {
+        return Ok(Value::from(
+            args.iter()
+                .filter(|arg| !arg.is_null())
+                .map(Value::to_string_lossy)
+                .collect::<String>(),
+        ));
+    }
+
+    let value = args.remove(0);
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    Ok(match (function, value) {
+        (ScalarFunction::Abs, Value::Integer(value)) => Value::Integer(value.abs()),
+        (ScalarFunction::Abs, Value::Float(value)) => Value::from_f64_or_zero(value.abs()),
+        (ScalarFunction::Length, Value::Bytes(bytes)) => {
+            Value::Integer(String::from_utf8_lossy(&bytes).chars().count() as i64)
+        }
+        (ScalarFunction::Length, Value::Array(array)) => Value::Integer(array.len() as i64),
+        (ScalarFunction::Lower, Value::Bytes(bytes)) => {
+            Value::from(String::from_utf8_lossy(&bytes).to_lowercase())
+        }
+        (ScalarFunction::Upper, Value::Bytes(bytes)) => {
+            Value::from(String::from_utf8_lossy(&bytes).to_uppercase())
+        }
+        (ScalarFunction::Round, Value::Integer(value)) => Value::Integer(value),
+        (ScalarFunction::Round, Value::Float(value)) => {
+            let digits = match args.first() {
+                Some(Value::Integer(digits)) => *digits as i32,
+                Some(Value::Null) | None => 0,
+                Some(digits) => {
+                    return Err(format!(
+                        "expected an integer number of digits, got {}",
+                        type_name(digits)
+                    ))
+                }
+            };
+            let factor = 10_f64.powi(digits);
+            Value::from_f64_or_zero((value.into_inner() * factor).round() / factor)
+        }
+        (function, value) => {
+            return Err(format!(
+                "can't call {:?} on {}",
+                function,
+                type_name(&value)
+            ))
+        }
+    })
+}
+
+/// An aggregate function of a query, with its argument.
+#[derive(Clone, Debug)]
+pub(super) struct Aggregate {
+    pub(super) function: AggregateFunction,
+    /// The argument of the function, which is missing for `COUNT(*)`.
+    pub(super) arg: Option<Expr>,
+    pub(super) distinct: bool,
+}
+
+#[derive(Clone, Debug)]
+enum State {
+    Count(i64),
+    Sum(Option<Value>),
+    Avg { sum: f64, count: u64 },
+    Min(Option<Value>),
+    Max(Option<Value>),
+}
+
+/// The state of an aggregate function over a group of events.
+#[derive(Clone, Debug)]
+pub(super) struct Accumulator {
+    state: State,
+    distinct: Option<HashSet<Value>>,
+}
+
+impl Accumulator {
+    pub(super) fn new(aggregate: &Aggregate) -> Self {
+        let state = match aggregate.function {
+            AggregateFunction::Avg => State::Avg { sum: 0.0, count: 0 },
+            AggregateFunction::Count => State::Count(0),
+            AggregateFunction::Max => State::Max(None),
+            AggregateFunction::Min => State::Min(None),
+            AggregateFunction::Sum => State::Sum(None),
+        };
+        Self {
+            state,
+            distinct: aggregate.distinct.then(HashSet::new),
+        }
+    }
+
+    /// Updates the state with the value of the argument for an event, or `None` for `COUNT(*)`.
+    pub(super) fn update(&mut self, value: Option<Value>) -> Result<(), String> {
+        let value = match value {
+            None => {
+                if let State::Count(count) = &mut self.state {
+                    *count += 1;
+                }
+                return Ok(());
+            }
+            // Null values are ignored by all the aggregate functions.
+            Some(Value::Null) => return Ok(()),
+            Some(value) => value,
+        };
+        if let Some(distinct) = &mut self.distinct {
+            if !distinct.insert(value.clone()) {
+                return Ok(());
+            }
+        }
+
+        let minimum = matches!(self.state, State::Min(_));
+        match &mut self.state {
+            State::Count(count) => *count += 1,
+            State::Sum(sum) => {
+                *sum = Some(match sum.take() {
+                    Some(sum) => arithmetic(&sum, BinaryOp::Add, &value)?,
+                    None if as_f64(&value).is_some() => value,
+                    None => return Err(format!("can't sum {}", type_name(&value))),
+                });
+            }
+            State::Avg { sum, count } => {
+                *sum +=
+                    as_f64(&value).ok_or_else(|| format!("can't average {}", type_name(&value)))?;
+                *count += 1;
+            }
+            State::Min(extremum) | State::Max(extremum) => {
+                let replace = match extremum {
+                    Some(current) => {
+                        compare(&value, current)?
+                            == Some(if minimum {
+                                Ordering::Less
+                            } else {
+                                Ordering::Greater
+                            })
+                    }
+                    None => true,
+                };
+                if replace {
+                    *extremum = Some(value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn finish(&self) -> Value {
+        match &self.state {
+            State::Count(count) => Value::Integer(*count),
+            State::Avg { count: 0, .. } => Value::Null,
+            State::Avg { sum, count } => Value::from_f64_or_zero(*sum / *count as f64),
+            State::Sum(value) | State::Min(value) | State::Max(value) => {
+                value.clone().unwrap_or(Value::Null)
+            }
+        }
+    }
+}
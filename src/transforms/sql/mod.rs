@@ -0,0 +1,407 @@
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use async_stream::stream;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
+use vector_config::configurable_component;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, EventMetadata, Value},
+    internal_events::SqlEvaluationError,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, TaskTransform, Transform},
+};
+
+mod expr;
+mod query;
+
+use self::{
+    expr::{Accumulator, Row},
+    query::Query,
+};
+
+/// Configuration for the `sql` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SqlConfig {
+    /// The SQL query run on the events.
+    ///
+    /// The events are queried from the `events` table, whose columns are their fields. Queries without aggregate
+    /// functions nor `GROUP BY` clause are run on each event, while the other ones are run on the events of each
+    /// window.
+    pub query: String,
+
+    /// The duration of the windows the events are aggregated over, in seconds.
+    ///
+    /// Only used by the queries with aggregate functions or a `GROUP BY` clause.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+}
+
+const fn default_window_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    TransformDescription::new::<SqlConfig>("sql")
+}
+
+impl GenerateConfig for SqlConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"query = "SELECT service, COUNT(*) AS errors FROM events WHERE level = 'error' GROUP BY service"
+            window_secs = 60"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sql")]
+impl TransformConfig for SqlConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        let query = Query::parse(&self.query)?;
+        if query.aggregation.is_none() {
+            return Ok(Transform::function(SqlProjection {
+                query: Arc::new(query),
+            }));
+        }
+
+        if self.window_secs == 0 {
+            return Err("`window_secs` must be greater than zero".into());
+        }
+        Ok(Transform::event_task(SqlAggregation::new(
+            query,
+            Duration::from_secs(self.window_secs),
+        )))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sql"
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+/// Runs a query without aggregations on each event.
+#[derive(Clone, Debug)]
+pub struct SqlProjection {
+    query: Arc<Query>,
+}
+
+impl FunctionTransform for SqlProjection {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        let log = event.into_log();
+        let result = self
+            .query
+            .matches(&log)
+            .and_then(|matches| matches.then(|| self.query.project(log)).transpose());
+        match result {
+            Ok(Some(log)) => output.push(log.into()),
+            Ok(None) => {}
+            Err(error) => emit!(SqlEvaluationError { error: &error }),
+        }
+    }
+}
+
+/// The state of a group of events in a window.
+#[derive(Debug)]
+struct Group {
+    accumulators: Vec<Accumulator>,
+    metadata: EventMetadata,
+}
+
+/// Runs a query with aggregations on the events of each window.
+#[derive(Debug)]
+pub struct SqlAggregation {
+    query: Query,
+    window: Duration,
+    window_start: DateTime<Utc>,
+    groups: IndexMap<Vec<Value>, Group>,
+}
+
+impl SqlAggregation {
+    fn new(query: Query, window: Duration) -> Self {
+        Self {
+            query,
+            window,
+            window_start: Utc::now(),
+            groups: IndexMap::new(),
+        }
+    }
+
+    fn record(&mut self, event: Event) -> Result<(), String> {
+        let log = event.into_log();
+        if !self.query.matches(&log)? {
+            return Ok(());
+        }
+
+        let aggregation = self
+            .query
+            .aggregation
+            .as_ref()
+            .expect("query is aggregated");
+        let row = Row::Event(&log);
+        let keys = aggregation
+            .group_by
+            .iter()
+            .map(|expr| expr.evaluate(&row))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = aggregation
+            .aggregates
+            .iter()
+            .map(|aggregate| {
+                aggregate
+                    .arg
+                    .as_ref()
+                    .map(|arg| arg.evaluate(&row))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (_, metadata) = log.into_parts();
+        let group = self.groups.entry(keys).or_insert_with(|| Group {
+            accumulators: aggregation
+                .aggregates
+                .iter()
+                .map(Accumulator::new)
+                .collect(),
+            metadata: EventMetadata::default(),
+        });
+        group.metadata.merge(metadata);
+        for (accumulator, value) in group.accumulators.iter_mut().zip(values) {
+            accumulator.update(value)?;
+        }
+        Ok(())
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let window_end = Utc::now();
+        let window = (self.window_start, window_end);
+        self.window_start = window_end;
+
+        for (keys, group) in std::mem::take(&mut self.groups) {
+            let aggregates = group
+                .accumulators
+                .iter()
+                .map(Accumulator::finish)
+                .collect::<Vec<_>>();
+            let row = Row::Group {
+                keys: &keys,
+                aggregates: &aggregates,
+                window,
+            };
+            let result = self.query.matches_group(&row).and_then(|matches| {
+                matches
+                    .then(|| self.query.project_group(&row, group.metadata))
+                    .transpose()
+            });
+            match result {
+                Ok(Some(log)) => output.push(log.into()),
+                Ok(None) => {}
+                Err(error) => emit!(SqlEvaluationError { error: &error }),
+            }
+        }
+    }
+}
+
+impl TaskTransform<Event> for SqlAggregation {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.window, self.window);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output);
+                                done = true;
+                            }
+                            Some(event) => {
+                                if let Err(error) = self.record(event) {
+                                    emit!(SqlEvaluationError { error: &error });
+                                }
+                            }
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::LogEvent, transforms::test::transform_one};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqlConfig>();
+    }
+
+    fn event(json: serde_json::Value) -> Event {
+        Event::from(LogEvent::try_from(json).unwrap())
+    }
+
+    fn projection(sql: &str) -> SqlProjection {
+        SqlProjection {
+            query: Arc::new(Query::parse(sql).unwrap()),
+        }
+    }
+
+    fn aggregation(sql: &str) -> SqlAggregation {
+        SqlAggregation::new(Query::parse(sql).unwrap(), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn projects_events() {
+        let mut transform = projection(
+            "SELECT message, UPPER(level) AS level, status / 100 AS class, http.method \
+             FROM events WHERE status >= 400 AND message LIKE 'GET %'",
+        );
+
+        let output = transform_one(
+            &mut transform,
+            event(serde_json::json!({
+                "message": "GET /users",
+                "level": "error",
+                "status": 503,
+                "http": { "method": "GET" },
+                "host": "a",
+            })),
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            event(serde_json::json!({
+                "message": "GET /users",
+                "level": "ERROR",
+                "class": 5,
+                "method": "GET",
+            }))
+        );
+
+        let ignored = event(serde_json::json!({ "message": "GET /", "status": 200 }));
+        assert!(transform_one(&mut transform, ignored).is_none());
+    }
+
+    #[test]
+    fn keeps_fields_with_wildcard() {
+        let mut transform = projection(
+            "SELECT *, CASE WHEN status >= 500 THEN 'server' ELSE 'client' END AS fault FROM events",
+        );
+
+        let output = transform_one(
+            &mut transform,
+            event(serde_json::json!({ "message": "oops", "status": 404 })),
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            event(serde_json::json!({ "message": "oops", "status": 404, "fault": "client" }))
+        );
+    }
+
+    #[test]
+    fn discards_events_failing_evaluation() {
+        let mut transform = projection("SELECT status + 1 AS next FROM events");
+        let invalid = event(serde_json::json!({ "status": "ok" }));
+        assert!(transform_one(&mut transform, invalid).is_none());
+    }
+
+    #[test]
+    fn aggregates_events_by_group() {
+        let mut transform = aggregation(
+            "SELECT service, COUNT(*) AS count, SUM(duration) AS total, AVG(duration) AS average, \
+             MAX(duration) AS slowest, COUNT(DISTINCT host) AS hosts \
+             FROM events WHERE level = 'info' GROUP BY service HAVING COUNT(*) > 1",
+        );
+        for (service, host, duration) in [
+            ("api", "a", 10),
+            ("api", "b", 30),
+            ("api", "a", 20),
+            ("web", "a", 5),
+        ] {
+            transform
+                .record(event(serde_json::json!({
+                    "level": "info",
+                    "service": service,
+                    "host": host,
+                    "duration": duration,
+                })))
+                .unwrap();
+        }
+        transform
+            .record(event(
+                serde_json::json!({ "level": "debug", "service": "api" }),
+            ))
+            .unwrap();
+
+        let mut output = Vec::new();
+        transform.flush_into(&mut output);
+        assert_eq!(
+            output,
+            vec![event(serde_json::json!({
+                "service": "api",
+                "count": 3,
+                "total": 60,
+                "average": 20.0,
+                "slowest": 30,
+                "hosts": 2,
+            }))]
+        );
+
+        output.clear();
+        transform.flush_into(&mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn adds_window_bounds() {
+        let mut transform = aggregation(
+            "SELECT window_start() AS start, window_end() AS end, COUNT(*) AS count FROM events",
+        );
+        transform
+            .record(event(serde_json::json!({ "message": "a" })))
+            .unwrap();
+
+        let mut output = Vec::new();
+        transform.flush_into(&mut output);
+        let log = output[0].as_log();
+        assert_eq!(log["count"], Value::Integer(1));
+        assert!(log["start"].as_timestamp().unwrap() <= log["end"].as_timestamp().unwrap());
+    }
+}
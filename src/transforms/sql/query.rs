@@ -0,0 +1,648 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use sqlparser::{
+    ast,
+    dialect::GenericDialect,
+    parser::{Parser, ParserError},
+};
+
+use super::expr::{is_true, Aggregate, AggregateFunction, BinaryOp, Expr, Row, ScalarFunction};
+use crate::event::{EventMetadata, LogEvent, Value};
+
+/// The name of the table of the events.
+const TABLE: &str = "events";
+
+#[derive(Debug, Snafu)]
+pub enum QueryError {
+    #[snafu(display("Could not parse SQL query: {}", source))]
+    Parse { source: ParserError },
+    #[snafu(display("Expected a single SELECT statement"))]
+    NotSelect,
+    #[snafu(display("Unsupported SQL: {}", what))]
+    Unsupported { what: String },
+    #[snafu(display("Invalid SQL query: {}", message))]
+    Invalid { message: String },
+}
+
+fn unsupported(what: impl std::fmt::Display) -> QueryError {
+    QueryError::Unsupported {
+        what: what.to_string(),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> QueryError {
+    QueryError::Invalid {
+        message: message.into(),
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Column {
+    /// All the fields of the event.
+    Wildcard,
+    Named {
+        name: String,
+        expr: Expr,
+    },
+}
+
+/// The aggregation of the events of a query in windows.
+#[derive(Clone, Debug)]
+pub(super) struct Aggregation {
+    pub(super) group_by: Vec<Expr>,
+    pub(super) aggregates: Vec<Aggregate>,
+    having: Option<Expr>,
+}
+
+/// A compiled SQL query.
+#[derive(Clone, Debug)]
+pub(super) struct Query {
+    columns: Vec<Column>,
+    filter: Option<Expr>,
+    pub(super) aggregation: Option<Aggregation>,
+}
+
+impl Query {
+    pub(super) fn parse(sql: &str) -> Result<Self, QueryError> {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql).context(ParseSnafu)?;
+        if statements.len() != 1 {
+            return Err(QueryError::NotSelect);
+        }
+        let query = match statements.remove(0) {
+            ast::Statement::Query(query) => query,
+            _ => return Err(QueryError::NotSelect),
+        };
+        if query.with.is_some() {
+            return Err(unsupported("WITH"));
+        }
+        if !query.order_by.is_empty() {
+            return Err(unsupported("ORDER BY"));
+        }
+        if query.limit.is_some() || query.offset.is_some() || query.fetch.is_some() {
+            return Err(unsupported("LIMIT, OFFSET and FETCH"));
+        }
+        let select = match query.body {
+            ast::SetExpr::Select(select) => select,
+            body => return Err(unsupported(body)),
+        };
+        if select.distinct || select.top.is_some() || select.into.is_some() {
+            return Err(unsupported("DISTINCT, TOP and INTO"));
+        }
+        if !select.lateral_views.is_empty()
+            || !select.cluster_by.is_empty()
+            || !select.distribute_by.is_empty()
+            || !select.sort_by.is_empty()
+            || select.qualify.is_some()
+        {
+            return Err(unsupported("Hive and Snowflake clauses"));
+        }
+
+        let table = table_alias(&select.from)?;
+        let filter = select
+            .selection
+            .as_ref()
+            .map(|selection| Compiler::scalar(&table, selection))
+            .transpose()?;
+        let group_by = select
+            .group_by
+            .iter()
+            .map(|expr| Compiler::scalar(&table, expr))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The query is first compiled as if it wasn't aggregated, to find out whether it uses aggregate functions.
+        let mut compiler = Compiler::new(&table, None);
+        let columns = compiler.columns(&select.projection)?;
+        if let Some(having) = &select.having {
+            compiler.compile(having)?;
+        }
+        if compiler.aggregates.is_empty() && select.group_by.is_empty() {
+            if select.having.is_some() || compiler.windowed {
+                return Err(invalid(
+                    "HAVING, window_start and window_end require GROUP BY or aggregate functions",
+                ));
+            }
+            return Ok(Self {
+                columns,
+                filter,
+                aggregation: None,
+            });
+        }
+
+        let mut compiler = Compiler::new(&table, Some(select.group_by.as_slice()));
+        let columns = compiler.columns(&select.projection)?;
+        let having = select
+            .having
+            .as_ref()
+            .map(|having| compiler.compile(having))
+            .transpose()?;
+        Ok(Self {
+            columns,
+            filter,
+            aggregation: Some(Aggregation {
+                group_by,
+                aggregates: compiler.aggregates,
+                having,
+            }),
+        })
+    }
+
+    /// Returns whether an event matches the `WHERE` clause.
+    pub(super) fn matches(&self, log: &LogEvent) -> Result<bool, String> {
+        match &self.filter {
+            Some(filter) => filter
+                .evaluate(&Row::Event(log))
+                .map(|value| is_true(&value)),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns whether a group of events matches the `HAVING` clause.
+    pub(super) fn matches_group(&self, row: &Row<'_>) -> Result<bool, String> {
+        match self
+            .aggregation
+            .as_ref()
+            .and_then(|aggregation| aggregation.having.as_ref())
+        {
+            Some(having) => having.evaluate(row).map(|value| is_true(&value)),
+            None => Ok(true),
+        }
+    }
+
+    fn evaluate_columns(&self, row: &Row<'_>) -> Result<Vec<(&str, Value)>, String> {
+        self.columns
+            .iter()
+            .filter_map(|column| match column {
+                Column::Wildcard => None,
+                Column::Named { name, expr } => {
+                    Some(expr.evaluate(row).map(|value| (name.as_str(), value)))
+                }
+            })
+            .collect()
+    }
+
+    /// Projects an event on the columns of the query.
+    pub(super) fn project(&self, log: LogEvent) -> Result<LogEvent, String> {
+        let values = self.evaluate_columns(&Row::Event(&log))?;
+        let (value, metadata) = log.into_parts();
+        let mut fields = if self
+            .columns
+            .iter()
+            .any(|column| matches!(column, Column::Wildcard))
+        {
+            value.into_object().unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+        fields.extend(
+            values
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value)),
+        );
+        Ok(LogEvent::from_map(fields, metadata))
+    }
+
+    /// Projects a group of events on the columns of the query.
+    pub(super) fn project_group(
+        &self,
+        row: &Row<'_>,
+        metadata: EventMetadata,
+    ) -> Result<LogEvent, String> {
+        let fields = self
+            .evaluate_columns(row)?
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect();
+        Ok(LogEvent::from_map(fields, metadata))
+    }
+}
+
+/// Returns the name the table is referred to with in the query.
+fn table_alias(from: &[ast::TableWithJoins]) -> Result<String, QueryError> {
+    let table = match from {
+        [table] if table.joins.is_empty() => &table.relation,
+        [] => return Err(invalid(format!("expected FROM {}", TABLE))),
+        _ => return Err(unsupported("JOIN")),
+    };
+    match table {
+        ast::TableFactor::Table {
+            name,
+            alias,
+            args: None,
+            ..
+        } if name.0.len() == 1 && name.0[0].value.eq_ignore_ascii_case(TABLE) => Ok(alias
+            .as_ref()
+            .map_or_else(|| TABLE.to_owned(), |alias| alias.name.value.clone())),
+        table => Err(invalid(format!(
+            "unknown table {}, expected {}",
+            table, TABLE
+        ))),
+    }
+}
+
+/// Compiles the expressions of a query.
+struct Compiler<'a> {
+    table: &'a str,
+    /// The expressions of the `GROUP BY` clause, when compiling the expressions of an aggregated query.
+    group_by: Option<&'a [ast::Expr]>,
+    aggregates: Vec<Aggregate>,
+    in_aggregate: bool,
+    /// Whether `window_start` or `window_end` are used.
+    windowed: bool,
+}
+
+impl<'a> Compiler<'a> {
+    const fn new(table: &'a str, group_by: Option<&'a [ast::Expr]>) -> Self {
+        Self {
+            table,
+            group_by,
+            aggregates: Vec::new(),
+            in_aggregate: false,
+            windowed: false,
+        }
+    }
+
+    /// Compiles an expression evaluated on each event, such as the ones of the `WHERE` and `GROUP BY` clauses.
+    fn scalar(table: &'a str, expr: &ast::Expr) -> Result<Expr, QueryError> {
+        let mut compiler = Self::new(table, None);
+        let compiled = compiler.compile(expr)?;
+        if !compiler.aggregates.is_empty() || compiler.windowed {
+            return Err(invalid(format!(
+                "aggregate and window functions can't be used in WHERE and GROUP BY clauses, found in {}",
+                expr
+            )));
+        }
+        Ok(compiled)
+    }
+
+    fn columns(&mut self, projection: &[ast::SelectItem]) -> Result<Vec<Column>, QueryError> {
+        projection
+            .iter()
+            .map(|item| match item {
+                ast::SelectItem::Wildcard if self.group_by.is_none() => Ok(Column::Wildcard),
+                ast::SelectItem::Wildcard | ast::SelectItem::QualifiedWildcard(_) => {
+                    Err(unsupported(item))
+                }
+                ast::SelectItem::UnnamedExpr(expr) => Ok(Column::Named {
+                    name: column_name(expr),
+                    expr: self.compile(expr)?,
+                }),
+                ast::SelectItem::ExprWithAlias { expr, alias } => Ok(Column::Named {
+                    name: alias.value.clone(),
+                    expr: self.compile(expr)?,
+                }),
+            })
+            .collect()
+    }
+
+    fn compile_box(&mut self, expr: &ast::Expr) -> Result<Box<Expr>, QueryError> {
+        self.compile(expr).map(Box::new)
+    }
+
+    fn compile(&mut self, expr: &ast::Expr) -> Result<Expr, QueryError> {
+        if let Some(group_by) = self.group_by.filter(|_| !self.in_aggregate) {
+            if let Some(index) = group_by.iter().position(|key| key == expr) {
+                return Ok(Expr::GroupKey(index));
+            }
+        }
+
+        Ok(match expr {
+            ast::Expr::Identifier(ident) => self.field(std::slice::from_ref(ident))?,
+            ast::Expr::CompoundIdentifier(idents) => self.field(idents)?,
+            ast::Expr::Value(value) => Expr::Literal(literal(value)?),
+            ast::Expr::Nested(expr) => self.compile(expr)?,
+            ast::Expr::IsNull(expr) => Expr::IsNull {
+                expr: self.compile_box(expr)?,
+                negated: false,
+            },
+            ast::Expr::IsNotNull(expr) => Expr::IsNull {
+                expr: self.compile_box(expr)?,
+                negated: true,
+            },
+            ast::Expr::InList {
+                expr,
+                list,
+                negated,
+            } => Expr::InList {
+                expr: self.compile_box(expr)?,
+                list: list
+                    .iter()
+                    .map(|item| self.compile(item))
+                    .collect::<Result<_, _>>()?,
+                negated: *negated,
+            },
+            ast::Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Expr::Between {
+                expr: self.compile_box(expr)?,
+                low: self.compile_box(low)?,
+                high: self.compile_box(high)?,
+                negated: *negated,
+            },
+            ast::Expr::UnaryOp { op, expr } => match op {
+                ast::UnaryOperator::Plus => self.compile(expr)?,
+                ast::UnaryOperator::Minus => Expr::Negate(self.compile_box(expr)?),
+                ast::UnaryOperator::Not => Expr::Not(self.compile_box(expr)?),
+                op => return Err(unsupported(format!("operator {}", op))),
+            },
+            ast::Expr::BinaryOp { left, op, right } => self.binary(left, op, right)?,
+            ast::Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => Expr::Case {
+                operand: operand
+                    .as_ref()
+                    .map(|operand| self.compile_box(operand))
+                    .transpose()?,
+                branches: conditions
+                    .iter()
+                    .zip(results)
+                    .map(|(condition, result)| {
+                        Ok((self.compile(condition)?, self.compile(result)?))
+                    })
+                    .collect::<Result<_, QueryError>>()?,
+                else_result: else_result
+                    .as_ref()
+                    .map(|else_result| self.compile_box(else_result))
+                    .transpose()?,
+            },
+            ast::Expr::Function(function) => self.function(function)?,
+            expr => return Err(unsupported(expr)),
+        })
+    }
+
+    fn field(&self, idents: &[ast::Ident]) -> Result<Expr, QueryError> {
+        if self.group_by.is_some() && !self.in_aggregate {
+            return Err(invalid(format!(
+                "{} must appear in the GROUP BY clause or be used in an aggregate function",
+                ast::ObjectName(idents.to_vec())
+            )));
+        }
+
+        // Fields may be qualified with the name of the table.
+        let idents = match idents {
+            [table, rest @ ..] if !rest.is_empty() && table.value == self.table => rest,
+            idents => idents,
+        };
+        Ok(Expr::Field(
+            idents
+                .iter()
+                .map(|ident| path_segment(&ident.value))
+                .collect::<Vec<_>>()
+                .join("."),
+        ))
+    }
+
+    fn binary(
+        &mut self,
+        left: &ast::Expr,
+        op: &ast::BinaryOperator,
+        right: &ast::Expr,
+    ) -> Result<Expr, QueryError> {
+        let (op, negated) = match op {
+            ast::BinaryOperator::Like => (None, false),
+            ast::BinaryOperator::NotLike => (None, true),
+            ast::BinaryOperator::Plus => (Some(BinaryOp::Add), false),
+            ast::BinaryOperator::Minus => (Some(BinaryOp::Subtract), false),
+            ast::BinaryOperator::Multiply => (Some(BinaryOp::Multiply), false),
+            ast::BinaryOperator::Divide => (Some(BinaryOp::Divide), false),
+            ast::BinaryOperator::Modulo => (Some(BinaryOp::Modulo), false),
+            ast::BinaryOperator::StringConcat => (Some(BinaryOp::Concat), false),
+            ast::BinaryOperator::Eq => (Some(BinaryOp::Eq), false),
+            ast::BinaryOperator::NotEq => (Some(BinaryOp::NotEq), false),
+            ast::BinaryOperator::Lt => (Some(BinaryOp::Lt), false),
+            ast::BinaryOperator::LtEq => (Some(BinaryOp::LtEq), false),
+            ast::BinaryOperator::Gt => (Some(BinaryOp::Gt), false),
+            ast::BinaryOperator::GtEq => (Some(BinaryOp::GtEq), false),
+            ast::BinaryOperator::And => (Some(BinaryOp::And), false),
+            ast::BinaryOperator::Or => (Some(BinaryOp::Or), false),
+            op => return Err(unsupported(format!("operator {}", op))),
+        };
+
+        Ok(match op {
+            Some(op) => Expr::Binary {
+                left: self.compile_box(left)?,
+                op,
+                right: self.compile_box(right)?,
+            },
+            None => {
+                let pattern = match right {
+                    ast::Expr::Value(ast::Value::SingleQuotedString(pattern)) => like(pattern),
+                    _ => return Err(unsupported("LIKE with a pattern that's not a string")),
+                };
+                Expr::Like {
+                    expr: self.compile_box(left)?,
+                    pattern,
+                    negated,
+                }
+            }
+        })
+    }
+
+    fn function(&mut self, function: &ast::Function) -> Result<Expr, QueryError> {
+        let name = function.name.to_string().to_lowercase();
+        if function.over.is_some() {
+            return Err(unsupported("window functions"));
+        }
+
+        let aggregate = match name.as_str() {
+            "avg" => Some(AggregateFunction::Avg),
+            "count" => Some(AggregateFunction::Count),
+            "max" => Some(AggregateFunction::Max),
+            "min" => Some(AggregateFunction::Min),
+            "sum" => Some(AggregateFunction::Sum),
+            _ => None,
+        };
+        if let Some(aggregate) = aggregate {
+            return self.aggregate(aggregate, function);
+        }
+        if function.distinct {
+            return Err(invalid(format!("{} isn't an aggregate function", name)));
+        }
+
+        let mut args = Vec::new();
+        for arg in &function.args {
+            match arg {
+                ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(expr)) => {
+                    args.push(self.compile(expr)?)
+                }
+                arg => return Err(unsupported(format!("argument {} of {}", arg, name))),
+            }
+        }
+
+        let (function, arity) = match name.as_str() {
+            "window_start" | "window_end" => {
+                if self.in_aggregate {
+                    return Err(invalid(format!(
+                        "{} can't be used in aggregate functions",
+                        name
+                    )));
+                }
+                self.windowed = true;
+                if !args.is_empty() {
+                    return Err(invalid(format!("{} doesn't take arguments", name)));
+                }
+                return Ok(if name == "window_start" {
+                    Expr::WindowStart
+                } else {
+                    Expr::WindowEnd
+                });
+            }
+            "abs" => (ScalarFunction::Abs, 1..=1),
+            "coalesce" => (ScalarFunction::Coalesce, 1..=usize::MAX),
+            "concat" => (ScalarFunction::Concat, 1..=usize::MAX),
+            "length" | "char_length" => (ScalarFunction::Length, 1..=1),
+            "lower" => (ScalarFunction::Lower, 1..=1),
+            "round" => (ScalarFunction::Round, 1..=2),
+            "upper" => (ScalarFunction::Upper, 1..=1),
+            _ => return Err(unsupported(format!("function {}", name))),
+        };
+        if !arity.contains(&args.len()) {
+            return Err(invalid(format!("wrong number of arguments for {}", name)));
+        }
+        Ok(Expr::Function { function, args })
+    }
+
+    fn aggregate(
+        &mut self,
+        function: AggregateFunction,
+        call: &ast::Function,
+    ) -> Result<Expr, QueryError> {
+        if self.in_aggregate {
+            return Err(invalid("aggregate functions can't be nested"));
+        }
+        let arg = match call.args.as_slice() {
+            [ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Wildcard)]
+                if function == AggregateFunction::Count && !call.distinct =>
+            {
+                None
+            }
+            [ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(expr))] => {
+                self.in_aggregate = true;
+                let arg = self.compile(expr);
+                self.in_aggregate = false;
+                Some(arg?)
+            }
+            _ => return Err(invalid(format!("invalid arguments for {}", call.name))),
+        };
+
+        self.aggregates.push(Aggregate {
+            function,
+            arg,
+            distinct: call.distinct,
+        });
+        Ok(Expr::Aggregate(self.aggregates.len() - 1))
+    }
+}
+
+/// Returns the name of the column of an expression without an alias.
+fn column_name(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::Identifier(ident) => ident.value.clone(),
+        ast::Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map(|ident| ident.value.clone())
+            .unwrap_or_default(),
+        expr => expr.to_string(),
+    }
+}
+
+/// Quotes the segments of the path of a field that aren't plain identifiers.
+fn path_segment(segment: &str) -> String {
+    if !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@')
+    {
+        segment.to_owned()
+    } else {
+        format!("\"{}\"", segment.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+fn literal(value: &ast::Value) -> Result<Value, QueryError> {
+    Ok(match value {
+        ast::Value::Number(number, _) => match number.parse::<i64>() {
+            Ok(integer) => Value::Integer(integer),
+            Err(_) => number
+                .parse::<f64>()
+                .map(Value::from_f64_or_zero)
+                .map_err(|_| invalid(format!("invalid number {}", number)))?,
+        },
+        ast::Value::SingleQuotedString(string) => Value::from(string.as_str()),
+        ast::Value::Boolean(boolean) => Value::Boolean(*boolean),
+        ast::Value::Null => Value::Null,
+        value => return Err(unsupported(value)),
+    })
+}
+
+/// Converts a `LIKE` pattern to a regular expression.
+fn like(pattern: &str) -> Regex {
+    let mut regex = String::from("(?s)^");
+    for c in pattern.chars() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped patterns are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiles_projections() {
+        let query =
+            Query::parse("SELECT message, status * 2 AS double FROM events WHERE status >= 400")
+                .unwrap();
+        assert!(query.aggregation.is_none());
+        assert_eq!(query.columns.len(), 2);
+        assert!(query.filter.is_some());
+    }
+
+    #[test]
+    fn compiles_aggregations() {
+        let query = Query::parse(
+            "SELECT service, COUNT(*) AS count, AVG(duration) FROM events GROUP BY service HAVING COUNT(*) > 1",
+        )
+        .unwrap();
+        let aggregation = query.aggregation.unwrap();
+        assert_eq!(aggregation.group_by.len(), 1);
+        assert_eq!(aggregation.aggregates.len(), 3);
+        assert!(aggregation.having.is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        for sql in [
+            "DELETE FROM events",
+            "SELECT * FROM logs",
+            "SELECT message FROM events ORDER BY message",
+            "SELECT message, COUNT(*) FROM events",
+            "SELECT * FROM events GROUP BY service",
+            "SELECT SUM(COUNT(*)) FROM events",
+            "SELECT window_start() FROM events",
+            "SELECT unknown(message) FROM events",
+        ] {
+            assert!(Query::parse(sql).is_err(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn converts_like_patterns() {
+        let pattern = like("GET /api/%.json_");
+        assert!(pattern.is_match("GET /api/users.jsonp"));
+        assert!(!pattern.is_match("GET /api/users.json"));
+        assert!(!pattern.is_match("POST /api/users.jsonp"));
+    }
+}
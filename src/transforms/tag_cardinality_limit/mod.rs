@@ -2,12 +2,14 @@ use std::{
     borrow::{Borrow, Cow},
     collections::{HashMap, HashSet},
     fmt,
-    future::ready,
     pin::Pin,
+    time::Duration,
 };
 
+use async_stream::stream;
 use bloom::{BloomFilter, ASMS};
 use futures::{Stream, StreamExt};
+use snafu::Snafu;
 use vector_config::configurable_component;
 
 use crate::{
@@ -17,13 +19,18 @@ use crate::{
     },
     event::Event,
     internal_events::{
-        TagCardinalityLimitRejectingEvent, TagCardinalityLimitRejectingTag,
-        TagCardinalityValueLimitReached,
+        TagCardinalityLimitPersistenceError, TagCardinalityLimitRejectingEvent,
+        TagCardinalityLimitRejectingTag, TagCardinalityValueLimitReached,
     },
     schema,
     transforms::{TaskTransform, Transform},
 };
 
+mod persistence;
+
+use self::persistence::{AcceptedValues, Store};
+pub use self::persistence::{PersistenceBackendConfig, PersistenceConfig};
+
 /// Configuration for the `tag_cardinality_limit` transform.
 #[configurable_component(transform)]
 #[derive(Clone, Debug)]
@@ -38,6 +45,43 @@ pub struct TagCardinalityLimitConfig {
 
     #[serde(flatten)]
     pub mode: Mode,
+
+    /// The limits of specific tags, overriding the global ones.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub per_tag: HashMap<String, TagLimitConfig>,
+
+    /// Persists the accepted values of the tags across restarts.
+    ///
+    /// Without it, the accepted values are forgotten when Vector restarts, and the first values seen afterwards are
+    /// accepted instead. Only supported in `exact` mode.
+    #[configurable(derived)]
+    pub persistence: Option<PersistenceConfig>,
+}
+
+/// The limits of a tag.
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TagLimitConfig {
+    /// How many distinct values to accept for the tag.
+    ///
+    /// By default, the global `value_limit` applies.
+    pub value_limit: Option<u32>,
+
+    /// The action to take when the limit of the tag is exceeded.
+    ///
+    /// By default, the global `limit_exceeded_action` applies.
+    #[configurable(derived)]
+    pub limit_exceeded_action: Option<LimitExceededAction>,
+
+    /// The values of the tag that are always accepted, even once its limit is reached.
+    ///
+    /// These values don't count towards the limit.
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
 }
 
 /// Controls the approach taken for tracking tag cardinality.
@@ -73,7 +117,7 @@ pub struct BloomFilterConfig {
 
 /// Possible actions to take when an event arrives that would exceed the cardinality limit for one or more of its tags.
 #[configurable_component]
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LimitExceededAction {
     /// Drop the tag(s) that would exceed the configured limit.
@@ -83,10 +127,30 @@ pub enum LimitExceededAction {
     DropEvent,
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Persistence is only supported in `exact` mode"))]
+    PersistenceRequiresExactMode,
+}
+
+/// The limits of a tag, resolved from the global and per tag configuration.
 #[derive(Debug)]
+struct TagLimit {
+    value_limit: u32,
+    limit_exceeded_action: LimitExceededAction,
+    allowed_values: HashSet<String>,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct TagCardinalityLimit {
     config: TagCardinalityLimitConfig,
+    per_tag: HashMap<String, TagLimit>,
     accepted_tags: HashMap<String, TagValueSet>,
+    #[derivative(Debug = "ignore")]
+    store: Option<Store>,
+    /// Whether values were accepted since the accepted values were last saved.
+    dirty: bool,
 }
 
 const fn default_limit_exceeded_action() -> LimitExceededAction {
@@ -111,6 +175,8 @@ impl GenerateConfig for TagCardinalityLimitConfig {
             mode: Mode::Exact,
             value_limit: default_value_limit(),
             limit_exceeded_action: default_limit_exceeded_action(),
+            per_tag: HashMap::new(),
+            persistence: None,
         })
         .unwrap()
     }
@@ -119,10 +185,18 @@ impl GenerateConfig for TagCardinalityLimitConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "tag_cardinality_limit")]
 impl TransformConfig for TagCardinalityLimitConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::event_task(TagCardinalityLimit::new(
-            self.clone(),
-        )))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let mut transform = TagCardinalityLimit::new(self.clone());
+        if let Some(persistence) = &self.persistence {
+            if !matches!(self.mode, Mode::Exact) {
+                return Err(Box::new(BuildError::PersistenceRequiresExactMode));
+            }
+            let mut store = persistence.backend.build(context).await?;
+            let accepted_values = store.load().await?;
+            transform.restore(accepted_values);
+            transform.store = Some(store);
+        }
+        Ok(Transform::event_task(transform))
     }
 
     fn input(&self) -> Input {
@@ -180,6 +254,14 @@ impl TagValueSet {
         }
     }
 
+    /// Returns the accepted values, if they are known exactly.
+    fn values(&self) -> Option<Vec<String>> {
+        match &self.storage {
+            TagValueSetStorage::Set(set) => Some(set.iter().cloned().collect()),
+            TagValueSetStorage::Bloom(_) => None,
+        }
+    }
+
     fn contains(&self, value: Cow<'_, String>) -> bool {
         match &self.storage {
             TagValueSetStorage::Set(set) => set.contains(value.borrow() as &String),
@@ -205,25 +287,97 @@ impl TagValueSet {
 
 impl TagCardinalityLimit {
     fn new(config: TagCardinalityLimitConfig) -> Self {
+        let per_tag = config
+            .per_tag
+            .iter()
+            .map(|(key, limit)| {
+                let limit = TagLimit {
+                    value_limit: limit.value_limit.unwrap_or(config.value_limit),
+                    limit_exceeded_action: limit
+                        .limit_exceeded_action
+                        .unwrap_or(config.limit_exceeded_action),
+                    allowed_values: limit.allowed_values.iter().cloned().collect(),
+                };
+                (key.clone(), limit)
+            })
+            .collect();
+
         Self {
             config,
+            per_tag,
             accepted_tags: HashMap::new(),
+            store: None,
+            dirty: false,
+        }
+    }
+
+    fn value_limit(&self, key: &str) -> u32 {
+        self.per_tag
+            .get(key)
+            .map_or(self.config.value_limit, |limit| limit.value_limit)
+    }
+
+    fn limit_exceeded_action(&self, key: &str) -> LimitExceededAction {
+        self.per_tag
+            .get(key)
+            .map_or(self.config.limit_exceeded_action, |limit| {
+                limit.limit_exceeded_action
+            })
+    }
+
+    /// Restores the persisted accepted values, as many as the current limit of each tag.
+    fn restore(&mut self, accepted_values: AcceptedValues) {
+        for (key, values) in accepted_values {
+            let value_limit = self.value_limit(&key);
+            let mut tag_value_set = TagValueSet::new(value_limit, &self.config.mode);
+            for value in values.into_iter().take(value_limit as usize) {
+                tag_value_set.insert(Cow::Owned(value));
+            }
+            self.accepted_tags.insert(key, tag_value_set);
+        }
+    }
+
+    fn accepted_values(&self) -> AcceptedValues {
+        self.accepted_tags
+            .iter()
+            .filter_map(|(key, tag_value_set)| Some((key.clone(), tag_value_set.values()?)))
+            .collect()
+    }
+
+    /// Saves the accepted values, if any were accepted since they were last saved.
+    async fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let accepted_values = self.accepted_values();
+        if let Some(store) = self.store.as_mut() {
+            match store.save(&accepted_values).await {
+                Ok(()) => self.dirty = false,
+                Err(error) => emit!(TagCardinalityLimitPersistenceError { error }),
+            }
         }
     }
 
     /// Takes in key and a value corresponding to a tag on an incoming Metric
     /// Event.  If that value is already part of set of accepted values for that
-    /// key, then simply returns true.  If that value is not yet part of the
-    /// accepted values for that key, checks whether we have hit the value_limit
-    /// for that key yet and if not adds the value to the set of accepted values
-    /// for the key and returns true, otherwise returns false.  A false return
-    /// value indicates to the caller that the value is not accepted for this
-    /// key, and the configured limit_exceeded_action should be taken.
+    /// key, or is allowed for that key, then simply returns true.  If that value
+    /// is not yet part of the accepted values for that key, checks whether we
+    /// have hit the value_limit for that key yet and if not adds the value to
+    /// the set of accepted values for the key and returns true, otherwise
+    /// returns false.  A false return value indicates to the caller that the
+    /// value is not accepted for this key, and the limit_exceeded_action of the
+    /// key should be taken.
     fn try_accept_tag(&mut self, key: &str, value: Cow<'_, String>) -> bool {
+        let value_limit = match self.per_tag.get(key) {
+            Some(limit) if limit.allowed_values.contains(value.as_ref()) => return true,
+            Some(limit) => limit.value_limit,
+            None => self.config.value_limit,
+        };
+
         if !self.accepted_tags.contains_key(key) {
             self.accepted_tags.insert(
                 key.to_string(),
-                TagValueSet::new(self.config.value_limit, &self.config.mode),
+                TagValueSet::new(value_limit, &self.config.mode),
             );
         }
         let tag_value_set = self.accepted_tags.get_mut(key).unwrap();
@@ -234,11 +388,12 @@ impl TagCardinalityLimit {
         }
 
         // Tag value not yet part of the accepted set.
-        if tag_value_set.len() < self.config.value_limit as usize {
+        if tag_value_set.len() < value_limit as usize {
             // accept the new value
             tag_value_set.insert(value);
+            self.dirty = true;
 
-            if tag_value_set.len() == self.config.value_limit as usize {
+            if tag_value_set.len() == value_limit as usize {
                 emit!(TagCardinalityValueLimitReached { key });
             }
 
@@ -252,34 +407,31 @@ impl TagCardinalityLimit {
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
         let metric = event.as_mut_metric();
         if let Some(tags_map) = metric.tags() {
-            match self.config.limit_exceeded_action {
-                LimitExceededAction::DropEvent => {
-                    for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
-                            emit!(TagCardinalityLimitRejectingEvent {
-                                tag_key: key,
-                                tag_value: value,
-                            });
-                            return None;
-                        }
-                    }
+            let mut to_delete = Vec::new();
+            for (key, value) in tags_map {
+                if self.try_accept_tag(key, Cow::Borrowed(value)) {
+                    continue;
                 }
-                LimitExceededAction::DropTag => {
-                    let mut to_delete = Vec::new();
-                    for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
-                            emit!(TagCardinalityLimitRejectingTag {
-                                tag_key: key,
-                                tag_value: value,
-                            });
-                            to_delete.push(key.clone());
-                        }
+                match self.limit_exceeded_action(key) {
+                    LimitExceededAction::DropEvent => {
+                        emit!(TagCardinalityLimitRejectingEvent {
+                            tag_key: key,
+                            tag_value: value,
+                        });
+                        return None;
                     }
-                    for key in to_delete {
-                        metric.remove_tag(&key);
+                    LimitExceededAction::DropTag => {
+                        emit!(TagCardinalityLimitRejectingTag {
+                            tag_key: key,
+                            tag_value: value,
+                        });
+                        to_delete.push(key.clone());
                     }
                 }
             }
+            for key in to_delete {
+                metric.remove_tag(&key);
+            }
         }
         Some(event)
     }
@@ -288,13 +440,45 @@ impl TagCardinalityLimit {
 impl TaskTransform<Event> for TagCardinalityLimit {
     fn transform(
         self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        // The interval is only polled when the accepted values are persisted.
+        let interval_secs = inner
+            .config
+            .persistence
+            .as_ref()
+            .map_or(1, |persistence| persistence.interval_secs.max(1));
+        let mut save_interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        Box::pin(stream! {
+            loop {
+                let mut output = None;
+                let done = tokio::select! {
+                    maybe_event = input_rx.next() => match maybe_event {
+                        Some(event) => {
+                            output = inner.transform_one(event);
+                            false
+                        }
+                        None => true,
+                    },
+                    _ = save_interval.tick(), if inner.store.is_some() => {
+                        inner.save().await;
+                        false
+                    }
+                };
+                if let Some(event) = output {
+                    yield event;
+                }
+                if done {
+                    inner.save().await;
+                    break;
+                }
+            }
+        })
     }
 }
 
@@ -332,6 +516,8 @@ mod tests {
             value_limit,
             limit_exceeded_action,
             mode: Mode::Exact,
+            per_tag: HashMap::new(),
+            persistence: None,
         })
     }
 
@@ -345,6 +531,8 @@ mod tests {
             mode: Mode::Probabilistic(BloomFilterConfig {
                 cache_size_per_key: default_cache_size(),
             }),
+            per_tag: HashMap::new(),
+            persistence: None,
         })
     }
 
@@ -477,4 +665,72 @@ mod tests {
         assert_eq!(new_event2, event2);
         assert_eq!(new_event3, event3);
     }
+
+    #[test]
+    fn tag_cardinality_limit_per_tag() {
+        let mut transform = TagCardinalityLimit::new(TagCardinalityLimitConfig {
+            value_limit: 1,
+            limit_exceeded_action: LimitExceededAction::DropTag,
+            mode: Mode::Exact,
+            per_tag: HashMap::from([
+                (
+                    "host".to_owned(),
+                    TagLimitConfig {
+                        value_limit: Some(2),
+                        limit_exceeded_action: Some(LimitExceededAction::DropEvent),
+                        allowed_values: vec!["localhost".to_owned()],
+                    },
+                ),
+                (
+                    "region".to_owned(),
+                    TagLimitConfig {
+                        allowed_values: vec!["global".to_owned()],
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            persistence: None,
+        });
+        let event = |host: &str, region: &str| {
+            make_metric(BTreeMap::from([
+                ("host".to_owned(), host.to_owned()),
+                ("region".to_owned(), region.to_owned()),
+            ]))
+        };
+
+        assert!(transform.transform_one(event("a", "eu")).is_some());
+        assert!(transform.transform_one(event("b", "eu")).is_some());
+
+        // The region exceeds the global limit, so it's dropped, and the allowed values are always accepted.
+        let dropped_tag = transform.transform_one(event("a", "us")).unwrap();
+        assert!(!dropped_tag
+            .as_metric()
+            .tags()
+            .unwrap()
+            .contains_key("region"));
+        let allowed = event("localhost", "global");
+        assert_eq!(transform.transform_one(allowed.clone()), Some(allowed));
+
+        // The host exceeds its own limit, so the event is dropped.
+        assert_eq!(transform.transform_one(event("c", "eu")), None);
+    }
+
+    #[test]
+    fn tag_cardinality_limit_restores_accepted_values() {
+        let mut transform = make_transform_hashset(2, LimitExceededAction::DropEvent);
+        transform.transform_one(make_metric(BTreeMap::from([(
+            "tag1".to_owned(),
+            "val1".to_owned(),
+        )])));
+        let accepted_values = transform.accepted_values();
+        assert!(transform.dirty);
+
+        // The first values accepted before the restart are still accepted, and count towards the limit.
+        let mut transform = make_transform_hashset(2, LimitExceededAction::DropEvent);
+        transform.restore(accepted_values);
+        let tags = |value: &str| BTreeMap::from([("tag1".to_owned(), value.to_owned())]);
+        assert!(transform.transform_one(make_metric(tags("val2"))).is_some());
+        assert!(transform.transform_one(make_metric(tags("val1"))).is_some());
+        assert!(transform.transform_one(make_metric(tags("val3"))).is_none());
+    }
 }
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use redis::{aio::ConnectionManager, AsyncCommands, RedisError};
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+
+use crate::config::TransformContext;
+
+/// The accepted values of each tag.
+pub(super) type AcceptedValues = HashMap<String, Vec<String>>;
+
+/// Configuration for persisting the accepted values of the tags across restarts.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    #[configurable(derived)]
+    #[serde(flatten)]
+    pub backend: PersistenceBackendConfig,
+
+    /// The interval between the saves of the accepted values, in seconds.
+    ///
+    /// The accepted values are also saved when Vector stops.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+const fn default_interval_secs() -> u64 {
+    10
+}
+
+/// Where the accepted values of the tags are persisted.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PersistenceBackendConfig {
+    /// In a file of the data directory.
+    Disk {
+        /// The directory used to persist the accepted values.
+        ///
+        /// By default, the global `data_dir` option is used. Make sure the running user has write permissions to
+        /// this directory.
+        data_dir: Option<PathBuf>,
+    },
+
+    /// In a Redis key, so that the accepted values can be shared with other instances.
+    Redis {
+        /// The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format.
+        url: String,
+
+        /// The Redis key holding the accepted values.
+        ///
+        /// By default, it's `vector:tag_cardinality_limit:<component_id>`.
+        key: Option<String>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum PersistenceError {
+    #[snafu(display("Failed to access {:?}: {}", path, source))]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Failed to access Redis: {}", source))]
+    Redis { source: RedisError },
+    #[snafu(display("Invalid persisted values: {}", source))]
+    Json { source: serde_json::Error },
+}
+
+impl PersistenceBackendConfig {
+    pub(super) async fn build(&self, context: &TransformContext) -> crate::Result<Store> {
+        let component_id = context
+            .key
+            .as_ref()
+            .map_or("tag_cardinality_limit", |key| key.id());
+        match self {
+            Self::Disk { data_dir } => {
+                let data_dir = context
+                    .globals
+                    .resolve_and_make_data_subdir(data_dir.as_ref(), component_id)?;
+                Ok(Store::Disk {
+                    path: data_dir.join("accepted_values.json"),
+                })
+            }
+            Self::Redis { url, key } => {
+                let client = redis::Client::open(url.as_str()).context(RedisSnafu)?;
+                let conn = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(RedisSnafu)?;
+                let key = key
+                    .clone()
+                    .unwrap_or_else(|| format!("vector:tag_cardinality_limit:{}", component_id));
+                Ok(Store::Redis { conn, key })
+            }
+        }
+    }
+}
+
+/// Saves and loads the accepted values of the tags, as a JSON object of the values of each tag.
+pub(super) enum Store {
+    Disk {
+        path: PathBuf,
+    },
+    Redis {
+        conn: ConnectionManager,
+        key: String,
+    },
+}
+
+impl Store {
+    pub(super) async fn load(&mut self) -> Result<AcceptedValues, PersistenceError> {
+        let json = match self {
+            Self::Disk { path } => match tokio::fs::read(&path).await {
+                Ok(json) => Some(json),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+                Err(source) => {
+                    return Err(PersistenceError::Io {
+                        path: path.clone(),
+                        source,
+                    })
+                }
+            },
+            Self::Redis { conn, key } => conn
+                .get::<_, Option<Vec<u8>>>(key.as_str())
+                .await
+                .context(RedisSnafu)?,
+        };
+
+        match json {
+            Some(json) => serde_json::from_slice(&json).context(JsonSnafu),
+            None => Ok(AcceptedValues::new()),
+        }
+    }
+
+    pub(super) async fn save(&mut self, values: &AcceptedValues) -> Result<(), PersistenceError> {
+        let json = serde_json::to_vec(values).context(JsonSnafu)?;
+        match self {
+            Self::Disk { path } => {
+                // The values are written to a temporary file first, so that they're never left half written.
+                let temporary = path.with_extension("json.tmp");
+                tokio::fs::write(&temporary, json)
+                    .await
+                    .context(IoSnafu { path: &temporary })?;
+                tokio::fs::rename(&temporary, &path)
+                    .await
+                    .context(IoSnafu { path: path.clone() })
+            }
+            Self::Redis { conn, key } => conn
+                .set::<_, _, ()>(key.as_str(), json)
+                .await
+                .context(RedisSnafu),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn saves_and_loads_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = Store::Disk {
+            path: dir.path().join("accepted_values.json"),
+        };
+        assert!(store.load().await.unwrap().is_empty());
+
+        let values = AcceptedValues::from([("host".to_owned(), vec!["a".to_owned()])]);
+        store.save(&values).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), values);
+    }
+}
@@ -0,0 +1,551 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use lru::LruCache;
+use snafu::Snafu;
+use vector_config::configurable_component;
+
+use crate::{
+    config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, Value},
+    internal_events::TailSamplingTraceDiscarded,
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+/// Configuration for the `tail_sampling` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct TailSamplingConfig {
+    /// How long the spans of a trace are buffered before deciding whether to keep it, in seconds.
+    ///
+    /// The window starts when the first span of the trace is received. Spans of a trace received after its
+    /// decision follow that decision.
+    #[serde(default = "default_decision_wait_secs")]
+    #[derivative(Default(value = "default_decision_wait_secs()"))]
+    pub decision_wait_secs: u64,
+
+    /// Whether to keep the traces with at least one span in error.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    pub keep_errors: bool,
+
+    /// The duration from which a span makes its trace kept, in milliseconds.
+    ///
+    /// By default, traces aren't kept for their latency.
+    pub latency_threshold_ms: Option<u64>,
+
+    /// The rate at which the traces not kept by any other rule are kept, expressed as `1/N`.
+    ///
+    /// The trace ID is hashed, so all instances configured with the same rate make the same decision for a trace.
+    /// By default, these traces are discarded.
+    pub rate: Option<u64>,
+
+    /// The maximum number of traces buffered at once.
+    ///
+    /// When a new trace would exceed it, the decision of the oldest trace is made early.
+    #[serde(default = "default_max_traces")]
+    #[derivative(Default(value = "default_max_traces()"))]
+    pub max_traces: usize,
+}
+
+const fn default_decision_wait_secs() -> u64 {
+    10
+}
+
+const fn default_max_traces() -> usize {
+    50_000
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`decision_wait_secs` must be greater than zero"))]
+    ZeroDecisionWait,
+    #[snafu(display("`rate` must be greater than zero"))]
+    ZeroRate,
+    #[snafu(display("`max_traces` must be greater than zero"))]
+    ZeroMaxTraces,
+}
+
+inventory::submit! {
+    TransformDescription::new::<TailSamplingConfig>("tail_sampling")
+}
+
+impl_generate_config_from_default!(TailSamplingConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "tail_sampling")]
+impl TransformConfig for TailSamplingConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::event_task(TailSampling::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Trace)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "tail_sampling"
+    }
+}
+
+/// The spans of a trace received so far, and what is known of them.
+#[derive(Debug)]
+struct PendingTrace {
+    events: Vec<Event>,
+    error: bool,
+    max_duration: Option<Duration>,
+}
+
+struct TailSampling {
+    decision_wait: Duration,
+    keep_errors: bool,
+    latency_threshold: Option<Duration>,
+    rate: Option<u64>,
+    max_traces: usize,
+
+    /// The traces waiting for their decision, by trace ID.
+    pending: HashMap<String, PendingTrace>,
+
+    /// The IDs of the pending traces, in the order their first span was received.
+    received: VecDeque<(Instant, String)>,
+
+    /// Whether the recently decided traces were kept, for their late spans.
+    decided: LruCache<String, bool>,
+}
+
+impl TailSampling {
+    fn new(config: &TailSamplingConfig) -> crate::Result<Self> {
+        if config.decision_wait_secs == 0 {
+            return Err(Box::new(BuildError::ZeroDecisionWait));
+        }
+        if config.rate == Some(0) {
+            return Err(Box::new(BuildError::ZeroRate));
+        }
+        if config.max_traces == 0 {
+            return Err(Box::new(BuildError::ZeroMaxTraces));
+        }
+        Ok(Self {
+            decision_wait: Duration::from_secs(config.decision_wait_secs),
+            keep_errors: config.keep_errors,
+            latency_threshold: config.latency_threshold_ms.map(Duration::from_millis),
+            rate: config.rate,
+            max_traces: config.max_traces,
+            pending: HashMap::new(),
+            received: VecDeque::new(),
+            decided: LruCache::new(config.max_traces),
+        })
+    }
+
+    fn record(&mut self, event: Event, now: Instant, output: &mut Vec<Event>) {
+        self.flush_expired_into(now, output);
+
+        let trace_id = match trace_id(&event) {
+            Some(trace_id) => trace_id,
+            // Spans that can't be grouped into a trace aren't sampled.
+            None => return output.push(event),
+        };
+
+        if let Some(keep) = self.decided.get(&trace_id).copied() {
+            if keep {
+                output.push(event);
+            } else {
+                emit!(TailSamplingTraceDiscarded { count: 1 });
+            }
+            return;
+        }
+
+        if !self.pending.contains_key(&trace_id) {
+            if self.pending.len() >= self.max_traces {
+                if let Some((_, oldest)) = self.received.pop_front() {
+                    self.decide_into(oldest, output);
+                }
+            }
+            self.received.push_back((now, trace_id.clone()));
+        }
+
+        let (error, max_duration) = summarize(&event);
+        let trace = self
+            .pending
+            .entry(trace_id)
+            .or_insert_with(|| PendingTrace {
+                events: Vec::new(),
+                error: false,
+                max_duration: None,
+            });
+        trace.error |= error;
+        trace.max_duration = trace.max_duration.max(max_duration);
+        trace.events.push(event);
+    }
+
+    /// Decides the traces whose decision window ended by `now`.
+    fn flush_expired_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        while let Some((received_at, _)) = self.received.front() {
+            if *received_at + self.decision_wait > now {
+                break;
+            }
+            let (_, trace_id) = self.received.pop_front().expect("front exists");
+            self.decide_into(trace_id, output);
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        while let Some((_, trace_id)) = self.received.pop_front() {
+            self.decide_into(trace_id, output);
+        }
+    }
+
+    /// Whether to keep a trace: when it has an error, when it's slow, or else when it's sampled.
+    fn keep(&self, trace_id: &str, trace: &PendingTrace) -> bool {
+        (self.keep_errors && trace.error)
+            || self
+                .latency_threshold
+                .map_or(false, |threshold| trace.max_duration >= Some(threshold))
+            || self
+                .rate
+                .map_or(false, |rate| seahash::hash(trace_id.as_bytes()) % rate == 0)
+    }
+
+    fn decide_into(&mut self, trace_id: String, output: &mut Vec<Event>) {
+        let trace = match self.pending.remove(&trace_id) {
+            Some(trace) => trace,
+            None => return,
+        };
+        let keep = self.keep(&trace_id, &trace);
+        if keep {
+            output.extend(trace.events);
+        } else {
+            emit!(TailSamplingTraceDiscarded {
+                count: trace.events.len()
+            });
+        }
+        self.decided.put(trace_id, keep);
+    }
+}
+
+/// The ID of the trace of an event, either its own or the one of its first span.
+fn trace_id(event: &Event) -> Option<String> {
+    let trace = event.as_trace();
+    trace
+        .get("trace_id")
+        .or_else(|| {
+            trace
+                .get("spans")
+                .and_then(Value::as_array)
+                .and_then(|spans| spans.first())
+                .and_then(Value::as_object)
+                .and_then(|span| span.get("trace_id"))
+        })
+        .map(Value::to_string_lossy)
+}
+
+/// Whether any span of an event is in error, and the longest duration of its spans.
+///
+/// An event either holds its spans in a `spans` array, as the events of the `datadog_agent` source, or is a span
+/// itself, as the events of the `opentelemetry` source.
+fn summarize(event: &Event) -> (bool, Option<Duration>) {
+    let trace = event.as_trace();
+    match trace.get("spans").and_then(Value::as_array) {
+        Some(spans) => spans.iter().filter_map(Value::as_object).fold(
+            (false, None),
+            |(error, max_duration), span| {
+                (
+                    error || span_error(span),
+                    max_duration.max(span_duration(span)),
+                )
+            },
+        ),
+        None => {
+            let span = trace.as_map();
+            (span_error(span), span_duration(span))
+        }
+    }
+}
+
+fn span_error(span: &BTreeMap<String, Value>) -> bool {
+    match span.get("error") {
+        Some(Value::Integer(error)) => *error != 0,
+        Some(Value::Boolean(error)) => *error,
+        _ => span
+            .get("status")
+            .and_then(Value::as_object)
+            .and_then(|status| status.get("code"))
+            .map_or(false, |code| code.to_string_lossy() == "error"),
+    }
+}
+
+fn span_duration(span: &BTreeMap<String, Value>) -> Option<Duration> {
+    match span.get("duration") {
+        Some(Value::Integer(nanos)) => u64::try_from(*nanos).ok().map(Duration::from_nanos),
+        _ => {
+            let start = span.get("start_timestamp").and_then(Value::as_timestamp)?;
+            let end = span.get("end_timestamp").and_then(Value::as_timestamp)?;
+            (*end - *start).to_std().ok()
+        }
+    }
+}
+
+impl TaskTransform<Event> for TailSampling {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(
+            stream! {
+                loop {
+                    let mut output = Vec::new();
+                    let done = tokio::select! {
+                        _ = flush_stream.tick() => {
+                            self.flush_expired_into(Instant::now(), &mut output);
+                            false
+                        }
+                        maybe_event = input_rx.next() => {
+                            match maybe_event {
+                                None => {
+                                    self.flush_all_into(&mut output);
+                                    true
+                                }
+                                Some(event) => {
+                                    self.record(event, Instant::now(), &mut output);
+                                    false
+                                }
+                            }
+                        }
+                    };
+                    yield stream::iter(output.into_iter());
+                    if done { break }
+                }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::event::TraceEvent;
+
+    fn tail_sampling(config: TailSamplingConfig) -> TailSampling {
+        TailSampling::new(&config).unwrap()
+    }
+
+    fn datadog_trace(trace_id: i64, spans: &[(i64, i64)]) -> Event {
+        let mut trace = TraceEvent::default();
+        trace.insert("trace_id", trace_id);
+        trace.insert(
+            "spans",
+            spans
+                .iter()
+                .map(|(error, duration)| {
+                    let mut span = BTreeMap::new();
+                    span.insert("trace_id".to_owned(), Value::from(trace_id));
+                    span.insert("error".to_owned(), Value::from(*error));
+                    span.insert("duration".to_owned(), Value::from(*duration));
+                    Value::from(span)
+                })
+                .collect::<Vec<_>>(),
+        );
+        Event::Trace(trace)
+    }
+
+    fn opentelemetry_span(trace_id: &str, status: &str, duration_ms: i64) -> Event {
+        let start = Utc.timestamp(1_600_000_000, 0);
+        let mut span = TraceEvent::default();
+        span.insert("trace_id", trace_id);
+        span.insert("start_timestamp", start);
+        span.insert(
+            "end_timestamp",
+            start + chrono::Duration::milliseconds(duration_ms),
+        );
+        span.insert("status.code", status);
+        Event::Trace(span)
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSamplingConfig>();
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        for config in [
+            TailSamplingConfig {
+                decision_wait_secs: 0,
+                ..Default::default()
+            },
+            TailSamplingConfig {
+                rate: Some(0),
+                ..Default::default()
+            },
+            TailSamplingConfig {
+                max_traces: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(TailSampling::new(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn keeps_whole_traces_with_errors() {
+        let mut sampler = tail_sampling(TailSamplingConfig::default());
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        sampler.record(datadog_trace(1, &[(0, 1_000)]), now, &mut output);
+        sampler.record(datadog_trace(1, &[(1, 1_000)]), now, &mut output);
+        sampler.record(datadog_trace(2, &[(0, 1_000)]), now, &mut output);
+        assert!(output.is_empty());
+
+        sampler.flush_expired_into(now + Duration::from_secs(9), &mut output);
+        assert!(output.is_empty());
+
+        sampler.flush_expired_into(now + Duration::from_secs(10), &mut output);
+        assert_eq!(output.len(), 2);
+        assert!(output
+            .iter()
+            .all(|event| trace_id(event) == Some("1".to_owned())));
+        assert!(sampler.pending.is_empty());
+    }
+
+    #[test]
+    fn keeps_slow_traces() {
+        let mut sampler = tail_sampling(TailSamplingConfig {
+            keep_errors: false,
+            latency_threshold_ms: Some(500),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        sampler.record(opentelemetry_span("a1", "ok", 100), now, &mut output);
+        sampler.record(opentelemetry_span("a1", "ok", 500), now, &mut output);
+        sampler.record(opentelemetry_span("b2", "error", 499), now, &mut output);
+        sampler.record(
+            datadog_trace(3, &[(0, 10_000_000), (0, 600_000_000)]),
+            now,
+            &mut output,
+        );
+        sampler.flush_all_into(&mut output);
+
+        let ids = output.iter().filter_map(trace_id).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["a1", "a1", "3"]);
+    }
+
+    #[test]
+    fn samples_other_traces_consistently() {
+        let config = TailSamplingConfig {
+            rate: Some(4),
+            ..Default::default()
+        };
+        let kept = |config: &TailSamplingConfig| {
+            let mut sampler = tail_sampling(config.clone());
+            let mut output = Vec::new();
+            for trace_id in 0..1000 {
+                sampler.record(
+                    datadog_trace(trace_id, &[(0, 1)]),
+                    Instant::now(),
+                    &mut output,
+                );
+            }
+            sampler.flush_all_into(&mut output);
+            output.iter().filter_map(trace_id).collect::<Vec<_>>()
+        };
+
+        let first = kept(&config);
+        assert!(first.len() > 150 && first.len() < 350, "{}", first.len());
+        assert_eq!(first, kept(&config));
+    }
+
+    #[test]
+    fn late_spans_follow_the_decision() {
+        let mut sampler = tail_sampling(TailSamplingConfig::default());
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        sampler.record(datadog_trace(1, &[(1, 1)]), now, &mut output);
+        sampler.record(datadog_trace(2, &[(0, 1)]), now, &mut output);
+        sampler.flush_expired_into(now + Duration::from_secs(10), &mut output);
+        assert_eq!(output.len(), 1);
+
+        let later = now + Duration::from_secs(11);
+        sampler.record(datadog_trace(1, &[(0, 1)]), later, &mut output);
+        sampler.record(datadog_trace(2, &[(1, 1)]), later, &mut output);
+        assert_eq!(output.len(), 2);
+        assert!(sampler.pending.is_empty());
+    }
+
+    #[test]
+    fn decides_oldest_trace_early_when_full() {
+        let mut sampler = tail_sampling(TailSamplingConfig {
+            max_traces: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        let mut output = Vec::new();
+        sampler.record(datadog_trace(1, &[(1, 1)]), now, &mut output);
+        sampler.record(datadog_trace(2, &[(1, 1)]), now, &mut output);
+        sampler.record(datadog_trace(2, &[(0, 1)]), now, &mut output);
+        assert!(output.is_empty());
+
+        sampler.record(datadog_trace(3, &[(1, 1)]), now, &mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(trace_id(&output[0]), Some("1".to_owned()));
+        assert_eq!(sampler.pending.len(), 2);
+    }
+
+    #[test]
+    fn forwards_spans_without_trace_id() {
+        let mut sampler = tail_sampling(TailSamplingConfig::default());
+
+        let mut output = Vec::new();
+        sampler.record(
+            Event::Trace(TraceEvent::default()),
+            Instant::now(),
+            &mut output,
+        );
+        assert_eq!(output.len(), 1);
+        assert!(sampler.pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn decides_buffered_traces_on_shutdown() {
+        let sampler = Transform::event_task(tail_sampling(TailSamplingConfig {
+            decision_wait_secs: 3600,
+            ..Default::default()
+        }))
+        .into_task();
+
+        let events = vec![
+            datadog_trace(1, &[(0, 1), (1, 1)]),
+            datadog_trace(2, &[(0, 1)]),
+        ];
+        let output = sampler
+            .transform_events(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(trace_id(&output[0]), Some("1".to_owned()));
+    }
+}
@@ -6,11 +6,14 @@ use governor::{clock, Quota, RateLimiter};
 use snafu::Snafu;
 use vector_config::configurable_component;
 
+mod redis;
+
+use self::redis::{RedisBackendConfig, RedisLimiter};
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
     event::Event,
-    internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
+    internal_events::{TemplateRenderingError, ThrottleBackendError, ThrottleEventDiscarded},
     schema,
     template::Template,
     transforms::{TaskTransform, Transform},
@@ -37,6 +40,26 @@ pub struct ThrottleConfig {
 
     /// A logical condition used to exclude events from sampling.
     exclude: Option<AnyCondition>,
+
+    #[configurable(derived)]
+    backend: BackendConfig,
+}
+
+/// Where the token buckets of the keys are kept.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackendConfig {
+    /// In memory, so each Vector instance rate limits its events separately.
+    #[derivative(Default)]
+    Local,
+
+    /// In Redis, so all Vector instances sharing the same Redis keys enforce a single rate limit.
+    ///
+    /// When Redis fails or doesn't reply in time, events are rate limited with the local token buckets of the
+    /// instance.
+    Redis(#[configurable(derived)] RedisBackendConfig),
 }
 
 inventory::submit! {
@@ -49,7 +72,16 @@ impl_generate_config_from_default!(ThrottleConfig);
 #[typetag::serde(name = "throttle")]
 impl TransformConfig for ThrottleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Throttle::new(self, context, clock::MonotonicClock).map(Transform::event_task)
+        let mut throttle = Throttle::new(self, context, clock::MonotonicClock)?;
+        if let BackendConfig::Redis(redis) = &self.backend {
+            let component_id = context.key.as_ref().map(|key| key.id());
+            throttle.redis = Some(
+                redis
+                    .build(component_id, self.threshold, self.window_secs)
+                    .await?,
+            );
+        }
+        Ok(Transform::event_task(throttle))
     }
 
     fn input(&self) -> Input {
@@ -71,6 +103,7 @@ pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
     flush_keys_interval: Duration,
     key_field: Option<Template>,
     exclude: Option<Condition>,
+    redis: Option<RedisLimiter>,
     clock: C,
 }
 
@@ -109,6 +142,7 @@ where
             flush_keys_interval,
             key_field: config.key_field.clone(),
             exclude,
+            redis: None,
         })
     }
 }
@@ -131,6 +165,8 @@ where
 
         let limiter = RateLimiter::dashmap_with_clock(self.quota, &self.clock);
 
+        let mut redis = self.redis.clone();
+
         Box::pin(
             stream! {
               loop {
@@ -162,17 +198,23 @@ where
                                                 .ok()
                                         });
 
-                                        match limiter.check_key(&key) {
-                                            Ok(()) => {
-                                                output.push(event);
-                                            }
-                                            _ => {
-                                                if let Some(key) = key {
-                                                  emit!(ThrottleEventDiscarded{key})
-                                                } else {
-                                                  emit!(ThrottleEventDiscarded{key: "None".to_string()})
+                                        let allowed = match redis.as_mut() {
+                                            Some(redis) => match redis.check_key(key.as_deref()).await {
+                                                Ok(allowed) => allowed,
+                                                Err(error) => {
+                                                    emit!(ThrottleBackendError { error });
+                                                    limiter.check_key(&key).is_ok()
                                                 }
-                                            }
+                                            },
+                                            None => limiter.check_key(&key).is_ok(),
+                                        };
+
+                                        if allowed {
+                                            output.push(event);
+                                        } else if let Some(key) = key {
+                                          emit!(ThrottleEventDiscarded{key})
+                                        } else {
+                                          emit!(ThrottleEventDiscarded{key: "None".to_string()})
                                         }
                                     } else {
                                         output.push(event)
@@ -218,6 +260,29 @@ mod tests {
         crate::test_util::test_generate_config::<ThrottleConfig>();
     }
 
+    #[test]
+    fn parses_backend() {
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+"#,
+        )
+        .unwrap();
+        assert!(matches!(config.backend, BackendConfig::Local));
+
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+backend.type = "redis"
+backend.url = "redis://127.0.0.1:6379/0"
+"#,
+        )
+        .unwrap();
+        assert!(matches!(config.backend, BackendConfig::Redis(_)));
+    }
+
     #[tokio::test]
     async fn throttle_events() {
         let clock = clock::FakeRelativeClock::default();
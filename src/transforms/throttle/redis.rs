@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use redis::{aio::ConnectionManager, RedisError, Script};
+use snafu::Snafu;
+use vector_config::configurable_component;
+
+/// A token bucket kept in a Redis hash, refilled continuously at `threshold` tokens per window.
+///
+/// The time is read from Redis, so that the instances sharing a bucket agree on it regardless of their clocks.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+redis.replicate_commands()
+local capacity = tonumber(ARGV[1])
+local window_us = tonumber(ARGV[2]) * 1000000
+local time = redis.call("TIME")
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local bucket = redis.call("HMGET", KEYS[1], "tokens", "updated")
+local tokens = tonumber(bucket[1]) or capacity
+local updated = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - updated) * capacity / window_us)
+local allowed = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+end
+redis.call("HSET", KEYS[1], "tokens", tostring(tokens), "updated", tostring(now))
+redis.call("PEXPIRE", KEYS[1], math.ceil(window_us / 500))
+return allowed
+"#;
+
+/// Configuration of the Redis backend of the `throttle` transform.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RedisBackendConfig {
+    /// The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format.
+    url: String,
+
+    /// The prefix of the Redis keys holding the token buckets.
+    ///
+    /// All instances sharing a rate limit must use the same prefix. By default, it's
+    /// `vector:throttle:<component_id>:`, so that instances running the same configuration share their rate limits.
+    key_prefix: Option<String>,
+
+    /// How long to wait for Redis before rate limiting an event with the local token buckets, in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+}
+
+const fn default_timeout_ms() -> u64 {
+    100
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to connect to Redis: {}", source))]
+    Connect { source: RedisError },
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum CheckError {
+    #[snafu(display("{}", source))]
+    Redis { source: RedisError },
+    #[snafu(display("Redis didn't reply in time"))]
+    Timeout,
+}
+
+impl RedisBackendConfig {
+    pub(super) async fn build(
+        &self,
+        component_id: Option<&str>,
+        threshold: u32,
+        window_secs: f64,
+    ) -> crate::Result<RedisLimiter> {
+        let client = redis::Client::open(self.url.as_str())
+            .map_err(|source| BuildError::Connect { source })?;
+        let conn = client
+            .get_tokio_connection_manager()
+            .await
+            .map_err(|source| BuildError::Connect { source })?;
+        let key_prefix = self
+            .key_prefix
+            .clone()
+            .unwrap_or_else(|| format!("vector:throttle:{}:", component_id.unwrap_or("throttle")));
+        Ok(RedisLimiter {
+            conn,
+            script: Script::new(TOKEN_BUCKET_SCRIPT),
+            key_prefix,
+            threshold,
+            window_secs,
+            timeout: Duration::from_millis(self.timeout_ms),
+        })
+    }
+}
+
+/// Rate limits the keys of events with token buckets shared through Redis.
+#[derive(Clone)]
+pub(super) struct RedisLimiter {
+    conn: ConnectionManager,
+    script: Script,
+    key_prefix: String,
+    threshold: u32,
+    window_secs: f64,
+    timeout: Duration,
+}
+
+impl RedisLimiter {
+    /// Takes a token from the bucket of a key, returning whether there was one.
+    pub(super) async fn check_key(&mut self, key: Option<&str>) -> Result<bool, CheckError> {
+        let invocation = self
+            .script
+            .key(format!("{}{}", self.key_prefix, key.unwrap_or_default()))
+            .arg(self.threshold)
+            .arg(self.window_secs)
+            .invoke_async(&mut self.conn);
+        match tokio::time::timeout(self.timeout, invocation).await {
+            Ok(result) => result.map_err(|source| CheckError::Redis { source }),
+            Err(_) => Err(CheckError::Timeout),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "redis-integration-tests"))]
+mod integration_tests {
+    use super::*;
+
+    const REDIS_SERVER: &str = "redis://redis:6379/0";
+
+    fn config(key_prefix: &str) -> RedisBackendConfig {
+        RedisBackendConfig {
+            url: REDIS_SERVER.to_owned(),
+            key_prefix: Some(key_prefix.to_owned()),
+            timeout_ms: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_buckets_across_limiters() {
+        let key_prefix = format!("throttle:{}:", crate::test_util::random_string(10));
+        let mut limiters = [
+            config(&key_prefix).build(None, 3, 60.0).await.unwrap(),
+            config(&key_prefix).build(None, 3, 60.0).await.unwrap(),
+        ];
+
+        let mut allowed = Vec::new();
+        for index in [0, 1, 0, 1] {
+            allowed.push(limiters[index].check_key(Some("a")).await.unwrap());
+        }
+        assert_eq!(allowed, vec![true, true, true, false]);
+
+        // Other keys have their own buckets.
+        assert!(limiters[1].check_key(Some("b")).await.unwrap());
+        assert!(limiters[0].check_key(None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn refills_buckets_over_the_window() {
+        let key_prefix = format!("throttle:{}:", crate::test_util::random_string(10));
+        let mut limiter = config(&key_prefix).build(None, 2, 1.0).await.unwrap();
+
+        assert!(limiter.check_key(Some("a")).await.unwrap());
+        assert!(limiter.check_key(Some("a")).await.unwrap());
+        assert!(!limiter.check_key(Some("a")).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(limiter.check_key(Some("a")).await.unwrap());
+        assert!(!limiter.check_key(Some("a")).await.unwrap());
+    }
+}
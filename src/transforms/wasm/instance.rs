@@ -0,0 +1,249 @@
+use std::path::Path;
+
+use snafu::Snafu;
+use wasmtime::{
+    Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder};
+
+/// The alignment of the strings exchanged with the modules.
+const STRING_ALIGN: i32 = 1;
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("Could not load WebAssembly module {:?}: {}", path, source))]
+    LoadModule {
+        path: std::path::PathBuf,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[snafu(display("Could not instantiate WebAssembly module: {}", source))]
+    Instantiate {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[snafu(display("WebAssembly module doesn't export `{}`: {}", export, source))]
+    MissingExport {
+        export: &'static str,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub enum ProcessError {
+    #[snafu(display("WebAssembly module trapped: {}", source))]
+    Trap { source: wasmtime::Trap },
+    #[snafu(display("WebAssembly module exhausted its fuel"))]
+    FuelExhausted,
+    #[snafu(display("WebAssembly module returned an out of bounds string"))]
+    OutOfBounds,
+    #[snafu(display("WebAssembly module returned an invalid result: {}", message))]
+    InvalidResult { message: String },
+    #[snafu(display("WebAssembly module failed to process event: {}", message))]
+    Guest { message: String },
+    #[snafu(display("{}", source))]
+    Instantiate { source: BuildError },
+}
+
+impl ProcessError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            ProcessError::Trap { .. } => "trap",
+            ProcessError::FuelExhausted => "fuel_exhausted",
+            ProcessError::OutOfBounds => "out_of_bounds",
+            ProcessError::InvalidResult { .. } => "invalid_result",
+            ProcessError::Guest { .. } => "guest_error",
+            ProcessError::Instantiate { .. } => "instantiate_failed",
+        }
+    }
+
+    /// Whether the state of the instance can't be trusted after the error, so that it must be replaced.
+    pub const fn poisons_instance(&self) -> bool {
+        matches!(
+            self,
+            ProcessError::Trap { .. } | ProcessError::FuelExhausted | ProcessError::OutOfBounds
+        )
+    }
+}
+
+/// The limits applied to the modules.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub fuel_per_event: u64,
+    pub max_memory_bytes: usize,
+}
+
+/// A compiled module, from which instances are created.
+pub struct WasmModule {
+    engine: Engine,
+    module: Module,
+    linker: Linker<State>,
+    limits: Limits,
+}
+
+struct State {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+}
+
+impl WasmModule {
+    pub fn from_file(path: &Path, limits: Limits) -> Result<Self, BuildError> {
+        let load_error = |source: anyhow::Error| BuildError::LoadModule {
+            path: path.into(),
+            source: source.into(),
+        };
+
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(load_error)?;
+        let module = Module::from_file(&engine, path).map_err(load_error)?;
+
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::add_to_linker(&mut linker, |state: &mut State| &mut state.wasi)
+            .map_err(load_error)?;
+
+        Ok(Self {
+            engine,
+            module,
+            linker,
+            limits,
+        })
+    }
+
+    /// Creates an instance of the module, with its own memory and WASI context.
+    pub fn instantiate(&self) -> Result<WasmInstance, BuildError> {
+        // The standard streams of the modules are discarded, except for their error stream, which is useful to debug
+        // them. They have no access to the file system, network, or environment.
+        let state = State {
+            wasi: WasiCtxBuilder::new().inherit_stderr().build(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.limits.max_memory_bytes)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+
+        let instance = self
+            .linker
+            .instantiate(&mut store, &self.module)
+            .map_err(|source| BuildError::Instantiate {
+                source: source.into(),
+            })?;
+        let memory =
+            instance
+                .get_memory(&mut store, "memory")
+                .ok_or_else(|| BuildError::MissingExport {
+                    export: "memory",
+                    source: "not a memory".into(),
+                })?;
+
+        Ok(WasmInstance {
+            realloc: typed_func(&instance, &mut store, "canonical_abi_realloc")?,
+            free: typed_func(&instance, &mut store, "canonical_abi_free")?,
+            process: typed_func(&instance, &mut store, "process")?,
+            store,
+            memory,
+            fuel_per_event: self.limits.fuel_per_event,
+        })
+    }
+}
+
+fn typed_func<Params, Results>(
+    instance: &Instance,
+    store: &mut Store<State>,
+    export: &'static str,
+) -> Result<TypedFunc<Params, Results>, BuildError>
+where
+    Params: wasmtime::WasmParams,
+    Results: wasmtime::WasmResults,
+{
+    instance
+        .get_typed_func(store, export)
+        .map_err(|source| BuildError::MissingExport {
+            export,
+            source: source.into(),
+        })
+}
+
+/// An instance of a module, implementing the `process: func(event: string) -> expected<string, string>` function of
+/// the interface of the transform, in its canonical ABI.
+pub struct WasmInstance {
+    store: Store<State>,
+    memory: Memory,
+    realloc: TypedFunc<(i32, i32, i32, i32), i32>,
+    free: TypedFunc<(i32, i32, i32), ()>,
+    process: TypedFunc<(i32, i32), i32>,
+    fuel_per_event: u64,
+}
+
+impl WasmInstance {
+    /// Passes an event, encoded as JSON, to the module, and returns the JSON array of events it produced.
+    ///
+    /// The instance must not be used anymore if this returns an error that poisons it.
+    pub fn process(&mut self, event: &[u8]) -> Result<Vec<u8>, ProcessError> {
+        self.refuel();
+
+        let len = i32::try_from(event.len()).map_err(|_| ProcessError::OutOfBounds)?;
+        let ptr = self.call(|instance| {
+            instance
+                .realloc
+                .call(&mut instance.store, (0, 0, STRING_ALIGN, len))
+        })?;
+        self.memory
+            .write(&mut self.store, ptr as usize, event)
+            .map_err(|_| ProcessError::OutOfBounds)?;
+
+        // The module owns the event from now on, and returns a pointer to the discriminant of the result, followed by
+        // the pointer and length of its string at the next word.
+        let result =
+            self.call(|instance| instance.process.call(&mut instance.store, (ptr, len)))?;
+        let mut area = [0; 12];
+        self.memory
+            .read(&self.store, result as usize, &mut area)
+            .map_err(|_| ProcessError::OutOfBounds)?;
+        let discriminant = area[0];
+        let ptr = i32::from_le_bytes(area[4..8].try_into().expect("slice of 4 bytes"));
+        let len = i32::from_le_bytes(area[8..12].try_into().expect("slice of 4 bytes"));
+
+        let mut string = vec![0; usize::try_from(len).map_err(|_| ProcessError::OutOfBounds)?];
+        self.memory
+            .read(&self.store, ptr as usize, &mut string)
+            .map_err(|_| ProcessError::OutOfBounds)?;
+        self.call(|instance| {
+            instance
+                .free
+                .call(&mut instance.store, (ptr, len, STRING_ALIGN))
+        })?;
+
+        match discriminant {
+            0 => Ok(string),
+            1 => Err(ProcessError::Guest {
+                message: String::from_utf8_lossy(&string).into_owned(),
+            }),
+            _ => Err(ProcessError::InvalidResult {
+                message: format!("unknown discriminant {}", discriminant),
+            }),
+        }
+    }
+
+    /// Tops the fuel of the instance up, so that each event gets the same budget.
+    fn refuel(&mut self) {
+        let remaining = self.store.consume_fuel(0).unwrap_or(0);
+        if remaining < self.fuel_per_event {
+            // Fuel consumption is enabled by the engine, so this can't fail.
+            let _ = self.store.add_fuel(self.fuel_per_event - remaining);
+        }
+    }
+
+    fn call<T>(
+        &mut self,
+        call: impl FnOnce(&mut Self) -> Result<T, wasmtime::Trap>,
+    ) -> Result<T, ProcessError> {
+        call(self).map_err(|trap| {
+            if self.store.consume_fuel(0).ok() == Some(0) {
+                ProcessError::FuelExhausted
+            } else {
+                ProcessError::Trap { source: trap }
+            }
+        })
+    }
+}
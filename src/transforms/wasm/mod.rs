@@ -0,0 +1,366 @@
+mod instance;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use vector_config::configurable_component;
+use vector_core::transform::runtime_transform::{RuntimeTransform, Timer};
+
+pub use self::instance::{BuildError, ProcessError};
+use self::instance::{Limits, WasmInstance, WasmModule};
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{WasmModuleReloadFailed, WasmModuleReloaded, WasmProcessingError},
+    schema,
+    transforms::Transform,
+};
+
+/// Configuration for the `wasm` transform.
+#[configurable_component(transform)]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WasmConfig {
+    /// The path to the WebAssembly module to run for each event.
+    ///
+    /// The module must target WASI and export the `process` function of the interface of the transform, in its
+    /// canonical ABI. If a relative path is provided, its root is the current working directory.
+    pub module: PathBuf,
+
+    /// The amount of fuel the module can consume to process an event.
+    ///
+    /// Each WebAssembly instruction consumes about one unit of fuel. The events for which the module runs out of fuel
+    /// are discarded.
+    #[serde(default = "default_fuel_per_event")]
+    pub fuel_per_event: u64,
+
+    /// The maximum size of the memory of the module, in bytes.
+    ///
+    /// The events for which the module fails to grow its memory beyond it are discarded.
+    #[serde(default = "default_max_memory_bytes")]
+    pub max_memory_bytes: usize,
+
+    /// Whether to reload the module when its file changes.
+    ///
+    /// The file is checked every second. If the new module fails to load, the previous one keeps running.
+    #[serde(default)]
+    pub hot_reload: bool,
+}
+
+const fn default_fuel_per_event() -> u64 {
+    10_000_000
+}
+
+const fn default_max_memory_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+/// How often the file of the module is checked for changes when it's hot reloaded.
+const RELOAD_INTERVAL_SECS: u64 = 1;
+
+inventory::submit! {
+    TransformDescription::new::<WasmConfig>("wasm")
+}
+
+impl GenerateConfig for WasmConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"module = "/etc/vector/transform.wasm""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "wasm")]
+impl TransformConfig for WasmConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::event_task(Wasm::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "wasm"
+    }
+}
+
+pub struct Wasm {
+    path: PathBuf,
+    limits: Limits,
+    hot_reload: bool,
+    modified: Option<SystemTime>,
+    module: WasmModule,
+    /// The instance processing the events, which is replaced when it can't be trusted anymore.
+    instance: Option<WasmInstance>,
+}
+
+impl Wasm {
+    pub fn new(config: &WasmConfig) -> Result<Self, BuildError> {
+        let limits = Limits {
+            fuel_per_event: config.fuel_per_event,
+            max_memory_bytes: config.max_memory_bytes,
+        };
+        let modified = modified(&config.module);
+        let module = WasmModule::from_file(&config.module, limits)?;
+        let instance = module.instantiate()?;
+
+        Ok(Self {
+            path: config.module.clone(),
+            limits,
+            hot_reload: config.hot_reload,
+            modified,
+            module,
+            instance: Some(instance),
+        })
+    }
+
+    fn process(&mut self, event: Event) -> Result<Vec<Event>, ProcessError> {
+        if self.instance.is_none() {
+            let instance = self
+                .module
+                .instantiate()
+                .map_err(|source| ProcessError::Instantiate { source })?;
+            self.instance = Some(instance);
+        }
+        let instance = self.instance.as_mut().expect("instance was just created");
+
+        let (value, metadata) = event.into_log().into_parts();
+        let input = serde_json::to_vec(&value).expect("values are serializable");
+        let output = instance.process(&input);
+        if matches!(&output, Err(error) if error.poisons_instance()) {
+            self.instance = None;
+        }
+        let output = output?;
+
+        let events =
+            serde_json::from_slice::<Vec<BTreeMap<String, Value>>>(&output).map_err(|error| {
+                ProcessError::InvalidResult {
+                    message: format!("expected a JSON array of objects: {}", error),
+                }
+            })?;
+        Ok(events
+            .into_iter()
+            .map(|fields| Event::from(LogEvent::from_map(fields, metadata.clone())))
+            .collect())
+    }
+
+    fn reload(&mut self) {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        match WasmModule::from_file(&self.path, self.limits)
+            .and_then(|module| module.instantiate().map(|instance| (module, instance)))
+        {
+            Ok((module, instance)) => {
+                self.module = module;
+                self.instance = Some(instance);
+                emit!(WasmModuleReloaded { path: &self.path });
+            }
+            Err(error) => emit!(WasmModuleReloadFailed { error }),
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl RuntimeTransform for Wasm {
+    fn hook_process<F>(&mut self, event: Event, mut emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        match self.process(event) {
+            Ok(events) => events.into_iter().for_each(&mut emit_fn),
+            Err(error) => emit!(WasmProcessingError { error }),
+        }
+    }
+
+    fn timer_handler<F>(&mut self, _timer: Timer, _emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.reload();
+    }
+
+    fn timers(&self) -> Vec<Timer> {
+        if self.hot_reload {
+            vec![Timer {
+                id: 0,
+                interval_seconds: RELOAD_INTERVAL_SECS,
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use futures::{stream, StreamExt};
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::{test_util::trace_init, transforms::TaskTransform};
+
+    /// A module wrapping each event in an array, or failing for the events with an `error` field. It allocates its
+    /// strings from the end of the previous one, which is enough for a few events.
+    const ECHO: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func $alloc (export "canonical_abi_realloc")
+            (param i32 i32 i32) (param $len i32) (result i32)
+            (global.get $next)
+            (global.set $next (i32.add (global.get $next) (local.get $len))))
+          (func (export "canonical_abi_free") (param i32 i32 i32))
+          (func (export "process") (param $ptr i32) (param $len i32) (result i32)
+            (local $out i32)
+            ;; `{"error"` starts the events to fail.
+            (if (i32.eq (i32.load8_u offset=2 (local.get $ptr)) (i32.const 101))
+              (then
+                (i32.store8 (i32.const 0) (i32.const 1))
+                (i32.store (i32.const 4) (local.get $ptr))
+                (i32.store (i32.const 8) (local.get $len))
+                (return (i32.const 0))))
+            (local.set $out (call $alloc (i32.const 0) (i32.const 0) (i32.const 1)
+              (i32.add (local.get $len) (i32.const 2))))
+            (i32.store8 (local.get $out) (i32.const 91))
+            (memory.copy (i32.add (local.get $out) (i32.const 1)) (local.get $ptr) (local.get $len))
+            (i32.store8 (i32.add (i32.add (local.get $out) (i32.const 1)) (local.get $len))
+              (i32.const 93))
+            (i32.store8 (i32.const 0) (i32.const 0))
+            (i32.store (i32.const 4) (local.get $out))
+            (i32.store (i32.const 8) (i32.add (local.get $len) (i32.const 2)))
+            (i32.const 0)))
+    "#;
+
+    /// A module looping forever, or growing its memory for the events with an `alloc` field.
+    const RUNAWAY: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "canonical_abi_realloc") (param i32 i32 i32 i32) (result i32)
+            (i32.const 1024))
+          (func (export "canonical_abi_free") (param i32 i32 i32))
+          (func (export "process") (param $ptr i32) (param i32) (result i32)
+            (if (i32.eq (i32.load8_u offset=2 (local.get $ptr)) (i32.const 97))
+              (then
+                (if (i32.eq (memory.grow (i32.const 1024)) (i32.const -1))
+                  (then unreachable))))
+            (loop $forever (br $forever))
+            (i32.const 0)))
+    "#;
+
+    fn module(source: &str) -> NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(".wat").tempfile().unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+        file
+    }
+
+    fn wasm(module: &Path) -> Wasm {
+        Wasm::new(&WasmConfig {
+            module: module.into(),
+            fuel_per_event: 100_000,
+            max_memory_bytes: 1024 * 1024,
+            hot_reload: false,
+        })
+        .unwrap()
+    }
+
+    fn log(field: &str, value: &str) -> Event {
+        Event::from(LogEvent::from(BTreeMap::from([(
+            field.to_owned(),
+            Value::from(value),
+        )])))
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WasmConfig>();
+    }
+
+    #[test]
+    fn processes_events() {
+        let module = module(ECHO);
+        let mut wasm = wasm(module.path());
+
+        let events = wasm.process(log("message", "foo")).unwrap();
+        assert_eq!(events, vec![log("message", "foo")]);
+
+        let error = wasm.process(log("error", "bar")).unwrap_err();
+        assert!(matches!(error, ProcessError::Guest { .. }));
+
+        // Errors of the module don't replace its instance.
+        assert!(wasm.instance.is_some());
+        let events = wasm.process(log("message", "baz")).unwrap();
+        assert_eq!(events, vec![log("message", "baz")]);
+    }
+
+    #[test]
+    fn limits_fuel_and_memory() {
+        let module = module(RUNAWAY);
+        let mut wasm = wasm(module.path());
+
+        let error = wasm.process(log("message", "foo")).unwrap_err();
+        assert!(matches!(error, ProcessError::FuelExhausted));
+        assert!(wasm.instance.is_none());
+
+        let error = wasm.process(log("alloc", "true")).unwrap_err();
+        assert!(matches!(error, ProcessError::Trap { .. }));
+        assert!(wasm.instance.is_none());
+    }
+
+    #[test]
+    fn reloads_modules() {
+        let module = module(RUNAWAY);
+        let mut wasm = wasm(module.path());
+
+        // Invalid modules are ignored.
+        fs::write(module.path(), "(module").unwrap();
+        wasm.modified = None;
+        wasm.reload();
+        assert!(wasm.process(log("message", "foo")).is_err());
+
+        fs::write(module.path(), ECHO).unwrap();
+        wasm.modified = None;
+        wasm.reload();
+        let events = wasm.process(log("message", "foo")).unwrap();
+        assert_eq!(events, vec![log("message", "foo")]);
+    }
+
+    #[tokio::test]
+    async fn runs_as_a_task() {
+        trace_init();
+
+        let module = module(ECHO);
+        let transform = Box::new(wasm(module.path()));
+        let events = vec![
+            log("message", "foo"),
+            log("error", "bar"),
+            log("message", "baz"),
+        ];
+
+        let output = transform
+            .transform(Box::pin(stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(output, vec![log("message", "foo"), log("message", "baz"),]);
+    }
+}
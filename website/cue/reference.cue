@@ -23,7 +23,7 @@ _values: {
 // * `gzip` - gzip compression applied
 #CompressionAlgorithm: "none" | "gzip" | "lz4" | "snappy" | "zstd"
 
-#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=9
+#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=22
 
 #Date: =~"^\\d{4}-\\d{2}-\\d{2}"
 
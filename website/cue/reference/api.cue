@@ -47,9 +47,156 @@ api: {
 				of the address set using the `bind` parameter.
 				"""
 		}
+		profiling: {
+			common:   false
+			required: false
+			type: bool: default: false
+			description: """
+				Whether CPU and heap profiles of Vector are served, in the pprof format,
+				by the `/debug/pprof/profile` and `/debug/pprof/heap` endpoints. They can
+				be fetched with `go tool pprof http://127.0.0.1:8686/debug/pprof/heap`.
+				Heap allocations are only sampled while this option is enabled. Profiles
+				reveal the internals of Vector, so the API shouldn't be exposed to
+				untrusted networks with this option enabled. Changes to this option only
+				take effect when Vector is restarted.
+				"""
+		}
+		readiness: {
+			common:   false
+			required: false
+			description: """
+				The criteria under which the `/health/ready` endpoint reports Vector
+				as ready, on top of the tasks of all of its components running. Changes
+				to this option only take effect when Vector is restarted.
+				"""
+			type: object: options: {
+				require_healthchecks: {
+					common:   true
+					required: false
+					type: bool: default: true
+					description: """
+						Whether sinks must have passed their healthcheck to be ready, rather
+						than having it pending, failed or timed out. Sinks whose healthcheck
+						is disabled are ready regardless.
+						"""
+				}
+				max_buffer_utilization: {
+					common:   true
+					required: false
+					type: float: {
+						default: null
+						examples: [0.9]
+					}
+					description: """
+						The ratio of the capacity of the buffer of a sink in use, between 0 and
+						1, past which the sink isn't ready. Unbounded buffers are ignored.
+						"""
+				}
+				max_source_idle_secs: {
+					common:   false
+					required: false
+					type: uint: {
+						default: null
+						examples: [300]
+						unit: "seconds"
+					}
+					description: """
+						The number of seconds without events past which a source isn't ready.
+						Sources that haven't received any events yet are ready regardless.
+						"""
+				}
+			}
+		}
+		reload_token: {
+			common:   false
+			required: false
+			type: string: {
+				default: null
+				examples: ["${VECTOR_RELOAD_TOKEN}"]
+			}
+			description: """
+				The token that must be presented as a bearer token to push configuration
+				to the `/reload` endpoint, to edit components through the `/components`
+				endpoints, or to pause, resume and throttle components with GraphQL
+				mutations. These are disabled unless a token is configured. Changes to this option only take effect when Vector is
+				restarted.
+				"""
+		}
 	}
 
 	endpoints: {
+		"/components/{kind}/{id}": {
+			PUT: {
+				description: """
+					Adds the component `id` of the given `kind` (`sources`,
+					`transforms`, or `sinks`) to the running configuration, or
+					replaces it, with the component configuration in the request
+					body, whose format is detected like for `/reload`. The
+					configuration is validated and the topology is reloaded with
+					it: only the component, and the components it's wired to, are
+					rebuilt, and the events buffered for a replaced sink are handed
+					over to the new one. Edits are applied on top of each other,
+					and are replaced by the configuration files on the next reload
+					from disk. Requires the same `Authorization` header as
+					`/reload`.
+					"""
+				responses: {
+					"202": {
+						description: "The edited configuration is valid, and is being applied."
+					}
+					"400": {
+						description: "The edited configuration is invalid. The `errors` key lists the reasons."
+					}
+					"401": {
+						description: "The reload token is missing or invalid."
+					}
+				}
+			}
+			DELETE: {
+				description: """
+					Removes the component `id` of the given `kind` from the running
+					configuration. The removal is rejected if other components
+					still use the component as an input.
+					"""
+				responses: {
+					"202": {
+						description: "The edited configuration is valid, and is being applied."
+					}
+					"400": {
+						description: "The edited configuration is invalid. The `errors` key lists the reasons."
+					}
+					"401": {
+						description: "The reload token is missing or invalid."
+					}
+					"404": {
+						description: "The component doesn't exist."
+					}
+				}
+			}
+		}
+		"/components/{kind}/{id}/inputs": {
+			PUT: {
+				description: """
+					Rewires the transform or sink `id` to the inputs in the request
+					body, a JSON array of component IDs, without restating the rest
+					of its configuration.
+					"""
+				responses: {
+					"202": {
+						description: "The edited configuration is valid, and is being applied."
+					}
+					"400": {
+						description: "The edited configuration is invalid. The `errors` key lists the reasons."
+					}
+					"401": {
+						description: "The reload token is missing or invalid."
+					}
+					"404": {
+						description: "The component doesn't exist."
+					}
+				}
+			}
+		}
 		"/graphql": {
 			POST: {
 				description: """
@@ -82,6 +229,109 @@ api: {
 				}
 			}
 		}
+		"/health/live": {
+			GET: {
+				description: """
+					Liveness endpoint, for Kubernetes liveness probes. Vector is live
+					while it's running and the tasks of all of its components are too.
+					The `stopped` key lists the components whose task has stopped.
+					"""
+				responses: {
+					"200": {
+						description: "Vector and all of its components are running."
+					}
+					"503": {
+						description: "Vector is shutting down, or a component has stopped."
+					}
+				}
+			}
+		}
+		"/health/ready": {
+			GET: {
+				description: """
+					Readiness endpoint, for Kubernetes readiness probes. Vector is ready
+					while it's running and all of its components meet the criteria set by
+					`readiness`. The `components` key reports, for each component,
+					whether it's ready and the reasons it isn't, along with the status of
+					the healthcheck of sinks, the ratio of their buffer in use, and the
+					number of seconds since sources last received events.
+					"""
+				responses: {
+					"200": {
+						description: "Vector is running and all of its components are ready."
+					}
+					"503": {
+						description: "Vector is shutting down, or a component isn't ready."
+					}
+				}
+			}
+		}
+		"/reload": {
+			POST: {
+				description: """
+					Validates the configuration in the request body and reloads
+					the topology with it, in the same way as on `SIGHUP`:
+					components whose configuration hasn't changed keep running.
+					Requires an `Authorization: Bearer` header with the token set
+					by `reload_token`. The body format is detected from the
+					`Content-Type` header (`application/json`, `application/yaml`,
+					or TOML otherwise). With the `partial=true` query parameter,
+					the configuration is layered on top of the configuration files
+					Vector was started with, rather than replacing them. Pushed
+					configuration can't use providers or secrets, and is replaced
+					by the configuration files on the next reload from disk.
+					"""
+				responses: {
+					"202": {
+						description: "The configuration is valid, and is being applied."
+					}
+					"400": {
+						description: "The configuration is invalid. The `errors` key lists the reasons."
+					}
+					"401": {
+						description: "The reload token is missing or invalid."
+					}
+				}
+			}
+		}
+		"/debug/pprof/profile": {
+			GET: {
+				description: """
+					Samples the stacks of all the threads of Vector for `seconds`
+					(30 by default, at most 300) at a `frequency` per second (99 by
+					default), and returns them as a gzipped pprof CPU profile. Only
+					served when `profiling` is enabled.
+					"""
+				responses: {
+					"200": {
+						description: "The CPU profile."
+					}
+					"400": {
+						description: "The query parameters are invalid."
+					}
+					"409": {
+						description: "Another CPU profile is already being taken."
+					}
+				}
+			}
+		}
+		"/debug/pprof/heap": {
+			GET: {
+				description: """
+					Returns the sampled heap allocations that are still live as a
+					gzipped pprof heap profile. Only served when `profiling` is
+					enabled.
+					"""
+				responses: {
+					"200": {
+						description: "The heap profile."
+					}
+					"500": {
+						description: "The heap profile couldn't be taken, for instance as Vector wasn't built with jemalloc."
+					}
+				}
+			}
+		}
 		"/playground": {
 			GET: {
 				description: """
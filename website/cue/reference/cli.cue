@@ -179,6 +179,65 @@ cli: {
 
 			options: _core_options
 		}
+		"config": {
+			description: """
+				Output a provided Vector configuration file/dir as a single JSON object, useful for
+				checking in to version control. Use `vector config render` to output the configuration
+				after resolving includes and overlays instead, in TOML, JSON, or YAML
+				"""
+
+			example: "vector config render --config prod.toml --format yaml"
+
+			flags: _default_flags & {
+				"pretty": {
+					_short:      "p"
+					description: "Pretty print JSON"
+				}
+				"include-defaults": {
+					_short:      "i"
+					description: "Include default values where missing from config"
+				}
+			}
+
+			options: _core_config_options
+		}
+
+		"convert": {
+			description: """
+				Convert a fluentd, Logstash or Filebeat configuration into a Vector
+				configuration. What can't be converted exactly is explained in
+				notes, rendered as comments in the converted configuration
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"from": {
+					description: "The log shipper the configuration is written for, detected from the configuration when omitted"
+					type:        "enum"
+					enum: {
+						fluentd:  "Convert a fluentd configuration"
+						logstash: "Convert a Logstash pipeline configuration"
+						filebeat: "Convert a Filebeat configuration"
+					}
+				}
+				"output": {
+					_short:      "o"
+					description: "Write the converted configuration to a file, rather than to the standard output"
+					type:        "string"
+					example:     "/etc/vector/vector.toml"
+				}
+			}
+
+			args: {
+				input: {
+					description: "The configuration file to convert"
+					type:        "string"
+					required:    true
+				}
+			}
+		}
+
 		"generate": {
 			description: "Generate a Vector configuration containing a list of components"
 
@@ -227,6 +286,44 @@ cli: {
 			}
 		}
 
+		"reload": {
+			description: """
+				Push a configuration to a local or remote Vector instance. The
+				instance validates the configuration and reloads its topology in
+				the same way as on `SIGHUP`, reusing unchanged components
+				"""
+
+			flags: _default_flags & {
+				"partial": {
+					description: """
+						Layer the pushed configuration on top of the configuration
+						files the Vector instance was started with, rather than
+						replacing them
+						"""
+				}
+			}
+
+			options: {
+				"remote": {
+					description: """
+						The URL of the Vector API server to push the configuration to.
+						Defaults to the local API address
+						"""
+					type: "string"
+				}
+				"token": {
+					description: "The token configured as `api.reload_token` on the Vector instance"
+					type:        "string"
+					env_var:     "VECTOR_RELOAD_TOKEN"
+				}
+				"config": {
+					_short:      "c"
+					description: "The Vector config file to push. The format is detected from the file name"
+					type:        "string"
+				}
+			}
+		}
+
 		"test": {
 			description: """
 				Run Vector config unit tests, then exit. This command is experimental and
@@ -321,6 +418,16 @@ cli: {
 					description: "Components (sources, transforms) to observe for their inputs (comma-separated; accepts glob patterns)"
 					type:        "list"
 				}
+				"filter": {
+					description: "[VRL boolean expression](\(urls.vrl_boolean_expression)) events must match to be observed, such as `.tenant == \"acme\"`"
+					type:        "string"
+				}
+				"sample-rate": {
+					_short:      "s"
+					description: "Observe only one out of every `sample-rate` events, after filtering"
+					type:        "integer"
+					default:     1
+				}
 			}
 
 			args: {
@@ -341,7 +448,8 @@ cli: {
 		"top": {
 			description: """
 				Display topology and metrics in the console, for a local or remote Vector
-				instance
+				instance. Selecting a component with the up and down arrow keys shows
+				its buffer usage, retry backoff and errors by type.
 				"""
 
 			flags: _default_flags & {
@@ -349,6 +349,7 @@ components: {
 				default: #CompressionAlgorithm
 				algorithms: [#CompressionAlgorithm, ...#CompressionAlgorithm]
 				levels: [#CompressionLevel, ...#CompressionLevel]
+				zstd_long_window: bool | *false
 			}
 		}
 
@@ -591,6 +592,99 @@ components: {
 				}
 			}
 
+			_source_checkpoint_store: {
+				common:      false
+				description: "Where to persist the checkpoint of the source. By default, it's persisted in a file of the data directory. Storing it in Redis or S3 lets an instance replacing another one with the same configuration, such as on ephemeral hosts, resume from where it left off."
+				required:    false
+				type: object: {
+					examples: []
+					options: {
+						type: {
+							common:      true
+							description: "The type of checkpoint store."
+							required:    false
+							type: string: {
+								default: "file"
+								enum: {
+									file:  "A file in the data directory."
+									redis: "A key in Redis, which outlives the host running Vector."
+									s3:    "An object in S3, which outlives the host running Vector."
+								}
+							}
+						}
+						data_dir: {
+							common:        false
+							description:   "The directory used to persist the checkpoint. By default, the `data_dir` option of the source, or else the global `data_dir` option, is used."
+							required:      false
+							relevant_when: "type = \"file\""
+							type: string: {
+								default: null
+								examples: ["/var/local/lib/vector/"]
+							}
+						}
+						url: {
+							description:   "The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format."
+							required:      true
+							relevant_when: "type = \"redis\""
+							type: string: {
+								examples: ["redis://127.0.0.1:6379/0"]
+							}
+						}
+						bucket: {
+							description:   "The name of the bucket to store the checkpoint in."
+							required:      true
+							relevant_when: "type = \"s3\""
+							type: string: {
+								examples: ["my-vector-bucket"]
+							}
+						}
+						key_prefix: {
+							common:        false
+							description:   "The prefix of the key of the checkpoint, which is followed by the name of the checkpoint. Defaults to `vector:checkpoints:<component ID>:` in Redis, and `vector/checkpoints/<component ID>/` in S3."
+							required:      false
+							relevant_when: "type = \"redis\" or type = \"s3\""
+							type: string: {
+								default: null
+								examples: ["fargate/checkpoints/"]
+							}
+						}
+						key: {
+							common:        false
+							description:   "The key of the checkpoint, overriding `key_prefix`."
+							required:      false
+							relevant_when: "type = \"redis\" or type = \"s3\""
+							type: string: {
+								default: null
+								examples: ["checkpoints/app.json"]
+							}
+						}
+						region: {
+							common:        false
+							description:   "The AWS region of the bucket."
+							required:      false
+							relevant_when: "type = \"s3\""
+							type: string: {
+								default: null
+								examples: ["us-east-1"]
+							}
+						}
+						endpoint: {
+							common:        false
+							description:   "The API endpoint of S3, such as the one of an S3-compatible service."
+							required:      false
+							relevant_when: "type = \"s3\""
+							type: string: {
+								default: null
+								examples: ["http://127.0.0.0:9000"]
+							}
+						}
+						auth: components._aws.configuration.auth & {
+							relevant_when: "type = \"s3\""
+						}
+					}
+				}
+			}
+
 			_tls_accept: {
 				_args: {
 					can_verify_certificate: bool | *true
@@ -920,6 +1014,92 @@ components: {
 				}
 			}
 
+			if Kind == "sink" {
+				dead_letter: {
+					common: false
+					description: """
+						The ID of a transform or sink that events this sink fails to deliver are routed to,
+						rather than being dropped. Events are routed once they are rejected by the downstream
+						service, such as with a 4xx response or a partial bulk failure, or once retries are
+						exhausted.
+
+						Routed log and trace events have a `dead_letter` field describing the failure, with
+						the `component_id`, `component_type`, `reason` (`errored`, `rejected` or
+						`circuit_open`) and `timestamp` of the failure. Routed metrics have
+						`dead_letter_component_id`, `dead_letter_component_type` and `dead_letter_reason` tags
+						instead.
+
+						Events routed to the dead letter component are only acknowledged to their sources
+						once that component delivers them.
+						"""
+					required: false
+					type: string: {
+						default: null
+						examples: ["my-dead-letter-sink"]
+					}
+				}
+
+				circuit_breaker: {
+					common: false
+					description: """
+						Stops handing events to this sink once it failed to deliver a number of consecutive
+						batches of events, whether rejected by the downstream service or errored past its
+						retries, falling back to pausing the sink or to routing its events to its
+						`dead_letter` component. Once open, the circuit breaker hands the sink a single
+						batch of events every `probe_interval_secs` seconds, and closes again once one of
+						these probes is delivered.
+						"""
+					required: false
+					type: object: options: {
+						failure_threshold: {
+							common:      false
+							description: "The number of consecutive batches of events the sink failed to deliver after which the circuit breaker opens."
+							required:    false
+							type: uint: {
+								default: 5
+								unit:    null
+							}
+						}
+						probe_interval_secs: {
+							common:      false
+							description: "How long the circuit breaker stays open before handing the sink a batch of events to probe whether it recovered."
+							required:    false
+							type: uint: {
+								default: 30
+								unit:    "seconds"
+							}
+						}
+						fallback: {
+							common:      false
+							description: "What happens to the events while the circuit breaker is open."
+							required:    false
+							type: string: {
+								default: "pause"
+								enum: {
+									pause:       "The events are held back, applying backpressure to the components sending them."
+									dead_letter: "The events are routed to the `dead_letter` component of the sink, with the `circuit_open` reason. Requires the `dead_letter` option."
+								}
+							}
+						}
+					}
+				}
+			}
+
+			shutdown_timeout_secs: {
+				common: false
+				description: """
+					How long the component is given to finish when Vector shuts down, after which it's killed,
+					overriding the `\(Kind)s_timeout_secs` option of the global [`shutdown`](\(urls.vector_configuration)/global-options#shutdown)
+					options.
+					"""
+				required: false
+				type: uint: {
+					default: null
+					examples: [300]
+					unit: "seconds"
+				}
+			}
+
 			"type": {
 				description: "The component type. This is a required field for all components and tells Vector which component to use."
 				required:    true
@@ -74,6 +74,55 @@ components: sinks: [Name=string]: {
 			type: object: {
 				examples: []
 				options: {
+					compression: {
+						common: false
+						description: """
+							The compression algorithm used for the buffered events. Events are compressed in
+							batches as they are written to disk, and batches that don't get any smaller are
+							written uncompressed. Changing the algorithm doesn't affect events already in the
+							buffer, which can always be read back.
+							"""
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: string: {
+							default: "none"
+							enum: {
+								none: "Events are written uncompressed."
+								lz4:  "[LZ4](\(urls.lz4)) compression, which is very fast, but compresses less."
+								zstd: "[Zstandard](\(urls.zstd)) compression, which compresses better, but uses more CPU."
+							}
+						}
+					}
+					encryption: {
+						common:        false
+						description:   "Configures encryption at rest of the buffered events, using [AES-256-GCM](\(urls.aes_gcm))."
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: object: {
+							examples: []
+							options: {
+								keys: {
+									description: """
+										The hex-encoded 256-bit keys used to encrypt the buffered events. Keys can be
+										supplied through [secrets](\(urls.vector_configuration)/#secrets-management) or environment variables
+										rather than in plain text.
+
+										The first key is the active key: every time the buffer rolls over to a new data
+										file, a new data file key is derived from it. The other keys are only used to
+										decrypt events written before the active key was rotated, and can be removed once
+										those events have been read from the buffer.
+										"""
+									required: true
+									type: array: {
+										items: type: string: {
+											examples: ["SECRET[backend.buffer_key]", "${VECTOR_BUFFER_KEY}"]
+											syntax: "literal"
+										}
+									}
+								}
+							}
+						}
+					}
 					max_events: {
 						common:        true
 						description:   "The maximum number of [events](\(urls.vector_data_model)) allowed in the buffer."
@@ -98,6 +147,26 @@ components: sinks: [Name=string]: {
 							unit: "bytes"
 						}
 					}
+					partition_by: {
+						common: false
+						description: """
+							A template of the key the buffered events are partitioned by, such as a tenant or a
+							topic. Each partition is a disk buffer of its own, with its own `max_size`, kept in
+							the `partitions/<sink ID>/<partition>` subdirectory of the data directory. Partitions
+							are read in turn, so the backlog of a slow partition doesn't delay the events of the
+							other partitions.
+
+							Events whose key can't be rendered go to the `_default` partition. Partitioning is
+							only supported by buffers with a single stage.
+							"""
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: string: {
+							default: null
+							examples: ["{{ tenant }}", "{{ topic }}"]
+							syntax: "template"
+						}
+					}
 					type: {
 						common:      true
 						description: "The buffer's type and storage mechanism."
@@ -116,10 +185,27 @@ components: sinks: [Name=string]: {
 							}
 						}
 					}
+					use_io_uring: {
+						common: false
+						description: """
+							Write the buffer's data files through an io_uring, on Linux. Writes are copied into
+							buffers registered with the kernel, which are written while the next ones are filled,
+							reducing the system calls made by busy disk buffers. If io_uring is not supported by the
+							kernel, or can't be set up, such as when the limit of locked memory is too low, data
+							files are written as usual.
+							"""
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: bool: default: false
+					}
 					when_full: {
 						common:      false
-						description: "The behavior when the buffer becomes full."
-						required:    false
+						description: """
+							The behavior when the buffer becomes full. When dropping data, lower [priority](\(urls.vector_priority_transform))
+							events are dropped first: low priority events are dropped as soon as the buffer is half full,
+							and high priority events are never dropped, applying back pressure instead.
+							"""
+						required: false
 						type: string: {
 							default: "block"
 							enum: {
@@ -159,9 +245,17 @@ components: sinks: [Name=string]: {
 								if algo == "lz4" {
 									lz4: "[lz4](\(urls.lz4)) compression."
 								}
-								if algo == "zstd" {
+								if algo == "zstd" && !features.send.compression.zstd_long_window {
 									zstd: "[zstd](\(urls.zstd)) compression."
 								}
+								if algo == "zstd" && features.send.compression.zstd_long_window {
+									zstd: """
+										[zstd](\(urls.zstd)) compression. Levels range from 1 to 22, defaulting to 3. Use the
+										`{ algorithm = "zstd", level = 19, long_window = 27 }` form to enable long distance
+										matching with a window of `2^long_window` bytes; decompressors need a matching window
+										limit (for example `zstd --long=27`) for windows over 2^27 bytes.
+										"""
+								}
 							}
 						}
 					}
@@ -218,7 +312,22 @@ components: sinks: [Name=string]: {
 												ndjson: "Newline delimited list of JSON encoded events."
 											}
 											if codec == "avro" {
-												avro: "Avro encoded event with a given schema."
+												avro: "Avro encoded event with a given schema, read inline or from `schema_file`, optionally prefixed with the Confluent Schema Registry header when `schema_id` is set."
+											}
+											if codec == "bson" {
+												bson: "[BSON](\(urls.bson)) encoded document, with timestamps encoded as date/times and single field [extended JSON](\(urls.mongodb_extended_json)) objects, such as `{\"$oid\": \"...\"}`, encoded as the BSON type they represent."
+											}
+											if codec == "cbor" {
+												cbor: "[CBOR](\(urls.cbor)) encoded event, with timestamps encoded as tagged date/time strings."
+											}
+											if codec == "cef" {
+												cef: "[ArcSight Common Event Format](\(urls.cef)) encoded event."
+											}
+											if codec == "leef" {
+												leef: "IBM QRadar [Log Event Extended Format](\(urls.leef)) 1.0 or 2.0 encoded event."
+											}
+											if codec == "protobuf" {
+												protobuf: "[Protocol Buffers](\(urls.protobuf)) encoded event, serialized into the `message_type` message of the `desc_file` descriptor set."
 											}
 										}
 									}
@@ -362,6 +471,15 @@ components: sinks: [Name=string]: {
 									unit:    "seconds"
 								}
 							}
+							rate_limit_group: {
+								common:      false
+								description: "The name of a [rate limit group](\(urls.vector_configuration)#rate_limit_groups) whose budget the sink shares with the other sinks referencing it."
+								required:    false
+								type: string: {
+									default: null
+									examples: ["elasticsearch_cluster"]
+								}
+							}
 							rate_limit_num: {
 								common:      true
 								description: "The maximum number of requests allowed within the `rate_limit_duration_secs` time window."
@@ -631,6 +749,27 @@ components: sinks: [Name=string]: {
 								These will apply to both `adaptive` and fixed `request.concurrency` values.
 								"""
 						},
+						{
+							title: "Rate limit groups"
+							body: """
+								Rate limits apply to each sink separately, so splitting traffic between several sinks
+								multiplies them. Sinks sending to the same service can instead share a budget, defined
+								in the global `rate_limit_groups` option, by referencing it with the
+								`request.rate_limit_group` option:
+
+								```toml title="vector.toml"
+								[rate_limit_groups.cluster]
+								  max_requests_per_sec = 100
+								  max_bytes_per_sec = 10000000
+
+								[sinks.my-sink]
+								  request.rate_limit_group = "cluster"
+
+								[sinks.my-other-sink]
+								  request.rate_limit_group = "cluster"
+								```
+								"""
+						},
 					]
 				}
 
@@ -56,6 +56,48 @@ components: sinks: datadog_events: {
 		}
 		endpoint: sinks._datadog.configuration.endpoint
 		site:     sinks._datadog.configuration.site
+		title: {
+			common:      true
+			description: "The event title. When set, this overrides any `title` field already present on the event."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["Deployment of {{ service }}"]
+				syntax: "template"
+			}
+		}
+		text: {
+			common:      true
+			description: "The event body. When unset, the `text` field of the event is used, falling back to the message."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ message }}"]
+				syntax: "template"
+			}
+		}
+		tags: {
+			common:      false
+			description: "Tags attached to the event, each rendered as a `key:value` string."
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["service:{{ service }}", "env:prod"]
+					syntax: "template"
+				}
+			}
+		}
+		alert_type: {
+			common:      false
+			description: "The alert type of the event. The rendered value must be one of `error`, `warning`, `info`, or `success`; other values are ignored."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["error", "{{ level }}"]
+				syntax: "template"
+			}
+		}
 	}
 
 	input: {
@@ -25,15 +25,16 @@ components: sinks: http: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+				zstd_long_window: true
 			}
 			encoding: {
 				enabled: true
 				codec: {
 					enabled: true
 					batched: true
-					enum: ["json", "ndjson", "text"]
+					enum: ["cbor", "json", "ndjson", "protobuf", "text"]
 				}
 			}
 			proxy: enabled: true
@@ -99,6 +100,33 @@ components: sinks: http: {
 				examples: ["https://10.22.212.22:9000/health"]
 			}
 		}
+		encoding: type: object: options: protobuf: {
+			common:        false
+			description:   "Options for the `protobuf` codec."
+			required:      false
+			relevant_when: "codec = \"protobuf\""
+			type: object: options: {
+				desc_file: {
+					description: "The path of the compiled descriptor set containing the message type, as produced by `protoc --include_imports --descriptor_set_out`."
+					required:    true
+					type: string: examples: ["/etc/vector/protos/events.desc"]
+				}
+				message_type: {
+					description: "The fully qualified name of the message type the events are serialized into."
+					required:    true
+					type: string: examples: ["mypackage.MyMessage"]
+				}
+				field_mappings: {
+					common:      false
+					description: "Maps the fields of the message, by path, to the fields of the events they are read from. The message fields that aren't mapped are read from the event fields of the same name."
+					required:    false
+					type: object: {
+						examples: [{"resource.service_name": "kubernetes.pod_labels.app"}]
+						options: {}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -66,6 +66,26 @@ components: sinks: kafka: {
 				unit: null
 			}
 		}
+		request: {
+			common:      false
+			description: "Configures the concurrency of the messages sent. librdkafka retries and times out messages itself, according to `message_timeout_ms`."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					adaptive_concurrency: components.sinks.http.configuration.request.type.object.options.adaptive_concurrency
+					concurrency: {
+						common:      true
+						description: "The maximum number of messages in flight at any given time, or \"adaptive\" to allow Vector to automatically set the limit based on the delivery latency of messages. Messages librdkafka couldn't queue or deliver in time are treated as back pressure."
+						required:    false
+						type: uint: {
+							default: 100000
+							unit:    "requests"
+						}
+					}
+				}
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -160,5 +180,7 @@ components: sinks: kafka: {
 		kafka_produced_messages_bytes_total: components.sources.internal_metrics.output.metrics.kafka_produced_messages_bytes_total
 		kafka_consumed_messages_total:       components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
 		kafka_consumed_messages_bytes_total: components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
+		adaptive_concurrency_limit:          components.sources.internal_metrics.output.metrics.adaptive_concurrency_limit
+		adaptive_concurrency_in_flight:      components.sources.internal_metrics.output.metrics.adaptive_concurrency_in_flight
 	}
 }
@@ -0,0 +1,203 @@
+package metadata
+
+components: sinks: pagerduty: {
+	title: "PagerDuty"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["PagerDuty"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: false
+		send: {
+			batch: enabled:       false
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.pagerduty
+
+				interface: {
+					socket: {
+						api: {
+							title: "PagerDuty Events API v2"
+							url:   urls.pagerduty_events_v2
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		routing_key: {
+			description: "The integration key of the PagerDuty service that events are routed to."
+			required:    true
+			type: string: {
+				examples: ["${PAGERDUTY_ROUTING_KEY}"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The Events API v2 endpoint to send events to."
+			required:    false
+			type: string: {
+				default: "https://events.pagerduty.com/v2/enqueue"
+			}
+		}
+		event_action: {
+			common:      true
+			description: "The action to take on the alert. Must render to `trigger`, `acknowledge`, or `resolve`; events with any other value are dropped."
+			required:    false
+			type: string: {
+				default: "trigger"
+				examples: ["{{ action }}"]
+				syntax: "template"
+			}
+		}
+		dedup_key: {
+			common:      true
+			description: "The deduplication key used to correlate triggers and resolves of the same alert. Required for `acknowledge` and `resolve` actions."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ host }}-{{ check }}"]
+				syntax: "template"
+			}
+		}
+		summary: {
+			common:      true
+			description: "A brief text summary of the alert."
+			required:    false
+			type: string: {
+				default: "{{ message }}"
+				syntax:  "template"
+			}
+		}
+		source: {
+			common:      false
+			description: "The unique location of the affected system. Defaults to the host field of the event, or the local hostname."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ host }}"]
+				syntax: "template"
+			}
+		}
+		severity: {
+			common:      true
+			description: "The severity of the alert. The rendered value is looked up in `severity_map` first, then matched against the PagerDuty severities. Unknown values fall back to `default_severity`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ level }}"]
+				syntax: "template"
+			}
+		}
+		severity_map: {
+			common:      false
+			description: "Maps rendered `severity` values onto PagerDuty severities."
+			required:    false
+			type: object: {
+				examples: [{"ERR": "error", "CRIT": "critical"}]
+				options: {}
+			}
+		}
+		default_severity: {
+			common:      false
+			description: "The severity used when `severity` is unset or doesn't match a known severity."
+			required:    false
+			type: string: {
+				default: "error"
+				enum: {
+					critical: "Critical severity."
+					error:    "Error severity."
+					warning:  "Warning severity."
+					info:     "Info severity."
+				}
+			}
+		}
+		component: {
+			common:      false
+			description: "The component of the source machine that is responsible for the event."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ service }}"]
+				syntax: "template"
+			}
+		}
+		group: {
+			common:      false
+			description: "A logical grouping of components of a service."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ cluster }}"]
+				syntax: "template"
+			}
+		}
+		class: {
+			common:      false
+			description: "The class or type of the event."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["deploy"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		event_payload: {
+			title: "Event payload"
+			body: """
+				Each log event is sent as a single PagerDuty event. For `trigger` actions, the remaining
+				fields of the log event (after applying the `encoding` options) are sent as the
+				`custom_details` of the alert.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -0,0 +1,122 @@
+package metadata
+
+components: sinks: quickwit: {
+	title: "Quickwit"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_485_760
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.quickwit
+
+				interface: {
+					socket: {
+						api: {
+							title: "Quickwit ingest API"
+							url:   urls.quickwit_ingest_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${QUICKWIT_PASSWORD}"
+			username_example: "${QUICKWIT_USERNAME}"
+		}}
+		endpoint: {
+			description: "The base URL of the Quickwit cluster."
+			required:    true
+			type: string: {
+				examples: ["http://localhost:7280"]
+			}
+		}
+		index: {
+			description: "The ID of the index that events are ingested into."
+			required:    true
+			type: string: {
+				examples: ["application-logs", "logs-{{ service }}"]
+				syntax: "template"
+			}
+		}
+		commit: {
+			common:      false
+			description: "Controls when the ingested documents become searchable."
+			required:    false
+			type: string: {
+				default: "auto"
+				enum: {
+					auto:     "Documents are committed according to the index `commit_timeout_secs` setting."
+					wait_for: "Each request waits until its documents have been committed."
+					force:    "Each request forces a commit and waits until its documents have been committed."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body: """
+				Events are batched per rendered index and sent as newline-delimited JSON. Batches are capped
+				at 10MB, matching the default payload limit of the Quickwit ingest API.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -16,12 +16,18 @@ components: sinks: socket: {
 		acknowledgements: false
 		healthcheck: enabled: true
 		send: {
-			compression: enabled: false
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "zstd", "snappy"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+				zstd_long_window: true
+			}
 			encoding: {
 				enabled: true
 				codec: {
 					enabled: true
-					enum: ["json", "text"]
+					enum: ["bson", "cbor", "cef", "json", "leef", "text"]
 				}
 			}
 			send_buffer_bytes: {
@@ -57,6 +63,124 @@ components: sinks: socket: {
 	}
 
 	configuration: {
+		encoding: type: object: options: cef: {
+			common:        false
+			description:   "Options for the `cef` codec. The header fields and extension are read from the event fields they are mapped to, which default to the fields the `cef` decoder decodes them into."
+			required:      false
+			relevant_when: "codec = \"cef\""
+			type: object: options: {
+				version: {
+					common:      false
+					description: "The CEF version."
+					required:    false
+					type: string: default: "0"
+				}
+				device_vendor_field: {
+					common:      false
+					description: "The field the device vendor is read from."
+					required:    false
+					type: string: default: "device_vendor"
+				}
+				device_product_field: {
+					common:      false
+					description: "The field the device product is read from."
+					required:    false
+					type: string: default: "device_product"
+				}
+				device_version_field: {
+					common:      false
+					description: "The field the device version is read from."
+					required:    false
+					type: string: default: "device_version"
+				}
+				device_event_class_id_field: {
+					common:      false
+					description: "The field the device event class ID is read from."
+					required:    false
+					type: string: default: "device_event_class_id"
+				}
+				name_field: {
+					common:      false
+					description: "The field the name is read from."
+					required:    false
+					type: string: default: "name"
+				}
+				severity_field: {
+					common:      false
+					description: "The field the severity is read from."
+					required:    false
+					type: string: default: "severity"
+				}
+				extensions: {
+					common:      false
+					description: "Maps the keys of the extension to the fields their values are read from. When empty, the extension is read from the `extensions` object of the events."
+					required:    false
+					type: object: {
+						examples: [{"src": "client.ip", "suser": "user.name"}]
+						options: {}
+					}
+				}
+			}
+		}
+		encoding: type: object: options: leef: {
+			common:        false
+			description:   "Options for the `leef` codec. The header fields and attributes are read from the event fields they are mapped to, which default to the fields the `leef` decoder decodes them into."
+			required:      false
+			relevant_when: "codec = \"leef\""
+			type: object: options: {
+				version: {
+					common:      false
+					description: "The LEEF version."
+					required:    false
+					type: string: {
+						default: "2.0"
+						enum: {
+							"1.0": "LEEF 1.0, whose attributes are separated by tabs."
+							"2.0": "LEEF 2.0, which declares the delimiter of the attributes in the header."
+						}
+					}
+				}
+				delimiter: {
+					common:      false
+					description: "The character separating the attributes. Only LEEF 2.0 messages can use another delimiter than a tab."
+					required:    false
+					type: string: default: "\t"
+				}
+				vendor_field: {
+					common:      false
+					description: "The field the vendor is read from."
+					required:    false
+					type: string: default: "vendor"
+				}
+				product_field: {
+					common:      false
+					description: "The field the product is read from."
+					required:    false
+					type: string: default: "product"
+				}
+				product_version_field: {
+					common:      false
+					description: "The field the product version is read from."
+					required:    false
+					type: string: default: "product_version"
+				}
+				event_id_field: {
+					common:      false
+					description: "The field the event ID is read from."
+					required:    false
+					type: string: default: "event_id"
+				}
+				attributes: {
+					common:      false
+					description: "Maps the keys of the attributes to the fields their values are read from. When empty, the attributes are read from the `attributes` object of the events."
+					required:    false
+					type: object: {
+						examples: [{"src": "client.ip", "usrName": "user.name"}]
+						options: {}
+					}
+				}
+			}
+		}
 		address: {
 			description:   "The address to connect to. The address _must_ include a port."
 			relevant_when: "mode = `tcp` or `udp`"
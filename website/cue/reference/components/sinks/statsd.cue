@@ -34,7 +34,7 @@ components: sinks: statsd: {
 	support: sinks.socket.support
 
 	input: {
-		logs: false
+		logs: true
 		metrics: {
 			counter:      true
 			distribution: true
@@ -86,11 +86,58 @@ components: sinks: statsd: {
 				examples: ["service"]
 			}
 		}
+		flavor: {
+			common:      false
+			description: "The statsd dialect to emit."
+			required:    false
+			type: string: {
+				default: "statsd"
+				enum: {
+					statsd:    "Plain statsd. Only metrics are accepted."
+					dogstatsd: "DogStatsD. Logs are additionally accepted and encoded as events or service checks."
+				}
+			}
+		}
+		container_id_tag: {
+			common: false
+			description: """
+				The metric tag holding the container ID of the emitter. With the `dogstatsd` flavor, the tag is
+				sent as origin metadata (`|c:`) instead of as a regular tag.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["container_id"]
+			}
+		}
+	}
+
+	how_it_works: {
+		dogstatsd: {
+			title: "DogStatsD"
+			body: """
+				With `flavor = "dogstatsd"` the sink emits the DogStatsD extensions understood by the Datadog
+				Agent and other compatible receivers: distributions (`|d`), sample rates and tags. Logs are
+				accepted as well:
+
+				* Logs with a `check` field are sent as service checks. The `status` field is required and
+				  may be `0`-`3` or one of `ok`, `warning`, `critical` and `unknown`. The `message` field is
+				  sent as the check message.
+				* All other logs are sent as events. The `title` field is required, while the `text` field
+				  (falling back to `message`) holds the event text. The `aggregation_key`, `priority`,
+				  `source_type_name` and `alert_type` fields are passed through.
+
+				In both cases the timestamp and host fields are used when present, and the `tags` field
+				may be an array of tags or an object of tag names to values.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
 		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
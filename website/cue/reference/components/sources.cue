@@ -5,6 +5,109 @@ components: sources: [Name=string]: {
 	features: _
 
 	configuration: {
+		priority: {
+			common: false
+			description: """
+				The priority given to the events of this source. When a buffer configured to drop
+				events, or to overflow, is full, lower priority events are shed first.
+				"""
+			required: false
+			type: string: {
+				default: "normal"
+				enum: {
+					low:    "Shed as soon as a buffer is half full."
+					normal: "Shed once a buffer is full."
+					high:   "Never shed. Waits for the buffer to have room instead."
+				}
+			}
+		}
+
+		leader_election: {
+			common: false
+			description: """
+				Elects the only replica of Vector running this source, among all of the replicas running
+				it, for sources that must run once per cluster. Another replica takes over once the
+				leader stops renewing its lock.
+				"""
+			required: false
+			type: object: options: {
+				type: {
+					description: "The kind of lock held by the leader."
+					required:    true
+					type: string: enum: {
+						kubernetes: "A `Lease` object of the Kubernetes API."
+						redis:      "A key of Redis, expiring once the leader stops renewing it."
+					}
+				}
+				namespace: {
+					description:   "The namespace of the lease. Defaults to the namespace Vector runs in."
+					relevant_when: "type = \"kubernetes\""
+					required:      false
+					type: string: {
+						default: null
+						examples: ["vector"]
+					}
+				}
+				lease_name: {
+					description:   "The name of the lease. Defaults to `vector-<component ID>`."
+					relevant_when: "type = \"kubernetes\""
+					required:      false
+					type: string: {
+						default: null
+						examples: ["vector-events"]
+					}
+				}
+				kube_config_file: {
+					description:   "Optional path to a kubeconfig file readable by Vector. If not set, Vector connects to Kubernetes using the in-cluster configuration."
+					relevant_when: "type = \"kubernetes\""
+					required:      false
+					type: string: {
+						default: null
+						examples: ["/path/to/.kube/config"]
+					}
+				}
+				url: {
+					description:   "The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format."
+					relevant_when: "type = \"redis\""
+					required:      true
+					type: string: examples: ["redis://127.0.0.1:6379/0"]
+				}
+				key: {
+					description:   "The key of the lock. Defaults to `vector:leader:<component ID>`."
+					relevant_when: "type = \"redis\""
+					required:      false
+					type: string: {
+						default: null
+						examples: ["vector:leader:events"]
+					}
+				}
+				identity: {
+					description: "The identity of this replica, which must differ between replicas. Defaults to the hostname, which is the name of the pod on Kubernetes."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["vector-0"]
+					}
+				}
+				lease_duration_secs: {
+					description: "How long the lock is held after it was last renewed. Once a leader stops renewing it, for instance because it crashed, another replica takes over after this long."
+					required:    false
+					type: uint: {
+						default: 15
+						unit:    "seconds"
+					}
+				}
+				renew_interval_secs: {
+					description: "How often the leader renews the lock, and the other replicas try to take it. Must be less than `lease_duration_secs`."
+					required:    false
+					type: uint: {
+						default: 5
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+
 		if features.collect != _|_ {
 			if features.collect.checkpoint.enabled {
 				data_dir: {
@@ -84,11 +187,13 @@ components: sources: [Name=string]: {
 							type: string: {
 								default: features.codecs.default_framing
 								enum: {
-									bytes:               "Byte frames are passed through as-is according to the underlying I/O boundaries (e.g. split between messages or stream segments)."
-									character_delimited: "Byte frames which are delimited by a chosen character."
-									length_delimited:    "Byte frames whose length is encoded in a header."
-									newline_delimited:   "Byte frames which are delimited by a newline character."
-									octet_counting:      "Byte frames according to the [octet counting](\(urls.rfc_6587_3_4_1)) format."
+									bytes:                   "Byte frames are passed through as-is according to the underlying I/O boundaries (e.g. split between messages or stream segments)."
+									character_delimited:     "Byte frames which are delimited by a chosen character."
+									length_delimited:        "Byte frames whose length is encoded in a header."
+									multiline:               "Byte frames made of the newline delimited lines of a message, aggregated according to the `multiline` options."
+									newline_delimited:       "Byte frames which are delimited by a newline character."
+									octet_counting:          "Byte frames according to the [octet counting](\(urls.rfc_6587_3_4_1)) format."
+									varint_length_delimited: "Byte frames whose length is encoded as a protobuf varint in a header, as written by `writeDelimitedTo`."
 								}
 							}
 						}
@@ -116,6 +221,70 @@ components: sources: [Name=string]: {
 								}
 							}
 						}
+						multiline: {
+							description:   "Options for `multiline` framing, which aggregates the lines of multiline messages, such as stack traces, into single frames."
+							required:      true
+							relevant_when: "method = `multiline`"
+							type: object: options: {
+								start_pattern: {
+									description: "Start regex pattern to look for as a beginning of the message."
+									required:    true
+									type: string: {
+										examples: ["^[^\\s]", "\\\\$", "^(INFO|ERROR) ", "[^;]$"]
+										syntax: "regex"
+									}
+								}
+								condition_pattern: {
+									description: "Condition regex pattern to look for. Exact behavior is configured via `mode`."
+									required:    true
+									type: string: {
+										examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
+										syntax: "regex"
+									}
+								}
+								mode: {
+									description: "Mode of operation, specifies how the `condition_pattern` is interpreted."
+									required:    true
+									type: string: {
+										enum: {
+											continue_through: "All consecutive lines matching this pattern are included in the group. The first line (the line that matched the start pattern) does not need to match the `ContinueThrough` pattern. This is useful in cases such as a Java stack trace, where some indicator in the line (such as leading whitespace) indicates that it is an extension of the preceding line."
+											continue_past:    "All consecutive lines matching this pattern, plus one additional line, are included in the group. This is useful in cases where a log message ends with a continuation marker, such as a backslash, indicating that the following line is part of the same message."
+											halt_before:      "All consecutive lines not matching this pattern are included in the group. This is useful where a log line contains a marker indicating that it begins a new message."
+											halt_with:        "All consecutive lines, up to and including the first line matching this pattern, are included in the group. This is useful where a log line ends with a termination marker, such as a semicolon."
+										}
+									}
+								}
+								timeout_ms: {
+									description: "The maximum time to wait for the continuation. Once this timeout is reached, the buffered message is flushed, even if incomplete, whether or not more data is received."
+									required:    false
+									common:      false
+									type: uint: {
+										default: 1_000
+										unit:    "milliseconds"
+									}
+								}
+								max_lines: {
+									description: "The maximum number of lines of a message. Once a message has this many lines, it is flushed, even if incomplete."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [500]
+										unit: "lines"
+									}
+								}
+								max_bytes: {
+									description: "The maximum length of a message. Once a message is this long, it is flushed, even if incomplete."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [102400]
+										unit: "bytes"
+									}
+								}
+							}
+						}
 						newline_delimited: {
 							description:   "Options for `newline_delimited` framing."
 							required:      false
@@ -152,6 +321,23 @@ components: sources: [Name=string]: {
 								}
 							}
 						}
+						varint_length_delimited: {
+							description:   "Options for `varint_length_delimited` framing."
+							required:      false
+							common:        false
+							relevant_when: "method = `varint_length_delimited`"
+							type: object: options: {
+								max_frame_length: {
+									description: "The maximum frame length limit, not including the length header. Frames longer than `max_frame_length` bytes are rejected."
+									required:    false
+									common:      false
+									type: uint: {
+										default: 8388608
+										unit:    "bytes"
+									}
+								}
+							}
+						}
 					}
 				}
 				decoding: {
@@ -171,6 +357,192 @@ components: sources: [Name=string]: {
 									syslog:      "Events being parsed from a Syslog message."
 									native:      "Events being parsed from Vector's [native protobuf format](\(urls.native_proto_schema)) ([EXPERIMENTAL](/highlights/2022-03-31-native-event-codecs))."
 									native_json: "Events being parsed from Vector's [native JSON format](\(urls.native_json_schema)) ([EXPERIMENTAL](/highlights/2022-03-31-native-event-codecs))."
+									avro:        "Events being parsed from an [Avro](\(urls.apache_avro)) datum, optionally framed with the Confluent Schema Registry wire format."
+									bson:        "Events being parsed from a sequence of [BSON](\(urls.bson)) documents, such as a `mongodump` file, with ObjectIds and Decimal128s decoded into strings and date/times into timestamps."
+									cbor:        "Events being parsed from the maps of a sequence of [CBOR](\(urls.cbor)) data items, with byte strings decoded into bytes and tagged date/times into timestamps."
+									cef:         "Events being parsed from an [ArcSight Common Event Format](\(urls.cef)) message, with a field per header field and the `extensions` object holding the extension."
+									csv:         "Events being parsed from the [CSV](\(urls.csv)) records of the frame, with a field per column."
+									leef:        "Events being parsed from an IBM QRadar [Log Event Extended Format](\(urls.leef)) 1.0 or 2.0 message, with a field per header field and the `attributes` object holding the attributes."
+									access_log:  "Events being parsed from an Apache or nginx access log line in the common, combined or a custom [`log_format`](\(urls.nginx_combined)) format, with a field per variable of the format."
+									w3c:         "Events being parsed from the entries of a [W3C Extended Log Format](\(urls.w3c_extended_log_format)) log, such as IIS or CloudFront access logs, with a field per field named by the `#Fields` directive and the `date` and `time` fields used as the timestamp."
+								}
+							}
+						}
+						csv: {
+							description:   "Options for the CSV codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"csv\""
+							type: object: options: {
+								delimiter: {
+									description: "The character separating the fields of a record."
+									required:    false
+									common:      true
+									type: string: default: ","
+								}
+								quote: {
+									description: "The character quoting the fields containing delimiters, quotes or line breaks. Quote characters are escaped within quoted fields by doubling them."
+									required:    false
+									common:      false
+									type: string: default: "\""
+								}
+								header: {
+									description: "Whether the first record is a header naming the columns. When `columns` is set, the header is skipped. Defaults to `true` unless `columns` is set."
+									required:    false
+									common:      true
+									type: bool: default: null
+								}
+								columns: {
+									description: "The names of the columns, used as the names of the fields of the events."
+									required:    false
+									common:      true
+									type: array: {
+										default: null
+										items: type: string: examples: ["timestamp", "host", "message"]
+									}
+								}
+								types: {
+									description: "The types the values of the columns are coerced to, by column name. The values of the other columns are kept as strings."
+									required:    false
+									common:      false
+									type: object: {
+										examples: [{"status": "integer", "duration": "float", "timestamp": "timestamp|%F %T"}]
+										options: {}
+									}
+								}
+								timezone: {
+									description: "The time zone of the timestamps without one. Defaults to the local time zone."
+									required:    false
+									common:      false
+									type: string: {
+										default: null
+										examples: ["local", "America/New_York"]
+									}
+								}
+							}
+						}
+						leef: {
+							description:   "Options for the LEEF codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"leef\""
+							type: object: options: {
+								delimiter: {
+									description: "The character separating the attributes of the messages that don't declare one. LEEF 1.0 messages can't declare one, and use tabs by default."
+									required:    false
+									common:      false
+									type: string: default: "\t"
+								}
+							}
+						}
+						w3c: {
+							description:   "Options for the W3C codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"w3c\""
+							type: object: options: {
+								fields: {
+									description: "The names of the fields, used until a `#Fields` directive is read. This is needed for logs written without directives, such as AWS ALB access logs."
+									required:    false
+									common:      false
+									type: array: {
+										default: null
+										items: type: string: examples: ["type", "time", "elb", "client:port"]
+									}
+								}
+							}
+						}
+						access_log: {
+							description:   "Options for the access log codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"access_log\""
+							type: object: options: {
+								format: {
+									description: "The format of the access log."
+									required:    false
+									common:      true
+									type: string: {
+										default: "combined"
+										enum: {
+											common:   "The Common Log Format, as written by Apache's and nginx's `common` formats."
+											combined: "The Combined Log Format, as written by Apache's `combined` and nginx's default `combined` formats."
+											custom:   "The format set by `log_format`."
+										}
+									}
+								}
+								log_format: {
+									description: "The format of the access log when `format` is `custom`, in the syntax of the nginx [`log_format`](\(urls.nginx_combined)) directive. Status, byte count and timing variables are decoded into integers and floats, and `$time_local` and `$time_iso8601` into timestamps."
+									required:    false
+									common:      false
+									type: string: {
+										default: null
+										examples: [#"$remote_addr [$time_iso8601] "$request" $status $request_time"#]
+									}
+								}
+							}
+						}
+						bson: {
+							description:   "Options for the BSON codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"bson\""
+							type: object: options: {
+								extended_json: {
+									description: "Whether ObjectIds and Decimal128s are decoded into [extended JSON](\(urls.mongodb_extended_json)) objects, such as `{\"$oid\": \"...\"}`, rather than strings. The `bson` encoder encodes these objects back into their BSON type."
+									required:    false
+									common:      false
+									type: bool: default: false
+								}
+							}
+						}
+						avro: {
+							description:   "Options for the Avro codec. Exactly one of `schema`, `schema_file` or `schema_registry` must be set."
+							required:      false
+							common:        false
+							relevant_when: "codec = \"avro\""
+							type: object: options: {
+								schema: {
+									description: "The Avro schema of the datums, in JSON."
+									required:    false
+									common:      true
+									type: string: {
+										default: null
+										examples: [#"{ "type": "record", "name": "log", "fields": [{ "name": "message", "type": "string" }] }"#]
+									}
+								}
+								schema_file: {
+									description: "The path of a file containing the Avro schema of the datums."
+									required:    false
+									common:      true
+									type: string: {
+										default: null
+										examples: ["/etc/vector/schemas/log.avsc"]
+									}
+								}
+								schema_registry: {
+									description: "Fetches the schemas from a Confluent Schema Registry, using the schema ID in the header of each message."
+									required:    false
+									common:      false
+									type: object: options: {
+										url: {
+											description: "The URL of the schema registry."
+											required:    true
+											type: string: examples: ["http://localhost:8081"]
+										}
+										username: {
+											description: "The username used to authenticate with the schema registry."
+											required:    false
+											common:      false
+											type: string: default: null
+										}
+										password: {
+											description: "The password used to authenticate with the schema registry."
+											required:    false
+											common:      false
+											type: string: default: null
+										}
+									}
 								}
 							}
 						}
@@ -338,6 +710,25 @@ components: sources: [Name=string]: {
 			}
 		}
 
+		leader_election: {
+			title: "Leader election"
+			body:  """
+				Sources that must run once per cluster, such as the `kubernetes_events` source or the
+				CDC sources, can still be deployed on several replicas for failover with the
+				`leader_election` option. Every replica builds the source, but only the one holding a
+				lock, the leader, runs it. The leader renews the lock every `renew_interval_secs`, and
+				stops the source as soon as it fails to renew it for close to `lease_duration_secs`.
+				Once the lock expires, another replica takes it and starts the source, resuming from
+				its checkpoint when the source stores it outside of the host, for instance with its
+				`checkpoint_store` option.
+
+				The lock is either a Kubernetes `Lease` object, which requires the service account of
+				Vector to be allowed to get, create and update leases, or a Redis key. Object stores
+				such as S3 can't hold the lock, as they can't atomically update an object only if it
+				didn't change.
+				"""
+		}
+
 		context: {
 			title: "Context"
 			body:  """
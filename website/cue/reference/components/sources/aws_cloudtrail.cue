@@ -0,0 +1,170 @@
+package metadata
+
+components: sources: aws_cloudtrail: components._aws & {
+	title: "AWS CloudTrail"
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.aws_cloudtrail
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The AWS CloudTrail source requires a SQS queue receiving the notifications of the log
+				files delivered by the trail, either sent by CloudTrail through an
+				[SNS topic](\(urls.aws_cloudtrail_sns_notifications)), or by the bucket.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		digest_files: {
+			common:      false
+			description: "Whether to send the [digest files](\(urls.aws_cloudtrail_digest_files)) of the trail as events. Digest files are delivered when log file integrity validation is enabled."
+			required:    false
+			type: bool: default: false
+		}
+		sqs: {
+			description: "SQS options."
+			required:    true
+			type: object: {
+				examples: []
+				options: {
+					poll_secs: {
+						common:      true
+						description: "How long to wait when polling SQS for new messages."
+						required:    false
+						type: uint: {
+							default: 15
+							unit:    "seconds"
+						}
+					}
+					visibility_timeout_secs: {
+						common:      false
+						description: "The visibility timeout to use for messages in seconds. This controls how long a message is left unavailable when Vector receives it. If Vector does not delete the message before the timeout expires, it will be made available again for another consumer."
+						required:    false
+						warnings: ["Should be set higher than the length of time it takes to process an individual message to avoid that message being reprocessed."]
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					delete_message: {
+						common:      true
+						description: "Whether to delete the message once Vector processes it. It can be useful to set this to `false` to debug or during initial Vector setup."
+						required:    false
+						type: bool: default: true
+					}
+					queue_url: {
+						description: "The URL of the SQS queue to receive log file notifications from."
+						required:    true
+						type: string: {
+							examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: """
+			An individual [CloudTrail record](\(urls.aws_cloudtrail_record_contents)), with its
+			fields, such as `eventName`, `eventSource` and `userIdentity`, at the root of the event.
+			"""
+		fields: {
+			timestamp: fields._current_timestamp & {
+				description: "The `eventTime` of the record, or the `digestEndTime` of a digest file."
+			}
+		}
+	}
+
+	how_it_works: {
+		log_files: {
+			title: "Log files"
+			body: """
+				Each log file notified to the queue is fetched, decompressed, and the records of its
+				`Records` array are sent as individual events. Log files are only considered processed
+				once all their records are sent, and delivered when acknowledgements are enabled, after
+				which their notification is deleted.
+
+				Notifications can be sent by CloudTrail through an SNS topic configured on the trail, or
+				be the notifications of the bucket, sent directly or through an SNS topic or an
+				EventBridge rule.
+				"""
+		}
+		digest_files: {
+			title: "Digest files"
+			body: """
+				When log file integrity validation is enabled, CloudTrail delivers digest files
+				alongside the log files, under the `CloudTrail-Digest` prefix. They are skipped by
+				default, and are sent as a single event each with `digest_files` set to `true`.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform:      "aws"
+			_service:      "s3"
+			_docs_tag:     "AmazonS3"
+			_url_fragment: "API"
+
+			policies: [
+				{
+					_action: "GetObject"
+				},
+			]
+		},
+		{
+			platform:  "aws"
+			_service:  "sqs"
+			_docs_tag: "AWSSimpleQueueService"
+
+			policies: [
+				{
+					_action: "ReceiveMessage"
+				},
+				{
+					_action:       "DeleteMessage"
+					required_when: "[`delete_message`](#sqs.delete_message) is set to `true`"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		sqs_message_delete_failed_total:      components.sources.internal_metrics.output.metrics.sqs_message_delete_failed_total
+	}
+}
@@ -0,0 +1,281 @@
+package metadata
+
+components: sources: aws_cloudwatch_logs: components._aws & {
+	title: "AWS CloudWatch Logs"
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			checkpoint: enabled: true
+			proxy: enabled:      true
+			from: service:       services.aws_cloudwatch_logs
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				With the `kinesis` strategy, the AWS CloudWatch Logs source requires a Kinesis data
+				stream that [subscription filters](\(urls.aws_cloudwatch_logs_subscriptions_kinesis))
+				send log events to.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the checkpoints of the log streams, or of the Kinesis shards. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		strategy: {
+			common:      true
+			description: "The strategy to use to consume log events from AWS CloudWatch Logs."
+			required:    false
+			type: string: {
+				default: "filter"
+				enum: {
+					filter:  "Consume log events by periodically filtering the log events of log groups."
+					kinesis: "Consume log events sent to a Kinesis data stream by [subscription filters](\(urls.aws_cloudwatch_logs_subscriptions))."
+				}
+			}
+		}
+		filter: {
+			common:      true
+			description: "Filter strategy options. Required if strategy=`filter`."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					log_group_names: {
+						description: "The names of the log groups to consume log events from."
+						required:    true
+						type: array: items: type: string: {
+							examples: ["/aws/lambda/app"]
+						}
+					}
+					log_stream_name_prefix: {
+						common:      false
+						description: "Only log events of the log streams whose name starts with this prefix are consumed."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["2022/08/"]
+						}
+					}
+					filter_pattern: {
+						common:      false
+						description: "The [filter pattern](\(urls.aws_cloudwatch_logs_filter_pattern)) log events must match to be consumed. By default, all log events are consumed."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["ERROR", "{ $.level = \"error\" }"]
+						}
+					}
+					poll_secs: {
+						common:      true
+						description: "How long to wait between filterings of the log groups."
+						required:    false
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
+					ingestion_delay_secs: {
+						common:      false
+						description: "How long to keep looking for log events after their timestamp. Log events ingested into AWS CloudWatch Logs later than this after their timestamp are missed."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+		kinesis: {
+			common:      true
+			description: "Kinesis strategy options. Required if strategy=`kinesis`."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					stream_name: {
+						description: "The name of the Kinesis data stream the subscription filters send log events to."
+						required:    true
+						type: string: {
+							examples: ["cloudwatch-logs"]
+						}
+					}
+					poll_secs: {
+						common:      false
+						description: "How long to wait between reads of the shards of the stream."
+						required:    false
+						type: uint: {
+							default: 1
+							unit:    "seconds"
+						}
+					}
+					start_position: {
+						common:      false
+						description: "Where to start reading the shards of the stream from, when no checkpoint exists yet. Shards created afterwards, such as when the stream is resharded, are always read from their start."
+						required:    false
+						type: string: {
+							default: "latest"
+							enum: {
+								latest:       "Only read the records added after the source starts."
+								trim_horizon: "Read all the records retained by the stream."
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual log event."
+		fields: {
+			message: {
+				description: "The message of the log event."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The timestamp of the log event."
+			}
+			log_group: {
+				description: "The name of the log group of the log event."
+				required:    true
+				type: string: {
+					examples: ["/aws/lambda/app"]
+				}
+			}
+			log_stream: {
+				description: "The name of the log stream of the log event."
+				required:    true
+				type: string: {
+					examples: ["2022/08/01/[$LATEST]0123456789abcdef"]
+				}
+			}
+			id: {
+				description: "The ID of the log event."
+				required:    true
+				type: string: {
+					examples: ["36984816412347897645213587542363652541225669401812312064"]
+				}
+			}
+			owner: {
+				description: "The ID of the AWS account of the log group. Only set with the `kinesis` strategy."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["111111111111"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		filter: {
+			title: "Filtering"
+			body: """
+				With the `filter` strategy, the log events of each log group are periodically
+				filtered since the time the log group was first configured. Each log event is
+				consumed once, by checkpointing the latest log event consumed in each log stream.
+
+				As log events can be ingested into AWS CloudWatch Logs some time after their
+				timestamp, log events keep being looked for during `ingestion_delay_secs`, which
+				should be longer than the delay of the producers of the log group.
+
+				Live tailing log groups with `StartLiveTail` isn't supported.
+				"""
+		}
+		subscriptions: {
+			title: "Subscription filters"
+			body: """
+				With the `kinesis` strategy, the source reads the records sent to a Kinesis data
+				stream by subscription filters, and decodes their gzipped payload into individual
+				log events. Control messages, sent by AWS CloudWatch Logs to check that the stream is
+				reachable, are dropped. The latest record consumed from each shard is checkpointed,
+				and the shards created when the stream is resharded are read once their parents are
+				finished.
+
+				To consume subscription filters sending log events to a
+				[Kinesis Data Firehose](\(urls.aws_cloudwatch_logs_firehose)) delivery stream instead,
+				use the [`aws_kinesis_firehose` source](\(urls.vector_aws_kinesis_firehose_source)).
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "aws"
+			_service: "AmazonCloudWatchLogs"
+
+			policies: [
+				{
+					_action:       "FilterLogEvents"
+					required_when: "[`strategy`](#strategy) is set to `filter`"
+				},
+			]
+		},
+		{
+			platform: "aws"
+			_service: "kinesis"
+
+			policies: [
+				{
+					_action:       "GetRecords"
+					required_when: "[`strategy`](#strategy) is set to `kinesis`"
+				},
+				{
+					_action:       "GetShardIterator"
+					required_when: "[`strategy`](#strategy) is set to `kinesis`"
+				},
+				{
+					_action:       "ListShards"
+					required_when: "[`strategy`](#strategy) is set to `kinesis`"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+}
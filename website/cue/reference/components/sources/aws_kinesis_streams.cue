@@ -0,0 +1,324 @@
+package metadata
+
+components: sources: aws_kinesis_streams: components._aws & {
+	title: "AWS Kinesis Data Streams"
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			checkpoint: enabled: true
+			proxy: enabled:      true
+			from: service:       services.aws_kinesis_data_streams
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				With `checkpoint.type` set to `dynamodb`, the source requires a DynamoDB table whose
+				partition key is a string attribute named `stream_name`.
+				""",
+		]
+		warnings: [
+			"""
+				The shards of a stream are read by a single Vector instance, as they aren't leased
+				between instances. Running several instances with the same checkpoint reads each
+				record several times.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		stream_name: {
+			description: "The name of the stream to read."
+			required:    true
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+		mode: {
+			common:      true
+			description: "How to read the records of the shards."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					type: {
+						common:      true
+						description: "The mode used to read the shards."
+						required:    false
+						type: string: {
+							default: "polling"
+							enum: {
+								polling:          "Periodically read the shards with `GetRecords`, sharing the read throughput of each shard with the other consumers of the stream."
+								enhanced_fan_out: "Subscribe to the shards with `SubscribeToShard`, which pushes their records to an [enhanced fan-out](\(urls.aws_kinesis_streams_enhanced_fan_out)) consumer with a dedicated read throughput."
+							}
+						}
+					}
+					poll_secs: {
+						common:        false
+						description:   "How long to wait between reads of each shard. A shard supports up to five reads per second, shared by all the consumers polling the stream."
+						relevant_when: "type = `polling`"
+						required:      false
+						type: uint: {
+							default: 1
+							unit:    "seconds"
+						}
+					}
+					max_records: {
+						common:        false
+						description:   "The maximum number of records read from a shard at once, up to 10000."
+						relevant_when: "type = `polling`"
+						required:      false
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+					consumer_name: {
+						description:   "The name of the consumer to register on the stream. The consumer is registered when the source starts, unless it already exists."
+						relevant_when: "type = `enhanced_fan_out`"
+						required:      true
+						type: string: {
+							examples: ["vector"]
+						}
+					}
+				}
+			}
+		}
+		start_position: {
+			common:      false
+			description: "Where to start reading the shards of the stream from, when no checkpoint exists yet. Shards created afterwards, such as when the stream is resharded, are always read from their start."
+			required:    false
+			type: string: {
+				default: "latest"
+				enum: {
+					latest:       "Only read the records added after the source starts."
+					trim_horizon: "Read all the records retained by the stream."
+				}
+			}
+		}
+		checkpoint: {
+			common:      false
+			description: "Where to persist the progress of the shards."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					type: {
+						common:      true
+						description: "The kind of checkpoint store."
+						required:    false
+						type: string: {
+							default: "file"
+							enum: {
+								file:     "A file in the data directory."
+								dynamodb: "An item in a DynamoDB table, which outlives the host running Vector."
+							}
+						}
+					}
+					data_dir: {
+						common:        false
+						description:   "The directory used to persist the checkpoint. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+						relevant_when: "type = `file`"
+						required:      false
+						type: string: {
+							default: null
+							examples: ["/var/lib/vector"]
+						}
+					}
+					table: {
+						description:   "The name of the DynamoDB table to store the checkpoint in. Its partition key must be a string attribute named `stream_name`, so that a table can hold the checkpoints of several streams."
+						relevant_when: "type = `dynamodb`"
+						required:      true
+						type: string: {
+							examples: ["vector-checkpoints"]
+						}
+					}
+				}
+			}
+		}
+		checkpoint_interval_secs: {
+			common:      false
+			description: "How long to wait between saves of the checkpoint. The records read since the last save are read again when the source restarts."
+			required:    false
+			type: uint: {
+				default: 5
+				unit:    "seconds"
+			}
+		}
+		shard_discovery_interval_secs: {
+			common:      false
+			description: "How long to wait between listings of the shards of the stream. The shards are also listed as soon as a shard is closed, so that its children are read without delay."
+			required:    false
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual record."
+		fields: {
+			message: {
+				description: "The data of the record."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the record was added to the stream."
+			}
+			stream_name: {
+				description: "The name of the stream of the record."
+				required:    true
+				type: string: {
+					examples: ["logs"]
+				}
+			}
+			shard_id: {
+				description: "The ID of the shard of the record."
+				required:    true
+				type: string: {
+					examples: ["shardId-000000000000"]
+				}
+			}
+			partition_key: {
+				description: "The partition key of the record."
+				required:    true
+				type: string: {
+					examples: ["web-1"]
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the record in its shard."
+				required:    true
+				type: string: {
+					examples: ["49590338271490256608559692538361571095921575989136588898"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		shards: {
+			title: "Shards"
+			body: """
+				The source lists the shards of the stream every `shard_discovery_interval_secs`, and
+				reads each open shard from its own task. When the stream is
+				[resharded](\(urls.aws_kinesis_streams_resharding)), the shards it creates are only
+				read once their parents are finished, so that the records of each partition key are
+				read in order. Shards past the retention period of the stream are forgotten.
+				"""
+		}
+		enhanced_fan_out: {
+			title: "Enhanced fan-out"
+			body: """
+				With the `enhanced_fan_out` mode, the source registers a consumer on the stream, and
+				subscribes to the shards over HTTP/2. The records are pushed to the source as soon as
+				they are added to the stream, with a read throughput of 2 MB/s per shard dedicated to
+				the consumer. Subscriptions expire after five minutes, and are renewed from the last
+				record read. The consumer isn't deregistered when Vector stops, and is billed by AWS
+				until it is.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				The sequence number of the last record read from each shard is saved every
+				`checkpoint_interval_secs`, and when Vector stops, either to a file in the data
+				directory, or to an item of a DynamoDB table, which survives the loss of the host
+				running Vector. With acknowledgements enabled, records are only checkpointed once
+				their events are delivered.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "aws"
+			_service: "kinesis"
+
+			policies: [
+				{
+					_action: "ListShards"
+				},
+				{
+					_action:       "GetShardIterator"
+					required_when: "[`mode.type`](#mode.type) is set to `polling`"
+				},
+				{
+					_action:       "GetRecords"
+					required_when: "[`mode.type`](#mode.type) is set to `polling`"
+				},
+				{
+					_action:       "DescribeStreamSummary"
+					required_when: "[`mode.type`](#mode.type) is set to `enhanced_fan_out`"
+				},
+				{
+					_action:       "DescribeStreamConsumer"
+					required_when: "[`mode.type`](#mode.type) is set to `enhanced_fan_out`"
+				},
+				{
+					_action:       "RegisterStreamConsumer"
+					required_when: "[`mode.type`](#mode.type) is set to `enhanced_fan_out`"
+				},
+				{
+					_action:       "SubscribeToShard"
+					required_when: "[`mode.type`](#mode.type) is set to `enhanced_fan_out`"
+				},
+			]
+		},
+		{
+			platform:  "aws"
+			_service:  "dynamodb"
+			_docs_tag: "amazondynamodb"
+
+			policies: [
+				{
+					_action:       "GetItem"
+					required_when: "[`checkpoint.type`](#checkpoint.type) is set to `dynamodb`"
+				},
+				{
+					_action:       "PutItem"
+					required_when: "[`checkpoint.type`](#checkpoint.type) is set to `dynamodb`"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+}
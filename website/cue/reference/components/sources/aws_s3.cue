@@ -52,7 +52,8 @@ components: sources: aws_s3: components._aws & {
 			type: string: {
 				default: "sqs"
 				enum: {
-					sqs: "Consume S3 objects by polling for bucket notifications sent to an [AWS SQS queue](\(urls.aws_sqs))."
+					sqs:  "Consume S3 objects by polling for bucket notifications sent to an [AWS SQS queue](\(urls.aws_sqs))."
+					list: "Consume S3 objects by periodically listing a bucket, for buckets whose notifications can't be configured."
 				}
 			}
 		}
@@ -70,6 +71,47 @@ components: sources: aws_s3: components._aws & {
 				}
 			}
 		}
+		list: {
+			common:      false
+			description: "List strategy options. Required if strategy=`list`."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					bucket: {
+						description: "The name of the bucket to list objects from."
+						required:    true
+						type: string: {
+							examples: ["my-bucket"]
+						}
+					}
+					prefix: {
+						common:      true
+						description: "Only objects whose key starts with this prefix are ingested. Listing a prefix rather than the whole bucket reduces the cost of each listing."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["AWSLogs/"]
+						}
+					}
+					poll_secs: {
+						common:      true
+						description: "How long to wait between listings of the bucket."
+						required:    false
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+					checkpoint: {
+						common:      false
+						description: "Where to persist the objects already ingested. Storing the checkpoint in S3 without a `region` or an `endpoint` of its own uses the region and credentials of the source. The key of the checkpoint object should not start with the listed `prefix`, or the checkpoint is ingested as well."
+						required:    false
+						type:        configuration._source_checkpoint_store.type
+					}
+				}
+			}
+		}
 		sqs: {
 			common:      true
 			description: "SQS strategy options. Required if strategy=`sqs`."
@@ -187,6 +229,38 @@ components: sources: aws_s3: components._aws & {
 				```
 				"""
 		}
+		notifications: {
+			title: "Notification formats"
+			body: """
+				With `strategy` set to `sqs`, the queue can receive object notifications in the
+				following ways:
+
+				* Bucket notifications sent to the queue directly.
+				* Events matched by an [EventBridge](\(urls.aws_eventbridge_s3)) rule targeting
+				  the queue. Only `Object Created` events are processed, others are ignored.
+				* Log file notifications sent by [CloudTrail](\(urls.aws_cloudtrail_sns_notifications)).
+				* Either of the above sent through an SNS topic, with or without
+				  [raw message delivery](\(urls.aws_sns_raw_message_delivery)).
+				"""
+		}
+		listing: {
+			title: "Listing buckets"
+			body: """
+				With `strategy` set to `list`, the bucket is listed every `list.poll_secs`, instead of
+				relying on bucket notifications. The objects listed under `list.prefix` which weren't
+				ingested yet are then ingested one after the other.
+
+				The key and ETag of each ingested object are recorded in a checkpoint, once its
+				events are acknowledged if acknowledgements are enabled. An object is ingested again
+				if it is overwritten, as its ETag changes, and objects no longer listed are removed
+				from the checkpoint. The checkpoint is stored as a file in the data directory by
+				default, or in Redis or as an S3 object with `list.checkpoint.type` set to `redis`
+				or `s3`, so that it outlives the host running Vector.
+
+				As the whole prefix is listed each time, this strategy is best suited to buckets
+				whose objects are regularly deleted, such as with a lifecycle rule.
+				"""
+		}
 	}
 
 	permissions: iam: [
@@ -200,6 +274,14 @@ components: sources: aws_s3: components._aws & {
 				{
 					_action: "GetObject"
 				},
+				{
+					_action:       "ListBucket"
+					required_when: "[`strategy`](#strategy) is set to `list`"
+				},
+				{
+					_action:       "PutObject"
+					required_when: "[`strategy`](#strategy) is set to `list` and [`list.checkpoint.type`](#list.checkpoint.type) is set to `s3`"
+				},
 			]
 		},
 		{
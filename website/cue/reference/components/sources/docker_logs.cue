@@ -103,6 +103,9 @@ components: sources: docker_logs: {
 				If absent, Vector will try to use `DOCKER_HOST` environment variable.
 				If `DOCKER_HOST` is also absent, Vector will use default Docker local socket
 				(`/var/run/docker.sock` on Unix platforms, `//./pipe/docker_engine` on Windows).
+				On Unix platforms, if the Docker socket doesn't exist, Vector will use the Podman
+				socket of the current user (`$XDG_RUNTIME_DIR/podman/podman.sock`) or of the system
+				(`/run/podman/podman.sock`), whichever exists.
 				"""
 			required: false
 			type: string: {
@@ -226,6 +229,19 @@ components: sources: docker_logs: {
 				}
 			}
 		}
+		journalctl_path: {
+			common: false
+			description: """
+				The path to the `journalctl` binary, used to read the logs of containers using the
+				`journald` logging driver when the daemon can't serve them, such as Podman built
+				without journald support. If not set, Vector will search the path for `journalctl`.
+				"""
+			required: false
+			type: string: {
+				default: "journalctl"
+				examples: ["/usr/local/bin/journalctl"]
+			}
+		}
 		retry_backoff_secs: {
 			common: false
 			description: """
@@ -304,6 +320,33 @@ components: sources: docker_logs: {
 					required:    true
 					type: timestamp: {}
 				}
+				pod_id: {
+					description: "The ID of the Podman pod of the container, if it belongs to one."
+					required:    false
+					common:      false
+					type: string: {
+						default: null
+						examples: ["8a1b2c3d4e5f"]
+					}
+				}
+				pod_name: {
+					description: "The name of the Podman pod of the container, if it belongs to one."
+					required:    false
+					common:      false
+					type: string: {
+						default: null
+						examples: ["web"]
+					}
+				}
+				pod_label: {
+					description: "The labels of the Podman pod of the container, if it belongs to one."
+					required:    false
+					common:      false
+					type: object: {
+						examples: [{"app": "web"}]
+						options: {}
+					}
+				}
 				host: fields._local_host
 				"*": {
 					description: "Each container label is inserted with it's exact key/value pair."
@@ -359,6 +402,25 @@ components: sources: docker_logs: {
 				`partial_event_marker_field` option.
 				"""
 		}
+		podman: {
+			title: "Podman"
+			body: """
+				Vector can collect the logs of Podman containers through the Docker compatible API
+				of Podman, served on the Podman socket (`podman system service`). When connected
+				to Podman, Vector reads the pods of the containers from the Podman API on the same
+				Unix socket, and adds their ID, name and labels to the events as `pod_id`,
+				`pod_name` and `pod_label`.
+				"""
+		}
+		journald_logging_driver: {
+			title: "Journald Logging Driver"
+			body: """
+				Some daemons, such as Podman built without journald support, can't serve the logs of
+				containers using the [`journald` logging driver](\(urls.docker_logging_driver_journald)).
+				For these containers, Vector reads the logs from the journal by running
+				`journalctl`, so Vector must be able to read the journal of the host.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -0,0 +1,273 @@
+package metadata
+
+components: sources: ebpf: {
+	title: "eBPF"
+
+	description: """
+		Loads eBPF programs into the Linux kernel to observe the processes of the host,
+		reporting the programs they execute, the TCP connections they establish, and the
+		DNS queries they send, enriched with their cgroup, container and namespaces.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The kernel must be Linux 5.8 or later, built with BTF type information
+				(`CONFIG_DEBUG_INFO_BTF`), which is the case of most distributions.
+				""",
+			"""
+				Vector must run as root, or with the `CAP_BPF`, `CAP_PERFMON` and `CAP_SYS_RESOURCE`
+				capabilities. In a container, the cgroup hierarchy of the host must be mounted at
+				`cgroup_root` to resolve the cgroups of the processes.
+				""",
+			"""
+				This source isn't part of the default builds, and needs Vector to be built with the
+				`sources-ebpf` feature, `clang` and the `libbpf` headers.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		cgroup_root: {
+			common:      false
+			description: "The mount point of the cgroup v2 hierarchy, used to resolve the cgroups of the processes."
+			required:    false
+			type: string: {
+				default: "/sys/fs/cgroup"
+				examples: ["/host/sys/fs/cgroup"]
+			}
+		}
+		emit_logs: {
+			common:      true
+			description: "Whether to emit each kernel event as a log event."
+			required:    false
+			type: bool: default: true
+		}
+		emit_metrics: {
+			common:      true
+			description: "Whether to count the kernel events in metrics, tagged with the command of the process and its container ID."
+			required:    false
+			type: bool: default: false
+		}
+		events: {
+			common:      true
+			description: "The kinds of kernel events to collect."
+			required:    false
+			type: array: {
+				default: ["exec", "tcp", "dns"]
+				items: type: string: {
+					enum: {
+						exec: "Executions of programs by processes."
+						tcp:  "TCP connections established and closed by processes, in both directions."
+						dns:  "DNS queries sent by processes over UDP."
+					}
+				}
+			}
+		}
+		per_cpu_buffer_pages: {
+			common:      false
+			description: "The number of memory pages of each per-CPU buffer the kernel writes events to. Events are lost if a buffer fills up before they're read, and it must be a power of two."
+			required:    false
+			type: uint: {
+				default: 64
+				unit:    null
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A kernel event."
+		fields: {
+			event: {
+				description: "The kind of kernel event."
+				required:    true
+				type: string: enum: {
+					exec:        "A process executed a program."
+					tcp_connect: "A process connected to a TCP server."
+					tcp_accept:  "A process accepted a TCP connection."
+					tcp_close:   "A process closed a TCP connection."
+					dns_query:   "A process sent a DNS query."
+				}
+			}
+			process: {
+				description: "The process the event happened in, with its `pid`, `tid`, `ppid`, `uid`, `gid`, its command `comm`, and the `filename` of the program executed for `exec` events."
+				required:    true
+				type: object: {
+					examples: [{"pid": 1234, "tid": 1234, "ppid": 1, "uid": 0, "gid": 0, "comm": "curl", "filename": "/usr/bin/curl"}]
+					options: {}
+				}
+			}
+			namespaces: {
+				description: "The inode numbers of the `mnt`, `pid` and `net` namespaces of the process."
+				required:    true
+				type: object: {
+					examples: [{"mnt": 4026531840, "pid": 4026531836, "net": 4026531992}]
+					options: {}
+				}
+			}
+			cgroup: {
+				description: "The `id` of the cgroup of the process, and its `path` in the cgroup hierarchy if resolved."
+				required:    true
+				type: object: {
+					examples: [{"id": 4242, "path": "/system.slice/docker-3b1a5e4c1d9f.scope"}]
+					options: {}
+				}
+			}
+			container_id: {
+				description: "The ID of the container of the process, extracted from its cgroup."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["3b1a5e4c1d9f0e2a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c7d6e5f4a"]
+				}
+			}
+			source: {
+				description: "The local `ip` and `port` of TCP connections."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"ip": "10.0.0.1", "port": 43210}]
+					options: {}
+				}
+			}
+			destination: {
+				description: "The remote `ip` and `port` of TCP connections, or the DNS server queries are sent to."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"ip": "192.0.2.1", "port": 443}]
+					options: {}
+				}
+			}
+			dns: {
+				description: "The `id` of DNS queries, and their `questions`, with their `name` and `type`."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"id": 4242, "questions": [{"name": "vector.dev.", "type": "A"}]}]
+					options: {}
+				}
+			}
+			host:        fields._local_host
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["ebpf"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	output: metrics: {
+		_ebpf_tags: {
+			comm: {
+				description: "The command of the process."
+				required:    true
+				examples: ["curl"]
+			}
+			container_id: {
+				description: "The ID of the container of the process."
+				required:    false
+				examples: ["3b1a5e4c1d9f0e2a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c7d6e5f4a"]
+			}
+			host: {
+				description: "The hostname of the originating system."
+				required:    true
+				examples: [_values.local_host]
+			}
+		}
+
+		process_execs_total: {
+			description:       "The number of programs executed, when `emit_metrics` is enabled."
+			type:              "counter"
+			default_namespace: "ebpf"
+			tags:              _ebpf_tags
+		}
+		tcp_connections_total: {
+			description:       "The number of TCP connections established and closed, when `emit_metrics` is enabled."
+			type:              "counter"
+			default_namespace: "ebpf"
+			tags:              _ebpf_tags & {
+				event: {
+					description: "Whether the connection was established by connecting or accepting, or closed."
+					required:    true
+					examples: ["tcp_connect", "tcp_accept", "tcp_close"]
+				}
+			}
+		}
+		dns_queries_total: {
+			description:       "The number of DNS queries sent, when `emit_metrics` is enabled."
+			type:              "counter"
+			default_namespace: "ebpf"
+			tags:              _ebpf_tags & {
+				query_type: {
+					description: "The type of the first question of the query."
+					required:    false
+					examples: ["A", "AAAA"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		programs: {
+			title: "eBPF programs"
+			body: """
+				The source embeds eBPF programs, which are relocated against the BTF type information
+				of the running kernel when loaded (CO-RE), so that the same build of Vector runs on
+				any kernel with BTF. Process executions are observed with the `sched_process_exec`
+				tracepoint, TCP connections with probes of `tcp_connect`, `inet_csk_accept` and
+				`tcp_close`, and DNS queries with probes of `udp_sendmsg` and `udpv6_sendmsg`.
+
+				The programs write the events to per-CPU perf buffers, which are read by the source.
+				Events are lost, and counted in `component_discarded_events_total`, if the buffers
+				fill up faster than they're read, which can be avoided by increasing
+				`per_cpu_buffer_pages`.
+				"""
+		}
+		enrichment: {
+			title: "Enrichment"
+			body: """
+				Each event is enriched with the cgroup of its process, resolved by walking the cgroup
+				v2 hierarchy mounted at `cgroup_root`, and with the ID of its container, extracted
+				from the cgroup path of Docker, containerd, CRI-O and Podman containers. The inode
+				numbers of the namespaces of the process allow matching events of processes sharing
+				a namespace, such as the containers of a Kubernetes pod.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -78,6 +78,21 @@ components: sources: exec: {
 				unit:    "bytes"
 			}
 		}
+		kill_after_secs: {
+			common:      false
+			description: "The amount of time in seconds after which a running command is killed. For scheduled commands, this defaults to the time until the next run."
+			required:    false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		include_exit_event: {
+			common:      false
+			description: "Generate an event when the command exits, holding its exit code, or the signal which terminated it, along with the last lines written to stderr if `include_stderr` is enabled."
+			required:    false
+			type: bool: default: false
+		}
 		scheduled: {
 			common:      true
 			description: "The scheduled options."
@@ -95,6 +110,26 @@ components: sources: exec: {
 							unit:    "seconds"
 						}
 					}
+					cron: {
+						common:        false
+						description:   "A cron expression, with a leading seconds field, scheduling the command runs instead of `exec_interval_secs`. The expression is evaluated in UTC."
+						relevant_when: "mode = `scheduled`"
+						required:      false
+						type: string: {
+							default: null
+							examples: ["0 */5 * * * *"]
+						}
+					}
+					jitter_secs: {
+						common:        false
+						description:   "The maximum amount of time in seconds each run is randomly delayed by, to spread the runs of many Vector instances sharing the same schedule."
+						relevant_when: "mode = `scheduled`"
+						required:      false
+						type: uint: {
+							default: 0
+							unit:    "seconds"
+						}
+					}
 				}
 			}
 		}
@@ -122,6 +157,16 @@ components: sources: exec: {
 							unit:    "seconds"
 						}
 					}
+					respawn_backoff_max_secs: {
+						common:        false
+						description:   "The maximum interval in seconds between restarting streaming commands. If set, the interval doubles each time the command exits, starting from `respawn_interval_secs`, until the command runs for at least this amount of time."
+						relevant_when: "mode = `streaming`"
+						required:      false
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
 				}
 			}
 		}
@@ -159,6 +204,31 @@ components: sources: exec: {
 					}
 				}
 			}
+			exit_code: {
+				description:   "The exit code of the command, for the event generated when it exits."
+				relevant_when: "include_exit_event = true"
+				required:      false
+				type: int: {
+					examples: [0, 1]
+				}
+			}
+			signal: {
+				description:   "The signal which terminated the command, for the event generated when it exits."
+				relevant_when: "include_exit_event = true"
+				required:      false
+				type: uint: {
+					examples: [9, 15]
+					unit: null
+				}
+			}
+			stderr: {
+				description:   "The last lines written to stderr by the command, for the event generated when it exits."
+				relevant_when: "include_exit_event = true"
+				required:      false
+				type: array: items: type: string: {
+					examples: ["curl: (6) Could not resolve host: example.com"]
+				}
+			}
 		}
 	}
 
@@ -49,6 +49,7 @@ components: sources: file: {
 
 	configuration: {
 		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
 		exclude: {
 			common:      false
 			description: "Array of file patterns to exclude. [Globbing](#globbing) is supported.*Takes precedence over the [`include` option](#include).*"
@@ -226,6 +227,31 @@ components: sources: file: {
 			required:    false
 			type: bool: default: false
 		}
+		use_notifications: {
+			category: "Reading"
+			common:   false
+			description: """
+				Read files when their changes are notified by the operating system, rather than polling them. This
+				relies on inotify on Linux, FSEvents on macOS, and `ReadDirectoryChangesW` on Windows, watching the
+				directories holding the files. With many files, this reduces CPU usage and the delay before new lines
+				are read. Files are still polled along with the search for new files, every `glob_minimum_cooldown_ms`,
+				which also covers file systems not supporting notifications, such as network file systems.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		use_io_uring: {
+			category: "Reading"
+			common:   false
+			description: """
+				Read files through an io_uring, on Linux. The files to read, and to fingerprint, are read in batches
+				submitted at once, into buffers registered with the kernel, which reduces the system calls made when
+				tailing many files. If io_uring is not supported by the kernel, or can't be set up, such as when the
+				limit of locked memory is too low, files are read as usual.
+				"""
+			required: false
+			type: bool: default: false
+		}
 	}
 
 	output: logs: line: {
@@ -283,6 +309,18 @@ components: sources: file: {
 				"""
 		}
 
+		checkpoint_stores: {
+			title: "Checkpoint stores"
+			body: """
+				With `checkpoint_store` set to a store other than a file, such as Redis or S3, the
+				checkpoints file in the data directory is restored from the store when it doesn't
+				exist yet, such as when Vector runs on a new host. The checkpoints are written
+				through to the store whenever they are persisted, so that an instance replacing
+				another one resumes reading files from where it left off, as long as it reads the
+				same files.
+				"""
+		}
+
 		compressed_files: {
 			title: "Compressed Files"
 			body: """
@@ -373,6 +411,16 @@ components: sources: file: {
 				read it uncompressed to identify it, and then ensure it has all of
 				the data, including any written in a gap between Vector's last read
 				and the actual rotation event.
+
+				When the rotated file is compressed right away, Vector can still
+				recover that gap. If a watched file is truncated or removed before
+				Vector has read all of it, Vector looks in the same directory for a
+				rotated copy whose name starts with the name of the file, such as
+				`app.log.1` or `app.log.1.gz` for `app.log`. Copies compressed with
+				Gzip or Zstandard are decompressed to identify them. Vector then reads
+				the rest of the file from that copy. This requires one of the checksum
+				`fingerprint` strategies, since compressed copies don't share the
+				device and inode of the original file.
 				"""
 		}
 
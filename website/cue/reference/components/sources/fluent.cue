@@ -69,6 +69,55 @@ components: sources: fluent: {
 				unit:    "concurrency"
 			}
 		}
+		security: {
+			common:      false
+			description: "Authenticates the clients with the shared-key handshake of the forward protocol."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					self_hostname: {
+						common:      false
+						description: "The hostname the source identifies itself with to the clients. Defaults to the hostname of the machine."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["vector-aggregator"]
+						}
+					}
+					shared_key: {
+						description: "The key shared with the clients, which they have to prove they know to connect."
+						required:    true
+						type: string: {
+							examples: ["${FLUENT_SHARED_KEY}"]
+						}
+					}
+					users: {
+						common:      false
+						description: "The users the clients can authenticate as. If empty, the clients are only authenticated with the shared key."
+						required:    false
+						type: array: {
+							default: []
+							items: type: object: {
+								examples: [{"username": "fluent-bit", "password": "${FLUENT_PASSWORD}"}]
+								options: {
+									username: {
+										description: "The name of the user."
+										required:    true
+										type: string: examples: ["fluent-bit"]
+									}
+									password: {
+										description: "The password of the user."
+										required:    true
+										type: string: examples: ["${FLUENT_PASSWORD}"]
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
 	}
 
 	output: logs: line: {
@@ -181,15 +230,11 @@ components: sources: fluent: {
 		secure_mode: {
 			title: "Secure forward mode support"
 			body:  """
-				The `fluent` source currently supports using TLS, but does not support the authentication part of the
-				Fluent protocol including:
-
-				- Shared key
-				- Username and password
-
-				And so these options of the secure forward output plugins for Fluent and Fluent Bit cannot be used.
-
-				If you would find this useful, [please let us know](\(urls.vector_repo)/issues/7532).
+				The `fluent` source supports TLS, and the authentication part of the Fluent protocol with the
+				`security` option: clients have to prove they know the shared key, and the password of one of the
+				`users` if any are configured, in a handshake before sending events. This matches the `<security>`
+				section of the Fluentd `forward` output, and the `Shared_Key`, `Username` and `Password` options of
+				the Fluent Bit `forward` output. Clients sending events without authenticating are disconnected.
 				"""
 		}
 	}
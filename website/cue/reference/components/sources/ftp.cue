@@ -0,0 +1,271 @@
+package metadata
+
+components: sources: ftp: {
+	title: "FTP / SFTP"
+
+	description: """
+		Periodically lists the files of an SFTP or FTP server, and reads the new or changed
+		files matching the configured patterns.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		codecs: {
+			enabled:         true
+			default_framing: "newline_delimited"
+		}
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			checkpoint: enabled: true
+			from: service:       services.ftp
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				With SFTP, the host key of the server must be listed in the `known_hosts` file, as
+				connections to unknown hosts are refused.
+				""",
+		]
+		warnings: [
+			"""
+				The files of a server are read by a single Vector instance, as they aren't leased
+				between instances. Running several instances against the same files reads each
+				file several times.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		after_delivery: {
+			common:      false
+			description: "What to do with the files read, once their events are delivered."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					type: {
+						common:      true
+						description: "The action applied to the files."
+						required:    false
+						type: string: {
+							default: "keep"
+							enum: {
+								keep:   "Leave the files as they are. They are read again if they change."
+								delete: "Delete the files."
+								move:   "Move the files to another directory."
+							}
+						}
+					}
+					directory: {
+						description:   "The directory to move the files to. It must not be matched by `include`, or the files would be read again."
+						relevant_when: "type = `move`"
+						required:      true
+						type: string: {
+							examples: ["/archive"]
+						}
+					}
+				}
+			}
+		}
+		checkpoint_store: configuration._source_checkpoint_store
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the files read, and to download them. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the server. The scheme selects the protocol, either `sftp` or `ftp`. FTP sessions are secured with `AUTH TLS` when `tls.enabled` is set."
+			required:    true
+			type: string: {
+				examples: ["sftp://files.example.com", "ftp://files.example.com:2121"]
+			}
+		}
+		exclude: {
+			common:      false
+			description: "Absolute glob patterns of the remote files to skip."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/outgoing/**/*.tmp"]
+				}
+			}
+		}
+		include: {
+			description: "Absolute glob patterns of the remote files to read."
+			required:    true
+			type: array: items: type: string: {
+				examples: ["/outgoing/**/*.log"]
+			}
+		}
+		known_hosts_path: {
+			common:      false
+			description: "The path of the [`known_hosts`](\(urls.ssh_known_hosts)) file the host key of the server is verified against. Only used with SFTP."
+			required:    false
+			type: string: {
+				default: "~/.ssh/known_hosts"
+			}
+		}
+		min_age_secs: {
+			common:      false
+			description: "The minimum time since a file was last modified before it is read. This keeps files that are still being uploaded from being read."
+			required:    false
+			type: uint: {
+				default: 0
+				unit:    "seconds"
+			}
+		}
+		password: {
+			common:      true
+			description: "The password of the user."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${FTP_PASSWORD}"]
+			}
+		}
+		poll_interval_secs: {
+			common:      true
+			description: "The interval between listings of the remote files."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		private_key_passphrase: {
+			common:      false
+			description: "The passphrase of the private key."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${SFTP_KEY_PASSPHRASE}"]
+			}
+		}
+		private_key_path: {
+			common:      false
+			description: "The path of the private key to authenticate with, instead of a password. Only used with SFTP."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/etc/vector/id_ed25519"]
+			}
+		}
+		timeout_secs: {
+			common:      false
+			description: "The timeout of the operations on the server."
+			required:    false
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+		username: {
+			description: "The user to log in as."
+			required:    true
+			type: string: {
+				examples: ["vector"]
+			}
+		}
+	}
+
+	output: logs: line: {
+		description: "An individual event decoded from a file."
+		fields: {
+			message: {
+				description: "The decoded line of the file."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			path: {
+				description: "The remote path of the file."
+				required:    true
+				type: string: {
+					examples: ["/outgoing/2020-10-01/access.log"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["ftp"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		polling: {
+			title: "Polling"
+			body:  """
+				Every `poll_interval_secs`, the source connects to the server, lists the
+				directories matched by `include`, and reads the files that are new or whose size
+				or modification time changed, once they are at least `min_age_secs` old. Each file
+				is downloaded into the data directory before it is decoded, so that large files
+				aren't held in memory.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body:  """
+				The size and modification time of the files read are saved to a file in the data
+				directory, once their events are delivered. Files are read again from their start
+				when they change, or when Vector stops before their events are delivered. Files
+				removed from the server are forgotten.
+				"""
+		}
+		after_delivery: {
+			title: "After delivery"
+			body:  """
+				Once the events of a file are delivered, the file is left as is, deleted, or moved
+				to another directory, as set by `after_delivery`. Failed deletions and moves are
+				retried at the next listing.
+				"""
+		}
+		host_key_verification: {
+			title: "Host key verification"
+			body:  """
+				With SFTP, the host key of the server is always verified against the
+				`known_hosts_path` file, such as populated by `ssh-keyscan`, before the
+				credentials are sent.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
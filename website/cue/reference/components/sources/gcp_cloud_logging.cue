@@ -0,0 +1,202 @@
+package metadata
+
+components: sources: gcp_cloud_logging: {
+	title: "GCP Cloud Logging"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.gcp_operations_logs
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The GCP Cloud Logging source requires a Pub/Sub subscription to the topic of a
+				[log sink](\(urls.gcp_cloud_logging_pubsub_sink)).
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements:   configuration._source_acknowledgements
+		ack_deadline_secs:  components.sources.gcp_pubsub.configuration.ack_deadline_secs
+		api_key:            configuration._gcp_api_key
+		credentials_path:   configuration._gcp_credentials_path
+		endpoint:           components.sources.gcp_pubsub.configuration.endpoint
+		full_response_size: components.sources.gcp_pubsub.configuration.full_response_size
+		keepalive_secs:     components.sources.gcp_pubsub.configuration.keepalive_secs
+		max_concurrency:    components.sources.gcp_pubsub.configuration.max_concurrency
+		poll_time_seconds:  components.sources.gcp_pubsub.configuration.poll_time_seconds
+		project: {
+			description: "The project of the subscription."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		retry_delay_secs:   components.sources.gcp_pubsub.configuration.retry_delay_secs
+		subscription: {
+			description: "The Pub/Sub subscription receiving the log entries exported by a log sink."
+			required:    true
+			type: string: {
+				examples: ["vector-logs"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual [log entry](\(urls.gcp_cloud_logging_log_entry))."
+		fields: {
+			message: {
+				description: "The `textPayload` of the log entry, or the `message` field of its `jsonPayload`."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["connection refused"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `timestamp` of the log entry, or its `receiveTimestamp` if missing."
+			}
+			payload: {
+				description: "The `jsonPayload` or `protoPayload` of the log entry."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"latency_ms": 12, "message": "request served"}]
+				}
+			}
+			payload_type: {
+				description: "The type of the `protoPayload` of the log entry."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["type.googleapis.com/google.cloud.audit.AuditLog"]
+				}
+			}
+			audit: {
+				description: "The commonly used fields of an [audit log](\(urls.gcp_cloud_logging_audit_log)): `service_name`, `method_name`, `resource_name`, `principal_email`, `caller_ip`, `status_code` and `status_message`."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"service_name": "storage.googleapis.com", "method_name": "storage.buckets.delete", "principal_email": "admin@example.com"}]
+				}
+			}
+			severity: {
+				description: "The [severity](\(urls.gcp_stackdriver_severity)) of the log entry."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["ERROR"]
+				}
+			}
+			resource: {
+				description: "The monitored resource of the log entry, with its `type` and `labels`."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"type": "k8s_container", "labels": {"cluster_name": "prod"}}]
+				}
+			}
+			labels: {
+				description: "The labels of the log entry."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"k8s-pod/app": "web"}]
+				}
+			}
+			trace: {
+				description: "The trace of the log entry, such as `projects/my-project/traces/0123456789abcdef`."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["projects/my-project/traces/0123456789abcdef"]
+				}
+			}
+			trace_id: {
+				description: "The ID of the trace of the log entry."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["0123456789abcdef"]
+				}
+			}
+			span_id: {
+				description: "The ID of the span of the log entry."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["000000000000004a"]
+				}
+			}
+			log_name: {
+				description: "The name of the log of the log entry."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["projects/my-project/logs/stdout"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		log_entries: {
+			title: "Log entries"
+			body: """
+				Log entries are pulled from the subscription the same way as with the `gcp_pubsub`
+				source, and each is decoded into an event. The fields of log entries are renamed to
+				snake case, such as `insertId` becoming `insert_id`, and their `httpRequest`,
+				`operation` and `sourceLocation` objects are kept as is.
+
+				The `protoPayload` of audit logs, whose type is
+				`type.googleapis.com/google.cloud.audit.AuditLog`, is kept as `payload`, and its
+				commonly used fields are additionally set in the `audit` object.
+
+				Messages that aren't log entries are dropped, and reported with an error.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total:components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_event_bytes_total:components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_bytes_total:components.sources.internal_metrics.output.metrics.component_received_bytes_total
+	}
+}
@@ -0,0 +1,216 @@
+package metadata
+
+components: sources: gcp_cloud_monitoring: {
+	title: "GCP Cloud Monitoring"
+
+	description: """
+		Periodically queries the time series of Cloud Monitoring, such as the metrics of
+		GCP services, and maps their data points into metrics.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.gcp_cloud_monitoring
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The credentials used by the source require the `monitoring.timeSeries.list`
+				permission on the project, such as granted by the `roles/monitoring.viewer` role.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		api_key:          configuration._gcp_api_key
+		credentials_path: configuration._gcp_credentials_path
+		delay_secs: {
+			common:      false
+			description: "How far behind the current time the queried window ends. Data points can take a few minutes to be queryable after they are written, and are missed if they land in a window that was already queried."
+			required:    false
+			type: uint: {
+				default: 0
+				unit:    "seconds"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint of Cloud Monitoring, from which to query time series."
+			required:    false
+			type: string: {
+				default: "https://monitoring.googleapis.com"
+				examples: ["https://monitoring.googleapis.com"]
+			}
+		}
+		namespace: {
+			common:      false
+			description: "The namespace of the metrics. Disabled if empty."
+			required:    false
+			type: string: {
+				default: "gcp"
+			}
+		}
+		project: {
+			description: "The project whose time series are queried."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		queries: {
+			description: "The queries selecting the time series to collect."
+			required:    true
+			type: array: items: type: object: options: {
+				filter: {
+					description: "The [monitoring filter](\(urls.gcp_cloud_monitoring_filters)) selecting the time series. The filter must select a single metric type."
+					required:    true
+					type: string: {
+						examples: ["metric.type = \"compute.googleapis.com/instance/cpu/utilization\""]
+					}
+				}
+				aggregation: {
+					common:      false
+					description: "How the data points of the time series are aligned and combined. When unset, the raw data points are collected."
+					required:    false
+					type: object: options: {
+						alignment_period_secs: {
+							common:      false
+							description: "The period over which the data points of each time series are aligned."
+							required:    false
+							type: uint: {
+								default: 60
+								unit:    "seconds"
+							}
+						}
+						per_series_aligner: {
+							common:      true
+							description: "How the data points of each time series are [aligned](\(urls.gcp_cloud_monitoring_aligners))."
+							required:    false
+							type: string: {
+								default: null
+								examples: ["ALIGN_RATE", "ALIGN_MEAN"]
+							}
+						}
+						cross_series_reducer: {
+							common:      false
+							description: "How the aligned time series are [combined](\(urls.gcp_cloud_monitoring_reducers)). Requires `per_series_aligner` to be set."
+							required:    false
+							type: string: {
+								default: null
+								examples: ["REDUCE_SUM"]
+							}
+						}
+						group_by_fields: {
+							common:      false
+							description: "The labels preserved when the time series are combined."
+							required:    false
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["resource.label.zone"]
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		scrape_interval_secs: {
+			common:      true
+			description: "The interval between queries. Each query reads the data points written since the previous one."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	how_it_works: {
+		querying: {
+			title: "Querying"
+			body:  """
+				Every `scrape_interval_secs`, each query lists the time series matching its
+				filter, with their data points written since its previous query. Queries that
+				fail are retried from the same start, so that no data points are missed.
+				"""
+		}
+		metrics: {
+			title: "Metrics"
+			body:  """
+				Metrics are named after their type, without the domain of the service, such as
+				`instance_cpu_utilization` for `compute.googleapis.com/instance/cpu/utilization`.
+				They are tagged with the labels of the time series, its `metric_type`, the
+				`resource_type` of its monitored resource, and the labels of that resource
+				prefixed with `resource_`, such as `resource_zone`.
+
+				Data points are mapped according to the
+				[kind](\(urls.gcp_cloud_monitoring_metric_kinds)) of their metric: `GAUGE` metrics
+				into absolute gauges, `DELTA` metrics into incremental counters, and `CUMULATIVE`
+				metrics into absolute counters. Distributions are mapped into histograms, booleans
+				into gauges of `0` or `1`, and strings are skipped.
+				"""
+		}
+	}
+
+	output: metrics: {
+		_extra_tags: {
+			"metric_type": {
+				description: "The type of the metric of the time series."
+				examples: ["compute.googleapis.com/instance/cpu/utilization"]
+				required: true
+			}
+			"resource_type": {
+				description: "The type of the monitored resource of the time series."
+				examples: ["gce_instance"]
+				required: true
+			}
+		}
+
+		counter: output._passthrough_counter & {
+			tags: _extra_tags
+		}
+		gauge: output._passthrough_gauge & {
+			tags: _extra_tags
+		}
+		histogram: output._passthrough_histogram & {
+			tags: _extra_tags
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:              components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_seconds:             components.sources.internal_metrics.output.metrics.collect_duration_seconds
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
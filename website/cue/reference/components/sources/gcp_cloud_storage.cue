@@ -0,0 +1,223 @@
+package metadata
+
+components: sources: gcp_cloud_storage: {
+	title: "GCP Cloud Storage"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.gcp_cloud_storage
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: [
+			"""
+				The GCP Cloud Storage source requires a Pub/Sub subscription to a topic receiving
+				[notifications](\(urls.gcp_cloud_storage_pubsub_notifications)) of the bucket.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		api_key:          configuration._gcp_api_key
+		client_concurrency: {
+			common:      false
+			description: "Number of concurrent tasks to create for pulling notifications. Defaults to the number of available CPUs on the system."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [5]
+				unit: "concurrency"
+			}
+		}
+		compression: {
+			common:      false
+			description: "The compression format of the objects."
+			required:    false
+			type: string: {
+				default: "auto"
+				enum: {
+					auto: "Vector will try to determine the compression format of the object from its: `Content-Encoding` metadata, `Content-Type` metadata, and name suffix (e.g. `.gz`). It will fallback to 'none' if it cannot determine the compression."
+					gzip: "GZIP format."
+					none: "Uncompressed."
+				}
+			}
+		}
+		credentials_path: configuration._gcp_credentials_path
+		max_messages: {
+			common:      false
+			description: "The maximum number of notifications to pull at once."
+			required:    false
+			type: uint: {
+				default: 10
+				examples: [10, 100]
+				unit: null
+			}
+		}
+		project: {
+			description: "The project of the subscription."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		pubsub_endpoint: {
+			common:      false
+			description: "The endpoint of Pub/Sub, from which to pull notifications."
+			required:    false
+			type: string: {
+				default: "https://pubsub.googleapis.com"
+				examples: ["https://us-central1-pubsub.googleapis.com"]
+			}
+		}
+		retry_delay_secs: {
+			common:      false
+			description: "The amount of time to wait between retry attempts after an error."
+			required:    false
+			type: float: {
+				default: 1.0
+				examples: [0.5]
+				unit: "seconds"
+			}
+		}
+		storage_endpoint: {
+			common:      false
+			description: "The endpoint of Cloud Storage, from which to download objects."
+			required:    false
+			type: string: {
+				default: "https://storage.googleapis.com"
+				examples: ["https://storage.googleapis.com"]
+			}
+		}
+		subscription: {
+			description: "The Pub/Sub subscription receiving the notifications of the bucket."
+			required:    true
+			type: string: {
+				examples: ["vector-logs-notifications"]
+			}
+		}
+	}
+
+	output: logs: object: {
+		description: "A line from a Cloud Storage object."
+		fields: {
+			message: {
+				description: "A line from the object."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the object was created, from its notification. Defaults to the current timestamp if this information is missing."
+			}
+			bucket: {
+				description: "The bucket of the object the line came from."
+				required:    true
+				type: string: {
+					examples: ["my-bucket"]
+				}
+			}
+			object: {
+				description: "The object the line came from."
+				required:    true
+				type: string: {
+					examples: ["logs/2022/08/01/out.log.gz"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		notifications: {
+			title: "Notifications"
+			body: """
+				The `gcp_cloud_storage` source pulls the
+				[notifications](\(urls.gcp_cloud_storage_pubsub_notifications)) of a bucket from a
+				Pub/Sub subscription, and downloads the generation of the object created by each
+				`OBJECT_FINALIZE` notification, outputting one event per line. Other notifications
+				are ignored, and notifications can be sent with or without a payload.
+
+				A notification is acknowledged once its object is processed, which is once its
+				events are delivered if acknowledgements are enabled. Otherwise, it is made
+				available again right away, to be retried. The acknowledgement deadline of the
+				subscription should be longer than the time it takes to process an object, or
+				its notification is delivered again in the meantime.
+				"""
+		}
+		compression: {
+			title: "Compression"
+			body: """
+				Objects stored with `Content-Encoding: gzip` are
+				[decompressed](\(urls.gcp_cloud_storage_transcoding)) by Cloud Storage when
+				downloaded. Other objects are decompressed by Vector according to `compression`.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "storage"
+
+			policies: [
+				{
+					_action: "objects.get"
+				},
+			]
+		},
+		{
+			platform: "gcp"
+			_service: "pubsub"
+
+			policies: [
+				{
+					_action: "subscriptions.consume"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,121 @@
+package metadata
+
+components: sources: grpc: {
+	_port: 6100
+
+	title: "gRPC"
+
+	description: """
+		Serves a [gRPC](\(urls.grpc)) service defined by a user-provided
+		[file descriptor set](\(urls.protobuf_descriptor_set)), and decodes each request
+		message it receives into a log event, so that services can push events to Vector
+		without going through HTTP and JSON.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.grpc
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: enabled: false
+			keepalive: enabled:            true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		address: {
+			description: "The address to listen for connections on. It _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+			}
+		}
+		desc_file: {
+			description: "The path to the file descriptor set defining the service, as generated by `protoc --include_imports --descriptor_set_out`."
+			required:    true
+			type: string: {
+				examples: ["/etc/vector/protos/events.desc"]
+			}
+		}
+		service: {
+			description: "The fully qualified name of the service to serve. The request messages of its unary and client streaming methods are decoded into events, and the responses are empty messages. Server streaming methods are not supported."
+			required:    true
+			type: string: {
+				examples: ["mypackage.MyService"]
+			}
+		}
+	}
+
+	output: logs: message: {
+		description: "A request message received by the service."
+		fields: {
+			"*": {
+				description: "The fields of the message, named as in its definition."
+				required:    true
+				type: "*": {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["grpc"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		decoding: {
+			title: "Decoding"
+			body: """
+				Each request message is decoded with its descriptor, and its fields become the
+				fields of the event, following the JSON mapping of Protocol Buffers, except that
+				fields keep the names of their definition. Fields set to their default value are
+				omitted. Once the event is delivered, an empty response message is returned, or
+				an error status if it couldn't be delivered while acknowledgements are enabled.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
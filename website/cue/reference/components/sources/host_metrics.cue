@@ -68,21 +68,23 @@ components: sources: host_metrics: {
 
 	configuration: {
 		collectors: {
-			description: "The list of host metric collector services to use. Defaults to all collectors."
+			description: "The list of host metric collector services to use. Defaults to all collectors, except `process`."
 			common:      true
 			required:    false
 			type: array: {
-				default: ["cgroups", "cpu", "disk", "filesystem", "load", "host", "memory", "network"]
+				default: ["cgroups", "cpu", "disk", "filesystem", "gpu", "load", "host", "memory", "network"]
 				items: type: string: {
 					enum: {
 						cgroups:    "Metrics related to Linux control groups."
 						cpu:        "Metrics related to CPU utilization."
 						disk:       "Metrics related to disk I/O utilization."
 						filesystem: "Metrics related to filesystem space utilization."
+						gpu:        "Metrics related to GPU utilization, memory, temperature, and power (Linux only)."
 						load:       "Load average metrics (UNIX only)."
 						host:       "Metrics related to host"
 						memory:     "Metrics related to memory utilization."
 						network:    "Metrics related to network utilization."
+						process:    "Metrics related to the CPU, memory, file descriptor, and I/O utilization of each process (Linux only)."
 					}
 				}
 			}
@@ -353,6 +355,68 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		gpu: {
+			common: false
+			description: #"""
+				Options for the "gpu" metrics collector.
+
+				Note: this collector is only available on Linux systems. NVIDIA GPUs are read through the NVML library
+				installed with their driver, and AMD GPUs through the `rocm-smi` command when NVML isn't available.
+				"""#
+			required: false
+			type: object: options: {
+				devices: {
+					common:      false
+					required:    false
+					description: "Lists of device index patterns to include or exclude."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of device index patterns for which to gather GPU metrics.
+
+								Defaults to including all devices.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["0", "1"]
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of device index patterns for which to gather GPU metrics.
+
+								Defaults to excluding no devices.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["0", "1"]
+								}
+							}
+						}
+					}
+				}
+				rocm_smi_path: {
+					common:      false
+					required:    false
+					description: "The path to the `rocm-smi` command, used to read the metrics of AMD GPUs."
+					type: string: {
+						default: "rocm-smi"
+						examples: ["/opt/rocm/bin/rocm-smi"]
+					}
+				}
+			}
+		}
 		network: {
 			common:      false
 			description: #"Options for the "network" metrics collector."#
@@ -401,6 +465,141 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		process: {
+			common: false
+			description: #"""
+				Options for the "process" metrics collector.
+
+				Note: this collector is only available on Linux systems. As it reports metrics for each process, it is
+				only enabled when listed in `collectors`.
+				"""#
+			required: false
+			type: object: options: {
+				cmdlines: {
+					common:      false
+					required:    false
+					description: "Lists of command line patterns to include or exclude. The command line of a process is its arguments joined with spaces."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of command line patterns for which to gather process metrics.
+
+								Defaults to including all processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["java *", "* --config /etc/*"]
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of command line patterns for which to gather process metrics.
+
+								Defaults to excluding no processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["java *", "* --config /etc/*"]
+								}
+							}
+						}
+					}
+				}
+				names: {
+					common:      false
+					required:    false
+					description: "Lists of process name patterns to include or exclude. The name of a process is the file name of its executable, truncated to 15 characters."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which to gather process metrics.
+
+								Defaults to including all processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["nginx", "postgres*"]
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which to gather process metrics.
+
+								Defaults to excluding no processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["nginx", "postgres*"]
+								}
+							}
+						}
+					}
+				}
+				users: {
+					common:      false
+					required:    false
+					description: "Lists of user name patterns to include or exclude. The processes of users without a name are matched on their user ID."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of user name patterns for which to gather process metrics.
+
+								Defaults to including all processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["www-data", "1000"]
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of user name patterns for which to gather process metrics.
+
+								Defaults to excluding no processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["www-data", "1000"]
+								}
+							}
+						}
+					}
+				}
+			}
+		}
 	}
 
 	output: metrics: {
@@ -455,6 +654,29 @@ components: sources: host_metrics: {
 		filesystem_used_bytes:  _host & _filesystem_bytes & {description: "The number of bytes used on the named filesystem."}
 		filesystem_used_ratio:  _host & _filesystem_bytes & {description: "The ratio between used and total bytes on the named filesystem."}
 
+		// Host GPU
+		gpu_memory_free_bytes:        _host & _gpu_gauge & {description: "The number of bytes of GPU memory not used."}
+		gpu_memory_total_bytes:       _host & _gpu_gauge & {description: "The total number of bytes of GPU memory."}
+		gpu_memory_used_bytes:        _host & _gpu_gauge & {description: "The number of bytes of GPU memory used."}
+		gpu_memory_utilization_ratio: _host & _gpu_gauge & {description: "The ratio of time the GPU memory was read or written over the last sample period."}
+		gpu_power_watts:              _host & _gpu_gauge & {description: "The power drawn by the GPU, in watts."}
+		gpu_temperature_celsius:      _host & _gpu_gauge & {description: "The temperature of the GPU, in degrees Celsius."}
+		gpu_utilization_ratio:        _host & _gpu_gauge & {description: "The ratio of time the GPU was running kernels over the last sample period."}
+
+		// Host processes
+		process_cpu_seconds_total: _host & _process_counter & {
+			description: "The number of CPU seconds accumulated by the process in different operating modes."
+			tags: mode: {
+				description: "Which mode the process was running in during the given time."
+				required:    true
+				examples: ["user", "system"]
+			}
+		}
+		process_memory_rss_bytes:    _host & _process_gauge & {description:   "The number of bytes of main memory resident for the process, not reported for kernel threads."}
+		process_open_fds:            _host & _process_gauge & {description:   "The number of file descriptors opened by the process."}
+		process_read_bytes_total:    _host & _process_counter & {description: "The accumulated number of bytes read in from storage by the process."}
+		process_written_bytes_total: _host & _process_counter & {description: "The accumulated number of bytes written out to storage by the process."}
+
 		// Host load
 		load1:  _host & _loadavg & {description: "System load averaged over the last 1 second."}
 		load5:  _host & _loadavg & {description: "System load averaged over the last 5 seconds."}
@@ -543,6 +765,28 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		_gpu_gauge: {
+			type: "gauge"
+			tags: _host_metrics_tags & {
+				collector: examples: ["gpu"]
+				device: {
+					description: "The index of the GPU."
+					required:    true
+					examples: ["0", "1"]
+				}
+				model: {
+					description: "The product name of the GPU."
+					required:    false
+					examples: ["NVIDIA A100-SXM4-40GB", "AMD Instinct MI210"]
+				}
+				vendor: {
+					description: "The vendor of the GPU."
+					required:    true
+					examples: ["nvidia", "amd"]
+				}
+			}
+			relevant_when: "OS is Linux"
+		}
 		_loadavg: {
 			type: "gauge"
 			tags: _host_metrics_tags & {
@@ -582,6 +826,34 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		_process_tags: _host_metrics_tags & {
+			collector: examples: ["process"]
+			name: {
+				description: "The name of the process."
+				required:    true
+				examples: ["nginx", "postgres"]
+			}
+			pid: {
+				description: "The ID of the process."
+				required:    true
+				examples: ["1234"]
+			}
+			user: {
+				description: "The name of the user running the process, or its ID if it has no name."
+				required:    true
+				examples: ["www-data", "1000"]
+			}
+		}
+		_process_counter: {
+			type:          "counter"
+			tags:          _process_tags
+			relevant_when: "OS is Linux"
+		}
+		_process_gauge: {
+			type:          "gauge"
+			tags:          _process_tags
+			relevant_when: "OS is Linux"
+		}
 		_network_nomac: _network_gauge & {relevant_when: "OS is not macOS"}
 	}
 
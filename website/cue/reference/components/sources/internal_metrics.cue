@@ -123,6 +123,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		anomalies_detected_total: {
+			description:       "The number of anomalous metric values detected by the anomaly_detection transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		api_started_total: {
 			description:       "The number of times the Vector GraphQL API has been started."
 			type:              "counter"
@@ -505,12 +511,91 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		component_acknowledged_events_total: {
+			description:       "The number of events a sink finished handling, by whether it delivered them and why it didn't."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				outcome: {
+					description: "Whether the sink delivered the events."
+					required:    true
+					enum: {
+						"delivered": "The events were delivered."
+						"errored":   "The events failed to be delivered, such as once retries were exhausted."
+						"rejected":  "The events were rejected by the downstream service, such as with a 4xx response or a partial bulk failure."
+					}
+				}
+				error_class: {
+					description: "Why the sink failed to deliver the events, for the `errored` and `rejected` outcomes."
+					required:    false
+					enum: {
+						"timeout":            "The request timed out."
+						"io":                 "The request failed with an I/O error, such as a refused connection."
+						"request_failed":     "The request failed with another error."
+						"transient_response": "The downstream service responded with a retriable error."
+						"failed_response":    "The downstream service responded with a permanent error."
+						"http_server_error":  "The downstream service responded with a 5xx status."
+						"http_client_error":  "The downstream service responded with a 4xx status."
+						"bulk_item_failures": "The downstream service failed to handle some of the items of a bulk request."
+					}
+				}
+			}
+		}
+		component_circuit_breaker_open: {
+			description:       "Whether the circuit breaker of a sink is open, 1 when it is and 0 once it closed again."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_circuit_breaker_probes_total: {
+			description:       "The number of batches of events handed to a sink to probe whether it recovered, while its circuit breaker is open."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_circuit_breaker_trips_total: {
+			description:       "The number of times the circuit breaker of a sink opened."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				fallback: {
+					description: "What happens to the events while the circuit breaker is open."
+					required:    true
+					enum: {
+						"pause":       "The sink is paused."
+						"dead_letter": "The events are routed to the `dead_letter` component of the sink."
+					}
+				}
+			}
+		}
+		component_dead_lettered_events_total: {
+			description:       "The number of events a sink failed to deliver and routed to its `dead_letter` component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "Why the sink failed to deliver the events."
+					required:    true
+					enum: {
+						"errored":      "The events failed to be delivered, such as once retries were exhausted."
+						"rejected":     "The events were rejected by the downstream service, such as with a 4xx response or a partial bulk failure."
+						"circuit_open": "The events were diverted while the circuit breaker of the sink was open."
+					}
+				}
+			}
+		}
 		component_discarded_events_total: {
 			description:       "The number of events dropped by this component."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		component_pauses_total: {
+			description:       "The total number of times events were held back because the component was paused through the API."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		component_received_bytes_total: {
 			description:       string | *"The number of raw bytes accepted by this component from source origins."
 			type:              "counter"
@@ -620,6 +705,18 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		component_retries_total: {
+			description:       "The number of requests retried by this sink."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_retry_backoff_seconds: {
+			description:       "The delay before the latest request retried by this sink is sent again, or zero if the latest request didn't need to be retried."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		component_sent_events_total: {
 			description:       "The total number of events emitted by this component."
 			type:              "counter"
@@ -632,6 +729,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags & {output: _output}
 		}
+		component_throttled_events_total: {
+			description:       "The total number of events delayed because they exceeded the rate limit set on the component through the API."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		datadog_logs_received_in_total: {
 			description:       "Number of Datadog logs received."
 			type:              "counter"
@@ -704,6 +807,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kubernetes_metadata_lookup_failures_total: {
+			description:       "The total number of events whose Kubernetes pod wasn't found by the `kubernetes_metadata` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -760,6 +869,12 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		geoip_database_reloads_total: {
+			description:       "The number of GeoIP databases reloaded by the geoip transform after they were modified."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		glob_errors_total: {
 			description:       "The total number of errors encountered when globbing paths. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -768,6 +883,12 @@ components: sources: internal_metrics: {
 				path: _path
 			}
 		}
+		groups_evicted_total: {
+			description:       "The number of groups that Vector has flushed before the end of their window, to stay within the maximum number of groups."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		http_bad_requests_total: {
 			description:       "The total number of HTTP `400 Bad Request` errors encountered."
 			type:              "counter"
@@ -835,12 +956,72 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		leader: {
+			description:       "Whether this replica is the leader running a source with the `leader_election` option, as 1, or not, as 0."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		leader_elections_lost_total: {
+			description:       "The total number of times this replica stopped being the leader running a source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		leader_elections_won_total: {
+			description:       "The total number of times this replica became the leader running a source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		log_patterns_created_total: {
+			description:       "The number of log patterns created by the log_clustering transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		lua_memory_used_bytes: {
 			description:       "The total memory currently being used by the Lua runtime."
 			type:              "gauge"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		memory_budget_shed_events_total: {
+			description:       "The total number of events shed by sources because the memory budget of the process was running low."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				policy: {
+					description: "How the events were shed."
+					required:    true
+					enum: {
+						drop_low_priority: "Low priority events were dropped."
+					}
+				}
+			}
+		}
+		memory_budget_source_pauses_total: {
+			description:       "The total number of times sources were paused because the memory budget of the process was exhausted."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		memory_budget_used_bytes: {
+			description:       "The memory currently reserved against the memory budget of the process."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				usage: {
+					description: "What the memory is used for."
+					required:    true
+					enum: {
+						batches:         "The batches being built by sinks."
+						buffers:         "The events held by in-memory buffers."
+						transform_state: "The state held by transforms such as `reduce` and `aggregate`."
+					}
+				}
+			}
+		}
 		metadata_refresh_failed_total: {
 			description:       "The total number of failed efforts to refresh AWS EC2 metadata."
 			type:              "counter"
@@ -865,6 +1046,21 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		pii_detections_total: {
+			description:       "The number of matches of the detectors of personally identifiable information."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				action: {
+					description: "The action taken on the matches."
+					required:    true
+				}
+				detector: {
+					description: "The name of the detector, either built-in or of a custom rule."
+					required:    true
+				}
+			}
+		}
 		processed_bytes_total: {
 			description:       "The number of bytes processed by the component."
 			type:              "counter"
@@ -920,6 +1116,25 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		rate_limit_group_throttled_total: {
+			description:       "The total number of times the sinks of a rate limit group were delayed because they exceeded its budget."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				group: {
+					description: "The rate limit group whose budget was exceeded."
+					required:    true
+				}
+				limit: {
+					description: "The limit that was exceeded."
+					required:    true
+					enum: {
+						bytes:    "The `max_bytes_per_sec` limit."
+						requests: "The `max_requests_per_sec` limit."
+					}
+				}
+			}
+		}
 		request_errors_total: {
 			description:       "The total number of requests errors for this component."
 			type:              "counter"
@@ -956,6 +1171,24 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		rollup_counter_resets_total: {
+			description:       "The number of absolute counter resets detected by the rollup transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		rollup_flushes_total: {
+			description:       "The number of rollup flushes."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		rollup_series_flushed_total: {
+			description:       "The number of series flushed by the rollup transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -1052,6 +1285,29 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		telemetry_export_dropped_spans_total: {
+			description:       "The total number of internal spans dropped as too many were waiting to be exported by `telemetry_export`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		telemetry_export_errors_total: {
+			description:       "The total number of errors exporting internal telemetry to the collector of `telemetry_export`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		tenant_throttled_events_total: {
+			description: """
+				The total number of events whose sending was delayed because the sources of their
+				tenant exceeded its `max_events_per_sec` quota.
+				"""
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				tenant: _tenant
+			}
+		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
 			type:              "counter"
@@ -1151,6 +1407,7 @@ components: sources: internal_metrics: {
 			component_id:   _component_id
 			component_name: _component_name
 			component_type: _component_type
+			tenant:         _tenant
 		}
 
 		// All available tags
@@ -1255,6 +1512,10 @@ components: sources: internal_metrics: {
 			description: "The path that produced the error."
 			required:    true
 		}
+		_tenant: {
+			description: "The tenant of the component, or whose quota was exceeded."
+			required:    false
+		}
 		_reason: {
 			description: "The type of the error"
 			required:    true
@@ -0,0 +1,233 @@
+package metadata
+
+components: sources: jmx_metrics: {
+	title: "JMX Metrics"
+
+	description: """
+		Collects the attributes of JMX MBeans through a Jolokia agent, so that JVM
+		services such as Kafka or Cassandra can be scraped without a separate exporter.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.jolokia
+
+				interface: {
+					socket: {
+						api: {
+							title: "Jolokia protocol"
+							url:   urls.jolokia_protocol
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+			proxy: enabled: true
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"A Jolokia agent should be attached to the JVM, or run in proxy mode in front of it.",
+		]
+
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		endpoints: {
+			description: "HTTP/HTTPS endpoints of Jolokia agents."
+			required:    true
+			type: array: {
+				items: type: string: {
+					examples: ["http://localhost:8778/jolokia"]
+				}
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between scrapes."
+			common:      true
+			required:    false
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			description: "The namespace of metrics. Disabled if empty."
+			common:      false
+			required:    false
+			type: string: {
+				default: "jmx"
+			}
+		}
+		beans: {
+			description: "The MBeans to collect attributes from."
+			common:      true
+			required:    false
+			type: array: {
+				default: [
+					{mbean: "java.lang:type=Memory"},
+					{mbean: "java.lang:type=Threading"},
+				]
+				items: type: object: options: {
+					mbean: {
+						description: "The ObjectName of the MBean. Patterns collect the attributes of all the matching MBeans."
+						required:    true
+						type: string: {
+							examples: ["java.lang:type=Memory", "kafka.server:type=BrokerTopicMetrics,name=*"]
+						}
+					}
+					attributes: {
+						description: "The attributes to collect. If empty, all the attributes of the MBean are collected."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["HeapMemoryUsage", "Count"]
+							}
+						}
+					}
+					exclude_attributes: {
+						description: "The attributes not to collect."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["RateUnit"]
+							}
+						}
+					}
+					metric_type: {
+						description: "The type of the metrics the attributes are mapped into."
+						required:    false
+						type: string: {
+							default: "gauge"
+							enum: {
+								gauge:   "The attributes are mapped into gauges."
+								counter: "The attributes are mapped into counters, for monotonically increasing values."
+							}
+						}
+					}
+				}
+			}
+		}
+		target: {
+			description: "The JVM to scrape through a Jolokia agent running in [proxy mode](\(urls.jolokia_proxy_mode))."
+			common:      false
+			required:    false
+			type: object: options: {
+				url: {
+					description: "The JMX service URL of the JVM."
+					required:    true
+					type: string: {
+						examples: ["service:jmx:rmi:///jndi/rmi://kafka:9999/jmxrmi"]
+					}
+				}
+				user: {
+					description: "The JMX username."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["${JMX_USERNAME}"]
+					}
+				}
+				password: {
+					description: "The JMX password."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["${JMX_PASSWORD}"]
+					}
+				}
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${HTTP_PASSWORD}"
+			username_example: "${HTTP_USERNAME}"
+		}}
+	}
+
+	how_it_works: {
+		jolokia: {
+			title: "Jolokia"
+			body:  """
+				The attributes of all the configured MBeans are read with a single
+				[bulk request](\(urls.jolokia_protocol)) per endpoint and scrape.
+
+				JVMs which only expose JMX over RMI can be scraped by running the Jolokia
+				agent in [proxy mode](\(urls.jolokia_proxy_mode)) and setting `target` to
+				the JMX service URL of the JVM. Vector does not speak RMI directly.
+				"""
+		}
+		metric_names: {
+			title: "Metric names"
+			body:  """
+				Metric names are made of the domain of the MBean, its `type` key property
+				and the attribute name, converted to snake case. Composite attributes are
+				flattened by appending their keys, so that the `used` key of the
+				`HeapMemoryUsage` attribute of `java.lang:type=Memory` becomes
+				`java_lang_memory_heap_memory_usage_used`. The other key properties of the
+				MBean are added as tags.
+
+				Numeric attributes are emitted as is, booleans are emitted as `0` or `1`,
+				and all other attributes are skipped.
+				"""
+		}
+	}
+
+	output: metrics: {
+		_jmx_metrics_tags: {
+			endpoint: {
+				description: "The Jolokia endpoint."
+				required:    true
+				examples: ["http://localhost:8778/jolokia"]
+			}
+			host: {
+				description: "The hostname of the Jolokia agent."
+				required:    true
+				examples: [_values.local_host]
+			}
+		}
+
+		up: {
+			description:       "If the Jolokia agent is up or not."
+			type:              "gauge"
+			default_namespace: "jmx"
+			tags:              _jmx_metrics_tags
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:              components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_seconds:             components.sources.internal_metrics.output.metrics.collect_duration_seconds
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
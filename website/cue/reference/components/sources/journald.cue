@@ -45,6 +45,7 @@ components: sources: journald: {
 
 	configuration: {
 		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
 		batch_size: {
 			common:      false
 			description: "The systemd journal is read in batches, and a checkpoint is set at the end of each batch. This option limits the size of the batch."
@@ -158,6 +159,36 @@ components: sources: journald: {
 				examples: ["/run/log/journal"]
 			}
 		}
+		journal_files: {
+			common:      false
+			description: "A list of journal files to read, such as journals exported from other hosts. The paths may contain glob patterns. Can't be used along with `journal_directory`."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/var/lib/exported/*.journal"]
+				}
+			}
+		}
+		merge: {
+			common:      false
+			description: "Whether to read the journals of all hosts, such as those uploaded by `systemd-journal-remote`, rather than only those of the local host."
+			required:    false
+			type: bool: default: false
+		}
+		namespace: {
+			common:      false
+			description: """
+				The [journal namespace](\(urls.journald_namespaces)) to read. Use `*` to read all namespaces, or a
+				namespace prefixed with `+` to read both that namespace and the default one. If not set, only the
+				default namespace is read. Can't be used along with `journal_directory` or `journal_files`.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["app", "+app", "*"]
+			}
+		}
 	}
 
 	output: logs: {
@@ -237,6 +268,17 @@ components: sources: journald: {
 				[issue #1473](\(urls.vector_issues)/1437).
 				"""
 		}
+		namespaces: {
+			title: "Namespaces and Other Journals"
+			body: """
+				By default, the journal of the default namespace of the local host is read. The
+				`namespace` option reads the journal of another namespace, and each namespace has its
+				own checkpoint, so that changing it doesn't resume from a cursor of another namespace.
+				Journals exported from other hosts can be read with `journal_files`, and those uploaded
+				by `systemd-journal-remote` with `journal_directory` set to their directory, or with
+				`merge` if they are stored along with the journals of the local host.
+				"""
+		}
 		non_ascii: {
 			title: "Non-ASCII Messages"
 			body: """
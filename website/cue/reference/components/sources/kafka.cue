@@ -58,6 +58,20 @@ components: sources: kafka: {
 				unit: "milliseconds"
 			}
 		}
+		drain_timeout_ms: {
+			common:      false
+			description: """
+				The maximum time to wait for the events of partitions being revoked to be acknowledged,
+				before letting the consumer group rebalance. Only relevant when acknowledgements are
+				enabled.
+				"""
+			required:    false
+			type: uint: {
+				default: 10000
+				examples: [5000, 30000]
+				unit: "milliseconds"
+			}
+		}
 		fetch_wait_max_ms: {
 			common:      false
 			description: "Maximum time the broker may wait to fill the response."
@@ -75,6 +89,20 @@ components: sources: kafka: {
 				examples: ["consumer-group-name"]
 			}
 		}
+		group_instance_id: {
+			common:      false
+			description: """
+				The static membership ID of this consumer within the consumer group. Consumers with a
+				static membership keep their partitions when they restart within `session_timeout_ms`,
+				instead of triggering a rebalance. It must be unique within the consumer group, and
+				stay the same across restarts.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-0", "${HOSTNAME}"]
+			}
+		}
 		key_field: {
 			common:      true
 			description: "The log field name to use for the Kafka message key."
@@ -121,6 +149,18 @@ components: sources: kafka: {
 			}
 		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
+		rebalance_strategy: {
+			common:      false
+			description: "The strategy used to assign partitions to the members of the consumer group."
+			required:    false
+			type: string: {
+				default: "eager"
+				enum: {
+					eager:              "Revokes all partitions from all members on each rebalance, using the `range` and `roundrobin` assignors."
+					cooperative_sticky: "Only revokes the partitions moving to another member on each rebalance, using the `cooperative-sticky` assignor. It can't be used in a consumer group whose members use the `eager` strategy."
+				}
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -164,6 +204,36 @@ components: sources: kafka: {
 				}
 			}
 		}
+		schema_registry: {
+			common:      false
+			description: """
+				Decodes messages as Avro with a [Confluent Schema Registry](\(urls.confluent_schema_registry)),
+				instead of using `framing` and `decoding`. Messages must be framed with the
+				[wire format](\(urls.confluent_wire_format)) used by the Confluent serializers.
+				"""
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					url: {
+						description: "The URL of the schema registry."
+						required:    true
+						type: string: {
+							examples: ["http://localhost:8081"]
+						}
+					}
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+					}}
+					tls: configuration._tls_connect & {_args: {
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+				}
+			}
+		}
 		session_timeout_ms: {
 			common:      false
 			description: "The Kafka session timeout in milliseconds."
@@ -245,5 +315,42 @@ components: sources: kafka: {
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		rebalancing: {
+			title: "Rebalancing"
+			body: """
+				When the members of the consumer group change, partitions are reassigned among them.
+				To limit the impact of restarts, such as rolling restarts of a Vector deployment:
+
+				* `group_instance_id` gives the consumer a static membership, so that restarting it
+				  within `session_timeout_ms` doesn't rebalance the consumer group at all.
+				* The `cooperative_sticky` strategy only revokes the partitions moving to another
+				  member, while the other partitions keep being consumed.
+
+				With acknowledgements enabled, the consumer waits up to `drain_timeout_ms` for the
+				events of partitions being revoked to be acknowledged, so that their offsets are
+				committed before the partitions move to another member, instead of being consumed
+				again.
+				"""
+		}
+		schema_registry: {
+			title: "Schema Registry"
+			body: """
+				With `schema_registry` set, each message is decoded as Avro with the schema whose ID
+				prefixes it. Schemas are fetched from the registry the first time they are
+				encountered, and cached afterwards, as a schema ID always refers to the same schema.
+
+				Records become the fields of the log event, while other values are set as the
+				`message` field. [Logical types](\(urls.avro_logical_types)) are converted as follows:
+
+				* `date`, `timestamp-millis` and `timestamp-micros` become timestamps.
+				* `time-millis` and `time-micros` become strings, such as `17:00:00.500`.
+				* `decimal` becomes a string holding its exact value, such as `-123.45`.
+				* `uuid` becomes a string.
+				* `duration` becomes an object with the `months`, `days` and `milliseconds` fields.
+
+				Messages that can't be decoded are dropped, and reported with an error.
+				"""
+		}
+	}
 }
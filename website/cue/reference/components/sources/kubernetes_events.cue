@@ -0,0 +1,194 @@
+package metadata
+
+components: sources: kubernetes_events: {
+	title: "Kubernetes Events"
+
+	description: """
+		Collects `Event` objects from the Kubernetes API, cluster-wide or from a
+		set of namespaces.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.kubernetes
+
+				interface: {
+					socket: {
+						api: {
+							title: "Kubernetes API"
+							url:   urls.kubernetes_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				[Kubernetes](\(urls.kubernetes)) version `\(services.kubernetes.versions)` is required.
+				""",
+			"""
+				The service account Vector runs as must be allowed to `list` and `watch` `events`
+				in the configured namespaces.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		checkpoint_store: configuration._source_checkpoint_store
+		field_selector: {
+			common:      false
+			description: "Specifies the field selector to filter `Event`s with, to be used in addition to the built-in `Event` fields."
+			required:    false
+			type: string: {
+				default: ""
+				examples: ["involvedObject.kind=Pod", "type!=Normal"]
+			}
+		}
+		kube_config_file: {
+			common:      false
+			description: "Optional path to a kubeconfig file readable by Vector. If not set, Vector will try to connect to Kubernetes using in-cluster configuration."
+			required:    false
+			type: string: default: null
+		}
+		label_selector: {
+			common:      false
+			description: "Specifies the label selector to filter `Event`s with."
+			required:    false
+			type: string: {
+				default: ""
+				examples: ["app=nginx"]
+			}
+		}
+		namespaces: {
+			common:      true
+			description: "The namespaces to watch events in. If empty, events are watched cluster-wide."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["default", "kube-system"]
+				}
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A Kubernetes `Event` object."
+		fields: {
+			count: {
+				description: "The number of times this event has occurred."
+				required:    false
+				common:      true
+				type: uint: {
+					default: null
+					examples: [3]
+					unit: null
+				}
+			}
+			involvedObject: {
+				description: "The object this event is about."
+				required:    true
+				type: object: {
+					examples: [{"kind": "Pod", "name": "web-1", "namespace": "default"}]
+					options: {}
+				}
+			}
+			message: {
+				description: "A human-readable description of the event."
+				required:    true
+				type: string: {
+					examples: ["Back-off restarting failed container"]
+				}
+			}
+			metadata: {
+				description: "The metadata of the `Event` object, without its `managedFields`."
+				required:    true
+				type: object: {
+					examples: [{"name": "web-1.16b6c9cd0c5e3b8f", "namespace": "default", "uid": "4f3c1f5e-5f4b-4c6a-a3c2-0d0d5e7f9e1b"}]
+					options: {}
+				}
+			}
+			reason: {
+				description: "A short, machine understandable reason for the event."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["BackOff"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["kubernetes_events"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `lastTimestamp` of the event, falling back to its `eventTime` or `firstTimestamp`."
+			}
+			type: {
+				description: "The type of the event."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["Normal", "Warning"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		deduplication: {
+			title: "Deduplication"
+			body: """
+				Kubernetes aggregates repeated occurrences of an event into a single
+				`Event` object by incrementing its `count` and updating its
+				`lastTimestamp`. Vector emits the object whenever one of these changes,
+				and ignores other updates of an event it already emitted.
+				"""
+		}
+
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				Vector checkpoints the `resourceVersion` of the events it watched in its
+				data directory, and resumes watching from it after a restart. If the
+				checkpointed `resourceVersion` has expired, the events are listed again
+				and only those more recent than the last emitted event are sent.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
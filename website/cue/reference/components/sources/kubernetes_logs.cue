@@ -203,6 +203,42 @@ components: sources: kubernetes_logs: {
 			required:    false
 			type: string: default: null
 		}
+		kube_config_context: {
+			common:      false
+			description: "The name of the kubeconfig context to use, instead of its current context. The context is taken from `kube_config_file`, or from the kubeconfig file of the environment if it's not set, which allows a central Vector to enrich the logs of other clusters than its own."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["cluster1"]
+			}
+		}
+		kube_api_endpoint: {
+			common:      false
+			description: "The URL of the Kubernetes API server, overriding the one of the kubeconfig file or of the in-cluster configuration."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["https://cluster1.example.com:6443"]
+			}
+		}
+		pod_logs_dir: {
+			common:      false
+			description: "The directory holding the pod log directories. This is where `kubelet` writes them by default, but it can be set to collect the logs shipped from another cluster, along with `kube_config_context` or `kube_api_endpoint`."
+			required:    false
+			type: string: {
+				default: "/var/log/pods"
+				examples: ["/mnt/cluster1/pods"]
+			}
+		}
+		cluster_name: {
+			common:      false
+			description: "The name of the cluster, added to each event in the `kubernetes.cluster_name` field. This tells apart the events of the clusters collected by a central Vector."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["cluster1"]
+			}
+		}
 		self_node_name: {
 			common:      false
 			description: "The name of the Kubernetes `Node` this Vector instance runs at. Configured to use an env var by default, to be evaluated to a value provided by Kubernetes at Pod deploy time."
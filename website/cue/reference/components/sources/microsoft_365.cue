@@ -0,0 +1,207 @@
+package metadata
+
+components: sources: microsoft_365: {
+	title: "Microsoft 365"
+
+	description: """
+		Polls the [Office 365 Management Activity API](\(urls.microsoft_365_management_activity_api))
+		for the content blobs of the unified audit log, and emits each audit record as a log event.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.microsoft_365
+
+				interface: {
+					socket: {
+						api: {
+							title: "Office 365 Management Activity API"
+							url:   urls.microsoft_365_management_activity_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+			proxy: enabled: true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The Microsoft 365 source requires an [Azure AD application](\(urls.microsoft_365_management_api_app))
+				with the `ActivityFeed.Read` application permission of the Office 365 Management APIs,
+				and the `ActivityFeed.ReadDlp` permission to read the `DLP.All` content type. Auditing
+				must be enabled for the organization.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
+		client_id: {
+			description: "The ID of the Azure AD application used to authenticate, with the client credentials grant."
+			required:    true
+			type: string: {
+				examples: ["c5d3a2f0-8d0c-4f3e-9e2c-1f6a0f0b5e7d"]
+			}
+		}
+		client_secret: {
+			description: "A client secret of the Azure AD application."
+			required:    true
+			type: string: {
+				examples: ["${MICROSOFT_365_CLIENT_SECRET}"]
+			}
+		}
+		content_delay_secs: {
+			common:      false
+			description: "How long to keep looking for content blobs after their creation time. Content blobs becoming available later than this are missed."
+			required:    false
+			type: uint: {
+				default: 3600
+				unit:    "seconds"
+			}
+		}
+		content_types: {
+			common:      true
+			description: "The content types to subscribe to. By default, all content types are subscribed to."
+			required:    false
+			type: array: {
+				default: ["Audit.AzureActiveDirectory", "Audit.Exchange", "Audit.SharePoint", "Audit.General", "DLP.All"]
+				items: type: string: {
+					enum: {
+						"Audit.AzureActiveDirectory": "Azure Active Directory audit records."
+						"Audit.Exchange":             "Exchange audit records."
+						"Audit.SharePoint":           "SharePoint audit records."
+						"Audit.General":              "The audit records of the other workloads."
+						"DLP.All":                    "Data loss prevention events."
+					}
+				}
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the progress of each content type. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint of the Office 365 Management Activity API, which differs for the government clouds."
+			required:    false
+			type: string: {
+				default: "https://manage.office.com"
+				examples: ["https://manage-gcc.office.com"]
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls of the content blobs."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		login_endpoint: {
+			common:      false
+			description: "The endpoint of Azure AD, from which access tokens are requested."
+			required:    false
+			type: string: {
+				default: "https://login.microsoftonline.com"
+				examples: ["https://login.microsoftonline.us"]
+			}
+		}
+		tenant_id: {
+			description: "The ID of the Azure AD tenant of the organization."
+			required:    true
+			type: string: {
+				examples: ["41463f53-8812-40f4-890f-865bf6e35190"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An audit record, with the fields of its [schema](\(urls.microsoft_365_audit_log_schema))."
+		fields: {
+			"*": {
+				description: "The fields of the audit record, such as `Operation`, `Workload`, `UserId` and `RecordType`."
+				required:    true
+				type: "*": {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["microsoft_365"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `CreationTime` of the audit record."
+			}
+		}
+	}
+
+	how_it_works: {
+		subscriptions: {
+			title: "Subscriptions"
+			body: """
+				The source starts the subscription to each content type, which has no effect if it's
+				already enabled, and then periodically lists the content blobs created since the
+				previous poll. The audit records of each new content blob are fetched and emitted.
+				Without a checkpoint, the content blobs of the last 24 hours are consumed, and
+				catching up is done in windows of 24 hours, within the last 7 days.
+				"""
+		}
+
+		deduplication: {
+			title: "Overlapping windows"
+			body: """
+				Content blobs are listed by their creation time, but can become available some
+				time after it. Each listing window thus starts `content_delay_secs` before the end of
+				the previous one, and the IDs of the content blobs consumed in the overlap are
+				checkpointed in the data directory, once their audit records were delivered, to
+				skip them. Audit records duplicated across content blobs by the API itself aren't
+				deduplicated.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,197 @@
+package metadata
+
+components: sources: mongodb_change_stream: {
+	title: "MongoDB Change Stream"
+
+	description: """
+		Streams the changes of a MongoDB collection, database or deployment through
+		[change streams](\(urls.mongodb_change_streams)).
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.mongodb
+
+				interface: {
+					socket: {
+						api: {
+							title: "MongoDB change streams"
+							url:   urls.mongodb_change_streams
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				Change streams are only available on replica sets and sharded clusters.
+				""",
+			"""
+				User from endpoint should have the `changeStream` and `find` privileges on the
+				watched collections.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		checkpoint_store: configuration._source_checkpoint_store
+		endpoint: {
+			description: "MongoDB [Connection String URI Format](\(urls.mongodb_connection_string_uri_format)) of the deployment to watch."
+			required:    true
+			type: string: {
+				examples: ["mongodb://localhost:27017/?replicaSet=rs0"]
+			}
+		}
+		database: {
+			description: "The database to watch. If not set, all the databases of the deployment are watched."
+			common:      true
+			required:    false
+			type: string: {
+				default: null
+				examples: ["app"]
+			}
+		}
+		collection: {
+			description: "The collection to watch. If not set, all the collections of `database` are watched."
+			common:      true
+			required:    false
+			type: string: {
+				default: null
+				examples: ["orders"]
+			}
+		}
+		full_document: {
+			description: "Whether change events of updates contain the full document."
+			common:      false
+			required:    false
+			type: string: {
+				default: "default"
+				enum: {
+					default:       "Update events only contain the `updateDescription` delta."
+					update_lookup: "Update events also contain the current version of the full document, looked up when the event is read."
+				}
+			}
+		}
+		batch_size: {
+			description: "The maximum number of change events to send downstream at once. Events already received from the server are batched together, up to this number."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+	}
+
+	output: logs: change: {
+		description: "A [change event](\(urls.mongodb_change_events)), without its `_id` resume token."
+		fields: {
+			documentKey: {
+				description: "The `_id` of the changed document."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"_id": "62a0c4f4b5a3d2c1e0f9a8b7"}]
+					options: {}
+				}
+			}
+			fullDocument: {
+				description: "The document, for inserts and replacements, and for updates with `full_document` set to `update_lookup`."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"_id": "62a0c4f4b5a3d2c1e0f9a8b7", "status": "shipped"}]
+					options: {}
+				}
+			}
+			ns: {
+				description: "The database and collection of the change."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"db": "app", "coll": "orders"}]
+					options: {}
+				}
+			}
+			operationType: {
+				description: "The kind of change."
+				required:    true
+				type: string: {
+					examples: ["insert", "update", "replace", "delete", "drop", "invalidate"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["mongodb_change_stream"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `wallTime` of the change, falling back to its `clusterTime`."
+			}
+			updateDescription: {
+				description: "The updated and removed fields, for updates."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"updatedFields": {"status": "shipped"}, "removedFields": ["note"]}]
+					options: {}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		resume_tokens: {
+			title: "Resume tokens"
+			body: """
+				The resume token of the last delivered batch of changes is checkpointed in the data
+				directory, or in the store set with `checkpoint_store`, and the change stream is resumed after it on restart. If the token is no
+				longer in the oplog, a warning is logged and the change stream is opened again from
+				the current position.
+				"""
+		}
+
+		backpressure: {
+			title: "Batching and backpressure"
+			body: """
+				Changes already received from the server are sent downstream together, up to
+				`batch_size`. The change stream is not read further until a batch is sent, or
+				acknowledged when acknowledgements are enabled, so the server cursor is only
+				advanced as fast as the events are processed.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
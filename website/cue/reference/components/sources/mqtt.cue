@@ -0,0 +1,219 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			from: {
+				service: services.mqtt
+				interface: socket: {
+					api: {
+						title: "MQTT"
+						url:   urls.mqtt_v5
+					}
+					direction: "outgoing"
+					port:      1883
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		host: {
+			description: "The host of the MQTT broker to connect to."
+			required:    true
+			type: string: {
+				examples: ["localhost", "mqtt.example.com"]
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the MQTT broker to connect to."
+			required:    false
+			type: uint: {
+				default: 1883
+				unit:    null
+			}
+		}
+		version: {
+			common:      false
+			description: "The version of the MQTT protocol."
+			required:    false
+			type: string: {
+				default: "3.1.1"
+				enum: {
+					"3.1.1": "[MQTT 3.1.1](\(urls.mqtt_v3_1_1))."
+					"5":     "[MQTT 5](\(urls.mqtt_v5))."
+				}
+			}
+		}
+		topics: {
+			description: "The topic filters to subscribe to. The `+` and `#` wildcards are supported."
+			required:    true
+			type: array: items: type: string: {
+				examples: ["sensors/+/temperature", "gateway/#"]
+			}
+		}
+		qos: {
+			common:      true
+			description: "The quality of service of subscriptions. Messages are delivered with the lowest of this QoS and the QoS they were published with."
+			required:    false
+			type: string: {
+				default: "at_least_once"
+				enum: {
+					at_most_once:  "QoS 0, messages are delivered at most once."
+					at_least_once: "QoS 1, messages are delivered at least once."
+					exactly_once:  "QoS 2, messages are delivered exactly once."
+				}
+			}
+		}
+		client_id: {
+			common:      false
+			description: "The client ID identifying this client to the broker. If not set, a random client ID is generated. It must be set to use persistent sessions."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-gateway"]
+			}
+		}
+		persistent_session: {
+			common:      false
+			description: "Whether or not to keep the session across connections, along with its subscriptions and the messages not yet acknowledged."
+			required:    false
+			type: bool: default: false
+		}
+		session_expiry_interval_secs: {
+			common:      false
+			description: "The time the broker keeps a persistent session after the client disconnected. Only relevant for MQTT 5."
+			required:    false
+			type: uint: {
+				default: 86400
+				unit:    "seconds"
+			}
+		}
+		keep_alive_secs: {
+			common:      false
+			description: "The interval at which the connection is kept alive."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		user: {
+			common:      false
+			description: "The username to authenticate with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector"]
+			}
+		}
+		password: {
+			common:      false
+			description: "The password to authenticate with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${MQTT_PASSWORD}"]
+			}
+		}
+		topic_key: {
+			common:      false
+			description: "The log field name to use for the topic the message was published to."
+			required:    false
+			type: string: {
+				default: "topic"
+				examples: ["topic"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual MQTT message."
+		fields: {
+			message: {
+				description: "The payload of the message."
+				required:    true
+				type: string: {
+					examples: ["{\"temperature\": 21.5}"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the message was published to."
+				required:    true
+				type: string: {
+					examples: ["sensors/kitchen/temperature"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				Messages received with QoS 1 or 2 are normally acknowledged to the broker as
+				soon as they are received. With acknowledgements enabled, they are instead
+				acknowledged once their events are delivered, in the order they were received.
+
+				Messages whose events are rejected are left unacknowledged. Brokers deliver them
+				again when the session is resumed, which requires `persistent_session` to be
+				enabled.
+				"""
+		}
+		persistent_sessions: {
+			title: "Persistent sessions"
+			body:  """
+				With `persistent_session` enabled, the broker keeps the subscriptions of the
+				client, along with the messages published while it is disconnected, so that
+				restarting Vector doesn't lose messages. Sessions are bound to the `client_id`,
+				which must therefore be set, and be unique among the clients of the broker.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -4,7 +4,7 @@ components: sources: nats: {
 	title: "NATS"
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			from: components._nats.features.collect.from
@@ -32,9 +32,62 @@ components: sources: nats: {
 	}
 
 	configuration: components._nats.configuration & {
+		acknowledgements: configuration._source_acknowledgements
+		jetstream: {
+			common:      false
+			description: "Consume messages from a JetStream stream, through a durable pull consumer, instead of subscribing to the subject. The subject is used to filter the messages of the stream."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					stream: {
+						description: "The name of the stream to consume messages from."
+						required:    true
+						type: string: {
+							examples: ["EVENTS"]
+						}
+					}
+					consumer: {
+						description: "The name of the durable pull consumer to consume messages as. The consumer is created if it doesn't exist."
+						required:    true
+						type: string: {
+							examples: ["vector"]
+						}
+					}
+					max_ack_pending: {
+						common:      false
+						description: "The maximum number of messages delivered to the consumer but not yet acknowledged."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    null
+						}
+					}
+					ack_wait_secs: {
+						common:      false
+						description: "The time after which messages not yet acknowledged are delivered again."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+					max_deliver: {
+						common:      false
+						description: "The maximum number of times a message is delivered. By default, messages are delivered until they are acknowledged."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [5]
+							unit: null
+						}
+					}
+				}
+			}
+		}
 		queue: {
 			common:      false
-			description: "NATS Queue Group to join"
+			description: "NATS Queue Group to join. Not supported when consuming from JetStream."
 			required:    false
 			type: string: {
 				default: "vector"
@@ -67,5 +120,20 @@ components: sources: nats: {
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body:  """
+				With `jetstream` set, messages are consumed from a JetStream stream through a durable
+				pull consumer, whose progress is kept by the server across Vector restarts. Messages
+				are explicitly acknowledged once their events are delivered when acknowledgements are
+				enabled, or as soon as they are received otherwise.
+
+				Messages that are not acknowledged within `jetstream.ack_wait_secs` are delivered
+				again. Messages whose events fail to be delivered are negatively acknowledged, to be
+				delivered again right away, while those whose events are rejected are terminated, so
+				that they are not delivered again.
+				"""
+		}
+	}
 }
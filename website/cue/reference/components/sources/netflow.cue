@@ -0,0 +1,189 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	description: """
+		Collects NetFlow v5, NetFlow v9 and IPFIX flow records exported over UDP by
+		routers, switches and probes.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.netflow
+				interface: socket: {
+					api: {
+						title: "IPFIX"
+						url:   urls.ipfix
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled: false
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for flows on."
+			common:      true
+			required:    false
+			type: string: {
+				default: "0.0.0.0:\(_port)"
+				examples: ["0.0.0.0:4739"]
+			}
+		}
+		max_templates: {
+			description: "The maximum number of templates kept across all exporters. Templates beyond this limit are ignored."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the exporter. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+	}
+
+	output: logs: flow: {
+		description: "A flow record, or an options record."
+		fields: {
+			host: {
+				description: "The address of the exporter."
+				required:    true
+				type: string: {
+					examples: [_values.remote_host]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["netflow"]
+				}
+			}
+			timestamp: {
+				description: "The export time of the message containing the record."
+				required:    true
+				type: timestamp: {}
+			}
+			version: {
+				description: "The version of the protocol, `10` being IPFIX."
+				required:    true
+				type: uint: {
+					examples: [5, 9, 10]
+					unit: null
+				}
+			}
+			record_type: {
+				description: "The type of the record."
+				required:    true
+				type: string: {
+					enum: {
+						flow:    "A flow record."
+						options: "An options record, describing the exporter itself."
+					}
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the message containing the record."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+			observation_domain_id: {
+				description: "The observation domain, or source ID, of NetFlow v9 and IPFIX records."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [1]
+					unit: null
+				}
+			}
+			"*": {
+				description: """
+					The fields of the record, named after their [information element](\(urls.ipfix_information_elements))
+					in snake case, like `source_ipv4_address`.
+					"""
+				required:    true
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		templates: {
+			title: "Templates"
+			body:  """
+				The records of [NetFlow v9](\(urls.netflow_v9)) and [IPFIX](\(urls.ipfix))
+				messages are described by templates, which exporters send periodically.
+				Templates are kept for each exporter address and observation domain, and
+				records received before their template are dropped.
+				"""
+		}
+		fields: {
+			title: "Fields"
+			body:  """
+				Fields are named after their [information element](\(urls.ipfix_information_elements)),
+				such as `destination_transport_port`, and decoded according to its type. The
+				fields of [NetFlow v5](\(urls.netflow_v5)) records are named after their
+				equivalent elements. Unknown fields are named `field_<id>`, or
+				`enterprise_<number>_<id>` for enterprise-specific fields, and formatted in
+				hexadecimal.
+
+				The flow start and end times of NetFlow v5 and v9 records, which are relative
+				to the uptime of the exporter, are converted into `flow_start_milliseconds`
+				and `flow_end_milliseconds` timestamps.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,170 @@
+package metadata
+
+components: sources: okta: {
+	title: "Okta"
+
+	description: """
+		Polls the [Okta System Log API](\(urls.okta_system_log_api)), and emits each log event
+		of the organization, such as sign-ins and administrative changes, as a log event.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.okta
+
+				interface: {
+					socket: {
+						api: {
+							title: "Okta System Log API"
+							url:   urls.okta_system_log_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+			proxy: enabled: true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The Okta source requires an [API token](\(urls.okta_api_token)) of an
+				administrator allowed to read the System Log.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the cursor of the System Log. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the Okta organization."
+			required:    true
+			type: string: {
+				examples: ["https://example.okta.com"]
+			}
+		}
+		filter: {
+			common:      true
+			description: "The [filter expression](\(urls.okta_system_log_filter)) the log events must match. By default, all log events are collected."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["eventType eq \"user.session.start\""]
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls of the System Log. Every page available is read at each poll."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		limit: {
+			common:      false
+			description: "The maximum number of log events to request per page, between 1 and 1000."
+			required:    false
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		token: {
+			description: "The API token used to authenticate the requests."
+			required:    true
+			type: string: {
+				examples: ["${OKTA_API_TOKEN}"]
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A log event of the System Log, with the fields of its [LogEvent object](\(urls.okta_system_log_api)#logevent-object)."
+		fields: {
+			"*": {
+				description: "The fields of the log event, such as `eventType`, `actor`, `outcome` and `displayMessage`."
+				required:    true
+				type: "*": {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["okta"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `published` time of the log event."
+			}
+		}
+	}
+
+	how_it_works: {
+		pagination: {
+			title: "Pagination and checkpointing"
+			body: """
+				At each poll, the pages of the System Log are read by following their `next`
+				links, until a page isn't full. The cursor of the next page is checkpointed in the
+				data directory once the log events of a page were delivered, so polling resumes
+				where it left off after a restart. Without a checkpoint, polling starts from the log
+				events of the last seven days.
+				"""
+		}
+
+		rate_limits: {
+			title: "Rate limits"
+			body: """
+				The System Log API is [rate limited](\(urls.okta_rate_limits)). When the
+				`X-Rate-Limit-Remaining` header of a response reaches zero, or when a request is
+				rejected with a `429 Too Many Requests` status, the source waits until the time of
+				the `X-Rate-Limit-Reset` header before sending the next request.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,269 @@
+package metadata
+
+components: sources: opentelemetry: {
+	_grpc_port: 4317
+	_http_port: 4318
+
+	title: "OpenTelemetry"
+
+	description: """
+		Receives logs, metrics, and traces sent with the OpenTelemetry Protocol (OTLP) over gRPC and HTTP.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.opentelemetry
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _grpc_port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: enabled: false
+			keepalive: enabled:            true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		auth: {
+			common:      false
+			description: "Options for HTTP Basic authentication, applied to both the gRPC and the HTTP server. Requests without valid credentials are rejected."
+			required:    false
+			type: object: options: {
+				username: {
+					description: "The basic authentication user name."
+					required:    true
+					type: string: {
+						examples: ["${OTLP_USERNAME}", "username"]
+					}
+				}
+				password: {
+					description: "The basic authentication password."
+					required:    true
+					type: string: {
+						examples: ["${OTLP_PASSWORD}", "password"]
+					}
+				}
+			}
+		}
+		grpc: {
+			description: "Configuration for the OTLP/gRPC server."
+			required:    true
+			type: object: options: {
+				address: {
+					description: "The gRPC address to listen for connections on. It _must_ include a port."
+					required:    true
+					type: string: examples: ["0.0.0.0:\(_grpc_port)"]
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_verify_certificate: true
+					enabled_default:        false
+				}}
+			}
+		}
+		http: {
+			description: "Configuration for the OTLP/HTTP server, accepting binary protobuf payloads."
+			required:    true
+			type: object: options: {
+				address: {
+					description: "The HTTP address to listen for connections on. It _must_ include a port."
+					required:    true
+					type: string: examples: ["0.0.0.0:\(_http_port)"]
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_verify_certificate: true
+					enabled_default:        false
+				}}
+			}
+		}
+	}
+
+	outputs: [
+		{
+			name: "logs"
+			description: """
+				Received log events go to this output stream. Use `<component_id>.logs` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "metrics"
+			description: """
+				Received metric events go to this output stream. Use `<component_id>.metrics` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "traces"
+			description: """
+				Received trace events go to this output stream. Use `<component_id>.traces` as an input to downstream transforms and sinks.
+				"""
+		},
+	]
+
+	output: {
+		logs: record: {
+			description: "An individual OTLP log record."
+			fields: {
+				message: {
+					description: "The body of the log record."
+					required:    true
+					type: "*": {}
+				}
+				attributes: {
+					description: "The attributes of the log record."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				resources: {
+					description: "The attributes of the resource which produced the log record."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				scope: {
+					description: "The `name`, `version` and `attributes` of the instrumentation scope which produced the log record."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				severity_text: {
+					description: "The severity text of the log record, also known as log level."
+					required:    false
+					common:      true
+					type: string: examples: ["INFO"]
+				}
+				severity_number: {
+					description: "The numerical severity of the log record."
+					required:    false
+					common:      true
+					type: uint: {
+						examples: [9]
+						unit: null
+					}
+				}
+				trace_id: {
+					description: "The hex encoded ID of the trace the log record belongs to."
+					required:    false
+					common:      false
+					type: string: examples: ["66346462623365366234373737663835"]
+				}
+				span_id: {
+					description: "The hex encoded ID of the span the log record belongs to."
+					required:    false
+					common:      false
+					type: string: examples: ["43222c2d51a7abe3"]
+				}
+				observed_timestamp: {
+					description: "The time the log record was observed by the collection system."
+					required:    false
+					common:      false
+					type: timestamp: {}
+				}
+				timestamp: fields._current_timestamp & {
+					description: "The time the log record occurred, falling back to the observed time or the time of ingestion."
+				}
+			}
+		}
+		metrics: {
+			counter:   output._passthrough_counter
+			gauge:     output._passthrough_gauge
+			histogram: output._passthrough_histogram
+			summary:   output._passthrough_summary
+		}
+		traces: {
+			description: "An individual OTLP span."
+			fields: {
+				trace_id: {
+					description: "The hex encoded ID of the trace."
+					required:    true
+					type: string: examples: ["66346462623365366234373737663835"]
+				}
+				span_id: {
+					description: "The hex encoded ID of the span."
+					required:    true
+					type: string: examples: ["43222c2d51a7abe3"]
+				}
+				name: {
+					description: "The name of the span."
+					required:    true
+					type: string: examples: ["GET /cart"]
+				}
+				kind: {
+					description: "The kind of the span."
+					required:    true
+					type: string: enum: {
+						unspecified: "The span kind is unknown."
+						internal:    "An internal operation."
+						server:      "The server side of a synchronous request."
+						client:      "The client side of a synchronous request."
+						producer:    "The initiator of an asynchronous request."
+						consumer:    "The receiver of an asynchronous request."
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		signals: {
+			title: "Receiving OTLP"
+			body: """
+				The source listens for OTLP/gRPC requests on the `grpc` address, and for OTLP/HTTP requests
+				with binary protobuf payloads on the `/v1/logs`, `/v1/metrics` and `/v1/traces` paths of the `http`
+				address. Each signal is sent to the output of the same name.
+				"""
+		}
+		resource_scope: {
+			title: "Resource and scope attributes"
+			body: """
+				Resource and instrumentation scope attributes are carried the same way for every signal. Logs and
+				spans hold them in the `resources` and `scope` fields. Metrics hold them as tags prefixed with
+				`resource.` and `scope.`, next to the tags built from the data point attributes.
+				"""
+		}
+		metric_types: {
+			title: "Metric types"
+			body: """
+				Monotonic sums are converted to counters, while gauges and non-monotonic sums are converted
+				to gauges. Metrics with delta temporality are incremental, and cumulative metrics are absolute.
+				Exponential histograms are converted to aggregated histograms bounded by the upper limit of each
+				exponential bucket.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+	}
+}
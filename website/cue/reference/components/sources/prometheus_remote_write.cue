@@ -81,6 +81,29 @@ components: sources: prometheus_remote_write: {
 				are emitted as gauges.
 				"""
 		}
+		protocol_versions: {
+			title: "Protocol versions"
+			body: """
+				Both versions of the protocol are accepted, as selected by the `proto` parameter
+				of the `Content-Type` header of each request. Requests of the
+				[Remote Write 2.0](\(urls.prometheus_remote_write_v2)) protocol, whose
+				`proto` is `io.prometheus.write.v2.Request`, are accepted without their native
+				histograms, and are answered without the headers reporting the number of
+				samples written. Requests with any other `proto` are rejected with a `415`
+				status.
+				"""
+		}
+		prometheus_metadata: {
+			title: "Exemplars and metadata"
+			body: """
+				The exemplars of the series, and the help and unit of their metric families,
+				aren't part of Vector metrics. They are kept in the metadata of the events,
+				and written again by the `prometheus_exporter` and `prometheus_remote_write`
+				sinks, including after a hop through a `vector` sink and source. The
+				exemplars of histogram buckets are attached to the bucket that contains
+				their value.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -143,6 +143,20 @@ components: sources: prometheus_scrape: {
 				}
 			}
 		}
+		native_histograms: {
+			common: false
+			description: """
+				Whether to request the protobuf exposition format, in order to scrape
+				[native histograms](\(urls.prometheus_histogram)). Native histograms are only exposed in the
+				protobuf format. Their exponential buckets are converted into histogram buckets bounded by the boundaries
+				of their schema. Targets not supporting the protobuf format are scraped in the
+				[text format](\(urls.prometheus_text_based_exposition_format)).
+				"""
+			required: false
+			type: bool: {
+				default: false
+			}
+		}
 		auth: configuration._http_auth & {_args: {
 			password_example: "${PROMETHEUS_PASSWORD}"
 			username_example: "${PROMETHEUS_USERNAME}"
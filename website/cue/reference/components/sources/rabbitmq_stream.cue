@@ -0,0 +1,229 @@
+package metadata
+
+components: sources: rabbitmq_stream: {
+	title: "RabbitMQ Stream"
+
+	description: """
+		Consumes a RabbitMQ [stream](\(urls.rabbitmq_streams)) with the stream protocol.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.rabbitmq
+				interface: socket: {
+					api: {
+						title: "RabbitMQ Streams"
+						url:   urls.rabbitmq_streams
+					}
+					direction: "outgoing"
+					port:      5552
+					protocols: ["tcp"]
+					ssl: "disabled"
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The `rabbitmq_stream` plugin must be enabled on the RabbitMQ nodes.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: configuration._source_checkpoint_store
+		host: {
+			common:      true
+			description: "The host of the RabbitMQ node to connect to."
+			required:    false
+			type: string: {
+				default: "localhost"
+				examples: ["rabbitmq.example.com"]
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the stream protocol on the RabbitMQ node."
+			required:    false
+			type: uint: {
+				default: 5552
+				unit:    null
+			}
+		}
+		user: {
+			common:      false
+			description: "The username to authenticate with."
+			required:    false
+			type: string: {
+				default: "guest"
+				examples: ["vector"]
+			}
+		}
+		password: {
+			common:      false
+			description: "The password to authenticate with."
+			required:    false
+			type: string: {
+				default: "guest"
+				examples: ["${RABBITMQ_PASSWORD}"]
+			}
+		}
+		virtual_host: {
+			common:      false
+			description: "The virtual host the stream belongs to."
+			required:    false
+			type: string: {
+				default: "/"
+				examples: ["production"]
+			}
+		}
+		stream: {
+			description: "The name of the stream to consume."
+			required:    true
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+		offset: {
+			common:      true
+			description: "Where to start consuming the stream."
+			required:    false
+			type: object: {
+				examples: [{"type": "timestamp", "timestamp": 1660000000}]
+				options: {
+					type: {
+						common:      true
+						description: "The kind of [offset specification](\(urls.rabbitmq_stream_offsets))."
+						required:    false
+						type: string: {
+							default: "stored"
+							enum: {
+								stored:    "Resume after the last delivered message, as persisted in the checkpoint. Without a checkpoint, the stream is consumed from the first message available."
+								first:     "Start from the first message available in the stream."
+								last:      "Start from the last chunk of messages written to the stream."
+								next:      "Start from the next message written to the stream."
+								timestamp: "Start from the first chunk of messages written at or after `timestamp`."
+							}
+						}
+					}
+					timestamp: {
+						common:        false
+						description:   "The point in time to start from, as a Unix timestamp in seconds."
+						relevant_when: "type = \"timestamp\""
+						required:      false
+						type: uint: {
+							default: null
+							examples: [1660000000]
+							unit: "seconds"
+						}
+					}
+				}
+			}
+		}
+		batch_size: {
+			common:      false
+			description: "The maximum number of messages to send downstream at once."
+			required:    false
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+		offset_key: {
+			common:      false
+			description: "The log field name to use for the offset of the message in the stream."
+			required:    false
+			type: string: {
+				default: "offset"
+				examples: ["offset"]
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the offset of the last delivered message. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the user Vector is running as has write permissions to this directory."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual message of the stream."
+		fields: {
+			message: {
+				description: "The body of the message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			offset: {
+				description: "The offset of the message in the stream."
+				required:    true
+				type: uint: {
+					examples: [100]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				The offset of the last message of each batch is checkpointed in the data directory
+				once the batch is sent, or delivered when acknowledgements are enabled. With the
+				`stored` offset, the stream is consumed again after it on restart.
+
+				Messages whose events are rejected are not consumed again, as a stream can only be
+				read from an earlier offset along with all the messages after it. The rejection is
+				logged, and the offset is checkpointed regardless.
+				"""
+		}
+		backpressure: {
+			title: "Batching and backpressure"
+			body: """
+				Messages already received from the node are sent downstream together, up to
+				`batch_size`. The stream is not read further until a batch is sent, so that
+				messages are only consumed as fast as the events are processed.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -4,7 +4,7 @@ components: sources: redis: {
 	title: "Redis"
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			tls: enabled:        false
@@ -58,6 +58,7 @@ components: sources: redis: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements
 		url: {
 			description: "The Redis URL to connect to. The url _must_ take the form of `protocol://server:port/db` where the protocol can either be `redis` or `rediss` for connections secured via TLS."
 			groups: ["tcp"]
@@ -79,13 +80,14 @@ components: sources: redis: {
 		}
 		data_type: {
 			common:      false
-			description: "The Redis data type (`list` or `channel`) to use."
+			description: "The Redis data type (`list`, `channel` or `stream`) to use."
 			required:    false
 			type: string: {
 				default: "list"
 				enum: {
 					list:    "Use the Redis `list` data type."
 					channel: "Use the Redis `channel` data type."
+					stream:  "Use the Redis `stream` data type, reading entries as a member of a consumer group."
 				}
 				syntax: "literal"
 			}
@@ -114,6 +116,82 @@ components: sources: redis: {
 				}
 			}
 		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type. Required when `data_type` is `stream`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					group: {
+						description: "The consumer group to read entries as. The group is created if it doesn't exist, along with the stream."
+						required:    true
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+					consumer: {
+						common:      false
+						description: "The name of this consumer within the group. Each Vector instance must use a distinct name. Defaults to the hostname."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["vector-0"]
+							syntax: "literal"
+						}
+					}
+					start_id: {
+						common:      false
+						description: "The ID of the last entry considered delivered to the group when it is created. `$` only reads entries added afterwards, while `0` reads the whole stream."
+						required:    false
+						type: string: {
+							default: "$"
+							examples: ["$", "0"]
+							syntax: "literal"
+						}
+					}
+					field: {
+						common:      false
+						description: "The field of entries holding the message to decode."
+						required:    false
+						type: string: {
+							default: "message"
+							syntax:  "literal"
+						}
+					}
+					id_key: {
+						common:      false
+						description: "The log field name to use for the entry ID. If not set, the ID is not added to the log event."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["id"]
+							syntax: "literal"
+						}
+					}
+					count: {
+						common:      false
+						description: "The maximum number of entries to read at once."
+						required:    false
+						type: uint: {
+							default: 100
+							unit:    null
+						}
+					}
+					claim_min_idle_secs: {
+						common:      false
+						description: "The time after which pending entries are claimed from other consumers of the group, such as crashed ones. This should be longer than it takes to deliver events."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 		redis_key: {
 			common:      false
 			description: "The log field name to use for the redis key. If set to an empty string or null, the key is not added to the log event."
@@ -146,6 +224,21 @@ components: sources: redis: {
 	}
 
 	how_it_works: {
+		consumer_groups: {
+			title: "Consumer groups"
+			body:  """
+				With the `stream` data type, entries are read with `XREADGROUP` as a member of a
+				consumer group, so that several Vector instances can share a stream. Entries are
+				acknowledged with `XACK` once their events are delivered, when acknowledgements are
+				enabled, or as soon as they are read otherwise.
+
+				On startup, the entries previously read by the consumer but never acknowledged are
+				read again. Entries left pending by other consumers, such as crashed ones, are
+				claimed with `XAUTOCLAIM` once they have been pending for `stream.claim_min_idle_secs`,
+				which requires Redis 6.2 or later. Entries whose events are rejected are left
+				pending, and delivered again once claimed.
+				"""
+		}
 		redis_rs: {
 			title: "redis-rs"
 			body:  """
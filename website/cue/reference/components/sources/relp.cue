@@ -0,0 +1,156 @@
+package metadata
+
+components: sources: relp: {
+	_port: 2514
+
+	title: "RELP"
+
+	description: """
+		Receives syslog messages over the Reliable Event Logging Protocol, as sent by the
+		omrelp module of rsyslog, acknowledging each message once its event is delivered.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		receive: {
+			from: {
+				service: services.relp
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: {
+				enabled: true
+			}
+			keepalive: enabled: true
+			tls: sources.socket.features.receive.tls
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		address: {
+			description: "The address to listen for TCP connections on."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+			}
+		}
+		connection_limit: {
+			common:      false
+			description: "The max number of TCP connections that will be processed."
+			required:    false
+			type: uint: {
+				default: null
+				unit:    "concurrency"
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the current host. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+		max_length: {
+			common:      false
+			description: "The maximum size of the messages of RELP frames. Connections sending larger messages are closed."
+			required:    false
+			type: uint: {
+				default: 102400
+				unit:    "bytes"
+			}
+		}
+	}
+
+	output: logs: line: {
+		description: "A syslog message received over RELP."
+		fields: {
+			appname:  components.sources.syslog.output.logs.line.fields.appname
+			facility: components.sources.syslog.output.logs.line.fields.facility
+			host: {
+				description: "The hostname extracted from the syslog message, or else the IP address of the RELP client."
+				required:    true
+				type: string: {
+					examples: ["my.host.com"]
+				}
+			}
+			hostname: components.sources.syslog.output.logs.line.fields.hostname
+			message: {
+				description: "The message extracted from the syslog message, or the whole message if it isn't a valid syslog message."
+				required:    true
+				type: string: {
+					examples: ["Hello world"]
+				}
+			}
+			msgid:    components.sources.syslog.output.logs.line.fields.msgid
+			procid:   components.sources.syslog.output.logs.line.fields.procid
+			severity: components.sources.syslog.output.logs.line.fields.severity
+			source_ip: {
+				description: "The IP address of the RELP client."
+				required:    true
+				type: string: {
+					examples: ["127.0.0.1"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["relp"]
+				}
+			}
+			timestamp:       components.sources.syslog.output.logs.line.fields.timestamp
+			version:         components.sources.syslog.output.logs.line.fields.version
+			client_metadata: fields._client_metadata
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				Each RELP transaction is answered once the events of its message are acknowledged by
+				the sinks when `acknowledgements` is enabled, or as soon as they are sent downstream
+				otherwise. If the events are rejected or can't be delivered, an error response is sent
+				and the connection is closed, so that the client sends the messages again.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,237 @@
+package metadata
+
+components: sources: sflow: {
+	_port: 6343
+
+	title: "sFlow"
+
+	description: """
+		Collects the flow and counter samples of sFlow v5 datagrams sent over UDP by
+		switches and routers.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.sflow
+				interface: socket: {
+					api: {
+						title: "sFlow v5"
+						url:   urls.sflow_v5
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled: false
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for sFlow datagrams on."
+			common:      true
+			required:    false
+			type: string: {
+				default: "0.0.0.0:\(_port)"
+				examples: ["0.0.0.0:6343"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the sending host. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+	}
+
+	output: logs: sample: {
+		description: "A flow sample or a counter sample."
+		fields: {
+			host: {
+				description: "The address of the host the datagram was received from."
+				required:    true
+				type: string: {
+					examples: [_values.remote_host]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["sflow"]
+				}
+			}
+			timestamp: {
+				description: "The time the datagram was received."
+				required:    true
+				type: timestamp: {}
+			}
+			agent_address: {
+				description: "The address of the agent, as reported in the datagram."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			sub_agent_id: {
+				description: "The ID of the sub-agent that sent the datagram."
+				required:    true
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the datagram."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+			uptime: {
+				description: "The uptime of the agent when the datagram was sent."
+				required:    true
+				type: uint: {
+					examples: [3600000]
+					unit: "milliseconds"
+				}
+			}
+			sample_type: {
+				description: "The type of the sample."
+				required:    true
+				type: string: {
+					enum: {
+						flow:    "A flow sample, describing a sampled packet."
+						counter: "A counter sample, carrying interface or device counters."
+					}
+				}
+			}
+			sample_sequence_number: {
+				description: "The sequence number of the sample for its data source."
+				required:    true
+				type: uint: {
+					examples: [1024]
+					unit: null
+				}
+			}
+			source_id_type: {
+				description: "The type of the data source, `0` being an interface."
+				required:    true
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			source_id_index: {
+				description: "The index of the data source, such as the `ifIndex` of an interface."
+				required:    true
+				type: uint: {
+					examples: [3]
+					unit: null
+				}
+			}
+			sampling_rate: {
+				description: "The sampling rate of flow samples, one packet being sampled out of this many."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [512]
+					unit: null
+				}
+			}
+			sample_pool: {
+				description: "The total number of packets that could have been sampled by the data source, for flow samples."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [1048576]
+					unit: null
+				}
+			}
+			drops: {
+				description: "The number of samples dropped by the agent due to lack of resources, for flow samples."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [0]
+					unit: null
+				}
+			}
+			"*": {
+				description: """
+					The records of the sample, each as an object such as `raw_packet_header`, `ipv4`,
+					`extended_switch`, `generic_interface` or `processor`.
+					"""
+				required:    false
+				type: object: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		records: {
+			title: "Records"
+			body:  """
+				Each flow or counter sample of a datagram is emitted as an event, with the
+				fields of the datagram header. The records of the sample are decoded into
+				objects named after their type, per the [sFlow v5 specification](\(urls.sflow_v5)):
+
+				* `raw_packet_header`, with the Ethernet, IP and transport fields of the sampled
+				  packet, `ethernet`, `ipv4` and `ipv6` for flow samples, along with
+				  `extended_switch` and `extended_router`.
+				* `generic_interface`, `ethernet_interface` and `processor` for counter samples.
+
+				Other records, including enterprise-specific ones, are skipped.
+				"""
+		}
+		sampling: {
+			title: "Sampling"
+			body:  """
+				Flow samples only describe one packet out of `sampling_rate`. Multiplying the
+				`frame_length` of sampled packets by their `sampling_rate` estimates the traffic
+				they represent, while the `sample_pool` and `drops` fields allow detecting
+				samples lost by the agent.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,302 @@
+package metadata
+
+components: sources: snmp_metrics: {
+	title: "SNMP Metrics"
+
+	description: """
+		Polls scalar objects and tables from SNMP agents, such as switches and routers,
+		and maps them into metrics.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.snmp
+
+				interface: {
+					socket: {
+						api: {
+							title: "SNMP"
+							url:   urls.snmp
+						}
+						direction: "outgoing"
+						protocols: ["udp"]
+						ssl: "disabled"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				The `des` privacy protocol is not available when Vector is linked against
+				OpenSSL 3 without its legacy provider.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		targets: {
+			description: "The SNMP agents to poll."
+			required:    true
+			type: array: items: type: object: options: {
+				address: {
+					description: "The address of the agent, including its port."
+					required:    true
+					type: string: {
+						examples: ["192.0.2.1:161", "switch.example.com:161"]
+					}
+				}
+				version: {
+					description: "The SNMP version used to poll the agent."
+					required:    false
+					type: string: {
+						default: "v2c"
+						enum: {
+							v1:  "SNMPv1, authenticated with a community."
+							v2c: "SNMPv2c, authenticated with a community."
+							v3:  "SNMPv3, authenticated with the credentials of a user."
+						}
+					}
+				}
+				community: {
+					description: "The community of SNMPv1 and SNMPv2c requests."
+					required:    false
+					type: string: {
+						default: "public"
+						examples: ["${SNMP_COMMUNITY}"]
+					}
+				}
+				user: {
+					description: "The user of SNMPv3 requests, required with `version = \"v3\"`."
+					required:    false
+					type: object: options: components.sources.snmp_trap.configuration.users.type.array.items.type.object.options
+				}
+				context_name: {
+					description: "The context name of SNMPv3 requests."
+					required:    false
+					type: string: {
+						default: ""
+					}
+				}
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between scrapes."
+			common:      true
+			required:    false
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		timeout_secs: {
+			description: "The time to wait for the response to a request before retrying it."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 5
+				unit:    "seconds"
+			}
+		}
+		retries: {
+			description: "The number of times a request is retried when it times out."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+		concurrency: {
+			description: "The maximum number of targets polled concurrently."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "concurrency"
+			}
+		}
+		max_repetitions: {
+			description: "The maximum number of objects requested at once when walking tables of SNMPv2c and SNMPv3 targets."
+			common:      false
+			required:    false
+			type: uint: {
+				default: 25
+				unit:    null
+			}
+		}
+		namespace: {
+			description: "The namespace of metrics. Disabled if empty."
+			common:      false
+			required:    false
+			type: string: {
+				default: "snmp"
+			}
+		}
+		metrics: {
+			description: "The scalar objects to poll, each mapped into one metric per target."
+			common:      true
+			required:    false
+			type: array: {
+				default: [
+					{name: "sys_up_time", oid: "1.3.6.1.2.1.1.3.0"},
+				]
+				items: type: object: options: {
+					name: {
+						description: "The name of the metric."
+						required:    true
+						type: string: {
+							examples: ["sys_up_time"]
+						}
+					}
+					oid: {
+						description: "The numeric OID of the object instance."
+						required:    true
+						type: string: {
+							examples: ["1.3.6.1.2.1.1.3.0"]
+						}
+					}
+					metric_type: {
+						description: "The type of the metric."
+						required:    false
+						type: string: {
+							default: "gauge"
+							enum: {
+								gauge:   "The object is mapped into a gauge."
+								counter: "The object is mapped into a counter, for monotonically increasing values."
+							}
+						}
+					}
+				}
+			}
+		}
+		tables: {
+			description: "The tables to poll."
+			common:      true
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					name: {
+						description: "The prefix of the names of the metrics of the table."
+						required:    true
+						type: string: {
+							examples: ["interface"]
+						}
+					}
+					columns: {
+						description: "The columns of the table to poll."
+						required:    true
+						type: array: items: type: object: options: {
+							name: {
+								description: "The name of the column, appended to the name of the table to name metrics."
+								required:    true
+								type: string: {
+									examples: ["in_octets", "descr"]
+								}
+							}
+							oid: {
+								description: "The numeric OID of the column."
+								required:    true
+								type: string: {
+									examples: ["1.3.6.1.2.1.2.2.1.10"]
+								}
+							}
+							type: {
+								description: "How the cells of the column are mapped."
+								required:    false
+								type: string: {
+									default: "gauge"
+									enum: {
+										gauge:   "The cells are mapped into gauges."
+										counter: "The cells are mapped into counters, for monotonically increasing values."
+										tag:     "The cells are added as tags to the metrics of their row."
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		polling: {
+			title: "Polling"
+			body:  """
+				Scalar objects are read with a single `GetRequest` per target and scrape,
+				and each table column is walked with `GetBulkRequest`, or `GetNextRequest`
+				for SNMPv1 targets. Numeric values, and strings formatting numbers, are
+				mapped into metrics, while other values are skipped.
+
+				Before polling SNMPv3 targets, Vector discovers their engine ID and time as
+				described in [RFC 3414](\(urls.snmp_usm)).
+				"""
+		}
+		tables: {
+			title: "Tables"
+			body:  """
+				The metrics of a table row are tagged with the `index` of the row, such as
+				`2` for the second interface of the `ifTable`, and with the values of its
+				`tag` columns. For example, the following table is mapped into
+				`interface_in_octets` counters tagged with the description of their
+				interface:
+
+				```toml
+				[[sources.my_source_id.tables]]
+				name = "interface"
+				columns = [
+				  { name = "descr", oid = "1.3.6.1.2.1.2.2.1.2", type = "tag" },
+				  { name = "in_octets", oid = "1.3.6.1.2.1.2.2.1.10", type = "counter" },
+				]
+				```
+				"""
+		}
+	}
+
+	output: metrics: {
+		up: {
+			description:       "If the SNMP agent is up or not."
+			type:              "gauge"
+			default_namespace: "snmp"
+			tags: {
+				host: {
+					description: "The address of the SNMP agent."
+					required:    true
+					examples: ["192.0.2.1:161"]
+				}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:              components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_seconds:             components.sources.internal_metrics.output.metrics.collect_duration_seconds
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,385 @@
+package metadata
+
+components: sources: snmp_trap: {
+	_port: 162
+
+	title: "SNMP Trap"
+
+	description: """
+		Receives SNMP traps and informs from network devices, replacing setups where
+		`snmptrapd` writes traps to a file that is then tailed.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.snmp
+				interface: socket: {
+					api: {
+						title: "SNMP"
+						url:   urls.snmp
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled: false
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				The `des` privacy protocol is not available when Vector is linked against
+				OpenSSL 3 without its legacy provider.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for traps and informs on."
+			common:      true
+			required:    false
+			type: string: {
+				default: "0.0.0.0:\(_port)"
+				examples: ["0.0.0.0:1162"]
+			}
+		}
+		mib_paths: {
+			description: "MIB files, or directories of MIB files, used to name the OIDs of traps and varbinds."
+			common:      true
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/usr/share/snmp/mibs"]
+				}
+			}
+		}
+		communities: {
+			description: "The communities accepted in SNMPv1 and SNMPv2c messages. All communities are accepted if empty."
+			common:      true
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["public"]
+				}
+			}
+		}
+		users: {
+			description: "The users accepted in SNMPv3 messages."
+			common:      false
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					name: {
+						description: "The name of the user."
+						required:    true
+						type: string: {
+							examples: ["vector"]
+						}
+					}
+					auth_protocol: {
+						description: "The authentication protocol of the user. Messages of users without one are neither authenticated nor encrypted."
+						required:    false
+						type: string: {
+							default: null
+							enum: {
+								md5:    "HMAC-MD5-96."
+								sha:    "HMAC-SHA-96."
+								sha224: "HMAC-SHA-224."
+								sha256: "HMAC-SHA-256."
+								sha384: "HMAC-SHA-384."
+								sha512: "HMAC-SHA-512."
+							}
+						}
+					}
+					auth_password: {
+						description: "The authentication password of the user, of at least 8 characters."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["${SNMP_AUTH_PASSWORD}"]
+						}
+					}
+					priv_protocol: {
+						description: "The privacy protocol of the user. Messages of users without one are not encrypted."
+						required:    false
+						type: string: {
+							default: null
+							enum: {
+								des: "CBC-DES."
+								aes: "CFB128-AES-128."
+							}
+						}
+					}
+					priv_password: {
+						description: "The privacy password of the user, of at least 8 characters."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["${SNMP_PRIV_PASSWORD}"]
+						}
+					}
+				}
+			}
+		}
+		engine_id: {
+			description: """
+				The SNMPv3 engine ID of Vector, as a hexadecimal string of 5 to 32 bytes.
+				SNMPv3 informs are rejected if it is not set.
+				"""
+			common:      false
+			required:    false
+			type: string: {
+				default: null
+				examples: ["80001f8804766563746f72"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the sender. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+	}
+
+	output: logs: trap: {
+		description: "An SNMP trap or inform."
+		fields: {
+			host: {
+				description: "The address of the sender."
+				required:    true
+				type: string: {
+					examples: [_values.remote_host]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["snmp_trap"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			version: {
+				description: "The SNMP version of the message."
+				required:    true
+				type: string: {
+					enum: {
+						"1":  "SNMPv1."
+						"2c": "SNMPv2c."
+						"3":  "SNMPv3."
+					}
+				}
+			}
+			community: {
+				description: "The community of SNMPv1 and SNMPv2c messages."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["public"]
+				}
+			}
+			user: {
+				description: "The user of SNMPv3 messages."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["vector"]
+				}
+			}
+			pdu_type: {
+				description: "The type of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						trap:   "An unacknowledged notification."
+						inform: "A notification acknowledged by Vector."
+					}
+				}
+			}
+			uptime: {
+				description: "The uptime of the sender, in hundredths of a second."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [123456]
+					unit: null
+				}
+			}
+			trap_oid: {
+				description: "The OID of the notification."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.6.3.1.1.5.3"]
+				}
+			}
+			trap_name: {
+				description: "The name of the notification, resolved with the MIB files."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["IF-MIB::linkDown"]
+				}
+			}
+			enterprise: {
+				description: "The enterprise of SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["SNMPv2-SMI::enterprises.8072.3.2.10"]
+				}
+			}
+			agent_address: {
+				description: "The agent address of SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["192.0.2.1"]
+				}
+			}
+			generic_trap: {
+				description: "The generic trap type of SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [2]
+					unit: null
+				}
+			}
+			specific_trap: {
+				description: "The specific trap type of SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [0]
+					unit: null
+				}
+			}
+			varbinds: {
+				description: "The variable bindings of the notification, keyed by the names of their OIDs."
+				required:    true
+				type: object: {
+					examples: [{"IF-MIB::ifIndex.2": 2, "IF-MIB::ifAdminStatus.2": "down"}]
+					options: {}
+				}
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "SNMPv2c linkDown trap"
+			configuration: {
+				communities: ["public"]
+				mib_paths: ["/usr/share/snmp/mibs"]
+			}
+			input: """
+				```text
+				snmptrap -v 2c -c public localhost:162 '' IF-MIB::linkDown \\
+				  IF-MIB::ifIndex.2 i 2 IF-MIB::ifAdminStatus.2 i 2 IF-MIB::ifDescr.2 s eth1
+				```
+				"""
+			output: log: {
+				host:        _values.remote_host
+				source_type: "snmp_trap"
+				timestamp:   "2020-10-10T17:07:36.452332Z"
+				version:     "2c"
+				community:   "public"
+				pdu_type:    "trap"
+				uptime:      123456
+				trap_oid:    "1.3.6.1.6.3.1.1.5.3"
+				trap_name:   "IF-MIB::linkDown"
+				varbinds: {
+					"IF-MIB::ifIndex.2":       2
+					"IF-MIB::ifAdminStatus.2": "down"
+					"IF-MIB::ifDescr.2":       "eth1"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		mibs: {
+			title: "MIB files"
+			body:  """
+				The OIDs of notifications and varbinds are named `MODULE::object.index`
+				using the objects defined in the MIB files of `mib_paths`, and enumerated
+				integers are replaced by their labels. OIDs unknown to these files are kept
+				in numeric form, and a few common objects, such as the standard traps, are
+				always known.
+
+				SNMPv1 traps are converted into SNMPv2 notification OIDs as described in
+				[RFC 3584](\(urls.snmp_v1_v2_coexistence)).
+				"""
+		}
+		values: {
+			title: "Values"
+			body:  """
+				Octet strings are decoded as text when printable, and otherwise formatted as
+				colon-separated hexadecimal bytes. IP addresses are formatted in dotted
+				notation, and counters, gauges and time ticks are decoded as integers.
+				"""
+		}
+		informs: {
+			title: "Informs"
+			body:  """
+				Informs are acknowledged by sending a response back to their sender, after
+				their community or user has been accepted.
+
+				Vector is the authoritative engine of the SNMPv3 informs it receives: senders
+				must use the configured `engine_id`, or discover it as described in
+				[RFC 3414](\(urls.snmp_usm)). The engine time of Vector is not checked
+				against the time of received messages.
+				"""
+		}
+		security: {
+			title: "SNMPv3 security"
+			body:  """
+				SNMPv3 messages must use the security level of their user: messages of users
+				with an `auth_protocol` are authenticated, and messages of users with a
+				`priv_protocol` are also encrypted. Messages from unknown users or failing
+				authentication are rejected.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -115,6 +115,37 @@ components: sources: socket: {
 				examples: ["/path/to/socket"]
 			}
 		}
+		peer_credentials: {
+			common:        false
+			description:   "The credentials of the peer processes added to events."
+			relevant_when: "mode = `unix_datagram` or `unix_stream`"
+			required:      false
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Whether to add the process ID, user ID, and group ID of the peer process to each event, as `pid`, `uid`, and `gid` under `key`. They are reported by the kernel, so they can't be forged by the peer. With the `unix_datagram` mode, this is only supported on Linux."
+						required:    false
+						type: bool: default: false
+					}
+					key: {
+						common:      false
+						description: "The name of the log field the credentials are added under."
+						required:    false
+						type: string: {
+							default: "peer"
+						}
+					}
+					process_name: {
+						common:      false
+						description: "Whether to also add the name of the peer process, as `process_name`. The name is read from `/proc/<pid>/comm`, so it's only supported on Linux. It's missing if the process has already exited, and unlike the IDs, it can be changed by the process itself."
+						required:    false
+						type: bool: default: false
+					}
+				}
+			}
+		}
 		socket_file_mode: {
 			common: false
 			description: """
@@ -151,6 +182,18 @@ components: sources: socket: {
 				unit:    "concurrency"
 			}
 		}
+		proxy_protocol: {
+			common:      false
+			description: """
+				Whether connections start with a [PROXY protocol](\(urls.haproxy_proxy_protocol)) header, as sent by
+				load balancers such as HAProxy, holding the address of the client. When enabled, the address and port of
+				the client are used as the peer host and port rather than those of the load balancer, and connections not
+				starting with a valid header are closed.
+				"""
+			relevant_when: "mode = `tcp`"
+			required:      false
+			type: bool: default: false
+		}
 	}
 
 	output: logs: line: {
@@ -176,6 +219,15 @@ components: sources: socket: {
 				}
 			}
 			client_metadata: fields._client_metadata
+			peer: {
+				description: "The credentials of the peer process, when `peer_credentials.enabled` is set."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"pid": 1234, "uid": 1000, "gid": 1000, "process_name": "myapp"}]
+					options: {}
+				}
+			}
 		}
 	}
 
@@ -102,7 +102,12 @@ components: sources: statsd: {
 				unit:    "concurrency"
 			}
 		}
-
+		dogstatsd_events: {
+			common:      false
+			description: "Whether to decode DogStatsD events and service checks into log events. Otherwise, they are rejected as invalid records."
+			required:    false
+			type: bool: default: false
+		}
 	}
 
 	output: metrics: {
@@ -112,6 +117,94 @@ components: sources: statsd: {
 		set:          output._passthrough_set
 	}
 
+	output: logs: {
+		event: {
+			description: "A DogStatsD event, when `dogstatsd_events` is enabled."
+			fields: {
+				title: {
+					description: "The title of the event."
+					required:    true
+					type: string: examples: ["Deployment finished"]
+				}
+				message: {
+					description: "The text of the event."
+					required:    true
+					type: string: examples: ["Version 1.2.3 was deployed"]
+				}
+				priority: {
+					description: "The priority of the event."
+					required:    true
+					type: string: examples: ["normal", "low"]
+				}
+				alert_type: {
+					description: "The alert type of the event."
+					required:    true
+					type: string: examples: ["info", "warning", "error", "success"]
+				}
+				aggregation_key: {
+					description: "The key used to group the event with others."
+					required:    false
+					common:      false
+					type: string: {
+						default: null
+						examples: ["deploy"]
+					}
+				}
+				host: fields._local_host
+				tags: {
+					description: "The tags of the event."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"env": "prod"}]
+						options: {}
+					}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+		service_check: {
+			description: "A DogStatsD service check, when `dogstatsd_events` is enabled."
+			fields: {
+				name: {
+					description: "The name of the service check."
+					required:    true
+					type: string: examples: ["app.health"]
+				}
+				status: {
+					description: "The status of the service check."
+					required:    true
+					type: string: enum: {
+						ok:       "The service is healthy."
+						warning:  "The service is degraded."
+						critical: "The service is failing."
+						unknown:  "The status of the service is unknown."
+					}
+				}
+				message: {
+					description: "The message describing the status."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["Connection refused"]
+					}
+				}
+				host: fields._local_host
+				tags: {
+					description: "The tags of the service check."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"env": "prod"}]
+						options: {}
+					}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+	}
+
 	how_it_works: {
 		timestamps: {
 			title: "Timestamps"
@@ -122,6 +215,20 @@ components: sources: statsd: {
 				`null` timestamps are substituted with the current time by downstream sinks or
 				third-party services during sending/ingestion. See the
 				[metric data model](\(urls.vector_metric)) page for more info.
+
+				Metrics using the DogStatsD `|T` extension are assigned the timestamp they were sent
+				with instead.
+				"""
+		}
+		dogstatsd: {
+			title: "DogStatsD"
+			body:  """
+				The [DogStatsD](\(urls.dogstatsd)) extensions to the protocol are supported: tags,
+				multiple values in a single histogram, timing or distribution record, and timestamps.
+				Other extensions, such as container IDs, are ignored.
+
+				DogStatsD events (`_e{...}`) and service checks (`_sc|...`) are decoded into log
+				events when `dogstatsd_events` is enabled.
 				"""
 		}
 	}
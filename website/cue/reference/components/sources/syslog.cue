@@ -108,6 +108,18 @@ components: sources: syslog: {
 				unit:    "concurrency"
 			}
 		}
+		proxy_protocol: {
+			common:      false
+			description: """
+				Whether connections start with a [PROXY protocol](\(urls.haproxy_proxy_protocol)) header, as sent by
+				load balancers such as HAProxy, holding the address of the client. When enabled, the address of the client
+				is used as the peer host rather than that of the load balancer, and connections not starting with a valid
+				header are closed.
+				"""
+			relevant_when: "mode = `tcp`"
+			required:      false
+			type: bool: default: false
+		}
 	}
 
 	output: logs: line: {
@@ -0,0 +1,169 @@
+package metadata
+
+components: sources: websocket: {
+	title: "WebSocket"
+
+	description: """
+		Connects to a WebSocket endpoint, and decodes each text or binary message it
+		receives into events, for APIs which only stream over WebSocket.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.websocket
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${WEBSOCKET_PASSWORD}"
+			username_example: "${WEBSOCKET_USERNAME}"
+		}}
+		headers: {
+			common:      false
+			description: "Additional headers to send with the handshake request."
+			required:    false
+			type: object: {
+				examples: [{"X-API-Key": "${WEBSOCKET_API_KEY}"}]
+				options: {}
+			}
+		}
+		ping_interval: {
+			common:      true
+			description: "Send WebSocket pings each this number of seconds, to keep the connection alive."
+			required:    false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		ping_timeout: {
+			common:        true
+			description:   "Reconnect to the WebSocket server if a pong isn't received for this number of seconds."
+			relevant_when: "ping_interval is set"
+			required:      false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		resume: {
+			common:      false
+			description: "Resumes the stream after reconnecting, by sending the position of the last event received as a query parameter."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					field: {
+						description: "The field of the events holding their position in the stream, such as a sequence number or an ID."
+						required:    true
+						type: string: {
+							examples: ["sequence"]
+						}
+					}
+					query_parameter: {
+						description: "The query parameter set to the value of `field` in the last event received, when reconnecting."
+						required:    true
+						type: string: {
+							examples: ["since"]
+						}
+					}
+				}
+			}
+		}
+		uri: {
+			description: """
+				The WebSocket URI to connect to. This should include the protocol and host,
+				but can also include the port, path, and any other valid part of a URI.
+				"""
+			required: true
+			type: string: {
+				examples: ["wss://stream.example.com/v1/events"]
+			}
+		}
+	}
+
+	output: logs: message: {
+		description: "An individual message received from the WebSocket server."
+		fields: {
+			message: {
+				description: "The decoded message."
+				required:    true
+				type: string: {
+					examples: ["{\"sequence\":42,\"type\":\"trade\"}"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["websocket"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		reconnection: {
+			title: "Reconnection"
+			body: """
+				When the connection is closed by the server, or lost, the source reconnects with an
+				exponential backoff of up to a minute. If `resume` is set, the value of its `field`
+				in the last event received is sent as its `query_parameter`, so that the server can
+				resume the stream where it left off.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_established_total:         components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_shutdown_total:            components.sources.internal_metrics.output.metrics.connection_shutdown_total
+	}
+}
@@ -0,0 +1,204 @@
+package metadata
+
+components: transforms: aggregate_logs: {
+	title: "Aggregate Logs"
+
+	description: """
+		Aggregates log events over time windows into a single summarizing event per group,
+		with the number of events, statistics of numeric fields, and a sample of messages.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		group_by: {
+			common: true
+			description: """
+				The fields by which to group events, mapped to the template rendering their value. Each group of
+				events with the same rendered values is aggregated separately, and its fields are added to its
+				aggregated event. When a template can't be rendered, such as when its field is missing, the event is
+				grouped without that field. When no fields are specified, all events are aggregated in a single group.
+				"""
+			required: false
+			type: object: {
+				examples: [{service: "{{ service }}", status: "{{ status_code }}"}]
+				options: {}
+			}
+		}
+		max: {
+			common:      true
+			description: "The numeric fields whose maximum is kept."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["duration_ms"]
+				}
+			}
+		}
+		max_groups: {
+			common: false
+			description: """
+				The maximum number of groups held at once, across all open windows. When a new group would exceed it,
+				the group of the oldest window is flushed early.
+				"""
+			required: false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		min: {
+			common:      true
+			description: "The numeric fields whose minimum is kept."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["duration_ms"]
+				}
+			}
+		}
+		sample_size: {
+			common:      false
+			description: "The number of messages of each group kept in its aggregated event. The first messages of each window are kept."
+			required:    false
+			type: uint: {
+				default: 3
+				unit:    null
+			}
+		}
+		slide_secs: {
+			common: false
+			description: """
+				The interval between the starts of successive windows. When smaller than `window_secs`, windows overlap,
+				and each event is aggregated in every window it falls into. By default, windows don't overlap.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [10]
+				unit: "seconds"
+			}
+		}
+		sum: {
+			common:      true
+			description: "The numeric fields to sum."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["bytes"]
+				}
+			}
+		}
+		window_secs: {
+			common: true
+			description: """
+				The length of the windows over which events are aggregated. Windows are aligned on the wall clock, and
+				events are assigned to windows by the time they are received.
+				"""
+			required: false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		groups_evicted_total: components.sources.internal_metrics.output.metrics.groups_evicted_total
+	}
+
+	examples: [
+		{
+			title: "Request summary"
+			input: [
+				{
+					log: {
+						message:     "GET /users"
+						service:     "api"
+						duration_ms: 12
+					}
+				},
+				{
+					log: {
+						message:     "GET /orders"
+						service:     "api"
+						duration_ms: 30
+					}
+				},
+			]
+
+			configuration: {
+				group_by: service: "{{ service }}"
+				window_secs: 60
+				sum: ["duration_ms"]
+				max: ["duration_ms"]
+			}
+
+			output: [
+				{
+					log: {
+						service:      "api"
+						count:        2
+						window_start: "2020-10-07T12:33:00Z"
+						window_end:   "2020-10-07T12:34:00Z"
+						sum: duration_ms: 42.0
+						max: duration_ms: 30.0
+						samples: ["GET /users", "GET /orders"]
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				Events are aggregated in the windows they are received in. Windows are `window_secs` long and start
+				every `slide_secs`, aligned on the wall clock. Each group of a window is flushed as a single event once
+				its window ends, and all groups are flushed when Vector stops.
+				"""
+		}
+		aggregated_events: {
+			title: "Aggregated events"
+			body: """
+				Each aggregated event holds the fields of `group_by`, the `count` of its events, its `window_start`
+				and `window_end`, and the `sum`, `min`, and `max` objects, keyed by field, of the numeric fields
+				configured. Fields without any numeric values are left out. The first `sample_size` messages of the
+				group are kept in `samples`.
+				"""
+		}
+		memory: {
+			title: "Memory"
+			body: """
+				The groups of all open windows are held in memory, and are bounded by `max_groups`. When the bound is
+				reached, the group of the oldest window is flushed early, and counted by the `groups_evicted_total`
+				metric.
+				"""
+		}
+	}
+}
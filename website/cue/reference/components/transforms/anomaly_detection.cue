@@ -0,0 +1,123 @@
+package metadata
+
+components: transforms: anomaly_detection: {
+	title: "Anomaly Detection"
+
+	description: """
+		Detects anomalous metric values, by comparing each value to the exponentially weighted moving average and
+		variance of its series, and annotates them and emits an alert for each of them.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		alpha: {
+			common: true
+			description: """
+				The weight of each new value in the moving average and variance of its series, greater than 0 and at
+				most 1. Higher weights adapt faster to changes in the series, but make its deviations less significant.
+				"""
+			required: false
+			type: float: {
+				default: 0.1
+			}
+		}
+		annotate: {
+			common:      false
+			description: "Whether to add the `anomaly` and `anomaly_score` tags to the anomalous metrics."
+			required:    false
+			type: bool: default: true
+		}
+		max_series: {
+			common: false
+			description: """
+				The maximum number of series to keep the state of. Once reached, the state of the least recently seen
+				series is forgotten, and it's learned again if it's seen again.
+				"""
+			required: false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		threshold: {
+			common:      true
+			description: "The number of standard deviations from the moving average beyond which a value is anomalous."
+			required:    false
+			type: float: {
+				default: 3.0
+			}
+		}
+		warmup_samples: {
+			common:      false
+			description: "The number of values of a series to learn from before detecting its anomalies."
+			required:    false
+			type: uint: {
+				default: 30
+				unit:    null
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    false
+			set:          false
+			summary:      false
+		}
+		traces: false
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "alerts"
+			description: """
+				A log event for each anomalous value, holding the `metric` name, namespace, and tags, its `value`, the
+				`expected` value, the `stddev` of its series, and its `score`. For a transform component named `foo`,
+				this output can be accessed by specifying `foo.alerts` as the input to another component.
+				"""
+		},
+	]
+
+	telemetry: metrics: {
+		anomalies_detected_total: components.sources.internal_metrics.output.metrics.anomalies_detected_total
+	}
+
+	how_it_works: {
+		scoring: {
+			title: "Scoring"
+			body: """
+				Each series, identified by its name, namespace, and tags, keeps the exponentially weighted moving average
+				and variance of its values. The score of a value is its distance to the average, in standard deviations,
+				before the value is learned. Once a series has learned `warmup_samples` values, the values whose score
+				is at least `threshold` in absolute value are anomalous.
+				"""
+		}
+		values: {
+			title: "Analyzed values"
+			body: """
+				The values of gauges and incremental counters are analyzed as they are. Absolute counters only increase,
+				so the differences between their successive values are analyzed instead, and their resets are ignored.
+				Other metrics are forwarded without being analyzed.
+				"""
+		}
+	}
+}
@@ -40,6 +40,37 @@ components: transforms: dedupe: {
 							unit:    null
 						}
 					}
+					window_secs: {
+						common:      false
+						description: "The time window of deduplication. When set, Events are only duplicates of the Events first seen less than `window_secs` ago. With the `bloom` backend, Events are remembered for between one and two windows."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [60, 3600]
+							unit: "seconds"
+						}
+					}
+					backend: {
+						common:      false
+						description: "The backend remembering the Events seen."
+						required:    false
+						type: string: {
+							default: "lru"
+							enum: {
+								lru:   "Remembers the last `num_events` distinct Events, evicting the least recently seen ones first."
+								bloom: "Remembers the Events in two rotating bloom filters, sized for `num_events` Events each. Their size doesn't depend on the size of the Events, but Events are wrongly considered duplicates at the rate of `false_positive_rate`."
+							}
+						}
+					}
+					false_positive_rate: {
+						common:        false
+						description:   "The rate at which the `bloom` backend wrongly considers Events duplicates, between 0 and 1."
+						relevant_when: "backend = \"bloom\""
+						required:      false
+						type: float: {
+							default: 0.0001
+						}
+					}
 				}
 			}
 		}
@@ -96,6 +127,25 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		bloom_backend: {
+			title: "Bloom Filter Backend"
+			body: """
+				With `cache.backend` set to `bloom`, Events are remembered in two
+				bloom filters instead. Each filter holds up to `cache.num_events`
+				Events, using about `1.44 * log2(1 / false_positive_rate)` bits per
+				Event, whatever the size of the Events. New Events are added to the
+				current filter, and once it's full, or once `cache.window_secs` has
+				elapsed, it replaces the previous filter, which is discarded. Events
+				are duplicates if either filter contains them, so the last
+				`cache.num_events` distinct Events are always remembered.
+
+				Bloom filters have false positives: at the rate of
+				`cache.false_positive_rate`, Events that were never seen are
+				considered duplicates and discarded. They never have false
+				negatives.
+				"""
+		}
+
 		memory_usage_details: {
 			title: "Memory Usage Details"
 			body: """
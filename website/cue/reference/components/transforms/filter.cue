@@ -33,6 +33,12 @@ components: transforms: filter: {
 			required: true
 			type: condition: {}
 		}
+		reroute_dropped: {
+			common:      false
+			description: "Send the events not matching the condition to the `dropped` output instead of dropping them."
+			required:    false
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -79,6 +85,18 @@ components: transforms: filter: {
 		},
 	]
 
+	outputs: [
+		components._default_output,
+		{
+			name: "dropped"
+			description: """
+				When `reroute_dropped` is set to `true`, the events not matching the condition are sent to the `dropped`
+				output, unmodified, instead of being dropped. For a transform component named `foo`, this
+				output can be accessed by specifying `foo.dropped` as the input to another component.
+				"""
+		},
+	]
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
@@ -5,7 +5,8 @@ components: transforms: geoip: {
 
 	description: """
 		Enrich events with geolocation data from the MaxMind GeoIP2-City,
-		GeoLite2-City, GeoIP2-ISP and GeoLite2-ASN databases.
+		GeoLite2-City, GeoIP2-ISP, GeoLite2-ASN and GeoIP2-Connection-Type
+		databases, or from the IPinfo MMDB databases.
 		"""
 
 	classes: {
@@ -33,20 +34,70 @@ components: transforms: geoip: {
 
 	configuration: {
 		database: {
+			common: true
 			description: """
 				Path to the [MaxMind GeoIP2](\(urls.maxmind_geoip2)) or [GeoLite2 binary city
-				database](\(urls.maxmind_geolite2_city)) file (`GeoLite2-City.mmdb`). Other
-				databases, such as the country database, are not supported.
+				database](\(urls.maxmind_geolite2_city)) file (`GeoLite2-City.mmdb`). ASN, ISP,
+				and Connection-Type databases are also supported, as well as the
+				[IPinfo](\(urls.ipinfo_mmdb)) MMDB databases. Other databases, such as the
+				country database, are not supported. At least one of `database` or `databases`
+				must be set.
 				"""
-			required:    true
+			required: false
 			type: string: {
+				default: null
 				examples: ["/path/to/GeoLite2-City.mmdb", "/path/to/GeoLite2-ISP.mmdb"]
 			}
 		}
+		databases: {
+			common: false
+			description: """
+				Paths to additional databases, looked up along with `database`. The GeoIP data
+				of all the databases is merged, so that, for example, a City and an ASN database
+				can be used together. When several databases provide the same field, the value
+				of the last one is kept.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/path/to/GeoLite2-ASN.mmdb", "/path/to/GeoIP2-Connection-Type.mmdb"]
+				}
+			}
+		}
+		fields: {
+			common: false
+			description: """
+				The IP fields to enrich, in addition to `source`, mapped to the field to insert
+				their GeoIP data into. At least one of `source` or `fields` must be set.
+				"""
+			required: false
+			type: object: {
+				examples: [{"client.ip": "client.geoip", "server.ip": "server.geoip"}]
+				options: {}
+			}
+		}
+		reload_interval_secs: {
+			common: false
+			description: """
+				The interval between the checks for changes of the databases. When a database
+				file is modified, it's reloaded, and the lookups use the previous version of the
+				database until the new one is loaded. By default, the databases are not
+				reloaded.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [60]
+				unit: "seconds"
+			}
+		}
 		source: {
-			description: "The field name that contains the IP address. This field should contain a valid IPv4 or IPv6 address."
-			required:    true
+			common:      true
+			description: "The field name that contains the IP address. This field should contain a valid IPv4 or IPv6 address. Its GeoIP data is inserted into `target`."
+			required:    false
 			type: string: {
+				default: null
 				examples: ["ip_address", "x-forwarded-for", "parent.child", "array[0]"]
 			}
 		}
@@ -94,11 +145,38 @@ components: transforms: geoip: {
 				* [GeoIP2-ISP.mmdb](\(urls.maxmind_geoip2_isp)) (paid) — Determine the Internet
 					Service Provider (ISP), organization name, and autonomous system organization
 					and number associated with an IP address.
+				* [GeoIP2-Connection-Type.mmdb](\(urls.maxmind_geoip2_connection_type)) (paid) —
+					Determine the type of connection, such as `Cable/DSL` or `Cellular`,
+					associated with an IP address.
+
+				The [IPinfo](\(urls.ipinfo_mmdb)) databases are also supported. Their fields are
+				renamed to those of the MaxMind databases when they match, such as `city` to
+				`city_name` or `asn` to `autonomous_system_number`, and are kept as they are
+				otherwise.
 
 				The database files should be in the [MaxMind DB file
 				format](\(urls.maxmind_db_file_format)).
 				"""
 		}
+		multiple_databases: {
+			title: "Multiple databases and fields"
+			body: """
+				Several databases can be used at once with `databases`, such as a City, an ASN,
+				and a Connection-Type database, in which case the fields of all of them are
+				inserted into the target field. Several IP fields can also be enriched in one
+				pass with `fields`, each into its own target field.
+				"""
+		}
+		reloading: {
+			title: "Reloading databases"
+			body: """
+				With `reload_interval_secs`, the modification times of the database files are
+				checked periodically, and the modified databases are reloaded without
+				interrupting the lookups. When a database fails to load, such as when it's
+				still being written, its previous version keeps being used, and it's loaded
+				again at the next check.
+				"""
+		}
 	}
 
 	output: logs: line: {
@@ -111,8 +189,8 @@ components: transforms: geoip: {
 		fields: {
 			geoip: {
 				description: """
-					The root field containing all geolocation data as subfields. Depending on the
-					database used, either the city or the ISP field is populated.
+					The root field containing all geolocation data as subfields. The fields of
+					each database used are populated.
 					"""
 				required: true
 				type: object: {
@@ -162,6 +240,20 @@ components: transforms: geoip: {
 							}
 							groups: ["City"]
 						}
+						connection_type: {
+							description: """
+								The type of connection associated with the IP address. Available with
+								the [GeoIP2-Connection-Type](\(urls.maxmind_geoip2_connection_type))
+								database.
+								"""
+							required: false
+							common:   false
+							type: string: {
+								default: null
+								examples: ["Cable/DSL", "Cellular", "Corporate", "Satellite"]
+							}
+							groups: ["Connection-Type"]
+						}
 						continent_code: {
 							description: """
 								The continent code associated with the IP address.
@@ -308,6 +400,7 @@ components: transforms: geoip: {
 	}
 
 	telemetry: metrics: {
-		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+		geoip_database_reloads_total: components.sources.internal_metrics.output.metrics.geoip_database_reloads_total
+		processing_errors_total:      components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
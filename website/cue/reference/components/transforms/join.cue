@@ -0,0 +1,172 @@
+package metadata
+
+components: transforms: join: {
+	title: "Join"
+
+	description: """
+		Joins the log events of two sets of inputs on a key, within a time window, such as request logs with the
+		authentication logs of their requests.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		emit_unmatched: {
+			common: false
+			description: """
+				Whether to send unmatched events to the `<transform_name>.unmatched_left` and
+				`<transform_name>.unmatched_right` outputs. Otherwise, unmatched events are discarded.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		left: {
+			description: "The left side of the join, into whose events the events of the right side are merged."
+			required:    true
+			type: object: options: {
+				inputs: {
+					description: "The components whose events make up this side of the join. The `inputs` option of the transform itself must not be set."
+					required:    true
+					type: array: items: type: string: {
+						examples: ["requests"]
+					}
+				}
+				key: {
+					description: "The key on which events are joined. Events of both sides whose keys render to the same value are joined. Events whose key can't be rendered are unmatched."
+					required:    true
+					type: string: {
+						examples: ["{{ request_id }}"]
+						syntax: "template"
+					}
+				}
+			}
+		}
+		right: {
+			description: "The right side of the join."
+			required:    true
+			type: object: options: {
+				inputs: {
+					description: "The components whose events make up this side of the join."
+					required:    true
+					type: array: items: type: string: {
+						examples: ["auth"]
+					}
+				}
+				key: {
+					description: "The key on which events are joined."
+					required:    true
+					type: string: {
+						examples: ["{{ request_id }}"]
+						syntax: "template"
+					}
+				}
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "How long an event waits for an event of the other side with the same key. Events that aren't matched by the end of their window are unmatched."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	outputs: [
+		{
+			name:        "unmatched_left"
+			description: "The events of the left side that weren't matched, when `emit_unmatched` is enabled. They can be referenced as an input by other components with the name `<transform_name>.unmatched_left`."
+		},
+		{
+			name:        "unmatched_right"
+			description: "The events of the right side that weren't matched, when `emit_unmatched` is enabled. They can be referenced as an input by other components with the name `<transform_name>.unmatched_right`."
+		},
+	]
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+
+	examples: [
+		{
+			title: "Join requests with their authentication"
+			input: [
+				{
+					log: {
+						request_id: "1"
+						message:    "GET /users"
+					}
+				},
+				{
+					log: {
+						request_id: "1"
+						user:       "alice"
+					}
+				},
+			]
+
+			configuration: {
+				left: {
+					inputs: ["requests"]
+					key: "{{ request_id }}"
+				}
+				right: {
+					inputs: ["auth"]
+					key: "{{ request_id }}"
+				}
+			}
+
+			output: [
+				{
+					log: {
+						request_id: "1"
+						message:    "GET /users"
+						user:       "alice"
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		matching: {
+			title: "Matching"
+			body: """
+				Events are buffered until an event of the other side with the same key is received, for up to
+				`window_secs`. Each event is joined with at most one event of the other side, the earliest buffered
+				one. Joined events hold the fields of the left event, and the fields of the right event that the left
+				event doesn't have. Events still buffered when Vector stops are unmatched.
+				"""
+		}
+		inputs: {
+			title: "Inputs"
+			body: """
+				As no transform can tell which input an event comes from, the inputs of each side are set by
+				`left.inputs` and `right.inputs`, and the transform expands into inner transforms, named
+				`<transform_name>.left`, `<transform_name>.right`, and `<transform_name>.matcher`.
+				"""
+		}
+	}
+}
@@ -0,0 +1,101 @@
+package metadata
+
+components: transforms: json_schema: {
+	title: "JSON Schema"
+
+	description: """
+		Validates log events against a JSON Schema, annotating the events failing validation with their errors,
+		or rerouting them to a `rejected` output, so that bad data can be quarantined before it reaches
+		expensive sinks.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		max_errors: {
+			common:      false
+			description: "The maximum number of validation errors annotating an event."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		reroute_rejected: {
+			common:      true
+			description: "Send the events failing validation to the `rejected` output, rather than to the default output."
+			required:    false
+			type: bool: default: false
+		}
+		schema_file: {
+			description: "The path to the JSON Schema to validate the events against. Schemas follow draft 2020-12, unless they declare another draft with `$schema`. References to other documents are not resolved."
+			required:    true
+			type: string: {
+				examples: ["/etc/vector/schemas/event.json"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "rejected"
+			description: """
+				When `reroute_rejected` is set to `true`, the events failing validation are sent to the
+				`rejected` output instead of the default output. For a transform component named `foo`,
+				this output can be accessed by specifying `foo.rejected` as the input to another component.
+				"""
+		},
+	]
+
+	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+
+	how_it_works: {
+		annotations: {
+			title: "Annotations"
+			body: """
+				The events failing validation are annotated with their errors under `metadata.rejected`,
+				in both the default and `rejected` outputs:
+
+				```json
+				{
+				  "metadata": {
+				    "rejected": {
+				      "reason": "json_schema",
+				      "errors": [{ "path": "/status", "message": "1000 is greater than the maximum of 599" }],
+				      "component_id": "validate",
+				      "component_type": "json_schema",
+				      "component_kind": "transform"
+				    }
+				  }
+				}
+				```
+
+				The path of each error is a JSON pointer to the invalid value in the event.
+				"""
+		}
+	}
+}
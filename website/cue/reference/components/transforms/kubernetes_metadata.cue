@@ -0,0 +1,214 @@
+package metadata
+
+components: transforms: kubernetes_metadata: {
+	title: "Kubernetes Metadata"
+
+	description: """
+		Enriches events with the metadata of the Kubernetes pods which sent them, as well as with the metadata of
+		their namespaces and nodes.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "Kubernetes API"
+				url:      urls.kubernetes_api
+				versions: ">= 1.19"
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				Vector must be allowed to `list` and `watch` the pods, namespaces and nodes of the cluster. See the
+				[Kubernetes authorization documentation](\(urls.kubernetes_authorization)) for more details.
+				""",
+		]
+		notices: []
+		warnings: []
+	}
+
+	configuration: {
+		delay_deletion_ms: {
+			common:      false
+			description: "How long to keep the metadata of deleted objects, so that the events sent by a pod right before it got deleted can still be enriched."
+			required:    false
+			type: uint: {
+				default: 60_000
+				unit:    "milliseconds"
+			}
+		}
+		kube_config_file: {
+			common:      false
+			description: "Optional path to a readable kubeconfig file. If not set, a connection to Kubernetes is made using the in-cluster configuration."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/path/to/.kube/config"]
+			}
+		}
+		pod_ip_field: {
+			common:      true
+			description: "The field holding the IP address of the pod which sent the event. Pods using the host network are never matched by their IP address, as it is shared with their node. At least one of `pod_ip_field` and `pod_uid_field` must be set."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["source_ip", "resource.attributes.\"k8s.pod.ip\""]
+			}
+		}
+		pod_uid_field: {
+			common:      true
+			description: "The field holding the UID of the pod which sent the event. When both `pod_uid_field` and `pod_ip_field` are set, the UID is looked up first."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["resource.attributes.\"k8s.pod.uid\""]
+			}
+		}
+		target: {
+			common:      true
+			description: "The field under which the metadata is inserted."
+			required:    false
+			type: string: {
+				default: "kubernetes"
+				examples: ["kubernetes", "k8s"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	output: logs: log: {
+		description: "Log event enriched with the Kubernetes metadata, under the `target` field."
+		fields: {
+			pod_name: {
+				description: "The name of the pod."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["web-6d8f7c9b5-x2x4z"]
+				}
+			}
+			pod_namespace: {
+				description: "The namespace of the pod."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["default"]
+				}
+			}
+			pod_uid: {
+				description: "The UID of the pod."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["ba46d8c9-9541-4f6b-bbf9-d23b36f2f136"]
+				}
+			}
+			pod_ip: {
+				description: "The IP address of the pod."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["10.0.0.12"]
+				}
+			}
+			pod_node_name: {
+				description: "The name of the node the pod runs on."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["minikube"]
+				}
+			}
+			pod_labels: {
+				description: "The labels of the pod."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"app.kubernetes.io/name": "web"}]
+					options: {}
+				}
+			}
+			pod_annotations: {
+				description: "The annotations of the pod."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"prometheus.io/scrape": "true"}]
+					options: {}
+				}
+			}
+			namespace_labels: {
+				description: "The labels of the namespace of the pod."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"kubernetes.io/metadata.name": "default"}]
+					options: {}
+				}
+			}
+			namespace_annotations: {
+				description: "The annotations of the namespace of the pod."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"team": "core"}]
+					options: {}
+				}
+			}
+			node_labels: {
+				description: "The labels of the node the pod runs on."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"kubernetes.io/hostname": "minikube"}]
+					options: {}
+				}
+			}
+			node_annotations: {
+				description: "The annotations of the node the pod runs on."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"node.alpha.kubernetes.io/ttl": "0"}]
+					options: {}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		lookup: {
+			title: "Pod Lookup"
+			body: """
+				The pods, namespaces and nodes of the cluster are watched through the Kubernetes API, so that
+				events sent by in-cluster workloads through other sources, such as `syslog` or `opentelemetry`, can
+				be enriched with the same metadata as the `kubernetes_logs` source adds. The pod of each event is
+				looked up by the UID or IP address found in the configured fields. Events whose pod isn't found are
+				forwarded unchanged.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		kubernetes_metadata_lookup_failures_total: components.sources.internal_metrics.output.metrics.kubernetes_metadata_lookup_failures_total
+	}
+}
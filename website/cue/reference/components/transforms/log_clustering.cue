@@ -0,0 +1,181 @@
+package metadata
+
+components: transforms: log_clustering: {
+	title: "Log Clustering"
+
+	description: """
+		Clusters log messages into patterns with the Drain algorithm, and annotates each event with the ID of its
+		pattern and the variable parts of its message. Optionally counts the events of each pattern, to analyze the
+		volume of each kind of log, or detect new kinds of logs downstream.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		depth: {
+			common: false
+			description: """
+				The depth of the parse tree, including its root and its leaves. Messages are first grouped by their
+				number of tokens, then by their first `depth - 2` tokens, before being compared to the patterns of
+				their group.
+				"""
+			required: false
+			type: uint: {
+				default: 4
+				unit:    null
+			}
+		}
+		emit_metrics: {
+			common:      true
+			description: "Whether to count the events of each pattern, and emit the counts to the `metrics` output."
+			required:    false
+			type: bool: default: false
+		}
+		field: {
+			common:      true
+			description: "The field holding the message to cluster."
+			required:    false
+			type: string: default: "message"
+		}
+		max_children: {
+			common: false
+			description: """
+				The maximum number of children of each node of the parse tree. Once reached, the tokens without their
+				own node are grouped under a `<*>` node.
+				"""
+			required: false
+			type: uint: {
+				default: 100
+				unit:    null
+			}
+		}
+		max_patterns: {
+			common: false
+			description: """
+				The maximum number of patterns to keep. Once reached, the least recently matched pattern is forgotten
+				when a new one is created.
+				"""
+			required: false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		metrics_interval_secs: {
+			common:      false
+			description: "The interval between the emissions of the counts of events of each pattern."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		parameters_field: {
+			common:      false
+			description: "The field to insert the variable parts of the message into, in order."
+			required:    false
+			type: string: default: "pattern_parameters"
+		}
+		pattern_field: {
+			common:      false
+			description: "The field to insert the pattern of the message into. The variable parts of the pattern are replaced by `<*>`."
+			required:    false
+			type: string: default: "pattern"
+		}
+		pattern_id_field: {
+			common:      false
+			description: "The field to insert the ID of the pattern of the message into."
+			required:    false
+			type: string: default: "pattern_id"
+		}
+		similarity_threshold: {
+			common:      false
+			description: "The fraction of the tokens of a message that must match a pattern for the message to be part of it."
+			required:    false
+			type: float: {
+				default: 0.4
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "metrics"
+			description: """
+				When `emit_metrics` is enabled, the `log_pattern_events_total` incremental counters of the events of
+				each pattern, tagged with its `pattern_id` and `pattern`, are emitted every `metrics_interval_secs`.
+				For a transform component named `foo`, this output can be accessed by specifying `foo.metrics` as the
+				input to another component.
+				"""
+		},
+	]
+
+	examples: [
+		{
+			title: "Cluster connection logs"
+			configuration: {}
+			input: log: {
+				message: "Connection from 10.0.0.1 closed after 25 ms"
+			}
+			output: log: {
+				message:    "Connection from 10.0.0.1 closed after 25 ms"
+				pattern_id: "8e5b0b6fc9a6d0f1"
+				pattern:    "Connection from <*> closed after <*> ms"
+				pattern_parameters: ["10.0.0.1", "25"]
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		log_patterns_created_total: components.sources.internal_metrics.output.metrics.log_patterns_created_total
+	}
+
+	how_it_works: {
+		drain: {
+			title: "Drain"
+			body: """
+				Messages are split into tokens on whitespace, and routed through a parse tree by their number of
+				tokens, then by their first tokens. Tokens with digits are considered variable, and don't get their
+				own nodes. At the leaves, a message joins the most similar pattern whose fraction of matching tokens
+				is at least `similarity_threshold`, and the tokens of the pattern that differ become `<*>`. When none
+				is similar enough, the message creates a new pattern.
+				"""
+		}
+		pattern_ids: {
+			title: "Pattern IDs"
+			body: """
+				The ID of a pattern is derived from the message that created it, with its tokens with digits replaced
+				by `<*>`. IDs are kept as patterns are generalized, and are stable across restarts as long as the same
+				kind of message creates the pattern.
+				"""
+		}
+		metrics: {
+			title: "Pattern metrics"
+			body: """
+				The counts of events are emitted as events are received, once `metrics_interval_secs` have elapsed
+				since they were last emitted. The counts of the events received since then are not emitted when
+				Vector stops.
+				"""
+		}
+	}
+}
@@ -31,6 +31,20 @@ components: transforms: log_to_metric: {
 			type: array: items: type: object: {
 				examples: []
 				options: {
+					buckets: {
+						description: """
+							The upper limits of the buckets of the histogram, in increasing order. When set, an aggregated
+							histogram with these buckets is generated, instead of a distribution with the value of `field` as
+							its single sample.
+							"""
+						required:      false
+						common:        false
+						relevant_when: #"type = "histogram""#
+						type: array: {
+							default: null
+							items: type: float: examples: [0.005, 0.01, 0.1, 1.0]
+						}
+					}
 					field: {
 						description: "The log field to use as the metric."
 						required:    true
@@ -116,8 +130,9 @@ components: transforms: log_to_metric: {
 							enum: {
 								counter:   "A [counter metric type](\(urls.vector_metric)#counter)."
 								gauge:     "A [gauge metric type](\(urls.vector_metric)#gauge)."
-								histogram: "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
+								histogram: "A [distribution metric type](\(urls.vector_metric)#distribution) with histogram statistic, or a [histogram metric type](\(urls.vector_metric)#histogram) when `buckets` is set."
 								set:       "A [set metric type](\(urls.vector_metric)#set)."
+								sketch:    "A DDSketch, with the relative accuracy of the Datadog Agent, so that it can be merged with the sketches of the Agent."
 								summary:   "A [distribution metric type](\(urls.vector_metric)#distribution) with summary statistic."
 							}
 						}
@@ -137,6 +152,7 @@ components: transforms: log_to_metric: {
 		counter:      output._passthrough_counter
 		distribution: output._passthrough_distribution
 		gauge:        output._passthrough_gauge
+		histogram:    output._passthrough_histogram
 		set:          output._passthrough_set
 	}
 
@@ -319,6 +335,39 @@ components: transforms: log_to_metric: {
 				}
 			}}]
 		},
+		{
+			title: "Histogram with buckets"
+			notes: "This example demonstrates capturing timings in your logs to compute a histogram with fixed buckets."
+
+			configuration: {
+				metrics: [
+					{
+						type:  "histogram"
+						field: "time"
+						name:  "time_ms"
+						buckets: [10.0, 50.0, 100.0]
+					},
+				]
+			}
+
+			input: log: {
+				message: "Sent 200 in 54.2ms"
+				time:    54.2
+			}
+			output: [{metric: {
+				kind: "incremental"
+				name: "time_ms"
+				histogram: {
+					buckets: [
+						{upper_limit: 10.0, count: 0},
+						{upper_limit: 50.0, count: 0},
+						{upper_limit: 100.0, count: 1},
+					]
+					count: 1
+					sum:   54.2
+				}
+			}}]
+		},
 		{
 			title: "Summary distribution"
 			notes: "This example demonstrates capturing timings in your logs to compute summary."
@@ -98,6 +98,32 @@ components: transforms: lua: {
 				}
 			}
 		}
+		dedicated_threads: {
+			common:      false
+			description: "Whether to run the Lua runtime on a dedicated pool of threads. This prevents a slow or runaway script from stalling the threads running the rest of the topology, at the cost of passing events between threads."
+			required:    false
+			type: bool: default: false
+		}
+		max_instructions: {
+			common:      false
+			description: "The maximum number of Lua instructions a hook can execute for a single invocation. When a hook exceeds it, such as `hooks.process` for an event, it's aborted with an error, and the event is discarded. The instructions are counted in batches of 1000, so a hook may slightly exceed it."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [1000000]
+				unit: null
+			}
+		}
+		max_memory_bytes: {
+			common:      false
+			description: "The maximum amount of memory the Lua runtime can allocate. When the Lua runtime fails to allocate memory beyond it, the hook being invoked is aborted with an error, and the event is discarded."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [67108864]
+				unit: "bytes"
+			}
+		}
 		search_dirs: {
 			common:      false
 			description: "A list of directories to search when loading a Lua file via the `require` function. If not specified, the modules are looked up in the directories of Vector's configs."
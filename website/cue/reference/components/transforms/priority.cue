@@ -0,0 +1,100 @@
+package metadata
+
+components: transforms: priority: {
+	title: "Priority"
+
+	description: """
+		Sets the priority of events based on a set of conditions, which decides the events that buffers shed first
+		when they are full.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		high: {
+			common: true
+			description: """
+				The condition matching the events to give a high priority. This is checked before the `low`
+				condition.
+				"""
+			required: false
+			type: condition: {}
+		}
+		low: {
+			common:      true
+			description: "The condition matching the events to give a low priority."
+			required:    false
+			type: condition: {}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	examples: [
+		{
+			title: "Shed debug logs first"
+			configuration: {
+				high: #".level == "error""#
+				low:  #".level == "debug""#
+			}
+			input: {
+				log: {
+					level:   "debug"
+					message: "I'm a noisy debug log"
+				}
+			}
+			output: {
+				log: {
+					level:   "debug"
+					message: "I'm a noisy debug log"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		shedding: {
+			title: "Shedding"
+			body: """
+				Events are given a normal priority by default, unless their source sets a `priority`. When a buffer
+				configured with `when_full` set to `drop_newest` or `overflow` fills up, lower priority events are
+				shed first:
+
+				* Low priority events are dropped, or sent to the overflow buffer, as soon as the buffer is half full.
+				* Normal priority events are dropped, or sent to the overflow buffer, once the buffer is full.
+				* High priority events are never dropped. They wait for a `drop_newest` buffer to have room
+				  instead, and are sent to the overflow buffer once an `overflow` buffer is full, waiting for
+				  the overflow buffer to have room once it's full as well.
+
+				Events that match neither condition keep their current priority. Priorities are not persisted by
+				disk buffers, so events read back from a disk buffer have a normal priority.
+				"""
+		}
+	}
+}
@@ -0,0 +1,96 @@
+package metadata
+
+components: transforms: protobuf: {
+	title: "Protobuf"
+
+	description: """
+		Decodes a field holding a raw protobuf message into the fields of the event, using a user-provided file
+		descriptor set, such as for Kafka topics carrying protobuf messages without the Confluent wire format.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		desc_file: {
+			description: "The path to the file descriptor set defining the message, as generated by `protoc --include_imports --descriptor_set_out`."
+			required:    true
+			type: string: {
+				examples: ["/etc/vector/protos/events.desc"]
+			}
+		}
+		drop_field: {
+			common:      true
+			description: "Whether to remove the field holding the encoded message once it's decoded."
+			required:    false
+			type: bool: default: true
+		}
+		drop_invalid: {
+			common:      true
+			description: "Whether to drop the events whose message fails to decode. By default, they are forwarded unchanged."
+			required:    false
+			type: bool: default: false
+		}
+		field: {
+			common:      true
+			description: "The field holding the encoded message. The message is expected without any framing, such as the length prefix of gRPC or the schema ID of the Confluent wire format."
+			required:    false
+			type: string: {
+				default: "message"
+			}
+		}
+		message_type: {
+			description: "The fully qualified name of the message."
+			required:    true
+			type: string: {
+				examples: ["mypackage.MyMessage"]
+			}
+		}
+		target_field: {
+			common:      false
+			description: "The field to insert the fields of the decoded message into. By default, the fields of the message are inserted at the root of the event, overwriting the existing ones."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["decoded"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+
+	how_it_works: {
+		mapping: {
+			title: "Mapping"
+			body: """
+				The message is decoded following the JSON mapping of protobuf, with the original names of its fields,
+				except for 64-bit integers, which are kept as integers. Bytes fields are encoded in base64, enums are
+				named, and well-known types such as `google.protobuf.Timestamp` are decoded to their JSON
+				representation. Fields set to their default value are omitted.
+				"""
+		}
+	}
+}
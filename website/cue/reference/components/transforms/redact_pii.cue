@@ -0,0 +1,174 @@
+package metadata
+
+components: transforms: redact_pii: {
+	title: "Redact PII"
+
+	description: """
+		Detects personally identifiable information and secrets in log events, such as emails, credit card
+		numbers, and access keys, and masks, hashes, drops, or quarantines them.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		actions: {
+			common:      true
+			description: "A map of detector names, either built-in or of custom rules, to the action to take when they find a match. The detectors that aren't listed take the default action."
+			required:    false
+			type: object: {
+				examples: [{ssn: "drop_field", secret: "quarantine"}]
+				options: {
+					"*": {
+						description: "The action to take when the detector finds a match."
+						required:    true
+						type: string: enum: {
+							mask:       "Replace the match with the mask."
+							hash:       "Replace the match with the hexadecimal SHA-256 hash of the salt and the match."
+							drop_field: "Remove the field containing the match from the event."
+							quarantine: "Forward the event unchanged to the `quarantine` output, rather than to the default output."
+						}
+					}
+				}
+			}
+		}
+		custom_rules: {
+			common:      false
+			description: "Additional detectors, matching regular expressions."
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					name: {
+						description: "The name of the detector, which is used to configure its action and in the internal metrics."
+						required:    true
+						type: string: examples: ["employee_id"]
+					}
+					pattern: {
+						description: "The regular expression matching the information to detect."
+						required:    true
+						type: string: examples: ["EMP-\\d{6}"]
+					}
+				}
+			}
+		}
+		default_action: {
+			common:      true
+			description: "The action to take when the detectors that aren't listed in `actions` find a match."
+			required:    false
+			type: string: {
+				default: "mask"
+				enum: {
+					mask:       "Replace the match with the mask."
+					hash:       "Replace the match with the hexadecimal SHA-256 hash of the salt and the match."
+					drop_field: "Remove the field containing the match from the event."
+					quarantine: "Forward the event unchanged to the `quarantine` output, rather than to the default output."
+				}
+			}
+		}
+		detectors: {
+			common:      true
+			description: "The built-in detectors to run."
+			required:    false
+			type: array: {
+				default: ["email", "credit_card", "ssn", "ip", "jwt", "secret"]
+				items: type: string: enum: {
+					email:       "Email addresses."
+					credit_card: "Credit card numbers, which pass the Luhn check, optionally grouped with spaces or dashes."
+					ssn:         "US social security numbers, in the `AAA-GG-SSSS` format."
+					ip:          "IPv4 and IPv6 addresses."
+					jwt:         "JSON Web Tokens."
+					secret:      "Common secrets, such as private keys and the access keys of AWS, GitHub, Google, Slack, and Stripe."
+				}
+			}
+		}
+		fields: {
+			common:      true
+			description: "The fields to scan for personally identifiable information. By default, all the string fields of the events are scanned, including the nested ones."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["message", "user.email"]
+			}
+		}
+		hash_salt: {
+			common:      false
+			description: "A salt prepended to the matches of the detectors whose action is `hash`, before they're hashed. Without a salt, the hashes of values with few possibilities, such as social security numbers, can be reversed by hashing all of them."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${PII_HASH_SALT}"]
+			}
+		}
+		mask: {
+			common:      false
+			description: "The string replacing the matches of the detectors whose action is `mask`."
+			required:    false
+			type: string: default: "[REDACTED]"
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "quarantine"
+			description: """
+				When a detector whose action is `quarantine` finds a match, the event is sent unchanged to the
+				`quarantine` output instead of the default output. For a transform component named `foo`, this
+				output can be accessed by specifying `foo.quarantine` as the input to another component.
+				"""
+		},
+	]
+
+	examples: [
+		{
+			title: "Mask emails and hash credit card numbers"
+			configuration: {
+				detectors: ["email", "credit_card"]
+				actions: credit_card: "hash"
+			}
+			input: log: {
+				message: "Order from jane.doe@example.com"
+			}
+			output: log: {
+				message: "Order from [REDACTED]"
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		pii_detections_total: components.sources.internal_metrics.output.metrics.pii_detections_total
+	}
+
+	how_it_works: {
+		detection_order: {
+			title: "Detection order"
+			body: """
+				The detectors are applied in order, built-in detectors first, in the order of `detectors`,
+				followed by the custom rules. The matches masked or hashed by a detector are not seen by the
+				following ones. The matches of the built-in detectors prone to false positives are validated,
+				such as with the Luhn check for credit card numbers, or by parsing IP addresses.
+				"""
+		}
+	}
+}
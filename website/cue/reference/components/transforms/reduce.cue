@@ -58,6 +58,16 @@ components: transforms: reduce: {
 				unit:    "milliseconds"
 			}
 		}
+		flush_when: {
+			common: false
+			description: """
+				A condition used to distinguish a complete transaction, evaluated against the combined event. Once an
+				event is merged, if this condition resolves to `true` for the combined event of its transaction, the
+				transaction is immediately flushed.
+				"""
+			required: false
+			type: condition: {}
+		}
 		group_by: {
 			common: true
 			description: """
@@ -77,6 +87,30 @@ components: transforms: reduce: {
 				}
 			}
 		}
+		max_bytes: {
+			common: false
+			description: """
+				The maximum size of a combined event, estimated from the in-memory size of the events merged into it. If
+				merging an event would exceed it, the transaction is flushed (without this event) and a new transaction
+				is started.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [1048576]
+				unit: "bytes"
+			}
+		}
+		max_events: {
+			common:      false
+			description: "The maximum number of events merged into a combined event. Once a transaction reaches it, it's immediately flushed."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [100]
+				unit: null
+			}
+		}
 		merge_strategies: {
 			common: false
 			description: """
@@ -104,6 +104,175 @@ components: transforms: "remap": {
 				"""
 			type: bool: default: false
 		}
+		lookups: {
+			common:      false
+			description: "Functions looking up external services made available to the program. They're disabled by default. Processing of an event blocks until its lookups complete or time out, so their results are cached. Only available when Vector is built with the `transforms-remap-lookups` feature."
+			required:    false
+			type: object: options: {
+			dns: {
+				description: "The `dns_lookup` function, resolving host names to IP addresses, or IP addresses to host names with `reverse: true`."
+				required:    false
+				type: object: options: {
+					cache_max_entries: {
+						description: "The maximum number of results cached."
+						required:    false
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+					cache_ttl_secs: {
+						description: "How long the results of lookups are cached. Failed lookups aren't cached."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					enabled: {
+						description: "Whether the `dns_lookup` function is available to the program."
+						required:    false
+						type: bool: default: false
+					}
+					max_concurrency: {
+						description: "The maximum number of lookups in flight at once."
+						required:    false
+						type: uint: {
+							default: 16
+							unit:    null
+						}
+					}
+					timeout_ms: {
+						description: "How long a lookup may take, including its wait for a free slot, before the function fails."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+				}
+			}
+			http: {
+				description: "The `http_get` function, fetching the body of a URL. Responses with a non-2xx status are errors. The global `proxy` options apply to its requests."
+				required:    false
+				type: object: options: {
+					cache_max_entries: {
+						description: "The maximum number of results cached."
+						required:    false
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+					cache_ttl_secs: {
+						description: "How long the results of lookups are cached. Failed lookups aren't cached."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					enabled: {
+						description: "Whether the `http_get` function is available to the program."
+						required:    false
+						type: bool: default: false
+					}
+					max_concurrency: {
+						description: "The maximum number of lookups in flight at once."
+						required:    false
+						type: uint: {
+							default: 16
+							unit:    null
+						}
+					}
+					timeout_ms: {
+						description: "How long a lookup may take, including its wait for a free slot, before the function fails."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+				}
+			}
+			}
+		}
+		state: {
+			common:      false
+			description: "Functions keeping per-key state across events made available to the program. They're disabled by default. Only available when Vector is built with the `transforms-remap-state` feature."
+			required:    false
+			type: object: options: {
+				enabled: {
+					description: "Whether the `counter`, `rate`, and `remember` functions are available to the program."
+					required:    false
+					type: bool: default: false
+				}
+				max_keys: {
+					description: "The maximum number of keys kept, shared by all functions. Once reached, the least recently used keys are forgotten."
+					required:    false
+					type: uint: {
+						default: 100000
+						unit:    null
+					}
+				}
+			}
+		}
+		encryption_keys: {
+			common:      false
+			description: "Keys of key management services available to the `encrypt_envelope` and `decrypt_envelope` functions, by name. Only available when Vector is built with the `transforms-remap-kms` feature."
+			required:    false
+			type: object: options: {
+				"*": {
+					description: "A key wrapping the data keys that encrypt values."
+					required:    true
+					type: object: options: {
+						data_key_ttl_secs: {
+							description: "How long a data key encrypts values before it's replaced. Unwrapped data keys are cached for as long."
+							required:    false
+							type: uint: {
+								default: 3600
+								unit:    "seconds"
+							}
+						}
+						key_id: {
+							description:   "The ID, ARN, or alias of the AWS KMS key."
+							required:      true
+							relevant_when: "provider = \"aws_kms\""
+							type: string: examples: ["alias/pii"]
+						}
+						key_name: {
+							description:   "The resource name of the Google Cloud KMS key, or the name of the Azure Key Vault key."
+							required:      true
+							relevant_when: "provider = \"gcp_kms\" or provider = \"azure_key_vault\""
+							type: string: examples: ["projects/my-project/locations/global/keyRings/vector/cryptoKeys/pii", "pii"]
+						}
+						provider: {
+							description: "The key management service holding the key."
+							required:    true
+							type: string: enum: {
+								aws_kms:         "AWS Key Management Service. The `region`, `endpoint`, and `auth` options of AWS components apply."
+								azure_key_vault: "Azure Key Vault, with an RSA key. Vector authenticates with the default Azure credentials of its environment."
+								gcp_kms:         "Google Cloud Key Management Service. The `api_key` and `credentials_path` options of GCP components apply."
+							}
+						}
+						timeout_ms: {
+							description: "How long to wait for the key management service."
+							required:    false
+							type: uint: {
+								default: 5000
+								unit:    "milliseconds"
+							}
+						}
+						vault_url: {
+							description:   "The URL of the Azure Key Vault."
+							required:      true
+							relevant_when: "provider = \"azure_key_vault\""
+							type: string: examples: ["https://my-vault.vault.azure.net"]
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -190,6 +359,116 @@ components: transforms: "remap": {
 				reference](\#(urls.vrl_runtime_errors)).
 				"""#
 		}
+		external_lookups: {
+			title: "External lookups"
+			body: #"""
+				The `dns_lookup` and `http_get` functions are only available when Vector is built with the
+				`transforms-remap-lookups` feature, and to programs when they're enabled in the `lookups`
+				option:
+
+				```toml
+				[transforms.enrich]
+				type = "remap"
+				inputs = ["logs"]
+				lookups.dns.enabled = true
+				lookups.http.enabled = true
+				source = '''
+				.addresses = dns_lookup!(.host)
+				.owner = http_get!("http://inventory.local/owners/" + string!(.host))
+				'''
+				```
+
+				VRL programs run synchronously, so the processing of an event waits for its lookups to
+				complete, or fail after `timeout_ms`. Successful lookups are cached for `cache_ttl_secs`,
+				and at most `max_concurrency` lookups of each function are in flight at once, to keep the
+				transform's throughput and the load on the looked up services in check.
+				"""#
+		}
+		stateful_functions: {
+			title: "Stateful functions"
+			body: #"""
+				The `counter`, `rate`, and `remember` functions keep state per key across the events
+				processed by the transform, and are only available when Vector is built with the
+				`transforms-remap-state` feature, and to programs when `state.enabled` is `true`:
+
+				* `counter(key)` increments the counter of a key, and returns its new value.
+				* `rate!(key, window: <seconds>)` records an occurrence of a key, and returns its
+				  occurrences per second over the trailing window, estimated from the counts of the
+				  current and previous windows.
+				* `remember!(key, value, ttl: <seconds>)` stores a value for a key, and returns the value
+				  it replaced, or `null` if there was none or it expired.
+
+				This makes simple in-stream flags possible, such as marking the first time a user is
+				seen in a day:
+
+				```coffee
+				.first_seen = remember!(string!(.user), true, ttl: 86400) == null
+				```
+
+				The state is kept in memory, bounded by `state.max_keys`, and isn't shared with other
+				transforms or Vector instances. It survives reloads of the configuration as long as the
+				ID of the transform is unchanged, but is lost when Vector restarts.
+				"""#
+		}
+		envelope_encryption: {
+			title: "Envelope encryption"
+			body: #"""
+				The `encrypt_envelope` and `decrypt_envelope` functions encrypt fields, such as personal
+				data, with keys held by AWS KMS, Google Cloud KMS, or Azure Key Vault, so that no raw
+				key is embedded in the configuration. These functions are only available when Vector is built
+				with the `transforms-remap-kms` feature:
+
+				```toml
+				[transforms.protect]
+				type = "remap"
+				inputs = ["logs"]
+				encryption_keys.pii = { provider = "aws_kms", key_id = "alias/pii", region = "us-east-1" }
+				source = '''
+				.ssn = encode_base64(encrypt_envelope!(string!(.ssn), key: "pii"))
+				'''
+				```
+
+				Each value is encrypted with AES-256-GCM under a random data key, which is wrapped by
+				the key management service and stored with the ciphertext. A data key encrypts values
+				for `data_key_ttl_secs` before it's replaced, and unwrapped data keys are cached for as
+				long, so the service is only called when data keys rotate. Values encrypted before a
+				rotation, or of a previous version of the key, remain decryptable.
+
+				The `key` argument must be the name of a configured key, which is checked when the
+				program is compiled.
+				"""#
+		}
+		function_libraries: {
+			title: "Function libraries"
+			body: #"""
+				Functions shared by several programs can be defined in libraries, which programs
+				import with `import` statements at their top:
+
+				```coffee
+				# lib/parsers.vrl
+				fn parse_user(value) {
+					user = parse_json!(value).user
+					downcase(string!(user))
+				}
+				```
+
+				```coffee
+				import "lib/parsers.vrl"
+				import "lib/enrich.vrl" as enrich
+
+				.user = parsers::parse_user!(.message)
+				```
+
+				The functions of a library are called through its namespace, which defaults to the name
+				of the file and can be set with `as`. The body of a function is a VRL program, whose
+				arguments are variables and whose last expression is the result. It can call the
+				functions defined before it in the library, but can't read or modify the event.
+
+				Libraries are loaded and compiled with the program, so calls with missing arguments or
+				unhandled errors are rejected when the configuration is loaded. Relative paths are
+				resolved from the current working directory.
+				"""#
+		}
 		emitting_multiple_events: {
 			title: "Emitting multiple log events"
 			body: #"""
@@ -204,6 +483,20 @@ components: transforms: "remap": {
 				  "message": 123
 				}
 				```
+
+				Events can also be emitted with the `emit` function, which is only available to the
+				`remap` transform. It takes an object and emits it as a log event, with the metadata of
+				the processed event, before the event itself. To only emit the events passed to `emit`,
+				assign an empty array to `.`:
+
+				```coffee
+				for_each(array!(.records)) -> |_index, record| {
+				  emit(object!(record))
+				}
+				. = []
+				```
+
+				If the program fails or aborts, the emitted events are discarded.
 				"""#
 		}
 	}
@@ -0,0 +1,147 @@
+package metadata
+
+components: transforms: rollup: {
+	title: "Rollup"
+
+	description: """
+		Rolls up metrics into a coarser resolution, emitting each series at most once per interval. This
+		reduces the volume of high-frequency metrics, such as frequently scraped ones, before they're sent
+		to a remote storage.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {}
+
+	support: {
+		requirements: []
+		notices: []
+		warnings: []
+	}
+
+	configuration: {
+		interval_secs: {
+			common:      true
+			description: "The resolution of the rolled up metrics. Each series is emitted at most once per interval."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		gauge_aggregations: {
+			common: true
+			description: """
+				The aggregations of the values of the absolute gauges over each interval. When several aggregations
+				are set, a series is emitted for each of them, with the name of the aggregation appended to its
+				name, as in `cpu_usage_max`.
+				"""
+			required: false
+			type: array: {
+				default: ["avg"]
+				items: type: string: enum: {
+					avg:  "The average of the values."
+					max:  "The maximum of the values."
+					min:  "The minimum of the values."
+					sum:  "The sum of the values."
+					last: "The last value."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	examples: [
+		{
+			title: "Roll up gauges into their average and maximum"
+			input: [
+				{
+					metric: {
+						kind: "absolute"
+						name: "cpu_usage"
+						gauge: {
+							value: 20.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "absolute"
+						name: "cpu_usage"
+						gauge: {
+							value: 40.0
+						}
+					}
+				},
+			]
+			configuration: {
+				interval_secs: 60
+				gauge_aggregations: ["avg", "max"]
+			}
+			output: [
+				{
+					metric: {
+						kind: "absolute"
+						name: "cpu_usage_avg"
+						gauge: {
+							value: 30.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "absolute"
+						name: "cpu_usage_max"
+						gauge: {
+							value: 40.0
+						}
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		rollup_behavior: {
+			title: "Rollup Behavior"
+			body: """
+				Metrics are rolled up based on their kind. During an interval, the values of the `absolute`
+				gauges are aggregated as configured by `gauge_aggregations`, `incremental` metrics are added
+				together, and newer values of the other `absolute` metrics replace older ones in the same series.
+				At the end of the interval, each series is emitted with the latest timestamp it was seen with.
+				"""
+		}
+
+		counter_resets: {
+			title: "Counter Resets"
+			body: """
+				An `absolute` counter that decreases, such as when the process exposing it restarts, is considered
+				reset. Its value before the reset is added to its following values, so that the rolled up counter
+				keeps increasing. The state of a counter is forgotten when it isn't seen for 10 intervals.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		rollup_counter_resets_total: components.sources.internal_metrics.output.metrics.rollup_counter_resets_total
+		rollup_flushes_total:        components.sources.internal_metrics.output.metrics.rollup_flushes_total
+		rollup_series_flushed_total: components.sources.internal_metrics.output.metrics.rollup_series_flushed_total
+	}
+}
@@ -36,7 +36,7 @@ components: transforms: route: {
 				`_unmatched` is a reserved output name and cannot be used as a route name. `_default` is also reserved
 				for future use.
 				"""
-			required: true
+			required: false
 			type: object: {
 				options: {
 					"*": {
@@ -50,6 +50,38 @@ components: transforms: route: {
 				}
 			}
 		}
+		weighted: {
+			description: """
+				A table of route identifiers to the percentage of the events they receive, regardless of their
+				conditions. Weighted routes receive their events in addition to the routes they match, such as to mirror
+				a fraction of the traffic to a canary sink, and don't change which events are sent to the
+				`<transform_name>._unmatched` output.
+				"""
+			required: false
+			type: object: {
+				examples: [{canary: 5.0}]
+				options: {
+					"*": {
+						description: "The percentage of the events to send to this route, between 0 and 100."
+						required:    true
+						type: float: {}
+					}
+				}
+			}
+		}
+		weighted_key_field: {
+			description: """
+				The name of the field whose value is hashed to decide which events the weighted routes receive, so that
+				the events with the same value are consistently sent to the same weighted routes. For metrics, it's the
+				name of a tag. If left unspecified, or if the event doesn't have the field, every Nth event is sent to
+				the weighted routes.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["user_id"]
+			}
+		}
 	}
 
 	input: {
@@ -120,4 +152,17 @@ components: transforms: route: {
 			description: "Each route can be referenced as an input by other components with the name `<transform_name>.<route_id>`."
 		},
 	]
+
+	how_it_works: {
+		weighted_routes: {
+			title: "Weighted routes"
+			body: """
+				Weighted routes receive a fixed percentage of the events, such as to send a sample of the traffic to a
+				canary deployment. When `weighted_key_field` is set, the value of the field is hashed with the name of
+				the route, so that the decision is consistent for a given value across restarts and Vector instances,
+				but the actual percentage depends on how the values are distributed. Otherwise, the events are
+				selected deterministically, one every `100 / percentage` events.
+				"""
+		}
+	}
 }
@@ -33,11 +33,15 @@ components: transforms: sample: {
 				Consistently samples the same events. Actual rate of sampling may differ from the configured one if
 				values in the field are not uniformly distributed. If left unspecified, or if the event doesn't have
 				`key_field`, events will be count rated.
+
+				The hash doesn't depend on the order events arrive in, nor on the Vector instance, so all events
+				sharing a value, such as a `trace_id` or a `user_id`, are kept or dropped together across instances
+				configured with the same `rate`.
 				"""
 			required: false
 			type: string: {
 				default: null
-				examples: ["message"]
+				examples: ["message", "trace_id"]
 			}
 		}
 		exclude: {
@@ -59,6 +63,12 @@ components: transforms: sample: {
 				unit: null
 			}
 		}
+		reroute_dropped: {
+			common:      false
+			description: "Send the events not sampled to the `dropped` output instead of dropping them."
+			required:    false
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -67,6 +77,18 @@ components: transforms: sample: {
 		traces:  false
 	}
 
+	outputs: [
+		components._default_output,
+		{
+			name: "dropped"
+			description: """
+				When `reroute_dropped` is set to `true`, the events not sampled are sent to the `dropped`
+				output, unmodified, instead of being dropped. For a transform component named `foo`, this
+				output can be accessed by specifying `foo.dropped` as the input to another component.
+				"""
+		},
+	]
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
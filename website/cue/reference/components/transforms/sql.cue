@@ -0,0 +1,126 @@
+package metadata
+
+components: transforms: sql: {
+	title: "SQL"
+
+	description: """
+		Queries log events with SQL, for per-event projections and filtering, or for aggregations over
+		windows of events, with the event stream as the `events` table.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "SQL"
+				url:     urls.sql
+				version: null
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		query: {
+			description: """
+				The SQL query run on the events. The events are queried from the `events` table, whose columns
+				are their fields. Queries without aggregate functions nor `GROUP BY` clause are run on each
+				event, while the other ones are run on the events of each window.
+				"""
+			required: true
+			type: string: {
+				examples: [
+					"SELECT message, status FROM events WHERE status >= 500",
+					"SELECT service, COUNT(*) AS errors FROM events WHERE level = 'error' GROUP BY service",
+				]
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "The duration of the windows the events are aggregated over. Only used by the queries with aggregate functions or a `GROUP BY` clause."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	examples: [
+		{
+			title: "Count the errors of each service"
+			configuration: {
+				query:       "SELECT service, COUNT(*) AS errors FROM events WHERE level = 'error' GROUP BY service"
+				window_secs: 60
+			}
+			input: [
+				{log: {service: "api", level: "error", message: "Connection refused"}},
+				{log: {service: "api", level: "error", message: "Timed out"}},
+				{log: {service: "web", level: "info", message: "Started"}},
+			]
+			output: [
+				{log: {service: "api", errors: 2}},
+			]
+		},
+	]
+
+	how_it_works: {
+		projections: {
+			title: "Projections"
+			body: """
+				Queries without aggregate functions nor `GROUP BY` clause are run on each event. The events not
+				matching the `WHERE` clause are dropped, and the other ones are replaced with the selected
+				columns. Columns are named after their alias, or after the last segment of the selected field,
+				as in `http.method` which is named `method`. `*` selects all the fields of the event, along with
+				the other columns.
+				"""
+		}
+
+		aggregations: {
+			title: "Aggregations"
+			body: """
+				Queries with aggregate functions or a `GROUP BY` clause are run on the events received during
+				each window of `window_secs`, at the end of the window. An event is emitted for each group of
+				events matching the `HAVING` clause, and nothing is emitted for windows without events. The
+				`window_start()` and `window_end()` functions return the bounds of the window.
+
+				The supported aggregate functions are `COUNT`, `SUM`, `AVG`, `MIN`, and `MAX`, along with
+				`COUNT(DISTINCT ...)`.
+				"""
+		}
+
+		supported_sql: {
+			title: "Supported SQL"
+			body: """
+				Only `SELECT` queries on the `events` table are supported, with `WHERE`, `GROUP BY` and
+				`HAVING` clauses. Expressions support arithmetic, comparison and logical operators, `||`,
+				`LIKE`, `IN`, `BETWEEN`, `IS NULL`, `CASE`, and the `ABS`, `COALESCE`, `CONCAT`, `LENGTH`,
+				`LOWER`, `ROUND` and `UPPER` functions. Missing fields are `NULL`.
+
+				Events failing evaluation, such as when adding a string to a number, are discarded and
+				reported as errors.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+}
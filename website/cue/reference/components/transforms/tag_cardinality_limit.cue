@@ -59,6 +59,104 @@ components: transforms: tag_cardinality_limit: {
 				}
 			}
 		}
+		per_tag: {
+			common:      false
+			description: "The limits of specific tags, keyed by tag, overriding the global ones."
+			required:    false
+			type: object: {
+				examples: [{host: {value_limit: 1000, limit_exceeded_action: "drop_event", allowed_values: ["localhost"]}}]
+				options: {
+					"*": {
+						description: "The limits of a tag."
+						required:    true
+						type: object: options: {
+							allowed_values: {
+								description: "The values of the tag that are always accepted, even once its limit is reached. These values don't count towards the limit."
+								required:    false
+								type: array: {
+									default: []
+									items: type: string: examples: ["localhost"]
+								}
+							}
+							limit_exceeded_action: {
+								description: "The action to take when the limit of the tag is exceeded. By default, the global `limit_exceeded_action` applies."
+								required:    false
+								type: string: {
+									default: null
+									enum: {
+										drop_tag:   "Remove the tag from the incoming metric"
+										drop_event: "Drop the incoming metric event"
+									}
+								}
+							}
+							value_limit: {
+								description: "How many distinct values to accept for the tag. By default, the global `value_limit` applies."
+								required:    false
+								type: uint: {
+									default: null
+									unit:    null
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		persistence: {
+			common: false
+			description: """
+				Persists the accepted values of the tags across restarts. Without it, the accepted values are forgotten
+				when Vector restarts, and the first values seen afterwards are accepted instead. Only supported in
+				`exact` mode.
+				"""
+			required: false
+			type: object: options: {
+				data_dir: {
+					description:   "The directory used to persist the accepted values. By default, the global `data_dir` option is used. Make sure the running user has write permissions to this directory."
+					required:      false
+					relevant_when: "type = \"disk\""
+					type: string: {
+						default: null
+						examples: ["/var/local/lib/vector/"]
+					}
+				}
+				interval_secs: {
+					description: "The interval between the saves of the accepted values. The accepted values are also saved when Vector stops."
+					required:    false
+					type: uint: {
+						default: 10
+						unit:    "seconds"
+					}
+				}
+				key: {
+					description:   "The Redis key holding the accepted values. By default, it's `vector:tag_cardinality_limit:<component_id>`."
+					required:      false
+					relevant_when: "type = \"redis\""
+					type: string: {
+						default: null
+						examples: ["metrics:accepted_tags"]
+					}
+				}
+				type: {
+					description: "Where the accepted values are persisted."
+					required:    true
+					type: string: {
+						enum: {
+							disk:  "In a file of the data directory."
+							redis: "In a Redis key, so that the accepted values can be shared with other instances."
+						}
+					}
+				}
+				url: {
+					description:   "The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format."
+					required:      true
+					relevant_when: "type = \"redis\""
+					type: string: {
+						examples: ["redis://127.0.0.1:6379/0"]
+					}
+				}
+			}
+		}
 		value_limit: {
 			common:      true
 			description: "How many distinct values to accept for any given key."
@@ -150,10 +248,11 @@ components: transforms: tag_cardinality_limit: {
 				This transform is intended to be used as a protection mechanism to prevent
 				upstream mistakes. Such as a developer accidentally adding a `request_id`
 				tag. When this is happens, it is recommended to fix the upstream error as soon
-				as possible. This is because Vector's cardinality cache is held in memory and it
-				will be erased when Vector is restarted. This will cause new tag values to pass
-				through until the cardinality limit is reached again. For normal usage this
-				should not be a common problem since Vector processes are normally long-lived.
+				as possible. This is because Vector's cardinality cache is held in memory and,
+				unless `persistence` is configured, it will be erased when Vector is restarted.
+				This will cause new tag values to pass through until the cardinality limit is
+				reached again. For normal usage this should not be a common problem since Vector
+				processes are normally long-lived.
 				"""
 		}
 
@@ -210,6 +309,12 @@ components: transforms: tag_cardinality_limit: {
 				will reset the cache. This means that new values will be passed through until
 				the cardinality limit is reached again. See [intended usage](#intended-usage)
 				for more info.
+
+				In mode `exact`, the accepted values can be persisted with the `persistence`
+				option, either to disk or to Redis. They are saved every `interval_secs` and
+				when Vector stops, and are restored when Vector starts, so that the same values
+				keep being accepted. When the limit of a tag was lowered in between, only the
+				as many values as the new limit are restored.
 				"""
 		}
 	}
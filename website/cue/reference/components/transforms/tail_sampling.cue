@@ -0,0 +1,112 @@
+package metadata
+
+components: transforms: tail_sampling: {
+	title: "Tail Sampling"
+
+	description: """
+		Samples whole traces, deciding whether to keep each trace once its spans were buffered for a decision
+		window, so that traces with errors or high latency are kept.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		decision_wait_secs: {
+			common:      true
+			description: "How long the spans of a trace are buffered before deciding whether to keep it. The window starts when the first span of the trace is received. Spans of a trace received after its decision follow that decision."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		keep_errors: {
+			common:      true
+			description: "Whether to keep the traces with at least one span in error."
+			required:    false
+			type: bool: default: true
+		}
+		latency_threshold_ms: {
+			common:      true
+			description: "The duration from which a span makes its trace kept. By default, traces aren't kept for their latency."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [500]
+				unit: "milliseconds"
+			}
+		}
+		max_traces: {
+			common:      false
+			description: "The maximum number of traces buffered at once. When a new trace would exceed it, the decision of the oldest trace is made early."
+			required:    false
+			type: uint: {
+				default: 50000
+				unit:    null
+			}
+		}
+		rate: {
+			common: true
+			description: """
+				The rate at which the traces not kept by any other rule are kept, expressed as 1/N. For example,
+				`rate = 10` means 1 out of every 10 of these traces is kept. The trace ID is hashed, so all Vector
+				instances configured with the same rate make the same decision for a trace. By default, these traces
+				are discarded.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [10]
+				unit: null
+			}
+		}
+	}
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+
+	how_it_works: {
+		decisions: {
+			title: "Decisions"
+			body: """
+				The spans of each trace are buffered for `decision_wait_secs`, and the trace is then kept whole or
+				discarded whole. A trace is kept when one of its spans is in error, when one of its spans lasts at
+				least `latency_threshold_ms`, or else when its trace ID is sampled at `rate`. The decisions of recent
+				traces are remembered, so their late spans are kept or discarded along with them. Traces still
+				buffered when Vector stops are decided right away.
+				"""
+		}
+		spans: {
+			title: "Spans"
+			body: """
+				An event either holds its spans in a `spans` array, as the events of the `datadog_agent` source, or is
+				a span itself, as the events of the `opentelemetry` source. The trace ID is read from the `trace_id`
+				field of the event, or of its first span. A span is in error when its `error` field is set, or when
+				its `status.code` is `error`. Its duration is read from its `duration` field, in nanoseconds, or from
+				its `start_timestamp` and `end_timestamp` fields. Events without a trace ID are forwarded as they are.
+				"""
+		}
+	}
+}
@@ -25,6 +25,50 @@ components: transforms: throttle: {
 	}
 
 	configuration: {
+		backend: {
+			common:      false
+			description: "Where the token buckets of the keys are kept."
+			required:    false
+			type: object: options: {
+				key_prefix: {
+					description:   "The prefix of the Redis keys holding the token buckets. All instances sharing a rate limit must use the same prefix. By default, it's `vector:throttle:<component_id>:`, so that instances running the same configuration share their rate limits."
+					required:      false
+					relevant_when: "type = \"redis\""
+					type: string: {
+						default: null
+						examples: ["rate_limits:users:"]
+					}
+				}
+				timeout_ms: {
+					description:   "How long to wait for Redis before rate limiting an event with the local token buckets."
+					required:      false
+					relevant_when: "type = \"redis\""
+					type: uint: {
+						default: 100
+						unit:    "milliseconds"
+					}
+				}
+				type: {
+					description: "The backend of the token buckets."
+					required:    false
+					type: string: {
+						default: "local"
+						enum: {
+							local: "In memory, so each Vector instance rate limits its events separately."
+							redis: "In Redis, so all Vector instances sharing the same Redis keys enforce a single rate limit."
+						}
+					}
+				}
+				url: {
+					description:   "The URL of the Redis server, in the `redis://[:<password>@]<hostname>[:port][/<db>]` format."
+					required:      true
+					relevant_when: "type = \"redis\""
+					type: string: {
+						examples: ["redis://127.0.0.1:6379/0"]
+					}
+				}
+			}
+		}
 		exclude: {
 			common: true
 			description: """
@@ -79,6 +123,7 @@ components: transforms: throttle: {
 	}
 
 	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 
@@ -156,6 +201,17 @@ components: transforms: throttle: {
 						by the bucket's `key`.
 						"""
 				},
+				{
+					title: "Distributed Rate Limiting"
+					body: """
+						With the `redis` backend, the token buckets are kept in Redis, so a fleet of Vector instances
+						enforces a single rate limit per key, instead of one per instance. The buckets are refilled
+						continuously at `threshold` tokens per `window_secs`, based on the time of the Redis server, and
+						each event takes a token atomically. When Redis fails or doesn't reply within `backend.timeout_ms`,
+						the event is rate limited with the local buckets of the instance instead, and the error is tracked
+						by a `component_errors_total` metric.
+						"""
+				},
 			]
 		}
 	}
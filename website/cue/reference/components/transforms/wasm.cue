@@ -0,0 +1,122 @@
+package metadata
+
+components: transforms: wasm: {
+	title: "WebAssembly"
+
+	description: """
+		Processes log events with a user-provided WebAssembly module, for logic too complex for VRL, with
+		limits on the fuel and memory the module can use.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "WebAssembly"
+				url:     urls.wasm
+				version: null
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				Modules run in a sandbox without access to the file system, network, or environment, but they run
+				on the threads of Vector, so expensive modules slow the whole pipeline down.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		fuel_per_event: {
+			common:      false
+			description: "The amount of fuel the module can consume to process an event. Each WebAssembly instruction consumes about one unit of fuel. The events for which the module runs out of fuel are discarded."
+			required:    false
+			type: uint: {
+				default: 10000000
+				unit:    null
+			}
+		}
+		hot_reload: {
+			common:      true
+			description: "Whether to reload the module when its file changes. The file is checked every second. If the new module fails to load, the previous one keeps running."
+			required:    false
+			type: bool: default: false
+		}
+		max_memory_bytes: {
+			common:      false
+			description: "The maximum size of the memory of the module. The events for which the module fails to grow its memory beyond it are discarded."
+			required:    false
+			type: uint: {
+				default: 67108864
+				unit:    "bytes"
+			}
+		}
+		module: {
+			description: "The path to the WebAssembly module to run for each event. If a relative path is provided, its root is the current working directory."
+			required:    true
+			type: string: {
+				examples: ["/etc/vector/transform.wasm"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+
+	how_it_works: {
+		interface: {
+			title: "Interface"
+			body: """
+				Modules target WASI and implement the following interface, which guest bindings can be generated
+				from with [`wit-bindgen`](\(urls.wit_bindgen)):
+
+				```text
+				process: func(event: string) -> expected<string, string>
+				```
+
+				The event is passed as a JSON object, and the module returns a JSON array of the events to forward,
+				which may be empty to discard the event. The metadata of the event is kept on the events returned.
+				Events for which the module returns an error are discarded.
+
+				Modules are built as core modules exporting the function in its canonical ABI, along with their
+				`memory` and the `canonical_abi_realloc` and `canonical_abi_free` functions, as generated by
+				`wit-bindgen`.
+				"""
+		}
+		limits: {
+			title: "Limits"
+			body: """
+				Each event can consume up to `fuel_per_event` units of fuel, and the memory of the module can't grow
+				beyond `max_memory_bytes`. When the module runs out of fuel or traps, the event is discarded and the
+				module is instantiated again, losing any state it kept in memory.
+				"""
+		}
+		hot_reload: {
+			title: "Hot reload"
+			body: """
+				With `hot_reload` enabled, the module is reloaded when its file changes, without reloading the
+				configuration. A module that fails to load is reported, and the previous module keeps processing
+				events. Modules should be written to a temporary file then renamed, so that partial writes aren't
+				loaded.
+				"""
+		}
+	}
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: aws_cloudtrail: {
+	name:     "AWS CloudTrail"
+	thing:    "an \(name) trail"
+	url:      urls.aws_cloudtrail
+	versions: null
+
+	description: "[AWS CloudTrail](\(urls.aws_cloudtrail)) records the actions taken by users, roles, and AWS services in an AWS account, and delivers them as log files to an S3 bucket."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: ftp: {
+	name:     "FTP or SFTP"
+	thing:    "an \(name) server"
+	url:      urls.sftp
+	versions: null
+
+	description: "[SFTP](\(urls.sftp)) and [FTP](\(urls.ftp)) are protocols used to transfer files to and from remote servers, over SSH for SFTP, and optionally over TLS for FTP ([FTPS](\(urls.ftps)))."
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: grpc: {
+	name:     "gRPC"
+	thing:    "a \(name) client"
+	url:      urls.grpc
+	versions: null
+}
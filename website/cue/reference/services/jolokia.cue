@@ -0,0 +1,10 @@
+package metadata
+
+services: jolokia: {
+	name:     "Jolokia"
+	thing:    "a \(name) agent"
+	url:      urls.jolokia
+	versions: null
+
+	description: "[Jolokia](\(urls.jolokia)) is a JMX-HTTP bridge exposing the MBeans of Java applications, such as Kafka or Cassandra, over HTTP."
+}
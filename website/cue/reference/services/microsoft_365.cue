@@ -0,0 +1,10 @@
+package metadata
+
+services: microsoft_365: {
+	name:     "Microsoft 365"
+	thing:    "a \(name) organization"
+	url:      urls.microsoft_365
+	versions: null
+
+	description: "[Microsoft 365](\(urls.microsoft_365)) is a suite of productivity services, such as Exchange Online, SharePoint Online and Azure Active Directory, whose user and administrator activities are recorded in the unified audit log."
+}
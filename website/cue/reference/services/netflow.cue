@@ -0,0 +1,10 @@
+package metadata
+
+services: netflow: {
+	name:     "NetFlow"
+	thing:    "a \(name) exporter"
+	url:      urls.netflow_v9
+	versions: "v5, v9, IPFIX"
+
+	description: "[NetFlow](\(urls.netflow_v9)) and its standard successor [IPFIX](\(urls.ipfix)) are used by routers, switches and probes to export summaries of the network flows they observe."
+}
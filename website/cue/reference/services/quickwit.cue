@@ -0,0 +1,10 @@
+package metadata
+
+services: quickwit: {
+	name:     "Quickwit"
+	thing:    "a \(name) index"
+	url:      urls.quickwit
+	versions: null
+
+	description: "[Quickwit](\(urls.quickwit)) is a cloud-native search engine for log management and analytics, storing its indexes on object storage."
+}
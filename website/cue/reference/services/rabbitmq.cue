@@ -0,0 +1,10 @@
+package metadata
+
+services: rabbitmq: {
+	name:     "RabbitMQ"
+	thing:    "a \(name) node"
+	url:      urls.rabbitmq
+	versions: ">= 3.9"
+
+	description: "[RabbitMQ](\(urls.rabbitmq)) is an open source message broker, supporting several messaging protocols, and append-only [streams](\(urls.rabbitmq_streams)) with non-destructive consumption."
+}
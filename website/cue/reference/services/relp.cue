@@ -0,0 +1,10 @@
+package metadata
+
+services: relp: {
+	name:     "RELP"
+	thing:    "a \(name) client"
+	url:      urls.relp
+	versions: null
+
+	description: "The [Reliable Event Logging Protocol](\(urls.relp)) (RELP) is a protocol for forwarding syslog messages over TCP, where each message is acknowledged by the receiver, so that no message is lost when connections break. It is used by the [omrelp](\(urls.rsyslog_omrelp)) module of rsyslog."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: sflow: {
+	name:     "sFlow"
+	thing:    "an \(name) agent"
+	url:      urls.sflow
+	versions: "v5"
+
+	description: "[sFlow](\(urls.sflow)) is a sampling technology embedded in switches and routers, which export randomly sampled packet headers along with periodic interface counters."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: snmp: {
+	name:     "SNMP"
+	thing:    "an \(name) agent"
+	url:      urls.snmp
+	versions: "v1, v2c, v3"
+
+	description: "The [Simple Network Management Protocol](\(urls.snmp)) is used to monitor network devices, which report events to managers by sending traps and informs."
+}
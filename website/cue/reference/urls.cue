@@ -4,9 +4,11 @@ urls: {
 	azure_blob_storage:                           "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	affine_type_system:                           "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:            "/blog/adaptive-request-concurrency/"
+	aes_gcm:                                      "https://en.wikipedia.org/wiki/Galois/Counter_Mode"
 	amazon_linux:                                 "https://aws.amazon.com/amazon-linux-ami/"
 	ansi_escape_codes:                            "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                       "https://httpd.apache.org"
+	apache_avro:                                  "https://avro.apache.org/"
 	apache_common:                                "\(apache)/docs/current/logs.html#common"
 	apache_combined:                              "\(apache)/docs/current/logs.html#combined"
 	apache_error:                                 "\(apache)/docs/current/logs.html#errorlog"
@@ -15,14 +17,20 @@ urls: {
 	apache_mod_status:                            "http://httpd.apache.org/docs/current/mod/mod_status.html"
 	apt:                                          "\(wikipedia)/wiki/APT_(software)"
 	arm:                                          "\(wikipedia)/wiki/ARM_architecture"
+	avro_logical_types:                           "https://avro.apache.org/docs/current/spec.html#Logical+Types"
 	aws_access_keys:                              "\(aws_docs)/IAM/latest/UserGuide/id_credentials_access-keys.html"
 	aws_arm_g2_announcement:                      "https://aws.amazon.com/about-aws/whats-new/2019/12/announcing-new-amazon-ec2-m6g-c6g-and-r6g-instances-powered-by-next-generation-arm-based-aws-graviton2-processors/"
 	aws_athena:                                   "https://aws.amazon.com/athena/"
 	aws_athena_console:                           "https://console.aws.amazon.com/athena/home"
 	aws_canonical_user_id:                        "\(aws_docs)/general/latest/gr/acct-identifiers.html#FindingCanonicalId"
+	aws_cloudtrail:                               "https://aws.amazon.com/cloudtrail/"
+	aws_cloudtrail_digest_files:                  "\(aws_docs)/awscloudtrail/latest/userguide/cloudtrail-log-file-validation-digest-file-structure.html"
+	aws_cloudtrail_record_contents:               "\(aws_docs)/awscloudtrail/latest/userguide/cloudtrail-event-reference-record-contents.html"
+	aws_cloudtrail_sns_notifications:             "\(aws_docs)/awscloudtrail/latest/userguide/configure-sns-notifications-for-cloudtrail.html"
 	aws_cloudwatch:                               "https://aws.amazon.com/cloudwatch/"
 	aws_cloudwatch_logs:                          "\(aws_docs)/AmazonCloudWatch/latest/logs/WhatIsCloudWatchLogs.html"
 	aws_cloudwatch_logs_api:                      "\(aws_docs)/AmazonCloudWatchLogs/latest/APIReference/Welcome.html"
+	aws_cloudwatch_logs_filter_pattern:           "\(aws_docs)/AmazonCloudWatch/latest/logs/FilterAndPatternSyntax.html"
 	aws_cloudwatch_logs_firehose:                 "\(aws_docs)/firehose/latest/dev/writing-with-cloudwatch-logs.html"
 	aws_cloudwatch_logs_group_name:               "\(aws_docs)/AmazonCloudWatch/latest/logs/Working-with-log-groups-and-streams.html"
 	aws_cloudwatch_logs_service_limits:           "\(aws_docs)/AmazonCloudWatch/latest/logs/cloudwatch_limits_cwl.html"
@@ -32,6 +40,7 @@ urls: {
 	aws_cloudwatch_logs_s3_export:                "\(aws_docs)/AmazonCloudWatch/latest/logs/S3Export.html"
 	aws_cloudwatch_logs_subscriptions:            "\(aws_docs)/AmazonCloudWatch/latest/logs/SubscriptionFilters.html"
 	aws_cloudwatch_logs_subscriptions_firehose:   "\(aws_docs)/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#FirehoseExample"
+	aws_cloudwatch_logs_subscriptions_kinesis:    "\(aws_docs)/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#DestinationKinesisExample"
 	aws_cloudwatch_metrics:                       "\(aws_docs)/AmazonCloudWatch/latest/monitoring/working_with_metrics.html"
 	aws_cloudwatch_metrics_api:                   "\(aws_docs)/AmazonCloudWatch/latest/APIReference/API_PutMetricData.html"
 	aws_cloudwatch_metrics_service_limits:        "\(aws_docs)/en_pv/AmazonCloudWatch/latest/monitoring/cloudwatch_limits.html"
@@ -47,6 +56,7 @@ urls: {
 	aws_elb:                                      "https://aws.amazon.com/elasticloadbalancing/"
 	aws_elb_access_format:                        "\(aws_docs)/elasticloadbalancing/latest/application/load-balancer-access-logs.html#access-log-entry-examples"
 	aws_elb_https:                                "\(aws_docs)/elasticloadbalancing/latest/classic/elb-create-https-ssl-load-balancer.html"
+	aws_eventbridge_s3:                           "https://docs.aws.amazon.com/AmazonS3/latest/userguide/EventBridge.html"
 	aws_iam:                                      "\(aws_docs)/IAM/latest/UserGuide/introduction.html"
 	aws_iam_role:                                 "\(aws_docs)/IAM/latest/UserGuide/id_roles.html"
 	aws_imds_v1_security_problems:                "https://aws.amazon.com/blogs/security/defense-in-depth-open-firewalls-reverse-proxies-ssrf-vulnerabilities-ec2-instance-metadata-service/"
@@ -59,6 +69,8 @@ urls: {
 	aws_kinesis_partition_key:                    "\(aws_docs)/kinesis/latest/APIReference/API_PutRecordsRequestEntry.html#Streams-Type-PutRecordsRequestEntry-PartitionKey"
 	aws_kinesis_streams:                          "https://aws.amazon.com/kinesis/data-streams/"
 	aws_kinesis_streams_api:                      "\(aws_docs)/kinesis/latest/APIReference/API_PutRecords.html"
+	aws_kinesis_streams_enhanced_fan_out:         "\(aws_docs)/streams/latest/dev/enhanced-consumers.html"
+	aws_kinesis_streams_resharding:               "\(aws_docs)/streams/latest/dev/kinesis-using-sdk-java-after-resharding.html"
 	aws_kinesis_streams_service_limits:           "\(aws_docs)/streams/latest/dev/service-sizes-and-limits.html"
 	aws_kinesis_split_shards:                     "\(aws_docs)/streams/latest/dev/kinesis-using-sdk-java-resharding-split.html"
 	aws_regions:                                  "\(aws_docs)/AmazonRDS/latest/UserGuide/Concepts.RegionsAndAvailabilityZones.html"
@@ -76,6 +88,7 @@ urls: {
 	aws_s3_sse:                                   "\(aws_docs)/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                       "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                  "\(aws_docs)/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sns_raw_message_delivery:                 "https://docs.aws.amazon.com/sns/latest/dg/sns-large-payload-raw-message-delivery.html"
 	aws_sqs:                                      "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                  "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/Welcome.html"
 	aws_sqs_create:                               "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-configure-create-queue.html"
@@ -92,8 +105,11 @@ urls: {
 	basic_auth:                                   "\(wikipedia)/wiki/Basic_access_authentication"
 	big_query_streaming:                          "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	bind_dnstap:                                  "https://kb.isc.org/docs/aa-01342"
+	bson:                                         "https://bsonspec.org/spec.html"
 	b_tree_map:                                   "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                  "\(github)/RustSec/cargo-audit"
+	cbor:                                         "https://www.rfc-editor.org/rfc/rfc8949.html"
+	cef:                                          "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                       "https://www.centos.org/"
 	chrono_time_formats:                          "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                      "https://the.binbashtheory.com/control-resources-cgroups/"
@@ -102,6 +118,8 @@ urls: {
 	cloudsmith:                                   "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                               "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                               "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	confluent_schema_registry:                    "https://docs.confluent.io/platform/current/schema-registry/index.html"
+	confluent_wire_format:                        "https://docs.confluent.io/platform/current/schema-registry/serdes-develop/index.html#wire-format"
 	console:                                      "\(wikipedia)/wiki/System_console"
 	conventional_commits:                         "https://www.conventionalcommits.org"
 	contributing:                                 "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
@@ -187,12 +205,23 @@ urls: {
 	fluentd:                                      "https://www.fluentd.org/"
 	fluentbit:                                    "https://fluentbit.io/"
 	freebsd:                                      "https://www.freebsd.org/"
+	ftp:                                          "https://datatracker.ietf.org/doc/html/rfc959"
+	ftps:                                         "https://datatracker.ietf.org/doc/html/rfc4217"
 	gcp:                                          "https://cloud.google.com"
 	gcp_authentication:                           "\(gcp)/docs/authentication/"
 	gcp_authentication_api_key:                   "\(gcp)/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:          "\(gcp)/docs/authentication/production"
 	gcp_authentication_service_account:           "\(gcp)/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_cloud_logging_audit_log:                  "\(gcp)/logging/docs/reference/audit/auditlog/rest/Shared.Types/AuditLog"
+	gcp_cloud_logging_log_entry:                  "\(gcp)/logging/docs/reference/v2/rest/v2/LogEntry"
+	gcp_cloud_logging_pubsub_sink:                "\(gcp)/logging/docs/export/pubsub"
+	gcp_cloud_monitoring_aligners:                "\(gcp)/monitoring/api/ref_v3/rest/v3/projects.alertPolicies#Aligner"
+	gcp_cloud_monitoring_filters:                 "\(gcp)/monitoring/api/v3/filters"
+	gcp_cloud_monitoring_metric_kinds:            "\(gcp)/monitoring/api/v3/kinds-and-types"
+	gcp_cloud_monitoring_reducers:                "\(gcp)/monitoring/api/ref_v3/rest/v3/projects.alertPolicies#Reducer"
 	gcp_cloud_storage:                            "\(gcp)/storage"
+	gcp_cloud_storage_pubsub_notifications:       "https://cloud.google.com/storage/docs/pubsub-notifications"
+	gcp_cloud_storage_transcoding:                "https://cloud.google.com/storage/docs/transcoding"
 	gcp_folders:                                  "\(gcp)/resource-manager/docs/creating-managing-folders"
 	gcp_pubsub:                                   "\(gcp)/pubsub/"
 	gcp_pubsub_rest:                              "\(gcp)/pubsub/docs/reference/rest/"
@@ -222,8 +251,10 @@ urls: {
 	grok:                                         "https://grokdebug.herokuapp.com/"
 	grok_debugger:                                "https://grokdebug.herokuapp.com/"
 	grok_patterns:                                "\(github)/daschl/grok/tree/master/patterns"
+	grpc:                                         "https://grpc.io"
 	gzip:                                         "https://www.gzip.org/"
 	haproxy:                                      "https://www.haproxy.org/"
+	haproxy_proxy_protocol:                       "https://www.haproxy.org/download/2.6/doc/proxy-protocol.txt"
 	helm:                                         "https://helm.sh/"
 	heroku:                                       "https://www.heroku.com"
 	heroku_http_log_drain:                        "https://devcenter.heroku.com/articles/log-drains#https-drains"
@@ -256,11 +287,18 @@ urls: {
 	ip_ntoa:                                      "https://linux.die.net/man/3/inet_ntoa"
 	ip_ntop:                                      "https://linux.die.net/man/3/inet_ntop"
 	ip_pton:                                      "https://linux.die.net/man/3/inet_pton"
+	ipfix:                                        "https://datatracker.ietf.org/doc/html/rfc7011"
+	ipfix_information_elements:                   "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
+	ipinfo_mmdb:                                  "https://ipinfo.io/products/ip-database-download"
 	iso_8601:                                     "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                    "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                   "\(vector_repo)/issues/1694"
+	jolokia:                                      "https://jolokia.org/"
+	jolokia_protocol:                             "https://jolokia.org/reference/html/protocol.html"
+	jolokia_proxy_mode:                           "https://jolokia.org/reference/html/proxy.html"
 	journalctl:                                   "https://www.freedesktop.org/software/systemd/man/journalctl.html"
 	journald:                                     "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
+	journald_namespaces:                          "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html#Journal%20Namespaces"
 	json:                                         "\(wikipedia)/wiki/JSON"
 	json_types:                                   "\(wikipedia)/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                      "https://jsonnet.org/"
@@ -282,6 +320,7 @@ urls: {
 	kubernetes_rbac:                              "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                     "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                         "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	leef:                                         "https://www.ibm.com/docs/en/dsm?topic=leef-overview"
 	leveldb:                                      "\(github)/google/leveldb"
 	leveldb_sys_2:                                "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                "\(github)/vectordotdev/leveldb-sys/tree/v3.0.0"
@@ -325,21 +364,34 @@ urls: {
 	maxmind_db_file_format:                       "https://maxmind.github.io/MaxMind-DB/"
 	maxmind_geoip2:                               "https://dev.maxmind.com/geoip/geoip2/downloadable"
 	maxmind_geoip2_city:                          "https://www.maxmind.com/en/geoip2-city"
+	maxmind_geoip2_connection_type:               "https://www.maxmind.com/en/geoip2-connection-type-database"
 	maxmind_geoip2_isp:                           "https://www.maxmind.com/en/geoip2-isp-database"
 	maxmind_geolite2_asn:                         "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
 	maxmind_geolite2_city:                        "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
 	memory_safety:                                "\(wikipedia)/wiki/Memory_safety"
 	memory_safety_bugs:                           "https://thenewstack.io/microsoft-rust-is-the-industrys-best-chance-at-safe-systems-programming/"
 	metric_event_source:                          "\(vector_repo)/blob/master/src/event/metric.rs"
+	microsoft_365:                                "https://www.microsoft.com/microsoft-365"
+	microsoft_365_audit_log_schema:               "https://docs.microsoft.com/en-us/office/office-365-management-api/office-365-management-activity-api-schema"
+	microsoft_365_management_activity_api:        "https://docs.microsoft.com/en-us/office/office-365-management-api/office-365-management-activity-api-reference"
+	microsoft_365_management_api_app:             "https://docs.microsoft.com/en-us/office/office-365-management-api/get-started-with-office-365-management-apis"
 	mlua:                                         "\(github)/khvzak/mlua"
 	mongodb:                                      "https://www.mongodb.com"
+	mongodb_change_events:                        "https://www.mongodb.com/docs/manual/reference/change-events/"
+	mongodb_change_streams:                       "https://www.mongodb.com/docs/manual/changeStreams/"
 	mongodb_command_server_status:                "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:         "https://docs.mongodb.com/manual/reference/connection-string/"
+	mongodb_extended_json:                        "https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/"
+	mqtt:                                         "https://mqtt.org/"
+	mqtt_v3_1_1:                                  "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/mqtt-v3.1.1.html"
+	mqtt_v5:                                      "https://docs.oasis-open.org/mqtt/mqtt/v5.0/mqtt-v5.0.html"
 	musl_builder_docker_image:                    "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	native_proto_schema:                          "\(vector_repo)/blob/master/lib/vector-core/proto/event.proto"
 	native_json_schema:                           "\(vector_repo)/blob/master/lib/codecs/tests/data/native_encoding/schema.cue"
 	nats:                                         "https://nats.io/"
 	nats_rs:                                      "\(github)/nats-io/nats.rs"
+	netflow_v5:                                   "https://www.cisco.com/c/en/us/td/docs/net_mgmt/netflow_collection_engine/3-6/user/guide/format.html"
+	netflow_v9:                                   "https://datatracker.ietf.org/doc/html/rfc3954"
 	new_bug_report:                               "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                          "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                    "https://newrelic.com/"
@@ -357,8 +409,16 @@ urls: {
 	nix:                                          "https://nixos.org/nix/"
 	nixos:                                        "https://nixos.org/"
 	nixpkgs_9682:                                 "\(github)/NixOS/nixpkgs/issues/9682"
+	okta:                                         "https://www.okta.com/"
+	okta_api_token:                               "https://developer.okta.com/docs/guides/create-an-api-token/main/"
+	okta_rate_limits:                             "https://developer.okta.com/docs/reference/rl-best-practices/"
+	okta_system_log_api:                          "https://developer.okta.com/docs/reference/api/system-log/"
+	okta_system_log_filter:                       "https://developer.okta.com/docs/reference/api/system-log/#filtering-results"
 	openssl:                                      "https://www.openssl.org/"
+	opentelemetry:                                "https://opentelemetry.io"
 	order_of_ops:                                 "\(wikipedia)/wiki/Order_of_operations"
+	pagerduty:                                    "https://www.pagerduty.com/"
+	pagerduty_events_v2:                          "https://developer.pagerduty.com/docs/ZG9jOjExMDI5NTgw-events-api-v2-overview"
 	papertrail:                                   "https://www.papertrail.com/"
 	papertrail_syslog:                            "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                 "https://www.perl.org/get.html#win32"
@@ -374,7 +434,9 @@ urls: {
 	posix_acls:                                   "https://www.usenix.org/legacy/publications/library/proceedings/usenix03/tech/freenix03/full_papers/gruenbacher/gruenbacher_html/main.html"
 	postgresql:                                   "https://www.postgresql.org/"
 	postgresql_csvlog:                            "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
+	postgresql_logical_decoding:                  "https://www.postgresql.org/docs/current/logicaldecoding.html"
 	postgresql_matching:                          "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
+	postgresql_replica_identity:                  "https://www.postgresql.org/docs/current/sql-altertable.html#SQL-ALTERTABLE-REPLICA-IDENTITY"
 	procfs:                                       "https://en.wikipedia.org/wiki/Procfs#:~:text=The%20proc%20filesystem%20(procfs)%20is,in%20the%20kernel%20than%20traditional"
 	prometheus:                                   "https://prometheus.io/"
 	prometheus_client:                            "https://prometheus.io/docs/instrumenting/clientlibs/"
@@ -390,14 +452,22 @@ urls: {
 	prometheus_remote_integrations:               "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_remote_write:                      "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:             "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
+	prometheus_remote_write_v2:                   "https://prometheus.io/docs/specs/remote_write_spec_2_0/"
 	protobuf:                                     "https://developers.google.com/protocol-buffers"
+	protobuf_descriptor_set:                      "https://protobuf.dev/programming-guides/techniques/#self-description"
 	pulsar:                                       "https://pulsar.apache.org/"
 	pulsar_protocol:                              "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	quickwit:                                     "https://quickwit.io/"
+	quickwit_ingest_api:                          "https://quickwit.io/docs/reference/rest-api#ingest-data-into-an-index"
+	rabbitmq:                                     "https://www.rabbitmq.com/"
+	rabbitmq_stream_offsets:                      "https://www.rabbitmq.com/streams.html#consuming"
+	rabbitmq_streams:                             "https://www.rabbitmq.com/streams.html"
 	raspbian:                                     "https://www.raspbian.org/"
 	rdkafka:                                      "\(github)/edenhill/librdkafka"
 	regex:                                        "\(wikipedia)/wiki/Regular_expression"
 	regex_grouping_and_flags:                     "https://docs.rs/regex/latest/regex/#grouping-and-flags"
 	regex_tester:                                 "https://rustexp.lpil.uk/"
+	relp:                                         "https://www.rsyslog.com/doc/relp.html"
 	rfc_768:                                      "https://tools.ietf.org/html/rfc768"
 	rfc_791:                                      "https://tools.ietf.org/html/rfc791"
 	rfc_793:                                      "https://tools.ietf.org/html/rfc793"
@@ -413,6 +483,7 @@ urls: {
 	rfc_6891:                                     "https://tools.ietf.org/html/rfc6891"
 	rhel:                                         "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                          "https://rpm.org/"
+	rsyslog_omrelp:                               "https://www.rsyslog.com/doc/configuration/modules/omrelp.html"
 	rust:                                         "https://www.rust-lang.org/"
 	rust_date_time:                               "https://docs.rs/chrono/latest/chrono/struct.DateTime.html"
 	rust_grok_library:                            "\(github)/daschl/grok"
@@ -434,12 +505,19 @@ urls: {
 	sematext_monitoring:                          "https://sematext.com/docs/monitoring/"
 	sematext_registration:                        "https://apps.sematext.com/ui/registration"
 	semver:                                       "https://semver.org/"
+	sflow:                                        "https://sflow.org/"
+	sflow_v5:                                     "https://sflow.org/sflow_version_5.txt"
+	sftp:                                         "https://datatracker.ietf.org/doc/html/draft-ietf-secsh-filexfer-02"
 	sha1:                                         "\(wikipedia)/wiki/SHA-1"
 	sha2:                                         "\(wikipedia)/wiki/SHA-2"
 	sha3:                                         "\(wikipedia)/wiki/SHA-3"
 	signal:                                       "\(wikipedia)/wiki/Signal_(IPC)"
 	snake_case:                                   "\(wikipedia)/wiki/Snake_case"
 	snappy:                                       "https://google.github.io/snappy/"
+	snmp:                                         "https://datatracker.ietf.org/doc/html/rfc3416"
+	snmp_usm:                                     "https://datatracker.ietf.org/doc/html/rfc3414"
+	snmp_v1_v2_coexistence:                       "https://datatracker.ietf.org/doc/html/rfc3584"
+	snmptrapd:                                    "http://www.net-snmp.org/docs/man/snmptrapd.html"
 	socket:                                       "\(wikipedia)/wiki/Network_socket"
 	splunk:                                       "https://www.splunk.com"
 	splunk_hec:                                   "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
@@ -450,8 +528,11 @@ urls: {
 	splunk_hec_protocol:                          "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/HECRESTendpoints"
 	splunk_hec_raw_endpoint:                      "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                             "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
+	sql:                                          "https://en.wikipedia.org/wiki/SQL"
 	splunk_hec_metadata:                          "https://docs.splunk.com/Documentation/Splunk/latest/Data/FormateventsforHTTPEventCollector#Event_metadata"
 	specs_instrumentation:                        "\(vector_repo)/blob/master/docs/specs/instrumentation.md)"
+	sqlx:                                         "https://github.com/launchbadge/sqlx"
+	ssh_known_hosts:                              "https://man.openbsd.org/sshd.8#SSH_KNOWN_HOSTS_FILE_FORMAT"
 	standard_streams:                             "\(wikipedia)/wiki/Standard_streams"
 	statsd:                                       "\(github)/statsd/statsd"
 	statsd_multi:                                 "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"
@@ -497,6 +578,7 @@ urls: {
 	user_agent:                                   "https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent"
 	vector_agent_role:                            "/docs/setup/deployment/roles/#agent"
 	vector_aggregator_role:                       "/docs/setup/deployment/roles/#aggregator"
+	vector_aws_kinesis_firehose_source:           "/docs/reference/configuration/sources/aws_kinesis_firehose/"
 	vector_aws_s3_sink:                           "/docs/reference/configuration/sinks/aws_s3/"
 	vector_aws_s3_sink_auth:                      "/docs/reference/configuration/sinks/aws_s3/#auth"
 	vector_aws_s3_sink_how_it_works:              "/docs/reference/configuration/sinks/aws_s3/#how-it-works"
@@ -552,6 +634,7 @@ urls: {
 	vector_packages_root:                         "https://packages.timber.io"
 	vector_parsing_transforms:                    "/components/?functions%5B%5D=parse"
 	vector_performance:                           "\(vector_repo)/#performance"
+	vector_priority_transform:                    "/docs/reference/configuration/transforms/priority"
 	vector_privacy_policy:                        "\(vector_repo)/blob/master/PRIVACY.md"
 	vector_programmable_transforms:               "/components/?functions%5B%5D=program"
 	vector_pull_requests:                         "\(vector_repo)/pulls"
@@ -615,7 +698,9 @@ urls: {
 	vrl_runtime_errors:                           "\(vrl_errors_reference)#runtime-errors"
 	vrl_safety:                                   "\(vrl_reference)#safety"
 	vrl_type_safety:                              "\(vrl_reference)#type-safety"
+	w3c_extended_log_format:                      "https://www.w3.org/TR/WD-logfile.html"
 	vote_feature:                                 "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
+	wal2json:                                     "https://github.com/eulerto/wal2json"
 	wasm:                                         "https://webassembly.org/"
 	wasm_languages:                               "\(github)/appcypher/awesome-wasm-langs"
 	websocket:                                    "\(wikipedia)/wiki/WebSocket"
@@ -623,6 +708,7 @@ urls: {
 	windows:                                      "https://www.microsoft.com/en-us/windows"
 	windows_installer:                            "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                              "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
+	wit_bindgen:                                  "\(github)/bytecodealliance/wit-bindgen"
 	woothee:                                      "https://github.com/woothee/woothee"
 	yaml:                                         "https://yaml.org/"
 	ytt:                                          "https://carvel.dev/ytt/"